@@ -0,0 +1,201 @@
+// Copyright (c) 2025 Varshith Gudur. Dual-licensed under MIT OR Apache-2.0.
+//! Seeded dataset generators.
+//!
+//! Each generator is a pure function of `(kind, n, dim, seed)`. The same
+//! spec gives bit-identical vectors on every machine, so the state hash at
+//! the end of a run is reproducible.
+//!
+//! - **clustered** — Gaussian blobs around `clusters` random centres. The
+//!   shape ANN indexes are tuned for.
+//! - **drifting** — the same blobs, but every centre moves along a fixed
+//!   direction as ids increase. Later inserts land away from where the
+//!   early graph or IVF centroids were built.
+//! - **adversarial** — exact duplicates, near-duplicates one Q16 step
+//!   apart, collinear points, zero vectors and far outliers. These stress
+//!   tie-breaking, quantization and graph connectivity.
+
+use crate::rng::SplitMix64;
+use serde::{Deserialize, Serialize};
+
+/// Smallest step the kernel's Q16.16 representation can tell apart.
+pub const Q16_STEP: f32 = 1.0 / 65536.0;
+
+/// Half-width of the cube cluster centres are drawn from.
+const CENTRE_SPREAD: f32 = 10.0;
+/// Standard deviation of points around their centre.
+const CLUSTER_SIGMA: f32 = 0.5;
+/// Total distance each centre moves over a drifting dataset.
+const DRIFT_DISTANCE: f32 = 20.0;
+/// Coordinate magnitude of adversarial outliers.
+const OUTLIER_MAGNITUDE: f32 = 1000.0;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum DatasetKind {
+    Clustered,
+    Drifting,
+    Adversarial,
+}
+
+impl DatasetKind {
+    pub const ALL: [DatasetKind; 3] = [
+        DatasetKind::Clustered,
+        DatasetKind::Drifting,
+        DatasetKind::Adversarial,
+    ];
+
+    pub fn name(self) -> &'static str {
+        match self {
+            DatasetKind::Clustered => "clustered",
+            DatasetKind::Drifting => "drifting",
+            DatasetKind::Adversarial => "adversarial",
+        }
+    }
+
+    pub fn from_name(name: &str) -> Option<Self> {
+        Self::ALL.into_iter().find(|k| k.name() == name)
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub struct DatasetSpec {
+    pub kind: DatasetKind,
+    pub n: usize,
+    pub dim: usize,
+    pub seed: u64,
+    /// Number of clusters for `clustered` and `drifting`.
+    pub clusters: usize,
+}
+
+impl DatasetSpec {
+    pub fn new(kind: DatasetKind, n: usize, dim: usize, seed: u64) -> Self {
+        Self {
+            kind,
+            n,
+            dim,
+            seed,
+            clusters: 16,
+        }
+    }
+
+    /// The `n` vectors of the dataset, in insert order.
+    pub fn generate(&self) -> Vec<Vec<f32>> {
+        let mut rng = SplitMix64::new(self.seed);
+        match self.kind {
+            DatasetKind::Clustered => {
+                let centres = centres(&mut rng, self.clusters.max(1), self.dim);
+                (0..self.n)
+                    .map(|_| {
+                        let c = &centres[rng.below(centres.len() as u64) as usize];
+                        blob_point(&mut rng, c)
+                    })
+                    .collect()
+            }
+            DatasetKind::Drifting => {
+                let centres = centres(&mut rng, self.clusters.max(1), self.dim);
+                let drift = direction(&mut rng, self.dim);
+                (0..self.n)
+                    .map(|i| {
+                        let t = progress(i, self.n) * DRIFT_DISTANCE;
+                        let c = &centres[rng.below(centres.len() as u64) as usize];
+                        let moved: Vec<f32> =
+                            c.iter().zip(&drift).map(|(x, d)| x + d * t).collect();
+                        blob_point(&mut rng, &moved)
+                    })
+                    .collect()
+            }
+            DatasetKind::Adversarial => adversarial(&mut rng, self.n, self.dim),
+        }
+    }
+
+    /// `count` query vectors near the dataset's clusters (for adversarial,
+    /// near random centres), drawn from their own stream so they do not
+    /// depend on `n`.
+    pub fn queries(&self, count: usize) -> Vec<Vec<f32>> {
+        // Replay the dataset stream's prefix to get the same centres and drift.
+        let mut base = SplitMix64::new(self.seed);
+        let centres = centres(&mut base, self.clusters.max(1), self.dim);
+        let drift = direction(&mut base, self.dim);
+        let mut rng = SplitMix64::fork(self.seed, 1);
+        (0..count)
+            .map(|_| {
+                let c = &centres[rng.below(centres.len() as u64) as usize];
+                if self.kind == DatasetKind::Drifting {
+                    // Spread over the whole drift path.
+                    let t = rng.unit() * DRIFT_DISTANCE;
+                    let moved: Vec<f32> = c.iter().zip(&drift).map(|(x, d)| x + d * t).collect();
+                    blob_point(&mut rng, &moved)
+                } else {
+                    blob_point(&mut rng, c)
+                }
+            })
+            .collect()
+    }
+}
+
+fn centres(rng: &mut SplitMix64, count: usize, dim: usize) -> Vec<Vec<f32>> {
+    (0..count)
+        .map(|_| (0..dim).map(|_| rng.signed() * CENTRE_SPREAD).collect())
+        .collect()
+}
+
+fn blob_point(rng: &mut SplitMix64, centre: &[f32]) -> Vec<f32> {
+    centre
+        .iter()
+        .map(|c| c + rng.normal() * CLUSTER_SIGMA)
+        .collect()
+}
+
+/// A direction with every coordinate in `[-1, 1)`. Not normalised — a
+/// square root would be the one inexact step.
+fn direction(rng: &mut SplitMix64, dim: usize) -> Vec<f32> {
+    (0..dim).map(|_| rng.signed()).collect()
+}
+
+fn progress(i: usize, n: usize) -> f32 {
+    if n <= 1 {
+        0.0
+    } else {
+        i as f32 / (n - 1) as f32
+    }
+}
+
+fn adversarial(rng: &mut SplitMix64, n: usize, dim: usize) -> Vec<Vec<f32>> {
+    let anchor: Vec<f32> = (0..dim).map(|_| rng.signed() * CENTRE_SPREAD).collect();
+    let line = direction(rng, dim);
+    let mut out: Vec<Vec<f32>> = Vec::with_capacity(n);
+    for i in 0..n {
+        let v = match i % 6 {
+            // Exact duplicate of an earlier point.
+            0 if !out.is_empty() => out[rng.below(out.len() as u64) as usize].clone(),
+            // One Q16 step away from an earlier point, in one coordinate.
+            // On an outlier the step is below f32 resolution, so it stays
+            // an exact duplicate.
+            1 if !out.is_empty() => {
+                let mut v = out[rng.below(out.len() as u64) as usize].clone();
+                let d = rng.below(dim as u64) as usize;
+                v[d] += Q16_STEP;
+                v
+            }
+            // Collinear: every point on one line through the anchor.
+            2 => {
+                let t = rng.signed() * CENTRE_SPREAD;
+                anchor.iter().zip(&line).map(|(a, d)| a + d * t).collect()
+            }
+            3 => vec![0.0; dim],
+            // Far outlier on a random orthant corner.
+            4 => (0..dim)
+                .map(|_| {
+                    if rng.next_u64() & 1 == 0 {
+                        OUTLIER_MAGNITUDE
+                    } else {
+                        -OUTLIER_MAGNITUDE
+                    }
+                })
+                .collect(),
+            _ => blob_point(rng, &anchor),
+        };
+        out.push(v);
+    }
+    out
+}
@@ -0,0 +1,111 @@
+// Copyright (c) 2025 Varshith Gudur. Dual-licensed under MIT OR Apache-2.0.
+//! Deterministic benchmark harness.
+//!
+//! A run is a [`BenchSpec`]: a seeded [dataset](dataset), a standard
+//! [workload](workload) and an index kind. It runs against an in-memory
+//! [`Engine`] and produces a [`BenchResult`]: per-phase latency and
+//! throughput, recall, and the final state hash. The state hash depends only
+//! on the spec, so comparing results release over release catches both
+//! performance regressions and determinism breaks.
+
+pub mod dataset;
+pub mod report;
+pub mod rng;
+pub mod workload;
+
+use anyhow::{bail, Result};
+use serde::{Deserialize, Serialize};
+use std::sync::Arc;
+use valori_engine::{Engine, EngineConfig, IndexKind, QuantizationKind};
+use valori_kernel::crypto::{CryptoError, KeyVault};
+
+pub use dataset::{DatasetKind, DatasetSpec};
+pub use report::{BenchResult, Comparison, PhaseStats};
+pub use workload::{WorkloadKind, WorkloadSpec};
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub struct BenchSpec {
+    pub dataset: DatasetSpec,
+    pub workload: WorkloadSpec,
+    pub index: IndexKind,
+}
+
+/// The harness stores no encrypted payloads; the vault only has to exist.
+struct PlaintextVault;
+
+impl KeyVault for PlaintextVault {
+    fn encrypt(&self, _key_id: [u8; 16], plaintext: &[u8]) -> Result<Vec<u8>, CryptoError> {
+        Ok(plaintext.to_vec())
+    }
+    fn decrypt(&self, _key_id: [u8; 16], ciphertext: &[u8]) -> Result<Vec<u8>, CryptoError> {
+        Ok(ciphertext.to_vec())
+    }
+    fn shred(&self, _key_id: [u8; 16]) -> Result<(), CryptoError> {
+        Ok(())
+    }
+    fn key_exists(&self, _key_id: &[u8; 16]) -> bool {
+        true
+    }
+}
+
+/// In-memory engine sized for `spec`: no snapshot, WAL or event log, so
+/// disk speed does not leak into the numbers.
+pub fn engine_config(spec: &BenchSpec) -> EngineConfig {
+    EngineConfig {
+        dim: spec.dataset.dim,
+        max_records: spec.dataset.n.max(1),
+        max_nodes: 16,
+        max_edges: 16,
+        index_kind: spec.index,
+        quantization_kind: QuantizationKind::None,
+        hnsw_m: None,
+        hnsw_ef_construction: None,
+        hnsw_ef_search: None,
+        ivf_n_list: None,
+        ivf_n_probe: None,
+        snapshot_path: None,
+        wal_path: None,
+        event_log_path: None,
+        event_log_rotation_bytes: None,
+        event_log_sync: Default::default(),
+        quarantine_corrupt_segments: false,
+        vector_file_path: None,
+        decay_half_life_secs: None,
+        shard_count: 1,
+        anomaly_k: None,
+        anomaly_threshold: None,
+        forget_policy: None,
+        snapshot_check: Default::default(),
+        metadata_policy: Default::default(),
+        metadata_index_fields: Vec::new(),
+        recovery_policy: Default::default(),
+        extra_indexes: Vec::new(),
+        object_store_keep: 0,
+        object_store: None,
+        vault: Arc::new(PlaintextVault),
+        embed_config: None,
+    }
+}
+
+pub fn run(spec: &BenchSpec) -> Result<BenchResult> {
+    if spec.dataset.dim == 0 || spec.workload.k == 0 {
+        bail!("dim and k must be at least 1");
+    }
+    let mut engine = Engine::with_config(engine_config(spec));
+    engine.create_collection("default")?;
+    let outcome = workload::run(&mut engine, &spec.dataset, &spec.workload)?;
+    Ok(BenchResult {
+        version: env!("CARGO_PKG_VERSION").to_string(),
+        dataset: spec.dataset.kind.name().to_string(),
+        workload: spec.workload.kind.name().to_string(),
+        index: spec.index.name().to_string(),
+        n: spec.dataset.n,
+        dim: spec.dataset.dim,
+        seed: spec.dataset.seed,
+        k: spec.workload.k,
+        phases: outcome.phases,
+        recall: outcome.recall,
+        records: engine.record_count(),
+        state_hash: engine.state_hash_hex(),
+    })
+}
@@ -0,0 +1,233 @@
+// Copyright (c) 2025 Varshith Gudur. Dual-licensed under MIT OR Apache-2.0.
+//! `valori-bench` — run the deterministic benchmark matrix and compare runs.
+//!
+//! ```text
+//! valori-bench run --out results/0.2.4.jsonl
+//! valori-bench run --dataset clustered --workload search --index hnsw -n 50000
+//! valori-bench compare results/0.2.3.jsonl results/0.2.4.jsonl --max-regression 15
+//! ```
+//!
+//! `run` prints one JSON result per line on stdout (and appends them to
+//! `--out`), with a short summary on stderr.
+
+use anyhow::{bail, Context, Result};
+use clap::{Parser, Subcommand};
+use std::collections::BTreeMap;
+use std::io::Write;
+use std::path::{Path, PathBuf};
+use valori_bench::{
+    BenchResult, BenchSpec, Comparison, DatasetKind, DatasetSpec, WorkloadKind, WorkloadSpec,
+};
+use valori_engine::IndexKind;
+
+#[derive(Parser)]
+#[command(
+    name = "valori-bench",
+    version,
+    about = "Deterministic Valori benchmark harness"
+)]
+struct Cli {
+    #[command(subcommand)]
+    command: Command,
+}
+
+#[derive(Subcommand)]
+enum Command {
+    /// Run every combination of the given datasets, workloads and indexes
+    Run {
+        /// Datasets, comma-separated (clustered, drifting, adversarial)
+        #[arg(
+            long,
+            value_delimiter = ',',
+            default_value = "clustered,drifting,adversarial"
+        )]
+        dataset: Vec<String>,
+        /// Workloads, comma-separated (ingest, batch_ingest, search, mixed)
+        #[arg(
+            long,
+            value_delimiter = ',',
+            default_value = "ingest,batch_ingest,search,mixed"
+        )]
+        workload: Vec<String>,
+        /// Indexes, comma-separated (brute_force, hnsw, ivf, bq)
+        #[arg(long, value_delimiter = ',', default_value = "brute_force,hnsw")]
+        index: Vec<String>,
+        /// Vectors per dataset
+        #[arg(short, long, default_value_t = 10_000)]
+        n: usize,
+        #[arg(long, default_value_t = 64)]
+        dim: usize,
+        #[arg(long, default_value_t = 42)]
+        seed: u64,
+        /// Searches (search) or operations (mixed) after the load
+        #[arg(long, default_value_t = 1000)]
+        ops: usize,
+        #[arg(short, long, default_value_t = 10)]
+        k: usize,
+        /// Vectors per batch insert
+        #[arg(long, default_value_t = 256)]
+        batch: usize,
+        /// Append results to this JSON Lines file
+        #[arg(long)]
+        out: Option<PathBuf>,
+    },
+    /// Compare two result files run by run
+    Compare {
+        baseline: PathBuf,
+        current: PathBuf,
+        /// Fail when a phase's throughput drops by more than this many percent
+        #[arg(long)]
+        max_regression: Option<f64>,
+    },
+    /// List datasets, workloads and indexes
+    List,
+}
+
+fn parse_all<T>(names: &[String], what: &str, parse: impl Fn(&str) -> Option<T>) -> Result<Vec<T>> {
+    names
+        .iter()
+        .map(|n| parse(n.trim()).with_context(|| format!("unknown {what} '{n}'")))
+        .collect()
+}
+
+fn read_results(path: &Path) -> Result<Vec<BenchResult>> {
+    let text =
+        std::fs::read_to_string(path).with_context(|| format!("cannot read {}", path.display()))?;
+    text.lines()
+        .enumerate()
+        .filter(|(_, l)| !l.trim().is_empty())
+        .map(|(i, l)| {
+            serde_json::from_str(l).with_context(|| format!("{}:{}", path.display(), i + 1))
+        })
+        .collect()
+}
+
+fn main() -> Result<()> {
+    match Cli::parse().command {
+        Command::Run {
+            dataset,
+            workload,
+            index,
+            n,
+            dim,
+            seed,
+            ops,
+            k,
+            batch,
+            out,
+        } => {
+            let datasets = parse_all(&dataset, "dataset", DatasetKind::from_name)?;
+            let workloads = parse_all(&workload, "workload", WorkloadKind::from_name)?;
+            let indexes = parse_all(&index, "index", IndexKind::from_name)?;
+            let mut sink = match &out {
+                Some(path) => Some(
+                    std::fs::OpenOptions::new()
+                        .create(true)
+                        .append(true)
+                        .open(path)
+                        .with_context(|| format!("cannot open {}", path.display()))?,
+                ),
+                None => None,
+            };
+            for &d in &datasets {
+                for &w in &workloads {
+                    for &i in &indexes {
+                        let spec = BenchSpec {
+                            dataset: DatasetSpec::new(d, n, dim, seed),
+                            workload: WorkloadSpec {
+                                kind: w,
+                                ops,
+                                k,
+                                batch,
+                            },
+                            index: i,
+                        };
+                        let result = valori_bench::run(&spec)?;
+                        let line = serde_json::to_string(&result)?;
+                        println!("{line}");
+                        if let Some(f) = sink.as_mut() {
+                            writeln!(f, "{line}")?;
+                        }
+                        let phases: Vec<String> = result
+                            .phases
+                            .iter()
+                            .map(|p| {
+                                format!(
+                                    "{} {:.0} ops/s p99 {:.0}us",
+                                    p.name, p.ops_per_sec, p.p99_us
+                                )
+                            })
+                            .collect();
+                        eprintln!(
+                            "{}: {} | recall {} | hash {}",
+                            result.key(),
+                            phases.join(", "),
+                            result.recall.map_or("-".to_string(), |r| format!("{r:.4}")),
+                            &result.state_hash[..16.min(result.state_hash.len())],
+                        );
+                    }
+                }
+            }
+        }
+        Command::Compare {
+            baseline,
+            current,
+            max_regression,
+        } => {
+            let base: BTreeMap<String, BenchResult> = read_results(&baseline)?
+                .into_iter()
+                .map(|r| (r.key(), r))
+                .collect();
+            let mut failed = 0;
+            let mut matched = 0;
+            for cur in read_results(&current)? {
+                let Some(b) = base.get(&cur.key()) else {
+                    println!("{}: no baseline", cur.key());
+                    continue;
+                };
+                matched += 1;
+                let cmp = Comparison::new(b, &cur);
+                let changes: Vec<String> = cmp
+                    .throughput
+                    .iter()
+                    .map(|(name, _, _, change)| format!("{name} {change:+.1}%"))
+                    .collect();
+                let regressed = max_regression.map_or(Vec::new(), |m| cmp.regressions(m));
+                let verdict = if !cmp.mismatches.is_empty() {
+                    format!("NONDETERMINISTIC ({})", cmp.mismatches.join(", "))
+                } else if !regressed.is_empty() {
+                    format!("REGRESSED ({})", regressed.join(", "))
+                } else {
+                    "ok".to_string()
+                };
+                if verdict != "ok" {
+                    failed += 1;
+                }
+                println!("{}: {verdict} | {}", cmp.key, changes.join(", "));
+            }
+            if matched == 0 {
+                bail!(
+                    "no runs in {} match {}",
+                    current.display(),
+                    baseline.display()
+                );
+            }
+            if failed > 0 {
+                bail!("{failed} of {matched} runs failed");
+            }
+        }
+        Command::List => {
+            let names = |v: Vec<&str>| v.join(", ");
+            println!(
+                "datasets:  {}",
+                names(DatasetKind::ALL.iter().map(|k| k.name()).collect())
+            );
+            println!(
+                "workloads: {}",
+                names(WorkloadKind::ALL.iter().map(|k| k.name()).collect())
+            );
+            println!("indexes:   brute_force, hnsw, ivf, bq, auto");
+        }
+    }
+    Ok(())
+}
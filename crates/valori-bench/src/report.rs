@@ -0,0 +1,153 @@
+// Copyright (c) 2025 Varshith Gudur. Dual-licensed under MIT OR Apache-2.0.
+//! Machine-readable results.
+//!
+//! A run is one JSON object. `valori-bench run --out` appends it as a line
+//! to a JSON Lines file, and `valori-bench compare` matches two such files
+//! run by run.
+//!
+//! Fields split into two groups. The **deterministic** ones — `state_hash`,
+//! `records`, `recall` — depend only on the spec and the code, so any
+//! change between releases is a behaviour change. The **timing** ones —
+//! `phases` — are what regressions are tracked on.
+
+use serde::{Deserialize, Serialize};
+use std::time::Duration;
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct BenchResult {
+    /// `valori-bench` version that produced the result.
+    pub version: String,
+    pub dataset: String,
+    pub workload: String,
+    pub index: String,
+    pub n: usize,
+    pub dim: usize,
+    pub seed: u64,
+    pub k: usize,
+    pub phases: Vec<PhaseStats>,
+    /// Recall@k of the index against exact search, for workloads that
+    /// search.
+    pub recall: Option<f64>,
+    /// Live records at the end of the run.
+    pub records: usize,
+    /// Engine state hash at the end of the run.
+    pub state_hash: String,
+}
+
+impl BenchResult {
+    /// Everything that identifies a run, for matching results across
+    /// releases.
+    pub fn key(&self) -> String {
+        format!(
+            "{}/{}/{} n={} dim={} k={} seed={}",
+            self.dataset, self.workload, self.index, self.n, self.dim, self.k, self.seed
+        )
+    }
+
+    pub fn phase(&self, name: &str) -> Option<&PhaseStats> {
+        self.phases.iter().find(|p| p.name == name)
+    }
+}
+
+/// Latency and throughput of one phase of a workload.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct PhaseStats {
+    pub name: String,
+    pub ops: usize,
+    pub total_ms: f64,
+    pub ops_per_sec: f64,
+    pub p50_us: f64,
+    pub p95_us: f64,
+    pub p99_us: f64,
+    pub max_us: f64,
+}
+
+impl PhaseStats {
+    /// Stats over per-operation latencies. `ops` may exceed the number of
+    /// samples when one sample covers a batch.
+    pub fn new(name: &str, ops: usize, total: Duration, mut samples: Vec<Duration>) -> Self {
+        samples.sort_unstable();
+        let pct = |p: f64| -> f64 {
+            if samples.is_empty() {
+                return 0.0;
+            }
+            let rank = ((p / 100.0) * samples.len() as f64).ceil() as usize;
+            micros(samples[rank.clamp(1, samples.len()) - 1])
+        };
+        let total_ms = total.as_secs_f64() * 1000.0;
+        Self {
+            name: name.to_string(),
+            ops,
+            total_ms: round3(total_ms),
+            ops_per_sec: if total_ms > 0.0 {
+                round3(ops as f64 / total.as_secs_f64())
+            } else {
+                0.0
+            },
+            p50_us: pct(50.0),
+            p95_us: pct(95.0),
+            p99_us: pct(99.0),
+            max_us: samples.last().copied().map_or(0.0, micros),
+        }
+    }
+}
+
+fn micros(d: Duration) -> f64 {
+    round3(d.as_secs_f64() * 1e6)
+}
+
+fn round3(v: f64) -> f64 {
+    (v * 1000.0).round() / 1000.0
+}
+
+/// How a run compared with its baseline.
+#[derive(Debug, Clone, PartialEq, Serialize)]
+pub struct Comparison {
+    pub key: String,
+    /// Deterministic fields that changed, e.g. `state_hash`.
+    pub mismatches: Vec<String>,
+    /// Per phase: `(name, baseline ops/s, current ops/s, change in %)`.
+    pub throughput: Vec<(String, f64, f64, f64)>,
+}
+
+impl Comparison {
+    pub fn new(baseline: &BenchResult, current: &BenchResult) -> Self {
+        let mut mismatches = Vec::new();
+        if baseline.state_hash != current.state_hash {
+            mismatches.push("state_hash".to_string());
+        }
+        if baseline.records != current.records {
+            mismatches.push("records".to_string());
+        }
+        if baseline.recall != current.recall {
+            mismatches.push("recall".to_string());
+        }
+        let throughput = baseline
+            .phases
+            .iter()
+            .filter_map(|b| {
+                let c = current.phase(&b.name)?;
+                let change = if b.ops_per_sec > 0.0 {
+                    round3((c.ops_per_sec - b.ops_per_sec) / b.ops_per_sec * 100.0)
+                } else {
+                    0.0
+                };
+                Some((b.name.clone(), b.ops_per_sec, c.ops_per_sec, change))
+            })
+            .collect();
+        Self {
+            key: current.key(),
+            mismatches,
+            throughput,
+        }
+    }
+
+    /// Phases whose throughput dropped by more than `max_drop_pct`.
+    pub fn regressions(&self, max_drop_pct: f64) -> Vec<&str> {
+        self.throughput
+            .iter()
+            .filter(|(_, _, _, change)| *change < -max_drop_pct)
+            .map(|(name, ..)| name.as_str())
+            .collect()
+    }
+}
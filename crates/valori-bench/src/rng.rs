@@ -0,0 +1,52 @@
+// Copyright (c) 2025 Varshith Gudur. Dual-licensed under MIT OR Apache-2.0.
+//! SplitMix64, kept in-tree so a seed means the same stream on every
+//! platform and every release. An upstream RNG crate is free to change its
+//! algorithm between versions; this one is not.
+
+#[derive(Debug, Clone)]
+pub struct SplitMix64(u64);
+
+impl SplitMix64 {
+    pub fn new(seed: u64) -> Self {
+        Self(seed)
+    }
+
+    /// An independent stream for `stream`, so queries and workload ops do
+    /// not shift when the dataset size changes.
+    pub fn fork(seed: u64, stream: u64) -> Self {
+        let mut base = Self(seed ^ stream.wrapping_mul(0xA076_1D64_78BD_642F));
+        Self(base.next_u64())
+    }
+
+    pub fn next_u64(&mut self) -> u64 {
+        self.0 = self.0.wrapping_add(0x9E37_79B9_7F4A_7C15);
+        let mut z = self.0;
+        z = (z ^ (z >> 30)).wrapping_mul(0xBF58_476D_1CE4_E5B9);
+        z = (z ^ (z >> 27)).wrapping_mul(0x94D0_49BB_1331_11EB);
+        z ^ (z >> 31)
+    }
+
+    /// Uniform in `[0, n)`. `n` must be non-zero.
+    pub fn below(&mut self, n: u64) -> u64 {
+        ((self.next_u64() as u128 * n as u128) >> 64) as u64
+    }
+
+    /// Uniform in `[0, 1)` on a 2^-24 grid, so every value is exact in f32.
+    pub fn unit(&mut self) -> f32 {
+        (self.next_u64() >> 40) as f32 / (1u32 << 24) as f32
+    }
+
+    /// Uniform in `[-1, 1)`.
+    pub fn signed(&mut self) -> f32 {
+        self.unit() * 2.0 - 1.0
+    }
+
+    /// Approximately standard normal: the Irwin–Hall sum of four uniforms,
+    /// centred and scaled to unit variance. Only adds and multiplies, which
+    /// IEEE 754 rounds identically everywhere. `ln`/`cos` (Box–Muller) are
+    /// not guaranteed to.
+    pub fn normal(&mut self) -> f32 {
+        let s = self.unit() + self.unit() + self.unit() + self.unit();
+        (s - 2.0) * 1.732_050_8
+    }
+}
@@ -0,0 +1,197 @@
+// Copyright (c) 2025 Varshith Gudur. Dual-licensed under MIT OR Apache-2.0.
+//! Standard workloads.
+//!
+//! - **ingest** — every vector inserted on its own (`insert`).
+//! - **batch_ingest** — `batch` vectors per `insert_batch` call. Latency
+//!   samples are per batch.
+//! - **search** — bulk load (`load`), then `ops` top-`k` searches
+//!   (`search`) and a recall estimate.
+//! - **mixed** — load half the dataset, then `ops` operations drawn from
+//!   the seed: 70% search, 20% insert of the next unloaded vector, 10%
+//!   delete of a random live record. The op sequence, and so the final
+//!   state, depends only on the spec.
+
+use crate::dataset::DatasetSpec;
+use crate::report::PhaseStats;
+use crate::rng::SplitMix64;
+use anyhow::{Context, Result};
+use serde::{Deserialize, Serialize};
+use std::time::{Duration, Instant};
+use valori_engine::Engine;
+
+/// Queries used for the recall estimate at the end of searching workloads.
+const RECALL_SAMPLE: usize = 100;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum WorkloadKind {
+    Ingest,
+    BatchIngest,
+    Search,
+    Mixed,
+}
+
+impl WorkloadKind {
+    pub const ALL: [WorkloadKind; 4] = [
+        WorkloadKind::Ingest,
+        WorkloadKind::BatchIngest,
+        WorkloadKind::Search,
+        WorkloadKind::Mixed,
+    ];
+
+    pub fn name(self) -> &'static str {
+        match self {
+            WorkloadKind::Ingest => "ingest",
+            WorkloadKind::BatchIngest => "batch_ingest",
+            WorkloadKind::Search => "search",
+            WorkloadKind::Mixed => "mixed",
+        }
+    }
+
+    pub fn from_name(name: &str) -> Option<Self> {
+        Self::ALL
+            .into_iter()
+            .find(|k| k.name() == name.replace('-', "_"))
+    }
+
+    /// Whether the workload searches, and so reports recall.
+    pub fn searches(self) -> bool {
+        matches!(self, WorkloadKind::Search | WorkloadKind::Mixed)
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub struct WorkloadSpec {
+    pub kind: WorkloadKind,
+    /// Searches (`search`) or operations (`mixed`) after the load.
+    pub ops: usize,
+    pub k: usize,
+    /// Vectors per `insert_batch` call.
+    pub batch: usize,
+}
+
+impl WorkloadSpec {
+    pub fn new(kind: WorkloadKind) -> Self {
+        Self {
+            kind,
+            ops: 1000,
+            k: 10,
+            batch: 256,
+        }
+    }
+}
+
+/// What a workload leaves behind for the report.
+pub struct Outcome {
+    pub phases: Vec<PhaseStats>,
+    pub recall: Option<f64>,
+}
+
+/// Per-phase latency samples, kept in first-use order.
+#[derive(Default)]
+struct Timings(Vec<(&'static str, usize, Duration, Vec<Duration>)>);
+
+impl Timings {
+    fn record(&mut self, phase: &'static str, ops: usize, took: Duration) {
+        match self.0.iter_mut().find(|(name, ..)| *name == phase) {
+            Some((_, n, total, samples)) => {
+                *n += ops;
+                *total += took;
+                samples.push(took);
+            }
+            None => self.0.push((phase, ops, took, vec![took])),
+        }
+    }
+
+    fn finish(self) -> Vec<PhaseStats> {
+        self.0
+            .into_iter()
+            .map(|(name, ops, total, samples)| PhaseStats::new(name, ops, total, samples))
+            .collect()
+    }
+}
+
+fn timed<T>(t: &mut Timings, phase: &'static str, ops: usize, f: impl FnOnce() -> T) -> T {
+    let started = Instant::now();
+    let out = f();
+    t.record(phase, ops, started.elapsed());
+    out
+}
+
+fn load(
+    engine: &mut Engine,
+    t: &mut Timings,
+    phase: &'static str,
+    vectors: &[Vec<f32>],
+    batch: usize,
+) -> Result<Vec<u32>> {
+    let mut ids = Vec::with_capacity(vectors.len());
+    for chunk in vectors.chunks(batch.max(1)) {
+        ids.extend(timed(t, phase, chunk.len(), || engine.insert_batch(chunk))?);
+    }
+    Ok(ids)
+}
+
+/// Run `workload` over `dataset` against `engine`, which must be empty.
+pub fn run(engine: &mut Engine, dataset: &DatasetSpec, workload: &WorkloadSpec) -> Result<Outcome> {
+    let vectors = dataset.generate();
+    let mut t = Timings::default();
+    match workload.kind {
+        WorkloadKind::Ingest => {
+            for v in &vectors {
+                timed(&mut t, "insert", 1, || engine.insert_record_from_f32(v))?;
+            }
+        }
+        WorkloadKind::BatchIngest => {
+            load(engine, &mut t, "batch_insert", &vectors, workload.batch)?;
+        }
+        WorkloadKind::Search => {
+            load(engine, &mut t, "load", &vectors, workload.batch)?;
+            for q in dataset.queries(workload.ops) {
+                timed(&mut t, "search", 1, || engine.search_l2(&q, workload.k))?;
+            }
+        }
+        WorkloadKind::Mixed => {
+            let (initial, rest) = vectors.split_at(vectors.len() / 2);
+            let mut live = load(engine, &mut t, "load", initial, workload.batch)?;
+            let mut pending = rest.iter();
+            let mut queries = dataset.queries(workload.ops).into_iter();
+            let mut rng = SplitMix64::fork(dataset.seed, 2);
+            for _ in 0..workload.ops {
+                match rng.below(10) {
+                    0..=6 => {
+                        let q = queries.next().context("query stream ran short")?;
+                        timed(&mut t, "search", 1, || engine.search_l2(&q, workload.k))?;
+                    }
+                    7 | 8 => {
+                        if let Some(v) = pending.next() {
+                            let id =
+                                timed(&mut t, "insert", 1, || engine.insert_record_from_f32(v))?;
+                            live.push(id);
+                        }
+                    }
+                    _ => {
+                        if !live.is_empty() {
+                            let id = live.swap_remove(rng.below(live.len() as u64) as usize);
+                            timed(&mut t, "delete", 1, || engine.delete_record(id))?;
+                        }
+                    }
+                }
+            }
+        }
+    }
+
+    let recall = if workload.kind.searches() && engine.record_count() > 1 {
+        Some(
+            engine
+                .estimate_recall(RECALL_SAMPLE, workload.k, None)?
+                .recall,
+        )
+    } else {
+        None
+    };
+    Ok(Outcome {
+        phases: t.finish(),
+        recall,
+    })
+}
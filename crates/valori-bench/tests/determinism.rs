@@ -0,0 +1,123 @@
+// Copyright (c) 2025 Varshith Gudur. Dual-licensed under MIT OR Apache-2.0.
+//! The harness's contract: a spec fixes the data, the op sequence and the
+//! final state hash.
+
+use valori_bench::{BenchSpec, Comparison, DatasetKind, DatasetSpec, WorkloadKind, WorkloadSpec};
+use valori_engine::IndexKind;
+
+fn spec(dataset: DatasetKind, workload: WorkloadKind, index: IndexKind, seed: u64) -> BenchSpec {
+    BenchSpec {
+        dataset: DatasetSpec::new(dataset, 300, 8, seed),
+        workload: WorkloadSpec {
+            ops: 200,
+            k: 5,
+            batch: 64,
+            ..WorkloadSpec::new(workload)
+        },
+        index,
+    }
+}
+
+#[test]
+fn generators_are_seeded_and_in_range() {
+    for kind in DatasetKind::ALL {
+        let a = DatasetSpec::new(kind, 500, 16, 7);
+        let v = a.generate();
+        assert_eq!(v.len(), 500);
+        assert!(v.iter().all(|x| x.len() == 16));
+        assert!(v
+            .iter()
+            .flatten()
+            .all(|x| x.is_finite() && *x >= -32768.0 && *x <= 32767.99));
+        assert_eq!(v, a.generate(), "{kind:?} is not reproducible");
+        assert_ne!(v, DatasetSpec::new(kind, 500, 16, 8).generate());
+        assert_eq!(a.queries(20), a.queries(20));
+        // The query stream does not depend on the dataset size.
+        assert_eq!(a.queries(20), DatasetSpec::new(kind, 50, 16, 7).queries(20));
+    }
+}
+
+#[test]
+fn adversarial_data_contains_duplicates_and_zero_vectors() {
+    let v = DatasetSpec::new(DatasetKind::Adversarial, 120, 8, 3).generate();
+    assert!(v.iter().any(|x| x.iter().all(|c| *c == 0.0)));
+    let dups = (1..v.len()).filter(|&i| v[..i].contains(&v[i])).count();
+    assert!(dups >= 20, "only {dups} duplicates");
+}
+
+#[test]
+fn same_spec_gives_same_state_hash() {
+    for workload in WorkloadKind::ALL {
+        for dataset in DatasetKind::ALL {
+            let s = spec(dataset, workload, IndexKind::Hnsw, 42);
+            let a = valori_bench::run(&s).unwrap();
+            let b = valori_bench::run(&s).unwrap();
+            assert_eq!(a.state_hash, b.state_hash, "{}", a.key());
+            assert_eq!(a.records, b.records);
+            assert_eq!(a.recall, b.recall);
+            assert!(Comparison::new(&a, &b).mismatches.is_empty());
+            assert_eq!(a.recall.is_some(), workload.searches());
+        }
+    }
+}
+
+#[test]
+fn seed_and_workload_change_the_state_hash() {
+    let base = valori_bench::run(&spec(
+        DatasetKind::Clustered,
+        WorkloadKind::Mixed,
+        IndexKind::BruteForce,
+        1,
+    ))
+    .unwrap();
+    let reseeded = valori_bench::run(&spec(
+        DatasetKind::Clustered,
+        WorkloadKind::Mixed,
+        IndexKind::BruteForce,
+        2,
+    ))
+    .unwrap();
+    assert_ne!(base.state_hash, reseeded.state_hash);
+    assert_eq!(
+        Comparison::new(&base, &reseeded).mismatches[0],
+        "state_hash"
+    );
+
+    // Ingest and batch ingest of the same data end in the same state.
+    let single = valori_bench::run(&spec(
+        DatasetKind::Drifting,
+        WorkloadKind::Ingest,
+        IndexKind::BruteForce,
+        1,
+    ))
+    .unwrap();
+    let batched = valori_bench::run(&spec(
+        DatasetKind::Drifting,
+        WorkloadKind::BatchIngest,
+        IndexKind::BruteForce,
+        1,
+    ))
+    .unwrap();
+    assert_eq!(single.records, 300);
+    assert_eq!(single.state_hash, batched.state_hash);
+}
+
+#[test]
+fn exact_index_has_perfect_recall_and_results_round_trip() {
+    let r = valori_bench::run(&spec(
+        DatasetKind::Clustered,
+        WorkloadKind::Search,
+        IndexKind::BruteForce,
+        9,
+    ))
+    .unwrap();
+    assert_eq!(r.recall, Some(1.0));
+    let search = r.phase("search").unwrap();
+    assert_eq!(search.ops, 200);
+    assert!(search.p50_us <= search.p99_us && search.p99_us <= search.max_us);
+    assert_eq!(r.phase("load").unwrap().ops, 300);
+
+    let line = serde_json::to_string(&r).unwrap();
+    let back: valori_bench::BenchResult = serde_json::from_str(&line).unwrap();
+    assert_eq!((back.key(), back.state_hash), (r.key(), r.state_hash));
+}
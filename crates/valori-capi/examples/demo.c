@@ -0,0 +1,45 @@
+/* Copyright (c) 2025 Varshith Gudur. Dual-licensed under MIT OR Apache-2.0. */
+/* Minimal C host for libvalori_capi.
+ *
+ *   cargo build --release -p valori-capi
+ *   cc examples/demo.c -Iinclude -L../../target/release -lvalori_capi -o demo
+ *   LD_LIBRARY_PATH=../../target/release ./demo /tmp/valori-demo
+ */
+#include <stdio.h>
+#include "valori.h"
+
+#define DIM 4
+
+static int check(ValoriStatus status, const char *what) {
+  if (status != VALORI_STATUS_OK) {
+    const char *err = valori_last_error();
+    fprintf(stderr, "%s failed (%d): %s\n", what, (int)status, err ? err : "?");
+    return 1;
+  }
+  return 0;
+}
+
+int main(int argc, char **argv) {
+  const char *path = argc > 1 ? argv[1] : "valori-demo";
+  ValoriEngine *engine = NULL;
+  if (check(valori_engine_open(path, DIM, NULL, &engine), "open")) return 1;
+
+  float a[DIM] = {0.1f, 0.2f, 0.3f, 0.4f};
+  float b[DIM] = {0.9f, 0.9f, 0.9f, 0.9f};
+  uint32_t id;
+  if (check(valori_insert(engine, a, DIM, 0, &id), "insert")) return 1;
+  if (check(valori_insert(engine, b, DIM, 0, &id), "insert")) return 1;
+
+  ValoriHit hits[2];
+  size_t n = 0;
+  if (check(valori_search(engine, a, DIM, 2, false, 0, hits, &n), "search")) return 1;
+  for (size_t i = 0; i < n; i++) printf("hit %u score %f\n", hits[i].id, hits[i].score);
+
+  char *proof = NULL;
+  if (check(valori_proof_json(engine, &proof), "proof")) return 1;
+  printf("%s\n", proof);
+  valori_string_free(proof);
+
+  valori_engine_free(engine);
+  return 0;
+}
@@ -0,0 +1,164 @@
+/* Copyright (c) 2025 Varshith Gudur. Dual-licensed under MIT OR Apache-2.0. */
+/* Generated with cbindgen from crates/valori-capi/src/lib.rs — do not edit.
+ * Regenerate: cbindgen --config cbindgen.toml --crate valori-capi --output include/valori.h */
+
+#ifndef VALORI_H
+#define VALORI_H
+
+#include <stdarg.h>
+#include <stdbool.h>
+#include <stddef.h>
+#include <stdint.h>
+#include <stdlib.h>
+
+/**
+ * Result of every `valori_*` call.
+ */
+typedef enum ValoriStatus {
+  VALORI_STATUS_OK = 0,
+  /**
+   * A NULL pointer, bad UTF-8, wrong dimension or out-of-range value.
+   */
+  VALORI_STATUS_INVALID_ARGUMENT = 1,
+  /**
+   * The record does not exist.
+   */
+  VALORI_STATUS_NOT_FOUND = 2,
+  /**
+   * The engine failed (I/O, corrupt snapshot, capacity, …).
+   */
+  VALORI_STATUS_INTERNAL = 3,
+} ValoriStatus;
+
+/**
+ * Opaque engine handle.
+ */
+typedef struct ValoriEngine ValoriEngine;
+
+/**
+ * One search hit: record id and squared L2 distance (the `score` of
+ * `/v1/search`).
+ */
+typedef struct ValoriHit {
+  uint32_t id;
+  float score;
+} ValoriHit;
+
+/**
+ * Bytes owned by the library; release with [`valori_buffer_free`].
+ */
+typedef struct ValoriBuffer {
+  uint8_t *data;
+  size_t len;
+} ValoriBuffer;
+
+#ifdef __cplusplus
+extern "C" {
+#endif // __cplusplus
+
+/**
+ * The message for the last failed call on this thread, or NULL. Valid until
+ * the next `valori_*` call on the same thread.
+ */
+const char *valori_last_error(void);
+
+/**
+ * Open (or create) an engine for `dim`-element vectors stored under the
+ * directory `path`, recovering any state already there. `index_kind` is
+ * `"bruteforce"`, `"hnsw"` or `"ivf"`; NULL means brute force. Writes the
+ * handle to `*out`.
+ */
+ValoriStatus valori_engine_open(const char *path,
+                                size_t dim,
+                                const char *index_kind,
+                                ValoriEngine **out);
+
+/**
+ * Flush pending log entries and release the engine. NULL is a no-op.
+ */
+void valori_engine_free(ValoriEngine *handle);
+
+/**
+ * Insert a `dim`-element vector with filter tag `tag`; the new record id is
+ * written to `*out_id`.
+ */
+ValoriStatus valori_insert(const ValoriEngine *handle,
+                           const float *vector,
+                           size_t dim,
+                           uint64_t tag,
+                           uint32_t *out_id);
+
+/**
+ * Hard-delete record `id`.
+ */
+ValoriStatus valori_delete(const ValoriEngine *handle, uint32_t id);
+
+/**
+ * Up to `k` nearest records to the `dim`-element `query`, nearest first,
+ * written to `out_hits` (room for `k`); the count goes to `*out_len`.
+ * `filter_tag` restricts to records with that tag unless `has_filter` is 0.
+ */
+ValoriStatus valori_search(const ValoriEngine *handle,
+                           const float *query,
+                           size_t dim,
+                           size_t k,
+                           bool has_filter,
+                           uint64_t filter_tag,
+                           ValoriHit *out_hits,
+                           size_t *out_len);
+
+/**
+ * Number of live records.
+ */
+ValoriStatus valori_record_count(const ValoriEngine *handle, size_t *out);
+
+/**
+ * Encode a full snapshot into a library-owned buffer.
+ */
+ValoriStatus valori_snapshot(const ValoriEngine *handle, ValoriBuffer *out);
+
+/**
+ * Replace the engine state with a snapshot from [`valori_snapshot`] (or a
+ * node's `/v1/snapshot/download`).
+ */
+ValoriStatus valori_restore(const ValoriEngine *handle, const uint8_t *data, size_t len);
+
+/**
+ * Flush pending log entries and write the snapshot to
+ * `<path>/current.snap`, so the next open recovers from it.
+ */
+ValoriStatus valori_save_snapshot(const ValoriEngine *handle);
+
+/**
+ * Flush buffered log entries to disk.
+ */
+ValoriStatus valori_flush(const ValoriEngine *handle);
+
+/**
+ * The 32-byte BLAKE3 state hash, the `final_state_hash` of
+ * `/v1/proof/state`.
+ */
+ValoriStatus valori_state_hash(const ValoriEngine *handle, uint8_t *out);
+
+/**
+ * The `/v1/proof/event-log` document as a JSON string: `kernel_version`,
+ * `final_state_hash`, `event_log_hash`, `committed_height` and, after a
+ * quarantined log tail, `truncated`. Pending log entries are flushed first.
+ */
+ValoriStatus valori_proof_json(const ValoriEngine *handle, char **out);
+
+/**
+ * Release a buffer from [`valori_snapshot`]. A NULL `data` is a no-op.
+ */
+void valori_buffer_free(ValoriBuffer buffer);
+
+/**
+ * Release a string from [`valori_proof_json`]. NULL is a no-op.
+ */
+void valori_string_free(char *s);
+
+#ifdef __cplusplus
+}  // extern "C"
+#endif // __cplusplus
+
+#endif  /* VALORI_H */
@@ -0,0 +1,494 @@
+// Copyright (c) 2025 Varshith Gudur. Dual-licensed under MIT OR Apache-2.0.
+//! Stable `extern "C"` interface to the Valori engine.
+//!
+//! The C counterpart of the PyO3 binding in `valori-ffi`: the same embedded
+//! engine (WAL, event log and snapshots under one directory), driven through
+//! an opaque handle. The declarations live in `include/valori.h`.
+//!
+//! Conventions:
+//! * Every call returns a [`ValoriStatus`]; results come back through out
+//!   pointers. On failure [`valori_last_error`] describes what went wrong.
+//! * Memory the library allocates (snapshot buffers, JSON strings) is freed
+//!   with the matching `valori_*_free` call, never with the host's `free`.
+//! * A handle may be shared between threads; calls on it are serialised.
+
+use std::cell::RefCell;
+use std::ffi::{c_char, CStr, CString};
+use std::path::PathBuf;
+use std::sync::Mutex;
+
+use valori_kernel::fxp::ops::from_f32;
+use valori_kernel::types::id::{RecordId, DEFAULT_NS};
+use valori_kernel::types::scalar::FxpScalar;
+use valori_kernel::types::vector::FxpVector;
+use valori_node::config::{IndexKind, NodeConfig};
+use valori_node::engine::Engine;
+use valori_node::EngineFromNodeConfig;
+
+/// Result of every `valori_*` call.
+#[repr(C)]
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ValoriStatus {
+    Ok = 0,
+    /// A NULL pointer, bad UTF-8, wrong dimension or out-of-range value.
+    InvalidArgument = 1,
+    /// The record does not exist.
+    NotFound = 2,
+    /// The engine failed (I/O, corrupt snapshot, capacity, …).
+    Internal = 3,
+}
+
+/// One search hit: record id and squared L2 distance (the `score` of
+/// `/v1/search`).
+#[repr(C)]
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct ValoriHit {
+    pub id: u32,
+    pub score: f32,
+}
+
+/// Bytes owned by the library; release with [`valori_buffer_free`].
+#[repr(C)]
+#[derive(Debug)]
+pub struct ValoriBuffer {
+    pub data: *mut u8,
+    pub len: usize,
+}
+
+/// Opaque engine handle.
+pub struct ValoriEngine {
+    inner: Mutex<Engine>,
+}
+
+thread_local! {
+    static LAST_ERROR: RefCell<Option<CString>> = const { RefCell::new(None) };
+}
+
+fn fail(status: ValoriStatus, msg: impl Into<String>) -> ValoriStatus {
+    let msg = CString::new(msg.into().replace('\0', " ")).unwrap_or_default();
+    LAST_ERROR.with(|e| *e.borrow_mut() = Some(msg));
+    status
+}
+
+fn clear_error() {
+    LAST_ERROR.with(|e| *e.borrow_mut() = None);
+}
+
+/// Unwrap a `Result` inside a `ValoriStatus`-returning function.
+macro_rules! try_status {
+    ($expr:expr, $status:expr) => {
+        match $expr {
+            Ok(v) => v,
+            Err(e) => return fail($status, format!("{e:?}")),
+        }
+    };
+}
+
+/// Borrow the engine behind `handle`, failing on NULL or a poisoned lock.
+macro_rules! lock_engine {
+    ($handle:expr) => {{
+        clear_error();
+        let Some(handle) = (unsafe { $handle.as_ref() }) else {
+            return fail(ValoriStatus::InvalidArgument, "engine handle is NULL");
+        };
+        match handle.inner.lock() {
+            Ok(engine) => engine,
+            Err(_) => {
+                return fail(
+                    ValoriStatus::Internal,
+                    "engine mutex poisoned by a prior panic; reopen the engine",
+                )
+            }
+        }
+    }};
+}
+
+unsafe fn str_arg<'a>(ptr: *const c_char, what: &str) -> Result<&'a str, String> {
+    if ptr.is_null() {
+        return Err(format!("{what} is NULL"));
+    }
+    CStr::from_ptr(ptr)
+        .to_str()
+        .map_err(|_| format!("{what} is not valid UTF-8"))
+}
+
+unsafe fn slice_arg<'a, T>(ptr: *const T, len: usize, what: &str) -> Result<&'a [T], String> {
+    if len == 0 {
+        return Ok(&[]);
+    }
+    if ptr.is_null() {
+        return Err(format!("{what} is NULL"));
+    }
+    Ok(std::slice::from_raw_parts(ptr, len))
+}
+
+fn check_dim(engine: &Engine, len: usize) -> Result<(), String> {
+    match engine.kernel_dim() {
+        Some(dim) if dim != len => Err(format!(
+            "dimension mismatch: engine expects {dim}, got {len}"
+        )),
+        _ => Ok(()),
+    }
+}
+
+/// The message for the last failed call on this thread, or NULL. Valid until
+/// the next `valori_*` call on the same thread.
+#[no_mangle]
+pub extern "C" fn valori_last_error() -> *const c_char {
+    LAST_ERROR.with(|e| e.borrow().as_ref().map_or(std::ptr::null(), |s| s.as_ptr()))
+}
+
+/// Open (or create) an engine for `dim`-element vectors stored under the
+/// directory `path`, recovering any state already there. `index_kind` is
+/// `"bruteforce"`, `"hnsw"` or `"ivf"`; NULL means brute force. Writes the
+/// handle to `*out`.
+///
+/// # Safety
+/// `path` and a non-NULL `index_kind` must be NUL-terminated strings; `out`
+/// must be writable.
+#[no_mangle]
+pub unsafe extern "C" fn valori_engine_open(
+    path: *const c_char,
+    dim: usize,
+    index_kind: *const c_char,
+    out: *mut *mut ValoriEngine,
+) -> ValoriStatus {
+    clear_error();
+    if out.is_null() {
+        return fail(ValoriStatus::InvalidArgument, "out is NULL");
+    }
+    if dim == 0 {
+        return fail(ValoriStatus::InvalidArgument, "dim must be at least 1");
+    }
+    let path = try_status!(str_arg(path, "path"), ValoriStatus::InvalidArgument);
+    let index_kind = if index_kind.is_null() {
+        "bruteforce"
+    } else {
+        try_status!(
+            str_arg(index_kind, "index_kind"),
+            ValoriStatus::InvalidArgument
+        )
+    };
+
+    // Same clean config as the Python binding: NodeConfig::default() reads
+    // VALORI_* env vars, and server-only settings must not leak in.
+    let dir = PathBuf::from(path);
+    let config = NodeConfig {
+        auth_token: None,
+        keys_path: None,
+        object_store_url: None,
+        embed_provider: None,
+        cors_origin: None,
+        dim,
+        wal_path: Some(dir.join("wal.log")),
+        event_log_path: Some(dir.join("events.log")),
+        snapshot_path: Some(dir.join("current.snap")),
+        index_kind: match index_kind {
+            "hnsw" => IndexKind::Hnsw,
+            "ivf" => IndexKind::Ivf,
+            _ => IndexKind::BruteForce,
+        },
+        ..NodeConfig::default()
+    };
+    try_status!(std::fs::create_dir_all(&dir), ValoriStatus::Internal);
+
+    let mut engine = Engine::new(&config);
+    engine.try_recover();
+    *out = Box::into_raw(Box::new(ValoriEngine {
+        inner: Mutex::new(engine),
+    }));
+    ValoriStatus::Ok
+}
+
+/// Flush pending log entries and release the engine. NULL is a no-op.
+///
+/// # Safety
+/// `handle` must come from [`valori_engine_open`] and not be used again.
+#[no_mangle]
+pub unsafe extern "C" fn valori_engine_free(handle: *mut ValoriEngine) {
+    if handle.is_null() {
+        return;
+    }
+    let mut engine = Box::from_raw(handle)
+        .inner
+        .into_inner()
+        .unwrap_or_else(|e| e.into_inner());
+    if let Some(c) = engine.event_committer_mut() {
+        let _ = c.flush_pending();
+    }
+}
+
+/// Insert a `dim`-element vector with filter tag `tag`; the new record id is
+/// written to `*out_id`.
+///
+/// # Safety
+/// `vector` must point to `dim` floats; `out_id` must be writable.
+#[no_mangle]
+pub unsafe extern "C" fn valori_insert(
+    handle: *const ValoriEngine,
+    vector: *const f32,
+    dim: usize,
+    tag: u64,
+    out_id: *mut u32,
+) -> ValoriStatus {
+    let mut engine = lock_engine!(handle);
+    if out_id.is_null() {
+        return fail(ValoriStatus::InvalidArgument, "out_id is NULL");
+    }
+    let vector = try_status!(
+        slice_arg(vector, dim, "vector"),
+        ValoriStatus::InvalidArgument
+    );
+    try_status!(check_dim(&engine, dim), ValoriStatus::InvalidArgument);
+
+    let mut data = Vec::with_capacity(dim);
+    for (i, &f) in vector.iter().enumerate() {
+        if !(-32767.0..=32767.0).contains(&f) {
+            return fail(
+                ValoriStatus::InvalidArgument,
+                format!("float at index {i} ({f}) outside valid Q16.16 range [-32767, 32767]"),
+            );
+        }
+        data.push(FxpScalar(from_f32(f).0));
+    }
+    let id = try_status!(
+        engine.insert_record_fxp(FxpVector { data }, None, tag, DEFAULT_NS.0),
+        ValoriStatus::Internal
+    );
+    *out_id = id;
+    ValoriStatus::Ok
+}
+
+/// Hard-delete record `id`.
+///
+/// # Safety
+/// `handle` must be a live engine handle.
+#[no_mangle]
+pub unsafe extern "C" fn valori_delete(handle: *const ValoriEngine, id: u32) -> ValoriStatus {
+    let mut engine = lock_engine!(handle);
+    if engine.get_record(RecordId(id)).is_none() {
+        return fail(ValoriStatus::NotFound, format!("record {id} not found"));
+    }
+    try_status!(engine.delete_record(id), ValoriStatus::Internal);
+    ValoriStatus::Ok
+}
+
+/// Up to `k` nearest records to the `dim`-element `query`, nearest first,
+/// written to `out_hits` (room for `k`); the count goes to `*out_len`.
+/// `filter_tag` restricts to records with that tag unless `has_filter` is 0.
+///
+/// # Safety
+/// `query` must point to `dim` floats, `out_hits` to `k` writable hits and
+/// `out_len` must be writable.
+#[no_mangle]
+pub unsafe extern "C" fn valori_search(
+    handle: *const ValoriEngine,
+    query: *const f32,
+    dim: usize,
+    k: usize,
+    has_filter: bool,
+    filter_tag: u64,
+    out_hits: *mut ValoriHit,
+    out_len: *mut usize,
+) -> ValoriStatus {
+    let engine = lock_engine!(handle);
+    if out_len.is_null() || (k > 0 && out_hits.is_null()) {
+        return fail(ValoriStatus::InvalidArgument, "out_hits or out_len is NULL");
+    }
+    let query = try_status!(
+        slice_arg(query, dim, "query"),
+        ValoriStatus::InvalidArgument
+    );
+    try_status!(check_dim(&engine, dim), ValoriStatus::InvalidArgument);
+
+    // The ANN index has no tag awareness, so a filtered query falls back to
+    // the tag-filtered brute-force scan — as in the Python binding.
+    let hits = if has_filter {
+        try_status!(
+            engine.search_l2_filtered(query, k, Some(filter_tag)),
+            ValoriStatus::Internal
+        )
+    } else {
+        engine.index.search(query, k)
+    };
+    let n = hits.len().min(k);
+    for (i, &(id, score)) in hits.iter().take(n).enumerate() {
+        *out_hits.add(i) = ValoriHit { id, score };
+    }
+    *out_len = n;
+    ValoriStatus::Ok
+}
+
+/// Number of live records.
+///
+/// # Safety
+/// `out` must be writable.
+#[no_mangle]
+pub unsafe extern "C" fn valori_record_count(
+    handle: *const ValoriEngine,
+    out: *mut usize,
+) -> ValoriStatus {
+    let engine = lock_engine!(handle);
+    if out.is_null() {
+        return fail(ValoriStatus::InvalidArgument, "out is NULL");
+    }
+    *out = engine.record_count();
+    ValoriStatus::Ok
+}
+
+/// Encode a full snapshot into a library-owned buffer.
+///
+/// # Safety
+/// `out` must be writable; free the buffer with [`valori_buffer_free`].
+#[no_mangle]
+pub unsafe extern "C" fn valori_snapshot(
+    handle: *const ValoriEngine,
+    out: *mut ValoriBuffer,
+) -> ValoriStatus {
+    let engine = lock_engine!(handle);
+    if out.is_null() {
+        return fail(ValoriStatus::InvalidArgument, "out is NULL");
+    }
+    let bytes = try_status!(engine.snapshot(), ValoriStatus::Internal).into_boxed_slice();
+    let len = bytes.len();
+    *out = ValoriBuffer {
+        data: Box::into_raw(bytes) as *mut u8,
+        len,
+    };
+    ValoriStatus::Ok
+}
+
+/// Replace the engine state with a snapshot from [`valori_snapshot`] (or a
+/// node's `/v1/snapshot/download`).
+///
+/// # Safety
+/// `data` must point to `len` readable bytes.
+#[no_mangle]
+pub unsafe extern "C" fn valori_restore(
+    handle: *const ValoriEngine,
+    data: *const u8,
+    len: usize,
+) -> ValoriStatus {
+    let mut engine = lock_engine!(handle);
+    let data = try_status!(slice_arg(data, len, "data"), ValoriStatus::InvalidArgument);
+    try_status!(engine.restore(data), ValoriStatus::Internal);
+    ValoriStatus::Ok
+}
+
+/// Flush pending log entries and write the snapshot to
+/// `<path>/current.snap`, so the next open recovers from it.
+///
+/// # Safety
+/// `handle` must be a live engine handle.
+#[no_mangle]
+pub unsafe extern "C" fn valori_save_snapshot(handle: *const ValoriEngine) -> ValoriStatus {
+    let mut engine = lock_engine!(handle);
+    if let Some(c) = engine.event_committer_mut() {
+        try_status!(c.flush_pending(), ValoriStatus::Internal);
+    }
+    try_status!(engine.save_snapshot(None), ValoriStatus::Internal);
+    ValoriStatus::Ok
+}
+
+/// Flush buffered log entries to disk.
+///
+/// # Safety
+/// `handle` must be a live engine handle.
+#[no_mangle]
+pub unsafe extern "C" fn valori_flush(handle: *const ValoriEngine) -> ValoriStatus {
+    let mut engine = lock_engine!(handle);
+    if let Some(c) = engine.event_committer_mut() {
+        try_status!(c.flush_pending(), ValoriStatus::Internal);
+    }
+    ValoriStatus::Ok
+}
+
+/// The 32-byte BLAKE3 state hash, the `final_state_hash` of
+/// `/v1/proof/state`.
+///
+/// # Safety
+/// `out` must point to 32 writable bytes.
+#[no_mangle]
+pub unsafe extern "C" fn valori_state_hash(
+    handle: *const ValoriEngine,
+    out: *mut u8,
+) -> ValoriStatus {
+    let engine = lock_engine!(handle);
+    if out.is_null() {
+        return fail(ValoriStatus::InvalidArgument, "out is NULL");
+    }
+    let hash = engine.get_proof().final_state_hash;
+    std::ptr::copy_nonoverlapping(hash.as_ptr(), out, hash.len());
+    ValoriStatus::Ok
+}
+
+/// The `/v1/proof/event-log` document as a JSON string: `kernel_version`,
+/// `final_state_hash`, `event_log_hash`, `committed_height` and, after a
+/// quarantined log tail, `truncated`. Pending log entries are flushed first.
+///
+/// # Safety
+/// `out` must be writable; free the string with [`valori_string_free`].
+#[no_mangle]
+pub unsafe extern "C" fn valori_proof_json(
+    handle: *const ValoriEngine,
+    out: *mut *mut c_char,
+) -> ValoriStatus {
+    let mut engine = lock_engine!(handle);
+    if out.is_null() {
+        return fail(ValoriStatus::InvalidArgument, "out is NULL");
+    }
+    if let Some(c) = engine.event_committer_mut() {
+        try_status!(c.flush_pending(), ValoriStatus::Internal);
+    }
+    let mut body = serde_json::json!({
+        "kernel_version": engine.get_proof().kernel_version,
+        "final_state_hash": engine.state_hash_hex(),
+        "event_log_hash": null,
+        "committed_height": null,
+    });
+    if let Some(committer) = engine.event_committer() {
+        let log = committer.event_log();
+        let hash = match log.log_hash() {
+            Some(h) => h,
+            None => try_status!(
+                valori_node::events::event_proof::compute_log_hash(log.path()),
+                ValoriStatus::Internal
+            ),
+        };
+        body["event_log_hash"] = serde_json::json!(hex::encode(hash));
+        body["committed_height"] = serde_json::json!(committer.journal().committed_height());
+    }
+    if let Some(damage) = &engine.damage {
+        body["truncated"] = serde_json::json!(damage);
+    }
+    // JSON never contains a raw NUL, so this cannot fail.
+    *out = CString::new(body.to_string())
+        .unwrap_or_default()
+        .into_raw();
+    ValoriStatus::Ok
+}
+
+/// Release a buffer from [`valori_snapshot`]. A NULL `data` is a no-op.
+///
+/// # Safety
+/// `buffer` must be exactly as returned and not freed before.
+#[no_mangle]
+pub unsafe extern "C" fn valori_buffer_free(buffer: ValoriBuffer) {
+    if !buffer.data.is_null() {
+        drop(Box::from_raw(std::ptr::slice_from_raw_parts_mut(
+            buffer.data,
+            buffer.len,
+        )));
+    }
+}
+
+/// Release a string from [`valori_proof_json`]. NULL is a no-op.
+///
+/// # Safety
+/// `s` must come from this library and not be freed before.
+#[no_mangle]
+pub unsafe extern "C" fn valori_string_free(s: *mut c_char) {
+    if !s.is_null() {
+        drop(CString::from_raw(s));
+    }
+}
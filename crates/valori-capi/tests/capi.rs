@@ -0,0 +1,190 @@
+// Copyright (c) 2025 Varshith Gudur. Dual-licensed under MIT OR Apache-2.0.
+//! Drive the C ABI exactly as a C host would: raw pointers, status codes and
+//! library-owned buffers.
+
+use std::ffi::{CStr, CString};
+use std::ptr;
+
+use valori_capi::*;
+
+const DIM: usize = 4;
+
+fn open(dir: &std::path::Path) -> *mut ValoriEngine {
+    let path = CString::new(dir.to_str().unwrap()).unwrap();
+    let mut handle = ptr::null_mut();
+    let status = unsafe { valori_engine_open(path.as_ptr(), DIM, ptr::null(), &mut handle) };
+    assert_eq!(status, ValoriStatus::Ok);
+    assert!(!handle.is_null());
+    handle
+}
+
+fn insert(h: *const ValoriEngine, v: &[f32], tag: u64) -> u32 {
+    let mut id = u32::MAX;
+    let status = unsafe { valori_insert(h, v.as_ptr(), v.len(), tag, &mut id) };
+    assert_eq!(status, ValoriStatus::Ok);
+    id
+}
+
+fn search(h: *const ValoriEngine, q: &[f32], k: usize, tag: Option<u64>) -> Vec<ValoriHit> {
+    let mut hits = vec![ValoriHit { id: 0, score: 0.0 }; k];
+    let mut n = 0;
+    let status = unsafe {
+        valori_search(
+            h,
+            q.as_ptr(),
+            q.len(),
+            k,
+            tag.is_some(),
+            tag.unwrap_or(0),
+            hits.as_mut_ptr(),
+            &mut n,
+        )
+    };
+    assert_eq!(status, ValoriStatus::Ok);
+    hits.truncate(n);
+    hits
+}
+
+fn state_hash(h: *const ValoriEngine) -> [u8; 32] {
+    let mut out = [0u8; 32];
+    assert_eq!(
+        unsafe { valori_state_hash(h, out.as_mut_ptr()) },
+        ValoriStatus::Ok
+    );
+    out
+}
+
+fn last_error() -> String {
+    let p = valori_last_error();
+    assert!(!p.is_null());
+    unsafe { CStr::from_ptr(p) }.to_string_lossy().into_owned()
+}
+
+#[test]
+fn insert_search_delete() {
+    let dir = tempfile::tempdir().unwrap();
+    let h = open(dir.path());
+
+    let a = insert(h, &[0.1, 0.2, 0.3, 0.4], 1);
+    let b = insert(h, &[0.9, 0.9, 0.9, 0.9], 2);
+    let hits = search(h, &[0.1, 0.2, 0.3, 0.4], 2, None);
+    assert_eq!(hits.iter().map(|x| x.id).collect::<Vec<_>>(), vec![a, b]);
+    assert!(hits[0].score < hits[1].score);
+
+    let tagged = search(h, &[0.1, 0.2, 0.3, 0.4], 2, Some(2));
+    assert_eq!(tagged.len(), 1);
+    assert_eq!(tagged[0].id, b);
+
+    assert_eq!(unsafe { valori_delete(h, a) }, ValoriStatus::Ok);
+    let mut count = 0;
+    unsafe { valori_record_count(h, &mut count) };
+    assert_eq!(count, 1);
+    assert_eq!(unsafe { valori_delete(h, a) }, ValoriStatus::NotFound);
+    assert!(last_error().contains("not found"));
+
+    unsafe { valori_engine_free(h) };
+}
+
+#[test]
+fn bad_arguments_set_last_error() {
+    let dir = tempfile::tempdir().unwrap();
+    let h = open(dir.path());
+    insert(h, &[0.0; DIM], 0);
+
+    let mut id = 0;
+    let wrong_dim = [0.0f32; DIM + 1];
+    let status = unsafe { valori_insert(h, wrong_dim.as_ptr(), wrong_dim.len(), 0, &mut id) };
+    assert_eq!(status, ValoriStatus::InvalidArgument);
+    assert!(last_error().contains("dimension mismatch"));
+
+    let status = unsafe { valori_insert(h, ptr::null(), DIM, 0, &mut id) };
+    assert_eq!(status, ValoriStatus::InvalidArgument);
+    assert_eq!(
+        unsafe { valori_record_count(ptr::null(), &mut 0) },
+        ValoriStatus::InvalidArgument
+    );
+
+    // A successful call clears the previous error.
+    insert(h, &[0.5; DIM], 0);
+    assert!(valori_last_error().is_null());
+
+    unsafe { valori_engine_free(h) };
+}
+
+#[test]
+fn snapshot_restore_and_reopen() {
+    let dir = tempfile::tempdir().unwrap();
+    let h = open(dir.path());
+    insert(h, &[0.1, 0.2, 0.3, 0.4], 0);
+    let before = state_hash(h);
+
+    let mut buf = ValoriBuffer {
+        data: ptr::null_mut(),
+        len: 0,
+    };
+    assert_eq!(unsafe { valori_snapshot(h, &mut buf) }, ValoriStatus::Ok);
+    assert!(buf.len > 0);
+
+    let other_dir = tempfile::tempdir().unwrap();
+    let other = open(other_dir.path());
+    assert_eq!(
+        unsafe { valori_restore(other, buf.data, buf.len) },
+        ValoriStatus::Ok
+    );
+    assert_eq!(state_hash(other), before);
+    unsafe { valori_buffer_free(buf) };
+    unsafe { valori_engine_free(other) };
+
+    // Saved state survives closing and reopening the directory.
+    assert_eq!(unsafe { valori_save_snapshot(h) }, ValoriStatus::Ok);
+    unsafe { valori_engine_free(h) };
+    let reopened = open(dir.path());
+    assert_eq!(state_hash(reopened), before);
+    unsafe { valori_engine_free(reopened) };
+}
+
+#[test]
+fn proof_json_matches_state_hash() {
+    let dir = tempfile::tempdir().unwrap();
+    let h = open(dir.path());
+    insert(h, &[0.3; DIM], 0);
+    insert(h, &[0.6; DIM], 0);
+
+    let mut s = ptr::null_mut();
+    assert_eq!(unsafe { valori_proof_json(h, &mut s) }, ValoriStatus::Ok);
+    let json: serde_json::Value =
+        serde_json::from_str(unsafe { CStr::from_ptr(s) }.to_str().unwrap()).unwrap();
+    unsafe { valori_string_free(s) };
+
+    assert_eq!(json["final_state_hash"], hex::encode(state_hash(h)));
+    assert_eq!(json["committed_height"], 2);
+    assert_eq!(json["event_log_hash"].as_str().unwrap().len(), 64);
+    unsafe { valori_engine_free(h) };
+}
+
+/// `include/valori.h` is checked in; every exported function must be
+/// declared there and nothing stale may linger.
+#[test]
+fn header_declares_every_export() {
+    let root = env!("CARGO_MANIFEST_DIR");
+    let src = std::fs::read_to_string(format!("{root}/src/lib.rs")).unwrap();
+    let header = std::fs::read_to_string(format!("{root}/include/valori.h")).unwrap();
+
+    let exported: Vec<&str> = src
+        .split("extern \"C\" fn ")
+        .skip(1)
+        .map(|rest| rest.split('(').next().unwrap())
+        .collect();
+    let declared: Vec<&str> = header
+        .split_whitespace()
+        .filter_map(|w| w.trim_start_matches('*').split('(').next())
+        .filter(|w| w.starts_with("valori_") && !w.ends_with(".h"))
+        .collect();
+
+    for f in &exported {
+        assert!(declared.contains(f), "{f} missing from include/valori.h");
+    }
+    for f in &declared {
+        assert!(exported.contains(f), "{f} in valori.h is not exported");
+    }
+}
@@ -5,7 +5,7 @@ use std::fs::File;
 use std::time::Instant;
 use valori_kernel::adapters::sift_batch::SiftBatchLoader; // The "Senior" way to cast types
 
-const Q16_SCALE: f32 = 65536.0;
+use valori_protocol::fxp::SCALE_F32 as Q16_SCALE;
 
 fn main() -> Result<()> {
     println!("🚀 Starting SIFT1M Granular Benchmark...");
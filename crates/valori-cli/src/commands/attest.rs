@@ -0,0 +1,200 @@
+// Copyright (c) 2025 Varshith Gudur. Dual-licensed under MIT OR Apache-2.0.
+//! `valori attest` — check that a running peer and a local event log agree
+//! on the state they produce.
+//!
+//! Fetches the peer's proof at a height (`GET /v1/proof/at`), replays the
+//! local log (with its archived segments) to the same height in a fresh
+//! kernel, and compares event chain, record / node / edge section hashes and
+//! the state hash, in that order. On a different event history the first
+//! divergent event is located by bisecting the peer's
+//! `GET /v1/replication/chain`. Exits non-zero unless the two agree byte for
+//! byte.
+//!
+//! ```text
+//! valori attest --peer http://10.0.0.2:3000 --log /data/events.log
+//! valori attest --peer http://10.0.0.2:3000 --log /data/events.log --at 5000 --json
+//! ```
+
+use anyhow::{bail, Context, Result};
+use serde::Serialize;
+use valori_node::attest::{first_divergence, prove, AttestProof, Section};
+use valori_node::geo_replication::{chain_hex, event_chain};
+use valori_storage::events::event_replay::read_all_segments;
+
+pub struct AttestArgs {
+    pub peer: String,
+    pub log: String,
+    /// Height to compare at (default: the lower of the two heads).
+    pub at: Option<u64>,
+    pub token: Option<String>,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+pub struct AttestReport {
+    pub peer: String,
+    pub height: u64,
+    /// Events in the local log and committed on the peer.
+    pub local_head: u64,
+    pub peer_head: u64,
+    pub agree: bool,
+    /// First section that differs (`None` when `agree`).
+    pub divergent_section: Option<Section>,
+    /// First event (1-based height) the histories disagree on, when the
+    /// divergent section is `events`.
+    pub first_divergent_event: Option<u64>,
+    pub local: AttestProof,
+    pub remote: AttestProof,
+}
+
+struct Peer<'a> {
+    base: &'a str,
+    token: Option<&'a str>,
+}
+
+impl Peer<'_> {
+    fn get(&self, path: &str, what: &str) -> Result<serde_json::Value> {
+        let mut req = ureq::get(&format!("{}{path}", self.base));
+        if let Some(t) = self.token {
+            req = req.set("Authorization", &format!("Bearer {t}"));
+        }
+        match req.call() {
+            Ok(resp) => resp
+                .into_json()
+                .with_context(|| format!("{what} response was not JSON")),
+            Err(ureq::Error::Status(code, resp)) => {
+                let msg = resp
+                    .into_json::<serde_json::Value>()
+                    .ok()
+                    .and_then(|b| b["error"].as_str().map(str::to_string))
+                    .unwrap_or_default();
+                bail!("{what} failed (HTTP {code}) {msg}")
+            }
+            Err(e) => bail!("cannot reach {}: {e}", self.base),
+        }
+    }
+
+    fn chain_hash(&self, height: u64) -> Result<String> {
+        let body = self.get(
+            &format!("/v1/replication/chain?height={height}"),
+            "peer event chain",
+        )?;
+        body["chain_hash"]
+            .as_str()
+            .map(str::to_string)
+            .context("peer event chain has no chain_hash")
+    }
+}
+
+/// Build both proofs and compare them.
+pub fn attest(args: &AttestArgs) -> Result<AttestReport> {
+    let peer = Peer {
+        base: args.peer.trim_end_matches('/'),
+        token: args.token.as_deref(),
+    };
+    let events = read_all_segments(&args.log, None)
+        .map_err(|e| anyhow::anyhow!("cannot read event log {}: {e}", args.log))?;
+    let local_head = events.len() as u64;
+    let peer_head = peer.get("/v1/replication/head", "peer head")?["height"]
+        .as_u64()
+        .context("peer head has no height")?;
+    let height = args.at.unwrap_or(local_head.min(peer_head));
+    if height > local_head {
+        bail!("the local log holds {local_head} events, short of height {height}");
+    }
+    if height > peer_head {
+        bail!("the peer is at height {peer_head}, short of height {height}");
+    }
+
+    let remote: AttestProof =
+        serde_json::from_value(peer.get(&format!("/v1/proof/at?height={height}"), "peer proof")?)
+            .context("peer proof has an unexpected shape")?;
+    let local = prove(&events, height).map_err(|e| anyhow::anyhow!("local replay: {e}"))?;
+
+    let divergent_section = first_divergence(&local, &remote);
+    let first_divergent_event = match divergent_section {
+        Some(Section::Events) => {
+            // chain[lo] agrees, chain[hi] does not.
+            let chain = event_chain(&events[..height as usize]);
+            let (mut lo, mut hi) = (0, height);
+            while hi - lo > 1 {
+                let mid = lo + (hi - lo) / 2;
+                if peer.chain_hash(mid)? == chain_hex(&chain[mid as usize]) {
+                    lo = mid;
+                } else {
+                    hi = mid;
+                }
+            }
+            Some(hi)
+        }
+        _ => None,
+    };
+    Ok(AttestReport {
+        peer: peer.base.to_string(),
+        height,
+        local_head,
+        peer_head,
+        agree: divergent_section.is_none(),
+        divergent_section,
+        first_divergent_event,
+        local,
+        remote,
+    })
+}
+
+pub fn run(args: AttestArgs, json: bool) -> Result<()> {
+    let report = attest(&args)?;
+    if json {
+        println!("{}", serde_json::to_string_pretty(&report)?);
+    } else {
+        println!(
+            "height {} (local head {}, peer head {})",
+            report.height, report.local_head, report.peer_head
+        );
+        let rows = [
+            (
+                "event chain",
+                &report.local.event_chain_hash,
+                &report.remote.event_chain_hash,
+            ),
+            (
+                "records",
+                &report.local.sections.records,
+                &report.remote.sections.records,
+            ),
+            (
+                "nodes",
+                &report.local.sections.nodes,
+                &report.remote.sections.nodes,
+            ),
+            (
+                "edges",
+                &report.local.sections.edges,
+                &report.remote.sections.edges,
+            ),
+            ("state", &report.local.state_hash, &report.remote.state_hash),
+        ];
+        for (name, local, remote) in rows {
+            if local == remote {
+                println!("  {name:<12} ok        {local}");
+            } else {
+                println!("  {name:<12} DIFFERS   local {local}");
+                println!("  {:<12}           peer  {remote}", "");
+            }
+        }
+    }
+    match (report.divergent_section, report.first_divergent_event) {
+        (None, _) => {
+            if !json {
+                println!("AGREE — same events, same state, byte for byte");
+            }
+            Ok(())
+        }
+        (Some(Section::Events), Some(h)) => {
+            bail!("event histories diverge at event {h}; the states cannot agree")
+        }
+        (Some(section), _) => bail!(
+            "same events but the {} section differs — a determinism bug",
+            serde_json::to_value(section)?.as_str().unwrap_or("state")
+        ),
+    }
+}
@@ -0,0 +1,82 @@
+// Copyright (c) 2025 Varshith Gudur. Dual-licensed under MIT OR Apache-2.0.
+//! `valori audit export` — dump a node's API audit trail as JSON Lines.
+//!
+//! Pages through `GET /v1/audit` (admin scope) using the `next_since` cursor
+//! until the node returns an empty page, so the export is complete even when
+//! the trail is larger than one page.
+//!
+//! ```text
+//! valori audit export --url http://10.0.0.1:3000 --out audit.jsonl
+//! valori audit export --url http://10.0.0.1:3000 --since 1200 --principal role:writer
+//! ```
+
+use anyhow::{bail, Context, Result};
+use std::io::Write;
+use std::path::PathBuf;
+
+/// Page size requested from the node (the server caps it at 1000).
+const PAGE: usize = 1_000;
+
+pub struct AuditExportArgs {
+    pub url: String,
+    pub token: Option<String>,
+    pub since: u64,
+    pub principal: Option<String>,
+    pub route: Option<String>,
+    /// Output file; `None` = stdout.
+    pub out: Option<PathBuf>,
+}
+
+pub fn export(args: AuditExportArgs) -> Result<()> {
+    let mut sink: Box<dyn Write> = match &args.out {
+        Some(p) => Box::new(std::io::BufWriter::new(
+            std::fs::File::create(p).with_context(|| format!("cannot create {}", p.display()))?,
+        )),
+        None => Box::new(std::io::stdout().lock()),
+    };
+
+    let base = args.url.trim_end_matches('/');
+    let mut since = args.since;
+    let mut total = 0u64;
+    loop {
+        let mut req = ureq::get(&format!("{base}/v1/audit"))
+            .query("since", &since.to_string())
+            .query("limit", &PAGE.to_string());
+        if let Some(p) = &args.principal {
+            req = req.query("principal", p);
+        }
+        if let Some(r) = &args.route {
+            req = req.query("route", r);
+        }
+        if let Some(t) = &args.token {
+            req = req.set("Authorization", &format!("Bearer {t}"));
+        }
+        let body: serde_json::Value = match req.call() {
+            Ok(resp) => resp.into_json().context("audit response was not JSON")?,
+            Err(ureq::Error::Status(code, _)) => {
+                bail!("audit export failed (HTTP {code}) — an admin token is required")
+            }
+            Err(e) => bail!("cannot reach {base}: {e}"),
+        };
+
+        let entries = body["entries"].as_array().cloned().unwrap_or_default();
+        if entries.is_empty() {
+            break;
+        }
+        for e in &entries {
+            serde_json::to_writer(&mut sink, e)?;
+            sink.write_all(b"\n")?;
+        }
+        total += entries.len() as u64;
+        match body["next_since"].as_u64() {
+            Some(next) if next > since => since = next,
+            _ => break,
+        }
+    }
+    sink.flush()?;
+
+    if let Some(p) = &args.out {
+        eprintln!("exported {total} audit entries to {}", p.display());
+    }
+    Ok(())
+}
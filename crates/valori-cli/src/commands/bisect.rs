@@ -0,0 +1,224 @@
+// Copyright (c) 2025 Varshith Gudur. Dual-licensed under MIT OR Apache-2.0.
+//! `valori bisect` — find the first event where two histories diverge.
+//!
+//! Two nodes that should agree report different state hashes. Both sides
+//! (an optional snapshot baseline plus an event log) are replayed in
+//! lockstep, one data event at a time, and the BLAKE3 state hash is compared
+//! after every step. The first step whose hashes differ is reported together
+//! with the event each side applied there — the offending event is almost
+//! always one of the two.
+//!
+//! Step 0 is the baseline: if the snapshots already differ, no replay
+//! happens. When one log ends first, the remaining events of the other are
+//! still compared against the shorter side's final state.
+
+use crate::engine::{ForensicEngine, LogEvent, LogEvents};
+use comfy_table::presets::UTF8_FULL;
+use comfy_table::{Attribute, Cell, Color, ContentArrangement, Table};
+use serde::Serialize;
+
+/// Longest event rendering kept in the report (vectors get long).
+const EVENT_DETAIL_MAX: usize = 160;
+
+/// One side of the comparison: an optional snapshot baseline and a log.
+#[derive(Debug, Clone, Copy)]
+pub struct Side<'a> {
+    pub snapshot: Option<&'a str>,
+    pub log: &'a str,
+}
+
+/// The event one side applied at a compared step.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+pub struct StepEvent {
+    /// Event index in that side's log (1-based).
+    pub height: u64,
+    pub event_type: String,
+    pub namespace_id: Option<u16>,
+    pub detail: String,
+}
+
+/// The first step whose state hashes differ.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+pub struct Divergence {
+    /// Lockstep position: 0 = baseline, n = after each side's n-th event.
+    pub step: u64,
+    /// `None` when side A had no event left (or at the baseline).
+    pub event_a: Option<StepEvent>,
+    pub event_b: Option<StepEvent>,
+    pub hash_a: String,
+    pub hash_b: String,
+    /// Hash both sides shared at the previous step (`None` at the baseline).
+    pub last_agreed_hash: Option<String>,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+pub struct BisectReport {
+    /// Events replayed on each side (up to and including the divergence).
+    pub events_a: u64,
+    pub events_b: u64,
+    /// Steps whose hashes matched before the divergence (or in total).
+    pub agreed_steps: u64,
+    pub divergence: Option<Divergence>,
+}
+
+fn engine_for(side: Side<'_>) -> anyhow::Result<ForensicEngine> {
+    match side.snapshot {
+        Some(path) => ForensicEngine::from_snapshot(path),
+        None => Ok(ForensicEngine::empty()),
+    }
+}
+
+fn step_event(ev: &LogEvent) -> StepEvent {
+    let mut detail = format!("{:?}", ev.event);
+    if detail.len() > EVENT_DETAIL_MAX {
+        let mut cut = EVENT_DETAIL_MAX;
+        while !detail.is_char_boundary(cut) {
+            cut -= 1;
+        }
+        detail.truncate(cut);
+        detail.push('…');
+    }
+    StepEvent {
+        height: ev.index,
+        event_type: ev.event.event_type().to_string(),
+        namespace_id: ev.namespace_id,
+        detail,
+    }
+}
+
+/// Advance one side by one event. `Ok(None)` once its log is exhausted.
+fn step(
+    engine: &mut ForensicEngine,
+    events: &mut LogEvents,
+    label: &str,
+) -> anyhow::Result<Option<StepEvent>> {
+    match events.next() {
+        None => Ok(None),
+        Some(ev) => {
+            let ev = ev.map_err(|e| anyhow::anyhow!("Log {label}: {e}"))?;
+            engine
+                .apply(&ev)
+                .map_err(|e| anyhow::anyhow!("Log {label}: {e}"))?;
+            Ok(Some(step_event(&ev)))
+        }
+    }
+}
+
+/// Replay both sides in lockstep and locate the first divergent step.
+pub fn bisect(a: Side<'_>, b: Side<'_>) -> anyhow::Result<BisectReport> {
+    let mut engine_a = engine_for(a)?;
+    let mut engine_b = engine_for(b)?;
+    let mut log_a = LogEvents::open(a.log)?;
+    let mut log_b = LogEvents::open(b.log)?;
+
+    let mut report = BisectReport {
+        events_a: 0,
+        events_b: 0,
+        agreed_steps: 0,
+        divergence: None,
+    };
+
+    let (mut hash_a, mut hash_b) = (engine_a.blake3_hex(), engine_b.blake3_hex());
+    if hash_a != hash_b {
+        report.divergence = Some(Divergence {
+            step: 0,
+            event_a: None,
+            event_b: None,
+            hash_a,
+            hash_b,
+            last_agreed_hash: None,
+        });
+        return Ok(report);
+    }
+
+    loop {
+        let event_a = step(&mut engine_a, &mut log_a, "A")?;
+        let event_b = step(&mut engine_b, &mut log_b, "B")?;
+        if event_a.is_none() && event_b.is_none() {
+            return Ok(report);
+        }
+        report.events_a += event_a.is_some() as u64;
+        report.events_b += event_b.is_some() as u64;
+
+        let last_agreed = hash_a;
+        hash_a = engine_a.blake3_hex();
+        hash_b = engine_b.blake3_hex();
+        if hash_a != hash_b {
+            report.divergence = Some(Divergence {
+                step: report.agreed_steps + 1,
+                event_a,
+                event_b,
+                hash_a,
+                hash_b,
+                last_agreed_hash: Some(last_agreed),
+            });
+            return Ok(report);
+        }
+        report.agreed_steps += 1;
+    }
+}
+
+pub fn run(a: Side<'_>, b: Side<'_>, json: bool) -> anyhow::Result<()> {
+    let report = bisect(a, b)?;
+    if json {
+        println!("{}", serde_json::to_string_pretty(&report)?);
+        return Ok(());
+    }
+
+    let Some(d) = &report.divergence else {
+        println!(
+            "\n  \x1b[32mIDENTICAL\x1b[0m — {} step(s) replayed, state hashes agree at every step.\n",
+            report.agreed_steps
+        );
+        return Ok(());
+    };
+
+    let mut table = Table::new();
+    table
+        .load_preset(UTF8_FULL)
+        .set_content_arrangement(ContentArrangement::Dynamic)
+        .set_header(vec![
+            Cell::new("").add_attribute(Attribute::Bold),
+            Cell::new("A").add_attribute(Attribute::Bold),
+            Cell::new("B").add_attribute(Attribute::Bold),
+        ]);
+    let height = |e: &Option<StepEvent>| {
+        e.as_ref()
+            .map_or_else(|| "—".to_string(), |e| format!("#{}", e.height))
+    };
+    let event = |e: &Option<StepEvent>| match e {
+        Some(e) => match e.namespace_id {
+            Some(ns) => format!("{} (ns {ns})\n{}", e.event_type, e.detail),
+            None => format!("{}\n{}", e.event_type, e.detail),
+        },
+        None if d.step == 0 => "baseline snapshot".to_string(),
+        None => "log exhausted".to_string(),
+    };
+    table.add_row(vec![
+        Cell::new("Event"),
+        Cell::new(height(&d.event_a)),
+        Cell::new(height(&d.event_b)),
+    ]);
+    table.add_row(vec![
+        Cell::new("Applied"),
+        Cell::new(event(&d.event_a)),
+        Cell::new(event(&d.event_b)),
+    ]);
+    table.add_row(vec![
+        Cell::new("State hash (BLAKE3)"),
+        Cell::new(&d.hash_a).fg(Color::Yellow),
+        Cell::new(&d.hash_b).fg(Color::Yellow),
+    ]);
+
+    println!("\nFirst Divergence  (step {})", d.step);
+    println!("{}", "─".repeat(46));
+    println!("{table}");
+    match &d.last_agreed_hash {
+        Some(h) => println!(
+            "  Agreed for {} step(s); last shared hash {h}\n",
+            report.agreed_steps
+        ),
+        None => println!("  Baselines differ — no events were replayed.\n"),
+    }
+    Ok(())
+}
@@ -3,16 +3,37 @@
 //!
 //! Replays the event log twice from the same snapshot baseline — once to
 //! `--from` and once to `--to` — then reports the state-hash delta and,
-//! optionally, nearest-neighbour rank changes for a query vector.
+//! optionally, nearest-neighbour rank changes for a query vector. Graph
+//! changes (nodes / edges by kind, and connectivity around the query's
+//! top-K) come from [`graph_diff`](crate::commands::graph_diff).
+//!
+//! `--output structural` adds the record / node / edge delta and per-section
+//! hashes from `valori_kernel::state::diff`; `--output json` prints only that
+//! structural diff, in the same shape as the node's `GET /v1/diff`.
 
+use crate::commands::graph_diff;
 use crate::engine::{floats_to_fxp, ForensicEngine};
 use comfy_table::presets::UTF8_FULL;
 use comfy_table::{Attribute, Cell, Color, ContentArrangement, Table};
-use std::collections::{HashMap, HashSet};
+use std::collections::{BTreeSet, HashMap, HashSet};
 use valori_kernel::index::SearchResult;
+use valori_kernel::state::diff::{diff_states, SectionHashes, StateDiff};
 use valori_kernel::types::id::RecordId;
 use valori_kernel::types::vector::FxpVector;
 
+/// What `valori diff` prints.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq, clap::ValueEnum)]
+pub enum DiffOutput {
+    /// State comparison, drift analysis and the optional semantic diff.
+    #[default]
+    Table,
+    /// `table` plus the structural record / graph delta.
+    Structural,
+    /// Structural diff as JSON (no tables).
+    Json,
+}
+
+#[allow(clippy::too_many_arguments)]
 pub fn run(
     snapshot_path: &str,
     log_path: &str,
@@ -20,6 +41,7 @@ pub fn run(
     to_count: u64,
     query_arg: Option<String>,
     top_k: usize,
+    output: DiffOutput,
 ) -> anyhow::Result<()> {
     // ── Engine A — state at `from` ────────────────────────────────────────────
     let mut engine_a = ForensicEngine::from_snapshot(snapshot_path)?;
@@ -32,6 +54,13 @@ pub fn run(
     engine_b.replay_to(log_path, to_count)?;
     let hash_b = engine_b.blake3_hex();
 
+    let structural = diff_states(&engine_a.state, &engine_b.state);
+    if output == DiffOutput::Json {
+        let json = structural_json(from_count, to_count, &hash_a, &hash_b, &structural);
+        println!("{}", serde_json::to_string_pretty(&json)?);
+        return Ok(());
+    }
+
     let state_changed = hash_a != hash_b;
     let status_label = if state_changed {
         "DRIFTED"
@@ -108,7 +137,14 @@ pub fn run(
         }
     }
 
+    if output == DiffOutput::Structural {
+        print_structural(&structural);
+    }
+
     // ── Semantic diff (optional) ──────────────────────────────────────────────
+    // Records in either side's top-K; their graph neighbourhoods are compared
+    // below.
+    let mut focus: Vec<u32> = Vec::new();
     if let Some(query_str) = query_arg {
         let floats: Vec<f64> = serde_json::from_str(&query_str).map_err(|_| {
             anyhow::anyhow!(
@@ -125,6 +161,13 @@ pub fn run(
 
         let ranks_a = rank_map(&results_a);
         let ranks_b = rank_map(&results_b);
+        focus = results_a
+            .iter()
+            .chain(&results_b)
+            .map(|r| r.id.0)
+            .collect::<BTreeSet<_>>()
+            .into_iter()
+            .collect();
 
         let mut sem = Table::new();
         sem.load_preset(UTF8_FULL)
@@ -176,11 +219,192 @@ pub fn run(
         }
     }
 
+    // ── Graph diff ────────────────────────────────────────────────────────────
+    let graph = graph_diff::graph_diff(&engine_a.state, &engine_b.state, &structural, &focus);
+    if graph.is_empty() {
+        if state_changed {
+            println!("Graph Diff: no graph changes detected.\n");
+        }
+    } else {
+        graph_diff::print(&graph);
+    }
+
     Ok(())
 }
 
 // ─── Internal helpers ─────────────────────────────────────────────────────────
 
+fn hex(bytes: &[u8; 32]) -> String {
+    bytes.iter().map(|b| format!("{b:02x}")).collect()
+}
+
+fn sections_json(h: &SectionHashes) -> serde_json::Value {
+    serde_json::json!({
+        "records": hex(&h.records),
+        "nodes": hex(&h.nodes),
+        "edges": hex(&h.edges),
+    })
+}
+
+fn structural_json(
+    from: u64,
+    to: u64,
+    hash_a: &str,
+    hash_b: &str,
+    d: &StateDiff,
+) -> serde_json::Value {
+    serde_json::json!({
+        "from": from,
+        "to": to,
+        "from_state_hash": hash_a,
+        "to_state_hash": hash_b,
+        "from_sections": sections_json(&d.from_hashes),
+        "to_sections": sections_json(&d.to_hashes),
+        "records_added": d.records_added,
+        "records_removed": d.records_removed,
+        "records_changed": d.records_changed,
+        "nodes_added": d.nodes_added,
+        "nodes_removed": d.nodes_removed,
+        "nodes_changed": d.nodes_changed,
+        "edges_added": d.edges_added,
+        "edges_removed": d.edges_removed,
+        "edges_changed": d.edges_changed,
+    })
+}
+
+fn print_structural(d: &StateDiff) {
+    let mut sections = Table::new();
+    sections
+        .load_preset(UTF8_FULL)
+        .set_content_arrangement(ContentArrangement::Dynamic)
+        .set_header(vec![
+            Cell::new("Section").add_attribute(Attribute::Bold),
+            Cell::new("Added").add_attribute(Attribute::Bold),
+            Cell::new("Removed").add_attribute(Attribute::Bold),
+            Cell::new("Changed").add_attribute(Attribute::Bold),
+            Cell::new("Hash A → B (BLAKE3)").add_attribute(Attribute::Bold),
+        ]);
+    let rows = [
+        (
+            "Records",
+            d.records_added.len(),
+            d.records_removed.len(),
+            d.records_changed.len(),
+            &d.from_hashes.records,
+            &d.to_hashes.records,
+        ),
+        (
+            "Nodes",
+            d.nodes_added.len(),
+            d.nodes_removed.len(),
+            d.nodes_changed.len(),
+            &d.from_hashes.nodes,
+            &d.to_hashes.nodes,
+        ),
+        (
+            "Edges",
+            d.edges_added.len(),
+            d.edges_removed.len(),
+            d.edges_changed.len(),
+            &d.from_hashes.edges,
+            &d.to_hashes.edges,
+        ),
+    ];
+    for (name, added, removed, changed, a, b) in rows {
+        let hashes = if a == b {
+            Cell::new(format!("{} (unchanged)", &hex(a)[..16])).fg(Color::Green)
+        } else {
+            Cell::new(format!("{} → {}", &hex(a)[..16], &hex(b)[..16])).fg(Color::Yellow)
+        };
+        sections.add_row(vec![
+            Cell::new(name),
+            Cell::new(added),
+            Cell::new(removed),
+            Cell::new(changed),
+            hashes,
+        ]);
+    }
+    println!("Structural Diff");
+    println!("{}", "─".repeat(46));
+    println!("{sections}");
+
+    if d.is_empty() {
+        println!("  No structural changes.\n");
+        return;
+    }
+
+    let mut detail = Table::new();
+    detail
+        .load_preset(UTF8_FULL)
+        .set_content_arrangement(ContentArrangement::Dynamic)
+        .set_header(vec![
+            Cell::new("Item").add_attribute(Attribute::Bold),
+            Cell::new("Change").add_attribute(Attribute::Bold),
+            Cell::new("Detail").add_attribute(Attribute::Bold),
+        ]);
+    let added = || Cell::new("+ Added").fg(Color::Green);
+    let removed = || Cell::new("- Removed").fg(Color::Red);
+    let changed = || Cell::new("~ Changed").fg(Color::Yellow);
+    for id in &d.records_added {
+        detail.add_row(vec![
+            Cell::new(format!("record {id}")),
+            added(),
+            Cell::new(""),
+        ]);
+    }
+    for id in &d.records_removed {
+        detail.add_row(vec![
+            Cell::new(format!("record {id}")),
+            removed(),
+            Cell::new(""),
+        ]);
+    }
+    for c in &d.records_changed {
+        let fields: Vec<&str> = [
+            (c.vector, "vector"),
+            (c.tag, "tag"),
+            (c.metadata, "metadata"),
+            (c.flags, "flags"),
+        ]
+        .into_iter()
+        .filter_map(|(hit, f)| hit.then_some(f))
+        .collect();
+        detail.add_row(vec![
+            Cell::new(format!("record {}", c.id)),
+            changed(),
+            Cell::new(fields.join(", ")),
+        ]);
+    }
+    for (list, cell) in [
+        (&d.nodes_added, added()),
+        (&d.nodes_removed, removed()),
+        (&d.nodes_changed, changed()),
+    ] {
+        for n in list {
+            let record = n.record.map_or("-".to_string(), |r| r.to_string());
+            detail.add_row(vec![
+                Cell::new(format!("node {}", n.id)),
+                cell.clone(),
+                Cell::new(format!("kind {}, record {record}", n.kind)),
+            ]);
+        }
+    }
+    for (list, cell) in [
+        (&d.edges_added, added()),
+        (&d.edges_removed, removed()),
+        (&d.edges_changed, changed()),
+    ] {
+        for e in list {
+            detail.add_row(vec![
+                Cell::new(format!("edge {}", e.id)),
+                cell.clone(),
+                Cell::new(format!("{} → {} (kind {})", e.from, e.to, e.kind)),
+            ]);
+        }
+    }
+    println!("{detail}\n");
+}
+
 fn search(engine: &ForensicEngine, query: &FxpVector, k: usize) -> Vec<SearchResult> {
     let mut buf = vec![
         SearchResult {
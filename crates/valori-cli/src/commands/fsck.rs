@@ -0,0 +1,109 @@
+// Copyright (c) 2025 Varshith Gudur. Dual-licensed under MIT OR Apache-2.0.
+//! `valori fsck` — check a running node's indexes, and optionally repair them.
+//!
+//! Calls `GET /v1/admin/check` (index and graph maps against the kernel) and
+//! `GET /v1/admin/index/repair` (HNSW graph: orphaned links, stale entry
+//! point, unreachable nodes). With `--repair` the second call is a `POST`,
+//! which fixes the graph in place. Both need admin scope. Exits non-zero
+//! while a problem remains.
+//!
+//! ```text
+//! valori fsck --url http://10.0.0.1:3000
+//! valori fsck --url http://10.0.0.1:3000 --repair
+//! ```
+
+use anyhow::{bail, Context, Result};
+
+pub struct FsckArgs {
+    pub url: String,
+    pub token: Option<String>,
+    /// Apply the graph repair instead of only reporting it.
+    pub repair: bool,
+}
+
+fn call(req: ureq::Request, what: &str, base: &str) -> Result<serde_json::Value> {
+    match req.call() {
+        Ok(resp) => resp
+            .into_json()
+            .with_context(|| format!("{what} response was not JSON")),
+        Err(ureq::Error::Status(code, resp)) => {
+            let msg = resp
+                .into_json::<serde_json::Value>()
+                .ok()
+                .and_then(|b| b["error"].as_str().map(str::to_string))
+                .unwrap_or_default();
+            bail!("{what} failed (HTTP {code}) {msg}")
+        }
+        Err(e) => bail!("cannot reach {base}: {e}"),
+    }
+}
+
+pub fn run(args: FsckArgs) -> Result<()> {
+    let base = args.url.trim_end_matches('/');
+    let auth = |req: ureq::Request| match &args.token {
+        Some(t) => req.set("Authorization", &format!("Bearer {t}")),
+        None => req,
+    };
+
+    let check = call(
+        auth(ureq::get(&format!("{base}/v1/admin/check"))),
+        "consistency check",
+        base,
+    )?;
+    let consistent = check["ok"].as_bool().unwrap_or(false);
+    if consistent {
+        println!("consistency: ok");
+    } else {
+        println!("consistency: FAILED");
+        for key in ["kernel", "index_missing", "index_stale", "record_to_node"] {
+            let n = check[key].as_array().map_or(0, Vec::len);
+            if n > 0 {
+                println!("  {key}: {n}");
+            }
+        }
+        if check["committer_diverged"].as_bool() == Some(true) {
+            println!("  committer_diverged: true");
+        }
+    }
+
+    let url = format!("{base}/v1/admin/index/repair");
+    let req = if args.repair {
+        ureq::post(&url)
+    } else {
+        ureq::get(&url)
+    };
+    let graph = call(auth(req), "index repair", base)?;
+    let indexes = graph["indexes"].as_object().cloned().unwrap_or_default();
+    if indexes.is_empty() {
+        println!("graph: no graph indexes");
+    }
+    for (name, r) in &indexes {
+        let unreachable = r["unreachable"].as_array().map_or(0, Vec::len);
+        let left = r["still_unreachable"].as_array().map_or(0, Vec::len);
+        println!(
+            "graph {name}: {} nodes, {} orphan links, entry point {}, {unreachable} unreachable",
+            r["nodes"],
+            r["orphan_links"],
+            if r["entry_point_fixed"].as_bool() == Some(true) {
+                "stale"
+            } else {
+                "ok"
+            },
+        );
+        if args.repair {
+            println!(
+                "  repaired: {} nodes relinked, {left} still unreachable",
+                r["relinked"]
+            );
+        }
+    }
+    let graph_ok = graph["ok"].as_bool().unwrap_or(false);
+    if !graph_ok && !args.repair {
+        println!("run again with --repair to fix the graph");
+    }
+
+    if !consistent || !graph_ok {
+        bail!("fsck found problems");
+    }
+    Ok(())
+}
@@ -0,0 +1,185 @@
+// Copyright (c) 2025 Varshith Gudur. Dual-licensed under MIT OR Apache-2.0.
+//! Graph half of `valori diff` — how the knowledge graph moved between the
+//! two replay points.
+//!
+//! Summarises the structural node / edge delta by kind, and — when a query
+//! is given — compares the graph neighbourhood of every record in either
+//! side's top-K: a result whose vector did not move can still have gained a
+//! `Contradicts` edge or lost its `Supersedes` link, which changes what an
+//! agent retrieving it through GraphRAG sees.
+
+use comfy_table::presets::UTF8_FULL;
+use comfy_table::{Attribute, Cell, Color, ContentArrangement, Table};
+use std::collections::{BTreeMap, BTreeSet};
+use valori_kernel::state::diff::StateDiff;
+use valori_kernel::state::kernel::KernelState;
+use valori_kernel::types::enums::{EdgeKind, NodeKind};
+use valori_kernel::types::id::RecordId;
+
+/// Per-kind counts of added / removed graph items.
+#[derive(Debug, Default, Clone, PartialEq, Eq)]
+pub struct KindCounts {
+    pub added: usize,
+    pub removed: usize,
+}
+
+/// Connectivity change around one top-K record.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Neighbourhood {
+    pub record: u32,
+    pub degree_a: usize,
+    pub degree_b: usize,
+    /// Links present in B only, as `"<EdgeKind> → <neighbour>"`.
+    pub gained: Vec<String>,
+    /// Links present in A only.
+    pub lost: Vec<String>,
+}
+
+#[derive(Debug, Default, Clone, PartialEq, Eq)]
+pub struct GraphDiff {
+    pub nodes_by_kind: BTreeMap<String, KindCounts>,
+    pub edges_by_kind: BTreeMap<String, KindCounts>,
+    /// Only records whose neighbourhood changed, in `focus` order.
+    pub neighbourhoods: Vec<Neighbourhood>,
+}
+
+impl GraphDiff {
+    pub fn is_empty(&self) -> bool {
+        self.nodes_by_kind.is_empty()
+            && self.edges_by_kind.is_empty()
+            && self.neighbourhoods.is_empty()
+    }
+}
+
+fn node_kind_name(kind: u8) -> String {
+    NodeKind::from_u8(kind).map_or_else(|| format!("Kind{kind}"), |k| format!("{k:?}"))
+}
+
+fn edge_kind_name(kind: u8) -> String {
+    EdgeKind::from_u8(kind).map_or_else(|| format!("Kind{kind}"), |k| format!("{k:?}"))
+}
+
+/// Human label for the node at the other end of a link.
+pub(crate) fn node_label(state: &KernelState, id: u32) -> String {
+    match state.get_node(valori_kernel::types::id::NodeId(id)) {
+        Some(n) => match n.record {
+            Some(r) => format!("record {}", r.0),
+            None => format!("{:?} node {id}", n.kind),
+        },
+        None => format!("node {id}"),
+    }
+}
+
+/// Every link touching the nodes attached to `record`, as
+/// `"<EdgeKind> → <neighbour>"` (or `←` for incoming).
+fn links(state: &KernelState, record: u32) -> BTreeSet<String> {
+    let mut out = BTreeSet::new();
+    for node in state
+        .iter_nodes()
+        .filter(|n| n.record == Some(RecordId(record)))
+    {
+        for e in state.outgoing_edges(node.id).into_iter().flatten() {
+            out.insert(format!("{:?} → {}", e.kind, node_label(state, e.to.0)));
+        }
+        for e in state.incoming_edges(node.id).into_iter().flatten() {
+            out.insert(format!("{:?} ← {}", e.kind, node_label(state, e.from.0)));
+        }
+    }
+    out
+}
+
+/// Graph changes from `a` to `b`. `focus` is the union of both sides'
+/// top-K record ids (empty when no query was given).
+pub fn graph_diff(a: &KernelState, b: &KernelState, d: &StateDiff, focus: &[u32]) -> GraphDiff {
+    let mut diff = GraphDiff::default();
+    for n in &d.nodes_added {
+        diff.nodes_by_kind
+            .entry(node_kind_name(n.kind))
+            .or_default()
+            .added += 1;
+    }
+    for n in &d.nodes_removed {
+        diff.nodes_by_kind
+            .entry(node_kind_name(n.kind))
+            .or_default()
+            .removed += 1;
+    }
+    for e in &d.edges_added {
+        diff.edges_by_kind
+            .entry(edge_kind_name(e.kind))
+            .or_default()
+            .added += 1;
+    }
+    for e in &d.edges_removed {
+        diff.edges_by_kind
+            .entry(edge_kind_name(e.kind))
+            .or_default()
+            .removed += 1;
+    }
+
+    for &record in focus {
+        let (la, lb) = (links(a, record), links(b, record));
+        if la == lb {
+            continue;
+        }
+        diff.neighbourhoods.push(Neighbourhood {
+            record,
+            degree_a: la.len(),
+            degree_b: lb.len(),
+            gained: lb.difference(&la).cloned().collect(),
+            lost: la.difference(&lb).cloned().collect(),
+        });
+    }
+    diff
+}
+
+pub fn print(g: &GraphDiff) {
+    if !g.nodes_by_kind.is_empty() || !g.edges_by_kind.is_empty() {
+        let mut kinds = Table::new();
+        kinds
+            .load_preset(UTF8_FULL)
+            .set_content_arrangement(ContentArrangement::Dynamic)
+            .set_header(vec![
+                Cell::new("Graph item").add_attribute(Attribute::Bold),
+                Cell::new("Kind").add_attribute(Attribute::Bold),
+                Cell::new("Added").add_attribute(Attribute::Bold),
+                Cell::new("Removed").add_attribute(Attribute::Bold),
+            ]);
+        for (item, map) in [("Node", &g.nodes_by_kind), ("Edge", &g.edges_by_kind)] {
+            for (kind, c) in map {
+                kinds.add_row(vec![
+                    Cell::new(item),
+                    Cell::new(kind),
+                    Cell::new(format!("+{}", c.added)).fg(Color::Green),
+                    Cell::new(format!("-{}", c.removed)).fg(Color::Red),
+                ]);
+            }
+        }
+        println!("Graph Changes");
+        println!("{}", "─".repeat(46));
+        println!("{kinds}\n");
+    }
+
+    if !g.neighbourhoods.is_empty() {
+        let mut conn = Table::new();
+        conn.load_preset(UTF8_FULL)
+            .set_content_arrangement(ContentArrangement::Dynamic)
+            .set_header(vec![
+                Cell::new("Record ID").add_attribute(Attribute::Bold),
+                Cell::new("Links A → B").add_attribute(Attribute::Bold),
+                Cell::new("Gained").add_attribute(Attribute::Bold),
+                Cell::new("Lost").add_attribute(Attribute::Bold),
+            ]);
+        for n in &g.neighbourhoods {
+            conn.add_row(vec![
+                Cell::new(n.record),
+                Cell::new(format!("{} → {}", n.degree_a, n.degree_b)),
+                Cell::new(n.gained.join("\n")).fg(Color::Green),
+                Cell::new(n.lost.join("\n")).fg(Color::Red),
+            ]);
+        }
+        println!("Connectivity around top-K");
+        println!("{}", "─".repeat(46));
+        println!("{conn}\n");
+    }
+}
@@ -0,0 +1,226 @@
+// Copyright (c) 2025 Varshith Gudur. Dual-licensed under MIT OR Apache-2.0.
+//! Graph half of `valori replay-query` — graph queries against the state at
+//! the replayed height.
+//!
+//! Three questions, each answerable offline from a snapshot and a log: what
+//! is node N linked to, how many nodes and edges of each kind exist, and how
+//! are two nodes connected. Paths follow edges in either direction — an
+//! investigator wants to know that two nodes are related before caring which
+//! way the edge points — and each hop reports the edge's real direction.
+
+use super::graph_diff::node_label;
+use comfy_table::presets::UTF8_FULL;
+use comfy_table::{Attribute, Cell, Color, ContentArrangement, Table};
+use std::collections::{BTreeMap, HashMap, VecDeque};
+use valori_kernel::state::kernel::KernelState;
+use valori_kernel::types::id::NodeId;
+
+/// Default hop limit for `--path`.
+pub const DEFAULT_MAX_DEPTH: usize = 6;
+
+/// Which graph queries `replay-query` runs after the replay.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct GraphQuery {
+    /// List the edges of this node.
+    pub neighbors: Option<u32>,
+    /// Count nodes and edges by kind.
+    pub kinds: bool,
+    /// Find a shortest path between these two nodes.
+    pub path: Option<(u32, u32)>,
+    /// Longest path searched, in edges.
+    pub max_depth: usize,
+}
+
+impl Default for GraphQuery {
+    fn default() -> Self {
+        Self {
+            neighbors: None,
+            kinds: false,
+            path: None,
+            max_depth: DEFAULT_MAX_DEPTH,
+        }
+    }
+}
+
+impl GraphQuery {
+    pub fn is_empty(&self) -> bool {
+        self.neighbors.is_none() && !self.kinds && self.path.is_none()
+    }
+}
+
+/// One edge of a node, seen from that node.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Neighbor {
+    pub edge: u32,
+    pub kind: String,
+    /// `true` for an edge leaving the node.
+    pub outgoing: bool,
+    /// The node at the other end.
+    pub node: u32,
+}
+
+/// One edge along a path, in path order.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Hop {
+    pub edge: u32,
+    pub kind: String,
+    /// The edge's own endpoints (not necessarily path order).
+    pub from: u32,
+    pub to: u32,
+}
+
+/// Outgoing then incoming edges of `node`, or `None` if it does not exist.
+pub fn neighbors(state: &KernelState, node: u32) -> Option<Vec<Neighbor>> {
+    let outgoing = state.outgoing_edges(NodeId(node))?.map(|e| Neighbor {
+        edge: e.id.0,
+        kind: format!("{:?}", e.kind),
+        outgoing: true,
+        node: e.to.0,
+    });
+    let incoming = state.incoming_edges(NodeId(node))?.map(|e| Neighbor {
+        edge: e.id.0,
+        kind: format!("{:?}", e.kind),
+        outgoing: false,
+        node: e.from.0,
+    });
+    Some(outgoing.chain(incoming).collect())
+}
+
+/// Live node and edge counts by kind.
+pub fn counts_by_kind(state: &KernelState) -> (BTreeMap<String, usize>, BTreeMap<String, usize>) {
+    let mut nodes = BTreeMap::new();
+    let mut edges = BTreeMap::new();
+    for node in state.iter_nodes() {
+        *nodes.entry(format!("{:?}", node.kind)).or_default() += 1;
+        for edge in state.outgoing_edges(node.id).into_iter().flatten() {
+            *edges.entry(format!("{:?}", edge.kind)).or_default() += 1;
+        }
+    }
+    (nodes, edges)
+}
+
+/// A shortest path from `from` to `to` of at most `max_depth` edges,
+/// following edges either way. `Some(vec![])` when `from == to`; `None`
+/// when either node is missing or no path is that short.
+pub fn shortest_path(
+    state: &KernelState,
+    from: u32,
+    to: u32,
+    max_depth: usize,
+) -> Option<Vec<Hop>> {
+    state.get_node(NodeId(from))?;
+    state.get_node(NodeId(to))?;
+    // Node → the hop that reached it first (BFS, so along a shortest path).
+    let mut reached: HashMap<u32, Option<(u32, Hop)>> = HashMap::from([(from, None)]);
+    let mut frontier = VecDeque::from([(from, 0usize)]);
+    while let Some((node, depth)) = frontier.pop_front() {
+        if node == to {
+            let mut hops = Vec::new();
+            let mut at = to;
+            while let Some(Some((prev, hop))) = reached.get(&at) {
+                hops.push(hop.clone());
+                at = *prev;
+            }
+            hops.reverse();
+            return Some(hops);
+        }
+        if depth == max_depth {
+            continue;
+        }
+        for n in neighbors(state, node).unwrap_or_default() {
+            if reached.contains_key(&n.node) {
+                continue;
+            }
+            let (a, b) = if n.outgoing {
+                (node, n.node)
+            } else {
+                (n.node, node)
+            };
+            let hop = Hop {
+                edge: n.edge,
+                kind: n.kind,
+                from: a,
+                to: b,
+            };
+            reached.insert(n.node, Some((node, hop)));
+            frontier.push_back((n.node, depth + 1));
+        }
+    }
+    None
+}
+
+fn table(header: &[&str]) -> Table {
+    let mut table = Table::new();
+    table
+        .load_preset(UTF8_FULL)
+        .set_content_arrangement(ContentArrangement::Dynamic)
+        .set_header(
+            header
+                .iter()
+                .map(|h| Cell::new(h).add_attribute(Attribute::Bold)),
+        );
+    table
+}
+
+/// Run and print every query in `query` against `state`.
+pub fn print(state: &KernelState, query: &GraphQuery) {
+    if let Some(node) = query.neighbors {
+        match neighbors(state, node) {
+            None => println!("⚠️  Node {node} does not exist at this height.\n"),
+            Some(list) => {
+                let mut t = table(&["Edge", "Direction", "Kind", "Node"]);
+                for n in &list {
+                    let dir = if n.outgoing { "→ out" } else { "← in" };
+                    t.add_row(vec![
+                        Cell::new(n.edge),
+                        Cell::new(dir),
+                        Cell::new(&n.kind).fg(Color::Cyan),
+                        Cell::new(format!("{} ({})", n.node, node_label(state, n.node))),
+                    ]);
+                }
+                println!(
+                    "Neighbours of node {node} ({})  ·  {} edge(s)",
+                    node_label(state, node),
+                    list.len()
+                );
+                println!("{}", "─".repeat(40));
+                println!("{t}\n");
+            }
+        }
+    }
+
+    if query.kinds {
+        let (nodes, edges) = counts_by_kind(state);
+        let mut t = table(&["", "Kind", "Count"]);
+        for (what, counts) in [("Nodes", &nodes), ("Edges", &edges)] {
+            for (kind, n) in counts {
+                t.add_row(vec![Cell::new(what), Cell::new(kind), Cell::new(n)]);
+            }
+        }
+        println!("Graph by Kind");
+        println!("{}", "─".repeat(40));
+        println!("{t}\n");
+    }
+
+    if let Some((from, to)) = query.path {
+        match shortest_path(state, from, to, query.max_depth) {
+            None => println!(
+                "⚠️  No path from node {from} to node {to} within {} hop(s).\n",
+                query.max_depth
+            ),
+            Some(hops) => {
+                let mut t = table(&["Hop", "Edge", "Link"]);
+                for (i, hop) in hops.iter().enumerate() {
+                    t.add_row(vec![
+                        Cell::new(i + 1),
+                        Cell::new(hop.edge),
+                        Cell::new(format!("{} —{}→ {}", hop.from, hop.kind, hop.to)),
+                    ]);
+                }
+                println!("Path {from} ⇢ {to}  ·  {} hop(s)", hops.len());
+                println!("{}", "─".repeat(40));
+                println!("{t}\n");
+            }
+        }
+    }
+}
@@ -1,11 +1,13 @@
 // Copyright (c) 2025 Varshith Gudur. Dual-licensed under MIT OR Apache-2.0.
 //! `valori inspect` — structural status report for a database directory.
+//!
+//! `--deep` adds the cross-checks in [`super::inspect_deep`].
 
 use crate::engine::{inspect_snapshot_bytes, parse_kernel_from_snapshot_bytes};
 use comfy_table::presets::UTF8_FULL;
 use comfy_table::{Attribute, Cell, Color, ContentArrangement, Table};
 use std::path::PathBuf;
-use valori_node::events::event_log::LogEntry;
+use valori_node::events::event_log::{AdminEvent, LogEntry};
 use valori_wire::{decode_entry, parse_header};
 
 const DEFAULT_SNAPSHOT: &str = "snapshot.val";
@@ -15,6 +17,7 @@ pub fn run(
     dir: Option<PathBuf>,
     snapshot_arg: Option<String>,
     log_arg: Option<String>,
+    deep: bool,
 ) -> anyhow::Result<()> {
     let (s_path, w_path) = match &dir {
         Some(d) => (d.join(DEFAULT_SNAPSHOT), d.join(DEFAULT_LOG)),
@@ -143,6 +146,7 @@ pub fn run(
                 let mut event_count: u64 = 0;
                 let mut offset = header.header_len;
                 let mut corrupt_msg: Option<String> = None;
+                let mut annotations: Vec<(u64, String)> = Vec::new();
 
                 'parse: while offset < bytes.len() {
                     match decode_entry(header.version, &bytes[offset..]) {
@@ -150,10 +154,14 @@ pub fn run(
                             offset += n;
                             match chained.entry {
                                 LogEntry::Event(_) | LogEntry::EventNs { .. } => event_count += 1,
-                                LogEntry::Checkpoint { event_count: c, .. } => {
+                                LogEntry::Checkpoint { event_count: c, .. }
+                                | LogEntry::CheckpointV2 { event_count: c, .. } => {
                                     event_count = c;
                                 }
-                                LogEntry::Admin(_) => {}
+                                LogEntry::Admin(AdminEvent::Annotate { height, label, .. }) => {
+                                    annotations.push((height, label))
+                                }
+                                LogEntry::Admin(_) | LogEntry::BatchCommit { .. } => {}
                             }
                         }
                         Err(e) => {
@@ -186,6 +194,13 @@ pub fn run(
                         )),
                     ]);
                 }
+                for (height, label) in annotations {
+                    table.add_row(vec![
+                        Cell::new("annotation"),
+                        Cell::new(format!("height {height}")).fg(Color::Blue),
+                        Cell::new(label),
+                    ]);
+                }
             }
         }
     } else {
@@ -197,5 +212,13 @@ pub fn run(
     }
 
     println!("{table}\n");
+
+    if deep {
+        let report = super::inspect_deep::deep_check(&s_path, &w_path);
+        super::inspect_deep::print(&report);
+        if !report.ok() {
+            anyhow::bail!("deep verification failed");
+        }
+    }
     Ok(())
 }
@@ -0,0 +1,383 @@
+// Copyright (c) 2025 Varshith Gudur. Dual-licensed under MIT OR Apache-2.0.
+//! Deep half of `valori inspect` — `--deep` verification.
+//!
+//! The plain report only checks that the files parse. `--deep` checks that
+//! they agree:
+//!
+//! 1. **Snapshot** — the `CRC3` checksum trailer, the section lengths and
+//!    the kernel blob's graph invariants, via the engine's own startup
+//!    [`check_snapshot`].
+//! 2. **Index ↔ pool** — a sample of searchable records must each be found
+//!    by the kernel index at distance 0 from its own vector.
+//! 3. **Replay** — the event log (with its archived segments) is replayed
+//!    from genesis and must reproduce the snapshot's state hash at the
+//!    snapshot height. A log that cannot replay from genesis is instead
+//!    applied on top of the snapshot, as `replay-query` does.
+//! 4. **Checkpoints** — every checkpoint entry's hash must match the
+//!    replayed state at its event count.
+
+use comfy_table::presets::UTF8_FULL;
+use comfy_table::{Attribute, Cell, Color, ContentArrangement, Table};
+use std::collections::BTreeMap;
+use std::path::Path;
+use valori_kernel::index::SearchResult;
+use valori_kernel::snapshot::blake3::hash_state_blake3;
+use valori_kernel::state::kernel::KernelState;
+use valori_kernel::types::id::RecordId;
+use valori_node::engine::check_snapshot;
+use valori_node::events::event_log::LogEntry;
+use valori_storage::events::event_replay::{read_all_segments, segment_paths};
+
+/// Records probed by the index check.
+const INDEX_SAMPLE: usize = 256;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum CheckStatus {
+    Ok,
+    /// Not wrong, but not fully verified (e.g. no checksum trailer).
+    Warn,
+    Fail,
+    Skipped,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct DeepCheck {
+    pub name: &'static str,
+    pub status: CheckStatus,
+    pub detail: String,
+}
+
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct DeepReport {
+    pub checks: Vec<DeepCheck>,
+}
+
+impl DeepReport {
+    pub fn ok(&self) -> bool {
+        self.checks.iter().all(|c| c.status != CheckStatus::Fail)
+    }
+
+    pub fn get(&self, name: &str) -> Option<&DeepCheck> {
+        self.checks.iter().find(|c| c.name == name)
+    }
+
+    fn push(&mut self, name: &'static str, status: CheckStatus, detail: impl Into<String>) {
+        self.checks.push(DeepCheck {
+            name,
+            status,
+            detail: detail.into(),
+        });
+    }
+
+    /// Compare every checkpoint with the replayed hash at its event count.
+    fn push_checkpoints(
+        &mut self,
+        checkpoints: &BTreeMap<u64, [u8; 32]>,
+        hashes: &BTreeMap<u64, [u8; 32]>,
+    ) {
+        let mut bad = Vec::new();
+        for (count, hash) in checkpoints {
+            match hashes.get(count) {
+                Some(h) if h == hash => {}
+                Some(_) => bad.push(format!("{count} (hash differs)")),
+                None => bad.push(format!("{count} (past the end of the log)")),
+            }
+        }
+        if checkpoints.is_empty() {
+            self.push("checkpoints", CheckStatus::Ok, "none in the log");
+        } else if bad.is_empty() {
+            self.push(
+                "checkpoints",
+                CheckStatus::Ok,
+                format!(
+                    "{} checkpoint(s) match the replayed state",
+                    checkpoints.len()
+                ),
+            );
+        } else {
+            self.push(
+                "checkpoints",
+                CheckStatus::Fail,
+                format!("mismatch at event count {}", bad.join(", ")),
+            );
+        }
+    }
+}
+
+fn hex(bytes: &[u8]) -> String {
+    bytes.iter().map(|b| format!("{b:02x}")).collect()
+}
+
+/// Run every deep check on `snapshot` and, when it exists, `log`.
+pub fn deep_check(snapshot: &Path, log: &Path) -> DeepReport {
+    let mut report = DeepReport::default();
+    let log = log.exists().then_some(log);
+
+    let state = match std::fs::read(snapshot) {
+        Err(e) => {
+            report.push("snapshot", CheckStatus::Fail, format!("unreadable: {e}"));
+            None
+        }
+        Ok(bytes) => {
+            let check = check_snapshot(&bytes, None);
+            if check.ok {
+                let status = if check.checksummed {
+                    CheckStatus::Ok
+                } else {
+                    CheckStatus::Warn
+                };
+                let trailer = if check.checksummed {
+                    "checksum OK"
+                } else {
+                    "no checksum trailer"
+                };
+                report.push(
+                    "snapshot",
+                    status,
+                    format!(
+                        "{trailer}, sections tile the file, invariants hold (height {})",
+                        check.height
+                    ),
+                );
+            } else {
+                report.push("snapshot", CheckStatus::Fail, check.failures.join("; "));
+            }
+            crate::engine::parse_kernel_from_snapshot_bytes(&bytes).ok()
+        }
+    };
+
+    match &state {
+        Some(state) => {
+            let (status, detail) = index_check(state);
+            report.push("index ↔ pool", status, detail);
+        }
+        None => report.push(
+            "index ↔ pool",
+            CheckStatus::Skipped,
+            "no decodable snapshot",
+        ),
+    }
+
+    let Some(log) = log else {
+        report.push("replay", CheckStatus::Skipped, "no event log");
+        report.push("checkpoints", CheckStatus::Skipped, "no event log");
+        return report;
+    };
+    replay_checks(&mut report, state.as_ref(), log);
+    report
+}
+
+/// Probe an evenly spaced sample of searchable records through the index.
+fn index_check(state: &KernelState) -> (CheckStatus, String) {
+    let searchable: Vec<RecordId> = (0..state.total_record_slots() as u32)
+        .map(RecordId)
+        .filter(|id| state.get_record(*id).is_some_and(|r| r.is_searchable()))
+        .collect();
+    if searchable.is_empty() {
+        return (CheckStatus::Ok, "no searchable records".to_string());
+    }
+    let step = searchable.len().div_ceil(INDEX_SAMPLE);
+    let mut probed = 0;
+    let mut missing = Vec::new();
+    let mut buf = [SearchResult {
+        id: RecordId(0),
+        score: i64::MAX,
+    }; 4];
+    for id in searchable.iter().step_by(step) {
+        let record = state.get_record(*id).expect("searchable record exists");
+        let found = state.search_l2(&record.vector, &mut buf, None);
+        let hits = &buf[..found];
+        // Duplicate vectors can crowd the record out of the top 4; any
+        // exact hit still shows the index reaches that vector.
+        let reached = hits.iter().any(|r| r.id == *id)
+            || (found == buf.len() && hits.iter().all(|r| r.score == 0));
+        if !reached {
+            missing.push(id.0);
+        }
+        probed += 1;
+    }
+    if missing.is_empty() {
+        (
+            CheckStatus::Ok,
+            format!(
+                "{probed} of {} searchable record(s) found",
+                searchable.len()
+            ),
+        )
+    } else {
+        (
+            CheckStatus::Fail,
+            format!(
+                "{} of {probed} probed record(s) not found by their own vector: {:?}",
+                missing.len(),
+                missing
+            ),
+        )
+    }
+}
+
+/// Checkpoint entries in every segment of `log`: event count → hash.
+fn checkpoints(log: &Path) -> anyhow::Result<BTreeMap<u64, [u8; 32]>> {
+    let mut out = BTreeMap::new();
+    for path in segment_paths(log) {
+        let bytes = std::fs::read(&path)?;
+        if bytes.len() < 16 {
+            continue;
+        }
+        let header = valori_wire::parse_header(&bytes)
+            .map_err(|e| anyhow::anyhow!("{}: invalid header: {e}", path.display()))?;
+        let mut offset = header.header_len;
+        while offset < bytes.len() {
+            let (chained, n) = valori_wire::decode_entry(header.version, &bytes[offset..])
+                .map_err(|e| anyhow::anyhow!("{}: byte {offset}: {e}", path.display()))?;
+            offset += n;
+            if let LogEntry::Checkpoint {
+                event_count,
+                snapshot_hash,
+                ..
+            }
+            | LogEntry::CheckpointV2 {
+                event_count,
+                snapshot_hash,
+                ..
+            } = chained.entry
+            {
+                out.insert(event_count, snapshot_hash);
+            }
+        }
+    }
+    Ok(out)
+}
+
+fn replay_checks(report: &mut DeepReport, snapshot: Option<&KernelState>, log: &Path) {
+    let events = match read_all_segments(log, None) {
+        Ok(events) => events,
+        Err(e) => {
+            report.push("replay", CheckStatus::Fail, format!("log unreadable: {e}"));
+            report.push("checkpoints", CheckStatus::Skipped, "log unreadable");
+            return;
+        }
+    };
+    let checkpoints = match checkpoints(log) {
+        Ok(c) => c,
+        Err(e) => {
+            report.push("replay", CheckStatus::Fail, format!("log unreadable: {e}"));
+            report.push("checkpoints", CheckStatus::Skipped, "log unreadable");
+            return;
+        }
+    };
+    let snapshot_at = snapshot.map(|s| (s.version(), hash_state_blake3(s)));
+
+    // Replay from genesis, hashing at the snapshot height and checkpoints.
+    let mut state = KernelState::new();
+    let mut hashes: BTreeMap<u64, [u8; 32]> = BTreeMap::new();
+    let want = |height: u64| {
+        snapshot_at.is_some_and(|(h, _)| h == height) || checkpoints.contains_key(&height)
+    };
+    let mut genesis_error = None;
+    if want(0) {
+        hashes.insert(0, hash_state_blake3(&state));
+    }
+    for (i, (ns, event)) in events.iter().enumerate() {
+        if let Err(e) = state.apply_event_ns(event, *ns) {
+            genesis_error = Some((i as u64 + 1, format!("{e:?}")));
+            break;
+        }
+        let height = i as u64 + 1;
+        if want(height) {
+            hashes.insert(height, hash_state_blake3(&state));
+        }
+    }
+
+    // Genesis replay reproduces the snapshot: check the checkpoints too.
+    let genesis_matches = genesis_error.is_none()
+        && snapshot_at.is_none_or(|(h, want)| hashes.get(&h) == Some(&want));
+    if genesis_matches {
+        let detail = match snapshot_at {
+            None => format!("{} event(s) replayed from genesis", events.len()),
+            Some((h, _)) => format!(
+                "{} event(s) replayed from genesis; snapshot hash matches at height {h}",
+                events.len()
+            ),
+        };
+        report.push("replay", CheckStatus::Ok, detail);
+        report.push_checkpoints(&checkpoints, &hashes);
+        return;
+    }
+
+    // Otherwise the log may continue the snapshot, as `replay-query` reads it.
+    if let Some(base) = snapshot {
+        let mut state = base.clone();
+        let continues = events
+            .iter()
+            .all(|(ns, ev)| state.apply_event_ns(ev, *ns).is_ok());
+        if continues {
+            report.push(
+                "replay",
+                CheckStatus::Ok,
+                format!(
+                    "log continues the snapshot: {} event(s) applied on top",
+                    events.len()
+                ),
+            );
+            report.push(
+                "checkpoints",
+                CheckStatus::Skipped,
+                "log does not start at genesis",
+            );
+            return;
+        }
+    }
+
+    let replayed_all = genesis_error.is_none();
+    let detail = match (genesis_error, snapshot_at) {
+        (Some((n, e)), _) => format!("event #{n} fails to apply from genesis: {e}"),
+        (None, Some((h, _))) if h > events.len() as u64 => format!(
+            "log holds {} event(s), fewer than the snapshot height {h}",
+            events.len()
+        ),
+        (None, Some((h, want))) => format!(
+            "replay gives state hash {} at the snapshot height {h}, snapshot has {}",
+            hashes.get(&h).map(|h| hex(h)).unwrap_or_default(),
+            hex(&want)
+        ),
+        (None, None) => unreachable!("genesis replay without a snapshot always matches"),
+    };
+    report.push("replay", CheckStatus::Fail, detail);
+    if replayed_all {
+        report.push_checkpoints(&checkpoints, &hashes);
+    } else {
+        report.push("checkpoints", CheckStatus::Skipped, "replay failed");
+    }
+}
+
+/// Print `report` as a table.
+pub fn print(report: &DeepReport) {
+    let mut table = Table::new();
+    table
+        .load_preset(UTF8_FULL)
+        .set_content_arrangement(ContentArrangement::Dynamic)
+        .set_header(vec![
+            Cell::new("Check").add_attribute(Attribute::Bold),
+            Cell::new("Result").add_attribute(Attribute::Bold),
+            Cell::new("Details").add_attribute(Attribute::Bold),
+        ]);
+    for check in &report.checks {
+        let status = match check.status {
+            CheckStatus::Ok => Cell::new("OK")
+                .fg(Color::Green)
+                .add_attribute(Attribute::Bold),
+            CheckStatus::Warn => Cell::new("WARN").fg(Color::Yellow),
+            CheckStatus::Fail => Cell::new("FAIL").fg(Color::Red),
+            CheckStatus::Skipped => Cell::new("SKIPPED").fg(Color::DarkGrey),
+        };
+        table.add_row(vec![
+            Cell::new(check.name),
+            status,
+            Cell::new(&check.detail),
+        ]);
+    }
+    println!("Deep Verification");
+    println!("{}", "─".repeat(52));
+    println!("{table}\n");
+}
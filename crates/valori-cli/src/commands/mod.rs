@@ -1,8 +1,17 @@
+pub mod attest;
+pub mod audit;
+pub mod bisect;
 pub mod cluster;
 pub mod diff;
+pub mod fsck;
+pub mod graph_diff;
+pub mod graph_query;
 pub mod import;
 pub mod inspect;
+pub mod inspect_deep;
+pub mod repair_log;
 pub mod replay_query;
 pub mod timeline;
+pub mod vacuum;
 pub mod verify;
 pub mod wizard;
@@ -0,0 +1,55 @@
+// Copyright (c) 2025 Varshith Gudur. Dual-licensed under MIT OR Apache-2.0.
+//! `valori repair-log` — cut a torn final entry off the live event log.
+//!
+//! Hard power loss mid-append can leave half an entry at the end of the live
+//! segment. Recovery skips it, but the writer would append *behind* it and
+//! every later entry would be unreadable. This truncates the segment to its
+//! last complete entry, fsyncs, and reports the committed height and hashes
+//! the node will restart from. Any other damage is refused.
+//!
+//! Run it with the node stopped.
+
+use valori_kernel::snapshot::blake3::hash_state_blake3;
+use valori_node::events::event_log::{repair_torn_tail, torn_tail};
+use valori_node::events::event_replay::{read_all_segments, replay_events};
+
+fn hex(bytes: &[u8]) -> String {
+    bytes.iter().map(|b| format!("{b:02x}")).collect()
+}
+
+pub fn run(log_path: &str, dry_run: bool) -> anyhow::Result<()> {
+    println!("\nRepair — {log_path}\n");
+
+    let check = if dry_run {
+        torn_tail(log_path)
+    } else {
+        repair_torn_tail(log_path)
+    };
+    let repair = check.map_err(|e| {
+        anyhow::anyhow!(
+            "{e}\n    Not a torn tail — truncating would drop committed entries. \
+             See VALORI_QUARANTINE_CORRUPT_SEGMENTS."
+        )
+    })?;
+
+    match (repair.torn_bytes, dry_run) {
+        (0, _) => println!("✅  TAIL CLEAN             nothing to repair"),
+        (n, true) => {
+            println!("⚠️   TORN TAIL              {n} byte(s) would be truncated (dry run)")
+        }
+        (n, false) => println!("✅  TORN TAIL REPAIRED     truncated {n} byte(s), fsynced"),
+    }
+
+    let events = read_all_segments(log_path, None)
+        .map_err(|e| anyhow::anyhow!("Cannot replay '{}': {}", log_path, e))?;
+    let state = replay_events(&events)
+        .map_err(|e| anyhow::anyhow!("Cannot replay '{}': {}", log_path, e))?;
+    println!("    Segment length:   {} bytes", repair.segment_len);
+    println!("    Committed height: {}", events.len());
+    println!("    Chain head:       {}", hex(&repair.chain_head));
+    println!(
+        "    State hash:       {}\n",
+        hex(&hash_state_blake3(&state))
+    );
+    Ok(())
+}
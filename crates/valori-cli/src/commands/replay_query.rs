@@ -1,29 +1,59 @@
 // Copyright (c) 2025 Varshith Gudur. Dual-licensed under MIT OR Apache-2.0.
 //! `valori replay-query` — time-travel to a specific event count and query.
+//!
+//! With `--type`, `--record`, `--node` or `--namespace`, it also lists the
+//! replayed events that match — e.g. every change to record 42. The graph
+//! flags (`--neighbors`, `--kinds`, `--path`) query the graph at that height;
+//! see [`super::graph_query`].
 
+use super::graph_query::{self, GraphQuery};
 use crate::engine::{floats_to_fxp, ForensicEngine};
 use comfy_table::presets::UTF8_FULL;
 use comfy_table::{Attribute, Cell, Color, ContentArrangement, Table};
+use std::ops::RangeInclusive;
 use std::time::Instant;
 use valori_kernel::index::SearchResult;
 use valori_kernel::types::id::RecordId;
+use valori_storage::events::EventFilter;
 
+/// Parse an id or an inclusive id range: `42` or `40-50`.
+pub fn parse_id_range(s: &str) -> Result<RangeInclusive<u32>, String> {
+    let parse = |v: &str| {
+        v.trim()
+            .parse::<u32>()
+            .map_err(|_| format!("invalid id '{v}' (expected N or A-B)"))
+    };
+    match s.split_once('-') {
+        Some((a, b)) => {
+            let (a, b) = (parse(a)?, parse(b)?);
+            if a > b {
+                return Err(format!("empty range '{s}'"));
+            }
+            Ok(a..=b)
+        }
+        None => parse(s).map(|id| id..=id),
+    }
+}
+
+/// `target_count` of `u64::MAX` replays the whole log.
 pub fn run(
     snapshot_path: &str,
     log_path: &str,
     target_count: u64,
     query_arg: Option<String>,
     top_k: usize,
+    filter: &EventFilter,
+    graph: &GraphQuery,
 ) -> anyhow::Result<()> {
     // ── Restore baseline ─────────────────────────────────────────────────────
     let mut engine = ForensicEngine::from_snapshot(snapshot_path)?;
 
     // ── Replay ───────────────────────────────────────────────────────────────
     let t0 = Instant::now();
-    let replayed = engine.replay_to(log_path, target_count)?;
+    let (replayed, matched) = engine.replay_to_filtered(log_path, target_count, filter)?;
     let elapsed = t0.elapsed();
 
-    if engine.current_event_count < target_count {
+    if engine.current_event_count < target_count && target_count != u64::MAX {
         println!(
             "\n⚠️  Reached end of event log before target event #{target_count}.\n\
              State is fast-forwarded to event #{}.\n",
@@ -41,12 +71,20 @@ pub fn run(
             Cell::new("Value").add_attribute(Attribute::Bold),
         ]);
 
-    table.add_row(vec!["Target event", &target_count.to_string()]);
+    let target = if target_count == u64::MAX {
+        "end of log".to_string()
+    } else {
+        target_count.to_string()
+    };
+    table.add_row(vec!["Target event", &target]);
     table.add_row(vec![
         "Current event",
         &engine.current_event_count.to_string(),
     ]);
     table.add_row(vec!["Events replayed", &replayed.to_string()]);
+    if !filter.is_empty() {
+        table.add_row(vec!["Matching events", &matched.len().to_string()]);
+    }
     table.add_row(vec![
         "Replay time",
         &format!("{:.3} ms", elapsed.as_secs_f64() * 1000.0),
@@ -60,6 +98,35 @@ pub fn run(
     println!("{}", "─".repeat(40));
     println!("{table}\n");
 
+    // ── Matching events ──────────────────────────────────────────────────────
+    if !filter.is_empty() {
+        let mut ev_table = Table::new();
+        ev_table
+            .load_preset(UTF8_FULL)
+            .set_content_arrangement(ContentArrangement::Dynamic)
+            .set_header(vec![
+                Cell::new("Event #").add_attribute(Attribute::Bold),
+                Cell::new("Type").add_attribute(Attribute::Bold),
+                Cell::new("Details").add_attribute(Attribute::Bold),
+            ]);
+        for ev in &matched {
+            let (type_cell, detail) = super::timeline::describe_event(&ev.event);
+            let detail = match ev.namespace_id {
+                Some(ns) => format!("[ns {ns}] {detail}"),
+                None => detail,
+            };
+            ev_table.add_row(vec![Cell::new(ev.index), type_cell, Cell::new(detail)]);
+        }
+        println!("Matching Events  ·  {}", matched.len());
+        println!("{}", "─".repeat(40));
+        println!("{ev_table}\n");
+    }
+
+    // ── Graph queries ────────────────────────────────────────────────────────
+    if !graph.is_empty() {
+        graph_query::print(engine.kernel_state(), graph);
+    }
+
     // ── Optional search ───────────────────────────────────────────────────────
     if let Some(query_str) = query_arg {
         let floats: Vec<f64> = serde_json::from_str(&query_str).map_err(|_| {
@@ -1,29 +1,230 @@
 // Copyright (c) 2025 Varshith Gudur. Dual-licensed under MIT OR Apache-2.0.
 //! `valori timeline` — print the event history from an event log.
+//!
+//! Every data event is decoded and replayed (onto an optional snapshot
+//! baseline) so each row can show the ids it touched and the live record
+//! and node counts after it. Checkpoints and admin entries are shown in
+//! place. A per-type breakdown follows the table.
+//!
+//! Without a baseline, a log that starts after a snapshot cannot be
+//! replayed from empty; the counts then stop at the first event that fails
+//! to apply and the rest of the timeline is still listed.
 
+use std::collections::BTreeMap;
+
+use crate::engine::{ForensicEngine, LogEvent};
 use comfy_table::presets::UTF8_FULL;
 use comfy_table::{Attribute, Cell, Color, ContentArrangement, Table};
+use serde::Serialize;
 use valori_kernel::event::KernelEvent;
-use valori_node::events::event_log::LogEntry;
+use valori_node::events::event_log::{AdminEvent, LogEntry};
+use valori_storage::events::{touched_nodes, touched_records};
+
+/// What `valori timeline` prints.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq, clap::ValueEnum)]
+pub enum TimelineFormat {
+    /// Event table followed by the per-type breakdown.
+    #[default]
+    Table,
+    /// The whole report as JSON.
+    Json,
+}
+
+/// One data event on the timeline.
+#[derive(Debug, Clone, Serialize)]
+pub struct TimelineEvent {
+    /// Event index in the log (1-based; checkpoints can move it forward).
+    pub height: u64,
+    pub event_type: String,
+    pub namespace_id: Option<u16>,
+    /// Record and node ids the event touched, `Auto*` ids resolved.
+    pub records: Vec<u32>,
+    pub nodes: Vec<u32>,
+    pub detail: String,
+    /// Live counts after the event; `None` once replay lost track.
+    pub record_count: Option<usize>,
+    pub node_count: Option<usize>,
+    /// Coloured type cell for the table.
+    #[serde(skip)]
+    type_cell: Cell,
+}
 
-pub fn run(log_path: &str, limit: usize) -> anyhow::Result<()> {
+/// One row of the timeline, in log order.
+#[derive(Debug, Clone, Serialize)]
+#[serde(tag = "entry", rename_all = "snake_case")]
+pub enum TimelineEntry {
+    Event(TimelineEvent),
+    /// A snapshot was taken after `event_count` events.
+    Checkpoint {
+        event_count: u64,
+    },
+    Annotation {
+        height: u64,
+        label: String,
+    },
+    /// Any other admin entry (membership changes).
+    Admin {
+        description: String,
+    },
+}
+
+#[derive(Debug, Clone, Default, Serialize)]
+pub struct TimelineReport {
+    pub log_version: u32,
+    pub dim: u32,
+    pub entries: Vec<TimelineEntry>,
+    /// Data events listed.
+    pub events: u64,
+    /// Listed data events per type.
+    pub event_types: BTreeMap<String, u64>,
+    /// `--limit` stopped the listing before the end of the log.
+    pub truncated: bool,
+    /// Why decoding stopped before the end of the log.
+    pub error: Option<String>,
+    /// Why the record and node counts stopped (an event failed to apply).
+    pub replay_error: Option<String>,
+}
+
+/// Decode `log_path`, replaying onto `snapshot` (or empty state), listing
+/// at most `limit` data events (0 = all).
+pub fn timeline(
+    log_path: &str,
+    snapshot: Option<&str>,
+    limit: usize,
+) -> anyhow::Result<TimelineReport> {
     let bytes = std::fs::read(log_path)
         .map_err(|e| anyhow::anyhow!("Cannot read '{}': {}", log_path, e))?;
-
+    let mut report = TimelineReport::default();
     if bytes.len() < 16 {
-        println!(
-            "\n⚠️  Event log is empty or too short to parse ({} bytes).\n",
+        report.error = Some(format!(
+            "event log is empty or too short to parse ({} bytes)",
             bytes.len()
-        );
-        return Ok(());
+        ));
+        return Ok(report);
     }
 
-    let log_version = u32::from_le_bytes(bytes[0..4].try_into().unwrap());
-    let dim = u32::from_le_bytes(bytes[4..8].try_into().unwrap());
+    let header = valori_wire::parse_header(&bytes)
+        .map_err(|e| anyhow::anyhow!("Invalid event log header: {e}"))?;
+    report.log_version = header.version;
+    report.dim = header.dim;
+    let mut engine = match snapshot {
+        Some(path) => ForensicEngine::from_snapshot(path)?,
+        None => ForensicEngine::empty(),
+    };
+    let mut offset = header.header_len;
+    let mut height = 0u64;
+
+    while offset < bytes.len() {
+        let (chained, bytes_read) =
+            match valori_wire::decode_entry(header.version, &bytes[offset..]) {
+                Ok(ok) => ok,
+                Err(e) => {
+                    report.error = Some(format!(
+                        "decoding stopped at byte offset {offset} after {} event(s): {e}",
+                        report.events
+                    ));
+                    break;
+                }
+            };
+        offset += bytes_read;
+
+        let (namespace_id, event) = match chained.entry {
+            LogEntry::Event(event) => (None, event),
+            // S15: EventNs is the same as Event for the timeline, just
+            // tagged with the collection it landed in.
+            LogEntry::EventNs {
+                namespace_id,
+                event,
+            } => (Some(namespace_id), event),
+            LogEntry::Checkpoint { event_count, .. }
+            | LogEntry::CheckpointV2 { event_count, .. } => {
+                report
+                    .entries
+                    .push(TimelineEntry::Checkpoint { event_count });
+                height = event_count;
+                continue;
+            }
+            LogEntry::Admin(AdminEvent::Annotate { height, label, .. }) => {
+                report
+                    .entries
+                    .push(TimelineEntry::Annotation { height, label });
+                continue;
+            }
+            LogEntry::Admin(admin) => {
+                report.entries.push(TimelineEntry::Admin {
+                    description: admin.describe(),
+                });
+                continue;
+            }
+            LogEntry::BatchCommit { .. } => continue,
+        };
+
+        if limit > 0 && report.events as usize >= limit {
+            report.truncated = true;
+            break;
+        }
+        height += 1;
+        let (type_cell, detail) = describe_event(&event);
+        let event_type = type_cell.content();
+        let state = &engine.state;
+        let records: Vec<u32> = touched_records(state, &event).collect();
+        let nodes: Vec<u32> = touched_nodes(state, &event).collect();
+
+        let counted = if report.replay_error.is_none() {
+            let ev = LogEvent {
+                index: height,
+                namespace_id,
+                event,
+            };
+            match engine.apply(&ev) {
+                Ok(()) => Some((engine.record_count(), engine.node_count())),
+                Err(e) => {
+                    report.replay_error = Some(e.to_string());
+                    None
+                }
+            }
+        } else {
+            None
+        };
+
+        report.events += 1;
+        *report.event_types.entry(event_type.clone()).or_default() += 1;
+        report.entries.push(TimelineEntry::Event(TimelineEvent {
+            height,
+            event_type,
+            namespace_id,
+            records,
+            nodes,
+            detail,
+            record_count: counted.map(|c| c.0),
+            node_count: counted.map(|c| c.1),
+            type_cell,
+        }));
+    }
+    Ok(report)
+}
+
+pub fn run(
+    log_path: &str,
+    snapshot: Option<&str>,
+    limit: usize,
+    format: TimelineFormat,
+) -> anyhow::Result<()> {
+    let report = timeline(log_path, snapshot, limit)?;
+    if format == TimelineFormat::Json {
+        println!("{}", serde_json::to_string_pretty(&report)?);
+        return Ok(());
+    }
+    if report.entries.is_empty() {
+        if let Some(e) = &report.error {
+            println!("\n⚠️  {e}\n");
+            return Ok(());
+        }
+    }
 
     println!(
         "\nEvent Timeline  ·  {}  (log-version {}, dim {})\n",
-        log_path, log_version, dim
+        log_path, report.log_version, report.dim
     );
 
     let mut table = Table::new();
@@ -31,100 +232,117 @@ pub fn run(log_path: &str, limit: usize) -> anyhow::Result<()> {
         .load_preset(UTF8_FULL)
         .set_content_arrangement(ContentArrangement::Dynamic)
         .set_header(vec![
-            Cell::new("Event #").add_attribute(Attribute::Bold),
+            Cell::new("Height").add_attribute(Attribute::Bold),
             Cell::new("Type").add_attribute(Attribute::Bold),
+            Cell::new("Ids").add_attribute(Attribute::Bold),
             Cell::new("Details").add_attribute(Attribute::Bold),
+            Cell::new("Records").add_attribute(Attribute::Bold),
+            Cell::new("Nodes").add_attribute(Attribute::Bold),
         ]);
+    let count = |c: Option<usize>| c.map_or_else(|| "?".to_string(), |c| c.to_string());
+    let dash = || Cell::new("—");
 
-    let header = valori_wire::parse_header(&bytes)
-        .map_err(|e| anyhow::anyhow!("Invalid event log header: {e}"))?;
-    let mut offset = header.header_len;
-    let mut event_num = 0u64; // 1-based display counter
-
-    while offset < bytes.len() {
-        match valori_wire::decode_entry(header.version, &bytes[offset..]) {
-            Ok((chained, bytes_read)) => {
-                offset += bytes_read;
-
-                match chained.entry {
-                    // S15: EventNs is the same as Event for the timeline, just
-                    // tagged with the collection it landed in.
-                    LogEntry::Event(event) => {
-                        event_num += 1;
-                        let (type_cell, detail) = describe_event(&event);
-                        table.add_row(vec![
-                            Cell::new(event_num.to_string()),
-                            type_cell,
-                            Cell::new(detail),
-                        ]);
-                        if limit > 0 && event_num as usize >= limit {
-                            println!("{table}");
-                            println!(
-                                "\n  … display limited to first {limit} events. \
-                                 Pass --limit 0 to show all.\n"
-                            );
-                            return Ok(());
-                        }
-                    }
-
-                    LogEntry::EventNs {
-                        namespace_id,
-                        event,
-                    } => {
-                        event_num += 1;
-                        let (type_cell, detail) = describe_event(&event);
-                        table.add_row(vec![
-                            Cell::new(event_num.to_string()),
-                            type_cell,
-                            Cell::new(format!("[ns {namespace_id}] {detail}")),
-                        ]);
-                        if limit > 0 && event_num as usize >= limit {
-                            println!("{table}");
-                            println!(
-                                "\n  … display limited to first {limit} events. \
-                                 Pass --limit 0 to show all.\n"
-                            );
-                            return Ok(());
-                        }
-                    }
-
-                    LogEntry::Checkpoint { event_count, .. } => {
-                        table.add_row(vec![
-                            Cell::new("—"),
-                            Cell::new("Checkpoint").fg(Color::Cyan),
-                            Cell::new(format!("snapshot taken at event count {event_count}")),
-                        ]);
-                        event_num = event_count;
-                    }
-
-                    LogEntry::Admin(admin) => {
-                        table.add_row(vec![
-                            Cell::new("—"),
-                            Cell::new("Admin").fg(Color::Magenta),
-                            Cell::new(admin.describe()),
-                        ]);
-                    }
-                }
+    for entry in &report.entries {
+        match entry {
+            TimelineEntry::Event(ev) => {
+                let detail = match ev.namespace_id {
+                    Some(ns) => format!("[ns {ns}] {}", ev.detail),
+                    None => ev.detail.clone(),
+                };
+                table.add_row(vec![
+                    Cell::new(ev.height.to_string()),
+                    ev.type_cell.clone(),
+                    Cell::new(affected_ids(&ev.records, &ev.nodes)),
+                    Cell::new(detail),
+                    Cell::new(count(ev.record_count)),
+                    Cell::new(count(ev.node_count)),
+                ]);
+            }
+            TimelineEntry::Checkpoint { event_count } => {
+                table.add_row(vec![
+                    dash(),
+                    Cell::new("Checkpoint").fg(Color::Cyan),
+                    dash(),
+                    Cell::new(format!("snapshot taken at event count {event_count}")),
+                    dash(),
+                    dash(),
+                ]);
+            }
+            TimelineEntry::Annotation { height, label } => {
+                table.add_row(vec![
+                    Cell::new(height.to_string()),
+                    Cell::new("Annotation").fg(Color::Blue),
+                    dash(),
+                    Cell::new(format!("{label:?}")),
+                    dash(),
+                    dash(),
+                ]);
             }
-            Err(e) => {
-                println!("{table}");
-                println!(
-                    "\n⚠️  Decoding stopped at byte offset {offset} after {} event(s): {e}\n",
-                    event_num
-                );
-                return Ok(());
+            TimelineEntry::Admin { description } => {
+                table.add_row(vec![
+                    dash(),
+                    Cell::new("Admin").fg(Color::Magenta),
+                    dash(),
+                    Cell::new(description),
+                    dash(),
+                    dash(),
+                ]);
             }
         }
     }
-
     println!("{table}");
-    println!("\n  Total: {} event(s)\n", event_num);
+
+    if report.truncated {
+        println!(
+            "\n  … display limited to first {limit} events. \
+             Pass --limit 0 to show all."
+        );
+    }
+    if let Some(e) = &report.error {
+        println!("\n⚠️  {e}");
+    }
+    if let Some(e) = &report.replay_error {
+        println!(
+            "\n⚠️  Record/node counts stop at a failed replay ({e}). \
+             Pass --snapshot with the baseline this log starts from."
+        );
+    }
+
+    let mut breakdown = Table::new();
+    breakdown
+        .load_preset(UTF8_FULL)
+        .set_content_arrangement(ContentArrangement::Dynamic)
+        .set_header(vec![
+            Cell::new("Event type").add_attribute(Attribute::Bold),
+            Cell::new("Count").add_attribute(Attribute::Bold),
+        ]);
+    for (event_type, n) in &report.event_types {
+        breakdown.add_row(vec![Cell::new(event_type), Cell::new(n.to_string())]);
+    }
+    println!("\n{breakdown}");
+    println!("\n  Total: {} event(s)\n", report.events);
     Ok(())
 }
 
 // ─── Helpers ─────────────────────────────────────────────────────────────────
 
-fn describe_event(event: &KernelEvent) -> (Cell, String) {
+/// `r 3, 4 · n 7` — the record and node ids an event touched.
+fn affected_ids(records: &[u32], nodes: &[u32]) -> String {
+    let list = |ids: &[u32]| {
+        ids.iter()
+            .map(u32::to_string)
+            .collect::<Vec<_>>()
+            .join(", ")
+    };
+    match (records.is_empty(), nodes.is_empty()) {
+        (true, true) => "—".to_string(),
+        (false, true) => format!("r {}", list(records)),
+        (true, false) => format!("n {}", list(nodes)),
+        (false, false) => format!("r {} · n {}", list(records), list(nodes)),
+    }
+}
+
+pub(crate) fn describe_event(event: &KernelEvent) -> (Cell, String) {
     match event {
         KernelEvent::InsertRecord { id, tag, .. } => (
             Cell::new("InsertRecord").fg(Color::Green),
@@ -237,6 +455,11 @@ fn describe_event(event: &KernelEvent) -> (Cell, String) {
             format!("key={key:?}  value={value:?}"),
         ),
 
+        KernelEvent::DeleteMeta { key } => (
+            Cell::new("DeleteMeta").fg(Color::Yellow),
+            format!("key={key:?}"),
+        ),
+
         KernelEvent::AutoCreateNamespace { name } => (
             Cell::new("AutoCreateNamespace").fg(Color::Cyan),
             format!("name={name:?}  (id assigned at apply)"),
@@ -251,5 +474,32 @@ fn describe_event(event: &KernelEvent) -> (Cell, String) {
             Cell::new("UpdateRecordMetadata").fg(Color::White),
             format!("record_id={}", id.0),
         ),
+
+        KernelEvent::ResizePools {
+            records,
+            nodes,
+            edges,
+        } => (
+            Cell::new("ResizePools").fg(Color::White),
+            format!("records={records} nodes={nodes} edges={edges}"),
+        ),
+
+        KernelEvent::Vacuum { moves } => (
+            Cell::new("Vacuum").fg(Color::Yellow),
+            format!("moved={} records", moves.len()),
+        ),
+
+        KernelEvent::Omitted { record } => (
+            Cell::new("Omitted").fg(Color::DarkGrey),
+            match record {
+                Some(r) => format!("record_id={} (not replicated here)", r.0),
+                None => "(not replicated here)".to_string(),
+            },
+        ),
+
+        KernelEvent::SetSparseVector { id, vector } => (
+            Cell::new("SetSparseVector").fg(Color::Blue),
+            format!("record_id={}  nnz={}", id.0, vector.nnz()),
+        ),
     }
 }
@@ -0,0 +1,69 @@
+// Copyright (c) 2025 Varshith Gudur. Dual-licensed under MIT OR Apache-2.0.
+//! `valori vacuum` — re-pack a running node's record pool.
+//!
+//! Calls `POST /v1/admin/vacuum` (admin scope), which commits one `Vacuum`
+//! event moving every live record into the lowest free slots. The old→new
+//! id mapping is in the event log and in the response; `--mapping` writes it
+//! out as JSON Lines for clients that keep record ids of their own.
+//!
+//! ```text
+//! valori vacuum --url http://10.0.0.1:3000
+//! valori vacuum --url http://10.0.0.1:3000 --mapping moves.jsonl
+//! ```
+
+use anyhow::{bail, Context, Result};
+use std::io::Write;
+use std::path::PathBuf;
+
+pub struct VacuumArgs {
+    pub url: String,
+    pub token: Option<String>,
+    /// Where to write `{"old":…,"new":…}` lines; `None` = don't.
+    pub mapping: Option<PathBuf>,
+}
+
+pub fn run(args: VacuumArgs) -> Result<()> {
+    let base = args.url.trim_end_matches('/');
+    let mut req = ureq::post(&format!("{base}/v1/admin/vacuum"));
+    if let Some(t) = &args.token {
+        req = req.set("Authorization", &format!("Bearer {t}"));
+    }
+    let body: serde_json::Value = match req.call() {
+        Ok(resp) => resp.into_json().context("vacuum response was not JSON")?,
+        Err(ureq::Error::Status(code, resp)) => {
+            let msg = resp
+                .into_json::<serde_json::Value>()
+                .ok()
+                .and_then(|b| b["error"].as_str().map(str::to_string))
+                .unwrap_or_default();
+            bail!("vacuum failed (HTTP {code}) {msg}")
+        }
+        Err(e) => bail!("cannot reach {base}: {e}"),
+    };
+
+    let reclaimed = body["reclaimed"].as_u64().unwrap_or(0);
+    let moves = body["moves"].as_array().cloned().unwrap_or_default();
+    if reclaimed == 0 {
+        println!("nothing to reclaim — the record pool is already packed");
+        return Ok(());
+    }
+    println!(
+        "reclaimed {reclaimed} slots, moved {} records ({} live), height {}",
+        moves.len(),
+        body["records"],
+        body["height"],
+    );
+
+    if let Some(p) = &args.mapping {
+        let mut out = std::io::BufWriter::new(
+            std::fs::File::create(p).with_context(|| format!("cannot create {}", p.display()))?,
+        );
+        for m in &moves {
+            serde_json::to_writer(&mut out, &serde_json::json!({ "old": m[0], "new": m[1] }))?;
+            out.write_all(b"\n")?;
+        }
+        out.flush()?;
+        eprintln!("wrote {} id moves to {}", moves.len(), p.display());
+    }
+    Ok(())
+}
@@ -2,7 +2,8 @@
 //! `valori verify` — snapshot integrity check.
 //!
 //! Performs two complementary checks:
-//! 1. **Structural validity** — magic bytes and section-length consistency.
+//! 1. **Structural validity** — magic bytes, section lengths that tile the
+//!    file, and the container's CRC32 trailer when it has one.
 //! 2. **State hash** — decodes the kernel section and computes the canonical
 //!    BLAKE3 content hash so the result can be compared against a known-good
 //!    value.
@@ -10,6 +11,7 @@
 use crate::engine::{inspect_snapshot_bytes, parse_kernel_from_snapshot_bytes};
 use crc64fast::Digest;
 use valori_kernel::snapshot::blake3::hash_state_blake3;
+use valori_kernel::snapshot::container::Checksum;
 
 pub fn run(snapshot_path: &str) -> anyhow::Result<()> {
     let bytes = std::fs::read(snapshot_path)
@@ -20,7 +22,14 @@ pub fn run(snapshot_path: &str) -> anyhow::Result<()> {
     println!("\nVerify — {snapshot_path}  ({file_kb:.2} KB)\n");
 
     // ── 1. Structural check ───────────────────────────────────────────────────
-    let info = inspect_snapshot_bytes(&bytes)?;
+    let info = match inspect_snapshot_bytes(&bytes) {
+        Ok(info) => info,
+        Err(e) => {
+            println!("❌  STRUCTURAL INTEGRITY   FAILED");
+            println!("    {e}");
+            anyhow::bail!("Snapshot sections are malformed");
+        }
+    };
 
     if !info.magic_ok {
         println!("❌  STRUCTURAL INTEGRITY   FAILED");
@@ -32,23 +41,27 @@ pub fn run(snapshot_path: &str) -> anyhow::Result<()> {
         anyhow::bail!("Snapshot has invalid magic bytes");
     }
 
-    // Verify total byte count matches sum of sections.
-    let expected_total = 4                       // "VAL1"
-        + 4 + info.kernel_len                    // kernel section
-        + 4 + info.metadata_len                  // metadata section
-        + 4 + info.index_len; // index section
-
-    let structure_ok = expected_total == bytes.len();
-
-    if structure_ok {
-        println!("✅  STRUCTURAL INTEGRITY   PASSED");
-    } else {
-        println!("⚠️   STRUCTURAL INTEGRITY   PARTIAL");
-        println!(
-            "    Expected {} bytes from section headers, file is {} bytes",
-            expected_total,
-            bytes.len()
-        );
+    println!("✅  STRUCTURAL INTEGRITY   PASSED");
+    println!(
+        "    Sections:    kernel {} B  metadata {} B  index {} B",
+        info.kernel_len, info.metadata_len, info.index_len
+    );
+    if !info.sections.is_empty() {
+        let tagged: Vec<String> = info
+            .sections
+            .iter()
+            .map(|(tag, len)| format!("{tag} {len} B"))
+            .collect();
+        println!("    Tagged:      {}", tagged.join("  "));
+    }
+    match info.checksum {
+        Checksum::Valid => println!("    CRC32:       trailer matches"),
+        Checksum::Absent => println!("    CRC32:       no trailer (older snapshot)"),
+        Checksum::Mismatch { stored, computed } => {
+            println!("❌  CHECKSUM               FAILED");
+            println!("    Trailer holds {stored:08x}, contents give {computed:08x}");
+            anyhow::bail!("Snapshot checksum mismatch");
+        }
     }
 
     // ── 2. CRC64 file checksum ────────────────────────────────────────────────
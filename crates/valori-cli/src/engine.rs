@@ -7,13 +7,12 @@
 //! **read-only, forensic view** of the database.
 
 use anyhow::{bail, Context, Result};
+use valori_kernel::event::KernelEvent;
 use valori_kernel::snapshot::blake3::hash_state_blake3;
-use valori_kernel::snapshot::decode::decode_state;
+use valori_kernel::snapshot::container::{self, Checksum, Container};
 use valori_kernel::state::kernel::KernelState;
 use valori_node::events::event_log::LogEntry;
-
-/// Magic bytes that prefix every Valori snapshot blob.
-const SNAPSHOT_MAGIC: &[u8; 4] = b"VAL1";
+use valori_storage::events::EventFilter;
 
 // ─── ForensicEngine ──────────────────────────────────────────────────────────
 
@@ -33,7 +32,7 @@ pub struct ForensicEngine {
 }
 
 impl ForensicEngine {
-    /// Restore from a `snapshot_path` (VAL1 format).
+    /// Restore from a `snapshot_path` (snapshot container format).
     ///
     /// The resulting state reflects the exact point-in-time captured by the
     /// snapshot.  Call [`replay_to`](Self::replay_to) afterwards to advance
@@ -71,77 +70,60 @@ impl ForensicEngine {
     ///
     /// Returns the number of events actually applied in this call.
     pub fn replay_to(&mut self, log_path: &str, target_count: u64) -> Result<usize> {
-        let raw = std::fs::read(log_path)
-            .with_context(|| format!("Cannot read event log: {log_path}"))?;
+        let mut replayed = 0;
 
-        if raw.len() < 16 {
-            return Ok(0); // Empty log — nothing to replay.
+        for item in LogEvents::open(log_path)? {
+            let ev = item?;
+            if ev.index > target_count {
+                break;
+            }
+            self.apply(&ev)?;
+            replayed += 1;
         }
 
-        let header = valori_wire::parse_header(&raw)
-            .map_err(|e| anyhow::anyhow!("Invalid event log header: {e}"))?;
-        let mut offset = header.header_len;
-        let mut event_index: u64 = 0;
+        Ok(replayed)
+    }
+
+    /// [`replay_to`](Self::replay_to), also returning the replayed events
+    /// `filter` matches. Every event is applied; the filter only decides
+    /// what is reported, judged against the state just before each event.
+    pub fn replay_to_filtered(
+        &mut self,
+        log_path: &str,
+        target_count: u64,
+        filter: &EventFilter,
+    ) -> Result<(usize, Vec<LogEvent>)> {
         let mut replayed = 0;
+        let mut matched = Vec::new();
 
-        while offset < raw.len() {
-            match valori_wire::decode_entry(header.version, &raw[offset..]) {
-                Ok((chained, bytes_read)) => {
-                    offset += bytes_read;
-                    match chained.entry {
-                        LogEntry::Event(event) => {
-                            event_index += 1;
-
-                            if event_index > target_count {
-                                break;
-                            }
-
-                            self.state.apply_event(&event).map_err(|e| {
-                                anyhow::anyhow!("Event #{event_index} failed: {e:?}")
-                            })?;
-
-                            self.current_event_count = event_index;
-                            self.applied_events.push(event_index);
-                            replayed += 1;
-                        }
-                        // S15: namespace-scoped data event — replay into its
-                        // own collection so point-in-time state matches.
-                        LogEntry::EventNs {
-                            namespace_id,
-                            event,
-                        } => {
-                            event_index += 1;
-
-                            if event_index > target_count {
-                                break;
-                            }
-
-                            self.state
-                                .apply_event_ns(&event, namespace_id)
-                                .map_err(|e| {
-                                    anyhow::anyhow!("Event #{event_index} failed: {e:?}")
-                                })?;
-
-                            self.current_event_count = event_index;
-                            self.applied_events.push(event_index);
-                            replayed += 1;
-                        }
-                        LogEntry::Checkpoint { event_count, .. } => {
-                            // Checkpoint entries record cumulative event count
-                            // at the time a snapshot was taken.
-                            event_index = event_count;
-                        }
-                        // Admin events never touch kernel state.
-                        LogEntry::Admin(_) => {}
-                    }
-                }
-                Err(e) => {
-                    bail!("Event log corrupt at byte offset {offset}: {e}");
-                }
+        for item in LogEvents::open(log_path)? {
+            let ev = item?;
+            if ev.index > target_count {
+                break;
+            }
+            if filter.matches(&self.state, ev.namespace_id.unwrap_or(0), &ev.event) {
+                matched.push(ev.clone());
             }
+            self.apply(&ev)?;
+            replayed += 1;
         }
 
-        Ok(replayed)
+        Ok((replayed, matched))
+    }
+
+    /// Apply one decoded log event and record it as replayed.
+    pub fn apply(&mut self, ev: &LogEvent) -> Result<()> {
+        let applied = match ev.namespace_id {
+            // S15: namespace-scoped data event — replay into its own
+            // collection so point-in-time state matches.
+            Some(ns) => self.state.apply_event_ns(&ev.event, ns),
+            None => self.state.apply_event(&ev.event),
+        };
+        applied.map_err(|e| anyhow::anyhow!("Event #{} failed: {e:?}", ev.index))?;
+
+        self.current_event_count = ev.index;
+        self.applied_events.push(ev.index);
+        Ok(())
     }
 
     // Mirror the Engine accessor API so CLI commands compile unchanged.
@@ -174,6 +156,101 @@ impl ForensicEngine {
     }
 }
 
+// ─── LogEvents ───────────────────────────────────────────────────────────────
+
+/// One data event decoded from an event log.
+#[derive(Debug, Clone)]
+pub struct LogEvent {
+    /// 1-based event index (checkpoints may advance it past skipped events).
+    pub index: u64,
+    /// `Some` for namespace-scoped (`EventNs`) entries.
+    pub namespace_id: Option<u16>,
+    pub event: KernelEvent,
+}
+
+/// Lazily decodes the data events of an event log, in order.
+///
+/// Checkpoint entries move the running index to their recorded
+/// `event_count`; admin entries and batch commit records are skipped. Decoding stops at the first
+/// corrupt entry, so a caller that stops early never sees corruption past
+/// the point it needed.
+pub struct LogEvents {
+    raw: Vec<u8>,
+    version: u32,
+    offset: usize,
+    event_index: u64,
+}
+
+impl LogEvents {
+    pub fn open(log_path: &str) -> Result<Self> {
+        let raw = std::fs::read(log_path)
+            .with_context(|| format!("Cannot read event log: {log_path}"))?;
+
+        if raw.len() < 16 {
+            // Empty log — nothing to replay.
+            return Ok(Self {
+                offset: raw.len(),
+                raw,
+                version: 0,
+                event_index: 0,
+            });
+        }
+
+        let header = valori_wire::parse_header(&raw)
+            .map_err(|e| anyhow::anyhow!("Invalid event log header: {e}"))?;
+        Ok(Self {
+            raw,
+            version: header.version,
+            offset: header.header_len,
+            event_index: 0,
+        })
+    }
+}
+
+impl Iterator for LogEvents {
+    type Item = Result<LogEvent>;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        while self.offset < self.raw.len() {
+            let (chained, bytes_read) =
+                match valori_wire::decode_entry(self.version, &self.raw[self.offset..]) {
+                    Ok(ok) => ok,
+                    Err(e) => {
+                        let offset = self.offset;
+                        self.offset = self.raw.len();
+                        return Some(Err(anyhow::anyhow!(
+                            "Event log corrupt at byte offset {offset}: {e}"
+                        )));
+                    }
+                };
+            self.offset += bytes_read;
+            let (namespace_id, event) = match chained.entry {
+                LogEntry::Event(event) => (None, event),
+                LogEntry::EventNs {
+                    namespace_id,
+                    event,
+                } => (Some(namespace_id), event),
+                LogEntry::Checkpoint { event_count, .. }
+                | LogEntry::CheckpointV2 { event_count, .. } => {
+                    // Checkpoint entries record cumulative event count
+                    // at the time a snapshot was taken.
+                    self.event_index = event_count;
+                    continue;
+                }
+                // Admin events and batch records never touch kernel state.
+                LogEntry::Admin(_) | LogEntry::BatchCommit { .. } => continue,
+            };
+            self.event_index += 1;
+            return Some(Ok(LogEvent {
+                index: self.event_index,
+                namespace_id,
+                event,
+            }));
+        }
+        None
+    }
+}
+
 // ─── Shared helpers ───────────────────────────────────────────────────────────
 
 /// Convert f64 float values to a Q16.16 fixed-point vector for kernel search.
@@ -185,7 +262,7 @@ pub fn floats_to_fxp(floats: &[f64]) -> valori_kernel::types::vector::FxpVector
             .iter()
             .map(|&f| {
                 FxpScalar(
-                    (f as f32 * 65536.0)
+                    (f as f32 * valori_protocol::fxp::SCALE_F32)
                         .round()
                         .clamp(i32::MIN as f32, i32::MAX as f32) as i32,
                 )
@@ -196,45 +273,19 @@ pub fn floats_to_fxp(floats: &[f64]) -> valori_kernel::types::vector::FxpVector
 
 // ─── Snapshot parsing helpers ─────────────────────────────────────────────────
 
-/// Parse a [`KernelState`] from raw snapshot bytes (VAL1 format).
-///
-/// # Layout
-/// ```text
-/// [4 B]  magic       "VAL1"
-/// [4 B]  kernel_len  (u32 LE)
-/// [N B]  kernel_data (VALK-encoded KernelState)
-/// [4 B]  meta_len    (u32 LE)
-/// [M B]  meta_data
-/// [4 B]  index_len   (u32 LE)
-/// [K B]  index_data
-/// ```
+/// Parse a [`KernelState`] from raw snapshot bytes — a node snapshot or any
+/// other [`valori_kernel::snapshot::container`]. Only the kernel section is
+/// decoded; a checksum mismatch is left to `valori verify`.
 pub fn parse_kernel_from_snapshot_bytes(data: &[u8]) -> Result<KernelState> {
-    if data.len() < 12 {
-        bail!("Snapshot is too short ({} bytes)", data.len());
-    }
-    if &data[0..4] != SNAPSHOT_MAGIC {
-        bail!(
-            "Invalid snapshot magic: expected {:?}, got {:?}",
-            SNAPSHOT_MAGIC,
-            &data[0..4]
-        );
-    }
-
-    let k_len = u32::from_le_bytes(data[4..8].try_into().unwrap()) as usize;
-    if 8 + k_len > data.len() {
-        bail!(
-            "Snapshot kernel section truncated (need {} bytes, have {})",
-            8 + k_len,
-            data.len()
-        );
-    }
-
-    decode_state(&data[8..8 + k_len])
+    let container = Container::parse(data).map_err(|e| anyhow::anyhow!("Snapshot: {e}"))?;
+    container
+        .decode_state()
         .map_err(|e| anyhow::anyhow!("KernelState decode error: {e:?}"))
 }
 
-/// Read the snapshot magic and section lengths without fully decoding the
-/// state.  Cheap structural check — suitable for the `inspect` command.
+/// Read the snapshot magic and section lengths without decoding the state.
+/// Cheap structural check — suitable for the `inspect` command. Bytes with
+/// the right magic whose sections do not tile the file are an error.
 pub fn inspect_snapshot_bytes(data: &[u8]) -> Result<SnapshotInfo> {
     if data.len() < 4 {
         bail!(
@@ -243,40 +294,30 @@ pub fn inspect_snapshot_bytes(data: &[u8]) -> Result<SnapshotInfo> {
         );
     }
 
-    let magic_ok = &data[0..4] == SNAPSHOT_MAGIC;
-
-    if !magic_ok || data.len() < 12 {
+    if !data.starts_with(container::MAGIC) {
         return Ok(SnapshotInfo {
-            magic_ok,
+            magic_ok: false,
             kernel_len: 0,
             metadata_len: 0,
             index_len: 0,
+            sections: Vec::new(),
+            checksum: Checksum::Absent,
             total_size: data.len(),
         });
     }
 
-    let k_len = u32::from_le_bytes(data[4..8].try_into().unwrap()) as usize;
-    let mut cursor = 8 + k_len;
-
-    let metadata_len = if cursor + 4 <= data.len() {
-        let v = u32::from_le_bytes(data[cursor..cursor + 4].try_into().unwrap()) as usize;
-        cursor += 4 + v;
-        v
-    } else {
-        0
-    };
-
-    let index_len = if cursor + 4 <= data.len() {
-        u32::from_le_bytes(data[cursor..cursor + 4].try_into().unwrap()) as usize
-    } else {
-        0
-    };
-
+    let c = Container::parse(data).map_err(|e| anyhow::anyhow!("Snapshot: {e}"))?;
     Ok(SnapshotInfo {
-        magic_ok,
-        kernel_len: k_len,
-        metadata_len,
-        index_len,
+        magic_ok: true,
+        kernel_len: c.kernel.len(),
+        metadata_len: c.metadata.len(),
+        index_len: c.index.len(),
+        sections: c
+            .sections
+            .iter()
+            .map(|s| (s.name(), s.data.len()))
+            .collect(),
+        checksum: c.checksum,
         total_size: data.len(),
     })
 }
@@ -288,5 +329,8 @@ pub struct SnapshotInfo {
     pub kernel_len: usize,
     pub metadata_len: usize,
     pub index_len: usize,
+    /// Tagged sections (tag, length) in file order.
+    pub sections: Vec<(String, usize)>,
+    pub checksum: Checksum,
     pub total_size: usize,
 }
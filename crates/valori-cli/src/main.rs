@@ -1,9 +1,13 @@
 // Copyright (c) 2025 Varshith Gudur. Dual-licensed under MIT OR Apache-2.0.
 use clap::{Parser, Subcommand};
+use std::ops::RangeInclusive;
 use std::path::PathBuf;
+use valori_cli::commands::graph_query::{self, GraphQuery};
 use valori_cli::commands::{
-    cluster, diff, import, inspect, replay_query, timeline, verify, wizard,
+    attest, audit, bisect, cluster, diff, fsck, import, inspect, repair_log, replay_query,
+    timeline, vacuum, verify, wizard,
 };
+use valori_storage::events::{EventClass, EventFilter};
 
 #[derive(Parser)]
 #[command(
@@ -48,6 +52,12 @@ enum Commands {
         /// Path to the event log file (overrides --dir).
         #[arg(long)]
         log: Option<String>,
+
+        /// Also verify the snapshot checksum and invariants, index ↔ pool
+        /// consistency, and replay the log against the snapshot hash and
+        /// checkpoints. Exits non-zero on a failed check.
+        #[arg(long, default_value_t = false)]
+        deep: bool,
     },
 
     /// Verify the structural integrity and magic bytes of a snapshot file.
@@ -56,7 +66,25 @@ enum Commands {
         snapshot: String,
     },
 
+    /// Truncate a torn final entry (crash mid-append) off the live event log.
+    ///
+    /// Run with the node stopped. Prints the committed height, chain head and
+    /// state hash the node will restart from. Refuses any damage other than a
+    /// torn tail.
+    RepairLog {
+        /// Path to the live events.log file.
+        log: String,
+
+        /// Report what would be truncated without changing the file.
+        #[arg(long)]
+        dry_run: bool,
+    },
+
     /// Print the event timeline from an event log.
+    ///
+    /// Each event row shows its height, type, the record and node ids it
+    /// touched and the live record / node counts after it, with
+    /// checkpoints and annotations in place and a per-type breakdown.
     Timeline {
         /// Path to the events.log file.
         log: String,
@@ -64,12 +92,24 @@ enum Commands {
         /// Maximum number of events to display (0 = all).
         #[arg(long, default_value = "0")]
         limit: usize,
+
+        /// Snapshot the log starts from, for the running record and node
+        /// counts (default: empty state).
+        #[arg(long)]
+        snapshot: Option<String>,
+
+        /// Output format: `table` or `json`.
+        #[arg(long, value_enum, default_value = "table")]
+        format: timeline::TimelineFormat,
     },
 
     /// Fast-forward to a specific event count and report the database state.
     ///
     /// Restores the snapshot baseline, then replays events 1–N from the event
     /// log, and prints the state hash and optional search results at event N.
+    /// The filter flags list the replayed events that match, e.g.
+    /// `--record 42` for every change to record 42. `--neighbors`, `--kinds`
+    /// and `--path` query the graph as it was at event N.
     ReplayQuery {
         /// Path to the snapshot file (baseline state).
         #[arg(long)]
@@ -79,9 +119,27 @@ enum Commands {
         #[arg(long)]
         log: String,
 
-        /// Replay events 1–N and report the kernel state at event N.
+        /// Replay events 1–N and report the kernel state at event N
+        /// (default: the whole log).
         #[arg(long, short)]
-        at: u64,
+        at: Option<u64>,
+
+        /// List matching events of this class: record, graph, meta,
+        /// namespace, key or maintenance. Repeatable.
+        #[arg(long = "type", value_name = "CLASS")]
+        types: Vec<EventClass>,
+
+        /// List matching events touching this record id or range (`42`, `40-50`).
+        #[arg(long, value_parser = replay_query::parse_id_range)]
+        record: Option<RangeInclusive<u32>>,
+
+        /// List matching events touching this graph node id or range.
+        #[arg(long, value_parser = replay_query::parse_id_range)]
+        node: Option<RangeInclusive<u32>>,
+
+        /// List matching events applied in this namespace id.
+        #[arg(long)]
+        namespace: Option<u16>,
 
         /// Optional JSON float array query, e.g. '[0.1, 0.2, 0.3]'.
         #[arg(long, short)]
@@ -90,9 +148,27 @@ enum Commands {
         /// Number of nearest neighbours to return (applies to --query).
         #[arg(long, default_value = "5")]
         top_k: usize,
+
+        /// List the incoming and outgoing edges of this graph node.
+        #[arg(long, value_name = "NODE")]
+        neighbors: Option<u32>,
+
+        /// Count graph nodes and edges by kind.
+        #[arg(long, default_value_t = false)]
+        kinds: bool,
+
+        /// Find a shortest path between two graph nodes, following edges
+        /// either way.
+        #[arg(long, num_args = 2, value_names = ["FROM", "TO"])]
+        path: Option<Vec<u32>>,
+
+        /// Longest path searched by --path, in edges.
+        #[arg(long, default_value_t = graph_query::DEFAULT_MAX_DEPTH)]
+        max_depth: usize,
     },
 
-    /// Compare database state between two event counts (semantic diff).
+    /// Compare database state between two event counts (semantic and
+    /// structural diff).
     ///
     /// Replays to --from and --to independently from the same snapshot
     /// baseline and reports the state-hash delta and nearest-neighbour rank
@@ -121,6 +197,38 @@ enum Commands {
         /// Number of nearest neighbours to compare.
         #[arg(long, default_value = "5")]
         top_k: usize,
+
+        /// Output mode: `table`, `structural` (adds record / graph deltas
+        /// and per-section hashes) or `json` (structural diff only).
+        #[arg(long, value_enum, default_value = "table")]
+        output: diff::DiffOutput,
+    },
+
+    /// Find the first event where two histories diverge.
+    ///
+    /// Replays two event logs (each on an optional snapshot baseline) in
+    /// lockstep, hashing state after every event, and reports the first
+    /// step whose state hashes differ along with the event each side applied.
+    Bisect {
+        /// Event log of side A.
+        #[arg(long)]
+        log_a: String,
+
+        /// Event log of side B.
+        #[arg(long)]
+        log_b: String,
+
+        /// Snapshot baseline for side A (default: empty state).
+        #[arg(long)]
+        snapshot_a: Option<String>,
+
+        /// Snapshot baseline for side B (default: empty state).
+        #[arg(long)]
+        snapshot_b: Option<String>,
+
+        /// Print the report as JSON.
+        #[arg(long, default_value_t = false)]
+        json: bool,
     },
 
     /// Operate a running Raft cluster (status, health, membership).
@@ -140,6 +248,95 @@ enum Commands {
         #[command(subcommand)]
         source: ImportSource,
     },
+
+    /// Export a running node's API audit trail (who called which route).
+    Audit {
+        #[command(subcommand)]
+        action: AuditAction,
+    },
+
+    /// Re-pack a running node's record pool (`POST /v1/admin/vacuum`).
+    ///
+    /// Live records move into the lowest free slots through one logged
+    /// `Vacuum` event; deleted slots are given back. Record ids change —
+    /// pass --mapping to save the old→new ids.
+    Vacuum {
+        /// Base URL of the node, e.g. http://10.0.0.1:3000
+        #[arg(long, default_value = "http://127.0.0.1:3000")]
+        url: String,
+        /// Write the old→new id mapping here as JSON Lines.
+        #[arg(long)]
+        mapping: Option<PathBuf>,
+        /// Admin bearer token (prefer the VALORI_AUTH_TOKEN env var).
+        #[arg(long)]
+        token: Option<String>,
+    },
+
+    /// Check a running node's indexes (`/v1/admin/check` and
+    /// `/v1/admin/index/repair`); exits non-zero if anything is wrong.
+    ///
+    /// --repair drops orphaned HNSW links, resets a stale entry point and
+    /// re-links unreachable nodes. Records and state are never touched.
+    Fsck {
+        /// Base URL of the node, e.g. http://10.0.0.1:3000
+        #[arg(long, default_value = "http://127.0.0.1:3000")]
+        url: String,
+        /// Repair the graph instead of only reporting.
+        #[arg(long)]
+        repair: bool,
+        /// Admin bearer token (prefer the VALORI_AUTH_TOKEN env var).
+        #[arg(long)]
+        token: Option<String>,
+    },
+
+    /// Cross-machine determinism check: replay a local event log to the
+    /// peer's height and compare event chain, section and state hashes
+    /// against the peer's `/v1/proof/at`.
+    ///
+    /// Exits non-zero on any difference and names the first divergent
+    /// section (and, for a different history, the first divergent event).
+    Attest {
+        /// Base URL of the peer node, e.g. http://10.0.0.2:3000
+        #[arg(long)]
+        peer: String,
+        /// Local event log (archived segments beside it are read too).
+        #[arg(long)]
+        log: String,
+        /// Height to compare at (default: the lower of the two heads).
+        #[arg(long)]
+        at: Option<u64>,
+        /// Print the report as JSON.
+        #[arg(long, default_value_t = false)]
+        json: bool,
+        /// Bearer token for the peer (prefer the VALORI_AUTH_TOKEN env var).
+        #[arg(long)]
+        token: Option<String>,
+    },
+}
+
+#[derive(Subcommand)]
+enum AuditAction {
+    /// Write every audit entry after --since as JSON Lines.
+    Export {
+        /// Base URL of the node, e.g. http://10.0.0.1:3000
+        #[arg(long, default_value = "http://127.0.0.1:3000")]
+        url: String,
+        /// Only entries with a sequence number greater than this.
+        #[arg(long, default_value = "0")]
+        since: u64,
+        /// Only entries for this principal, e.g. `role:writer` or `key:key_ab12…`.
+        #[arg(long)]
+        principal: Option<String>,
+        /// Only entries whose route starts with this prefix, e.g. /v1/records.
+        #[arg(long)]
+        route: Option<String>,
+        /// Output file (default: stdout).
+        #[arg(long, short)]
+        out: Option<PathBuf>,
+        /// Admin bearer token (prefer the VALORI_AUTH_TOKEN env var).
+        #[arg(long)]
+        token: Option<String>,
+    },
 }
 
 #[derive(Subcommand)]
@@ -276,16 +473,57 @@ async fn main() -> anyhow::Result<()> {
         None => wizard::run("127.0.0.1").await,
         Some(Commands::Setup { bind }) => wizard::run(&bind).await,
 
-        Some(Commands::Inspect { dir, snapshot, log }) => inspect::run(dir, snapshot, log),
+        Some(Commands::Inspect {
+            dir,
+            snapshot,
+            log,
+            deep,
+        }) => inspect::run(dir, snapshot, log, deep),
         Some(Commands::Verify { snapshot }) => verify::run(&snapshot),
-        Some(Commands::Timeline { log, limit }) => timeline::run(&log, limit),
+        Some(Commands::RepairLog { log, dry_run }) => repair_log::run(&log, dry_run),
+        Some(Commands::Timeline {
+            log,
+            limit,
+            snapshot,
+            format,
+        }) => timeline::run(&log, snapshot.as_deref(), limit, format),
         Some(Commands::ReplayQuery {
             snapshot,
             log,
             at,
+            types,
+            record,
+            node,
+            namespace,
             query,
             top_k,
-        }) => replay_query::run(&snapshot, &log, at, query, top_k),
+            neighbors,
+            kinds,
+            path,
+            max_depth,
+        }) => {
+            let graph = GraphQuery {
+                neighbors,
+                kinds,
+                path: path.map(|p| (p[0], p[1])),
+                max_depth,
+            };
+            let filter = EventFilter {
+                classes: types,
+                records: record,
+                nodes: node,
+                namespace,
+            };
+            replay_query::run(
+                &snapshot,
+                &log,
+                at.unwrap_or(u64::MAX),
+                query,
+                top_k,
+                &filter,
+                &graph,
+            )
+        }
         Some(Commands::Diff {
             snapshot,
             log,
@@ -293,7 +531,25 @@ async fn main() -> anyhow::Result<()> {
             to,
             query,
             top_k,
-        }) => diff::run(&snapshot, &log, from, to, query, top_k),
+            output,
+        }) => diff::run(&snapshot, &log, from, to, query, top_k, output),
+        Some(Commands::Bisect {
+            log_a,
+            log_b,
+            snapshot_a,
+            snapshot_b,
+            json,
+        }) => bisect::run(
+            bisect::Side {
+                snapshot: snapshot_a.as_deref(),
+                log: &log_a,
+            },
+            bisect::Side {
+                snapshot: snapshot_b.as_deref(),
+                log: &log_b,
+            },
+            json,
+        ),
         Some(Commands::Cluster { action }) => match action {
             ClusterAction::Status { url } => cluster::status(&url),
             ClusterAction::Health { url } => cluster::health(&url),
@@ -310,6 +566,88 @@ async fn main() -> anyhow::Result<()> {
             } => cluster::upgrade(&url, &target_version),
         },
 
+        Some(Commands::Audit { action }) => match action {
+            AuditAction::Export {
+                url,
+                since,
+                principal,
+                route,
+                out,
+                token,
+            } => {
+                if token.is_some() {
+                    eprintln!(
+                        "Warning: --token is visible in process listings. \
+                         Prefer VALORI_AUTH_TOKEN env var to pass credentials securely."
+                    );
+                }
+                let token = token.or_else(|| std::env::var("VALORI_AUTH_TOKEN").ok());
+                audit::export(audit::AuditExportArgs {
+                    url,
+                    token,
+                    since,
+                    principal,
+                    route,
+                    out,
+                })
+            }
+        },
+
+        Some(Commands::Vacuum {
+            url,
+            mapping,
+            token,
+        }) => {
+            if token.is_some() {
+                eprintln!(
+                    "Warning: --token is visible in process listings. \
+                     Prefer VALORI_AUTH_TOKEN env var to pass credentials securely."
+                );
+            }
+            let token = token.or_else(|| std::env::var("VALORI_AUTH_TOKEN").ok());
+            vacuum::run(vacuum::VacuumArgs {
+                url,
+                token,
+                mapping,
+            })
+        }
+
+        Some(Commands::Fsck { url, repair, token }) => {
+            if token.is_some() {
+                eprintln!(
+                    "Warning: --token is visible in process listings. \
+                     Prefer VALORI_AUTH_TOKEN env var to pass credentials securely."
+                );
+            }
+            let token = token.or_else(|| std::env::var("VALORI_AUTH_TOKEN").ok());
+            fsck::run(fsck::FsckArgs { url, token, repair })
+        }
+
+        Some(Commands::Attest {
+            peer,
+            log,
+            at,
+            json,
+            token,
+        }) => {
+            if token.is_some() {
+                eprintln!(
+                    "Warning: --token is visible in process listings. \
+                     Prefer VALORI_AUTH_TOKEN env var to pass credentials securely."
+                );
+            }
+            let token = token.or_else(|| std::env::var("VALORI_AUTH_TOKEN").ok());
+            attest::run(
+                attest::AttestArgs {
+                    peer,
+                    log,
+                    at,
+                    token,
+                },
+                json,
+            )
+        }
+
         Some(Commands::Import { source }) => match source {
             ImportSource::Qdrant {
                 url,
@@ -0,0 +1,121 @@
+// Copyright (c) 2025 Varshith Gudur. Dual-licensed under MIT OR Apache-2.0.
+//! `valori attest` against a real running node: a local copy of the same
+//! history agrees byte for byte, a forked history is pinned to its first
+//! divergent event.
+
+use std::path::{Path, PathBuf};
+use std::process::Command;
+use std::sync::Arc;
+
+use tokio::sync::RwLock;
+use valori_cli::commands::attest::{attest, AttestArgs};
+use valori_node::attest::Section;
+use valori_node::config::NodeConfig;
+use valori_node::engine::Engine;
+use valori_node::geo_replication::local_events;
+use valori_node::server::{build_router, SharedEngine};
+use valori_node::EngineFromNodeConfig;
+
+fn valori_bin() -> &'static str {
+    env!("CARGO_BIN_EXE_valori")
+}
+
+/// A standalone engine logging to `dir/events.log`, fed `rows` and flushed.
+async fn logged_engine(dir: &Path, rows: &[[f32; 4]]) -> (SharedEngine, PathBuf) {
+    let log = dir.join("events.log");
+    let mut cfg = NodeConfig::default();
+    cfg.dim = 4;
+    cfg.event_log_path = Some(log.clone());
+    let shared = Arc::new(RwLock::new(Engine::new(&cfg)));
+    for row in rows {
+        shared.write().await.insert_record_from_f32(row).unwrap();
+    }
+    // Flushes the log so a reader sees every committed event.
+    local_events(&shared).await.unwrap();
+    (shared, log)
+}
+
+async fn serve(engine: SharedEngine) -> String {
+    let app = build_router(engine, None, None);
+    let listener = tokio::net::TcpListener::bind("127.0.0.1:0").await.unwrap();
+    let addr = listener.local_addr().unwrap();
+    tokio::spawn(async move {
+        axum::serve(listener, app).await.unwrap();
+    });
+    format!("http://{addr}")
+}
+
+#[tokio::test(flavor = "multi_thread", worker_threads = 4)]
+async fn attest_agrees_on_the_same_history_and_pins_a_fork() {
+    let rows = [
+        [1.0, 0.0, 0.0, 0.0],
+        [0.0, 1.0, 0.0, 0.0],
+        [0.0, 0.0, 1.0, 0.0],
+    ];
+    let peer_dir = tempfile::tempdir().unwrap();
+    let (peer, peer_log) = logged_engine(peer_dir.path(), &rows).await;
+    let url = serve(peer).await;
+
+    // The peer's own log replayed here: agreement, exit 0.
+    let (u, log) = (url.clone(), peer_log.display().to_string());
+    let out = tokio::task::spawn_blocking(move || {
+        Command::new(valori_bin())
+            .args(["attest", "--peer", &u, "--log", &log])
+            .output()
+            .unwrap()
+    })
+    .await
+    .unwrap();
+    let stdout = String::from_utf8_lossy(&out.stdout);
+    assert!(
+        out.status.success(),
+        "stderr: {}",
+        String::from_utf8_lossy(&out.stderr)
+    );
+    assert!(stdout.contains("AGREE"), "{stdout}");
+
+    // Same first two inserts, different third: the fork is the last event.
+    let fork_dir = tempfile::tempdir().unwrap();
+    let mut forked = rows;
+    forked[2] = [0.0, 0.0, 0.0, 1.0];
+    let (_fork, fork_log) = logged_engine(fork_dir.path(), &forked).await;
+    let args = AttestArgs {
+        peer: url.clone(),
+        log: fork_log.display().to_string(),
+        at: None,
+        token: None,
+    };
+    let report = tokio::task::spawn_blocking(move || attest(&args).unwrap())
+        .await
+        .unwrap();
+    assert!(!report.agree);
+    assert_eq!(report.height, report.peer_head);
+    assert_eq!(report.divergent_section, Some(Section::Events));
+    assert_eq!(report.first_divergent_event, Some(report.height));
+
+    // One event short of the fork, the two agree.
+    let args = AttestArgs {
+        peer: url.clone(),
+        log: fork_log.display().to_string(),
+        at: Some(report.height - 1),
+        token: None,
+    };
+    let short = tokio::task::spawn_blocking(move || attest(&args).unwrap())
+        .await
+        .unwrap();
+    assert!(short.agree, "{short:?}");
+
+    let log = fork_log.display().to_string();
+    let out = tokio::task::spawn_blocking(move || {
+        Command::new(valori_bin())
+            .args(["attest", "--peer", &url, "--log", &log, "--json"])
+            .output()
+            .unwrap()
+    })
+    .await
+    .unwrap();
+    assert!(!out.status.success(), "a fork must exit non-zero");
+    let body: serde_json::Value = serde_json::from_slice(&out.stdout).unwrap();
+    assert_eq!(body["divergent_section"], "events");
+    assert!(String::from_utf8_lossy(&out.stderr).contains("diverge at event"));
+}
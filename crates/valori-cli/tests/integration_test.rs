@@ -7,8 +7,12 @@
 
 use std::path::{Path, PathBuf};
 use tempfile::tempdir;
-use valori_cli::commands::{diff, inspect, replay_query, timeline, verify};
+use valori_cli::commands::graph_query::GraphQuery;
+use valori_cli::commands::{
+    bisect, diff, graph_diff, graph_query, inspect, repair_log, replay_query, timeline, verify,
+};
 use valori_cli::engine::ForensicEngine;
+use valori_storage::events::{EventClass, EventFilter};
 
 // ─── Fixture helpers ──────────────────────────────────────────────────────────
 
@@ -86,10 +90,100 @@ fn test_inspect_finds_both_files() {
     let paths = build_test_db(dir.path()).unwrap();
     let _ = paths; // keep alive
 
-    let result = inspect::run(Some(dir.path().to_path_buf()), None, None);
+    let result = inspect::run(Some(dir.path().to_path_buf()), None, None, false);
     assert!(result.is_ok(), "inspect should succeed: {result:?}");
 }
 
+#[test]
+fn test_inspect_deep_accepts_a_log_continuing_the_snapshot() {
+    use valori_cli::commands::inspect_deep::{deep_check, CheckStatus};
+
+    let dir = tempdir().unwrap();
+    let paths = build_test_db(dir.path()).unwrap();
+    let report = deep_check(&paths.snapshot, &paths.log);
+    assert!(report.ok(), "{report:?}");
+    assert_eq!(
+        report.get("snapshot").unwrap().status,
+        CheckStatus::Warn,
+        "the fixture has no checksum trailer"
+    );
+    assert_eq!(report.get("index ↔ pool").unwrap().status, CheckStatus::Ok);
+    assert!(report
+        .get("replay")
+        .unwrap()
+        .detail
+        .contains("continues the snapshot"));
+    assert!(inspect::run(Some(dir.path().to_path_buf()), None, None, true).is_ok());
+}
+
+#[test]
+fn test_inspect_deep_replays_from_genesis_and_checks_checkpoints() {
+    use valori_cli::commands::inspect_deep::{deep_check, CheckStatus};
+    use valori_kernel::event::KernelEvent;
+    use valori_kernel::snapshot::blake3::hash_state_blake3;
+    use valori_kernel::snapshot::encode::encode_state;
+    use valori_kernel::state::kernel::KernelState;
+    use valori_kernel::types::id::RecordId;
+    use valori_kernel::types::vector::FxpVector;
+    use valori_node::events::event_log::{EventLogWriter, LogEntry};
+
+    let insert = |i: u32| KernelEvent::InsertRecord {
+        id: RecordId(i),
+        vector: FxpVector::new_zeros(4),
+        metadata: None,
+        tag: i as u64,
+    };
+    let write = |dir: &Path, checkpoint_hash: Option<[u8; 32]>| {
+        // Snapshot at height 3 of a 5-event log, checkpoint at 5.
+        let mut state = KernelState::new();
+        let mut writer = EventLogWriter::open(dir.join("events.log"), Some(4)).unwrap();
+        for i in 0..5 {
+            state.apply_event(&insert(i)).unwrap();
+            writer.append(&LogEntry::Event(insert(i))).unwrap();
+            if i == 2 {
+                let mut kernel = Vec::new();
+                encode_state(&state, &mut kernel).unwrap();
+                let mut snap = b"VAL1".to_vec();
+                snap.extend_from_slice(&(kernel.len() as u32).to_le_bytes());
+                snap.extend_from_slice(&kernel);
+                snap.extend_from_slice(&[0; 8]);
+                std::fs::write(dir.join("snapshot.val"), snap).unwrap();
+            }
+        }
+        writer
+            .append(&LogEntry::Checkpoint {
+                event_count: 5,
+                snapshot_hash: checkpoint_hash.unwrap_or(hash_state_blake3(&state)),
+                timestamp: 0,
+            })
+            .unwrap();
+    };
+
+    let dir = tempdir().unwrap();
+    write(dir.path(), None);
+    let report = deep_check(
+        &dir.path().join("snapshot.val"),
+        &dir.path().join("events.log"),
+    );
+    assert!(report.ok(), "{report:?}");
+    assert!(report
+        .get("replay")
+        .unwrap()
+        .detail
+        .contains("snapshot hash matches at height 3"));
+    assert_eq!(report.get("checkpoints").unwrap().status, CheckStatus::Ok);
+
+    let dir = tempdir().unwrap();
+    write(dir.path(), Some([7; 32]));
+    let report = deep_check(
+        &dir.path().join("snapshot.val"),
+        &dir.path().join("events.log"),
+    );
+    assert!(!report.ok());
+    assert_eq!(report.get("checkpoints").unwrap().status, CheckStatus::Fail);
+    assert!(inspect::run(Some(dir.path().to_path_buf()), None, None, true).is_err());
+}
+
 #[test]
 fn test_verify_passes_on_valid_snapshot() {
     let dir = tempdir().unwrap();
@@ -107,13 +201,108 @@ fn test_timeline_reads_all_events() {
     let dir = tempdir().unwrap();
     let paths = build_test_db(dir.path()).unwrap();
 
-    let result = timeline::run(paths.log.to_str().unwrap(), 0 /* no limit */);
+    let result = timeline::run(
+        paths.log.to_str().unwrap(),
+        None,
+        0, /* no limit */
+        timeline::TimelineFormat::Table,
+    );
     assert!(
         result.is_ok(),
         "timeline should parse the event log: {result:?}"
     );
 }
 
+#[test]
+fn test_timeline_reports_ids_counts_and_type_breakdown() {
+    use valori_kernel::event::KernelEvent;
+    use valori_kernel::types::enums::NodeKind;
+    use valori_kernel::types::id::RecordId;
+    use valori_node::events::event_log::{EventLogWriter, LogEntry};
+
+    let dir = tempdir().unwrap();
+    let paths = build_test_db(dir.path()).unwrap();
+    let mut writer = EventLogWriter::open(&paths.log, Some(4)).unwrap();
+    writer
+        .append(&LogEntry::Event(KernelEvent::AutoCreateNode {
+            kind: NodeKind::Document,
+            record: Some(RecordId(4)),
+        }))
+        .unwrap();
+    drop(writer);
+
+    let report = timeline::timeline(
+        paths.log.to_str().unwrap(),
+        Some(paths.snapshot.to_str().unwrap()),
+        0,
+    )
+    .unwrap();
+    assert_eq!(report.events, 4);
+    assert!(report.error.is_none() && report.replay_error.is_none());
+    let events: Vec<_> = report
+        .entries
+        .iter()
+        .filter_map(|e| match e {
+            timeline::TimelineEntry::Event(ev) => Some(ev),
+            _ => None,
+        })
+        .collect();
+    assert_eq!(events[0].height, 1);
+    assert_eq!(events[0].records, [3]);
+    assert_eq!(events[0].record_count, Some(4), "3 from the snapshot");
+    assert_eq!(events[3].event_type, "AutoCreateNode");
+    assert_eq!(
+        (events[3].records.as_slice(), events[3].nodes.as_slice()),
+        (&[4][..], &[0][..])
+    );
+    assert_eq!(events[3].node_count, Some(1));
+    assert_eq!(report.event_types["InsertRecord"], 3);
+    assert_eq!(report.event_types["AutoCreateNode"], 1);
+
+    let json = serde_json::to_value(&report).unwrap();
+    assert_eq!(json["entries"][3]["entry"], "event");
+    assert_eq!(json["entries"][3]["nodes"], serde_json::json!([0]));
+
+    let limited = timeline::timeline(paths.log.to_str().unwrap(), None, 2).unwrap();
+    assert_eq!(limited.events, 2);
+    assert!(limited.truncated);
+}
+
+#[test]
+fn test_timeline_and_inspect_show_annotations() {
+    use valori_node::events::event_log::{AdminEvent, EventLogWriter, LogEntry};
+
+    let dir = tempdir().unwrap();
+    let paths = build_test_db(dir.path()).unwrap();
+    let mut writer = EventLogWriter::open(&paths.log, Some(4)).unwrap();
+    writer
+        .append(&LogEntry::Admin(AdminEvent::Annotate {
+            height: 2,
+            label: "incident start".into(),
+            authorized_by: [0u8; 16],
+        }))
+        .unwrap();
+    drop(writer);
+
+    let report = timeline::timeline(paths.log.to_str().unwrap(), None, 0).unwrap();
+    assert!(report.entries.iter().any(|e| matches!(
+        e,
+        timeline::TimelineEntry::Annotation { height: 2, label } if label == "incident start"
+    )));
+    assert!(inspect::run(Some(dir.path().to_path_buf()), None, None, false).is_ok());
+    // An annotation is not an event: replay past it still sees three.
+    let result = replay_query::run(
+        paths.snapshot.to_str().unwrap(),
+        paths.log.to_str().unwrap(),
+        3,
+        None,
+        5,
+        &EventFilter::default(),
+        &GraphQuery::default(),
+    );
+    assert!(result.is_ok(), "{result:?}");
+}
+
 #[test]
 fn test_verify_rejects_corrupt_snapshot() {
     let dir = tempdir().unwrap();
@@ -124,6 +313,39 @@ fn test_verify_rejects_corrupt_snapshot() {
     assert!(result.is_err(), "verify should reject a corrupt snapshot");
 }
 
+#[test]
+fn test_node_snapshot_is_readable_by_the_cli() {
+    use valori_kernel::snapshot::blake3::hash_state_blake3;
+    use valori_node::EngineFromNodeConfig;
+
+    let dir = tempdir().unwrap();
+    let mut cfg = valori_node::config::NodeConfig::default();
+    cfg.dim = 4;
+    let mut engine = valori_node::engine::Engine::new(&cfg);
+    engine.create_collection("docs").unwrap();
+    for i in 0..3 {
+        engine.insert_record_from_f32(&[i as f32 * 0.1; 4]).unwrap();
+    }
+    let path = dir.path().join("node.val");
+    std::fs::write(&path, engine.snapshot().unwrap()).unwrap();
+
+    // Tagged sections and the checksum trailer are part of the container.
+    assert!(verify::run(path.to_str().unwrap()).is_ok());
+    let forensic = ForensicEngine::from_snapshot(path.to_str().unwrap()).unwrap();
+    assert_eq!(forensic.state.record_count(), 3);
+    assert_eq!(
+        hash_state_blake3(&forensic.state),
+        hash_state_blake3(&engine.state)
+    );
+
+    // A flipped byte fails verification but not forensic decoding.
+    let mut bytes = std::fs::read(&path).unwrap();
+    *bytes.last_mut().unwrap() ^= 0xff;
+    std::fs::write(&path, &bytes).unwrap();
+    assert!(verify::run(path.to_str().unwrap()).is_err());
+    assert!(ForensicEngine::from_snapshot(path.to_str().unwrap()).is_ok());
+}
+
 #[test]
 fn test_replay_to_advances_state() {
     let dir = tempdir().unwrap();
@@ -136,10 +358,53 @@ fn test_replay_to_advances_state() {
         2, // replay 2 events
         None,
         5,
+        &EventFilter::default(),
+        &GraphQuery::default(),
     );
     assert!(result.is_ok(), "replay-query should succeed: {result:?}");
 }
 
+#[test]
+fn test_replay_filters_report_only_matching_events() {
+    let dir = tempdir().unwrap();
+    let paths = build_test_db(dir.path()).unwrap();
+    let log = paths.log.to_str().unwrap();
+
+    let record_4 = EventFilter {
+        records: Some(replay_query::parse_id_range("4").unwrap()),
+        ..Default::default()
+    };
+    let mut engine = ForensicEngine::from_snapshot(paths.snapshot.to_str().unwrap()).unwrap();
+    let (replayed, matched) = engine.replay_to_filtered(log, u64::MAX, &record_4).unwrap();
+    assert_eq!(replayed, 3, "every event is still applied");
+    assert_eq!(engine.state.record_count(), 6);
+    assert_eq!(matched.iter().map(|e| e.index).collect::<Vec<_>>(), vec![2]);
+
+    let graph = EventFilter {
+        classes: vec![EventClass::Graph],
+        ..Default::default()
+    };
+    let mut engine = ForensicEngine::from_snapshot(paths.snapshot.to_str().unwrap()).unwrap();
+    assert!(engine
+        .replay_to_filtered(log, u64::MAX, &graph)
+        .unwrap()
+        .1
+        .is_empty());
+
+    assert_eq!(replay_query::parse_id_range("3-5").unwrap(), 3..=5);
+    assert!(replay_query::parse_id_range("5-3").is_err());
+    assert!(replay_query::run(
+        paths.snapshot.to_str().unwrap(),
+        log,
+        u64::MAX,
+        None,
+        5,
+        &record_4,
+        &GraphQuery::default(),
+    )
+    .is_ok());
+}
+
 #[test]
 fn test_replay_beyond_log_end_is_graceful() {
     let dir = tempdir().unwrap();
@@ -152,6 +417,8 @@ fn test_replay_beyond_log_end_is_graceful() {
         99,
         None,
         5,
+        &EventFilter::default(),
+        &GraphQuery::default(),
     );
     assert!(
         result.is_ok(),
@@ -171,6 +438,7 @@ fn test_diff_identical_positions_shows_no_drift() {
         2,
         None,
         5,
+        diff::DiffOutput::Table,
     );
     assert!(result.is_ok(), "diff at identical positions: {result:?}");
 }
@@ -187,10 +455,281 @@ fn test_diff_forward_detects_new_events() {
         3,
         None,
         5,
+        diff::DiffOutput::Table,
     );
     assert!(result.is_ok(), "diff forward: {result:?}");
 }
 
+#[test]
+fn test_diff_structural_and_json_outputs() {
+    let dir = tempdir().unwrap();
+    let paths = build_test_db(dir.path()).unwrap();
+
+    for output in [diff::DiffOutput::Structural, diff::DiffOutput::Json] {
+        let result = diff::run(
+            paths.snapshot.to_str().unwrap(),
+            paths.log.to_str().unwrap(),
+            1,
+            3,
+            None,
+            5,
+            output,
+        );
+        assert!(result.is_ok(), "diff {output:?}: {result:?}");
+    }
+}
+
+#[test]
+fn test_graph_diff_reports_kinds_and_top_k_connectivity() {
+    use valori_kernel::event::KernelEvent;
+    use valori_kernel::state::diff::diff_states;
+    use valori_kernel::state::kernel::KernelState;
+    use valori_kernel::types::enums::{EdgeKind, NodeKind};
+    use valori_kernel::types::id::{EdgeId, NodeId, RecordId};
+    use valori_kernel::types::vector::FxpVector;
+
+    let mut a = KernelState::new();
+    for i in 0u32..2 {
+        a.apply_event(&KernelEvent::InsertRecord {
+            id: RecordId(i),
+            vector: FxpVector::new_zeros(4),
+            metadata: None,
+            tag: 0,
+        })
+        .unwrap();
+        a.apply_event(&KernelEvent::CreateNode {
+            id: NodeId(i),
+            kind: NodeKind::Record,
+            record: Some(RecordId(i)),
+        })
+        .unwrap();
+    }
+    let mut b = a.clone();
+    b.apply_event(&KernelEvent::CreateNode {
+        id: NodeId(2),
+        kind: NodeKind::Concept,
+        record: None,
+    })
+    .unwrap();
+    b.apply_event(&KernelEvent::CreateEdge {
+        id: EdgeId(0),
+        from: NodeId(0),
+        to: NodeId(2),
+        kind: EdgeKind::Mentions,
+    })
+    .unwrap();
+
+    let g = graph_diff::graph_diff(&a, &b, &diff_states(&a, &b), &[0, 1]);
+    assert_eq!(g.nodes_by_kind["Concept"].added, 1);
+    assert_eq!(g.edges_by_kind["Mentions"].added, 1);
+    // Record 1's neighbourhood is untouched; record 0 gained the Concept link.
+    assert_eq!(g.neighbourhoods.len(), 1);
+    assert_eq!(g.neighbourhoods[0].record, 0);
+    assert_eq!(
+        (g.neighbourhoods[0].degree_a, g.neighbourhoods[0].degree_b),
+        (0, 1)
+    );
+    assert_eq!(
+        g.neighbourhoods[0].gained,
+        vec!["Mentions → Concept node 2"]
+    );
+
+    assert!(graph_diff::graph_diff(&b, &b, &diff_states(&b, &b), &[0, 1]).is_empty());
+}
+
+#[test]
+fn test_replay_query_graph_neighbors_kinds_and_paths() {
+    use valori_kernel::event::KernelEvent;
+    use valori_kernel::types::enums::{EdgeKind, NodeKind};
+    use valori_kernel::types::id::{EdgeId, NodeId, RecordId};
+    use valori_node::events::event_log::{EventLogWriter, LogEntry};
+
+    // Snapshot nodes: none. Log: nodes 0..4 on records 0..4, then a chain
+    // 0 → 1 ← 2 → 3, node 4 isolated, and the 2 → 3 edge deleted last.
+    let dir = tempdir().unwrap();
+    let paths = build_test_db(dir.path()).unwrap();
+    let mut writer = EventLogWriter::open(&paths.log, Some(4)).unwrap();
+    let mut append = |event| writer.append(&LogEntry::Event(event)).unwrap();
+    for i in 0u32..5 {
+        append(KernelEvent::CreateNode {
+            id: NodeId(i),
+            kind: if i == 0 {
+                NodeKind::Document
+            } else {
+                NodeKind::Chunk
+            },
+            record: Some(RecordId(i)),
+        });
+    }
+    for (id, (from, to)) in [(0, 1), (2, 1), (2, 3)].into_iter().enumerate() {
+        append(KernelEvent::CreateEdge {
+            id: EdgeId(id as u32),
+            from: NodeId(from),
+            to: NodeId(to),
+            kind: EdgeKind::Mentions,
+        });
+    }
+    append(KernelEvent::DeleteEdge { id: EdgeId(2) });
+    drop(writer);
+
+    // After all but the last event (3 inserts, 5 nodes, 3 edges).
+    let mut engine = ForensicEngine::from_snapshot(paths.snapshot.to_str().unwrap()).unwrap();
+    engine.replay_to(paths.log.to_str().unwrap(), 11).unwrap();
+    let state = engine.kernel_state();
+
+    let around_2 = graph_query::neighbors(state, 2).unwrap();
+    assert_eq!(
+        around_2
+            .iter()
+            .map(|n| (n.node, n.outgoing))
+            .collect::<Vec<_>>(),
+        [(3, true), (1, true)],
+        "order follows the edge lists, newest first"
+    );
+    assert!(graph_query::neighbors(state, 9).is_none());
+
+    let (nodes, edges) = graph_query::counts_by_kind(state);
+    assert_eq!((nodes["Document"], nodes["Chunk"]), (1, 4));
+    assert_eq!(edges["Mentions"], 3);
+
+    let path = graph_query::shortest_path(state, 0, 3, 6).unwrap();
+    let links: Vec<_> = path.iter().map(|h| (h.from, h.to)).collect();
+    assert_eq!(
+        links,
+        [(0, 1), (2, 1), (2, 3)],
+        "edges are walked either way"
+    );
+    assert!(graph_query::shortest_path(state, 0, 3, 2).is_none());
+    assert!(graph_query::shortest_path(state, 0, 4, 6).is_none());
+    assert_eq!(graph_query::shortest_path(state, 4, 4, 6), Some(vec![]));
+
+    // At the end of the log the 2 → 3 edge is gone.
+    let mut engine = ForensicEngine::from_snapshot(paths.snapshot.to_str().unwrap()).unwrap();
+    engine
+        .replay_to(paths.log.to_str().unwrap(), u64::MAX)
+        .unwrap();
+    assert!(graph_query::shortest_path(engine.kernel_state(), 0, 3, 6).is_none());
+
+    let graph = GraphQuery {
+        neighbors: Some(1),
+        kinds: true,
+        path: Some((0, 2)),
+        ..GraphQuery::default()
+    };
+    assert!(replay_query::run(
+        paths.snapshot.to_str().unwrap(),
+        paths.log.to_str().unwrap(),
+        u64::MAX,
+        None,
+        5,
+        &EventFilter::default(),
+        &graph,
+    )
+    .is_ok());
+}
+
+/// Write `tags.len()` inserts (record id = position, tag from `tags`).
+fn write_tagged_log(path: &Path, tags: &[u64]) {
+    use valori_kernel::event::KernelEvent;
+    use valori_kernel::types::id::RecordId;
+    use valori_kernel::types::vector::FxpVector;
+    use valori_node::events::event_log::{EventLogWriter, LogEntry};
+
+    let mut writer = EventLogWriter::open(path, Some(4)).unwrap();
+    for (i, &tag) in tags.iter().enumerate() {
+        writer
+            .append(&LogEntry::Event(KernelEvent::InsertRecord {
+                id: RecordId(i as u32),
+                vector: FxpVector::new_zeros(4),
+                metadata: None,
+                tag,
+            }))
+            .unwrap();
+    }
+}
+
+#[test]
+fn test_repair_log_truncates_torn_tail_and_refuses_corruption() {
+    let dir = tempdir().unwrap();
+    let log = dir.path().join("events.log");
+    write_tagged_log(&log, &[1, 2, 3]);
+    let clean = std::fs::read(&log).unwrap();
+    let torn = [clean.as_slice(), &[0x40, 0, 0]].concat();
+    std::fs::write(&log, &torn).unwrap();
+    let log_str = log.to_str().unwrap();
+
+    repair_log::run(log_str, true).unwrap();
+    assert_eq!(std::fs::read(&log).unwrap(), torn, "dry run must not write");
+    repair_log::run(log_str, false).unwrap();
+    assert_eq!(std::fs::read(&log).unwrap(), clean);
+    repair_log::run(log_str, false).unwrap();
+
+    // Damage before the end is not a torn tail.
+    let mut corrupt = clean.clone();
+    let mid = corrupt.len() - 20;
+    corrupt[mid] ^= 0xff;
+    std::fs::write(&log, &corrupt).unwrap();
+    assert!(repair_log::run(log_str, false).is_err());
+    assert_eq!(std::fs::read(&log).unwrap(), corrupt);
+}
+
+#[test]
+fn test_bisect_reports_first_divergent_event() {
+    let dir = tempdir().unwrap();
+    let (a, b, c) = (
+        dir.path().join("a.log"),
+        dir.path().join("b.log"),
+        dir.path().join("c.log"),
+    );
+    write_tagged_log(&a, &[1, 2, 3, 4]);
+    write_tagged_log(&b, &[1, 2, 9, 4]);
+    write_tagged_log(&c, &[1, 2, 3, 4]);
+    fn side(log: &Path) -> bisect::Side<'_> {
+        bisect::Side {
+            snapshot: None,
+            log: log.to_str().unwrap(),
+        }
+    }
+
+    let same = bisect::bisect(side(&a), side(&c)).unwrap();
+    assert_eq!(same.divergence, None);
+    assert_eq!(same.agreed_steps, 4);
+
+    let report = bisect::bisect(side(&a), side(&b)).unwrap();
+    assert_eq!(report.agreed_steps, 2);
+    let d = report.divergence.expect("logs diverge");
+    assert_eq!(d.step, 3);
+    let (ea, eb) = (d.event_a.unwrap(), d.event_b.unwrap());
+    assert_eq!((ea.height, eb.height), (3, 3));
+    assert_eq!(ea.event_type, "InsertRecord");
+    assert!(eb.detail.contains("tag: 9"), "{}", eb.detail);
+    assert_ne!(d.hash_a, d.hash_b);
+    assert!(d.last_agreed_hash.is_some());
+
+    // A shorter log diverges at the first event it lacks.
+    let short = dir.path().join("short.log");
+    write_tagged_log(&short, &[1, 2]);
+    let d = bisect::bisect(side(&a), side(&short))
+        .unwrap()
+        .divergence
+        .unwrap();
+    assert_eq!(d.step, 3);
+    assert!(d.event_b.is_none());
+
+    // Different baselines diverge before any replay.
+    let paths = build_test_db(dir.path()).unwrap();
+    let with_snap = bisect::Side {
+        snapshot: Some(paths.snapshot.to_str().unwrap()),
+        log: paths.log.to_str().unwrap(),
+    };
+    let d = bisect::bisect(with_snap, side(&a))
+        .unwrap()
+        .divergence
+        .unwrap();
+    assert_eq!(d.step, 0);
+    assert!(bisect::run(side(&a), side(&b), true).is_ok());
+}
+
 #[test]
 fn test_forensic_engine_state_changes_after_replay() {
     let dir = tempdir().unwrap();
@@ -0,0 +1,215 @@
+// Copyright (c) 2025 Varshith Gudur. Dual-licensed under MIT OR Apache-2.0.
+//! Async client for a Valori node's HTTP API.
+//!
+//! Requests and responses are the node's own [`valori_node::api`] types,
+//! re-exported here as [`api`], so a field added on the server is a field
+//! on the client at the next build.
+//!
+//! ```no_run
+//! # async fn demo() -> Result<(), valori_client::ClientError> {
+//! use valori_client::{api::SearchRequest, ValoriClient};
+//!
+//! let client = ValoriClient::new("http://127.0.0.1:3000").with_token("secret");
+//! let hits = client.search(&SearchRequest::new(vec![0.1; 384], 5)).await?;
+//! let proof = client.state_proof().await?;
+//! # Ok(()) }
+//! ```
+
+use reqwest::{Method, RequestBuilder, StatusCode};
+use serde::{de::DeserializeOwned, Serialize};
+
+pub use valori_node::api;
+use valori_node::api::*;
+
+#[derive(Debug, thiserror::Error)]
+pub enum ClientError {
+    /// Connection, TLS, timeout or body-decoding failure.
+    #[error("request failed: {0}")]
+    Http(#[from] reqwest::Error),
+    /// The node answered with a non-2xx status. `message` is the `error`
+    /// field of its JSON body, or the raw body when there is none.
+    #[error("node returned {status}: {message}")]
+    Status { status: StatusCode, message: String },
+}
+
+pub type Result<T> = std::result::Result<T, ClientError>;
+
+#[derive(Debug, Clone)]
+pub struct ValoriClient {
+    base_url: String,
+    http: reqwest::Client,
+    /// Bearer token sent on every request.
+    token: Option<String>,
+}
+
+impl ValoriClient {
+    /// Client for the node at `base_url` (e.g. `http://127.0.0.1:3000`).
+    pub fn new(base_url: impl Into<String>) -> Self {
+        let base_url = base_url.into().trim_end_matches('/').to_string();
+        Self {
+            base_url,
+            http: reqwest::Client::new(),
+            token: None,
+        }
+    }
+
+    /// Authenticate every request with `token` (`VALORI_AUTH_TOKEN` or an
+    /// API key).
+    pub fn with_token(mut self, token: impl Into<String>) -> Self {
+        self.token = Some(token.into());
+        self
+    }
+
+    /// Send requests through `http` — for custom timeouts, proxies or TLS
+    /// roots.
+    pub fn with_http_client(mut self, http: reqwest::Client) -> Self {
+        self.http = http;
+        self
+    }
+
+    pub fn base_url(&self) -> &str {
+        &self.base_url
+    }
+
+    // ── Records ──────────────────────────────────────────────────────────
+
+    /// `POST /v1/records` — insert one vector and return its id and receipt.
+    pub async fn insert(&self, req: &InsertRecordRequest) -> Result<InsertRecordResponse> {
+        self.post("/v1/records", req).await
+    }
+
+    /// `POST /v1/vectors/batch-insert` — insert many vectors in one commit.
+    pub async fn insert_batch(&self, req: &BatchInsertRequest) -> Result<BatchInsertResponse> {
+        self.post("/v1/vectors/batch-insert", req).await
+    }
+
+    /// `POST /v1/delete`
+    pub async fn delete(&self, req: &DeleteRecordRequest) -> Result<DeleteRecordResponse> {
+        self.post("/v1/delete", req).await
+    }
+
+    /// `POST /v1/soft-delete` — tombstone the record; it stays in the audit
+    /// chain but no longer matches searches.
+    pub async fn soft_delete(&self, req: &DeleteRecordRequest) -> Result<DeleteRecordResponse> {
+        self.post("/v1/soft-delete", req).await
+    }
+
+    /// `POST /v1/records/delete_batch` — delete many ids as one committed
+    /// batch. Ids that are not a live record come back in `missing`.
+    pub async fn delete_batch(&self, req: &DeleteBatchRequest) -> Result<DeleteBatchResponse> {
+        self.post("/v1/records/delete_batch", req).await
+    }
+
+    /// `POST /v1/search` — start from [`SearchRequest::new`] and set the
+    /// fields you need.
+    pub async fn search(&self, req: &SearchRequest) -> Result<SearchResponse> {
+        self.post("/v1/search", req).await
+    }
+
+    // ── Graph ────────────────────────────────────────────────────────────
+
+    /// `POST /v1/graph/node`
+    pub async fn create_node(&self, req: &CreateNodeRequest) -> Result<CreateNodeResponse> {
+        self.post("/v1/graph/node", req).await
+    }
+
+    /// `POST /v1/graph/edge`
+    pub async fn create_edge(&self, req: &CreateEdgeRequest) -> Result<CreateEdgeResponse> {
+        self.post("/v1/graph/edge", req).await
+    }
+
+    /// `GET /v1/graph/node/:id`
+    pub async fn get_node(&self, id: u32, collection: Option<&str>) -> Result<GetNodeResponse> {
+        let req = self.request(Method::GET, &format!("/v1/graph/node/{id}"));
+        Self::send(with_collection(req, collection)).await
+    }
+
+    /// `GET /v1/graph/edges/:id` — outgoing edges of node `id`.
+    pub async fn get_edges(&self, id: u32, collection: Option<&str>) -> Result<GetEdgesResponse> {
+        let req = self.request(Method::GET, &format!("/v1/graph/edges/{id}"));
+        Self::send(with_collection(req, collection)).await
+    }
+
+    // ── Snapshots ────────────────────────────────────────────────────────
+
+    /// `POST /v1/snapshot/save` — to a path or, with `label`, into the catalog.
+    pub async fn snapshot_save(&self, req: &SnapshotSaveRequest) -> Result<SnapshotSaveResponse> {
+        self.post("/v1/snapshot/save", req).await
+    }
+
+    /// `POST /v1/snapshot/restore` — from a path or a catalog id.
+    pub async fn snapshot_restore(
+        &self,
+        req: &SnapshotRestoreRequest,
+    ) -> Result<SnapshotRestoreResponse> {
+        self.post("/v1/snapshot/restore", req).await
+    }
+
+    /// `GET /v1/snapshot/list` — the snapshot catalog.
+    pub async fn snapshot_list(&self) -> Result<SnapshotListResponse> {
+        self.get("/v1/snapshot/list").await
+    }
+
+    // ── Replication and proofs ───────────────────────────────────────────
+
+    /// `GET /v1/replication/state` — this node's follower status.
+    pub async fn replication_state(&self) -> Result<ReplicationStateResponse> {
+        self.get("/v1/replication/state").await
+    }
+
+    /// `GET /v1/proof/state` — the current BLAKE3 state hash.
+    pub async fn state_proof(&self) -> Result<StateProofResponse> {
+        self.get("/v1/proof/state").await
+    }
+
+    /// `GET /v1/proof/event-log` — state hash plus event-log hash and
+    /// height, for offline verification with `valori verify`.
+    pub async fn event_log_proof(&self) -> Result<EventProofResponse> {
+        self.get("/v1/proof/event-log").await
+    }
+
+    /// `GET /health` — the node's health report. A full pool or detected
+    /// drift comes back as [`ClientError::Status`] with status 503.
+    pub async fn health(&self) -> Result<serde_json::Value> {
+        self.get("/health").await
+    }
+
+    fn request(&self, method: Method, path: &str) -> RequestBuilder {
+        let req = self
+            .http
+            .request(method, format!("{}{}", self.base_url, path));
+        match &self.token {
+            Some(t) => req.bearer_auth(t),
+            None => req,
+        }
+    }
+
+    async fn get<T: DeserializeOwned>(&self, path: &str) -> Result<T> {
+        Self::send(self.request(Method::GET, path)).await
+    }
+
+    async fn post<B: Serialize, T: DeserializeOwned>(&self, path: &str, body: &B) -> Result<T> {
+        Self::send(self.request(Method::POST, path).json(body)).await
+    }
+
+    async fn send<T: DeserializeOwned>(req: RequestBuilder) -> Result<T> {
+        let resp = req.send().await?;
+        let status = resp.status();
+        if status.is_success() {
+            return Ok(resp.json().await?);
+        }
+        let body = resp.text().await.unwrap_or_default();
+        let message = serde_json::from_str::<serde_json::Value>(&body)
+            .ok()
+            .and_then(|v| v.get("error")?.as_str().map(str::to_string))
+            .unwrap_or(body);
+        Err(ClientError::Status { status, message })
+    }
+}
+
+fn with_collection(req: RequestBuilder, collection: Option<&str>) -> RequestBuilder {
+    match collection {
+        Some(c) => req.query(&[("collection", c)]),
+        None => req,
+    }
+}
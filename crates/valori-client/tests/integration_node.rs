@@ -0,0 +1,210 @@
+// Copyright (c) 2025 Varshith Gudur. Dual-licensed under MIT OR Apache-2.0.
+//! Drive the client against a real in-process valori-node router, so every
+//! request/response type is proven to round-trip through the live handlers.
+
+use std::sync::Arc;
+use tokio::sync::RwLock;
+
+use valori_client::api::*;
+use valori_client::{ClientError, ValoriClient};
+use valori_node::config::NodeConfig;
+use valori_node::engine::Engine;
+use valori_node::server::build_router;
+use valori_node::EngineFromNodeConfig;
+
+const DIM: usize = 8;
+
+/// Boot a node with an event log (so the event-log proof is available) and
+/// a snapshot path (so the catalog is), and return its base URL.
+async fn spawn_node() -> String {
+    let dir = tempfile::tempdir().unwrap().keep();
+
+    let mut cfg = NodeConfig::default();
+    cfg.dim = DIM;
+    cfg.max_records = 1000;
+    cfg.event_log_path = Some(dir.join("events.log"));
+    cfg.snapshot_path = Some(dir.join("snapshot.bin"));
+    cfg.wal_path = None;
+
+    let router = build_router(Arc::new(RwLock::new(Engine::new(&cfg))), None, None);
+    let listener = tokio::net::TcpListener::bind("127.0.0.1:0").await.unwrap();
+    let addr = listener.local_addr().unwrap();
+    tokio::spawn(async move {
+        axum::serve(listener, router).await.unwrap();
+    });
+    format!("http://{addr}/")
+}
+
+fn vec_n(seed: f32) -> Vec<f32> {
+    (0..DIM).map(|i| seed + i as f32 * 0.01).collect()
+}
+
+fn insert_req(seed: f32) -> InsertRecordRequest {
+    InsertRecordRequest {
+        values: vec_n(seed),
+        collection: None,
+        text: None,
+        if_version: None,
+        tag: None,
+        on_duplicate: None,
+    }
+}
+
+#[tokio::test(flavor = "multi_thread", worker_threads = 2)]
+async fn records_search_and_proofs_round_trip() {
+    let client = ValoriClient::new(spawn_node().await);
+
+    let a = client.insert(&insert_req(0.1)).await.unwrap();
+    assert_eq!(a.receipt.record_id, a.id);
+    let batch = client
+        .insert_batch(&BatchInsertRequest {
+            batch: vec![vec_n(0.5), vec_n(0.9)],
+            collection: None,
+            metadata: None,
+            request_ids: None,
+            texts: None,
+            if_version: None,
+            on_duplicate: None,
+        })
+        .await
+        .unwrap();
+    assert_eq!(batch.ids.len(), 2);
+    assert!(batch.state_version.is_some());
+
+    let hits = client
+        .search(&SearchRequest::new(vec_n(0.1), 2))
+        .await
+        .unwrap();
+    assert_eq!(hits.results.len(), 2);
+    assert_eq!(hits.results[0].id, a.id);
+
+    let deleted = client
+        .delete(&DeleteRecordRequest {
+            id: a.id,
+            collection: None,
+            // Searching does not move the version.
+            if_version: batch.state_version,
+        })
+        .await
+        .unwrap();
+    assert!(deleted.success);
+
+    let state = client.state_proof().await.unwrap();
+    assert_eq!(state.final_state_hash.len(), 64);
+    assert!(state.truncated.is_none());
+    let log = client.event_log_proof().await.unwrap();
+    assert_eq!(log.final_state_hash, state.final_state_hash);
+    assert_eq!(log.committed_height, 4);
+
+    let purged = client
+        .delete_batch(&DeleteBatchRequest {
+            ids: vec![batch.ids[0], batch.ids[1], a.id],
+            collection: None,
+            soft: false,
+            if_version: None,
+        })
+        .await
+        .unwrap();
+    assert_eq!(purged.deleted, batch.ids);
+    assert_eq!(purged.missing, vec![a.id]);
+
+    assert_eq!(
+        client.replication_state().await.unwrap().status,
+        valori_node::replication::replication_display_state()
+    );
+    assert!(client.health().await.unwrap()["status"].is_string());
+}
+
+#[tokio::test(flavor = "multi_thread", worker_threads = 2)]
+async fn graph_calls_round_trip() {
+    let client = ValoriClient::new(spawn_node().await);
+    let rec = client.insert(&insert_req(0.2)).await.unwrap();
+
+    let doc = client
+        .create_node(&CreateNodeRequest {
+            record_id: Some(rec.id),
+            kind: 1,
+            collection: None,
+        })
+        .await
+        .unwrap();
+    let chunk = client
+        .create_node(&CreateNodeRequest {
+            record_id: None,
+            kind: 2,
+            collection: None,
+        })
+        .await
+        .unwrap();
+    client
+        .create_edge(&CreateEdgeRequest {
+            from: doc.node_id,
+            to: chunk.node_id,
+            kind: 0,
+            collection: None,
+        })
+        .await
+        .unwrap();
+
+    let node = client.get_node(doc.node_id, None).await.unwrap();
+    assert_eq!(node.record_id, Some(rec.id));
+    let edges = client
+        .get_edges(doc.node_id, Some("default"))
+        .await
+        .unwrap();
+    assert_eq!(edges.edges.len(), 1);
+    assert_eq!(edges.edges[0].to_node, chunk.node_id);
+}
+
+#[tokio::test(flavor = "multi_thread", worker_threads = 2)]
+async fn snapshot_save_and_restore() {
+    let client = ValoriClient::new(spawn_node().await);
+    client.insert(&insert_req(0.3)).await.unwrap();
+    let before = client.state_proof().await.unwrap().final_state_hash;
+
+    let saved = client
+        .snapshot_save(&SnapshotSaveRequest {
+            path: None,
+            label: Some("before".into()),
+        })
+        .await
+        .unwrap();
+    assert!(saved.success);
+
+    client.insert(&insert_req(0.6)).await.unwrap();
+    let restored = client
+        .snapshot_restore(&SnapshotRestoreRequest {
+            path: None,
+            id: saved.id,
+        })
+        .await
+        .unwrap();
+    assert!(restored.success);
+    let catalog = client.snapshot_list().await.unwrap();
+    assert_eq!(catalog.snapshots.len(), 1);
+    assert_eq!(client.state_proof().await.unwrap().final_state_hash, before);
+}
+
+#[tokio::test(flavor = "multi_thread", worker_threads = 2)]
+async fn node_errors_surface_as_status() {
+    let client = ValoriClient::new(spawn_node().await);
+
+    let err = client
+        .insert(&InsertRecordRequest {
+            values: vec![0.1; DIM + 1],
+            collection: None,
+            text: None,
+            if_version: None,
+            tag: None,
+            on_duplicate: None,
+        })
+        .await
+        .unwrap_err();
+    match err {
+        ClientError::Status { status, message } => {
+            assert_eq!(status, 400);
+            assert!(message.contains("DimensionMismatch"), "{message}");
+        }
+        other => panic!("expected a status error, got {other:?}"),
+    }
+}
@@ -174,6 +174,8 @@ mod tests {
                 text: None,
                 metadata: None,
                 tag: 0,
+                if_version: None,
+                on_duplicate: None,
             },
             request_id: "req-1".into(),
         };
@@ -198,6 +200,8 @@ mod tests {
                 text: None,
                 metadata: None,
                 tag: 0,
+                if_version: None,
+                on_duplicate: None,
             },
             request_id: "req-2".into(),
         };
@@ -319,6 +319,8 @@ mod tests {
             text: None,
             metadata: None,
             tag: 0,
+            if_version: None,
+            on_duplicate: None,
         };
         let v = cap.apply_command(0, 0, &body, "test-req").await.unwrap();
         assert!(v["state_hash"].as_str().unwrap().len() == 64);
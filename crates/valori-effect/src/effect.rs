@@ -59,7 +59,14 @@ pub enum KernelCommandBody {
         values: Vec<f32>,
         text: Option<String>,
         metadata: Option<serde_json::Value>,
-        tag: u8,
+        tag: u64,
+        /// Commit only if the kernel is still at this version.
+        #[serde(default)]
+        if_version: Option<u64>,
+        /// What to do when the namespace already holds this exact vector
+        /// (`reject`, `return_existing` or `link`); unset inserts anyway.
+        #[serde(default, skip_serializing_if = "Option::is_none")]
+        on_duplicate: Option<String>,
     },
     SoftDeleteRecord {
         record_id: u32,
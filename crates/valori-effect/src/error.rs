@@ -17,6 +17,13 @@ pub enum EffectError {
     Duplicate(String),
     #[error("Capacity limit reached: {0}")]
     Capacity(String),
+    /// An `if_version` precondition did not match; nothing was committed.
+    #[error("State version is {current}, not {expected}")]
+    VersionConflict { expected: u64, current: u64 },
+    /// The insert rejects duplicates and record `existing` already has
+    /// the vector; nothing was committed.
+    #[error("Record {existing} already has this vector")]
+    DuplicateVector { existing: u32 },
 }
 
 pub type EffectResult<T> = Result<T, EffectError>;
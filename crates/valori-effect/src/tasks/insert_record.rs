@@ -1,8 +1,8 @@
 // Copyright (c) 2025 Varshith Gudur. Dual-licensed under MIT OR Apache-2.0.
 //! InsertRecordTask — inserts one vector record via KernelCapability.
 //!
-//! Inputs:  `{"namespace_id": 0, "shard_id": 0, "values": [...], "text": null, "metadata": null, "tag": 0, "request_id": null}`
-//! Outputs: `{"record_id": 42, "state_hash_after": "..."}`
+//! Inputs:  `{"namespace_id": 0, "shard_id": 0, "values": [...], "text": null, "metadata": null, "tag": 0, "request_id": null, "if_version": null, "on_duplicate": null}`
+//! Outputs: `{"record_id": 42, "state_hash_after": "...", "state_version": 7, "duplicate_of": null}`
 //! Effects: `KernelWrite(KernelCommand)` — Durable
 //!          `Counter("records_inserted", 1.0)` — Ephemeral
 use crate::effect::{Effect, EffectId, EffectPayload, KernelCommand, KernelCommandBody};
@@ -21,15 +21,25 @@ struct InsertInputs {
     #[serde(default)]
     metadata: Option<serde_json::Value>,
     #[serde(default)]
-    tag: u8,
+    tag: u64,
     #[serde(default)]
     request_id: Option<String>,
+    #[serde(default)]
+    if_version: Option<u64>,
+    #[serde(default)]
+    on_duplicate: Option<String>,
 }
 
 #[derive(Debug, Serialize)]
 struct InsertOutput {
     record_id: u32,
     state_hash_after: String,
+    /// Kernel version after the insert, when the capability reports one.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    state_version: Option<u64>,
+    /// Record with the same vector, when the insert checked for one.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    duplicate_of: Option<u32>,
 }
 
 pub struct InsertRecordTask;
@@ -62,6 +72,8 @@ impl Task for InsertRecordTask {
                 text: inputs.text,
                 metadata: inputs.metadata,
                 tag: inputs.tag,
+                if_version: inputs.if_version,
+                on_duplicate: inputs.on_duplicate,
             },
             request_id,
         };
@@ -97,6 +109,11 @@ impl Task for InsertRecordTask {
         let out = InsertOutput {
             record_id,
             state_hash_after: state_hash.clone(),
+            state_version: result.get("state_version").and_then(|v| v.as_u64()),
+            duplicate_of: result
+                .get("duplicate_of")
+                .and_then(|v| v.as_u64())
+                .map(|v| v as u32),
         };
         Ok(TaskOutput::with_value(
             serde_json::to_value(out).map_err(EffectError::Serde)?,
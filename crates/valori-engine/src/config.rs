@@ -24,11 +24,41 @@ pub enum IndexKind {
     Hnsw,
     Ivf,
     Bq,
+    /// 8-bit scalar-quantized codes in RAM; candidates are rescored exactly
+    /// against the kernel's full-precision vectors.
+    Sq,
     /// Automatically selects the tier based on live record count:
     /// < 10 000 → BruteForce, 10 000–2 000 000 → BQ, > 2 000 000 → HNSW.
     Auto,
 }
 
+impl IndexKind {
+    /// Name used by `VALORI_INDEX`, `/v1/index/config` and per-query index
+    /// selection.
+    pub fn name(self) -> &'static str {
+        match self {
+            IndexKind::BruteForce => "brute_force",
+            IndexKind::Hnsw => "hnsw",
+            IndexKind::Ivf => "ivf",
+            IndexKind::Bq => "bq",
+            IndexKind::Sq => "sq",
+            IndexKind::Auto => "auto",
+        }
+    }
+
+    pub fn from_name(name: &str) -> Option<Self> {
+        match name {
+            "brute_force" | "brute" => Some(IndexKind::BruteForce),
+            "hnsw" => Some(IndexKind::Hnsw),
+            "ivf" => Some(IndexKind::Ivf),
+            "bq" => Some(IndexKind::Bq),
+            "sq" => Some(IndexKind::Sq),
+            "auto" | "mstg" => Some(IndexKind::Auto),
+            _ => None,
+        }
+    }
+}
+
 /// Which quantization scheme to apply to stored vectors.
 #[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
 pub enum QuantizationKind {
@@ -37,6 +67,69 @@ pub enum QuantizationKind {
     Product,
 }
 
+impl QuantizationKind {
+    /// Name used by `VALORI_QUANT` and the collections API.
+    pub fn name(self) -> &'static str {
+        match self {
+            QuantizationKind::None => "none",
+            QuantizationKind::Scalar => "scalar",
+            QuantizationKind::Product => "product",
+        }
+    }
+
+    pub fn from_name(name: &str) -> Option<Self> {
+        match name {
+            "none" => Some(QuantizationKind::None),
+            "scalar" => Some(QuantizationKind::Scalar),
+            "product" => Some(QuantizationKind::Product),
+            _ => None,
+        }
+    }
+}
+
+/// What [`crate::Engine::try_recover`] does when a persistence artifact
+/// (event log, snapshot or WAL) exists but cannot be read or replayed.
+/// Missing artifacts are never an error: with nothing on disk every policy
+/// starts empty.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default, Serialize, Deserialize)]
+pub enum RecoveryPolicy {
+    /// Log the failure and try the next source: event log, then snapshot,
+    /// then legacy WAL, then an empty store.
+    #[default]
+    Fallback,
+    /// Stop at the first failure with [`crate::RecoveryMode::Refused`]; the
+    /// engine stays empty and refuses writes.
+    FailClosed,
+    /// Recover from the event log alone; the snapshot and WAL are never
+    /// read. A log that fails is refused as under `FailClosed`.
+    RecoverFromEventLogOnly,
+    /// On the first failure, move every artifact into `quarantine/<unix
+    /// secs>/` next to it and start empty with a fresh event log.
+    StartEmptyAndQuarantine,
+}
+
+impl RecoveryPolicy {
+    /// Name used by `VALORI_RECOVERY_POLICY`.
+    pub fn name(self) -> &'static str {
+        match self {
+            RecoveryPolicy::Fallback => "fallback",
+            RecoveryPolicy::FailClosed => "fail-closed",
+            RecoveryPolicy::RecoverFromEventLogOnly => "event-log-only",
+            RecoveryPolicy::StartEmptyAndQuarantine => "start-empty-and-quarantine",
+        }
+    }
+
+    pub fn from_name(name: &str) -> Option<Self> {
+        match name.replace('_', "-").as_str() {
+            "fallback" => Some(RecoveryPolicy::Fallback),
+            "fail-closed" => Some(RecoveryPolicy::FailClosed),
+            "event-log-only" => Some(RecoveryPolicy::RecoverFromEventLogOnly),
+            "start-empty-and-quarantine" => Some(RecoveryPolicy::StartEmptyAndQuarantine),
+            _ => None,
+        }
+    }
+}
+
 /// All configuration the [`super::Engine`] needs at construction time.
 ///
 /// `valori-node` builds this from its `NodeConfig` (env vars) and injects
@@ -52,6 +145,10 @@ pub struct EngineConfig {
     // ── Index selection ───────────────────────────────────────────────────────
     pub index_kind: IndexKind,
     pub quantization_kind: QuantizationKind,
+    /// Indexes kept alongside the primary one over the same records and
+    /// selectable per query by [`IndexKind::name`]. `Auto` and duplicates of
+    /// the primary are ignored.
+    pub extra_indexes: Vec<IndexKind>,
 
     // ── HNSW tuning ───────────────────────────────────────────────────────────
     pub hnsw_m: Option<usize>,
@@ -67,10 +164,34 @@ pub struct EngineConfig {
     pub wal_path: Option<PathBuf>,
     pub event_log_path: Option<PathBuf>,
     pub event_log_rotation_bytes: Option<u64>,
+    /// How event-log writes are made durable.
+    pub event_log_sync: valori_storage::durability::SyncStrategy,
+    /// Quarantine a corrupt event-log tail at construction instead of
+    /// refusing the log (see `valori_storage::events::quarantine`).
+    pub quarantine_corrupt_segments: bool,
+    /// Keep record vectors in this memory-mapped file instead of RAM (see
+    /// `valori_kernel::storage::vector_file`). Recreated at construction.
+    pub vector_file_path: Option<PathBuf>,
 
     // ── Feature knobs ─────────────────────────────────────────────────────────
     pub decay_half_life_secs: Option<u64>,
     pub shard_count: usize,
+    /// Neighbours used for insert-time anomaly scoring; `None` = off.
+    pub anomaly_k: Option<usize>,
+    /// Score above which an insert is flagged as an outlier.
+    pub anomaly_threshold: Option<f32>,
+    /// Periodic deletion of low-importance records; `None` = off.
+    pub forget_policy: Option<crate::forget::ForgetPolicy>,
+    /// What recovery does when the snapshot it falls back to fails
+    /// [`crate::snapshot_check::check_snapshot`].
+    pub snapshot_check: crate::snapshot_check::SnapshotCheckPolicy,
+    /// What recovery does with an artifact that fails to load.
+    pub recovery_policy: RecoveryPolicy,
+    /// Size and content rules for record metadata, checked before commit.
+    pub metadata_policy: crate::metadata_policy::MetadataPolicy,
+    /// Top-level metadata fields kept in a
+    /// [`MetadataIndex`](crate::MetadataIndex); empty = no index.
+    pub metadata_index_fields: Vec<String>,
 
     // ── Object store ──────────────────────────────────────────────────────────
     pub object_store_keep: u32,
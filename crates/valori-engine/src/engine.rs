@@ -11,25 +11,34 @@
 //! the `EngineFromNodeConfig` extension trait so that tests and `main.rs` can
 //! still call `Engine::new(&node_config)` after importing the trait.
 
-use std::collections::HashMap;
+use std::collections::{BTreeMap, HashMap};
 use std::path::{Path, PathBuf};
 use std::sync::Arc;
 
+use valori_kernel::config::MAX_SPARSE_NNZ;
 use valori_kernel::error::KernelError;
 use valori_kernel::fxp::qformat::SCALE;
+use valori_kernel::index::TagFilter;
+use valori_kernel::math::l2::fxp_l2_sq_slice;
+use valori_kernel::snapshot::container::{Checksum, Container, ContainerWriter, Section};
 use valori_kernel::snapshot::decode::decode_state;
 use valori_kernel::snapshot::encode::encode_state;
 use valori_kernel::state::kernel::KernelState;
+use valori_kernel::storage::vector_file::VectorFile;
 use valori_kernel::types::enums::{EdgeKind, NodeKind};
 use valori_kernel::types::id::RecordId;
 use valori_kernel::types::scalar::FxpScalar;
+use valori_kernel::types::sparse::SparseVector;
 use valori_kernel::types::vector::FxpVector;
 
 use valori_index::{BruteForceIndex, NoQuantizer, Quantizer, ScalarQuantizer, VectorIndex};
 use valori_metadata::CollectionRegistry;
+use valori_storage::durability::SyncStrategy;
 use valori_storage::events::event_commit::EventCommitter;
 use valori_storage::events::event_journal::EventJournal;
 use valori_storage::events::event_log::EventLogWriter;
+use valori_storage::events::quarantine::{load_damage_report, quarantine_corrupt_segments};
+use valori_storage::events::{DamageReport, RecoveryProgress, Rotation};
 
 use crate::config::{EngineConfig, IndexKind, QuantizationKind};
 use crate::error::EngineError;
@@ -40,6 +49,9 @@ use crate::persistence::Persistence;
 const AUTO_TIER_BQ_MIN: usize = 10_000;
 const AUTO_TIER_HNSW_MIN: usize = 2_000_000;
 
+/// Longest timeline annotation label, in characters.
+pub const MAX_ANNOTATION_LEN: usize = 256;
+
 // ── Support types ─────────────────────────────────────────────────────────────
 
 /// Utilisation stats for a single bounded pool (records, nodes, or edges).
@@ -57,6 +69,8 @@ pub struct PoolStats {
 /// * `"ok"`       → 200, route freely
 /// * `"degraded"` → 200, any pool ≥ 90 % full; still serves all operations
 /// * `"full"`     → 503, at least one pool at 100 %
+/// * `"read_only"` → 503, recovery was refused or the startup snapshot
+///   failed its self-check, and writes are refused
 #[derive(Debug, serde::Serialize)]
 pub struct EngineHealth {
     pub status: &'static str,
@@ -77,10 +91,73 @@ pub struct EngineHealth {
     #[serde(skip_serializing_if = "Option::is_none")]
     pub embed_provider: Option<String>,
     pub shard_count: usize,
+    /// False once the consistency sentinel has detected drift.
+    pub consistent: bool,
+    /// True between [`Engine::begin_bulk_load`] and [`Engine::finish_bulk_load`].
+    pub bulk_loading: bool,
+    /// Self-check of the snapshot recovery started from, if it did.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub snapshot_check: Option<crate::snapshot_check::SnapshotCheck>,
+    /// Why recovery was refused under the recovery policy, if it was.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub recovery_refused: Option<String>,
+    /// Leader epoch this node last led or followed; 0 before the first.
+    pub leader_epoch: u64,
+    /// Why this node stopped taking writes after a newer epoch appeared.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub fenced: Option<String>,
+    /// Primary region this node replicates from until it is promoted.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub secondary_of: Option<String>,
+    /// Kernel version: one more per applied event. Writes take it back as
+    /// `if_version`.
+    pub state_version: u64,
+}
+
+/// A file on disk and its size.
+#[derive(Debug, Clone, serde::Serialize)]
+pub struct FileUsage {
+    pub path: String,
+    pub bytes: u64,
+}
+
+/// Event-log footprint: the live segment plus the sealed archives next to it.
+#[derive(Debug, Clone, serde::Serialize)]
+pub struct EventLogUsage {
+    pub path: String,
+    pub height: u64,
+    pub live_bytes: u64,
+    pub archived_segments: usize,
+    pub archived_bytes: u64,
+    pub total_bytes: u64,
+}
+
+/// Metadata store size: `bytes` is its serialized (snapshot / sidecar) size.
+#[derive(Debug, Clone, serde::Serialize)]
+pub struct MetadataUsage {
+    pub entries: usize,
+    pub bytes: u64,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub file: Option<FileUsage>,
+}
+
+/// Result of [`Engine::storage_stats`] (`GET /v1/stats/storage`).
+#[derive(Debug, serde::Serialize)]
+pub struct StorageStats {
+    pub records: PoolStats,
+    pub nodes: PoolStats,
+    pub edges: PoolStats,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub event_log: Option<EventLogUsage>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub snapshot: Option<FileUsage>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub wal: Option<FileUsage>,
+    pub metadata: MetadataUsage,
 }
 
 /// Result of [`Engine::try_recover`].
-#[derive(Debug, Clone, PartialEq, Eq)]
+#[derive(Debug, Clone, PartialEq, Eq, serde::Serialize)]
 pub enum RecoveryMode {
     EventLog(u64),
     Snapshot,
@@ -91,6 +168,189 @@ pub enum RecoveryMode {
     /// WAL are an either/or fallback, not layered.
     Wal(usize),
     Fresh,
+    /// A source failed under `RecoveryPolicy::FailClosed` or
+    /// `RecoverFromEventLogOnly`. The store is empty and refuses writes.
+    Refused(String),
+    /// A source failed under `RecoveryPolicy::StartEmptyAndQuarantine`:
+    /// every artifact was moved into this directory and the store started
+    /// empty.
+    Quarantined(PathBuf),
+}
+
+/// Result of [`Engine::verify_recovery`]: what a real recovery would load.
+#[derive(Debug, Clone, PartialEq, Eq, serde::Serialize)]
+pub struct RecoveryVerification {
+    pub mode: RecoveryMode,
+    /// BLAKE3 hash of the recovered kernel state, lowercase hex.
+    pub state_hash: String,
+    pub records: usize,
+    pub nodes: usize,
+    pub edges: usize,
+    /// Everything a real recovery would have logged as an error or skipped
+    /// over, plus consistency-check violations. Empty means clean.
+    pub anomalies: Vec<String>,
+}
+
+/// Result of [`Engine::check_consistency`] (`GET /v1/admin/check`).
+#[derive(Debug, Clone, Default, PartialEq, Eq, serde::Serialize, serde::Deserialize)]
+pub struct ConsistencyReport {
+    /// True when every list below is empty and the committer agrees.
+    pub ok: bool,
+    /// Kernel graph-pool invariant violations.
+    pub kernel: Vec<String>,
+    /// Searchable records missing from the vector index.
+    pub index_missing: Vec<u32>,
+    /// Indexed ids with no searchable record behind them.
+    pub index_stale: Vec<u32>,
+    /// `record_to_node` entries that disagree with the node pool.
+    pub record_to_node: Vec<String>,
+    /// The event committer's live state hashes differently from the engine's.
+    pub committer_diverged: bool,
+}
+
+/// Result of [`Engine::vacuum`] (`POST /v1/admin/vacuum`).
+#[derive(Debug, Clone, Default, PartialEq, Eq, serde::Serialize)]
+pub struct VacuumReport {
+    /// Record slots given back to the pool.
+    pub reclaimed: usize,
+    /// `(old, new)` id of every record that moved, in old-id order.
+    pub moves: Vec<(u32, u32)>,
+}
+
+/// Result of [`Engine::delete_batch_ns`] (`POST /v1/records/delete_batch`).
+#[derive(Debug, Clone, Default, PartialEq, Eq, serde::Serialize)]
+pub struct BatchDeleteReport {
+    /// Records deleted, in request order.
+    pub deleted: Vec<u32>,
+    /// Requested ids that were not an active record of the collection.
+    pub missing: Vec<u32>,
+}
+
+/// One operation of an [`Engine::commit_transaction_ns`] transaction.
+#[derive(Debug, Clone, PartialEq)]
+pub enum TxOp {
+    Insert {
+        values: Vec<f32>,
+        metadata: Option<Vec<u8>>,
+        tag: u64,
+    },
+    CreateNode {
+        kind: u8,
+        record: Option<TxRef>,
+    },
+    CreateEdge {
+        from: TxRef,
+        to: TxRef,
+        kind: u8,
+    },
+    /// Compare-and-swap when `expected_version` is set, as
+    /// [`Engine::update_record_metadata`].
+    UpdateMetadata {
+        id: TxRef,
+        metadata: Option<Vec<u8>>,
+        expected_version: Option<u64>,
+    },
+    /// Deletes the record's graph node with it, as [`Engine::delete_record`].
+    DeleteRecord {
+        id: TxRef,
+        soft: bool,
+    },
+    DeleteNode {
+        id: TxRef,
+    },
+    DeleteEdge {
+        id: TxRef,
+    },
+    /// Audited metadata sidecar entry, as [`Engine::set_meta_audited`].
+    SetMeta {
+        key: String,
+        value: serde_json::Value,
+    },
+}
+
+/// A record, node or edge id inside a transaction: one that already exists,
+/// or the one created by an earlier operation of the same transaction.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum TxRef {
+    Id(u32),
+    /// Index of the creating operation.
+    Op(usize),
+}
+
+/// What an insert does when its namespace already holds an active record
+/// with exactly the same (fixed-point) vector.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default, serde::Serialize, serde::Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum OnDuplicate {
+    /// Insert without checking.
+    #[default]
+    Allow,
+    /// Refuse with [`EngineError::DuplicateVector`]; nothing is written.
+    Reject,
+    /// Write nothing and answer with the existing record's id.
+    ReturnExisting,
+    /// Insert, and commit a `RefersTo` edge from the new record's graph
+    /// node to the existing record's in the same batch.
+    Link,
+}
+
+/// One item of [`Engine::insert_record_dedup_ns`] or
+/// [`Engine::insert_batch_dedup_ns`].
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct DedupInsert {
+    /// The record written, or the existing one under
+    /// [`OnDuplicate::ReturnExisting`].
+    pub id: u32,
+    /// The record whose vector this item repeats.
+    pub duplicate_of: Option<u32>,
+}
+
+impl DedupInsert {
+    /// Whether the item wrote a record of its own.
+    pub fn inserted(&self) -> bool {
+        self.duplicate_of != Some(self.id)
+    }
+}
+
+/// Earlier copy of a batch item's vector.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+enum Earlier {
+    Stored(u32),
+    /// Index of an earlier item of the same batch.
+    Item(usize),
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum TxObject {
+    Record,
+    Node,
+    Edge,
+}
+
+impl TxObject {
+    fn name(self) -> &'static str {
+        match self {
+            TxObject::Record => "record",
+            TxObject::Node => "node",
+            TxObject::Edge => "edge",
+        }
+    }
+}
+
+/// Result of [`Engine::estimate_recall`].
+#[derive(Debug, Clone, PartialEq, serde::Serialize)]
+pub struct RecallReport {
+    /// Index measured, under the name [`Engine::search_l2_index`] takes.
+    pub index: String,
+    pub k: usize,
+    /// Records used as queries.
+    pub sampled: usize,
+    /// Mean recall@k over the sample; 1.0 when nothing was sampled.
+    pub recall: f64,
+    /// Recall of the worst single query.
+    pub min_recall: f64,
+    /// Queries that missed at least one true neighbour.
+    pub imperfect: usize,
 }
 
 /// Application-layer caches that sit above the database layer.
@@ -108,6 +368,16 @@ impl ExecutionResources {
     }
 }
 
+/// Search-hit statistics for one record. Host-side only: not logged and not
+/// part of the state hash, but carried in snapshots (`ACCS` section).
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, serde::Serialize, serde::Deserialize)]
+pub struct RecordAccess {
+    /// Times the record appeared in a search result.
+    pub hits: u64,
+    /// Unix seconds of the most recent hit (0 = never).
+    pub last_hit_unix: u64,
+}
+
 // ── Engine ────────────────────────────────────────────────────────────────────
 
 /// The Node Engine orchestrates state, persistence, and indexing.
@@ -115,6 +385,9 @@ pub struct Engine {
     pub state: KernelState,
     pub metadata: MetadataStore,
     pub index: Box<dyn VectorIndex + Send + Sync>,
+    /// Extra indexes over the same records, keyed by [`IndexKind::name`] and
+    /// chosen per query (see [`Engine::search_l2_index`]).
+    pub named_indexes: BTreeMap<String, Box<dyn VectorIndex + Send + Sync>>,
     pub quant: Box<dyn Quantizer + Send + Sync>,
 
     pub index_kind: IndexKind,
@@ -122,6 +395,9 @@ pub struct Engine {
     pub quantization_kind: QuantizationKind,
     pub wal_path: Option<PathBuf>,
     pub snapshot_path: Option<PathBuf>,
+    pub event_log_path: Option<PathBuf>,
+    /// How every event log this engine opens makes its writes durable.
+    pub event_log_sync: SyncStrategy,
 
     pub max_records: usize,
     pub max_nodes: usize,
@@ -152,6 +428,65 @@ pub struct Engine {
     pub embed_config: Option<valori_ingest::EmbedConfig>,
     pub resources: ExecutionResources,
     pub shard_count: usize,
+    /// Set by the node's consistency sentinel when its last check found drift.
+    pub consistency_drift: bool,
+    pub anomaly_k: Option<usize>,
+    pub anomaly_threshold: Option<f32>,
+    /// Insert-time anomaly score per live record (see [`Engine::record_anomaly`]).
+    pub anomaly_scores: HashMap<u32, f32>,
+    pub forget_policy: Option<crate::forget::ForgetPolicy>,
+    /// Height the forgetting policy last ran at (or first saw).
+    pub forget_last_height: Option<u64>,
+    /// Search hits per live record since its insert. Behind a mutex because
+    /// searches run under the shared read lock.
+    pub access_stats: std::sync::Mutex<HashMap<u32, RecordAccess>>,
+    /// `(state root, canonical hash)` of the last proof, so an unchanged
+    /// state is not re-hashed on every `/v1/proof/state` poll.
+    proof_cache: std::sync::Mutex<Option<([u8; 32], [u8; 32])>>,
+    /// Progress of the running (or last) [`Engine::try_recover`]. Shared so
+    /// readiness probes can read it without the engine lock.
+    pub recovery_progress: Arc<RecoveryProgress>,
+    /// Set when part of the event log was quarantined: the state ends at the
+    /// report's `recovered_height`, and proofs say so.
+    pub damage: Option<DamageReport>,
+    /// Set by [`Engine::shutdown_checkpoint`]; every later write is refused
+    /// so nothing lands behind the final checkpoint.
+    pub shutting_down: bool,
+    /// Set by [`Engine::begin_bulk_load`]: inserts are committed and stored
+    /// but skip the ANN indexes until [`Engine::finish_bulk_load`] builds
+    /// them in one pass.
+    pub bulk_loading: bool,
+    pub snapshot_check_policy: crate::snapshot_check::SnapshotCheckPolicy,
+    /// Result of the startup snapshot self-check; a failure under
+    /// `RefuseWrites` blocks every write.
+    pub snapshot_check: Option<crate::snapshot_check::SnapshotCheck>,
+    pub recovery_policy: crate::config::RecoveryPolicy,
+    /// Size and content rules every record's metadata must pass before
+    /// its event is logged.
+    pub metadata_policy: crate::metadata_policy::MetadataPolicy,
+    /// Why recovery was refused (see [`RecoveryMode::Refused`]); every
+    /// write is refused while set.
+    pub recovery_refused: Option<String>,
+    /// Current leader epoch, loaded from `epoch_path` at startup.
+    pub leader_epoch: crate::epoch::LeaderEpoch,
+    /// `events.epoch.json` next to the event log, if there is one.
+    pub epoch_path: Option<PathBuf>,
+    /// Set when a newer leader epoch superseded this node or its log forked
+    /// from the leader's; every write is refused while set.
+    pub fenced: Option<String>,
+    /// Primary region URL while this node is an asynchronous secondary
+    /// region; writes are refused until it is promoted. Unlike `fenced`,
+    /// the node still reports itself healthy.
+    pub secondary_of: Option<String>,
+    /// Node id [`Engine::optimize_index_step`] resumes from.
+    pub index_optimize_cursor: u32,
+    /// Searchable record ids by [`vector_hash`], built on the first
+    /// duplicate check and kept up to date by inserts after that. Deleted
+    /// ids are pruned when looked up.
+    vector_hashes: Option<rustc_hash::FxHashMap<u64, Vec<u32>>>,
+    /// Where record vectors live instead of RAM, when configured. Shared by
+    /// the engine state and the committer's copy.
+    vector_file: Option<VectorFile>,
 }
 
 impl Engine {
@@ -190,19 +525,39 @@ impl Engine {
                 use valori_index::BqIndex;
                 Box::new(BqIndex::new())
             }
+            IndexKind::Sq => Box::new(valori_index::SqIndex::new()),
         }
     }
 
     /// Primary constructor. `valori-node` wraps this via the `EngineFromNodeConfig`
     /// extension trait so existing `Engine::new(&node_config)` call sites compile
     /// unchanged after importing that trait.
+    ///
+    /// # Panics
+    /// If `vector_file_path` is set and the file cannot be created or
+    /// filled. [`Engine::try_with_config`] returns that error instead.
     pub fn with_config(cfg: EngineConfig) -> Self {
+        Self::try_with_config(cfg).unwrap_or_else(|e| panic!("engine construction failed: {e}"))
+    }
+
+    /// [`Engine::with_config`], failing when the configured vector file
+    /// cannot be created or the vectors cannot be moved into it. Every other
+    /// persistence problem is logged and the engine starts without it, as
+    /// before; a vector file that silently fell back to RAM would defeat the
+    /// point of configuring it.
+    pub fn try_with_config(cfg: EngineConfig) -> Result<Self, EngineError> {
         let initial_kind = match cfg.index_kind {
             IndexKind::Auto => IndexKind::BruteForce,
             other => other,
         };
         let index = Self::make_index(initial_kind, &cfg);
         let current_effective_kind = initial_kind;
+        let named_indexes = cfg
+            .extra_indexes
+            .iter()
+            .filter(|&&kind| kind != IndexKind::Auto && kind != cfg.index_kind)
+            .map(|&kind| (kind.name().to_string(), Self::make_index(kind, &cfg)))
+            .collect();
 
         let quant: Box<dyn Quantizer + Send + Sync> = match cfg.quantization_kind {
             QuantizationKind::None => Box::new(NoQuantizer),
@@ -213,8 +568,21 @@ impl Engine {
             }
         };
 
+        // Quarantine runs before the writer opens: a corrupt segment would
+        // otherwise fail the open and leave the engine without a log.
+        let mut damage = cfg.event_log_path.as_deref().and_then(load_damage_report);
+        if let Some(path) = cfg.event_log_path.as_deref() {
+            if cfg.quarantine_corrupt_segments && path.exists() {
+                match quarantine_corrupt_segments(path, cfg.dim as u32) {
+                    Ok(Some(report)) => damage = Some(report),
+                    Ok(None) => {}
+                    Err(e) => tracing::error!("Event-log quarantine scan failed: {}", e),
+                }
+            }
+        }
+
         let persistence = if let Some(ref path) = cfg.event_log_path {
-            match EventLogWriter::open(path, Some(cfg.dim as u32)) {
+            match EventLogWriter::open_with_sync(path, Some(cfg.dim as u32), cfg.event_log_sync) {
                 Ok(log_writer) => {
                     let journal = EventJournal::new();
                     let live_state = KernelState::with_dim(cfg.dim);
@@ -252,13 +620,26 @@ impl Engine {
             .event_log_path
             .as_ref()
             .map(|p| p.with_extension("metadata.json"));
+        let epoch_path = cfg.event_log_path.as_deref().map(crate::epoch::epoch_path);
+        let leader_epoch = epoch_path
+            .as_deref()
+            .and_then(crate::epoch::load_epoch)
+            .unwrap_or_default();
         let namespaces_path = cfg
             .event_log_path
             .as_ref()
             .or(cfg.snapshot_path.as_ref())
             .map(|p| p.with_extension("namespaces.json"));
 
+        let vector_file = cfg
+            .vector_file_path
+            .as_deref()
+            .map(VectorFile::create)
+            .transpose()
+            .map_err(EngineError::Kernel)?;
+
         let mut kernel_state = KernelState::with_dim(cfg.dim);
+        kernel_state.track_state_root();
         match initial_kind {
             IndexKind::Bq => {
                 use valori_kernel::index::IndexVariant;
@@ -300,16 +681,19 @@ impl Engine {
             }
         };
 
-        Self {
+        let mut engine = Self {
             state: kernel_state,
-            metadata: MetadataStore::new(),
+            metadata: MetadataStore::with_indexed_fields(cfg.metadata_index_fields),
             index,
+            named_indexes,
             quant,
             index_kind: cfg.index_kind,
             current_effective_kind,
             quantization_kind: cfg.quantization_kind,
             wal_path: cfg.wal_path,
             snapshot_path: cfg.snapshot_path,
+            event_log_path: cfg.event_log_path,
+            event_log_sync: cfg.event_log_sync,
             max_records: cfg.max_records,
             max_nodes: cfg.max_nodes,
             max_edges: cfg.max_edges,
@@ -331,7 +715,33 @@ impl Engine {
             embed_config: cfg.embed_config,
             resources: ExecutionResources::new(),
             shard_count: cfg.shard_count,
-        }
+            consistency_drift: false,
+            anomaly_k: cfg.anomaly_k,
+            anomaly_threshold: cfg.anomaly_threshold,
+            anomaly_scores: HashMap::new(),
+            forget_policy: cfg.forget_policy,
+            forget_last_height: None,
+            access_stats: std::sync::Mutex::new(HashMap::new()),
+            proof_cache: std::sync::Mutex::new(None),
+            recovery_progress: Arc::default(),
+            damage,
+            shutting_down: false,
+            bulk_loading: false,
+            snapshot_check_policy: cfg.snapshot_check,
+            snapshot_check: None,
+            recovery_policy: cfg.recovery_policy,
+            metadata_policy: cfg.metadata_policy,
+            recovery_refused: None,
+            leader_epoch,
+            epoch_path,
+            fenced: None,
+            secondary_of: None,
+            index_optimize_cursor: 0,
+            vector_hashes: None,
+            vector_file,
+        };
+        engine.move_vectors_to_file()?;
+        Ok(engine)
     }
 
     #[inline]
@@ -348,8 +758,45 @@ impl Engine {
         event: &valori_kernel::event::KernelEvent,
         namespace_id: u16,
     ) -> Result<(), EngineError> {
-        self.persistence.log_event_ns(event, namespace_id)?;
-        self.apply_committed_event_ns(event, namespace_id)
+        self.check_writable()?;
+        self.metadata_policy
+            .check_events(std::slice::from_ref(event))?;
+        tracing::debug_span!("commit", namespace_id)
+            .in_scope(|| self.persistence.log_event_ns(event, namespace_id))?;
+        tracing::debug_span!("apply").in_scope(|| self.ingest_event(event, namespace_id))
+    }
+
+    /// True when recovery was refused, the node is fenced by a newer leader
+    /// epoch, or the startup snapshot failed its self-check under
+    /// [`SnapshotCheckPolicy::RefuseWrites`](crate::SnapshotCheckPolicy).
+    pub fn writes_refused(&self) -> bool {
+        self.recovery_refused.is_some()
+            || self.fenced.is_some()
+            || self.snapshot_check_policy == crate::SnapshotCheckPolicy::RefuseWrites
+                && self.snapshot_check.as_ref().is_some_and(|c| !c.ok)
+    }
+
+    /// The error a write would fail with right now, if any. Streaming
+    /// writers check it before they accept a body.
+    pub fn check_writable(&self) -> Result<(), EngineError> {
+        if self.shutting_down {
+            return Err(EngineError::ShuttingDown);
+        }
+        if let Some(reason) = &self.recovery_refused {
+            return Err(EngineError::RecoveryRefused(reason.clone()));
+        }
+        if let Some(reason) = &self.fenced {
+            return Err(EngineError::Fenced(reason.clone()));
+        }
+        if let Some(primary) = &self.secondary_of {
+            return Err(EngineError::Fenced(format!(
+                "secondary region replicating from {primary}; promote it to take writes"
+            )));
+        }
+        if self.writes_refused() {
+            return Err(EngineError::SnapshotUnverified);
+        }
+        Ok(())
     }
 
     pub fn event_committer(&self) -> Option<&EventCommitter> {
@@ -371,7 +818,57 @@ impl Engine {
         self.created_at.get(&id).copied()
     }
 
+    /// Anomaly score of record `id` and whether it crosses the configured
+    /// threshold. `None` when scoring was off at insert time, the index was
+    /// empty, or the record was inserted before the last restart.
+    pub fn record_anomaly(&self, id: u32) -> Option<(f32, bool)> {
+        let score = *self.anomaly_scores.get(&id)?;
+        let outlier = self.anomaly_threshold.is_some_and(|t| score > t);
+        Some((score, outlier))
+    }
+
+    /// Distance from `vector` to the centroid of its `k` nearest indexed
+    /// neighbours, in float units. Runs before the record itself is indexed.
+    fn anomaly_score(
+        &self,
+        vector: &valori_kernel::types::vector::FxpVector,
+        k: usize,
+    ) -> Option<f32> {
+        let query: Vec<f32> = vector
+            .data
+            .iter()
+            .map(|fxp| fxp.0 as f32 / SCALE as f32)
+            .collect();
+        let mut sums = vec![0i128; vector.len()];
+        let mut n = 0i128;
+        for (id, _) in Self::index_search(&self.state, self.index.as_ref(), &query, k, &|| false)? {
+            let Some(rec) = self.state.get_record(RecordId(id)) else {
+                continue;
+            };
+            for (sum, s) in sums.iter_mut().zip(self.state.record_vector(rec)) {
+                *sum += s.0 as i128;
+            }
+            n += 1;
+        }
+        if n == 0 {
+            return None;
+        }
+        // Truncating Q16.16 mean, as in kernel k-means.
+        let sq: i128 = sums
+            .iter()
+            .zip(vector.as_slice())
+            .map(|(sum, s)| {
+                let d = s.0 as i128 - sum / n;
+                d * d
+            })
+            .sum();
+        Some(((sq as f64).sqrt() / SCALE as f64) as f32)
+    }
+
     fn rebuild_record_to_node(&mut self) {
+        // Every state swap comes through here; the duplicate-check hashes
+        // are rebuilt from the new state on their next use.
+        self.vector_hashes = None;
         self.record_to_node.clear();
         for node in self.state.iter_nodes() {
             if let Some(rid) = node.record {
@@ -380,6 +877,54 @@ impl Engine {
         }
     }
 
+    /// Point the engine state and the committer's copy at the vector file,
+    /// moving the vectors they hold in RAM into it. Both apply the same
+    /// events, so each vector is written to the file once. Called after
+    /// every state swap.
+    fn move_vectors_to_file(&mut self) -> Result<(), EngineError> {
+        let Some(file) = self.vector_file.clone() else {
+            return Ok(());
+        };
+        let mut states = vec![&mut self.state];
+        if let Persistence::EventLog(ref mut committer) = self.persistence {
+            states.push(committer.live_state_mut());
+        }
+        for state in states {
+            state
+                .move_vectors_to(file.clone())
+                .map_err(EngineError::Kernel)?;
+        }
+        Ok(())
+    }
+
+    /// [`Self::move_vectors_to_file`] after a recovery. On failure the
+    /// recovered state is still served, from RAM, but writes are refused:
+    /// the configured file no longer holds the vectors.
+    fn recovered_vectors_to_file(&mut self) -> Result<(), RecoveryMode> {
+        if let Err(e) = self.move_vectors_to_file() {
+            let reason = format!("vectors could not be moved to the vector file: {e}");
+            tracing::error!("{}; writes are disabled", reason);
+            self.recovery_refused = Some(reason.clone());
+            return Err(RecoveryMode::Refused(reason));
+        }
+        Ok(())
+    }
+
+    /// The vector file record vectors are kept in, if one is configured.
+    pub fn vector_file(&self) -> Option<&VectorFile> {
+        self.vector_file.as_ref()
+    }
+
+    /// Adopt the pool limits committed by the last `ResizePools`, if any.
+    /// Until then the configured `max_*` values apply.
+    fn sync_pool_limits(&mut self) {
+        if let Some(cap) = self.state.capacity() {
+            self.max_records = cap.records as usize;
+            self.max_nodes = cap.nodes as usize;
+            self.max_edges = cap.edges as usize;
+        }
+    }
+
     // ── Metadata sidecar ─────────────────────────────────────────────────────
 
     pub fn flush_metadata(&self) -> Result<(), EngineError> {
@@ -408,20 +953,89 @@ impl Engine {
         }
     }
 
+    /// Bring the sidecar-loaded metadata store up to date with a replayed
+    /// event log. The sidecar lags the log by whatever was committed after
+    /// its last flush; overlaying the kernel's meta map covers sets, but a
+    /// sidecar key the map lacks may have been removed by a `DeleteMeta`.
+    /// Only then is the log scanned, replaying its meta events in order.
+    fn replay_metadata_log(&mut self, log_path: &Path) {
+        let lagging = self
+            .metadata
+            .keys()
+            .iter()
+            .any(|key| !self.state.meta.contains_key(key));
+        if lagging {
+            match valori_storage::events::event_replay::read_all_segments(log_path, None) {
+                Ok(events) => {
+                    for (_, event) in &events {
+                        self.apply_meta_event_to_store(event);
+                    }
+                }
+                Err(e) => tracing::warn!("Metadata replay skipped; event log read failed: {e}"),
+            }
+        }
+        self.sync_metadata_from_state();
+    }
+
+    /// Mirror one committed `SetMeta` / `DeleteMeta` into the metadata store.
+    /// Values that are not JSON (hex blobs from the Python bindings) are
+    /// left to the sidecar, as in [`Self::sync_metadata_from_state`].
+    fn apply_meta_event_to_store(&self, event: &valori_kernel::event::KernelEvent) {
+        use valori_kernel::event::KernelEvent;
+        match event {
+            KernelEvent::SetMeta { key, value } => {
+                if let Ok(parsed) = serde_json::from_str(value) {
+                    self.metadata.set(key.clone(), parsed);
+                }
+            }
+            KernelEvent::DeleteMeta { key } => {
+                self.metadata.remove(key);
+            }
+            _ => {}
+        }
+    }
+
+    /// Refuse a `SetMeta` of `key` over the `MAX_META_*` limits, given the
+    /// current `meta` map, before it is committed. The kernel applies any
+    /// `SetMeta`, so that logs written before the limits still replay; new
+    /// writes are held to them here.
+    pub fn check_meta_limits(
+        meta: &std::collections::BTreeMap<String, String>,
+        key: &str,
+        value: &str,
+    ) -> Result<(), EngineError> {
+        use valori_protocol::{MAX_METADATA_SIZE, MAX_META_ENTRIES, MAX_META_KEY_SIZE};
+        if key.len() > MAX_META_KEY_SIZE || value.len() > MAX_METADATA_SIZE {
+            return Err(EngineError::Kernel(KernelError::MetadataTooLarge));
+        }
+        if meta.len() >= MAX_META_ENTRIES && !meta.contains_key(key) {
+            return Err(EngineError::Kernel(KernelError::CapacityExceeded));
+        }
+        Ok(())
+    }
+
     pub fn set_meta_audited(
         &mut self,
         key: String,
         value: serde_json::Value,
     ) -> Result<(), EngineError> {
-        let event = valori_kernel::event::KernelEvent::SetMeta {
-            key: key.clone(),
-            value: value.to_string(),
-        };
+        let value = value.to_string();
+        Self::check_meta_limits(&self.state.meta, &key, &value)?;
+        let event = valori_kernel::event::KernelEvent::SetMeta { key, value };
         self.commit_and_apply_ns(&event, 0)?;
-        self.metadata.set(key, value);
         self.flush_metadata()
     }
 
+    /// Remove `key` through an audited `DeleteMeta` event. Returns whether
+    /// the key was set; deleting a missing key still commits the event.
+    pub fn delete_meta_audited(&mut self, key: String) -> Result<bool, EngineError> {
+        let existed = self.state.meta.contains_key(&key) || self.metadata.get(&key).is_some();
+        let event = valori_kernel::event::KernelEvent::DeleteMeta { key };
+        self.commit_and_apply_ns(&event, 0)?;
+        self.flush_metadata()?;
+        Ok(existed)
+    }
+
     pub fn flush_namespaces(&self) -> Result<(), EngineError> {
         if let Some(ref path) = self.namespaces_path {
             let json = serde_json::to_vec(&self.namespaces).map_err(|e| {
@@ -478,7 +1092,11 @@ impl Engine {
         let node_fill = pct(live_nodes, self.max_nodes);
         let edge_fill = pct(live_edges, self.max_edges);
 
-        let status = if rec_fill >= 100.0 || node_fill >= 100.0 || edge_fill >= 100.0 {
+        let status = if self.consistency_drift {
+            "drift"
+        } else if self.writes_refused() {
+            "read_only"
+        } else if rec_fill >= 100.0 || node_fill >= 100.0 || edge_fill >= 100.0 {
             "full"
         } else if rec_fill >= 90.0 || node_fill >= 90.0 || edge_fill >= 90.0 {
             "degraded"
@@ -537,6 +1155,133 @@ impl Engine {
             embed_enabled: self.embed_config.is_some(),
             embed_provider: self.embed_config.as_ref().map(|c| c.provider.clone()),
             shard_count: self.shard_count,
+            consistent: !self.consistency_drift,
+            bulk_loading: self.bulk_loading,
+            snapshot_check: self.snapshot_check.clone(),
+            recovery_refused: self.recovery_refused.clone(),
+            leader_epoch: self.leader_epoch.epoch,
+            fenced: self.fenced.clone(),
+            secondary_of: self.secondary_of.clone(),
+            state_version: self.state.version(),
+        }
+    }
+
+    /// Disk and pool usage: the pools against their capacities (as in
+    /// [`Engine::health`]), the event log's live and archived segments, the
+    /// snapshot / WAL files and the metadata store. Files that do not exist
+    /// (yet) are left out.
+    pub fn storage_stats(&self) -> StorageStats {
+        fn file(path: &Path) -> Option<FileUsage> {
+            let meta = std::fs::metadata(path).ok()?;
+            Some(FileUsage {
+                path: path.to_string_lossy().into_owned(),
+                bytes: meta.len(),
+            })
+        }
+        let len = |p: &Path| std::fs::metadata(p).map_or(0, |m| m.len());
+
+        let event_log = self.event_committer().map(|c| {
+            let live = c.event_log().path();
+            let archives: Vec<PathBuf> = valori_storage::events::event_replay::segment_paths(live)
+                .into_iter()
+                .filter(|p| p != live)
+                .collect();
+            let live_bytes = len(live);
+            let archived_bytes = archives.iter().map(|p| len(p)).sum();
+            EventLogUsage {
+                path: live.to_string_lossy().into_owned(),
+                height: c.journal().committed_height(),
+                live_bytes,
+                archived_segments: archives.len(),
+                archived_bytes,
+                total_bytes: live_bytes + archived_bytes,
+            }
+        });
+
+        let EngineHealth {
+            records,
+            nodes,
+            edges,
+            ..
+        } = self.health();
+        StorageStats {
+            records,
+            nodes,
+            edges,
+            event_log,
+            snapshot: self.snapshot_path.as_deref().and_then(file),
+            wal: self.wal_path.as_deref().and_then(file),
+            metadata: MetadataUsage {
+                entries: self.metadata.len(),
+                bytes: self.metadata.snapshot().len() as u64,
+                file: self.metadata_path.as_deref().and_then(file),
+            },
+        }
+    }
+
+    /// Run the kernel invariants plus index-vs-kernel and
+    /// `record_to_node`-vs-graph consistency checks. Read-only; cost is
+    /// linear in the pool sizes.
+    pub fn check_consistency(&self) -> ConsistencyReport {
+        let kernel: Vec<String> = self
+            .state
+            .invariant_violations()
+            .iter()
+            .map(|v| v.to_string())
+            .collect();
+
+        let searchable: Vec<u32> = (0..self.state.total_record_slots() as u32)
+            .filter(|&i| {
+                self.state
+                    .get_record(RecordId(i))
+                    .is_some_and(|r| r.is_searchable())
+            })
+            .collect();
+        // A bulk load leaves the index behind the kernel on purpose.
+        let indexed = if self.bulk_loading {
+            searchable.clone()
+        } else {
+            self.index.ids()
+        };
+        let index_missing: Vec<u32> = searchable
+            .iter()
+            .filter(|id| indexed.binary_search(id).is_err())
+            .copied()
+            .collect();
+        let index_stale: Vec<u32> = indexed
+            .iter()
+            .filter(|id| searchable.binary_search(id).is_err())
+            .copied()
+            .collect();
+
+        let mut record_to_node: Vec<String> = Vec::new();
+        let mut mapped: Vec<(&u32, &u32)> = self.record_to_node.iter().collect();
+        mapped.sort();
+        for (&rid, &nid) in mapped {
+            let node = self.state.get_node(valori_kernel::types::id::NodeId(nid));
+            if node.and_then(|n| n.record) != Some(RecordId(rid)) {
+                record_to_node.push(format!(
+                    "record {rid} maps to node {nid}, which does not point back"
+                ));
+            }
+        }
+
+        let committer_diverged = self.event_committer().is_some_and(|c| {
+            valori_kernel::snapshot::blake3::hash_state_blake3(c.live_state())
+                != valori_kernel::snapshot::blake3::hash_state_blake3(&self.state)
+        });
+
+        ConsistencyReport {
+            ok: kernel.is_empty()
+                && index_missing.is_empty()
+                && index_stale.is_empty()
+                && record_to_node.is_empty()
+                && !committer_diverged,
+            kernel,
+            index_missing,
+            index_stale,
+            record_to_node,
+            committer_diverged,
         }
     }
 
@@ -598,13 +1343,24 @@ impl Engine {
         &mut self,
         values: &[f32],
         namespace_id: u16,
+    ) -> Result<u32, EngineError> {
+        self.insert_record_tagged_ns(values, 0, namespace_id)
+    }
+
+    /// As [`insert_record_from_f32_ns`](Self::insert_record_from_f32_ns),
+    /// storing `tag` on the record for [`TagFilter`] searches.
+    pub fn insert_record_tagged_ns(
+        &mut self,
+        values: &[f32],
+        tag: u64,
+        namespace_id: u16,
     ) -> Result<u32, EngineError> {
         if self.state.record_count() >= self.max_records {
             return Err(EngineError::Kernel(KernelError::CapacityExceeded));
         }
         let mut fxp_data = Vec::with_capacity(values.len());
         for &v in values {
-            if v > 32767.99 || v < -32768.0 {
+            if !valori_protocol::fxp::in_range(v) {
                 return Err(EngineError::InvalidInput(
                     "Vector values must be between -32768.0 and 32767.99".to_string(),
                 ));
@@ -617,11 +1373,12 @@ impl Engine {
             id: rid,
             vector,
             metadata: None,
-            tag: 0,
+            tag,
         };
         self.commit_and_apply_ns(&event, namespace_id)?;
         self.auto_tier_check();
         self.created_at.insert(rid.0, Self::now_unix());
+        self.run_forget_policy_if_due();
         Ok(rid.0)
     }
 
@@ -677,11 +1434,13 @@ impl Engine {
         self.commit_and_apply_ns(&event, namespace_id)?;
         let now = Self::now_unix();
         self.created_at.insert(rid.0, now);
+        self.run_forget_policy_if_due();
         Ok(rid.0)
     }
 
     /// Commit a SetMeta key-value event into the default namespace.
     pub fn apply_meta_event(&mut self, key: String, value: String) -> Result<(), EngineError> {
+        Self::check_meta_limits(&self.state.meta, &key, &value)?;
         let event = valori_kernel::event::KernelEvent::SetMeta { key, value };
         self.commit_and_apply_ns(&event, valori_kernel::types::id::DEFAULT_NS.0)
     }
@@ -716,6 +1475,7 @@ impl Engine {
             tag,
         };
         self.commit_and_apply_ns(&event, namespace_id)?;
+        self.run_forget_policy_if_due();
         Ok(rid.0)
     }
 
@@ -770,7 +1530,7 @@ impl Engine {
             let values = &batch[i];
             let mut fxp_data = Vec::with_capacity(values.len());
             for &v in values {
-                if v > 32767.99 || v < -32768.0 {
+                if !valori_protocol::fxp::in_range(v) {
                     return Err(EngineError::InvalidInput(
                         "Vector values must be between -32768.0 and 32767.99".to_string(),
                     ));
@@ -788,18 +1548,20 @@ impl Engine {
             id_map[i] = id;
         }
 
-        self.persistence.log_batch_ns(&events, namespace_id)?;
-        for event in &events {
-            self.apply_committed_event_ns(event, namespace_id)?;
-        }
+        self.check_writable()?;
+        self.metadata_policy.check_events(&events)?;
+        tracing::debug_span!("commit", namespace_id, events = events.len())
+            .in_scope(|| self.persistence.log_batch_ns(&events, namespace_id))?;
+        tracing::debug_span!("apply").in_scope(|| {
+            events
+                .iter()
+                .try_for_each(|event| self.ingest_event(event, namespace_id))
+        })?;
         self.auto_tier_check();
 
         for &i in &insert_indices {
             if let Some(Some(rid)) = request_ids.and_then(|r| r.get(i)) {
-                if self.batch_seen.len() >= 65536 {
-                    self.batch_seen.clear();
-                }
-                self.batch_seen.insert(*rid, id_map[i]);
+                self.remember_request_id(*rid, id_map[i]);
             }
         }
 
@@ -808,69 +1570,535 @@ impl Engine {
             self.created_at.insert(id_map[i], now);
         }
 
+        self.run_forget_policy_if_due();
         Ok(id_map)
     }
 
-    // ── Search ────────────────────────────────────────────────────────────────
+    // ── Deduplicating inserts ─────────────────────────────────────────────────
+
+    /// Active record of `namespace_id` whose vector is exactly `vector`,
+    /// the lowest id if there are several.
+    pub fn find_duplicate(&mut self, vector: &FxpVector, namespace_id: u16) -> Option<u32> {
+        let state = &self.state;
+        let hashes = self.vector_hashes.get_or_insert_with(|| {
+            let mut hashes = rustc_hash::FxHashMap::<u64, Vec<u32>>::default();
+            for i in 0..state.total_record_slots() as u32 {
+                if let Some(r) = state.get_record(RecordId(i)).filter(|r| r.is_searchable()) {
+                    hashes
+                        .entry(vector_hash(state.record_vector(r)))
+                        .or_default()
+                        .push(i);
+                }
+            }
+            hashes
+        });
+        let hash = vector_hash(vector.as_slice());
+        let ids = hashes.get_mut(&hash)?;
+        // Drop ids that were deleted, or reused by a different vector.
+        ids.retain(|&id| {
+            state
+                .get_record(RecordId(id))
+                .is_some_and(|r| r.is_searchable() && vector_hash(state.record_vector(r)) == hash)
+        });
+        ids.iter().copied().find(|&id| {
+            state.get_record(RecordId(id)).is_some_and(|r| {
+                r.namespace_id == namespace_id && state.record_vector(r) == vector.as_slice()
+            })
+        })
+    }
 
-    pub fn search_l2(&self, query: &[f32], k: usize) -> Result<Vec<(u32, f32)>, EngineError> {
-        self.search_l2_ns(query, k, valori_kernel::types::id::DEFAULT_NS.0)
+    /// [`insert_record_tagged_ns`](Self::insert_record_tagged_ns) that
+    /// first looks for an active record of the namespace with the same
+    /// vector and, if there is one, does what `on_duplicate` says.
+    pub fn insert_record_dedup_ns(
+        &mut self,
+        values: &[f32],
+        tag: u64,
+        namespace_id: u16,
+        on_duplicate: OnDuplicate,
+    ) -> Result<DedupInsert, EngineError> {
+        let existing = match on_duplicate {
+            OnDuplicate::Allow => None,
+            _ => self.find_duplicate(&fxp_vector(values)?, namespace_id),
+        };
+        let Some(existing) = existing else {
+            let id = self.insert_record_tagged_ns(values, tag, namespace_id)?;
+            return Ok(DedupInsert {
+                id,
+                duplicate_of: None,
+            });
+        };
+        let id = match on_duplicate {
+            OnDuplicate::Reject => return Err(EngineError::DuplicateVector { existing }),
+            OnDuplicate::Allow | OnDuplicate::ReturnExisting => existing,
+            OnDuplicate::Link => {
+                return Ok(self.insert_batch_linked(
+                    &[values.to_vec()],
+                    None,
+                    namespace_id,
+                    None,
+                    tag,
+                    &[Some(Earlier::Stored(existing))],
+                )?[0]);
+            }
+        };
+        Ok(DedupInsert {
+            id,
+            duplicate_of: Some(existing),
+        })
     }
 
-    pub fn search_l2_ns(
-        &self,
-        query: &[f32],
-        k: usize,
+    /// [`insert_batch_ns`](Self::insert_batch_ns) with a duplicate check
+    /// per item, against the store and against earlier items of the batch.
+    /// Items whose request id was already seen return their old id, as
+    /// there. Under [`OnDuplicate::Reject`] one duplicate refuses the whole
+    /// batch.
+    pub fn insert_batch_dedup_ns(
+        &mut self,
+        batch: &[Vec<f32>],
+        metadata: Option<&[Option<Vec<u8>>]>,
         namespace_id: u16,
-    ) -> Result<Vec<(u32, f32)>, EngineError> {
-        use valori_kernel::index::SearchResult;
+        request_ids: Option<&[Option<[u8; 16]>]>,
+        on_duplicate: OnDuplicate,
+    ) -> Result<Vec<DedupInsert>, EngineError> {
+        if on_duplicate == OnDuplicate::Allow {
+            let ids = self.insert_batch_ns(batch, metadata, namespace_id, request_ids)?;
+            return Ok(ids
+                .into_iter()
+                .map(|id| DedupInsert {
+                    id,
+                    duplicate_of: None,
+                })
+                .collect());
+        }
 
-        if let Some(dim) = self.state.dim {
-            if query.len() != dim {
-                return Err(EngineError::Kernel(KernelError::DimensionMismatch {
-                    expected: dim,
-                    found: query.len(),
-                }));
+        let request_id = |i: usize| request_ids.and_then(|r| r.get(i).copied().flatten());
+        let mut copies: Vec<Option<Earlier>> = Vec::with_capacity(batch.len());
+        let mut in_batch: rustc_hash::FxHashMap<u64, Vec<usize>> = Default::default();
+        let mut vectors = Vec::with_capacity(batch.len());
+        for (i, values) in batch.iter().enumerate() {
+            let vector = fxp_vector(values)?;
+            if request_id(i).is_some_and(|rid| self.batch_seen.contains_key(&rid)) {
+                copies.push(None);
+                vectors.push(vector);
+                continue;
             }
-        }
-        for &v in query {
-            if v > 32767.99 || v < -32768.0 {
-                return Err(EngineError::InvalidInput(
-                    "Query vector values must be between -32768.0 and 32767.99".to_string(),
-                ));
+            let hash = vector_hash(vector.as_slice());
+            let copy = match self.find_duplicate(&vector, namespace_id) {
+                Some(id) => Some(Earlier::Stored(id)),
+                None => in_batch
+                    .get(&hash)
+                    .and_then(|js| js.iter().find(|&&j| vectors[j] == vector))
+                    .map(|&j| Earlier::Item(j)),
+            };
+            if copy.is_none() {
+                in_batch.entry(hash).or_default().push(i);
             }
+            copies.push(copy);
+            vectors.push(vector);
         }
 
-        if self.effective_index_kind() != IndexKind::BruteForce {
-            let candidates = self.index.search(query, k);
-            let hits: Vec<(u32, f32)> = candidates
-                .into_iter()
-                .filter(|(id, _)| {
-                    self.state
-                        .get_record(RecordId(*id))
-                        .map_or(false, |r| r.namespace_id == namespace_id)
-                })
-                .take(k)
-                .collect();
-            return Ok(hits);
+        match on_duplicate {
+            OnDuplicate::Reject => {
+                for (i, copy) in copies.iter().enumerate() {
+                    match *copy {
+                        Some(Earlier::Stored(existing)) => {
+                            return Err(EngineError::DuplicateVector { existing })
+                        }
+                        Some(Earlier::Item(j)) => {
+                            return Err(EngineError::InvalidInput(format!(
+                                "items {j} and {i} of the batch have the same vector"
+                            )))
+                        }
+                        None => {}
+                    }
+                }
+            }
+            OnDuplicate::Link if copies.iter().any(Option::is_some) => {
+                return self.insert_batch_linked(
+                    batch,
+                    metadata,
+                    namespace_id,
+                    request_ids,
+                    0,
+                    &copies,
+                );
+            }
+            _ => {}
         }
 
-        let fxp_data: Vec<FxpScalar> = query
-            .iter()
-            .map(|&v| FxpScalar((v * SCALE as f32) as i32))
+        // Everything left is a plain batch insert of the items that write.
+        let writes: Vec<usize> = (0..batch.len())
+            .filter(|&i| on_duplicate == OnDuplicate::Link || copies[i].is_none())
             .collect();
-        let fxp_query = FxpVector { data: fxp_data };
-        let mut results = vec![SearchResult::default(); k];
-        let found = self
-            .state
-            .search_l2_ns(&fxp_query, &mut results, namespace_id);
-        Ok(results[..found]
-            .iter()
-            .map(|r| (r.id.0, r.score as f32 / (SCALE as f32 * SCALE as f32)))
-            .collect())
-    }
-
-    // ── Collections ───────────────────────────────────────────────────────────
+        let pick = |i: &usize| batch[*i].clone();
+        let sub_batch: Vec<Vec<f32>> = writes.iter().map(pick).collect();
+        let sub_meta: Option<Vec<Option<Vec<u8>>>> = metadata.map(|m| {
+            writes
+                .iter()
+                .map(|&i| m.get(i).cloned().flatten())
+                .collect()
+        });
+        let sub_rids: Option<Vec<Option<[u8; 16]>>> =
+            request_ids.map(|_| writes.iter().map(|&i| request_id(i)).collect());
+        let ids = if sub_batch.is_empty() {
+            Vec::new()
+        } else {
+            self.insert_batch_ns(
+                &sub_batch,
+                sub_meta.as_deref(),
+                namespace_id,
+                sub_rids.as_deref(),
+            )?
+        };
+        let mut out: Vec<Option<DedupInsert>> = vec![None; batch.len()];
+        for (&i, id) in writes.iter().zip(ids) {
+            out[i] = Some(DedupInsert {
+                id,
+                duplicate_of: None,
+            });
+        }
+        for (i, copy) in copies.iter().enumerate() {
+            let existing = match *copy {
+                Some(Earlier::Stored(id)) => id,
+                Some(Earlier::Item(j)) => out[j].map(|d| d.id).unwrap_or_default(),
+                None => continue,
+            };
+            out[i] = Some(DedupInsert {
+                id: existing,
+                duplicate_of: Some(existing),
+            });
+        }
+        Ok(out.into_iter().flatten().collect())
+    }
+
+    /// Insert every item not already seen by request id, plus a graph node
+    /// for each duplicate and a `RefersTo` edge to the node of its earlier
+    /// copy, as one transaction.
+    fn insert_batch_linked(
+        &mut self,
+        batch: &[Vec<f32>],
+        metadata: Option<&[Option<Vec<u8>>]>,
+        namespace_id: u16,
+        request_ids: Option<&[Option<[u8; 16]>]>,
+        tag: u64,
+        copies: &[Option<Earlier>],
+    ) -> Result<Vec<DedupInsert>, EngineError> {
+        let request_id = |i: usize| request_ids.and_then(|r| r.get(i).copied().flatten());
+        let mut ops = Vec::new();
+        let mut insert_op: Vec<Option<usize>> = vec![None; batch.len()];
+        for (i, values) in batch.iter().enumerate() {
+            if request_id(i).is_some_and(|rid| self.batch_seen.contains_key(&rid)) {
+                continue;
+            }
+            insert_op[i] = Some(ops.len());
+            ops.push(TxOp::Insert {
+                values: values.clone(),
+                metadata: metadata.and_then(|m| m.get(i)).cloned().flatten(),
+                tag,
+            });
+        }
+        let record_node = |ops: &mut Vec<TxOp>, record: TxRef| {
+            ops.push(TxOp::CreateNode {
+                kind: NodeKind::Record as u8,
+                record: Some(record),
+            });
+            TxRef::Op(ops.len() - 1)
+        };
+        let mut nodes: HashMap<Earlier, TxRef> = HashMap::new();
+        for (i, copy) in copies.iter().enumerate() {
+            let (Some(copy), Some(op)) = (*copy, insert_op[i]) else {
+                continue;
+            };
+            let to = match nodes.get(&copy) {
+                Some(&node) => node,
+                None => {
+                    let node = match copy {
+                        Earlier::Stored(id) => match self.record_to_node.get(&id) {
+                            Some(&node) => TxRef::Id(node),
+                            None => record_node(&mut ops, TxRef::Id(id)),
+                        },
+                        Earlier::Item(j) => {
+                            record_node(&mut ops, TxRef::Op(insert_op[j].unwrap_or_default()))
+                        }
+                    };
+                    nodes.insert(copy, node);
+                    node
+                }
+            };
+            let from = record_node(&mut ops, TxRef::Op(op));
+            ops.push(TxOp::CreateEdge {
+                from,
+                to,
+                kind: EdgeKind::RefersTo as u8,
+            });
+        }
+
+        let results = self.commit_transaction_ns(&ops, namespace_id)?;
+        let mut out: Vec<DedupInsert> = Vec::with_capacity(batch.len());
+        for i in 0..batch.len() {
+            let id = match insert_op[i] {
+                Some(op) => {
+                    let id = results[op].unwrap_or_default();
+                    if let Some(rid) = request_id(i) {
+                        self.remember_request_id(rid, id);
+                    }
+                    id
+                }
+                None => request_id(i)
+                    .and_then(|rid| self.batch_seen.get(&rid).copied())
+                    .unwrap_or_default(),
+            };
+            let duplicate_of = match copies[i] {
+                Some(Earlier::Stored(id)) => Some(id),
+                Some(Earlier::Item(j)) => Some(out[j].id),
+                None => None,
+            };
+            out.push(DedupInsert { id, duplicate_of });
+        }
+        Ok(out)
+    }
+
+    fn remember_request_id(&mut self, request_id: [u8; 16], id: u32) {
+        if self.batch_seen.len() >= 65536 {
+            self.batch_seen.clear();
+        }
+        self.batch_seen.insert(request_id, id);
+    }
+
+    // ── Search ────────────────────────────────────────────────────────────────
+
+    pub fn search_l2(&self, query: &[f32], k: usize) -> Result<Vec<(u32, f32)>, EngineError> {
+        self.search_l2_ns(query, k, valori_kernel::types::id::DEFAULT_NS.0)
+    }
+
+    pub fn search_l2_ns(
+        &self,
+        query: &[f32],
+        k: usize,
+        namespace_id: u16,
+    ) -> Result<Vec<(u32, f32)>, EngineError> {
+        self.search_l2_index(query, k, namespace_id, None)
+    }
+
+    /// Search through the index named `index`: the primary index (under its
+    /// effective or configured name) or one of [`Engine::named_indexes`].
+    /// `None` uses the primary.
+    pub fn search_l2_index(
+        &self,
+        query: &[f32],
+        k: usize,
+        namespace_id: u16,
+        index: Option<&str>,
+    ) -> Result<Vec<(u32, f32)>, EngineError> {
+        self.search_l2_index_until(query, k, namespace_id, index, None)
+    }
+
+    /// [`search_l2_index`](Self::search_l2_index) that gives up with
+    /// [`EngineError::DeadlineExceeded`] once `deadline` passes. Scans check
+    /// the clock periodically rather than per record, so the caller's lock
+    /// is released within one check interval of the deadline.
+    pub fn search_l2_index_until(
+        &self,
+        query: &[f32],
+        k: usize,
+        namespace_id: u16,
+        index: Option<&str>,
+        deadline: Option<std::time::Instant>,
+    ) -> Result<Vec<(u32, f32)>, EngineError> {
+        use valori_kernel::index::SearchResult;
+
+        let expired = || deadline.is_some_and(|d| std::time::Instant::now() >= d);
+
+        if let Some(dim) = self.state.dim {
+            if query.len() != dim {
+                return Err(EngineError::Kernel(KernelError::DimensionMismatch {
+                    expected: dim,
+                    found: query.len(),
+                }));
+            }
+        }
+        for &v in query {
+            if !valori_protocol::fxp::in_range(v) {
+                return Err(EngineError::InvalidInput(
+                    "Query vector values must be between -32768.0 and 32767.99".to_string(),
+                ));
+            }
+        }
+
+        let primary = self.effective_index_kind();
+        let selected = self.named_index(index)?;
+
+        if selected.is_some() || primary != IndexKind::BruteForce {
+            if self.bulk_loading {
+                return Err(EngineError::BulkLoadInProgress);
+            }
+            let idx = selected.unwrap_or(self.index.as_ref());
+            let candidates = Self::index_search(&self.state, idx, query, k, &expired)
+                .ok_or(EngineError::DeadlineExceeded)?;
+            let hits: Vec<(u32, f32)> = candidates
+                .into_iter()
+                .filter(|(id, _)| {
+                    self.state
+                        .get_record(RecordId(*id))
+                        .map_or(false, |r| r.namespace_id == namespace_id)
+                })
+                .take(k)
+                .collect();
+            self.record_access(&hits);
+            return Ok(hits);
+        }
+
+        let fxp_data: Vec<FxpScalar> = query
+            .iter()
+            .map(|&v| FxpScalar((v * SCALE as f32) as i32))
+            .collect();
+        let fxp_query = FxpVector { data: fxp_data };
+        let mut results = vec![SearchResult::default(); k];
+        let found = self
+            .state
+            .search_l2_ns_cancellable(&fxp_query, &mut results, namespace_id, &expired)
+            .ok_or(EngineError::DeadlineExceeded)?;
+        let hits: Vec<(u32, f32)> = results[..found]
+            .iter()
+            .map(|r| (r.id.0, r.score as f32 / (SCALE as f32 * SCALE as f32)))
+            .collect();
+        self.record_access(&hits);
+        Ok(hits)
+    }
+
+    /// Records of `namespace_id` within `max_distance` of `query`, nearest
+    /// first and at most `limit` of them. Distances are squared L2, as the
+    /// scores of [`search_l2_ns`](Self::search_l2_ns). An exact scan of the
+    /// namespace that skips the ANN indexes, so it also runs during a bulk
+    /// load; hits are not counted in the access stats.
+    pub fn search_range_ns(
+        &self,
+        query: &[f32],
+        max_distance: f32,
+        limit: usize,
+        namespace_id: u16,
+    ) -> Result<Vec<(u32, f32)>, EngineError> {
+        use valori_kernel::index::SearchResult;
+        if let Some(dim) = self.state.dim {
+            if query.len() != dim {
+                return Err(EngineError::Kernel(KernelError::DimensionMismatch {
+                    expected: dim,
+                    found: query.len(),
+                }));
+            }
+        }
+        if max_distance.is_nan() || max_distance < 0.0 {
+            return Err(EngineError::InvalidInput(
+                "max_distance must be zero or more".to_string(),
+            ));
+        }
+        let scale = SCALE as f64 * SCALE as f64;
+        // Saturates for radii past the i64 range.
+        let max_score = (max_distance as f64 * scale) as i64;
+        let mut results = vec![SearchResult::default(); limit];
+        let found =
+            self.state
+                .search_range_ns(&fxp_vector(query)?, max_score, &mut results, namespace_id);
+        Ok(results[..found]
+            .iter()
+            .map(|r| (r.id.0, (r.score as f64 / scale) as f32))
+            .collect())
+    }
+
+    /// Records of `namespace_id` whose sparse vector has a positive dot
+    /// product with the sparse query `indices`/`values`, highest first and
+    /// at most `k` of them, each with that dot product. An exact scan of the
+    /// sparse vectors; hits are not counted in the access stats.
+    pub fn search_sparse_ns(
+        &self,
+        indices: &[u32],
+        values: &[f32],
+        k: usize,
+        namespace_id: u16,
+    ) -> Result<Vec<(u32, f32)>, EngineError> {
+        use valori_kernel::index::SearchResult;
+        let query = fxp_sparse(indices, values)?;
+        let scale = SCALE as f64 * SCALE as f64;
+        let mut results = vec![SearchResult::default(); k.min(self.state.sparse_count())];
+        let found = self
+            .state
+            .search_sparse_ns(&query, &mut results, namespace_id);
+        Ok(results[..found]
+            .iter()
+            .map(|r| (r.id.0, (-(r.score as f64) / scale) as f32))
+            .collect())
+    }
+
+    /// Search `idx` for `k` candidates. An index with a
+    /// [`rescore_pool`](VectorIndex::rescore_pool) is asked for that many,
+    /// which are then rescored by exact L2 against the kernel's vectors, so
+    /// distances come out as a brute-force scan would give them. `None` once
+    /// `cancelled` returns true.
+    fn index_search(
+        state: &KernelState,
+        idx: &(dyn VectorIndex + Send + Sync),
+        query: &[f32],
+        k: usize,
+        cancelled: &dyn Fn() -> bool,
+    ) -> Option<Vec<(u32, f32)>> {
+        let Some(pool) = idx.rescore_pool(k) else {
+            return idx.search_cancellable(query, k, cancelled);
+        };
+        let candidates = idx.search_cancellable(query, pool, cancelled)?;
+        let fxp_query: Vec<FxpScalar> = query
+            .iter()
+            .map(|&v| FxpScalar((v * SCALE as f32) as i32))
+            .collect();
+        let scale = SCALE as f32 * SCALE as f32;
+        let mut hits: Vec<(i64, u32)> = candidates
+            .into_iter()
+            .filter_map(|(id, _)| {
+                let rec = state.get_record(RecordId(id))?;
+                Some((fxp_l2_sq_slice(state.record_vector(rec), &fxp_query), id))
+            })
+            .collect();
+        hits.sort_unstable();
+        hits.truncate(k);
+        Some(
+            hits.into_iter()
+                .map(|(score, id)| (id, score as f32 / scale))
+                .collect(),
+        )
+    }
+
+    /// The named index `name` refers to; `None` for the primary index (by
+    /// its effective or configured name, or no name at all).
+    fn named_index(
+        &self,
+        name: Option<&str>,
+    ) -> Result<Option<&(dyn VectorIndex + Send + Sync)>, EngineError> {
+        match name {
+            None => Ok(None),
+            Some(n) if n == self.effective_index_kind().name() || n == self.index_kind.name() => {
+                Ok(None)
+            }
+            Some(n) => self
+                .named_indexes
+                .get(n)
+                .map(|idx| Some(idx.as_ref()))
+                .ok_or_else(|| {
+                    EngineError::InvalidInput(format!(
+                        "unknown index '{n}' (available: {})",
+                        self.index_names().join(", ")
+                    ))
+                }),
+        }
+    }
+
+    /// Names accepted by [`Engine::search_l2_index`], primary first.
+    pub fn index_names(&self) -> Vec<&str> {
+        let mut names = vec![self.effective_index_kind().name()];
+        names.extend(self.named_indexes.keys().map(String::as_str));
+        names
+    }
+
+    // ── Collections ───────────────────────────────────────────────────────────
 
     /// Tag-filtered brute-force L2 search across all records.
     ///
@@ -884,6 +2112,17 @@ impl Engine {
         query: &[f32],
         k: usize,
         tag: Option<u64>,
+    ) -> Result<Vec<(u32, f32)>, EngineError> {
+        self.search_l2_tagged(query, k, tag.map(TagFilter::Exact))
+    }
+
+    /// As [`search_l2_filtered`](Self::search_l2_filtered), with any
+    /// [`TagFilter`]: an exact tag, or a bitmask matched with ANY/ALL semantics.
+    pub fn search_l2_tagged(
+        &self,
+        query: &[f32],
+        k: usize,
+        filter: Option<TagFilter>,
     ) -> Result<Vec<(u32, f32)>, EngineError> {
         use valori_kernel::index::SearchResult;
 
@@ -896,7 +2135,7 @@ impl Engine {
             }
         }
         for &v in query {
-            if v > 32767.99 || v < -32768.0 {
+            if !valori_protocol::fxp::in_range(v) {
                 return Err(EngineError::InvalidInput(
                     "Query vector values must be between -32768.0 and 32767.99".to_string(),
                 ));
@@ -909,17 +2148,171 @@ impl Engine {
             .collect();
         let fxp_query = FxpVector { data: fxp_data };
         let mut results = vec![SearchResult::default(); k];
-        let found = self.state.search_l2(&fxp_query, &mut results, tag);
-        Ok(results[..found]
+        let found = self.state.search_l2(&fxp_query, &mut results, filter);
+        let hits: Vec<(u32, f32)> = results[..found]
             .iter()
             .map(|r| (r.id.0, r.score as f32 / (SCALE as f32 * SCALE as f32)))
-            .collect())
+            .collect();
+        self.record_access(&hits);
+        Ok(hits)
+    }
+
+    /// Count a search hit for every returned record.
+    fn record_access(&self, hits: &[(u32, f32)]) {
+        if hits.is_empty() {
+            return;
+        }
+        let now = Self::now_unix();
+        let mut stats = self.access_stats.lock().unwrap_or_else(|e| e.into_inner());
+        for (id, _) in hits {
+            let entry = stats.entry(*id).or_default();
+            entry.hits += 1;
+            entry.last_hit_unix = now;
+        }
+    }
+
+    /// Search-hit statistics for record `id` (zeroes if it was never hit).
+    pub fn record_access_stats(&self, id: u32) -> RecordAccess {
+        self.access_stats
+            .lock()
+            .unwrap_or_else(|e| e.into_inner())
+            .get(&id)
+            .copied()
+            .unwrap_or_default()
+    }
+
+    // ── Forgetting policy ─────────────────────────────────────────────────────
+
+    /// Records the forgetting policy would delete at the current height,
+    /// least important first. Empty without a policy or an event log.
+    pub fn forget_candidates(&self) -> Vec<crate::forget::ForgetCandidate> {
+        use valori_kernel::event::KernelEvent;
+        use valori_kernel::types::id::NodeId;
+
+        let (Some(policy), Some(committer)) = (&self.forget_policy, self.event_committer()) else {
+            return Vec::new();
+        };
+        let journal = committer.journal().committed();
+        let height = journal.len() as u64;
+
+        let mut inserted_at: HashMap<u32, u64> = HashMap::new();
+        for (i, event) in journal.iter().enumerate() {
+            match event {
+                KernelEvent::InsertRecord { id, .. }
+                | KernelEvent::InsertRecordEncrypted { id, .. } => {
+                    inserted_at.insert(id.0, i as u64 + 1);
+                }
+                _ => {}
+            }
+        }
+        let stats = self.access_stats.lock().unwrap_or_else(|e| e.into_inner());
+
+        let mut out: Vec<crate::forget::ForgetCandidate> = Vec::new();
+        for id in 0..self.state.total_record_slots() as u32 {
+            if self.state.get_record(RecordId(id)).is_none() {
+                continue;
+            }
+            let age_events = height - inserted_at.get(&id).copied().unwrap_or(0);
+            if age_events < policy.min_age_events {
+                continue;
+            }
+            let accesses = stats.get(&id).map_or(0, |a| a.hits);
+            let degree = self.record_to_node.get(&id).map_or(0, |&nid| {
+                let out = self
+                    .state
+                    .outgoing_edges(NodeId(nid))
+                    .map_or(0, |e| e.count());
+                let inc = self
+                    .state
+                    .incoming_edges(NodeId(nid))
+                    .map_or(0, |e| e.count());
+                (out + inc) as u64
+            });
+            let importance = policy.importance(age_events, accesses, degree);
+            if importance < policy.threshold {
+                out.push(crate::forget::ForgetCandidate {
+                    record_id: id,
+                    age_events,
+                    accesses,
+                    degree,
+                    importance,
+                });
+            }
+        }
+        out.sort_by(|a, b| {
+            a.importance
+                .total_cmp(&b.importance)
+                .then(a.record_id.cmp(&b.record_id))
+        });
+        out.truncate(policy.max_per_run);
+        out
+    }
+
+    /// Delete every current forget candidate. Returns the forgotten ids.
+    pub fn run_forget_policy(&mut self) -> Result<Vec<u32>, EngineError> {
+        let ids: Vec<u32> = self
+            .forget_candidates()
+            .iter()
+            .map(|c| c.record_id)
+            .collect();
+        for &id in &ids {
+            self.delete_record(id)?;
+        }
+        if !ids.is_empty() {
+            metrics::counter!("valori_forget_records_total", ids.len() as u64);
+            tracing::info!(forgotten = ids.len(), "forgetting policy ran");
+        }
+        Ok(ids)
+    }
+
+    /// Run the forgetting policy when the committed height has crossed a
+    /// multiple of `every_events` since the last run. The first call only
+    /// records the height, so a restart does not trigger a run by itself.
+    pub fn run_forget_policy_if_due(&mut self) {
+        let Some(policy) = &self.forget_policy else {
+            return;
+        };
+        let Some(height) = self
+            .event_committer()
+            .map(|c| c.journal().committed_height())
+        else {
+            return;
+        };
+        let last = *self.forget_last_height.get_or_insert(height);
+        if !policy.due(last, height) {
+            return;
+        }
+        if let Err(e) = self.run_forget_policy() {
+            tracing::warn!("forgetting policy stopped early: {e}");
+        }
+        self.forget_last_height = self
+            .event_committer()
+            .map(|c| c.journal().committed_height());
+    }
+
+    /// Kernel version: the number of events applied since the store was
+    /// created. It survives snapshots and restarts.
+    pub fn state_version(&self) -> u64 {
+        self.state.version()
+    }
+
+    /// Optimistic-concurrency precondition for a write. Call it under the
+    /// same write lock as the commit. `None` always passes.
+    pub fn check_version(&self, expected: Option<u64>) -> Result<(), EngineError> {
+        match expected {
+            Some(expected) if expected != self.state.version() => {
+                Err(EngineError::VersionConflict {
+                    expected,
+                    current: self.state.version(),
+                })
+            }
+            _ => Ok(()),
+        }
     }
 
     /// BLAKE3 hash of the current kernel state, as a lowercase hex string.
     pub fn state_hash_hex(&self) -> String {
-        use valori_kernel::snapshot::blake3::hash_state_blake3;
-        hash_state_blake3(&self.state)
+        self.canonical_state_hash()
             .iter()
             .map(|b| format!("{:02x}", b))
             .collect()
@@ -961,21 +2354,12 @@ impl Engine {
             .namespaces
             .drop(name)
             .ok_or_else(|| EngineError::InvalidInput(format!("collection '{name}' not found")))?;
-        let ns_record_ids: Vec<u64> = self
-            .state
-            .iter_records_in_ns(id)
-            .map(|r| r.id.0 as u64)
-            .collect();
-        self.state.apply_event_ns(
+        self.ingest_event(
             &valori_kernel::event::KernelEvent::DropNamespace {
                 name: String::new(),
             },
             id,
         )?;
-        for rid in &ns_record_ids {
-            self.index.delete(*rid as u32);
-        }
-        self.reranker.remove_batch(&ns_record_ids);
         self.flush_namespaces()?;
         Ok(())
     }
@@ -987,136 +2371,817 @@ impl Engine {
     // ── Snapshot ──────────────────────────────────────────────────────────────
 
     pub fn snapshot(&self) -> Result<Vec<u8>, EngineError> {
-        let mut buffer = Vec::new();
-        buffer.extend_from_slice(b"VAL1");
-
+        // The index sections would be missing every bulk-loaded record.
+        if self.bulk_loading {
+            return Err(EngineError::BulkLoadInProgress);
+        }
         let hint = valori_kernel::snapshot::encode::encode_capacity_hint(&self.state);
         let mut k_buf = Vec::with_capacity(hint);
         encode_state(&self.state, &mut k_buf)?;
-        buffer.extend_from_slice(&(k_buf.len() as u32).to_le_bytes());
-        buffer.extend_from_slice(&k_buf);
-
         let m_buf = self.metadata.snapshot();
-        buffer.extend_from_slice(&(m_buf.len() as u32).to_le_bytes());
-        buffer.extend_from_slice(&m_buf);
-
         let i_buf = self
             .index
             .snapshot()
             .map_err(|e| EngineError::InvalidInput(e.to_string()))?;
-        buffer.extend_from_slice(&(i_buf.len() as u32).to_le_bytes());
-        buffer.extend_from_slice(&i_buf);
+        let mut container = ContainerWriter::new(&k_buf, &m_buf, &i_buf);
 
         let ns_json = serde_json::to_vec(&self.namespaces)
             .map_err(|e| EngineError::InvalidInput(e.to_string()))?;
-        buffer.extend_from_slice(b"NSRG");
-        buffer.extend_from_slice(&(ns_json.len() as u32).to_le_bytes());
-        buffer.extend_from_slice(&ns_json);
+        container.section(b"NSRG", &ns_json);
 
         let crts_buf = bincode::serde::encode_to_vec(&self.created_at, bincode::config::standard())
             .map_err(|e| EngineError::InvalidInput(e.to_string()))?;
-        buffer.extend_from_slice(b"CRTS");
-        buffer.extend_from_slice(&(crts_buf.len() as u32).to_le_bytes());
-        buffer.extend_from_slice(&crts_buf);
+        container.section(b"CRTS", &crts_buf);
+
+        let accs_buf = {
+            let stats = self.access_stats.lock().unwrap_or_else(|e| e.into_inner());
+            bincode::serde::encode_to_vec(&*stats, bincode::config::standard())
+                .map_err(|e| EngineError::InvalidInput(e.to_string()))?
+        };
+        container.section(b"ACCS", &accs_buf);
+
+        // One IDXN section per named index: name_len u32, name, index blob.
+        for (name, idx) in &self.named_indexes {
+            let blob = idx
+                .snapshot()
+                .map_err(|e| EngineError::InvalidInput(e.to_string()))?;
+            let mut section = Vec::with_capacity(4 + name.len() + blob.len());
+            section.extend_from_slice(&(name.len() as u32).to_le_bytes());
+            section.extend_from_slice(name.as_bytes());
+            section.extend_from_slice(&blob);
+            container.section(b"IDXN", &section);
+        }
+
+        let (corpus, total_tokens) = self.reranker.snapshot_corpus();
+        let bcrp_buf =
+            bincode::serde::encode_to_vec(&(corpus, total_tokens), bincode::config::standard())
+                .map_err(|e| EngineError::InvalidInput(e.to_string()))?;
+        container.section(b"BCRP", &bcrp_buf);
+
+        Ok(container.finish())
+    }
+
+    /// Clean-shutdown sequence: refuse further writes, fsync the event log,
+    /// save a final snapshot to `snapshot_path` (if any), then append a
+    /// `Checkpoint` recording the committed height and state hash. Returns
+    /// that height, or `None` without an event log.
+    ///
+    /// The caller holds the engine write lock, so writes queued ahead of it
+    /// have drained by the time this runs.
+    pub fn shutdown_checkpoint(
+        &mut self,
+        snapshot_path: Option<&Path>,
+    ) -> Result<Option<u64>, EngineError> {
+        self.shutting_down = true;
+        if self.bulk_loading {
+            self.finish_bulk_load()?;
+        }
+        if let Some(committer) = self.event_committer_mut() {
+            committer
+                .flush_log()
+                .map_err(|e| EngineError::InvalidInput(format!("event log flush: {e}")))?;
+        }
+        if let Some(path) = snapshot_path {
+            self.save_snapshot(Some(path))?;
+        }
+        let state_hash = valori_kernel::snapshot::blake3::hash_state_blake3(&self.state);
+        let Some(committer) = self.event_committer_mut() else {
+            return Ok(None);
+        };
+        let height = committer.journal().committed_height();
+        committer
+            .write_checkpoint(valori_storage::events::event_log::LogEntry::Checkpoint {
+                event_count: height,
+                snapshot_hash: state_hash,
+                timestamp: Self::now_unix(),
+            })
+            .map_err(|e| EngineError::InvalidInput(format!("shutdown checkpoint: {e}")))?;
+        Ok(Some(height))
+    }
+
+    /// Archive the live event-log segment now and open the next one with a
+    /// checkpoint entry (see [`EventCommitter::rotate_now`]). Lets rotation
+    /// be scheduled externally instead of waiting for the byte limit.
+    pub fn rotate_event_log(&mut self) -> Result<Rotation, EngineError> {
+        self.event_committer_mut()
+            .ok_or_else(|| EngineError::InvalidInput("log rotation needs an event log".into()))?
+            .rotate_now()
+            .map_err(|e| EngineError::InvalidInput(format!("event log rotation: {e}")))
+    }
+
+    /// Compaction: write a snapshot of the current state, then rotate so the
+    /// new live segment opens with a checkpoint at the snapshot's height.
+    /// Recovery then starts from the snapshot and replays only the new
+    /// segment, and the archived segments are needed for audit alone.
+    /// Returns the snapshot path and the rotation.
+    pub fn compact(&mut self) -> Result<(PathBuf, Rotation), EngineError> {
+        if self.event_committer().is_none() {
+            return Err(EngineError::InvalidInput(
+                "compaction needs an event log".into(),
+            ));
+        }
+        if let Some(committer) = self.event_committer_mut() {
+            committer
+                .flush_log()
+                .map_err(|e| EngineError::InvalidInput(format!("event log flush: {e}")))?;
+        }
+        let snapshot = self.save_snapshot(None)?;
+        let rotation = self.rotate_event_log()?;
+        Ok((snapshot, rotation))
+    }
+
+    pub fn save_snapshot(&self, path: Option<&Path>) -> Result<PathBuf, EngineError> {
+        let target = path
+            .or(self.snapshot_path.as_deref())
+            .ok_or(EngineError::InvalidInput(
+                "No snapshot path configured".into(),
+            ))?;
+        let data = self.snapshot()?;
+        std::fs::write(target, data).map_err(|e| EngineError::InvalidInput(e.to_string()))?;
+        tracing::info!("Snapshot saved to {:?}", target);
+        Ok(target.to_path_buf())
+    }
+
+    /// Load a snapshot container. A container whose checksum trailer does
+    /// not match is refused; one written before the trailer existed loads.
+    pub fn restore(&mut self, data: &[u8]) -> Result<(), EngineError> {
+        let container = parse_container(data)?;
+        let ns_registry: Option<CollectionRegistry> = container
+            .section(b"NSRG")
+            .map(serde_json::from_slice)
+            .transpose()
+            .map_err(|e| EngineError::InvalidInput(format!("ns registry decode: {e}")))?;
+
+        self.restore_from_components(
+            container.kernel,
+            container.metadata,
+            Some(container.index),
+            ns_registry,
+        )?;
+        let restored = self.restore_trailing_sections(&container);
+        // Named indexes without a usable section (older snapshot, or one
+        // taken before the index was configured) are rebuilt.
+        let missing: Vec<String> = self
+            .named_indexes
+            .keys()
+            .filter(|name| !restored.contains(*name))
+            .cloned()
+            .collect();
+        self.rebuild_named(&missing);
+        Ok(())
+    }
+
+    /// The `final_state_hash` and kernel version of the state a snapshot
+    /// container holds, decoded without restoring it — what a caller checks
+    /// before letting [`Self::restore`] replace its state.
+    pub fn snapshot_state_proof(data: &[u8]) -> Result<([u8; 32], u64), EngineError> {
+        let container = parse_container(data)?;
+        let state = decode_state(container.kernel)?;
+        Ok((
+            valori_kernel::snapshot::blake3::hash_state_blake3(&state),
+            state.version(),
+        ))
+    }
+
+    // ── Mutations ─────────────────────────────────────────────────────────────
+
+    /// Point-in-time restore: start from `base` (snapshot bytes taken at the
+    /// given committed height, or genesis when `None`) and replay the on-disk
+    /// event log up to exactly `height`, each event into its recorded
+    /// namespace. The resulting kernel state — and so `get_proof` — equals
+    /// the historical state at `height`. Returns the number of events
+    /// replayed.
+    ///
+    /// The event log is left untouched for audits but detached: the engine
+    /// continues in memory only, since appending after the log's head would
+    /// no longer match the restored state.
+    pub fn restore_to_height(
+        &mut self,
+        base: Option<(&[u8], u64)>,
+        height: u64,
+    ) -> Result<u64, EngineError> {
+        let committer = self.event_committer_mut().ok_or_else(|| {
+            EngineError::InvalidInput(
+                "point-in-time restore requires the event log (set VALORI_EVENT_LOG_PATH)".into(),
+            )
+        })?;
+        committer
+            .flush_log()
+            .map_err(|e| EngineError::InvalidInput(format!("event log flush: {e}")))?;
+        let head = committer.journal().committed_height();
+        let log_path = committer.event_log().path().to_path_buf();
+
+        let base_height = base.map_or(0, |(_, h)| h);
+        if height > head {
+            return Err(EngineError::InvalidInput(format!(
+                "height {height} is beyond the committed height {head}"
+            )));
+        }
+        if base_height > height {
+            return Err(EngineError::InvalidInput(format!(
+                "base snapshot height {base_height} is above the target height {height}"
+            )));
+        }
+        let events = valori_storage::events::event_replay::read_all_segments(&log_path, None)
+            .map_err(|e| EngineError::InvalidInput(format!("event log read: {e}")))?;
+        if events.len() as u64 != head {
+            return Err(EngineError::InvalidInput(format!(
+                "event log on disk holds {} events but the committed height is {head} \
+                 (archived segments missing?)",
+                events.len()
+            )));
+        }
+
+        match base {
+            Some((data, _)) => self.restore(data)?,
+            None => {
+                self.state = KernelState::with_dim(self.dim);
+                self.state.track_state_root();
+                self.access_stats
+                    .get_mut()
+                    .unwrap_or_else(|e| e.into_inner())
+                    .clear();
+                self.anomaly_scores.clear();
+                self.rebuild_index();
+                self.rebuild_named_indexes();
+                self.rebuild_record_to_node();
+                self.move_vectors_to_file()?;
+            }
+        }
+        for (namespace_id, event) in &events[base_height as usize..height as usize] {
+            self.ingest_event(event, *namespace_id)?;
+        }
+        self.sync_metadata_from_state();
+
+        tracing::warn!(
+            "Restored to height {height} (base {base_height}); event log {:?} detached",
+            log_path
+        );
+        self.persistence = Persistence::Ephemeral;
+        Ok(height - base_height)
+    }
+
+    pub fn soft_delete_record(&mut self, id: u32) -> Result<(), EngineError> {
+        if let Some(node_id) = self.record_to_node.get(&id).copied() {
+            self.delete_node(node_id)?;
+        }
+        let rid = RecordId(id);
+        let event = valori_kernel::event::KernelEvent::SoftDeleteRecord { id: rid };
+        self.commit_and_apply_ns(&event, valori_kernel::types::id::DEFAULT_NS.0)
+    }
+
+    /// Replace a record's metadata and return its new version. With
+    /// `expected_version`, the update is a compare-and-swap: it is rejected
+    /// with [`EngineError::RecordVersionConflict`] before anything is
+    /// committed unless the record is still at that version.
+    pub fn update_record_metadata(
+        &mut self,
+        id: u32,
+        metadata: Option<Vec<u8>>,
+        namespace_id: u16,
+        expected_version: Option<u64>,
+    ) -> Result<u64, EngineError> {
+        let rid = RecordId(id);
+        let current = self
+            .state
+            .get_record(rid)
+            .map(|r| r.version)
+            .ok_or(EngineError::Kernel(KernelError::NotFound))?;
+        if let Some(expected) = expected_version.filter(|&e| e != current) {
+            return Err(EngineError::RecordVersionConflict {
+                id,
+                expected,
+                current,
+            });
+        }
+        let event = valori_kernel::event::KernelEvent::UpdateRecordMetadata { id: rid, metadata };
+        self.commit_and_apply_ns(&event, namespace_id)?;
+        Ok(current + 1)
+    }
+
+    /// Give record `id` of `namespace_id` a sparse vector, replacing any it
+    /// had; empty `indices`/`values` remove it. Zero weights are dropped.
+    /// Encrypted records cannot hold one.
+    pub fn set_sparse_vector(
+        &mut self,
+        id: u32,
+        indices: &[u32],
+        values: &[f32],
+        namespace_id: u16,
+    ) -> Result<(), EngineError> {
+        let rid = RecordId(id);
+        let vector = fxp_sparse(indices, values)?;
+        match self.state.get_record(rid) {
+            Some(r) if r.is_active() && r.namespace_id == namespace_id => {
+                if !r.is_searchable() {
+                    return Err(EngineError::InvalidInput(
+                        "encrypted records cannot hold a sparse vector".to_string(),
+                    ));
+                }
+            }
+            _ => return Err(EngineError::Kernel(KernelError::NotFound)),
+        }
+        let event = valori_kernel::event::KernelEvent::SetSparseVector { id: rid, vector };
+        self.commit_and_apply_ns(&event, namespace_id)
+    }
+
+    pub fn delete_record(&mut self, id: u32) -> Result<(), EngineError> {
+        if let Some(node_id) = self.record_to_node.get(&id).copied() {
+            self.delete_node(node_id)?;
+        }
+        let rid = RecordId(id);
+        let event = valori_kernel::event::KernelEvent::DeleteRecord { id: rid };
+        self.commit_and_apply_ns(&event, valori_kernel::types::id::DEFAULT_NS.0)
+    }
 
-        let (corpus, total_tokens) = self.reranker.snapshot_corpus();
-        let bcrp_buf =
-            bincode::serde::encode_to_vec(&(corpus, total_tokens), bincode::config::standard())
-                .map_err(|e| EngineError::InvalidInput(e.to_string()))?;
-        buffer.extend_from_slice(b"BCRP");
-        buffer.extend_from_slice(&(bcrp_buf.len() as u32).to_le_bytes());
-        buffer.extend_from_slice(&bcrp_buf);
+    /// Delete many records of one collection as a single committed event
+    /// batch: one log write and one fsync, however many ids. Graph nodes
+    /// linked to the records are deleted in the same batch. Ids that are not
+    /// an active record of `namespace_id` (unknown, already deleted,
+    /// soft-deleted, or in another collection) are skipped and reported as
+    /// missing, and repeats count once. Nothing is committed when no id
+    /// matches.
+    pub fn delete_batch_ns(
+        &mut self,
+        ids: &[u32],
+        namespace_id: u16,
+        soft: bool,
+    ) -> Result<BatchDeleteReport, EngineError> {
+        use valori_kernel::event::KernelEvent;
+        use valori_kernel::types::id::NodeId;
+        let mut report = BatchDeleteReport::default();
+        let mut seen = std::collections::HashSet::new();
+        let mut events = Vec::new();
+        for &id in ids {
+            if !seen.insert(id) {
+                continue;
+            }
+            let active = self
+                .state
+                .get_record(RecordId(id))
+                .is_some_and(|r| r.is_active() && r.namespace_id == namespace_id);
+            if !active {
+                report.missing.push(id);
+                continue;
+            }
+            if let Some(node_id) = self.record_to_node.get(&id).copied() {
+                events.push(KernelEvent::DeleteNode {
+                    id: NodeId(node_id),
+                });
+            }
+            events.push(if soft {
+                KernelEvent::SoftDeleteRecord { id: RecordId(id) }
+            } else {
+                KernelEvent::DeleteRecord { id: RecordId(id) }
+            });
+            report.deleted.push(id);
+        }
+        if events.is_empty() {
+            return Ok(report);
+        }
 
-        Ok(buffer)
+        self.check_writable()?;
+        tracing::debug_span!("commit", namespace_id, events = events.len())
+            .in_scope(|| self.persistence.log_batch_ns(&events, namespace_id))?;
+        tracing::debug_span!("apply").in_scope(|| {
+            events
+                .iter()
+                .try_for_each(|event| self.ingest_event(event, namespace_id))
+        })?;
+        Ok(report)
     }
 
-    pub fn save_snapshot(&self, path: Option<&Path>) -> Result<PathBuf, EngineError> {
-        let target = path
-            .or(self.snapshot_path.as_deref())
-            .ok_or(EngineError::InvalidInput(
-                "No snapshot path configured".into(),
-            ))?;
-        let data = self.snapshot()?;
-        std::fs::write(target, data).map_err(|e| EngineError::InvalidInput(e.to_string()))?;
-        tracing::info!("Snapshot saved to {:?}", target);
-        Ok(target.to_path_buf())
-    }
+    /// Commit a list of operations as one event batch in `namespace_id`:
+    /// either all of them are committed, or none is. Returns, per
+    /// operation, the id it created or acted on (`None` for `SetMeta`).
+    ///
+    /// Ids of records, nodes and edges created by the transaction are
+    /// assigned up front, so later operations can refer to them with
+    /// [`TxRef::Op`]. The whole batch is applied to a copy of the state
+    /// before anything is logged. An operation that would fail, or a
+    /// capacity or `expected_version` check that does not hold, rejects the
+    /// transaction with the operation's index in the error.
+    pub fn commit_transaction_ns(
+        &mut self,
+        ops: &[TxOp],
+        namespace_id: u16,
+    ) -> Result<Vec<Option<u32>>, EngineError> {
+        self.check_writable()?;
+        let (events, results) = self.plan_transaction_ns(ops, namespace_id)?;
+        if events.is_empty() {
+            return Ok(results);
+        }
+        self.metadata_policy.check_events(&events)?;
+
+        tracing::debug_span!("commit", namespace_id, events = events.len())
+            .in_scope(|| self.persistence.log_batch_ns(&events, namespace_id))?;
+        tracing::debug_span!("apply").in_scope(|| {
+            events
+                .iter()
+                .try_for_each(|event| self.ingest_event(event, namespace_id))
+        })?;
 
-    pub fn restore(&mut self, data: &[u8]) -> Result<(), EngineError> {
-        if data.len() < 16 {
-            return Err(EngineError::InvalidInput("Buffer too small".into()));
+        let now = Self::now_unix();
+        let mut meta_changed = false;
+        for (op, id) in ops.iter().zip(&results) {
+            match (op, id) {
+                (TxOp::Insert { .. }, Some(id)) => {
+                    self.created_at.insert(*id, now);
+                }
+                (TxOp::SetMeta { .. }, _) => meta_changed = true,
+                _ => {}
+            }
         }
-        if &data[0..4] != b"VAL1" {
-            return Err(EngineError::InvalidInput("Invalid magic bytes".into()));
+        self.auto_tier_check();
+        self.run_forget_policy_if_due();
+        if meta_changed {
+            self.flush_metadata()?;
         }
-        let mut offset = 4;
+        Ok(results)
+    }
 
-        let k_len = read_u32(data, &mut offset, "k_len")? as usize;
-        let k_data = slice_at(data, &mut offset, k_len, "k_data")?;
+    /// Dry run of [`Self::commit_transaction_ns`]: the same checks against
+    /// the current state, and the ids the transaction would get if it were
+    /// committed now. Nothing is logged or applied.
+    pub fn validate_transaction_ns(
+        &self,
+        ops: &[TxOp],
+        namespace_id: u16,
+    ) -> Result<Vec<Option<u32>>, EngineError> {
+        self.plan_transaction_ns(ops, namespace_id)
+            .map(|(_, results)| results)
+    }
 
-        let m_len = read_u32(data, &mut offset, "m_len")? as usize;
-        let m_data = slice_at(data, &mut offset, m_len, "m_data")?;
+    /// Turn `ops` into the events of one batch and shadow-apply them on a
+    /// copy of the state.
+    fn plan_transaction_ns(
+        &self,
+        ops: &[TxOp],
+        namespace_id: u16,
+    ) -> Result<(Vec<valori_kernel::event::KernelEvent>, Vec<Option<u32>>), EngineError> {
+        use valori_kernel::event::KernelEvent;
+        use valori_kernel::types::id::{EdgeId, NodeId};
+        let fail =
+            |i: usize, msg: String| EngineError::InvalidInput(format!("operation {i}: {msg}"));
+
+        let first_new_record = self.state.next_record_id().0;
+        let mut next_record = first_new_record;
+        let mut next_node = self.state.next_node_id().0;
+        let mut next_edge = self.state.next_edge_id().0;
+        let mut records = self.state.record_count();
+        let mut nodes = self.state.node_count();
+        let mut edges = self.state.edge_count();
+        // What each operation created, for `TxRef::Op`.
+        let mut created: Vec<Option<(TxObject, u32)>> = Vec::with_capacity(ops.len());
+        let mut results = Vec::with_capacity(ops.len());
+        let mut events = Vec::with_capacity(ops.len());
+        // Operation index of each event, to attribute shadow-apply errors.
+        let mut event_ops = Vec::with_capacity(ops.len());
+        let mut linked: HashMap<u32, u32> = HashMap::new();
+        let mut versions: HashMap<u32, u64> = HashMap::new();
+        let mut deleted_records = std::collections::HashSet::new();
+        let mut deleted_nodes = std::collections::HashSet::new();
+
+        for (i, op) in ops.iter().enumerate() {
+            let resolve = |r: &TxRef, want: TxObject| match *r {
+                TxRef::Id(id) => Ok(id),
+                TxRef::Op(j) => match created.get(j).copied().flatten() {
+                    Some((kind, id)) if kind == want => Ok(id),
+                    _ => Err(fail(
+                        i,
+                        format!("operation {j} is not an earlier {} create", want.name()),
+                    )),
+                },
+            };
+            let mut made = None;
+            let target = match op {
+                TxOp::Insert {
+                    values,
+                    metadata,
+                    tag,
+                } => {
+                    records += 1;
+                    if records > self.max_records {
+                        return Err(EngineError::Kernel(KernelError::CapacityExceeded));
+                    }
+                    let mut data = Vec::with_capacity(values.len());
+                    for &v in values {
+                        if !valori_protocol::fxp::in_range(v) {
+                            return Err(fail(
+                                i,
+                                "vector values must be between -32768.0 and 32767.99".to_string(),
+                            ));
+                        }
+                        data.push(FxpScalar((v * SCALE as f32) as i32));
+                    }
+                    let id = next_record;
+                    next_record += 1;
+                    events.push(KernelEvent::InsertRecord {
+                        id: RecordId(id),
+                        vector: FxpVector { data },
+                        metadata: metadata.clone(),
+                        tag: *tag,
+                    });
+                    versions.insert(id, 1);
+                    made = Some((TxObject::Record, id));
+                    Some(id)
+                }
+                TxOp::CreateNode { kind, record } => {
+                    nodes += 1;
+                    if nodes > self.max_nodes {
+                        return Err(EngineError::Kernel(KernelError::CapacityExceeded));
+                    }
+                    let record = record
+                        .as_ref()
+                        .map(|r| resolve(r, TxObject::Record))
+                        .transpose()?;
+                    let id = next_node;
+                    next_node += 1;
+                    events.push(KernelEvent::CreateNode {
+                        id: NodeId(id),
+                        kind: NodeKind::from_u8(*kind).unwrap_or_default(),
+                        record: record.map(RecordId),
+                    });
+                    if let Some(rid) = record {
+                        linked.insert(rid, id);
+                    }
+                    made = Some((TxObject::Node, id));
+                    Some(id)
+                }
+                TxOp::CreateEdge { from, to, kind } => {
+                    edges += 1;
+                    if edges > self.max_edges {
+                        return Err(EngineError::Kernel(KernelError::CapacityExceeded));
+                    }
+                    let id = next_edge;
+                    next_edge += 1;
+                    events.push(KernelEvent::CreateEdge {
+                        id: EdgeId(id),
+                        kind: EdgeKind::from_u8(*kind).unwrap_or_default(),
+                        from: NodeId(resolve(from, TxObject::Node)?),
+                        to: NodeId(resolve(to, TxObject::Node)?),
+                    });
+                    made = Some((TxObject::Edge, id));
+                    Some(id)
+                }
+                TxOp::UpdateMetadata {
+                    id,
+                    metadata,
+                    expected_version,
+                } => {
+                    let id = resolve(id, TxObject::Record)?;
+                    if deleted_records.contains(&id) {
+                        return Err(fail(i, format!("record {id} was deleted earlier")));
+                    }
+                    let current = match versions.get(&id) {
+                        Some(&v) => v,
+                        None => self
+                            .state
+                            .get_record(RecordId(id))
+                            .filter(|r| r.namespace_id == namespace_id)
+                            .map(|r| r.version)
+                            .ok_or_else(|| fail(i, format!("record {id} not found")))?,
+                    };
+                    if let Some(expected) = expected_version.filter(|&e| e != current) {
+                        return Err(EngineError::RecordVersionConflict {
+                            id,
+                            expected,
+                            current,
+                        });
+                    }
+                    versions.insert(id, current + 1);
+                    events.push(KernelEvent::UpdateRecordMetadata {
+                        id: RecordId(id),
+                        metadata: metadata.clone(),
+                    });
+                    Some(id)
+                }
+                TxOp::DeleteRecord { id, soft } => {
+                    let id = resolve(id, TxObject::Record)?;
+                    let active = id >= first_new_record
+                        || self
+                            .state
+                            .get_record(RecordId(id))
+                            .is_some_and(|r| r.is_active() && r.namespace_id == namespace_id);
+                    if !active || !deleted_records.insert(id) {
+                        return Err(fail(i, format!("record {id} is not an active record")));
+                    }
+                    let node = linked
+                        .get(&id)
+                        .or_else(|| self.record_to_node.get(&id))
+                        .copied();
+                    if let Some(node) = node.filter(|n| deleted_nodes.insert(*n)) {
+                        events.push(KernelEvent::DeleteNode { id: NodeId(node) });
+                        event_ops.push(i);
+                    }
+                    events.push(if *soft {
+                        KernelEvent::SoftDeleteRecord { id: RecordId(id) }
+                    } else {
+                        KernelEvent::DeleteRecord { id: RecordId(id) }
+                    });
+                    versions.remove(&id);
+                    Some(id)
+                }
+                TxOp::DeleteNode { id } => {
+                    let id = resolve(id, TxObject::Node)?;
+                    deleted_nodes.insert(id);
+                    events.push(KernelEvent::DeleteNode { id: NodeId(id) });
+                    Some(id)
+                }
+                TxOp::DeleteEdge { id } => {
+                    let id = resolve(id, TxObject::Edge)?;
+                    events.push(KernelEvent::DeleteEdge { id: EdgeId(id) });
+                    Some(id)
+                }
+                TxOp::SetMeta { key, value } => {
+                    let value = value.to_string();
+                    Self::check_meta_limits(&self.state.meta, key, &value)?;
+                    events.push(KernelEvent::SetMeta {
+                        key: key.clone(),
+                        value,
+                    });
+                    None
+                }
+            };
+            event_ops.push(i);
+            created.push(made);
+            results.push(target);
+        }
+        if events.is_empty() {
+            return Ok((events, results));
+        }
 
-        let i_len = read_u32(data, &mut offset, "i_len")? as usize;
-        let i_data = if offset + i_len <= data.len() {
-            Some(&data[offset..offset + i_len])
-        } else {
-            None
-        };
-        offset += i_len;
+        let mut shadow = self.state.clone();
+        for (event, &i) in events.iter().zip(&event_ops) {
+            shadow
+                .apply_event_ns(event, namespace_id)
+                .map_err(|e| fail(i, e.to_string()))?;
+        }
+        Ok((events, results))
+    }
 
-        let ns_registry: Option<CollectionRegistry> = if offset + 4 <= data.len()
-            && &data[offset..offset + 4] == b"NSRG"
+    /// Commit new record / node / edge limits as a `ResizePools` event, so
+    /// replicas and replays grow (or shrink) at the same height. Limits below
+    /// a pool's live count are rejected.
+    pub fn resize_pools(
+        &mut self,
+        records: usize,
+        nodes: usize,
+        edges: usize,
+    ) -> Result<(), EngineError> {
+        if records < self.state.record_count()
+            || nodes < self.state.node_count()
+            || edges < self.state.edge_count()
         {
-            offset += 4;
-            let ns_len = read_u32(data, &mut offset, "ns_len")? as usize;
-            let ns_json = slice_at(data, &mut offset, ns_len, "ns_data")?;
-            Some(
-                serde_json::from_slice(ns_json)
-                    .map_err(|e| EngineError::InvalidInput(format!("ns registry decode: {e}")))?,
-            )
-        } else {
-            None
+            return Err(EngineError::InvalidInput(
+                "pool limits cannot go below the live record/node/edge counts".to_string(),
+            ));
+        }
+        let limit = |n: usize| u32::try_from(n).unwrap_or(u32::MAX);
+        let event = valori_kernel::event::KernelEvent::ResizePools {
+            records: limit(records),
+            nodes: limit(nodes),
+            edges: limit(edges),
         };
+        self.commit_and_apply_ns(&event, valori_kernel::types::id::DEFAULT_NS.0)
+    }
 
-        self.restore_from_components(k_data, m_data, i_data, ns_registry)?;
-        self.restore_trailing_sections(data, offset);
-        Ok(())
+    /// Re-pack the record pool into its lowest slots with one `Vacuum`
+    /// event carrying the old→new id of every record that moves. Hard-deleted
+    /// gaps and soft-deleted records no graph node links to are reclaimed.
+    /// Nothing is committed when there is nothing to reclaim.
+    pub fn vacuum(&mut self) -> Result<VacuumReport, EngineError> {
+        let reclaimed = self.state.reclaimable_record_slots();
+        if reclaimed == 0 {
+            return Ok(VacuumReport::default());
+        }
+        let moves = self.state.vacuum_moves()?;
+        let event = valori_kernel::event::KernelEvent::Vacuum {
+            moves: moves.clone(),
+        };
+        self.commit_and_apply_ns(&event, valori_kernel::types::id::DEFAULT_NS.0)?;
+        Ok(VacuumReport {
+            reclaimed,
+            moves: moves.into_iter().map(|(o, n)| (o.0, n.0)).collect(),
+        })
     }
 
-    // ── Mutations ─────────────────────────────────────────────────────────────
+    /// Label a point on the timeline with an `Annotate` admin entry at
+    /// `height` (default: the committed height). The label is durable and
+    /// chained in the event log but changes no state. Returns the height.
+    pub fn annotate(
+        &mut self,
+        height: Option<u64>,
+        label: &str,
+        authorized_by: [u8; 16],
+    ) -> Result<u64, EngineError> {
+        let label = label.trim();
+        if label.is_empty() || label.chars().count() > MAX_ANNOTATION_LEN {
+            return Err(EngineError::InvalidInput(format!(
+                "label must be 1..={MAX_ANNOTATION_LEN} characters"
+            )));
+        }
+        let committer = self.persistence.event_committer_mut().ok_or_else(|| {
+            EngineError::InvalidInput("annotations need an event log".to_string())
+        })?;
+        let committed = committer.journal().committed_height();
+        let height = height.unwrap_or(committed);
+        if height > committed {
+            return Err(EngineError::InvalidInput(format!(
+                "height {height} is past the committed height {committed}"
+            )));
+        }
+        committer
+            .append_admin(valori_storage::events::event_log::AdminEvent::Annotate {
+                height,
+                label: label.to_string(),
+                authorized_by,
+            })
+            .map_err(|e| EngineError::InvalidInput(format!("event log append: {e}")))?;
+        Ok(height)
+    }
 
-    pub fn soft_delete_record(&mut self, id: u32) -> Result<(), EngineError> {
-        if let Some(node_id) = self.record_to_node.get(&id).copied() {
-            self.delete_node(node_id)?;
+    /// Start a new leader epoch, one above the last this node led or
+    /// followed, at the committed height. Called when the node starts as
+    /// leader; a promoted follower therefore outranks its old leader.
+    pub fn claim_leader_epoch(&mut self) -> Result<crate::epoch::LeaderEpoch, EngineError> {
+        let epoch = crate::epoch::LeaderEpoch {
+            epoch: self.leader_epoch.epoch + 1,
+            start_height: self
+                .event_committer()
+                .map(|c| c.journal().committed_height())
+                .unwrap_or(0),
+        };
+        if let Some(committer) = self.persistence.event_committer_mut() {
+            committer
+                .append_admin(valori_storage::events::event_log::AdminEvent::LeaderEpoch {
+                    epoch: epoch.epoch,
+                    start_height: epoch.start_height,
+                })
+                .map_err(|e| EngineError::InvalidInput(format!("event log append: {e}")))?;
         }
-        let rid = RecordId(id);
-        let event = valori_kernel::event::KernelEvent::SoftDeleteRecord { id: rid };
-        self.commit_and_apply_ns(&event, valori_kernel::types::id::DEFAULT_NS.0)?;
-        self.reranker.remove(id as u64);
-        self.created_at.remove(&id);
-        Ok(())
+        self.record_leader_epoch(epoch)?;
+        Ok(epoch)
     }
 
-    pub fn update_record_metadata(
+    /// Check the epoch a leader announced in the replication handshake
+    /// before replicating from it (see [`crate::epoch::check_leader`]). A
+    /// newer epoch this node is a prefix of is adopted — recorded in the
+    /// epoch file only; the leader's chain entry arrives with the stream. A forked log fences
+    /// the node and returns [`EngineError::Fenced`]; a stale leader is an
+    /// `InvalidInput` error and changes nothing.
+    pub fn follow_leader_epoch(
         &mut self,
-        id: u32,
-        metadata: Option<Vec<u8>>,
-        namespace_id: u16,
-    ) -> Result<(), EngineError> {
-        let rid = RecordId(id);
-        let event = valori_kernel::event::KernelEvent::UpdateRecordMetadata { id: rid, metadata };
-        self.commit_and_apply_ns(&event, namespace_id)
+        leader: crate::epoch::LeaderEpoch,
+    ) -> Result<crate::epoch::EpochCheck, EngineError> {
+        use crate::epoch::{check_leader, EpochCheck};
+        let height = self
+            .event_committer()
+            .map(|c| c.journal().committed_height())
+            .unwrap_or(0);
+        let check = check_leader(self.leader_epoch, height, leader);
+        match check {
+            EpochCheck::Current => {}
+            EpochCheck::Adopt => self.record_leader_epoch(leader)?,
+            EpochCheck::StaleLeader => {
+                return Err(EngineError::InvalidInput(format!(
+                    "leader is at epoch {}, but this node has seen epoch {} — it was superseded",
+                    leader.epoch, self.leader_epoch.epoch
+                )));
+            }
+            EpochCheck::Forked => {
+                let reason = format!(
+                    "split brain: epoch {} started at height {}, but this node holds {} events \
+                     written under epoch {}",
+                    leader.epoch, leader.start_height, height, self.leader_epoch.epoch
+                );
+                self.fenced = Some(reason.clone());
+                return Err(EngineError::Fenced(reason));
+            }
+        }
+        Ok(check)
     }
 
-    pub fn delete_record(&mut self, id: u32) -> Result<(), EngineError> {
-        if let Some(node_id) = self.record_to_node.get(&id).copied() {
-            self.delete_node(node_id)?;
+    /// Fence this node if `seen` — an epoch another node reported in the
+    /// replication handshake — is newer than its own: it was superseded as
+    /// leader and must stop taking writes. Returns whether it is fenced.
+    pub fn fence_if_superseded(&mut self, seen: u64) -> bool {
+        if seen > self.leader_epoch.epoch && self.fenced.is_none() {
+            self.fenced = Some(format!(
+                "superseded as leader: epoch {} is newer than this node's epoch {}",
+                seen, self.leader_epoch.epoch
+            ));
         }
-        let rid = RecordId(id);
-        let event = valori_kernel::event::KernelEvent::DeleteRecord { id: rid };
-        self.commit_and_apply_ns(&event, valori_kernel::types::id::DEFAULT_NS.0)?;
-        self.created_at.remove(&id);
+        self.fenced.is_some()
+    }
+
+    /// Make `epoch` current and write it to the epoch file.
+    fn record_leader_epoch(&mut self, epoch: crate::epoch::LeaderEpoch) -> Result<(), EngineError> {
+        if let Some(path) = &self.epoch_path {
+            crate::epoch::save_epoch(path, &epoch)
+                .map_err(|e| EngineError::InvalidInput(format!("epoch file: {e}")))?;
+        }
+        self.leader_epoch = epoch;
         Ok(())
     }
 
@@ -1167,9 +3232,9 @@ impl Engine {
         if self.state.edge_count() >= self.max_edges {
             return Err(EngineError::Kernel(KernelError::CapacityExceeded));
         }
-        use valori_kernel::types::id::{EdgeId, NodeId};
+        use valori_kernel::types::id::NodeId;
         let kind = EdgeKind::from_u8(kind).unwrap_or_default();
-        let edge_id = EdgeId(self.state.edge_count() as u32);
+        let edge_id = self.state.next_edge_id();
         let event = valori_kernel::event::KernelEvent::CreateEdge {
             id: edge_id,
             kind,
@@ -1180,9 +3245,30 @@ impl Engine {
         Ok(edge_id.0)
     }
 
-    pub fn get_proof(&self) -> valori_kernel::proof::DeterministicProof {
+    /// Merkle root of the kernel state, maintained per event in O(log N)
+    /// (`valori_kernel::state::merkle`).
+    pub fn state_root(&self) -> [u8; 32] {
+        self.state.state_root()
+    }
+
+    /// Canonical BLAKE3 state hash, memoised against the state root: only
+    /// the first proof after a change pays the O(N) re-hash.
+    pub fn canonical_state_hash(&self) -> [u8; 32] {
         use valori_kernel::snapshot::blake3::hash_state_blake3;
-        let final_state_hash = hash_state_blake3(&self.state);
+        let root = self.state_root();
+        let mut cache = self.proof_cache.lock().unwrap_or_else(|e| e.into_inner());
+        match *cache {
+            Some((r, hash)) if r == root => hash,
+            _ => {
+                let hash = hash_state_blake3(&self.state);
+                *cache = Some((root, hash));
+                hash
+            }
+        }
+    }
+
+    pub fn get_proof(&self) -> valori_kernel::proof::DeterministicProof {
+        let final_state_hash = self.canonical_state_hash();
         valori_kernel::proof::DeterministicProof {
             kernel_version: 1,
             snapshot_hash: [0u8; 32],
@@ -1193,64 +3279,188 @@ impl Engine {
 
     // ── Event application ─────────────────────────────────────────────────────
 
-    pub fn apply_committed_event(
+    /// Apply one committed event: the kernel state, then everything the
+    /// engine derives from it — the search indexes, the metadata store, the
+    /// record → node map, the reranker corpus and the per-record stats.
+    ///
+    /// Every committed event goes through here, whoever logged it: local
+    /// writes, batches and transactions after their commit, replication,
+    /// point-in-time restore. The event must already be durable; nothing is
+    /// logged.
+    pub fn ingest_event(
         &mut self,
         event: &valori_kernel::event::KernelEvent,
+        namespace_id: u16,
     ) -> Result<(), EngineError> {
         use valori_kernel::event::KernelEvent;
-        if let KernelEvent::DeleteNode { id } = event {
-            if let Some(node) = self.state.get_node(*id) {
-                if let Some(rid) = node.record {
+        // What the event removes has to be read before it is gone.
+        let mut dropped = Vec::new();
+        match event {
+            KernelEvent::DeleteNode { id } => {
+                if let Some(rid) = self.state.get_node(*id).and_then(|n| n.record) {
                     self.record_to_node.remove(&rid.0);
                 }
             }
+            KernelEvent::DropNamespace { .. } => {
+                dropped = self
+                    .state
+                    .iter_records_in_ns(namespace_id)
+                    .map(|r| r.id.0)
+                    .collect();
+            }
+            _ => {}
         }
-        self.state.apply_event(event)?;
-        self.post_apply_derived(event);
+        self.state.apply_event_ns(event, namespace_id)?;
+        self.post_apply_derived(event, &dropped);
         Ok(())
     }
 
+    /// [`Engine::ingest_event`] in the default namespace.
+    pub fn apply_committed_event(
+        &mut self,
+        event: &valori_kernel::event::KernelEvent,
+    ) -> Result<(), EngineError> {
+        self.ingest_event(event, valori_kernel::types::id::DEFAULT_NS.0)
+    }
+
+    /// Same as [`Engine::ingest_event`].
     pub fn apply_committed_event_ns(
         &mut self,
         event: &valori_kernel::event::KernelEvent,
         namespace_id: u16,
     ) -> Result<(), EngineError> {
-        use valori_kernel::event::KernelEvent;
-        if let KernelEvent::DeleteNode { id } = event {
-            if let Some(node) = self.state.get_node(*id) {
-                if let Some(rid) = node.record {
-                    self.record_to_node.remove(&rid.0);
-                }
-            }
-        }
-        self.state.apply_event_ns(event, namespace_id)?;
-        self.post_apply_derived(event);
-        Ok(())
+        self.ingest_event(event, namespace_id)
     }
 
-    fn post_apply_derived(&mut self, event: &valori_kernel::event::KernelEvent) {
+    /// Host-side follow-up of an applied event. `dropped` holds the records
+    /// a `DropNamespace` removed.
+    fn post_apply_derived(&mut self, event: &valori_kernel::event::KernelEvent, dropped: &[u32]) {
         use valori_kernel::event::KernelEvent;
         match event {
             KernelEvent::InsertRecord { id, vector, .. } => {
+                self.access_stats
+                    .get_mut()
+                    .unwrap_or_else(|e| e.into_inner())
+                    .remove(&id.0);
+                if let Some(hashes) = &mut self.vector_hashes {
+                    hashes
+                        .entry(vector_hash(vector.as_slice()))
+                        .or_default()
+                        .push(id.0);
+                }
+                if self.bulk_loading {
+                    return;
+                }
+                if let Some(k) = self.anomaly_k {
+                    match self.anomaly_score(vector, k) {
+                        Some(score) => self.anomaly_scores.insert(id.0, score),
+                        None => self.anomaly_scores.remove(&id.0),
+                    };
+                }
                 let vals: Vec<f32> = vector
                     .data
                     .iter()
                     .map(|fxp| fxp.0 as f32 / SCALE as f32)
                     .collect();
                 self.index.insert(id.0, &vals);
+                for idx in self.named_indexes.values_mut() {
+                    idx.insert(id.0, &vals);
+                }
             }
             KernelEvent::DeleteRecord { id } | KernelEvent::SoftDeleteRecord { id } => {
-                self.index.delete(id.0);
+                self.forget_derived(&[id.0]);
             }
+            KernelEvent::DropNamespace { .. } => self.forget_derived(dropped),
             KernelEvent::CreateNode { id, record, .. } => {
                 if let Some(rid) = record {
                     self.record_to_node.insert(rid.0, id.0);
                 }
             }
+            KernelEvent::ResizePools { .. } => self.sync_pool_limits(),
+            KernelEvent::Vacuum { moves } => self.remap_after_vacuum(moves),
+            KernelEvent::SetMeta { .. } | KernelEvent::DeleteMeta { .. } => {
+                self.apply_meta_event_to_store(event)
+            }
             _ => {}
         }
     }
 
+    /// Drop removed records from the indexes, the reranker corpus and the
+    /// per-record maps.
+    fn forget_derived(&mut self, ids: &[u32]) {
+        let access_stats = self
+            .access_stats
+            .get_mut()
+            .unwrap_or_else(|e| e.into_inner());
+        for &id in ids {
+            self.index.delete(id);
+            for idx in self.named_indexes.values_mut() {
+                idx.delete(id);
+            }
+            self.anomaly_scores.remove(&id);
+            access_stats.remove(&id);
+            self.created_at.remove(&id);
+            self.reranker.remove(id as u64);
+        }
+    }
+
+    /// Follow a committed [`KernelEvent::Vacuum`]: re-key every record-id map
+    /// to the new ids, drop entries of reclaimed slots, and rebuild the
+    /// indexes (deferred to [`Engine::finish_bulk_load`] during a bulk load).
+    fn remap_after_vacuum(
+        &mut self,
+        moves: &[(
+            valori_kernel::types::id::RecordId,
+            valori_kernel::types::id::RecordId,
+        )],
+    ) {
+        let moved: HashMap<u32, u32> = moves.iter().map(|(o, n)| (o.0, n.0)).collect();
+        let taken: std::collections::HashSet<u32> = moved.values().copied().collect();
+        let state = &self.state;
+        // An unmoved id kept its slot if a record is still there that did
+        // not move in from elsewhere; otherwise it was reclaimed.
+        let remap = |id: u32| match moved.get(&id) {
+            Some(&new) => Some(new),
+            None if !taken.contains(&id) && state.get_record(RecordId(id)).is_some() => Some(id),
+            None => None,
+        };
+        fn rekey<V>(map: &mut HashMap<u32, V>, remap: impl Fn(u32) -> Option<u32>) {
+            *map = map
+                .drain()
+                .filter_map(|(id, v)| Some((remap(id)?, v)))
+                .collect();
+        }
+        rekey(&mut self.created_at, remap);
+        rekey(&mut self.anomaly_scores, remap);
+        rekey(
+            self.access_stats
+                .get_mut()
+                .unwrap_or_else(|e| e.into_inner()),
+            remap,
+        );
+        self.batch_seen.retain(|_, id| match remap(*id) {
+            Some(new) => {
+                *id = new;
+                true
+            }
+            None => false,
+        });
+        self.metadata.remap_records(remap);
+        let (corpus, total_tokens) = self.reranker.snapshot_corpus();
+        let corpus = corpus
+            .iter()
+            .filter_map(|(&id, tokens)| Some((remap(id as u32)? as u64, tokens.clone())))
+            .collect();
+        self.reranker.restore_corpus(corpus, total_tokens);
+        self.rebuild_record_to_node();
+
+        if !self.bulk_loading {
+            self.current_effective_kind = self.effective_index_kind();
+            self.rebuild_index();
+            self.rebuild_named_indexes();
+        }
+    }
+
     // ── Tree cache ────────────────────────────────────────────────────────────
 
     pub fn cache_tree(&mut self, text: &str, tree: valori_rag::tree::TreeIndex) -> String {
@@ -1317,6 +3527,14 @@ impl Engine {
         self.state.get_record(id)
     }
 
+    /// A record's vector; see [`KernelState::record_vector`].
+    pub fn record_vector<'a>(
+        &'a self,
+        record: &'a valori_kernel::storage::record::Record,
+    ) -> &'a [FxpScalar] {
+        self.state.record_vector(record)
+    }
+
     pub fn get_edge(
         &self,
         id: valori_kernel::types::id::EdgeId,
@@ -1332,8 +3550,18 @@ impl Engine {
         if !rec_a.is_searchable() || !rec_b.is_searchable() {
             return None;
         }
-        let va: Vec<i32> = rec_a.vector.data.iter().map(|s| s.0).collect();
-        let vb: Vec<i32> = rec_b.vector.data.iter().map(|s| s.0).collect();
+        let va: Vec<i32> = self
+            .state
+            .record_vector(rec_a)
+            .iter()
+            .map(|s| s.0)
+            .collect();
+        let vb: Vec<i32> = self
+            .state
+            .record_vector(rec_b)
+            .iter()
+            .map(|s| s.0)
+            .collect();
         let dot = dot_product(&va, &vb) as f64;
         let mag_a = (dot_product(&va, &va) as f64).sqrt();
         let mag_b = (dot_product(&vb, &vb) as f64).sqrt();
@@ -1346,6 +3574,12 @@ impl Engine {
     // ── Index management ──────────────────────────────────────────────────────
 
     pub fn build_index(&mut self) {
+        let records = self.indexable_records();
+        self.index.build(&records);
+    }
+
+    /// Searchable records as `(id, f32 vector)`, in id order.
+    fn indexable_records(&self) -> Vec<(u32, Vec<f32>)> {
         let total_slots = self.state.total_record_slots();
         let mut records: Vec<(u32, Vec<f32>)> = Vec::with_capacity(total_slots);
         for i in 0..total_slots {
@@ -1353,21 +3587,215 @@ impl Engine {
                 if !record.is_searchable() {
                     continue;
                 }
-                let vals: Vec<f32> = record
-                    .vector
-                    .data
+                let vals: Vec<f32> = self
+                    .state
+                    .record_vector(record)
                     .iter()
                     .map(|fxp| fxp.0 as f32 / SCALE as f32)
                     .collect();
                 records.push((i as u32, vals));
             }
         }
-        self.index.build(&records);
+        records
+    }
+
+    /// Rebuild every named index from the record pool.
+    pub fn rebuild_named_indexes(&mut self) {
+        let names: Vec<String> = self.named_indexes.keys().cloned().collect();
+        self.rebuild_named(&names);
+    }
+
+    fn rebuild_named(&mut self, names: &[String]) {
+        if names.is_empty() {
+            return;
+        }
+        let records = self.indexable_records();
+        for name in names {
+            let Some(kind) = IndexKind::from_name(name) else {
+                continue;
+            };
+            let mut idx = self.blank_index(kind);
+            idx.build(&records);
+            self.named_indexes.insert(name.clone(), idx);
+        }
+    }
+
+    /// Query-time HNSW beam width for the live indexes and any rebuilt later.
+    /// Not logged: it changes recall and latency, never state.
+    pub fn set_ef_search(&mut self, ef: usize) {
+        self.hnsw_config.ef_search = ef;
+        self.index.set_ef_search(ef);
+        for idx in self.named_indexes.values_mut() {
+            idx.set_ef_search(ef);
+        }
+    }
+
+    /// One bounded step of background graph optimization on the primary
+    /// index, resuming where the previous step stopped and wrapping to the
+    /// start after a full pass. `None` when the index has no graph to
+    /// optimize or a bulk load is pending. Not logged — like
+    /// [`Engine::set_ef_search`], it changes recall, never state.
+    pub fn optimize_index_step(&mut self, budget: usize) -> Option<valori_index::OptimizeStep> {
+        if self.bulk_loading {
+            return None;
+        }
+        let step = self
+            .index
+            .optimize_step(self.index_optimize_cursor, budget)?;
+        self.index_optimize_cursor = step.next_cursor.unwrap_or(0);
+        Some(step)
+    }
+
+    /// Check the graph of every index that has one — the primary under its
+    /// effective kind name, then the named indexes — for orphaned links, a
+    /// stale entry point and unreachable nodes, and with `apply` repair
+    /// them. Indexes without a graph are left out. Not logged: a repair
+    /// changes recall, never state.
+    pub fn repair_indexes(
+        &mut self,
+        apply: bool,
+    ) -> Result<BTreeMap<String, valori_index::GraphRepair>, EngineError> {
+        if self.bulk_loading {
+            return Err(EngineError::BulkLoadInProgress);
+        }
+        let mut reports = BTreeMap::new();
+        if let Some(report) = self.index.repair(apply) {
+            reports.insert(self.effective_index_kind().name().to_string(), report);
+        }
+        for (name, idx) in self.named_indexes.iter_mut() {
+            if let Some(report) = idx.repair(apply) {
+                reports.insert(name.clone(), report);
+            }
+        }
+        Ok(reports)
+    }
+
+    /// Measure recall@`k` of an index against exact search. Up to `sample`
+    /// searchable records, evenly spaced by id, each serve as a query. The
+    /// index's top `k` is compared with the exact top `k` by L2 over every
+    /// searchable record. The query record itself is left out of both. A
+    /// hit counts when its exact distance is within the k-th true distance,
+    /// so ties at the boundary are not misses. Read-only and deterministic
+    /// for a given state and graph.
+    pub fn estimate_recall(
+        &self,
+        sample: usize,
+        k: usize,
+        index: Option<&str>,
+    ) -> Result<RecallReport, EngineError> {
+        if k == 0 {
+            return Err(EngineError::InvalidInput("k must be at least 1".into()));
+        }
+        if self.bulk_loading {
+            return Err(EngineError::BulkLoadInProgress);
+        }
+        let selected = self.named_index(index)?;
+        let name = match index {
+            Some(n) if selected.is_some() => n.to_string(),
+            _ => self.effective_index_kind().name().to_string(),
+        };
+        let idx = selected.unwrap_or(self.index.as_ref());
+
+        let records = self.indexable_records();
+        let n = records.len();
+        let sampled = sample.min(n);
+        let dist =
+            |a: &[f32], b: &[f32]| -> f32 { a.iter().zip(b).map(|(x, y)| (x - y) * (x - y)).sum() };
+
+        let (mut total, mut min_recall, mut imperfect) = (0.0f64, 1.0f64, 0usize);
+        for s in 0..sampled {
+            let (qid, query) = &records[s * n / sampled];
+            let mut exact: Vec<(f32, u32)> = records
+                .iter()
+                .filter(|(id, _)| id != qid)
+                .map(|(id, v)| (dist(query, v), *id))
+                .collect();
+            let want = k.min(exact.len());
+            if want == 0 {
+                total += 1.0;
+                continue;
+            }
+            exact.select_nth_unstable_by(want - 1, |a, b| a.0.total_cmp(&b.0).then(a.1.cmp(&b.1)));
+            let kth = exact[want - 1].0;
+            let bound = kth + kth.abs() * 1e-5;
+
+            let found = Self::index_search(&self.state, idx, query, k + 1, &|| false)
+                .unwrap_or_default()
+                .into_iter()
+                .filter(|(id, _)| id != qid)
+                .take(want)
+                .filter(|(id, _)| {
+                    self.state
+                        .get_record(RecordId(*id))
+                        .filter(|r| r.is_searchable())
+                        .is_some_and(|r| {
+                            let v: Vec<f32> = self
+                                .state
+                                .record_vector(r)
+                                .iter()
+                                .map(|f| f.0 as f32 / SCALE as f32)
+                                .collect();
+                            dist(query, &v) <= bound
+                        })
+                })
+                .count();
+            let recall = found as f64 / want as f64;
+            total += recall;
+            min_recall = min_recall.min(recall);
+            imperfect += (found < want) as usize;
+        }
+
+        Ok(RecallReport {
+            index: name,
+            k,
+            sampled,
+            recall: if sampled == 0 {
+                1.0
+            } else {
+                total / sampled as f64
+            },
+            min_recall,
+            imperfect,
+        })
     }
 
     pub fn rebuild_index(&mut self) {
-        let target = self.effective_index_kind();
-        let blank: Box<dyn VectorIndex + Send + Sync> = match target {
+        self.index = self.blank_index(self.effective_index_kind());
+        self.build_index();
+    }
+
+    /// Start a bulk load: from here on inserts are committed to the log and
+    /// stored in the kernel as usual, but skip the primary and named ANN
+    /// indexes (and anomaly scoring). Index-backed searches and snapshots
+    /// answer [`EngineError::BulkLoadInProgress`] until
+    /// [`Engine::finish_bulk_load`]. Not logged — recovery replays the same
+    /// events with indexing on, so nothing about the state depends on it.
+    pub fn begin_bulk_load(&mut self) -> Result<(), EngineError> {
+        if self.shutting_down {
+            return Err(EngineError::ShuttingDown);
+        }
+        self.bulk_loading = true;
+        Ok(())
+    }
+
+    /// End a bulk load by building every index once from the record pool,
+    /// in record-id order, exactly as recovery does. Returns the number of
+    /// records indexed.
+    pub fn finish_bulk_load(&mut self) -> Result<usize, EngineError> {
+        if !self.bulk_loading {
+            return Err(EngineError::InvalidInput(
+                "no bulk load in progress".to_string(),
+            ));
+        }
+        self.bulk_loading = false;
+        self.current_effective_kind = self.effective_index_kind();
+        self.rebuild_index();
+        self.rebuild_named_indexes();
+        Ok(self.index.ids().len())
+    }
+
+    fn blank_index(&self, kind: IndexKind) -> Box<dyn VectorIndex + Send + Sync> {
+        match kind {
             IndexKind::BruteForce | IndexKind::Auto => Box::new(BruteForceIndex::new()),
             IndexKind::Hnsw => {
                 use valori_index::HnswIndex;
@@ -1381,10 +3809,9 @@ impl Engine {
             IndexKind::Bq => {
                 use valori_index::BqIndex;
                 Box::new(BqIndex::new())
-            }
-        };
-        self.index = blank;
-        self.build_index();
+            }
+            IndexKind::Sq => Box::new(valori_index::SqIndex::new()),
+        }
     }
 
     pub fn effective_index_kind(&self) -> IndexKind {
@@ -1404,7 +3831,7 @@ impl Engine {
     }
 
     pub fn auto_tier_check(&mut self) {
-        if self.index_kind != IndexKind::Auto {
+        if self.index_kind != IndexKind::Auto || self.bulk_loading {
             return;
         }
         let target = self.effective_index_kind();
@@ -1419,14 +3846,182 @@ impl Engine {
 
     // ── Crash recovery ────────────────────────────────────────────────────────
 
+    /// Recover durable state, reporting progress to
+    /// [`Engine::recovery_progress`] for the whole attempt.
     pub fn try_recover(&mut self) -> RecoveryMode {
+        let progress = self.recovery_progress.clone();
+        progress.begin();
+        let mode = self.recover_inner(&progress);
+        progress.finish();
+        mode
+    }
+
+    /// Dry-run recovery: load what [`Engine::try_recover`] would from the
+    /// given event log, `snapshot_path` and WAL, in the same priority order,
+    /// but only ever read them. Call it on a throwaway engine built without an
+    /// event log or WAL writer; it ends up holding the recovered state.
+    pub fn verify_recovery(
+        &mut self,
+        event_log_path: Option<&Path>,
+        wal_path: Option<&Path>,
+    ) -> RecoveryVerification {
+        let mut anomalies = Vec::new();
+        let mut mode = None;
+
+        if let Some(log_path) = event_log_path.filter(|p| p.exists()) {
+            match valori_state::bootstrap::recover_from_events(log_path) {
+                Ok((_, _, 0)) => {}
+                Ok((state, _, count)) => {
+                    if let Some(log_dim) = state.dim.filter(|&d| d != self.dim) {
+                        anomalies.push(format!(
+                            "event log dimension {log_dim} differs from the configured {}",
+                            self.dim
+                        ));
+                    }
+                    self.state = state;
+                    self.state.track_state_root();
+                    mode = Some(RecoveryMode::EventLog(count));
+                }
+                Err(e) => anomalies.push(format!("event log {log_path:?}: {e}")),
+            }
+        }
+
+        let mut snapshot_recovered = false;
+        if let Some(path) = self.snapshot_path.clone().filter(|p| p.exists()) {
+            let loaded = std::fs::read(&path)
+                .map_err(|e| e.to_string())
+                .and_then(|data| {
+                    if mode.is_some() {
+                        // Only checked, so a bad snapshot is caught while the
+                        // event log still covers for it.
+                        self.scratch().restore(&data)
+                    } else {
+                        self.restore(&data)
+                    }
+                    .map_err(|e| e.to_string())?;
+                    Ok(data)
+                });
+            match loaded {
+                Ok(data) => {
+                    snapshot_recovered = mode.is_none();
+                    // A snapshot that loads can still fail the self-check.
+                    let check = crate::snapshot_check::check_snapshot(&data, event_log_path);
+                    anomalies.extend(
+                        check
+                            .failures
+                            .iter()
+                            .map(|f| format!("snapshot {path:?}: {f}")),
+                    );
+                }
+                Err(e) => anomalies.push(format!("snapshot {path:?}: {e}")),
+            }
+        }
+        if snapshot_recovered {
+            mode = Some(RecoveryMode::Snapshot);
+        }
+
+        if mode.is_none() {
+            if let Some(wal_path) = wal_path.filter(|p| p.exists()) {
+                match valori_state::bootstrap::replay_wal(&mut self.state, wal_path) {
+                    Ok((0, _)) => {}
+                    Ok((count, _)) => mode = Some(RecoveryMode::Wal(count)),
+                    Err(e) => anomalies.push(format!("WAL {wal_path:?}: {e}")),
+                }
+            }
+        }
+
+        let mode = mode.unwrap_or(RecoveryMode::Fresh);
+        if matches!(mode, RecoveryMode::EventLog(_) | RecoveryMode::Wal(_)) {
+            self.rebuild_index();
+            self.rebuild_named_indexes();
+            self.rebuild_record_to_node();
+            if let Err(e) = self.move_vectors_to_file() {
+                anomalies.push(format!("vector file: {e}"));
+            }
+        }
+        let report = self.check_consistency();
+        anomalies.extend(report.kernel);
+        anomalies.extend(report.record_to_node);
+        if !report.index_missing.is_empty() || !report.index_stale.is_empty() {
+            anomalies.push(format!(
+                "index: {} record(s) missing, {} stale",
+                report.index_missing.len(),
+                report.index_stale.len()
+            ));
+        }
+
+        RecoveryVerification {
+            mode,
+            state_hash: self.state_hash_hex(),
+            records: self.state.record_count(),
+            nodes: self.state.node_count(),
+            edges: self.state.edge_count(),
+            anomalies,
+        }
+    }
+
+    /// An empty in-memory engine with this one's shape, for checks that must
+    /// not disturb `self`.
+    fn scratch(&self) -> Engine {
+        Engine::with_config(EngineConfig {
+            dim: self.dim,
+            max_records: self.max_records,
+            max_nodes: self.max_nodes,
+            max_edges: self.max_edges,
+            index_kind: self.index_kind,
+            quantization_kind: self.quantization_kind,
+            extra_indexes: Vec::new(),
+            hnsw_m: None,
+            hnsw_ef_construction: None,
+            hnsw_ef_search: None,
+            ivf_n_list: None,
+            ivf_n_probe: None,
+            snapshot_path: None,
+            wal_path: None,
+            event_log_path: None,
+            event_log_rotation_bytes: None,
+            event_log_sync: SyncStrategy::default(),
+            quarantine_corrupt_segments: false,
+            vector_file_path: None,
+            decay_half_life_secs: None,
+            shard_count: self.shard_count,
+            anomaly_k: None,
+            anomaly_threshold: None,
+            forget_policy: None,
+            snapshot_check: Default::default(),
+            recovery_policy: Default::default(),
+            metadata_policy: self.metadata_policy,
+            metadata_index_fields: Vec::new(),
+            object_store_keep: 0,
+            object_store: None,
+            vault: self.vault.clone(),
+            embed_config: None,
+        })
+    }
+
+    fn recover_inner(&mut self, progress: &RecoveryProgress) -> RecoveryMode {
+        use crate::config::RecoveryPolicy;
         let log_info = self
             .event_committer()
             .map(|c| (c.event_log().path().to_path_buf(), c.event_log().dim()));
+        let log_path = log_info.as_ref().map(|(p, _)| p.clone());
+
+        // `Engine::with_config` already logged why the writer did not open.
+        if log_info.is_none() {
+            if let Some(path) = self.event_log_path.clone().filter(|p| p.exists()) {
+                if let Some(mode) =
+                    self.recovery_failed(format!("event log {path:?} could not be opened"))
+                {
+                    return mode;
+                }
+            }
+        }
 
         if let Some((log_path, dim)) = log_info {
             if log_path.exists() {
-                match valori_state::bootstrap::recover_from_events(&log_path) {
+                match valori_state::bootstrap::recover_from_events_with_progress(
+                    &log_path, progress,
+                ) {
                     Ok((recovered_state, recovered_journal, count)) => {
                         if count == 0 {
                             tracing::info!("Event log exists but is empty; trying snapshot");
@@ -1437,21 +4032,31 @@ impl Engine {
                                 log_path
                             );
                             self.persistence = Persistence::Ephemeral;
-                            match EventLogWriter::open(&log_path, Some(dim)) {
+                            match EventLogWriter::open_with_sync(
+                                &log_path,
+                                Some(dim),
+                                self.event_log_sync,
+                            ) {
                                 Ok(log_writer) => {
                                     let state_for_committer = recovered_state.clone();
                                     self.state = recovered_state;
+                                    self.state.track_state_root();
                                     self.persistence = Persistence::EventLog(EventCommitter::new(
                                         log_writer,
                                         recovered_journal,
                                         state_for_committer,
                                     ));
                                     self.rebuild_index();
+                                    self.rebuild_named_indexes();
                                     self.auto_tier_check();
                                     self.rebuild_record_to_node();
+                                    self.sync_pool_limits();
                                     self.load_metadata().ok();
-                                    self.sync_metadata_from_state();
+                                    self.replay_metadata_log(&log_path);
                                     self.load_namespaces().ok();
+                                    if let Err(mode) = self.recovered_vectors_to_file() {
+                                        return mode;
+                                    }
                                     return RecoveryMode::EventLog(count);
                                 }
                                 Err(e) => {
@@ -1459,31 +4064,67 @@ impl Engine {
                                         "Failed to reopen event log after recovery: {}",
                                         e
                                     );
+                                    if let Some(mode) = self.recovery_failed(format!(
+                                        "event log {log_path:?} could not be reopened: {e}"
+                                    )) {
+                                        return mode;
+                                    }
                                 }
                             }
                         }
                     }
                     Err(e) => {
                         tracing::error!("Event-log recovery failed ({:?}); trying snapshot", e);
+                        if let Some(mode) =
+                            self.recovery_failed(format!("event log {log_path:?}: {e}"))
+                        {
+                            return mode;
+                        }
                     }
                 }
             }
         }
 
+        if self.recovery_policy == RecoveryPolicy::RecoverFromEventLogOnly {
+            for path in [&self.snapshot_path, &self.wal_path].into_iter().flatten() {
+                if path.exists() {
+                    tracing::warn!("Recovery policy is event-log-only; ignoring {:?}", path);
+                }
+            }
+            self.load_namespaces().ok();
+            tracing::info!("No events on the log; starting from an empty store");
+            return RecoveryMode::Fresh;
+        }
+
         let mut snapshot_recovered = false;
         if let Some(path) = self.snapshot_path.clone() {
             if path.exists() {
-                match std::fs::read(&path) {
-                    Ok(data) => match self.restore(&data) {
-                        Ok(()) => {
-                            tracing::info!("Snapshot recovery succeeded from {:?}", path);
-                            snapshot_recovered = true;
+                let size = std::fs::metadata(&path).map_or(0, |m| m.len());
+                progress.add_bytes_total(size);
+                let loaded = std::fs::read(&path)
+                    .inspect(|_| progress.add_bytes_read(size))
+                    .map_err(|e| e.to_string())
+                    .and_then(|data| {
+                        // Only `Fallback` may be left holding half a restore,
+                        // so the others restore into a scratch engine first.
+                        if self.recovery_policy != RecoveryPolicy::Fallback {
+                            self.scratch().restore(&data).map_err(|e| e.to_string())?;
                         }
-                        Err(e) => {
-                            tracing::error!("Snapshot restore failed ({:?}); starting fresh", e)
+                        self.self_check_snapshot(&data, log_path.as_deref())
+                            .map_err(|e| e.to_string())
+                    });
+                match loaded {
+                    Ok(()) => {
+                        tracing::info!("Snapshot recovery succeeded from {:?}", path);
+                        snapshot_recovered = true;
+                    }
+                    Err(e) => {
+                        tracing::error!("Snapshot recovery from {:?} failed: {}", path, e);
+                        if let Some(mode) = self.recovery_failed(format!("snapshot {path:?}: {e}"))
+                        {
+                            return mode;
                         }
-                    },
-                    Err(e) => tracing::error!("Failed to read snapshot file {:?}: {}", path, e),
+                    }
                 }
             }
         }
@@ -1504,23 +4145,44 @@ impl Engine {
         if !snapshot_recovered {
             if let Some(wal_path) = self.wal_path.clone() {
                 if wal_path.exists() {
-                    match valori_state::bootstrap::replay_wal(&mut self.state, &wal_path) {
+                    let size = std::fs::metadata(&wal_path).map_or(0, |m| m.len());
+                    progress.add_bytes_total(size);
+                    // Replayed into a copy so a WAL that fails part-way
+                    // leaves the store empty rather than half-applied.
+                    let mut state = self.state.clone();
+                    let replayed = valori_state::bootstrap::replay_wal(&mut state, &wal_path);
+                    progress.add_bytes_read(size);
+                    match replayed {
                         Ok((count, _hasher)) if count > 0 => {
                             tracing::info!(
                                 "WAL recovery: replayed {} commands from {:?}",
                                 count,
                                 wal_path
                             );
+                            self.state = state;
+                            self.state.track_state_root();
                             self.rebuild_index();
+                            self.rebuild_named_indexes();
                             self.auto_tier_check();
                             self.rebuild_record_to_node();
+                            self.sync_pool_limits();
                             self.load_metadata().ok();
                             self.sync_metadata_from_state();
                             self.load_namespaces().ok();
+                            if let Err(mode) = self.recovered_vectors_to_file() {
+                                return mode;
+                            }
                             return RecoveryMode::Wal(count);
                         }
                         Ok(_) => {} // WAL exists but is empty — nothing to replay.
-                        Err(e) => tracing::error!("WAL replay failed ({:?})", e),
+                        Err(e) => {
+                            tracing::error!("WAL replay failed ({:?})", e);
+                            if let Some(mode) =
+                                self.recovery_failed(format!("WAL {wal_path:?}: {e}"))
+                            {
+                                return mode;
+                            }
+                        }
                     }
                 }
             }
@@ -1538,6 +4200,135 @@ impl Engine {
         RecoveryMode::Fresh
     }
 
+    /// Apply the [`RecoveryPolicy`](crate::RecoveryPolicy) to a recovery
+    /// source that failed. `None` means try the next source. Every caller
+    /// runs before anything is loaded into `self`, so the store is still
+    /// empty here.
+    fn recovery_failed(&mut self, reason: String) -> Option<RecoveryMode> {
+        use crate::config::RecoveryPolicy;
+        match self.recovery_policy {
+            RecoveryPolicy::Fallback => None,
+            RecoveryPolicy::FailClosed | RecoveryPolicy::RecoverFromEventLogOnly => {
+                tracing::error!(
+                    "Recovery refused under the {} policy; writes are disabled",
+                    self.recovery_policy.name()
+                );
+                self.recovery_refused = Some(reason.clone());
+                Some(RecoveryMode::Refused(reason))
+            }
+            RecoveryPolicy::StartEmptyAndQuarantine => {
+                let dir = self.quarantine_artifacts();
+                tracing::warn!(
+                    "Recovery failed ({reason}); artifacts moved to {:?}, starting empty",
+                    dir
+                );
+                Some(RecoveryMode::Quarantined(dir))
+            }
+        }
+    }
+
+    /// Move every persistence artifact into `quarantine/<unix secs>/` next
+    /// to the first configured one, then reopen empty persistence.
+    fn quarantine_artifacts(&mut self) -> PathBuf {
+        use valori_storage::events::event_replay::segment_paths;
+        use valori_storage::events::quarantine::damage_report_path;
+
+        let anchor = [&self.event_log_path, &self.snapshot_path, &self.wal_path]
+            .into_iter()
+            .flatten()
+            .next()
+            .cloned()
+            .unwrap_or_default();
+        let secs = std::time::SystemTime::now()
+            .duration_since(std::time::UNIX_EPOCH)
+            .map_or(0, |d| d.as_secs());
+        let dir = anchor
+            .parent()
+            .unwrap_or(Path::new("."))
+            .join("quarantine")
+            .join(secs.to_string());
+
+        // Drop the open writer before its file moves.
+        self.persistence = Persistence::Ephemeral;
+        let mut artifacts = Vec::new();
+        if let Some(log) = &self.event_log_path {
+            artifacts.extend(segment_paths(log));
+            artifacts.push(damage_report_path(log));
+        }
+        artifacts.extend(
+            [
+                &self.snapshot_path,
+                &self.wal_path,
+                &self.metadata_path,
+                &self.namespaces_path,
+            ]
+            .into_iter()
+            .flatten()
+            .cloned(),
+        );
+        if let Err(e) = std::fs::create_dir_all(&dir) {
+            tracing::error!("Cannot create quarantine directory {:?}: {}", dir, e);
+        }
+        for path in artifacts.iter().filter(|p| p.exists()) {
+            let Some(name) = path.file_name() else {
+                continue;
+            };
+            if let Err(e) = std::fs::rename(path, dir.join(name)) {
+                tracing::error!("Failed to quarantine {:?}: {}", path, e);
+            }
+        }
+        self.damage = None;
+
+        if let Some(log) = &self.event_log_path {
+            match EventLogWriter::open_with_sync(log, Some(self.dim as u32), self.event_log_sync) {
+                Ok(writer) => {
+                    self.persistence = Persistence::EventLog(EventCommitter::new(
+                        writer,
+                        EventJournal::new(),
+                        self.state.clone(),
+                    ))
+                }
+                Err(e) => tracing::error!("Failed to open a fresh event log: {}", e),
+            }
+        } else if let Some(wal) = &self.wal_path {
+            match valori_storage::wal_writer::WalWriter::open(wal, self.dim as u32) {
+                Ok(writer) => self.persistence = Persistence::Wal(writer),
+                Err(e) => tracing::error!("Failed to open a fresh WAL: {}", e),
+            }
+        }
+        dir
+    }
+
+    /// Run the startup self-check on snapshot `data` (unless the policy is
+    /// `Off`), record the outcome, then restore it. A failing snapshot is
+    /// still loaded so reads can be served; the policy decides about writes.
+    fn self_check_snapshot(
+        &mut self,
+        data: &[u8],
+        event_log: Option<&Path>,
+    ) -> Result<(), EngineError> {
+        use crate::snapshot_check::{check_snapshot, SnapshotCheckPolicy};
+        if self.snapshot_check_policy != SnapshotCheckPolicy::Off {
+            let check = check_snapshot(data, event_log);
+            if check.ok {
+                tracing::info!(
+                    height = check.height,
+                    replayed = check.replayed,
+                    "Snapshot self-check passed"
+                );
+            } else {
+                for failure in &check.failures {
+                    tracing::error!("Snapshot self-check: {failure}");
+                }
+                if self.snapshot_check_policy == SnapshotCheckPolicy::RefuseWrites {
+                    tracing::error!("Refusing writes until the snapshot is repaired");
+                }
+            }
+            self.snapshot_check = Some(check);
+        }
+        self.restore(data)
+    }
+
     fn restore_from_components(
         &mut self,
         k_data: &[u8],
@@ -1546,6 +4337,7 @@ impl Engine {
         ns_registry: Option<CollectionRegistry>,
     ) -> Result<(), EngineError> {
         self.state = decode_state(k_data)?;
+        self.state.track_state_root();
         if !m_data.is_empty() {
             self.metadata.restore(m_data);
         }
@@ -1559,25 +4351,23 @@ impl Engine {
         }
         self.auto_tier_check();
         self.rebuild_record_to_node();
+        self.move_vectors_to_file()?;
+        self.sync_pool_limits();
         if let Some(reg) = ns_registry {
             self.namespaces = reg;
         }
         Ok(())
     }
 
-    fn restore_trailing_sections(&mut self, data: &[u8], mut offset: usize) {
-        while offset + 8 <= data.len() {
-            let tag = &data[offset..offset + 4];
-            let section_len =
-                u32::from_le_bytes(data[offset + 4..offset + 8].try_into().unwrap_or([0; 4]))
-                    as usize;
-            offset += 8;
-            if offset + section_len > data.len() {
-                break;
-            }
-            let section = &data[offset..offset + section_len];
-            offset += section_len;
-
+    /// Restores the tagged sections other than the namespace registry and
+    /// returns the names of the named indexes restored from `IDXN` sections.
+    fn restore_trailing_sections(&mut self, container: &Container<'_>) -> Vec<String> {
+        let mut restored_indexes = Vec::new();
+        for &Section {
+            ref tag,
+            data: section,
+        } in &container.sections
+        {
             if tag == b"CRTS" {
                 if let Ok((map, _)) = bincode::serde::decode_from_slice::<HashMap<u32, u64>, _>(
                     section,
@@ -1585,6 +4375,31 @@ impl Engine {
                 ) {
                     self.created_at = map;
                 }
+            } else if tag == b"ACCS" {
+                if let Ok((map, _)) = bincode::serde::decode_from_slice::<
+                    HashMap<u32, RecordAccess>,
+                    _,
+                >(section, bincode::config::standard())
+                {
+                    *self
+                        .access_stats
+                        .get_mut()
+                        .unwrap_or_else(|e| e.into_inner()) = map;
+                }
+            } else if tag == b"IDXN" && section.len() >= 4 {
+                let name_len =
+                    u32::from_le_bytes(section[..4].try_into().unwrap_or([0; 4])) as usize;
+                let Some(name) = section
+                    .get(4..4 + name_len)
+                    .and_then(|b| std::str::from_utf8(b).ok())
+                else {
+                    continue;
+                };
+                if let Some(idx) = self.named_indexes.get_mut(name) {
+                    if idx.restore(&section[4 + name_len..]).is_ok() {
+                        restored_indexes.push(name.to_string());
+                    }
+                }
             } else if tag == b"BCRP" {
                 use std::collections::HashMap as StdMap;
                 if let Ok(((corpus, total_tokens), _)) =
@@ -1597,6 +4412,7 @@ impl Engine {
                 }
             }
         }
+        restored_indexes
     }
 }
 
@@ -1612,47 +4428,78 @@ impl Drop for Engine {
 
 // ── Helpers ───────────────────────────────────────────────────────────────────
 
-fn read_u32(data: &[u8], offset: &mut usize, field: &'static str) -> Result<u32, EngineError> {
-    if *offset + 4 > data.len() {
-        return Err(EngineError::InvalidInput(format!(
-            "Truncated snapshot: missing {field}"
-        )));
+fn pct(used: usize, capacity: usize) -> f64 {
+    if capacity == 0 {
+        0.0
+    } else {
+        used as f64 / capacity as f64 * 100.0
     }
-    let val = u32::from_le_bytes(
-        data[*offset..*offset + 4]
-            .try_into()
-            .map_err(|_| EngineError::InvalidInput(format!("Failed to read {field}")))?,
-    );
-    *offset += 4;
-    Ok(val)
 }
 
-fn slice_at<'a>(
-    data: &'a [u8],
-    offset: &mut usize,
-    len: usize,
-    field: &'static str,
-) -> Result<&'a [u8], EngineError> {
-    if *offset + len > data.len() {
+fn round1(v: f64) -> f64 {
+    (v * 10.0).round() / 10.0
+}
+
+/// Parse a snapshot container, refusing one whose `CRC3` trailer does not
+/// match. Containers without a trailer predate it and are accepted.
+fn parse_container(data: &[u8]) -> Result<Container<'_>, EngineError> {
+    let container =
+        Container::parse(data).map_err(|e| EngineError::InvalidInput(format!("snapshot: {e}")))?;
+    if let Checksum::Mismatch { stored, computed } = container.checksum {
         return Err(EngineError::InvalidInput(format!(
-            "Truncated snapshot: {field} out of bounds"
+            "snapshot: checksum mismatch (stored {stored:08x}, computed {computed:08x})"
         )));
     }
-    let s = &data[*offset..*offset + len];
-    *offset += len;
-    Ok(s)
+    Ok(container)
 }
 
-fn pct(used: usize, capacity: usize) -> f64 {
-    if capacity == 0 {
-        0.0
-    } else {
-        used as f64 / capacity as f64 * 100.0
+/// Key of a vector in [`Engine`]'s duplicate check. Equal vectors hash
+/// equal; candidates are still compared in full.
+fn vector_hash(vector: &[FxpScalar]) -> u64 {
+    use std::hash::Hasher;
+    let mut h = rustc_hash::FxHasher::default();
+    h.write_usize(vector.len());
+    for x in vector {
+        h.write_i32(x.0);
     }
+    h.finish()
 }
 
-fn round1(v: f64) -> f64 {
-    (v * 10.0).round() / 10.0
+fn fxp_vector(values: &[f32]) -> Result<FxpVector, EngineError> {
+    let mut data = Vec::with_capacity(values.len());
+    for &v in values {
+        if !valori_protocol::fxp::in_range(v) {
+            return Err(EngineError::InvalidInput(
+                "Vector values must be between -32768.0 and 32767.99".to_string(),
+            ));
+        }
+        data.push(FxpScalar((v * SCALE as f32) as i32));
+    }
+    Ok(FxpVector { data })
+}
+
+fn fxp_sparse(indices: &[u32], values: &[f32]) -> Result<SparseVector, EngineError> {
+    if indices.len() != values.len() {
+        return Err(EngineError::InvalidInput(
+            "indices and values must have the same length".to_string(),
+        ));
+    }
+    if indices.len() > MAX_SPARSE_NNZ {
+        return Err(EngineError::InvalidInput(format!(
+            "sparse vectors hold at most {MAX_SPARSE_NNZ} entries"
+        )));
+    }
+    let mut pairs = Vec::with_capacity(indices.len());
+    for (&i, &v) in indices.iter().zip(values) {
+        if !valori_protocol::fxp::in_range(v) {
+            return Err(EngineError::InvalidInput(
+                "Vector values must be between -32768.0 and 32767.99".to_string(),
+            ));
+        }
+        pairs.push((i, FxpScalar((v * SCALE as f32) as i32)));
+    }
+    SparseVector::from_pairs(pairs)
+        .map_err(|_| EngineError::InvalidInput("sparse indices must be unique".to_string()))
 }
 
 // ── Tests ─────────────────────────────────────────────────────────────────────
@@ -1696,8 +4543,19 @@ mod tests {
             wal_path: None,
             event_log_path: None,
             event_log_rotation_bytes: None,
+            event_log_sync: SyncStrategy::default(),
+            quarantine_corrupt_segments: false,
+            vector_file_path: None,
             decay_half_life_secs: None,
             shard_count: 1,
+            anomaly_k: None,
+            anomaly_threshold: None,
+            forget_policy: None,
+            snapshot_check: Default::default(),
+            recovery_policy: Default::default(),
+            metadata_policy: Default::default(),
+            metadata_index_fields: Vec::new(),
+            extra_indexes: Vec::new(),
             object_store_keep: 7,
             object_store: None,
             vault: Arc::new(NoopVault),
@@ -1714,6 +4572,354 @@ mod tests {
         assert_eq!(results[0].0, id);
     }
 
+    #[test]
+    fn sparse_vectors_rank_by_dot_product() {
+        let mut e = Engine::with_config(tiny_cfg());
+        e.create_collection("default").unwrap();
+        let ns = valori_kernel::types::id::DEFAULT_NS.0;
+        let a = e.insert_record_from_f32(&[1.0, 0.0, 0.0, 0.0]).unwrap();
+        let b = e.insert_record_from_f32(&[0.0, 1.0, 0.0, 0.0]).unwrap();
+        let c = e.insert_record_from_f32(&[0.0, 0.0, 1.0, 0.0]).unwrap();
+        e.set_sparse_vector(a, &[10, 500], &[0.5, 2.0], ns).unwrap();
+        e.set_sparse_vector(b, &[10, 77], &[3.0, 1.0], ns).unwrap();
+        e.set_sparse_vector(c, &[9], &[4.0], ns).unwrap();
+
+        let hits = e.search_sparse_ns(&[500, 10], &[1.0, 1.0], 10, ns).unwrap();
+        assert_eq!(hits, vec![(b, 3.0), (a, 2.5)]);
+
+        // An empty vector removes it; a deleted record takes its vector along.
+        e.set_sparse_vector(b, &[], &[], ns).unwrap();
+        e.delete_record(a).unwrap();
+        assert!(e
+            .search_sparse_ns(&[10], &[1.0], 10, ns)
+            .unwrap()
+            .is_empty());
+        assert_eq!(e.kernel_state().sparse_count(), 1);
+
+        assert!(matches!(
+            e.set_sparse_vector(c, &[1, 1], &[1.0, 2.0], ns),
+            Err(EngineError::InvalidInput(_))
+        ));
+        assert!(matches!(
+            e.set_sparse_vector(a, &[1], &[1.0], ns),
+            Err(EngineError::Kernel(KernelError::NotFound))
+        ));
+    }
+
+    #[test]
+    fn search_past_its_deadline_is_abandoned() {
+        let mut cfg = tiny_cfg();
+        cfg.extra_indexes = vec![IndexKind::Hnsw];
+        let mut e = Engine::with_config(cfg);
+        e.create_collection("default").unwrap();
+        e.insert_record_from_f32(&[1.0, 0.0, 0.0, 0.0]).unwrap();
+        let q = [1.0, 0.0, 0.0, 0.0];
+        let ns = valori_kernel::types::id::DEFAULT_NS.0;
+        let past = Some(std::time::Instant::now());
+        for index in [None, Some("hnsw")] {
+            assert!(matches!(
+                e.search_l2_index_until(&q, 1, ns, index, past),
+                Err(EngineError::DeadlineExceeded)
+            ));
+            let later = Some(std::time::Instant::now() + std::time::Duration::from_secs(60));
+            assert_eq!(
+                e.search_l2_index_until(&q, 1, ns, index, later)
+                    .unwrap()
+                    .len(),
+                1
+            );
+        }
+    }
+
+    #[test]
+    fn anomaly_score_is_distance_to_knn_centroid() {
+        let mut cfg = tiny_cfg();
+        cfg.anomaly_k = Some(2);
+        cfg.anomaly_threshold = Some(1.5);
+        let mut e = Engine::with_config(cfg);
+        e.create_collection("default").unwrap();
+        let first = e.insert_record_from_f32(&[1.0, 0.0, 0.0, 0.0]).unwrap();
+        e.insert_record_from_f32(&[-1.0, 0.0, 0.0, 0.0]).unwrap();
+        // Neighbour centroid is the origin.
+        let far = e.insert_record_from_f32(&[0.0, 2.0, 0.0, 0.0]).unwrap();
+        assert_eq!(e.record_anomaly(first), None);
+        assert_eq!(e.record_anomaly(far), Some((2.0, true)));
+        e.delete_record(far).unwrap();
+        assert_eq!(e.record_anomaly(far), None);
+    }
+
+    #[test]
+    fn resize_pools_lifts_capacity_and_survives_snapshot() {
+        let mut cfg = tiny_cfg();
+        cfg.max_records = 1;
+        let mut e = Engine::with_config(cfg);
+        e.create_collection("default").unwrap();
+        e.insert_record_from_f32(&[1.0, 0.0, 0.0, 0.0]).unwrap();
+        assert!(matches!(
+            e.insert_record_from_f32(&[0.0, 1.0, 0.0, 0.0]),
+            Err(EngineError::Kernel(KernelError::CapacityExceeded))
+        ));
+        assert!(e.resize_pools(0, 32, 64).is_err());
+
+        e.resize_pools(2, 32, 64).unwrap();
+        e.insert_record_from_f32(&[0.0, 1.0, 0.0, 0.0]).unwrap();
+        assert_eq!(e.max_records, 2);
+
+        let snap = e.snapshot().unwrap();
+        let mut e2 = Engine::with_config(tiny_cfg());
+        e2.restore(&snap).unwrap();
+        assert_eq!(e2.max_records, 2);
+    }
+
+    #[test]
+    fn named_indexes_track_writes_and_survive_snapshot() {
+        let mut cfg = tiny_cfg();
+        cfg.extra_indexes = vec![IndexKind::Hnsw, IndexKind::BruteForce, IndexKind::Auto];
+        let mut e = Engine::with_config(cfg);
+        assert_eq!(e.index_names(), vec!["brute_force", "hnsw"]);
+        e.create_collection("default").unwrap();
+        for i in 0..10 {
+            e.insert_record_from_f32(&[i as f32, 0.0, 0.0, 0.0])
+                .unwrap();
+        }
+        e.delete_record(3).unwrap();
+
+        let q = [3.1, 0.0, 0.0, 0.0];
+        let exact = e.search_l2_index(&q, 1, 0, Some("brute_force")).unwrap();
+        let hnsw = e.search_l2_index(&q, 1, 0, Some("hnsw")).unwrap();
+        assert_eq!(exact[0].0, 4);
+        assert_eq!(hnsw[0].0, 4);
+        assert!(e.search_l2_index(&q, 1, 0, Some("ivf")).is_err());
+
+        let snap = e.snapshot().unwrap();
+        let mut cfg = tiny_cfg();
+        cfg.extra_indexes = vec![IndexKind::Hnsw];
+        let mut e2 = Engine::with_config(cfg);
+        e2.restore(&snap).unwrap();
+        assert_eq!(
+            e2.named_indexes["hnsw"].ids(),
+            e.named_indexes["hnsw"].ids()
+        );
+        assert_eq!(e2.search_l2_index(&q, 1, 0, Some("hnsw")).unwrap(), hnsw);
+
+        // Snapshots without an IDXN section rebuild the index instead.
+        let mut plain = Engine::with_config(tiny_cfg());
+        plain.restore(&snap).unwrap();
+        let mut cfg = tiny_cfg();
+        cfg.extra_indexes = vec![IndexKind::Hnsw];
+        let mut e3 = Engine::with_config(cfg);
+        e3.restore(&plain.snapshot().unwrap()).unwrap();
+        assert_eq!(e3.named_indexes["hnsw"].ids().len(), 9);
+    }
+
+    #[test]
+    fn sq_index_rescores_candidates_exactly() {
+        let cfg = |index_kind| EngineConfig {
+            max_records: 400,
+            index_kind,
+            ..tiny_cfg()
+        };
+        let mut e = Engine::with_config(cfg(IndexKind::Sq));
+        let mut exact = Engine::with_config(cfg(IndexKind::BruteForce));
+        for engine in [&mut e, &mut exact] {
+            engine.create_collection("default").unwrap();
+            for i in 0..300 {
+                let x = i as f32;
+                engine
+                    .insert_record_from_f32(&[x.sin(), (x * 0.3).cos(), x / 300.0, 0.5])
+                    .unwrap();
+            }
+        }
+        assert_eq!(e.index_names(), vec!["sq"]);
+
+        // Same ids and the kernel's own scores, as a brute-force scan gives.
+        for q in [
+            [0.2, 0.9, 0.5, 0.5],
+            [-0.7, -0.1, 0.05, 0.5],
+            [0.99, 0.4, 0.8, 0.5],
+        ] {
+            assert_eq!(e.search_l2(&q, 5).unwrap(), exact.search_l2(&q, 5).unwrap());
+        }
+        assert_eq!(e.estimate_recall(50, 5, None).unwrap().recall, 1.0);
+    }
+
+    #[test]
+    fn vector_file_that_cannot_be_created_fails_construction() {
+        let dir = tempfile::tempdir().unwrap();
+        let result = Engine::try_with_config(EngineConfig {
+            vector_file_path: Some(dir.path().join("missing").join("vectors.bin")),
+            ..tiny_cfg()
+        });
+        assert!(matches!(
+            result,
+            Err(EngineError::Kernel(KernelError::IoError(_)))
+        ));
+    }
+
+    #[test]
+    fn vector_file_keeps_results_and_hashes() {
+        let dir = tempfile::tempdir().unwrap();
+        let mut on_disk = Engine::with_config(EngineConfig {
+            event_log_path: Some(dir.path().join("events.log")),
+            vector_file_path: Some(dir.path().join("vectors.bin")),
+            ..tiny_cfg()
+        });
+        let mut in_ram = Engine::with_config(tiny_cfg());
+        for engine in [&mut on_disk, &mut in_ram] {
+            engine.create_collection("default").unwrap();
+            for i in 0..40 {
+                let x = i as f32;
+                engine
+                    .insert_record_from_f32(&[x.sin(), x.cos(), x / 40.0, 0.25])
+                    .unwrap();
+            }
+        }
+        assert!(!on_disk.vector_file().unwrap().is_empty());
+
+        let id = RecordId(7);
+        let record = on_disk.get_record(id).unwrap();
+        assert!(record.vector.is_empty());
+        let Persistence::EventLog(ref committer) = on_disk.persistence else {
+            panic!("expected an event log");
+        };
+        assert!(committer
+            .live_state()
+            .get_record(id)
+            .unwrap()
+            .vector
+            .is_empty());
+        assert_eq!(
+            on_disk.record_vector(record),
+            in_ram.record_vector(in_ram.get_record(id).unwrap())
+        );
+        let q = [0.3, -0.8, 0.5, 0.25];
+        assert_eq!(
+            on_disk.search_l2(&q, 5).unwrap(),
+            in_ram.search_l2(&q, 5).unwrap()
+        );
+        assert_eq!(on_disk.state_hash_hex(), in_ram.state_hash_hex());
+
+        // A restored state moves into the file too.
+        let snap = in_ram.snapshot().unwrap();
+        on_disk.restore(&snap).unwrap();
+        assert!(on_disk.get_record(id).unwrap().vector.is_empty());
+        assert_eq!(
+            on_disk.search_l2(&q, 5).unwrap(),
+            in_ram.search_l2(&q, 5).unwrap()
+        );
+        assert_eq!(on_disk.state_hash_hex(), in_ram.state_hash_hex());
+    }
+
+    #[test]
+    fn bulk_load_defers_indexing_and_builds_once() {
+        let cfg = || {
+            let mut cfg = tiny_cfg();
+            cfg.index_kind = IndexKind::Hnsw;
+            cfg.extra_indexes = vec![IndexKind::BruteForce];
+            cfg
+        };
+        let mut live = Engine::with_config(cfg());
+        let mut bulk = Engine::with_config(cfg());
+        for e in [&mut live, &mut bulk] {
+            e.create_collection("default").unwrap();
+        }
+        assert!(bulk.finish_bulk_load().is_err());
+
+        bulk.begin_bulk_load().unwrap();
+        for i in 0..20 {
+            let v = [i as f32, (i % 3) as f32, 0.0, 1.0];
+            live.insert_record_from_f32(&v).unwrap();
+            bulk.insert_record_from_f32(&v).unwrap();
+        }
+        live.delete_record(7).unwrap();
+        bulk.delete_record(7).unwrap();
+
+        assert!(bulk.index.ids().is_empty());
+        assert!(bulk.health().bulk_loading);
+        assert!(bulk.check_consistency().ok);
+        let q = [7.2, 1.0, 0.0, 1.0];
+        assert!(matches!(
+            bulk.search_l2(&q, 3),
+            Err(EngineError::BulkLoadInProgress)
+        ));
+        assert!(matches!(
+            bulk.snapshot(),
+            Err(EngineError::BulkLoadInProgress)
+        ));
+
+        assert_eq!(bulk.finish_bulk_load().unwrap(), 19);
+        assert_eq!(bulk.state_hash_hex(), live.state_hash_hex());
+        assert_eq!(bulk.index.ids(), live.index.ids());
+        assert_eq!(
+            bulk.named_indexes["brute_force"].ids(),
+            live.named_indexes["brute_force"].ids()
+        );
+        assert_eq!(
+            bulk.search_l2(&q, 3).unwrap(),
+            live.search_l2(&q, 3).unwrap()
+        );
+        assert!(bulk.check_consistency().ok);
+        assert!(bulk.snapshot().is_ok());
+    }
+
+    #[test]
+    fn vacuum_moves_engine_maps_with_the_records() {
+        let mut e = Engine::with_config(tiny_cfg());
+        e.create_collection("default").unwrap();
+        for i in 0..8 {
+            e.insert_record_from_f32(&[i as f32, 0.0, 0.0, 1.0])
+                .unwrap();
+        }
+        e.set_meta_audited("record:5".into(), serde_json::json!({"n": 5}))
+            .unwrap();
+        e.delete_record(1).unwrap();
+        e.delete_record(2).unwrap();
+
+        let report = e.vacuum().unwrap();
+        assert_eq!(report.reclaimed, 2);
+        assert_eq!(report.moves, vec![(3, 1), (4, 2), (5, 3), (6, 4), (7, 5)]);
+        assert_eq!(
+            e.metadata.get("record:3"),
+            Some(serde_json::json!({"n": 5}))
+        );
+        assert_eq!(e.metadata.get("record:5"), None);
+        let mut created: Vec<u32> = e.created_at.keys().copied().collect();
+        created.sort_unstable();
+        assert_eq!(created, vec![0, 1, 2, 3, 4, 5]);
+        assert_eq!(e.search_l2(&[5.0, 0.0, 0.0, 1.0], 1).unwrap()[0].0, 3);
+        assert!(e.check_consistency().ok);
+
+        // Nothing left to reclaim: no event is committed.
+        let hash = e.state_hash_hex();
+        assert_eq!(e.vacuum().unwrap(), VacuumReport::default());
+        assert_eq!(e.state_hash_hex(), hash);
+    }
+
+    #[test]
+    fn meta_limits_are_checked_before_commit_not_at_replay() {
+        let mut e = Engine::with_config(tiny_cfg());
+        let long_key = "k".repeat(valori_protocol::MAX_META_KEY_SIZE + 1);
+        let version = e.state.version();
+        assert!(matches!(
+            e.set_meta_audited(long_key.clone(), serde_json::json!(1)),
+            Err(EngineError::Kernel(KernelError::MetadataTooLarge))
+        ));
+        assert!(matches!(
+            e.apply_meta_event(long_key.clone(), "1".into()),
+            Err(EngineError::Kernel(KernelError::MetadataTooLarge))
+        ));
+        assert_eq!(e.state.version(), version);
+        assert!(e.state.meta.is_empty());
+
+        // An event already in a log is replayed as written.
+        let event = valori_kernel::event::KernelEvent::SetMeta {
+            key: long_key.clone(),
+            value: "1".into(),
+        };
+        e.ingest_event(&event, 0).unwrap();
+        assert_eq!(e.state.meta.get(&long_key).map(String::as_str), Some("1"));
+    }
+
     #[test]
     fn health_reports_ok() {
         let e = Engine::with_config(tiny_cfg());
@@ -1742,6 +4948,175 @@ mod tests {
         assert_eq!(e2.record_count(), 1);
     }
 
+    #[test]
+    fn restore_refuses_a_checksum_mismatch() {
+        let mut e = Engine::with_config(tiny_cfg());
+        e.create_collection("default").unwrap();
+        e.insert_record_from_f32(&[0.5, 0.5, 0.5, 0.5]).unwrap();
+        let snap = e.snapshot().unwrap();
+
+        // One flipped byte in the last section, just before the trailer.
+        let mut flipped = snap.clone();
+        let at = flipped.len() - 13;
+        flipped[at] ^= 0x01;
+        let mut e2 = Engine::with_config(tiny_cfg());
+        let err = e2.restore(&flipped).unwrap_err();
+        assert!(err.to_string().contains("checksum mismatch"), "{err}");
+        assert_eq!(e2.record_count(), 0);
+        assert!(Engine::snapshot_state_proof(&flipped).is_err());
+
+        // Without a trailer, as written before it existed, it still loads.
+        let untrailed = &snap[..snap.len() - 12];
+        e2.restore(untrailed).unwrap();
+        assert_eq!(e2.record_count(), 1);
+    }
+
+    #[test]
+    fn ingested_events_keep_every_host_view_in_step() {
+        use valori_kernel::event::KernelEvent;
+        use valori_kernel::types::enums::NodeKind;
+        use valori_kernel::types::id::NodeId;
+        let insert = |i, x: f32| KernelEvent::InsertRecord {
+            id: RecordId(i),
+            vector: FxpVector {
+                data: [x, 1.0 - x, 0.0, 0.0]
+                    .iter()
+                    .map(|v| FxpScalar((v * SCALE as f32) as i32))
+                    .collect(),
+            },
+            metadata: None,
+            tag: 0,
+        };
+        // What a follower sees: events already committed by the leader.
+        let mut e = Engine::with_config(tiny_cfg());
+        let ns = e.create_collection("docs").unwrap();
+        for event in [
+            insert(0, 1.0),
+            insert(1, 0.0),
+            KernelEvent::CreateNode {
+                id: NodeId(0),
+                kind: NodeKind::default(),
+                record: Some(RecordId(1)),
+            },
+            KernelEvent::SetMeta {
+                key: "owner".into(),
+                value: "\"ops\"".into(),
+            },
+        ] {
+            e.ingest_event(&event, 0).unwrap();
+        }
+        e.ingest_event(&insert(2, 0.5), ns).unwrap();
+        e.reranker_insert(0, "stale text");
+        assert_eq!(e.index.ids().len(), 3);
+        assert_eq!(e.record_to_node.get(&1), Some(&0));
+        assert_eq!(e.metadata.get("owner"), Some(serde_json::json!("ops")));
+
+        for (event, ns) in [
+            (KernelEvent::DeleteRecord { id: RecordId(0) }, 0),
+            (KernelEvent::DeleteNode { id: NodeId(0) }, 0),
+            (
+                KernelEvent::DeleteMeta {
+                    key: "owner".into(),
+                },
+                0,
+            ),
+            (
+                KernelEvent::DropNamespace {
+                    name: String::new(),
+                },
+                ns,
+            ),
+        ] {
+            e.ingest_event(&event, ns).unwrap();
+        }
+        assert_eq!(e.index.ids(), [1]);
+        assert_eq!(e.reranker_corpus_len(), 0);
+        assert!(e.record_to_node.is_empty());
+        assert_eq!(e.metadata.get("owner"), None);
+    }
+
+    #[test]
+    fn duplicate_inserts_follow_on_duplicate() {
+        use valori_kernel::types::id::{EdgeId, NodeId};
+        let v = [0.25, 0.5, 0.0, 1.0];
+        let mut e = Engine::with_config(tiny_cfg());
+        let first = e.insert_record_tagged_ns(&v, 0, 0).unwrap();
+
+        assert!(matches!(
+            e.insert_record_dedup_ns(&v, 0, 0, OnDuplicate::Reject),
+            Err(EngineError::DuplicateVector { existing }) if existing == first
+        ));
+        let same = e
+            .insert_record_dedup_ns(&v, 0, 0, OnDuplicate::ReturnExisting)
+            .unwrap();
+        assert_eq!((same.id, same.inserted()), (first, false));
+        assert_eq!(e.state.record_count(), 1);
+
+        // Another namespace does not count.
+        let ns = e.create_collection("other").unwrap();
+        let elsewhere = e
+            .insert_record_dedup_ns(&v, 0, ns, OnDuplicate::Reject)
+            .unwrap();
+        assert_eq!(elsewhere.duplicate_of, None);
+
+        let linked = e
+            .insert_record_dedup_ns(&v, 0, 0, OnDuplicate::Link)
+            .unwrap();
+        assert!(linked.inserted());
+        assert_eq!(linked.duplicate_of, Some(first));
+        let edge = e.state.get_edge(EdgeId(0)).unwrap();
+        assert_eq!(edge.kind, EdgeKind::RefersTo);
+        let record_of = |n: NodeId| e.state.get_node(n).and_then(|n| n.record).map(|r| r.0);
+        assert_eq!(record_of(edge.from), Some(linked.id));
+        assert_eq!(record_of(edge.to), Some(first));
+
+        // Deleted records are not duplicates.
+        e.delete_record(first).unwrap();
+        e.delete_record(linked.id).unwrap();
+        let fresh = e
+            .insert_record_dedup_ns(&v, 0, 0, OnDuplicate::Reject)
+            .unwrap();
+        assert_eq!(fresh.duplicate_of, None);
+    }
+
+    #[test]
+    fn batch_duplicates_are_checked_against_the_store_and_the_batch() {
+        let (a, b) = (vec![1.0, 0.0, 0.0, 0.0], vec![0.0, 1.0, 0.0, 0.0]);
+        let mut e = Engine::with_config(tiny_cfg());
+        let stored = e.insert_record_tagged_ns(&a, 0, 0).unwrap();
+        let batch = [a.clone(), b.clone(), b.clone()];
+
+        assert!(matches!(
+            e.insert_batch_dedup_ns(&batch, None, 0, None, OnDuplicate::Reject),
+            Err(EngineError::DuplicateVector { existing }) if existing == stored
+        ));
+        assert!(e
+            .insert_batch_dedup_ns(&batch[1..], None, 0, None, OnDuplicate::Reject)
+            .is_err());
+        assert_eq!(e.state.record_count(), 1);
+
+        let items = e
+            .insert_batch_dedup_ns(&batch, None, 0, None, OnDuplicate::ReturnExisting)
+            .unwrap();
+        let new_b = items[1].id;
+        assert_eq!(
+            items
+                .iter()
+                .map(|d| (d.id, d.duplicate_of))
+                .collect::<Vec<_>>(),
+            [(stored, Some(stored)), (new_b, None), (new_b, Some(new_b))]
+        );
+        assert_eq!(e.state.record_count(), 2);
+
+        let items = e
+            .insert_batch_dedup_ns(&[a, b], None, 0, None, OnDuplicate::Link)
+            .unwrap();
+        assert!(items.iter().all(DedupInsert::inserted));
+        assert_eq!(items[0].duplicate_of, Some(stored));
+        assert_eq!(items[1].duplicate_of, Some(new_b));
+        assert_eq!((e.state.record_count(), e.state.edge_count()), (4, 2));
+    }
+
     #[test]
     fn collection_create_and_drop() {
         let mut e = Engine::with_config(tiny_cfg());
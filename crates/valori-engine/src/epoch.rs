@@ -0,0 +1,94 @@
+// Copyright (c) 2025 Varshith Gudur. Dual-licensed under MIT OR Apache-2.0.
+//! Leader epochs — split-brain detection for leader/follower replication.
+//!
+//! A node claims a new epoch, one above the highest it has seen, every time
+//! it starts as leader; a promoted follower therefore always outranks the
+//! leader it followed. Followers learn their leader's epoch in the
+//! replication handshake and record it. The current epoch and the height it
+//! started at are kept next to the event log (`events.epoch.json`, so they
+//! survive rotation and bootstrap) and appended to the hash chain as an
+//! `AdminEvent::LeaderEpoch` entry.
+//!
+//! Two checks follow from that:
+//!
+//! - a leader asked for a handshake by a node that has seen a newer epoch
+//!   has been superseded: it fences itself and refuses every write;
+//! - a node joining a leader of a newer epoch that holds more events than the
+//!   height that epoch started at wrote them under the old one — the logs
+//!   have forked, and it fails closed instead of replicating over them.
+
+use serde::{Deserialize, Serialize};
+use std::path::{Path, PathBuf};
+
+/// An epoch and the committed height it started at.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub struct LeaderEpoch {
+    /// 0 for a node that has never led or followed a leader.
+    pub epoch: u64,
+    pub start_height: u64,
+}
+
+/// How a follower relates to the leader it is about to replicate from.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum EpochCheck {
+    /// Same epoch — replicate.
+    Current,
+    /// The leader is newer and this node is a prefix of its log — record the
+    /// leader's epoch, then replicate.
+    Adopt,
+    /// This node has seen a newer epoch than the leader's: the leader has
+    /// been superseded. Do not replicate from it.
+    StaleLeader,
+    /// This node holds events past the height the leader's epoch started at,
+    /// written under an older epoch. The logs have forked.
+    Forked,
+}
+
+/// Compare this node's epoch and committed height with the leader's epoch.
+pub fn check_leader(local: LeaderEpoch, local_height: u64, leader: LeaderEpoch) -> EpochCheck {
+    use std::cmp::Ordering;
+    match leader.epoch.cmp(&local.epoch) {
+        Ordering::Equal => EpochCheck::Current,
+        Ordering::Less => EpochCheck::StaleLeader,
+        Ordering::Greater if local_height > leader.start_height => EpochCheck::Forked,
+        Ordering::Greater => EpochCheck::Adopt,
+    }
+}
+
+/// Epoch file of the log at `live_path`. Deliberately not `events.log.*`,
+/// which segment discovery would pick up as an archive.
+pub fn epoch_path(live_path: &Path) -> PathBuf {
+    live_path.with_extension("epoch.json")
+}
+
+/// The epoch recorded at `path`, if any.
+pub fn load_epoch(path: &Path) -> Option<LeaderEpoch> {
+    let bytes = std::fs::read(path).ok()?;
+    serde_json::from_slice(&bytes).ok()
+}
+
+/// Write `epoch` to `path` atomically (temp file + rename).
+pub fn save_epoch(path: &Path, epoch: &LeaderEpoch) -> std::io::Result<()> {
+    let tmp = path.with_extension("json.tmp");
+    let bytes = serde_json::to_vec(epoch).map_err(std::io::Error::other)?;
+    std::fs::write(&tmp, bytes)?;
+    std::fs::rename(&tmp, path)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn newer_leader_is_adopted_only_if_this_log_is_a_prefix() {
+        let at = |epoch, start_height| LeaderEpoch {
+            epoch,
+            start_height,
+        };
+        assert_eq!(check_leader(at(2, 5), 9, at(2, 5)), EpochCheck::Current);
+        assert_eq!(check_leader(at(2, 5), 9, at(1, 0)), EpochCheck::StaleLeader);
+        assert_eq!(check_leader(at(2, 5), 9, at(3, 9)), EpochCheck::Adopt);
+        assert_eq!(check_leader(at(2, 5), 7, at(3, 9)), EpochCheck::Adopt);
+        assert_eq!(check_leader(at(2, 5), 10, at(3, 9)), EpochCheck::Forked);
+    }
+}
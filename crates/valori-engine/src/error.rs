@@ -65,11 +65,70 @@ pub enum EngineError {
     Network(String),
     #[error("Unknown error: {0}")]
     Unknown(String),
+    /// The final shutdown checkpoint has been written; no more writes.
+    #[error("Node is shutting down")]
+    ShuttingDown,
+    /// Index-backed reads and snapshots wait for the bulk load to finish.
+    #[error("Bulk load in progress")]
+    BulkLoadInProgress,
+    /// The snapshot loaded at startup failed its self-check under the
+    /// `refuse-writes` policy; reads are still served.
+    #[error("Startup snapshot failed its self-check")]
+    SnapshotUnverified,
+    /// Startup recovery stopped under `RecoveryPolicy::FailClosed` or
+    /// `RecoverFromEventLogOnly`; the store is empty and takes no writes.
+    #[error("Startup recovery refused: {0}")]
+    RecoveryRefused(String),
+    /// A newer leader epoch exists: this node was superseded as leader, or
+    /// its log forked from the current leader's. Reads are still served.
+    #[error("Node fenced: {0}")]
+    Fenced(String),
+    /// The request's deadline passed before it finished; the engine lock
+    /// was released without a result.
+    #[error("Request deadline exceeded")]
+    DeadlineExceeded,
+    /// An `if_version` precondition did not match; nothing was committed.
+    #[error("State version is {current}, not {expected}")]
+    VersionConflict { expected: u64, current: u64 },
+    /// An `expected_version` precondition on a record update did not
+    /// match; nothing was committed.
+    #[error("Record {id} is at version {current}, not {expected}")]
+    RecordVersionConflict {
+        id: u32,
+        expected: u64,
+        current: u64,
+    },
+    /// A record's metadata is over the configured
+    /// [`MetadataPolicy`](crate::MetadataPolicy) size limit; nothing was
+    /// written.
+    #[error("Metadata of record {id} is {len} bytes, over the {max}-byte limit")]
+    MetadataTooLarge { id: u32, len: usize, max: usize },
+    /// A record's metadata is not the content the
+    /// [`MetadataPolicy`](crate::MetadataPolicy) requires; nothing was
+    /// written.
+    #[error("Metadata of record {id} is not valid {content}: {reason}")]
+    MetadataInvalid {
+        id: u32,
+        content: &'static str,
+        reason: String,
+    },
+    /// An insert asked to reject duplicates and the namespace already holds
+    /// an active record with the same vector; nothing was written.
+    #[error("Record {existing} already has this vector")]
+    DuplicateVector { existing: u32 },
 }
 
 impl IntoResponse for EngineError {
     fn into_response(self) -> Response {
         use valori_kernel::error::KernelError;
+        // Conflicts report the current version so the caller can re-read
+        // and retry, and a rejected duplicate the record it repeats.
+        let current_version = match &self {
+            EngineError::VersionConflict { current, .. } => Some(("state_version", *current)),
+            EngineError::RecordVersionConflict { current, .. } => Some(("version", *current)),
+            EngineError::DuplicateVector { existing } => Some(("existing_id", *existing as u64)),
+            _ => None,
+        };
         let (status, message) = match self {
             EngineError::Kernel(k_err) => match k_err {
                 KernelError::NotFound => (
@@ -78,7 +137,7 @@ impl IntoResponse for EngineError {
                 ),
                 KernelError::CapacityExceeded => (
                     StatusCode::INSUFFICIENT_STORAGE,
-                    "Record pool is full — increase VALORI_MAX_RECORDS and restart".to_string(),
+                    "Pool is full — raise the limit with POST /v1/admin/resize".to_string(),
                 ),
                 KernelError::DimensionMismatch { expected, found } => (
                     StatusCode::BAD_REQUEST,
@@ -98,7 +157,10 @@ impl IntoResponse for EngineError {
                 ),
                 KernelError::MetadataTooLarge => (
                     StatusCode::BAD_REQUEST,
-                    "Metadata too large (max 4 KB per record)".to_string(),
+                    format!(
+                        "Metadata too large (max {} bytes per record)",
+                        valori_protocol::MAX_METADATA_SIZE
+                    ),
                 ),
                 KernelError::QueryOutOfRange(v) => (
                     StatusCode::BAD_REQUEST,
@@ -144,8 +206,85 @@ impl IntoResponse for EngineError {
                 StatusCode::INTERNAL_SERVER_ERROR,
                 format!("Unknown error: {}", msg),
             ),
+            EngineError::ShuttingDown => (
+                StatusCode::SERVICE_UNAVAILABLE,
+                "Node is shutting down — retry against another node or after restart".to_string(),
+            ),
+            EngineError::RecoveryRefused(reason) => (
+                StatusCode::SERVICE_UNAVAILABLE,
+                format!("Writes refused: startup recovery failed ({reason})"),
+            ),
+            EngineError::Fenced(reason) => (
+                StatusCode::SERVICE_UNAVAILABLE,
+                format!("Writes refused: {reason}"),
+            ),
+            EngineError::SnapshotUnverified => (
+                StatusCode::SERVICE_UNAVAILABLE,
+                "Writes refused: the snapshot loaded at startup failed its self-check \
+                 (see snapshot_check in GET /health)"
+                    .to_string(),
+            ),
+            EngineError::BulkLoadInProgress => (
+                StatusCode::CONFLICT,
+                "Bulk load in progress — indexes are built by POST /v1/admin/bulk-load/finish"
+                    .to_string(),
+            ),
+            EngineError::DeadlineExceeded => (
+                StatusCode::GATEWAY_TIMEOUT,
+                "Request deadline exceeded (timeout_ms / VALORI_REQUEST_TIMEOUT_MS); \
+                 no result was produced"
+                    .to_string(),
+            ),
+            EngineError::VersionConflict { expected, current } => (
+                StatusCode::PRECONDITION_FAILED,
+                format!(
+                    "if_version {expected} does not match the current state version {current}; \
+                     nothing was written"
+                ),
+            ),
+            EngineError::RecordVersionConflict {
+                id,
+                expected,
+                current,
+            } => (
+                StatusCode::PRECONDITION_FAILED,
+                format!(
+                    "expected_version {expected} does not match record {id}'s version \
+                     {current}; nothing was written"
+                ),
+            ),
+            EngineError::MetadataTooLarge { id, len, max } => (
+                StatusCode::PAYLOAD_TOO_LARGE,
+                format!(
+                    "Metadata of record {id} is {len} bytes, over the {max}-byte limit \
+                     (VALORI_MAX_METADATA_BYTES); nothing was written"
+                ),
+            ),
+            EngineError::MetadataInvalid {
+                id,
+                content,
+                reason,
+            } => (
+                StatusCode::UNPROCESSABLE_ENTITY,
+                format!(
+                    "Metadata of record {id} is not valid {content} \
+                     (VALORI_METADATA_CONTENT): {reason}; nothing was written"
+                ),
+            ),
+            EngineError::DuplicateVector { existing } => (
+                StatusCode::CONFLICT,
+                format!(
+                    "Record {existing} already has this vector (on_duplicate = reject); \
+                     nothing was written"
+                ),
+            ),
         };
-        (status, Json(json!({ "error": message }))).into_response()
+        match current_version {
+            Some((field, v)) => {
+                (status, Json(json!({ "error": message, field: v }))).into_response()
+            }
+            None => (status, Json(json!({ "error": message }))).into_response(),
+        }
     }
 }
 
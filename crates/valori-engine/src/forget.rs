@@ -0,0 +1,95 @@
+// Copyright (c) 2025 Varshith Gudur. Dual-licensed under MIT OR Apache-2.0.
+//! Forgetting policy — let low-importance memories fade.
+//!
+//! Every `every_events` committed events the engine scores each live record
+//! and deletes the least important ones through ordinary `DeleteRecord`
+//! events, so followers and replays see plain deletes and stay bit-identical.
+//!
+//! ```text
+//! importance = access_weight · accesses + degree_weight · degree − age_weight · age
+//! ```
+//!
+//! - `age` — committed events since the record's current insert. Records
+//!   whose insert is not in the journal (restored from a snapshot, or
+//!   `AutoInsertRecord`) count from height 0.
+//! - `accesses` — search hits served since the record was inserted
+//!   ([`crate::RecordAccess`]). Hits are not logged, which is why only the
+//!   leader decides and the outcome is logged as deletes.
+//! - `degree` — graph edges touching the record's node.
+//!
+//! A record is forgotten when it is at least `min_age_events` old and its
+//! importance is below `threshold`. Lowest importance goes first (ties by
+//! record id), at most `max_per_run` per run.
+
+use serde::Serialize;
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct ForgetPolicy {
+    /// Run whenever the committed height crosses a multiple of this.
+    pub every_events: u64,
+    /// Records younger than this are never forgotten.
+    pub min_age_events: u64,
+    pub age_weight: f64,
+    pub access_weight: f64,
+    pub degree_weight: f64,
+    pub threshold: f64,
+    pub max_per_run: usize,
+}
+
+impl ForgetPolicy {
+    /// Defaults: an untouched, unlinked record loses one point per 1000
+    /// events and is forgotten once `every_events` old; each search hit or
+    /// edge buys it another 1000 events.
+    pub fn every(every_events: u64) -> Self {
+        Self {
+            every_events,
+            min_age_events: every_events,
+            age_weight: 0.001,
+            access_weight: 1.0,
+            degree_weight: 1.0,
+            threshold: 0.0,
+            max_per_run: 1000,
+        }
+    }
+
+    pub fn importance(&self, age_events: u64, accesses: u64, degree: u64) -> f64 {
+        self.access_weight * accesses as f64 + self.degree_weight * degree as f64
+            - self.age_weight * age_events as f64
+    }
+
+    /// `true` when the policy is due after committed height moved from
+    /// `last` to `height`.
+    pub fn due(&self, last: u64, height: u64) -> bool {
+        self.every_events > 0 && height / self.every_events > last / self.every_events
+    }
+}
+
+/// A record the policy would forget, with the inputs that condemned it.
+#[derive(Debug, Clone, PartialEq, Serialize)]
+pub struct ForgetCandidate {
+    pub record_id: u32,
+    pub age_events: u64,
+    pub accesses: u64,
+    pub degree: u64,
+    pub importance: f64,
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn importance_trades_age_against_use() {
+        let p = ForgetPolicy::every(100);
+        assert!(p.importance(100, 0, 0) < p.threshold);
+        assert!(p.importance(100, 1, 0) > p.threshold);
+        assert!(p.importance(100, 0, 1) > p.threshold);
+        assert!(p.importance(5000, 2, 2) < p.threshold);
+
+        assert!(!p.due(0, 99));
+        assert!(p.due(99, 100));
+        assert!(p.due(150, 230));
+        assert!(!p.due(200, 299));
+        assert!(!ForgetPolicy::every(0).due(0, 10));
+    }
+}
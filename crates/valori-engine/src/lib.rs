@@ -11,18 +11,37 @@
 //! |---|---|
 //! | `config`      | [`IndexKind`], [`QuantizationKind`], [`EngineConfig`] |
 //! | `error`       | [`EngineError`], [`CommitError`] |
+//! | `forget`      | [`ForgetPolicy`] — periodic deletion of low-importance records |
 //! | `metadata`    | [`MetadataStore`] — in-process JSON key-value sidecar |
+//! | `metadata_index` | [`MetadataIndex`] — secondary index over selected metadata fields |
+//! | `metadata_policy` | [`MetadataPolicy`] — per-record metadata size and content limits |
 //! | `persistence` | [`Persistence`] — standalone durability funnel |
+//! | `snapshot_check` | [`SnapshotCheck`] — startup self-check of a snapshot before it is trusted |
+//! | `epoch`       | [`LeaderEpoch`] — leader epochs for split-brain detection |
 //! | `engine`      | [`Engine`] struct + all orchestration impl blocks |
 
 pub mod config;
 pub mod engine;
+pub mod epoch;
 pub mod error;
+pub mod forget;
 pub mod metadata;
+pub mod metadata_index;
+pub mod metadata_policy;
 pub mod persistence;
+pub mod snapshot_check;
 
-pub use config::{EngineConfig, IndexKind, QuantizationKind};
-pub use engine::{Engine, EngineHealth, ExecutionResources, PoolStats, RecoveryMode};
+pub use config::{EngineConfig, IndexKind, QuantizationKind, RecoveryPolicy};
+pub use engine::{
+    BatchDeleteReport, ConsistencyReport, DedupInsert, Engine, EngineHealth, EventLogUsage,
+    ExecutionResources, FileUsage, MetadataUsage, OnDuplicate, PoolStats, RecallReport,
+    RecordAccess, RecoveryMode, RecoveryVerification, StorageStats, TxOp, TxRef, VacuumReport,
+};
+pub use epoch::{EpochCheck, LeaderEpoch};
 pub use error::{CommitError, EngineError};
+pub use forget::{ForgetCandidate, ForgetPolicy};
 pub use metadata::MetadataStore;
+pub use metadata_index::MetadataIndex;
+pub use metadata_policy::{MetadataContent, MetadataPolicy};
 pub use persistence::Persistence;
+pub use snapshot_check::{check_snapshot, SnapshotCheck, SnapshotCheckPolicy};
@@ -6,38 +6,166 @@
 //! writes a `.tmp` file and renames atomically so a crash mid-write never leaves
 //! a half-written file.
 
-use std::collections::HashMap;
+use std::collections::{BTreeSet, HashMap};
 use std::path::Path;
 use std::sync::RwLock;
 
-use serde_json::Value;
+use serde_json::{Map, Value};
+
+use crate::metadata_index::MetadataIndex;
 
 pub struct MetadataStore {
     data: RwLock<HashMap<String, Value>>,
+    /// Secondary index over the `rec:<id>` values; `None` = no fields
+    /// configured. Always locked after `data`.
+    index: RwLock<Option<MetadataIndex>>,
+}
+
+/// The record id a `rec:<id>` key belongs to.
+fn record_key_id(key: &str) -> Option<u32> {
+    key.strip_prefix("rec:")?.parse().ok()
 }
 
 impl MetadataStore {
     pub fn new() -> Self {
         Self {
             data: RwLock::new(HashMap::new()),
+            index: RwLock::new(None),
         }
     }
 
+    /// A store that indexes `fields` of every `rec:<id>` value.
+    pub fn with_indexed_fields(fields: Vec<String>) -> Self {
+        let store = Self::new();
+        if !fields.is_empty() {
+            *store.index.write().unwrap() = Some(MetadataIndex::new(fields));
+        }
+        store
+    }
+
     pub fn set(&self, key: String, value: Value) {
-        self.data.write().unwrap().insert(key, value);
+        let mut data = self.data.write().unwrap();
+        if let (Some(id), Some(index)) = (record_key_id(&key), &mut *self.index.write().unwrap()) {
+            if let Some(old) = data.get(&key) {
+                index.remove(id, old);
+            }
+            index.insert(id, &value);
+        }
+        data.insert(key, value);
     }
 
     pub fn get(&self, key: &str) -> Option<Value> {
         self.data.read().unwrap().get(key).cloned()
     }
 
+    pub fn remove(&self, key: &str) -> Option<Value> {
+        let mut data = self.data.write().unwrap();
+        let old = data.remove(key)?;
+        if let (Some(id), Some(index)) = (record_key_id(key), &mut *self.index.write().unwrap()) {
+            index.remove(id, &old);
+        }
+        Some(old)
+    }
+
+    /// The indexed metadata fields, sorted; empty when none are configured.
+    pub fn indexed_fields(&self) -> Vec<String> {
+        self.index
+            .read()
+            .unwrap()
+            .as_ref()
+            .map(|index| index.fields().map(str::to_string).collect())
+            .unwrap_or_default()
+    }
+
+    /// Record ids the secondary index says may match `filter`, or `None`
+    /// when it cannot narrow it (no index, or no indexed field in it).
+    pub fn candidates(&self, filter: &Map<String, Value>) -> Option<BTreeSet<u32>> {
+        self.index.read().unwrap().as_ref()?.candidates(filter)
+    }
+
+    /// Up to `limit` record ids, ascending, for which `keep` holds and
+    /// whose `rec:<id>` metadata matches `filter`. Only the index's
+    /// candidates are read when it can narrow the filter; otherwise every
+    /// record key is scanned.
+    pub fn record_ids_where(
+        &self,
+        filter: &Map<String, Value>,
+        keep: impl Fn(u32) -> bool,
+        limit: usize,
+    ) -> Vec<u32> {
+        let candidates = self.candidates(filter);
+        let data = self.data.read().unwrap();
+        let ids =
+            candidates.unwrap_or_else(|| data.keys().filter_map(|k| record_key_id(k)).collect());
+        ids.into_iter()
+            .filter(|&id| {
+                keep(id)
+                    && data
+                        .get(&format!("rec:{id}"))
+                        .is_some_and(|meta| valori_search::matches_metadata_filter(meta, filter))
+            })
+            .take(limit)
+            .collect()
+    }
+
+    /// Rebuild the index from `data`, after a bulk replacement.
+    fn reindex(&self, data: &HashMap<String, Value>) {
+        if let Some(index) = &mut *self.index.write().unwrap() {
+            index.clear();
+            for (key, value) in data {
+                if let Some(id) = record_key_id(key) {
+                    index.insert(id, value);
+                }
+            }
+        }
+    }
+
+    /// Move `record:<id>` keys to the ids `remap` gives, dropping those it
+    /// maps to `None`. Keys under any other prefix are untouched.
+    pub fn remap_records(&self, remap: impl Fn(u32) -> Option<u32>) {
+        let mut data = self.data.write().unwrap();
+        let keys: Vec<(String, u32)> = data
+            .keys()
+            .filter_map(|k| Some((k.clone(), k.strip_prefix("record:")?.parse().ok()?)))
+            .collect();
+        let mut moved = Vec::new();
+        for (key, id) in keys {
+            let new = remap(id);
+            if new == Some(id) {
+                continue;
+            }
+            if let (Some(value), Some(new)) = (data.remove(&key), new) {
+                moved.push((format!("record:{new}"), value));
+            }
+        }
+        data.extend(moved);
+    }
+
+    /// Every key, sorted.
+    pub fn keys(&self) -> Vec<String> {
+        let mut keys: Vec<String> = self.data.read().unwrap().keys().cloned().collect();
+        keys.sort_unstable();
+        keys
+    }
+
+    /// Number of keys stored.
+    pub fn len(&self) -> usize {
+        self.data.read().unwrap().len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.len() == 0
+    }
+
     pub fn snapshot(&self) -> Vec<u8> {
         serde_json::to_vec(&*self.data.read().unwrap()).unwrap_or_default()
     }
 
     pub fn restore(&self, data: &[u8]) {
         if let Ok(map) = serde_json::from_slice(data) {
-            *self.data.write().unwrap() = map;
+            let mut data = self.data.write().unwrap();
+            *data = map;
+            self.reindex(&data);
         }
     }
 
@@ -0,0 +1,270 @@
+// Copyright (c) 2025 Varshith Gudur. Dual-licensed under MIT OR Apache-2.0.
+//! Secondary index over selected top-level metadata fields.
+//!
+//! Record metadata lives in the [`MetadataStore`](crate::MetadataStore)
+//! under `rec:<id>` keys. Without an index, a metadata filter has to fetch
+//! and parse every candidate's blob. A [`MetadataIndex`] keeps, per indexed
+//! field, a BTree from the field's scalar value to the records holding it,
+//! so equality and numeric range predicates resolve to a record-id set.
+//!
+//! The index only narrows: callers still run the full filter on the
+//! candidates it returns, so a predicate it cannot answer (arrays, objects)
+//! simply does not narrow. `VALORI_METADATA_INDEX_FIELDS` picks the fields.
+
+use std::cmp::Ordering;
+use std::collections::{BTreeMap, BTreeSet};
+
+use serde_json::{Map, Value};
+
+/// An `f64` with a total order, so numbers can key a BTree.
+#[derive(Debug, Clone, Copy)]
+struct OrdF64(f64);
+
+impl PartialEq for OrdF64 {
+    fn eq(&self, other: &Self) -> bool {
+        self.cmp(other) == Ordering::Equal
+    }
+}
+
+impl Eq for OrdF64 {}
+
+impl PartialOrd for OrdF64 {
+    fn partial_cmp(&self, other: &Self) -> Option<Ordering> {
+        Some(self.cmp(other))
+    }
+}
+
+impl Ord for OrdF64 {
+    fn cmp(&self, other: &Self) -> Ordering {
+        self.0.total_cmp(&other.0)
+    }
+}
+
+/// One indexed value. Numbers are keyed by their `f64` value, the same
+/// conversion the range predicates use.
+#[derive(Debug, Clone, PartialEq, Eq, PartialOrd, Ord)]
+enum IndexKey {
+    Null,
+    Bool(bool),
+    Num(OrdF64),
+    Str(String),
+}
+
+impl IndexKey {
+    fn of(value: &Value) -> Option<Self> {
+        match value {
+            Value::Null => Some(Self::Null),
+            Value::Bool(b) => Some(Self::Bool(*b)),
+            Value::Number(n) => n.as_f64().map(Self::num),
+            Value::String(s) => Some(Self::Str(s.clone())),
+            Value::Array(_) | Value::Object(_) => None,
+        }
+    }
+
+    fn num(n: f64) -> Self {
+        // -0.0 and 0.0 are equal numbers but distinct under `total_cmp`.
+        Self::Num(OrdF64(if n == 0.0 { 0.0 } else { n }))
+    }
+}
+
+/// Value → record ids, for each indexed field.
+#[derive(Debug, Default)]
+pub struct MetadataIndex {
+    fields: BTreeMap<String, BTreeMap<IndexKey, BTreeSet<u32>>>,
+}
+
+impl MetadataIndex {
+    pub fn new(fields: impl IntoIterator<Item = String>) -> Self {
+        Self {
+            fields: fields.into_iter().map(|f| (f, BTreeMap::new())).collect(),
+        }
+    }
+
+    /// The indexed field names, sorted.
+    pub fn fields(&self) -> impl Iterator<Item = &str> {
+        self.fields.keys().map(String::as_str)
+    }
+
+    /// Index record `id`'s metadata.
+    pub fn insert(&mut self, id: u32, meta: &Value) {
+        let Some(obj) = meta.as_object() else { return };
+        for (field, values) in self.fields.iter_mut() {
+            if let Some(key) = obj.get(field).and_then(IndexKey::of) {
+                values.entry(key).or_default().insert(id);
+            }
+        }
+    }
+
+    /// Drop record `id`'s entries for metadata `meta`, as last inserted.
+    pub fn remove(&mut self, id: u32, meta: &Value) {
+        let Some(obj) = meta.as_object() else { return };
+        for (field, values) in self.fields.iter_mut() {
+            let Some(key) = obj.get(field).and_then(IndexKey::of) else {
+                continue;
+            };
+            if let Some(ids) = values.get_mut(&key) {
+                ids.remove(&id);
+                if ids.is_empty() {
+                    values.remove(&key);
+                }
+            }
+        }
+    }
+
+    pub fn clear(&mut self) {
+        for values in self.fields.values_mut() {
+            values.clear();
+        }
+    }
+
+    /// Records that may match `filter`: the intersection over every
+    /// predicate on an indexed field. `None` when no predicate could be
+    /// answered here and the caller must scan.
+    pub fn candidates(&self, filter: &Map<String, Value>) -> Option<BTreeSet<u32>> {
+        let mut out: Option<BTreeSet<u32>> = None;
+        for (field, expected) in filter {
+            let Some(values) = self.fields.get(field) else {
+                continue;
+            };
+            let Some(ids) = Self::lookup(values, expected) else {
+                continue;
+            };
+            out = Some(match out {
+                None => ids,
+                Some(acc) => acc.intersection(&ids).copied().collect(),
+            });
+        }
+        out
+    }
+
+    /// Ids whose value satisfies one predicate, mirroring
+    /// `valori_search::filter`: a scalar is an exact match, an object with
+    /// `eq`/`gt`/`gte`/`lt`/`lte` a numeric range.
+    fn lookup(
+        values: &BTreeMap<IndexKey, BTreeSet<u32>>,
+        expected: &Value,
+    ) -> Option<BTreeSet<u32>> {
+        let exact = |key: IndexKey| values.get(&key).cloned().unwrap_or_default();
+        let Some(ops) = expected.as_object() else {
+            return IndexKey::of(expected).map(exact);
+        };
+        if !["eq", "gt", "gte", "lt", "lte"]
+            .iter()
+            .any(|op| ops.contains_key(*op))
+        {
+            return None;
+        }
+        let op = |name: &str| ops.get(name).and_then(Value::as_f64);
+        let (gt, gte, lt, lte) = (op("gt"), op("gte"), op("lt"), op("lte"));
+        if let Some(eq) = ops.get("eq") {
+            // Only a number can equal `eq` and also pass the range check.
+            return Some(
+                eq.as_f64()
+                    .map(IndexKey::num)
+                    .map(exact)
+                    .unwrap_or_default(),
+            );
+        }
+        // Seek to the tightest bounds, then apply the strict ones exactly.
+        let lo = gt.into_iter().chain(gte).fold(f64::NEG_INFINITY, f64::max);
+        let hi = lt.into_iter().chain(lte).fold(f64::INFINITY, f64::min);
+        if lo > hi {
+            return Some(BTreeSet::new());
+        }
+        Some(
+            values
+                .range(IndexKey::num(lo)..=IndexKey::num(hi))
+                .filter(|(key, _)| {
+                    let IndexKey::Num(OrdF64(n)) = key else {
+                        return false;
+                    };
+                    gt.is_none_or(|v| *n > v)
+                        && gte.is_none_or(|v| *n >= v)
+                        && lt.is_none_or(|v| *n < v)
+                        && lte.is_none_or(|v| *n <= v)
+                })
+                .flat_map(|(_, ids)| ids.iter().copied())
+                .collect(),
+        )
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+
+    fn filter(v: Value) -> Map<String, Value> {
+        serde_json::from_value(v).unwrap()
+    }
+
+    fn index() -> MetadataIndex {
+        let mut idx = MetadataIndex::new(["author".to_string(), "year".to_string()]);
+        idx.insert(1, &json!({ "author": "Alice", "year": 2020 }));
+        idx.insert(2, &json!({ "author": "Bob", "year": 2022.0 }));
+        idx.insert(
+            3,
+            &json!({ "author": "Alice", "year": 2024, "tags": ["x"] }),
+        );
+        idx.insert(4, &json!({ "author": ["not", "scalar"], "year": "2023" }));
+        idx
+    }
+
+    fn ids(set: Option<BTreeSet<u32>>) -> Option<Vec<u32>> {
+        set.map(|s| s.into_iter().collect())
+    }
+
+    #[test]
+    fn exact_and_range_predicates() {
+        let idx = index();
+        let c = |v| ids(idx.candidates(&filter(v)));
+        assert_eq!(c(json!({ "author": "Alice" })), Some(vec![1, 3]));
+        assert_eq!(c(json!({ "year": 2022 })), Some(vec![2]));
+        assert_eq!(c(json!({ "year": { "gte": 2022 } })), Some(vec![2, 3]));
+        assert_eq!(
+            c(json!({ "year": { "gt": 2020, "lt": 2024 } })),
+            Some(vec![2])
+        );
+        assert_eq!(
+            c(json!({ "year": { "gt": 2022, "gte": 2022 } })),
+            Some(vec![3])
+        );
+        assert_eq!(c(json!({ "year": { "eq": 2024 } })), Some(vec![3]));
+        assert_eq!(
+            c(json!({ "year": { "gt": 2030, "lt": 2000 } })),
+            Some(vec![])
+        );
+        assert_eq!(
+            c(json!({ "author": "Alice", "year": { "lte": 2021 } })),
+            Some(vec![1])
+        );
+        assert_eq!(c(json!({ "author": "Carol" })), Some(vec![]));
+    }
+
+    #[test]
+    fn unanswerable_predicates_do_not_narrow() {
+        let idx = index();
+        let c = |v| ids(idx.candidates(&filter(v)));
+        assert_eq!(c(json!({ "tags": ["x"] })), None, "field not indexed");
+        assert_eq!(c(json!({ "author": ["not", "scalar"] })), None);
+        assert_eq!(
+            c(json!({ "tags": ["x"], "author": "Alice" })),
+            Some(vec![1, 3])
+        );
+    }
+
+    #[test]
+    fn remove_drops_entries() {
+        let mut idx = index();
+        idx.remove(3, &json!({ "author": "Alice", "year": 2024 }));
+        assert_eq!(
+            ids(idx.candidates(&filter(json!({ "author": "Alice" })))),
+            Some(vec![1])
+        );
+        idx.clear();
+        assert_eq!(
+            ids(idx.candidates(&filter(json!({ "author": "Alice" })))),
+            Some(vec![])
+        );
+    }
+}
@@ -0,0 +1,182 @@
+// Copyright (c) 2025 Varshith Gudur. Dual-licensed under MIT OR Apache-2.0.
+//! Per-record metadata limits, checked before an event is logged.
+//!
+//! The kernel refuses a blob over [`MAX_METADATA_SIZE`] when it applies the
+//! event, but by then the standalone commit path may already have written
+//! it. [`MetadataPolicy::check_events`] runs first, so a rejected blob
+//! never reaches the event log, the WAL or a replaying follower.
+//!
+//! `VALORI_MAX_METADATA_BYTES` lowers the size limit; it cannot raise it
+//! above the kernel's ceiling, which every replayer enforces.
+//! `VALORI_METADATA_CONTENT` picks what the bytes must be.
+
+use crate::EngineError;
+use valori_kernel::event::KernelEvent;
+use valori_protocol::MAX_METADATA_SIZE;
+
+/// What a metadata blob must contain.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum MetadataContent {
+    /// Any bytes.
+    #[default]
+    Any,
+    /// Valid UTF-8 text.
+    Utf8,
+    /// One JSON value.
+    Json,
+}
+
+impl MetadataContent {
+    pub fn from_name(name: &str) -> Option<Self> {
+        match name {
+            "any" => Some(Self::Any),
+            "utf8" | "utf-8" | "text" => Some(Self::Utf8),
+            "json" => Some(Self::Json),
+            _ => None,
+        }
+    }
+
+    pub fn name(self) -> &'static str {
+        match self {
+            Self::Any => "any",
+            Self::Utf8 => "utf8",
+            Self::Json => "json",
+        }
+    }
+}
+
+/// Size and content rules for one record's metadata.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct MetadataPolicy {
+    /// Largest blob accepted, clamped to [`MAX_METADATA_SIZE`].
+    pub max_bytes: usize,
+    pub content: MetadataContent,
+}
+
+impl Default for MetadataPolicy {
+    fn default() -> Self {
+        Self {
+            max_bytes: MAX_METADATA_SIZE,
+            content: MetadataContent::Any,
+        }
+    }
+}
+
+impl MetadataPolicy {
+    /// The size limit actually enforced.
+    pub fn limit(&self) -> usize {
+        self.max_bytes.min(MAX_METADATA_SIZE)
+    }
+
+    /// Check the metadata of record `id`.
+    pub fn check(&self, id: u32, metadata: &[u8]) -> Result<(), EngineError> {
+        let max = self.limit();
+        if metadata.len() > max {
+            return Err(EngineError::MetadataTooLarge {
+                id,
+                len: metadata.len(),
+                max,
+            });
+        }
+        let invalid = |reason: String| EngineError::MetadataInvalid {
+            id,
+            content: self.content.name(),
+            reason,
+        };
+        match self.content {
+            MetadataContent::Any => Ok(()),
+            MetadataContent::Utf8 => std::str::from_utf8(metadata)
+                .map(|_| ())
+                .map_err(|e| invalid(e.to_string())),
+            MetadataContent::Json => serde_json::from_slice::<serde::de::IgnoredAny>(metadata)
+                .map(|_| ())
+                .map_err(|e| invalid(e.to_string())),
+        }
+    }
+
+    /// Check every metadata blob in `events`; the first failure wins.
+    pub fn check_events(&self, events: &[KernelEvent]) -> Result<(), EngineError> {
+        for event in events {
+            if let KernelEvent::InsertRecord {
+                id,
+                metadata: Some(m),
+                ..
+            }
+            | KernelEvent::UpdateRecordMetadata {
+                id,
+                metadata: Some(m),
+            } = event
+            {
+                self.check(id.0, m)?;
+            }
+        }
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use valori_kernel::types::id::RecordId;
+    use valori_kernel::types::vector::FxpVector;
+
+    fn insert(id: u32, metadata: &[u8]) -> KernelEvent {
+        KernelEvent::InsertRecord {
+            id: RecordId(id),
+            vector: FxpVector { data: vec![] },
+            metadata: Some(metadata.to_vec()),
+            tag: 0,
+        }
+    }
+
+    #[test]
+    fn size_limit_is_clamped_to_the_kernel_ceiling() {
+        let policy = MetadataPolicy {
+            max_bytes: usize::MAX,
+            content: MetadataContent::Any,
+        };
+        assert_eq!(policy.limit(), MAX_METADATA_SIZE);
+
+        let policy = MetadataPolicy {
+            max_bytes: 4,
+            content: MetadataContent::Any,
+        };
+        assert!(policy.check_events(&[insert(0, b"abcd")]).is_ok());
+        assert!(matches!(
+            policy.check_events(&[insert(0, b"abcd"), insert(1, b"abcde")]),
+            Err(EngineError::MetadataTooLarge {
+                id: 1,
+                len: 5,
+                max: 4
+            })
+        ));
+    }
+
+    #[test]
+    fn content_modes() {
+        let utf8 = MetadataPolicy {
+            content: MetadataContent::Utf8,
+            ..Default::default()
+        };
+        let json = MetadataPolicy {
+            content: MetadataContent::Json,
+            ..Default::default()
+        };
+        assert!(utf8.check(0, "héllo".as_bytes()).is_ok());
+        assert!(matches!(
+            utf8.check(0, &[0xff, 0xfe]),
+            Err(EngineError::MetadataInvalid {
+                content: "utf8",
+                ..
+            })
+        ));
+        assert!(json.check(0, br#"{"a": [1, 2]}"#).is_ok());
+        assert!(json.check(0, b"not json").is_err());
+        assert!(MetadataPolicy::default().check(0, &[0xff]).is_ok());
+        assert_eq!(
+            MetadataContent::from_name("json"),
+            Some(MetadataContent::Json)
+        );
+        assert_eq!(MetadataContent::from_name("xml"), None);
+    }
+}
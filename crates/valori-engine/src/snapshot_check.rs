@@ -0,0 +1,216 @@
+// Copyright (c) 2025 Varshith Gudur. Dual-licensed under MIT OR Apache-2.0.
+//! Startup self-check of an engine snapshot before it is trusted.
+//!
+//! When recovery falls back to `snapshot_path`, the file is checked first:
+//!
+//! 1. **Checksum** — the CRC32 in the container's `CRC3` trailer covers
+//!    every byte before it. Snapshots written before the trailer existed
+//!    have none and skip this step.
+//! 2. **Section lengths** — the fixed kernel / metadata / index sections and
+//!    every tagged section after them must tile the file exactly.
+//! 3. **Kernel invariants** — the kernel section decodes and its graph pools
+//!    pass [`valori_kernel::state::kernel::KernelState::invariant_violations`].
+//! 4. **Replay** — with an event log holding events, the first `height`
+//!    events (the snapshot's kernel version) are replayed from genesis and
+//!    must reproduce the snapshot's state hash.
+//!
+//! What a failure does is the [`SnapshotCheckPolicy`]
+//! (`VALORI_SNAPSHOT_CHECK`).
+
+use serde::Serialize;
+use std::path::Path;
+use valori_kernel::snapshot::blake3::hash_state_blake3;
+use valori_kernel::snapshot::container::{Checksum, Container};
+
+pub use valori_kernel::snapshot::container::CHECKSUM_TAG;
+
+/// What recovery does with a snapshot that fails [`check_snapshot`].
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum SnapshotCheckPolicy {
+    /// Load the snapshot unchecked.
+    Off,
+    /// Log the failures and serve reads and writes as usual.
+    Warn,
+    /// Serve reads, refuse every write with
+    /// [`crate::EngineError::SnapshotUnverified`] until restart.
+    #[default]
+    RefuseWrites,
+}
+
+impl SnapshotCheckPolicy {
+    pub fn from_name(name: &str) -> Option<Self> {
+        match name {
+            "off" => Some(Self::Off),
+            "warn" => Some(Self::Warn),
+            "refuse-writes" | "refuse_writes" => Some(Self::RefuseWrites),
+            _ => None,
+        }
+    }
+}
+
+/// Outcome of [`check_snapshot`], reported under `snapshot_check` in
+/// `/health`.
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize)]
+pub struct SnapshotCheck {
+    /// True when `failures` is empty.
+    pub ok: bool,
+    /// Committed height the snapshot was taken at.
+    pub height: u64,
+    /// BLAKE3 hash of the snapshot's kernel state, lowercase hex.
+    pub state_hash: String,
+    /// False for snapshots without a checksum trailer.
+    pub checksummed: bool,
+    /// True when the event log was replayed to `height` and compared.
+    pub replayed: bool,
+    pub failures: Vec<String>,
+}
+
+/// Run every check on the snapshot bytes `data`. `event_log` is the live
+/// event-log path; archived segments next to it are read too.
+pub fn check_snapshot(data: &[u8], event_log: Option<&Path>) -> SnapshotCheck {
+    let mut check = SnapshotCheck::default();
+
+    let container = match Container::parse(data) {
+        Ok(container) => container,
+        Err(e) => {
+            check.failures.push(e.to_string());
+            return finish(check);
+        }
+    };
+    match container.checksum {
+        Checksum::Absent => {}
+        Checksum::Valid => check.checksummed = true,
+        Checksum::Mismatch { .. } => {
+            check.checksummed = true;
+            check.failures.push("checksum mismatch".to_string());
+        }
+    }
+
+    let state = match container.decode_state() {
+        Ok(state) => state,
+        Err(e) => {
+            check
+                .failures
+                .push(format!("kernel section does not decode: {e:?}"));
+            return finish(check);
+        }
+    };
+    check.height = state.version();
+    let hash = hash_state_blake3(&state);
+    check.state_hash = hex(&hash);
+    check.failures.extend(
+        state
+            .invariant_violations()
+            .iter()
+            .map(|v| format!("kernel: {v}")),
+    );
+
+    if let Some(log) = event_log.filter(|p| p.exists()) {
+        match valori_storage::events::event_replay::read_all_segments(log, None) {
+            // An empty log has nothing to say about the snapshot.
+            Ok(events) if events.is_empty() => {}
+            Ok(events) if (events.len() as u64) < check.height => {
+                check.failures.push(format!(
+                    "event log holds {} events, fewer than the snapshot height {}",
+                    events.len(),
+                    check.height
+                ));
+            }
+            Ok(events) => {
+                check.replayed = true;
+                match valori_storage::events::event_replay::replay_events(
+                    &events[..check.height as usize],
+                ) {
+                    Ok(replayed) if hash_state_blake3(&replayed) == hash => {}
+                    Ok(replayed) => check.failures.push(format!(
+                        "replaying the event log to height {} gives state hash {}",
+                        check.height,
+                        hex(&hash_state_blake3(&replayed))
+                    )),
+                    Err(e) => check.failures.push(format!(
+                        "event log replay to height {} failed: {e}",
+                        check.height
+                    )),
+                }
+            }
+            Err(e) => check.failures.push(format!("event log unreadable: {e}")),
+        }
+    }
+    finish(check)
+}
+
+fn finish(mut check: SnapshotCheck) -> SnapshotCheck {
+    check.ok = check.failures.is_empty();
+    check
+}
+
+fn hex(bytes: &[u8]) -> String {
+    bytes.iter().map(|b| format!("{b:02x}")).collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// A minimal snapshot: empty kernel state, empty metadata and index.
+    fn snapshot() -> Vec<u8> {
+        let mut kernel = Vec::new();
+        valori_kernel::snapshot::encode::encode_state(
+            &valori_kernel::state::kernel::KernelState::with_dim(4),
+            &mut kernel,
+        )
+        .unwrap();
+        let mut container =
+            valori_kernel::snapshot::container::ContainerWriter::new(&kernel, &[], &[]);
+        container.section(b"NSRG", b"{}");
+        container.finish()
+    }
+
+    #[test]
+    fn clean_snapshot_passes() {
+        let check = check_snapshot(&snapshot(), None);
+        assert!(check.ok, "{:?}", check.failures);
+        assert!(check.checksummed);
+        assert!(!check.replayed);
+        assert_eq!(check.height, 0);
+    }
+
+    #[test]
+    fn flipped_byte_fails_the_checksum() {
+        let mut data = snapshot();
+        data[10] ^= 0xff;
+        let check = check_snapshot(&data, None);
+        assert!(!check.ok);
+        assert_eq!(check.failures[0], "checksum mismatch");
+    }
+
+    #[test]
+    fn overrunning_section_is_reported() {
+        let mut data = snapshot();
+        let crc = data.len() - 12;
+        data.truncate(crc);
+        // The namespace section now claims more bytes than are left.
+        let ns_len = data.len() - 6;
+        data[ns_len..ns_len + 4].copy_from_slice(&9u32.to_le_bytes());
+        let check = check_snapshot(&data, None);
+        assert!(!check.checksummed);
+        assert!(check.failures[0].contains("NSRG"), "{:?}", check.failures);
+    }
+
+    #[test]
+    fn policy_names() {
+        assert_eq!(
+            SnapshotCheckPolicy::from_name("refuse-writes"),
+            Some(SnapshotCheckPolicy::RefuseWrites)
+        );
+        assert_eq!(
+            SnapshotCheckPolicy::from_name("warn"),
+            Some(SnapshotCheckPolicy::Warn)
+        );
+        assert_eq!(SnapshotCheckPolicy::from_name("strict"), None);
+        assert_eq!(
+            SnapshotCheckPolicy::default(),
+            SnapshotCheckPolicy::RefuseWrites
+        );
+    }
+}
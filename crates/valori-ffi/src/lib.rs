@@ -4,8 +4,10 @@ use pyo3::prelude::*;
 use std::sync::{Arc, Mutex};
 use valori_kernel::event::KernelEvent;
 use valori_kernel::fxp::ops::from_f32;
+use valori_kernel::index::TagFilter;
 use valori_kernel::proof::generate_proof_bytes;
 use valori_kernel::types::id::RecordId;
+use valori_kernel::types::scalar::FxpScalar;
 use valori_kernel::types::vector::FxpVector;
 use valori_node::config::NodeConfig;
 use valori_node::engine::Engine;
@@ -100,83 +102,126 @@ impl ValoricoreEngine {
             .map_err(|e| PyRuntimeError::new_err(format!("insert failed: {:?}", e)))
     }
 
-    #[pyo3(signature = (vector, k, filter_tag=None))]
+    /// Nearest neighbours as `(record_id, score)`, where `score` is the
+    /// squared L2 distance in Q16.16 (`distance² × 65536`). See
+    /// [`Self::search_scored`] for plain float distances.
+    ///
+    /// `filter_tag` keeps records with exactly that tag. `tag_any` /
+    /// `tag_all` read tags as bitmasks: a record must share at least one bit
+    /// with the mask, or carry all of its bits. Pass at most one of the three.
+    #[pyo3(signature = (vector, k, filter_tag=None, tag_any=None, tag_all=None))]
     fn search(
         &self,
         vector: Vec<f32>,
         k: usize,
         filter_tag: Option<u64>,
+        tag_any: Option<u64>,
+        tag_all: Option<u64>,
     ) -> PyResult<Vec<(u32, i64)>> {
+        let filter = tag_filter(filter_tag, tag_any, tag_all)?;
         let engine = lock_engine!(self);
+        Ok(search_hits(&engine, &vector, k, filter)?
+            .into_iter()
+            .map(|(id, dist)| (id, (dist * valori_protocol::fxp::SCALE_F32) as i64))
+            .collect())
+    }
+
+    /// Nearest neighbours as `(record_id, distance, similarity)`.
+    ///
+    /// `distance` is the Euclidean distance, `sqrt(score / 65536)` of
+    /// [`Self::search`], so the order is the same. `similarity` is the cosine
+    /// similarity between the query and the stored vector, both taken at the
+    /// Q16.16 precision the kernel stores, or `None` when either is all zeros.
+    /// The tag arguments are as for [`Self::search`].
+    #[pyo3(signature = (vector, k, filter_tag=None, tag_any=None, tag_all=None))]
+    fn search_scored(
+        &self,
+        vector: Vec<f32>,
+        k: usize,
+        filter_tag: Option<u64>,
+        tag_any: Option<u64>,
+        tag_all: Option<u64>,
+    ) -> PyResult<Vec<(u32, f32, Option<f32>)>> {
+        let filter = tag_filter(filter_tag, tag_any, tag_all)?;
+        let engine = lock_engine!(self);
+        let hits = search_hits(&engine, &vector, k, filter)?;
+        let query: Vec<i32> = vector.iter().map(|&f| from_f32(f).0).collect();
+        Ok(hits
+            .into_iter()
+            .map(|(id, dist)| {
+                let similarity = engine
+                    .get_record(RecordId(id))
+                    .and_then(|r| cosine_fxp(&query, engine.record_vector(r)));
+                (id, dist.max(0.0).sqrt(), similarity)
+            })
+            .collect())
+    }
+
+    /// Search every row of `queries` (an `(N, D)` numpy array or a list of
+    /// vectors) under one lock, with the GIL released.
+    ///
+    /// Returns `(ids, scores)`, two `(N, k)` int64 arrays: row `i` holds the
+    /// hits of query `i` as [`Self::search`] ranks and scores them, padded
+    /// with `-1` when fewer than `k` records match. `threads` splits the rows
+    /// over that many threads (`0` = one per CPU). Every query is checked
+    /// before any is run. The tag arguments are as for [`Self::search`].
+    #[pyo3(signature = (queries, k, filter_tag=None, tag_any=None, tag_all=None, threads=1))]
+    fn search_many<'py>(
+        &self,
+        py: Python<'py>,
+        queries: &Bound<'py, PyAny>,
+        k: usize,
+        filter_tag: Option<u64>,
+        tag_any: Option<u64>,
+        tag_all: Option<u64>,
+        threads: usize,
+    ) -> PyResult<(Bound<'py, PyAny>, Bound<'py, PyAny>)> {
+        let filter = tag_filter(filter_tag, tag_any, tag_all)?;
+        let queries = if queries.hasattr("tolist")? {
+            queries.call_method0("tolist")?
+        } else {
+            queries.clone()
+        };
+        let queries: Vec<Vec<f32>> = queries.extract().map_err(|_| {
+            PyValueError::new_err("queries must be an (N, D) array or a list of float vectors")
+        })?;
 
-        // H-3: Reject dimension mismatches; the kernel silently truncates to
-        // min(query.len(), record.len()) which produces wrong distances, not errors.
+        let engine = lock_engine!(self);
         if let Some(dim) = engine.kernel_dim() {
-            if vector.len() != dim {
+            if let Some((i, q)) = queries.iter().enumerate().find(|(_, q)| q.len() != dim) {
                 return Err(PyValueError::new_err(format!(
-                    "dimension mismatch: engine expects {dim}, got {}",
-                    vector.len()
+                    "queries[{i}] dimension mismatch: engine expects {dim}, got {}",
+                    q.len()
                 )));
             }
         }
-
-        // I-1: use engine.index (HNSW/IVF/brute) when no tag filter — gives the
-        // correct index for the configured kind.  Fall back to tag-filtered brute-force
-        // only when a tag is provided (the ANN index has no tag awareness).
-        let py_results: Vec<(u32, i64)> = if filter_tag.is_none() {
-            let hits = engine.index.search(&vector, k);
-            hits.into_iter()
-                .map(|(id, dist)| (id, (dist * 65536.0) as i64))
-                .collect()
-        } else {
-            engine
-                .search_l2_filtered(&vector, k, filter_tag)
-                .map_err(|e| PyRuntimeError::new_err(e.to_string()))?
-                .into_iter()
-                .map(|(id, dist)| (id, (dist * 65536.0) as i64))
-                .collect()
+        let threads = match threads {
+            0 => std::thread::available_parallelism().map_or(1, |n| n.get()),
+            n => n,
         };
-
-        Ok(py_results)
+        let engine: &Engine = &engine;
+        let hits = py.detach(|| search_rows(engine, &queries, k, filter, threads))?;
+
+        let (mut ids, mut scores) = (vec![-1i64; hits.len() * k], vec![-1i64; hits.len() * k]);
+        for (row, row_hits) in hits.iter().enumerate() {
+            for (col, &(id, dist)) in row_hits.iter().take(k).enumerate() {
+                ids[row * k + col] = id as i64;
+                scores[row * k + col] = (dist * valori_protocol::fxp::SCALE_F32) as i64;
+            }
+        }
+        let numpy = py.import("numpy")?;
+        let shape = (hits.len(), k);
+        let as_matrix = |flat: Vec<i64>| -> PyResult<Bound<'py, PyAny>> {
+            numpy
+                .call_method1("asarray", (flat, "int64"))?
+                .call_method1("reshape", (shape,))
+        };
+        Ok((as_matrix(ids)?, as_matrix(scores)?))
     }
 
     #[pyo3(signature = (kind, record_id=None))]
     fn create_node(&self, kind: u8, record_id: Option<u32>) -> PyResult<u32> {
         let mut engine = lock_engine!(self);
-
-        // When the event-log committer is active, insert_with_proof commits
-        // records only to live_state (not engine.state). create_node_for_record
-        // validates the record_id against engine.state via apply_committed_event_ns,
-        // so it would return NotFound for records inserted via the committer path.
-        // We must use the committer directly here to stay on the same state.
-        if let Some(committer) = engine.event_committer_mut() {
-            let node_kind =
-                valori_kernel::types::enums::NodeKind::from_u8(kind).unwrap_or_default();
-            let record = record_id.map(valori_kernel::types::id::RecordId);
-
-            // Validate record exists in live_state before committing.
-            if let Some(rid) = record {
-                if committer.live_state().get_record(rid).is_none() {
-                    return Err(PyRuntimeError::new_err(format!(
-                        "CreateNode failed: Kernel(NotFound) — record {} not in live_state",
-                        rid.0
-                    )));
-                }
-            }
-
-            let node_id = committer.live_state().next_node_id();
-            let event = KernelEvent::CreateNode {
-                id: node_id,
-                kind: node_kind,
-                record,
-            };
-            committer
-                .commit_event(event)
-                .map_err(|e| PyRuntimeError::new_err(format!("CreateNode failed: {:?}", e)))?;
-            return Ok(node_id.0);
-        }
-
-        // WAL / ephemeral path: commit_and_apply_ns touches engine.state directly.
         let node_id = engine
             .create_node_for_record(record_id, kind, 0)
             .map_err(|e| PyRuntimeError::new_err(format!("CreateNode failed: {:?}", e)))?;
@@ -185,27 +230,6 @@ impl ValoricoreEngine {
 
     fn create_edge(&self, from: u32, to: u32, kind: u8) -> PyResult<u32> {
         let mut engine = lock_engine!(self);
-
-        // Same live_state/engine.state split as create_node: use committer when active.
-        if let Some(committer) = engine.event_committer_mut() {
-            use valori_kernel::types::id::{EdgeId, NodeId};
-            let from_id = NodeId(from);
-            let to_id = NodeId(to);
-            let edge_kind =
-                valori_kernel::types::enums::EdgeKind::from_u8(kind).unwrap_or_default();
-            let edge_id = committer.live_state().next_edge_id();
-            let event = KernelEvent::CreateEdge {
-                id: edge_id,
-                kind: edge_kind,
-                from: from_id,
-                to: to_id,
-            };
-            committer
-                .commit_event(event)
-                .map_err(|e| PyRuntimeError::new_err(format!("CreateEdge failed: {:?}", e)))?;
-            return Ok(edge_id.0);
-        }
-
         engine
             .create_edge(from, to, kind)
             .map_err(|e| PyRuntimeError::new_err(format!("CreateEdge failed: {:?}", e)))
@@ -451,6 +475,135 @@ impl ValoricoreEngine {
         Ok(results)
     }
 
+    /// Insert every row of a pandas DataFrame in one call.
+    ///
+    /// `vector_col` holds one embedding per row (list or numpy array);
+    /// `tag_col` is an optional integer column; `metadata_cols` are stored per
+    /// record as compact JSON, the same encoding `LocalClient.set_metadata`
+    /// uses. All rows are converted and validated before anything is
+    /// written, so a bad row leaves the engine untouched. Returns the new
+    /// record ids in row order.
+    #[pyo3(signature = (df, vector_col, tag_col=None, metadata_cols=None))]
+    fn insert_dataframe(
+        &self,
+        df: &Bound<'_, PyAny>,
+        vector_col: &str,
+        tag_col: Option<&str>,
+        metadata_cols: Option<Vec<String>>,
+    ) -> PyResult<Vec<u32>> {
+        let py = df.py();
+        let cells = dataframe_column(df, vector_col)?;
+        let rows = cells.len();
+
+        let tags = match tag_col {
+            Some(col) => {
+                let values = dataframe_column(df, col)?;
+                let mut tags = Vec::with_capacity(rows);
+                for (i, v) in values.iter().enumerate() {
+                    tags.push(v.extract::<u64>().map_err(|_| {
+                        PyValueError::new_err(format!(
+                            "{col}[{i}] is not a non-negative integer tag"
+                        ))
+                    })?);
+                }
+                Some(tags)
+            }
+            None => None,
+        };
+
+        let metadata = match metadata_cols.filter(|c| !c.is_empty()) {
+            Some(cols) => {
+                let records = df
+                    .get_item(cols.clone())
+                    .map_err(|_| {
+                        PyValueError::new_err(format!("metadata columns {cols:?} not found"))
+                    })?
+                    .call_method1("to_dict", ("records",))?;
+                let dumps = py.import("json")?.getattr("dumps")?;
+                let kwargs = pyo3::types::PyDict::new(py);
+                kwargs.set_item("separators", (",", ":"))?;
+                kwargs.set_item("default", py.import("builtins")?.getattr("str")?)?;
+                let mut blobs = Vec::with_capacity(rows);
+                for (i, record) in records.try_iter()?.enumerate() {
+                    let json: String = dumps.call((record?,), Some(&kwargs))?.extract()?;
+                    if json.len() > valori_protocol::MAX_METADATA_SIZE {
+                        return Err(PyValueError::new_err(format!(
+                            "row {i}: metadata too large (max 64 KB)"
+                        )));
+                    }
+                    blobs.push(json.into_bytes());
+                }
+                Some(blobs)
+            }
+            None => None,
+        };
+
+        let mut engine = lock_engine!(self);
+        let dim = engine.kernel_dim();
+        let mut vectors = Vec::with_capacity(rows);
+        for (i, cell) in cells.iter().enumerate() {
+            let cell = if cell.hasattr("tolist")? {
+                cell.call_method0("tolist")?
+            } else {
+                cell.clone()
+            };
+            let vector: Vec<f32> = cell.extract().map_err(|_| {
+                PyValueError::new_err(format!("{vector_col}[{i}] is not a sequence of floats"))
+            })?;
+            if let Some(dim) = dim {
+                if vector.len() != dim {
+                    return Err(PyValueError::new_err(format!(
+                        "{vector_col}[{i}] dimension mismatch: engine expects {dim}, got {}",
+                        vector.len()
+                    )));
+                }
+            }
+            let mut fxp_data = Vec::with_capacity(vector.len());
+            for (j, &f) in vector.iter().enumerate() {
+                if !(-32767.0..=32767.0).contains(&f) {
+                    return Err(PyValueError::new_err(format!(
+                        "{vector_col}[{i}][{j}] ({f}) outside valid Q16.16 range [-32767, 32767]"
+                    )));
+                }
+                fxp_data.push(valori_kernel::types::scalar::FxpScalar(from_f32(f).0));
+            }
+            vectors.push(FxpVector { data: fxp_data });
+        }
+
+        let mut ids = Vec::with_capacity(rows);
+        for (i, fxp_vec) in vectors.into_iter().enumerate() {
+            let tag = tags.as_ref().map_or(0, |t| t[i]);
+            let rid = engine
+                .insert_record_fxp(fxp_vec, None, tag, valori_kernel::types::id::DEFAULT_NS.0)
+                .map_err(|e| {
+                    PyRuntimeError::new_err(format!("insert_dataframe failed at row {i}: {:?}", e))
+                })?;
+            ids.push(rid);
+        }
+
+        if let Some(blobs) = metadata {
+            for (rid, blob) in ids.iter().zip(blobs) {
+                let key = format!("record_{}", rid);
+                engine
+                    .apply_meta_event(key.clone(), hex::encode(&blob))
+                    .map_err(|e| {
+                        PyRuntimeError::new_err(format!(
+                            "insert_dataframe metadata commit failed: {:?}",
+                            e
+                        ))
+                    })?;
+                let json_value = serde_json::to_value(&blob)
+                    .map_err(|e| PyValueError::new_err(format!("serialize failed: {}", e)))?;
+                engine.metadata.set(key, json_value);
+            }
+            engine.flush_metadata().map_err(|e| {
+                PyRuntimeError::new_err(format!("insert_dataframe: sidecar flush failed: {:?}", e))
+            })?;
+        }
+
+        Ok(ids)
+    }
+
     fn get_metadata(&self, record_id: u32) -> PyResult<Option<Vec<u8>>> {
         let engine = lock_engine!(self);
         let rid = RecordId(record_id);
@@ -471,7 +624,7 @@ impl ValoricoreEngine {
     }
 
     fn set_metadata(&self, record_id: u32, metadata: Vec<u8>) -> PyResult<()> {
-        if metadata.len() > 65536 {
+        if metadata.len() > valori_protocol::MAX_METADATA_SIZE {
             return Err(PyValueError::new_err("metadata too large (max 64 KB)"));
         }
 
@@ -505,6 +658,50 @@ impl ValoricoreEngine {
         Ok(engine.state_hash_hex())
     }
 
+    /// Hex BLAKE3 hash of the kernel state, the `final_state_hash` of
+    /// `/v1/proof/state`. Two engines that applied the same events report
+    /// the same hash on any machine.
+    fn state_hash(&self) -> PyResult<String> {
+        let engine = lock_engine!(self);
+        Ok(engine.state_hash_hex())
+    }
+
+    /// The `/v1/proof/event-log` document as a dict, computed in-process.
+    ///
+    /// Keys: `kernel_version`, `final_state_hash`, `event_log_hash` and
+    /// `committed_height`. The last two are `None` without an event log.
+    /// `truncated` is added when recovery quarantined part of the log.
+    /// Buffered log entries are flushed first so `event_log_hash` covers
+    /// every committed event.
+    fn proof<'py>(&self, py: Python<'py>) -> PyResult<Bound<'py, PyAny>> {
+        let mut engine = lock_engine!(self);
+        if let Some(c) = engine.event_committer_mut() {
+            c.flush_pending()
+                .map_err(|e| PyRuntimeError::new_err(format!("flush failed: {:?}", e)))?;
+        }
+        let mut body = serde_json::json!({
+            "kernel_version": engine.get_proof().kernel_version,
+            "final_state_hash": engine.state_hash_hex(),
+            "event_log_hash": null,
+            "committed_height": null,
+        });
+        if let Some(committer) = engine.event_committer() {
+            let log = committer.event_log();
+            let hash = match log.log_hash() {
+                Some(h) => h,
+                None => valori_node::events::event_proof::compute_log_hash(log.path())
+                    .map_err(|e| PyRuntimeError::new_err(format!("event log hash failed: {e}")))?,
+            };
+            body["event_log_hash"] = serde_json::json!(hex::encode(hash));
+            body["committed_height"] = serde_json::json!(committer.journal().committed_height());
+        }
+        if let Some(damage) = &engine.damage {
+            body["truncated"] = serde_json::json!(damage);
+        }
+        py.import("json")?
+            .call_method1("loads", (body.to_string(),))
+    }
+
     fn record_count(&self) -> PyResult<usize> {
         let engine = lock_engine!(self);
         Ok(engine.record_count())
@@ -629,6 +826,38 @@ impl ValoricoreEngine {
         Ok((rid, proof_hex))
     }
 
+    /// Iterate the canonical event log as dicts, oldest first.
+    ///
+    /// Reads every segment on disk (sealed archives and the live file, in
+    /// order, splices verified) once when called, so the history is complete
+    /// even after a snapshot restore. Each dict has `log_index` (0-based; the
+    /// state after the event has height `log_index + 1`), `type`,
+    /// `namespace` and the event's fields. Vectors are descaled to floats
+    /// and byte fields are hex. Events before `start_height` are skipped.
+    #[pyo3(signature = (start_height=0))]
+    fn events(&self, py: Python<'_>, start_height: u64) -> PyResult<EventIter> {
+        let mut engine = lock_engine!(self);
+        let Some(committer) = engine.event_committer_mut() else {
+            return Err(PyRuntimeError::new_err("event log not enabled"));
+        };
+        committer
+            .flush_pending()
+            .map_err(|e| PyRuntimeError::new_err(format!("flush failed: {:?}", e)))?;
+        let path = committer.event_log().path().to_path_buf();
+        drop(engine);
+
+        let events =
+            valori_node::events::event_replay::read_all_segments(&path, None).map_err(|e| {
+                PyRuntimeError::new_err(format!("reading {} failed: {e}", path.display()))
+            })?;
+        let skip = usize::try_from(start_height).unwrap_or(usize::MAX);
+        Ok(EventIter {
+            events: events.into_iter().skip(skip),
+            log_index: start_height,
+            loads: py.import("json")?.getattr("loads")?.unbind(),
+        })
+    }
+
     fn get_timeline(&self) -> PyResult<Vec<String>> {
         let engine = lock_engine!(self);
         let Some(committer) = engine.event_committer() else {
@@ -711,6 +940,29 @@ impl ValoricoreEngine {
                     "Event ID {event_id}: UpdateRecordMetadata (Record {})",
                     id.0
                 ),
+                KernelEvent::ResizePools {
+                    records,
+                    nodes,
+                    edges,
+                } => format!(
+                    "Event ID {event_id}: ResizePools (records {records}, nodes {nodes}, edges {edges})"
+                ),
+                KernelEvent::Vacuum { moves } => format!(
+                    "Event ID {event_id}: Vacuum ({} records moved)",
+                    moves.len()
+                ),
+                KernelEvent::DeleteMeta { key } => {
+                    format!("Event ID {event_id}: DeleteMeta (Key: {key:?})")
+                }
+                KernelEvent::Omitted { record } => match record {
+                    Some(r) => format!("Event ID {event_id}: Omitted (Record {})", r.0),
+                    None => format!("Event ID {event_id}: Omitted"),
+                },
+                KernelEvent::SetSparseVector { id, vector } => format!(
+                    "Event ID {event_id}: SetSparseVector (Record {}, {} non-zero)",
+                    id.0,
+                    vector.nnz()
+                ),
             };
             events.push(event_str);
         }
@@ -719,6 +971,235 @@ impl ValoricoreEngine {
     }
 }
 
+/// Iterator returned by `ValoricoreEngine.events()`.
+#[pyclass]
+struct EventIter {
+    events: std::iter::Skip<std::vec::IntoIter<(u16, KernelEvent)>>,
+    log_index: u64,
+    loads: Py<PyAny>,
+}
+
+#[pymethods]
+impl EventIter {
+    fn __iter__(slf: PyRef<'_, Self>) -> PyRef<'_, Self> {
+        slf
+    }
+
+    fn __next__<'py>(&mut self, py: Python<'py>) -> PyResult<Option<Bound<'py, PyAny>>> {
+        let Some((namespace, event)) = self.events.next() else {
+            return Ok(None);
+        };
+        let body = event_json(self.log_index, namespace, &event);
+        self.log_index += 1;
+        self.loads.bind(py).call1((body.to_string(),)).map(Some)
+    }
+}
+
+/// One log event as the JSON object `EventIter` yields.
+fn event_json(log_index: u64, namespace: u16, event: &KernelEvent) -> serde_json::Value {
+    use serde_json::json;
+    let floats = |v: &FxpVector| -> Vec<f32> {
+        v.data
+            .iter()
+            .map(|&s| valori_kernel::fxp::ops::to_f32(s))
+            .collect()
+    };
+    let bytes = |b: &Option<Vec<u8>>| b.as_ref().map(hex::encode);
+    let fields = match event {
+        KernelEvent::InsertRecord {
+            id,
+            vector,
+            metadata,
+            tag,
+        } => json!({
+            "id": id.0, "vector": floats(vector), "metadata": bytes(metadata), "tag": tag,
+        }),
+        KernelEvent::DeleteRecord { id } | KernelEvent::SoftDeleteRecord { id } => {
+            json!({ "id": id.0 })
+        }
+        KernelEvent::CreateNode { id, kind, record } => json!({
+            "id": id.0, "kind": *kind as u8, "record": record.map(|r| r.0),
+        }),
+        KernelEvent::CreateEdge { id, from, to, kind } => json!({
+            "id": id.0, "from": from.0, "to": to.0, "kind": *kind as u8,
+        }),
+        KernelEvent::DeleteEdge { id } => json!({ "id": id.0 }),
+        KernelEvent::DeleteNode { id } => json!({ "id": id.0 }),
+        KernelEvent::InsertRecordEncrypted {
+            id,
+            key_id,
+            ciphertext,
+            metadata_ciphertext,
+            tag,
+        } => json!({
+            "id": id.0,
+            "key_id": hex::encode(key_id),
+            "ciphertext": hex::encode(ciphertext),
+            "metadata_ciphertext": bytes(metadata_ciphertext),
+            "tag": tag,
+        }),
+        KernelEvent::ShredKey { key_id } => json!({ "key_id": hex::encode(key_id) }),
+        KernelEvent::AutoInsertRecord {
+            vector,
+            metadata,
+            tag,
+        } => json!({ "vector": floats(vector), "metadata": bytes(metadata), "tag": tag }),
+        KernelEvent::AutoCreateNode { kind, record } => json!({
+            "kind": *kind as u8, "record": record.map(|r| r.0),
+        }),
+        KernelEvent::AutoCreateEdge { from, to, kind } => json!({
+            "from": from.0, "to": to.0, "kind": *kind as u8,
+        }),
+        KernelEvent::AutoInsertRecordEncrypted {
+            namespace_id,
+            key_id,
+            ciphertext,
+            tag,
+        } => json!({
+            "namespace_id": namespace_id,
+            "key_id": hex::encode(key_id),
+            "ciphertext": hex::encode(ciphertext),
+            "tag": tag,
+        }),
+        KernelEvent::UpdateRecordMetadata { id, metadata } => json!({
+            "id": id.0, "metadata": bytes(metadata),
+        }),
+        KernelEvent::SetMeta { key, value } => json!({ "key": key, "value": value }),
+        KernelEvent::AutoCreateNamespace { name } | KernelEvent::DropNamespace { name } => {
+            json!({ "name": name })
+        }
+        KernelEvent::ResizePools {
+            records,
+            nodes,
+            edges,
+        } => json!({ "records": records, "nodes": nodes, "edges": edges }),
+        KernelEvent::Vacuum { moves } => json!({
+            "moves": moves.iter().map(|(old, new)| [old.0, new.0]).collect::<Vec<_>>(),
+        }),
+        KernelEvent::DeleteMeta { key } => json!({ "key": key }),
+        KernelEvent::Omitted { record } => json!({ "record": record.map(|r| r.0) }),
+        KernelEvent::SetSparseVector { id, vector } => json!({ "id": id.0, "nnz": vector.nnz() }),
+    };
+    let mut body = json!({
+        "log_index": log_index,
+        "type": event.event_type(),
+        "namespace": namespace,
+    });
+    if let (Some(body), serde_json::Value::Object(fields)) = (body.as_object_mut(), fields) {
+        body.extend(fields);
+    }
+    body
+}
+
+/// The one tag restriction among the `search` keyword arguments.
+fn tag_filter(
+    exact: Option<u64>,
+    any: Option<u64>,
+    all: Option<u64>,
+) -> PyResult<Option<TagFilter>> {
+    let filters = [
+        exact.map(TagFilter::Exact),
+        any.map(TagFilter::Any),
+        all.map(TagFilter::All),
+    ];
+    let mut set = filters.into_iter().flatten();
+    let first = set.next();
+    if set.next().is_some() {
+        return Err(PyValueError::new_err(
+            "pass at most one of filter_tag, tag_any and tag_all",
+        ));
+    }
+    Ok(first)
+}
+
+/// Raw `(record_id, squared_l2)` hits, as the engine ranks them.
+fn search_hits(
+    engine: &Engine,
+    vector: &[f32],
+    k: usize,
+    filter: Option<TagFilter>,
+) -> PyResult<Vec<(u32, f32)>> {
+    // H-3: Reject dimension mismatches; the kernel silently truncates to
+    // min(query.len(), record.len()) which produces wrong distances, not errors.
+    if let Some(dim) = engine.kernel_dim() {
+        if vector.len() != dim {
+            return Err(PyValueError::new_err(format!(
+                "dimension mismatch: engine expects {dim}, got {}",
+                vector.len()
+            )));
+        }
+    }
+
+    // I-1: use engine.index (HNSW/IVF/brute) when no tag filter — gives the
+    // correct index for the configured kind.  Fall back to tag-filtered brute-force
+    // only when a tag is provided (the ANN index has no tag awareness).
+    if filter.is_none() {
+        Ok(engine.index.search(vector, k))
+    } else {
+        engine
+            .search_l2_tagged(vector, k, filter)
+            .map_err(|e| PyRuntimeError::new_err(e.to_string()))
+    }
+}
+
+/// [`search_hits`] for every query, in order, split into contiguous runs
+/// over `threads` scoped threads.
+fn search_rows(
+    engine: &Engine,
+    queries: &[Vec<f32>],
+    k: usize,
+    filter: Option<TagFilter>,
+    threads: usize,
+) -> PyResult<Vec<Vec<(u32, f32)>>> {
+    let run = |rows: &[Vec<f32>]| -> PyResult<Vec<Vec<(u32, f32)>>> {
+        rows.iter()
+            .map(|q| search_hits(engine, q, k, filter))
+            .collect()
+    };
+    if threads <= 1 || queries.len() < 2 {
+        return run(queries);
+    }
+    let chunk = queries.len().div_ceil(threads);
+    std::thread::scope(|scope| {
+        let workers: Vec<_> = queries
+            .chunks(chunk)
+            .map(|rows| scope.spawn(move || run(rows)))
+            .collect();
+        let mut hits = Vec::with_capacity(queries.len());
+        for worker in workers {
+            let rows = worker
+                .join()
+                .map_err(|_| PyRuntimeError::new_err("search_many worker panicked"))??;
+            hits.extend(rows);
+        }
+        Ok(hits)
+    })
+}
+
+/// Cosine similarity of two Q16.16 vectors, accumulated in f64 so large
+/// components cannot overflow. `None` when either vector is all zeros.
+fn cosine_fxp(query: &[i32], record: &[FxpScalar]) -> Option<f32> {
+    let (mut dot, mut qq, mut rr) = (0f64, 0f64, 0f64);
+    for (&q, r) in query.iter().zip(record) {
+        let (q, r) = (q as f64, r.0 as f64);
+        dot += q * r;
+        qq += q * q;
+        rr += r * r;
+    }
+    if qq == 0.0 || rr == 0.0 {
+        return None;
+    }
+    Some((dot / (qq.sqrt() * rr.sqrt())) as f32)
+}
+
+/// `df[col].tolist()`, with a missing column reported as a `ValueError`.
+fn dataframe_column<'py>(df: &Bound<'py, PyAny>, col: &str) -> PyResult<Vec<Bound<'py, PyAny>>> {
+    df.get_item(col)
+        .map_err(|_| PyValueError::new_err(format!("column {col:?} not found")))?
+        .call_method0("tolist")?
+        .extract()
+}
+
 #[pyfunction]
 fn ingest_embedding(floats: Vec<f32>) -> PyResult<Vec<i32>> {
     for (i, &f) in floats.iter().enumerate() {
@@ -776,6 +1257,7 @@ fn verify_log_file(log_path: String, expected_hash: Option<String>) -> PyResult<
 #[pymodule]
 fn valoricore_ffi(_py: Python, m: &Bound<'_, PyModule>) -> PyResult<()> {
     m.add_class::<ValoricoreEngine>()?;
+    m.add_class::<EventIter>()?;
     m.add_function(wrap_pyfunction!(ingest_embedding, m)?)?;
     m.add_function(wrap_pyfunction!(generate_proof, m)?)?;
     m.add_function(wrap_pyfunction!(verify_embedding, m)?)?;
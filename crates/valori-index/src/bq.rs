@@ -94,6 +94,12 @@ impl VectorIndex for BqIndex {
         self.vectors.remove(&id);
     }
 
+    fn ids(&self) -> Vec<u32> {
+        let mut ids: Vec<u32> = self.codes.keys().copied().collect();
+        ids.sort_unstable();
+        ids
+    }
+
     fn search(&self, query: &[f32], k: usize) -> Vec<(u32, f32)> {
         if k == 0 || self.codes.is_empty() {
             return Vec::new();
@@ -5,7 +5,7 @@
 //! reference for approximate indexes. Snapshot is a no-op because the engine
 //! rebuilds from the record pool on restore.
 
-use crate::traits::{l2_distance_sq, VectorIndex};
+use crate::traits::{l2_distance_sq, VectorIndex, CANCEL_CHECK_INTERVAL};
 use std::collections::HashMap;
 
 pub struct BruteForceIndex {
@@ -50,19 +50,37 @@ impl VectorIndex for BruteForceIndex {
         self.vectors.remove(&id);
     }
 
+    fn ids(&self) -> Vec<u32> {
+        let mut ids: Vec<u32> = self.vectors.keys().copied().collect();
+        ids.sort_unstable();
+        ids
+    }
+
     fn search(&self, query: &[f32], k: usize) -> Vec<(u32, f32)> {
-        let mut scores: Vec<(u32, f32)> = self
-            .vectors
-            .iter()
-            .map(|(&id, vec)| (id, l2_distance_sq(query, vec)))
-            .collect();
+        self.search_cancellable(query, k, &|| false)
+            .unwrap_or_default()
+    }
+
+    fn search_cancellable(
+        &self,
+        query: &[f32],
+        k: usize,
+        cancelled: &dyn Fn() -> bool,
+    ) -> Option<Vec<(u32, f32)>> {
+        let mut scores: Vec<(u32, f32)> = Vec::with_capacity(self.vectors.len());
+        for (i, (&id, vec)) in self.vectors.iter().enumerate() {
+            if i % CANCEL_CHECK_INTERVAL == 0 && cancelled() {
+                return None;
+            }
+            scores.push((id, l2_distance_sq(query, vec)));
+        }
         scores.sort_by(|a, b| {
             a.1.partial_cmp(&b.1)
                 .unwrap_or(std::cmp::Ordering::Equal)
                 .then_with(|| a.0.cmp(&b.0))
         });
         scores.truncate(k);
-        scores
+        Some(scores)
     }
 
     fn snapshot(&self) -> Result<Vec<u8>, Box<dyn std::error::Error + Send + Sync>> {
@@ -92,6 +110,7 @@ mod tests {
         idx.delete(1);
         let res2 = idx.search(&[1.0, 0.0], 2);
         assert_ne!(res2[0].0, 1);
+        assert_eq!(idx.ids(), vec![2, 3]);
     }
 
     #[test]
@@ -103,6 +122,27 @@ mod tests {
         assert_eq!(res[0].0, 5, "lower id wins on tie");
     }
 
+    #[test]
+    fn cancelled_search_gives_up() {
+        let mut idx = BruteForceIndex::new();
+        for id in 0..(2 * CANCEL_CHECK_INTERVAL as u32) {
+            idx.insert(id, &[id as f32]);
+        }
+        let polls = std::cell::Cell::new(0);
+        let cancel_after_first = || {
+            polls.set(polls.get() + 1);
+            polls.get() > 1
+        };
+        assert!(idx
+            .search_cancellable(&[0.0], 1, &cancel_after_first)
+            .is_none());
+        assert_eq!(polls.get(), 2, "polled once per interval");
+        assert_eq!(
+            idx.search_cancellable(&[0.0], 1, &|| false).unwrap()[0].0,
+            0
+        );
+    }
+
     #[test]
     fn build_replaces_existing() {
         let mut idx = BruteForceIndex::new();
@@ -4,94 +4,43 @@
 //! All distance computations use i64 integer arithmetic — no f32 in the hot
 //! path, so results are bit-identical across x86/ARM/WASM regardless of SIMD
 //! auto-vectorization or FPU rounding modes.
+//!
+//! The algorithm itself lives in the kernel (`valori_kernel::math::kmeans`);
+//! this wrapper quantizes `f32` training vectors to Q16.16 first.
+
+use valori_kernel::types::scalar::FxpScalar;
+use valori_kernel::types::vector::FxpVector;
 
+/// Centroids as raw Q16.16 `i32`s. Panics if the vectors' dimensions differ.
 pub fn deterministic_kmeans(
     records: &[(u32, Vec<f32>)],
     k: usize,
     iterations: usize,
 ) -> Vec<Vec<i32>> {
-    if records.is_empty() || k == 0 {
-        return Vec::new();
-    }
-
-    let dim = records[0].1.len();
-    for (_, v) in records.iter() {
-        assert_eq!(v.len(), dim, "All vectors must share the same dimension");
-    }
-
-    let q_records: Vec<(u32, Vec<i32>)> = records
-        .iter()
-        .map(|(id, vec)| (*id, vec.iter().map(|&v| f32_to_q16(v)).collect()))
-        .collect();
-
-    if k >= q_records.len() {
-        let mut sorted = q_records.clone();
-        sorted.sort_by_key(|r| r.0);
-        return sorted.into_iter().map(|r| r.1).collect();
-    }
-
-    fn hash_vec_id(id: u32, vec: &[i32]) -> u64 {
-        let mut hash: u64 = 0xcbf29ce484222325;
-        const FNV_PRIME: u64 = 0x100000001b3;
-        for &val in vec {
-            for byte in val.to_le_bytes() {
-                hash ^= byte as u64;
-                hash = hash.wrapping_mul(FNV_PRIME);
-            }
+    if let Some((_, first)) = records.first() {
+        let dim = first.len();
+        for (_, v) in records.iter() {
+            assert_eq!(v.len(), dim, "All vectors must share the same dimension");
         }
-        for byte in id.to_le_bytes() {
-            hash ^= byte as u64;
-            hash = hash.wrapping_mul(FNV_PRIME);
-        }
-        hash
     }
 
-    let mut scored: Vec<(u64, u32, &[i32])> = q_records
+    let points: Vec<(u32, FxpVector)> = records
         .iter()
-        .map(|(id, vec)| (hash_vec_id(*id, vec), *id, vec.as_slice()))
+        .map(|(id, vec)| {
+            (
+                *id,
+                FxpVector {
+                    data: vec.iter().map(|&v| FxpScalar(f32_to_q16(v))).collect(),
+                },
+            )
+        })
         .collect();
-    scored.sort_by(|a, b| a.0.cmp(&b.0).then_with(|| a.1.cmp(&b.1)));
-
-    let mut centroids: Vec<Vec<i32>> = scored.iter().take(k).map(|(_, _, v)| v.to_vec()).collect();
-
-    for _ in 0..iterations {
-        let mut assignments = vec![0usize; q_records.len()];
-
-        for (i, (_, vec)) in q_records.iter().enumerate() {
-            let mut best_dist = i64::MAX;
-            let mut best_c = 0usize;
-            for (c_idx, centroid) in centroids.iter().enumerate() {
-                let d = l2_sq_q16(vec, centroid);
-                if d < best_dist || (d == best_dist && c_idx < best_c) {
-                    best_dist = d;
-                    best_c = c_idx;
-                }
-            }
-            assignments[i] = best_c;
-        }
-
-        let mut sums: Vec<Vec<i128>> = vec![vec![0i128; dim]; k];
-        let mut counts: Vec<usize> = vec![0; k];
-
-        for (i, (_, vec)) in q_records.iter().enumerate() {
-            let c = assignments[i];
-            counts[c] += 1;
-            for (d, &val) in vec.iter().enumerate() {
-                sums[c][d] = sums[c][d].saturating_add(val as i128);
-            }
-        }
-
-        for c in 0..k {
-            if counts[c] > 0 {
-                for d in 0..dim {
-                    let avg = sums[c][d] / counts[c] as i128;
-                    centroids[c][d] = avg.clamp(i32::MIN as i128, i32::MAX as i128) as i32;
-                }
-            }
-        }
-    }
 
-    centroids
+    valori_kernel::math::kmeans::kmeans(&points, k, iterations)
+        .expect("dimensions checked above")
+        .into_iter()
+        .map(|c| c.data.into_iter().map(|s| s.0).collect())
+        .collect()
 }
 
 /// Squared L2 distance over Q16.16 fixed-point vectors (i64 arithmetic).
@@ -1,5 +1,5 @@
 // Copyright (c) 2025 Varshith Gudur. Dual-licensed under MIT OR Apache-2.0.
-use crate::traits::VectorIndex;
+use crate::traits::{GraphRepair, OptimizeStep, VectorIndex};
 use rustc_hash::FxHashSet;
 use serde::{Deserialize, Serialize};
 use std::cmp::Ordering;
@@ -27,6 +27,7 @@ impl Default for HnswConfig {
     }
 }
 
+#[derive(Clone)]
 struct Node {
     vector: Box<[f32]>,
     neighbors: Vec<Vec<u32>>,
@@ -299,6 +300,266 @@ impl HnswIndex {
         out.extend(result.iter().map(|c| c.id));
         out
     }
+
+    fn layer_cap(&self, level: usize) -> usize {
+        if level == 0 {
+            self.config.m_max0
+        } else {
+            self.config.m
+        }
+    }
+
+    /// Rank `links` by distance from `vector` and keep at most `m` of them
+    /// with the neighbour heuristic.
+    fn prune_links(
+        &self,
+        vector: &[f32],
+        links: &[u32],
+        m: usize,
+        nodes: &[Option<Node>],
+    ) -> Vec<u32> {
+        let mut ranked: Vec<Candidate> = links
+            .iter()
+            .filter_map(|&nid| {
+                nodes
+                    .get(nid as usize)
+                    .and_then(|n| n.as_ref())
+                    .map(|n| Candidate {
+                        id: nid,
+                        dist: Self::dist(vector, &n.vector),
+                    })
+            })
+            .collect();
+        ranked.sort();
+        self.select_neighbors_heuristic(vector, &ranked, m, nodes, true)
+    }
+
+    /// Link `id` from `nb` on `level`, pruning `nb`'s links back to `m` if
+    /// that overflows them.
+    fn link_back(&self, nodes: &mut [Option<Node>], nb: u32, id: u32, level: usize, m: usize) {
+        let Some(Some(nb_node)) = nodes.get_mut(nb as usize) else {
+            return;
+        };
+        let Some(edges) = nb_node.neighbors.get_mut(level) else {
+            return;
+        };
+        edges.push(id);
+        if edges.len() <= m {
+            return;
+        }
+        let edge_ids = edges.clone();
+        let nb_vec = nb_node.vector.clone();
+        let pruned = self.prune_links(&nb_vec, &edge_ids, m, nodes);
+        if let Some(Some(nb_node)) = nodes.get_mut(nb as usize) {
+            nb_node.neighbors[level] = pruned;
+        }
+    }
+
+    /// Rebuild `id`'s links on `level` from a fresh `ef_construction` search
+    /// seeded at `entry`, keeping its current links as candidates, and link
+    /// it back from every new neighbour. `false` if the links came out
+    /// unchanged.
+    fn relink(
+        &self,
+        nodes: &mut [Option<Node>],
+        id: u32,
+        level: usize,
+        entry: u32,
+        top: usize,
+    ) -> bool {
+        let Some(node) = nodes.get(id as usize).and_then(|n| n.as_ref()) else {
+            return false;
+        };
+        let vector = node.vector.clone();
+        let current = node.neighbors.get(level).cloned().unwrap_or_default();
+        let m = self.layer_cap(level);
+        let start = self.descend(&vector, entry, top, level, nodes);
+        let mut candidates: Vec<u32> = self
+            .search_layer(start, &vector, self.config.ef_construction, level, nodes)
+            .into_iter()
+            .map(|c| c.id)
+            .filter(|&c| c != id)
+            .collect();
+        for &c in &current {
+            if !candidates.contains(&c) {
+                candidates.push(c);
+            }
+        }
+        let links = self.prune_links(&vector, &candidates, m, nodes);
+        if links == current {
+            return false;
+        }
+        for &nb in links.iter().filter(|nb| !current.contains(nb)) {
+            if !Self::links_to(nodes, nb, id, level) {
+                self.link_back(nodes, nb, id, level, m);
+            }
+        }
+        if let Some(Some(node)) = nodes.get_mut(id as usize) {
+            node.neighbors[level] = links;
+        }
+        true
+    }
+
+    fn links_to(nodes: &[Option<Node>], from: u32, to: u32, level: usize) -> bool {
+        nodes
+            .get(from as usize)
+            .and_then(|n| n.as_ref())
+            .and_then(|n| n.neighbors.get(level))
+            .is_some_and(|e| e.contains(&to))
+    }
+
+    /// Live ids not reachable from `entry` over layer-0 links.
+    fn unreachable_from(nodes: &[Option<Node>], entry: Option<u32>) -> Vec<u32> {
+        let mut seen = vec![false; nodes.len()];
+        let mut stack: Vec<u32> = entry.into_iter().collect();
+        while let Some(id) = stack.pop() {
+            let Some(node) = nodes.get(id as usize).and_then(|n| n.as_ref()) else {
+                continue;
+            };
+            if std::mem::replace(&mut seen[id as usize], true) {
+                continue;
+            }
+            if let Some(links) = node.neighbors.first() {
+                stack.extend(
+                    links
+                        .iter()
+                        .filter(|&&nb| !seen.get(nb as usize).unwrap_or(&true)),
+                );
+            }
+        }
+        nodes
+            .iter()
+            .enumerate()
+            .filter(|(i, n)| n.is_some() && !seen[*i])
+            .map(|(i, _)| i as u32)
+            .collect()
+    }
+
+    /// The repair pass behind [`VectorIndex::repair`], run against
+    /// `nodes`, `entry` and `max_level` (the live graph, or a copy of it for
+    /// a dry run).
+    fn repair_graph(
+        &self,
+        nodes: &mut [Option<Node>],
+        entry: &mut Option<u32>,
+        max_level: &mut usize,
+    ) -> GraphRepair {
+        let mut report = GraphRepair::default();
+
+        // 1. Drop links to missing nodes, self-links and duplicates. Links to
+        //    a live node without that layer are left alone: insert makes
+        //    them when a new node raises the top level.
+        let levels: Vec<usize> = nodes
+            .iter()
+            .map(|n| n.as_ref().map_or(0, |n| n.neighbors.len()))
+            .collect();
+        let mut damaged = Vec::new();
+        for (i, slot) in nodes.iter_mut().enumerate() {
+            let Some(node) = slot else {
+                continue;
+            };
+            report.nodes += 1;
+            for (l, links) in node.neighbors.iter_mut().enumerate() {
+                let mut seen = FxHashSet::default();
+                let before = links.len();
+                links.retain(|&nb| {
+                    nb as usize != i
+                        && levels.get(nb as usize).is_some_and(|&n| n > 0)
+                        && seen.insert(nb)
+                });
+                if links.len() < before {
+                    report.orphan_links += before - links.len();
+                    damaged.push((i as u32, l));
+                }
+            }
+        }
+
+        // 2. The entry point must be a live node on the top layer: the
+        //    highest-level node, lowest id on ties.
+        let top = nodes
+            .iter()
+            .enumerate()
+            .filter_map(|(i, n)| n.as_ref().map(|n| (i as u32, n.neighbors.len())))
+            .fold(None, |best: Option<(u32, usize)>, (i, n)| match best {
+                Some((_, b)) if b >= n => best,
+                _ => Some((i, n)),
+            });
+        let expected = top.map(|(i, n)| (Some(i), n.saturating_sub(1)));
+        let valid = match (*entry, top) {
+            (Some(e), Some((_, n))) => {
+                levels.get(e as usize) == Some(&n) && *max_level == n.saturating_sub(1)
+            }
+            (None, None) => true,
+            _ => false,
+        };
+        if !valid {
+            report.entry_point_fixed = true;
+            (*entry, *max_level) = expected.unwrap_or((None, 0));
+        }
+        let Some(ep) = *entry else {
+            return report;
+        };
+
+        // 3. Rebuild the links of nodes that lost links, then of nodes the
+        //    entry point can no longer reach.
+        let mut relinked = FxHashSet::default();
+        for (id, l) in damaged {
+            if self.relink(nodes, id, l, ep, *max_level) {
+                relinked.insert(id);
+            }
+        }
+        report.unreachable = Self::unreachable_from(nodes, Some(ep));
+        for &id in &report.unreachable {
+            let changed = self.relink(nodes, id, 0, ep, *max_level);
+            // Its own links may already be fine; what it lacks is a way in.
+            let nearest = nodes[id as usize]
+                .as_ref()
+                .and_then(|n| n.neighbors[0].first().copied());
+            if let Some(nb) = nearest.filter(|&nb| !Self::links_to(nodes, nb, id, 0)) {
+                self.link_back(nodes, nb, id, 0, self.layer_cap(0));
+            }
+            if changed || nearest.is_some() {
+                relinked.insert(id);
+            }
+        }
+        report.relinked = relinked.len();
+        report.still_unreachable = Self::unreachable_from(nodes, Some(ep));
+        report
+    }
+
+    /// Greedy descent from `entry` through the layers above `level`.
+    fn descend(
+        &self,
+        query: &[f32],
+        mut entry: u32,
+        top: usize,
+        level: usize,
+        nodes: &[Option<Node>],
+    ) -> u32 {
+        for l in (level + 1..=top).rev() {
+            loop {
+                let Some(curr) = nodes.get(entry as usize).and_then(|n| n.as_ref()) else {
+                    break;
+                };
+                let mut best = entry;
+                let mut best_dist = Self::dist(query, &curr.vector);
+                for &nb in curr.neighbors.get(l).map(|v| v.as_slice()).unwrap_or(&[]) {
+                    if let Some(Some(nb_node)) = nodes.get(nb as usize) {
+                        let d = Self::dist(query, &nb_node.vector);
+                        if d < best_dist {
+                            best_dist = d;
+                            best = nb;
+                        }
+                    }
+                }
+                if best == entry {
+                    break;
+                }
+                entry = best;
+            }
+        }
+        entry
+    }
 }
 
 impl Default for HnswIndex {
@@ -422,34 +683,7 @@ impl VectorIndex for HnswIndex {
             }
 
             for &nb_id in &neighbors {
-                let nb_uid = nb_id as usize;
-                if let Some(Some(nb_node)) = nodes.get_mut(nb_uid) {
-                    if let Some(edges) = nb_node.neighbors.get_mut(l) {
-                        edges.push(id);
-                        if edges.len() > m {
-                            let edge_ids: Vec<u32> = edges.clone();
-                            let nb_vec: Box<[f32]> = nb_node.vector.clone();
-                            let mut ranked: Vec<Candidate> = Vec::with_capacity(edge_ids.len());
-                            ranked.extend(edge_ids.iter().filter_map(|&nid| {
-                                nodes.get(nid as usize).and_then(|n| n.as_ref()).map(|n| {
-                                    Candidate {
-                                        id: nid,
-                                        dist: Self::dist(&nb_vec, &n.vector),
-                                    }
-                                })
-                            }));
-                            ranked.sort();
-                            let pruned = self
-                                .select_neighbors_heuristic(&nb_vec, &ranked, m, &**nodes, true);
-                            if let Some(Some(nb2)) = nodes.get_mut(nb_uid) {
-                                if let Some(e) = nb2.neighbors.get_mut(l) {
-                                    e.clear();
-                                    e.extend(pruned);
-                                }
-                            }
-                        }
-                    }
-                }
+                self.link_back(&mut nodes, nb_id, id, l, m);
             }
 
             if !candidates.is_empty() {
@@ -486,6 +720,15 @@ impl VectorIndex for HnswIndex {
         }
     }
 
+    fn ids(&self) -> Vec<u32> {
+        let nodes = self.nodes.read().unwrap();
+        nodes
+            .iter()
+            .enumerate()
+            .filter_map(|(i, n)| n.as_ref().map(|_| i as u32))
+            .collect()
+    }
+
     fn search(&self, query: &[f32], k: usize) -> Vec<(u32, f32)> {
         let max_l = *self.max_level.read().unwrap();
         let mut curr_entry = match *self.entry_point.read().unwrap() {
@@ -615,6 +858,83 @@ impl VectorIndex for HnswIndex {
         *self.max_level.write().unwrap() = dump.max_level;
         Ok(())
     }
+
+    fn set_ef_search(&mut self, ef: usize) {
+        self.config.ef_search = ef;
+    }
+
+    /// Walk nodes in id order from `cursor`. A layer holding more links than
+    /// its cap is pruned back to it; one holding fewer than half of what the
+    /// layer allows (deletes leave these behind) is re-linked from a fresh
+    /// `ef_construction` search, keeping its old links as candidates. Both
+    /// use the insert-time neighbour heuristic, so the result depends only
+    /// on the graph.
+    fn optimize_step(&mut self, cursor: u32, budget: usize) -> Option<OptimizeStep> {
+        let entry = *self.entry_point.read().unwrap();
+        let max_l = *self.max_level.read().unwrap();
+        let mut nodes = self.nodes.write().unwrap();
+        let mut step = OptimizeStep {
+            slots: nodes.len(),
+            ..OptimizeStep::default()
+        };
+        let Some(entry) = entry else {
+            return Some(step);
+        };
+
+        // A node can link at most to the other nodes on its layer.
+        let mut per_layer = vec![0usize; max_l + 1];
+        for node in nodes.iter().flatten() {
+            for count in per_layer.iter_mut().take(node.neighbors.len()) {
+                *count += 1;
+            }
+        }
+
+        let mut id = cursor as usize;
+        while id < nodes.len() && step.examined < budget {
+            let Some(node) = nodes[id].as_ref() else {
+                id += 1;
+                continue;
+            };
+            step.examined += 1;
+            let vector = node.vector.clone();
+            let levels = node.neighbors.len();
+            let (mut relinked, mut pruned) = (false, false);
+            for l in 0..levels {
+                let m = self.layer_cap(l);
+                let current = nodes[id].as_ref().unwrap().neighbors[l].clone();
+                let reachable = m.min(per_layer.get(l).map_or(0, |n| n.saturating_sub(1)));
+                let links = if current.len() > m {
+                    pruned = true;
+                    self.prune_links(&vector, &current, m, &nodes)
+                } else if current.len() * 2 < reachable {
+                    relinked |= self.relink(&mut nodes, id as u32, l, entry, max_l);
+                    continue;
+                } else {
+                    continue;
+                };
+                nodes[id].as_mut().unwrap().neighbors[l] = links;
+            }
+            step.relinked += relinked as usize;
+            step.pruned += pruned as usize;
+            id += 1;
+        }
+        step.next_cursor = (id < nodes.len()).then_some(id as u32);
+        Some(step)
+    }
+
+    fn repair(&mut self, apply: bool) -> Option<GraphRepair> {
+        let mut entry = self.entry_point.write().unwrap();
+        let mut max_l = self.max_level.write().unwrap();
+        let mut nodes = self.nodes.write().unwrap();
+        let mut report = if apply {
+            self.repair_graph(&mut nodes, &mut entry, &mut max_l)
+        } else {
+            let (mut e, mut m) = (*entry, *max_l);
+            self.repair_graph(&mut nodes.clone(), &mut e, &mut m)
+        };
+        report.applied = apply;
+        Some(report)
+    }
 }
 
 #[cfg(test)]
@@ -645,6 +965,16 @@ mod tests {
         assert!(results.iter().all(|(id, _)| *id != 0));
     }
 
+    #[test]
+    fn ids_track_inserts_and_deletes() {
+        let mut idx = HnswIndex::new();
+        for i in [4u32, 1, 7] {
+            idx.insert(i, &[i as f32, 0.0]);
+        }
+        idx.delete(4);
+        assert_eq!(idx.ids(), vec![1, 7]);
+    }
+
     #[test]
     fn graph_navigable_after_entry_point_deleted() {
         let mut idx = HnswIndex::new();
@@ -676,4 +1006,87 @@ mod tests {
             r2.iter().map(|(id, _)| *id).collect::<Vec<_>>()
         );
     }
+
+    #[test]
+    fn optimize_relinks_nodes_stranded_by_deletes() {
+        let build = || {
+            let mut idx = HnswIndex::new();
+            for i in 0..200u32 {
+                idx.insert(i, &[(i % 20) as f32, (i / 20) as f32]);
+            }
+            for i in (0..200u32).filter(|i| i % 3 != 0) {
+                idx.delete(i);
+            }
+            idx
+        };
+
+        let mut a = build();
+        let mut step = a.optimize_step(0, 50).unwrap();
+        assert_eq!(step.examined, 50);
+        assert_eq!(step.slots, 200);
+        let mut relinked = step.relinked;
+        while let Some(cursor) = step.next_cursor {
+            step = a.optimize_step(cursor, 50).unwrap();
+            relinked += step.relinked;
+        }
+        assert!(relinked > 0);
+
+        // A second pass finds nothing left to do.
+        let again = a.optimize_step(0, usize::MAX).unwrap();
+        assert_eq!((again.relinked, again.pruned), (0, 0));
+
+        // The same graph optimizes to the same bytes.
+        let mut b = build();
+        b.optimize_step(0, usize::MAX);
+        assert_eq!(a.snapshot().unwrap(), b.snapshot().unwrap());
+
+        for i in (0..200u32).step_by(3) {
+            let hits = a.search(&[(i % 20) as f32, (i / 20) as f32], 1);
+            assert_eq!(hits[0].0, i);
+        }
+    }
+
+    #[test]
+    fn repair_drops_orphan_links_and_reconnects_stranded_nodes() {
+        let mut idx = HnswIndex::new();
+        for i in 0..100u32 {
+            idx.insert(i, &[(i % 10) as f32, (i / 10) as f32]);
+        }
+        let clean = idx.repair(false).unwrap();
+        assert!(clean.is_clean(), "{clean:?}");
+        assert_eq!(clean.nodes, 100);
+
+        // Corrupt the graph the way a bad restore would: a dangling link, a
+        // stale entry point, and a node nothing links to any more.
+        {
+            let mut nodes = idx.nodes.write().unwrap();
+            nodes[7].as_mut().unwrap().neighbors[0].push(5_000);
+            for node in nodes.iter_mut().flatten() {
+                for links in &mut node.neighbors {
+                    links.retain(|&n| n != 42);
+                }
+            }
+        }
+        *idx.entry_point.write().unwrap() = Some(9_999);
+        let before = idx.snapshot().unwrap();
+
+        let dry = idx.repair(false).unwrap();
+        assert!(!dry.applied);
+        assert_eq!(dry.orphan_links, 1);
+        assert!(dry.entry_point_fixed);
+        assert!(dry.unreachable.contains(&42));
+        assert!(dry.still_unreachable.is_empty());
+        assert_eq!(
+            idx.snapshot().unwrap(),
+            before,
+            "dry run must not touch the graph"
+        );
+
+        let fixed = idx.repair(true).unwrap();
+        assert!(fixed.applied);
+        assert_eq!((fixed.orphan_links, fixed.entry_point_fixed), (1, true));
+        assert!(fixed.still_unreachable.is_empty());
+        assert!(idx.repair(false).unwrap().is_clean());
+        assert_eq!(idx.search(&[2.0, 4.0], 1)[0].0, 42);
+    }
 }
@@ -162,6 +162,17 @@ impl VectorIndex for IvfIndex {
         }
     }
 
+    fn ids(&self) -> Vec<u32> {
+        let mut ids: Vec<u32> = self
+            .inverted_lists
+            .iter()
+            .flat_map(|list| list.iter().map(|(id, _)| *id))
+            .collect();
+        ids.sort_unstable();
+        ids.dedup();
+        ids
+    }
+
     fn search(&self, query: &[f32], k: usize) -> Vec<(u32, f32)> {
         let q_query: Vec<i32> = query.iter().map(|&v| f32_to_q16(v)).collect();
 
@@ -16,6 +16,7 @@ pub mod deterministic;
 pub mod hnsw;
 pub mod ivf;
 pub mod quant;
+pub mod sq;
 pub mod traits;
 
 pub use bq::BqIndex;
@@ -25,4 +26,5 @@ pub use hnsw::{HnswConfig, HnswIndex};
 pub use ivf::{IvfConfig, IvfIndex};
 pub use quant::pq::{PqConfig, ProductQuantizer};
 pub use quant::{NoQuantizer, Quantizer, ScalarQuantizer};
-pub use traits::VectorIndex;
+pub use sq::SqIndex;
+pub use traits::{GraphRepair, OptimizeStep, VectorIndex, CANCEL_CHECK_INTERVAL};
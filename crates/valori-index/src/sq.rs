@@ -0,0 +1,290 @@
+// Copyright (c) 2025 Varshith Gudur. Dual-licensed under MIT OR Apache-2.0.
+//! 8-bit Scalar Quantization index.
+//!
+//! Each dimension is mapped onto 256 levels between the smallest and largest
+//! value seen for it, so a vector costs one byte per dimension instead of
+//! four. The index keeps only those codes: search scores them against the
+//! full-precision query and returns approximate distances, and the caller
+//! rescores the [`rescore_pool`](VectorIndex::rescore_pool) against the
+//! full-precision vectors it already stores (the kernel's record pool).
+//!
+//! Ranges are trained from the first [`TRAIN_MIN`] vectors, which are held at
+//! full precision until then. Later values outside a trained range are
+//! clamped onto it; a rebuild retrains on everything.
+
+use crate::traits::{VectorIndex, CANCEL_CHECK_INTERVAL};
+use rustc_hash::FxHashMap;
+
+/// Vectors held at full precision before the ranges are trained.
+pub const TRAIN_MIN: usize = 256;
+
+const POOL_FACTOR: usize = 4;
+const MIN_CANDIDATES: usize = 64;
+
+pub struct SqIndex {
+    dim: usize,
+    /// Per-dimension lower bound; empty until trained.
+    min: Vec<f32>,
+    /// Per-dimension width of one code step.
+    step: Vec<f32>,
+    /// Vectors waiting for the ranges to be trained.
+    pending: Vec<(u32, Vec<f32>)>,
+    /// `ids[i]` owns `codes[i * dim..(i + 1) * dim]`.
+    ids: Vec<u32>,
+    codes: Vec<u8>,
+    slots: FxHashMap<u32, usize>,
+}
+
+impl SqIndex {
+    pub fn new() -> Self {
+        Self {
+            dim: 0,
+            min: Vec::new(),
+            step: Vec::new(),
+            pending: Vec::new(),
+            ids: Vec::new(),
+            codes: Vec::new(),
+            slots: FxHashMap::default(),
+        }
+    }
+
+    pub fn len(&self) -> usize {
+        self.ids.len() + self.pending.len()
+    }
+    pub fn is_empty(&self) -> bool {
+        self.len() == 0
+    }
+
+    /// Whether the per-dimension ranges have been trained.
+    pub fn is_trained(&self) -> bool {
+        !self.min.is_empty()
+    }
+
+    /// Bytes held by the codes and the vectors still awaiting training.
+    pub fn resident_bytes(&self) -> usize {
+        self.codes.len()
+            + self.ids.len() * std::mem::size_of::<u32>()
+            + self.pending.len() * self.dim * std::mem::size_of::<f32>()
+    }
+
+    fn train(&mut self, vectors: &[&[f32]]) {
+        let mut lo = vec![f32::INFINITY; self.dim];
+        let mut hi = vec![f32::NEG_INFINITY; self.dim];
+        for v in vectors {
+            for (d, &x) in v.iter().enumerate().take(self.dim) {
+                lo[d] = lo[d].min(x);
+                hi[d] = hi[d].max(x);
+            }
+        }
+        self.step = lo
+            .iter()
+            .zip(&hi)
+            .map(|(l, h)| if h > l { (h - l) / 255.0 } else { 0.0 })
+            .collect();
+        self.min = lo;
+    }
+
+    fn encode(&self, vec: &[f32], out: &mut [u8]) {
+        for (d, code) in out.iter_mut().enumerate() {
+            let x = vec.get(d).copied().unwrap_or(0.0);
+            *code = if self.step[d] > 0.0 {
+                ((x - self.min[d]) / self.step[d]).round().clamp(0.0, 255.0) as u8
+            } else {
+                0
+            };
+        }
+    }
+
+    fn approx_l2_sq(&self, query: &[f32], code: &[u8]) -> f32 {
+        code.iter()
+            .enumerate()
+            .map(|(d, &c)| {
+                let diff = query[d] - (self.min[d] + c as f32 * self.step[d]);
+                diff * diff
+            })
+            .sum()
+    }
+
+    fn put_code(&mut self, id: u32, vec: &[f32]) {
+        let dim = self.dim;
+        let slot = match self.slots.get(&id) {
+            Some(&slot) => slot,
+            None => {
+                self.slots.insert(id, self.ids.len());
+                self.ids.push(id);
+                self.codes.resize(self.codes.len() + dim, 0);
+                self.ids.len() - 1
+            }
+        };
+        let mut code = vec![0u8; dim];
+        self.encode(vec, &mut code);
+        self.codes[slot * dim..(slot + 1) * dim].copy_from_slice(&code);
+    }
+
+    /// Train on the pending vectors and code them.
+    fn flush_pending(&mut self) {
+        let pending = std::mem::take(&mut self.pending);
+        let vectors: Vec<&[f32]> = pending.iter().map(|(_, v)| v.as_slice()).collect();
+        self.train(&vectors);
+        for (id, vec) in &pending {
+            self.put_code(*id, vec);
+        }
+    }
+}
+
+impl Default for SqIndex {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl VectorIndex for SqIndex {
+    fn build(&mut self, records: &[(u32, Vec<f32>)]) {
+        *self = Self::new();
+        let Some((_, first)) = records.first() else {
+            return;
+        };
+        self.dim = first.len();
+        self.pending = records.to_vec();
+        if self.pending.len() >= TRAIN_MIN {
+            self.flush_pending();
+        }
+    }
+
+    fn insert(&mut self, id: u32, vec: &[f32]) {
+        if self.dim == 0 {
+            self.dim = vec.len();
+        }
+        if self.is_trained() {
+            self.put_code(id, vec);
+            return;
+        }
+        match self.pending.iter_mut().find(|(p, _)| *p == id) {
+            Some(entry) => entry.1 = vec.to_vec(),
+            None => self.pending.push((id, vec.to_vec())),
+        }
+        if self.pending.len() >= TRAIN_MIN {
+            self.flush_pending();
+        }
+    }
+
+    fn delete(&mut self, id: u32) {
+        self.pending.retain(|(p, _)| *p != id);
+        let Some(slot) = self.slots.remove(&id) else {
+            return;
+        };
+        let dim = self.dim;
+        let last = self.ids.len() - 1;
+        self.ids.swap_remove(slot);
+        if slot != last {
+            self.codes
+                .copy_within(last * dim..(last + 1) * dim, slot * dim);
+            self.slots.insert(self.ids[slot], slot);
+        }
+        self.codes.truncate(last * dim);
+    }
+
+    fn ids(&self) -> Vec<u32> {
+        let mut ids: Vec<u32> = self.ids.clone();
+        ids.extend(self.pending.iter().map(|(id, _)| *id));
+        ids.sort_unstable();
+        ids.dedup();
+        ids
+    }
+
+    fn search(&self, query: &[f32], k: usize) -> Vec<(u32, f32)> {
+        self.search_cancellable(query, k, &|| false)
+            .unwrap_or_default()
+    }
+
+    fn search_cancellable(
+        &self,
+        query: &[f32],
+        k: usize,
+        cancelled: &dyn Fn() -> bool,
+    ) -> Option<Vec<(u32, f32)>> {
+        if k == 0 || self.is_empty() {
+            return Some(Vec::new());
+        }
+        let mut scores: Vec<(u32, f32)> = Vec::with_capacity(self.len());
+        for (i, &id) in self.ids.iter().enumerate() {
+            if i % CANCEL_CHECK_INTERVAL == 0 && cancelled() {
+                return None;
+            }
+            let code = &self.codes[i * self.dim..(i + 1) * self.dim];
+            scores.push((id, self.approx_l2_sq(query, code)));
+        }
+        for (id, vec) in &self.pending {
+            scores.push((*id, crate::traits::l2_distance_sq(query, vec)));
+        }
+        scores.sort_by(|a, b| a.1.total_cmp(&b.1).then_with(|| a.0.cmp(&b.0)));
+        scores.truncate(k);
+        Some(scores)
+    }
+
+    fn rescore_pool(&self, k: usize) -> Option<usize> {
+        Some((POOL_FACTOR * k).max(MIN_CANDIDATES))
+    }
+
+    fn snapshot(&self) -> Result<Vec<u8>, Box<dyn std::error::Error + Send + Sync>> {
+        Ok(Vec::new())
+    }
+
+    fn restore(&mut self, _data: &[u8]) -> Result<(), Box<dyn std::error::Error + Send + Sync>> {
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn corpus(n: u32) -> Vec<(u32, Vec<f32>)> {
+        (0..n)
+            .map(|i| {
+                let x = i as f32;
+                (i, vec![x.sin(), (x * 0.7).cos(), x / n as f32, 1.0])
+            })
+            .collect()
+    }
+
+    #[test]
+    fn holds_vectors_until_trained() {
+        let mut idx = SqIndex::new();
+        idx.insert(1, &[1.0, 0.0]);
+        idx.insert(2, &[0.0, 1.0]);
+        assert!(!idx.is_trained());
+        let res = idx.search(&[1.0, 0.0], 1);
+        assert_eq!(res, vec![(1, 0.0)]);
+    }
+
+    #[test]
+    fn trained_codes_keep_the_nearest_in_the_pool() {
+        let mut idx = SqIndex::new();
+        let records = corpus(1000);
+        idx.build(&records);
+        assert!(idx.is_trained());
+        assert_eq!(idx.len(), 1000);
+        assert!(idx.resident_bytes() < 1000 * 4 * 4);
+
+        for (qid, query) in records.iter().step_by(97) {
+            let pool = idx.search(query, idx.rescore_pool(10).unwrap());
+            assert!(pool.iter().any(|(id, _)| id == qid), "query {qid} lost");
+        }
+    }
+
+    #[test]
+    fn insert_delete_keep_slots_consistent() {
+        let mut idx = SqIndex::new();
+        idx.build(&corpus(300));
+        idx.delete(0);
+        idx.delete(299);
+        idx.insert(7, &[0.5, 0.5, 0.5, 1.0]);
+        idx.insert(1000, &[0.0, 0.0, 0.0, 1.0]);
+        let ids = idx.ids();
+        assert_eq!(ids.len(), 299);
+        assert!(!ids.contains(&0) && !ids.contains(&299) && ids.contains(&1000));
+        let res = idx.search(&[0.5, 0.5, 0.5, 1.0], 1);
+        assert_eq!(res[0].0, 7);
+    }
+}
@@ -1,6 +1,10 @@
 // Copyright (c) 2025 Varshith Gudur. Dual-licensed under MIT OR Apache-2.0.
 //! The `VectorIndex` trait — the single interface every index must implement.
 
+/// Candidates an exhaustive scan scores between two polls of its
+/// cancellation check.
+pub const CANCEL_CHECK_INTERVAL: usize = 1024;
+
 /// Uniform interface for vector index structures.
 ///
 /// All methods accept raw `f32` vectors; Q16.16 conversion is the index's
@@ -16,17 +20,110 @@ pub trait VectorIndex {
     /// sorted ascending by distance, at most `k` results.
     fn search(&self, query: &[f32], k: usize) -> Vec<(u32, f32)>;
 
+    /// As [`search`](Self::search), but gives up with `None` once `cancelled`
+    /// returns true. Exhaustive scans poll it every [`CANCEL_CHECK_INTERVAL`]
+    /// candidates; the default polls once, before a plain `search`, which
+    /// suits indexes whose search is bounded by a beam or probe count.
+    fn search_cancellable(
+        &self,
+        query: &[f32],
+        k: usize,
+        cancelled: &dyn Fn() -> bool,
+    ) -> Option<Vec<(u32, f32)>> {
+        if cancelled() {
+            return None;
+        }
+        Some(self.search(query, k))
+    }
+
     /// Insert or update a single record. Must be O(log N) or better for live-write indexes.
     fn insert(&mut self, id: u32, vec: &[f32]);
 
     /// Remove a record. No-op if the id is not present.
     fn delete(&mut self, id: u32);
 
+    /// Record ids currently indexed, ascending and de-duplicated. Lets the
+    /// engine check the index against the kernel's record pool.
+    fn ids(&self) -> Vec<u32>;
+
     /// Serialize index state to bytes for inclusion in a node snapshot.
     fn snapshot(&self) -> Result<Vec<u8>, Box<dyn std::error::Error + Send + Sync>>;
 
     /// Restore index state from bytes produced by `snapshot`.
     fn restore(&mut self, data: &[u8]) -> Result<(), Box<dyn std::error::Error + Send + Sync>>;
+
+    /// How many candidates to fetch for `k` results, for indexes whose
+    /// distances are only approximate: the caller rescores that many against
+    /// full-precision vectors and keeps the best `k`. `None` (the default)
+    /// means the index's own distances are exact enough to return as is.
+    fn rescore_pool(&self, _k: usize) -> Option<usize> {
+        None
+    }
+
+    /// Change the query-time beam width, for indexes that have one. Takes
+    /// effect on the next search; the graph is untouched. Default: no-op.
+    fn set_ef_search(&mut self, _ef: usize) {}
+
+    /// Improve the graph for at most `budget` nodes, starting at node id
+    /// `cursor`, for indexes that have one. `None` (the default) means there
+    /// is nothing to optimize. Never changes which ids are indexed.
+    fn optimize_step(&mut self, _cursor: u32, _budget: usize) -> Option<OptimizeStep> {
+        None
+    }
+
+    /// Check the graph for links to deleted nodes, a stale entry point and
+    /// nodes the entry point cannot reach, and with `apply` repair what it
+    /// finds. `None` (the default) for indexes without a graph. Never
+    /// changes which ids are indexed.
+    fn repair(&mut self, _apply: bool) -> Option<GraphRepair> {
+        None
+    }
+}
+
+/// Outcome of [`VectorIndex::repair`]. A dry run reports what applying it
+/// would do.
+#[derive(Debug, Clone, Default, PartialEq, Eq, serde::Serialize)]
+pub struct GraphRepair {
+    /// Live nodes checked.
+    pub nodes: usize,
+    /// Links dropped: to deleted or out-of-range ids, self-links and
+    /// duplicates.
+    pub orphan_links: usize,
+    /// Whether the entry point (or top level) was stale and got reset to the
+    /// highest-level live node.
+    pub entry_point_fixed: bool,
+    /// Live nodes the entry point could not reach over layer 0 once the bad
+    /// links were gone.
+    pub unreachable: Vec<u32>,
+    /// Nodes whose links were rebuilt.
+    pub relinked: usize,
+    /// Live nodes still unreachable after the repair.
+    pub still_unreachable: Vec<u32>,
+    /// Whether the repair was written back to the index.
+    pub applied: bool,
+}
+
+impl GraphRepair {
+    /// True when the check found nothing to repair.
+    pub fn is_clean(&self) -> bool {
+        self.orphan_links == 0 && !self.entry_point_fixed && self.unreachable.is_empty()
+    }
+}
+
+/// Outcome of one [`VectorIndex::optimize_step`].
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct OptimizeStep {
+    /// Live nodes looked at.
+    pub examined: usize,
+    /// Nodes whose links were rebuilt because they had fallen below half
+    /// the usual degree on some layer.
+    pub relinked: usize,
+    /// Nodes whose links were pruned back to the layer's cap.
+    pub pruned: usize,
+    /// Where the next step resumes; `None` once the pass reached the end.
+    pub next_cursor: Option<u32>,
+    /// Node slots in the index, i.e. the length of a full pass.
+    pub slots: usize,
 }
 
 /// Squared Euclidean distance between two f32 slices.
@@ -18,6 +18,7 @@
 
 use crate::types::enums::{EdgeKind, NodeKind};
 use crate::types::id::{EdgeId, NodeId, RecordId};
+use crate::types::sparse::SparseVector;
 use crate::types::vector::FxpVector;
 use core::fmt;
 use serde::de::{self, SeqAccess, Visitor};
@@ -151,9 +152,70 @@ pub enum KernelEvent {
     /// Raft-apply critical section, so there is no time-of-check/time-of-use
     /// race between resolving and dropping.
     DropNamespace { name: alloc::string::String },
+
+    /// Set the record / node / edge pool limits. Recorded in the log (and the
+    /// snapshot header) so every replica enforces the same limits at the same
+    /// height. Rejected if any limit is below the pool's live count.
+    ResizePools {
+        records: u32,
+        nodes: u32,
+        edges: u32,
+    },
+
+    /// Re-pack the record pool into low slots: reclaim hard-deleted gaps and
+    /// soft-deleted tombstones no graph node points at, and renumber the
+    /// survivors densely in their existing order. `moves` lists `(old, new)`
+    /// for every record whose id changes, so the log itself says where each
+    /// record went; apply rejects a mapping that differs from the one the
+    /// state implies.
+    Vacuum {
+        moves: alloc::vec::Vec<(RecordId, RecordId)>,
+    },
+
+    /// Remove a key from the kernel's `meta` map. Removing a key that is not
+    /// there is a no-op, so replaying a delete twice is harmless.
+    DeleteMeta { key: alloc::string::String },
+
+    /// Stand-in for a leader event a partial replica does not keep, so its
+    /// height stays in step with the leader's. With `record`, it stands in
+    /// for an insert: the id is reserved as an empty slot, exactly as if the
+    /// record had been inserted and hard-deleted, so later ids line up. Never
+    /// written by a leader.
+    Omitted { record: Option<RecordId> },
+
+    /// Give record `id` a sparse vector alongside its dense one, replacing
+    /// any it had; an empty vector removes it. The record must be live and
+    /// unencrypted.
+    SetSparseVector { id: RecordId, vector: SparseVector },
 }
 
 impl KernelEvent {
+    /// Every name [`KernelEvent::event_type`] returns, in variant order.
+    pub const EVENT_TYPES: &'static [&'static str] = &[
+        "InsertRecord",
+        "DeleteRecord",
+        "CreateNode",
+        "CreateEdge",
+        "DeleteEdge",
+        "SoftDeleteRecord",
+        "DeleteNode",
+        "InsertRecordEncrypted",
+        "ShredKey",
+        "AutoInsertRecord",
+        "AutoCreateNode",
+        "AutoCreateEdge",
+        "AutoInsertRecordEncrypted",
+        "UpdateRecordMetadata",
+        "SetMeta",
+        "AutoCreateNamespace",
+        "DropNamespace",
+        "ResizePools",
+        "Vacuum",
+        "DeleteMeta",
+        "Omitted",
+        "SetSparseVector",
+    ];
+
     /// Returns a human-readable description of the event type
     pub fn event_type(&self) -> &'static str {
         match self {
@@ -174,6 +236,11 @@ impl KernelEvent {
             KernelEvent::SetMeta { .. } => "SetMeta",
             KernelEvent::AutoCreateNamespace { .. } => "AutoCreateNamespace",
             KernelEvent::DropNamespace { .. } => "DropNamespace",
+            KernelEvent::ResizePools { .. } => "ResizePools",
+            KernelEvent::Vacuum { .. } => "Vacuum",
+            KernelEvent::DeleteMeta { .. } => "DeleteMeta",
+            KernelEvent::Omitted { .. } => "Omitted",
+            KernelEvent::SetSparseVector { .. } => "SetSparseVector",
         }
     }
 }
@@ -350,6 +417,43 @@ impl Serialize for KernelEvent {
                 state.serialize_field("metadata", &RawMetadata(metadata.as_ref()))?;
                 state.end()
             }
+            KernelEvent::ResizePools {
+                records,
+                nodes,
+                edges,
+            } => {
+                let mut state =
+                    serializer.serialize_struct_variant("KernelEvent", 17, "ResizePools", 3)?;
+                state.serialize_field("records", records)?;
+                state.serialize_field("nodes", nodes)?;
+                state.serialize_field("edges", edges)?;
+                state.end()
+            }
+            KernelEvent::Vacuum { moves } => {
+                let mut state =
+                    serializer.serialize_struct_variant("KernelEvent", 18, "Vacuum", 1)?;
+                state.serialize_field("moves", moves)?;
+                state.end()
+            }
+            KernelEvent::DeleteMeta { key } => {
+                let mut state =
+                    serializer.serialize_struct_variant("KernelEvent", 19, "DeleteMeta", 1)?;
+                state.serialize_field("key", key)?;
+                state.end()
+            }
+            KernelEvent::Omitted { record } => {
+                let mut state =
+                    serializer.serialize_struct_variant("KernelEvent", 20, "Omitted", 1)?;
+                state.serialize_field("record", record)?;
+                state.end()
+            }
+            KernelEvent::SetSparseVector { id, vector } => {
+                let mut state =
+                    serializer.serialize_struct_variant("KernelEvent", 21, "SetSparseVector", 2)?;
+                state.serialize_field("id", id)?;
+                state.serialize_field("vector", vector)?;
+                state.end()
+            }
         }
     }
 }
@@ -464,6 +568,24 @@ impl<'de> Deserialize<'de> for KernelEvent {
                 #[serde(with = "raw_metadata_serde")]
                 metadata: Option<alloc::vec::Vec<u8>>,
             },
+            ResizePools {
+                records: u32,
+                nodes: u32,
+                edges: u32,
+            },
+            Vacuum {
+                moves: alloc::vec::Vec<(RecordId, RecordId)>,
+            },
+            DeleteMeta {
+                key: alloc::string::String,
+            },
+            Omitted {
+                record: Option<RecordId>,
+            },
+            SetSparseVector {
+                id: RecordId,
+                vector: SparseVector,
+            },
         }
 
         // Delegate to the Helper
@@ -539,6 +661,21 @@ impl<'de> Deserialize<'de> for KernelEvent {
             KernelEventHelper::UpdateRecordMetadata { id, metadata } => {
                 KernelEvent::UpdateRecordMetadata { id, metadata }
             }
+            KernelEventHelper::ResizePools {
+                records,
+                nodes,
+                edges,
+            } => KernelEvent::ResizePools {
+                records,
+                nodes,
+                edges,
+            },
+            KernelEventHelper::Vacuum { moves } => KernelEvent::Vacuum { moves },
+            KernelEventHelper::DeleteMeta { key } => KernelEvent::DeleteMeta { key },
+            KernelEventHelper::Omitted { record } => KernelEvent::Omitted { record },
+            KernelEventHelper::SetSparseVector { id, vector } => {
+                KernelEvent::SetSparseVector { id, vector }
+            }
         })
     }
 }
@@ -663,6 +800,81 @@ mod tests {
         assert_eq!(original.event_type(), "DropNamespace");
     }
 
+    #[test]
+    fn test_resize_pools_roundtrip() {
+        let original = KernelEvent::ResizePools {
+            records: 1_000_000,
+            nodes: 2_000,
+            edges: 3_000,
+        };
+        let bytes = bincode::serde::encode_to_vec(&original, bincode::config::standard()).unwrap();
+        // Variant index 17 — appended after UpdateRecordMetadata.
+        assert_eq!(bytes[0], 17);
+        let (decoded, _): (KernelEvent, _) =
+            bincode::serde::decode_from_slice(&bytes, bincode::config::standard()).unwrap();
+        assert_eq!(original, decoded);
+        assert_eq!(original.event_type(), "ResizePools");
+    }
+
+    #[test]
+    fn test_vacuum_roundtrip() {
+        let original = KernelEvent::Vacuum {
+            moves: alloc::vec![(RecordId(3), RecordId(1)), (RecordId(7), RecordId(2))],
+        };
+        let bytes = bincode::serde::encode_to_vec(&original, bincode::config::standard()).unwrap();
+        // Variant index 18 — appended after ResizePools.
+        assert_eq!(bytes[0], 18);
+        let (decoded, _): (KernelEvent, _) =
+            bincode::serde::decode_from_slice(&bytes, bincode::config::standard()).unwrap();
+        assert_eq!(original, decoded);
+        assert_eq!(original.event_type(), "Vacuum");
+    }
+
+    #[test]
+    fn test_delete_meta_roundtrip() {
+        let original = KernelEvent::DeleteMeta {
+            key: "record:7".into(),
+        };
+        let bytes = bincode::serde::encode_to_vec(&original, bincode::config::standard()).unwrap();
+        // Variant index 19 — appended after Vacuum.
+        assert_eq!(bytes[0], 19);
+        let (decoded, _): (KernelEvent, _) =
+            bincode::serde::decode_from_slice(&bytes, bincode::config::standard()).unwrap();
+        assert_eq!(original, decoded);
+        assert_eq!(original.event_type(), "DeleteMeta");
+    }
+
+    #[test]
+    fn test_omitted_roundtrip() {
+        let original = KernelEvent::Omitted {
+            record: Some(RecordId(4)),
+        };
+        let bytes = bincode::serde::encode_to_vec(&original, bincode::config::standard()).unwrap();
+        // Variant index 20 — appended after DeleteMeta.
+        assert_eq!(bytes[0], 20);
+        let (decoded, _): (KernelEvent, _) =
+            bincode::serde::decode_from_slice(&bytes, bincode::config::standard()).unwrap();
+        assert_eq!(original, decoded);
+        assert_eq!(original.event_type(), "Omitted");
+    }
+
+    #[test]
+    fn test_set_sparse_vector_roundtrip() {
+        use crate::types::scalar::FxpScalar;
+        let original = KernelEvent::SetSparseVector {
+            id: RecordId(3),
+            vector: SparseVector::from_pairs(alloc::vec![(7, FxpScalar(5)), (2, FxpScalar(-1))])
+                .unwrap(),
+        };
+        let bytes = bincode::serde::encode_to_vec(&original, bincode::config::standard()).unwrap();
+        // Variant index 21 — appended after Omitted.
+        assert_eq!(bytes[0], 21);
+        let (decoded, _): (KernelEvent, _) =
+            bincode::serde::decode_from_slice(&bytes, bincode::config::standard()).unwrap();
+        assert_eq!(original, decoded);
+        assert_eq!(original.event_type(), "SetSparseVector");
+    }
+
     #[test]
     fn test_namespace_events_serialization_determinism() {
         let create = KernelEvent::AutoCreateNamespace {
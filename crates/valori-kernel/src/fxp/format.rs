@@ -104,3 +104,4 @@ pub fn parse_format(name: &str) -> Option<u8> {
 // whole codebase still computes through `config::FRAC_BITS`.
 const _: () = assert!(Q16_16::FRAC_BITS == crate::config::FRAC_BITS);
 const _: () = assert!(1i32 << Q16_16::FRAC_BITS == crate::config::SCALE);
+const _: () = assert!(Q16_16::FORMAT_ID == valori_protocol::fxp::FORMAT_Q16_16);
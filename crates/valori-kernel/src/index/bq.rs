@@ -1,10 +1,11 @@
 // Copyright (c) 2025 Varshith Gudur. Dual-licensed under MIT OR Apache-2.0.
 //! 1-bit Binary Quantization (BQ) index with two-stage exact L2 rescoring.
 
-use crate::index::{SearchResult, VectorIndex};
-use crate::math::l2::fxp_l2_sq;
+use crate::index::{SearchResult, TagFilter, VectorIndex};
+use crate::math::l2::fxp_l2_sq_slice;
 use crate::storage::pool::RecordPool;
 use crate::types::id::RecordId;
+use crate::types::scalar::FxpScalar;
 use crate::types::vector::FxpVector;
 use core::cmp::Ordering;
 
@@ -38,10 +39,14 @@ impl BinaryQuantizationIndex {
     /// Quantize a Q16.16 vector into a packed 1-bit bitstring.
     /// Bit `i` is 1 if `vec.data[i] > 0`, 0 otherwise.
     pub fn encode_vector(&self, vec: &FxpVector) -> alloc::vec::Vec<u64> {
+        Self::encode_scalars(vec.as_slice())
+    }
+
+    fn encode_scalars(vec: &[FxpScalar]) -> alloc::vec::Vec<u64> {
         let dim = vec.len();
         let words = (dim + 63) / 64;
         let mut code = alloc::vec![0u64; words];
-        for (i, scalar) in vec.data.iter().enumerate() {
+        for (i, scalar) in vec.iter().enumerate() {
             if scalar.0 > 0 {
                 code[i / 64] |= 1u64 << (i % 64);
             }
@@ -105,8 +110,8 @@ impl VectorIndex for BinaryQuantizationIndex {
 
         for opt in pool.raw_records() {
             if let Some(r) = opt {
-                if !r.vector.data.is_empty() {
-                    self.dim = r.vector.len();
+                if !pool.vector(r).is_empty() {
+                    self.dim = pool.vector(r).len();
                     self.words_per_vec = (self.dim + 63) / 64;
                     break;
                 }
@@ -121,8 +126,8 @@ impl VectorIndex for BinaryQuantizationIndex {
             .resize(pool.total_slots() * self.words_per_vec, 0);
         for (idx, opt) in pool.raw_records().iter().enumerate() {
             if let Some(r) = opt {
-                if r.is_searchable() && r.vector.len() == self.dim {
-                    let code = self.encode_vector(&r.vector);
+                if r.is_searchable() && pool.vector(r).len() == self.dim {
+                    let code = Self::encode_scalars(pool.vector(r));
                     let start = idx * self.words_per_vec;
                     self.codes[start..start + self.words_per_vec].copy_from_slice(&code);
                 }
@@ -135,7 +140,7 @@ impl VectorIndex for BinaryQuantizationIndex {
         pool: &RecordPool,
         query: &FxpVector,
         results: &mut [SearchResult],
-        filter: Option<u64>,
+        filter: Option<TagFilter>,
     ) -> usize {
         let k = results.len();
         if k == 0 {
@@ -163,10 +168,8 @@ impl VectorIndex for BinaryQuantizationIndex {
             if !record.is_searchable() {
                 continue;
             }
-            if let Some(req_tag) = filter {
-                if record.tag != req_tag {
-                    continue;
-                }
+            if filter.is_some_and(|f| !f.matches(record.tag)) {
+                continue;
             }
 
             let start = record.id.0 as usize * self.words_per_vec;
@@ -199,7 +202,7 @@ impl VectorIndex for BinaryQuantizationIndex {
                 _ => continue,
             };
 
-            let dist_sq = fxp_l2_sq(&record.vector, query);
+            let dist_sq = fxp_l2_sq_slice(pool.vector(record), query.as_slice());
             let res = SearchResult {
                 score: dist_sq,
                 id: record.id,
@@ -1,8 +1,8 @@
 // Copyright (c) 2025 Varshith Gudur. Dual-licensed under MIT OR Apache-2.0.
 //! Brute-force index.
 
-use crate::index::{SearchResult, VectorIndex};
-use crate::math::l2::fxp_l2_sq;
+use crate::index::{SearchResult, TagFilter, VectorIndex};
+use crate::math::l2::fxp_l2_sq_slice;
 use crate::storage::pool::RecordPool;
 use crate::types::id::RecordId;
 use crate::types::vector::FxpVector;
@@ -24,7 +24,7 @@ impl VectorIndex for BruteForceIndex {
         pool: &RecordPool,
         query: &FxpVector,
         results: &mut [SearchResult],
-        filter: Option<u64>,
+        filter: Option<TagFilter>,
     ) -> usize {
         let k = results.len();
         if k == 0 {
@@ -37,13 +37,11 @@ impl VectorIndex for BruteForceIndex {
         let mut heap: BinaryHeap<SearchResult> = BinaryHeap::with_capacity(k + 1);
 
         for record in pool.iter() {
-            if let Some(req_tag) = filter {
-                if record.tag != req_tag {
-                    continue;
-                }
+            if filter.is_some_and(|f| !f.matches(record.tag)) {
+                continue;
             }
 
-            let dist_sq = fxp_l2_sq(&record.vector, query);
+            let dist_sq = fxp_l2_sq_slice(pool.vector(record), query.as_slice());
             let candidate = SearchResult {
                 score: dist_sq,
                 id: record.id,
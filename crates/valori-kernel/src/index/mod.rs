@@ -8,6 +8,7 @@ use crate::storage::pool::RecordPool;
 use crate::types::id::RecordId;
 use crate::types::vector::FxpVector;
 use core::cmp::Ordering;
+use serde::{Deserialize, Serialize};
 
 #[derive(Clone, Copy, Debug, PartialEq, Eq, Default)]
 pub struct SearchResult {
@@ -32,6 +33,40 @@ impl Ord for SearchResult {
     }
 }
 
+/// Records a cancellable scan visits between two polls of its
+/// cancellation check.
+pub const CANCEL_CHECK_INTERVAL: usize = 1024;
+
+/// Which record tags a search admits.
+///
+/// `Exact` is the original single-tag match. `Any` and `All` read the tag as
+/// a 64-bit mask, so a record can carry up to 64 labels: `Any(m)` admits a
+/// record sharing at least one bit with `m`, `All(m)` one carrying every bit
+/// of `m`. Serialized as `{"exact": t}`, `{"any": m}` or `{"all": m}`.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum TagFilter {
+    Exact(u64),
+    Any(u64),
+    All(u64),
+}
+
+impl TagFilter {
+    pub fn matches(self, tag: u64) -> bool {
+        match self {
+            TagFilter::Exact(t) => tag == t,
+            TagFilter::Any(mask) => tag & mask != 0,
+            TagFilter::All(mask) => tag & mask == mask,
+        }
+    }
+}
+
+impl From<u64> for TagFilter {
+    fn from(tag: u64) -> Self {
+        TagFilter::Exact(tag)
+    }
+}
+
 pub trait VectorIndex {
     fn on_insert(&mut self, id: RecordId, vec: &FxpVector);
     fn on_delete(&mut self, id: RecordId);
@@ -41,7 +76,7 @@ pub trait VectorIndex {
         pool: &RecordPool,
         query: &FxpVector,
         results: &mut [SearchResult],
-        filter: Option<u64>,
+        filter: Option<TagFilter>,
     ) -> usize;
 }
 
@@ -50,13 +85,15 @@ pub trait VectorIndex {
 /// Only `no_std`-compatible (fixed-point, alloc-only) variants live here.
 /// `HNSW` and `IVF` are not yet implemented in the kernel; selecting them at
 /// the node level maps to `BruteForce` in the kernel with an explicit log
-/// warning — they are documented, not silent.
+/// warning — they are documented, not silent. The one HNSW implementation is
+/// `valori_index::HnswIndex`, which the engine drives through
+/// `valori_index::VectorIndex`.
 #[derive(Clone, Debug, PartialEq, Eq)]
 pub enum IndexVariant {
     BruteForce,
     BinaryQuantization,
-    // Hnsw,  // not yet kernel-native; node uses its own std-only HnswIndex
-    // Ivf,   // not yet kernel-native; node uses its own std-only IvfIndex
+    // Hnsw,  // not yet kernel-native; the engine uses valori_index::HnswIndex
+    // Ivf,   // not yet kernel-native; the engine uses valori_index::IvfIndex
 }
 
 /// Polymorphic kernel index. Wraps every `no_std`-compatible index in a single
@@ -112,7 +149,7 @@ impl VectorIndex for ActiveIndex {
         pool: &RecordPool,
         query: &FxpVector,
         results: &mut [SearchResult],
-        filter: Option<u64>,
+        filter: Option<TagFilter>,
     ) -> usize {
         match self {
             ActiveIndex::BruteForce(i) => i.search(pool, query, results, filter),
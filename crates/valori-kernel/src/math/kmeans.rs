@@ -0,0 +1,168 @@
+// Copyright (c) 2025 Varshith Gudur. Dual-licensed under MIT OR Apache-2.0.
+//! Deterministic k-means over fixed-point vectors.
+//!
+//! Shared by IVF list training, PQ codebooks and anything else that needs
+//! centroids, so training never pulls float nondeterminism into the kernel:
+//!
+//! - Seeding: the `k` points with the smallest FNV-1a hash of
+//!   `(vector bytes LE, id LE)`, ties by id — no RNG, no input-order
+//!   dependence.
+//! - Assignment: squared L2 in i64; equal distances go to the lower
+//!   centroid index.
+//! - Update: per-dimension i128 sum, truncating division by the cluster
+//!   size, clamped to i32. An empty cluster keeps its previous centroid.
+//! - Exactly `iterations` rounds — no float convergence threshold.
+
+use crate::error::{KernelError, Result};
+use crate::math::l2::fxp_l2_sq;
+use crate::types::scalar::FxpScalar;
+use crate::types::vector::FxpVector;
+use alloc::vec;
+use alloc::vec::Vec;
+
+fn seed_hash(id: u32, v: &FxpVector) -> u64 {
+    const FNV_OFFSET: u64 = 0xcbf29ce484222325;
+    const FNV_PRIME: u64 = 0x100000001b3;
+    let mut hash = FNV_OFFSET;
+    for s in v.as_slice() {
+        for byte in s.0.to_le_bytes() {
+            hash ^= byte as u64;
+            hash = hash.wrapping_mul(FNV_PRIME);
+        }
+    }
+    for byte in id.to_le_bytes() {
+        hash ^= byte as u64;
+        hash = hash.wrapping_mul(FNV_PRIME);
+    }
+    hash
+}
+
+/// Index of the centroid closest to `v` (lowest index on ties), or `None`
+/// when `centroids` is empty.
+pub fn nearest_centroid(v: &FxpVector, centroids: &[FxpVector]) -> Option<usize> {
+    let mut best: Option<(i64, usize)> = None;
+    for (c, centroid) in centroids.iter().enumerate() {
+        let d = fxp_l2_sq(v, centroid);
+        if best.map_or(true, |(bd, _)| d < bd) {
+            best = Some((d, c));
+        }
+    }
+    best.map(|(_, c)| c)
+}
+
+/// `k` centroids for `points` (`(id, vector)` pairs) after `iterations`
+/// rounds. With `k >= points.len()` every point is its own centroid, in id
+/// order. Errors with `DimensionMismatch` unless all vectors share the
+/// first one's dimension.
+pub fn kmeans(points: &[(u32, FxpVector)], k: usize, iterations: usize) -> Result<Vec<FxpVector>> {
+    let Some((_, first)) = points.first() else {
+        return Ok(Vec::new());
+    };
+    if k == 0 {
+        return Ok(Vec::new());
+    }
+    let dim = first.len();
+    if let Some((_, v)) = points.iter().find(|(_, v)| v.len() != dim) {
+        return Err(KernelError::DimensionMismatch {
+            expected: dim,
+            found: v.len(),
+        });
+    }
+
+    if k >= points.len() {
+        let mut sorted: Vec<&(u32, FxpVector)> = points.iter().collect();
+        sorted.sort_by_key(|(id, _)| *id);
+        return Ok(sorted.into_iter().map(|(_, v)| v.clone()).collect());
+    }
+
+    let mut seeds: Vec<(u64, u32, &FxpVector)> = points
+        .iter()
+        .map(|(id, v)| (seed_hash(*id, v), *id, v))
+        .collect();
+    seeds.sort_by(|a, b| a.0.cmp(&b.0).then_with(|| a.1.cmp(&b.1)));
+    let mut centroids: Vec<FxpVector> =
+        seeds.iter().take(k).map(|(_, _, v)| (*v).clone()).collect();
+
+    for _ in 0..iterations {
+        let mut sums: Vec<Vec<i128>> = vec![vec![0i128; dim]; k];
+        let mut counts: Vec<usize> = vec![0; k];
+
+        for (_, v) in points {
+            // `centroids` is non-empty here (k > 0).
+            let c = nearest_centroid(v, &centroids).unwrap_or(0);
+            counts[c] += 1;
+            for (sum, s) in sums[c].iter_mut().zip(v.as_slice()) {
+                *sum = sum.saturating_add(s.0 as i128);
+            }
+        }
+
+        for (c, centroid) in centroids.iter_mut().enumerate() {
+            if counts[c] == 0 {
+                continue;
+            }
+            for (d, s) in centroid.as_mut_slice().iter_mut().enumerate() {
+                let avg = sums[c][d] / counts[c] as i128;
+                *s = FxpScalar(avg.clamp(i32::MIN as i128, i32::MAX as i128) as i32);
+            }
+        }
+    }
+
+    Ok(centroids)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn point(id: u32, x: i32, y: i32) -> (u32, FxpVector) {
+        (
+            id,
+            FxpVector {
+                data: vec![FxpScalar(x), FxpScalar(y)],
+            },
+        )
+    }
+
+    #[test]
+    fn separates_two_clusters_independent_of_input_order() {
+        let mut points: Vec<(u32, FxpVector)> = (0..10)
+            .map(|i| point(i, i as i32, 0))
+            .chain((10..20).map(|i| point(i, 1_000_000 + i as i32, 5)))
+            .collect();
+        let a = kmeans(&points, 2, 10).unwrap();
+        points.reverse();
+        let b = kmeans(&points, 2, 10).unwrap();
+        assert_eq!(a, b);
+
+        let mut xs: Vec<i32> = a.iter().map(|c| c[0].0).collect();
+        xs.sort_unstable();
+        // Truncating means of 0..10 and 1_000_010..1_000_020.
+        assert_eq!(xs, vec![4, 1_000_014]);
+    }
+
+    #[test]
+    fn edge_cases() {
+        assert!(kmeans(&[], 3, 5).unwrap().is_empty());
+        assert!(kmeans(&[point(0, 1, 1)], 0, 5).unwrap().is_empty());
+
+        let all = kmeans(&[point(2, 2, 0), point(1, 1, 0)], 5, 5).unwrap();
+        assert_eq!(all.iter().map(|c| c[0].0).collect::<Vec<_>>(), vec![1, 2]);
+
+        let bad = [point(0, 0, 0), (1, FxpVector::new_zeros(3))];
+        assert!(matches!(
+            kmeans(&bad, 1, 1),
+            Err(KernelError::DimensionMismatch {
+                expected: 2,
+                found: 3
+            })
+        ));
+    }
+
+    #[test]
+    fn nearest_centroid_breaks_ties_by_lowest_index() {
+        let cs = [point(0, -2, 0).1, point(1, 2, 0).1];
+        assert_eq!(nearest_centroid(&point(9, 0, 0).1, &cs), Some(0));
+        assert_eq!(nearest_centroid(&point(9, 1, 0).1, &cs), Some(1));
+        assert_eq!(nearest_centroid(&point(9, 1, 0).1, &[]), None);
+    }
+}
@@ -9,6 +9,7 @@
 //! The result is the same integer value regardless of path — SIMD is purely a
 //! throughput optimisation, not an approximation.  No floating-point is used.
 
+use crate::types::scalar::FxpScalar;
 use crate::types::vector::FxpVector;
 
 // ── public entry point ────────────────────────────────────────────────────────
@@ -16,8 +17,12 @@ use crate::types::vector::FxpVector;
 /// Squared L2 distance between two Q16.16 vectors (returns i64, no fp).
 #[inline(always)]
 pub fn fxp_l2_sq(a: &FxpVector, b: &FxpVector) -> i64 {
-    let a = a.as_slice();
-    let b = b.as_slice();
+    fxp_l2_sq_slice(a.as_slice(), b.as_slice())
+}
+
+/// [`fxp_l2_sq`] over scalar slices, e.g. vectors read from a vector file.
+#[inline(always)]
+pub fn fxp_l2_sq_slice(a: &[FxpScalar], b: &[FxpScalar]) -> i64 {
     let len = a.len().min(b.len());
 
     // Cast &[FxpScalar] → &[i32]; FxpScalar is #[repr(transparent)] over i32.
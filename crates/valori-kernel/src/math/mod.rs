@@ -1,3 +1,5 @@
 pub mod dot;
 // Copyright (c) 2025 Varshith Gudur. Dual-licensed under MIT OR Apache-2.0.
+pub mod kmeans;
 pub mod l2;
+pub mod prng;
@@ -0,0 +1,196 @@
+// Copyright (c) 2025 Varshith Gudur. Dual-licensed under MIT OR Apache-2.0.
+//! Deterministic PRNG — xoshiro256** with fixed seeding rules.
+//!
+//! Anything "random" that can influence state or results (IVF training
+//! samples, load-test workloads, randomized tests) draws from here, so the
+//! same seed yields the same stream on every platform and build. The
+//! generator is integer-only: no floats, no `std`, no OS entropy.
+//!
+//! Seeding rules (part of the determinism contract — never change them):
+//! - [`Prng::new`]`(seed)` expands the `u64` seed into the 256-bit state with
+//!   four SplitMix64 outputs, as recommended by the xoshiro authors.
+//! - [`Prng::derive`]`(seed, label)` gives an independent stream per
+//!   component: the state is `BLAKE3("valori-prng" || seed LE || label)`.
+//! - An all-zero state (xoshiro's only fixed point) is replaced by
+//!   `Prng::new(0)`'s state.
+
+use crate::fxp::qformat::FRAC_BITS;
+use crate::types::scalar::FxpScalar;
+use alloc::vec::Vec;
+
+/// Domain separator for [`Prng::derive`].
+const DERIVE_DOMAIN: &[u8] = b"valori-prng";
+
+fn splitmix64(x: &mut u64) -> u64 {
+    *x = x.wrapping_add(0x9E37_79B9_7F4A_7C15);
+    let mut z = *x;
+    z = (z ^ (z >> 30)).wrapping_mul(0xBF58_476D_1CE4_E5B9);
+    z = (z ^ (z >> 27)).wrapping_mul(0x94D0_49BB_1331_11EB);
+    z ^ (z >> 31)
+}
+
+/// Seeded xoshiro256** generator.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct Prng {
+    s: [u64; 4],
+}
+
+impl Prng {
+    /// Generator for `seed` (SplitMix64 expansion).
+    pub fn new(seed: u64) -> Self {
+        let mut x = seed;
+        Self {
+            s: [
+                splitmix64(&mut x),
+                splitmix64(&mut x),
+                splitmix64(&mut x),
+                splitmix64(&mut x),
+            ],
+        }
+    }
+
+    /// Independent stream for the component named `label` under `seed`.
+    pub fn derive(seed: u64, label: &[u8]) -> Self {
+        let mut h = blake3::Hasher::new();
+        h.update(DERIVE_DOMAIN);
+        h.update(&seed.to_le_bytes());
+        h.update(label);
+        Self::from_state_bytes(h.finalize().as_bytes())
+    }
+
+    /// Generator whose state is `bytes` read as four little-endian `u64`s.
+    pub fn from_state_bytes(bytes: &[u8; 32]) -> Self {
+        let mut s = [0u64; 4];
+        for (word, chunk) in s.iter_mut().zip(bytes.chunks_exact(8)) {
+            *word = u64::from_le_bytes(chunk.try_into().unwrap());
+        }
+        if s == [0; 4] {
+            return Self::new(0);
+        }
+        Self { s }
+    }
+
+    pub fn next_u64(&mut self) -> u64 {
+        let result = self.s[1].wrapping_mul(5).rotate_left(7).wrapping_mul(9);
+        let t = self.s[1] << 17;
+        self.s[2] ^= self.s[0];
+        self.s[3] ^= self.s[1];
+        self.s[1] ^= self.s[2];
+        self.s[0] ^= self.s[3];
+        self.s[2] ^= t;
+        self.s[3] = self.s[3].rotate_left(45);
+        result
+    }
+
+    /// Upper 32 bits of the next output (the strongest bits).
+    pub fn next_u32(&mut self) -> u32 {
+        (self.next_u64() >> 32) as u32
+    }
+
+    /// Uniform in `0..n` without modulo bias (Lemire's method).
+    /// Returns 0 when `n == 0`.
+    pub fn below(&mut self, n: u64) -> u64 {
+        if n == 0 {
+            return 0;
+        }
+        let mut m = (self.next_u64() as u128) * (n as u128);
+        if (m as u64) < n {
+            let threshold = n.wrapping_neg() % n;
+            while (m as u64) < threshold {
+                m = (self.next_u64() as u128) * (n as u128);
+            }
+        }
+        (m >> 64) as u64
+    }
+
+    /// Uniform fixed-point value in `[0, 1)`.
+    pub fn next_fxp_unit(&mut self) -> FxpScalar {
+        FxpScalar((self.next_u64() >> (64 - FRAC_BITS)) as i32)
+    }
+
+    /// Fisher–Yates shuffle.
+    pub fn shuffle<T>(&mut self, items: &mut [T]) {
+        for i in (1..items.len()).rev() {
+            let j = self.below(i as u64 + 1) as usize;
+            items.swap(i, j);
+        }
+    }
+
+    /// `k` distinct indices from `0..n` (all of them, shuffled, when
+    /// `k >= n`), in draw order.
+    pub fn sample_indices(&mut self, n: usize, k: usize) -> Vec<usize> {
+        let mut pool: Vec<usize> = (0..n).collect();
+        let k = k.min(n);
+        for i in 0..k {
+            let j = i + self.below((n - i) as u64) as usize;
+            pool.swap(i, j);
+        }
+        pool.truncate(k);
+        pool
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn stream_is_pinned() {
+        // Reference xoshiro256** with SplitMix64 seeding. Changing these
+        // values breaks the determinism contract.
+        let mut r = Prng::new(0);
+        assert_eq!(
+            [r.next_u64(), r.next_u64(), r.next_u64(), r.next_u64()],
+            [
+                0x99ec5f36cb75f2b4,
+                0xbf6e1f784956452a,
+                0x1a5f849d4933e6e0,
+                0x6aa594f1262d2d2c
+            ]
+        );
+        let mut r = Prng::new(42);
+        assert_eq!(r.next_u64(), 0x15780b2e0c2ec716);
+        assert_eq!(r.next_u64(), 0x6104d9866d113a7e);
+    }
+
+    #[test]
+    fn derive_separates_streams_and_rejects_zero_state() {
+        let a = Prng::derive(7, b"ivf-train");
+        assert_eq!(a, Prng::derive(7, b"ivf-train"));
+        assert_ne!(a, Prng::derive(7, b"load-test"));
+        assert_ne!(a, Prng::derive(8, b"ivf-train"));
+        assert_eq!(Prng::from_state_bytes(&[0; 32]), Prng::new(0));
+    }
+
+    #[test]
+    fn ranges_and_sampling_stay_in_bounds() {
+        let mut r = Prng::new(1);
+        for n in [1u64, 2, 3, 10, 1 << 40, u64::MAX] {
+            for _ in 0..64 {
+                assert!(r.below(n) < n);
+            }
+        }
+        assert_eq!(r.below(0), 0);
+        for _ in 0..64 {
+            let u = r.next_fxp_unit().0;
+            assert!((0..1 << FRAC_BITS).contains(&u));
+        }
+
+        let s = r.sample_indices(10, 4);
+        assert_eq!(s.len(), 4);
+        let mut sorted = s.clone();
+        sorted.sort_unstable();
+        sorted.dedup();
+        assert_eq!(sorted.len(), 4);
+        assert!(s.iter().all(|&i| i < 10));
+        assert_eq!(r.sample_indices(3, 9).len(), 3);
+
+        let mut v: Vec<u32> = (0..16).collect();
+        Prng::new(5).shuffle(&mut v);
+        let mut w: Vec<u32> = (0..16).collect();
+        Prng::new(5).shuffle(&mut w);
+        assert_eq!(v, w);
+        w.sort_unstable();
+        assert_eq!(w, (0..16).collect::<Vec<_>>());
+    }
+}
@@ -60,11 +60,22 @@ use blake3;
 ///   from (u32 LE)
 ///   to (u32 LE)
 ///   next_out (Option<u32> LE, None = u32::MAX)
+/// ↓
+/// Only after a ResizePools: "capacity" || records, nodes, edges (u32 LE)
+/// ↓
+/// Only with a non-empty meta map: "meta" || count (u32 LE), then for each
+/// entry in key order: key length (u32 LE) + key, value length (u32 LE) + value
+/// ↓
+/// Only with any sparse vector: "sparse" || count (u32 LE), then for each
+/// in record-id order: id (u32 LE), nnz (u32 LE), then (index u32 LE,
+/// value i32 LE) pairs
 /// ```
 ///
 /// Returns: [u8; 32] - BLAKE3 hash
 /// Version of the hash-input schema itself. Bumped whenever the structure
 /// below changes (v2 = added domain separation + tag/metadata coverage).
+/// Optional sections appended only when present (capacity, meta, sparse)
+/// leave every state without them hashing as before and need no bump.
 /// A state hashed under one domain version can never collide with the
 /// same bytes hashed under another — hash changes are versioned, visible
 /// events, not silent drift.
@@ -88,7 +99,7 @@ pub fn hash_state_blake3(state: &KernelState) -> [u8; 32] {
     for record in state.records.iter() {
         hasher.update(&record.id.0.to_le_bytes());
         hasher.update(&[record.flags]);
-        for scalar in record.vector.data.iter() {
+        for scalar in state.records.vector(record) {
             hasher.update(&scalar.0.to_le_bytes());
         }
         // Tag and metadata are state: tags drive filtered search and
@@ -155,6 +166,43 @@ pub fn hash_state_blake3(state: &KernelState) -> [u8; 32] {
         }
     }
 
+    // Pool capacities are state once committed. States that never saw a
+    // ResizePools hash exactly as before.
+    if let Some(cap) = state.capacity {
+        hasher.update(b"capacity");
+        hasher.update(&cap.records.to_le_bytes());
+        hasher.update(&cap.nodes.to_le_bytes());
+        hasher.update(&cap.edges.to_le_bytes());
+    }
+
+    // The meta map is state too: SetMeta / DeleteMeta replay it on every
+    // replica. BTreeMap order is deterministic; like the capacity section,
+    // it is only hashed when present.
+    if !state.meta.is_empty() {
+        hasher.update(b"meta");
+        hasher.update(&(state.meta.len() as u32).to_le_bytes());
+        for (key, value) in state.meta.iter() {
+            hasher.update(&(key.len() as u32).to_le_bytes());
+            hasher.update(key.as_bytes());
+            hasher.update(&(value.len() as u32).to_le_bytes());
+            hasher.update(value.as_bytes());
+        }
+    }
+
+    // Sparse vectors, likewise only when any record holds one.
+    if !state.sparse.is_empty() {
+        hasher.update(b"sparse");
+        hasher.update(&(state.sparse.len() as u32).to_le_bytes());
+        for (id, vector) in state.sparse.iter() {
+            hasher.update(&id.0.to_le_bytes());
+            hasher.update(&(vector.nnz() as u32).to_le_bytes());
+            for (index, value) in vector.iter() {
+                hasher.update(&index.to_le_bytes());
+                hasher.update(&value.0.to_le_bytes());
+            }
+        }
+    }
+
     *hasher.finalize().as_bytes()
 }
 
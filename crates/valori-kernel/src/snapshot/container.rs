@@ -0,0 +1,232 @@
+//! Snapshot container — the one snapshot file format.
+//!
+//! The engine, the node's snapshot catalog, the forensic CLI and the wasm
+//! kernel all read and write this layout, so a snapshot taken anywhere loads
+//! anywhere:
+//!
+//! ```text
+//! [4 B]  magic "VAL1"
+//! [u32]  kernel_len    [kernel_len B]  VALK-encoded KernelState
+//! [u32]  metadata_len  [metadata_len B]
+//! [u32]  index_len     [index_len B]
+//! ( [4 B tag] [u32 len] [len B] )*     tagged sections, in write order
+//! [4 B]  "CRC3"  [u32] 4  [u32] CRC32 of every byte before "CRC3"
+//! ```
+//!
+//! All lengths are little-endian. The three fixed sections are always
+//! present (possibly empty). Tagged sections carry what only some writers
+//! have — the engine's namespace registry, named indexes and so on — and a
+//! reader skips tags it does not know. A tag may repeat. The checksum trailer
+//! is written last; containers written before it existed have none.
+//!
+//! Parsing is strict about lengths: the sections must tile the file exactly.
+//! A checksum mismatch is reported, not rejected — whether such a snapshot
+//! is still loaded is the caller's policy.
+
+// Copyright (c) 2025 Varshith Gudur. Dual-licensed under MIT OR Apache-2.0.
+use crate::error::Result;
+use crate::state::kernel::KernelState;
+use alloc::string::String;
+use alloc::vec::Vec;
+use thiserror::Error;
+
+pub use valori_protocol::formats::snapshot::{CHECKSUM_TAG, CONTAINER_MAGIC as MAGIC};
+/// Magic plus three empty fixed sections.
+pub const MIN_LEN: usize = 16;
+
+const TRAILER_LEN: usize = 12;
+
+/// Why bytes are not a well-formed container.
+#[derive(Error, Debug, Clone, PartialEq, Eq)]
+pub enum ContainerError {
+    #[error("not a snapshot container (bad magic)")]
+    BadMagic,
+
+    #[error("{section} length missing at offset {offset}")]
+    MissingLength { section: String, offset: usize },
+
+    #[error("{section} section at offset {offset} claims {len} bytes, past the end of the file")]
+    Overrun {
+        section: String,
+        offset: usize,
+        len: usize,
+    },
+
+    #[error("truncated section tag at offset {offset}")]
+    TruncatedTag { offset: usize },
+}
+
+/// State of the checksum trailer.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Checksum {
+    /// Written before the trailer existed.
+    Absent,
+    Valid,
+    Mismatch {
+        stored: u32,
+        computed: u32,
+    },
+}
+
+/// One tagged section.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Section<'a> {
+    pub tag: [u8; 4],
+    pub data: &'a [u8],
+}
+
+impl Section<'_> {
+    /// The tag as text, for messages.
+    pub fn name(&self) -> String {
+        String::from_utf8_lossy(&self.tag).into_owned()
+    }
+}
+
+/// A parsed container, borrowing from the file bytes.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Container<'a> {
+    pub kernel: &'a [u8],
+    pub metadata: &'a [u8],
+    pub index: &'a [u8],
+    /// Tagged sections in file order, without the checksum trailer.
+    pub sections: Vec<Section<'a>>,
+    pub checksum: Checksum,
+}
+
+impl<'a> Container<'a> {
+    pub fn parse(data: &'a [u8]) -> core::result::Result<Self, ContainerError> {
+        if data.get(..4) != Some(MAGIC) {
+            return Err(ContainerError::BadMagic);
+        }
+        let (body, checksum) = split_trailer(data);
+
+        let mut offset = 4;
+        let kernel = read_section(body, &mut offset, "kernel")?;
+        let metadata = read_section(body, &mut offset, "metadata")?;
+        let index = read_section(body, &mut offset, "index")?;
+        let mut sections = Vec::new();
+        while offset < body.len() {
+            let tag: [u8; 4] = body
+                .get(offset..offset + 4)
+                .and_then(|t| t.try_into().ok())
+                .ok_or(ContainerError::TruncatedTag { offset })?;
+            offset += 4;
+            let name = String::from_utf8_lossy(&tag).into_owned();
+            let data = read_section(body, &mut offset, &name)?;
+            sections.push(Section { tag, data });
+        }
+
+        Ok(Self {
+            kernel,
+            metadata,
+            index,
+            sections,
+            checksum,
+        })
+    }
+
+    /// The first section tagged `tag`.
+    pub fn section(&self, tag: &[u8; 4]) -> Option<&'a [u8]> {
+        self.sections_tagged(tag).next()
+    }
+
+    /// Every section tagged `tag`, in file order.
+    pub fn sections_tagged<'s>(&'s self, tag: &'s [u8; 4]) -> impl Iterator<Item = &'a [u8]> + 's {
+        self.sections
+            .iter()
+            .filter(move |s| &s.tag == tag)
+            .map(|s| s.data)
+    }
+
+    pub fn decode_state(&self) -> Result<KernelState> {
+        super::decode::decode_state(self.kernel)
+    }
+}
+
+/// Builds a container; [`ContainerWriter::finish`] appends the checksum.
+#[derive(Debug, Clone)]
+pub struct ContainerWriter {
+    buf: Vec<u8>,
+}
+
+impl ContainerWriter {
+    pub fn new(kernel: &[u8], metadata: &[u8], index: &[u8]) -> Self {
+        let mut buf =
+            Vec::with_capacity(MIN_LEN + kernel.len() + metadata.len() + index.len() + TRAILER_LEN);
+        buf.extend_from_slice(MAGIC);
+        for data in [kernel, metadata, index] {
+            buf.extend_from_slice(&(data.len() as u32).to_le_bytes());
+            buf.extend_from_slice(data);
+        }
+        Self { buf }
+    }
+
+    pub fn section(&mut self, tag: &[u8; 4], data: &[u8]) -> &mut Self {
+        self.buf.extend_from_slice(tag);
+        self.buf
+            .extend_from_slice(&(data.len() as u32).to_le_bytes());
+        self.buf.extend_from_slice(data);
+        self
+    }
+
+    pub fn finish(mut self) -> Vec<u8> {
+        let crc = crc32fast::hash(&self.buf);
+        self.buf.extend_from_slice(CHECKSUM_TAG);
+        self.buf.extend_from_slice(&4u32.to_le_bytes());
+        self.buf.extend_from_slice(&crc.to_le_bytes());
+        self.buf
+    }
+}
+
+/// A container holding only `state`: empty metadata and index, no tagged
+/// sections. The engine rebuilds its index from the kernel on restore.
+pub fn encode_container(state: &KernelState) -> Result<Vec<u8>> {
+    let mut kernel = Vec::with_capacity(super::encode::encode_capacity_hint(state));
+    super::encode::encode_state(state, &mut kernel)?;
+    Ok(ContainerWriter::new(&kernel, &[], &[]).finish())
+}
+
+/// Split off a well-formed trailer and check it.
+fn split_trailer(data: &[u8]) -> (&[u8], Checksum) {
+    let Some(body_end) = data.len().checked_sub(TRAILER_LEN) else {
+        return (data, Checksum::Absent);
+    };
+    let (body, trailer) = data.split_at(body_end);
+    if &trailer[..4] != CHECKSUM_TAG || trailer[4..8] != 4u32.to_le_bytes() {
+        return (data, Checksum::Absent);
+    }
+    let stored = u32::from_le_bytes([trailer[8], trailer[9], trailer[10], trailer[11]]);
+    let computed = crc32fast::hash(body);
+    let checksum = if stored == computed {
+        Checksum::Valid
+    } else {
+        Checksum::Mismatch { stored, computed }
+    };
+    (body, checksum)
+}
+
+fn read_section<'a>(
+    data: &'a [u8],
+    offset: &mut usize,
+    section: &str,
+) -> core::result::Result<&'a [u8], ContainerError> {
+    let at = *offset;
+    let len = data
+        .get(at..at + 4)
+        .map(|b| u32::from_le_bytes([b[0], b[1], b[2], b[3]]) as usize)
+        .ok_or_else(|| ContainerError::MissingLength {
+            section: section.into(),
+            offset: at,
+        })?;
+    let start = at + 4;
+    let end = start
+        .checked_add(len)
+        .filter(|&end| end <= data.len())
+        .ok_or_else(|| ContainerError::Overrun {
+            section: section.into(),
+            offset: at,
+            len,
+        })?;
+    *offset = end;
+    Ok(&data[start..end])
+}
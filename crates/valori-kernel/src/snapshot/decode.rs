@@ -19,7 +19,7 @@
 // Copyright (c) 2025 Varshith Gudur. Dual-licensed under MIT OR Apache-2.0.
 
 use crate::config::{
-    MAX_DIM, MAX_EDGES, MAX_METADATA_SIZE, MAX_META_ENTRIES, MAX_NODES, MAX_RECORDS,
+    MAX_DIM, MAX_EDGES, MAX_METADATA_SIZE, MAX_META_ENTRIES, MAX_NODES, MAX_RECORDS, MAX_SPARSE_NNZ,
 };
 use crate::error::{KernelError, Result};
 use crate::graph::edge::GraphEdge;
@@ -29,6 +29,7 @@ use crate::storage::record::Record;
 use crate::types::enums::{EdgeKind, NodeKind};
 use crate::types::id::{EdgeId, NodeId, RecordId, Version, NS_LIST_NIL};
 use crate::types::scalar::FxpScalar;
+use crate::types::sparse::SparseVector;
 use crate::types::vector::FxpVector;
 
 // ── Read helpers ─────────────────────────────────────────────────────────────
@@ -165,7 +166,8 @@ pub fn decode_state(buf: &[u8]) -> Result<KernelState> {
     off += 4;
 
     let schema_ver = read_u32(buf, &mut off)?;
-    if schema_ver < 1 || schema_ver > 7 {
+    if valori_protocol::check_version(valori_protocol::Format::KernelSnapshot, schema_ver).is_err()
+    {
         return Err(KernelError::InvalidOperation); // unsupported version
     }
 
@@ -185,6 +187,17 @@ pub fn decode_state(buf: &[u8]) -> Result<KernelState> {
         }
     }
 
+    // V8+: committed pool capacities.
+    let capacity = if schema_ver >= 8 && read_u8(buf, &mut off)? == 1 {
+        Some(crate::state::kernel::PoolCapacity {
+            records: read_u32(buf, &mut off)?,
+            nodes: read_u32(buf, &mut off)?,
+            edges: read_u32(buf, &mut off)?,
+        })
+    } else {
+        None
+    };
+
     // Validate dim before any allocation.
     let dim = dim as usize;
     if dim > MAX_DIM {
@@ -196,6 +209,7 @@ pub fn decode_state(buf: &[u8]) -> Result<KernelState> {
 
     let mut state = KernelState::new();
     state.version = Version(version_val);
+    state.capacity = capacity;
     if dim > 0 {
         state.dim = Some(dim);
     }
@@ -269,6 +283,17 @@ pub fn decode_state(buf: &[u8]) -> Result<KernelState> {
             (0u16, NS_LIST_NIL, NS_LIST_NIL)
         };
 
+        // Before V9 every record reads as never updated.
+        let version = if schema_ver >= 9 {
+            let v = read_u64(buf, &mut off)?;
+            if v == 0 {
+                return Err(KernelError::InvalidOperation);
+            }
+            v
+        } else {
+            1
+        };
+
         state.records.records[i] = Some(Record {
             id: RecordId(i as u32),
             vector,
@@ -278,6 +303,7 @@ pub fn decode_state(buf: &[u8]) -> Result<KernelState> {
             namespace_id,
             next_in_ns,
             prev_in_ns,
+            version,
         });
     }
 
@@ -449,5 +475,33 @@ pub fn decode_state(buf: &[u8]) -> Result<KernelState> {
         }
     }
 
+    // ── V10+: sparse vectors ─────────────────────────────────────────────────
+
+    if schema_ver >= 10 {
+        let count = read_u32(buf, &mut off)? as usize;
+        if count > state.records.raw_records().len() {
+            return Err(KernelError::InvalidOperation);
+        }
+        let mut last_id = None;
+        for _ in 0..count {
+            let id = RecordId(read_u32(buf, &mut off)?);
+            if last_id.is_some_and(|last| id.0 <= last) || state.records.get(id).is_none() {
+                return Err(KernelError::InvalidOperation);
+            }
+            last_id = Some(id.0);
+            let nnz = read_u32(buf, &mut off)? as usize;
+            if nnz == 0 || nnz > MAX_SPARSE_NNZ {
+                return Err(KernelError::InvalidOperation);
+            }
+            let mut vector = SparseVector::default();
+            for _ in 0..nnz {
+                vector.indices.push(read_u32(buf, &mut off)?);
+                vector.values.push(FxpScalar(read_i32(buf, &mut off)?));
+            }
+            vector.validate()?;
+            state.sparse.set(id, vector);
+        }
+    }
+
     Ok(state)
 }
@@ -18,8 +18,9 @@
 use crate::error::Result;
 use crate::state::kernel::KernelState;
 
-pub const MAGIC: &[u8; 4] = b"VALK";
-pub const SCHEMA_VERSION: u32 = 7; // V7: adds the KernelState.meta sidecar (SetMeta-committed key/value pairs)
+pub use valori_protocol::formats::snapshot::{
+    KERNEL_MAGIC as MAGIC, KERNEL_SCHEMA_VERSION as SCHEMA_VERSION,
+};
 
 // ── infallible push helpers ────────────────────────────────────────────────────
 // Writing to a Vec<u8> can only fail on OOM, which panics (same as any alloc).
@@ -62,7 +63,8 @@ fn push_bytes(out: &mut alloc::vec::Vec<u8>, data: &[u8]) {
 /// V6 per-record layout (present slot):
 ///   1 (flag) + 4 (id) + 1 (flags) + 8 (tag) + dim×4 (vector)
 ///   + 4 (metadata len) + 2 (namespace_id) + 4 (next_in_ns) + 4 (prev_in_ns)
-///   = 28 + dim×4
+///   + 8 (V9 version)
+///   = 36 + dim×4
 ///
 /// Absent slot: 1 byte.  We pessimistically assume all slots are present.
 pub fn encode_capacity_hint(state: &KernelState) -> usize {
@@ -72,11 +74,12 @@ pub fn encode_capacity_hint(state: &KernelState) -> usize {
     let edge_count = state.edge_count();
 
     64                                          // header
-    + total_slots * (28 + dim * 4)             // records (V6 layout, all present)
+    + total_slots * (36 + dim * 4)             // records (V9 layout, all present)
     + node_count  * 30                         // nodes   (V6 layout)
     + edge_count  * 29                         // edges
     + 2 * 1024 * 4                             // namespace head arrays (2 × 1024 × u32)
     + state.meta.len() * 128                   // V7: rough per-entry meta estimate
+    + state.sparse.iter().map(|(_, v)| 8 + v.nnz() * 8).sum::<usize>() // V10: sparse
     + 4096 // small safety margin
 }
 
@@ -101,6 +104,17 @@ pub fn encode_state(state: &KernelState, out: &mut alloc::vec::Vec<u8>) -> Resul
     // V5: arithmetic format tag
     push_u8(out, crate::fxp::format::ACTIVE_FORMAT_ID);
 
+    // V8: pool capacities from the last ResizePools (flag 0 = unbounded).
+    match state.capacity {
+        Some(cap) => {
+            push_u8(out, 1);
+            push_u32(out, cap.records);
+            push_u32(out, cap.nodes);
+            push_u32(out, cap.edges);
+        }
+        None => push_u8(out, 0),
+    }
+
     // Records
     let total_slots = state.records.raw_records().len() as u32;
     push_u32(out, total_slots);
@@ -111,7 +125,7 @@ pub fn encode_state(state: &KernelState, out: &mut alloc::vec::Vec<u8>) -> Resul
             push_u32(out, record.id.0);
             push_u8(out, record.flags);
             push_u64(out, record.tag);
-            for scalar in record.vector.data.iter() {
+            for scalar in state.records.vector(record) {
                 push_i32(out, scalar.0);
             }
             match &record.metadata {
@@ -125,6 +139,8 @@ pub fn encode_state(state: &KernelState, out: &mut alloc::vec::Vec<u8>) -> Resul
             push_u16(out, record.namespace_id);
             push_u32(out, record.next_in_ns);
             push_u32(out, record.prev_in_ns);
+            // V9: per-record version
+            push_u64(out, record.version);
         } else {
             push_u8(out, 0); // absent slot
         }
@@ -226,5 +242,16 @@ pub fn encode_state(state: &KernelState, out: &mut alloc::vec::Vec<u8>) -> Resul
         push_bytes(out, value.as_bytes());
     }
 
+    // V10: sparse vectors, in record-id order.
+    push_u32(out, state.sparse.len() as u32);
+    for (id, vector) in state.sparse.iter() {
+        push_u32(out, id.0);
+        push_u32(out, vector.nnz() as u32);
+        for (index, value) in vector.iter() {
+            push_u32(out, index);
+            push_i32(out, value.0);
+        }
+    }
+
     Ok(())
 }
@@ -1,5 +1,6 @@
 // Copyright (c) 2025 Varshith Gudur. Dual-licensed under MIT OR Apache-2.0.
 pub mod blake3;
+pub mod container;
 pub mod decode;
 pub mod encode;
 pub mod hash;
@@ -0,0 +1,319 @@
+// Copyright (c) 2025 Varshith Gudur. Dual-licensed under MIT OR Apache-2.0.
+//! Structural diff between two kernel states.
+//!
+//! Unlike the CLI's semantic diff (which compares query results), this walks
+//! the pools slot by slot and reports which records, nodes and edges were
+//! added, removed or changed, together with a BLAKE3 hash of each section on
+//! both sides. Section hashes cover exactly the fields the diff compares, so
+//! two sections hash equal iff the diff reports no delta for them.
+//!
+//! Only live records count: a soft-deleted or shredded record shows up as
+//! removed. Adjacency pointers (`first_out_edge`, `next_out`, ...) are
+//! linkage, not content, and are ignored — an added edge is reported once
+//! as an edge delta rather than as a change to its endpoint nodes.
+
+use crate::state::kernel::KernelState;
+use crate::storage::record::Record;
+use alloc::vec::Vec;
+use serde::{Deserialize, Serialize};
+
+/// BLAKE3 hash of each state section.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Serialize, Deserialize)]
+pub struct SectionHashes {
+    pub records: [u8; 32],
+    pub nodes: [u8; 32],
+    pub edges: [u8; 32],
+}
+
+/// A record present on both sides whose content differs.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Serialize, Deserialize)]
+pub struct RecordChange {
+    pub id: u32,
+    pub vector: bool,
+    pub tag: bool,
+    pub metadata: bool,
+    pub flags: bool,
+}
+
+/// A node as seen by the diff (the `to` side for changed nodes).
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Serialize, Deserialize)]
+pub struct NodeDelta {
+    pub id: u32,
+    pub kind: u8,
+    pub record: Option<u32>,
+}
+
+/// An edge as seen by the diff (the `to` side for changed edges).
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Serialize, Deserialize)]
+pub struct EdgeDelta {
+    pub id: u32,
+    pub kind: u8,
+    pub from: u32,
+    pub to: u32,
+}
+
+/// Structural delta from one state to another. All lists are ascending by id.
+#[derive(Clone, Debug, PartialEq, Eq, Serialize, Deserialize)]
+pub struct StateDiff {
+    pub from_version: u64,
+    pub to_version: u64,
+    pub from_hashes: SectionHashes,
+    pub to_hashes: SectionHashes,
+    pub records_added: Vec<u32>,
+    pub records_removed: Vec<u32>,
+    pub records_changed: Vec<RecordChange>,
+    pub nodes_added: Vec<NodeDelta>,
+    pub nodes_removed: Vec<NodeDelta>,
+    pub nodes_changed: Vec<NodeDelta>,
+    pub edges_added: Vec<EdgeDelta>,
+    pub edges_removed: Vec<EdgeDelta>,
+    pub edges_changed: Vec<EdgeDelta>,
+}
+
+impl StateDiff {
+    /// True when no record, node or edge differs.
+    pub fn is_empty(&self) -> bool {
+        self.records_added.is_empty()
+            && self.records_removed.is_empty()
+            && self.records_changed.is_empty()
+            && self.nodes_added.is_empty()
+            && self.nodes_removed.is_empty()
+            && self.nodes_changed.is_empty()
+            && self.edges_added.is_empty()
+            && self.edges_removed.is_empty()
+            && self.edges_changed.is_empty()
+    }
+}
+
+fn live_record(state: &KernelState, idx: usize) -> Option<&Record> {
+    state
+        .records
+        .raw_records()
+        .get(idx)
+        .and_then(|r| r.as_ref())
+        .filter(|r| r.is_active())
+}
+
+fn node_at(state: &KernelState, idx: usize) -> Option<NodeDelta> {
+    let n = state.nodes.raw_nodes().get(idx)?.as_ref()?;
+    Some(NodeDelta {
+        id: n.id.0,
+        kind: n.kind as u8,
+        record: n.record.map(|r| r.0),
+    })
+}
+
+fn edge_at(state: &KernelState, idx: usize) -> Option<EdgeDelta> {
+    let e = state.edges.raw_edges().get(idx)?.as_ref()?;
+    Some(EdgeDelta {
+        id: e.id.0,
+        kind: e.kind as u8,
+        from: e.from.0,
+        to: e.to.0,
+    })
+}
+
+/// Hash each section of `state` over the fields `diff_states` compares.
+pub fn section_hashes(state: &KernelState) -> SectionHashes {
+    let format = [crate::fxp::format::ACTIVE_FORMAT_ID];
+
+    let mut records = blake3::Hasher::new();
+    records.update(b"valori-section-records");
+    records.update(&format);
+    for r in state.records.iter() {
+        records.update(&r.id.0.to_le_bytes());
+        records.update(&[r.flags]);
+        let vector = state.records.vector(r);
+        records.update(&(vector.len() as u32).to_le_bytes());
+        for s in vector {
+            records.update(&s.0.to_le_bytes());
+        }
+        records.update(&r.tag.to_le_bytes());
+        match &r.metadata {
+            Some(bytes) => {
+                records.update(&(bytes.len() as u32).to_le_bytes());
+                records.update(bytes);
+            }
+            None => {
+                records.update(&u32::MAX.to_le_bytes());
+            }
+        }
+    }
+
+    let mut nodes = blake3::Hasher::new();
+    nodes.update(b"valori-section-nodes");
+    for idx in 0..state.nodes.raw_nodes().len() {
+        if let Some(n) = node_at(state, idx) {
+            nodes.update(&n.id.to_le_bytes());
+            nodes.update(&[n.kind]);
+            nodes.update(&n.record.unwrap_or(u32::MAX).to_le_bytes());
+        }
+    }
+
+    let mut edges = blake3::Hasher::new();
+    edges.update(b"valori-section-edges");
+    for idx in 0..state.edges.raw_edges().len() {
+        if let Some(e) = edge_at(state, idx) {
+            edges.update(&e.id.to_le_bytes());
+            edges.update(&[e.kind]);
+            edges.update(&e.from.to_le_bytes());
+            edges.update(&e.to.to_le_bytes());
+        }
+    }
+
+    SectionHashes {
+        records: *records.finalize().as_bytes(),
+        nodes: *nodes.finalize().as_bytes(),
+        edges: *edges.finalize().as_bytes(),
+    }
+}
+
+/// Slot-by-slot comparison of two optional items.
+fn diff_slots<T: PartialEq + Copy>(
+    len: usize,
+    at: impl Fn(bool, usize) -> Option<T>,
+    added: &mut Vec<T>,
+    removed: &mut Vec<T>,
+    changed: &mut Vec<T>,
+) {
+    for idx in 0..len {
+        match (at(false, idx), at(true, idx)) {
+            (None, Some(b)) => added.push(b),
+            (Some(a), None) => removed.push(a),
+            (Some(a), Some(b)) if a != b => changed.push(b),
+            _ => {}
+        }
+    }
+}
+
+/// Structural delta that turns `from` into `to`.
+pub fn diff_states(from: &KernelState, to: &KernelState) -> StateDiff {
+    let mut diff = StateDiff {
+        from_version: from.version.0,
+        to_version: to.version.0,
+        from_hashes: section_hashes(from),
+        to_hashes: section_hashes(to),
+        records_added: Vec::new(),
+        records_removed: Vec::new(),
+        records_changed: Vec::new(),
+        nodes_added: Vec::new(),
+        nodes_removed: Vec::new(),
+        nodes_changed: Vec::new(),
+        edges_added: Vec::new(),
+        edges_removed: Vec::new(),
+        edges_changed: Vec::new(),
+    };
+
+    let record_slots = from
+        .records
+        .raw_records()
+        .len()
+        .max(to.records.raw_records().len());
+    for idx in 0..record_slots {
+        match (live_record(from, idx), live_record(to, idx)) {
+            (None, Some(b)) => diff.records_added.push(b.id.0),
+            (Some(a), None) => diff.records_removed.push(a.id.0),
+            (Some(a), Some(b)) => {
+                let change = RecordChange {
+                    id: b.id.0,
+                    vector: from.records.vector(a) != to.records.vector(b),
+                    tag: a.tag != b.tag,
+                    metadata: a.metadata != b.metadata,
+                    flags: a.flags != b.flags,
+                };
+                if change.vector || change.tag || change.metadata || change.flags {
+                    diff.records_changed.push(change);
+                }
+            }
+            (None, None) => {}
+        }
+    }
+
+    let node_slots = from.nodes.raw_nodes().len().max(to.nodes.raw_nodes().len());
+    diff_slots(
+        node_slots,
+        |side, idx| node_at(if side { to } else { from }, idx),
+        &mut diff.nodes_added,
+        &mut diff.nodes_removed,
+        &mut diff.nodes_changed,
+    );
+
+    let edge_slots = from.edges.raw_edges().len().max(to.edges.raw_edges().len());
+    diff_slots(
+        edge_slots,
+        |side, idx| edge_at(if side { to } else { from }, idx),
+        &mut diff.edges_added,
+        &mut diff.edges_removed,
+        &mut diff.edges_changed,
+    );
+
+    diff
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::event::KernelEvent;
+    use crate::types::enums::{EdgeKind, NodeKind};
+    use crate::types::id::{EdgeId, NodeId, RecordId};
+    use crate::types::vector::FxpVector;
+
+    fn insert(id: u32, tag: u64) -> KernelEvent {
+        KernelEvent::InsertRecord {
+            id: RecordId(id),
+            vector: FxpVector::new_zeros(4),
+            metadata: None,
+            tag,
+        }
+    }
+
+    #[test]
+    fn diff_reports_records_and_graph_deltas_with_section_hashes() {
+        let mut from = KernelState::new();
+        for i in 0..3 {
+            from.apply_event(&insert(i, 0)).unwrap();
+        }
+        for i in 0..2 {
+            from.apply_event(&KernelEvent::CreateNode {
+                id: NodeId(i),
+                kind: NodeKind::Record,
+                record: Some(RecordId(i)),
+            })
+            .unwrap();
+        }
+
+        let mut to = from.clone();
+        to.apply_event(&insert(3, 0)).unwrap();
+        to.apply_event(&KernelEvent::DeleteRecord { id: RecordId(1) })
+            .unwrap();
+        to.apply_event(&KernelEvent::UpdateRecordMetadata {
+            id: RecordId(2),
+            metadata: Some(alloc::vec![7]),
+        })
+        .unwrap();
+        to.apply_event(&KernelEvent::CreateEdge {
+            id: EdgeId(0),
+            kind: EdgeKind::Relation,
+            from: NodeId(0),
+            to: NodeId(1),
+        })
+        .unwrap();
+
+        let diff = diff_states(&from, &to);
+        assert_eq!(diff.records_added, alloc::vec![3]);
+        assert_eq!(diff.records_removed, alloc::vec![1]);
+        assert_eq!(diff.records_changed.len(), 1);
+        assert!(diff.records_changed[0].metadata && !diff.records_changed[0].vector);
+        assert!(diff.nodes_added.is_empty() && diff.nodes_changed.is_empty());
+        assert_eq!(diff.edges_added.len(), 1);
+        assert_eq!((diff.edges_added[0].from, diff.edges_added[0].to), (0, 1));
+
+        assert_ne!(diff.from_hashes.records, diff.to_hashes.records);
+        assert_eq!(diff.from_hashes.nodes, diff.to_hashes.nodes);
+        assert_ne!(diff.from_hashes.edges, diff.to_hashes.edges);
+
+        let same = diff_states(&to, &to);
+        assert!(same.is_empty());
+        assert_eq!(same.from_hashes, same.to_hashes);
+    }
+}
@@ -0,0 +1,199 @@
+// Copyright (c) 2025 Varshith Gudur. Dual-licensed under MIT OR Apache-2.0.
+//! Structural invariants of the graph pools.
+//!
+//! `KernelState::check_invariants` stops at the first problem, which is what
+//! the apply path wants. Operators diagnosing a damaged state want the full
+//! list, so the walk lives here and reports every violation it finds.
+
+use crate::error::KernelError;
+use crate::state::kernel::KernelState;
+use alloc::vec::Vec;
+use core::fmt;
+
+/// One broken invariant. Ids are raw slot indices.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum InvariantViolation {
+    /// Node stored in slot `slot` claims a different id.
+    NodeIdMismatch { slot: u32, id: u32 },
+    /// Node points at a record that does not exist.
+    NodeRecordMissing { node: u32, record: u32 },
+    /// Node's outgoing-edge list head does not exist.
+    NodeFirstEdgeMissing { node: u32, edge: u32 },
+    /// Node's outgoing-edge list head starts at another node.
+    NodeFirstEdgeForeign { node: u32, edge: u32 },
+    /// Edge stored in slot `slot` claims a different id.
+    EdgeIdMismatch { slot: u32, id: u32 },
+    /// Edge endpoint does not exist.
+    EdgeEndpointMissing { edge: u32, node: u32 },
+    /// Edge's `next_out` link does not exist.
+    EdgeNextMissing { edge: u32, next: u32 },
+    /// Edge's `next_out` link starts at another node.
+    EdgeNextForeign { edge: u32, next: u32 },
+}
+
+impl InvariantViolation {
+    /// The error `check_invariants` reports for this violation.
+    pub fn error(&self) -> KernelError {
+        match self {
+            Self::NodeIdMismatch { .. }
+            | Self::NodeFirstEdgeForeign { .. }
+            | Self::EdgeIdMismatch { .. }
+            | Self::EdgeNextForeign { .. } => KernelError::InvalidOperation,
+            Self::NodeRecordMissing { .. }
+            | Self::NodeFirstEdgeMissing { .. }
+            | Self::EdgeEndpointMissing { .. }
+            | Self::EdgeNextMissing { .. } => KernelError::NotFound,
+        }
+    }
+}
+
+impl fmt::Display for InvariantViolation {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::NodeIdMismatch { slot, id } => write!(f, "node slot {slot} holds node id {id}"),
+            Self::NodeRecordMissing { node, record } => {
+                write!(f, "node {node} references missing record {record}")
+            }
+            Self::NodeFirstEdgeMissing { node, edge } => {
+                write!(f, "node {node} first_out_edge {edge} does not exist")
+            }
+            Self::NodeFirstEdgeForeign { node, edge } => {
+                write!(
+                    f,
+                    "node {node} first_out_edge {edge} starts at another node"
+                )
+            }
+            Self::EdgeIdMismatch { slot, id } => write!(f, "edge slot {slot} holds edge id {id}"),
+            Self::EdgeEndpointMissing { edge, node } => {
+                write!(f, "edge {edge} endpoint node {node} does not exist")
+            }
+            Self::EdgeNextMissing { edge, next } => {
+                write!(f, "edge {edge} next_out {next} does not exist")
+            }
+            Self::EdgeNextForeign { edge, next } => {
+                write!(f, "edge {edge} next_out {next} starts at another node")
+            }
+        }
+    }
+}
+
+impl KernelState {
+    /// Every invariant violation in the graph pools, in slot order.
+    pub fn invariant_violations(&self) -> Vec<InvariantViolation> {
+        use InvariantViolation::*;
+        let mut out = Vec::new();
+
+        for (i, slot) in self.nodes.raw_nodes().iter().enumerate() {
+            let Some(node) = slot else { continue };
+            if node.id.0 as usize != i {
+                out.push(NodeIdMismatch {
+                    slot: i as u32,
+                    id: node.id.0,
+                });
+            }
+            if let Some(rid) = node.record {
+                if self.records.get(rid).is_none() {
+                    out.push(NodeRecordMissing {
+                        node: node.id.0,
+                        record: rid.0,
+                    });
+                }
+            }
+            if let Some(eid) = node.first_out_edge {
+                match self.edges.get(eid) {
+                    None => out.push(NodeFirstEdgeMissing {
+                        node: node.id.0,
+                        edge: eid.0,
+                    }),
+                    Some(edge) if edge.from != node.id => out.push(NodeFirstEdgeForeign {
+                        node: node.id.0,
+                        edge: eid.0,
+                    }),
+                    Some(_) => {}
+                }
+            }
+        }
+
+        for (i, slot) in self.edges.raw_edges().iter().enumerate() {
+            let Some(edge) = slot else { continue };
+            if edge.id.0 as usize != i {
+                out.push(EdgeIdMismatch {
+                    slot: i as u32,
+                    id: edge.id.0,
+                });
+            }
+            for end in [edge.from, edge.to] {
+                if self.nodes.get(end).is_none() {
+                    out.push(EdgeEndpointMissing {
+                        edge: edge.id.0,
+                        node: end.0,
+                    });
+                }
+            }
+            if let Some(next_id) = edge.next_out {
+                match self.edges.get(next_id) {
+                    None => out.push(EdgeNextMissing {
+                        edge: edge.id.0,
+                        next: next_id.0,
+                    }),
+                    Some(next) if next.from != edge.from => out.push(EdgeNextForeign {
+                        edge: edge.id.0,
+                        next: next_id.0,
+                    }),
+                    Some(_) => {}
+                }
+            }
+        }
+
+        out
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::event::KernelEvent;
+    use crate::types::enums::{EdgeKind, NodeKind};
+    use crate::types::id::{EdgeId, NodeId};
+
+    #[test]
+    fn reports_every_violation_not_just_the_first() {
+        let mut state = KernelState::new();
+        for i in 0..3 {
+            state
+                .apply_event(&KernelEvent::CreateNode {
+                    id: NodeId(i),
+                    kind: NodeKind::Concept,
+                    record: None,
+                })
+                .unwrap();
+        }
+        state
+            .apply_event(&KernelEvent::CreateEdge {
+                id: EdgeId(0),
+                from: NodeId(0),
+                to: NodeId(1),
+                kind: EdgeKind::Relation,
+            })
+            .unwrap();
+        assert!(state.invariant_violations().is_empty());
+        assert!(state.check_invariants().is_ok());
+
+        // Corrupt two slots directly.
+        state.nodes.nodes[2].as_mut().unwrap().id = NodeId(7);
+        state.edges.edges[0].as_mut().unwrap().to = NodeId(9);
+
+        let v = state.invariant_violations();
+        assert_eq!(
+            v,
+            alloc::vec![
+                InvariantViolation::NodeIdMismatch { slot: 2, id: 7 },
+                InvariantViolation::EdgeEndpointMissing { edge: 0, node: 9 },
+            ]
+        );
+        assert!(matches!(
+            state.check_invariants(),
+            Err(KernelError::InvalidOperation)
+        ));
+    }
+}
@@ -7,15 +7,32 @@ use crate::graph::adjacency::{add_edge, OutEdgeIterator};
 use crate::graph::node::GraphNode;
 use crate::graph::pool::{EdgePool, NodePool};
 use crate::index::{
-    ActiveIndex, BinaryQuantizationIndex, BruteForceIndex, IndexVariant, SearchResult, VectorIndex,
+    ActiveIndex, BinaryQuantizationIndex, BruteForceIndex, IndexVariant, SearchResult, TagFilter,
+    VectorIndex, CANCEL_CHECK_INTERVAL,
 };
-use crate::math::l2::fxp_l2_sq;
+use crate::math::l2::fxp_l2_sq_slice;
+use crate::state::merkle::{StateMerkle, Touched};
 use crate::storage::pool::RecordPool;
 use crate::storage::record::Record;
+use crate::storage::sparse_pool::SparsePool;
 use crate::types::id::{EdgeId, NodeId, RecordId};
 use crate::types::id::{Version, DEFAULT_NS, MAX_NAMESPACES, NS_LIST_NIL};
+use crate::types::scalar::FxpScalar;
+use crate::types::sparse::SparseVector;
 use crate::types::vector::FxpVector;
 
+/// Pool limits committed by `KernelEvent::ResizePools`.
+///
+/// Pools are growable `Vec`s, so a resize moves the limit, not the memory.
+/// Until the first `ResizePools` the kernel is unbounded and the host's
+/// configuration is the only limit.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct PoolCapacity {
+    pub records: u32,
+    pub nodes: u32,
+    pub edges: u32,
+}
+
 #[derive(Clone)]
 pub struct KernelState {
     pub dim: Option<usize>,
@@ -33,9 +50,19 @@ pub struct KernelState {
     #[cfg(feature = "std")]
     pub(crate) encrypted_record_keys: rustc_hash::FxHashMap<[u8; 16], alloc::vec::Vec<RecordId>>,
     pub(crate) namespace_node_heads: alloc::vec::Vec<u32>,
-    /// Replicated metadata sidecar — set via `KernelEvent::SetMeta`.
-    /// Key: arbitrary string (e.g. "record:42"). Value: pre-serialised JSON string.
+    /// Replicated metadata sidecar — set via `KernelEvent::SetMeta`, removed
+    /// via `KernelEvent::DeleteMeta`, covered by the state hash.
+    /// Key: arbitrary string (e.g. "record:42"). Value: pre-serialised JSON
+    /// string. The `MAX_META_*` limits are checked where a `SetMeta` is
+    /// committed, not here, so logs written before them still replay.
     pub meta: alloc::collections::BTreeMap<alloc::string::String, alloc::string::String>,
+    /// Limits from the last `ResizePools` event; `None` = unbounded.
+    pub(crate) capacity: Option<PoolCapacity>,
+    /// Sparse vectors set via `KernelEvent::SetSparseVector`, keyed by
+    /// record id and covered by the state hash.
+    pub(crate) sparse: SparsePool,
+    /// Pool Merkle trees behind [`KernelState::state_root`], once tracked.
+    pub(crate) merkle: Option<StateMerkle>,
 }
 
 impl KernelState {
@@ -52,6 +79,9 @@ impl KernelState {
             #[cfg(feature = "std")]
             encrypted_record_keys: rustc_hash::FxHashMap::default(),
             meta: alloc::collections::BTreeMap::new(),
+            capacity: None,
+            sparse: SparsePool::new(),
+            merkle: None,
         }
     }
 
@@ -100,9 +130,10 @@ impl KernelState {
             .encrypted_record_keys
             .remove(&key_id)
             .unwrap_or_default();
-        for rid in records {
-            let _ = self.records.mark_shredded(rid);
+        for rid in &records {
+            let _ = self.records.mark_shredded(*rid);
         }
+        self.refresh_state_root(&Touched::records(records.iter().map(|r| r.0)));
         Ok(())
     }
 
@@ -112,6 +143,11 @@ impl KernelState {
         self.version.0
     }
 
+    /// Pool limits committed by the last `ResizePools`, if any.
+    pub fn capacity(&self) -> Option<PoolCapacity> {
+        self.capacity
+    }
+
     pub fn record_count(&self) -> usize {
         self.records.iter().count()
     }
@@ -126,6 +162,36 @@ impl KernelState {
         self.records.get(id)
     }
 
+    /// `record`'s vector. Read it through here rather than `record.vector`,
+    /// which is empty once the pool keeps its vectors in a file.
+    pub fn record_vector<'a>(&'a self, record: &'a Record) -> &'a [FxpScalar] {
+        self.records.vector(record)
+    }
+
+    /// Keep record vectors in `file` rather than in RAM: the ones stored so
+    /// far move there now, and later inserts append to it. Hashes,
+    /// snapshots and searches come out the same either way.
+    #[cfg(feature = "std")]
+    pub fn move_vectors_to(&mut self, file: crate::storage::vector_file::VectorFile) -> Result<()> {
+        self.records.move_vectors_to(file)
+    }
+
+    /// The file set by [`KernelState::move_vectors_to`], if any.
+    #[cfg(feature = "std")]
+    pub fn vector_file(&self) -> Option<&crate::storage::vector_file::VectorFile> {
+        self.records.vector_file()
+    }
+
+    /// Record `id`'s sparse vector, if it was given one.
+    pub fn sparse_vector(&self, id: RecordId) -> Option<&SparseVector> {
+        self.sparse.get(id)
+    }
+
+    /// Records holding a sparse vector.
+    pub fn sparse_count(&self) -> usize {
+        self.sparse.len()
+    }
+
     pub fn get_node(&self, id: NodeId) -> Option<&GraphNode> {
         self.nodes.get(id)
     }
@@ -191,11 +257,12 @@ impl KernelState {
     }
 
     /// Search across ALL records regardless of namespace (backward-compat, single-tenant).
+    /// `filter` restricts the scan to records whose tag it admits.
     pub fn search_l2(
         &self,
         query: &FxpVector,
         results: &mut [SearchResult],
-        filter: Option<u64>,
+        filter: Option<TagFilter>,
     ) -> usize {
         self.index.search(&self.records, query, results, filter)
     }
@@ -208,13 +275,27 @@ impl KernelState {
         results: &mut [SearchResult],
         namespace_id: u16,
     ) -> usize {
+        self.search_l2_ns_cancellable(query, results, namespace_id, &|| false)
+            .unwrap_or(0)
+    }
+
+    /// [`search_l2_ns`](Self::search_l2_ns) that polls `cancelled` every
+    /// [`CANCEL_CHECK_INTERVAL`] records and returns `None` once it fires, so
+    /// a host can abandon a long scan past its deadline.
+    pub fn search_l2_ns_cancellable(
+        &self,
+        query: &FxpVector,
+        results: &mut [SearchResult],
+        namespace_id: u16,
+        cancelled: &dyn Fn() -> bool,
+    ) -> Option<usize> {
         let ns = namespace_id as usize;
         if ns >= MAX_NAMESPACES {
-            return 0;
+            return Some(0);
         }
         let k = results.len();
         if k == 0 {
-            return 0;
+            return Some(0);
         }
 
         for r in results.iter_mut() {
@@ -225,49 +306,107 @@ impl KernelState {
         }
 
         let mut found = 0usize;
+        let mut visited = 0usize;
         let mut cursor = self.namespace_record_heads[ns];
 
         while cursor != NS_LIST_NIL {
+            if visited % CANCEL_CHECK_INTERVAL == 0 && cancelled() {
+                return None;
+            }
+            visited += 1;
             let (next, vec_ref) = match self
                 .records
                 .records
                 .get(cursor as usize)
                 .and_then(|s| s.as_ref())
             {
-                Some(rec) if rec.is_active() => (rec.next_in_ns, Some(&rec.vector)),
+                Some(rec) if rec.is_active() => (rec.next_in_ns, Some(self.records.vector(rec))),
                 Some(rec) => (rec.next_in_ns, None),
                 None => break,
             };
 
             if let Some(vec) = vec_ref {
-                let dist = fxp_l2_sq(vec, query);
                 let candidate = SearchResult {
-                    score: dist,
+                    score: fxp_l2_sq_slice(vec, query.as_slice()),
                     id: RecordId(cursor),
                 };
-
-                if found < k {
-                    // Insertion sort into the result buffer
-                    let mut pos = found;
-                    while pos > 0 && results[pos - 1] > candidate {
-                        results[pos] = results[pos - 1];
-                        pos -= 1;
-                    }
-                    results[pos] = candidate;
-                    found += 1;
-                } else if candidate < results[k - 1] {
-                    let mut pos = k - 1;
-                    while pos > 0 && results[pos - 1] > candidate {
-                        results[pos] = results[pos - 1];
-                        pos -= 1;
-                    }
-                    results[pos] = candidate;
-                }
+                found = keep_nearest(results, found, candidate);
             }
 
             cursor = next;
         }
 
+        Some(found)
+    }
+
+    /// Range search: searchable records of `namespace_id` whose squared L2
+    /// distance to `query` is at most `max_score`, nearest first. Like
+    /// [`search_l2_ns`](Self::search_l2_ns) an exact scan of the namespace;
+    /// `results.len()` caps how many are kept.
+    pub fn search_range_ns(
+        &self,
+        query: &FxpVector,
+        max_score: i64,
+        results: &mut [SearchResult],
+        namespace_id: u16,
+    ) -> usize {
+        let ns = namespace_id as usize;
+        if ns >= MAX_NAMESPACES || results.is_empty() {
+            return 0;
+        }
+        let mut found = 0usize;
+        let mut cursor = self.namespace_record_heads[ns];
+        while cursor != NS_LIST_NIL {
+            let Some(rec) = self
+                .records
+                .records
+                .get(cursor as usize)
+                .and_then(|s| s.as_ref())
+            else {
+                break;
+            };
+            if rec.is_searchable() {
+                let score = fxp_l2_sq_slice(self.records.vector(rec), query.as_slice());
+                if score <= max_score {
+                    let candidate = SearchResult {
+                        score,
+                        id: RecordId(cursor),
+                    };
+                    found = keep_nearest(results, found, candidate);
+                }
+            }
+            cursor = rec.next_in_ns;
+        }
+        found
+    }
+
+    /// Sparse search: searchable records of `namespace_id` holding a sparse
+    /// vector with a positive dot product against `query`, highest first.
+    /// Scores are the negated raw dot product, so the buffer stays ascending
+    /// like the dense searches. An exact scan of the sparse pool.
+    pub fn search_sparse_ns(
+        &self,
+        query: &SparseVector,
+        results: &mut [SearchResult],
+        namespace_id: u16,
+    ) -> usize {
+        if results.is_empty() || query.is_empty() {
+            return 0;
+        }
+        let mut found = 0usize;
+        for (id, vector) in self.sparse.iter() {
+            let Some(rec) = self.records.get(id) else {
+                continue;
+            };
+            if rec.namespace_id != namespace_id || !rec.is_searchable() {
+                continue;
+            }
+            let dot = vector.dot(query);
+            if dot > 0 {
+                let candidate = SearchResult { score: -dot, id };
+                found = keep_nearest(results, found, candidate);
+            }
+        }
         found
     }
 
@@ -307,6 +446,18 @@ impl KernelState {
     /// This is the single authoritative apply path. Every mutation flows through here;
     /// there is no intermediate representation between `KernelEvent` and `KernelState`.
     pub fn apply_event_ns(&mut self, evt: &KernelEvent, namespace_id: u16) -> Result<()> {
+        if self.merkle.is_none() {
+            return self.apply_event_inner(evt, namespace_id);
+        }
+        let touched = StateMerkle::touched(self, evt);
+        let result = self.apply_event_inner(evt, namespace_id);
+        // Refresh even on error: the touched slots were collected up front.
+        self.refresh_state_root(&touched);
+        result
+    }
+
+    fn apply_event_inner(&mut self, evt: &KernelEvent, namespace_id: u16) -> Result<()> {
+        self.check_capacity(evt)?;
         match evt {
             KernelEvent::InsertRecord {
                 id,
@@ -366,6 +517,7 @@ impl KernelState {
                 };
                 self._unlink_record_from_ns(ns, prev_in_ns, next_in_ns);
                 self.records.delete(*id)?;
+                self.sparse.remove(*id);
                 self.index.on_delete(*id);
             }
 
@@ -543,7 +695,7 @@ impl KernelState {
             } => {
                 let id = self.next_record_id();
                 // Delegate to the concrete InsertRecordEncrypted arm; that arm will bump version.
-                return self.apply_event_ns(
+                return self.apply_event_inner(
                     &KernelEvent::InsertRecordEncrypted {
                         id,
                         key_id: *key_id,
@@ -563,6 +715,43 @@ impl KernelState {
                 self.meta.insert(key.clone(), value.clone());
             }
 
+            KernelEvent::DeleteMeta { key } => {
+                self.meta.remove(key);
+            }
+
+            KernelEvent::Omitted { record } => {
+                if let Some(id) = record {
+                    if self.records.next_id() != *id {
+                        return Err(KernelError::InvalidOperation);
+                    }
+                    self.records.reserve();
+                }
+            }
+
+            KernelEvent::ResizePools {
+                records,
+                nodes,
+                edges,
+            } => {
+                // Never below what is already live: the resize must not make
+                // the current state invalid.
+                if (*records as usize) < self.record_count()
+                    || (*nodes as usize) < self.node_count()
+                    || (*edges as usize) < self.edge_count()
+                {
+                    return Err(KernelError::InvalidInput);
+                }
+                self.capacity = Some(PoolCapacity {
+                    records: *records,
+                    nodes: *nodes,
+                    edges: *edges,
+                });
+            }
+
+            KernelEvent::Vacuum { moves } => {
+                self.apply_vacuum(moves)?;
+            }
+
             KernelEvent::AutoCreateNamespace { name: _ } => {
                 // The name is not stored in KernelState — namespaces are pure integer ids here.
                 // `namespace_id` is the id already allocated by the consensus layer.
@@ -592,6 +781,7 @@ impl KernelState {
                         .map(|r| r.next_in_ns)
                         .unwrap_or(NS_LIST_NIL);
                     self.records.records[cursor as usize] = None;
+                    self.sparse.remove(RecordId(cursor));
                     self.index.on_delete(RecordId(cursor));
                     cursor = next;
                 }
@@ -665,6 +855,15 @@ impl KernelState {
                 // Zero vectors are not added to the search index.
             }
 
+            KernelEvent::SetSparseVector { id, vector } => {
+                vector.validate()?;
+                let rec = self.records.get(*id).ok_or(KernelError::NotFound)?;
+                if !rec.is_searchable() {
+                    return Err(KernelError::InvalidOperation);
+                }
+                self.sparse.set(*id, vector.clone());
+            }
+
             KernelEvent::ShredKey { key_id } => {
                 #[cfg(feature = "std")]
                 self.apply_shred_key(*key_id)?;
@@ -680,6 +879,166 @@ impl KernelState {
         Ok(())
     }
 
+    /// Reject an allocating event when its pool is at the committed capacity.
+    fn check_capacity(&self, evt: &KernelEvent) -> Result<()> {
+        let Some(cap) = self.capacity else {
+            return Ok(());
+        };
+        let full = match evt {
+            KernelEvent::InsertRecord { .. }
+            | KernelEvent::AutoInsertRecord { .. }
+            | KernelEvent::InsertRecordEncrypted { .. }
+            | KernelEvent::AutoInsertRecordEncrypted { .. } => {
+                self.record_count() >= cap.records as usize
+            }
+            KernelEvent::CreateNode { .. } | KernelEvent::AutoCreateNode { .. } => {
+                self.node_count() >= cap.nodes as usize
+            }
+            KernelEvent::CreateEdge { .. } | KernelEvent::AutoCreateEdge { .. } => {
+                self.edge_count() >= cap.edges as usize
+            }
+            _ => false,
+        };
+        if full {
+            return Err(KernelError::CapacityExceeded);
+        }
+        Ok(())
+    }
+
+    // --- Vacuum ---
+
+    /// New id of every record slot under a vacuum now, or `NS_LIST_NIL` for
+    /// a slot it reclaims: hard-deleted gaps and soft-deleted tombstones no
+    /// graph node still points at. Survivors keep their relative order.
+    fn vacuum_remap(&self) -> Result<alloc::vec::Vec<u32>> {
+        use crate::storage::record::FLAG_SOFT_DELETED;
+
+        let mut referenced = alloc::collections::BTreeSet::new();
+        for node in self.iter_nodes() {
+            if let Some(rid) = node.record {
+                // A dangling reference would silently alias whichever
+                // record moves into that slot.
+                if self.records.get(rid).is_none() {
+                    return Err(KernelError::InvalidOperation);
+                }
+                referenced.insert(rid.0);
+            }
+        }
+        let mut next = 0u32;
+        Ok(self
+            .records
+            .raw_records()
+            .iter()
+            .enumerate()
+            .map(|(old, slot)| match slot {
+                Some(r)
+                    if r.flags & FLAG_SOFT_DELETED == 0 || referenced.contains(&(old as u32)) =>
+                {
+                    next += 1;
+                    next - 1
+                }
+                _ => NS_LIST_NIL,
+            })
+            .collect())
+    }
+
+    /// The `(old, new)` id pairs a [`KernelEvent::Vacuum`] must carry to be
+    /// applied to this state — every surviving record whose id changes.
+    pub fn vacuum_moves(&self) -> Result<alloc::vec::Vec<(RecordId, RecordId)>> {
+        Ok(self
+            .vacuum_remap()?
+            .into_iter()
+            .enumerate()
+            .filter(|&(old, new)| new != NS_LIST_NIL && new != old as u32)
+            .map(|(old, new)| (RecordId(old as u32), RecordId(new)))
+            .collect())
+    }
+
+    /// Record slots a vacuum would give back (see [`Self::vacuum_moves`]).
+    pub fn reclaimable_record_slots(&self) -> usize {
+        self.vacuum_remap()
+            .map(|m| m.iter().filter(|&&new| new == NS_LIST_NIL).count())
+            .unwrap_or(0)
+    }
+
+    /// Re-pack the record pool. `moves` must be exactly what
+    /// [`Self::vacuum_moves`] computes, so the log states the mapping every
+    /// replica applies. Node links, namespace lists, `record:<id>` meta keys,
+    /// sparse vectors and the kernel index follow the records to their new
+    /// ids; meta keys and sparse vectors of reclaimed records are dropped.
+    fn apply_vacuum(&mut self, moves: &[(RecordId, RecordId)]) -> Result<()> {
+        let remap = self.vacuum_remap()?;
+        let expected = remap
+            .iter()
+            .enumerate()
+            .filter(|&(old, &new)| new != NS_LIST_NIL && new != old as u32)
+            .map(|(old, &new)| (RecordId(old as u32), RecordId(new)));
+        if !expected.eq(moves.iter().copied()) {
+            return Err(KernelError::InvalidInput);
+        }
+        let map = |id: u32| {
+            if id == NS_LIST_NIL {
+                NS_LIST_NIL
+            } else {
+                remap[id as usize]
+            }
+        };
+
+        let old = core::mem::take(&mut self.records.records);
+        for (slot, &new) in old.into_iter().zip(remap.iter()) {
+            let Some(mut rec) = slot.filter(|_| new != NS_LIST_NIL) else {
+                continue;
+            };
+            rec.id = RecordId(new);
+            rec.next_in_ns = map(rec.next_in_ns);
+            rec.prev_in_ns = map(rec.prev_in_ns);
+            self.records.records.push(Some(rec));
+        }
+        #[cfg(feature = "std")]
+        self.records.remap_offsets(&remap);
+        for head in self.namespace_record_heads.iter_mut() {
+            *head = map(*head);
+        }
+        for node in self.nodes.nodes.iter_mut().flatten() {
+            if let Some(rid) = node.record {
+                node.record = Some(RecordId(remap[rid.0 as usize]));
+            }
+        }
+        #[cfg(feature = "std")]
+        for ids in self.encrypted_record_keys.values_mut() {
+            ids.retain_mut(|rid| {
+                rid.0 = map(rid.0);
+                rid.0 != NS_LIST_NIL
+            });
+        }
+
+        let keys: alloc::vec::Vec<alloc::string::String> = self
+            .meta
+            .keys()
+            .filter(|k| k.starts_with("record:"))
+            .cloned()
+            .collect();
+        let mut moved = alloc::vec::Vec::new();
+        for key in keys {
+            let Some(id) = key["record:".len()..].parse::<u32>().ok() else {
+                continue;
+            };
+            let new = remap.get(id as usize).copied().unwrap_or(NS_LIST_NIL);
+            if new == id {
+                continue;
+            }
+            let value = self.meta.remove(&key).unwrap_or_default();
+            if new != NS_LIST_NIL {
+                moved.push((alloc::format!("record:{new}"), value));
+            }
+        }
+        self.meta.extend(moved);
+        self.sparse.remap(&remap);
+
+        self.index.rebuild(&self.records);
+        Ok(())
+    }
+
     // --- Intrusive list helpers ---
 
     /// Unlink a record from its namespace list using the stored prev/next pointers.
@@ -824,50 +1183,12 @@ impl KernelState {
 
     // --- Invariant Checker ---
 
+    /// First violation from [`invariant_violations`](Self::invariant_violations).
     pub fn check_invariants(&self) -> Result<()> {
-        for (i, slot) in self.nodes.raw_nodes().iter().enumerate() {
-            if let Some(node) = slot {
-                if node.id.0 as usize != i {
-                    return Err(KernelError::InvalidOperation);
-                }
-                if let Some(rid) = node.record {
-                    if self.records.get(rid).is_none() {
-                        return Err(KernelError::NotFound);
-                    }
-                }
-                if let Some(eid) = node.first_out_edge {
-                    if self.edges.get(eid).is_none() {
-                        return Err(KernelError::NotFound);
-                    }
-                    let edge = self.edges.get(eid).unwrap();
-                    if edge.from != node.id {
-                        return Err(KernelError::InvalidOperation);
-                    }
-                }
-            }
+        match self.invariant_violations().first() {
+            Some(v) => Err(v.error()),
+            None => Ok(()),
         }
-
-        for (i, slot) in self.edges.raw_edges().iter().enumerate() {
-            if let Some(edge) = slot {
-                if edge.id.0 as usize != i {
-                    return Err(KernelError::InvalidOperation);
-                }
-                if self.nodes.get(edge.from).is_none() || self.nodes.get(edge.to).is_none() {
-                    return Err(KernelError::NotFound);
-                }
-                if let Some(next_id) = edge.next_out {
-                    if self.edges.get(next_id).is_none() {
-                        return Err(KernelError::NotFound);
-                    }
-                    let next_edge = self.edges.get(next_id).unwrap();
-                    if next_edge.from != edge.from {
-                        return Err(KernelError::InvalidOperation);
-                    }
-                }
-            }
-        }
-
-        Ok(())
     }
 
     /// Rebuild namespace linked lists from the namespace_id fields on records and nodes.
@@ -917,3 +1238,23 @@ impl KernelState {
         }
     }
 }
+
+/// Insert `candidate` into the ascending buffer `results`, of which the
+/// first `found` slots are filled; once full, keep only the `results.len()`
+/// nearest. Returns the new fill.
+fn keep_nearest(results: &mut [SearchResult], found: usize, candidate: SearchResult) -> usize {
+    let k = results.len();
+    let (mut pos, filled) = if found < k {
+        (found, found + 1)
+    } else if candidate < results[k - 1] {
+        (k - 1, found)
+    } else {
+        return found;
+    };
+    while pos > 0 && results[pos - 1] > candidate {
+        results[pos] = results[pos - 1];
+        pos -= 1;
+    }
+    results[pos] = candidate;
+    filled
+}
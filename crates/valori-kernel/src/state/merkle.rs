@@ -0,0 +1,594 @@
+// Copyright (c) 2025 Varshith Gudur. Dual-licensed under MIT OR Apache-2.0.
+//! Incrementally maintained state root.
+//!
+//! [`hash_state_blake3`](crate::snapshot::blake3::hash_state_blake3) streams
+//! the whole state through one hasher, so every proof costs O(N). The state
+//! root is a Merkle commitment to the same content: each pool is a binary
+//! tree over its slots, and only the slots an event touches are re-hashed —
+//! O(log N) per event, plus the out-degree of the nodes a graph event
+//! touches. Reading the root is O(1) in the pools.
+//!
+//! The root is a different hash from `hash_state_blake3`, not a faster way
+//! to compute it. It covers the same fields plus the slot counts, so two
+//! states with the same root also have the same canonical hash.
+//!
+//! # Root input
+//! ```text
+//! "valori-state-root" || STATE_ROOT_DOMAIN_VERSION (u8) || format_id (u8)
+//! version (u64 LE)
+//! records root, slot count (u32 LE)
+//! nodes root, slot count (u32 LE)
+//! edges root, slot count (u32 LE)
+//! capacity, meta and sparse sections, exactly as in hash_state_blake3
+//! ```
+//! Leaves hash the per-entry bytes of `hash_state_blake3` under a `0x00`
+//! prefix; an empty, deleted or soft-deleted slot is the all-zero leaf.
+//! Interior nodes hash `0x01 || left || right`, a missing right child being
+//! the all-zero hash. The tree shape depends only on the slot count.
+
+use crate::event::KernelEvent;
+use crate::graph::edge::GraphEdge;
+use crate::graph::node::GraphNode;
+use crate::state::kernel::KernelState;
+use crate::storage::pool::RecordPool;
+use crate::storage::record::Record;
+use crate::types::id::NodeId;
+use alloc::collections::BTreeSet;
+use alloc::vec::Vec;
+
+/// Version of the root-input schema, bumped whenever it changes.
+///
+/// - 1: pools, capacity and meta.
+/// - 2: adds the sparse vector section.
+pub const STATE_ROOT_DOMAIN_VERSION: u8 = 2;
+
+const EMPTY: [u8; 32] = [0; 32];
+
+/// Binary Merkle tree over the slots of one pool.
+#[derive(Clone, Debug, Default)]
+struct SlotTree {
+    /// `levels[0]` are the leaves; the last level holds the root.
+    levels: Vec<Vec<[u8; 32]>>,
+}
+
+fn interior(left: &[u8; 32], right: &[u8; 32]) -> [u8; 32] {
+    let mut h = blake3::Hasher::new();
+    h.update(&[1]);
+    h.update(left);
+    h.update(right);
+    *h.finalize().as_bytes()
+}
+
+impl SlotTree {
+    fn from_leaves(leaves: Vec<[u8; 32]>) -> Self {
+        let mut levels = alloc::vec![leaves];
+        while levels.last().is_some_and(|l| l.len() > 1) {
+            let below = levels.last().unwrap();
+            let up = below
+                .chunks(2)
+                .map(|pair| interior(&pair[0], pair.get(1).unwrap_or(&EMPTY)))
+                .collect();
+            levels.push(up);
+        }
+        Self { levels }
+    }
+
+    fn len(&self) -> usize {
+        self.levels.first().map_or(0, Vec::len)
+    }
+
+    fn root(&self) -> [u8; 32] {
+        self.levels
+            .last()
+            .and_then(|l| l.first())
+            .copied()
+            .unwrap_or(EMPTY)
+    }
+
+    /// Set leaf `i`, appending when `i == len()`, and re-hash its path.
+    fn set(&mut self, mut i: usize, leaf: [u8; 32]) {
+        if self.levels.is_empty() {
+            self.levels.push(Vec::new());
+        }
+        let leaves = &mut self.levels[0];
+        debug_assert!(i <= leaves.len());
+        if i == leaves.len() {
+            leaves.push(leaf);
+        } else {
+            leaves[i] = leaf;
+        }
+        let mut k = 0;
+        while self.levels[k].len() > 1 {
+            let p = i / 2;
+            let below = &self.levels[k];
+            let h = interior(&below[2 * p], below.get(2 * p + 1).unwrap_or(&EMPTY));
+            if k + 1 == self.levels.len() {
+                self.levels.push(Vec::new());
+            }
+            let up = &mut self.levels[k + 1];
+            if p == up.len() {
+                up.push(h);
+            } else {
+                up[p] = h;
+            }
+            i = p;
+            k += 1;
+        }
+    }
+}
+
+fn opt_id(id: Option<u32>) -> [u8; 4] {
+    id.unwrap_or(u32::MAX).to_le_bytes()
+}
+
+fn record_leaf(pool: &RecordPool, slot: Option<&Record>) -> [u8; 32] {
+    let Some(record) = slot.filter(|r| r.is_active()) else {
+        return EMPTY;
+    };
+    let mut h = blake3::Hasher::new();
+    h.update(&[0]);
+    h.update(&record.id.0.to_le_bytes());
+    h.update(&[record.flags]);
+    for scalar in pool.vector(record) {
+        h.update(&scalar.0.to_le_bytes());
+    }
+    h.update(&record.tag.to_le_bytes());
+    match &record.metadata {
+        Some(bytes) => {
+            h.update(&(bytes.len() as u32).to_le_bytes());
+            h.update(bytes);
+        }
+        None => {
+            h.update(&u32::MAX.to_le_bytes());
+        }
+    }
+    *h.finalize().as_bytes()
+}
+
+fn node_leaf(slot: Option<&GraphNode>) -> [u8; 32] {
+    let Some(node) = slot else {
+        return EMPTY;
+    };
+    let mut h = blake3::Hasher::new();
+    h.update(&[0]);
+    h.update(&node.id.0.to_le_bytes());
+    h.update(&[node.kind as u8]);
+    h.update(&opt_id(node.record.map(|r| r.0)));
+    h.update(&opt_id(node.first_out_edge.map(|e| e.0)));
+    *h.finalize().as_bytes()
+}
+
+fn edge_leaf(slot: Option<&GraphEdge>) -> [u8; 32] {
+    let Some(edge) = slot else {
+        return EMPTY;
+    };
+    let mut h = blake3::Hasher::new();
+    h.update(&[0]);
+    h.update(&edge.id.0.to_le_bytes());
+    h.update(&[edge.kind as u8]);
+    h.update(&edge.from.0.to_le_bytes());
+    h.update(&edge.to.0.to_le_bytes());
+    h.update(&opt_id(edge.next_out.map(|e| e.0)));
+    *h.finalize().as_bytes()
+}
+
+/// Slots an event can change, collected before it is applied. Slots the
+/// event appends are picked up from the pool lengths afterwards.
+#[derive(Debug, Default)]
+pub(crate) struct Touched {
+    records: BTreeSet<u32>,
+    nodes: BTreeSet<u32>,
+    edges: BTreeSet<u32>,
+    /// The event rewrites slots wholesale (vacuum, namespace drop).
+    all: bool,
+}
+
+impl Touched {
+    #[cfg(feature = "std")]
+    pub(crate) fn records(ids: impl IntoIterator<Item = u32>) -> Self {
+        Self {
+            records: ids.into_iter().collect(),
+            ..Self::default()
+        }
+    }
+}
+
+/// Per-pool Merkle trees of a [`KernelState`], kept in step with it by
+/// `apply_event_ns` once [`KernelState::track_state_root`] is called.
+#[derive(Clone, Debug, Default)]
+pub struct StateMerkle {
+    records: SlotTree,
+    nodes: SlotTree,
+    edges: SlotTree,
+}
+
+impl StateMerkle {
+    /// Hash every slot of `state`. O(N).
+    pub fn build(state: &KernelState) -> Self {
+        Self {
+            records: SlotTree::from_leaves(
+                state
+                    .records
+                    .raw_records()
+                    .iter()
+                    .map(|r| record_leaf(&state.records, r.as_ref()))
+                    .collect(),
+            ),
+            nodes: SlotTree::from_leaves(
+                state
+                    .nodes
+                    .raw_nodes()
+                    .iter()
+                    .map(|n| node_leaf(n.as_ref()))
+                    .collect(),
+            ),
+            edges: SlotTree::from_leaves(
+                state
+                    .edges
+                    .raw_edges()
+                    .iter()
+                    .map(|e| edge_leaf(e.as_ref()))
+                    .collect(),
+            ),
+        }
+    }
+
+    /// The slots `evt` may change in `state`, which it has not been applied
+    /// to yet.
+    pub(crate) fn touched(state: &KernelState, evt: &KernelEvent) -> Touched {
+        let mut t = Touched::default();
+        // A node's leaf covers its out-edge list head and an edge's covers
+        // its successor, so relinking a node's out-list touches the node
+        // and every edge on that list.
+        let out_list = |t: &mut Touched, node: NodeId| {
+            t.nodes.insert(node.0);
+            let mut curr = state.nodes.get(node).and_then(|n| n.first_out_edge);
+            while let Some(eid) = curr {
+                t.edges.insert(eid.0);
+                curr = state.edges.get(eid).and_then(|e| e.next_out);
+            }
+        };
+        match evt {
+            KernelEvent::DeleteRecord { id }
+            | KernelEvent::SoftDeleteRecord { id }
+            | KernelEvent::UpdateRecordMetadata { id, .. } => {
+                t.records.insert(id.0);
+            }
+            KernelEvent::CreateEdge { from, .. } | KernelEvent::AutoCreateEdge { from, .. } => {
+                t.nodes.insert(from.0);
+            }
+            KernelEvent::DeleteEdge { id } => {
+                if let Some(edge) = state.edges.get(*id) {
+                    out_list(&mut t, edge.from);
+                }
+            }
+            KernelEvent::DeleteNode { id } => {
+                out_list(&mut t, *id);
+                let mut curr = state.nodes.get(*id).and_then(|n| n.first_in_edge);
+                while let Some(eid) = curr {
+                    let Some(edge) = state.edges.get(eid) else {
+                        break;
+                    };
+                    out_list(&mut t, edge.from);
+                    curr = edge.next_in;
+                }
+            }
+            KernelEvent::Vacuum { .. } | KernelEvent::DropNamespace { .. } => t.all = true,
+            // Appends only, outside the pools, or handled where the slots
+            // are known (`apply_shred_key`).
+            _ => {}
+        }
+        t
+    }
+
+    /// Re-hash the `touched` slots and any slots appended since the last
+    /// refresh.
+    pub(crate) fn refresh(&mut self, state: &KernelState, touched: &Touched) {
+        if touched.all {
+            *self = Self::build(state);
+            return;
+        }
+        let records = state.records.raw_records();
+        let nodes = state.nodes.raw_nodes();
+        let edges = state.edges.raw_edges();
+        let old = (self.records.len(), self.nodes.len(), self.edges.len());
+        for i in touched
+            .records
+            .iter()
+            .map(|&i| i as usize)
+            .filter(|&i| i < old.0)
+        {
+            self.records
+                .set(i, record_leaf(&state.records, records[i].as_ref()));
+        }
+        for i in old.0..records.len() {
+            self.records
+                .set(i, record_leaf(&state.records, records[i].as_ref()));
+        }
+        for i in touched
+            .nodes
+            .iter()
+            .map(|&i| i as usize)
+            .filter(|&i| i < old.1)
+        {
+            self.nodes.set(i, node_leaf(nodes[i].as_ref()));
+        }
+        for i in old.1..nodes.len() {
+            self.nodes.set(i, node_leaf(nodes[i].as_ref()));
+        }
+        for i in touched
+            .edges
+            .iter()
+            .map(|&i| i as usize)
+            .filter(|&i| i < old.2)
+        {
+            self.edges.set(i, edge_leaf(edges[i].as_ref()));
+        }
+        for i in old.2..edges.len() {
+            self.edges.set(i, edge_leaf(edges[i].as_ref()));
+        }
+    }
+
+    /// Combine the pool roots with the rest of `state`.
+    pub fn root(&self, state: &KernelState) -> [u8; 32] {
+        let mut h = blake3::Hasher::new();
+        h.update(b"valori-state-root");
+        h.update(&[
+            STATE_ROOT_DOMAIN_VERSION,
+            crate::fxp::format::ACTIVE_FORMAT_ID,
+        ]);
+        h.update(&state.version.0.to_le_bytes());
+        for tree in [&self.records, &self.nodes, &self.edges] {
+            h.update(&tree.root());
+            h.update(&(tree.len() as u32).to_le_bytes());
+        }
+        if let Some(cap) = state.capacity {
+            h.update(b"capacity");
+            h.update(&cap.records.to_le_bytes());
+            h.update(&cap.nodes.to_le_bytes());
+            h.update(&cap.edges.to_le_bytes());
+        }
+        if !state.meta.is_empty() {
+            h.update(b"meta");
+            h.update(&(state.meta.len() as u32).to_le_bytes());
+            for (key, value) in state.meta.iter() {
+                h.update(&(key.len() as u32).to_le_bytes());
+                h.update(key.as_bytes());
+                h.update(&(value.len() as u32).to_le_bytes());
+                h.update(value.as_bytes());
+            }
+        }
+        if !state.sparse.is_empty() {
+            h.update(b"sparse");
+            h.update(&(state.sparse.len() as u32).to_le_bytes());
+            for (id, vector) in state.sparse.iter() {
+                h.update(&id.0.to_le_bytes());
+                h.update(&(vector.nnz() as u32).to_le_bytes());
+                for (index, value) in vector.iter() {
+                    h.update(&index.to_le_bytes());
+                    h.update(&value.0.to_le_bytes());
+                }
+            }
+        }
+        *h.finalize().as_bytes()
+    }
+}
+
+impl KernelState {
+    /// Start maintaining the state root incrementally: one O(N) build now,
+    /// then O(log N) per applied event. Idempotent.
+    pub fn track_state_root(&mut self) {
+        if self.merkle.is_none() {
+            self.merkle = Some(StateMerkle::build(self));
+        }
+    }
+
+    /// Whether [`track_state_root`](Self::track_state_root) is in effect.
+    pub fn tracks_state_root(&self) -> bool {
+        self.merkle.is_some()
+    }
+
+    /// Merkle root of the state (see the module docs). O(1) in the pools
+    /// when tracked, otherwise built from scratch in O(N).
+    pub fn state_root(&self) -> [u8; 32] {
+        match &self.merkle {
+            Some(m) => m.root(self),
+            None => StateMerkle::build(self).root(self),
+        }
+    }
+
+    /// Re-hash `touched` if the root is tracked.
+    pub(crate) fn refresh_state_root(&mut self, touched: &Touched) {
+        if let Some(mut m) = self.merkle.take() {
+            m.refresh(self, touched);
+            self.merkle = Some(m);
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::types::enums::{EdgeKind, NodeKind};
+    use crate::types::id::{EdgeId, RecordId};
+    use crate::types::scalar::FxpScalar;
+    use crate::types::sparse::SparseVector;
+    use crate::types::vector::FxpVector;
+
+    fn insert(state: &mut KernelState, id: u32) {
+        insert_ns(state, id, 0);
+    }
+
+    fn insert_ns(state: &mut KernelState, id: u32, namespace_id: u16) {
+        let mut vector = FxpVector::new_zeros(2);
+        vector.data[0] = FxpScalar(id as i32);
+        state
+            .apply_event_ns(
+                &KernelEvent::InsertRecord {
+                    id: RecordId(id),
+                    vector,
+                    metadata: None,
+                    tag: id as u64,
+                },
+                namespace_id,
+            )
+            .unwrap();
+    }
+
+    fn set_sparse(id: u32, pairs: &[(u32, i32)]) -> KernelEvent {
+        KernelEvent::SetSparseVector {
+            id: RecordId(id),
+            vector: SparseVector::from_pairs(
+                pairs.iter().map(|&(i, v)| (i, FxpScalar(v))).collect(),
+            )
+            .unwrap(),
+        }
+    }
+
+    #[test]
+    fn tracked_root_matches_a_full_rebuild_after_every_event() {
+        let mut tracked = KernelState::with_dim(2);
+        tracked.track_state_root();
+        let mut events = Vec::new();
+        for i in 0..5 {
+            events.push(KernelEvent::CreateNode {
+                id: NodeId(i),
+                kind: NodeKind::Record,
+                record: None,
+            });
+        }
+        for (i, (from, to)) in [(0, 1), (0, 2), (0, 3), (1, 0), (2, 0), (3, 4)]
+            .into_iter()
+            .enumerate()
+        {
+            events.push(KernelEvent::CreateEdge {
+                id: EdgeId(i as u32),
+                from: NodeId(from),
+                to: NodeId(to),
+                kind: EdgeKind::Relation,
+            });
+        }
+        events.extend([
+            KernelEvent::DeleteEdge { id: EdgeId(1) },
+            KernelEvent::DeleteNode { id: NodeId(0) },
+            KernelEvent::SetMeta {
+                key: "k".into(),
+                value: "v".into(),
+            },
+            KernelEvent::DeleteRecord { id: RecordId(2) },
+            KernelEvent::SoftDeleteRecord { id: RecordId(5) },
+            KernelEvent::UpdateRecordMetadata {
+                id: RecordId(7),
+                metadata: Some(alloc::vec![1, 2, 3]),
+            },
+        ]);
+
+        let mut seen = BTreeSet::new();
+        for i in 0..9 {
+            insert(&mut tracked, i);
+            let root = tracked.state_root();
+            let fresh = StateMerkle::build(&tracked).root(&tracked);
+            assert_eq!(root, fresh, "after insert {i}");
+            assert!(seen.insert(root));
+        }
+        for evt in &events {
+            tracked.apply_event(evt).unwrap();
+            let root = tracked.state_root();
+            assert_eq!(root, StateMerkle::build(&tracked).root(&tracked), "{evt:?}");
+            assert!(seen.insert(root), "{evt:?} left the root unchanged");
+        }
+
+        // Untracked states compute the same root from scratch.
+        let mut untracked = KernelState::with_dim(2);
+        for i in 0..9 {
+            insert(&mut untracked, i);
+        }
+        for evt in &events {
+            untracked.apply_event(evt).unwrap();
+        }
+        assert!(!untracked.tracks_state_root());
+        assert_eq!(untracked.state_root(), tracked.state_root());
+    }
+
+    #[test]
+    fn tracked_root_covers_sparse_vectors() {
+        let mut tracked = KernelState::with_dim(2);
+        tracked.track_state_root();
+        for i in 0..6 {
+            insert(&mut tracked, i);
+        }
+        for i in 6..8 {
+            insert_ns(&mut tracked, i, 1);
+        }
+
+        let mut seen = BTreeSet::new();
+        let mut check = |state: &KernelState, what: &str| {
+            let root = state.state_root();
+            assert_eq!(root, StateMerkle::build(state).root(state), "{what}");
+            assert!(seen.insert(root), "{what} left the root unchanged");
+        };
+        check(&tracked, "inserts");
+        for evt in [
+            set_sparse(1, &[(3, 10), (9, -4)]),
+            set_sparse(3, &[(0, 1)]),
+            set_sparse(4, &[(2, 7)]),
+            set_sparse(7, &[(5, 5)]),
+            set_sparse(1, &[(3, 11), (9, -4)]),
+        ] {
+            tracked.apply_event(&evt).unwrap();
+            check(&tracked, &alloc::format!("{evt:?}"));
+        }
+
+        tracked
+            .apply_event(&KernelEvent::DeleteRecord { id: RecordId(3) })
+            .unwrap();
+        assert_eq!(tracked.sparse.len(), 3);
+        check(&tracked, "delete");
+
+        tracked
+            .apply_event_ns(&KernelEvent::DropNamespace { name: "ns1".into() }, 1)
+            .unwrap();
+        assert_eq!(tracked.sparse.len(), 2);
+        check(&tracked, "drop namespace");
+
+        let moves = tracked.vacuum_moves().unwrap();
+        assert!(!moves.is_empty());
+        tracked.apply_event(&KernelEvent::Vacuum { moves }).unwrap();
+        assert_eq!(tracked.sparse.len(), 2);
+        check(&tracked, "vacuum");
+    }
+
+    #[test]
+    fn states_differing_only_in_sparse_values_have_different_roots() {
+        let build = |value: Option<i32>| {
+            let mut state = KernelState::with_dim(2);
+            insert(&mut state, 0);
+            if let Some(value) = value {
+                state.apply_event(&set_sparse(0, &[(4, value)])).unwrap();
+            }
+            state
+        };
+        let (a, b, none) = (build(Some(5)), build(Some(6)), build(None));
+        assert_ne!(a.state_root(), b.state_root());
+        assert_ne!(a.state_root(), none.state_root());
+        // The version counter alone must not be what tells them apart.
+        assert_eq!(a.version, b.version);
+    }
+
+    #[test]
+    fn slot_tree_appends_match_a_bulk_build() {
+        let leaves: Vec<[u8; 32]> = (0u8..13).map(|i| [i; 32]).collect();
+        let mut grown = SlotTree::default();
+        for (i, leaf) in leaves.iter().enumerate() {
+            grown.set(i, *leaf);
+            assert_eq!(
+                grown.root(),
+                SlotTree::from_leaves(leaves[..=i].to_vec()).root()
+            );
+        }
+        grown.set(4, EMPTY);
+        let mut edited = leaves.clone();
+        edited[4] = EMPTY;
+        assert_eq!(grown.root(), SlotTree::from_leaves(edited).root());
+    }
+}
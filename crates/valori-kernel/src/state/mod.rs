@@ -1,2 +1,6 @@
 // Copyright (c) 2025 Varshith Gudur. Dual-licensed under MIT OR Apache-2.0.
+pub mod diff;
+pub mod invariants;
 pub mod kernel;
+pub mod merkle;
+pub mod stats;
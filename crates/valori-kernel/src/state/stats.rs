@@ -0,0 +1,197 @@
+// Copyright (c) 2025 Varshith Gudur. Dual-licensed under MIT OR Apache-2.0.
+//! Occupancy statistics of the kernel pools.
+//!
+//! Every figure is read straight from the pools, so a [`KernelStats`] is
+//! exact for the `version` it reports. Slot maps give the per-id picture
+//! behind the counts: which ids are live, which were freed, and how
+//! fragmented a pool is before a vacuum.
+
+use crate::state::kernel::KernelState;
+use crate::types::id::NS_LIST_NIL;
+use alloc::vec::Vec;
+use serde::{Deserialize, Serialize};
+
+/// Occupancy of one pool.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Serialize, Deserialize)]
+pub struct PoolStats {
+    /// Ids allocated so far; the next insert takes this id.
+    pub slots: u32,
+    /// Slots holding a live entry.
+    pub live: u32,
+    /// Slots still holding an entry that no longer counts as live:
+    /// soft-deleted or shredded records. Always 0 for nodes and edges.
+    pub tombstoned: u32,
+    /// Limit from the last `ResizePools`; `None` = unbounded.
+    pub capacity: Option<u32>,
+}
+
+/// Counts, limits and version of a kernel state.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Serialize, Deserialize)]
+pub struct KernelStats {
+    pub version: u64,
+    pub dim: Option<u32>,
+    pub records: PoolStats,
+    pub nodes: PoolStats,
+    pub edges: PoolStats,
+    /// Keys in the replicated metadata sidecar.
+    pub meta_entries: u32,
+    /// Namespaces holding at least one record slot.
+    pub namespaces_in_use: u32,
+}
+
+/// Which slots of a pool are live: slot `i` is bit `i % 64` of
+/// `words[i / 64]`. Bits at or past `slots` are zero.
+#[derive(Clone, Debug, PartialEq, Eq, Serialize, Deserialize)]
+pub struct SlotMap {
+    pub slots: u32,
+    pub words: Vec<u64>,
+}
+
+impl SlotMap {
+    fn from_live(live: impl ExactSizeIterator<Item = bool>) -> Self {
+        let slots = live.len();
+        let mut words = alloc::vec![0u64; slots.div_ceil(64)];
+        for (i, _) in live.enumerate().filter(|(_, live)| *live) {
+            words[i / 64] |= 1 << (i % 64);
+        }
+        Self {
+            slots: slots as u32,
+            words,
+        }
+    }
+
+    pub fn is_live(&self, id: u32) -> bool {
+        id < self.slots && self.words[id as usize / 64] & (1 << (id % 64)) != 0
+    }
+
+    /// Number of live slots.
+    pub fn live(&self) -> u32 {
+        self.words.iter().map(|w| w.count_ones()).sum()
+    }
+
+    /// Little-endian bytes of `words`, trimmed to `slots` bits — bit `i % 8`
+    /// of byte `i / 8` is slot `i`.
+    pub fn to_bytes(&self) -> Vec<u8> {
+        let mut bytes: Vec<u8> = self.words.iter().flat_map(|w| w.to_le_bytes()).collect();
+        bytes.truncate((self.slots as usize).div_ceil(8));
+        bytes
+    }
+}
+
+impl KernelState {
+    /// Pool occupancy, limits and version.
+    pub fn stats(&self) -> KernelStats {
+        let raw = self.records.raw_records();
+        let live = self.records.iter().count() as u32;
+        let occupied = raw.iter().filter(|r| r.is_some()).count() as u32;
+        let capacity = self.capacity;
+        KernelStats {
+            version: self.version.0,
+            dim: self.dim.map(|d| d as u32),
+            records: PoolStats {
+                slots: raw.len() as u32,
+                live,
+                tombstoned: occupied - live,
+                capacity: capacity.map(|c| c.records),
+            },
+            nodes: PoolStats {
+                slots: self.nodes.len() as u32,
+                live: self.nodes.live_count() as u32,
+                tombstoned: 0,
+                capacity: capacity.map(|c| c.nodes),
+            },
+            edges: PoolStats {
+                slots: self.edges.len() as u32,
+                live: self.edges.live_count() as u32,
+                tombstoned: 0,
+                capacity: capacity.map(|c| c.edges),
+            },
+            meta_entries: self.meta.len() as u32,
+            namespaces_in_use: self
+                .namespace_record_heads
+                .iter()
+                .filter(|&&head| head != NS_LIST_NIL)
+                .count() as u32,
+        }
+    }
+
+    /// Live record slots (soft-deleted and shredded records are not live).
+    pub fn record_slot_map(&self) -> SlotMap {
+        SlotMap::from_live(
+            self.records
+                .raw_records()
+                .iter()
+                .map(|r| r.as_ref().is_some_and(|r| r.is_active())),
+        )
+    }
+
+    /// Live graph node slots.
+    pub fn node_slot_map(&self) -> SlotMap {
+        SlotMap::from_live(self.nodes.raw_nodes().iter().map(Option::is_some))
+    }
+
+    /// Live graph edge slots.
+    pub fn edge_slot_map(&self) -> SlotMap {
+        SlotMap::from_live(self.edges.raw_edges().iter().map(Option::is_some))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::event::KernelEvent;
+    use crate::types::enums::NodeKind;
+    use crate::types::id::{NodeId, RecordId};
+    use crate::types::vector::FxpVector;
+
+    #[test]
+    fn stats_and_slot_maps_follow_deletes() {
+        let mut state = KernelState::with_dim(2);
+        for i in 0..70 {
+            state
+                .apply_event(&KernelEvent::InsertRecord {
+                    id: RecordId(i),
+                    vector: FxpVector::new_zeros(2),
+                    metadata: None,
+                    tag: 0,
+                })
+                .unwrap();
+        }
+        state
+            .apply_event(&KernelEvent::DeleteRecord { id: RecordId(3) })
+            .unwrap();
+        state
+            .apply_event(&KernelEvent::SoftDeleteRecord { id: RecordId(65) })
+            .unwrap();
+        state
+            .apply_event(&KernelEvent::CreateNode {
+                id: NodeId(0),
+                kind: NodeKind::Record,
+                record: Some(RecordId(0)),
+            })
+            .unwrap();
+
+        let stats = state.stats();
+        assert_eq!(stats.dim, Some(2));
+        assert_eq!(
+            stats.records,
+            PoolStats {
+                slots: 70,
+                live: 68,
+                tombstoned: 1,
+                capacity: None,
+            }
+        );
+        assert_eq!((stats.nodes.live, stats.edges.slots), (1, 0));
+        assert_eq!(stats.namespaces_in_use, 1);
+
+        let map = state.record_slot_map();
+        assert_eq!((map.slots, map.words.len(), map.live()), (70, 2, 68));
+        assert!(map.is_live(0) && !map.is_live(3) && !map.is_live(65) && !map.is_live(70));
+        let bytes = map.to_bytes();
+        assert_eq!(bytes.len(), 9);
+        assert_eq!(bytes[0], 0b1111_0111);
+        assert_eq!(bytes[8], 0b0011_1101);
+        assert_eq!(state.node_slot_map().live(), 1);
+    }
+}
@@ -1,2 +1,5 @@
 pub mod pool;
 pub mod record;
+pub mod sparse_pool;
+#[cfg(feature = "std")]
+pub mod vector_file;
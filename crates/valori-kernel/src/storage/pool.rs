@@ -4,11 +4,21 @@
 use crate::error::{KernelError, Result};
 use crate::storage::record::{Record, FLAG_ENCRYPTED, FLAG_SHREDDED, FLAG_SOFT_DELETED};
 use crate::types::id::RecordId;
+use crate::types::scalar::FxpScalar;
 use crate::types::vector::FxpVector;
 
 #[derive(Clone)]
 pub struct RecordPool {
     pub(crate) records: alloc::vec::Vec<Option<Record>>,
+    /// Where vectors go once [`RecordPool::move_vectors_to`] ran; records
+    /// stored there keep an empty `vector` and an entry in `offsets`.
+    #[cfg(feature = "std")]
+    file: Option<crate::storage::vector_file::VectorFile>,
+    #[cfg(feature = "std")]
+    offsets: alloc::vec::Vec<u64>,
+    /// Length of the vectors in the file; the kernel keeps one dimension.
+    #[cfg(feature = "std")]
+    file_dim: usize,
 }
 
 impl RecordPool {
@@ -19,6 +29,12 @@ impl RecordPool {
     pub fn new() -> Self {
         Self {
             records: alloc::vec::Vec::new(),
+            #[cfg(feature = "std")]
+            file: None,
+            #[cfg(feature = "std")]
+            offsets: alloc::vec::Vec::new(),
+            #[cfg(feature = "std")]
+            file_dim: 0,
         }
     }
 
@@ -40,11 +56,109 @@ impl RecordPool {
         namespace_id: u16,
     ) -> Result<RecordId> {
         let id = RecordId(self.records.len() as u32);
+        #[cfg(feature = "std")]
+        let vector = self.store_vector(id, vector)?;
         self.records
             .push(Some(Record::new(id, vector, metadata, tag, namespace_id)));
         Ok(id)
     }
 
+    /// Append `vector` to the vector file, if there is one, and return what
+    /// the record itself keeps: an empty vector, or `vector` when there is
+    /// no file.
+    #[cfg(feature = "std")]
+    fn store_vector(&mut self, id: RecordId, vector: FxpVector) -> Result<FxpVector> {
+        let Some(file) = self.file.as_mut() else {
+            return Ok(vector);
+        };
+        let offset = file.append(id, vector.as_slice())?;
+        self.file_dim = vector.len();
+        let slot = id.0 as usize;
+        if self.offsets.len() <= slot {
+            self.offsets.resize(slot + 1, u64::MAX);
+        }
+        self.offsets[slot] = offset;
+        Ok(FxpVector::new_empty())
+    }
+
+    /// Move every stored vector into `file` and append later inserts there,
+    /// so the pool keeps only offsets in memory. Vectors already in `file`
+    /// stay put; ones in another file are copied over.
+    #[cfg(feature = "std")]
+    pub fn move_vectors_to(&mut self, file: crate::storage::vector_file::VectorFile) -> Result<()> {
+        let old = self.file.replace(file);
+        let old = old.filter(|old| !old.same_file(self.file.as_ref().unwrap()));
+        for slot in 0..self.records.len() {
+            let Some(record) = self.records[slot].as_mut() else {
+                continue;
+            };
+            let vector = if !record.vector.is_empty() {
+                core::mem::take(&mut record.vector)
+            } else {
+                match (&old, self.offsets.get(slot)) {
+                    (Some(old), Some(&offset)) => match old.get(offset, self.file_dim) {
+                        Some(v) => FxpVector { data: v.to_vec() },
+                        None => continue,
+                    },
+                    _ => continue,
+                }
+            };
+            let kept = self.store_vector(RecordId(slot as u32), vector)?;
+            if let Some(record) = self.records[slot].as_mut() {
+                record.vector = kept;
+            }
+        }
+        Ok(())
+    }
+
+    /// Follow a vacuum's renumbering: `remap[old]` is the record's new
+    /// slot, or out of range when the vacuum drops it. The vectors stay
+    /// where they are in the file; only their offsets move with the records.
+    #[cfg(feature = "std")]
+    pub(crate) fn remap_offsets(&mut self, remap: &[u32]) {
+        if self.offsets.is_empty() {
+            return;
+        }
+        let mut offsets = alloc::vec![u64::MAX; self.records.len()];
+        for (old, &new) in remap.iter().enumerate() {
+            if let (Some(&offset), Some(slot)) =
+                (self.offsets.get(old), offsets.get_mut(new as usize))
+            {
+                *slot = offset;
+            }
+        }
+        self.offsets = offsets;
+    }
+
+    /// The vector file set by [`RecordPool::move_vectors_to`], if any.
+    #[cfg(feature = "std")]
+    pub fn vector_file(&self) -> Option<&crate::storage::vector_file::VectorFile> {
+        self.file.as_ref()
+    }
+
+    /// `record`'s vector, from the record itself or the vector file.
+    pub fn vector<'a>(&'a self, record: &'a Record) -> &'a [FxpScalar] {
+        #[cfg(feature = "std")]
+        if record.vector.is_empty() {
+            if let (Some(file), Some(&offset)) =
+                (&self.file, self.offsets.get(record.id.0 as usize))
+            {
+                if let Some(vector) = file.get(offset, self.file_dim) {
+                    return vector;
+                }
+            }
+        }
+        record.vector.as_slice()
+    }
+
+    /// Appends an empty slot, reserving the next id without a record —
+    /// the same slot an insert followed by a hard delete leaves behind.
+    pub fn reserve(&mut self) -> RecordId {
+        let id = RecordId(self.records.len() as u32);
+        self.records.push(None);
+        id
+    }
+
     /// Deletes the record at the specified ID (index).
     pub fn delete(&mut self, id: RecordId) -> Result<()> {
         let idx = id.0 as usize;
@@ -60,7 +174,8 @@ impl RecordPool {
         }
     }
 
-    /// Updates the metadata bytes on an existing record in-place.
+    /// Updates the metadata bytes on an existing record in-place and bumps
+    /// its version.
     pub fn update_metadata(
         &mut self,
         id: RecordId,
@@ -70,6 +185,7 @@ impl RecordPool {
         match self.records.get_mut(idx).and_then(|s| s.as_mut()) {
             Some(rec) => {
                 rec.metadata = metadata;
+                rec.version += 1;
                 Ok(())
             }
             None => Err(KernelError::NotFound),
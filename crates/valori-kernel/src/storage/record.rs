@@ -35,6 +35,11 @@ pub struct Record {
     pub next_in_ns: u32,
     /// Previous record in this namespace's intrusive linked list (NS_LIST_NIL = head).
     pub prev_in_ns: u32,
+    /// Per-record version: 1 on insert, bumped by every
+    /// `UpdateRecordMetadata`. Derived from the record's events alone, so
+    /// replay reproduces it; it is saved in snapshots (V9) but, like the
+    /// namespace list pointers, left out of the state hash.
+    pub version: u64,
 }
 
 impl Record {
@@ -54,6 +59,7 @@ impl Record {
             namespace_id,
             next_in_ns: NS_LIST_NIL,
             prev_in_ns: NS_LIST_NIL,
+            version: 1,
         }
     }
 
@@ -0,0 +1,64 @@
+// Copyright (c) 2025 Varshith Gudur. Dual-licensed under MIT OR Apache-2.0.
+//! Sparse vectors alongside the record pool.
+//!
+//! A record may carry one [`SparseVector`] next to its dense vector. They
+//! are kept here, keyed by record id, rather than on the record: most
+//! records have none, and the record layout and its snapshot section stay
+//! as they were. Iteration is in id order.
+
+use crate::types::id::{RecordId, NS_LIST_NIL};
+use crate::types::sparse::SparseVector;
+use alloc::collections::BTreeMap;
+
+#[derive(Clone, Default)]
+pub struct SparsePool {
+    vectors: BTreeMap<u32, SparseVector>,
+}
+
+impl SparsePool {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    pub fn get(&self, id: RecordId) -> Option<&SparseVector> {
+        self.vectors.get(&id.0)
+    }
+
+    /// Give record `id` the vector `v`; an empty one removes it.
+    pub fn set(&mut self, id: RecordId, v: SparseVector) {
+        if v.is_empty() {
+            self.vectors.remove(&id.0);
+        } else {
+            self.vectors.insert(id.0, v);
+        }
+    }
+
+    pub fn remove(&mut self, id: RecordId) {
+        self.vectors.remove(&id.0);
+    }
+
+    pub fn len(&self) -> usize {
+        self.vectors.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.vectors.is_empty()
+    }
+
+    pub fn iter(&self) -> impl Iterator<Item = (RecordId, &SparseVector)> {
+        self.vectors.iter().map(|(&id, v)| (RecordId(id), v))
+    }
+
+    /// Follow a record-pool re-pack: `remap[old]` is the new id, or
+    /// `NS_LIST_NIL` for a reclaimed slot, whose vector is dropped.
+    pub(crate) fn remap(&mut self, remap: &[u32]) {
+        let old = core::mem::take(&mut self.vectors);
+        self.vectors = old
+            .into_iter()
+            .filter_map(|(id, v)| {
+                let new = remap.get(id as usize).copied().unwrap_or(NS_LIST_NIL);
+                (new != NS_LIST_NIL).then_some((new, v))
+            })
+            .collect();
+    }
+}
@@ -0,0 +1,235 @@
+//! Append-only, memory-mapped vector file.
+
+// Copyright (c) 2025 Varshith Gudur. Dual-licensed under MIT OR Apache-2.0.
+//
+// Lets a `RecordPool` keep its vectors out of RAM: each vector is appended
+// once, in native byte order, and the pool remembers only its offset. The
+// file is a cache of the pool rather than a durable format — it is created
+// empty when opened, and the event log or snapshot stays the source of truth.
+//
+// The file is mapped in fixed windows that never move, so a slice borrowed
+// from one stays valid while later appends map new windows. A vector never
+// straddles two windows.
+//
+// Clones of a pool share the file. Every clone applying the same events
+// writes the same vector for the same record id, so an append whose bytes
+// match the last vector written for that id reuses it instead: the engine
+// state, the committer's copy and their shadow clones store each vector once.
+
+use crate::error::{KernelError, Result};
+use crate::types::id::RecordId;
+use crate::types::scalar::FxpScalar;
+use alloc::sync::Arc;
+use alloc::vec::Vec;
+use memmap2::Mmap;
+use std::fs::File;
+use std::io::{Seek, SeekFrom, Write};
+use std::path::{Path, PathBuf};
+use std::sync::Mutex;
+
+/// Bytes per mapped window. Vectors longer than this cannot be stored.
+pub const WINDOW_BYTES: u64 = 16 << 20;
+
+const SCALAR_BYTES: usize = core::mem::size_of::<FxpScalar>();
+
+struct Shared {
+    path: PathBuf,
+    inner: Mutex<Inner>,
+}
+
+struct Inner {
+    file: File,
+    /// Logical end of the file; the last window is zero-filled past it.
+    end: u64,
+    windows: Vec<Arc<Mmap>>,
+    /// Offset of the last vector written for each record id.
+    latest: Vec<u64>,
+}
+
+/// Handle to a vector file. Cloning shares the file; each clone keeps its
+/// own list of mapped windows.
+#[derive(Clone)]
+pub struct VectorFile {
+    shared: Arc<Shared>,
+    windows: Vec<Arc<Mmap>>,
+}
+
+impl VectorFile {
+    /// Create (or truncate) the file at `path`.
+    pub fn create(path: impl AsRef<Path>) -> Result<Self> {
+        let path = path.as_ref().to_path_buf();
+        let file = std::fs::OpenOptions::new()
+            .read(true)
+            .write(true)
+            .create(true)
+            .truncate(true)
+            .open(&path)?;
+        Ok(Self {
+            shared: Arc::new(Shared {
+                path,
+                inner: Mutex::new(Inner {
+                    file,
+                    end: 0,
+                    windows: Vec::new(),
+                    latest: Vec::new(),
+                }),
+            }),
+            windows: Vec::new(),
+        })
+    }
+
+    /// Whether both handles write to the same file.
+    pub fn same_file(&self, other: &VectorFile) -> bool {
+        Arc::ptr_eq(&self.shared, &other.shared)
+    }
+
+    pub fn path(&self) -> &Path {
+        &self.shared.path
+    }
+
+    /// Bytes appended so far.
+    pub fn len(&self) -> u64 {
+        self.lock().end
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.len() == 0
+    }
+
+    fn lock(&self) -> std::sync::MutexGuard<'_, Inner> {
+        self.shared
+            .inner
+            .lock()
+            .unwrap_or_else(|poisoned| poisoned.into_inner())
+    }
+
+    /// Store `vector` as record `id`'s and return its offset.
+    pub fn append(&mut self, id: RecordId, vector: &[FxpScalar]) -> Result<u64> {
+        let bytes = vector.len() * SCALAR_BYTES;
+        if bytes as u64 > WINDOW_BYTES {
+            return Err(KernelError::InvalidInput);
+        }
+        let mut inner = self.lock();
+
+        if let Some(&off) = inner.latest.get(id.0 as usize) {
+            if off != u64::MAX && stored(&inner.windows, off, vector.len()) == Some(vector) {
+                let windows = self.newer_windows(&inner);
+                drop(inner);
+                self.adopt(windows);
+                return Ok(off);
+            }
+        }
+
+        let mut off = inner.end;
+        if off % WINDOW_BYTES + bytes as u64 > WINDOW_BYTES {
+            off = (off / WINDOW_BYTES + 1) * WINDOW_BYTES;
+        }
+        let window = (off / WINDOW_BYTES) as usize;
+        while inner.windows.len() <= window {
+            let start = inner.windows.len() as u64 * WINDOW_BYTES;
+            inner.file.set_len(start + WINDOW_BYTES)?;
+            // SAFETY: the file is private to this process and only ever
+            // appended to; bytes below `end` are never rewritten.
+            let map = unsafe {
+                memmap2::MmapOptions::new()
+                    .offset(start)
+                    .len(WINDOW_BYTES as usize)
+                    .map(&inner.file)?
+            };
+            inner.windows.push(Arc::new(map));
+        }
+
+        // SAFETY: FxpScalar is #[repr(transparent)] over i32.
+        let raw = unsafe { core::slice::from_raw_parts(vector.as_ptr() as *const u8, bytes) };
+        inner.file.seek(SeekFrom::Start(off))?;
+        inner.file.write_all(raw)?;
+        inner.end = off + bytes as u64;
+
+        let slot = id.0 as usize;
+        if inner.latest.len() <= slot {
+            inner.latest.resize(slot + 1, u64::MAX);
+        }
+        inner.latest[slot] = off;
+        let windows = self.newer_windows(&inner);
+        drop(inner);
+        self.adopt(windows);
+        Ok(off)
+    }
+
+    /// The shared window list, when it maps more than this handle does.
+    fn newer_windows(&self, inner: &Inner) -> Option<Vec<Arc<Mmap>>> {
+        (inner.windows.len() > self.windows.len()).then(|| inner.windows.clone())
+    }
+
+    fn adopt(&mut self, windows: Option<Vec<Arc<Mmap>>>) {
+        if let Some(windows) = windows {
+            self.windows = windows;
+        }
+    }
+
+    /// The `dim`-long vector stored at `offset`, if this handle has it mapped.
+    pub fn get(&self, offset: u64, dim: usize) -> Option<&[FxpScalar]> {
+        stored(&self.windows, offset, dim)
+    }
+}
+
+fn stored(windows: &[Arc<Mmap>], offset: u64, dim: usize) -> Option<&[FxpScalar]> {
+    let window = windows.get((offset / WINDOW_BYTES) as usize)?;
+    let start = (offset % WINDOW_BYTES) as usize;
+    let bytes = window.get(start..start + dim * SCALAR_BYTES)?;
+    // SAFETY: windows start page-aligned and every offset is a multiple of
+    // the scalar size, so the slice is aligned for i32; FxpScalar is
+    // #[repr(transparent)] over i32 and any bit pattern is valid.
+    Some(unsafe { core::slice::from_raw_parts(bytes.as_ptr() as *const FxpScalar, dim) })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn v(x: i32) -> Vec<FxpScalar> {
+        alloc::vec![FxpScalar(x), FxpScalar(-x), FxpScalar(7)]
+    }
+
+    #[test]
+    fn appends_and_reads_back() {
+        let dir = tempfile::tempdir().unwrap();
+        let mut file = VectorFile::create(dir.path().join("vectors.bin")).unwrap();
+        let a = file.append(RecordId(0), &v(1)).unwrap();
+        let b = file.append(RecordId(1), &v(2)).unwrap();
+        assert_eq!(file.get(a, 3).unwrap(), v(1).as_slice());
+        assert_eq!(file.get(b, 3).unwrap(), v(2).as_slice());
+        assert_eq!(file.len(), 24);
+    }
+
+    #[test]
+    fn clones_share_repeated_writes() {
+        let dir = tempfile::tempdir().unwrap();
+        let mut file = VectorFile::create(dir.path().join("vectors.bin")).unwrap();
+        let mut copy = file.clone();
+        let a = file.append(RecordId(0), &v(1)).unwrap();
+        assert_eq!(copy.append(RecordId(0), &v(1)).unwrap(), a);
+        assert_eq!(copy.get(a, 3).unwrap(), v(1).as_slice());
+
+        // A different vector for the same id is appended, not overwritten.
+        let b = copy.append(RecordId(0), &v(5)).unwrap();
+        assert_ne!(a, b);
+        assert_eq!(file.get(a, 3).unwrap(), v(1).as_slice());
+        assert_eq!(file.len(), 24);
+    }
+
+    #[test]
+    fn vectors_never_straddle_a_window() {
+        let dir = tempfile::tempdir().unwrap();
+        let mut file = VectorFile::create(dir.path().join("vectors.bin")).unwrap();
+        let dim = (WINDOW_BYTES as usize / SCALAR_BYTES) / 3 + 1;
+        let big: Vec<FxpScalar> = (0..dim as i32).map(FxpScalar).collect();
+        let offsets: Vec<u64> = (0..4)
+            .map(|i| file.append(RecordId(i), &big).unwrap())
+            .collect();
+        assert_eq!(offsets[2], WINDOW_BYTES);
+        for off in offsets {
+            assert_eq!(file.get(off, dim).unwrap(), big.as_slice());
+        }
+    }
+}
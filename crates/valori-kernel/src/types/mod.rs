@@ -2,4 +2,5 @@
 pub mod enums;
 pub mod id;
 pub mod scalar;
+pub mod sparse;
 pub mod vector;
@@ -0,0 +1,150 @@
+// Copyright (c) 2025 Varshith Gudur. Dual-licensed under MIT OR Apache-2.0.
+//! Sparse fixed-point vector: sorted `(index, value)` pairs.
+//!
+//! Learned sparse retrieval (SPLADE and the like) scores a query against
+//! documents over a vocabulary-sized space where only a few hundred weights
+//! are non-zero. A [`SparseVector`] keeps just those, with strictly
+//! increasing indices and no zero values, so equal vectors always encode the
+//! same way and two vectors are compared in one merge pass.
+
+use crate::config::MAX_SPARSE_NNZ;
+use crate::error::{KernelError, Result};
+use crate::types::scalar::FxpScalar;
+use serde::{Deserialize, Serialize};
+
+#[derive(Clone, Debug, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub struct SparseVector {
+    /// Strictly increasing.
+    pub indices: alloc::vec::Vec<u32>,
+    /// `values[i]` is the weight at `indices[i]`; never zero.
+    pub values: alloc::vec::Vec<FxpScalar>,
+}
+
+impl SparseVector {
+    /// Build from `(index, value)` pairs in any order. Zero values are
+    /// dropped; a repeated index or more than [`MAX_SPARSE_NNZ`] non-zero
+    /// entries is rejected.
+    pub fn from_pairs(mut pairs: alloc::vec::Vec<(u32, FxpScalar)>) -> Result<Self> {
+        pairs.retain(|&(_, v)| v != FxpScalar::ZERO);
+        pairs.sort_unstable_by_key(|&(i, _)| i);
+        let v = Self {
+            indices: pairs.iter().map(|&(i, _)| i).collect(),
+            values: pairs.iter().map(|&(_, v)| v).collect(),
+        };
+        v.validate()?;
+        Ok(v)
+    }
+
+    /// Check what [`SparseVector::from_pairs`] guarantees, for a vector
+    /// that arrived some other way (a decoded event or snapshot).
+    pub fn validate(&self) -> Result<()> {
+        if self.indices.len() != self.values.len()
+            || self.indices.len() > MAX_SPARSE_NNZ
+            || !self.indices.windows(2).all(|w| w[0] < w[1])
+            || self.values.contains(&FxpScalar::ZERO)
+        {
+            return Err(KernelError::InvalidInput);
+        }
+        Ok(())
+    }
+
+    /// Number of non-zero entries.
+    pub fn nnz(&self) -> usize {
+        self.indices.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.indices.is_empty()
+    }
+
+    pub fn iter(&self) -> impl Iterator<Item = (u32, FxpScalar)> + '_ {
+        self.indices
+            .iter()
+            .copied()
+            .zip(self.values.iter().copied())
+    }
+
+    /// Dot product over the indices both vectors hold, as the raw Q32.32
+    /// sum of products (no shift, like the dense distances). Summed in
+    /// i128 and saturated into i64, so no input can overflow it.
+    pub fn dot(&self, other: &SparseVector) -> i64 {
+        let (mut i, mut j) = (0, 0);
+        let mut sum: i128 = 0;
+        while i < self.indices.len() && j < other.indices.len() {
+            match self.indices[i].cmp(&other.indices[j]) {
+                core::cmp::Ordering::Less => i += 1,
+                core::cmp::Ordering::Greater => j += 1,
+                core::cmp::Ordering::Equal => {
+                    sum += self.values[i].0 as i128 * other.values[j].0 as i128;
+                    i += 1;
+                    j += 1;
+                }
+            }
+        }
+        sum.clamp(i64::MIN as i128, i64::MAX as i128) as i64
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::fxp::qformat::SCALE;
+
+    fn fxp(x: i32) -> FxpScalar {
+        FxpScalar(x * SCALE)
+    }
+
+    #[test]
+    fn from_pairs_sorts_and_drops_zeros() {
+        let v =
+            SparseVector::from_pairs(alloc::vec![(9, fxp(2)), (3, fxp(1)), (5, FxpScalar::ZERO)])
+                .unwrap();
+        assert_eq!(v.indices, [3, 9]);
+        assert_eq!(v.values, [fxp(1), fxp(2)]);
+        assert!(SparseVector::from_pairs(alloc::vec![(1, fxp(1)), (1, fxp(2))]).is_err());
+        let too_many = (0..=MAX_SPARSE_NNZ as u32).map(|i| (i, fxp(1))).collect();
+        assert!(SparseVector::from_pairs(too_many).is_err());
+    }
+
+    #[test]
+    fn validate_rejects_what_from_pairs_never_builds() {
+        let unsorted = SparseVector {
+            indices: alloc::vec![4, 2],
+            values: alloc::vec![fxp(1), fxp(1)],
+        };
+        let zero = SparseVector {
+            indices: alloc::vec![2],
+            values: alloc::vec![FxpScalar::ZERO],
+        };
+        let ragged = SparseVector {
+            indices: alloc::vec![2],
+            values: alloc::vec![],
+        };
+        for v in [unsorted, zero, ragged] {
+            assert!(matches!(v.validate(), Err(KernelError::InvalidInput)));
+        }
+    }
+
+    #[test]
+    fn dot_sums_shared_indices_only() {
+        let a =
+            SparseVector::from_pairs(alloc::vec![(1, fxp(2)), (4, fxp(3)), (7, fxp(-1))]).unwrap();
+        let b =
+            SparseVector::from_pairs(alloc::vec![(4, fxp(5)), (7, fxp(2)), (8, fxp(9))]).unwrap();
+        // 3·5 + (−1)·2 = 13, in Q32.32.
+        assert_eq!(a.dot(&b), 13 << 32);
+        assert_eq!(a.dot(&b), b.dot(&a));
+        assert_eq!(a.dot(&SparseVector::default()), 0);
+    }
+
+    #[test]
+    fn dot_saturates() {
+        let big = SparseVector::from_pairs(
+            (0..MAX_SPARSE_NNZ as u32)
+                .map(|i| (i, FxpScalar(i32::MAX)))
+                .collect(),
+        )
+        .unwrap();
+        assert_eq!(big.dot(&big), i64::MAX);
+    }
+}
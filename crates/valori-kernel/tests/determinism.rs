@@ -89,6 +89,59 @@ fn tag_change_changes_hash() {
     assert_ne!(replay(&a), replay(&b));
 }
 
+fn set_meta(key: &str, value: &str) -> KernelEvent {
+    KernelEvent::SetMeta {
+        key: key.into(),
+        value: value.into(),
+    }
+}
+
+#[test]
+fn meta_map_is_covered_by_the_hash() {
+    let mut a = build_events(10);
+    let mut b = build_events(10);
+    a.push(set_meta("doc:1", r#"{"v":1}"#));
+    b.push(set_meta("doc:1", r#"{"v":2}"#));
+    assert_ne!(replay(&a), replay(&b), "value change");
+
+    // Same final map whatever order the keys were set in.
+    let mut c = build_events(10);
+    let mut d = build_events(10);
+    c.extend([set_meta("a", "1"), set_meta("b", "2")]);
+    d.extend([set_meta("b", "2"), set_meta("a", "1")]);
+    assert_eq!(replay(&c), replay(&d));
+
+    // A delete is an event too: replay reproduces it exactly.
+    c.push(KernelEvent::DeleteMeta { key: "a".into() });
+    let mut state = KernelState::new();
+    for e in &c {
+        state.apply_event(e).unwrap();
+    }
+    assert!(!state.meta.contains_key("a"));
+    assert_eq!(state.meta.get("b").map(String::as_str), Some("2"));
+    assert_eq!(hash_state_blake3(&state), replay(&c));
+    assert_ne!(replay(&c), replay(&d));
+}
+
+#[test]
+fn meta_limits_are_not_checked_at_replay() {
+    use valori_kernel::config::MAX_META_KEY_SIZE;
+
+    // Logs written before the key limit may hold longer keys; they replay,
+    // and the snapshot of the result decodes to the same state.
+    let mut state = KernelState::new();
+    let long_key = "k".repeat(MAX_META_KEY_SIZE + 1);
+    state.apply_event(&set_meta(&long_key, "1")).unwrap();
+    assert_eq!(state.meta.get(&long_key).map(String::as_str), Some("1"));
+    let decoded = decode_state(&encode(&state)).unwrap();
+    assert_eq!(hash_state_blake3(&decoded), hash_state_blake3(&state));
+
+    // Deleting a missing key is a no-op, not an error.
+    state
+        .apply_event(&KernelEvent::DeleteMeta { key: "k".into() })
+        .unwrap();
+}
+
 #[test]
 fn empty_state_hash_is_constant() {
     let h1 = hash_state_blake3(&KernelState::new());
@@ -1,11 +1,13 @@
 // Copyright (c) 2025 Varshith Gudur. Dual-licensed under MIT OR Apache-2.0.
-//! L2 search: exact-match retrieval, deterministic ordering, tag filtering.
+//! L2 search: exact-match retrieval, deterministic ordering, tag filtering,
+//! cancellation, range search, vectors kept in a file, sparse search.
 
 use valori_kernel::event::KernelEvent;
-use valori_kernel::index::SearchResult;
+use valori_kernel::index::{SearchResult, TagFilter, CANCEL_CHECK_INTERVAL};
 use valori_kernel::state::kernel::KernelState;
-use valori_kernel::types::id::RecordId;
+use valori_kernel::types::id::{RecordId, DEFAULT_NS};
 use valori_kernel::types::scalar::FxpScalar;
+use valori_kernel::types::sparse::SparseVector;
 use valori_kernel::types::vector::FxpVector;
 
 const DIM: usize = 4;
@@ -32,7 +34,7 @@ fn populated() -> KernelState {
     state
 }
 
-fn search(state: &KernelState, query: &FxpVector, k: usize, filter: Option<u64>) -> Vec<u32> {
+fn search(state: &KernelState, query: &FxpVector, k: usize, filter: Option<TagFilter>) -> Vec<u32> {
     let mut buf = vec![
         SearchResult {
             id: RecordId(0),
@@ -69,7 +71,7 @@ fn search_is_deterministic() {
 fn tag_filter_excludes_other_tags() {
     let state = populated();
     // tag 1 → records 1 and 3 only
-    let hits = search(&state, &fxp(&[0, 0, 0, 0]), 4, Some(1));
+    let hits = search(&state, &fxp(&[0, 0, 0, 0]), 4, Some(1.into()));
     assert!(!hits.is_empty());
     for id in &hits {
         assert!(*id == 1 || *id == 3, "tag filter leaked record {id}");
@@ -82,3 +84,250 @@ fn k_larger_than_corpus_returns_all() {
     let hits = search(&state, &fxp(&[0, 0, 0, 0]), 16, None);
     assert_eq!(hits.len(), 4);
 }
+
+#[test]
+fn tag_bitmask_any_and_all() {
+    let mut state = KernelState::new();
+    // Tags as label bitmasks: bit 0 = "news", bit 1 = "sports", bit 2 = "eu".
+    let tags = [0b001u64, 0b011, 0b110, 0b111, 0];
+    for (i, &tag) in tags.iter().enumerate() {
+        state
+            .apply_event(&KernelEvent::InsertRecord {
+                id: RecordId(i as u32),
+                vector: fxp(&[i as i32, 0, 0, 0]),
+                metadata: None,
+                tag,
+            })
+            .unwrap();
+    }
+    let q = fxp(&[0, 0, 0, 0]);
+    let sorted = |f| {
+        let mut hits = search(&state, &q, 8, Some(f));
+        hits.sort_unstable();
+        hits
+    };
+    assert_eq!(sorted(TagFilter::Any(0b001)), vec![0, 1, 3]);
+    assert_eq!(sorted(TagFilter::Any(0b101)), vec![0, 1, 2, 3]);
+    assert_eq!(sorted(TagFilter::All(0b011)), vec![1, 3]);
+    assert_eq!(sorted(TagFilter::All(0b110)), vec![2, 3]);
+    assert_eq!(sorted(TagFilter::Exact(0b011)), vec![1]);
+    // An empty mask: `All` admits everything, `Any` nothing.
+    assert_eq!(sorted(TagFilter::All(0)).len(), 5);
+    assert!(sorted(TagFilter::Any(0)).is_empty());
+}
+
+#[test]
+fn cancelled_namespace_scan_gives_up() {
+    let mut state = KernelState::new();
+    for i in 0..(2 * CANCEL_CHECK_INTERVAL) {
+        state
+            .apply_event(&KernelEvent::InsertRecord {
+                id: RecordId(i as u32),
+                vector: fxp(&[i as i32, 0, 0, 0]),
+                metadata: None,
+                tag: 0,
+            })
+            .unwrap();
+    }
+    let q = fxp(&[0, 0, 0, 0]);
+    let mut buf = vec![SearchResult::default(); 2];
+
+    let polls = std::cell::Cell::new(0);
+    let cancel_after_first = || {
+        polls.set(polls.get() + 1);
+        polls.get() > 1
+    };
+    let out = state.search_l2_ns_cancellable(&q, &mut buf, DEFAULT_NS.0, &cancel_after_first);
+    assert_eq!(out, None);
+    assert_eq!(polls.get(), 2, "polled once per interval");
+
+    let found = state.search_l2_ns_cancellable(&q, &mut buf, DEFAULT_NS.0, &|| false);
+    assert_eq!(found, Some(2));
+    assert_eq!(state.search_l2_ns(&q, &mut buf, DEFAULT_NS.0), 2);
+}
+
+#[test]
+fn range_search_keeps_records_within_the_radius() {
+    let mut state = populated();
+    state
+        .apply_event(&KernelEvent::SoftDeleteRecord { id: RecordId(1) })
+        .unwrap();
+    let q = fxp(&[0, 0, 0, 0]);
+    let one = 1i64 << 32;
+    let range = |max: i64, k: usize| {
+        let mut buf = vec![SearchResult::default(); k];
+        let n = state.search_range_ns(&q, max, &mut buf, DEFAULT_NS.0);
+        buf[..n]
+            .iter()
+            .map(|r| (r.id.0, r.score / one))
+            .collect::<Vec<_>>()
+    };
+    // Record 1 (distance 1) is deleted; record 3 is at 324.
+    assert_eq!(range(0, 4), [(0, 0)]);
+    assert_eq!(range(25 * one, 4), [(0, 0), (2, 25)]);
+    assert_eq!(
+        range(25 * one, 1),
+        [(0, 0)],
+        "capped at the buffer, nearest kept"
+    );
+    assert_eq!(range(i64::MAX, 8).len(), 3);
+    assert!(range(-1, 4).is_empty());
+}
+
+#[test]
+fn vectors_moved_to_a_file_search_and_hash_the_same() {
+    use valori_kernel::snapshot::blake3::hash_state_blake3;
+    use valori_kernel::snapshot::encode::encode_state;
+    use valori_kernel::storage::vector_file::VectorFile;
+
+    let dir = tempfile::tempdir().unwrap();
+    let mut inline = populated();
+    let mut mapped = populated();
+    mapped
+        .move_vectors_to(VectorFile::create(dir.path().join("vectors.bin")).unwrap())
+        .unwrap();
+    let rec = mapped.get_record(RecordId(2)).unwrap();
+    assert!(rec.vector.is_empty());
+    assert_eq!(mapped.record_vector(rec), fxp(&[0, 5, 0, 0]).as_slice());
+
+    // Later inserts append to the file; a clone applying the same insert
+    // shares the bytes already there.
+    let mut shadow = mapped.clone();
+    for state in [&mut inline, &mut mapped, &mut shadow] {
+        state
+            .apply_event(&KernelEvent::InsertRecord {
+                id: RecordId(4),
+                vector: fxp(&[2, 2, 0, 0]),
+                metadata: None,
+                tag: 0,
+            })
+            .unwrap();
+    }
+    assert_eq!(mapped.vector_file().unwrap().len(), 5 * DIM as u64 * 4);
+
+    let query = fxp(&[1, 1, 0, 0]);
+    assert_eq!(
+        search(&mapped, &query, 3, None),
+        search(&inline, &query, 3, None)
+    );
+    assert_eq!(hash_state_blake3(&mapped), hash_state_blake3(&inline));
+    assert_eq!(hash_state_blake3(&shadow), hash_state_blake3(&inline));
+    let (mut a, mut b) = (Vec::new(), Vec::new());
+    encode_state(&mapped, &mut a).unwrap();
+    encode_state(&inline, &mut b).unwrap();
+    assert_eq!(a, b);
+}
+
+#[test]
+fn vacuum_with_vectors_in_a_file_matches_the_in_memory_state() {
+    use valori_kernel::snapshot::blake3::hash_state_blake3;
+    use valori_kernel::storage::vector_file::VectorFile;
+
+    let dir = tempfile::tempdir().unwrap();
+    let mut inline = populated();
+    let mut mapped = populated();
+    mapped
+        .move_vectors_to(VectorFile::create(dir.path().join("vectors.bin")).unwrap())
+        .unwrap();
+    for state in [&mut inline, &mut mapped] {
+        state
+            .apply_event(&KernelEvent::DeleteRecord { id: RecordId(0) })
+            .unwrap();
+        let moves = state.vacuum_moves().unwrap();
+        assert!(!moves.is_empty());
+        state.apply_event(&KernelEvent::Vacuum { moves }).unwrap();
+    }
+
+    // Every record moved down a slot and still reads its own vector.
+    for (id, p) in [&[1, 0, 0, 0], &[0, 5, 0, 0], &[9, 9, 9, 9]]
+        .iter()
+        .enumerate()
+    {
+        let rec = mapped.get_record(RecordId(id as u32)).unwrap();
+        assert_eq!(mapped.record_vector(rec), fxp(*p).as_slice(), "record {id}");
+        let rec = inline.get_record(RecordId(id as u32)).unwrap();
+        assert_eq!(inline.record_vector(rec), fxp(*p).as_slice(), "record {id}");
+    }
+    assert_eq!(hash_state_blake3(&mapped), hash_state_blake3(&inline));
+
+    // Inserts after the vacuum land in the next slot of both.
+    for state in [&mut inline, &mut mapped] {
+        state
+            .apply_event(&KernelEvent::InsertRecord {
+                id: RecordId(3),
+                vector: fxp(&[2, 2, 0, 0]),
+                metadata: None,
+                tag: 0,
+            })
+            .unwrap();
+    }
+    let rec = mapped.get_record(RecordId(3)).unwrap();
+    assert_eq!(mapped.record_vector(rec), fxp(&[2, 2, 0, 0]).as_slice());
+    assert_eq!(hash_state_blake3(&mapped), hash_state_blake3(&inline));
+}
+
+fn sparse(pairs: &[(u32, i32)]) -> SparseVector {
+    SparseVector::from_pairs(
+        pairs
+            .iter()
+            .map(|&(i, v)| (i, FxpScalar(v << 16)))
+            .collect(),
+    )
+    .unwrap()
+}
+
+#[test]
+fn sparse_search_ranks_by_dot_product_and_follows_the_records() {
+    let mut state = populated();
+    let before = valori_kernel::snapshot::blake3::hash_state_blake3(&state);
+    for (id, pairs) in [
+        (0, &[(5, 1), (9, 2)][..]),
+        (1, &[(5, 3)][..]),
+        (2, &[(9, 1), (70, 4)][..]),
+        (3, &[(5, -2)][..]),
+    ] {
+        state
+            .apply_event(&KernelEvent::SetSparseVector {
+                id: RecordId(id),
+                vector: sparse(pairs),
+            })
+            .unwrap();
+    }
+    assert_ne!(
+        valori_kernel::snapshot::blake3::hash_state_blake3(&state),
+        before
+    );
+    let query = sparse(&[(5, 1), (9, 1)]);
+    let one = 1i64 << 32;
+    let hits = |state: &KernelState| {
+        let mut buf = vec![SearchResult::default(); 8];
+        let n = state.search_sparse_ns(&query, &mut buf, DEFAULT_NS.0);
+        buf[..n]
+            .iter()
+            .map(|r| (r.id.0, -r.score / one))
+            .collect::<Vec<_>>()
+    };
+    // Record 3's dot product is negative and is left out.
+    assert_eq!(hits(&state), [(0, 3), (1, 3), (2, 1)]);
+    assert_eq!(state.search_sparse_ns(&query, &mut [], DEFAULT_NS.0), 0);
+
+    state
+        .apply_event(&KernelEvent::SoftDeleteRecord { id: RecordId(1) })
+        .unwrap();
+    state
+        .apply_event(&KernelEvent::DeleteRecord { id: RecordId(0) })
+        .unwrap();
+    assert_eq!(hits(&state), [(2, 1)]);
+    assert!(state
+        .apply_event(&KernelEvent::SetSparseVector {
+            id: RecordId(1),
+            vector: sparse(&[(5, 1)]),
+        })
+        .is_err());
+
+    // Vacuum re-packs record 2 into slot 0 and its sparse vector moves too.
+    let moves = state.vacuum_moves().unwrap();
+    state.apply_event(&KernelEvent::Vacuum { moves }).unwrap();
+    assert_eq!(hits(&state), [(0, 1)]);
+    assert_eq!(state.sparse_count(), 2);
+}
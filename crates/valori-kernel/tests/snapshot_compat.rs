@@ -19,6 +19,7 @@ use valori_kernel::state::kernel::KernelState;
 use valori_kernel::types::enums::{EdgeKind, NodeKind};
 use valori_kernel::types::id::{EdgeId, NodeId, RecordId};
 use valori_kernel::types::scalar::FxpScalar;
+use valori_kernel::types::sparse::SparseVector;
 use valori_kernel::types::vector::FxpVector;
 
 fn fixture_path(name: &str) -> std::path::PathBuf {
@@ -141,8 +142,68 @@ fn state_multi() -> KernelState {
     s
 }
 
+fn state_resized() -> KernelState {
+    let mut s = state_multi();
+    s.apply_event(&KernelEvent::ResizePools {
+        records: 1024,
+        nodes: 256,
+        edges: 512,
+    })
+    .unwrap();
+    s
+}
+
+fn state_versioned() -> KernelState {
+    let mut s = state_multi();
+    for metadata in [b"{\"rev\":1}".to_vec(), b"{\"rev\":2}".to_vec()] {
+        s.apply_event(&KernelEvent::UpdateRecordMetadata {
+            id: RecordId(3),
+            metadata: Some(metadata),
+        })
+        .unwrap();
+    }
+    s
+}
+
+fn state_sparse() -> KernelState {
+    let mut s = state_versioned();
+    for (id, pairs) in [
+        (2u32, vec![(17u32, 3i32), (900, -1)]),
+        (5, vec![(17, 2), (40_000, 7)]),
+        (9, vec![(1, 1)]),
+    ] {
+        let pairs = pairs
+            .into_iter()
+            .map(|(i, v)| (i, FxpScalar(v << 16)))
+            .collect();
+        s.apply_event(&KernelEvent::SetSparseVector {
+            id: RecordId(id),
+            vector: SparseVector::from_pairs(pairs).unwrap(),
+        })
+        .unwrap();
+    }
+    s
+}
+
 // ── Forever-decode tests ──────────────────────────────────────────────────────
 
+/// The hash a fixture was pinned with before the state hash covered the
+/// kernel `meta` map: the same state, hashed without it. Fixtures carrying
+/// meta pin both — this in `<name>.hash`, the full hash in
+/// `<name>.meta.hash`.
+fn hash_without_meta(state: &KernelState) -> [u8; 32] {
+    let mut state = state.clone();
+    state.meta.clear();
+    hash_state_blake3(&state)
+}
+
+fn pinned(name: &str) -> String {
+    std::fs::read_to_string(fixture_path(name))
+        .unwrap_or_else(|_| panic!("{name} must exist"))
+        .trim()
+        .to_string()
+}
+
 /// Empty state hash is also pinned in `format.rs::empty_state_hash_is_pinned` —
 /// the snapshot fixture test is the binary complement: same state, but now
 /// the binary encoding itself is also locked.
@@ -157,6 +218,7 @@ fn snapshot_v7_empty_decodes_forever() {
         "empty-state hash changed — snapshot format or hash domain broke compatibility"
     );
     assert_eq!(state.record_count(), 0);
+    assert_eq!(hash_state_blake3(&state), hash_state_blake3(&state_empty()));
 }
 
 #[test]
@@ -173,6 +235,10 @@ fn snapshot_v7_single_decodes_forever() {
     );
     assert_eq!(state.record_count(), 1);
     assert_eq!(state.node_count(), 1);
+    assert_eq!(
+        hash_state_blake3(&state),
+        hash_state_blake3(&state_single())
+    );
 }
 
 #[test]
@@ -183,10 +249,15 @@ fn snapshot_v7_multi_decodes_forever() {
         .expect("snapshot_v7_multi.hash must exist");
     let state = decode_state(&bytes).expect("fixture must decode forever");
     assert_eq!(
-        hex(&hash_state_blake3(&state)),
+        hex(&hash_without_meta(&state)),
         expected.trim(),
         "multi-record snapshot hash changed — snapshot format or hash domain broke compatibility"
     );
+    assert_eq!(
+        hex(&hash_state_blake3(&state)),
+        pinned("snapshot_v7_multi.meta.hash"),
+        "multi-record snapshot hash changed — the meta section broke compatibility"
+    );
     assert_eq!(state.record_count(), 16);
     assert_eq!(state.node_count(), 4);
     assert_eq!(state.edge_count(), 3);
@@ -230,6 +301,85 @@ fn snapshot_v7_multi_can_continue_after_restore() {
     );
 }
 
+/// V8 adds the committed pool capacities to the header (and, when set, to
+/// the state hash).
+#[test]
+fn snapshot_v8_resized_decodes_forever() {
+    let bytes = std::fs::read(fixture_path("snapshot_v8_resized.bin"))
+        .expect("committed snapshot_v8_resized.bin must exist");
+    let expected = std::fs::read_to_string(fixture_path("snapshot_v8_resized.hash"))
+        .expect("snapshot_v8_resized.hash must exist");
+    let state = decode_state(&bytes).expect("fixture must decode forever");
+    assert_eq!(
+        hex(&hash_without_meta(&state)),
+        expected.trim(),
+        "resized snapshot hash changed — snapshot format or hash domain broke compatibility"
+    );
+    assert_eq!(
+        hex(&hash_state_blake3(&state)),
+        pinned("snapshot_v8_resized.meta.hash"),
+        "resized snapshot hash changed — the meta section broke compatibility"
+    );
+    let cap = state.capacity().expect("capacity must survive");
+    assert_eq!((cap.records, cap.nodes, cap.edges), (1024, 256, 512));
+    assert_eq!(state.record_count(), 16);
+    assert_eq!(
+        hash_state_blake3(&state),
+        hash_state_blake3(&state_resized())
+    );
+}
+
+/// V9 adds the per-record version. Older snapshots decode with every
+/// record at version 1.
+#[test]
+fn snapshot_v9_versioned_decodes_forever() {
+    let bytes = std::fs::read(fixture_path("snapshot_v9_versioned.bin"))
+        .expect("committed snapshot_v9_versioned.bin must exist");
+    let expected = std::fs::read_to_string(fixture_path("snapshot_v9_versioned.hash"))
+        .expect("snapshot_v9_versioned.hash must exist");
+    let state = decode_state(&bytes).expect("fixture must decode forever");
+    assert_eq!(
+        hex(&hash_without_meta(&state)),
+        expected.trim(),
+        "versioned snapshot hash changed — snapshot format or hash domain broke compatibility"
+    );
+    assert_eq!(
+        hex(&hash_state_blake3(&state)),
+        pinned("snapshot_v9_versioned.meta.hash"),
+        "versioned snapshot hash changed — the meta section broke compatibility"
+    );
+    assert_eq!(state.get_record(RecordId(3)).unwrap().version, 3);
+    assert_eq!(state.get_record(RecordId(4)).unwrap().version, 1);
+
+    let v7 = decode_state(&std::fs::read(fixture_path("snapshot_v7_multi.bin")).unwrap())
+        .expect("fixture must decode forever");
+    assert!((0..16).all(|i| v7.get_record(RecordId(i)).unwrap().version == 1));
+}
+
+/// V10 adds sparse vectors. Older snapshots decode with none.
+#[test]
+fn snapshot_v10_sparse_decodes_forever() {
+    let bytes = std::fs::read(fixture_path("snapshot_v10_sparse.bin"))
+        .expect("committed snapshot_v10_sparse.bin must exist");
+    let expected = std::fs::read_to_string(fixture_path("snapshot_v10_sparse.hash"))
+        .expect("snapshot_v10_sparse.hash must exist");
+    let state = decode_state(&bytes).expect("fixture must decode forever");
+    assert_eq!(
+        hex(&hash_state_blake3(&state)),
+        expected.trim(),
+        "sparse snapshot hash changed — snapshot format or hash domain broke compatibility"
+    );
+    assert_eq!(state.sparse_count(), 3);
+    assert_eq!(
+        state.sparse_vector(RecordId(5)).unwrap().indices,
+        [17, 40_000]
+    );
+
+    let v9 = decode_state(&std::fs::read(fixture_path("snapshot_v9_versioned.bin")).unwrap())
+        .expect("fixture must decode forever");
+    assert_eq!(v9.sparse_count(), 0);
+}
+
 // ── Fixture generator (run once per schema version bump, then commit) ─────────
 
 /// `cargo test -p valori-kernel --test snapshot_compat generate_snapshot_fixtures -- --ignored --nocapture`
@@ -249,7 +399,11 @@ fn generate_snapshot_fixtures() {
         println!("{name}: {} bytes, hash {hash}", bytes.len());
     };
 
-    write_fixture("snapshot_v7_empty.bin", &state_empty());
-    write_fixture("snapshot_v7_single.bin", &state_single());
-    write_fixture("snapshot_v7_multi.bin", &state_multi());
+    // The V7 corpus (`snapshot_v7_{empty,single,multi}` from `state_empty`,
+    // `state_single` and `state_multi`), `snapshot_v8_resized` (from
+    // `state_resized`) and `snapshot_v9_versioned` (from `state_versioned`)
+    // were written by their encoders and must never be regenerated — only
+    // the current version is written here. Their `.meta.hash` pins were
+    // added alongside when the state hash began to cover the meta map.
+    write_fixture("snapshot_v10_sparse.bin", &state_sparse());
 }
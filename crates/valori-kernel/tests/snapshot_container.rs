@@ -0,0 +1,108 @@
+// Copyright (c) 2025 Varshith Gudur. Dual-licensed under MIT OR Apache-2.0.
+//! The snapshot container every crate reads and writes.
+
+use valori_kernel::event::KernelEvent;
+use valori_kernel::snapshot::blake3::hash_state_blake3;
+use valori_kernel::snapshot::container::{
+    encode_container, Checksum, Container, ContainerError, ContainerWriter,
+};
+use valori_kernel::state::kernel::KernelState;
+use valori_kernel::types::id::RecordId;
+use valori_kernel::types::vector::FxpVector;
+
+fn state() -> KernelState {
+    let mut state = KernelState::with_dim(4);
+    for i in 0..3 {
+        state
+            .apply_event(&KernelEvent::InsertRecord {
+                id: RecordId(i),
+                vector: FxpVector::new_zeros(4),
+                metadata: None,
+                tag: i as u64,
+            })
+            .unwrap();
+    }
+    state
+}
+
+fn container() -> Vec<u8> {
+    let mut w = ContainerWriter::new(b"kernel", b"meta", b"");
+    w.section(b"NSRG", b"{}")
+        .section(b"IDXN", b"first")
+        .section(b"IDXN", b"second");
+    w.finish()
+}
+
+#[test]
+fn sections_round_trip() {
+    let bytes = container();
+    let c = Container::parse(&bytes).unwrap();
+    assert_eq!(c.kernel, b"kernel");
+    assert_eq!(c.metadata, b"meta");
+    assert_eq!(c.index, b"");
+    assert_eq!(c.checksum, Checksum::Valid);
+    assert_eq!(c.section(b"NSRG"), Some(&b"{}"[..]));
+    assert_eq!(
+        c.sections_tagged(b"IDXN").collect::<Vec<_>>(),
+        [&b"first"[..], &b"second"[..]]
+    );
+    assert_eq!(c.section(b"BCRP"), None);
+    // The trailer is the checksum, not a section.
+    assert_eq!(c.sections.len(), 3);
+}
+
+#[test]
+fn kernel_only_container_restores_the_state_hash() {
+    let state = state();
+    let bytes = encode_container(&state).unwrap();
+    let restored = Container::parse(&bytes).unwrap().decode_state().unwrap();
+    assert_eq!(hash_state_blake3(&restored), hash_state_blake3(&state));
+    assert_eq!(restored.record_count(), 3);
+}
+
+#[test]
+fn flipped_byte_is_a_mismatch_not_an_error() {
+    let mut bytes = container();
+    bytes[8] ^= 0xff;
+    let c = Container::parse(&bytes).unwrap();
+    assert!(matches!(c.checksum, Checksum::Mismatch { .. }));
+}
+
+#[test]
+fn container_without_trailer_still_parses() {
+    let mut bytes = container();
+    bytes.truncate(bytes.len() - 12);
+    let c = Container::parse(&bytes).unwrap();
+    assert_eq!(c.checksum, Checksum::Absent);
+    assert_eq!(c.sections.len(), 3);
+}
+
+#[test]
+fn malformed_containers_are_rejected() {
+    assert_eq!(Container::parse(b"VALK"), Err(ContainerError::BadMagic));
+
+    let mut bytes = container();
+    bytes.truncate(bytes.len() - 12);
+    let last_len = bytes.len() - 6 - 4;
+    bytes[last_len..last_len + 4].copy_from_slice(&99u32.to_le_bytes());
+    let err = Container::parse(&bytes).unwrap_err();
+    assert!(
+        matches!(err, ContainerError::Overrun { ref section, len: 99, .. } if section == "IDXN")
+    );
+
+    let half = &container()[..10];
+    assert!(matches!(
+        Container::parse(half),
+        Err(ContainerError::Overrun { .. } | ContainerError::MissingLength { .. })
+    ));
+
+    let mut trailing = container();
+    trailing.truncate(trailing.len() - 12);
+    trailing.extend_from_slice(b"XY");
+    assert_eq!(
+        Container::parse(&trailing),
+        Err(ContainerError::TruncatedTag {
+            offset: trailing.len() - 2
+        })
+    );
+}
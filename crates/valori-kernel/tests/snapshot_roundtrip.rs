@@ -146,6 +146,29 @@ fn v7_meta_roundtrips() {
     );
 }
 
+#[test]
+fn v8_pool_capacity_roundtrips() {
+    let mut state = populated_state();
+    let unbounded_hash = hash_state_blake3(&state);
+    assert_eq!(
+        hash_state_blake3(&decode_state(&encode(&state)).unwrap()),
+        unbounded_hash
+    );
+
+    state
+        .apply_event(&KernelEvent::ResizePools {
+            records: 64,
+            nodes: 32,
+            edges: 16,
+        })
+        .unwrap();
+    assert_ne!(hash_state_blake3(&state), unbounded_hash);
+
+    let restored = decode_state(&encode(&state)).unwrap();
+    assert_eq!(restored.capacity(), state.capacity());
+    assert_eq!(hash_state_blake3(&restored), hash_state_blake3(&state));
+}
+
 // ── Decoder hardening tests ───────────────────────────────────────────────────
 // Each test crafts a minimally-valid snapshot then mutates one field to an
 // illegal value and verifies that decode_state returns Err.
@@ -165,7 +188,7 @@ fn valid_one_record_snapshot() -> Vec<u8> {
     encode(&state)
 }
 
-// Byte offsets in a current (V7) snapshot:
+// Byte offsets in a current (V8) snapshot:
 //   0..4   MAGIC
 //   4..8   schema_ver
 //   8..16  version_val
@@ -174,11 +197,12 @@ fn valid_one_record_snapshot() -> Vec<u8> {
 //  24..28  cap_nodes
 //  28..32  cap_edges
 //  32      format_id    (V5+)
-//  33..37  total_slots  (records section header)
-//  37      is_present   (first record slot flag)
+//  33      capacity flag (V8+; 0 = no ResizePools committed)
+//  34..38  total_slots  (records section header)
+//  38      is_present   (first record slot flag)
 const OFF_DIM: usize = 20;
-const OFF_TOTAL_SLOTS: usize = 33;
-const OFF_IS_PRESENT: usize = 37;
+const OFF_TOTAL_SLOTS: usize = 34;
+const OFF_IS_PRESENT: usize = 38;
 
 #[test]
 fn invalid_is_present_flag_is_rejected() {
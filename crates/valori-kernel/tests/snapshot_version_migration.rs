@@ -1,10 +1,11 @@
 // Copyright (c) 2025 Varshith Gudur. Dual-licensed under MIT OR Apache-2.0.
 //! K4 — cross-version snapshot migration.
 //!
-//! `decode_state` accepts `schema_ver` 1..=7 and has real, distinct
+//! `decode_state` accepts `schema_ver` 1..=10 and has real, distinct
 //! conditional branches per version (tag @V3, metadata @V2, incoming-edge
 //! back-pointers @V4 — reconstructed for older files, arithmetic-format
-//! byte @V5, namespace fields @V6, meta sidecar @V7). Every snapshot test
+//! byte @V5, namespace fields @V6, meta sidecar @V7, pool capacities @V8,
+//! per-record version @V9, sparse vectors @V10). Every snapshot test
 //! that existed before this file only ever exercised the CURRENT encoder,
 //! which always writes V7 — `tests/snapshot_compat.rs`'s "forever" fixtures
 //! are V7-only, `tests/snapshot_roundtrip.rs` round-trips only the current
@@ -91,6 +92,9 @@ fn encode_legacy(
     if schema_ver >= 5 {
         out.push(ACTIVE_FORMAT_ID);
     }
+    if schema_ver >= 8 {
+        out.push(0); // no ResizePools committed
+    }
 
     // ── Records ──────────────────────────────────────────────────────────
     out.extend_from_slice(&(records.len() as u32).to_le_bytes());
@@ -126,6 +130,9 @@ fn encode_legacy(
                     out.extend_from_slice(&NS_LIST_NIL.to_le_bytes()); // next_in_ns
                     out.extend_from_slice(&NS_LIST_NIL.to_le_bytes()); // prev_in_ns
                 }
+                if schema_ver >= 9 {
+                    out.extend_from_slice(&1u64.to_le_bytes()); // never updated
+                }
             }
         }
     }
@@ -204,6 +211,9 @@ fn encode_legacy(
     if schema_ver >= 7 {
         out.extend_from_slice(&0u32.to_le_bytes());
     }
+    if schema_ver >= 10 {
+        out.extend_from_slice(&0u32.to_le_bytes()); // no sparse vectors
+    }
 
     out
 }
@@ -496,7 +506,7 @@ fn v1_hole_slot_decodes_as_absent_without_shifting_ids() {
 
 #[test]
 fn cross_version_decode_reencode_chain_is_hash_stable() {
-    for schema_ver in 1u32..=7 {
+    for schema_ver in 1u32..=10 {
         let tag_supported = schema_ver >= 3;
         let metadata_supported = schema_ver >= 2;
 
@@ -559,6 +569,6 @@ fn schema_version_zero_is_rejected() {
     let buf = encode_legacy(0, 0, DIM, &scenario_a_records(), &[], &[]);
     assert!(
         decode_state(&buf).is_err(),
-        "schema_ver 0 is out of the valid 1..=7 range"
+        "schema_ver 0 is out of the valid 1..=10 range"
     );
 }
@@ -175,3 +175,142 @@ fn drop_namespace_zero_is_rejected() {
         "the default namespace must never be dropped"
     );
 }
+
+// ── ResizePools ─────────────────────────────────────────────────────────────
+
+#[test]
+fn resize_pools_moves_the_capacity_limit() {
+    let mut state = KernelState::new();
+    assert_eq!(state.capacity(), None);
+    state.apply_event(&insert(0)).unwrap();
+    state.apply_event(&insert(1)).unwrap();
+
+    let resize = |records| KernelEvent::ResizePools {
+        records,
+        nodes: 8,
+        edges: 8,
+    };
+    state.apply_event(&resize(2)).unwrap();
+    assert!(matches!(
+        state.apply_event(&insert(2)),
+        Err(valori_kernel::error::KernelError::CapacityExceeded)
+    ));
+    assert_eq!(state.record_count(), 2);
+
+    state.apply_event(&resize(3)).unwrap();
+    state.apply_event(&insert(2)).unwrap();
+    assert_eq!(state.capacity().map(|c| c.records), Some(3));
+}
+
+#[test]
+fn resize_pools_below_live_count_is_rejected() {
+    let mut state = KernelState::new();
+    for i in 0..3 {
+        state.apply_event(&insert(i)).unwrap();
+    }
+    let shrink = KernelEvent::ResizePools {
+        records: 2,
+        nodes: 8,
+        edges: 8,
+    };
+    assert!(state.apply_event(&shrink).is_err());
+    assert_eq!(state.capacity(), None);
+}
+
+#[test]
+fn vacuum_repacks_records_and_follows_node_links() {
+    use valori_kernel::index::SearchResult;
+    use valori_kernel::snapshot::blake3::hash_state_blake3;
+    use valori_kernel::types::scalar::FxpScalar;
+
+    let tagged = |id: u32| KernelEvent::InsertRecord {
+        id: RecordId(id),
+        vector: FxpVector {
+            data: vec![FxpScalar(id as i32 * 65536); DIM],
+        },
+        metadata: None,
+        tag: 100 + id as u64,
+    };
+    let mut events: Vec<KernelEvent> = (0..6).map(tagged).collect();
+    events.push(KernelEvent::DeleteRecord { id: RecordId(1) });
+    // Soft-deleted with no node: reclaimed. Soft-deleted but linked: kept.
+    events.push(KernelEvent::SoftDeleteRecord { id: RecordId(2) });
+    events.push(KernelEvent::CreateNode {
+        id: NodeId(0),
+        kind: NodeKind::Document,
+        record: Some(RecordId(4)),
+    });
+    events.push(KernelEvent::SoftDeleteRecord { id: RecordId(4) });
+
+    let mut state = KernelState::new();
+    for e in &events {
+        state.apply_event(e).unwrap();
+    }
+    assert_eq!(state.reclaimable_record_slots(), 2);
+    let moves = state.vacuum_moves().unwrap();
+    assert_eq!(
+        moves,
+        vec![
+            (RecordId(3), RecordId(1)),
+            (RecordId(4), RecordId(2)),
+            (RecordId(5), RecordId(3)),
+        ]
+    );
+
+    // The log must carry exactly the mapping the state implies.
+    let wrong = KernelEvent::Vacuum {
+        moves: moves[1..].to_vec(),
+    };
+    let before = hash_state_blake3(&state);
+    assert!(state.apply_event(&wrong).is_err());
+    assert_eq!(hash_state_blake3(&state), before);
+
+    let vacuum = KernelEvent::Vacuum { moves };
+    state.apply_event(&vacuum).unwrap();
+    events.push(vacuum);
+    assert_eq!(state.next_record_id(), RecordId(4));
+    assert_eq!(state.reclaimable_record_slots(), 0);
+    assert_eq!(state.get_record(RecordId(1)).unwrap().tag, 103);
+    assert_eq!(state.get_record(RecordId(3)).unwrap().tag, 105);
+    assert_eq!(state.get_node(NodeId(0)).unwrap().record, Some(RecordId(2)));
+
+    let mut hits = [SearchResult::default(); 1];
+    let query = FxpVector {
+        data: vec![FxpScalar(5 * 65536); DIM],
+    };
+    assert_eq!(state.search_l2(&query, &mut hits, None), 1);
+    assert_eq!(hits[0].id, RecordId(3));
+
+    // New inserts reuse the freed tail, and replay lands on the same state.
+    state.apply_event(&tagged(4)).unwrap();
+    events.push(tagged(4));
+    let mut replayed = KernelState::new();
+    for e in &events {
+        replayed.apply_event(e).unwrap();
+    }
+    assert_eq!(hash_state_blake3(&replayed), hash_state_blake3(&state));
+}
+
+#[test]
+fn omitted_insert_reserves_its_id() {
+    let mut state = KernelState::new();
+    state.apply_event(&insert(0)).unwrap();
+    state
+        .apply_event(&KernelEvent::Omitted {
+            record: Some(RecordId(1)),
+        })
+        .unwrap();
+    // The slot is taken, so the leader's next id still applies.
+    state.apply_event(&insert(2)).unwrap();
+    assert_eq!(state.record_count(), 2);
+    assert!(state.get_record(RecordId(1)).is_none());
+    assert!(state
+        .apply_event(&KernelEvent::Omitted {
+            record: Some(RecordId(7)),
+        })
+        .is_err());
+    state
+        .apply_event(&KernelEvent::Omitted { record: None })
+        .unwrap();
+    assert_eq!(state.next_record_id(), RecordId(3));
+}
@@ -16,6 +16,7 @@ pub enum IndexKind {
     Hnsw,
     Ivf,
     Bq,
+    Sq,
     Auto,
 }
 
@@ -26,6 +27,7 @@ impl std::fmt::Display for IndexKind {
             IndexKind::Hnsw => write!(f, "hnsw"),
             IndexKind::Ivf => write!(f, "ivf"),
             IndexKind::Bq => write!(f, "bq"),
+            IndexKind::Sq => write!(f, "sq"),
             IndexKind::Auto => write!(f, "auto"),
         }
     }
@@ -39,6 +41,7 @@ impl std::str::FromStr for IndexKind {
             "hnsw" => Ok(IndexKind::Hnsw),
             "ivf" => Ok(IndexKind::Ivf),
             "bq" => Ok(IndexKind::Bq),
+            "sq" => Ok(IndexKind::Sq),
             "auto" | "mstg" => Ok(IndexKind::Auto),
             other => Err(format!("Unknown index kind: {}", other)),
         }
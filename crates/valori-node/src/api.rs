@@ -1,5 +1,6 @@
 // Copyright (c) 2025 Varshith Gudur. Dual-licensed under MIT OR Apache-2.0.
 use serde::{Deserialize, Serialize};
+pub use valori_kernel::index::TagFilter;
 
 // Note: We need to make sure valori-kernel exports NodeKind/EdgeKind publicly or we redefine/wrap them.
 // Since valori-kernel is a dependency, we can use its types if they are pub.
@@ -30,7 +31,7 @@ pub fn validate_collection(collection: Option<&str>) -> Result<(), crate::errors
     }
 }
 
-#[derive(Deserialize)]
+#[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct InsertRecordRequest {
     pub values: Vec<f32>,
     #[serde(default)]
@@ -40,9 +41,22 @@ pub struct InsertRecordRequest {
     /// use term-frequency scoring to reorder results.
     #[serde(default)]
     pub text: Option<String>,
+    /// Commit only if the state version is still this one (optimistic
+    /// concurrency); otherwise 412 and nothing is written. Standalone only.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub if_version: Option<u64>,
+    /// Record tag, searchable with `tag_filter`. Set bits to give a record
+    /// several labels (`{"any": mask}` / `{"all": mask}`). Default 0.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub tag: Option<u64>,
+    /// What to do when the collection already has an active record with
+    /// exactly this vector: `reject` (409), `return_existing` or `link`.
+    /// Unset inserts without checking. Standalone only.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub on_duplicate: Option<crate::engine::OnDuplicate>,
 }
 
-#[derive(Serialize)]
+#[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct InsertReceiptJson {
     pub record_id: u32,
     pub old_root: String,
@@ -68,28 +82,262 @@ impl From<valori_kernel::proof::InsertReceipt> for InsertReceiptJson {
     }
 }
 
-#[derive(Serialize)]
+#[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct InsertRecordResponse {
     pub id: u32,
     pub receipt: InsertReceiptJson,
+    /// State version after the write — pass it as the next `if_version`.
+    /// Standalone only.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub state_version: Option<u64>,
+    /// Record with the same vector, when `on_duplicate` found one. Under
+    /// `return_existing` it is also `id`.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub duplicate_of: Option<u32>,
 }
 
-#[derive(Deserialize)]
+#[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct DeleteRecordRequest {
     pub id: u32,
     #[serde(default)]
     pub collection: Option<String>,
+    /// Commit only if the state version is still this one (optimistic
+    /// concurrency); otherwise 412 and nothing is written. Standalone only.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub if_version: Option<u64>,
 }
 
-#[derive(Serialize)]
+#[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct DeleteRecordResponse {
     pub success: bool,
     /// Raft log index of the committed write — cluster path only.
     #[serde(skip_serializing_if = "Option::is_none")]
     pub log_index: Option<u64>,
+    /// State version after the write — pass it as the next `if_version`.
+    /// Standalone only.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub state_version: Option<u64>,
 }
 
-#[derive(Deserialize)]
+/// Most ids one `POST /v1/records/delete_batch` accepts.
+pub const MAX_DELETE_BATCH: usize = 100_000;
+
+/// `POST /v1/records/delete_batch` — every id is deleted in one committed
+/// event batch.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(deny_unknown_fields)]
+pub struct DeleteBatchRequest {
+    pub ids: Vec<u32>,
+    #[serde(default)]
+    pub collection: Option<String>,
+    /// Tombstone instead of hard delete, as `POST /v1/soft-delete`.
+    #[serde(default)]
+    pub soft: bool,
+    /// Commit only if the state version is still this one (optimistic
+    /// concurrency); otherwise 412 and nothing is written. Standalone only.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub if_version: Option<u64>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct DeleteBatchResponse {
+    pub deleted: Vec<u32>,
+    /// Ids that were not an active record of the collection; skipped.
+    pub missing: Vec<u32>,
+    /// State version after the write — pass it as the next `if_version`.
+    /// Standalone only.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub state_version: Option<u64>,
+}
+
+/// Most operations one `POST /v1/transactions` accepts.
+pub const MAX_TRANSACTION_OPS: usize = 10_000;
+
+/// An id in a transaction: an existing one, or `{"result_of": i}` for the
+/// record, node or edge created by operation `i` of the same transaction.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(untagged)]
+pub enum TransactionRef {
+    Id(u32),
+    ResultOf { result_of: usize },
+}
+
+/// One operation of a `POST /v1/transactions` request, tagged by `op`.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(tag = "op", rename_all = "snake_case", deny_unknown_fields)]
+pub enum TransactionOp {
+    Insert {
+        values: Vec<f32>,
+        /// Stored as the record's metadata bytes (serialized JSON).
+        #[serde(default, skip_serializing_if = "Option::is_none")]
+        metadata: Option<serde_json::Value>,
+        #[serde(default)]
+        tag: u64,
+    },
+    CreateNode {
+        kind: u8,
+        #[serde(default, skip_serializing_if = "Option::is_none")]
+        record: Option<TransactionRef>,
+    },
+    CreateEdge {
+        from: TransactionRef,
+        to: TransactionRef,
+        kind: u8,
+    },
+    /// As `PATCH /v1/records/:id/metadata`.
+    UpdateMetadata {
+        id: TransactionRef,
+        metadata: serde_json::Value,
+        #[serde(default, skip_serializing_if = "Option::is_none")]
+        expected_version: Option<u64>,
+    },
+    /// As `POST /v1/delete` (or `/v1/soft-delete` with `soft`): the
+    /// record's graph node goes with it.
+    Delete {
+        id: TransactionRef,
+        #[serde(default)]
+        soft: bool,
+    },
+    DeleteNode {
+        id: TransactionRef,
+    },
+    DeleteEdge {
+        id: TransactionRef,
+    },
+    /// As `POST /v1/memory/meta/set`.
+    SetMeta {
+        key: String,
+        value: serde_json::Value,
+    },
+}
+
+/// `POST /v1/transactions` — the operations are committed as one event
+/// batch, or not at all.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(deny_unknown_fields)]
+pub struct TransactionRequest {
+    pub operations: Vec<TransactionOp>,
+    #[serde(default)]
+    pub collection: Option<String>,
+    /// Commit only if the state version is still this one (optimistic
+    /// concurrency); otherwise 412 and nothing is written.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub if_version: Option<u64>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct TransactionResponse {
+    /// Per operation, the id it created or acted on (`null` for `set_meta`).
+    pub results: Vec<Option<u32>>,
+    /// State version after the write — pass it as the next `if_version`.
+    pub state_version: u64,
+    /// Event-log height after the write: everything it committed is durable
+    /// up to here. Absent without an event log.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub height: Option<u64>,
+}
+
+/// `POST /v1/tx/stage` — stage writes in a transaction opened with
+/// `POST /v1/tx/begin` (which takes a [`SessionOpenRequest`]).
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(deny_unknown_fields)]
+pub struct TxStageRequest {
+    pub tx_id: String,
+    pub operations: Vec<TransactionOp>,
+}
+
+/// `POST /v1/tx/commit`
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(deny_unknown_fields)]
+pub struct TxCommitRequest {
+    pub tx_id: String,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub if_version: Option<u64>,
+}
+
+/// `POST /v1/tx/abort`
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(deny_unknown_fields)]
+pub struct TxAbortRequest {
+    pub tx_id: String,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct TxBeginResponse {
+    pub tx_id: String,
+    pub collection: Option<String>,
+}
+
+/// `POST /v1/sessions` — open a write session on one collection.
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+#[serde(deny_unknown_fields)]
+pub struct SessionOpenRequest {
+    #[serde(default)]
+    pub collection: Option<String>,
+}
+
+/// `POST /v1/sessions/:id/operations` — buffer operations in the session.
+/// `result_of` indexes count from the start of the session's buffer.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(deny_unknown_fields)]
+pub struct SessionOperationsRequest {
+    pub operations: Vec<TransactionOp>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct SessionOperationsResponse {
+    /// Per appended operation, the id it would get if the session committed
+    /// now. Other writers can shift ids before the commit.
+    pub results: Vec<Option<u32>>,
+    /// Operations buffered in the session.
+    pub operations: usize,
+}
+
+/// `POST /v1/sessions/:id/savepoints`
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(deny_unknown_fields)]
+pub struct SavepointRequest {
+    pub name: String,
+}
+
+/// `POST /v1/sessions/:id/rollback` — to `savepoint`, or the whole buffer.
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+#[serde(deny_unknown_fields)]
+pub struct SessionRollbackRequest {
+    #[serde(default)]
+    pub savepoint: Option<String>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct SessionRollbackResponse {
+    pub discarded: usize,
+    pub operations: usize,
+}
+
+/// `POST /v1/sessions/:id/commit`
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+#[serde(deny_unknown_fields)]
+pub struct SessionCommitRequest {
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub if_version: Option<u64>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct SessionSavepoint {
+    pub name: String,
+    /// Operations the savepoint keeps on rollback.
+    pub operations: usize,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct SessionResponse {
+    pub session_id: String,
+    pub collection: Option<String>,
+    pub operations: usize,
+    pub savepoints: Vec<SessionSavepoint>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct SearchRequest {
     pub query: Vec<f32>,
     pub k: usize,
@@ -126,16 +374,82 @@ pub struct SearchRequest {
     /// Example: `{"author": "Alice", "year": {"gte": 2020}}`
     #[serde(default)]
     pub metadata_filter: Option<serde_json::Map<String, serde_json::Value>>,
+    /// Restrict results to records whose tag matches: `{"exact": 5}`, or a
+    /// bitmask with `{"any": 6}` (shares a bit) / `{"all": 6}` (has every bit).
+    #[serde(default)]
+    pub tag_filter: Option<TagFilter>,
+    /// Shorthand for `tag_filter: {"exact": t}`, as the Python client sends
+    /// it. `tag_filter` wins when both are set.
+    #[serde(default)]
+    pub filter_tag: Option<u64>,
+    /// Index to search: the primary index or one listed in
+    /// `VALORI_EXTRA_INDEXES` (`brute_force`, `hnsw`, `ivf`, `bq`). Absent =
+    /// primary. Ignored for `as_of` queries, which always scan exactly.
+    #[serde(default)]
+    pub index: Option<String>,
+    /// Give up with `504` after this many milliseconds, counted from arrival
+    /// and including the wait for the engine. Absent = the node default
+    /// (`VALORI_REQUEST_TIMEOUT_MS`); `0` = no deadline.
+    #[serde(default)]
+    pub timeout_ms: Option<u64>,
 }
 
 fn default_rerank() -> bool {
     true
 }
 
+impl SearchRequest {
+    /// Top-`k` vector search in the default collection with every option at
+    /// its server default.
+    pub fn new(query: Vec<f32>, k: usize) -> Self {
+        Self {
+            query,
+            k,
+            collection: None,
+            as_of: None,
+            as_of_log_index: None,
+            decay_half_life_secs: None,
+            rerank: default_rerank(),
+            query_text: None,
+            metadata_filter: None,
+            tag_filter: None,
+            filter_tag: None,
+            index: None,
+            timeout_ms: None,
+        }
+    }
+    /// The tag restriction this request asks for, if any.
+    pub fn effective_tag_filter(&self) -> Option<TagFilter> {
+        self.tag_filter.or(self.filter_tag.map(TagFilter::Exact))
+    }
+}
+
+/// Body of `PUT /v1/records/:id/sparse`: the record's sparse vector as
+/// parallel index and weight lists, in any order. Zero weights are dropped;
+/// empty lists remove the vector.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct SparseVectorRequest {
+    pub indices: Vec<u32>,
+    pub values: Vec<f32>,
+}
+
+/// Body of `POST /v1/sparse/search`: a sparse query, scored against each
+/// record's sparse vector by dot product. Hits come back highest first with
+/// `score` the dot product; records sharing no index with the query are
+/// not returned.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct SparseSearchRequest {
+    pub indices: Vec<u32>,
+    pub values: Vec<f32>,
+    pub k: usize,
+    #[serde(default)]
+    pub collection: Option<String>,
+}
+
 // Metadata predicate matching now lives in valori-search.
 pub use valori_search::matches_metadata_filter;
 
-#[derive(Serialize)]
+#[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct SearchHit {
     pub id: u32,
     pub score: f32,
@@ -150,7 +464,7 @@ pub struct SearchHit {
     pub age_secs: Option<u64>,
 }
 
-#[derive(Serialize)]
+#[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct SearchResponse {
     pub results: Vec<SearchHit>,
     /// Present only for as-of searches: the log index of the replayed state.
@@ -202,6 +516,13 @@ pub struct TimelineEntry {
     /// Edge ID if this is a graph-edge event.
     #[serde(skip_serializing_if = "Option::is_none")]
     pub edge_id: Option<u32>,
+    /// Insert-time anomaly score (distance to the k-NN centroid) for the
+    /// insert that produced the live record. Requires `VALORI_ANOMALY_K`.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub anomaly_score: Option<f32>,
+    /// `anomaly_score` exceeds `VALORI_ANOMALY_THRESHOLD`.
+    #[serde(skip_serializing_if = "std::ops::Not::not")]
+    pub outlier: bool,
 }
 
 #[derive(Serialize)]
@@ -216,6 +537,46 @@ pub struct TimelineResponse {
     pub to_unix: Option<u64>,
 }
 
+/// `GET /v1/events` — one page of decoded log events.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct EventsResponse {
+    /// `{log_index, timestamp_unix, timestamp_iso, type, ...fields}` per event.
+    pub events: Vec<serde_json::Value>,
+    /// Pass as `from` to fetch the next page; absent on the last page.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub next: Option<u64>,
+    /// Committed events in the log.
+    pub height: u64,
+}
+
+/// Hex BLAKE3 hash of each state section (`valori_kernel::state::diff`).
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq, Eq)]
+pub struct SectionHashesHex {
+    pub records: String,
+    pub nodes: String,
+    pub edges: String,
+}
+
+/// `GET /v1/diff?from=&to=` — structural delta between two committed heights.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct DiffResponse {
+    pub from: u64,
+    pub to: u64,
+    pub from_state_hash: String,
+    pub to_state_hash: String,
+    pub from_sections: SectionHashesHex,
+    pub to_sections: SectionHashesHex,
+    pub records_added: Vec<u32>,
+    pub records_removed: Vec<u32>,
+    pub records_changed: Vec<valori_kernel::state::diff::RecordChange>,
+    pub nodes_added: Vec<valori_kernel::state::diff::NodeDelta>,
+    pub nodes_removed: Vec<valori_kernel::state::diff::NodeDelta>,
+    pub nodes_changed: Vec<valori_kernel::state::diff::NodeDelta>,
+    pub edges_added: Vec<valori_kernel::state::diff::EdgeDelta>,
+    pub edges_removed: Vec<valori_kernel::state::diff::EdgeDelta>,
+    pub edges_changed: Vec<valori_kernel::state::diff::EdgeDelta>,
+}
+
 #[derive(Clone, Debug, Serialize, Deserialize)]
 pub struct OperationSummary {
     pub id: String,
@@ -249,7 +610,7 @@ pub struct OperationDetailResponse {
     pub metrics: serde_json::Value,
 }
 
-#[derive(Deserialize)]
+#[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct CreateNodeRequest {
     pub record_id: Option<u32>,
     // NodeKind needs to be deserializable.
@@ -271,7 +632,7 @@ pub struct CreateNodeRequest {
     pub collection: Option<String>,
 }
 
-#[derive(Serialize)]
+#[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct CreateNodeResponse {
     pub node_id: u32,
     /// Raft log index of the committed write — cluster path only.
@@ -279,7 +640,7 @@ pub struct CreateNodeResponse {
     pub log_index: Option<u64>,
 }
 
-#[derive(Deserialize)]
+#[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct CreateEdgeRequest {
     pub from: u32,
     pub to: u32,
@@ -288,7 +649,7 @@ pub struct CreateEdgeRequest {
     pub collection: Option<String>,
 }
 
-#[derive(Serialize)]
+#[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct CreateEdgeResponse {
     pub edge_id: u32,
     /// Raft log index of the committed write — cluster path only.
@@ -296,21 +657,21 @@ pub struct CreateEdgeResponse {
     pub log_index: Option<u64>,
 }
 
-#[derive(Serialize)]
+#[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct GetNodeResponse {
     pub kind: u8,
     pub record_id: Option<u32>,
     pub namespace_id: u16,
 }
 
-#[derive(Serialize)]
+#[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct EdgeData {
     pub edge_id: u32,
     pub to_node: u32,
     pub kind: u8,
 }
 
-#[derive(Serialize)]
+#[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct GetEdgesResponse {
     pub edges: Vec<EdgeData>,
 }
@@ -426,6 +787,44 @@ pub struct MetadataGetRequest {
     pub target_id: String,
 }
 
+#[derive(Deserialize, Serialize, Debug)]
+pub struct MetadataDeleteRequest {
+    pub target_id: String,
+}
+
+#[derive(Serialize, Debug)]
+pub struct MetadataDeleteResponse {
+    pub success: bool,
+}
+
+/// Default and maximum page size of `GET /v1/records?where=`.
+pub const RECORDS_WHERE_DEFAULT: usize = 100;
+pub const RECORDS_WHERE_MAX: usize = 10_000;
+
+/// Query of `GET /v1/records?where=<json>`.
+#[derive(Deserialize, Debug)]
+pub struct RecordsWhereQuery {
+    /// A metadata filter as JSON, in the search `metadata_filter` syntax.
+    #[serde(rename = "where")]
+    pub filter: String,
+    #[serde(default)]
+    pub collection: Option<String>,
+    pub limit: Option<usize>,
+}
+
+impl RecordsWhereQuery {
+    /// The parsed filter and the clamped page size.
+    pub fn parse(&self) -> Result<(serde_json::Map<String, serde_json::Value>, usize), String> {
+        let filter = serde_json::from_str(&self.filter)
+            .map_err(|e| format!("where must be a JSON object: {e}"))?;
+        let limit = self
+            .limit
+            .unwrap_or(RECORDS_WHERE_DEFAULT)
+            .clamp(1, RECORDS_WHERE_MAX);
+        Ok((filter, limit))
+    }
+}
+
 #[derive(Serialize, Debug)]
 pub struct MetadataGetResponse {
     pub target_id: String,
@@ -436,27 +835,61 @@ pub struct MetadataGetResponse {
 pub struct SnapshotSaveRequest {
     // Optional path override. If None, uses configured snapshot path.
     pub path: Option<String>,
+    /// Save into the snapshot catalog under this label instead of
+    /// overwriting a path. Cannot be combined with `path`.
+    #[serde(default)]
+    pub label: Option<String>,
 }
 
-#[derive(Serialize, Debug)]
+#[derive(Serialize, Deserialize, Debug)]
 pub struct SnapshotSaveResponse {
     pub success: bool,
     pub path: String,
+    /// Catalog id of a labelled snapshot.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub id: Option<u64>,
 }
 
 #[derive(Deserialize, Serialize, Debug)]
 pub struct SnapshotRestoreRequest {
     // Path to load from.
-    pub path: String,
+    #[serde(default)]
+    pub path: Option<String>,
+    /// Catalog id to restore instead of a path.
+    #[serde(default)]
+    pub id: Option<u64>,
 }
 
-#[derive(Serialize, Debug)]
+/// `POST /v1/snapshot/upload/init` body.
+#[derive(Deserialize, Serialize, Debug)]
+pub struct SnapshotUploadInitRequest {
+    /// Size of the whole snapshot in bytes.
+    pub size: u64,
+    /// BLAKE3 hex of the whole snapshot.
+    pub hash: String,
+}
+
+/// `PUT /v1/snapshot/upload/:id` query: where the chunk starts and its
+/// BLAKE3 hex.
+#[derive(Deserialize, Serialize, Debug)]
+pub struct SnapshotUploadChunkParams {
+    pub offset: u64,
+    pub hash: String,
+}
+
+/// `GET /v1/snapshot/list` response.
+#[derive(Serialize, Deserialize, Debug)]
+pub struct SnapshotListResponse {
+    pub snapshots: Vec<crate::persistence::SnapshotEntry>,
+}
+
+#[derive(Serialize, Deserialize, Debug)]
 pub struct SnapshotRestoreResponse {
     pub success: bool,
 }
 
 // Phase 26: Event log proof API
-#[derive(Serialize, Debug)]
+#[derive(Serialize, Deserialize, Debug)]
 pub struct EventProofResponse {
     pub kernel_version: u32,
     pub event_log_hash: String,        // hex-encoded BLAKE3
@@ -464,6 +897,33 @@ pub struct EventProofResponse {
     pub snapshot_hash: Option<String>, // hex-encoded BLAKE3 (if snapshot exists)
     pub event_count: u64,
     pub committed_height: u64,
+    /// Present when a corrupt event-log tail was quarantined: the log (and
+    /// so this proof) ends at `truncated.recovered_height`.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub truncated: Option<valori_storage::events::DamageReport>,
+}
+
+/// `GET /v1/proof/state` response.
+#[derive(Serialize, Deserialize, Debug, Clone)]
+pub struct StateProofResponse {
+    pub final_state_hash: String, // hex-encoded BLAKE3
+    /// Merkle root of the state (hex), maintained per commit — see
+    /// `valori_kernel::state::merkle`.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub state_root: Option<String>,
+    /// Kernel version both hashes were taken at.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub version: Option<u64>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub truncated: Option<valori_storage::events::DamageReport>,
+}
+
+/// `GET /v1/replication/state` response.
+#[derive(Serialize, Deserialize, Debug, Clone)]
+pub struct ReplicationStateResponse {
+    /// `Synced`, `Diverged`, `Healing` or `Unknown` — see
+    /// [`crate::replication::replication_display_state`].
+    pub status: String,
 }
 
 // Phase 34: Batch Ingestion
@@ -490,11 +950,28 @@ pub struct BatchInsertRequest {
     /// so that future /search calls with `rerank=true` can re-score results.
     #[serde(default)]
     pub texts: Option<Vec<Option<String>>>,
+    /// Commit only if the state version is still this one (optimistic
+    /// concurrency); otherwise 412 and nothing is written. Standalone only.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub if_version: Option<u64>,
+    /// Duplicate check per item, against the collection and earlier items
+    /// of the batch, as on `/records`. Under `reject` one duplicate refuses
+    /// the whole batch. Standalone only.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub on_duplicate: Option<crate::engine::OnDuplicate>,
 }
 
 #[derive(Serialize, Deserialize, Debug)]
 pub struct BatchInsertResponse {
     pub ids: Vec<u32>,
+    /// State version after the write — pass it as the next `if_version`.
+    /// Standalone only.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub state_version: Option<u64>,
+    /// Per item, the record with the same vector; present when the request
+    /// set `on_duplicate`.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub duplicate_of: Option<Vec<Option<u32>>>,
 }
 
 // ── Collection (namespace) management ────────────────────────────────────────
@@ -504,7 +981,7 @@ pub struct CreateCollectionRequest {
     pub name: String,
 }
 
-#[derive(Serialize, Debug)]
+#[derive(Serialize, Deserialize, Debug)]
 pub struct CollectionInfo {
     pub name: String,
     pub id: u16,
@@ -517,11 +994,89 @@ pub struct CreateCollectionResponse {
     pub created: bool,
 }
 
-#[derive(Serialize, Debug)]
+#[derive(Serialize, Deserialize, Debug)]
 pub struct ListCollectionsResponse {
     pub collections: Vec<CollectionInfo>,
 }
 
+// ── Configured collections (`/v1/collections`) ──────────────────────────────
+
+/// `POST /v1/collections` — a collection with its own engine. Unset fields
+/// take the node's `VALORI_MAX_RECORDS`, `VALORI_INDEX` and `VALORI_QUANT`.
+#[derive(Deserialize, Debug)]
+pub struct CreateConfiguredCollectionRequest {
+    pub name: String,
+    pub dim: usize,
+    #[serde(default)]
+    pub max_records: Option<usize>,
+    /// `brute`, `hnsw`, `ivf`, `bq`, `sq` or `auto`.
+    #[serde(default)]
+    pub index: Option<String>,
+    /// `none`, `scalar` or `product`.
+    #[serde(default)]
+    pub quantization: Option<String>,
+}
+
+#[derive(Serialize, Deserialize, Debug)]
+pub struct ConfiguredCollectionInfo {
+    pub name: String,
+    pub dim: usize,
+    pub max_records: usize,
+    pub index: String,
+    pub quantization: String,
+    pub records: usize,
+}
+
+#[derive(Serialize, Deserialize, Debug)]
+pub struct ListConfiguredCollectionsResponse {
+    pub collections: Vec<ConfiguredCollectionInfo>,
+}
+
+/// `POST /v1/collections/:name/records`.
+#[derive(Deserialize, Debug)]
+pub struct CollectionInsertRequest {
+    pub values: Vec<f32>,
+    /// Stored on the record as JSON and returned by `GET .../records/:id`.
+    #[serde(default)]
+    pub metadata: Option<serde_json::Value>,
+    #[serde(default)]
+    pub tag: Option<u64>,
+}
+
+#[derive(Serialize, Deserialize, Debug)]
+pub struct CollectionInsertResponse {
+    pub id: u32,
+}
+
+/// `POST /v1/collections/:name/search`.
+#[derive(Deserialize, Debug)]
+pub struct CollectionSearchRequest {
+    pub query: Vec<f32>,
+    pub k: usize,
+}
+
+/// `GET /v1/collections/:name/proof`, the `POST .../snapshot` response and
+/// one entry of `GET /v1/proof/collections`. Hashes are hex.
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq, Eq)]
+pub struct CollectionProofResponse {
+    pub name: String,
+    pub kernel_version: u64,
+    pub final_state_hash: String,
+    pub state_root: String,
+    /// Kernel version both hashes were taken at.
+    pub version: u64,
+    /// This collection's leaf under the aggregate root.
+    pub leaf: String,
+}
+
+/// `GET /v1/proof/collections`.
+#[derive(Serialize, Deserialize, Debug)]
+pub struct CollectionsRootProofResponse {
+    /// Merkle root over every collection's leaf, in name order.
+    pub root: String,
+    pub collections: Vec<CollectionProofResponse>,
+}
+
 // ── C4.2: Memory consolidation ───────────────────────────────────────────────
 
 /// Replace an existing memory record with a new vector, committing a
@@ -582,3 +1137,48 @@ pub struct MemoryContradictResponse {
     #[serde(skip_serializing_if = "Option::is_none")]
     pub log_index: Option<u64>,
 }
+
+// ── Near-duplicate check ─────────────────────────────────────────────────────
+
+/// Most matches one `POST /v1/memory/dedup_check` returns.
+pub const MAX_DEDUP_MATCHES: usize = 1000;
+
+/// Records within `max_distance` of a candidate vector, looked up before it
+/// is inserted so an ingestion pipeline can skip near-identical chunks.
+/// Nothing is written.
+#[derive(Deserialize)]
+pub struct MemoryDedupCheckRequest {
+    pub vector: Vec<f32>,
+    /// Squared L2 distance, the unit of search scores. `0` matches exact
+    /// copies only.
+    pub max_distance: f32,
+    /// Most matches to return, nearest first (default 10, at most
+    /// [`MAX_DEDUP_MATCHES`]).
+    #[serde(default = "default_dedup_limit")]
+    pub limit: usize,
+    #[serde(default)]
+    pub collection: Option<String>,
+    /// Cluster mode only, as on `/v1/memory/search`: `"local"` skips the
+    /// read-index round trip.
+    #[serde(default)]
+    pub consistency: Option<String>,
+}
+
+fn default_dedup_limit() -> usize {
+    10
+}
+
+#[derive(Serialize, Deserialize)]
+pub struct MemoryDedupMatch {
+    pub memory_id: String,
+    pub record_id: u32,
+    pub distance: f32,
+    pub metadata: Option<serde_json::Value>,
+}
+
+#[derive(Serialize, Deserialize)]
+pub struct MemoryDedupCheckResponse {
+    /// Whether any record is within `max_distance`.
+    pub duplicate: bool,
+    pub matches: Vec<MemoryDedupMatch>,
+}
@@ -0,0 +1,415 @@
+// Copyright (c) 2025 Varshith Gudur. Dual-licensed under MIT OR Apache-2.0.
+//! Append-only audit trail of API operations.
+//!
+//! Separate from the kernel event log: the event log records *what changed*
+//! (and is hash-chained for replay), this trail records *who asked* — the
+//! authenticated principal, the route, the record ids the request touched and
+//! the event-log height the node stood at afterwards. Denied requests (401/403)
+//! are recorded too, with no principal.
+//!
+//! Storage is JSON Lines at `VALORI_API_AUDIT_PATH` (flushed per entry, not
+//! fsynced — the trail must never slow down or fail the request it observes).
+//! Without a path the last [`MEMORY_CAPACITY`] entries are kept in memory.
+//! Queried by `GET /v1/audit` (admin) and exported by `valori audit export`.
+//!
+//! A file-backed query reads the file on the blocking pool, starting at the
+//! nearest indexed offset before `since` and stopping at the length last
+//! appended, so it neither blocks appends nor reads a half-written line.
+
+use axum::body::{Body, Bytes, HttpBody};
+use axum::extract::Request;
+use axum::http::Method;
+use axum::http::StatusCode;
+use axum::middleware::Next;
+use axum::response::{IntoResponse, Response};
+use serde::{Deserialize, Serialize};
+use std::collections::VecDeque;
+use std::fs::{File, OpenOptions};
+use std::io::{BufRead, BufReader, Read, Seek, SeekFrom, Write};
+use std::path::PathBuf;
+use std::sync::Mutex;
+use std::time::{SystemTime, UNIX_EPOCH};
+
+/// Entries retained when the trail has no backing file.
+pub const MEMORY_CAPACITY: usize = 10_000;
+
+/// Request / response bodies larger than this are not inspected for ids.
+const MAX_INSPECT_BYTES: u64 = 64 * 1024;
+
+/// Default and maximum page size for [`ApiAuditLog::query`].
+const DEFAULT_PAGE: usize = 100;
+const MAX_PAGE: usize = 1_000;
+
+/// A file-backed trail remembers the offset of every this-many-th entry, so
+/// a `since` query skips the lines before it.
+const INDEX_STRIDE: u64 = 256;
+
+/// Identity the auth middleware resolved for a request. Attached to the
+/// response extensions so the audit layer (which wraps the auth guard) can
+/// attribute the operation.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct AuthPrincipal(pub String);
+
+/// Tag `resp` with the principal that was authorized to produce it.
+pub fn with_principal(mut resp: Response, principal: impl Into<String>) -> Response {
+    resp.extensions_mut()
+        .insert(AuthPrincipal(principal.into()));
+    resp
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+pub struct ApiAuditEntry {
+    /// Monotonic sequence number, 1-based; survives restarts when file-backed.
+    pub seq: u64,
+    pub ts_ms: u64,
+    /// `key:<id>`, `role:<role>`, `legacy`, `anonymous`, or `None` when denied.
+    pub principal: Option<String>,
+    pub method: String,
+    pub route: String,
+    pub status: u16,
+    /// Record / node ids named in the path, request body or response body.
+    pub ids: Vec<u64>,
+    /// Committed event-log height after a mutating request (standalone only).
+    pub event_height: Option<u64>,
+}
+
+/// Filters for `GET /v1/audit`.
+#[derive(Debug, Default, Clone, Deserialize)]
+pub struct AuditQuery {
+    /// Return entries with `seq > since`.
+    pub since: Option<u64>,
+    pub limit: Option<usize>,
+    pub principal: Option<String>,
+    /// Route prefix, e.g. `/v1/records`.
+    pub route: Option<String>,
+}
+
+struct Inner {
+    next_seq: u64,
+    file: Option<File>,
+    /// Bytes of the file holding whole entries.
+    len: u64,
+    /// `(seq, offset)` of every [`INDEX_STRIDE`]-th entry in the file.
+    index: Vec<(u64, u64)>,
+    memory: VecDeque<ApiAuditEntry>,
+}
+
+impl Inner {
+    fn note_offset(&mut self, seq: u64, offset: u64) {
+        if seq % INDEX_STRIDE == 1 {
+            self.index.push((seq, offset));
+        }
+    }
+}
+
+pub struct ApiAuditLog {
+    path: Option<PathBuf>,
+    inner: Mutex<Inner>,
+}
+
+impl ApiAuditLog {
+    /// In-memory trail (bounded to [`MEMORY_CAPACITY`]).
+    pub fn in_memory() -> Self {
+        Self {
+            path: None,
+            inner: Mutex::new(Inner {
+                next_seq: 1,
+                file: None,
+                len: 0,
+                index: Vec::new(),
+                memory: VecDeque::new(),
+            }),
+        }
+    }
+
+    /// Open (or create) a file-backed trail. Sequence numbering resumes after
+    /// the last well-formed line already in the file.
+    pub fn open(path: PathBuf) -> std::io::Result<Self> {
+        let mut inner = Inner {
+            next_seq: 1,
+            file: None,
+            len: 0,
+            index: Vec::new(),
+            memory: VecDeque::new(),
+        };
+        let mut last_seq = 0;
+        if let Ok(f) = File::open(&path) {
+            let mut reader = BufReader::new(f);
+            let mut line = String::new();
+            let mut offset = 0;
+            loop {
+                line.clear();
+                let n = reader.read_line(&mut line)?;
+                if n == 0 {
+                    break;
+                }
+                if let Ok(e) = serde_json::from_str::<ApiAuditEntry>(&line) {
+                    if e.seq > last_seq {
+                        inner.note_offset(e.seq, offset);
+                        last_seq = e.seq;
+                    }
+                }
+                offset += n as u64;
+            }
+        }
+        let file = OpenOptions::new().create(true).append(true).open(&path)?;
+        inner.len = file.metadata()?.len();
+        inner.next_seq = last_seq + 1;
+        inner.file = Some(file);
+        Ok(Self {
+            path: Some(path),
+            inner: Mutex::new(inner),
+        })
+    }
+
+    /// File-backed when `path` is set, in-memory otherwise. A file that cannot
+    /// be opened degrades to in-memory with an error log rather than refusing
+    /// to serve.
+    pub fn from_path(path: Option<PathBuf>) -> Self {
+        match path {
+            Some(p) => Self::open(p.clone()).unwrap_or_else(|e| {
+                tracing::error!(
+                    "api audit log {:?} unavailable ({e}); keeping it in memory",
+                    p
+                );
+                Self::in_memory()
+            }),
+            None => Self::in_memory(),
+        }
+    }
+
+    /// Append one entry; assigns `seq` and `ts_ms`.
+    pub fn record(
+        &self,
+        principal: Option<String>,
+        method: &Method,
+        route: &str,
+        status: u16,
+        ids: Vec<u64>,
+        event_height: Option<u64>,
+    ) -> ApiAuditEntry {
+        let mut inner = self.inner.lock().expect("api audit mutex");
+        let entry = ApiAuditEntry {
+            seq: inner.next_seq,
+            ts_ms: SystemTime::now()
+                .duration_since(UNIX_EPOCH)
+                .unwrap_or_default()
+                .as_millis() as u64,
+            principal,
+            method: method.to_string(),
+            route: route.to_string(),
+            status,
+            ids,
+            event_height,
+        };
+        inner.next_seq += 1;
+        let offset = inner.len;
+        match inner.file.as_mut() {
+            Some(f) => {
+                let mut line = serde_json::to_vec(&entry).expect("audit entry serializes");
+                line.push(b'\n');
+                match f.write_all(&line).and_then(|_| f.flush()) {
+                    Ok(()) => {
+                        inner.len += line.len() as u64;
+                        inner.note_offset(entry.seq, offset);
+                    }
+                    Err(e) => {
+                        tracing::error!("api audit append failed: {e}");
+                        // A partial line may have landed; later entries start
+                        // after it.
+                        if let Ok(m) = f.metadata() {
+                            inner.len = m.len();
+                        }
+                    }
+                }
+            }
+            None => {
+                if inner.memory.len() == MEMORY_CAPACITY {
+                    inner.memory.pop_front();
+                }
+                inner.memory.push_back(entry.clone());
+            }
+        }
+        entry
+    }
+
+    /// Entries matching `q`, oldest first, at most `limit` (default 100, max 1000).
+    pub async fn query(&self, q: &AuditQuery) -> Vec<ApiAuditEntry> {
+        let limit = q.limit.unwrap_or(DEFAULT_PAGE).clamp(1, MAX_PAGE);
+        let since = q.since.unwrap_or(0);
+        let keep = move |q: &AuditQuery, e: &ApiAuditEntry| {
+            e.seq > since
+                && q.principal
+                    .as_ref()
+                    .map_or(true, |p| e.principal.as_deref() == Some(p.as_str()))
+                && q.route.as_ref().map_or(true, |r| e.route.starts_with(r))
+        };
+        match &self.path {
+            Some(path) => {
+                let (start, end) = {
+                    let inner = self.inner.lock().expect("api audit mutex");
+                    let i = inner.index.partition_point(|&(seq, _)| seq <= since + 1);
+                    let start = i.checked_sub(1).map_or(0, |i| inner.index[i].1);
+                    (start, inner.len)
+                };
+                let (path, q) = (path.clone(), q.clone());
+                let read = tokio::task::spawn_blocking(move || {
+                    let mut f = File::open(&path)?;
+                    f.seek(SeekFrom::Start(start))?;
+                    Ok::<_, std::io::Error>(
+                        BufReader::new(f.take(end.saturating_sub(start)))
+                            .lines()
+                            .map_while(Result::ok)
+                            .filter_map(|l| serde_json::from_str::<ApiAuditEntry>(&l).ok())
+                            .filter(|e| keep(&q, e))
+                            .take(limit)
+                            .collect(),
+                    )
+                });
+                match read.await {
+                    Ok(Ok(entries)) => entries,
+                    Ok(Err(e)) => {
+                        tracing::error!("api audit read failed: {e}");
+                        Vec::new()
+                    }
+                    Err(e) => {
+                        tracing::error!("api audit read task failed: {e}");
+                        Vec::new()
+                    }
+                }
+            }
+            None => {
+                let inner = self.inner.lock().expect("api audit mutex");
+                inner
+                    .memory
+                    .iter()
+                    .filter(|e| keep(q, e))
+                    .take(limit)
+                    .cloned()
+                    .collect()
+            }
+        }
+    }
+}
+
+/// Run `req` through `next` and append one audit entry for it.
+///
+/// `event_height` is awaited only for successful non-GET requests; the
+/// standalone server reads the committed journal height, cluster passes `None`.
+pub async fn observe<F, Fut>(
+    log: &ApiAuditLog,
+    req: Request,
+    next: Next,
+    event_height: F,
+) -> Response
+where
+    F: FnOnce() -> Fut,
+    Fut: std::future::Future<Output = Option<u64>>,
+{
+    let method = req.method().clone();
+    let route = req.uri().path().to_string();
+    let mut ids = path_ids(&route);
+
+    let req = if method != Method::GET {
+        let (parts, body) = req.into_parts();
+        match inspect_body(body).await {
+            Ok((bytes, body)) => {
+                if let Some(b) = bytes {
+                    collect_json_ids(&b, &mut ids);
+                }
+                Request::from_parts(parts, body)
+            }
+            // The handler never sees a body it would take for empty.
+            Err(status) => {
+                let resp = (
+                    status,
+                    axum::Json(serde_json::json!({ "error": "request body could not be read" })),
+                )
+                    .into_response();
+                log.record(None, &method, &route, status.as_u16(), ids, None);
+                return resp;
+            }
+        }
+    } else {
+        req
+    };
+
+    let resp = next.run(req).await;
+    let status = resp.status();
+    let principal = resp
+        .extensions()
+        .get::<AuthPrincipal>()
+        .map(|p| p.0.clone());
+
+    let (resp, height) = if method != Method::GET && status.is_success() {
+        let (parts, body) = resp.into_parts();
+        let resp = match inspect_body(body).await {
+            Ok((bytes, body)) => {
+                if let Some(b) = bytes {
+                    collect_json_ids(&b, &mut ids);
+                }
+                Response::from_parts(parts, body)
+            }
+            Err(_) => {
+                tracing::error!("api audit: response body of {} failed", route);
+                StatusCode::INTERNAL_SERVER_ERROR.into_response()
+            }
+        };
+        (resp, event_height().await)
+    } else {
+        (resp, None)
+    };
+
+    ids.sort_unstable();
+    ids.dedup();
+    log.record(principal, &method, &route, status.as_u16(), ids, height);
+    resp
+}
+
+/// Buffer a small body so it can be inspected; larger or unsized bodies are
+/// passed through untouched. A body that fails while buffered, or runs past
+/// the length it declared, was consumed and cannot be passed on: that is
+/// `400`, never an empty body.
+async fn inspect_body(body: Body) -> Result<(Option<Bytes>, Body), StatusCode> {
+    match body.size_hint().exact() {
+        Some(n) if n > 0 && n <= MAX_INSPECT_BYTES => {
+            match axum::body::to_bytes(body, n as usize).await {
+                Ok(b) => Ok((Some(b.clone()), Body::from(b))),
+                Err(_) => Err(StatusCode::BAD_REQUEST),
+            }
+        }
+        _ => Ok((None, body)),
+    }
+}
+
+/// Numeric path segments (`/v1/records/42` → `[42]`).
+fn path_ids(path: &str) -> Vec<u64> {
+    path.split('/').filter_map(|s| s.parse().ok()).collect()
+}
+
+/// Top-level id-bearing fields of a JSON object body.
+fn collect_json_ids(body: &[u8], out: &mut Vec<u64>) {
+    let Ok(serde_json::Value::Object(map)) = serde_json::from_slice(body) else {
+        return;
+    };
+    for key in ["id", "record_id", "node_id", "edge_id", "ids", "record_ids"] {
+        match map.get(key) {
+            Some(serde_json::Value::Number(n)) => out.extend(n.as_u64()),
+            Some(serde_json::Value::Array(a)) => out.extend(a.iter().filter_map(|v| v.as_u64())),
+            _ => {}
+        }
+    }
+}
+
+/// `GET /v1/audit` — page through the trail. Served by both routers.
+pub async fn get_audit(
+    axum::extract::Extension(log): axum::extract::Extension<std::sync::Arc<ApiAuditLog>>,
+    axum::extract::Query(q): axum::extract::Query<AuditQuery>,
+) -> axum::Json<serde_json::Value> {
+    let entries = log.query(&q).await;
+    let next_since = entries.last().map(|e| e.seq).or(q.since);
+    axum::Json(serde_json::json!({
+        "entries": entries,
+        "next_since": next_since,
+    }))
+}
@@ -3,7 +3,11 @@
 //!
 //! Keys are stored hashed (BLAKE3, applied to a high-entropy random token)
 //! in a JSON file.  The raw token is shown exactly once at creation time.
-//! Three scope tiers: `read_only` < `read_write` < `admin`.
+//! Three scope tiers: `read_only` < `read_write` < `admin`, plus a narrow
+//! `replicator` scope for followers (replication streams + snapshot download).
+//!
+//! Static role tokens (`VALORI_AUTH_ROLES`) map the operator-facing role names
+//! `reader` / `writer` / `admin` / `replicator` onto the same scopes.
 
 use serde::{Deserialize, Serialize};
 use std::collections::HashMap;
@@ -19,6 +23,9 @@ pub enum ApiScope {
     ReadOnly,
     ReadWrite,
     Admin,
+    /// Follower identity: may read, stream the event log and download
+    /// snapshots, but never write or manage keys.
+    Replicator,
 }
 
 impl ApiScope {
@@ -27,9 +34,22 @@ impl ApiScope {
         match required {
             ApiScope::ReadOnly => true,
             ApiScope::ReadWrite => matches!(self, ApiScope::ReadWrite | ApiScope::Admin),
+            ApiScope::Replicator => matches!(self, ApiScope::Replicator | ApiScope::Admin),
             ApiScope::Admin => matches!(self, ApiScope::Admin),
         }
     }
+
+    /// Parse an operator-facing role name (`reader`, `writer`, `admin`,
+    /// `replicator`). The snake_case scope names are accepted as aliases.
+    pub fn from_role_name(name: &str) -> Option<ApiScope> {
+        match name.trim().to_ascii_lowercase().as_str() {
+            "reader" | "read_only" => Some(ApiScope::ReadOnly),
+            "writer" | "read_write" => Some(ApiScope::ReadWrite),
+            "admin" => Some(ApiScope::Admin),
+            "replicator" => Some(ApiScope::Replicator),
+            _ => None,
+        }
+    }
 }
 
 impl std::fmt::Display for ApiScope {
@@ -38,6 +58,7 @@ impl std::fmt::Display for ApiScope {
             ApiScope::ReadOnly => write!(f, "read_only"),
             ApiScope::ReadWrite => write!(f, "read_write"),
             ApiScope::Admin => write!(f, "admin"),
+            ApiScope::Replicator => write!(f, "replicator"),
         }
     }
 }
@@ -240,14 +261,30 @@ impl KeyStore {
 
 // ── Scope classification (used by auth middleware) ─────────────────────────────
 
+/// The replication route group: event-log streaming and the bootstrap
+/// snapshot a follower downloads.
+pub fn is_replication_route(method: &axum::http::Method, path: &str) -> bool {
+    path.starts_with("/v1/replication")
+        || (method == axum::http::Method::GET && path == "/v1/snapshot/download")
+}
+
 /// Determine the minimum scope required for a request based on method + path.
 pub fn required_scope(method: &axum::http::Method, path: &str) -> ApiScope {
-    // Admin-only: key management, snapshot operations, storage operations,
-    // and replication endpoints (H-4: replication streams expose ALL namespaces).
+    // Replication group. Admin also satisfies this (see `satisfies`).
+    if is_replication_route(method, path) {
+        return ApiScope::Replicator;
+    }
+    // Admin-only: key management, the API audit trail, consistency checks,
+    // snapshot operations, full exports and the event journal (every
+    // namespace, vectors included), storage operations.
     if path.starts_with("/v1/keys")
+        || path.starts_with("/v1/audit")
+        || path.starts_with("/v1/admin")
         || path.starts_with("/v1/snapshot")
+        || (path.starts_with("/v1/collections/") && path.ends_with("/snapshot"))
+        || path == "/v1/export"
+        || path == "/v1/events"
         || path.starts_with("/v1/storage")
-        || path.starts_with("/v1/replication")
     {
         return ApiScope::Admin;
     }
@@ -277,12 +314,48 @@ pub fn required_scope(method: &axum::http::Method, path: &str) -> ApiScope {
 pub struct AuthState {
     pub key_store: std::sync::Arc<KeyStore>,
     pub legacy_token: Option<String>,
+    /// Static role tokens from `NodeConfig::auth_roles` (`VALORI_AUTH_ROLES`).
+    pub role_tokens: Vec<(ApiScope, String)>,
 }
 
 impl AuthState {
     pub fn has_any_auth(&self) -> bool {
-        self.legacy_token.is_some() || !self.key_store.is_empty()
+        self.legacy_token.is_some() || !self.key_store.is_empty() || !self.role_tokens.is_empty()
+    }
+
+    /// Resolve a static role token to its scope. Every configured token is
+    /// compared in constant time so the match position is not observable (H-1).
+    pub fn role_for(&self, token: &str) -> Option<ApiScope> {
+        use subtle::ConstantTimeEq;
+        let mut found = None;
+        for (scope, t) in &self.role_tokens {
+            if bool::from(token.as_bytes().ct_eq(t.as_bytes())) && found.is_none() {
+                found = Some(scope.clone());
+            }
+        }
+        found
+    }
+}
+
+/// Parse `VALORI_AUTH_ROLES`: comma-separated `role=token` pairs, e.g.
+/// `reader=tok_r,writer=tok_w,replicator=tok_f`. Unknown roles and empty
+/// tokens are a configuration error — silently dropping them would leave a
+/// route group unexpectedly open or closed.
+pub fn parse_auth_roles(raw: &str) -> Result<Vec<(ApiScope, String)>, String> {
+    let mut out = Vec::new();
+    for entry in raw.split(',').map(str::trim).filter(|e| !e.is_empty()) {
+        let (role, token) = entry
+            .split_once('=')
+            .ok_or_else(|| format!("expected role=token, got '{entry}'"))?;
+        let scope =
+            ApiScope::from_role_name(role).ok_or_else(|| format!("unknown role '{role}'"))?;
+        let token = token.trim();
+        if token.is_empty() {
+            return Err(format!("empty token for role '{role}'"));
+        }
+        out.push((scope, token.to_string()));
     }
+    Ok(out)
 }
 
 // ── Token utilities ───────────────────────────────────────────────────────────
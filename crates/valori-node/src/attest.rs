@@ -0,0 +1,148 @@
+// Copyright (c) 2025 Varshith Gudur. Dual-licensed under MIT OR Apache-2.0.
+//! Cross-machine determinism attestation.
+//!
+//! The promise is "same events → same hash" on any machine. An
+//! [`AttestProof`] is what a node claims for one height: the event chain it
+//! applied (the same timestamp-free chain `GET /v1/replication/chain`
+//! serves), the hash of the state replayed from those events in a fresh
+//! kernel, and that state's per-section hashes. A node serves its proof on
+//! `GET /v1/proof/at?height=N`; `valori attest` builds the same proof from a
+//! local event log and compares the two with [`first_divergence`].
+//!
+//! The state is always replayed from height 0, not read from the live
+//! engine, so host-side settings (capacity config, the configured dimension)
+//! cannot make two honest nodes disagree.
+
+use crate::geo_replication::{chain_hex, event_chain};
+use serde::{Deserialize, Serialize};
+use valori_kernel::event::KernelEvent;
+use valori_kernel::snapshot::blake3::hash_state_blake3;
+use valori_kernel::state::diff::section_hashes;
+use valori_kernel::state::kernel::KernelState;
+
+/// Hex BLAKE3 hash of each state section (`valori_kernel::state::diff`).
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct SectionProof {
+    pub records: String,
+    pub nodes: String,
+    pub edges: String,
+}
+
+/// `GET /v1/proof/at` response.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct AttestProof {
+    /// Events applied.
+    pub height: u64,
+    /// Head of the event chain at `height`.
+    pub event_chain_hash: String,
+    /// Hash of the whole state: the sections plus metadata, namespaces and
+    /// pool limits.
+    pub state_hash: String,
+    pub sections: SectionProof,
+}
+
+/// Where two proofs for the same height part, in the order checked: the
+/// event history first, since a different input explains every later
+/// difference.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum Section {
+    Events,
+    Records,
+    Nodes,
+    Edges,
+    /// Sections agree but the state hash does not: metadata, namespaces or
+    /// pool limits.
+    Other,
+}
+
+/// Replay the first `height` of `events` into a fresh kernel and prove the
+/// result. `Err` names the event that failed to apply.
+pub fn prove(events: &[(u16, KernelEvent)], height: u64) -> Result<AttestProof, String> {
+    if height > events.len() as u64 {
+        return Err(format!(
+            "height {height} is beyond the {} events available",
+            events.len()
+        ));
+    }
+    let events = &events[..height as usize];
+    let mut state = KernelState::new();
+    for (i, (ns, event)) in events.iter().enumerate() {
+        state
+            .apply_event_ns(event, *ns)
+            .map_err(|e| format!("event {} failed to apply: {e:?}", i + 1))?;
+    }
+    let chain = event_chain(events);
+    let sections = section_hashes(&state);
+    Ok(AttestProof {
+        height,
+        event_chain_hash: chain_hex(&chain[height as usize]),
+        state_hash: chain_hex(&hash_state_blake3(&state)),
+        sections: SectionProof {
+            records: chain_hex(&sections.records),
+            nodes: chain_hex(&sections.nodes),
+            edges: chain_hex(&sections.edges),
+        },
+    })
+}
+
+/// The first section where `a` and `b` disagree; `None` when they agree
+/// byte for byte.
+pub fn first_divergence(a: &AttestProof, b: &AttestProof) -> Option<Section> {
+    if a.event_chain_hash != b.event_chain_hash {
+        Some(Section::Events)
+    } else if a.sections.records != b.sections.records {
+        Some(Section::Records)
+    } else if a.sections.nodes != b.sections.nodes {
+        Some(Section::Nodes)
+    } else if a.sections.edges != b.sections.edges {
+        Some(Section::Edges)
+    } else if a.state_hash != b.state_hash {
+        Some(Section::Other)
+    } else {
+        None
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use valori_kernel::types::id::RecordId;
+    use valori_kernel::types::vector::FxpVector;
+
+    fn insert(id: u32, tag: u64) -> (u16, KernelEvent) {
+        (
+            0,
+            KernelEvent::InsertRecord {
+                id: RecordId(id),
+                vector: FxpVector::new_zeros(2),
+                metadata: None,
+                tag,
+            },
+        )
+    }
+
+    #[test]
+    fn proofs_locate_the_first_divergent_section() {
+        let a = vec![insert(0, 1), insert(1, 1)];
+        let b = vec![insert(0, 1), insert(1, 2)];
+        assert_eq!(prove(&a, 2).unwrap(), prove(&a.clone(), 2).unwrap());
+        assert_eq!(
+            first_divergence(&prove(&a, 1).unwrap(), &prove(&b, 1).unwrap()),
+            None
+        );
+        assert_eq!(
+            first_divergence(&prove(&a, 2).unwrap(), &prove(&b, 2).unwrap()),
+            Some(Section::Events)
+        );
+
+        // Same events, different state: the record section is named.
+        let mut forged = prove(&a, 2).unwrap();
+        forged.sections.records = prove(&b, 2).unwrap().sections.records;
+        assert_eq!(
+            first_divergence(&prove(&a, 2).unwrap(), &forged),
+            Some(Section::Records)
+        );
+        assert!(prove(&a, 3).is_err());
+    }
+}
@@ -56,28 +56,54 @@ impl KernelCapability for EngineKernelCapability {
     ) -> Result<serde_json::Value, EffectError> {
         use valori_kernel::snapshot::blake3::hash_state_blake3;
         match body {
-            KernelCommandBody::InsertRecord { values, text, .. } => {
+            KernelCommandBody::InsertRecord {
+                values,
+                text,
+                tag,
+                if_version,
+                on_duplicate,
+                ..
+            } => {
+                let on_duplicate: crate::engine::OnDuplicate = match on_duplicate {
+                    Some(mode) => {
+                        serde_json::from_value(serde_json::json!(mode)).map_err(|_| {
+                            EffectError::TaskFailed(format!(
+                            "on_duplicate must be reject, return_existing or link, not {mode:?}"
+                        ))
+                        })?
+                    }
+                    None => Default::default(),
+                };
                 let mut eng = self.engine.write().await;
-                let record_id = eng
-                    .insert_record_from_f32_ns(values, namespace_id)
-                    .map_err(|e| {
-                        if let crate::errors::EngineError::Kernel(
+                if let Err(crate::errors::EngineError::VersionConflict { expected, current }) =
+                    eng.check_version(*if_version)
+                {
+                    return Err(EffectError::VersionConflict { expected, current });
+                }
+                let inserted = eng
+                    .insert_record_dedup_ns(values, *tag, namespace_id, on_duplicate)
+                    .map_err(|e| match e {
+                        crate::errors::EngineError::Kernel(
                             valori_kernel::error::KernelError::CapacityExceeded,
-                        ) = &e
-                        {
-                            EffectError::Capacity("record pool full".into())
-                        } else {
-                            EffectError::Dispatch(format!("kernel insert: {e}"))
+                        ) => EffectError::Capacity("record pool full".into()),
+                        crate::errors::EngineError::DuplicateVector { existing } => {
+                            EffectError::DuplicateVector { existing }
                         }
+                        e => EffectError::Dispatch(format!("kernel insert: {e}")),
                     })?;
-                if let Some(t) = text {
-                    eng.reranker_insert(record_id, t);
+                if let Some(t) = text.as_ref().filter(|_| inserted.inserted()) {
+                    eng.reranker_insert(inserted.id, t);
                 }
                 let hash = hash_state_blake3(&eng.state)
                     .iter()
                     .map(|b| format!("{:02x}", b))
                     .collect::<String>();
-                Ok(serde_json::json!({ "record_id": record_id, "state_hash": hash }))
+                Ok(serde_json::json!({
+                    "record_id": inserted.id,
+                    "state_hash": hash,
+                    "state_version": eng.state_version(),
+                    "duplicate_of": inserted.duplicate_of,
+                }))
             }
             KernelCommandBody::SoftDeleteRecord { record_id } => {
                 let mut eng = self.engine.write().await;
@@ -633,12 +659,24 @@ impl KernelCapability for RaftKernelCapability {
                 values,
                 metadata: _,
                 tag,
+                if_version,
+                on_duplicate,
                 ..
             } => {
+                if if_version.is_some() {
+                    return Err(EffectError::TaskFailed(
+                        "if_version is not supported in cluster mode".into(),
+                    ));
+                }
+                if on_duplicate.is_some() {
+                    return Err(EffectError::TaskFailed(
+                        "on_duplicate is not supported in cluster mode".into(),
+                    ));
+                }
                 let fxp: Result<Vec<_>, _> = values
                     .iter()
                     .map(|&v| {
-                        if v > 32767.99 || v < -32768.0 {
+                        if !valori_protocol::fxp::in_range(v) {
                             Err(EffectError::TaskFailed("value out of Q16.16 range".into()))
                         } else {
                             Ok(FxpScalar((v * SCALE as f32) as i32))
@@ -653,7 +691,7 @@ impl KernelCapability for RaftKernelCapability {
                     event: KernelEvent::AutoInsertRecord {
                         vector,
                         metadata: None,
-                        tag: *tag as u64,
+                        tag: *tag,
                     },
                     request_id: req_id_bytes,
                 };
@@ -847,7 +885,7 @@ impl KernelCapability for RaftKernelCapability {
         let fxp_data: Result<Vec<FxpScalar>, EffectError> = vector
             .iter()
             .map(|&v| {
-                if v > 32767.99 || v < -32768.0 {
+                if !valori_protocol::fxp::in_range(v) {
                     Err(EffectError::TaskFailed(
                         "query vector value out of Q16.16 range".into(),
                     ))
@@ -940,7 +978,7 @@ impl KernelCapability for RaftKernelCapability {
         let fxp_data: Result<Vec<FxpScalar>, EffectError> = vector
             .iter()
             .map(|&v| {
-                if v > 32767.99 || v < -32768.0 {
+                if !valori_protocol::fxp::in_range(v) {
                     Err(EffectError::TaskFailed(
                         "query vector value out of Q16.16 range".into(),
                     ))
@@ -351,6 +351,8 @@ pub async fn bootstrap_cluster(
         .map_err(|e| std::io::Error::other(format!("raft config invalid: {e}")))?,
     );
 
+    let event_log_sync = crate::config::event_log_sync_from_env();
+
     let mut shards: BTreeMap<ShardId, ShardHandle> = BTreeMap::new();
     let mut raft_instances: HashMap<ShardId, Raft> = HashMap::new();
 
@@ -379,7 +381,7 @@ pub async fn bootstrap_cluster(
         ) = match event_log_path {
             Some(base) => {
                 let path = shard_path(base, shard_id, cfg.shard_count);
-                match EventLogWriter::open(&path, Some(dim as u32)) {
+                match EventLogWriter::open_with_sync(&path, Some(dim as u32), event_log_sync) {
                     Ok(writer) => {
                         let mut sink = EventLogAuditSink::new(writer);
                         if let Some(limit) = event_log_rotation_bytes {
@@ -41,6 +41,7 @@ use valori_kernel::types::id::{NodeId, RecordId};
 use valori_kernel::types::scalar::FxpScalar;
 use valori_kernel::types::vector::FxpVector;
 
+use crate::api_audit::{with_principal, ApiAuditLog};
 use crate::api_keys::{required_scope, ApiScope, AuthState, KeyStore};
 use crate::cluster::ClusterHandle;
 use crate::cluster_api::cluster_router;
@@ -230,7 +231,7 @@ async fn cluster_auth_guard(
     next: Next,
 ) -> Result<axum::response::Response, StatusCode> {
     if !auth.has_any_auth() {
-        return Ok(next.run(req).await);
+        return Ok(with_principal(next.run(req).await, "anonymous"));
     }
     let path = req.uri().path().to_string();
     let method = req.method().clone();
@@ -248,7 +249,17 @@ async fn cluster_auth_guard(
 
     if let Some(record) = auth.key_store.lookup(token) {
         if record.scope.satisfies(&required) {
-            return Ok(next.run(req).await);
+            let principal = format!("key:{}", record.id);
+            return Ok(with_principal(next.run(req).await, principal));
+        }
+        return Err(StatusCode::FORBIDDEN);
+    }
+
+    // Static role tokens (VALORI_AUTH_ROLES).
+    if let Some(scope) = auth.role_for(token) {
+        if scope.satisfies(&required) {
+            let principal = format!("role:{scope}");
+            return Ok(with_principal(next.run(req).await, principal));
         }
         return Err(StatusCode::FORBIDDEN);
     }
@@ -256,13 +267,23 @@ async fn cluster_auth_guard(
     if let Some(ref legacy) = auth.legacy_token {
         use subtle::ConstantTimeEq;
         if token.as_bytes().ct_eq(legacy.as_bytes()).into() {
-            return Ok(next.run(req).await);
+            return Ok(with_principal(next.run(req).await, "legacy"));
         }
     }
 
     Err(StatusCode::UNAUTHORIZED)
 }
 
+/// Records every protected request in the API audit trail. Cluster writes
+/// commit through Raft, so no local event-log height is attached.
+async fn cluster_api_audit_guard(
+    Extension(audit): Extension<Arc<ApiAuditLog>>,
+    req: AxumRequest,
+    next: Next,
+) -> axum::response::Response {
+    crate::api_audit::observe(&audit, req, next, || async { None }).await
+}
+
 /// The full router a cluster node serves: data plane + management plane.
 pub fn build_cluster_router(
     handle: &ClusterHandle,
@@ -336,6 +357,7 @@ pub fn build_cluster_router_with_keys(
     let auth = Arc::new(AuthState {
         key_store,
         legacy_token: auth_token,
+        role_tokens: node_cfg.auth_roles.clone(),
     });
 
     // ── Public routes (no auth) ───────────────────────────────────────────────
@@ -346,7 +368,7 @@ pub fn build_cluster_router_with_keys(
 
     // ── Canonical v1 routes ───────────────────────────────────────────────────
     let v1 = Router::new()
-        .route("/v1/records", post(insert_record))
+        .route("/v1/records", post(insert_record).get(list_records_where))
         .route("/v1/records/:id", axum::routing::get(get_record_by_id))
         .route(
             "/v1/records/:id/metadata",
@@ -410,16 +432,19 @@ pub fn build_cluster_router_with_keys(
         .route("/v1/community/overview", get(cluster_community_overview))
         .route("/v1/memory/consolidate", post(cluster_memory_consolidate))
         .route("/v1/memory/contradict", post(cluster_memory_contradict))
+        .route("/v1/memory/dedup_check", post(cluster_memory_dedup_check))
         .route("/v1/memory/upsert", post(cluster_memory_upsert))
         .route("/v1/memory/upsert_vector", post(cluster_memory_upsert))
         .route("/v1/memory/search", post(cluster_memory_search))
         .route("/v1/memory/search_vector", post(cluster_memory_search))
         .route("/v1/memory/meta/set", post(cluster_meta_set))
         .route("/v1/memory/meta/get", axum::routing::get(cluster_meta_get))
+        .route("/v1/memory/meta/delete", post(cluster_meta_delete))
         .route("/v1/graph/nodes", get(cluster_list_nodes))
         .route("/v1/models/health", get(cluster_models_health))
         .route("/v1/version", get(cluster_version))
         .route("/v1/timeline", get(cluster_timeline))
+        .route("/v1/audit", get(crate::api_audit::get_audit))
         .route("/v1/operations", get(cluster_get_operations))
         .route("/v1/operations/:id", get(cluster_get_operation_by_id))
         .route(
@@ -481,13 +506,22 @@ pub fn build_cluster_router_with_keys(
         .with_state(state)
         .merge(cluster_router(raft, Arc::new(api_shards), audit))
         .layer(axum::middleware::from_fn(cluster_auth_guard))
+        .layer(axum::middleware::from_fn(cluster_api_audit_guard))
+        .layer(Extension(Arc::new(ApiAuditLog::from_path(
+            node_cfg.api_audit_path.clone(),
+        ))))
         .layer(Extension(auth.clone()))
         .layer(Extension(receipt_store))
         .layer(Extension(capability_registry))
         .layer(Extension(task_registry))
         .layer(Extension(execution_registry));
 
-    let mut router = Router::new().merge(public).merge(protected);
+    let mut router = Router::new()
+        .merge(public)
+        .merge(protected)
+        .layer(axum::middleware::from_fn(
+            crate::request_id::request_id_layer,
+        ));
     if let Some(cors) = make_cors_layer() {
         router = router.layer(cors);
     }
@@ -710,6 +744,22 @@ struct InsertRequest {
     /// byte-identical to pre-S7 behavior.
     #[serde(default)]
     collection: Option<String>,
+    /// Standalone only — rejected here rather than silently ignored.
+    #[serde(default)]
+    if_version: Option<u64>,
+}
+
+/// 400 for an `if_version` or `expected_version` precondition. Raft
+/// entries are proposed without the engine lock, so the check could not be
+/// atomic with the commit.
+fn precondition_unsupported(field: &str) -> Response {
+    (
+        StatusCode::BAD_REQUEST,
+        Json(serde_json::json!({
+            "error": format!("{field} is not supported in cluster mode")
+        })),
+    )
+        .into_response()
 }
 
 #[derive(Serialize)]
@@ -723,7 +773,7 @@ struct InsertResponse {
 fn to_fxp(values: &[f32]) -> Result<FxpVector, String> {
     let mut data = Vec::with_capacity(values.len());
     for &v in values {
-        if !(-32768.0..=32767.99).contains(&v) {
+        if !valori_protocol::fxp::in_range(v) {
             return Err("vector values must be between -32768.0 and 32767.99".into());
         }
         data.push(FxpScalar((v * SCALE as f32) as i32));
@@ -755,6 +805,9 @@ async fn insert_record(
     axum::Extension(receipts): axum::Extension<Arc<valori_effect::ReceiptStore>>,
     Json(req): Json<InsertRequest>,
 ) -> Response {
+    if req.if_version.is_some() {
+        return precondition_unsupported("if_version");
+    }
     let fxp_values: Vec<i32> = req
         .values
         .iter()
@@ -901,6 +954,13 @@ struct SearchRequest {
     /// Supports range operators: `{"year": {"gte": 2020, "lte": 2024}}`.
     #[serde(default)]
     metadata_filter: Option<serde_json::Map<String, serde_json::Value>>,
+    /// Restrict the scan to records whose tag matches: `{"exact": 5}`,
+    /// `{"any": mask}` or `{"all": mask}`. Applied inside the kernel scan.
+    #[serde(default)]
+    tag_filter: Option<valori_kernel::index::TagFilter>,
+    /// Shorthand for `tag_filter: {"exact": t}`.
+    #[serde(default)]
+    filter_tag: Option<u64>,
     /// Phase S7. Absent/"default" targets the default namespace, shard 0 —
     /// byte-identical to pre-S7 behavior.
     #[serde(default)]
@@ -999,6 +1059,9 @@ async fn search(
     let k = req.k.max(1);
     let half_life = req.decay_half_life_secs.unwrap_or(0);
     let mf = req.metadata_filter.clone();
+    let tag_filter = req
+        .tag_filter
+        .or(req.filter_tag.map(valori_kernel::index::TagFilter::Exact));
 
     // When metadata_filter is set, over-fetch so post-filtering has enough candidates.
     let base_k = if mf.is_some() {
@@ -1021,7 +1084,7 @@ async fn search(
         let raw: Vec<SearchHit> = shard_sm
             .with_state(|s| {
                 let mut buf = vec![KernelSearchResult::default(); fetch_k];
-                let n = s.search_l2(&query, &mut buf, None);
+                let n = s.search_l2(&query, &mut buf, tag_filter);
                 buf[..n]
                     .iter()
                     .map(|r| SearchHit {
@@ -1091,7 +1154,7 @@ async fn search(
         let decayed: Vec<valori_search::DecayedHit> = shard_sm
             .with_state_and_timestamps(|s, created_at| {
                 let mut buf = vec![KernelSearchResult::default(); pool];
-                let n = s.search_l2(&query, &mut buf, None);
+                let n = s.search_l2(&query, &mut buf, tag_filter);
                 let candidates: Vec<valori_search::DecayHit> = buf[..n]
                     .iter()
                     .map(|r| valori_search::DecayHit {
@@ -1304,7 +1367,11 @@ impl crate::routes::records::RecordOps for DataPlaneState {
         ns: u16,
         id: u32,
         soft: bool,
+        if_version: Option<u64>,
     ) -> Result<crate::routes::records::DeletedRecord, Response> {
+        if if_version.is_some() {
+            return Err(precondition_unsupported("if_version"));
+        }
         let shard = self.shard_for(ns);
         let shard_id = shard_for_namespace(ns, self.shard_count).0 as u8;
         let state_before: String = {
@@ -1337,6 +1404,7 @@ impl crate::routes::records::RecordOps for DataPlaneState {
             cluster: true,
             state_before,
             state_after,
+            state_version: None,
         })
     }
 }
@@ -1391,6 +1459,7 @@ async fn get_record_by_id(
                         "metadata": rec.metadata.as_ref()
                             .and_then(|b| serde_json::from_slice::<serde_json::Value>(b).ok()),
                         "tag": rec.tag,
+                        "version": rec.version,
                     })
                 })
         })
@@ -1405,12 +1474,58 @@ async fn get_record_by_id(
     }
 }
 
+/// `GET /v1/records?where=<json>` over the replicated `KernelState.meta`,
+/// the map cluster searches filter on, so every replica answers alike.
+/// Always a scan: the metadata index covers the standalone sidecar only.
+async fn list_records_where(
+    State(state): State<DataPlaneState>,
+    Query(q): Query<crate::api::RecordsWhereQuery>,
+) -> Result<Json<serde_json::Value>, Response> {
+    let (filter, limit) = q.parse().map_err(|e| {
+        (
+            axum::http::StatusCode::BAD_REQUEST,
+            axum::Json(serde_json::json!({ "error": e })),
+        )
+            .into_response()
+    })?;
+    let Some(ns) = state.sm.resolve_namespace(q.collection.as_deref()).await else {
+        return Err((
+            axum::http::StatusCode::NOT_FOUND,
+            axum::Json(serde_json::json!({"error": "collection not found"})),
+        )
+            .into_response());
+    };
+    let ids = state
+        .sm
+        .with_state(|s| {
+            let mut ids: Vec<u32> = s
+                .meta
+                .iter()
+                .filter_map(|(key, value)| {
+                    let id: u32 = key.strip_prefix("rec:")?.parse().ok()?;
+                    s.get_record(valori_kernel::types::id::RecordId(id))
+                        .filter(|r| r.namespace_id == ns)?;
+                    let meta = serde_json::from_str::<serde_json::Value>(value).ok()?;
+                    valori_search::matches_metadata_filter(&meta, &filter).then_some(id)
+                })
+                .collect();
+            ids.sort_unstable();
+            ids.truncate(limit);
+            ids
+        })
+        .await;
+    Ok(Json(serde_json::json!({ "ids": ids, "indexed": false })))
+}
+
 async fn update_record_metadata(
     State(state): State<DataPlaneState>,
     axum::extract::Path(id): axum::extract::Path<u32>,
-    Query(q): Query<crate::routes::graph::CollectionQuery>,
+    Query(q): Query<crate::routes::graph::RecordUpdateQuery>,
     Json(body): Json<serde_json::Value>,
 ) -> Result<Json<serde_json::Value>, Response> {
+    if q.expected_version.is_some() {
+        return Err(precondition_unsupported("expected_version"));
+    }
     let ns = match state.sm.resolve_namespace(q.collection.as_deref()).await {
         Some(ns) => ns,
         None => {
@@ -1477,6 +1592,8 @@ struct BatchInsertRequest {
     /// byte-identical to pre-S7 behavior.
     #[serde(default)]
     collection: Option<String>,
+    #[serde(default)]
+    if_version: Option<u64>,
 }
 
 async fn batch_insert(
@@ -1484,6 +1601,9 @@ async fn batch_insert(
     axum::Extension(receipts): axum::Extension<Arc<valori_effect::ReceiptStore>>,
     Json(req): Json<BatchInsertRequest>,
 ) -> Response {
+    if req.if_version.is_some() {
+        return precondition_unsupported("if_version");
+    }
     let ns_id = match state.sm.resolve_namespace(req.collection.as_deref()).await {
         Some(id) => id,
         None => {
@@ -1633,9 +1753,9 @@ async fn cluster_proof(State(state): State<DataPlaneState>) -> Response {
 }
 
 // ── Event-log proof ───────────────────────────────────────────────────────────
-// BLAKE3 hash of this node's events.log file, in the same format as the
-// standalone `/v1/proof/event-log` endpoint. The hash covers the raw bytes of
-// the current live segment — sealed archive segments are not included.
+// Running log hash of each shard's event log (every entry of the sealed
+// archives and the live segment), in the same format as the standalone
+// `/v1/proof/event-log` endpoint.
 
 async fn event_log_proof(State(state): State<DataPlaneState>) -> Response {
     if state.shard_event_log_paths.is_empty() {
@@ -1647,7 +1767,7 @@ async fn event_log_proof(State(state): State<DataPlaneState>) -> Response {
     }
     let mut shards = serde_json::Map::new();
     for (shard_id, path) in &state.shard_event_log_paths {
-        match crate::events::event_proof::compute_event_log_hash(path) {
+        match crate::events::event_proof::compute_log_hash(path) {
             Ok(bytes) => {
                 let hex: String = bytes.iter().map(|b| format!("{b:02x}")).collect();
                 shards.insert(
@@ -2936,6 +3056,57 @@ impl crate::routes::memory::MemoryOps for DataPlaneState {
             state_after,
         })
     }
+
+    async fn dedup_check(
+        &self,
+        ns: u16,
+        req: &crate::api::MemoryDedupCheckRequest,
+    ) -> Result<Vec<crate::api::MemoryDedupMatch>, Response> {
+        let bad_request = |error: String| {
+            (
+                StatusCode::BAD_REQUEST,
+                Json(serde_json::json!({ "error": error })),
+            )
+                .into_response()
+        };
+        if let Some(locked) = self.sm.locked_dim().await {
+            if req.vector.len() != locked {
+                return Err(bad_request(format!(
+                    "Vector has {} elements but this store is locked to dim={}.",
+                    req.vector.len(),
+                    locked
+                )));
+            }
+        }
+        let query = to_fxp(&req.vector).map_err(|e| bad_request(e.to_string()))?;
+        let scale = SCALE as f64 * SCALE as f64;
+        let max_score = (req.max_distance as f64 * scale) as i64;
+        let limit = req.limit;
+
+        let shard_sm = &self.shard_for(ns).state_machine;
+        let hits: Vec<(u32, f32)> = shard_sm
+            .with_state(|s| {
+                let mut buf = vec![KernelSearchResult::default(); limit];
+                let n = s.search_range_ns(&query, max_score, &mut buf, ns);
+                buf[..n]
+                    .iter()
+                    .map(|r| (r.id.0, (r.score as f64 / scale) as f32))
+                    .collect()
+            })
+            .await;
+        let mut matches = Vec::with_capacity(hits.len());
+        for (record_id, distance) in hits {
+            let memory_id = format!("rec:{record_id}");
+            let metadata = shard_sm.get_meta_json(&memory_id).await;
+            matches.push(crate::api::MemoryDedupMatch {
+                memory_id,
+                record_id,
+                distance,
+                metadata,
+            });
+        }
+        Ok(matches)
+    }
 }
 
 async fn cluster_memory_consolidate(
@@ -2954,6 +3125,13 @@ async fn cluster_memory_contradict(
     crate::routes::memory::memory_contradict(&state, &receipts, payload).await
 }
 
+async fn cluster_memory_dedup_check(
+    State(state): State<DataPlaneState>,
+    Json(payload): Json<crate::api::MemoryDedupCheckRequest>,
+) -> Result<Json<crate::api::MemoryDedupCheckResponse>, Response> {
+    crate::routes::memory::memory_dedup_check(&state, payload).await
+}
+
 // ── Phase I4: cluster full-pipeline ingest ────────────────────────────────────
 //
 // POST /v1/ingest  (cluster mode)
@@ -2970,12 +3148,17 @@ impl crate::routes::meta::MetaOps for DataPlaneState {
         target_id: String,
         metadata: serde_json::Value,
     ) -> Result<(), Response> {
+        let value = metadata.to_string();
+        self.sm
+            .with_state(|k| crate::engine::Engine::check_meta_limits(&k.meta, &target_id, &value))
+            .await
+            .map_err(IntoResponse::into_response)?;
         raft_write_data(
             &self.raft,
             ClientRequest {
                 event: KernelEvent::SetMeta {
                     key: target_id,
-                    value: metadata.to_string(),
+                    value,
                 },
                 request_id: None,
                 schema_version: CURRENT_SCHEMA_VERSION,
@@ -2996,6 +3179,20 @@ impl crate::routes::meta::MetaOps for DataPlaneState {
             })
             .await
     }
+
+    async fn delete_meta(&self, target_id: String) -> Result<(), Response> {
+        raft_write_data(
+            &self.raft,
+            ClientRequest {
+                event: KernelEvent::DeleteMeta { key: target_id },
+                request_id: None,
+                schema_version: CURRENT_SCHEMA_VERSION,
+                namespace_id: 0,
+            },
+        )
+        .await
+        .map(|_| ())
+    }
 }
 
 async fn cluster_meta_set(
@@ -3005,6 +3202,13 @@ async fn cluster_meta_set(
     crate::routes::meta::meta_set(&state, payload).await
 }
 
+async fn cluster_meta_delete(
+    State(state): State<DataPlaneState>,
+    Json(payload): Json<crate::api::MetadataDeleteRequest>,
+) -> Result<Json<crate::api::MetadataDeleteResponse>, Response> {
+    crate::routes::meta::meta_delete(&state, payload).await
+}
+
 async fn cluster_meta_get(
     State(state): State<DataPlaneState>,
     axum::extract::Query(q): axum::extract::Query<crate::api::MetadataGetRequest>,
@@ -4893,6 +5097,15 @@ fn collect_cluster_timeline(
                             KernelEvent::UpdateRecordMetadata { id, .. } => {
                                 ("UpdateRecordMetadata", Some(id.0), None, None)
                             }
+                            KernelEvent::ResizePools { .. } => ("ResizePools", None, None, None),
+                            KernelEvent::Vacuum { .. } => ("Vacuum", None, None, None),
+                            KernelEvent::DeleteMeta { .. } => ("DeleteMeta", None, None, None),
+                            KernelEvent::Omitted { record } => {
+                                ("Omitted", record.map(|r| r.0), None, None)
+                            }
+                            KernelEvent::SetSparseVector { id, .. } => {
+                                ("SetSparseVector", Some(id.0), None, None)
+                            }
                         };
                         entries.push(crate::api::TimelineEntry {
                             log_index,
@@ -4903,6 +5116,8 @@ fn collect_cluster_timeline(
                             record_id,
                             node_id,
                             edge_id,
+                            anomaly_score: None,
+                            outlier: false,
                         });
                     }
                     log_index += 1;
@@ -5229,23 +5444,36 @@ async fn cluster_snapshot_restore() -> Response {
 }
 
 async fn cluster_snapshot_download(State(state): State<DataPlaneState>) -> Response {
-    match state.sm.with_state(encode_cluster_snapshot).await {
-        Ok(bytes) => (
-            StatusCode::OK,
-            [
-                (header::CONTENT_TYPE.as_str(), "application/octet-stream"),
-                (
-                    header::CONTENT_DISPOSITION.as_str(),
-                    "attachment; filename=\"cluster-snapshot.snap\"",
-                ),
-            ],
-            bytes,
-        )
-            .into_response(),
+    // Spool next to shard 0's audit log, so a large snapshot is not written
+    // to a tmpfs /tmp.
+    let dir = state
+        .shard_event_log_paths
+        .values()
+        .next()
+        .and_then(|p| p.parent())
+        .map(std::path::Path::to_path_buf)
+        .unwrap_or_else(std::env::temp_dir);
+    let spooled = state
+        .sm
+        .with_state(|ks| {
+            let bytes = encode_cluster_snapshot(ks)?;
+            crate::snapshot_stream::SpooledSnapshot::write(&dir, &bytes)
+                .map_err(|e| format!("spool: {e}"))
+        })
+        .await;
+    let streamed = match spooled {
+        Ok(spool) => spool
+            .into_response("cluster-snapshot.snap")
+            .await
+            .map_err(|e| format!("spool: {e}")),
+        Err(e) => Err(e),
+    };
+    match streamed {
+        Ok(resp) => resp,
         Err(e) => (
             StatusCode::INTERNAL_SERVER_ERROR,
             Json(serde_json::json!({
-                "error": format!("snapshot encode failed: {e}")
+                "error": format!("snapshot download failed: {e}")
             })),
         )
             .into_response(),
@@ -26,6 +26,10 @@ pub struct NodeConfig {
     pub dim: usize,
     pub index_kind: IndexKind,
     pub quantization_kind: QuantizationKind,
+    // Env: VALORI_EXTRA_INDEXES=hnsw,brute_force
+    // Extra indexes kept over the same records; a search picks one with
+    // `"index": "<name>"`. Unknown names are ignored.
+    pub extra_indexes: Vec<IndexKind>,
     pub max_nodes: usize,
     pub max_edges: usize,
     pub bind_addr: SocketAddr,
@@ -39,6 +43,30 @@ pub struct NodeConfig {
     // Trigger an audit log rotation after this many bytes.
     pub event_log_rotation_bytes: Option<u64>,
 
+    // Env: VALORI_EVENT_LOG_SYNC=fdatasync|fsync|dsync (default fdatasync)
+    // How each event-log append is made durable: fdatasync after the write,
+    // a full fsync (data and all metadata), or an O_DSYNC file whose writes
+    // are durable when they return. Segment creation and rotation also sync
+    // the log's directory under every strategy.
+    pub event_log_sync: crate::durability::SyncStrategy,
+
+    // Env: VALORI_QUARANTINE_CORRUPT_SEGMENTS=1
+    // On startup, move a corrupt event-log segment (and everything after it)
+    // into quarantine/ and recover to the last verifiable height instead of
+    // refusing the log. Proofs are marked truncated afterwards.
+    pub quarantine_corrupt_segments: bool,
+
+    // Env: VALORI_VECTOR_FILE=<path>
+    // Keep record vectors in this append-only, memory-mapped file instead of
+    // RAM; the record pool holds only their offsets. Recreated empty at every
+    // start from the recovered state. Standalone only.
+    pub vector_file_path: Option<PathBuf>,
+
+    // Env: VALORI_COLLECTIONS_DIR=<dir>
+    // Where `/v1/collections` keeps each configured collection (its spec and
+    // event log, one subdirectory per collection). Unset = in memory only.
+    pub collections_dir: Option<PathBuf>,
+
     /// Deprecated: use snapshot_every_events / snapshot_every_bytes instead.
     /// Retained for backward compatibility; triggers a startup warning if set
     /// without the new cadence knobs. Will be removed in Phase 3.
@@ -53,10 +81,18 @@ pub struct NodeConfig {
     // Trigger a snapshot after this many bytes of log have been appended.
     pub snapshot_every_bytes: Option<u64>,
 
-    // Env: VALORI_SNAPSHOT_KEEP (default: 3)
-    // Number of most recent snapshot files to retain.
+    // Env: VALORI_SNAPSHOT_KEEP
+    // Number of most recent autosaved snapshots to retain in the catalog.
     pub snapshot_keep: Option<u32>,
 
+    // Env: VALORI_SNAPSHOT_KEEP_DAILY
+    // Also retain the newest autosaved snapshot of each of this many days.
+    pub snapshot_keep_daily: Option<u32>,
+
+    // Env: VALORI_SNAPSHOT_KEEP_CHECKPOINTS (default: true)
+    // Also retain the snapshot each event-log checkpoint height replays from.
+    pub snapshot_keep_checkpoints: bool,
+
     // Env: VALORI_ZSTD_LEVEL (default: 3)
     // zstd compression level applied to sealed (rotated) segment files.
     // Implementation: Phase 1.7/1.8 (seam reads the value; compressor wired later).
@@ -66,6 +102,74 @@ pub struct NodeConfig {
     // If true, skip snapshots and replay from genesis on startup (audit mode).
     pub genesis_replay: bool,
 
+    // Env: VALORI_CHECK_AFTER_RECOVERY=1
+    // If true, run the `/v1/admin/check` consistency check once recovery has
+    // finished and log every violation at error level.
+    pub check_after_recovery: bool,
+
+    // Env: VALORI_VERIFY_RECOVERY=1 (or the --verify-recovery CLI argument)
+    // Dry-run recovery into a throwaway engine, print the resulting state
+    // hash and any anomalies as JSON, and exit 0 (clean) or 1 — without
+    // writing to the data files or binding the listener.
+    pub verify_recovery: bool,
+
+    // Env: VALORI_SNAPSHOT_CHECK=off|warn|refuse-writes (default refuse-writes)
+    // When recovery falls back to the snapshot, check its checksum, section
+    // lengths and kernel invariants, and replay the event log (if any) to its
+    // height. On failure `warn` only logs; `refuse-writes` serves reads but
+    // rejects every write with 503 until restart.
+    pub snapshot_check: valori_engine::SnapshotCheckPolicy,
+
+    // Env: VALORI_RECOVERY_POLICY=fallback|fail-closed|event-log-only|start-empty-and-quarantine
+    // (default fallback)
+    // What startup recovery does when the event log, snapshot or WAL exists
+    // but fails to load. `fallback` tries the next source; `fail-closed`
+    // stops and exits; `event-log-only` never reads the snapshot or WAL and
+    // stops if the log fails; `start-empty-and-quarantine` moves every
+    // artifact into quarantine/<unix secs>/ and starts empty.
+    pub recovery_policy: valori_engine::RecoveryPolicy,
+
+    // Env: VALORI_MAX_METADATA_BYTES=<n> (default and ceiling 65536)
+    // Env: VALORI_METADATA_CONTENT=any|utf8|json (default any)
+    // Checked on every record insert and metadata update before the event is
+    // logged. Too large is 413, wrong content 422; nothing is written.
+    pub metadata_policy: valori_engine::MetadataPolicy,
+
+    // Env: VALORI_METADATA_INDEX_FIELDS=<field>,<field>,...
+    // Top-level record metadata fields to keep a secondary index over, so
+    // metadata-filtered search and `GET /v1/records?where=` resolve
+    // predicates on them without reading every record's metadata.
+    pub metadata_index_fields: Vec<String>,
+
+    // Env: VALORI_CONSISTENCY_CHECK_SECS=<n>
+    // If set (and non-zero), re-run the consistency check every n seconds in
+    // the background and flip `/health` to "drift" when it fails.
+    pub consistency_check_secs: Option<u64>,
+
+    // Env: VALORI_INDEX_OPTIMIZE_SECS=<n>
+    // If set (and non-zero), every n seconds without a committed write run
+    // one bounded step of HNSW graph optimization: re-link nodes that deletes
+    // left poorly connected and prune over-full link lists. Progress is
+    // reported in /metrics.
+    pub index_optimize_secs: Option<u64>,
+
+    // Env: VALORI_INDEX_OPTIMIZE_BUDGET=<n> (default 256)
+    // Nodes looked at per optimization step; bounds how long the engine
+    // lock is held.
+    pub index_optimize_budget: usize,
+
+    // Env: VALORI_RECALL_CHECK_SECS=<n>
+    // If set (and non-zero), every n seconds estimate recall@k of every
+    // index against exact search and publish it in /metrics.
+    pub recall_check_secs: Option<u64>,
+
+    // Env: VALORI_RECALL_SAMPLE=<n> (default 100, at most 10000)
+    // Records used as queries per estimate.
+    pub recall_sample: usize,
+
+    // Env: VALORI_RECALL_K=<n> (default 10)
+    pub recall_k: usize,
+
     // ── Phase 1.10 / 1.11 ────────────────────────────────────────────────────
     // Env: VALORI_NODE_ID
     // Stable numeric identity for this node. Phase 2: openraft NodeId.
@@ -80,6 +184,40 @@ pub struct NodeConfig {
     /// Path to the JSON file persisting API keys (Phase 3.5).
     /// Env: `VALORI_KEYS_PATH`. Absent = key store is in-memory only (resets on restart).
     pub keys_path: Option<PathBuf>,
+    /// Static role tokens enforced per route group by the auth middleware:
+    /// `reader` (search/reads), `writer` (inserts/deletes), `admin`
+    /// (snapshot, storage, key management) and `replicator` (replication
+    /// streams + snapshot download).
+    /// Env: `VALORI_AUTH_ROLES=reader=tok_r,writer=tok_w,replicator=tok_f`
+    pub auth_roles: Vec<(crate::api_keys::ApiScope, String)>,
+    /// Bearer token a follower presents to its leader (normally a
+    /// `replicator` role token). Env: `VALORI_REPLICATION_TOKEN`.
+    pub replication_token: Option<String>,
+    /// Mutual TLS for the HTTP API; replication routes then require a client
+    /// certificate signed by the replication CA, and a follower presents its
+    /// own certificate to the leader.
+    /// Env: `VALORI_REPLICATION_TLS_CA` / `_CERT` / `_KEY` (PEM paths).
+    pub replication_tls: Option<crate::tls::ReplicationTlsConfig>,
+    /// Encoding a follower requests for the leader's event stream.
+    /// Env: `VALORI_REPLICATION_COMPRESSION=none|gzip|zstd` (default none).
+    pub replication_compression: crate::replication_compression::StreamCompression,
+    /// Partial replication: a follower keeps only records in these
+    /// collections and/or with matching tags.
+    /// Env: `VALORI_REPLICATE_COLLECTIONS=docs,faq` (comma-separated) and
+    /// `VALORI_REPLICATE_TAGS=any:6` (`exact:`, `any:` or `all:`).
+    pub replication_filter: Option<crate::replication_filter::ReplicationFilter>,
+    /// Asynchronous secondary region: poll this primary region's leader
+    /// and refuse writes until promoted (see [`crate::geo_replication`]).
+    /// Env: `VALORI_REGION_PRIMARY` (URL), `VALORI_REGION` (this region's
+    /// name, default `secondary`), `VALORI_REGION_POLL_MS` (default 1000).
+    pub region: Option<crate::geo_replication::RegionConfig>,
+    /// JSON Lines file for the API audit trail (`GET /v1/audit`).
+    /// Env: `VALORI_API_AUDIT_PATH`. Absent = last 10 000 entries in memory.
+    pub api_audit_path: Option<PathBuf>,
+    /// Also serve the HTTP API on this Unix domain socket, for sidecars on
+    /// the same host. The TCP listener on `bind_addr` keeps running.
+    /// Env: `VALORI_UNIX_SOCKET`.
+    pub unix_socket: Option<PathBuf>,
 
     // Phase 3.6: Crypto-shredding
     // Env: VALORI_SHRED_LOG_PATH
@@ -99,6 +237,27 @@ pub struct NodeConfig {
     // Number of snapshots to retain in the object store after pruning.
     pub object_store_keep: u32,
 
+    // Env: VALORI_RATE_LIMIT_RPS
+    // Requests per second across the authenticated API (/v1/admin exempt);
+    // over the limit → 429. Absent or 0 = unlimited. Changeable at runtime
+    // through PATCH /v1/admin/config.
+    pub rate_limit_rps: Option<u32>,
+
+    // Env: VALORI_SLOW_QUERY_MS
+    // Searches and inserts slower than this are logged at warn with their
+    // parameters and lock-wait vs compute time, and counted in
+    // valori_slow_queries_total. Absent or 0 = off. Changeable at runtime
+    // through PATCH /v1/admin/config.
+    pub slow_query_ms: Option<u64>,
+
+    // Env: VALORI_REQUEST_TIMEOUT_MS
+    // Default deadline for searches, counted from arrival and covering the
+    // wait for the engine lock. A search past it stops scanning, releases
+    // the engine and returns 504. A request's own `timeout_ms` overrides
+    // it. Absent or 0 = no deadline. Changeable at runtime through
+    // PATCH /v1/admin/config.
+    pub request_timeout_ms: Option<u64>,
+
     // Env: VALORI_CORS_ORIGIN
     // Absent = no CORS headers (API-only, no browser access).
     // "*"    = permissive (all origins allowed — dev only).
@@ -135,6 +294,28 @@ pub struct NodeConfig {
     // Env: VALORI_DECAY_HALF_LIFE_SECS
     pub decay_half_life_secs: Option<u64>,
 
+    // ── Insert-time anomaly scoring ──────────────────────────────────────────
+    // Score every new vector by its distance to the centroid of its k nearest
+    // neighbours and flag outliers in /v1/timeline. Absent or 0 = off.
+    // Env: VALORI_ANOMALY_K
+    pub anomaly_k: Option<usize>,
+    // Score above which an insert is flagged as an outlier (float L2 units).
+    // Env: VALORI_ANOMALY_THRESHOLD
+    pub anomaly_threshold: Option<f32>,
+
+    // ── Forgetting policy ────────────────────────────────────────────────────
+    // Every N committed events, delete records whose importance
+    //   access_weight·accesses + degree_weight·degree − age_weight·age_events
+    // is below the threshold. Absent or 0 = off. Needs VALORI_EVENT_LOG_PATH.
+    // Env: VALORI_FORGET_EVERY_EVENTS=<n>
+    //      VALORI_FORGET_MIN_AGE_EVENTS   (default n)
+    //      VALORI_FORGET_AGE_WEIGHT       (default 0.001)
+    //      VALORI_FORGET_ACCESS_WEIGHT    (default 1.0)
+    //      VALORI_FORGET_DEGREE_WEIGHT    (default 1.0)
+    //      VALORI_FORGET_THRESHOLD        (default 0.0)
+    //      VALORI_FORGET_MAX_PER_RUN      (default 1000)
+    pub forget_policy: Option<valori_engine::ForgetPolicy>,
+
     // ── Phase I2: on-node embedding ───────────────────────────────────────────
     // When set, /v1/ingest calls the embedding provider and inserts vectors
     // without the client needing to run its own embed step.
@@ -180,10 +361,19 @@ impl Default for NodeConfig {
             Ok("hnsw") => IndexKind::Hnsw,
             Ok("ivf") => IndexKind::Ivf,
             Ok("bq") => IndexKind::Bq,
+            Ok("sq") => IndexKind::Sq,
             Ok("auto") | Ok("mstg") => IndexKind::Auto,
             _ => IndexKind::BruteForce,
         };
 
+        let extra_indexes: Vec<IndexKind> = std::env::var("VALORI_EXTRA_INDEXES")
+            .map(|v| {
+                v.split(',')
+                    .filter_map(|name| IndexKind::from_name(name.trim()))
+                    .collect()
+            })
+            .unwrap_or_default();
+
         let quantization_kind = match std::env::var("VALORI_QUANT").as_deref() {
             Ok("scalar") => QuantizationKind::Scalar,
             Ok("product") => QuantizationKind::Product,
@@ -226,18 +416,125 @@ impl Default for NodeConfig {
         let snapshot_keep = std::env::var("VALORI_SNAPSHOT_KEEP")
             .ok()
             .and_then(|v| v.parse::<u32>().ok());
+        let snapshot_keep_daily = std::env::var("VALORI_SNAPSHOT_KEEP_DAILY")
+            .ok()
+            .and_then(|v| v.parse::<u32>().ok());
+        let snapshot_keep_checkpoints = std::env::var("VALORI_SNAPSHOT_KEEP_CHECKPOINTS")
+            .map(|v| !(v == "0" || v.eq_ignore_ascii_case("false")))
+            .unwrap_or(true);
         let zstd_compression_level = std::env::var("VALORI_ZSTD_LEVEL")
             .ok()
             .and_then(|v| v.parse::<i32>().ok());
         let genesis_replay = std::env::var("VALORI_GENESIS_REPLAY")
             .map(|v| v == "1" || v.eq_ignore_ascii_case("true"))
             .unwrap_or(false);
+        let check_after_recovery = std::env::var("VALORI_CHECK_AFTER_RECOVERY")
+            .map(|v| v == "1" || v.eq_ignore_ascii_case("true"))
+            .unwrap_or(false);
+        let verify_recovery = std::env::var("VALORI_VERIFY_RECOVERY")
+            .map(|v| v == "1" || v.eq_ignore_ascii_case("true"))
+            .unwrap_or(false)
+            || std::env::args().any(|a| a == "--verify-recovery");
+        // Unknown values keep the strict default rather than weakening it.
+        let snapshot_check = std::env::var("VALORI_SNAPSHOT_CHECK")
+            .ok()
+            .and_then(|v| valori_engine::SnapshotCheckPolicy::from_name(&v))
+            .unwrap_or_default();
+        let recovery_policy = std::env::var("VALORI_RECOVERY_POLICY")
+            .ok()
+            .and_then(|v| valori_engine::RecoveryPolicy::from_name(&v))
+            .unwrap_or_default();
+        let metadata_policy = valori_engine::MetadataPolicy {
+            max_bytes: std::env::var("VALORI_MAX_METADATA_BYTES")
+                .ok()
+                .and_then(|v| v.parse::<usize>().ok())
+                .filter(|&n| n > 0)
+                .unwrap_or(valori_protocol::MAX_METADATA_SIZE),
+            content: std::env::var("VALORI_METADATA_CONTENT")
+                .ok()
+                .and_then(|v| valori_engine::MetadataContent::from_name(&v))
+                .unwrap_or_default(),
+        };
+        let metadata_index_fields = std::env::var("VALORI_METADATA_INDEX_FIELDS")
+            .map(|v| {
+                v.split(',')
+                    .map(str::trim)
+                    .filter(|f| !f.is_empty())
+                    .map(str::to_string)
+                    .collect()
+            })
+            .unwrap_or_default();
+        let consistency_check_secs = std::env::var("VALORI_CONSISTENCY_CHECK_SECS")
+            .ok()
+            .and_then(|v| v.parse::<u64>().ok())
+            .filter(|&n| n > 0);
+        let index_optimize_secs = std::env::var("VALORI_INDEX_OPTIMIZE_SECS")
+            .ok()
+            .and_then(|v| v.parse::<u64>().ok())
+            .filter(|&n| n > 0);
+        let index_optimize_budget = std::env::var("VALORI_INDEX_OPTIMIZE_BUDGET")
+            .ok()
+            .and_then(|v| v.parse::<usize>().ok())
+            .filter(|&n| n > 0)
+            .unwrap_or(256);
+        let recall_check_secs = std::env::var("VALORI_RECALL_CHECK_SECS")
+            .ok()
+            .and_then(|v| v.parse::<u64>().ok())
+            .filter(|&n| n > 0);
+        let recall_sample = std::env::var("VALORI_RECALL_SAMPLE")
+            .ok()
+            .and_then(|v| v.parse::<usize>().ok())
+            .filter(|&n| n > 0)
+            .unwrap_or(crate::recall_job::DEFAULT_SAMPLE)
+            .min(crate::recall_job::MAX_SAMPLE);
+        let recall_k = std::env::var("VALORI_RECALL_K")
+            .ok()
+            .and_then(|v| v.parse::<usize>().ok())
+            .filter(|&n| n > 0)
+            .unwrap_or(crate::recall_job::DEFAULT_K);
         let node_id = std::env::var("VALORI_NODE_ID")
             .ok()
             .and_then(|v| v.parse::<u32>().ok());
 
         let auth_token = std::env::var("VALORI_AUTH_TOKEN").ok();
         let keys_path = std::env::var("VALORI_KEYS_PATH").ok().map(PathBuf::from);
+        // Like VALORI_FORMAT, a malformed role list must stop the process:
+        // falling back to "no roles" would silently disable authorization.
+        let auth_roles = match std::env::var("VALORI_AUTH_ROLES") {
+            Ok(raw) => crate::api_keys::parse_auth_roles(&raw)
+                .unwrap_or_else(|e| panic!("VALORI_AUTH_ROLES is invalid: {e}")),
+            Err(_) => Vec::new(),
+        };
+        let replication_token = std::env::var("VALORI_REPLICATION_TOKEN").ok();
+        let replication_tls =
+            crate::tls::ReplicationTlsConfig::from_env().unwrap_or_else(|e| panic!("{e}"));
+        let replication_compression = match std::env::var("VALORI_REPLICATION_COMPRESSION") {
+            Ok(raw) => crate::replication_compression::StreamCompression::from_name(&raw)
+                .unwrap_or_else(|e| panic!("VALORI_REPLICATION_COMPRESSION is invalid: {e}")),
+            Err(_) => Default::default(),
+        };
+        let replication_filter = crate::replication_filter::ReplicationFilter::parse(
+            std::env::var("VALORI_REPLICATE_COLLECTIONS")
+                .ok()
+                .as_deref(),
+            std::env::var("VALORI_REPLICATE_TAGS").ok().as_deref(),
+        )
+        .unwrap_or_else(|e| panic!("VALORI_REPLICATE_TAGS is invalid: {e}"));
+        let region = std::env::var("VALORI_REGION_PRIMARY")
+            .ok()
+            .map(|primary_url| crate::geo_replication::RegionConfig {
+                name: std::env::var("VALORI_REGION").unwrap_or_else(|_| "secondary".into()),
+                primary_url,
+                poll_interval_ms: std::env::var("VALORI_REGION_POLL_MS")
+                    .ok()
+                    .and_then(|v| v.parse::<u64>().ok())
+                    .filter(|&ms| ms > 0)
+                    .unwrap_or(crate::geo_replication::DEFAULT_POLL_INTERVAL_MS),
+            });
+        let api_audit_path = std::env::var("VALORI_API_AUDIT_PATH")
+            .ok()
+            .map(PathBuf::from);
+        let unix_socket = std::env::var("VALORI_UNIX_SOCKET").ok().map(PathBuf::from);
         let shred_log_path = std::env::var("VALORI_SHRED_LOG_PATH")
             .ok()
             .map(PathBuf::from);
@@ -249,6 +546,18 @@ impl Default for NodeConfig {
             .unwrap_or(7);
 
         let cors_origin = std::env::var("VALORI_CORS_ORIGIN").ok();
+        let rate_limit_rps = std::env::var("VALORI_RATE_LIMIT_RPS")
+            .ok()
+            .and_then(|v| v.parse::<u32>().ok())
+            .filter(|&r| r > 0);
+        let slow_query_ms = std::env::var("VALORI_SLOW_QUERY_MS")
+            .ok()
+            .and_then(|v| v.parse::<u64>().ok())
+            .filter(|&ms| ms > 0);
+        let request_timeout_ms = std::env::var("VALORI_REQUEST_TIMEOUT_MS")
+            .ok()
+            .and_then(|v| v.parse::<u64>().ok())
+            .filter(|&ms| ms > 0);
 
         let hnsw_m = std::env::var("VALORI_HNSW_M")
             .ok()
@@ -271,6 +580,44 @@ impl Default for NodeConfig {
             .ok()
             .and_then(|v| v.parse::<u64>().ok())
             .filter(|&v| v > 0);
+        let anomaly_k = std::env::var("VALORI_ANOMALY_K")
+            .ok()
+            .and_then(|v| v.parse::<usize>().ok())
+            .filter(|&k| k > 0);
+        let anomaly_threshold = std::env::var("VALORI_ANOMALY_THRESHOLD")
+            .ok()
+            .and_then(|v| v.parse::<f32>().ok())
+            .filter(|t| t.is_finite() && *t >= 0.0);
+
+        let forget_policy = std::env::var("VALORI_FORGET_EVERY_EVENTS")
+            .ok()
+            .and_then(|v| v.parse::<u64>().ok())
+            .filter(|&n| n > 0)
+            .map(|n| {
+                fn env<T: std::str::FromStr>(key: &str) -> Option<T> {
+                    std::env::var(key).ok().and_then(|v| v.parse().ok())
+                }
+                let mut p = valori_engine::ForgetPolicy::every(n);
+                if let Some(v) = env("VALORI_FORGET_MIN_AGE_EVENTS") {
+                    p.min_age_events = v;
+                }
+                if let Some(v) = env("VALORI_FORGET_AGE_WEIGHT") {
+                    p.age_weight = v;
+                }
+                if let Some(v) = env("VALORI_FORGET_ACCESS_WEIGHT") {
+                    p.access_weight = v;
+                }
+                if let Some(v) = env("VALORI_FORGET_DEGREE_WEIGHT") {
+                    p.degree_weight = v;
+                }
+                if let Some(v) = env("VALORI_FORGET_THRESHOLD") {
+                    p.threshold = v;
+                }
+                if let Some(v) = env("VALORI_FORGET_MAX_PER_RUN") {
+                    p.max_per_run = v;
+                }
+                p
+            });
 
         let embed_provider = std::env::var("VALORI_EMBED_PROVIDER").ok();
         let embed_model = std::env::var("VALORI_EMBED_MODEL").ok();
@@ -297,6 +644,18 @@ impl Default for NodeConfig {
         let event_log_rotation_bytes = std::env::var("VALORI_EVENT_LOG_ROTATION_BYTES")
             .ok()
             .and_then(|v| v.parse::<u64>().ok());
+        let event_log_sync = event_log_sync_from_env();
+        let quarantine_corrupt_segments = std::env::var("VALORI_QUARANTINE_CORRUPT_SEGMENTS")
+            .map(|v| v == "1" || v.eq_ignore_ascii_case("true"))
+            .unwrap_or(false);
+        let vector_file_path = std::env::var("VALORI_VECTOR_FILE")
+            .ok()
+            .filter(|v| !v.is_empty())
+            .map(PathBuf::from);
+        let collections_dir = std::env::var("VALORI_COLLECTIONS_DIR")
+            .ok()
+            .filter(|v| !v.is_empty())
+            .map(PathBuf::from);
 
         Self {
             max_records,
@@ -306,25 +665,55 @@ impl Default for NodeConfig {
             bind_addr,
             index_kind,
             quantization_kind,
+            extra_indexes,
             snapshot_path,
             wal_path,
             event_log_path,
             event_log_rotation_bytes,
+            event_log_sync,
+            quarantine_corrupt_segments,
+            vector_file_path,
+            collections_dir,
             auto_snapshot_interval_secs,
             snapshot_every_events,
             snapshot_every_bytes,
             snapshot_keep,
+            snapshot_keep_daily,
+            snapshot_keep_checkpoints,
             zstd_compression_level,
             genesis_replay,
+            check_after_recovery,
+            verify_recovery,
+            snapshot_check,
+            recovery_policy,
+            metadata_policy,
+            metadata_index_fields,
+            consistency_check_secs,
+            index_optimize_secs,
+            index_optimize_budget,
+            recall_check_secs,
+            recall_sample,
+            recall_k,
             node_id,
             health_check_mode: false, // set by CLI arg, not env var
             auth_token,
             keys_path,
+            auth_roles,
+            replication_token,
+            replication_tls,
+            replication_compression,
+            replication_filter,
+            region,
+            api_audit_path,
+            unix_socket,
             shred_log_path,
             mode,
             object_store_url,
             object_store_keep,
             cors_origin,
+            rate_limit_rps,
+            slow_query_ms,
+            request_timeout_ms,
             hnsw_m,
             hnsw_ef_construction,
             hnsw_ef_search,
@@ -332,6 +721,9 @@ impl Default for NodeConfig {
             ivf_n_probe,
             shard_count,
             decay_half_life_secs,
+            anomaly_k,
+            anomaly_threshold,
+            forget_policy,
             embed_provider,
             embed_model,
             embed_url,
@@ -339,3 +731,76 @@ impl Default for NodeConfig {
         }
     }
 }
+
+impl NodeConfig {
+    /// Startup configuration, layered defaults < `--config` TOML file <
+    /// `VALORI_*` env vars < CLI flags, then validated. Errors here must
+    /// stop the process.
+    pub fn load() -> Result<Self, crate::config_file::ConfigError> {
+        let mut cfg = Self::default();
+        if let Some(path) = crate::config_file::config_path_from_args(std::env::args())? {
+            crate::config_file::ConfigFile::load(&path)?.apply(&mut cfg)?;
+        }
+        crate::config_file::apply_cli_flags(&mut cfg, std::env::args().skip(1))?;
+        cfg.validate()?;
+        Ok(cfg)
+    }
+
+    /// Cross-field checks on the final configuration.
+    pub fn validate(&self) -> Result<(), crate::config_file::ConfigError> {
+        let invalid = |msg: &str| Err(crate::config_file::ConfigError::Invalid(msg.into()));
+        if self.dim == 0 {
+            return invalid("dim must be at least 1");
+        }
+        if self.max_records == 0 {
+            return invalid("max_records must be at least 1");
+        }
+        if self.shard_count == 0 {
+            return invalid("shard_count must be at least 1");
+        }
+        if cfg!(not(unix)) && self.unix_socket.is_some() {
+            return invalid("unix_socket is only supported on Unix platforms");
+        }
+        if self.snapshot_retention().is_some() && self.snapshot_path.is_none() {
+            return invalid("snapshot keep/keep_daily need a snapshot path");
+        }
+        if let NodeMode::Follower { leader_url } = &self.mode {
+            if !(leader_url.starts_with("http://") || leader_url.starts_with("https://")) {
+                return invalid("leader_url must start with http:// or https://");
+            }
+        }
+        if let Some(region) = &self.region {
+            if !matches!(self.mode, NodeMode::Leader) {
+                return invalid("a secondary region's head node cannot also be a follower");
+            }
+            if !(region.primary_url.starts_with("http://")
+                || region.primary_url.starts_with("https://"))
+            {
+                return invalid("region primary URL must start with http:// or https://");
+            }
+        }
+        Ok(())
+    }
+
+    /// Autosave retention, when `VALORI_SNAPSHOT_KEEP` or
+    /// `VALORI_SNAPSHOT_KEEP_DAILY` is set. Without one, autosave keeps
+    /// overwriting `VALORI_SNAPSHOT_PATH` and nothing is cataloged.
+    pub fn snapshot_retention(&self) -> Option<crate::persistence::SnapshotRetention> {
+        if self.snapshot_keep.is_none() && self.snapshot_keep_daily.is_none() {
+            return None;
+        }
+        Some(crate::persistence::SnapshotRetention {
+            keep_last: self.snapshot_keep.unwrap_or(0) as usize,
+            keep_daily: self.snapshot_keep_daily.unwrap_or(0) as usize,
+            keep_checkpoints: self.snapshot_keep_checkpoints,
+        })
+    }
+}
+
+/// `VALORI_EVENT_LOG_SYNC`, read by standalone and cluster boot alike.
+pub(crate) fn event_log_sync_from_env() -> crate::durability::SyncStrategy {
+    std::env::var("VALORI_EVENT_LOG_SYNC")
+        .ok()
+        .and_then(|v| crate::durability::SyncStrategy::from_name(&v))
+        .unwrap_or_default()
+}
@@ -0,0 +1,619 @@
+// Copyright (c) 2025 Varshith Gudur. Dual-licensed under MIT OR Apache-2.0.
+//! `--config valori.toml` — file-based node configuration.
+//!
+//! The file covers the knobs operators most often want under version
+//! control: capacities, data paths, index/quantization, auth, replication
+//! mode and snapshot policy. Everything else stays env-only.
+//!
+//! Layers, lowest first: defaults < file < `VALORI_*` environment < CLI
+//! flags ([`apply_cli_flags`]). A container image can ship a file and a
+//! deployment can still move the bind address, token, leader or data paths
+//! with an env var or a flag, without rebuilding the image.
+//!
+//! Unlike the env parser, which falls back to defaults on a bad value, every
+//! mistake in the file (unknown key, unknown index name, follower without a
+//! leader URL, …) is a [`ConfigError`] that stops the process at startup.
+//!
+//! ```toml
+//! bind = "0.0.0.0:3000"
+//! unix_socket = "/run/valori/valori.sock"
+//!
+//! [capacity]
+//! dim = 768
+//! max_records = 2_000_000
+//!
+//! [paths]
+//! event_log = "/data/events.log"
+//! snapshot  = "/data/snapshot.bin"
+//!
+//! [index]
+//! kind = "hnsw"
+//! quantization = "scalar"
+//! hnsw_m = 32
+//!
+//! [auth.roles]
+//! reader = "tok_r"
+//! writer = "tok_w"
+//!
+//! [replication]
+//! mode = "follower"
+//! leader_url = "http://leader:3000"
+//!
+//! [snapshot]
+//! every_events = 100_000
+//! keep = 5
+//! ```
+
+use crate::config::{IndexKind, NodeConfig, NodeMode, QuantizationKind};
+use serde::Deserialize;
+use std::collections::BTreeMap;
+use std::path::{Path, PathBuf};
+
+#[derive(Debug, thiserror::Error)]
+pub enum ConfigError {
+    #[error("cannot read config file {path:?}: {source}")]
+    Read {
+        path: PathBuf,
+        source: std::io::Error,
+    },
+    #[error("config file {0:?} is not TOML (only .toml files are supported)")]
+    UnsupportedFormat(PathBuf),
+    #[error("config file is not valid: {0}")]
+    Parse(String),
+    #[error("{0} needs a value")]
+    MissingValue(String),
+    #[error("unknown argument {0:?}")]
+    UnknownFlag(String),
+    #[error("invalid configuration: {0}")]
+    Invalid(String),
+}
+
+#[derive(Debug, Default, Deserialize)]
+#[serde(deny_unknown_fields)]
+pub struct ConfigFile {
+    pub bind: Option<String>,
+    pub unix_socket: Option<PathBuf>,
+    #[serde(default)]
+    pub capacity: CapacitySection,
+    #[serde(default)]
+    pub paths: PathsSection,
+    #[serde(default)]
+    pub index: IndexSection,
+    #[serde(default)]
+    pub auth: AuthSection,
+    #[serde(default)]
+    pub replication: ReplicationSection,
+    #[serde(default)]
+    pub snapshot: SnapshotSection,
+}
+
+#[derive(Debug, Default, Deserialize)]
+#[serde(deny_unknown_fields)]
+pub struct CapacitySection {
+    pub dim: Option<usize>,
+    pub max_records: Option<usize>,
+    pub max_nodes: Option<usize>,
+    pub max_edges: Option<usize>,
+    pub shard_count: Option<usize>,
+}
+
+#[derive(Debug, Default, Deserialize)]
+#[serde(deny_unknown_fields)]
+pub struct PathsSection {
+    pub event_log: Option<PathBuf>,
+    pub snapshot: Option<PathBuf>,
+    pub wal: Option<PathBuf>,
+    pub keys: Option<PathBuf>,
+    pub api_audit: Option<PathBuf>,
+    pub shred_log: Option<PathBuf>,
+    pub vectors: Option<PathBuf>,
+    pub collections: Option<PathBuf>,
+}
+
+#[derive(Debug, Default, Deserialize)]
+#[serde(deny_unknown_fields)]
+pub struct IndexSection {
+    /// `brute_force`, `hnsw`, `ivf`, `bq` or `auto`.
+    pub kind: Option<String>,
+    /// `none`, `scalar` or `product`.
+    pub quantization: Option<String>,
+    pub extra: Option<Vec<String>>,
+    pub hnsw_m: Option<usize>,
+    pub hnsw_ef_construction: Option<usize>,
+    pub hnsw_ef_search: Option<usize>,
+    pub ivf_n_list: Option<usize>,
+    pub ivf_n_probe: Option<usize>,
+}
+
+#[derive(Debug, Default, Deserialize)]
+#[serde(deny_unknown_fields)]
+pub struct AuthSection {
+    pub token: Option<String>,
+    /// Role name → bearer token, same roles as `VALORI_AUTH_ROLES`.
+    pub roles: Option<BTreeMap<String, String>>,
+}
+
+#[derive(Debug, Default, Deserialize)]
+#[serde(deny_unknown_fields)]
+pub struct ReplicationSection {
+    /// `leader` (default) or `follower`.
+    pub mode: Option<String>,
+    pub leader_url: Option<String>,
+    pub token: Option<String>,
+    /// `none`, `gzip` or `zstd`.
+    pub compression: Option<String>,
+}
+
+#[derive(Debug, Default, Deserialize)]
+#[serde(deny_unknown_fields)]
+pub struct SnapshotSection {
+    pub every_events: Option<u64>,
+    pub every_bytes: Option<u64>,
+    pub keep: Option<u32>,
+    pub keep_daily: Option<u32>,
+    pub keep_checkpoints: Option<bool>,
+    /// Deprecated interval trigger, as `VALORI_SNAPSHOT_INTERVAL`.
+    pub interval_secs: Option<u64>,
+}
+
+/// The `--config <path>` / `--config=<path>` argument, falling back to
+/// `VALORI_CONFIG`.
+pub fn config_path_from_args(
+    mut args: impl Iterator<Item = String>,
+) -> Result<Option<PathBuf>, ConfigError> {
+    while let Some(arg) = args.next() {
+        if arg == "--config" {
+            return args
+                .next()
+                .filter(|p| !p.starts_with("--"))
+                .map(|p| Some(PathBuf::from(p)))
+                .ok_or(ConfigError::MissingValue(arg));
+        }
+        if let Some(p) = arg.strip_prefix("--config=") {
+            if p.is_empty() {
+                return Err(ConfigError::MissingValue("--config".into()));
+            }
+            return Ok(Some(PathBuf::from(p)));
+        }
+    }
+    Ok(std::env::var("VALORI_CONFIG").ok().map(PathBuf::from))
+}
+
+/// Apply command-line overrides — the top layer, above the environment.
+/// `args` excludes the program name. Flags take `--flag value` or
+/// `--flag=value`; anything unrecognised is an error rather than ignored.
+pub fn apply_cli_flags(
+    cfg: &mut NodeConfig,
+    mut args: impl Iterator<Item = String>,
+) -> Result<(), ConfigError> {
+    while let Some(arg) = args.next() {
+        let (flag, inline) = match arg.split_once('=') {
+            Some((f, v)) if f.starts_with("--") => (f.to_string(), Some(v.to_string())),
+            _ => (arg.clone(), None),
+        };
+        // Switches handled elsewhere in main / NodeConfig::default.
+        if inline.is_none() && matches!(flag.as_str(), "--health-check" | "--verify-recovery") {
+            continue;
+        }
+        let takes_value = matches!(
+            flag.as_str(),
+            "--config"
+                | "--bind"
+                | "--unix-socket"
+                | "--auth-token"
+                | "--leader-url"
+                | "--event-log"
+                | "--snapshot"
+                | "--wal"
+                | "--keys"
+                | "--api-audit"
+                | "--shred-log"
+        );
+        if !takes_value {
+            return Err(ConfigError::UnknownFlag(arg));
+        }
+        let value = inline
+            .or_else(|| args.next().filter(|v| !v.starts_with("--")))
+            .filter(|v| !v.is_empty())
+            .ok_or_else(|| ConfigError::MissingValue(flag.clone()))?;
+        match flag.as_str() {
+            "--bind" => {
+                cfg.bind_addr = value.parse().map_err(|_| {
+                    ConfigError::Invalid(format!("--bind {value:?} is not host:port"))
+                })?
+            }
+            "--unix-socket" => cfg.unix_socket = Some(value.into()),
+            "--auth-token" => cfg.auth_token = Some(value),
+            "--leader-url" => cfg.mode = NodeMode::Follower { leader_url: value },
+            "--event-log" => cfg.event_log_path = Some(value.into()),
+            "--snapshot" => cfg.snapshot_path = Some(value.into()),
+            "--wal" => cfg.wal_path = Some(value.into()),
+            "--keys" => cfg.keys_path = Some(value.into()),
+            "--api-audit" => cfg.api_audit_path = Some(value.into()),
+            "--shred-log" => cfg.shred_log_path = Some(value.into()),
+            _ => {} // --config: read by config_path_from_args
+        }
+    }
+    Ok(())
+}
+
+/// Set `slot` from the file unless the matching env var is present — the
+/// environment always wins.
+fn set<T>(slot: &mut T, value: Option<T>, env: &str) {
+    if let Some(v) = value {
+        if std::env::var_os(env).is_none() {
+            *slot = v;
+        }
+    }
+}
+
+fn index_kind(name: &str) -> Result<IndexKind, ConfigError> {
+    IndexKind::from_name(name).ok_or_else(|| {
+        ConfigError::Invalid(format!(
+            "unknown index kind {name:?} (expected brute_force, hnsw, ivf, bq or auto)"
+        ))
+    })
+}
+
+impl ConfigFile {
+    pub fn load(path: &Path) -> Result<Self, ConfigError> {
+        match path.extension().and_then(|e| e.to_str()) {
+            Some("toml") | None => {}
+            Some(_) => return Err(ConfigError::UnsupportedFormat(path.to_path_buf())),
+        }
+        let raw = std::fs::read_to_string(path).map_err(|source| ConfigError::Read {
+            path: path.to_path_buf(),
+            source,
+        })?;
+        Self::parse(&raw)
+    }
+
+    pub fn parse(raw: &str) -> Result<Self, ConfigError> {
+        toml::from_str(raw).map_err(|e| ConfigError::Parse(e.to_string()))
+    }
+
+    /// Overlay the file onto `cfg` (already built from defaults + env).
+    pub fn apply(self, cfg: &mut NodeConfig) -> Result<(), ConfigError> {
+        let bind = self
+            .bind
+            .map(|b| {
+                b.parse()
+                    .map_err(|_| ConfigError::Invalid(format!("bind {b:?} is not host:port")))
+            })
+            .transpose()?;
+        set(&mut cfg.bind_addr, bind, "VALORI_BIND");
+        set(
+            &mut cfg.unix_socket,
+            self.unix_socket.map(Some),
+            "VALORI_UNIX_SOCKET",
+        );
+
+        let c = self.capacity;
+        set(&mut cfg.dim, c.dim, "VALORI_DIM");
+        set(&mut cfg.max_records, c.max_records, "VALORI_MAX_RECORDS");
+        set(&mut cfg.max_nodes, c.max_nodes, "VALORI_MAX_NODES");
+        set(&mut cfg.max_edges, c.max_edges, "VALORI_MAX_EDGES");
+        set(&mut cfg.shard_count, c.shard_count, "VALORI_SHARD_COUNT");
+
+        let p = self.paths;
+        set(
+            &mut cfg.event_log_path,
+            p.event_log.map(Some),
+            "VALORI_EVENT_LOG_PATH",
+        );
+        set(
+            &mut cfg.snapshot_path,
+            p.snapshot.map(Some),
+            "VALORI_SNAPSHOT_PATH",
+        );
+        set(&mut cfg.wal_path, p.wal.map(Some), "VALORI_WAL_PATH");
+        set(&mut cfg.keys_path, p.keys.map(Some), "VALORI_KEYS_PATH");
+        set(
+            &mut cfg.api_audit_path,
+            p.api_audit.map(Some),
+            "VALORI_API_AUDIT_PATH",
+        );
+        set(
+            &mut cfg.shred_log_path,
+            p.shred_log.map(Some),
+            "VALORI_SHRED_LOG_PATH",
+        );
+        set(
+            &mut cfg.vector_file_path,
+            p.vectors.map(Some),
+            "VALORI_VECTOR_FILE",
+        );
+        set(
+            &mut cfg.collections_dir,
+            p.collections.map(Some),
+            "VALORI_COLLECTIONS_DIR",
+        );
+
+        let i = self.index;
+        let kind = i.kind.as_deref().map(index_kind).transpose()?;
+        set(&mut cfg.index_kind, kind, "VALORI_INDEX");
+        let quant = i
+            .quantization
+            .as_deref()
+            .map(|q| match q {
+                "none" => Ok(QuantizationKind::None),
+                "scalar" => Ok(QuantizationKind::Scalar),
+                "product" => Ok(QuantizationKind::Product),
+                other => Err(ConfigError::Invalid(format!(
+                    "unknown quantization {other:?} (expected none, scalar or product)"
+                ))),
+            })
+            .transpose()?;
+        set(&mut cfg.quantization_kind, quant, "VALORI_QUANT");
+        let extra = i
+            .extra
+            .map(|names| names.iter().map(|n| index_kind(n)).collect())
+            .transpose()?;
+        set(&mut cfg.extra_indexes, extra, "VALORI_EXTRA_INDEXES");
+        set(&mut cfg.hnsw_m, i.hnsw_m.map(Some), "VALORI_HNSW_M");
+        set(
+            &mut cfg.hnsw_ef_construction,
+            i.hnsw_ef_construction.map(Some),
+            "VALORI_HNSW_EF_CONSTRUCTION",
+        );
+        set(
+            &mut cfg.hnsw_ef_search,
+            i.hnsw_ef_search.map(Some),
+            "VALORI_HNSW_EF_SEARCH",
+        );
+        set(
+            &mut cfg.ivf_n_list,
+            i.ivf_n_list.map(Some),
+            "VALORI_IVF_N_LIST",
+        );
+        set(
+            &mut cfg.ivf_n_probe,
+            i.ivf_n_probe.map(Some),
+            "VALORI_IVF_N_PROBE",
+        );
+
+        let a = self.auth;
+        set(&mut cfg.auth_token, a.token.map(Some), "VALORI_AUTH_TOKEN");
+        let roles = a
+            .roles
+            .map(|roles| {
+                roles
+                    .into_iter()
+                    .map(|(role, token)| {
+                        let scope =
+                            crate::api_keys::ApiScope::from_role_name(&role).ok_or_else(|| {
+                                ConfigError::Invalid(format!("unknown auth role {role:?}"))
+                            })?;
+                        if token.trim().is_empty() {
+                            return Err(ConfigError::Invalid(format!(
+                                "empty token for auth role {role:?}"
+                            )));
+                        }
+                        Ok((scope, token.trim().to_string()))
+                    })
+                    .collect::<Result<Vec<_>, _>>()
+            })
+            .transpose()?;
+        set(&mut cfg.auth_roles, roles, "VALORI_AUTH_ROLES");
+
+        let r = self.replication;
+        let mode = match (r.mode.as_deref(), r.leader_url) {
+            (None, None) => None,
+            (Some("leader"), None) => Some(NodeMode::Leader),
+            (None | Some("follower"), Some(leader_url)) => Some(NodeMode::Follower { leader_url }),
+            (Some("follower"), None) => {
+                return Err(ConfigError::Invalid(
+                    "replication.mode = \"follower\" needs replication.leader_url".into(),
+                ))
+            }
+            (Some("leader"), Some(_)) => {
+                return Err(ConfigError::Invalid(
+                    "replication.leader_url is only valid with mode = \"follower\"".into(),
+                ))
+            }
+            (Some(other), _) => {
+                return Err(ConfigError::Invalid(format!(
+                    "unknown replication mode {other:?} (expected leader or follower)"
+                )))
+            }
+        };
+        set(&mut cfg.mode, mode, "VALORI_FOLLOWER_OF");
+        set(
+            &mut cfg.replication_token,
+            r.token.map(Some),
+            "VALORI_REPLICATION_TOKEN",
+        );
+        let compression = r
+            .compression
+            .as_deref()
+            .map(crate::replication_compression::StreamCompression::from_name)
+            .transpose()
+            .map_err(ConfigError::Invalid)?;
+        set(
+            &mut cfg.replication_compression,
+            compression,
+            "VALORI_REPLICATION_COMPRESSION",
+        );
+
+        let s = self.snapshot;
+        set(
+            &mut cfg.snapshot_every_events,
+            s.every_events.map(Some),
+            "VALORI_SNAPSHOT_EVERY_EVENTS",
+        );
+        set(
+            &mut cfg.snapshot_every_bytes,
+            s.every_bytes.map(Some),
+            "VALORI_SNAPSHOT_EVERY_BYTES",
+        );
+        set(
+            &mut cfg.snapshot_keep,
+            s.keep.map(Some),
+            "VALORI_SNAPSHOT_KEEP",
+        );
+        set(
+            &mut cfg.snapshot_keep_daily,
+            s.keep_daily.map(Some),
+            "VALORI_SNAPSHOT_KEEP_DAILY",
+        );
+        set(
+            &mut cfg.snapshot_keep_checkpoints,
+            s.keep_checkpoints,
+            "VALORI_SNAPSHOT_KEEP_CHECKPOINTS",
+        );
+        set(
+            &mut cfg.auto_snapshot_interval_secs,
+            s.interval_secs.map(Some),
+            "VALORI_SNAPSHOT_INTERVAL",
+        );
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn file_overlays_defaults_and_validates() {
+        let file = ConfigFile::parse(
+            r#"
+            bind = "127.0.0.1:4100"
+            unix_socket = "/run/valori.sock"
+            [capacity]
+            dim = 64
+            [paths]
+            snapshot = "/data/snap.bin"
+            [index]
+            kind = "hnsw"
+            quantization = "scalar"
+            extra = ["brute_force"]
+            [auth.roles]
+            reader = "tok_r"
+            [replication]
+            leader_url = "http://leader:3000"
+            [snapshot]
+            keep = 3
+            "#,
+        )
+        .unwrap();
+        let mut cfg = NodeConfig::default();
+        file.apply(&mut cfg).unwrap();
+        cfg.validate().unwrap();
+        assert_eq!(cfg.bind_addr.port(), 4100);
+        assert_eq!(cfg.unix_socket, Some(PathBuf::from("/run/valori.sock")));
+        assert_eq!(cfg.dim, 64);
+        assert_eq!(cfg.index_kind, IndexKind::Hnsw);
+        assert_eq!(cfg.quantization_kind, QuantizationKind::Scalar);
+        assert_eq!(cfg.extra_indexes, vec![IndexKind::BruteForce]);
+        assert_eq!(cfg.auth_roles.len(), 1);
+        assert_eq!(
+            cfg.mode,
+            NodeMode::Follower {
+                leader_url: "http://leader:3000".into()
+            }
+        );
+        assert_eq!(cfg.snapshot_keep, Some(3));
+    }
+
+    #[test]
+    fn mistakes_are_rejected() {
+        let apply = |raw: &str| {
+            let mut cfg = NodeConfig::default();
+            ConfigFile::parse(raw)?.apply(&mut cfg)?;
+            cfg.validate()
+        };
+        assert!(matches!(apply("dimm = 3"), Err(ConfigError::Parse(_))));
+        assert!(matches!(
+            apply("[index]\nkind = \"hnws\""),
+            Err(ConfigError::Invalid(_))
+        ));
+        assert!(matches!(
+            apply("[replication]\nmode = \"follower\""),
+            Err(ConfigError::Invalid(_))
+        ));
+        assert!(matches!(
+            apply("[auth.roles]\nroot = \"x\""),
+            Err(ConfigError::Invalid(_))
+        ));
+        assert!(matches!(
+            apply("[capacity]\ndim = 0"),
+            Err(ConfigError::Invalid(_))
+        ));
+        assert!(matches!(
+            ConfigFile::load(Path::new("valori.yaml")),
+            Err(ConfigError::UnsupportedFormat(_))
+        ));
+    }
+
+    #[test]
+    fn config_path_argument_forms() {
+        let args = |a: &[&str]| {
+            a.iter()
+                .map(|s| s.to_string())
+                .collect::<Vec<_>>()
+                .into_iter()
+        };
+        assert_eq!(
+            config_path_from_args(args(&["valori-node", "--config", "a.toml"])).unwrap(),
+            Some(PathBuf::from("a.toml"))
+        );
+        assert_eq!(
+            config_path_from_args(args(&["valori-node", "--config=b.toml"])).unwrap(),
+            Some(PathBuf::from("b.toml"))
+        );
+        assert!(matches!(
+            config_path_from_args(args(&["valori-node", "--config"])),
+            Err(ConfigError::MissingValue(_))
+        ));
+    }
+
+    #[test]
+    fn cli_flags_override_file_values() {
+        let args = |a: &[&str]| {
+            a.iter()
+                .map(|s| s.to_string())
+                .collect::<Vec<_>>()
+                .into_iter()
+        };
+        let mut cfg = NodeConfig::default();
+        ConfigFile::parse("bind = \"127.0.0.1:4100\"\n[paths]\nsnapshot = \"/file/snap.bin\"")
+            .unwrap()
+            .apply(&mut cfg)
+            .unwrap();
+        apply_cli_flags(
+            &mut cfg,
+            args(&[
+                "--config",
+                "ignored.toml",
+                "--bind=127.0.0.1:4200",
+                "--unix-socket",
+                "/cli/valori.sock",
+                "--snapshot",
+                "/cli/snap.bin",
+                "--leader-url",
+                "http://leader:3000",
+                "--health-check",
+            ]),
+        )
+        .unwrap();
+        assert_eq!(cfg.bind_addr.port(), 4200);
+        assert_eq!(cfg.unix_socket, Some(PathBuf::from("/cli/valori.sock")));
+        assert_eq!(cfg.snapshot_path, Some(PathBuf::from("/cli/snap.bin")));
+        assert_eq!(
+            cfg.mode,
+            NodeMode::Follower {
+                leader_url: "http://leader:3000".into()
+            }
+        );
+
+        assert!(matches!(
+            apply_cli_flags(&mut cfg, args(&["--bnd", "x"])),
+            Err(ConfigError::UnknownFlag(_))
+        ));
+        assert!(matches!(
+            apply_cli_flags(&mut cfg, args(&["--wal"])),
+            Err(ConfigError::MissingValue(_))
+        ));
+    }
+}
@@ -0,0 +1,71 @@
+// Copyright (c) 2025 Varshith Gudur. Dual-licensed under MIT OR Apache-2.0.
+//! Background consistency sentinel.
+//!
+//! `GET /v1/admin/check` only runs when an operator asks. With
+//! `VALORI_CONSISTENCY_CHECK_SECS` set, this task runs the same
+//! [`Engine::check_consistency`](crate::engine::Engine::check_consistency)
+//! on a timer: it re-hashes the kernel state against the event committer's
+//! incrementally-maintained live state, and checks index membership against
+//! the record pool. A failing check sets `Engine::consistency_drift`, which
+//! turns `/health` into `"drift"` / 503 so a load balancer stops routing
+//! here; a later passing check clears it again.
+//!
+//! Metrics: `valori_consistency_ok` (1/0) and
+//! `valori_consistency_drift_detections_total`.
+
+use crate::engine::ConsistencyReport;
+use crate::server::SharedEngine;
+
+/// Run one check and record the outcome on the engine and in metrics.
+/// `None` only if the check itself panicked.
+pub async fn check_once(state: &SharedEngine) -> Option<ConsistencyReport> {
+    let for_check = state.clone();
+    let report =
+        match tokio::task::spawn_blocking(move || for_check.blocking_read().check_consistency())
+            .await
+        {
+            Ok(r) => r,
+            Err(e) => {
+                tracing::error!("Consistency check task panicked: {:?}", e);
+                return None;
+            }
+        };
+
+    let was_drifted = {
+        let mut engine = state.write().await;
+        std::mem::replace(&mut engine.consistency_drift, !report.ok)
+    };
+
+    metrics::gauge!("valori_consistency_ok", if report.ok { 1.0 } else { 0.0 });
+    if report.ok {
+        if was_drifted {
+            tracing::info!("Consistency check passing again — drift flag cleared");
+        }
+    } else {
+        metrics::counter!("valori_consistency_drift_detections_total", 1);
+        tracing::error!(
+            kernel = ?report.kernel,
+            index_missing = ?report.index_missing,
+            index_stale = ?report.index_stale,
+            record_to_node = ?report.record_to_node,
+            committer_diverged = report.committer_diverged,
+            "CONSISTENCY DRIFT detected by background sentinel"
+        );
+    }
+    Some(report)
+}
+
+/// Spawn the periodic check. The first tick fires immediately.
+pub fn spawn_consistency_sentinel(
+    state: SharedEngine,
+    interval_secs: u64,
+) -> tokio::task::JoinHandle<()> {
+    tokio::spawn(async move {
+        let mut interval = tokio::time::interval(std::time::Duration::from_secs(interval_secs));
+        interval.set_missed_tick_behavior(tokio::time::MissedTickBehavior::Skip);
+        loop {
+            interval.tick().await;
+            check_once(&state).await;
+        }
+    })
+}
@@ -0,0 +1,296 @@
+// Copyright (c) 2025 Varshith Gudur. Dual-licensed under MIT OR Apache-2.0.
+//! Semantic drift — do recent inserts still look like the history?
+//!
+//! Statistics are rebuilt from the committed event journal, so they are a
+//! pure function of the log: two replicas at the same height report the
+//! same numbers. For every tag, inserts are split at `height - window` into
+//! a *baseline* (everything before) and a *recent* window. Per side we keep
+//! the Q16.16 centroid (i128 sums, truncating division — as in kernel
+//! k-means) and, for the baseline, its spread: the mean L2 distance of its
+//! vectors to their centroid.
+//!
+//! `shift` is the L2 distance between the two centroids and `score` is
+//! `shift / spread`. A tag drifts when `score > threshold`: the recent
+//! centroid has moved further than a typical historical vector sits from
+//! the historical centre. With `buckets > 0` the journal is also cut into
+//! equal height ranges and each range's centroid is compared against
+//! everything before it, showing when a shift started.
+//!
+//! Only plaintext inserts (`InsertRecord` / `AutoInsertRecord`) carry a
+//! vector; deletes do not rewrite history.
+
+use serde::{Deserialize, Serialize};
+use std::collections::BTreeMap;
+use valori_kernel::event::KernelEvent;
+use valori_kernel::types::vector::FxpVector;
+
+/// Q16.16 → float units for reported distances.
+use valori_protocol::fxp::SCALE_F64 as SCALE;
+
+/// Running per-dimension sums of one population.
+#[derive(Clone, Default)]
+struct Moments {
+    count: u64,
+    sums: Vec<i128>,
+}
+
+impl Moments {
+    fn add(&mut self, v: &FxpVector) {
+        if self.sums.len() < v.len() {
+            self.sums.resize(v.len(), 0);
+        }
+        for (sum, s) in self.sums.iter_mut().zip(v.as_slice()) {
+            *sum += s.0 as i128;
+        }
+        self.count += 1;
+    }
+
+    fn centroid(&self) -> Vec<i64> {
+        let n = self.count.max(1) as i128;
+        self.sums.iter().map(|s| (s / n) as i64).collect()
+    }
+}
+
+fn l2(a: &[i64], b: &[i64]) -> f64 {
+    let len = a.len().max(b.len());
+    let sq: i128 = (0..len)
+        .map(|i| {
+            let d = *a.get(i).unwrap_or(&0) as i128 - *b.get(i).unwrap_or(&0) as i128;
+            d * d
+        })
+        .sum();
+    (sq as f64).sqrt() / SCALE
+}
+
+fn as_i64(v: &FxpVector) -> Vec<i64> {
+    v.as_slice().iter().map(|s| s.0 as i64).collect()
+}
+
+/// One bucket of the per-tag drift series.
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+pub struct DriftPoint {
+    /// Height range `(from_height, to_height]`.
+    pub from_height: u64,
+    pub to_height: u64,
+    pub count: u64,
+    /// Distance from this bucket's centroid to the centroid of all earlier
+    /// inserts; `None` for the first bucket with data.
+    pub shift: Option<f64>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+pub struct TagDrift {
+    pub tag: u64,
+    pub baseline_count: u64,
+    pub recent_count: u64,
+    /// Mean distance of baseline vectors to the baseline centroid.
+    pub baseline_spread: Option<f64>,
+    /// Distance between the baseline and recent centroids.
+    pub shift: Option<f64>,
+    /// `shift / baseline_spread`; `None` without both populations or with
+    /// zero spread.
+    pub score: Option<f64>,
+    pub drifted: bool,
+    #[serde(skip_serializing_if = "Vec::is_empty", default)]
+    pub series: Vec<DriftPoint>,
+}
+
+/// `GET /v1/analytics/drift` response.
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+pub struct DriftReport {
+    /// Committed height the report was computed at.
+    pub height: u64,
+    /// Recent window = heights `(baseline_height, height]`.
+    pub baseline_height: u64,
+    pub window: u64,
+    pub threshold: f64,
+    /// Tags with `drifted == true`, ascending.
+    pub drifted_tags: Vec<u64>,
+    /// Every tag with at least one insert, ascending.
+    pub tags: Vec<TagDrift>,
+}
+
+/// Drift statistics over `events` (the committed journal, height = index + 1).
+pub fn drift_report(
+    events: &[KernelEvent],
+    window: u64,
+    threshold: f64,
+    tag_filter: Option<u64>,
+    buckets: u64,
+) -> DriftReport {
+    let height = events.len() as u64;
+    let baseline_height = height.saturating_sub(window);
+
+    // (height, tag, vector) for every insert.
+    let inserts: Vec<(u64, u64, &FxpVector)> = events
+        .iter()
+        .enumerate()
+        .filter_map(|(i, e)| match e {
+            KernelEvent::InsertRecord { vector, tag, .. }
+            | KernelEvent::AutoInsertRecord { vector, tag, .. } => {
+                Some((i as u64 + 1, *tag, vector))
+            }
+            _ => None,
+        })
+        .filter(|(_, tag, _)| tag_filter.map_or(true, |t| t == *tag))
+        .collect();
+
+    let mut baseline: BTreeMap<u64, Moments> = BTreeMap::new();
+    let mut recent: BTreeMap<u64, Moments> = BTreeMap::new();
+    for &(h, tag, v) in &inserts {
+        let side = if h > baseline_height {
+            &mut recent
+        } else {
+            &mut baseline
+        };
+        side.entry(tag).or_default().add(v);
+        // Keep every tag present in both maps so the report lists it once.
+        if h > baseline_height {
+            baseline.entry(tag).or_default();
+        } else {
+            recent.entry(tag).or_default();
+        }
+    }
+
+    let mut tags = Vec::with_capacity(baseline.len());
+    for (&tag, base) in &baseline {
+        let rec = &recent[&tag];
+        let base_c = base.centroid();
+
+        let baseline_spread = (base.count > 0).then(|| {
+            let total: f64 = inserts
+                .iter()
+                .filter(|(h, t, _)| *t == tag && *h <= baseline_height)
+                .map(|(_, _, v)| l2(&as_i64(v), &base_c))
+                .sum();
+            total / base.count as f64
+        });
+        let shift = (base.count > 0 && rec.count > 0).then(|| l2(&base_c, &rec.centroid()));
+        let score = match (shift, baseline_spread) {
+            (Some(s), Some(spread)) if spread > 0.0 => Some(s / spread),
+            _ => None,
+        };
+        // Zero spread: every historical vector was identical, so any
+        // movement at all is drift.
+        let drifted = match (score, shift) {
+            (Some(score), _) => score > threshold,
+            (None, Some(s)) => s > 0.0,
+            _ => false,
+        };
+
+        tags.push(TagDrift {
+            tag,
+            baseline_count: base.count,
+            recent_count: rec.count,
+            baseline_spread,
+            shift,
+            score,
+            drifted,
+            series: series(&inserts, tag, height, buckets),
+        });
+    }
+
+    DriftReport {
+        height,
+        baseline_height,
+        window,
+        threshold,
+        drifted_tags: tags.iter().filter(|t| t.drifted).map(|t| t.tag).collect(),
+        tags,
+    }
+}
+
+/// Per-bucket centroid shift for one tag (empty when `buckets == 0`).
+fn series(
+    inserts: &[(u64, u64, &FxpVector)],
+    tag: u64,
+    height: u64,
+    buckets: u64,
+) -> Vec<DriftPoint> {
+    if buckets == 0 || height == 0 {
+        return Vec::new();
+    }
+    let width = height.div_ceil(buckets);
+    // `inserts` is in height order, so one pass fills every bucket.
+    let mut mine = inserts.iter().filter(|(_, t, _)| *t == tag).peekable();
+    let mut before = Moments::default();
+    let mut out = Vec::new();
+    let mut from = 0;
+    while from < height {
+        let to = (from + width).min(height);
+        let mut bucket = Moments::default();
+        while let Some((_, _, v)) = mine.next_if(|(h, _, _)| *h <= to) {
+            bucket.add(v);
+        }
+        let shift = (before.count > 0 && bucket.count > 0)
+            .then(|| l2(&before.centroid(), &bucket.centroid()));
+        out.push(DriftPoint {
+            from_height: from,
+            to_height: to,
+            count: bucket.count,
+            shift,
+        });
+        before.count += bucket.count;
+        if before.sums.len() < bucket.sums.len() {
+            before.sums.resize(bucket.sums.len(), 0);
+        }
+        for (a, b) in before.sums.iter_mut().zip(&bucket.sums) {
+            *a += b;
+        }
+        from = to;
+    }
+    out
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use valori_kernel::types::id::RecordId;
+    use valori_kernel::types::scalar::FxpScalar;
+
+    fn insert(i: u32, tag: u64, x: i32) -> KernelEvent {
+        KernelEvent::InsertRecord {
+            id: RecordId(i),
+            vector: FxpVector {
+                data: vec![FxpScalar(x), FxpScalar(0)],
+            },
+            metadata: None,
+            tag,
+        }
+    }
+
+    #[test]
+    fn flags_tags_whose_recent_centroid_moves() {
+        // Tag 1 alternates around 0 then jumps to 10; tag 2 stays put.
+        let one = 1 << 16;
+        let mut events = Vec::new();
+        for i in 0..20u32 {
+            let x = if i % 2 == 0 { one } else { -one };
+            events.push(insert(2 * i, 1, x));
+            events.push(insert(2 * i + 1, 2, x));
+        }
+        for i in 40..44u32 {
+            events.push(insert(i, 1, 10 * one));
+        }
+        for i in 44..48u32 {
+            events.push(insert(i, 2, if i % 2 == 0 { one } else { -one }));
+        }
+
+        let r = drift_report(&events, 8, 1.0, None, 0);
+        assert_eq!(r.height, 48);
+        assert_eq!(r.baseline_height, 40);
+        assert_eq!(r.drifted_tags, vec![1]);
+        let t1 = &r.tags[0];
+        assert_eq!((t1.baseline_count, t1.recent_count), (20, 4));
+        assert_eq!(t1.baseline_spread, Some(1.0));
+        assert_eq!(t1.shift, Some(10.0));
+        assert_eq!(t1.score, Some(10.0));
+        assert_eq!(r.tags[1].shift, Some(0.0));
+        assert!(!r.tags[1].drifted);
+
+        let only2 = drift_report(&events, 8, 1.0, Some(2), 4);
+        assert_eq!(only2.tags.len(), 1);
+        assert_eq!(only2.tags[0].series.len(), 4);
+        assert_eq!(only2.tags[0].series[0].shift, None);
+    }
+}
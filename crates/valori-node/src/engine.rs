@@ -5,8 +5,11 @@
 //! without changes — they just need `use valori_node::EngineFromNodeConfig;`.
 
 pub use valori_engine::{
-    CommitError, Engine, EngineConfig, EngineError, EngineHealth, ExecutionResources, IndexKind,
-    MetadataStore, Persistence, PoolStats, QuantizationKind, RecoveryMode,
+    check_snapshot, BatchDeleteReport, CommitError, ConsistencyReport, DedupInsert, Engine,
+    EngineConfig, EngineError, EngineHealth, ExecutionResources, ForgetCandidate, ForgetPolicy,
+    IndexKind, MetadataStore, OnDuplicate, Persistence, PoolStats, QuantizationKind, RecallReport,
+    RecordAccess, RecoveryMode, RecoveryPolicy, RecoveryVerification, SnapshotCheck,
+    SnapshotCheckPolicy, TxOp, TxRef, VacuumReport,
 };
 
 use crate::config::NodeConfig;
@@ -19,12 +22,20 @@ use std::sync::Arc;
 /// Trait lives here (not in `valori-engine`) to avoid an orphan: both the
 /// type (`Engine`) and this impl belong to different crates, so we define the
 /// trait in `valori-node`, which owns `NodeConfig`.
-pub trait EngineFromNodeConfig {
+pub trait EngineFromNodeConfig: Sized {
+    /// Panics where [`try_new`](Self::try_new) fails.
     fn new(cfg: &NodeConfig) -> Self;
+    /// Fails when the configured vector file cannot be set up; see
+    /// [`Engine::try_with_config`].
+    fn try_new(cfg: &NodeConfig) -> Result<Self, EngineError>;
 }
 
 impl EngineFromNodeConfig for Engine {
     fn new(cfg: &NodeConfig) -> Self {
+        Self::try_new(cfg).unwrap_or_else(|e| panic!("engine construction failed: {e}"))
+    }
+
+    fn try_new(cfg: &NodeConfig) -> Result<Self, EngineError> {
         let vault: Arc<dyn valori_kernel::crypto::KeyVault> = {
             use crate::crypto_vault::AesGcmVault;
             use valori_kernel::crypto::KeyVault;
@@ -49,6 +60,7 @@ impl EngineFromNodeConfig for Engine {
             max_edges: cfg.max_edges,
             index_kind: cfg.index_kind,
             quantization_kind: cfg.quantization_kind,
+            extra_indexes: cfg.extra_indexes.clone(),
             hnsw_m: cfg.hnsw_m,
             hnsw_ef_construction: cfg.hnsw_ef_construction,
             hnsw_ef_search: cfg.hnsw_ef_search,
@@ -58,18 +70,42 @@ impl EngineFromNodeConfig for Engine {
             wal_path: cfg.wal_path.clone(),
             event_log_path: cfg.event_log_path.clone(),
             event_log_rotation_bytes: cfg.event_log_rotation_bytes,
+            event_log_sync: cfg.event_log_sync,
+            quarantine_corrupt_segments: cfg.quarantine_corrupt_segments,
+            vector_file_path: cfg.vector_file_path.clone(),
             decay_half_life_secs: cfg.decay_half_life_secs,
             shard_count: cfg.shard_count,
+            anomaly_k: cfg.anomaly_k,
+            anomaly_threshold: cfg.anomaly_threshold,
+            forget_policy: cfg.forget_policy.clone(),
+            snapshot_check: cfg.snapshot_check,
+            recovery_policy: cfg.recovery_policy,
+            metadata_policy: cfg.metadata_policy,
+            metadata_index_fields: cfg.metadata_index_fields.clone(),
             object_store_keep: cfg.object_store_keep,
             object_store: crate::object_store::ObjectStoreBackend::from_env(),
             vault,
             embed_config: embed_config_from_node(cfg),
         };
 
-        Engine::with_config(engine_cfg)
+        Engine::try_with_config(engine_cfg)
     }
 }
 
+/// Dry-run recovery for `cfg` (`VALORI_VERIFY_RECOVERY`). The engine is
+/// built with no event log or WAL path, so nothing is opened for writes; the
+/// configured files are only read. See [`Engine::verify_recovery`].
+pub fn verify_recovery(cfg: &NodeConfig) -> RecoveryVerification {
+    let scratch_cfg = NodeConfig {
+        event_log_path: None,
+        wal_path: None,
+        shred_log_path: None,
+        ..cfg.clone()
+    };
+    Engine::new(&scratch_cfg)
+        .verify_recovery(cfg.event_log_path.as_deref(), cfg.wal_path.as_deref())
+}
+
 pub(crate) fn embed_config_from_node(cfg: &NodeConfig) -> Option<valori_ingest::EmbedConfig> {
     let provider = cfg.embed_provider.clone()?;
     let model = cfg
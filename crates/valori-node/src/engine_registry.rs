@@ -0,0 +1,734 @@
+// Copyright (c) 2025 Varshith Gudur. Dual-licensed under MIT OR Apache-2.0.
+//! Collections with their own configuration (`/v1/collections`).
+//!
+//! Namespaces (`/v1/namespaces`) share the node engine and so its
+//! dimension, capacity and index. A collection here is a separate
+//! [`Engine`] with its own [`CollectionSpec`], so one process can serve
+//! several embedding models side by side.
+//!
+//! With a root directory (`VALORI_COLLECTIONS_DIR`) every collection lives
+//! in `<root>/<name>/`: `collection.json` holds the spec and `events.log` the
+//! collection's own event log, from which it is recovered at startup.
+//! Without one, collections are kept in memory and lost on restart.
+//!
+//! A collection restored from a snapshot ([`EngineRegistry::restore`]) keeps
+//! that snapshot as `base.snap` and starts a new log on top of it; startup
+//! then restores the base and replays the log after it.
+//!
+//! Each collection proves its own state ([`CollectionProof`]), and
+//! [`EngineRegistry::root_proof`] commits to all of them at once, so an
+//! audit can check one tenant without the others' data.
+
+use crate::commit::Persistence;
+use crate::config::NodeConfig;
+use crate::engine::{Engine, IndexKind, QuantizationKind, RecoveryMode};
+use crate::events::event_commit::EventCommitter;
+use crate::events::event_journal::EventJournal;
+use crate::events::event_log::EventLogWriter;
+use crate::server::SharedEngine;
+use crate::EngineFromNodeConfig;
+use axum::http::StatusCode;
+use axum::response::{IntoResponse, Response};
+use axum::Json;
+use serde::{Deserialize, Serialize};
+use std::collections::BTreeMap;
+use std::path::{Path, PathBuf};
+use std::sync::{Arc, RwLock};
+use valori_kernel::proof::DeterministicProof;
+
+/// File in a collection directory holding its [`CollectionSpec`].
+pub const SPEC_FILE: &str = "collection.json";
+/// The collection's event log, next to [`SPEC_FILE`].
+pub const EVENT_LOG_FILE: &str = "events.log";
+/// Snapshot the event log continues from, once the collection is restored.
+pub const BASE_SNAPSHOT_FILE: &str = "base.snap";
+/// Where a restore moves the log it replaces, under a per-restore directory.
+pub const REPLACED_DIR: &str = "replaced";
+/// A snapshot being restored, before it becomes [`BASE_SNAPSHOT_FILE`].
+const STAGED_BASE_FILE: &str = ".base.snap.tmp";
+
+/// Domain tag of a [`collection_leaf`].
+const DOMAIN_COLLECTION: &[u8] = b"VALORI_COLLECTION";
+
+/// What a collection is created with. Fixed for its lifetime.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub struct CollectionSpec {
+    pub dim: usize,
+    pub max_records: usize,
+    pub index: IndexKind,
+    pub quantization: QuantizationKind,
+}
+
+impl CollectionSpec {
+    /// Within the limits every build accepts: the kernel refuses a wider
+    /// vector or a larger pool when it is replayed or decoded.
+    fn validate(&self) -> Result<(), RegistryError> {
+        valori_protocol::check_dim(u32::try_from(self.dim).unwrap_or(u32::MAX))
+            .map_err(|e| RegistryError::Invalid(e.to_string()))?;
+        if !(1..=valori_protocol::MAX_RECORDS).contains(&self.max_records) {
+            return Err(RegistryError::Invalid(format!(
+                "max_records must be between 1 and {}",
+                valori_protocol::MAX_RECORDS
+            )));
+        }
+        Ok(())
+    }
+}
+
+#[derive(Debug, thiserror::Error)]
+pub enum RegistryError {
+    #[error("collection '{0}' already exists")]
+    Exists(String),
+    #[error("collection '{0}' not found")]
+    NotFound(String),
+    #[error("{0}")]
+    Invalid(String),
+    #[error("collection storage failed: {0}")]
+    Io(#[from] std::io::Error),
+}
+
+impl IntoResponse for RegistryError {
+    fn into_response(self) -> Response {
+        let status = match self {
+            RegistryError::Exists(_) => StatusCode::CONFLICT,
+            RegistryError::NotFound(_) => StatusCode::NOT_FOUND,
+            RegistryError::Invalid(_) => StatusCode::BAD_REQUEST,
+            RegistryError::Io(_) => StatusCode::INTERNAL_SERVER_ERROR,
+        };
+        (
+            status,
+            Json(serde_json::json!({ "error": self.to_string() })),
+        )
+            .into_response()
+    }
+}
+
+/// A registered collection: its spec and its engine.
+#[derive(Clone)]
+pub struct Collection {
+    pub spec: CollectionSpec,
+    pub engine: SharedEngine,
+}
+
+/// A collection's state proof and its leaf in the aggregate root.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct CollectionProof {
+    pub name: String,
+    pub proof: DeterministicProof,
+    pub state_root: [u8; 32],
+    pub version: u64,
+    pub leaf: [u8; 32],
+}
+
+impl CollectionProof {
+    /// The proof of `c`'s current state.
+    pub async fn of(name: &str, c: &Collection) -> Self {
+        let engine = c.engine.read().await;
+        let proof = engine.get_proof();
+        Self {
+            name: name.to_string(),
+            leaf: collection_leaf(name, &proof.final_state_hash),
+            state_root: engine.state_root(),
+            version: engine.state.version(),
+            proof,
+        }
+    }
+}
+
+/// Leaf for collection `name` in the aggregate root: BLAKE3 over a domain
+/// tag, the length-prefixed name and the collection's `final_state_hash`.
+/// Binding the name means two collections with equal state still differ.
+pub fn collection_leaf(name: &str, final_state_hash: &[u8; 32]) -> [u8; 32] {
+    let mut hasher = blake3::Hasher::new();
+    hasher.update(DOMAIN_COLLECTION);
+    hasher.update(&(name.len() as u32).to_le_bytes());
+    hasher.update(name.as_bytes());
+    hasher.update(final_state_hash);
+    *hasher.finalize().as_bytes()
+}
+
+/// Merkle root over collection leaves in name order
+/// ([`valori_kernel::proof::merkle_root`]); all zeroes with none.
+pub fn aggregate_root(proofs: &[CollectionProof]) -> [u8; 32] {
+    let leaves: Vec<[u8; 32]> = proofs.iter().map(|p| p.leaf).collect();
+    valori_kernel::proof::merkle_root(&leaves)
+}
+
+/// Every configured collection of the node, by name.
+pub struct EngineRegistry {
+    root: Option<PathBuf>,
+    /// Settings shared by every collection engine; the spec overrides the
+    /// shape and the paths are the collection's own.
+    base: NodeConfig,
+    collections: RwLock<BTreeMap<String, Collection>>,
+}
+
+impl EngineRegistry {
+    /// A registry whose collections are not persisted.
+    pub fn in_memory(base: NodeConfig) -> Self {
+        Self {
+            root: None,
+            base,
+            collections: RwLock::new(BTreeMap::new()),
+        }
+    }
+
+    /// Open the registry under `root`, recovering every collection found
+    /// there. A collection that fails to load is logged and skipped.
+    pub fn open(root: impl Into<PathBuf>, base: NodeConfig) -> std::io::Result<Self> {
+        let root = root.into();
+        std::fs::create_dir_all(&root)?;
+        let registry = Self {
+            root: Some(root.clone()),
+            base,
+            collections: RwLock::new(BTreeMap::new()),
+        };
+        let mut dirs: Vec<PathBuf> = std::fs::read_dir(&root)?
+            .filter_map(|entry| entry.ok().map(|e| e.path()))
+            .filter(|path| path.join(SPEC_FILE).is_file())
+            .collect();
+        dirs.sort();
+        let mut collections = BTreeMap::new();
+        for dir in dirs {
+            let Some(name) = dir.file_name().and_then(|n| n.to_str()) else {
+                continue;
+            };
+            let loaded = read_spec(&dir)
+                .map_err(RegistryError::from)
+                .and_then(|spec| Ok((spec, registry.build_engine(name, spec)?)));
+            match loaded {
+                Ok((spec, engine)) => {
+                    let collection = Collection {
+                        spec,
+                        engine: Arc::new(tokio::sync::RwLock::new(engine)),
+                    };
+                    collections.insert(name.to_string(), collection);
+                }
+                Err(e) => tracing::error!("Skipping collection {:?}: {}", dir, e),
+            }
+        }
+        *registry
+            .collections
+            .write()
+            .unwrap_or_else(|p| p.into_inner()) = collections;
+        Ok(registry)
+    }
+
+    /// Directory holding collection `name`, when the registry persists.
+    pub fn collection_dir(&self, name: &str) -> Option<PathBuf> {
+        self.root.as_ref().map(|root| root.join(name))
+    }
+
+    fn engine_config(&self, spec: CollectionSpec, event_log_path: Option<PathBuf>) -> NodeConfig {
+        NodeConfig {
+            dim: spec.dim,
+            max_records: spec.max_records,
+            index_kind: spec.index,
+            quantization_kind: spec.quantization,
+            extra_indexes: Vec::new(),
+            event_log_path,
+            snapshot_path: None,
+            wal_path: None,
+            vector_file_path: None,
+            shred_log_path: None,
+            forget_policy: None,
+            ..self.base.clone()
+        }
+    }
+
+    fn build_engine(&self, name: &str, spec: CollectionSpec) -> Result<Engine, RegistryError> {
+        let dir = self.collection_dir(name);
+        if let Some(dir) = dir.as_ref().filter(|d| d.join(BASE_SNAPSHOT_FILE).exists()) {
+            return self.recover_from_base(name, spec, dir);
+        }
+        let mut engine =
+            Engine::new(&self.engine_config(spec, dir.map(|d| d.join(EVENT_LOG_FILE))));
+        match engine.try_recover() {
+            RecoveryMode::EventLog(n) => {
+                tracing::info!("Collection '{}': recovered {} events", name, n)
+            }
+            RecoveryMode::Refused(reason) => {
+                tracing::error!("Collection '{}': recovery refused: {}", name, reason)
+            }
+            _ => {}
+        }
+        Ok(engine)
+    }
+
+    /// Restore the base snapshot, replay the log written since on top of it
+    /// and keep appending to that log. Engine recovery takes either a
+    /// snapshot or a log, never one after the other, so this is done here.
+    fn recover_from_base(
+        &self,
+        name: &str,
+        spec: CollectionSpec,
+        dir: &Path,
+    ) -> Result<Engine, RegistryError> {
+        let invalid = |what: &str, e: &dyn std::fmt::Display| {
+            RegistryError::Invalid(format!("collection '{name}': {what}: {e}"))
+        };
+        let base = std::fs::read(dir.join(BASE_SNAPSHOT_FILE))?;
+        let mut engine = Engine::new(&self.engine_config(spec, None));
+        engine
+            .restore(&base)
+            .map_err(|e| invalid("base snapshot", &e))?;
+
+        let log_path = dir.join(EVENT_LOG_FILE);
+        let dim = Some(spec.dim as u32);
+        let events = if log_path.exists() {
+            crate::events::event_replay::read_all_segments(&log_path, dim)
+                .map_err(|e| invalid("event log", &e))?
+        } else {
+            Vec::new()
+        };
+        for (ns, event) in &events {
+            engine
+                .ingest_event(event, *ns)
+                .map_err(|e| invalid("event log replay", &e))?;
+        }
+
+        let writer = EventLogWriter::open_with_sync(&log_path, dim, engine.event_log_sync)
+            .map_err(|e| invalid("event log", &e))?;
+        let journal = EventJournal::new_at_height(engine.state.version());
+        engine.persistence =
+            Persistence::EventLog(EventCommitter::new(writer, journal, engine.state.clone()));
+        tracing::info!(
+            "Collection '{}': restored base snapshot and {} events",
+            name,
+            events.len()
+        );
+        Ok(engine)
+    }
+
+    /// Create collection `name`. The name must already be validated.
+    pub fn create(&self, name: &str, spec: CollectionSpec) -> Result<Collection, RegistryError> {
+        spec.validate()?;
+        // Held throughout, so two creates of one name never share a log.
+        let mut collections = self.collections.write().unwrap_or_else(|p| p.into_inner());
+        if collections.contains_key(name) {
+            return Err(RegistryError::Exists(name.to_string()));
+        }
+        if let Some(dir) = self.collection_dir(name) {
+            // Left behind by a collection that failed to load; never reuse
+            // its log for a new one.
+            if dir.exists() {
+                return Err(RegistryError::Exists(name.to_string()));
+            }
+            std::fs::create_dir_all(&dir)?;
+            let json = serde_json::to_vec_pretty(&spec).map_err(std::io::Error::other)?;
+            std::fs::write(dir.join(SPEC_FILE), json)?;
+        }
+        let collection = Collection {
+            spec,
+            engine: Arc::new(tokio::sync::RwLock::new(self.build_engine(name, spec)?)),
+        };
+        collections.insert(name.to_string(), collection.clone());
+        Ok(collection)
+    }
+
+    pub fn get(&self, name: &str) -> Option<Collection> {
+        self.collections
+            .read()
+            .unwrap_or_else(|p| p.into_inner())
+            .get(name)
+            .cloned()
+    }
+
+    /// Every collection, by name.
+    pub fn list(&self) -> Vec<(String, Collection)> {
+        self.collections
+            .read()
+            .unwrap_or_else(|p| p.into_inner())
+            .iter()
+            .map(|(name, c)| (name.clone(), c.clone()))
+            .collect()
+    }
+
+    /// Replace collection `name`'s state with `snapshot`, as served by
+    /// `GET /v1/collections/:name/snapshot`. A snapshot that does not decode
+    /// or has another dimension is refused and the collection left as it
+    /// was.
+    ///
+    /// On disk the snapshot becomes the collection's `base.snap` and its log
+    /// starts over empty; the old log and base are moved to a fresh
+    /// `replaced/<unix secs>-<nanos>/`. The new engine is built before it
+    /// replaces the old one: if any step fails, the files are moved back and
+    /// the collection keeps serving its old state. Takes the engine lock with
+    /// `blocking_write`, so call it off the async runtime.
+    pub fn restore(&self, name: &str, snapshot: &[u8]) -> Result<(), RegistryError> {
+        let c = self
+            .get(name)
+            .ok_or_else(|| RegistryError::NotFound(name.to_string()))?;
+        let mut restored = Engine::new(&self.engine_config(c.spec, None));
+        restored
+            .restore(snapshot)
+            .map_err(|e| RegistryError::Invalid(e.to_string()))?;
+        if let Some(dim) = restored.state.dim.filter(|&d| d != c.spec.dim) {
+            return Err(RegistryError::Invalid(format!(
+                "snapshot has {dim} dimensions; the collection has {}",
+                c.spec.dim
+            )));
+        }
+
+        // Held until the swap, so nothing is appended to the old log while
+        // it is moved; the old engine keeps its open handle either way.
+        let mut engine = c.engine.blocking_write();
+        let Some(dir) = self.collection_dir(name) else {
+            *engine = restored;
+            return Ok(());
+        };
+        let mut undo = RestoreUndo::default();
+        match self.replace_files(name, c.spec, &dir, snapshot, &mut undo) {
+            Ok(built) => {
+                *engine = built;
+                Ok(())
+            }
+            Err(e) => {
+                if let Err(rollback) = undo.roll_back(&dir) {
+                    tracing::error!(
+                        "Collection '{}': rolling back a failed restore: {}",
+                        name,
+                        rollback
+                    );
+                }
+                Err(e)
+            }
+        }
+    }
+
+    /// File half of [`restore`](Self::restore): stage the snapshot, move
+    /// the old base and log aside, install the snapshot as the base and
+    /// build the engine from it. Each step is recorded in `undo`, so a
+    /// failure can be rolled back.
+    fn replace_files(
+        &self,
+        name: &str,
+        spec: CollectionSpec,
+        dir: &Path,
+        snapshot: &[u8],
+        undo: &mut RestoreUndo,
+    ) -> Result<Engine, RegistryError> {
+        let staged = dir.join(STAGED_BASE_FILE);
+        std::fs::write(&staged, snapshot)?;
+        let now = std::time::SystemTime::now()
+            .duration_since(std::time::UNIX_EPOCH)
+            .unwrap_or_default();
+        let replaced =
+            dir.join(REPLACED_DIR)
+                .join(format!("{}-{:09}", now.as_secs(), now.subsec_nanos()));
+        std::fs::create_dir_all(dir.join(REPLACED_DIR))?;
+        // `create_dir` fails on an existing directory: one restore's files
+        // are never mixed into another's.
+        std::fs::create_dir(&replaced)?;
+        undo.replaced = Some(replaced.clone());
+        for entry in std::fs::read_dir(dir)? {
+            let entry = entry?;
+            let file_name = entry.file_name();
+            let lossy = file_name.to_string_lossy();
+            if lossy.starts_with(EVENT_LOG_FILE) || lossy == BASE_SNAPSHOT_FILE {
+                let to = replaced.join(&file_name);
+                std::fs::rename(entry.path(), &to)?;
+                undo.moved.push((entry.path(), to));
+            }
+        }
+        let base = dir.join(BASE_SNAPSHOT_FILE);
+        std::fs::rename(&staged, &base)?;
+        undo.moved.push((staged, base));
+        undo.installed = true;
+        self.build_engine(name, spec)
+    }
+
+    /// Proof of every collection, in name order, with their aggregate root.
+    /// Each collection is read under its own lock in turn, so the set is not
+    /// one instant across collections.
+    pub async fn root_proof(&self) -> ([u8; 32], Vec<CollectionProof>) {
+        let mut proofs = Vec::new();
+        for (name, c) in self.list() {
+            proofs.push(CollectionProof::of(&name, &c).await);
+        }
+        (aggregate_root(&proofs), proofs)
+    }
+
+    /// Drop collection `name` and delete its directory. The delete runs on
+    /// the blocking pool, since a large collection can take a while.
+    pub async fn remove(&self, name: &str) -> Result<(), RegistryError> {
+        let removed = self
+            .collections
+            .write()
+            .unwrap_or_else(|p| p.into_inner())
+            .remove(name);
+        if removed.is_none() {
+            return Err(RegistryError::NotFound(name.to_string()));
+        }
+        if let Some(dir) = self.collection_dir(name) {
+            tokio::task::spawn_blocking(move || std::fs::remove_dir_all(dir))
+                .await
+                .map_err(std::io::Error::other)??;
+        }
+        Ok(())
+    }
+}
+
+/// What a failed [`EngineRegistry::restore`] has to undo.
+#[derive(Default)]
+struct RestoreUndo {
+    /// The per-restore directory under [`REPLACED_DIR`], once created.
+    replaced: Option<PathBuf>,
+    /// Renames done, as `(from, to)`, in order.
+    moved: Vec<(PathBuf, PathBuf)>,
+    /// Whether the snapshot was installed as the base, so that any log in
+    /// the collection directory is the new engine's.
+    installed: bool,
+}
+
+impl RestoreUndo {
+    /// Put the collection directory back as it was before the restore.
+    fn roll_back(&self, dir: &Path) -> std::io::Result<()> {
+        if self.installed {
+            for entry in std::fs::read_dir(dir)? {
+                let entry = entry?;
+                if entry
+                    .file_name()
+                    .to_string_lossy()
+                    .starts_with(EVENT_LOG_FILE)
+                {
+                    std::fs::remove_file(entry.path())?;
+                }
+            }
+        }
+        for (from, to) in self.moved.iter().rev() {
+            std::fs::rename(to, from)?;
+        }
+        let staged = dir.join(STAGED_BASE_FILE);
+        if staged.exists() {
+            std::fs::remove_file(staged)?;
+        }
+        if let Some(replaced) = &self.replaced {
+            std::fs::remove_dir(replaced)?;
+        }
+        Ok(())
+    }
+}
+
+fn read_spec(dir: &Path) -> std::io::Result<CollectionSpec> {
+    let bytes = std::fs::read(dir.join(SPEC_FILE))?;
+    serde_json::from_slice(&bytes).map_err(std::io::Error::other)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn spec(dim: usize) -> CollectionSpec {
+        CollectionSpec {
+            dim,
+            max_records: 100,
+            index: IndexKind::BruteForce,
+            quantization: QuantizationKind::None,
+        }
+    }
+
+    #[tokio::test]
+    async fn collections_survive_reopen() {
+        let dir = tempfile::tempdir().unwrap();
+        {
+            let registry = EngineRegistry::open(dir.path(), NodeConfig::default()).unwrap();
+            let small = registry.create("small", spec(2)).unwrap();
+            registry.create("wide", spec(8)).unwrap();
+            assert!(matches!(
+                registry.create("small", spec(2)),
+                Err(RegistryError::Exists(_))
+            ));
+            small
+                .engine
+                .write()
+                .await
+                .insert_record_from_f32(&[0.5, 0.25])
+                .unwrap();
+        }
+
+        let registry = EngineRegistry::open(dir.path(), NodeConfig::default()).unwrap();
+        let names: Vec<String> = registry.list().into_iter().map(|(n, _)| n).collect();
+        assert_eq!(names, ["small", "wide"]);
+        let small = registry.get("small").unwrap();
+        assert_eq!(small.spec, spec(2));
+        assert_eq!(small.engine.read().await.record_count(), 1);
+        assert_eq!(registry.get("wide").unwrap().engine.read().await.dim, 8);
+
+        registry.remove("wide").await.unwrap();
+        assert!(!dir.path().join("wide").exists());
+        assert!(matches!(
+            registry.remove("wide").await,
+            Err(RegistryError::NotFound(_))
+        ));
+    }
+
+    #[tokio::test]
+    async fn restored_collection_keeps_its_state_and_proof_across_reopen() {
+        let dir = tempfile::tempdir().unwrap();
+        let registry = Arc::new(EngineRegistry::open(dir.path(), NodeConfig::default()).unwrap());
+        let src = registry.create("src", spec(2)).unwrap();
+        registry.create("dst", spec(2)).unwrap();
+        registry.create("wide", spec(8)).unwrap();
+        {
+            let mut engine = src.engine.write().await;
+            engine.insert_record_from_f32(&[0.5, 0.25]).unwrap();
+            engine.insert_record_from_f32(&[-0.5, 0.75]).unwrap();
+        }
+        let snapshot = src.engine.read().await.snapshot().unwrap();
+
+        let restore = |name: &'static str| {
+            let registry = registry.clone();
+            let snapshot = snapshot.clone();
+            tokio::task::spawn_blocking(move || registry.restore(name, &snapshot))
+        };
+        assert!(matches!(
+            restore("wide").await.unwrap(),
+            Err(RegistryError::Invalid(_))
+        ));
+        restore("dst").await.unwrap().unwrap();
+
+        // Same state, different names: equal hashes, distinct leaves.
+        let (_, proofs) = registry.root_proof().await;
+        assert_eq!(proofs[0].proof, proofs[1].proof);
+        assert_ne!(proofs[0].leaf, proofs[1].leaf);
+
+        let dst = registry.get("dst").unwrap();
+        dst.engine
+            .write()
+            .await
+            .insert_record_from_f32(&[0.125, 0.125])
+            .unwrap();
+        let (root, proofs) = registry.root_proof().await;
+        let names: Vec<&str> = proofs.iter().map(|p| p.name.as_str()).collect();
+        assert_eq!(names, ["dst", "src", "wide"]);
+        assert_eq!(root, aggregate_root(&proofs));
+        assert!(dir.path().join("dst").join(BASE_SNAPSHOT_FILE).exists());
+        drop((src, dst));
+        drop(registry);
+
+        // The base snapshot plus the new log give back the same state.
+        let reopened = EngineRegistry::open(dir.path(), NodeConfig::default()).unwrap();
+        assert_eq!(
+            reopened
+                .get("dst")
+                .unwrap()
+                .engine
+                .read()
+                .await
+                .record_count(),
+            3
+        );
+        let (reopened_root, reopened_proofs) = reopened.root_proof().await;
+        assert_eq!(reopened_proofs, proofs);
+        assert_eq!(reopened_root, root);
+    }
+
+    /// Every file under `dir` with its contents, by relative path.
+    fn tree(dir: &Path) -> BTreeMap<PathBuf, Vec<u8>> {
+        let mut files = BTreeMap::new();
+        let mut stack = vec![dir.to_path_buf()];
+        while let Some(d) = stack.pop() {
+            for entry in std::fs::read_dir(d).unwrap() {
+                let path = entry.unwrap().path();
+                if path.is_dir() {
+                    stack.push(path);
+                } else {
+                    let bytes = std::fs::read(&path).unwrap();
+                    files.insert(path.strip_prefix(dir).unwrap().to_path_buf(), bytes);
+                }
+            }
+        }
+        files
+    }
+
+    #[tokio::test]
+    async fn failed_restore_keeps_the_old_engine_and_files() {
+        let dir = tempfile::tempdir().unwrap();
+        let registry = Arc::new(EngineRegistry::open(dir.path(), NodeConfig::default()).unwrap());
+        let c = registry.create("c", spec(2)).unwrap();
+        c.engine
+            .write()
+            .await
+            .insert_record_from_f32(&[0.5, 0.25])
+            .unwrap();
+        let snapshot = {
+            let mut other = Engine::new(&registry.engine_config(spec(2), None));
+            other.insert_record_from_f32(&[0.125, 0.5]).unwrap();
+            other.insert_record_from_f32(&[0.25, 0.5]).unwrap();
+            other.snapshot().unwrap()
+        };
+        let cdir = dir.path().join("c");
+
+        // Fails after the log and base are moved and the engine is built:
+        // rolling back restores the directory byte for byte.
+        let before = tree(&cdir);
+        let mut undo = RestoreUndo::default();
+        registry
+            .replace_files("c", spec(2), &cdir, &snapshot, &mut undo)
+            .unwrap();
+        assert!(undo.installed);
+        undo.roll_back(&cdir).unwrap();
+        assert_eq!(tree(&cdir), before);
+        assert!(!cdir.join(REPLACED_DIR).read_dir().unwrap().any(|_| true));
+
+        // Fails before any file moves: the old engine keeps serving and
+        // appending to its log.
+        std::fs::remove_dir(cdir.join(REPLACED_DIR)).unwrap();
+        std::fs::write(cdir.join(REPLACED_DIR), b"not a directory").unwrap();
+        let r = registry.clone();
+        let s = snapshot.clone();
+        let err = tokio::task::spawn_blocking(move || r.restore("c", &s))
+            .await
+            .unwrap();
+        assert!(matches!(err, Err(RegistryError::Io(_))));
+        assert!(!cdir.join(STAGED_BASE_FILE).exists());
+        assert!(!cdir.join(BASE_SNAPSHOT_FILE).exists());
+        {
+            let mut engine = c.engine.write().await;
+            assert_eq!(engine.record_count(), 1);
+            engine.insert_record_from_f32(&[-0.5, 0.25]).unwrap();
+        }
+        drop(c);
+        drop(registry);
+        let reopened = EngineRegistry::open(dir.path(), NodeConfig::default()).unwrap();
+        let c = reopened.get("c").unwrap();
+        assert_eq!(c.engine.read().await.record_count(), 2);
+    }
+
+    #[tokio::test]
+    async fn each_restore_moves_the_old_files_to_its_own_directory() {
+        let dir = tempfile::tempdir().unwrap();
+        let registry = Arc::new(EngineRegistry::open(dir.path(), NodeConfig::default()).unwrap());
+        let c = registry.create("c", spec(2)).unwrap();
+        let snapshot = c.engine.read().await.snapshot().unwrap();
+        for _ in 0..3 {
+            c.engine
+                .write()
+                .await
+                .insert_record_from_f32(&[0.5, 0.25])
+                .unwrap();
+            let r = registry.clone();
+            let s = snapshot.clone();
+            tokio::task::spawn_blocking(move || r.restore("c", &s))
+                .await
+                .unwrap()
+                .unwrap();
+        }
+        let replaced: Vec<PathBuf> = std::fs::read_dir(dir.path().join("c").join(REPLACED_DIR))
+            .unwrap()
+            .map(|e| e.unwrap().path())
+            .collect();
+        assert_eq!(replaced.len(), 3);
+        for d in &replaced {
+            assert!(d.join(EVENT_LOG_FILE).exists(), "{d:?}");
+        }
+        // The first restore had no base to move aside; the later ones did.
+        let with_base = replaced
+            .iter()
+            .filter(|d| d.join(BASE_SNAPSHOT_FILE).exists())
+            .count();
+        assert_eq!(with_base, 2);
+        assert_eq!(c.engine.read().await.record_count(), 0);
+    }
+}
@@ -0,0 +1,226 @@
+// Copyright (c) 2025 Varshith Gudur. Dual-licensed under MIT OR Apache-2.0.
+//! Streaming export (`GET /v1/export`).
+//!
+//! Streams one collection as NDJSON, one typed line per item:
+//!
+//! ```text
+//! {"type":"record","id":0,"vector":[…],"metadata":"…","tag":0}
+//! {"type":"node","id":0,"kind":0,"record":0}            (?graph=true)
+//! {"type":"edge","id":0,"kind":0,"from":0,"to":1}       (?graph=true)
+//! {"type":"meta","key":"record:0","value":{…}}          (?metadata=true)
+//! {"type":"end","records":1,"nodes":1,"edges":1,"meta":1,"height":7,"state_hash":"…"}
+//! ```
+//!
+//! Record lines are `/v1/admin/import` rows, and import skips the other
+//! types, so an export can be posted back as is. A stream without the `end`
+//! line was cut short.
+//!
+//! Items are read [`PAGE`] ids at a time, each page under its own read lock,
+//! so an export never holds the engine for long and never holds the whole
+//! collection in memory. Writes that land during the export may or may not
+//! be in it; `end` carries the height and state hash at the time it
+//! finished. For a point-in-time copy, download a snapshot instead.
+
+use crate::server::SharedEngine;
+use axum::body::Bytes;
+use serde::{Deserialize, Serialize};
+use tokio::sync::mpsc;
+use valori_kernel::types::id::{EdgeId, NodeId, RecordId};
+
+/// Ids read per engine lock.
+pub const PAGE: u32 = 256;
+
+/// What to export.
+#[derive(Debug, Clone, Copy)]
+pub struct ExportOptions {
+    /// Namespace of the collection to export.
+    pub ns: u16,
+    pub graph: bool,
+    pub metadata: bool,
+}
+
+/// One line of the export stream.
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq)]
+#[serde(tag = "type", rename_all = "snake_case")]
+pub enum ExportLine {
+    Record {
+        id: u32,
+        vector: Vec<f32>,
+        #[serde(default, skip_serializing_if = "Option::is_none")]
+        metadata: Option<String>,
+        tag: u64,
+    },
+    Node {
+        id: u32,
+        kind: u8,
+        #[serde(default, skip_serializing_if = "Option::is_none")]
+        record: Option<u32>,
+    },
+    Edge {
+        id: u32,
+        kind: u8,
+        from: u32,
+        to: u32,
+    },
+    Meta {
+        key: String,
+        value: serde_json::Value,
+    },
+    End {
+        records: u64,
+        nodes: u64,
+        edges: u64,
+        meta: u64,
+        height: u64,
+        state_hash: String,
+    },
+}
+
+/// Kernel pools exported by id.
+#[derive(Debug, Clone, Copy)]
+enum Pool {
+    Records = 0,
+    Nodes = 1,
+    Edges = 2,
+}
+
+/// Stream the export described by `opts` to `tx`, ending with an
+/// [`ExportLine::End`]. Stops early if the receiver is dropped.
+pub async fn run(state: SharedEngine, opts: ExportOptions, tx: mpsc::Sender<Bytes>) {
+    // Lines per pool, then metadata lines.
+    let mut counts = [0u64; 4];
+
+    let pools = [
+        (Pool::Records, true),
+        (Pool::Nodes, opts.graph),
+        (Pool::Edges, opts.graph),
+    ];
+    for (pool, enabled) in pools {
+        if !enabled {
+            continue;
+        }
+        let mut next = 0u32;
+        loop {
+            let (lines, end) = {
+                let engine = state.read().await;
+                read_page(&engine, pool, opts.ns, next)
+            };
+            counts[pool as usize] += lines.len() as u64;
+            if !send_all(&tx, &lines).await {
+                return;
+            }
+            if end {
+                break;
+            }
+            next += PAGE;
+        }
+    }
+
+    if opts.metadata {
+        // Keys only: values are fetched a page at a time like everything else.
+        let keys = state.read().await.metadata.keys();
+        for page in keys.chunks(PAGE as usize) {
+            let lines: Vec<ExportLine> = {
+                let engine = state.read().await;
+                page.iter()
+                    .filter_map(|key| {
+                        let value = engine.metadata.get(key)?;
+                        Some(ExportLine::Meta {
+                            key: key.clone(),
+                            value,
+                        })
+                    })
+                    .collect()
+            };
+            counts[3] += lines.len() as u64;
+            if !send_all(&tx, &lines).await {
+                return;
+            }
+        }
+    }
+
+    let end = {
+        let engine = state.read().await;
+        ExportLine::End {
+            records: counts[0],
+            nodes: counts[1],
+            edges: counts[2],
+            meta: counts[3],
+            height: engine
+                .event_committer()
+                .map_or(0, |c| c.journal().committed_height()),
+            state_hash: engine.state_hash_hex(),
+        }
+    };
+    send_all(&tx, &[end]).await;
+}
+
+/// Lines for ids `from..from + PAGE` of `pool` in namespace `ns`, and
+/// whether this was the last page.
+fn read_page(
+    engine: &crate::engine::Engine,
+    pool: Pool,
+    ns: u16,
+    from: u32,
+) -> (Vec<ExportLine>, bool) {
+    let state = &engine.state;
+    let limit = match pool {
+        Pool::Records => state.next_record_id().0,
+        Pool::Nodes => state.next_node_id().0,
+        Pool::Edges => state.next_edge_id().0,
+    };
+    let to = from.saturating_add(PAGE).min(limit);
+    let lines = (from..to)
+        .filter_map(|id| match pool {
+            Pool::Records => {
+                let r = state.get_record(RecordId(id))?;
+                (r.namespace_id == ns).then(|| ExportLine::Record {
+                    id,
+                    vector: state
+                        .record_vector(r)
+                        .iter()
+                        .map(|s| valori_kernel::fxp::ops::to_f32(*s))
+                        .collect(),
+                    metadata: r
+                        .metadata
+                        .as_ref()
+                        .and_then(|b| String::from_utf8(b.clone()).ok()),
+                    tag: r.tag,
+                })
+            }
+            Pool::Nodes => {
+                let n = state.get_node(NodeId(id))?;
+                (n.namespace_id == ns).then_some(ExportLine::Node {
+                    id,
+                    kind: n.kind as u8,
+                    record: n.record.map(|r| r.0),
+                })
+            }
+            Pool::Edges => {
+                let e = state.get_edge(EdgeId(id))?;
+                let from_ns = state.get_node(e.from)?.namespace_id;
+                (from_ns == ns).then_some(ExportLine::Edge {
+                    id,
+                    kind: e.kind as u8,
+                    from: e.from.0,
+                    to: e.to.0,
+                })
+            }
+        })
+        .collect();
+    (lines, to >= limit)
+}
+
+/// Send `lines` as NDJSON; false once the client has gone away.
+async fn send_all(tx: &mpsc::Sender<Bytes>, lines: &[ExportLine]) -> bool {
+    if lines.is_empty() {
+        return true;
+    }
+    let mut buf = Vec::new();
+    for line in lines {
+        if serde_json::to_writer(&mut buf, line).is_ok() {
+            buf.push(b'\n');
+        }
+    }
+    tx.send(Bytes::from(buf)).await.is_ok()
+}
@@ -0,0 +1,547 @@
+// Copyright (c) 2025 Varshith Gudur. Dual-licensed under MIT OR Apache-2.0.
+//! Asynchronous multi-region replication.
+//!
+//! A **secondary region** is a standalone node started with
+//! `VALORI_REGION_PRIMARY=<url of the primary region's leader>`. Unlike a
+//! follower it does not hold a stream open: every `VALORI_REGION_POLL_MS` it
+//! asks the primary for its head (`GET /v1/replication/head`) and, when
+//! behind, pulls the missing events — sealed segments first, then the live
+//! stream up to that head — and disconnects. Cross-region round trips
+//! therefore never sit on the primary's write path, and a slow link only
+//! shows up as lag, which [`RegionTracker`] reports in events and in
+//! milliseconds since the region was last caught up
+//! (`GET /v1/replication/region`, `valori_region_lag_*` gauges).
+//!
+//! Nodes inside the secondary region are ordinary followers of its head
+//! node. The head refuses writes until it is promoted
+//! (`POST /v1/admin/region/promote`): it stops pulling and claims a leader
+//! epoch above the primary's, which its own followers adopt at their next
+//! handshake — the whole region switches over.
+//!
+//! When a region (re)connects to a primary, before it applies anything it
+//! compares **event chains**: `chain[h] = BLAKE3(chain[h-1] ||
+//! bincode((namespace, event)))` over the first `h` events. Unlike the
+//! on-disk hash chain it covers no wall-clock times or admin entries, so two
+//! regions with the same history agree on it. A mismatch (or a local log
+//! longer than the primary's) is a conflict: the region is fenced, and the
+//! height where the histories part is reported so the operator can export
+//! the events past it (`GET /v1/events`) before re-seeding. This is what
+//! catches an old primary rejoining a region that was promoted while it was
+//! cut off.
+
+use crate::errors::EngineError;
+use crate::network::LeaderClient;
+use crate::replication::{
+    apply_replicated, bootstrap_from_leader, catch_up_from_segments, committed_height,
+    leader_epoch_ok,
+};
+use crate::replication_codec::{StreamDecoder, StreamFormat};
+use crate::server::SharedEngine;
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::Mutex;
+use std::time::{Duration, Instant, SystemTime, UNIX_EPOCH};
+use tokio_stream::StreamExt;
+use valori_kernel::event::KernelEvent;
+
+/// A stream that stays silent this long before reaching the primary's head
+/// is dropped; the next poll resumes from the committed height.
+const STREAM_IDLE: Duration = Duration::from_secs(5);
+
+/// `VALORI_REGION*` settings of a secondary region.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct RegionConfig {
+    /// This region's name (`VALORI_REGION`), for status and logs.
+    pub name: String,
+    /// Base URL of the primary region's leader (`VALORI_REGION_PRIMARY`).
+    pub primary_url: String,
+    /// How often to poll the primary (`VALORI_REGION_POLL_MS`).
+    pub poll_interval_ms: u64,
+}
+
+/// Default `VALORI_REGION_POLL_MS`.
+pub const DEFAULT_POLL_INTERVAL_MS: u64 = 1_000;
+
+/// `GET /v1/replication/head` — a node's committed height and leader epoch.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, serde::Serialize, serde::Deserialize)]
+pub struct ReplicationHead {
+    pub height: u64,
+    pub epoch: u64,
+}
+
+/// `GET /v1/replication/chain?height=N` — the event chain at `height`.
+#[derive(Debug, Clone, PartialEq, Eq, serde::Serialize, serde::Deserialize)]
+pub struct ChainProof {
+    pub height: u64,
+    pub chain_hash: String,
+}
+
+/// Where a region's history parts from the primary's.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, serde::Serialize, serde::Deserialize)]
+pub struct RegionConflict {
+    /// Highest height at which both event chains agree.
+    pub common_height: u64,
+    /// This region's committed height; events past `common_height` are the
+    /// primary's to overrule.
+    pub local_height: u64,
+    pub primary_height: u64,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, serde::Serialize, serde::Deserialize)]
+pub enum RegionState {
+    /// Polling the primary and applying its events.
+    Following,
+    /// The last poll could not reach the primary.
+    Unreachable,
+    /// Promoted: takes writes, no longer follows.
+    Promoted,
+    /// Histories diverged; fenced until re-seeded.
+    Conflict,
+}
+
+/// `GET /v1/replication/region` on a secondary region.
+#[derive(Debug, Clone, PartialEq, serde::Serialize, serde::Deserialize)]
+pub struct RegionStatus {
+    pub region: String,
+    pub primary: String,
+    pub state: RegionState,
+    /// Primary head at the last successful poll.
+    pub primary_height: u64,
+    pub applied_height: u64,
+    /// `primary_height - applied_height`.
+    pub lag_events: u64,
+    /// Milliseconds since this region last matched the primary's head; 0
+    /// while caught up.
+    pub lag_ms: u64,
+    /// Unix milliseconds of the last successful poll.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub last_contact_unix_ms: Option<u64>,
+    /// Leader epoch claimed on promotion.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub promoted_epoch: Option<u64>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub conflict: Option<RegionConflict>,
+}
+
+/// Shared between the poll loop, the status route and the promote route.
+pub struct RegionTracker {
+    config: RegionConfig,
+    status: Mutex<RegionStatus>,
+    /// When the region last matched the primary's head.
+    caught_up_at: Mutex<Instant>,
+    promoted: AtomicBool,
+    /// Held by the poll loop while it applies events, so a promotion never
+    /// claims its epoch in the middle of a pull.
+    applying: tokio::sync::Mutex<()>,
+}
+
+fn now_unix_ms() -> u64 {
+    SystemTime::now()
+        .duration_since(UNIX_EPOCH)
+        .map(|d| d.as_millis() as u64)
+        .unwrap_or(0)
+}
+
+impl RegionTracker {
+    pub fn new(config: RegionConfig) -> Self {
+        let status = RegionStatus {
+            region: config.name.clone(),
+            primary: config.primary_url.clone(),
+            state: RegionState::Following,
+            primary_height: 0,
+            applied_height: 0,
+            lag_events: 0,
+            lag_ms: 0,
+            last_contact_unix_ms: None,
+            promoted_epoch: None,
+            conflict: None,
+        };
+        Self {
+            config,
+            status: Mutex::new(status),
+            caught_up_at: Mutex::new(Instant::now()),
+            promoted: AtomicBool::new(false),
+            applying: tokio::sync::Mutex::new(()),
+        }
+    }
+
+    pub fn config(&self) -> &RegionConfig {
+        &self.config
+    }
+
+    pub fn is_promoted(&self) -> bool {
+        self.promoted.load(Ordering::Acquire)
+    }
+
+    /// Current status, with `lag_ms` measured now.
+    pub fn status(&self) -> RegionStatus {
+        let mut s = self.status.lock().unwrap().clone();
+        if s.lag_events > 0 && s.state != RegionState::Promoted {
+            s.lag_ms = self.caught_up_at.lock().unwrap().elapsed().as_millis() as u64;
+        }
+        s
+    }
+
+    /// Record a successful poll: the primary was at `primary_height`, this
+    /// region is now at `applied_height`.
+    pub fn observe(&self, primary_height: u64, applied_height: u64) {
+        let lag_events = primary_height.saturating_sub(applied_height);
+        if lag_events == 0 {
+            *self.caught_up_at.lock().unwrap() = Instant::now();
+        }
+        let mut s = self.status.lock().unwrap();
+        s.state = RegionState::Following;
+        s.primary_height = primary_height;
+        s.applied_height = applied_height;
+        s.lag_events = lag_events;
+        s.last_contact_unix_ms = Some(now_unix_ms());
+        let lag_ms = if lag_events == 0 {
+            0
+        } else {
+            self.caught_up_at.lock().unwrap().elapsed().as_millis() as u64
+        };
+        s.lag_ms = lag_ms;
+        metrics::gauge!("valori_region_lag_events", lag_events as f64);
+        metrics::gauge!("valori_region_lag_ms", lag_ms as f64);
+    }
+
+    fn set_state(&self, state: RegionState) {
+        self.status.lock().unwrap().state = state;
+    }
+
+    fn conflict(&self, conflict: RegionConflict) {
+        let mut s = self.status.lock().unwrap();
+        s.state = RegionState::Conflict;
+        s.conflict = Some(conflict);
+    }
+}
+
+/// `chain[h]` for `h` in `0..=events.len()`: `chain[0]` is all zeros, and
+/// each event extends it by `BLAKE3(prev || bincode((namespace, event)))`.
+pub fn event_chain(events: &[(u16, KernelEvent)]) -> Vec<[u8; 32]> {
+    let mut chain = Vec::with_capacity(events.len() + 1);
+    let mut head = [0u8; 32];
+    chain.push(head);
+    for (namespace_id, event) in events {
+        head = event_chain_advance(&head, *namespace_id, event);
+        chain.push(head);
+    }
+    chain
+}
+
+/// One step of [`event_chain`]: `BLAKE3(head || bincode((namespace, event)))`.
+pub fn event_chain_advance(head: &[u8; 32], namespace_id: u16, event: &KernelEvent) -> [u8; 32] {
+    let bytes = bincode::serde::encode_to_vec((namespace_id, event), bincode::config::standard())
+        .expect("KernelEvent is always serialisable");
+    let mut hasher = blake3::Hasher::new();
+    hasher.update(head);
+    hasher.update(&bytes);
+    *hasher.finalize().as_bytes()
+}
+
+/// Parse a [`chain_hex`] string.
+pub fn chain_from_hex(hex: &str) -> Option<[u8; 32]> {
+    let mut out = [0u8; 32];
+    if hex.len() != 64 || !hex.is_ascii() {
+        return None;
+    }
+    for (i, byte) in out.iter_mut().enumerate() {
+        *byte = u8::from_str_radix(&hex[2 * i..2 * i + 2], 16).ok()?;
+    }
+    Some(out)
+}
+
+pub fn chain_hex(hash: &[u8; 32]) -> String {
+    hash.iter().map(|b| format!("{b:02x}")).collect()
+}
+
+/// This node's committed events as `(namespace, event)`, read from its
+/// event log (archived segments included). `Err` when the log does not
+/// reach back to height 0 (the node was seeded from a snapshot).
+pub async fn local_events(state: &SharedEngine) -> Result<Vec<(u16, KernelEvent)>, EngineError> {
+    let (log_path, height) = {
+        let mut engine = state.write().await; // flush requires &mut
+        let Some(committer) = engine.event_committer_mut() else {
+            return Err(EngineError::InvalidInput(
+                "Event log not enabled".to_string(),
+            ));
+        };
+        committer
+            .flush_log()
+            .map_err(|e| EngineError::InvalidInput(format!("event log flush: {e}")))?;
+        (
+            committer.event_log().path().to_path_buf(),
+            committer.journal().committed_height(),
+        )
+    };
+    tokio::task::spawn_blocking(move || {
+        let mut events = crate::events::event_replay::read_all_segments(&log_path, None)
+            .map_err(|e| format!("event log read: {e}"))?;
+        if (events.len() as u64) < height {
+            return Err(format!(
+                "event log on disk holds {} events, short of height {height} \
+                 (seeded from a snapshot, or archived segments missing?)",
+                events.len()
+            ));
+        }
+        events.truncate(height as usize);
+        Ok(events)
+    })
+    .await
+    .map_err(|_| EngineError::Internal)?
+    .map_err(EngineError::InvalidInput)
+}
+
+/// This node's event chain up to its committed height (see [`local_events`]).
+pub async fn local_event_chain(state: &SharedEngine) -> Result<Vec<[u8; 32]>, EngineError> {
+    let events = local_events(state).await?;
+    tokio::task::spawn_blocking(move || event_chain(&events))
+        .await
+        .map_err(|_| EngineError::Internal)
+}
+
+/// Compare this region's event chain with the primary's (at head
+/// `primary_height`). `None` when this region's log is a prefix of the
+/// primary's.
+pub async fn find_conflict(
+    state: &SharedEngine,
+    client: &LeaderClient,
+    primary_height: u64,
+) -> Result<Option<RegionConflict>, EngineError> {
+    let chain = local_event_chain(state).await?;
+    let local_height = (chain.len() - 1) as u64;
+    let conflict = |common_height| {
+        Some(RegionConflict {
+            common_height,
+            local_height,
+            primary_height,
+        })
+    };
+    let top = local_height.min(primary_height);
+    let agrees = |h: u64, remote: String| chain_hex(&chain[h as usize]) == remote;
+    if top == 0 || agrees(top, client.chain_hash(top).await?) {
+        return Ok(if local_height > primary_height {
+            conflict(top)
+        } else {
+            None
+        });
+    }
+    // Chains that part once never meet again: bisect for the last height
+    // both agree on.
+    let (mut lo, mut hi) = (0, top);
+    while hi - lo > 1 {
+        let mid = lo + (hi - lo) / 2;
+        if agrees(mid, client.chain_hash(mid).await?) {
+            lo = mid;
+        } else {
+            hi = mid;
+        }
+    }
+    Ok(conflict(lo))
+}
+
+/// Poll the primary region and apply its events until this region is
+/// promoted or its history conflicts with the primary's.
+pub async fn run_secondary_region(
+    state: SharedEngine,
+    client: LeaderClient,
+    tracker: std::sync::Arc<RegionTracker>,
+) {
+    let poll = Duration::from_millis(tracker.config().poll_interval_ms.max(1));
+    // The chains are compared once per connection; after that the stream
+    // only appends to a history both sides agree on.
+    let mut verified = false;
+    loop {
+        if tracker.is_promoted() {
+            return;
+        }
+        let head = match client.head().await {
+            Ok(h) => h,
+            Err(e) => {
+                tracing::warn!("Primary region unreachable: {}", e);
+                tracker.set_state(RegionState::Unreachable);
+                verified = false;
+                tokio::time::sleep(poll).await;
+                continue;
+            }
+        };
+
+        let guard = tracker.applying.lock().await;
+        if tracker.is_promoted() {
+            return;
+        }
+        if !verified {
+            match find_conflict(&state, &client, head.height).await {
+                Ok(None) => verified = true,
+                Ok(Some(conflict)) => {
+                    let reason = format!(
+                        "region conflict: history parts from the primary's after height {} \
+                         (local {}, primary {})",
+                        conflict.common_height, conflict.local_height, conflict.primary_height
+                    );
+                    tracing::error!("Refusing to replicate — {}", reason);
+                    state.write().await.fenced = Some(reason);
+                    tracker.conflict(conflict);
+                    return;
+                }
+                Err(EngineError::Network(e)) => {
+                    tracing::warn!("Region chain check failed: {}", e);
+                    tracker.set_state(RegionState::Unreachable);
+                    drop(guard);
+                    tokio::time::sleep(poll).await;
+                    continue;
+                }
+                // A log that does not reach back to genesis cannot be
+                // compared; the epoch handshake below still applies.
+                Err(e) => {
+                    tracing::warn!("Region chain not verifiable: {}", e);
+                    verified = true;
+                }
+            }
+        }
+        if !leader_epoch_ok(&state, &client).await {
+            if let Some(reason) = state.read().await.fenced.clone() {
+                let local_height = committed_height(&state).await;
+                tracker.conflict(RegionConflict {
+                    common_height: local_height.min(head.height),
+                    local_height,
+                    primary_height: head.height,
+                });
+                tracing::error!("Secondary region stopped: {}", reason);
+                return;
+            }
+            verified = false;
+        } else if let Err(e) = pull(&state, &client, &tracker, head.height).await {
+            tracing::warn!("Region pull from {} failed: {}", client.base_url(), e);
+            verified = false;
+        }
+        tracker.observe(head.height, committed_height(&state).await);
+        drop(guard);
+        tokio::time::sleep(poll).await;
+    }
+}
+
+/// Apply the primary's events from this region's committed height up to
+/// `head`, then disconnect.
+async fn pull(
+    state: &SharedEngine,
+    client: &LeaderClient,
+    tracker: &RegionTracker,
+    head: u64,
+) -> Result<(), EngineError> {
+    let height = committed_height(state).await;
+    if height >= head {
+        return Ok(());
+    }
+    let start = match catch_up_from_segments(state, client, height, &mut None).await {
+        Ok(h) => h,
+        Err(e) if height == 0 => {
+            tracing::warn!("Segment catch-up unavailable ({}) — bootstrapping", e);
+            bootstrap_from_leader(state, client).await?;
+            committed_height(state).await
+        }
+        Err(e) => return Err(e),
+    };
+    if start >= head {
+        return Ok(());
+    }
+
+    let resp = client.stream_events(start).await?;
+    let format = StreamFormat::from_content_type(
+        resp.headers()
+            .get(reqwest::header::CONTENT_TYPE)
+            .and_then(|v| v.to_str().ok()),
+    );
+    let mut decoder = StreamDecoder::new(format);
+    let mut stream = resp.bytes_stream();
+    let mut applied = start;
+    while applied < head && !tracker.is_promoted() {
+        let chunk = match tokio::time::timeout(STREAM_IDLE, stream.next()).await {
+            Ok(Some(Ok(chunk))) => chunk,
+            Ok(Some(Err(e))) => return Err(EngineError::Network(e.to_string())),
+            Ok(None) => return Err(EngineError::Network("stream closed".into())),
+            Err(_) => return Err(EngineError::Network("stream idle".into())),
+        };
+        decoder.push(&chunk);
+        while let Some(entry) = decoder
+            .next_entry()
+            .map_err(|e| EngineError::Network(e.to_string()))?
+        {
+            apply_replicated(state, entry, &mut None, client).await?;
+        }
+        applied = committed_height(state).await;
+    }
+    Ok(())
+}
+
+/// Promote this secondary region: stop pulling, claim a leader epoch above
+/// the primary's and start taking writes.
+pub async fn promote(
+    state: &SharedEngine,
+    tracker: &RegionTracker,
+) -> Result<RegionStatus, EngineError> {
+    if tracker.status().state == RegionState::Conflict {
+        return Err(EngineError::InvalidInput(
+            "region history conflicts with the primary's; re-seed it before promoting".into(),
+        ));
+    }
+    tracker.promoted.store(true, Ordering::Release);
+    // Wait out a pull in progress.
+    let _guard = tracker.applying.lock().await;
+    let mut engine = state.write().await;
+    if engine.secondary_of.is_some() {
+        let epoch = engine.claim_leader_epoch()?;
+        engine.secondary_of = None;
+        tracing::info!(
+            "Region {} promoted: leading epoch {} from height {}",
+            tracker.config().name,
+            epoch.epoch,
+            epoch.start_height
+        );
+        let mut s = tracker.status.lock().unwrap();
+        s.state = RegionState::Promoted;
+        s.promoted_epoch = Some(epoch.epoch);
+    }
+    drop(engine);
+    Ok(tracker.status())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use valori_kernel::types::id::NodeId;
+
+    fn event(i: u32) -> (u16, KernelEvent) {
+        (0, KernelEvent::DeleteNode { id: NodeId(i) })
+    }
+
+    #[test]
+    fn event_chains_agree_on_shared_prefixes_only() {
+        let a: Vec<_> = (0..5).map(event).collect();
+        let mut b = a[..3].to_vec();
+        b.push(event(9));
+        let (ca, cb) = (event_chain(&a), event_chain(&b));
+        assert_eq!(ca.len(), 6);
+        assert_eq!(ca[..4], cb[..4]);
+        assert_ne!(ca[4], cb[4]);
+        // The namespace is part of the chain.
+        assert_ne!(event_chain(&[(1, a[0].1.clone())])[1], ca[1]);
+    }
+
+    #[test]
+    fn lag_resets_when_caught_up() {
+        let tracker = RegionTracker::new(RegionConfig {
+            name: "eu".into(),
+            primary_url: "http://primary".into(),
+            poll_interval_ms: DEFAULT_POLL_INTERVAL_MS,
+        });
+        tracker.observe(10, 4);
+        std::thread::sleep(Duration::from_millis(5));
+        let s = tracker.status();
+        assert_eq!(s.lag_events, 6);
+        assert!(s.lag_ms >= 5);
+        tracker.observe(12, 12);
+        let s = tracker.status();
+        assert_eq!((s.lag_events, s.lag_ms), (0, 0));
+        assert!(s.last_contact_unix_ms.is_some());
+    }
+}
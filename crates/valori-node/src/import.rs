@@ -0,0 +1,199 @@
+// Copyright (c) 2025 Varshith Gudur. Dual-licensed under MIT OR Apache-2.0.
+//! Streaming bulk import (`POST /v1/admin/import`).
+//!
+//! The request body is NDJSON, one row per line:
+//!
+//! ```text
+//! {"vector": [0.1, 0.2, …], "metadata": "…", "text": "…"}
+//! ```
+//!
+//! `metadata` and `text` are optional; `text` feeds the BM25 reranker, as in
+//! `/v1/vectors/batch-insert`. Lines of a `/v1/export` stream that are not
+//! records are skipped. Rows are committed in batches of `batch_size`
+//! as the body arrives, so the body is never held in memory and is exempt
+//! from the global request size limit (a single line is capped at
+//! [`MAX_LINE_BYTES`]). After each batch one progress line is streamed back:
+//!
+//! ```text
+//! {"rows": 2000, "events_committed": 2000, "state_hash": "…", "done": false}
+//! ```
+//!
+//! The last line has `"done": true`, or an `error` and the 1-based body
+//! `line` the import had reached. Batches committed before an error stay
+//! committed; resume by sending the rows after the first `rows`.
+
+use crate::server::SharedEngine;
+use axum::body::{Body, Bytes};
+use futures::StreamExt;
+use serde::{Deserialize, Serialize};
+use tokio::sync::mpsc;
+
+/// Rows per commit when the request does not say.
+pub const DEFAULT_BATCH_SIZE: usize = 1000;
+/// Largest `batch_size` a request may ask for.
+pub const MAX_BATCH_SIZE: usize = 10_000;
+/// Longest NDJSON line accepted.
+pub const MAX_LINE_BYTES: usize = 1024 * 1024;
+
+/// One NDJSON row of an import.
+#[derive(Deserialize, Debug)]
+pub struct ImportRow {
+    /// `/v1/export` line type. Rows typed as anything but `record` are
+    /// skipped, so an export can be imported as is.
+    #[serde(default, rename = "type")]
+    pub kind: Option<String>,
+    #[serde(default)]
+    pub vector: Vec<f32>,
+    #[serde(default)]
+    pub metadata: Option<String>,
+    #[serde(default)]
+    pub text: Option<String>,
+}
+
+/// One progress line of the response.
+#[derive(Serialize, Deserialize, Debug, Clone, Default, PartialEq)]
+pub struct ImportProgress {
+    /// Rows committed so far.
+    pub rows: u64,
+    /// Committed event-log height (0 without an event log).
+    pub events_committed: u64,
+    /// BLAKE3 state hash after the last committed batch.
+    pub state_hash: String,
+    pub done: bool,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub error: Option<String>,
+    /// 1-based body line reached when the import failed.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub line: Option<u64>,
+}
+
+/// Read NDJSON rows from `body`, commit them into namespace `ns` in batches
+/// of `batch_size`, and send a progress line to `tx` after each batch. Stops
+/// early if the receiver is dropped (the client went away).
+pub async fn run(
+    state: SharedEngine,
+    body: Body,
+    ns: u16,
+    batch_size: usize,
+    tx: mpsc::Sender<Bytes>,
+) {
+    let mut progress = ImportProgress::default();
+    let mut batch: Vec<ImportRow> = Vec::with_capacity(batch_size);
+    let mut buf: Vec<u8> = Vec::new();
+    let mut line_no = 0u64;
+    let mut stream = body.into_data_stream();
+
+    let failure = 'read: loop {
+        let chunk = match stream.next().await {
+            Some(Ok(chunk)) => chunk,
+            Some(Err(e)) => break 'read Some(format!("request body: {e}")),
+            None => break 'read None,
+        };
+        buf.extend_from_slice(&chunk);
+        while let Some(end) = buf.iter().position(|&b| b == b'\n') {
+            let line: Vec<u8> = buf.drain(..=end).collect();
+            line_no += 1;
+            match parse_line(&line) {
+                Ok(Some(row)) => batch.push(row),
+                Ok(None) => {}
+                Err(e) => break 'read Some(e),
+            }
+            if batch.len() >= batch_size {
+                if let Err(e) = commit(&state, &mut batch, ns, &mut progress).await {
+                    break 'read Some(e);
+                }
+                if !send(&tx, &progress).await {
+                    return;
+                }
+            }
+        }
+        if buf.len() > MAX_LINE_BYTES {
+            line_no += 1;
+            break 'read Some(format!("line longer than {MAX_LINE_BYTES} bytes"));
+        }
+    };
+
+    let failure = match failure {
+        Some(e) => Some(e),
+        // A final line without a trailing newline.
+        None => {
+            line_no += 1;
+            match parse_line(&buf) {
+                Ok(row) => {
+                    batch.extend(row);
+                    commit(&state, &mut batch, ns, &mut progress).await.err()
+                }
+                Err(e) => Some(e),
+            }
+        }
+    };
+    match failure {
+        Some(e) => {
+            progress.error = Some(e);
+            progress.line = Some(line_no);
+        }
+        None => progress.done = true,
+    }
+    if progress.rows == 0 {
+        let engine = state.read().await;
+        progress.events_committed = engine
+            .event_committer()
+            .map_or(0, |c| c.journal().committed_height());
+        progress.state_hash = engine.state_hash_hex();
+    }
+    send(&tx, &progress).await;
+}
+
+/// Parse one line; blank lines are skipped.
+fn parse_line(line: &[u8]) -> Result<Option<ImportRow>, String> {
+    if line.iter().all(u8::is_ascii_whitespace) {
+        return Ok(None);
+    }
+    let row: ImportRow = serde_json::from_slice(line).map_err(|e| format!("invalid row: {e}"))?;
+    match row.kind.as_deref() {
+        Some(kind) if kind != "record" => Ok(None),
+        _ if row.vector.is_empty() => Err("invalid row: missing vector".into()),
+        _ => Ok(Some(row)),
+    }
+}
+
+/// Commit `batch` (emptying it) and refresh `progress`. No-op when empty.
+async fn commit(
+    state: &SharedEngine,
+    batch: &mut Vec<ImportRow>,
+    ns: u16,
+    progress: &mut ImportProgress,
+) -> Result<(), String> {
+    if batch.is_empty() {
+        return Ok(());
+    }
+    let rows = std::mem::take(batch);
+    let vectors: Vec<Vec<f32>> = rows.iter().map(|r| r.vector.clone()).collect();
+    let metadata: Vec<Option<Vec<u8>>> = rows
+        .iter()
+        .map(|r| r.metadata.as_ref().map(|m| m.as_bytes().to_vec()))
+        .collect();
+
+    let mut engine = state.write().await;
+    let ids = engine
+        .insert_batch_ns(&vectors, Some(&metadata), ns, None)
+        .map_err(|e| e.to_string())?;
+    for (id, row) in ids.iter().zip(&rows) {
+        if let Some(text) = &row.text {
+            engine.reranker_insert(*id, text);
+        }
+    }
+    progress.rows += ids.len() as u64;
+    progress.events_committed = engine
+        .event_committer()
+        .map_or(0, |c| c.journal().committed_height());
+    progress.state_hash = engine.state_hash_hex();
+    Ok(())
+}
+
+/// Send one progress line; false once the client has gone away.
+async fn send(tx: &mpsc::Sender<Bytes>, progress: &ImportProgress) -> bool {
+    let mut line = serde_json::to_vec(progress).unwrap_or_default();
+    line.push(b'\n');
+    tx.send(Bytes::from(line)).await.is_ok()
+}
@@ -0,0 +1,122 @@
+// Copyright (c) 2025 Varshith Gudur. Dual-licensed under MIT OR Apache-2.0.
+//! Background HNSW optimizer.
+//!
+//! Deletes strip links out of the graph and never put any back, so a node
+//! whose neighbours were deleted can end up reachable through only one or
+//! two edges. With `VALORI_INDEX_OPTIMIZE_SECS` set, this task walks the
+//! primary index a slice at a time (`VALORI_INDEX_OPTIMIZE_BUDGET` nodes per
+//! step) and calls
+//! [`Engine::optimize_index_step`](crate::engine::Engine::optimize_index_step),
+//! which re-links poorly connected nodes and prunes over-full link lists
+//! back to `M`.
+//!
+//! It is low priority: a step only runs when no write was committed since
+//! the previous tick and the engine lock is free, so it never makes a
+//! request wait behind it. Only the graph changes, never the state hash.
+//!
+//! Metrics: `valori_index_optimize_steps_total`,
+//! `valori_index_optimize_nodes_examined_total`,
+//! `valori_index_optimize_relinked_total`, `valori_index_optimize_pruned_total`,
+//! `valori_index_optimize_passes_total`, the `valori_index_optimize_progress`
+//! gauge (fraction of the current pass done) and
+//! `valori_index_optimize_last_pass_changed` (nodes re-linked or pruned in the
+//! last complete pass — it falls towards 0 as the graph settles).
+
+use crate::server::SharedEngine;
+use valori_index::OptimizeStep;
+
+/// Tick-to-tick state of the optimizer.
+#[derive(Debug, Default)]
+pub struct IndexOptimizer {
+    budget: usize,
+    /// Committed height at the previous tick; a step only runs when the
+    /// height has not moved since.
+    last_height: Option<u64>,
+    /// Nodes changed so far in the current pass.
+    pass_changed: usize,
+}
+
+impl IndexOptimizer {
+    pub fn new(budget: usize) -> Self {
+        Self {
+            budget,
+            ..Self::default()
+        }
+    }
+
+    /// Run one step if the node has been idle since the last tick and the
+    /// engine lock is free. `None` when the step was skipped.
+    pub async fn tick(&mut self, state: &SharedEngine) -> Option<OptimizeStep> {
+        let Ok(engine) = state.clone().try_write_owned() else {
+            return None;
+        };
+        let height = engine.state.version();
+        if self.last_height.replace(height) != Some(height) {
+            return None;
+        }
+
+        let budget = self.budget;
+        let step = match tokio::task::spawn_blocking(move || {
+            let mut engine = engine;
+            engine.optimize_index_step(budget)
+        })
+        .await
+        {
+            Ok(step) => step?,
+            Err(e) => {
+                tracing::error!("Index optimization step panicked: {:?}", e);
+                return None;
+            }
+        };
+
+        metrics::counter!("valori_index_optimize_steps_total", 1);
+        metrics::counter!(
+            "valori_index_optimize_nodes_examined_total",
+            step.examined as u64
+        );
+        metrics::counter!("valori_index_optimize_relinked_total", step.relinked as u64);
+        metrics::counter!("valori_index_optimize_pruned_total", step.pruned as u64);
+        self.pass_changed += step.relinked + step.pruned;
+        match step.next_cursor {
+            Some(cursor) => {
+                metrics::gauge!(
+                    "valori_index_optimize_progress",
+                    cursor as f64 / step.slots.max(1) as f64
+                );
+            }
+            None => {
+                metrics::counter!("valori_index_optimize_passes_total", 1);
+                metrics::gauge!("valori_index_optimize_progress", 1.0);
+                metrics::gauge!(
+                    "valori_index_optimize_last_pass_changed",
+                    self.pass_changed as f64
+                );
+                if self.pass_changed > 0 {
+                    tracing::info!(
+                        changed = self.pass_changed,
+                        "Index optimization pass complete"
+                    );
+                }
+                self.pass_changed = 0;
+            }
+        }
+        Some(step)
+    }
+}
+
+/// Spawn the optimizer, ticking every `interval_secs`.
+pub fn spawn_index_optimizer(
+    state: SharedEngine,
+    interval_secs: u64,
+    budget: usize,
+) -> tokio::task::JoinHandle<()> {
+    tokio::spawn(async move {
+        let mut optimizer = IndexOptimizer::new(budget);
+        let mut interval = tokio::time::interval(std::time::Duration::from_secs(interval_secs));
+        interval.set_missed_tick_behavior(tokio::time::MissedTickBehavior::Skip);
+        loop {
+            interval.tick().await;
+            optimizer.tick(&state).await;
+        }
+    })
+}
@@ -1,8 +1,36 @@
 // Copyright (c) 2025 Varshith Gudur. Dual-licensed under MIT OR Apache-2.0.
 pub mod api;
+/// Append-only audit trail of API operations (who, route, ids, event height).
+pub mod api_audit;
+/// Cross-machine determinism proofs (`GET /v1/proof/at`, `valori attest`).
+pub mod attest;
 pub mod config;
+/// `--config valori.toml` file loading and validation.
+pub mod config_file;
+/// Periodic background consistency check (`VALORI_CONSISTENCY_CHECK_SECS`).
+pub mod consistency_sentinel;
+/// Per-tag semantic drift statistics (`GET /v1/analytics/drift`).
+pub mod drift;
 pub mod engine;
+/// Collections with their own dim, capacity and index (`/v1/collections`).
+pub mod engine_registry;
 pub mod errors;
+/// Streaming NDJSON export of a collection (`/v1/export`).
+pub mod export;
+/// Streaming NDJSON bulk import with progress lines (`/v1/admin/import`).
+pub mod import;
+/// Idle-time HNSW graph optimization (`VALORI_INDEX_OPTIMIZE_SECS`).
+pub mod index_optimizer;
+/// Recall@k of the ANN indexes against exact search (`/v1/admin/recall`).
+pub mod recall_job;
+/// `X-Request-Id` middleware: per-request tracing span, echoed id.
+pub mod request_id;
+/// Settings changeable without a restart (`/v1/admin/config`).
+pub mod runtime_config;
+/// Slow search/insert logging (`VALORI_SLOW_QUERY_MS`).
+pub mod slow_query;
+/// `GET /v1/snapshot/download` streamed from a spool file.
+pub mod snapshot_stream;
 pub use engine::EngineFromNodeConfig;
 pub mod execution_registry;
 /// Server-side document ingestion: full pipeline (chunk+embed+insert) handlers.
@@ -11,12 +39,15 @@ pub mod execution_registry;
 pub mod ingest;
 pub mod kernel_writer;
 pub mod server;
+/// Write sessions with savepoints (`/v1/sessions`).
+pub mod session;
 // embedder and chunking logic now live in the valori-ingest crate.
 pub mod metadata;
 pub mod persistence;
 pub mod telemetry;
 // Storage layer now lives in valori-storage; re-export here so all existing
 // `crate::wal_writer::*`, `crate::events::*`, etc. imports still compile.
+pub use valori_storage::durability;
 pub use valori_storage::events;
 pub use valori_storage::object_store;
 pub use valori_storage::wal_reader;
@@ -35,13 +66,29 @@ pub mod cluster_server;
 /// Phase 1.9: Committer trait seam (skeleton present; Engine wiring in Phase 1.9).
 /// See docs/phases/phase-1.9-committer-trait.md
 pub mod commit;
+/// Asynchronous multi-region replication: secondary regions, apply lag,
+/// promotion and rejoin conflict checks.
+pub mod geo_replication;
 pub mod network;
 pub mod replication;
+/// Wire formats of the replication event stream (CRC frames, legacy NDJSON).
+pub mod replication_codec;
+/// gzip / zstd Content-Encoding for the replication event stream.
+pub mod replication_compression;
+/// Follower filters for partial replication by collection / tag.
+pub mod replication_filter;
+/// Sealed event-log segments served to followers for deep catch-up.
+pub mod replication_segments;
 // object_store is re-exported from valori_storage above.
 /// Phase 3.5: Per-tenant API keys + RBAC.
 pub mod api_keys;
 /// Phase 3.6: AES-256-GCM vault for crypto-shredding (GDPR erasure).
 pub mod crypto_vault;
+/// Mutual TLS for the standalone replication channel (leader ↔ follower).
+pub mod tls;
+/// The HTTP API on a Unix domain socket (`VALORI_UNIX_SOCKET`).
+#[cfg(unix)]
+pub mod unix_socket;
 // graph_rag, tree_rag, and community now live in the valori-rag crate.
 /// Phase A7: Concrete capability implementations (EngineKernelCapability, HttpEmbedCapability).
 pub mod capabilities;
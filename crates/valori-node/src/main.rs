@@ -4,7 +4,9 @@ use tokio::sync::RwLock;
 use valori_node::api_keys::KeyStore;
 use valori_node::config::NodeConfig;
 use valori_node::engine::Engine;
-use valori_node::server::{build_router_with_keys, SharedEngine};
+use valori_node::persistence::SnapshotManager;
+use valori_node::runtime_config::RuntimeConfig;
+use valori_node::server::{build_router_with_collections, SharedEngine};
 use valori_node::EngineFromNodeConfig;
 
 #[tokio::main(flavor = "multi_thread")]
@@ -41,52 +43,263 @@ async fn main() {
         Ok(None) => { /* standalone — fall through */ }
     }
 
-    let cfg = NodeConfig::default();
+    let cfg = load_config();
 
     tracing::info!("Initializing Valori Node with config: {:?}", cfg);
 
-    let mut engine = Engine::new(&cfg);
+    // ── Recovery dry run ──────────────────────────────────────────────────────
+    // Replays into a throwaway engine and reports; the live files are only
+    // read and nothing listens, so it is safe to point a new binary at the
+    // current data directory before switching over to it.
+    if cfg.verify_recovery {
+        let report = valori_node::engine::verify_recovery(&cfg);
+        println!(
+            "{}",
+            serde_json::to_string_pretty(&report).expect("report serializes")
+        );
+        std::process::exit(if report.anomalies.is_empty() { 0 } else { 1 });
+    }
+
+    let mut engine = Engine::try_new(&cfg).unwrap_or_else(|e| {
+        eprintln!("FATAL: cannot start the engine: {e}");
+        std::process::exit(1);
+    });
+    // A secondary region takes no writes of its own until it is promoted.
+    if let Some(region) = &cfg.region {
+        engine.secondary_of = Some(region.primary_url.clone());
+    }
+    let recovery_progress = engine.recovery_progress.clone();
+    let shared_state: SharedEngine = Arc::new(RwLock::new(engine));
+
+    let key_store = Arc::new(KeyStore::new(cfg.keys_path.clone()));
+    let receipt_store = Arc::new(valori_effect::ReceiptStore::new(256));
+    let runtime = Arc::new(
+        RuntimeConfig::new(cfg.auto_snapshot_interval_secs, cfg.rate_limit_rps)
+            .with_slow_query_ms(cfg.slow_query_ms)
+            .with_request_timeout_ms(cfg.request_timeout_ms),
+    );
+    let collections = match &cfg.collections_dir {
+        Some(dir) => valori_node::engine_registry::EngineRegistry::open(dir, cfg.clone())
+            .unwrap_or_else(|e| {
+                eprintln!("FATAL: cannot open collections directory {dir:?}: {e}");
+                std::process::exit(1);
+            }),
+        None => valori_node::engine_registry::EngineRegistry::in_memory(cfg.clone()),
+    };
+    let app = build_router_with_collections(
+        shared_state.clone(),
+        cfg.auth_token.clone(),
+        cfg.cors_origin.clone(),
+        key_store,
+        receipt_store,
+        cfg.auth_roles.clone(),
+        Arc::new(valori_node::api_audit::ApiAuditLog::from_path(
+            cfg.api_audit_path.clone(),
+        )),
+        runtime.clone(),
+        Arc::new(collections),
+    );
+    let region = cfg
+        .region
+        .clone()
+        .map(|r| Arc::new(valori_node::geo_replication::RegionTracker::new(r)));
+    let app = match &region {
+        Some(tracker) => app.layer(axum::Extension(tracker.clone())),
+        None => app,
+    };
 
     // ── Crash Recovery ────────────────────────────────────────────────────────
     // Priority order: event log (canonical truth) → snapshot → legacy WAL
     // (replayed on top of the snapshot, if any) → fresh start.
-    // try_recover() never panics; on failure it logs and continues with the
-    // next source. A corrupt snapshot no longer kills the process.
-    let mode = engine.try_recover();
-    match mode {
-        valori_node::engine::RecoveryMode::EventLog(n) => {
-            tracing::info!("Recovered {} events from event log", n)
+    // try_recover() never panics. What a source that fails to load does is
+    // VALORI_RECOVERY_POLICY: by default it is logged and the next source is
+    // tried; under fail-closed / event-log-only the node exits instead of
+    // serving an empty store.
+    //
+    // Recovery runs while the server is already listening: it holds the
+    // engine write lock, so data routes wait for it, but /readyz reports
+    // "recovering (N%)" from the shared progress counters in the meantime.
+    recovery_progress.begin();
+    let mut engine = shared_state.clone().write_owned().await;
+    let check_after_recovery = cfg.check_after_recovery;
+    // A secondary region claims its epoch on promotion, above the primary's.
+    let is_leader =
+        matches!(cfg.mode, valori_node::config::NodeMode::Leader) && cfg.region.is_none();
+    let recovery = tokio::task::spawn_blocking(move || {
+        let mode = engine.try_recover();
+        match mode {
+            valori_node::engine::RecoveryMode::EventLog(n) => {
+                tracing::info!("Recovered {} events from event log", n)
+            }
+            valori_node::engine::RecoveryMode::Snapshot => {
+                tracing::info!("Recovered from snapshot")
+            }
+            valori_node::engine::RecoveryMode::Wal(n) => {
+                tracing::info!("Recovered {} commands from legacy WAL", n)
+            }
+            valori_node::engine::RecoveryMode::Fresh => {
+                tracing::info!("Starting fresh (no prior state found)")
+            }
+            valori_node::engine::RecoveryMode::Refused(reason) => {
+                tracing::error!("Recovery refused: {}", reason);
+                eprintln!("FATAL: recovery refused by VALORI_RECOVERY_POLICY: {reason}");
+                std::process::exit(1);
+            }
+            valori_node::engine::RecoveryMode::Quarantined(dir) => {
+                tracing::warn!("Started empty; previous data quarantined in {:?}", dir)
+            }
         }
-        valori_node::engine::RecoveryMode::Snapshot => tracing::info!("Recovered from snapshot"),
-        valori_node::engine::RecoveryMode::Wal(n) => {
-            tracing::info!("Recovered {} commands from legacy WAL", n)
+
+        if check_after_recovery {
+            let report = engine.check_consistency();
+            if report.ok {
+                tracing::info!("Post-recovery consistency check passed");
+            } else {
+                for v in &report.kernel {
+                    tracing::error!("Kernel invariant violated: {v}");
+                }
+                for v in &report.record_to_node {
+                    tracing::error!("record_to_node inconsistent: {v}");
+                }
+                tracing::error!(
+                    index_missing = report.index_missing.len(),
+                    index_stale = report.index_stale.len(),
+                    committer_diverged = report.committer_diverged,
+                    "Post-recovery consistency check failed (details: GET /v1/admin/check)"
+                );
+            }
         }
-        valori_node::engine::RecoveryMode::Fresh => {
-            tracing::info!("Starting fresh (no prior state found)")
+
+        // A leader starts a new epoch every time it starts, so followers of
+        // an older leader can tell the two apart (split-brain detection).
+        if is_leader {
+            match engine.claim_leader_epoch() {
+                Ok(e) => tracing::info!("Leading epoch {} from height {}", e.epoch, e.start_height),
+                Err(e) => tracing::error!("Could not claim a leader epoch: {}", e),
+            }
+        }
+    });
+
+    // ── Replication mode ──────────────────────────────────────────────────────
+    let follower_client = match &cfg.mode {
+        valori_node::config::NodeMode::Follower { leader_url } => {
+            tracing::info!("Node starting in FOLLOWER mode. Leader: {}", leader_url);
+            let client = valori_node::network::LeaderClient::new(leader_url.clone())
+                .with_token(cfg.replication_token.clone())
+                .with_compression(cfg.replication_compression)
+                .with_tls(cfg.replication_tls.as_ref())
+                .unwrap_or_else(|e| {
+                    eprintln!("FATAL: {e}");
+                    std::process::exit(1);
+                });
+            Some(client)
+        }
+        valori_node::config::NodeMode::Leader => {
+            tracing::info!("Node starting in LEADER mode.");
+            None
         }
+    };
+
+    // Background tasks start once recovery has released the engine.
+    {
+        let shared_state = shared_state.clone();
+        let cfg = cfg.clone();
+        tokio::spawn(async move {
+            if let Err(e) = recovery.await {
+                tracing::error!("Recovery task panicked: {:?}", e);
+            }
+            spawn_background_tasks(&cfg, shared_state, follower_client, region, &runtime);
+        });
     }
 
-    let shared_state: SharedEngine = Arc::new(RwLock::new(engine));
+    let addr = cfg.bind_addr;
+    tracing::info!("Listening on {}", addr);
+
+    let listener = TcpListener::bind(addr).await.unwrap_or_else(|e| {
+        let msg = if e.kind() == std::io::ErrorKind::AddrInUse {
+            format!(
+                "Port {} is already in use — set VALORI_BIND to a free port (e.g. VALORI_BIND=0.0.0.0:3001)",
+                addr
+            )
+        } else {
+            format!("Cannot bind to {addr}: {e}")
+        };
+        eprintln!("FATAL: {msg}");
+        std::process::exit(1);
+    });
+    let shutdown = shutdown_signal(shared_state.clone(), cfg.snapshot_path.clone());
+    #[cfg(unix)]
+    let shutdown = serve_unix_socket(&cfg, app.clone(), shutdown);
+    match &cfg.replication_tls {
+        Some(tls) => {
+            let server_cfg = tls.server_config().unwrap_or_else(|e| {
+                eprintln!("FATAL: replication TLS: {e}");
+                std::process::exit(1);
+            });
+            tracing::info!("Replication mTLS enabled (HTTPS)");
+            valori_node::tls::serve_tls(listener, app, server_cfg, shutdown).await;
+        }
+        None => {
+            axum::serve(listener, app)
+                .with_graceful_shutdown(shutdown)
+                .await
+                .unwrap();
+        }
+    }
+}
 
+/// Auto-snapshot, consistency sentinel and follower loop — everything that
+/// needs the recovered engine.
+fn spawn_background_tasks(
+    cfg: &NodeConfig,
+    shared_state: SharedEngine,
+    follower_client: Option<valori_node::network::LeaderClient>,
+    region: Option<Arc<valori_node::geo_replication::RegionTracker>>,
+    runtime: &RuntimeConfig,
+) {
     // ── Auto-snapshot task ────────────────────────────────────────────────────
-    if let (Some(path), Some(secs)) = (cfg.snapshot_path.clone(), cfg.auto_snapshot_interval_secs) {
+    // Follows the runtime interval: idle while it is unset, restarted from
+    // zero whenever PATCH /v1/admin/config changes it.
+    if let Some(path) = cfg.snapshot_path.clone() {
         let state_clone = shared_state.clone();
+        let retention = cfg.snapshot_retention();
+        let mut interval_rx = runtime.subscribe_snapshot_interval();
         tokio::spawn(async move {
-            let mut interval = tokio::time::interval(tokio::time::Duration::from_secs(secs));
             loop {
-                interval.tick().await;
+                let Some(secs) = *interval_rx.borrow_and_update() else {
+                    if interval_rx.changed().await.is_err() {
+                        return;
+                    }
+                    continue;
+                };
+                tokio::select! {
+                    _ = tokio::time::sleep(tokio::time::Duration::from_secs(secs)) => {}
+                    changed = interval_rx.changed() => {
+                        if changed.is_err() {
+                            return;
+                        }
+                        continue;
+                    }
+                }
 
                 tracing::debug!("Auto-snapshotting...");
                 let state_for_snap = state_clone.clone();
                 let path_for_snap = path.clone();
                 match tokio::task::spawn_blocking(move || {
-                    state_for_snap
-                        .blocking_read()
-                        .save_snapshot(Some(&path_for_snap))
+                    SnapshotManager::autosave(
+                        &state_for_snap.blocking_read(),
+                        &path_for_snap,
+                        retention.as_ref(),
+                    )
                 })
                 .await
                 {
-                    Ok(Ok(_)) => tracing::info!("Snapshot saved to {:?}", path),
+                    Ok(Ok(0)) => tracing::info!("Snapshot saved to {:?}", path),
+                    Ok(Ok(pruned)) => tracing::info!(
+                        "Snapshot saved to {:?}; pruned {} cataloged snapshot(s)",
+                        path,
+                        pruned
+                    ),
                     Ok(Err(e)) => tracing::error!("Snapshot failed: {:?}", e),
                     Err(e) => tracing::error!("Snapshot task panicked: {:?}", e),
                 }
@@ -94,54 +307,111 @@ async fn main() {
         });
     }
 
-    let key_store = Arc::new(KeyStore::new(cfg.keys_path.clone()));
-    let receipt_store = Arc::new(valori_effect::ReceiptStore::new(256));
-    let app = build_router_with_keys(
-        shared_state.clone(),
-        cfg.auth_token.clone(),
-        cfg.cors_origin.clone(),
-        key_store,
-        receipt_store,
-    );
+    // ── Consistency sentinel ──────────────────────────────────────────────────
+    if let Some(secs) = cfg.consistency_check_secs {
+        tracing::info!("Background consistency check every {}s", secs);
+        valori_node::consistency_sentinel::spawn_consistency_sentinel(shared_state.clone(), secs);
+    }
 
-    let addr = cfg.bind_addr;
-    tracing::info!("Listening on {}", addr);
+    // ── Idle-time index optimization ──────────────────────────────────────────
+    if let Some(secs) = cfg.index_optimize_secs {
+        tracing::info!(
+            "Background index optimization every {}s ({} nodes per step)",
+            secs,
+            cfg.index_optimize_budget
+        );
+        valori_node::index_optimizer::spawn_index_optimizer(
+            shared_state.clone(),
+            secs,
+            cfg.index_optimize_budget,
+        );
+    }
 
-    // ── Replication mode ──────────────────────────────────────────────────────
-    if let valori_node::config::NodeMode::Follower { leader_url } = cfg.mode {
-        tracing::info!("Node starting in FOLLOWER mode. Leader: {}", leader_url);
-        let state_clone = shared_state.clone();
+    // ── Recall estimation ─────────────────────────────────────────────────────
+    if let Some(secs) = cfg.recall_check_secs {
+        tracing::info!(
+            "Background recall@{} estimate every {}s ({} queries)",
+            cfg.recall_k,
+            secs,
+            cfg.recall_sample
+        );
+        valori_node::recall_job::spawn_recall_job(
+            shared_state.clone(),
+            secs,
+            cfg.recall_sample,
+            cfg.recall_k,
+        );
+    }
+
+    if let Some(tracker) = region {
+        let primary = &tracker.config().primary_url;
+        tracing::info!(
+            "Region {} is a secondary of {} (poll every {}ms)",
+            tracker.config().name,
+            primary,
+            tracker.config().poll_interval_ms
+        );
+        let client = valori_node::network::LeaderClient::new(primary.clone())
+            .with_token(cfg.replication_token.clone())
+            .with_compression(cfg.replication_compression)
+            .with_tls(cfg.replication_tls.as_ref())
+            .unwrap_or_else(|e| {
+                eprintln!("FATAL: {e}");
+                std::process::exit(1);
+            });
+        let state = shared_state.clone();
+        tokio::spawn(valori_node::geo_replication::run_secondary_region(
+            state, client, tracker,
+        ));
+    }
+
+    if let Some(client) = follower_client {
+        let filter = cfg.replication_filter.clone();
+        if let Some(f) = &filter {
+            tracing::info!("Partial replica: keeping only {}", f.query());
+        }
         tokio::spawn(async move {
-            valori_node::replication::run_follower_loop(state_clone, leader_url).await;
+            valori_node::replication::run_follower_loop_with_filter(shared_state, client, filter)
+                .await;
         });
-    } else {
-        tracing::info!("Node starting in LEADER mode.");
     }
+}
 
-    let listener = TcpListener::bind(addr).await.unwrap_or_else(|e| {
-        let msg = if e.kind() == std::io::ErrorKind::AddrInUse {
-            format!(
-                "Port {} is already in use — set VALORI_BIND to a free port (e.g. VALORI_BIND=0.0.0.0:3001)",
-                addr
-            )
-        } else {
-            format!("Cannot bind to {addr}: {e}")
-        };
-        eprintln!("FATAL: {msg}");
-        std::process::exit(1);
-    });
-    axum::serve(listener, app)
-        .with_graceful_shutdown(shutdown_signal(
-            shared_state.clone(),
-            cfg.snapshot_path.clone(),
-        ))
-        .await
-        .unwrap();
+/// Start the `VALORI_UNIX_SOCKET` listener, if configured, next to the TCP
+/// one. Returns `shutdown` extended to also stop the socket server (which
+/// removes the socket file) once the node has checkpointed.
+#[cfg(unix)]
+fn serve_unix_socket(
+    cfg: &NodeConfig,
+    app: axum::Router,
+    shutdown: impl std::future::Future<Output = ()> + Send,
+) -> impl std::future::Future<Output = ()> + Send {
+    let (stop_tx, mut stop_rx) = tokio::sync::watch::channel(());
+    if let Some(path) = &cfg.unix_socket {
+        let listener =
+            valori_node::unix_socket::UnixSocketListener::bind(path).unwrap_or_else(|e| {
+                eprintln!("FATAL: cannot bind unix socket {}: {e}", path.display());
+                std::process::exit(1);
+            });
+        tracing::info!("Listening on unix socket {}", path.display());
+        tokio::spawn(valori_node::unix_socket::serve_unix(
+            listener,
+            app,
+            async move {
+                let _ = stop_rx.changed().await;
+            },
+        ));
+    }
+    async move {
+        shutdown.await;
+        let _ = stop_tx.send(());
+    }
 }
 
 /// Resolve on SIGTERM / Ctrl-C. Before returning (which lets axum drain and exit)
-/// write a final snapshot if a snapshot path is configured. The WAL already
-/// guarantees durability — this just keeps the next start instant. Snapshot-on-close.
+/// run [`Engine::shutdown_checkpoint`]: drain queued writes, fsync the event
+/// log, write the final snapshot (if a snapshot path is configured) and seal
+/// the log with a checkpoint entry.
 async fn shutdown_signal(state: SharedEngine, snapshot_path: Option<std::path::PathBuf>) {
     let ctrl_c = async {
         let _ = tokio::signal::ctrl_c().await;
@@ -164,30 +434,37 @@ async fn shutdown_signal(state: SharedEngine, snapshot_path: Option<std::path::P
         _ = terminate => {}
     }
 
-    if let Some(path) = snapshot_path {
-        tracing::info!(
-            "Shutdown signal received — saving final snapshot to {:?}",
-            path
-        );
-        match tokio::task::spawn_blocking(move || {
-            state.blocking_read().save_snapshot(Some(path.as_path()))
-        })
+    // Taking the write lock drains every write queued ahead of us; after the
+    // checkpoint the engine refuses new writes (503) while axum finishes the
+    // in-flight requests.
+    tracing::info!("Shutdown signal received — draining writes");
+    let mut engine = state.write_owned().await;
+    match tokio::task::spawn_blocking(move || engine.shutdown_checkpoint(snapshot_path.as_deref()))
         .await
-        {
-            Ok(Ok(_)) => tracing::info!("Final snapshot saved"),
-            Ok(Err(e)) => tracing::error!("Final snapshot failed (WAL still durable): {:?}", e),
-            Err(e) => tracing::error!("Final snapshot task panicked: {:?}", e),
-        }
+    {
+        Ok(Ok(Some(height))) => tracing::info!("Final checkpoint written at height {}", height),
+        Ok(Ok(None)) => tracing::info!("Final flush done (no event log)"),
+        Ok(Err(e)) => tracing::error!("Final checkpoint failed (log still durable): {:?}", e),
+        Err(e) => tracing::error!("Final checkpoint task panicked: {:?}", e),
     }
 }
 
+/// `NodeConfig::load`, exiting on a configuration mistake rather than
+/// booting with a half-applied file.
+fn load_config() -> NodeConfig {
+    NodeConfig::load().unwrap_or_else(|e| {
+        eprintln!("FATAL: {e}");
+        std::process::exit(1);
+    })
+}
+
 // ── Cluster mode (Phase 2) ────────────────────────────────────────────────────
 
 async fn run_cluster(cluster_cfg: valori_node::cluster::ClusterConfig) {
     use valori_node::cluster::bootstrap_cluster;
     use valori_node::cluster_server::build_cluster_router;
 
-    let node_cfg = NodeConfig::default();
+    let node_cfg = load_config();
 
     tracing::info!(
         node_id = cluster_cfg.node_id,
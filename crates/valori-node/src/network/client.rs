@@ -1,16 +1,22 @@
 // Copyright (c) 2025 Varshith Gudur. Dual-licensed under MIT OR Apache-2.0.
 //! Leader HTTP client with exponential-backoff retry.
 //!
-//! All three RPCs (`get_proof`, `stream_events`, `download_snapshot`) retry
-//! transient network errors using truncated binary exponential backoff:
+//! The request/response RPCs (`get_proof`, `download_snapshot`,
+//! `list_segments`, `download_segment`, `list_collections`,
+//! `get_partial_proof`, `head`, `chain_hash`) retry transient network errors
+//! using truncated binary exponential backoff:
 //!   attempt 0 → immediate
 //!   attempt 1 → 500 ms
 //!   attempt 2 → 1 s
 //!   attempt 3 → 2 s  (capped at MAX_BACKOFF_MS)
 //!
 //! `stream_events` is a streaming endpoint; it does not retry mid-stream.
+//! It asks for length-prefixed frames (see `replication_codec`) and the
+//! configured [`StreamCompression`]; the response body is decompressed
+//! transparently by reqwest.
 
 use crate::errors::EngineError;
+use crate::replication_compression::StreamCompression;
 use reqwest::Client;
 use std::time::Duration;
 use tokio::time::sleep;
@@ -23,16 +29,36 @@ const INITIAL_BACKOFF_MS: u64 = 500;
 const MAX_BACKOFF_MS: u64 = 8_000;
 
 /// Minimal proof response matching the `/v1/proof/state` wire format.
-/// The endpoint returns `{"final_state_hash": "<64-char hex>"}`.
+/// The endpoint returns `{"final_state_hash": "<64-char hex>", "version": N}`.
 #[derive(Debug, Clone, serde::Deserialize)]
 pub struct LeaderProof {
     pub final_state_hash: String,
+    /// Kernel version the hash was taken at (absent from older leaders).
+    #[serde(default)]
+    pub version: Option<u64>,
+}
+
+/// A snapshot from `GET /v1/snapshot/download` with what the leader
+/// advertised about it in the response headers (absent from older leaders).
+#[derive(Debug, Clone)]
+pub struct LeaderSnapshot {
+    pub bytes: Vec<u8>,
+    /// BLAKE3 of `bytes` (hex).
+    pub bytes_hash: Option<String>,
+    /// `final_state_hash` of the state the snapshot holds (hex).
+    pub state_hash: Option<String>,
+    /// The leader's committed height when the snapshot was taken.
+    pub height: Option<u64>,
 }
 
 #[derive(Debug, Clone)]
 pub struct LeaderClient {
     base_url: String,
     client: Client,
+    /// Bearer token sent on every request (the follower's `replicator` role).
+    token: Option<String>,
+    /// Encoding requested for the event stream.
+    compression: StreamCompression,
 }
 
 impl LeaderClient {
@@ -43,6 +69,43 @@ impl LeaderClient {
                 .timeout(Duration::from_secs(30))
                 .build()
                 .expect("failed to build reqwest client"),
+            token: None,
+            compression: StreamCompression::Identity,
+        }
+    }
+
+    /// Present `tls`'s certificate to the leader and trust only its CA.
+    /// The leader URL must be `https://`.
+    pub fn with_tls(
+        mut self,
+        tls: Option<&crate::tls::ReplicationTlsConfig>,
+    ) -> Result<Self, EngineError> {
+        if let Some(tls) = tls {
+            self.client = tls
+                .client_builder(Client::builder().timeout(Duration::from_secs(30)))
+                .and_then(|b| b.build().map_err(|e| e.to_string()))
+                .map_err(|e| EngineError::Network(format!("replication TLS: {e}")))?;
+        }
+        Ok(self)
+    }
+
+    /// Authenticate every request to the leader with `token`.
+    pub fn with_token(mut self, token: Option<String>) -> Self {
+        self.token = token;
+        self
+    }
+
+    /// Ask the leader to compress the event stream with `compression`.
+    pub fn with_compression(mut self, compression: StreamCompression) -> Self {
+        self.compression = compression;
+        self
+    }
+
+    fn get(&self, url: &str) -> reqwest::RequestBuilder {
+        let req = self.client.get(url);
+        match &self.token {
+            Some(t) => req.bearer_auth(t),
+            None => req,
         }
     }
 
@@ -74,7 +137,7 @@ impl LeaderClient {
                 sleep(Duration::from_millis(delay)).await;
             }
 
-            match self.client.get(&url).send().await {
+            match self.get(&url).send().await {
                 Ok(resp) if resp.status().is_success() => {
                     return resp
                         .json::<LeaderProof>()
@@ -108,9 +171,21 @@ impl LeaderClient {
             "{}/v1/replication/events?start_offset={}",
             self.base_url, start_offset
         );
+        // Chained frames let the follower verify every entry; a leader that
+        // predates them answers with plain frames.
+        use crate::replication_codec::{CHAINED_FRAMES_CONTENT_TYPE, FRAMES_CONTENT_TYPE};
+        // Always send the header: left unset, reqwest would advertise every
+        // decoder it was built with.
         let resp = self
-            .client
             .get(&url)
+            .header(
+                reqwest::header::ACCEPT,
+                format!("{CHAINED_FRAMES_CONTENT_TYPE}, {FRAMES_CONTENT_TYPE}"),
+            )
+            .header(
+                reqwest::header::ACCEPT_ENCODING,
+                self.compression.header_value().unwrap_or("identity"),
+            )
             .send()
             .await
             .map_err(|e| EngineError::Network(e.to_string()))?;
@@ -125,35 +200,170 @@ impl LeaderClient {
         Ok(resp)
     }
 
+    /// Exchange leader epochs with the leader: send the newest epoch this
+    /// node has seen, get the leader's. `None` from a leader that predates
+    /// the handshake (404). A fenced leader answers 409, returned as an error.
+    pub async fn handshake(
+        &self,
+        epoch: u64,
+    ) -> Result<Option<valori_engine::LeaderEpoch>, EngineError> {
+        let url = format!("{}/v1/replication/handshake?epoch={}", self.base_url, epoch);
+        let resp = self
+            .get(&url)
+            .send()
+            .await
+            .map_err(|e| EngineError::Network(e.to_string()))?;
+        match resp.status() {
+            reqwest::StatusCode::NOT_FOUND => Ok(None),
+            status if status.is_success() => resp
+                .json()
+                .await
+                .map(Some)
+                .map_err(|e| EngineError::Network(e.to_string())),
+            status => Err(EngineError::Network(format!(
+                "Handshake request failed: {}",
+                status
+            ))),
+        }
+    }
+
     /// Download the full leader snapshot, retrying on transient errors.
     pub async fn download_snapshot(&self) -> Result<Vec<u8>, EngineError> {
         let url = format!("{}/v1/snapshot/download", self.base_url);
+        self.get_bytes_with_retry(&url, "Snapshot").await
+    }
+
+    /// [`Self::download_snapshot`] with the leader's advertised hashes and
+    /// height, retrying on transient errors.
+    pub async fn download_snapshot_with_proof(&self) -> Result<LeaderSnapshot, EngineError> {
+        use crate::snapshot_stream::{HEIGHT_HEADER, SNAPSHOT_HASH_HEADER, STATE_HASH_HEADER};
+
+        let url = format!("{}/v1/snapshot/download", self.base_url);
+        let resp = self.get_with_retry(&url, "Snapshot").await?;
+        let header = |name: &str| {
+            resp.headers()
+                .get(name)
+                .and_then(|v| v.to_str().ok())
+                .map(str::to_string)
+        };
+        let bytes_hash = header(SNAPSHOT_HASH_HEADER);
+        let state_hash = header(STATE_HASH_HEADER);
+        let height = header(HEIGHT_HEADER).and_then(|h| h.parse().ok());
+        let bytes = resp
+            .bytes()
+            .await
+            .map_err(|e| EngineError::Network(e.to_string()))?
+            .to_vec();
+        Ok(LeaderSnapshot {
+            bytes,
+            bytes_hash,
+            state_hash,
+            height,
+        })
+    }
+
+    /// List the leader's sealed event-log segments, retrying on transient errors.
+    pub async fn list_segments(
+        &self,
+    ) -> Result<crate::replication_segments::SegmentIndex, EngineError> {
+        let url = format!("{}/v1/replication/segments", self.base_url);
+        let bytes = self.get_bytes_with_retry(&url, "Segment index").await?;
+        serde_json::from_slice(&bytes).map_err(|e| EngineError::Network(e.to_string()))
+    }
+
+    /// Download sealed segment `seq` (raw on-disk bytes), retrying on
+    /// transient errors.
+    pub async fn download_segment(&self, seq: u32) -> Result<Vec<u8>, EngineError> {
+        let url = format!("{}/v1/replication/segments/{}", self.base_url, seq);
+        self.get_bytes_with_retry(&url, "Segment").await
+    }
+
+    /// The leader's collections as `(name, namespace id)`, including
+    /// `default`, retrying on transient errors.
+    pub async fn list_collections(&self) -> Result<Vec<(String, u16)>, EngineError> {
+        let url = format!("{}/v1/namespaces", self.base_url);
+        let bytes = self.get_bytes_with_retry(&url, "Collection list").await?;
+        let list: crate::api::ListCollectionsResponse =
+            serde_json::from_slice(&bytes).map_err(|e| EngineError::Network(e.to_string()))?;
+        Ok(list
+            .collections
+            .into_iter()
+            .map(|c| (c.name, c.id))
+            .collect())
+    }
+
+    /// The state hash a partial replica with `filter` has at `height`,
+    /// replayed by the leader, retrying on transient errors.
+    pub async fn get_partial_proof(
+        &self,
+        filter: &crate::replication_filter::ReplicationFilter,
+        height: u64,
+    ) -> Result<crate::replication_filter::PartialProof, EngineError> {
+        let url = format!(
+            "{}/v1/replication/partial-proof?{}&height={}",
+            self.base_url,
+            filter.query(),
+            height
+        );
+        let bytes = self.get_bytes_with_retry(&url, "Partial proof").await?;
+        serde_json::from_slice(&bytes).map_err(|e| EngineError::Network(e.to_string()))
+    }
+
+    /// The leader's committed height and epoch, retrying on transient
+    /// errors.
+    pub async fn head(&self) -> Result<crate::geo_replication::ReplicationHead, EngineError> {
+        let url = format!("{}/v1/replication/head", self.base_url);
+        let bytes = self.get_bytes_with_retry(&url, "Head").await?;
+        serde_json::from_slice(&bytes).map_err(|e| EngineError::Network(e.to_string()))
+    }
+
+    /// The leader's event chain at `height` (hex), retrying on transient
+    /// errors.
+    pub async fn chain_hash(&self, height: u64) -> Result<String, EngineError> {
+        let url = format!("{}/v1/replication/chain?height={}", self.base_url, height);
+        let bytes = self.get_bytes_with_retry(&url, "Event chain").await?;
+        serde_json::from_slice::<crate::geo_replication::ChainProof>(&bytes)
+            .map(|p| p.chain_hash)
+            .map_err(|e| EngineError::Network(e.to_string()))
+    }
+
+    /// GET `url` and return the body. 4xx fails immediately; 5xx and
+    /// network errors are retried with backoff.
+    async fn get_bytes_with_retry(&self, url: &str, what: &str) -> Result<Vec<u8>, EngineError> {
+        self.get_with_retry(url, what)
+            .await?
+            .bytes()
+            .await
+            .map(|b| b.to_vec())
+            .map_err(|e| EngineError::Network(e.to_string()))
+    }
+
+    /// GET `url` until it succeeds, as [`Self::get_bytes_with_retry`].
+    async fn get_with_retry(
+        &self,
+        url: &str,
+        what: &str,
+    ) -> Result<reqwest::Response, EngineError> {
         let mut last_err = EngineError::Network("unreachable".into());
 
         for attempt in 0..MAX_RETRIES {
             let delay = Self::backoff_ms(attempt);
             if delay > 0 {
-                tracing::debug!("download_snapshot: retry {} after {}ms", attempt, delay);
+                tracing::debug!("{} request: retry {} after {}ms", what, attempt, delay);
                 sleep(Duration::from_millis(delay)).await;
             }
 
-            match self.client.get(&url).send().await {
-                Ok(resp) if resp.status().is_success() => {
-                    return resp
-                        .bytes()
-                        .await
-                        .map(|b| b.to_vec())
-                        .map_err(|e| EngineError::Network(e.to_string()));
-                }
+            match self.get(url).send().await {
+                Ok(resp) if resp.status().is_success() => return Ok(resp),
                 Ok(resp) => {
                     let status = resp.status();
                     if status.is_client_error() {
                         return Err(EngineError::Network(format!(
-                            "Snapshot request failed: {}",
-                            status
+                            "{} request failed: {}",
+                            what, status
                         )));
                     }
-                    last_err = EngineError::Network(format!("Snapshot request failed: {}", status));
+                    last_err = EngineError::Network(format!("{} request failed: {}", what, status));
                 }
                 Err(e) => {
                     last_err = EngineError::Network(e.to_string());
@@ -1,169 +1,520 @@
-use crate::config::{IndexKind, QuantizationKind};
-use serde::{Deserialize, Serialize};
-use std::fs::File;
-use std::io::Write;
 // Copyright (c) 2025 Varshith Gudur. Dual-licensed under MIT OR Apache-2.0.
-use crc32fast::Hasher;
-use std::path::Path;
+//! Snapshot catalog and retention. The snapshots themselves are the engine's
+//! bytes, in the container format of
+//! [`valori_kernel::snapshot::container`].
+use serde::{Deserialize, Serialize};
+use std::collections::BTreeSet;
+use std::path::{Path, PathBuf};
+use std::sync::Mutex;
+
+/// Index file of the snapshot catalog, next to the cataloged snapshots.
+const CATALOG_FILE: &str = "catalog.json";
+
+/// Serialises catalog read-modify-write cycles within the process.
+static CATALOG_LOCK: Mutex<()> = Mutex::new(());
+
+/// Staging directory for multipart uploads, inside the catalog directory.
+const UPLOADS_DIR: &str = "uploads";
+
+/// Serialises multipart upload steps within the process.
+static UPLOAD_LOCK: Mutex<()> = Mutex::new(());
+
+/// One restore point in the snapshot catalog.
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq)]
+pub struct SnapshotEntry {
+    pub id: u64,
+    /// Committed event height when the snapshot was taken (0 without an
+    /// event log).
+    pub height: u64,
+    /// BLAKE3 state hash at that height.
+    pub hash: String,
+    /// Unix seconds.
+    pub timestamp: u64,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub label: Option<String>,
+    /// Snapshot file name inside the catalog directory.
+    pub file: String,
+    pub size_bytes: u64,
+}
 
-const MAGIC: u32 = 0x56414C4F; // VALO
-const SCHEMA_VERSION: u32 = 2;
+/// Which cataloged snapshots survive pruning. A snapshot is kept when any
+/// rule keeps it. Labelled snapshots are operator restore points and are
+/// never pruned.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub struct SnapshotRetention {
+    /// Keep the newest `keep_last` snapshots.
+    pub keep_last: usize,
+    /// Keep the newest snapshot of each of the `keep_daily` most recent UTC
+    /// days that have one.
+    pub keep_daily: usize,
+    /// For every event-log checkpoint height, keep the newest snapshot at or
+    /// below it — the one a replay of the following segments starts from.
+    pub keep_checkpoints: bool,
+}
 
-#[derive(Serialize, Deserialize, Debug)]
-pub struct SnapshotMeta {
-    pub version: u32,
-    pub timestamp: u64,
-    pub kernel_len: u64,
-    pub metadata_len: u64, // Length of MetadataStore blob
-    pub index_len: u64,
-    pub index_kind: IndexKind,
-    pub quant_kind: QuantizationKind,
-    #[serde(default)] // Backwards compatibility for prev version
-    pub deterministic_build: bool,
-    #[serde(default)]
-    pub algorithm_params: serde_json::Value,
+impl SnapshotRetention {
+    /// Ids of the unlabelled `entries` this policy prunes. `checkpoints` are
+    /// the committed heights at which event-log segments were sealed.
+    pub fn prunable(&self, entries: &[SnapshotEntry], checkpoints: &[u64]) -> Vec<u64> {
+        let mut newest: Vec<&SnapshotEntry> =
+            entries.iter().filter(|e| e.label.is_none()).collect();
+        newest.sort_by(|a, b| b.id.cmp(&a.id));
+
+        let mut keep: BTreeSet<u64> = newest.iter().take(self.keep_last).map(|e| e.id).collect();
+        let mut days = BTreeSet::new();
+        for e in &newest {
+            let day = e.timestamp / 86_400;
+            if days.len() < self.keep_daily && days.insert(day) {
+                keep.insert(e.id);
+            }
+        }
+        if self.keep_checkpoints {
+            for &h in checkpoints {
+                if let Some(e) = newest
+                    .iter()
+                    .filter(|e| e.height <= h)
+                    .max_by_key(|e| (e.height, e.id))
+                {
+                    keep.insert(e.id);
+                }
+            }
+        }
+
+        let mut pruned: Vec<u64> = newest
+            .iter()
+            .map(|e| e.id)
+            .filter(|id| !keep.contains(id))
+            .collect();
+        pruned.sort_unstable();
+        pruned
+    }
+}
+
+/// A multipart snapshot upload in progress. Staged as `<id>.part` (the bytes
+/// received so far) and `<id>.json` (this record) in `uploads/` inside the
+/// catalog directory.
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq)]
+pub struct UploadSession {
+    pub upload_id: String,
+    /// Size of the whole snapshot, declared at init.
+    pub size: u64,
+    /// BLAKE3 hex of the whole snapshot, checked at finalize.
+    pub hash: String,
+    /// Bytes received and made durable; the next chunk starts here.
+    pub received: u64,
+}
+
+/// Why a multipart upload step was refused.
+#[derive(Debug)]
+pub enum UploadError {
+    /// No upload with that id (never started, finalized, or aborted).
+    NotFound,
+    /// The chunk does not start where the upload left off. Resume from
+    /// `received`.
+    Offset {
+        received: u64,
+    },
+    /// The chunk does not match its hash; resend it.
+    ChunkHash,
+    /// The chunk runs past the declared size.
+    TooLarge,
+    /// Finalize before every byte arrived.
+    Incomplete {
+        received: u64,
+        size: u64,
+    },
+    /// The assembled snapshot does not match the hash declared at init.
+    SnapshotHash,
+    Io(std::io::Error),
+}
+
+impl std::fmt::Display for UploadError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Self::NotFound => write!(f, "no such upload"),
+            Self::Offset { received } => {
+                write!(f, "chunk offset does not match: resume from {received}")
+            }
+            Self::ChunkHash => write!(f, "chunk hash mismatch — resend the chunk"),
+            Self::TooLarge => write!(f, "chunk runs past the declared snapshot size"),
+            Self::Incomplete { received, size } => {
+                write!(f, "upload incomplete: {received} of {size} bytes")
+            }
+            Self::SnapshotHash => write!(f, "snapshot hash mismatch — upload discarded"),
+            Self::Io(e) => write!(f, "upload staging: {e}"),
+        }
+    }
+}
+
+impl From<std::io::Error> for UploadError {
+    fn from(e: std::io::Error) -> Self {
+        Self::Io(e)
+    }
+}
+
+#[derive(Serialize, Deserialize, Debug, Default)]
+struct SnapshotCatalog {
+    next_id: u64,
+    entries: Vec<SnapshotEntry>,
 }
 
 pub struct SnapshotManager;
 
+/// Snapshot catalog — several restore points kept side by side instead of
+/// one overwritten file. Snapshots are written as `snapshot-<id>.val` in the
+/// catalog directory and indexed by `catalog.json`; both are replaced
+/// atomically (write to `.tmp`, then rename).
 impl SnapshotManager {
-    pub fn save(
-        path: &Path,
-        kernel_data: &[u8],
-        metadata_data: &[u8],    // MetadataStore blob
-        meta: &mut SnapshotMeta, // Mutable to update lengths
-        index_data: &[u8],
-    ) -> Result<(), std::io::Error> {
-        let tmp_path = path.with_extension("tmp");
-
-        // Update lengths
-        meta.kernel_len = kernel_data.len() as u64;
-        meta.metadata_len = metadata_data.len() as u64;
-        meta.index_len = index_data.len() as u64;
-
-        {
-            let mut file = File::create(&tmp_path)?;
-            let mut hasher = Hasher::new();
-
-            // Serialize Meta (Header)
-            let meta_json = serde_json::to_vec(meta)?;
-            let meta_len = meta_json.len() as u32;
-
-            // Write Helper
-            let mut write_chunk = |data: &[u8]| -> std::io::Result<()> {
-                file.write_all(data)?;
-                hasher.update(data);
-                Ok(())
-            };
-
-            // [MAGIC][VER][META_LEN]
-            write_chunk(&MAGIC.to_le_bytes())?;
-            write_chunk(&SCHEMA_VERSION.to_le_bytes())?;
-            write_chunk(&meta_len.to_le_bytes())?;
-
-            // [META_JSON]
-            write_chunk(&meta_json)?;
-
-            // [KERNEL]
-            write_chunk(kernel_data)?;
-
-            // [METADATA_STORE]
-            write_chunk(metadata_data)?;
-
-            // [INDEX]
-            write_chunk(index_data)?;
-
-            // [CRC]
-            let checksum = hasher.finalize();
-            file.write_all(&checksum.to_le_bytes())?;
-        }
+    /// Catalog directory for a node whose snapshot path is `snapshot_path`:
+    /// a `snapshots/` directory next to it.
+    pub fn catalog_dir(snapshot_path: &Path) -> PathBuf {
+        snapshot_path
+            .parent()
+            .unwrap_or_else(|| Path::new("."))
+            .join("snapshots")
+    }
 
-        // ROTATION LOGIC: Keep one previous version
-        if path.exists() {
-            let prev_path = path.with_extension("bin.prev");
-            let _ = std::fs::rename(path, prev_path); // Ignore error if rename fails (e.g. permission)
-        }
+    /// Every cataloged snapshot, oldest first. Empty when the catalog does
+    /// not exist yet.
+    pub fn list(dir: &Path) -> Result<Vec<SnapshotEntry>, std::io::Error> {
+        Ok(Self::read_catalog(dir)?.entries)
+    }
+
+    /// Write `data` as a new catalog entry and return it.
+    pub fn add(
+        dir: &Path,
+        data: &[u8],
+        height: u64,
+        hash: String,
+        label: Option<String>,
+    ) -> Result<SnapshotEntry, std::io::Error> {
+        let _guard = CATALOG_LOCK.lock().unwrap_or_else(|e| e.into_inner());
+        std::fs::create_dir_all(dir)?;
+        let mut catalog = Self::read_catalog(dir)?;
+        let id = catalog.next_id;
+        let file = format!("snapshot-{id:06}.val");
+        write_atomic(&dir.join(&file), data)?;
+
+        let entry = SnapshotEntry {
+            id,
+            height,
+            hash,
+            timestamp: std::time::SystemTime::now()
+                .duration_since(std::time::UNIX_EPOCH)
+                .map(|d| d.as_secs())
+                .unwrap_or(0),
+            label,
+            file,
+            size_bytes: data.len() as u64,
+        };
+        catalog.next_id += 1;
+        catalog.entries.push(entry.clone());
+        write_atomic(
+            &dir.join(CATALOG_FILE),
+            &serde_json::to_vec_pretty(&catalog)?,
+        )?;
+        Ok(entry)
+    }
 
-        std::fs::rename(tmp_path, path)?;
-        Ok(())
+    /// The entry with `id` and its snapshot bytes, or `None` if the catalog
+    /// has no such entry.
+    pub fn load(dir: &Path, id: u64) -> Result<Option<(SnapshotEntry, Vec<u8>)>, std::io::Error> {
+        let Some(entry) = Self::list(dir)?.into_iter().find(|e| e.id == id) else {
+            return Ok(None);
+        };
+        let data = std::fs::read(dir.join(&entry.file))?;
+        Ok(Some((entry, data)))
     }
 
-    pub fn parse(
-        buffer: &[u8],
-    ) -> Result<(SnapshotMeta, Vec<u8>, Vec<u8>, Vec<u8>), Box<dyn std::error::Error + Send + Sync>>
-    {
-        if buffer.len() < 16 {
-            return Err("Snapshot too short".into());
+    /// Delete the snapshots `retention` does not keep and return their
+    /// entries. Files already gone are dropped from the catalog quietly.
+    pub fn prune(
+        dir: &Path,
+        retention: &SnapshotRetention,
+        checkpoints: &[u64],
+    ) -> Result<Vec<SnapshotEntry>, std::io::Error> {
+        let _guard = CATALOG_LOCK.lock().unwrap_or_else(|e| e.into_inner());
+        let mut catalog = Self::read_catalog(dir)?;
+        let doomed = retention.prunable(&catalog.entries, checkpoints);
+        if doomed.is_empty() {
+            return Ok(Vec::new());
         }
+        let (removed, kept): (Vec<_>, Vec<_>) = catalog
+            .entries
+            .into_iter()
+            .partition(|e| doomed.contains(&e.id));
+        catalog.entries = kept;
+        // Catalog first: a crash in between leaves orphan files, never
+        // entries pointing at deleted ones.
+        write_atomic(
+            &dir.join(CATALOG_FILE),
+            &serde_json::to_vec_pretty(&catalog)?,
+        )?;
+        for entry in &removed {
+            match std::fs::remove_file(dir.join(&entry.file)) {
+                Err(e) if e.kind() != std::io::ErrorKind::NotFound => return Err(e),
+                _ => {}
+            }
+        }
+        Ok(removed)
+    }
 
-        // Check Trailer
-        let split_idx = buffer.len() - 4;
-        let (content, trailer) = buffer.split_at(split_idx);
-        let stored_crc = u32::from_le_bytes(trailer.try_into().unwrap());
+    /// Committed heights at which the event log at `live_path` was sealed
+    /// into a new segment, ascending.
+    pub fn checkpoint_heights(live_path: &Path) -> Vec<u64> {
+        let Ok(index) = crate::replication_segments::index(live_path) else {
+            return Vec::new();
+        };
+        let heights: BTreeSet<u64> = index
+            .archived
+            .iter()
+            .filter_map(|s| s.first_height)
+            .chain([index.live_first_height])
+            .filter(|&h| h > 0)
+            .collect();
+        heights.into_iter().collect()
+    }
 
-        let mut hasher = Hasher::new();
-        hasher.update(content);
-        if hasher.finalize() != stored_crc {
-            return Err("Checksum mismatch".into());
-        }
+    /// One autosave: write `engine`'s snapshot to `path` and, with a
+    /// retention policy, also add it to the catalog and prune. Returns how
+    /// many cataloged snapshots were pruned.
+    pub fn autosave(
+        engine: &crate::engine::Engine,
+        path: &Path,
+        retention: Option<&SnapshotRetention>,
+    ) -> Result<usize, Box<dyn std::error::Error + Send + Sync>> {
+        let Some(retention) = retention else {
+            engine.save_snapshot(Some(path))?;
+            return Ok(0);
+        };
+        let data = engine.snapshot()?;
+        write_atomic(path, &data)?;
+        let committer = engine.event_committer();
+        let height = committer.map_or(0, |c| c.journal().committed_height());
+        let checkpoints = committer
+            .map(|c| Self::checkpoint_heights(c.event_log().path()))
+            .unwrap_or_default();
+        let dir = Self::catalog_dir(path);
+        Self::add(&dir, &data, height, engine.state_hash_hex(), None)?;
+        Ok(Self::prune(&dir, retention, &checkpoints)?.len())
+    }
+
+    /// Start a multipart upload of a `size`-byte snapshot whose BLAKE3 hash
+    /// is `hash` (hex).
+    pub fn upload_init(dir: &Path, size: u64, hash: &str) -> Result<UploadSession, UploadError> {
+        let uploads = dir.join(UPLOADS_DIR);
+        std::fs::create_dir_all(&uploads)?;
+        let mut buf = [0u8; 16];
+        getrandom::getrandom(&mut buf).map_err(|e| UploadError::Io(std::io::Error::other(e)))?;
+        let session = UploadSession {
+            upload_id: buf.iter().map(|b| format!("{b:02x}")).collect(),
+            size,
+            hash: hash.to_ascii_lowercase(),
+            received: 0,
+        };
+        std::fs::File::create(uploads.join(format!("{}.part", session.upload_id)))?;
+        write_session(&uploads, &session)?;
+        Ok(session)
+    }
 
-        // Parse Header
-        let magic = u32::from_le_bytes(content[0..4].try_into().unwrap());
-        if magic != MAGIC {
-            return Err("Invalid MAGIC".into());
+    /// The upload `id`, to resume it from `received`.
+    pub fn upload_status(dir: &Path, id: &str) -> Result<UploadSession, UploadError> {
+        let (json, _) = upload_paths(dir, id)?;
+        let bytes = std::fs::read(json).map_err(|e| match e.kind() {
+            std::io::ErrorKind::NotFound => UploadError::NotFound,
+            _ => UploadError::Io(e),
+        })?;
+        serde_json::from_slice(&bytes).map_err(|e| UploadError::Io(e.into()))
+    }
+
+    /// Append `data`, which must start at `offset` and hash to `hash`
+    /// (BLAKE3 hex). A chunk is durable once this returns; a chunk cut off by
+    /// a crash is dropped and the upload resumes from the last one.
+    pub fn upload_chunk(
+        dir: &Path,
+        id: &str,
+        offset: u64,
+        data: &[u8],
+        hash: &str,
+    ) -> Result<UploadSession, UploadError> {
+        use std::io::Write;
+        let _guard = UPLOAD_LOCK.lock().unwrap_or_else(|e| e.into_inner());
+        let mut session = Self::upload_status(dir, id)?;
+        if offset != session.received {
+            return Err(UploadError::Offset {
+                received: session.received,
+            });
+        }
+        if offset + data.len() as u64 > session.size {
+            return Err(UploadError::TooLarge);
+        }
+        if !blake3::hash(data).to_hex().eq_ignore_ascii_case(hash) {
+            return Err(UploadError::ChunkHash);
         }
+        let (_, part) = upload_paths(dir, id)?;
+        let mut file = std::fs::OpenOptions::new().append(true).open(&part)?;
+        // Drop the tail of a chunk a crash interrupted.
+        file.set_len(session.received)?;
+        file.write_all(data)?;
+        file.sync_data()?;
+        session.received += data.len() as u64;
+        write_session(&dir.join(UPLOADS_DIR), &session)?;
+        Ok(session)
+    }
 
-        let version = u32::from_le_bytes(content[4..8].try_into().unwrap());
-        if version != SCHEMA_VERSION {
-            return Err("Version mismatch".into());
+    /// Check that the upload is complete and matches the hash declared at
+    /// init, then return its bytes and discard the staging files. A hash
+    /// mismatch discards them too — the upload cannot be repaired.
+    pub fn upload_finalize(dir: &Path, id: &str) -> Result<Vec<u8>, UploadError> {
+        let _guard = UPLOAD_LOCK.lock().unwrap_or_else(|e| e.into_inner());
+        let session = Self::upload_status(dir, id)?;
+        if session.received != session.size {
+            return Err(UploadError::Incomplete {
+                received: session.received,
+                size: session.size,
+            });
+        }
+        let (_, part) = upload_paths(dir, id)?;
+        let mut data = std::fs::read(&part)?;
+        data.truncate(session.size as usize);
+        let matches = blake3::hash(&data).to_hex().as_str() == session.hash;
+        remove_upload(dir, id)?;
+        if !matches {
+            return Err(UploadError::SnapshotHash);
         }
+        Ok(data)
+    }
 
-        let meta_len = u32::from_le_bytes(content[8..12].try_into().unwrap()) as usize;
-        let meta_end = 12 + meta_len;
+    /// Discard the upload `id`.
+    pub fn upload_abort(dir: &Path, id: &str) -> Result<(), UploadError> {
+        let _guard = UPLOAD_LOCK.lock().unwrap_or_else(|e| e.into_inner());
+        Self::upload_status(dir, id)?;
+        remove_upload(dir, id)
+    }
 
-        // BOUNDS CHECK 1: Meta
-        if content.len() < meta_end {
-            return Err("Truncated metadata".into());
+    fn read_catalog(dir: &Path) -> Result<SnapshotCatalog, std::io::Error> {
+        match std::fs::read(dir.join(CATALOG_FILE)) {
+            Ok(bytes) => Ok(serde_json::from_slice(&bytes)?),
+            Err(e) if e.kind() == std::io::ErrorKind::NotFound => Ok(SnapshotCatalog::default()),
+            Err(e) => Err(e),
         }
+    }
+}
 
-        // Parse Meta
-        let meta: SnapshotMeta = serde_json::from_slice(&content[12..meta_end])?;
-
-        // Extract Blobs
-        let k_len = meta.kernel_len as usize;
-        let m_len = meta.metadata_len as usize;
-        let i_len = meta.index_len as usize;
+/// Staging paths (`<id>.json`, `<id>.part`) of upload `id`. Ids are the hex
+/// strings `upload_init` hands out; anything else is not found.
+fn upload_paths(dir: &Path, id: &str) -> Result<(PathBuf, PathBuf), UploadError> {
+    if id.len() != 32 || !id.bytes().all(|b| b.is_ascii_hexdigit()) {
+        return Err(UploadError::NotFound);
+    }
+    let uploads = dir.join(UPLOADS_DIR);
+    Ok((
+        uploads.join(format!("{id}.json")),
+        uploads.join(format!("{id}.part")),
+    ))
+}
 
-        // BOUNDS CHECK 2: Body consistency
-        let remaining_len = content.len() - meta_end;
-        let expected_len = k_len + m_len + i_len;
+fn write_session(uploads: &Path, session: &UploadSession) -> Result<(), std::io::Error> {
+    write_atomic(
+        &uploads.join(format!("{}.json", session.upload_id)),
+        &serde_json::to_vec(session)?,
+    )
+}
 
-        if remaining_len != expected_len {
-            return Err(format!(
-                "Snapshot corrupted: Meta claims {} bytes, found {}",
-                expected_len, remaining_len
-            )
-            .into());
+fn remove_upload(dir: &Path, id: &str) -> Result<(), UploadError> {
+    let (json, part) = upload_paths(dir, id)?;
+    // Record first: without it the part file is an orphan, never a live upload.
+    for path in [json, part] {
+        match std::fs::remove_file(path) {
+            Err(e) if e.kind() != std::io::ErrorKind::NotFound => return Err(e.into()),
+            _ => {}
         }
+    }
+    Ok(())
+}
 
-        let k_start = meta_end;
-        let k_end = k_start + k_len;
-
-        let m_start = k_end;
-        let m_end = m_start + m_len;
-
-        let i_start = m_end;
-        let i_end = i_start + i_len;
+fn write_atomic(path: &Path, data: &[u8]) -> Result<(), std::io::Error> {
+    let tmp_path = path.with_extension("tmp");
+    std::fs::write(&tmp_path, data)?;
+    std::fs::rename(tmp_path, path)
+}
 
-        // Redundant but safe final check
-        if i_end > content.len() {
-            return Err("Truncated body".into());
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn entry(id: u64, height: u64, day: u64, label: Option<&str>) -> SnapshotEntry {
+        SnapshotEntry {
+            id,
+            height,
+            hash: String::new(),
+            timestamp: day * 86_400 + id,
+            label: label.map(String::from),
+            file: format!("snapshot-{id:06}.val"),
+            size_bytes: 0,
         }
+    }
 
-        let k_data = content[k_start..k_end].to_vec();
-        let m_data = content[m_start..m_end].to_vec();
-        let i_data = content[i_start..i_end].to_vec();
+    #[test]
+    fn upload_drops_a_torn_chunk_and_resumes() {
+        let dir = tempfile::tempdir().unwrap();
+        let data = b"0123456789";
+        let hash = blake3::hash(data).to_hex();
+        let s = SnapshotManager::upload_init(dir.path(), 10, &hash).unwrap();
+        let chunk = |d: &[u8]| blake3::hash(d).to_hex().to_string();
+
+        SnapshotManager::upload_chunk(dir.path(), &s.upload_id, 0, b"0123", &chunk(b"0123"))
+            .unwrap();
+        // A crash mid-write left bytes the record never counted.
+        let part = dir
+            .path()
+            .join(UPLOADS_DIR)
+            .join(format!("{}.part", s.upload_id));
+        std::fs::OpenOptions::new()
+            .append(true)
+            .open(&part)
+            .and_then(|mut f| std::io::Write::write_all(&mut f, b"45x"))
+            .unwrap();
+
+        let s2 = SnapshotManager::upload_status(dir.path(), &s.upload_id).unwrap();
+        assert_eq!(s2.received, 4);
+        SnapshotManager::upload_chunk(dir.path(), &s.upload_id, 4, b"456789", &chunk(b"456789"))
+            .unwrap();
+        let out = SnapshotManager::upload_finalize(dir.path(), &s.upload_id).unwrap();
+        assert_eq!(out, data);
+        assert!(!part.exists());
+    }
 
-        Ok((meta, k_data, m_data, i_data))
+    #[test]
+    fn retention_keeps_the_union_of_its_rules() {
+        // Days 0,0,1,1,2,2 at heights 10..60; #1 is labelled.
+        let entries: Vec<SnapshotEntry> = (0..6)
+            .map(|i| entry(i, (i + 1) * 10, i / 2, (i == 1).then_some("manual")))
+            .collect();
+
+        let last = SnapshotRetention {
+            keep_last: 2,
+            ..Default::default()
+        };
+        assert_eq!(last.prunable(&entries, &[]), vec![0, 2, 3]);
+
+        let daily = SnapshotRetention {
+            keep_daily: 2,
+            ..Default::default()
+        };
+        assert_eq!(daily.prunable(&entries, &[]), vec![0, 2, 4]);
+
+        let checkpoints = SnapshotRetention {
+            keep_last: 1,
+            keep_checkpoints: true,
+            ..Default::default()
+        };
+        // Checkpoint 35 keeps height 30 (#2); 5 precedes every snapshot.
+        assert_eq!(checkpoints.prunable(&entries, &[5, 35]), vec![0, 3, 4]);
+
+        assert_eq!(
+            SnapshotRetention::default().prunable(&entries, &[]).len(),
+            5
+        );
     }
 }
@@ -0,0 +1,96 @@
+// Copyright (c) 2025 Varshith Gudur. Dual-licensed under MIT OR Apache-2.0.
+//! Recall estimation against exact search.
+//!
+//! `POST /v1/admin/recall` runs
+//! [`Engine::estimate_recall`](crate::engine::Engine::estimate_recall) once,
+//! for one index. With `VALORI_RECALL_CHECK_SECS` set, this task runs it on
+//! a timer for every index (`VALORI_RECALL_SAMPLE` queries at
+//! `VALORI_RECALL_K`). A falling recall means the HNSW / IVF parameters, or
+//! the graph after heavy deletes, are costing result quality.
+//!
+//! The job holds a read lock while it runs, which costs `sample × records`
+//! distance computations. Writes wait behind it, so keep the sample modest
+//! on large collections.
+//!
+//! Metrics, labelled by `index`: `valori_index_recall` (mean recall@k),
+//! `valori_index_recall_min`, `valori_index_recall_sampled` and
+//! `valori_index_recall_runs_total`.
+
+use crate::engine::{EngineError, RecallReport};
+use crate::server::SharedEngine;
+
+/// Queries per run when the request or config does not say.
+pub const DEFAULT_SAMPLE: usize = 100;
+/// `k` when the request or config does not say.
+pub const DEFAULT_K: usize = 10;
+/// Largest sample a single run accepts.
+pub const MAX_SAMPLE: usize = 10_000;
+
+/// Estimate recall for `index` (`None` = primary) off the async runtime and
+/// publish it as metrics.
+pub async fn run_once(
+    state: &SharedEngine,
+    sample: usize,
+    k: usize,
+    index: Option<String>,
+) -> Result<RecallReport, EngineError> {
+    if sample == 0 || sample > MAX_SAMPLE {
+        return Err(EngineError::InvalidInput(format!(
+            "sample must be between 1 and {MAX_SAMPLE}"
+        )));
+    }
+    let engine = state.clone().read_owned().await;
+    let report =
+        tokio::task::spawn_blocking(move || engine.estimate_recall(sample, k, index.as_deref()))
+            .await
+            .map_err(|e| {
+                tracing::error!("Recall estimation task panicked: {:?}", e);
+                EngineError::Internal
+            })??;
+
+    let label = report.index.clone();
+    metrics::gauge!("valori_index_recall", report.recall, "index" => label.clone());
+    metrics::gauge!("valori_index_recall_min", report.min_recall, "index" => label.clone());
+    metrics::gauge!(
+        "valori_index_recall_sampled",
+        report.sampled as f64,
+        "index" => label.clone()
+    );
+    metrics::counter!("valori_index_recall_runs_total", 1, "index" => label);
+    tracing::info!(
+        index = %report.index,
+        k = report.k,
+        sampled = report.sampled,
+        recall = report.recall,
+        min_recall = report.min_recall,
+        imperfect = report.imperfect,
+        "Recall estimated"
+    );
+    Ok(report)
+}
+
+/// Spawn the periodic estimate over every index. The first tick fires
+/// immediately.
+pub fn spawn_recall_job(
+    state: SharedEngine,
+    interval_secs: u64,
+    sample: usize,
+    k: usize,
+) -> tokio::task::JoinHandle<()> {
+    tokio::spawn(async move {
+        let mut interval = tokio::time::interval(std::time::Duration::from_secs(interval_secs));
+        interval.set_missed_tick_behavior(tokio::time::MissedTickBehavior::Skip);
+        loop {
+            interval.tick().await;
+            let names: Vec<String> = {
+                let engine = state.read().await;
+                engine.index_names().iter().map(|n| n.to_string()).collect()
+            };
+            for name in names {
+                if let Err(e) = run_once(&state, sample, k, Some(name.clone())).await {
+                    tracing::warn!(index = %name, "Recall estimation skipped: {e}");
+                }
+            }
+        }
+    })
+}
@@ -1,17 +1,38 @@
 use crate::errors::EngineError;
 use crate::events::event_log::LogEntry;
+use crate::replication_codec::{StreamDecoder, StreamFormat};
 use std::path::PathBuf;
 use tokio::fs::File;
 use tokio::io::{AsyncReadExt, BufReader};
 
+/// Stream committed data events from `start_offset` — first replayed from the
+/// log file, then live from `live_rx` — each wrapped for the wire in `format`.
+///
+/// `chain_anchor` is the event chain at `start_offset`; when set, every
+/// entry goes out with the chain before it (chained frames).
 pub async fn spawn_replication_stream(
     file_path: PathBuf,
     mut live_rx: tokio::sync::broadcast::Receiver<LogEntry>,
     start_offset: u64,
-) -> Result<tokio::sync::mpsc::Receiver<Result<String, EngineError>>, EngineError> {
+    format: StreamFormat,
+    chain_anchor: Option<[u8; 32]>,
+) -> Result<tokio::sync::mpsc::Receiver<Result<Vec<u8>, EngineError>>, EngineError> {
     let (tx, rx) = tokio::sync::mpsc::channel(100);
 
     tokio::spawn(async move {
+        let mut chain = chain_anchor;
+        // Wrap one sent entry, advancing the chain past data events.
+        let mut wrap = move |entry: &LogEntry, entry_bytes: &[u8]| match chain.as_mut() {
+            Some(head) => {
+                let wire = format.encode_chained(head, entry_bytes);
+                if let Some((namespace_id, event)) = data_event(entry) {
+                    *head = crate::geo_replication::event_chain_advance(head, namespace_id, event);
+                }
+                wire
+            }
+            None => format.encode(entry_bytes),
+        };
+
         let mut recent_hashes = std::collections::VecDeque::new();
         let max_history = 1000;
 
@@ -48,19 +69,25 @@ pub async fn spawn_replication_stream(
                             }
                             recent_hashes.push_back(hash);
 
+                            // A segment opened by rotation or bootstrap starts
+                            // with a checkpoint carrying the committed height,
+                            // so offsets stay global across rotations.
+                            if let Some(event_count) = chained.entry.checkpoint_height() {
+                                current_idx = event_count;
+                            }
                             // S15: stream both plain and namespace-scoped data
                             // events (checkpoints/admin are not replayed here).
                             if matches!(
                                 &chained.entry,
                                 LogEntry::Event(_) | LogEntry::EventNs { .. }
                             ) {
-                                if current_idx >= start_offset {
-                                    use base64::{engine::general_purpose::STANDARD, Engine as _};
-                                    let b64 = STANDARD.encode(&entry_bytes);
-                                    let json = format!(r#"{{"b64":"{}"}}"#, b64);
-                                    if tx.send(Ok(json + "\n")).await.is_err() {
-                                        return;
-                                    }
+                                if current_idx >= start_offset
+                                    && tx
+                                        .send(Ok(wrap(&chained.entry, &entry_bytes)))
+                                        .await
+                                        .is_err()
+                                {
+                                    return;
                                 }
                                 current_idx += 1;
                             }
@@ -88,10 +115,7 @@ pub async fn spawn_replication_stream(
                     }
                     recent_hashes.push_back(hash);
 
-                    use base64::{engine::general_purpose::STANDARD, Engine as _};
-                    let b64 = STANDARD.encode(&entry_bytes);
-                    let json = format!(r#"{{"b64":"{}"}}"#, b64);
-                    if tx.send(Ok(json + "\n")).await.is_err() {
+                    if tx.send(Ok(wrap(&entry, &entry_bytes))).await.is_err() {
                         return;
                     }
                 }
@@ -103,9 +127,23 @@ pub async fn spawn_replication_stream(
     Ok(rx)
 }
 
+/// The namespace and kernel event of a data entry.
+pub(crate) fn data_event(entry: &LogEntry) -> Option<(u16, &KernelEvent)> {
+    match entry {
+        LogEntry::Event(event) => Some((valori_kernel::types::id::DEFAULT_NS.0, event)),
+        LogEntry::EventNs {
+            namespace_id,
+            event,
+        } => Some((*namespace_id, event)),
+        _ => None,
+    }
+}
+
 use crate::network::LeaderClient;
+use crate::replication_filter::{ReplicationFilter, ResolvedFilter};
 use crate::server::SharedEngine;
 use tokio_stream::StreamExt;
+use valori_kernel::event::KernelEvent;
 
 #[derive(Debug, Clone, Copy, PartialEq, serde::Serialize)]
 pub enum ReplicationState {
@@ -131,18 +169,73 @@ pub fn replication_display_state() -> &'static str {
         1 => "Synced",
         2 => "Diverged",
         3 => "Healing",
+        4 => "SplitBrain",
         _ => "Unknown",
     }
 }
 
 pub async fn run_follower_loop(state: SharedEngine, leader_url: String) {
-    let client = LeaderClient::new(leader_url);
+    run_follower_loop_with_client(state, LeaderClient::new(leader_url)).await
+}
+
+/// Same as [`run_follower_loop`] with a pre-configured client (e.g. one that
+/// carries a `replicator` bearer token).
+pub async fn run_follower_loop_with_client(state: SharedEngine, client: LeaderClient) {
+    run_follower_loop_with_filter(state, client, None).await
+}
 
+/// A follower's partial-replication filter, re-resolved against the
+/// leader's collections whenever an event lands in a namespace it has not
+/// seen (see [`crate::replication_filter`]).
+pub(crate) struct PartialReplica {
+    filter: ReplicationFilter,
+    resolved: ResolvedFilter,
+}
+
+impl PartialReplica {
+    fn new(filter: ReplicationFilter) -> Self {
+        // Resolved lazily: the first event re-resolves against the leader.
+        let resolved = filter.resolve(&[]);
+        Self { filter, resolved }
+    }
+
+    /// Make sure `namespace_id` is resolved before an event in it is
+    /// filtered.
+    async fn resolve_for(
+        &mut self,
+        namespace_id: u16,
+        client: &LeaderClient,
+    ) -> Result<(), EngineError> {
+        if !self.resolved.is_unresolved(namespace_id) {
+            return Ok(());
+        }
+        let registry = client.list_collections().await?;
+        self.resolved = self.filter.resolve(&registry);
+        self.resolved.settle(namespace_id);
+        Ok(())
+    }
+}
+
+/// Same as [`run_follower_loop_with_client`], keeping only the records
+/// `filter` selects when it is set. A partial replica never bootstraps from
+/// the leader's (full) snapshot: it replays from height 0, and checks its
+/// state against the leader's `/v1/replication/partial-proof`.
+pub async fn run_follower_loop_with_filter(
+    state: SharedEngine,
+    client: LeaderClient,
+    filter: Option<ReplicationFilter>,
+) {
     // Single writer; stream loop only reads.
     let (status_tx, mut status_rx) = tokio::sync::watch::channel(ReplicationState::Unknown);
 
     let state_checker = state.clone();
     let client_checker = client.clone();
+    let filter_checker = filter.clone();
+    let mut partial = filter.map(PartialReplica::new);
+    // Where the last verified stream left the chain, so a reconnect at the
+    // same height skips re-reading the log.
+    let mut cursor: Option<ChainCursor> = None;
+    let mut warned_unverified = false;
 
     tokio::spawn(async move {
         loop {
@@ -165,9 +258,16 @@ pub async fn run_follower_loop(state: SharedEngine, leader_url: String) {
                 continue;
             }
 
-            match client_checker.get_proof().await {
-                Ok(proof) => {
-                    let new_state = if proof.final_state_hash == local_hash {
+            let leader_hash = match &filter_checker {
+                Some(f) => client_checker
+                    .get_partial_proof(f, local_height)
+                    .await
+                    .map(|p| p.partial_state_hash),
+                None => client_checker.get_proof().await.map(|p| p.final_state_hash),
+            };
+            match leader_hash {
+                Ok(leader_hash) => {
+                    let new_state = if leader_hash == local_hash {
                         DISPLAY_STATUS.store(1, std::sync::atomic::Ordering::Relaxed);
                         ReplicationState::Synced
                     } else {
@@ -201,17 +301,43 @@ pub async fn run_follower_loop(state: SharedEngine, leader_url: String) {
             }
         };
 
-        if is_empty {
+        // Check the leader's epoch before touching the log: replicating from a
+        // leader whose history forked from ours would overwrite it.
+        if !leader_epoch_ok(&state, &client).await {
+            if state.read().await.fenced.is_some() {
+                DISPLAY_STATUS.store(4, std::sync::atomic::Ordering::Relaxed); // SplitBrain
+                return;
+            }
+            tokio::time::sleep(tokio::time::Duration::from_secs(5)).await;
+            continue;
+        }
+
+        if is_empty && partial.is_none() {
             let _ = bootstrap_from_leader(&state, &client).await;
         }
 
-        let start_offset = {
-            let engine = state.read().await;
-            engine
-                .event_committer()
-                .unwrap()
-                .journal()
-                .committed_height() as u64
+        let start_offset = committed_height(&state).await;
+
+        // The live stream only covers the leader's current segment. Replay
+        // sealed segments for anything older; if they no longer reach back
+        // far enough, fall back to a snapshot.
+        let start_offset = match catch_up_from_segments(&state, &client, start_offset, &mut partial)
+            .await
+        {
+            Ok(h) => h,
+            Err(e) if partial.is_some() => {
+                tracing::warn!(
+                        "Partial replica cannot catch up ({}); it replays the leader's log and never bootstraps from a snapshot",
+                        e
+                    );
+                tokio::time::sleep(tokio::time::Duration::from_secs(5)).await;
+                continue;
+            }
+            Err(e) => {
+                tracing::warn!("Segment catch-up unavailable ({}) — bootstrapping", e);
+                let _ = bootstrap_from_leader(&state, &client).await;
+                committed_height(&state).await
+            }
         };
 
         // Mark the watch as seen before entering the stream loop so we only
@@ -219,8 +345,39 @@ pub async fn run_follower_loop(state: SharedEngine, leader_url: String) {
         status_rx.borrow_and_update();
 
         if let Ok(resp) = client.stream_events(start_offset).await {
+            let format = StreamFormat::from_content_type(
+                resp.headers()
+                    .get(reqwest::header::CONTENT_TYPE)
+                    .and_then(|v| v.to_str().ok()),
+            );
+            if format != StreamFormat::ChainedFrames && !warned_unverified {
+                tracing::warn!(
+                    "Leader {} does not send chained frames — replicated entries are not verified against the event chain",
+                    client.base_url()
+                );
+                warned_unverified = true;
+            }
+            if format == StreamFormat::ChainedFrames {
+                cursor = match cursor.take() {
+                    Some(c) if c.height == start_offset => Some(c),
+                    _ => match chain_anchor(&state, &client, start_offset, partial.is_some()).await
+                    {
+                        Ok(head) => Some(ChainCursor {
+                            height: start_offset,
+                            head,
+                        }),
+                        Err(e) => {
+                            tracing::warn!("Cannot anchor the replication stream: {}", e);
+                            tokio::time::sleep(tokio::time::Duration::from_secs(2)).await;
+                            continue;
+                        }
+                    },
+                };
+            } else {
+                cursor = None;
+            }
+            let mut decoder = StreamDecoder::new(format);
             let mut stream = resp.bytes_stream();
-            let mut buffer = String::new();
             let mut apply_failed = false;
 
             'stream: loop {
@@ -236,75 +393,44 @@ pub async fn run_follower_loop(state: SharedEngine, leader_url: String) {
                 match tokio::time::timeout(tokio::time::Duration::from_secs(1), stream.next()).await
                 {
                     Ok(Some(Ok(chunk))) => {
-                        let s = String::from_utf8_lossy(&chunk);
-                        tracing::debug!("Follower received chunk from stream: {}", s);
-                        buffer.push_str(&s);
-
-                        while let Some(idx) = buffer.find('\n') {
-                            let line = buffer.drain(..=idx).collect::<String>();
-                            let line = line.trim();
-                            if line.is_empty() {
-                                continue;
-                            }
-
-                            #[derive(serde::Deserialize)]
-                            struct B64Message {
-                                b64: String,
-                            }
-
-                            if let Ok(msg) = serde_json::from_str::<B64Message>(line) {
-                                use base64::{engine::general_purpose::STANDARD, Engine as _};
-                                if let Ok(bytes) = STANDARD.decode(&msg.b64) {
-                                    // S15: preserve the namespace across the wire so a
-                                    // replicated collection write lands in the same
-                                    // collection on the follower.
-                                    let decoded = bincode::serde::decode_from_slice::<LogEntry, _>(
-                                        &bytes,
-                                        bincode::config::standard(),
-                                    )
-                                    .ok()
-                                    .map(|(e, _)| e);
-                                    let ns_event = match decoded {
-                                        Some(LogEntry::Event(event)) => {
-                                            Some((valori_kernel::types::id::DEFAULT_NS.0, event))
-                                        }
-                                        Some(LogEntry::EventNs {
-                                            namespace_id,
-                                            event,
-                                        }) => Some((namespace_id, event)),
-                                        _ => None,
-                                    };
-                                    if let Some((namespace_id, event)) = ns_event {
-                                        let mut engine = state.write().await;
-                                        if let Some(committer) = engine.event_committer_mut() {
-                                            match committer
-                                                .commit_event_ns(event.clone(), namespace_id)
-                                            {
-                                                Ok(_) => {
-                                                    if let Err(e) = engine.apply_committed_event_ns(
-                                                        &event,
-                                                        namespace_id,
-                                                    ) {
-                                                        tracing::error!(
-                                                            "Failed to apply committed event: {:?}",
-                                                            e
-                                                        );
-                                                        apply_failed = true;
-                                                        break 'stream;
-                                                    }
-                                                    tracing::debug!("Successfully applied event to follower index");
-                                                }
-                                                Err(e) => {
-                                                    tracing::error!(
-                                                        "Follower failed to commit event: {:?}",
-                                                        e
-                                                    );
-                                                }
-                                            }
-                                        }
-                                    }
+                        tracing::debug!(
+                            "Follower received {} bytes from stream ({:?})",
+                            chunk.len(),
+                            format
+                        );
+                        decoder.push(&chunk);
+
+                        loop {
+                            let (prev_hash, decoded) = match decoder.next_chained() {
+                                Ok(Some(entry)) => entry,
+                                Ok(None) => break,
+                                Err(e) => {
+                                    // Reconnect from the committed height.
+                                    tracing::warn!("Replication stream rejected: {}", e);
+                                    break 'stream;
+                                }
+                            };
+                            if let (Some(c), Some(prev_hash)) = (cursor.as_mut(), prev_hash) {
+                                let at_anchor = c.height == start_offset;
+                                if let Err(e) = c.verify(&prev_hash, &decoded) {
+                                    metrics::counter!("valori_replication_chain_rejects_total", 1);
+                                    tracing::error!("Replication stream rejected: {}", e);
+                                    cursor = None;
+                                    // Disagreeing before anything was applied
+                                    // means the histories parted: heal. Later,
+                                    // the stream itself is bad: reconnect.
+                                    apply_failed = at_anchor;
+                                    break 'stream;
                                 }
                             }
+                            if apply_replicated(&state, decoded, &mut partial, &client)
+                                .await
+                                .is_err()
+                            {
+                                apply_failed = true;
+                                cursor = None;
+                                break 'stream;
+                            }
                         }
                     }
                     Ok(Some(Err(_))) | Ok(None) => break 'stream,
@@ -313,9 +439,15 @@ pub async fn run_follower_loop(state: SharedEngine, leader_url: String) {
                 }
             }
 
-            if apply_failed {
+            if apply_failed && partial.is_some() {
+                // A full snapshot would undo the filter; retry from the
+                // committed height instead.
+                DISPLAY_STATUS.store(2, std::sync::atomic::Ordering::Relaxed);
+                tracing::error!("Partial replica diverged from the leader's partial proof");
+            } else if apply_failed {
                 // Acquire engine lock here — hash-checker task never holds it,
                 // so there is no lock-ordering issue.
+                cursor = None;
                 let _ = status_tx_heal(&state, &client).await;
             }
         }
@@ -324,6 +456,233 @@ pub async fn run_follower_loop(state: SharedEngine, leader_url: String) {
     }
 }
 
+/// The leader's event chain as a follower checks it while streaming (see
+/// [`crate::replication_codec`]'s chained frames): the chain at `height`.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub(crate) struct ChainCursor {
+    pub height: u64,
+    pub head: [u8; 32],
+}
+
+impl ChainCursor {
+    /// Check an entry's `prev_hash` against the chain, then step past it.
+    /// Nothing is applied on `Err`.
+    pub(crate) fn verify(&mut self, prev_hash: &[u8; 32], entry: &LogEntry) -> Result<(), String> {
+        use crate::geo_replication::chain_hex;
+
+        if *prev_hash != self.head {
+            return Err(format!(
+                "event chain mismatch at height {}: leader {}, expected {}",
+                self.height,
+                chain_hex(prev_hash),
+                chain_hex(&self.head)
+            ));
+        }
+        self.advance(entry);
+        Ok(())
+    }
+
+    /// Step past `entry`, computing the chain where no link is sent.
+    pub(crate) fn advance(&mut self, entry: &LogEntry) {
+        if let Some((namespace_id, event)) = data_event(entry) {
+            self.head =
+                crate::geo_replication::event_chain_advance(&self.head, namespace_id, event);
+            self.height += 1;
+        }
+    }
+}
+
+/// The event chain at `height` a stream is verified from. A full replica
+/// uses its own, so a leader whose history parted from ours is refused at
+/// its first event. A partial replica (whose log holds filtered events) or
+/// a replica seeded from a snapshot takes the leader's
+/// `/v1/replication/chain`, which still pins every streamed event to it.
+async fn chain_anchor(
+    state: &SharedEngine,
+    client: &LeaderClient,
+    height: u64,
+    partial: bool,
+) -> Result<[u8; 32], EngineError> {
+    if !partial {
+        if let Ok(chain) = crate::geo_replication::local_event_chain(state).await {
+            if let Some(head) = chain.get(height as usize) {
+                return Ok(*head);
+            }
+        }
+    }
+    let hex = client.chain_hash(height).await?;
+    crate::geo_replication::chain_from_hex(&hex)
+        .ok_or_else(|| EngineError::Network(format!("malformed chain hash {hex:?}")))
+}
+
+/// Replication handshake: report our epoch, check the leader's. False when
+/// this node must not replicate from the leader right now — it is
+/// unreachable, stale, or (with the engine fenced) forked from our log.
+pub(crate) async fn leader_epoch_ok(state: &SharedEngine, client: &LeaderClient) -> bool {
+    let ours = state.read().await.leader_epoch.epoch;
+    let leader = match client.handshake(ours).await {
+        Ok(Some(leader)) => leader,
+        // Leader predates the handshake.
+        Ok(None) => return true,
+        Err(e) => {
+            tracing::warn!("Replication handshake failed: {}", e);
+            return false;
+        }
+    };
+    match state.write().await.follow_leader_epoch(leader) {
+        Ok(_) => true,
+        Err(EngineError::Fenced(reason)) => {
+            tracing::error!("Refusing to replicate — {}", reason);
+            false
+        }
+        Err(e) => {
+            tracing::warn!("Not replicating from {}: {}", client.base_url(), e);
+            false
+        }
+    }
+}
+
+pub(crate) async fn committed_height(state: &SharedEngine) -> u64 {
+    let engine = state.read().await;
+    engine
+        .event_committer()
+        .map(|c| c.journal().committed_height())
+        .unwrap_or(0)
+}
+
+/// Commit and apply one replicated entry, through `partial`'s filter when
+/// set. `Err` means the follower's state could not absorb it (divergence);
+/// non-data entries are ignored.
+pub(crate) async fn apply_replicated(
+    state: &SharedEngine,
+    entry: LogEntry,
+    partial: &mut Option<PartialReplica>,
+    client: &LeaderClient,
+) -> Result<(), EngineError> {
+    // S15: preserve the namespace across the wire so a replicated collection
+    // write lands in the same collection on the follower.
+    let (namespace_id, event) = match entry {
+        LogEntry::Event(event) => (valori_kernel::types::id::DEFAULT_NS.0, event),
+        LogEntry::EventNs {
+            namespace_id,
+            event,
+        } => (namespace_id, event),
+        _ => return Ok(()),
+    };
+    if let Some(p) = partial.as_mut() {
+        let target = match &event {
+            KernelEvent::AutoInsertRecordEncrypted { namespace_id, .. } => *namespace_id,
+            _ => namespace_id,
+        };
+        p.resolve_for(target, client).await?;
+    }
+    let mut engine = state.write().await;
+    let event = match partial.as_ref() {
+        Some(p) => p
+            .resolved
+            .apply(&engine.state, namespace_id, &event)
+            .map_err(|e| {
+                tracing::error!("Partial replication stopped: {}", e);
+                EngineError::InvalidInput(e)
+            })?,
+        None => event,
+    };
+    let Some(committer) = engine.event_committer_mut() else {
+        return Ok(());
+    };
+    if let Err(e) = committer.commit_event_ns(event.clone(), namespace_id) {
+        tracing::error!("Follower failed to commit event: {:?}", e);
+        return Err(EngineError::InvalidInput(format!(
+            "follower could not commit replicated event: {e:?}"
+        )));
+    }
+    engine.ingest_event(&event, namespace_id).inspect_err(|e| {
+        tracing::error!("Failed to apply committed event: {:?}", e);
+    })
+}
+
+/// Replay the leader's sealed segments from `height` up to the start of its
+/// live segment. Returns the height to stream from. A leader without the
+/// segments endpoint leaves `height` unchanged; archives that no longer
+/// reach back to `height` are an error (the caller bootstraps instead).
+///
+/// Each segment must splice onto the one before it (see
+/// [`crate::replication_segments::decode_segment`]), and its events must
+/// carry the event chain from ours at `height` to the leader's at the
+/// segment's end before any of them is applied — a follower whose history
+/// parted from the leader's is refused, as it is by the live stream.
+pub(crate) async fn catch_up_from_segments(
+    state: &SharedEngine,
+    client: &LeaderClient,
+    height: u64,
+    partial: &mut Option<PartialReplica>,
+) -> Result<u64, EngineError> {
+    let Ok(index) = client.list_segments().await else {
+        return Ok(height);
+    };
+    let plan = index.catch_up_plan(height).ok_or_else(|| {
+        EngineError::InvalidInput(format!(
+            "leader archives do not reach back to height {height}"
+        ))
+    })?;
+    if plan.is_empty() {
+        return Ok(height);
+    }
+    tracing::info!(
+        "Follower at height {} is behind the leader's live segment (starts at {}) — replaying {} archived segment(s)",
+        height,
+        index.live_first_height,
+        plan.len()
+    );
+
+    // Anchored like the live stream: a segment is applied only if its
+    // events extend our event chain to where the leader's chain stands.
+    let mut cursor = ChainCursor {
+        height,
+        head: chain_anchor(state, client, height, partial.is_some()).await?,
+    };
+    let mut prev = None;
+    for seq in plan {
+        let bytes = client.download_segment(seq).await?;
+        let segment = crate::replication_segments::decode_segment(&bytes, prev.as_ref())
+            .map_err(EngineError::InvalidInput)?;
+        prev = Some(segment.end);
+
+        let mut fresh = Vec::new();
+        let mut ahead = cursor;
+        for (h, entry) in segment.entries {
+            if h < ahead.height {
+                continue;
+            }
+            if h > ahead.height {
+                return Err(EngineError::InvalidInput(format!(
+                    "segment {seq} skips from height {} to {h}",
+                    ahead.height
+                )));
+            }
+            ahead.advance(&entry);
+            fresh.push(entry);
+        }
+        if fresh.is_empty() {
+            continue;
+        }
+        let leader_head = client.chain_hash(ahead.height).await?;
+        let ours = crate::geo_replication::chain_hex(&ahead.head);
+        if ours != leader_head {
+            metrics::counter!("valori_replication_chain_rejects_total", 1);
+            return Err(EngineError::InvalidInput(format!(
+                "segment {seq} does not extend the local event chain: it leads to {ours} at height {}, the leader's chain is {leader_head}",
+                ahead.height
+            )));
+        }
+        for entry in fresh {
+            apply_replicated(state, entry, partial, client).await?;
+        }
+        cursor = ahead;
+    }
+    Ok(committed_height(state).await)
+}
+
 /// Separate function so the healing path is clear and testable.
 async fn status_tx_heal(state: &SharedEngine, client: &LeaderClient) -> Result<(), EngineError> {
     tracing::warn!("Replication divergence detected — bootstrapping from leader");
@@ -335,13 +694,62 @@ async fn status_tx_heal(state: &SharedEngine, client: &LeaderClient) -> Result<(
     result
 }
 
-async fn bootstrap_from_leader(
+/// Check a snapshot downloaded from the leader before it replaces local
+/// state, failing closed. The bytes must match their advertised hash, and
+/// the state they decode to must match the leader's advertised state hash
+/// (its current proof, from a leader that advertises none). A proof taken
+/// at the snapshot's kernel version must agree as well. Returns the
+/// verified state hash.
+pub(crate) fn verify_leader_snapshot(
+    snapshot: &crate::network::client::LeaderSnapshot,
+    proof: &crate::network::client::LeaderProof,
+) -> Result<[u8; 32], EngineError> {
+    use crate::geo_replication::chain_hex;
+
+    let refuse = |why: String| {
+        tracing::error!("Refusing the leader's snapshot: {}", why);
+        EngineError::InvalidInput(format!("snapshot bootstrap refused: {why}"))
+    };
+    if let Some(advertised) = &snapshot.bytes_hash {
+        let got = blake3::hash(&snapshot.bytes).to_hex();
+        if got.as_str() != advertised {
+            return Err(refuse(format!(
+                "bytes hash to {got}, leader advertised {advertised}"
+            )));
+        }
+    }
+    let (hash, version) = crate::engine::Engine::snapshot_state_proof(&snapshot.bytes)
+        .map_err(|e| refuse(format!("undecodable: {e}")))?;
+    let hash_hex = chain_hex(&hash);
+    let advertised = snapshot
+        .state_hash
+        .as_deref()
+        .unwrap_or(&proof.final_state_hash);
+    if hash_hex != advertised {
+        return Err(refuse(format!(
+            "state hashes to {hash_hex}, leader advertised {advertised}"
+        )));
+    }
+    if proof.version == Some(version) && proof.final_state_hash != hash_hex {
+        return Err(refuse(format!(
+            "state hashes to {hash_hex}, leader proof at version {version} is {}",
+            proof.final_state_hash
+        )));
+    }
+    Ok(hash)
+}
+
+pub(crate) async fn bootstrap_from_leader(
     state: &SharedEngine,
     client: &LeaderClient,
 ) -> Result<(), EngineError> {
-    let snapshot_bytes = client.download_snapshot().await?;
+    let snapshot = client.download_snapshot_with_proof().await?;
+    // Fetched after the download, so it is never older than the snapshot.
+    let proof = client.get_proof().await?;
+    let state_hash = verify_leader_snapshot(&snapshot, &proof)?;
+
     let mut engine = state.write().await;
-    engine.restore(&snapshot_bytes)?;
+    engine.restore(&snapshot.bytes)?;
 
     let log_path = engine
         .event_committer()
@@ -353,11 +761,17 @@ async fn bootstrap_from_leader(
 
     let _ = tokio::fs::remove_file(&log_path).await;
 
-    let new_height = engine.record_count() as u64;
-    let state_hash = engine.get_proof().final_state_hash;
-
-    let log_writer = crate::events::event_log::EventLogWriter::open(&log_path, dim)
-        .map_err(|e| EngineError::InvalidInput(e.to_string()))?;
+    // Older leaders do not advertise their height.
+    let new_height = snapshot
+        .height
+        .unwrap_or_else(|| engine.record_count() as u64);
+
+    let log_writer = crate::events::event_log::EventLogWriter::open_with_sync(
+        &log_path,
+        dim,
+        engine.event_log_sync,
+    )
+    .map_err(|e| EngineError::InvalidInput(e.to_string()))?;
 
     let journal = crate::events::event_journal::EventJournal::new_at_height(new_height);
     let mut committer =
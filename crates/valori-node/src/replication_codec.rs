@@ -0,0 +1,329 @@
+// Copyright (c) 2025 Varshith Gudur. Dual-licensed under MIT OR Apache-2.0.
+//! Wire formats for `GET /v1/replication/events`.
+//!
+//! **Frames** (default for current followers) — the same per-entry layout
+//! as a v4 log segment, prefixed with a length so entries can be cut out of
+//! a byte stream:
+//!
+//! ```text
+//! [u32 LE payload_len][bincode LogEntry][u32 LE CRC32(payload)]
+//! ```
+//!
+//! The payload is the exact bincode encoding the leader's log decodes to, so
+//! the follower applies byte-for-byte what the leader committed, with no
+//! base64 / JSON round-trip on either end. A CRC mismatch or an oversized
+//! length ends the stream; the follower reconnects from its committed height.
+//!
+//! **Chained frames** (followers that verify the stream) — the same frame
+//! with the leader's event chain before the entry (see
+//! [`crate::geo_replication::event_chain`]) ahead of the bincode:
+//!
+//! ```text
+//! [u32 LE payload_len][prev_hash: 32 bytes][bincode LogEntry][u32 LE CRC32(payload)]
+//! ```
+//!
+//! The follower checks each data event's `prev_hash` against its own chain
+//! before committing it, so a stream that skips, reorders or rewrites an
+//! event — or a leader whose history parted from the follower's — is
+//! rejected at the first entry that disagrees instead of being applied.
+//! Non-data entries carry the chain head unchanged.
+//!
+//! **NDJSON** (legacy) — `{"b64":"<base64 bincode LogEntry>"}\n` per entry.
+//! Served to clients that do not send `Accept: application/x-valori-frames`,
+//! and decoded by followers when an older leader answers without the frames
+//! content type, so mixed-version pairs keep replicating during an upgrade.
+
+use crate::events::event_log::LogEntry;
+
+/// Content type of the framed format; followers send it in `Accept`.
+pub const FRAMES_CONTENT_TYPE: &str = "application/x-valori-frames";
+/// Content type of the chained framed format; preferred in followers'
+/// `Accept`.
+pub const CHAINED_FRAMES_CONTENT_TYPE: &str = "application/x-valori-chained-frames";
+/// Content type of the legacy NDJSON format.
+pub const NDJSON_CONTENT_TYPE: &str = "application/x-ndjson";
+
+/// `len` prefix + CRC suffix.
+const FRAME_OVERHEAD: usize = 8;
+
+fn frame(payload: &[u8]) -> Vec<u8> {
+    let mut out = Vec::with_capacity(payload.len() + FRAME_OVERHEAD);
+    out.extend_from_slice(&(payload.len() as u32).to_le_bytes());
+    out.extend_from_slice(payload);
+    out.extend_from_slice(&crc32fast::hash(payload).to_le_bytes());
+    out
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum StreamFormat {
+    #[default]
+    Ndjson,
+    Frames,
+    ChainedFrames,
+}
+
+impl StreamFormat {
+    /// Leader side: the richest format the `Accept` header names.
+    pub fn from_accept(accept: Option<&str>) -> Self {
+        match accept {
+            Some(a) if a.contains(CHAINED_FRAMES_CONTENT_TYPE) => Self::ChainedFrames,
+            Some(a) if a.contains(FRAMES_CONTENT_TYPE) => Self::Frames,
+            _ => Self::Ndjson,
+        }
+    }
+
+    /// Follower side: what the leader actually answered with.
+    pub fn from_content_type(content_type: Option<&str>) -> Self {
+        match content_type {
+            Some(ct) if ct.starts_with(CHAINED_FRAMES_CONTENT_TYPE) => Self::ChainedFrames,
+            Some(ct) if ct.starts_with(FRAMES_CONTENT_TYPE) => Self::Frames,
+            _ => Self::Ndjson,
+        }
+    }
+
+    pub fn content_type(self) -> &'static str {
+        match self {
+            Self::ChainedFrames => CHAINED_FRAMES_CONTENT_TYPE,
+            Self::Frames => FRAMES_CONTENT_TYPE,
+            Self::Ndjson => NDJSON_CONTENT_TYPE,
+        }
+    }
+
+    /// Wrap one bincode-encoded `LogEntry`, whose event chain before it is
+    /// `prev_hash`, for the wire. Only chained frames carry the hash.
+    pub fn encode_chained(self, prev_hash: &[u8; 32], entry_bytes: &[u8]) -> Vec<u8> {
+        match self {
+            Self::ChainedFrames => {
+                let mut payload = Vec::with_capacity(32 + entry_bytes.len());
+                payload.extend_from_slice(prev_hash);
+                payload.extend_from_slice(entry_bytes);
+                frame(&payload)
+            }
+            _ => self.encode(entry_bytes),
+        }
+    }
+
+    /// Wrap one bincode-encoded `LogEntry` for the wire.
+    ///
+    /// Chained frames need the chain: use [`Self::encode_chained`].
+    pub fn encode(self, entry_bytes: &[u8]) -> Vec<u8> {
+        match self {
+            Self::Frames | Self::ChainedFrames => frame(entry_bytes),
+            Self::Ndjson => {
+                use base64::{engine::general_purpose::STANDARD, Engine as _};
+                format!("{{\"b64\":\"{}\"}}\n", STANDARD.encode(entry_bytes)).into_bytes()
+            }
+        }
+    }
+}
+
+#[derive(Debug, PartialEq, Eq)]
+pub enum FrameError {
+    /// Declared payload length exceeds `MAX_ENTRY_DECODE_BYTES`.
+    TooLarge(u64),
+    CrcMismatch {
+        stored: u32,
+        computed: u32,
+    },
+    /// CRC was fine but the payload is not a `LogEntry` (or, chained, too
+    /// short to carry the hash).
+    Decode(String),
+}
+
+impl std::fmt::Display for FrameError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Self::TooLarge(n) => write!(f, "frame of {n} bytes exceeds the entry limit"),
+            Self::CrcMismatch { stored, computed } => write!(
+                f,
+                "frame CRC mismatch (stored {stored:08x}, computed {computed:08x})"
+            ),
+            Self::Decode(e) => write!(f, "frame payload: {e}"),
+        }
+    }
+}
+
+impl std::error::Error for FrameError {}
+
+/// Incremental decoder: feed it response chunks, pull complete entries out.
+pub struct StreamDecoder {
+    format: StreamFormat,
+    buf: Vec<u8>,
+}
+
+impl StreamDecoder {
+    pub fn new(format: StreamFormat) -> Self {
+        Self {
+            format,
+            buf: Vec::new(),
+        }
+    }
+
+    pub fn push(&mut self, chunk: &[u8]) {
+        self.buf.extend_from_slice(chunk);
+    }
+
+    /// Next complete entry, `Ok(None)` when more bytes are needed.
+    ///
+    /// NDJSON lines that do not decode are skipped, as before frames existed;
+    /// a bad frame is an error because the stream can no longer be trusted.
+    pub fn next_entry(&mut self) -> Result<Option<LogEntry>, FrameError> {
+        Ok(self.next_chained()?.map(|(_, entry)| entry))
+    }
+
+    /// [`Self::next_entry`] with the `prev_hash` chained frames carry
+    /// (`None` in the other formats).
+    pub fn next_chained(&mut self) -> Result<Option<(Option<[u8; 32]>, LogEntry)>, FrameError> {
+        match self.format {
+            StreamFormat::ChainedFrames => {
+                let Some(payload) = self.next_frame()? else {
+                    return Ok(None);
+                };
+                let Some((prev, entry)) = payload.split_first_chunk::<32>() else {
+                    return Err(FrameError::Decode(format!(
+                        "chained frame of {} bytes has no prev_hash",
+                        payload.len()
+                    )));
+                };
+                Ok(Some((Some(*prev), decode_payload(entry)?)))
+            }
+            StreamFormat::Frames => match self.next_frame()? {
+                Some(payload) => Ok(Some((None, decode_payload(&payload)?))),
+                None => Ok(None),
+            },
+            StreamFormat::Ndjson => Ok(self.next_line().map(|e| (None, e))),
+        }
+    }
+
+    /// The CRC-checked payload of the next complete frame.
+    fn next_frame(&mut self) -> Result<Option<Vec<u8>>, FrameError> {
+        let Some(len) = self.buf.get(..4) else {
+            return Ok(None);
+        };
+        let len = u32::from_le_bytes(len.try_into().expect("4 bytes")) as usize;
+        if len as u64 > valori_wire::MAX_ENTRY_DECODE_BYTES {
+            return Err(FrameError::TooLarge(len as u64));
+        }
+        if self.buf.len() < len + FRAME_OVERHEAD {
+            return Ok(None);
+        }
+        let frame: Vec<u8> = self.buf.drain(..len + FRAME_OVERHEAD).collect();
+        let payload = &frame[4..4 + len];
+        let stored = u32::from_le_bytes(frame[4 + len..].try_into().expect("4 bytes"));
+        let computed = crc32fast::hash(payload);
+        if stored != computed {
+            return Err(FrameError::CrcMismatch { stored, computed });
+        }
+        Ok(Some(payload.to_vec()))
+    }
+
+    fn next_line(&mut self) -> Option<LogEntry> {
+        #[derive(serde::Deserialize)]
+        struct B64Message {
+            b64: String,
+        }
+
+        while let Some(idx) = self.buf.iter().position(|b| *b == b'\n') {
+            let line: Vec<u8> = self.buf.drain(..=idx).collect();
+            let Ok(msg) = serde_json::from_slice::<B64Message>(&line) else {
+                continue;
+            };
+            use base64::{engine::general_purpose::STANDARD, Engine as _};
+            let Ok(bytes) = STANDARD.decode(&msg.b64) else {
+                continue;
+            };
+            if let Ok((entry, _)) = bincode::serde::decode_from_slice::<LogEntry, _>(
+                &bytes,
+                bincode::config::standard(),
+            ) {
+                return Some(entry);
+            }
+        }
+        None
+    }
+}
+
+fn decode_payload(payload: &[u8]) -> Result<LogEntry, FrameError> {
+    bincode::serde::decode_from_slice::<LogEntry, _>(payload, bincode::config::standard())
+        .map(|(e, _)| e)
+        .map_err(|e| FrameError::Decode(e.to_string()))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn entry(n: u64) -> Vec<u8> {
+        let e = LogEntry::Checkpoint {
+            event_count: n,
+            snapshot_hash: [n as u8; 32],
+            timestamp: 1_700_000_000 + n,
+        };
+        bincode::serde::encode_to_vec(&e, bincode::config::standard()).unwrap()
+    }
+
+    #[test]
+    fn frames_and_ndjson_decode_across_arbitrary_chunk_boundaries() {
+        for format in [
+            StreamFormat::ChainedFrames,
+            StreamFormat::Frames,
+            StreamFormat::Ndjson,
+        ] {
+            let wire: Vec<u8> = (0..5)
+                .flat_map(|n| format.encode_chained(&[n as u8; 32], &entry(n)))
+                .collect();
+            let mut dec = StreamDecoder::new(format);
+            let mut got = Vec::new();
+            for chunk in wire.chunks(7) {
+                dec.push(chunk);
+                while let Some(e) = dec.next_entry().unwrap() {
+                    got.push(e);
+                }
+            }
+            let counts: Vec<u64> = got
+                .iter()
+                .map(|e| match e {
+                    LogEntry::Checkpoint { event_count, .. } => *event_count,
+                    _ => panic!("unexpected entry"),
+                })
+                .collect();
+            assert_eq!(counts, vec![0, 1, 2, 3, 4], "{format:?}");
+        }
+    }
+
+    #[test]
+    fn corrupted_or_oversized_frame_is_an_error() {
+        let mut wire = StreamFormat::Frames.encode(&entry(1));
+        wire[6] ^= 0xff;
+        let mut dec = StreamDecoder::new(StreamFormat::Frames);
+        dec.push(&wire);
+        assert!(matches!(
+            dec.next_entry(),
+            Err(FrameError::CrcMismatch { .. })
+        ));
+
+        let mut dec = StreamDecoder::new(StreamFormat::Frames);
+        dec.push(&u32::MAX.to_le_bytes());
+        assert!(matches!(dec.next_entry(), Err(FrameError::TooLarge(_))));
+    }
+
+    #[test]
+    fn chained_frames_carry_the_prev_hash() {
+        let format = StreamFormat::ChainedFrames;
+        let mut dec = StreamDecoder::new(format);
+        dec.push(&format.encode_chained(&[7u8; 32], &entry(3)));
+        let (prev, e) = dec.next_chained().unwrap().unwrap();
+        assert_eq!(prev, Some([7u8; 32]));
+        assert_eq!(e.checkpoint_height(), Some(3));
+
+        // Plain frames have no hash to offer.
+        let mut dec = StreamDecoder::new(StreamFormat::Frames);
+        dec.push(&StreamFormat::Frames.encode_chained(&[7u8; 32], &entry(3)));
+        assert_eq!(dec.next_chained().unwrap().unwrap().0, None);
+
+        // A CRC-valid frame too short for the hash is rejected.
+        let mut dec = StreamDecoder::new(format);
+        dec.push(&frame(&[1, 2, 3]));
+        assert!(matches!(dec.next_chained(), Err(FrameError::Decode(_))));
+    }
+}
@@ -0,0 +1,289 @@
+// Copyright (c) 2025 Varshith Gudur. Dual-licensed under MIT OR Apache-2.0.
+//! Partial replication: a follower that keeps only some collections or tags.
+//!
+//! An edge node set up with `VALORI_REPLICATE_COLLECTIONS` and/or
+//! `VALORI_REPLICATE_TAGS` still consumes every event the leader commits,
+//! but rewrites each one through a [`ResolvedFilter`] before committing it:
+//!
+//! - an insert whose record is outside the filter becomes
+//!   `KernelEvent::Omitted { record: Some(id) }`, which reserves the id as an
+//!   empty slot so the leader's later ids still apply;
+//! - a delete or metadata update of a record the replica does not hold
+//!   becomes `Omitted { record: None }`;
+//! - a graph node linked to such a record is created unlinked;
+//! - everything else (graph, meta, namespaces, pool limits) is kept.
+//!
+//! The replica's height therefore tracks the leader's one for one, and its
+//! state is a pure function of the leader's log and the filter. The leader
+//! replays its own log through the same filter to serve the matching hash
+//! (`GET /v1/replication/partial-proof`), which the follower's hash checker
+//! compares against its own `/v1/proof/state`.
+//!
+//! A leader-side vacuum renumbers records by a mapping a partial replica
+//! cannot reproduce, so it stops partial replication; reseed the replica.
+
+use std::collections::BTreeSet;
+use valori_kernel::event::KernelEvent;
+use valori_kernel::index::TagFilter;
+use valori_kernel::state::kernel::KernelState;
+
+/// Which records a partial replica keeps. A record is kept when it lives in
+/// one of `collections` (any collection when `None`) and its tag matches
+/// `tags` (any tag when `None`).
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ReplicationFilter {
+    pub collections: Option<BTreeSet<String>>,
+    pub tags: Option<TagFilter>,
+}
+
+impl ReplicationFilter {
+    /// Parse the `VALORI_REPLICATE_COLLECTIONS` / `VALORI_REPLICATE_TAGS`
+    /// values (or the matching query parameters). `None` when neither is
+    /// set — a full replica.
+    pub fn parse(collections: Option<&str>, tags: Option<&str>) -> Result<Option<Self>, String> {
+        let collections = collections
+            .map(str::trim)
+            .filter(|s| !s.is_empty())
+            .map(|raw| {
+                raw.split(',')
+                    .map(str::trim)
+                    .filter(|s| !s.is_empty())
+                    .map(String::from)
+                    .collect::<BTreeSet<_>>()
+            });
+        let tags = match tags.map(str::trim).filter(|s| !s.is_empty()) {
+            Some(raw) => Some(parse_tag_filter(raw)?),
+            None => None,
+        };
+        if collections.is_none() && tags.is_none() {
+            return Ok(None);
+        }
+        Ok(Some(Self { collections, tags }))
+    }
+
+    /// Query string for `GET /v1/replication/partial-proof`, in the format
+    /// [`ReplicationFilter::parse`] reads back.
+    pub fn query(&self) -> String {
+        let mut parts = Vec::new();
+        if let Some(c) = &self.collections {
+            parts.push(format!(
+                "collections={}",
+                c.iter().cloned().collect::<Vec<_>>().join(",")
+            ));
+        }
+        if let Some(t) = self.tags {
+            let (kind, v) = match t {
+                TagFilter::Exact(v) => ("exact", v),
+                TagFilter::Any(v) => ("any", v),
+                TagFilter::All(v) => ("all", v),
+            };
+            parts.push(format!("tags={kind}:{v}"));
+        }
+        parts.join("&")
+    }
+
+    /// Bind collection names to namespace ids using `registry` (the
+    /// leader's `(name, id)` list). Names the registry does not know match
+    /// nothing until the next resolve.
+    pub fn resolve(&self, registry: &[(String, u16)]) -> ResolvedFilter {
+        ResolvedFilter {
+            keep: self.collections.as_ref().map(|names| {
+                registry
+                    .iter()
+                    .filter(|(name, _)| names.contains(name))
+                    .map(|(_, id)| *id)
+                    .collect()
+            }),
+            known: registry.iter().map(|(_, id)| *id).collect(),
+            tags: self.tags,
+        }
+    }
+}
+
+/// Parse a tag filter: `exact:5` (or just `5`), `any:6`, `all:6`. Values
+/// may be decimal or `0x` hex.
+pub fn parse_tag_filter(raw: &str) -> Result<TagFilter, String> {
+    let (kind, value) = raw.trim().split_once(':').unwrap_or(("exact", raw.trim()));
+    let value = value.trim();
+    let parsed = match value.strip_prefix("0x") {
+        Some(hex) => u64::from_str_radix(hex, 16),
+        None => value.parse(),
+    }
+    .map_err(|_| format!("tag filter {raw:?}: {value:?} is not a u64"))?;
+    match kind.trim().to_ascii_lowercase().as_str() {
+        "exact" => Ok(TagFilter::Exact(parsed)),
+        "any" => Ok(TagFilter::Any(parsed)),
+        "all" => Ok(TagFilter::All(parsed)),
+        other => Err(format!(
+            "tag filter {raw:?}: unknown kind {other:?} (expected exact, any or all)"
+        )),
+    }
+}
+
+/// A [`ReplicationFilter`] with its collection names bound to namespace ids.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ResolvedFilter {
+    /// Namespaces whose records are kept; `None` = every namespace.
+    keep: Option<BTreeSet<u16>>,
+    /// Every namespace id the registry knew at resolve time.
+    known: BTreeSet<u16>,
+    tags: Option<TagFilter>,
+}
+
+impl ResolvedFilter {
+    /// True when `namespace_id` postdates the resolve, so whether it is one
+    /// of the wanted collections is not known yet.
+    pub fn is_unresolved(&self, namespace_id: u16) -> bool {
+        self.keep.is_some() && !self.known.contains(&namespace_id)
+    }
+
+    /// Settle an unresolved namespace the registry still does not list
+    /// (its collection was dropped meanwhile): it is not kept.
+    pub fn settle(&mut self, namespace_id: u16) {
+        self.known.insert(namespace_id);
+    }
+
+    fn keeps(&self, namespace_id: u16, tag: u64) -> bool {
+        self.keep
+            .as_ref()
+            .map_or(true, |k| k.contains(&namespace_id))
+            && self.tags.map_or(true, |t| t.matches(tag))
+    }
+
+    /// The event a partial replica at `state` commits in place of the
+    /// leader's `event` (applied in `namespace_id`). `Err` for an event a
+    /// partial replica cannot follow.
+    pub fn apply(
+        &self,
+        state: &KernelState,
+        namespace_id: u16,
+        event: &KernelEvent,
+    ) -> Result<KernelEvent, String> {
+        let omit = |record| Ok(KernelEvent::Omitted { record });
+        let held = |id| state.get_record(id).is_some();
+        match event {
+            KernelEvent::InsertRecord { id, tag, .. }
+            | KernelEvent::InsertRecordEncrypted { id, tag, .. }
+                if !self.keeps(namespace_id, *tag) =>
+            {
+                omit(Some(*id))
+            }
+            KernelEvent::AutoInsertRecord { tag, .. } if !self.keeps(namespace_id, *tag) => {
+                omit(Some(state.next_record_id()))
+            }
+            // Applied in the namespace the event carries, not the entry's.
+            KernelEvent::AutoInsertRecordEncrypted {
+                namespace_id: ns,
+                tag,
+                ..
+            } if !self.keeps(*ns, *tag) => omit(Some(state.next_record_id())),
+            KernelEvent::DeleteRecord { id }
+            | KernelEvent::SoftDeleteRecord { id }
+            | KernelEvent::UpdateRecordMetadata { id, .. }
+                if !held(*id) =>
+            {
+                omit(None)
+            }
+            KernelEvent::CreateNode {
+                id,
+                kind,
+                record: Some(rid),
+            } if !held(*rid) => Ok(KernelEvent::CreateNode {
+                id: *id,
+                kind: *kind,
+                record: None,
+            }),
+            KernelEvent::AutoCreateNode {
+                kind,
+                record: Some(rid),
+            } if !held(*rid) => Ok(KernelEvent::AutoCreateNode {
+                kind: *kind,
+                record: None,
+            }),
+            KernelEvent::Vacuum { .. } => {
+                Err("a vacuum on the leader cannot be applied to a partial replica".into())
+            }
+            other => Ok(other.clone()),
+        }
+    }
+}
+
+/// `GET /v1/replication/partial-proof` response: the state hash a partial
+/// replica with the requested filter has at `height`.
+#[derive(Debug, Clone, PartialEq, Eq, serde::Serialize, serde::Deserialize)]
+pub struct PartialProof {
+    pub height: u64,
+    pub partial_state_hash: String,
+}
+
+/// Replay `events` (the leader's log, `(namespace, event)` per height)
+/// through `filter` into a fresh kernel — the state a partial replica
+/// reaches at `events.len()`.
+pub fn replay_partial(
+    events: &[(u16, KernelEvent)],
+    filter: &ResolvedFilter,
+    dim: usize,
+) -> Result<KernelState, String> {
+    let mut state = KernelState::with_dim(dim);
+    for (height, (ns, event)) in events.iter().enumerate() {
+        let kept = filter.apply(&state, *ns, event)?;
+        state
+            .apply_event_ns(&kept, *ns)
+            .map_err(|e| format!("height {height}: {e:?}"))?;
+    }
+    Ok(state)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use valori_kernel::types::id::RecordId;
+    use valori_kernel::types::vector::FxpVector;
+
+    fn insert(id: u32, tag: u64) -> KernelEvent {
+        KernelEvent::InsertRecord {
+            id: RecordId(id),
+            vector: FxpVector::new_zeros(2),
+            metadata: None,
+            tag,
+        }
+    }
+
+    #[test]
+    fn parses_env_values() {
+        assert_eq!(ReplicationFilter::parse(None, Some(" ")).unwrap(), None);
+        let f = ReplicationFilter::parse(Some("docs, edge"), Some("any:0x6"))
+            .unwrap()
+            .unwrap();
+        assert_eq!(f.tags, Some(TagFilter::Any(6)));
+        assert_eq!(f.query(), "collections=docs,edge&tags=any:6");
+        assert_eq!(parse_tag_filter("5").unwrap(), TagFilter::Exact(5));
+        assert!(parse_tag_filter("some:1").is_err());
+        assert!(parse_tag_filter("all:x").is_err());
+    }
+
+    #[test]
+    fn unwanted_records_leave_reserved_ids() {
+        let filter = ReplicationFilter::parse(Some("edge"), Some("all:2"))
+            .unwrap()
+            .unwrap()
+            .resolve(&[("default".into(), 0), ("edge".into(), 3)]);
+        let log = vec![
+            (3, insert(0, 2)),
+            (0, insert(1, 2)), // wrong collection
+            (3, insert(2, 1)), // wrong tag
+            (3, KernelEvent::DeleteRecord { id: RecordId(1) }),
+            (3, insert(3, 6)),
+        ];
+        let state = replay_partial(&log, &filter, 2).unwrap();
+        assert_eq!(state.record_count(), 2);
+        assert!(state.get_record(RecordId(0)).is_some());
+        assert!(state.get_record(RecordId(2)).is_none());
+        assert!(state.get_record(RecordId(3)).is_some());
+
+        assert!(!filter.is_unresolved(3));
+        assert!(filter.is_unresolved(4));
+        let vacuum = KernelEvent::Vacuum { moves: Vec::new() };
+        assert!(filter.apply(&state, 0, &vacuum).is_err());
+    }
+}
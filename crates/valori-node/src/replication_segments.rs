@@ -0,0 +1,426 @@
+// Copyright (c) 2025 Varshith Gudur. Dual-licensed under MIT OR Apache-2.0.
+//! Archived event-log segments for deep follower catch-up.
+//!
+//! The live replication stream only covers the leader's current segment.
+//! Rotation seals `events.log` into `events.log.NNNNNN` and opens the next
+//! segment with a `Checkpoint { event_count }` recording the committed
+//! height at the seal, so every segment after the genesis one knows the
+//! height its first data event lands at. A follower whose committed height
+//! is below the live segment's first height downloads the sealed segments
+//! that cover the gap (`GET /v1/replication/segments/{n}`, raw on-disk
+//! bytes), replays them, and only then opens the live stream — instead of
+//! pulling a whole snapshot.
+//!
+//! Archives that have been pruned (or moved to the object store) leave a
+//! gap; the follower then falls back to a snapshot bootstrap as before.
+//!
+//! Downloaded bytes are checked like the leader's own log before anything
+//! is replayed: the log chain and running log hash must hold within each
+//! segment and splice across them, and the events must extend the
+//! follower's event chain to the leader's.
+
+use crate::events::event_log::LogEntry;
+use serde::{Deserialize, Serialize};
+use std::io::Read;
+use std::path::{Path, PathBuf};
+
+/// Bytes read from the front of a segment to find its opening checkpoint.
+const HEAD_PROBE_BYTES: u64 = 64 * 1024;
+
+/// One sealed segment as listed by `GET /v1/replication/segments`.
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq, Eq)]
+pub struct SegmentInfo {
+    pub seq: u32,
+    /// Committed height of the segment's first data event; `None` when the
+    /// segment carries no opening checkpoint (legacy / non-genesis v2).
+    pub first_height: Option<u64>,
+    pub bytes: u64,
+}
+
+/// `GET /v1/replication/segments` response.
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq, Eq)]
+pub struct SegmentIndex {
+    /// Sealed segments, ascending by `seq`.
+    pub archived: Vec<SegmentInfo>,
+    pub live_seq: u32,
+    /// First height the live stream can serve.
+    pub live_first_height: u64,
+}
+
+impl SegmentIndex {
+    /// Sealed segments a follower at `height` must replay, in order. `None`
+    /// when the archives do not reach back to `height` (snapshot needed).
+    pub fn catch_up_plan(&self, height: u64) -> Option<Vec<u32>> {
+        if height >= self.live_first_height {
+            return Some(Vec::new());
+        }
+        // A segment is needed if it ends above `height`; it ends where the
+        // next one (or the live segment) starts.
+        let mut plan = Vec::new();
+        for (i, seg) in self.archived.iter().enumerate() {
+            let end = self
+                .archived
+                .get(i + 1)
+                .and_then(|n| n.first_height)
+                .unwrap_or(self.live_first_height);
+            if end <= height {
+                continue;
+            }
+            if plan.is_empty() && seg.first_height? > height {
+                return None;
+            }
+            plan.push(seg.seq);
+        }
+        // Sequence numbers must be contiguous up to the live segment.
+        let contiguous = plan
+            .iter()
+            .chain(std::iter::once(&self.live_seq))
+            .collect::<Vec<_>>()
+            .windows(2)
+            .all(|w| *w[1] == *w[0] + 1);
+        (!plan.is_empty() && contiguous).then_some(plan)
+    }
+}
+
+/// Sealed segment files next to `live_path` as `(seq, first_height, path)`,
+/// ascending by header `segment_seq`.
+fn archived_segments(live_path: &Path) -> Vec<(u32, Option<u64>, PathBuf)> {
+    let (Some(dir), Some(fname)) = (
+        live_path.parent(),
+        live_path.file_name().and_then(|n| n.to_str()),
+    ) else {
+        return Vec::new();
+    };
+    let prefix = format!("{fname}.");
+    let mut out = Vec::new();
+    if let Ok(entries) = std::fs::read_dir(dir) {
+        for entry in entries.flatten() {
+            let is_archive = entry
+                .file_name()
+                .to_str()
+                .is_some_and(|n| n.starts_with(&prefix));
+            if !is_archive {
+                continue;
+            }
+            if let Ok((seq, first)) = probe(&entry.path()) {
+                out.push((seq, first, entry.path()));
+            }
+        }
+    }
+    out.sort_by_key(|(seq, _, _)| *seq);
+    out
+}
+
+/// Segment sequence and first data height, from the head of the file.
+fn probe(path: &Path) -> std::io::Result<(u32, Option<u64>)> {
+    let mut head = Vec::new();
+    std::fs::File::open(path)?
+        .take(HEAD_PROBE_BYTES)
+        .read_to_end(&mut head)?;
+    let header = valori_wire::parse_header(&head)
+        .map_err(|e| std::io::Error::new(std::io::ErrorKind::InvalidData, e.to_string()))?;
+    let first = match valori_wire::decode_entry(header.version, &head[header.header_len..]) {
+        Ok((d, _)) => match d.entry {
+            LogEntry::Checkpoint { event_count, .. }
+            | LogEntry::CheckpointV2 { event_count, .. } => Some(event_count),
+            _ if header.segment_seq == 0 => Some(0),
+            _ => None,
+        },
+        // Empty genesis segment.
+        Err(_) if header.segment_seq == 0 => Some(0),
+        Err(_) => None,
+    };
+    Ok((header.segment_seq, first))
+}
+
+/// Index of sealed segments plus the live segment's starting height.
+pub fn index(live_path: &Path) -> std::io::Result<SegmentIndex> {
+    let (live_seq, live_first) = probe(live_path)?;
+    let archived = archived_segments(live_path)
+        .into_iter()
+        .map(|(seq, first_height, path)| SegmentInfo {
+            seq,
+            first_height,
+            bytes: std::fs::metadata(&path).map(|m| m.len()).unwrap_or(0),
+        })
+        .collect();
+    Ok(SegmentIndex {
+        archived,
+        live_seq,
+        live_first_height: live_first.unwrap_or(0),
+    })
+}
+
+/// First height the live segment at `live_path` can stream from.
+pub fn live_first_height(live_path: &Path) -> u64 {
+    probe(live_path).ok().and_then(|(_, h)| h).unwrap_or(0)
+}
+
+/// Path of sealed segment `seq`, if it is still on disk.
+pub fn archived_path(live_path: &Path, seq: u32) -> Option<PathBuf> {
+    archived_segments(live_path)
+        .into_iter()
+        .find(|(s, _, _)| *s == seq)
+        .map(|(_, _, p)| p)
+}
+
+/// Where a downloaded segment leaves the leader's log: the next segment
+/// must splice onto it.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct SegmentEnd {
+    pub seq: u32,
+    /// Log chain head after the segment's last entry.
+    pub chain_head: [u8; 32],
+    /// Running log hash after the segment's last entry; `None` when the
+    /// segment gives no way to seed it (legacy archives without a
+    /// `CheckpointV2`).
+    pub log_hash: Option<[u8; 32]>,
+}
+
+/// A downloaded segment that passed [`decode_segment`]'s checks.
+#[derive(Debug, Clone)]
+pub struct DecodedSegment {
+    /// Data events with their committed heights, in log order.
+    pub entries: Vec<(u64, LogEntry)>,
+    pub end: SegmentEnd,
+}
+
+/// Data events of a downloaded segment with their committed heights, in
+/// log order. Checkpoints re-anchor the height; admin entries and batch
+/// commit records are skipped.
+///
+/// The segment is checked the way the leader's own reader checks it, not
+/// just per entry: every entry's `prev_hash` must continue the log chain
+/// from the header's `prev_segment_chain_head`, and every `CheckpointV2`
+/// must record the running log hash at its position. `prev` is the segment
+/// downloaded before this one; a v3/v4 segment must then be the next in
+/// sequence and open on its chain head and log hash.
+pub fn decode_segment(bytes: &[u8], prev: Option<&SegmentEnd>) -> Result<DecodedSegment, String> {
+    let header = valori_wire::parse_header(bytes).map_err(|e| e.to_string())?;
+    let seq = header.segment_seq;
+    let mut chain_head = header.prev_segment_chain_head;
+    let mut log_hash = if seq == 0 { Some([0u8; 32]) } else { None };
+    if let Some(prev) = prev {
+        if header.version >= valori_wire::VERSION_V3 {
+            if seq != prev.seq + 1 {
+                return Err(format!(
+                    "segment {seq} does not follow segment {}",
+                    prev.seq
+                ));
+            }
+            if chain_head != prev.chain_head {
+                return Err(format!(
+                    "segment {seq} does not extend the chain of segment {}",
+                    prev.seq
+                ));
+            }
+        }
+        log_hash = prev.log_hash;
+    }
+
+    let mut offset = header.header_len;
+    let mut height = 0u64;
+    let mut out = Vec::new();
+    while offset < bytes.len() {
+        let (decoded, n) = valori_wire::decode_entry(header.version, &bytes[offset..])
+            .map_err(|e| format!("segment {seq} at byte {offset}: {e}"))?;
+        if decoded.prev_hash != chain_head {
+            return Err(format!("segment {seq} at byte {offset}: log chain broken"));
+        }
+        chain_head = valori_wire::chain_advance(header.version, &chain_head, &decoded)
+            .map_err(|e| format!("segment {seq} at byte {offset}: {e}"))?;
+        if let LogEntry::CheckpointV2 {
+            log_hash: recorded, ..
+        } = &decoded.entry
+        {
+            match log_hash {
+                Some(running) if running != *recorded => {
+                    return Err(format!(
+                        "segment {seq} at byte {offset}: checkpoint records log hash {}, expected {}",
+                        valori_wire::hex(recorded),
+                        valori_wire::hex(&running)
+                    ));
+                }
+                _ => log_hash = Some(*recorded),
+            }
+        }
+        log_hash = log_hash.map(|h| valori_wire::log_hash_advance(&h, &bytes[offset..offset + n]));
+        offset += n;
+        match decoded.entry {
+            LogEntry::Checkpoint { event_count, .. }
+            | LogEntry::CheckpointV2 { event_count, .. } => height = event_count,
+            e @ (LogEntry::Event(_) | LogEntry::EventNs { .. }) => {
+                out.push((height, e));
+                height += 1;
+            }
+            LogEntry::Admin(_) | LogEntry::BatchCommit { .. } => {}
+        }
+    }
+    Ok(DecodedSegment {
+        entries: out,
+        end: SegmentEnd {
+            seq,
+            chain_head,
+            log_hash,
+        },
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    use crate::events::event_log::EventLogWriter;
+    use valori_kernel::event::KernelEvent;
+    use valori_kernel::types::id::RecordId;
+    use valori_kernel::types::vector::FxpVector;
+
+    fn event(i: u32) -> LogEntry {
+        LogEntry::Event(KernelEvent::InsertRecord {
+            id: RecordId(i),
+            vector: FxpVector::new_zeros(16),
+            metadata: None,
+            tag: 0,
+        })
+    }
+
+    /// Two sealed segments of three events each, then the live segment
+    /// with two more. Returns the segment bytes in order and the writer.
+    fn rotated_log(dir: &Path) -> (Vec<Vec<u8>>, EventLogWriter) {
+        let path = dir.join("events.log");
+        let mut writer = EventLogWriter::open(&path, Some(16)).unwrap();
+        for seg in 0..2u32 {
+            for i in 0..3 {
+                writer.append(&event(seg * 3 + i)).unwrap();
+            }
+            let checkpoint = LogEntry::Checkpoint {
+                event_count: u64::from(seg * 3 + 3),
+                snapshot_hash: [0u8; 32],
+                timestamp: 0,
+            };
+            writer
+                .rotate(dir.join(format!("events.log.{seg}")), Some(checkpoint))
+                .unwrap();
+        }
+        writer.append(&event(6)).unwrap();
+        writer.append(&event(7)).unwrap();
+        let segments = ["events.log.0", "events.log.1", "events.log"]
+            .iter()
+            .map(|f| std::fs::read(dir.join(f)).unwrap())
+            .collect();
+        (segments, writer)
+    }
+
+    /// A genesis segment holding `entries`, each linked to `prev_hash`.
+    fn genesis_with(entries: &[(&[u8; 32], LogEntry)]) -> Vec<u8> {
+        let mut bytes =
+            valori_wire::encode_header_v4(16, valori_wire::FORMAT_Q16_16, 0, &[0u8; 32]).to_vec();
+        for (prev_hash, entry) in entries {
+            bytes.extend(
+                valori_wire::encode_entry(valori_wire::VERSION_V4, prev_hash, 0, None, entry)
+                    .unwrap(),
+            );
+        }
+        bytes
+    }
+
+    #[test]
+    fn downloaded_segments_splice_and_carry_the_log_hash() {
+        let dir = tempfile::tempdir().unwrap();
+        let (segments, writer) = rotated_log(dir.path());
+
+        let mut prev = None;
+        let mut next = 0;
+        for bytes in &segments {
+            let segment = decode_segment(bytes, prev.as_ref()).unwrap();
+            for (h, _) in &segment.entries {
+                assert_eq!(*h, next);
+                next += 1;
+            }
+            prev = Some(segment.end);
+        }
+        let end = prev.unwrap();
+        assert_eq!(next, 8);
+        assert_eq!(end.seq, 2);
+        assert_eq!(&end.chain_head, writer.chain_head());
+        assert_eq!(end.log_hash, writer.log_hash());
+    }
+
+    #[test]
+    fn segment_that_does_not_extend_the_previous_one_is_rejected() {
+        let dir = tempfile::tempdir().unwrap();
+        let (segments, _) = rotated_log(dir.path());
+        let first = decode_segment(&segments[0], None).unwrap().end;
+        let second = decode_segment(&segments[1], Some(&first)).unwrap().end;
+
+        // Segment 1 skipped.
+        let err = decode_segment(&segments[2], Some(&first)).unwrap_err();
+        assert!(err.contains("does not follow"), "{err}");
+        // Right sequence, different history.
+        let forked = SegmentEnd {
+            chain_head: [7u8; 32],
+            ..second
+        };
+        let err = decode_segment(&segments[2], Some(&forked)).unwrap_err();
+        assert!(err.contains("does not extend"), "{err}");
+        // Right chain, different bytes before it.
+        let rewritten = SegmentEnd {
+            log_hash: Some([7u8; 32]),
+            ..second
+        };
+        let err = decode_segment(&segments[2], Some(&rewritten)).unwrap_err();
+        assert!(err.contains("log hash"), "{err}");
+    }
+
+    #[test]
+    fn broken_chain_link_or_wrong_log_hash_is_rejected() {
+        // Every entry passes its CRC; only the links are wrong.
+        let err = decode_segment(&genesis_with(&[(&[1u8; 32], event(0))]), None).unwrap_err();
+        assert!(err.contains("log chain broken"), "{err}");
+
+        let checkpoint = LogEntry::CheckpointV2 {
+            event_count: 0,
+            snapshot_hash: [0u8; 32],
+            timestamp: 0,
+            log_hash: [9u8; 32],
+        };
+        let err = decode_segment(&genesis_with(&[(&[0u8; 32], checkpoint)]), None).unwrap_err();
+        assert!(err.contains("log hash"), "{err}");
+    }
+
+    fn seg(seq: u32, first_height: Option<u64>) -> SegmentInfo {
+        SegmentInfo {
+            seq,
+            first_height,
+            bytes: 1,
+        }
+    }
+
+    #[test]
+    fn catch_up_plan_picks_covering_segments_or_gives_up() {
+        let idx = SegmentIndex {
+            archived: vec![seg(0, Some(0)), seg(1, Some(100)), seg(2, Some(250))],
+            live_seq: 3,
+            live_first_height: 400,
+        };
+        assert_eq!(idx.catch_up_plan(400), Some(vec![]));
+        assert_eq!(idx.catch_up_plan(0), Some(vec![0, 1, 2]));
+        assert_eq!(idx.catch_up_plan(120), Some(vec![1, 2]));
+        assert_eq!(idx.catch_up_plan(250), Some(vec![2]));
+
+        // Segment 0 pruned: heights below 100 are unreachable.
+        let pruned = SegmentIndex {
+            archived: idx.archived[1..].to_vec(),
+            ..idx.clone()
+        };
+        assert_eq!(pruned.catch_up_plan(50), None);
+        assert_eq!(pruned.catch_up_plan(150), Some(vec![1, 2]));
+
+        // A missing middle segment breaks the chain.
+        let holed = SegmentIndex {
+            archived: vec![seg(0, Some(0)), seg(2, Some(250))],
+            ..idx
+        };
+        assert_eq!(holed.catch_up_plan(10), None);
+    }
+}
@@ -0,0 +1,111 @@
+// Copyright (c) 2025 Varshith Gudur. Dual-licensed under MIT OR Apache-2.0.
+//! `X-Request-Id` — one id per HTTP request, carried through its spans.
+//!
+//! [`request_id_layer`] takes the caller's `X-Request-Id` when it is usable
+//! (1–128 visible ASCII characters) and otherwise generates one. The handler
+//! runs inside a `request` span carrying the id. Below it the engine opens
+//! `commit`, `fsync` and `apply` spans, so every line a write logs, down to
+//! the event log's fsync, names the request.
+//!
+//! The id goes back in the `X-Request-Id` response header. JSON error bodies
+//! also get it as a `request_id` field next to `error`. When the request
+//! ends, its status and elapsed time are logged: at `warn` for a 5xx, at
+//! `debug` otherwise.
+
+use axum::body::{Body, HttpBody};
+use axum::http::header::CONTENT_TYPE;
+use axum::http::{HeaderValue, Request};
+use axum::middleware::Next;
+use axum::response::Response;
+use tracing::Instrument;
+
+/// Header read from the request and set on the response.
+pub const HEADER: &str = "x-request-id";
+
+/// Longest caller-supplied id that is kept; longer ones are replaced.
+pub const MAX_LEN: usize = 128;
+
+/// Error bodies larger than this are passed through without a `request_id`.
+const MAX_ERROR_BODY: u64 = 64 * 1024;
+
+/// The request's id, as a request extension for handlers that want it.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct RequestId(pub String);
+
+fn usable(id: &str) -> bool {
+    !id.is_empty() && id.len() <= MAX_LEN && id.bytes().all(|b| b.is_ascii_graphic())
+}
+
+/// 16 random bytes as 32 hex characters.
+fn generate() -> String {
+    let mut buf = [0u8; 16];
+    getrandom::getrandom(&mut buf).expect("OS CSPRNG unavailable — cannot generate request id");
+    buf.iter().map(|b| format!("{b:02x}")).collect()
+}
+
+pub async fn request_id_layer(mut req: Request<Body>, next: Next) -> Response {
+    let id = req
+        .headers()
+        .get(HEADER)
+        .and_then(|v| v.to_str().ok())
+        .filter(|v| usable(v))
+        .map(str::to_string)
+        .unwrap_or_else(generate);
+    req.extensions_mut().insert(RequestId(id.clone()));
+
+    let span = tracing::info_span!(
+        "request",
+        request_id = %id,
+        method = %req.method(),
+        path = %req.uri().path(),
+    );
+    let started = std::time::Instant::now();
+    let mut resp = next.run(req).instrument(span.clone()).await;
+
+    let status = resp.status();
+    let elapsed_ms = started.elapsed().as_millis() as u64;
+    span.in_scope(|| {
+        if status.is_server_error() {
+            tracing::warn!(status = status.as_u16(), elapsed_ms, "request failed");
+        } else {
+            tracing::debug!(status = status.as_u16(), elapsed_ms, "request finished");
+        }
+    });
+
+    if status.is_client_error() || status.is_server_error() {
+        resp = tag_error_body(resp, &id).await;
+    }
+    if let Ok(v) = HeaderValue::from_str(&id) {
+        resp.headers_mut().insert(HEADER, v);
+    }
+    resp
+}
+
+/// Add `"request_id"` to a JSON object error body. Anything else — other
+/// content types, large or non-object bodies — is returned unchanged.
+async fn tag_error_body(resp: Response, id: &str) -> Response {
+    let is_json = resp
+        .headers()
+        .get(CONTENT_TYPE)
+        .is_some_and(|v| v.as_bytes().starts_with(b"application/json"));
+    let small = resp
+        .body()
+        .size_hint()
+        .upper()
+        .is_some_and(|n| n <= MAX_ERROR_BODY);
+    if !is_json || !small {
+        return resp;
+    }
+
+    let (mut parts, body) = resp.into_parts();
+    let Ok(bytes) = axum::body::to_bytes(body, MAX_ERROR_BODY as usize).await else {
+        return Response::from_parts(parts, Body::empty());
+    };
+    let Ok(serde_json::Value::Object(mut obj)) = serde_json::from_slice(&bytes) else {
+        return Response::from_parts(parts, Body::from(bytes));
+    };
+    obj.insert("request_id".into(), id.into());
+    let tagged = serde_json::to_vec(&obj).unwrap_or_else(|_| bytes.to_vec());
+    parts.headers.remove(axum::http::header::CONTENT_LENGTH);
+    Response::from_parts(parts, Body::from(tagged))
+}
@@ -51,12 +51,10 @@ fn bad_request(msg: impl Into<String>) -> Response {
     EngineError::InvalidInput(msg.into()).into_response()
 }
 
-pub async fn create_collection<O: CollectionOps>(
-    ops: &O,
-    payload: CreateCollectionRequest,
-) -> Result<Json<CreateCollectionResponse>, Response> {
-    let name = payload.name.trim().to_string();
-    // M-2: restrict to safe identifier characters to prevent path/injection issues.
+/// M-2: restrict names to safe identifier characters to prevent
+/// path/injection issues. Also applied to `/v1/collections` names, which
+/// become directory names.
+pub fn validate_name(name: &str) -> Result<(), Response> {
     if name.is_empty() {
         return Err(bad_request("collection name cannot be empty"));
     }
@@ -73,6 +71,15 @@ pub async fn create_collection<O: CollectionOps>(
             "collection name may only contain [a-zA-Z0-9_-]",
         ));
     }
+    Ok(())
+}
+
+pub async fn create_collection<O: CollectionOps>(
+    ops: &O,
+    payload: CreateCollectionRequest,
+) -> Result<Json<CreateCollectionResponse>, Response> {
+    let name = payload.name.trim().to_string();
+    validate_name(&name)?;
     if name == "default" {
         // Idempotent no-op — "default" always exists as id 0.
         return Ok(Json(CreateCollectionResponse {
@@ -82,6 +82,15 @@ pub struct CollectionQuery {
     pub collection: Option<String>,
 }
 
+#[derive(Deserialize)]
+pub struct RecordUpdateQuery {
+    #[serde(default)]
+    pub collection: Option<String>,
+    /// Compare-and-swap: only update if the record is still at this
+    /// version. Standalone only.
+    pub expected_version: Option<u64>,
+}
+
 #[derive(Deserialize)]
 pub struct ListNodesQuery {
     #[serde(default)]
@@ -1,14 +1,16 @@
 // Copyright (c) 2025 Varshith Gudur. Dual-licensed under MIT OR Apache-2.0.
 //! Memory domain — shared bodies for `POST /v1/memory/upsert`, `POST /v1/memory/search`,
-//! `POST /v1/memory/consolidate`, and `POST /v1/memory/contradict` (and aliases).
+//! `POST /v1/memory/consolidate`, `POST /v1/memory/contradict` and
+//! `POST /v1/memory/dedup_check` (and aliases).
 //!
 //! Canonical behavior (both paths, enforced here):
 //! * Unknown `collection` -> 404 Not Found.
 //! * Consolidate sets metadata if provided in the payload on BOTH paths (previously omitted on cluster).
 //! * Contradict checks similarity threshold and commits Contradicts edge identically on both paths.
 //! * Upsert, consolidate, and contradict emit write receipts through `receipt_bridge`.
-//! * Read consistency for search: cluster mode executes read-index check via `ensure_read_consistency`
-//!   before searching, while standalone mode executes a zero-overhead local read.
+//! * Read consistency for search and dedup check: cluster mode executes read-index check via
+//!   `ensure_read_consistency` before searching, while standalone mode executes a zero-overhead
+//!   local read.
 
 use axum::http::StatusCode;
 use axum::response::{IntoResponse, Response};
@@ -17,8 +19,9 @@ use std::sync::Arc;
 
 use crate::api::{
     MemoryConsolidateRequest, MemoryConsolidateResponse, MemoryContradictRequest,
-    MemoryContradictResponse, MemorySearchHit, MemorySearchResponse, MemorySearchVectorRequest,
-    MemoryUpsertResponse, MemoryUpsertVectorRequest,
+    MemoryContradictResponse, MemoryDedupCheckRequest, MemoryDedupCheckResponse, MemoryDedupMatch,
+    MemorySearchHit, MemorySearchResponse, MemorySearchVectorRequest, MemoryUpsertResponse,
+    MemoryUpsertVectorRequest, MAX_DEDUP_MATCHES,
 };
 
 /// Outcome of a memory vector upsert.
@@ -107,6 +110,14 @@ pub trait MemoryOps: Send + Sync {
         ns: u16,
         req: &MemoryContradictRequest,
     ) -> Result<ContradictedMemory, Response>;
+
+    /// Range search for near-duplicates: records within `req.max_distance`
+    /// of `req.vector`, nearest first, with metadata attached.
+    async fn dedup_check(
+        &self,
+        ns: u16,
+        req: &MemoryDedupCheckRequest,
+    ) -> Result<Vec<MemoryDedupMatch>, Response>;
 }
 
 async fn resolve<O: MemoryOps>(ops: &O, collection: Option<&str>) -> Result<u16, Response> {
@@ -236,3 +247,33 @@ pub async fn memory_contradict<O: MemoryOps>(
         log_index: c.log_index,
     }))
 }
+
+pub async fn memory_dedup_check<O: MemoryOps>(
+    ops: &O,
+    req: MemoryDedupCheckRequest,
+) -> Result<Json<MemoryDedupCheckResponse>, Response> {
+    if req.limit > MAX_DEDUP_MATCHES {
+        return Err((
+            StatusCode::BAD_REQUEST,
+            Json(serde_json::json!({
+                "error": format!("limit is at most {MAX_DEDUP_MATCHES}, got {}", req.limit)
+            })),
+        )
+            .into_response());
+    }
+    if req.max_distance.is_nan() || req.max_distance < 0.0 {
+        return Err((
+            StatusCode::BAD_REQUEST,
+            Json(serde_json::json!({ "error": "max_distance must be zero or more" })),
+        )
+            .into_response());
+    }
+    let ns = resolve(ops, req.collection.as_deref()).await?;
+    ops.ensure_read_consistency(ns, req.consistency.as_deref())
+        .await?;
+    let matches = ops.dedup_check(ns, &req).await?;
+    Ok(Json(MemoryDedupCheckResponse {
+        duplicate: !matches.is_empty(),
+        matches,
+    }))
+}
@@ -6,12 +6,14 @@
 //! * set → `{"success": true}`. (The cluster path previously answered
 //!   `{"ok": true}` — a silent wire divergence from standalone.)
 //! * get → `{"target_id": …, "metadata": …}` with `metadata: null` when unset.
+//! * delete → `{"success": true}`, whether or not the key was set.
 
 use axum::response::Response;
 use axum::Json;
 
 use crate::api::{
-    MetadataGetRequest, MetadataGetResponse, MetadataSetRequest, MetadataSetResponse,
+    MetadataDeleteRequest, MetadataDeleteResponse, MetadataGetRequest, MetadataGetResponse,
+    MetadataSetRequest, MetadataSetResponse,
 };
 
 #[async_trait::async_trait]
@@ -24,6 +26,8 @@ pub trait MetaOps: Send + Sync {
         metadata: serde_json::Value,
     ) -> Result<(), Response>;
     async fn get_meta(&self, target_id: &str) -> Option<serde_json::Value>;
+    /// Commit a `KernelEvent::DeleteMeta`, audited like a set.
+    async fn delete_meta(&self, target_id: String) -> Result<(), Response>;
 }
 
 pub async fn meta_set<O: MetaOps>(
@@ -34,6 +38,14 @@ pub async fn meta_set<O: MetaOps>(
     Ok(Json(MetadataSetResponse { success: true }))
 }
 
+pub async fn meta_delete<O: MetaOps>(
+    ops: &O,
+    req: MetadataDeleteRequest,
+) -> Result<Json<MetadataDeleteResponse>, Response> {
+    ops.delete_meta(req.target_id).await?;
+    Ok(Json(MetadataDeleteResponse { success: true }))
+}
+
 pub async fn meta_get<O: MetaOps>(ops: &O, req: MetadataGetRequest) -> Json<MetadataGetResponse> {
     let metadata = ops.get_meta(&req.target_id).await;
     Json(MetadataGetResponse {
@@ -6,7 +6,9 @@
 //! * Unknown `collection` → 404. (Standalone previously returned 400.)
 //! * `POST /v1/soft-delete` exists on BOTH paths. (Previously cluster-only;
 //!   the standalone engine has had `soft_delete_record` all along.)
-//! * Responses carry `log_index` on the cluster path only.
+//! * Responses carry `log_index` on the cluster path only, and
+//!   `state_version` on the standalone path only. `if_version` is checked
+//!   under the standalone write lock; the cluster path rejects it with 400.
 //! * Both paths emit a Delete receipt through `receipt_bridge`.
 
 use axum::http::StatusCode;
@@ -25,14 +27,23 @@ pub struct DeletedRecord {
     pub cluster: bool,
     pub state_before: String,
     pub state_after: String,
+    /// State version after the delete — standalone path only.
+    pub state_version: Option<u64>,
 }
 
 #[async_trait::async_trait]
 pub trait RecordOps: Send + Sync {
     /// Optional collection name → namespace id (`None` = default).
     async fn resolve_collection(&self, name: Option<&str>) -> Option<u16>;
-    /// Commit the (soft) delete.
-    async fn delete(&self, ns: u16, id: u32, soft: bool) -> Result<DeletedRecord, Response>;
+    /// Commit the (soft) delete, if the state version still matches
+    /// `if_version` when one is given.
+    async fn delete(
+        &self,
+        ns: u16,
+        id: u32,
+        soft: bool,
+        if_version: Option<u64>,
+    ) -> Result<DeletedRecord, Response>;
 }
 
 async fn resolve<O: RecordOps>(ops: &O, collection: Option<&str>) -> Result<u16, Response> {
@@ -57,7 +68,7 @@ pub async fn delete_record<O: RecordOps>(
     soft: bool,
 ) -> Result<Json<DeleteRecordResponse>, Response> {
     let ns = resolve(ops, req.collection.as_deref()).await?;
-    let d = ops.delete(ns, req.id, soft).await?;
+    let d = ops.delete(ns, req.id, soft, req.if_version).await?;
     {
         use valori_planner::operation::{OperationInputs, OperationKind};
         let inputs = OperationInputs::Delete {
@@ -80,5 +91,6 @@ pub async fn delete_record<O: RecordOps>(
     Ok(Json(DeleteRecordResponse {
         success: true,
         log_index: d.log_index,
+        state_version: d.state_version,
     }))
 }
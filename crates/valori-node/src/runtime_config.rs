@@ -0,0 +1,152 @@
+// Copyright (c) 2025 Varshith Gudur. Dual-licensed under MIT OR Apache-2.0.
+//! Settings that can change while the node runs (`/v1/admin/config`).
+//!
+//! Only knobs that never touch state or its hashes live here: the
+//! auto-snapshot interval, the request rate limit, the slow query
+//! threshold, the default request timeout, the HNSW `ef_search` default and
+//! the log filter. `ef_search` is stored on the engine and the log filter in
+//! [`crate::telemetry`]; this struct holds the other four.
+
+use axum::{
+    extract::{Request, State},
+    http::{header::RETRY_AFTER, StatusCode},
+    middleware::Next,
+    response::{IntoResponse, Response},
+};
+use std::sync::atomic::{AtomicU32, AtomicU64, Ordering};
+use std::sync::{Arc, Mutex};
+use std::time::{Duration, Instant};
+use tokio::sync::watch;
+
+pub struct RuntimeConfig {
+    /// Seconds between auto-snapshots; `None` = off. The snapshot task
+    /// watches this, so a change applies at the next tick.
+    snapshot_interval: watch::Sender<Option<u64>>,
+    /// Requests per second across the protected API; 0 = unlimited.
+    rate_limit_rps: AtomicU32,
+    /// Start of the current one-second window and requests seen in it.
+    window: Mutex<(Instant, u32)>,
+    /// Searches and inserts slower than this many milliseconds are logged
+    /// (see [`crate::slow_query`]); 0 = off.
+    slow_query_ms: AtomicU64,
+    /// Searches without their own `timeout_ms` give up after this many
+    /// milliseconds with `504`; 0 = no deadline.
+    request_timeout_ms: AtomicU64,
+}
+
+impl Default for RuntimeConfig {
+    fn default() -> Self {
+        Self::new(None, None)
+    }
+}
+
+impl RuntimeConfig {
+    pub fn new(snapshot_interval_secs: Option<u64>, rate_limit_rps: Option<u32>) -> Self {
+        Self {
+            snapshot_interval: watch::Sender::new(snapshot_interval_secs.filter(|&s| s > 0)),
+            rate_limit_rps: AtomicU32::new(rate_limit_rps.unwrap_or(0)),
+            window: Mutex::new((Instant::now(), 0)),
+            slow_query_ms: AtomicU64::new(0),
+            request_timeout_ms: AtomicU64::new(0),
+        }
+    }
+
+    pub fn with_slow_query_ms(self, ms: Option<u64>) -> Self {
+        self.set_slow_query_ms(ms);
+        self
+    }
+
+    pub fn slow_query_ms(&self) -> Option<u64> {
+        Some(self.slow_query_ms.load(Ordering::Relaxed)).filter(|&ms| ms > 0)
+    }
+
+    pub fn set_slow_query_ms(&self, ms: Option<u64>) {
+        self.slow_query_ms.store(ms.unwrap_or(0), Ordering::Relaxed);
+    }
+
+    /// The threshold for [`crate::slow_query::QueryTimer`].
+    pub fn slow_query_threshold(&self) -> Option<Duration> {
+        self.slow_query_ms().map(Duration::from_millis)
+    }
+
+    pub fn with_request_timeout_ms(self, ms: Option<u64>) -> Self {
+        self.set_request_timeout_ms(ms);
+        self
+    }
+
+    pub fn request_timeout_ms(&self) -> Option<u64> {
+        Some(self.request_timeout_ms.load(Ordering::Relaxed)).filter(|&ms| ms > 0)
+    }
+
+    pub fn set_request_timeout_ms(&self, ms: Option<u64>) {
+        self.request_timeout_ms
+            .store(ms.unwrap_or(0), Ordering::Relaxed);
+    }
+
+    /// When a request that arrived at `start` must be done: its own
+    /// `timeout_ms` if it set one (0 = never), else the node default.
+    pub fn deadline(&self, start: Instant, timeout_ms: Option<u64>) -> Option<Instant> {
+        timeout_ms
+            .or_else(|| self.request_timeout_ms())
+            .filter(|&ms| ms > 0)
+            .map(|ms| start + Duration::from_millis(ms))
+    }
+
+    pub fn snapshot_interval_secs(&self) -> Option<u64> {
+        *self.snapshot_interval.borrow()
+    }
+
+    pub fn set_snapshot_interval_secs(&self, secs: Option<u64>) {
+        self.snapshot_interval.send_replace(secs.filter(|&s| s > 0));
+    }
+
+    pub fn subscribe_snapshot_interval(&self) -> watch::Receiver<Option<u64>> {
+        self.snapshot_interval.subscribe()
+    }
+
+    pub fn rate_limit_rps(&self) -> Option<u32> {
+        Some(self.rate_limit_rps.load(Ordering::Relaxed)).filter(|&r| r > 0)
+    }
+
+    pub fn set_rate_limit_rps(&self, rps: Option<u32>) {
+        self.rate_limit_rps
+            .store(rps.unwrap_or(0), Ordering::Relaxed);
+    }
+
+    /// Count one request against the current one-second window; `false`
+    /// when the window is already full.
+    pub fn try_acquire(&self) -> bool {
+        let limit = self.rate_limit_rps.load(Ordering::Relaxed);
+        if limit == 0 {
+            return true;
+        }
+        let mut window = self.window.lock().unwrap_or_else(|e| e.into_inner());
+        if window.0.elapsed() >= Duration::from_secs(1) {
+            *window = (Instant::now(), 0);
+        }
+        if window.1 >= limit {
+            return false;
+        }
+        window.1 += 1;
+        true
+    }
+}
+
+/// Rejects requests over the runtime rate limit with `429` and
+/// `Retry-After: 1`. `/v1/admin/*` is exempt so an operator can always
+/// reach `/v1/admin/config` to lift a limit that was set too low.
+pub async fn rate_limit_guard(
+    State(runtime): State<Arc<RuntimeConfig>>,
+    req: Request,
+    next: Next,
+) -> Response {
+    if req.uri().path().starts_with("/v1/admin") || runtime.try_acquire() {
+        return next.run(req).await;
+    }
+    (
+        StatusCode::TOO_MANY_REQUESTS,
+        [(RETRY_AFTER, "1")],
+        "rate limit exceeded",
+    )
+        .into_response()
+}
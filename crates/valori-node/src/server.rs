@@ -1,9 +1,14 @@
 // Copyright (c) 2025 Varshith Gudur. Dual-licensed under MIT OR Apache-2.0.
 use crate::api::*;
+use crate::api_audit::{with_principal, ApiAuditLog};
 use crate::api_keys::{required_scope, ApiScope, AuthState, KeyStore};
 use crate::crypto_vault::{hex_to_key_id, key_id_to_hex, new_key_id};
 use crate::engine::Engine;
 use crate::errors::EngineError;
+use crate::runtime_config::RuntimeConfig;
+use crate::session::{SessionError, SessionRegistry};
+use crate::slow_query::{QueryParams, QueryTimer};
+use crate::snapshot_stream::SpooledSnapshot;
 use axum::{
     body::Body,
     extract::{Extension, Path as AxumPath, State},
@@ -54,17 +59,43 @@ fn apply_metadata_filter(
 ) -> Vec<(u32, f32)> {
     match filter {
         None => hits.take(limit).collect(),
-        Some(f) => hits
-            .filter(|(id, _)| {
-                let key = format!("rec:{id}");
-                match meta_store.get(&key) {
-                    Some(meta) => valori_search::matches_metadata_filter(&meta, f),
-                    None => false,
-                }
-            })
-            .take(limit)
-            .collect(),
+        Some(f) => {
+            let candidates = meta_store.candidates(f);
+            hits.filter(|(id, _)| metadata_matches(meta_store, candidates.as_ref(), f, *id))
+                .take(limit)
+                .collect()
+        }
+    }
+}
+
+/// Whether record `id`'s metadata passes `filter`. `candidates` is the
+/// metadata index's answer for the filter: a hit outside it is dropped
+/// without reading its metadata.
+fn metadata_matches(
+    meta_store: &crate::metadata::MetadataStore,
+    candidates: Option<&std::collections::BTreeSet<u32>>,
+    filter: &serde_json::Map<String, serde_json::Value>,
+    id: u32,
+) -> bool {
+    if candidates.is_some_and(|c| !c.contains(&id)) {
+        return false;
     }
+    meta_store
+        .get(&format!("rec:{id}"))
+        .is_some_and(|meta| valori_search::matches_metadata_filter(&meta, filter))
+}
+
+/// Whether record `id`'s tag passes `filter`; `None` admits every record.
+fn tag_matches(
+    state: &valori_kernel::state::kernel::KernelState,
+    filter: Option<valori_kernel::index::TagFilter>,
+    id: u32,
+) -> bool {
+    filter.is_none_or(|f| {
+        state
+            .get_record(valori_kernel::types::id::RecordId(id))
+            .is_some_and(|r| f.matches(r.tag))
+    })
 }
 
 fn safe_path(
@@ -119,7 +150,7 @@ async fn auth_guard_v2(
     next: Next,
 ) -> Result<Response, StatusCode> {
     if !auth.has_any_auth() {
-        return Ok(next.run(req).await);
+        return Ok(with_principal(next.run(req).await, "anonymous"));
     }
     let path = req.uri().path().to_string();
     let method = req.method().clone();
@@ -138,7 +169,17 @@ async fn auth_guard_v2(
     // Key store check first.
     if let Some(record) = auth.key_store.lookup(token) {
         if record.scope.satisfies(&required) {
-            return Ok(next.run(req).await);
+            let principal = format!("key:{}", record.id);
+            return Ok(with_principal(next.run(req).await, principal));
+        }
+        return Err(StatusCode::FORBIDDEN);
+    }
+
+    // Static role tokens (VALORI_AUTH_ROLES).
+    if let Some(scope) = auth.role_for(token) {
+        if scope.satisfies(&required) {
+            let principal = format!("role:{scope}");
+            return Ok(with_principal(next.run(req).await, principal));
         }
         return Err(StatusCode::FORBIDDEN);
     }
@@ -147,13 +188,30 @@ async fn auth_guard_v2(
     if let Some(ref legacy) = auth.legacy_token {
         use subtle::ConstantTimeEq;
         if token.as_bytes().ct_eq(legacy.as_bytes()).into() {
-            return Ok(next.run(req).await);
+            return Ok(with_principal(next.run(req).await, "legacy"));
         }
     }
 
     Err(StatusCode::UNAUTHORIZED)
 }
 
+/// Records every protected request in the API audit trail, tagging mutating
+/// requests with the committed event-log height they left behind.
+async fn api_audit_guard(
+    State(state): State<SharedEngine>,
+    Extension(audit): Extension<Arc<ApiAuditLog>>,
+    req: AxumRequest,
+    next: Next,
+) -> Response {
+    crate::api_audit::observe(&audit, req, next, || async move {
+        let engine = state.read().await;
+        engine
+            .event_committer()
+            .map(|c| c.journal().committed_height())
+    })
+    .await
+}
+
 /// Build the CORS layer.
 ///
 /// H-5: `VALORI_CORS_ORIGIN=*` with auth enabled is a misconfiguration —
@@ -184,6 +242,9 @@ fn make_cors_layer(origin: &Option<String>, has_auth: bool) -> Option<CorsLayer>
                 axum::http::Method::OPTIONS,
             ])
             .allow_headers(Any)
+            .expose_headers([axum::http::HeaderName::from_static(
+                crate::request_id::HEADER,
+            )])
     };
     Some(layer)
 }
@@ -218,20 +279,99 @@ pub fn build_router(
     )
 }
 
-/// Full router builder used by `main.rs` — supports per-tenant API keys.
+/// Router builder with per-tenant API keys but no static role tokens.
 pub fn build_router_with_keys(
     state: SharedEngine,
     auth_token: Option<String>,
     cors_origin: Option<String>,
     key_store: Arc<KeyStore>,
     receipt_store: Arc<valori_effect::ReceiptStore>,
+) -> Router {
+    build_router_with_auth(
+        state,
+        auth_token,
+        cors_origin,
+        key_store,
+        receipt_store,
+        Vec::new(),
+        Arc::new(ApiAuditLog::in_memory()),
+    )
+}
+
+/// Router builder with per-tenant API keys, the static role tokens from
+/// `NodeConfig::auth_roles` and the API audit trail, but no runtime settings
+/// shared with the caller (rate limit off, no auto-snapshot interval).
+pub fn build_router_with_auth(
+    state: SharedEngine,
+    auth_token: Option<String>,
+    cors_origin: Option<String>,
+    key_store: Arc<KeyStore>,
+    receipt_store: Arc<valori_effect::ReceiptStore>,
+    auth_roles: Vec<(ApiScope, String)>,
+    api_audit: Arc<ApiAuditLog>,
+) -> Router {
+    build_router_with_runtime(
+        state,
+        auth_token,
+        cors_origin,
+        key_store,
+        receipt_store,
+        auth_roles,
+        api_audit,
+        Arc::new(RuntimeConfig::default()),
+    )
+}
+
+/// Everything in [`build_router_with_auth`] plus the [`RuntimeConfig`] that
+/// `/v1/admin/config` edits and the background tasks read. Configured
+/// collections are kept in memory.
+#[allow(clippy::too_many_arguments)]
+pub fn build_router_with_runtime(
+    state: SharedEngine,
+    auth_token: Option<String>,
+    cors_origin: Option<String>,
+    key_store: Arc<KeyStore>,
+    receipt_store: Arc<valori_effect::ReceiptStore>,
+    auth_roles: Vec<(ApiScope, String)>,
+    api_audit: Arc<ApiAuditLog>,
+    runtime: Arc<RuntimeConfig>,
+) -> Router {
+    build_router_with_collections(
+        state,
+        auth_token,
+        cors_origin,
+        key_store,
+        receipt_store,
+        auth_roles,
+        api_audit,
+        runtime,
+        Arc::new(EngineRegistry::in_memory(
+            crate::config::NodeConfig::default(),
+        )),
+    )
+}
+
+/// Full router builder used by `main.rs`: everything in
+/// [`build_router_with_runtime`] plus the [`EngineRegistry`] behind
+/// `/v1/collections`.
+#[allow(clippy::too_many_arguments)]
+pub fn build_router_with_collections(
+    state: SharedEngine,
+    auth_token: Option<String>,
+    cors_origin: Option<String>,
+    key_store: Arc<KeyStore>,
+    receipt_store: Arc<valori_effect::ReceiptStore>,
+    auth_roles: Vec<(ApiScope, String)>,
+    api_audit: Arc<ApiAuditLog>,
+    runtime: Arc<RuntimeConfig>,
+    collections: Arc<EngineRegistry>,
 ) -> Router {
     use crate::capabilities::CapabilityRegistryBuilder;
     use crate::runner::TaskRegistry;
-    let sc = if let Ok(eng) = state.try_read() {
-        eng.shard_count as u8
+    let (sc, recovery_progress) = if let Ok(eng) = state.try_read() {
+        (eng.shard_count as u8, eng.recovery_progress.clone())
     } else {
-        1
+        (1, Arc::default())
     };
     let capability_registry: Arc<valori_effect::capability::CapabilityRegistry> = Arc::new(
         CapabilityRegistryBuilder::new(state.clone(), sc, shared_http_client().clone()).build(),
@@ -239,11 +379,17 @@ pub fn build_router_with_keys(
     let task_registry: Arc<TaskRegistry> = Arc::new(TaskRegistry::default_registry());
     let execution_registry: Arc<crate::execution_registry::ExecutionRegistry> =
         Arc::new(crate::execution_registry::ExecutionRegistry::default());
+    let session_registry: Arc<SessionRegistry> = Arc::new(SessionRegistry::default());
     // ── Public routes — no auth required ─────────────────────────────────────
     let public = Router::new()
         .route("/health", axum::routing::get(health_check))
         .route("/metrics", axum::routing::get(metrics_handler))
-        .with_state(state.clone());
+        .with_state(state.clone())
+        .merge(
+            Router::new()
+                .route("/readyz", axum::routing::get(readiness_check))
+                .with_state(recovery_progress),
+        );
 
     // ── Key management routes (admin scope enforced by middleware) ────────────
     let key_routes = Router::new()
@@ -255,13 +401,22 @@ pub fn build_router_with_keys(
     // surface. All legacy paths below alias into these same handlers.
     let v1 = Router::new()
         .route("/v1/version", axum::routing::get(version_handler))
-        .route("/v1/records", post(insert_record))
+        .route("/v1/records", post(insert_record).get(list_records_where))
         .route("/v1/records/:id", axum::routing::get(get_record_by_id))
+        .route(
+            "/v1/records/:id/stats",
+            axum::routing::get(get_record_stats),
+        )
         .route(
             "/v1/records/:id/metadata",
             axum::routing::patch(update_record_metadata),
         )
+        .route(
+            "/v1/records/:id/sparse",
+            axum::routing::put(set_sparse_vector),
+        )
         .route("/v1/search", post(search))
+        .route("/v1/sparse/search", post(search_sparse))
         .route("/v1/graph/node", post(create_node))
         .route(
             "/v1/graph/node/:id",
@@ -273,22 +428,60 @@ pub fn build_router_with_keys(
         .route("/v1/graph/subgraph", axum::routing::get(get_subgraph))
         .route("/v1/delete", post(delete_record))
         .route("/v1/soft-delete", post(soft_delete_record))
+        .route("/v1/records/delete_batch", post(delete_record_batch))
+        .route("/v1/transactions", post(commit_transaction))
+        .route("/v1/sessions", post(open_session))
+        .route(
+            "/v1/sessions/:id",
+            axum::routing::get(get_session).delete(abandon_session),
+        )
+        .route(
+            "/v1/sessions/:id/operations",
+            post(append_session_operations),
+        )
+        .route(
+            "/v1/sessions/:id/savepoints",
+            post(create_session_savepoint),
+        )
+        .route("/v1/sessions/:id/rollback", post(rollback_session))
+        .route("/v1/sessions/:id/commit", post(commit_session))
+        .route("/v1/tx/begin", post(begin_tx))
+        .route("/v1/tx/stage", post(stage_tx))
+        .route("/v1/tx/commit", post(commit_tx))
+        .route("/v1/tx/abort", post(abort_tx))
         .route("/v1/vectors/batch-insert", post(batch_insert))
         .route("/v1/graphrag", post(graphrag))
         .route("/v1/snapshot/download", axum::routing::get(snapshot))
+        .route("/v1/export", get(export_records))
         .route("/v1/snapshot/upload", post(restore))
+        .route("/v1/snapshot/upload/init", post(snapshot_upload_init))
+        .route(
+            "/v1/snapshot/upload/:id",
+            get(snapshot_upload_status)
+                .put(snapshot_upload_chunk)
+                .delete(snapshot_upload_abort)
+                .layer(axum::extract::DefaultBodyLimit::max(MAX_UPLOAD_CHUNK)),
+        )
+        .route(
+            "/v1/snapshot/upload/:id/finalize",
+            post(snapshot_upload_finalize),
+        )
         .route("/v1/snapshot/save", post(snapshot_save))
         .route("/v1/snapshot/restore", post(snapshot_restore))
+        .route("/v1/snapshot/list", axum::routing::get(snapshot_list))
         .route("/v1/memory/upsert", post(memory_upsert_vector))
         .route("/v1/memory/upsert_vector", post(memory_upsert_vector))
         .route("/v1/memory/search", post(memory_search_vector))
         .route("/v1/memory/search_vector", post(memory_search_vector))
         .route("/v1/memory/consolidate", post(memory_consolidate))
         .route("/v1/memory/contradict", post(memory_contradict))
+        .route("/v1/memory/dedup_check", post(memory_dedup_check))
         .route("/v1/memory/meta/set", post(meta_set))
         .route("/v1/memory/meta/get", axum::routing::get(meta_get))
+        .route("/v1/memory/meta/delete", post(meta_delete))
         .route("/v1/proof/state", axum::routing::get(get_proof))
         .route("/v1/proof/event-log", axum::routing::get(get_event_proof))
+        .route("/v1/proof/at", axum::routing::get(get_attest_proof))
         .route("/v1/proof/receipt", axum::routing::get(get_latest_receipt))
         .route(
             "/v1/proof/receipt/:id",
@@ -299,11 +492,65 @@ pub fn build_router_with_keys(
             "/v1/replication/events",
             axum::routing::get(get_replication_events),
         )
+        .route(
+            "/v1/replication/handshake",
+            axum::routing::get(replication_handshake),
+        )
         .route(
             "/v1/replication/state",
             axum::routing::get(get_replication_state),
         )
+        .route(
+            "/v1/replication/segments",
+            axum::routing::get(get_replication_segments),
+        )
+        .route(
+            "/v1/replication/segments/:seq",
+            axum::routing::get(download_replication_segment),
+        )
+        .route(
+            "/v1/replication/partial-proof",
+            axum::routing::get(get_partial_proof),
+        )
+        .route(
+            "/v1/replication/head",
+            axum::routing::get(get_replication_head),
+        )
+        .route("/v1/replication/chain", axum::routing::get(get_event_chain))
+        .route(
+            "/v1/replication/region",
+            axum::routing::get(get_region_status),
+        )
+        .route("/v1/admin/region/promote", post(promote_region))
         .route("/v1/timeline", axum::routing::get(get_timeline))
+        .route("/v1/events", axum::routing::get(get_events))
+        .route("/v1/diff", axum::routing::get(get_state_diff))
+        .route("/v1/analytics/drift", axum::routing::get(get_drift))
+        .route("/v1/stats", axum::routing::get(kernel_stats))
+        .route("/v1/stats/storage", axum::routing::get(storage_stats))
+        .route("/v1/audit", axum::routing::get(crate::api_audit::get_audit))
+        .route("/v1/admin/check", axum::routing::get(admin_check))
+        .route(
+            "/v1/admin/forget",
+            axum::routing::get(get_forget_candidates).post(run_forget_policy),
+        )
+        .route("/v1/admin/resize", axum::routing::post(resize_pools))
+        .route("/v1/admin/vacuum", post(vacuum_records))
+        .route("/v1/admin/annotations", post(annotate_timeline))
+        .route("/v1/admin/recall", post(estimate_recall))
+        .route(
+            "/v1/admin/index/repair",
+            axum::routing::get(check_index_graphs).post(repair_index_graphs),
+        )
+        .route("/v1/admin/restore", axum::routing::post(restore_to_height))
+        .route("/v1/admin/rotate-log", post(rotate_event_log))
+        .route("/v1/admin/compact", post(compact_event_log))
+        .route("/v1/admin/bulk-load/begin", post(begin_bulk_load))
+        .route("/v1/admin/bulk-load/finish", post(finish_bulk_load))
+        .route(
+            "/v1/admin/config",
+            axum::routing::get(get_runtime_config).patch(patch_runtime_config),
+        )
         .route("/v1/operations", axum::routing::get(get_operations))
         .route(
             "/v1/operations/:id",
@@ -318,6 +565,26 @@ pub fn build_router_with_keys(
             post(create_collection_handler).get(list_collections_handler),
         )
         .route("/v1/namespaces/:name", delete(drop_collection_handler))
+        .route(
+            "/v1/collections",
+            post(create_configured_collection).get(list_configured_collections),
+        )
+        .route(
+            "/v1/collections/:name",
+            get(get_configured_collection).delete(drop_configured_collection),
+        )
+        .route("/v1/collections/:name/records", post(collection_insert))
+        .route(
+            "/v1/collections/:name/records/:id",
+            get(collection_get_record).delete(collection_delete_record),
+        )
+        .route("/v1/collections/:name/search", post(collection_search))
+        .route(
+            "/v1/collections/:name/snapshot",
+            get(collection_snapshot).post(collection_restore),
+        )
+        .route("/v1/collections/:name/proof", get(collection_proof))
+        .route("/v1/proof/collections", get(collections_root_proof))
         .route(
             "/v1/storage/snapshots",
             axum::routing::get(list_remote_snapshots),
@@ -384,11 +651,23 @@ pub fn build_router_with_keys(
         .layer(axum::middleware::from_fn(deprecation_warning));
 
     // ── Protected routes = canonical v1 + deprecated legacy ──────────────────
-    let protected = Router::new().merge(v1).merge(legacy).with_state(state);
+    let audit_state = state.clone();
+    // H-2: body size limit — prevent OOM via unbounded request bodies. Layers
+    // only wrap routes added before them, so the streaming import added
+    // after it is exempt: it reads its body a line at a time.
+    let protected = Router::new()
+        .merge(v1)
+        .merge(legacy)
+        .layer(tower_http::limit::RequestBodyLimitLayer::new(
+            MAX_REQUEST_BODY,
+        ))
+        .route("/v1/admin/import", post(bulk_import))
+        .with_state(state);
 
     let auth = Arc::new(AuthState {
         key_store: key_store.clone(),
         legacy_token: auth_token,
+        role_tokens: auth_roles,
     });
     let has_auth = auth.has_any_auth();
     if has_auth {
@@ -399,20 +678,38 @@ pub fn build_router_with_keys(
 
     // Extension must be the outermost layer (applied last) so it is injected
     // into the request BEFORE auth_guard_v2 runs and tries to extract it.
+    // The audit layer wraps the auth guard so denied requests are recorded too.
     let protected = protected
+        .layer(axum::middleware::from_fn_with_state(
+            runtime.clone(),
+            crate::runtime_config::rate_limit_guard,
+        ))
         .layer(axum::middleware::from_fn(auth_guard_v2))
+        .layer(axum::middleware::from_fn(
+            crate::tls::replication_mtls_guard,
+        ))
+        .layer(axum::middleware::from_fn_with_state(
+            audit_state,
+            api_audit_guard,
+        ))
+        .layer(Extension(api_audit))
+        .layer(Extension(runtime))
         .layer(Extension(auth))
         .layer(Extension(receipt_store))
         .layer(Extension(capability_registry))
         .layer(Extension(task_registry))
-        .layer(Extension(execution_registry));
-
-    // H-2: Global body size limit — prevent OOM via unbounded request bodies.
-    // Snapshot upload (binary) legitimately needs more room; everything else
-    // uses JSON that should never exceed 32 MB.
-    let mut router = Router::new().merge(public).merge(protected).layer(
-        tower_http::limit::RequestBodyLimitLayer::new(32 * 1024 * 1024),
-    );
+        .layer(Extension(execution_registry))
+        .layer(Extension(session_registry))
+        .layer(Extension(collections));
+
+    let mut router = Router::new()
+        .merge(public.layer(tower_http::limit::RequestBodyLimitLayer::new(
+            MAX_REQUEST_BODY,
+        )))
+        .merge(protected)
+        .layer(axum::middleware::from_fn(
+            crate::request_id::request_id_layer,
+        ));
     if let Some(cors) = make_cors_layer(&cors_origin, has_auth) {
         tracing::info!("CORS enabled: origin = {:?}", cors_origin);
         router = router.layer(cors);
@@ -426,6 +723,9 @@ pub fn build_router_with_keys(
 /// * **200** `"ok"`       — all pools below 90 % capacity
 /// * **200** `"degraded"` — at least one pool ≥ 90 %; still serving all requests
 /// * **503** `"full"`     — at least one pool at 100 %; inserts are being rejected
+/// * **503** `"drift"`    — the consistency sentinel found drift
+/// * **503** `"read_only"` — the startup snapshot failed its self-check; writes
+///   are refused (`VALORI_SNAPSHOT_CHECK=refuse-writes`)
 ///
 /// This endpoint is **always unauthenticated** so that load-balancer health
 /// probes and liveness checks work without a bearer token.
@@ -438,7 +738,7 @@ async fn health_check(State(state): State<SharedEngine>) -> impl IntoResponse {
     // heavy write bursts.
     engine.update_prometheus_metrics();
 
-    let status_code = if h.status == "full" {
+    let status_code = if matches!(h.status, "full" | "drift" | "read_only") {
         StatusCode::SERVICE_UNAVAILABLE
     } else {
         StatusCode::OK
@@ -447,6 +747,37 @@ async fn health_check(State(state): State<SharedEngine>) -> impl IntoResponse {
     (status_code, Json(h))
 }
 
+/// `GET /readyz` — readiness probe.
+///
+/// * **200** `"ready"`             — startup recovery has finished
+/// * **503** `"recovering (43%)"` — still replaying; the body carries the
+///   byte / event counters and ETA
+///
+/// Reads only the shared [`RecoveryProgress`] counters, never the engine
+/// lock, so it answers while recovery holds the engine. Unauthenticated, like
+/// `/health`.
+///
+/// [`RecoveryProgress`]: valori_storage::events::RecoveryProgress
+async fn readiness_check(
+    State(progress): State<Arc<valori_storage::events::RecoveryProgress>>,
+) -> impl IntoResponse {
+    let status = progress.status();
+    if status.recovering {
+        (
+            StatusCode::SERVICE_UNAVAILABLE,
+            Json(serde_json::json!({
+                "status": format!("recovering ({:.0}%)", status.percent),
+                "recovery": status,
+            })),
+        )
+    } else {
+        (
+            StatusCode::OK,
+            Json(serde_json::json!({ "status": "ready" })),
+        )
+    }
+}
+
 use crate::routes::version as version_handler;
 
 /// Standalone impl of the shared record-deletion primitives.
@@ -461,9 +792,13 @@ impl crate::routes::records::RecordOps for SharedEngine {
         _ns: u16,
         id: u32,
         soft: bool,
+        if_version: Option<u64>,
     ) -> Result<crate::routes::records::DeletedRecord, Response> {
         use valori_kernel::snapshot::blake3::hash_state_blake3;
         let mut engine = self.write().await;
+        engine
+            .check_version(if_version)
+            .map_err(|e| e.into_response())?;
         let state_before: String = hash_state_blake3(&engine.state)
             .iter()
             .map(|b| format!("{:02x}", b))
@@ -485,6 +820,7 @@ impl crate::routes::records::RecordOps for SharedEngine {
             cluster: false,
             state_before,
             state_after,
+            state_version: Some(engine.state_version()),
         })
     }
 }
@@ -505,85 +841,636 @@ async fn soft_delete_record(
     crate::routes::records::delete_record(&state, &receipts, payload, true).await
 }
 
-async fn get_record_by_id(
+/// `POST /v1/records/delete_batch` — delete up to [`MAX_DELETE_BATCH`] ids
+/// of one collection as a single event batch, for purge jobs that would
+/// otherwise make one round trip per record. Unknown ids are skipped and
+/// listed in `missing`, so a retried purge succeeds. One receipt covers the
+/// whole batch.
+async fn delete_record_batch(
     State(state): State<SharedEngine>,
-    axum::extract::Path(id): axum::extract::Path<u32>,
-    Query(q): Query<crate::routes::graph::CollectionQuery>,
-) -> Result<Json<serde_json::Value>, Response> {
-    let engine = state.read().await;
-    let ns = engine
-        .resolve_collection(q.collection.as_deref())
+    axum::Extension(receipts): axum::Extension<Arc<valori_effect::ReceiptStore>>,
+    Json(payload): Json<DeleteBatchRequest>,
+) -> Result<Json<DeleteBatchResponse>, Response> {
+    use valori_kernel::snapshot::blake3::hash_state_blake3;
+    if payload.ids.len() > MAX_DELETE_BATCH {
+        return Err(EngineError::InvalidInput(format!(
+            "at most {MAX_DELETE_BATCH} ids per batch, got {}",
+            payload.ids.len()
+        ))
+        .into_response());
+    }
+    let mut engine = state.write().await;
+    let Some(ns) = engine.namespaces.resolve(payload.collection.as_deref()) else {
+        return Err((
+            StatusCode::NOT_FOUND,
+            Json(serde_json::json!({
+                "error": format!(
+                    "unknown collection '{}' — create it first with POST /v1/namespaces",
+                    payload.collection.as_deref().unwrap_or("default")
+                )
+            })),
+        )
+            .into_response());
+    };
+    engine
+        .check_version(payload.if_version)
         .map_err(|e| e.into_response())?;
-    let rec_id = valori_kernel::types::id::RecordId(id);
-    let rec = engine
-        .state
-        .get_record(rec_id)
-        .filter(|r| r.namespace_id == ns)
-        .ok_or_else(|| {
-            (
-                axum::http::StatusCode::NOT_FOUND,
-                axum::Json(serde_json::json!({"error": "record not found"})),
-            )
-                .into_response()
-        })?;
-    let vector: Vec<f32> = rec
-        .vector
-        .data
+    let state_before: String = hash_state_blake3(&engine.state)
         .iter()
-        .map(|s| valori_kernel::fxp::ops::to_f32(*s))
+        .map(|b| format!("{:02x}", b))
         .collect();
-    Ok(Json(serde_json::json!({
-        "id": id,
-        "vector": vector,
-        "metadata": rec.metadata.as_ref()
-            .and_then(|b| serde_json::from_slice::<serde_json::Value>(b).ok()),
-        "tag": rec.tag,
-    })))
+    let report = engine
+        .delete_batch_ns(&payload.ids, ns, payload.soft)
+        .map_err(|e| e.into_response())?;
+    let state_after: String = hash_state_blake3(&engine.state)
+        .iter()
+        .map(|b| format!("{:02x}", b))
+        .collect();
+    let state_version = engine.state_version();
+    drop(engine);
+    if !report.deleted.is_empty() {
+        use valori_planner::operation::{OperationInputs, OperationKind};
+        let inputs = OperationInputs::Delete {
+            collection: payload
+                .collection
+                .clone()
+                .unwrap_or_else(|| "default".into()),
+            shard_id: 0,
+            mode: if payload.soft { "soft" } else { "hard" }.into(),
+        };
+        crate::receipt_bridge::emit_write(
+            &receipts,
+            OperationKind::Delete,
+            &inputs,
+            ns,
+            0,
+            0,
+            false,
+            state_before,
+            state_after,
+        );
+    }
+    Ok(Json(DeleteBatchResponse {
+        deleted: report.deleted,
+        missing: report.missing,
+        state_version: Some(state_version),
+    }))
 }
 
-async fn update_record_metadata(
+fn tx_ref(r: TransactionRef) -> crate::engine::TxRef {
+    match r {
+        TransactionRef::Id(id) => crate::engine::TxRef::Id(id),
+        TransactionRef::ResultOf { result_of } => crate::engine::TxRef::Op(result_of),
+    }
+}
+
+fn tx_op(op: TransactionOp) -> crate::engine::TxOp {
+    use crate::engine::TxOp;
+    match op {
+        TransactionOp::Insert {
+            values,
+            metadata,
+            tag,
+        } => TxOp::Insert {
+            values,
+            metadata: metadata.and_then(|m| serde_json::to_vec(&m).ok()),
+            tag,
+        },
+        TransactionOp::CreateNode { kind, record } => TxOp::CreateNode {
+            kind,
+            record: record.map(tx_ref),
+        },
+        TransactionOp::CreateEdge { from, to, kind } => TxOp::CreateEdge {
+            from: tx_ref(from),
+            to: tx_ref(to),
+            kind,
+        },
+        TransactionOp::UpdateMetadata {
+            id,
+            metadata,
+            expected_version,
+        } => TxOp::UpdateMetadata {
+            id: tx_ref(id),
+            metadata: serde_json::to_vec(&metadata).ok(),
+            expected_version,
+        },
+        TransactionOp::Delete { id, soft } => TxOp::DeleteRecord {
+            id: tx_ref(id),
+            soft,
+        },
+        TransactionOp::DeleteNode { id } => TxOp::DeleteNode { id: tx_ref(id) },
+        TransactionOp::DeleteEdge { id } => TxOp::DeleteEdge { id: tx_ref(id) },
+        TransactionOp::SetMeta { key, value } => TxOp::SetMeta { key, value },
+    }
+}
+
+/// `POST /v1/transactions` — commit up to [`MAX_TRANSACTION_OPS`] inserts,
+/// graph edits, metadata updates and deletes of one collection as a single
+/// event batch: all of them or none. One receipt covers the transaction.
+async fn commit_transaction(
     State(state): State<SharedEngine>,
-    axum::extract::Path(id): axum::extract::Path<u32>,
-    Query(q): Query<crate::routes::graph::CollectionQuery>,
-    Json(body): Json<serde_json::Value>,
-) -> Result<Json<serde_json::Value>, Response> {
-    let mut engine = state.write().await;
-    let ns = engine
-        .resolve_collection(q.collection.as_deref())
-        .map_err(|e| e.into_response())?;
-    let rec_id = valori_kernel::types::id::RecordId(id);
-    if engine
-        .state
-        .get_record(rec_id)
-        .filter(|r| r.namespace_id == ns)
-        .is_none()
-    {
-        return Err((
-            axum::http::StatusCode::NOT_FOUND,
-            axum::Json(serde_json::json!({"error": "record not found"})),
-        )
-            .into_response());
+    axum::Extension(receipts): axum::Extension<Arc<valori_effect::ReceiptStore>>,
+    Json(payload): Json<TransactionRequest>,
+) -> Result<Json<TransactionResponse>, Response> {
+    let count = payload.operations.len();
+    if count > MAX_TRANSACTION_OPS {
+        return Err(EngineError::InvalidInput(format!(
+            "at most {MAX_TRANSACTION_OPS} operations per transaction, got {count}"
+        ))
+        .into_response());
     }
-    let metadata_bytes = serde_json::to_vec(&body).ok();
-    engine
-        .update_record_metadata(id, metadata_bytes, ns)
-        .map_err(|e| {
-            (
-                axum::http::StatusCode::INTERNAL_SERVER_ERROR,
-                axum::Json(serde_json::json!({"error": e.to_string()})),
+    let ops: Vec<_> = payload.operations.into_iter().map(tx_op).collect();
+    commit_ops(
+        &state,
+        &receipts,
+        &ops,
+        payload.collection.as_deref(),
+        payload.if_version,
+    )
+    .await
+    .map(Json)
+}
+
+fn unknown_collection(name: Option<&str>) -> Response {
+    (
+        StatusCode::NOT_FOUND,
+        Json(serde_json::json!({
+            "error": format!(
+                "unknown collection '{}' — create it first with POST /v1/namespaces",
+                name.unwrap_or("default")
             )
-                .into_response()
-        })?;
-    Ok(Json(serde_json::json!({ "ok": true, "id": id })))
+        })),
+    )
+        .into_response()
 }
 
-async fn snapshot_save(
-    State(state): State<SharedEngine>,
-    axum::Extension(caps): axum::Extension<Arc<valori_effect::capability::CapabilityRegistry>>,
-    axum::Extension(task_reg): axum::Extension<Arc<crate::runner::TaskRegistry>>,
-    Json(req): Json<SnapshotSaveRequest>,
-) -> Result<Json<SnapshotSaveResponse>, EngineError> {
-    use crate::runner::run_graph_inline;
+/// Commit `ops` as one transaction on `collection` and emit its receipt.
+/// Shared by `/v1/transactions` and session commits.
+async fn commit_ops(
+    state: &SharedEngine,
+    receipts: &valori_effect::ReceiptStore,
+    ops: &[crate::engine::TxOp],
+    collection: Option<&str>,
+    if_version: Option<u64>,
+) -> Result<TransactionResponse, Response> {
+    use valori_kernel::snapshot::blake3::hash_state_blake3;
+    let count = ops.len();
+    let mut engine = state.write().await;
+    let Some(ns) = engine.namespaces.resolve(collection) else {
+        return Err(unknown_collection(collection));
+    };
+    engine
+        .check_version(if_version)
+        .map_err(|e| e.into_response())?;
+    let state_before: String = hash_state_blake3(&engine.state)
+        .iter()
+        .map(|b| format!("{:02x}", b))
+        .collect();
+    let results = engine
+        .commit_transaction_ns(ops, ns)
+        .map_err(|e| e.into_response())?;
+    let state_after: String = hash_state_blake3(&engine.state)
+        .iter()
+        .map(|b| format!("{:02x}", b))
+        .collect();
+    let state_version = engine.state_version();
+    let height = engine
+        .event_committer()
+        .map(|c| c.journal().committed_height());
+    drop(engine);
+    if count > 0 {
+        use valori_planner::operation::{OperationInputs, OperationKind};
+        let inputs = OperationInputs::Transaction {
+            count: count as u32,
+            collection: collection.unwrap_or("default").to_string(),
+            shard_id: 0,
+        };
+        crate::receipt_bridge::emit_write(
+            receipts,
+            OperationKind::Transaction,
+            &inputs,
+            ns,
+            0,
+            0,
+            false,
+            state_before,
+            state_after,
+        );
+    }
+    Ok(TransactionResponse {
+        results,
+        state_version,
+        height,
+    })
+}
+
+fn session_error(e: SessionError) -> Response {
+    let status = match e {
+        SessionError::NotFound | SessionError::UnknownSavepoint(_) => StatusCode::NOT_FOUND,
+        SessionError::TooManySessions(_) => StatusCode::TOO_MANY_REQUESTS,
+    };
+    (status, Json(serde_json::json!({ "error": e.to_string() }))).into_response()
+}
+
+fn session_response(session: &crate::session::Session) -> SessionResponse {
+    SessionResponse {
+        session_id: session.id.clone(),
+        collection: session.collection.clone(),
+        operations: session.ops().len(),
+        savepoints: session
+            .savepoints()
+            .iter()
+            .map(|(name, operations)| SessionSavepoint {
+                name: name.clone(),
+                operations: *operations,
+            })
+            .collect(),
+    }
+}
+
+/// `POST /v1/sessions` — open a write session. Operations buffered in it
+/// are only committed by `POST /v1/sessions/:id/commit`.
+async fn open_session(
+    State(state): State<SharedEngine>,
+    axum::Extension(sessions): axum::Extension<Arc<SessionRegistry>>,
+    payload: Option<Json<SessionOpenRequest>>,
+) -> Result<(StatusCode, Json<SessionResponse>), Response> {
+    let collection = payload.and_then(|Json(p)| p.collection);
+    if state
+        .read()
+        .await
+        .namespaces
+        .resolve(collection.as_deref())
+        .is_none()
+    {
+        return Err(unknown_collection(collection.as_deref()));
+    }
+    let id = sessions.open(collection).map_err(session_error)?;
+    let session = sessions.lock(&id).await.map_err(session_error)?;
+    Ok((StatusCode::CREATED, Json(session_response(&session))))
+}
+
+async fn get_session(
+    axum::Extension(sessions): axum::Extension<Arc<SessionRegistry>>,
+    axum::extract::Path(id): axum::extract::Path<String>,
+) -> Result<Json<SessionResponse>, Response> {
+    let session = sessions.lock(&id).await.map_err(session_error)?;
+    Ok(Json(session_response(&session)))
+}
+
+/// `POST /v1/sessions/:id/operations` — buffer operations. The whole buffer
+/// is dry-run against the current state first, so an operation that could
+/// not commit is refused (and not buffered) right away.
+async fn append_session_operations(
+    State(state): State<SharedEngine>,
+    axum::Extension(sessions): axum::Extension<Arc<SessionRegistry>>,
+    axum::extract::Path(id): axum::extract::Path<String>,
+    Json(payload): Json<SessionOperationsRequest>,
+) -> Result<Json<SessionOperationsResponse>, Response> {
+    let mut session = sessions.lock(&id).await.map_err(session_error)?;
+    let buffered = session.ops().len();
+    if buffered + payload.operations.len() > MAX_TRANSACTION_OPS {
+        return Err(EngineError::InvalidInput(format!(
+            "at most {MAX_TRANSACTION_OPS} operations per session, {buffered} already buffered"
+        ))
+        .into_response());
+    }
+    let new: Vec<_> = payload.operations.into_iter().map(tx_op).collect();
+    let mut all = session.ops().to_vec();
+    all.extend(new.iter().cloned());
+    let engine = state.read().await;
+    let Some(ns) = engine.namespaces.resolve(session.collection.as_deref()) else {
+        return Err(unknown_collection(session.collection.as_deref()));
+    };
+    let mut results = engine
+        .validate_transaction_ns(&all, ns)
+        .map_err(|e| e.into_response())?;
+    drop(engine);
+    session.push(new);
+    Ok(Json(SessionOperationsResponse {
+        results: results.split_off(buffered),
+        operations: session.ops().len(),
+    }))
+}
+
+async fn create_session_savepoint(
+    axum::Extension(sessions): axum::Extension<Arc<SessionRegistry>>,
+    axum::extract::Path(id): axum::extract::Path<String>,
+    Json(payload): Json<SavepointRequest>,
+) -> Result<Json<SessionSavepoint>, Response> {
+    let mut session = sessions.lock(&id).await.map_err(session_error)?;
+    let operations = session.savepoint(&payload.name);
+    Ok(Json(SessionSavepoint {
+        name: payload.name,
+        operations,
+    }))
+}
+
+/// `POST /v1/sessions/:id/rollback` — discard the operations buffered after
+/// a savepoint, or all of them. Nothing was committed, so nothing is undone
+/// in the log.
+async fn rollback_session(
+    axum::Extension(sessions): axum::Extension<Arc<SessionRegistry>>,
+    axum::extract::Path(id): axum::extract::Path<String>,
+    payload: Option<Json<SessionRollbackRequest>>,
+) -> Result<Json<SessionRollbackResponse>, Response> {
+    let savepoint = payload.and_then(|Json(p)| p.savepoint);
+    let mut session = sessions.lock(&id).await.map_err(session_error)?;
+    let discarded = session
+        .rollback(savepoint.as_deref())
+        .map_err(session_error)?;
+    Ok(Json(SessionRollbackResponse {
+        discarded,
+        operations: session.ops().len(),
+    }))
+}
+
+/// `POST /v1/sessions/:id/commit` — commit the buffer as one transaction
+/// and end the session. A failed commit leaves the session open, so the
+/// client can roll back and retry.
+async fn commit_session(
+    State(state): State<SharedEngine>,
+    axum::Extension(receipts): axum::Extension<Arc<valori_effect::ReceiptStore>>,
+    axum::Extension(sessions): axum::Extension<Arc<SessionRegistry>>,
+    axum::extract::Path(id): axum::extract::Path<String>,
+    payload: Option<Json<SessionCommitRequest>>,
+) -> Result<Json<TransactionResponse>, Response> {
+    let if_version = payload.and_then(|Json(p)| p.if_version);
+    let mut session = sessions.lock(&id).await.map_err(session_error)?;
+    let response = commit_ops(
+        &state,
+        &receipts,
+        session.ops(),
+        session.collection.as_deref(),
+        if_version,
+    )
+    .await?;
+    sessions.close(&mut session);
+    Ok(Json(response))
+}
+
+/// `DELETE /v1/sessions/:id` — abandon the session and its buffer.
+async fn abandon_session(
+    axum::Extension(sessions): axum::Extension<Arc<SessionRegistry>>,
+    axum::extract::Path(id): axum::extract::Path<String>,
+) -> Result<Json<serde_json::Value>, Response> {
+    let mut session = sessions.lock(&id).await.map_err(session_error)?;
+    let discarded = session.ops().len();
+    sessions.close(&mut session);
+    Ok(Json(
+        serde_json::json!({ "ok": true, "discarded": discarded }),
+    ))
+}
+
+// ── Commit barriers (`/v1/tx/*`) ────────────────────────────────────────
+// The session machinery above, addressed by `tx_id` in the body, for ETL
+// jobs: stage any number of writes, then commit them as one event batch —
+// one log append and one fsync — or abort.
+
+/// `POST /v1/tx/begin` — open a staged-write transaction.
+async fn begin_tx(
+    state: State<SharedEngine>,
+    sessions: axum::Extension<Arc<SessionRegistry>>,
+    payload: Option<Json<SessionOpenRequest>>,
+) -> Result<(StatusCode, Json<TxBeginResponse>), Response> {
+    let (status, Json(session)) = open_session(state, sessions, payload).await?;
+    Ok((
+        status,
+        Json(TxBeginResponse {
+            tx_id: session.session_id,
+            collection: session.collection,
+        }),
+    ))
+}
+
+/// `POST /v1/tx/stage` — dry-run and stage writes; nothing is logged.
+async fn stage_tx(
+    state: State<SharedEngine>,
+    sessions: axum::Extension<Arc<SessionRegistry>>,
+    Json(payload): Json<TxStageRequest>,
+) -> Result<Json<SessionOperationsResponse>, Response> {
+    append_session_operations(
+        state,
+        sessions,
+        axum::extract::Path(payload.tx_id),
+        Json(SessionOperationsRequest {
+            operations: payload.operations,
+        }),
+    )
+    .await
+}
+
+/// `POST /v1/tx/commit` — the barrier: every staged write is committed in
+/// one batch and is durable when this returns.
+async fn commit_tx(
+    state: State<SharedEngine>,
+    receipts: axum::Extension<Arc<valori_effect::ReceiptStore>>,
+    sessions: axum::Extension<Arc<SessionRegistry>>,
+    Json(payload): Json<TxCommitRequest>,
+) -> Result<Json<TransactionResponse>, Response> {
+    commit_session(
+        state,
+        receipts,
+        sessions,
+        axum::extract::Path(payload.tx_id),
+        Some(Json(SessionCommitRequest {
+            if_version: payload.if_version,
+        })),
+    )
+    .await
+}
+
+/// `POST /v1/tx/abort` — drop the staged writes.
+async fn abort_tx(
+    sessions: axum::Extension<Arc<SessionRegistry>>,
+    Json(payload): Json<TxAbortRequest>,
+) -> Result<Json<serde_json::Value>, Response> {
+    abandon_session(sessions, axum::extract::Path(payload.tx_id)).await
+}
+
+async fn get_record_by_id(
+    State(state): State<SharedEngine>,
+    axum::extract::Path(id): axum::extract::Path<u32>,
+    Query(q): Query<crate::routes::graph::CollectionQuery>,
+) -> Result<Json<serde_json::Value>, Response> {
+    let engine = state.read().await;
+    let ns = engine
+        .resolve_collection(q.collection.as_deref())
+        .map_err(|e| e.into_response())?;
+    let rec_id = valori_kernel::types::id::RecordId(id);
+    let rec = engine
+        .state
+        .get_record(rec_id)
+        .filter(|r| r.namespace_id == ns)
+        .ok_or_else(|| {
+            (
+                axum::http::StatusCode::NOT_FOUND,
+                axum::Json(serde_json::json!({"error": "record not found"})),
+            )
+                .into_response()
+        })?;
+    let vector: Vec<f32> = engine
+        .state
+        .record_vector(rec)
+        .iter()
+        .map(|s| valori_kernel::fxp::ops::to_f32(*s))
+        .collect();
+    Ok(Json(serde_json::json!({
+        "id": id,
+        "vector": vector,
+        "metadata": rec.metadata.as_ref()
+            .and_then(|b| serde_json::from_slice::<serde_json::Value>(b).ok()),
+        "tag": rec.tag,
+        "version": rec.version,
+    })))
+}
+
+/// `GET /v1/records?where=<json>` — ids of the collection's records whose
+/// metadata matches the filter, ascending. Predicates on fields in
+/// `VALORI_METADATA_INDEX_FIELDS` are answered by the metadata index;
+/// `indexed` reports whether it narrowed this query.
+async fn list_records_where(
+    State(state): State<SharedEngine>,
+    Query(q): Query<crate::api::RecordsWhereQuery>,
+) -> Result<Json<serde_json::Value>, EngineError> {
+    let (filter, limit) = q.parse().map_err(EngineError::InvalidInput)?;
+    let engine = state.read().await;
+    let ns = engine.resolve_collection(q.collection.as_deref())?;
+    let in_collection = |id: u32| {
+        engine
+            .state
+            .get_record(valori_kernel::types::id::RecordId(id))
+            .is_some_and(|r| r.namespace_id == ns)
+    };
+    let ids = engine
+        .metadata
+        .record_ids_where(&filter, in_collection, limit);
+    Ok(Json(serde_json::json!({
+        "ids": ids,
+        "indexed": engine.metadata.candidates(&filter).is_some(),
+    })))
+}
+
+/// Host-side usage statistics for one record: search hits and insert time.
+async fn get_record_stats(
+    State(state): State<SharedEngine>,
+    axum::extract::Path(id): axum::extract::Path<u32>,
+    Query(q): Query<crate::routes::graph::CollectionQuery>,
+) -> Result<Json<serde_json::Value>, Response> {
+    let engine = state.read().await;
+    let ns = engine
+        .resolve_collection(q.collection.as_deref())
+        .map_err(|e| e.into_response())?;
+    if engine
+        .state
+        .get_record(valori_kernel::types::id::RecordId(id))
+        .filter(|r| r.namespace_id == ns)
+        .is_none()
+    {
+        return Err((
+            axum::http::StatusCode::NOT_FOUND,
+            axum::Json(serde_json::json!({"error": "record not found"})),
+        )
+            .into_response());
+    }
+    let access = engine.record_access_stats(id);
+    let last_hit = (access.last_hit_unix > 0).then_some(access.last_hit_unix);
+    Ok(Json(serde_json::json!({
+        "id": id,
+        "hits": access.hits,
+        "last_hit_unix": last_hit,
+        "last_hit_iso": last_hit.map(unix_to_iso8601),
+        "created_at_unix": engine.record_created_at(id),
+    })))
+}
+
+async fn update_record_metadata(
+    State(state): State<SharedEngine>,
+    axum::extract::Path(id): axum::extract::Path<u32>,
+    Query(q): Query<crate::routes::graph::RecordUpdateQuery>,
+    Json(body): Json<serde_json::Value>,
+) -> Result<Json<serde_json::Value>, Response> {
+    let mut engine = state.write().await;
+    let ns = engine
+        .resolve_collection(q.collection.as_deref())
+        .map_err(|e| e.into_response())?;
+    let rec_id = valori_kernel::types::id::RecordId(id);
+    if engine
+        .state
+        .get_record(rec_id)
+        .filter(|r| r.namespace_id == ns)
+        .is_none()
+    {
+        return Err((
+            axum::http::StatusCode::NOT_FOUND,
+            axum::Json(serde_json::json!({"error": "record not found"})),
+        )
+            .into_response());
+    }
+    let metadata_bytes = serde_json::to_vec(&body).ok();
+    // The write lock is held from the existence check through the commit,
+    // so the expected_version comparison cannot race another update.
+    let version = engine
+        .update_record_metadata(id, metadata_bytes, ns, q.expected_version)
+        .map_err(|e| match e {
+            EngineError::RecordVersionConflict { .. }
+            | EngineError::MetadataTooLarge { .. }
+            | EngineError::MetadataInvalid { .. } => e.into_response(),
+            e => (
+                axum::http::StatusCode::INTERNAL_SERVER_ERROR,
+                axum::Json(serde_json::json!({"error": e.to_string()})),
+            )
+                .into_response(),
+        })?;
+    Ok(Json(
+        serde_json::json!({ "ok": true, "id": id, "version": version }),
+    ))
+}
+
+async fn set_sparse_vector(
+    State(state): State<SharedEngine>,
+    axum::extract::Path(id): axum::extract::Path<u32>,
+    Query(q): Query<crate::routes::graph::CollectionQuery>,
+    Json(body): Json<crate::api::SparseVectorRequest>,
+) -> Result<Json<serde_json::Value>, EngineError> {
+    let mut engine = state.write().await;
+    let ns = engine.resolve_collection(q.collection.as_deref())?;
+    engine.set_sparse_vector(id, &body.indices, &body.values, ns)?;
+    let nnz = engine
+        .state
+        .sparse_vector(valori_kernel::types::id::RecordId(id))
+        .map_or(0, |v| v.nnz());
+    Ok(Json(
+        serde_json::json!({ "ok": true, "id": id, "nnz": nnz }),
+    ))
+}
+
+async fn search_sparse(
+    State(state): State<SharedEngine>,
+    Json(payload): Json<crate::api::SparseSearchRequest>,
+) -> Result<Json<SearchResponse>, EngineError> {
+    let engine = state.read().await;
+    let ns = engine.resolve_collection(payload.collection.as_deref())?;
+    let hits = engine.search_sparse_ns(&payload.indices, &payload.values, payload.k, ns)?;
+    Ok(Json(SearchResponse::simple(
+        hits.into_iter()
+            .map(|(id, score)| SearchHit {
+                id,
+                score,
+                decay_factor: None,
+                age_secs: None,
+            })
+            .collect(),
+    )))
+}
+
+async fn snapshot_save(
+    State(state): State<SharedEngine>,
+    axum::Extension(caps): axum::Extension<Arc<valori_effect::capability::CapabilityRegistry>>,
+    axum::Extension(task_reg): axum::Extension<Arc<crate::runner::TaskRegistry>>,
+    Json(req): Json<SnapshotSaveRequest>,
+) -> Result<Json<SnapshotSaveResponse>, EngineError> {
+    use crate::runner::run_graph_inline;
     use valori_planner::context::{
         CapabilitySet, PlannerFingerprint, PlanningContext, PlanningContextHash,
     };
@@ -593,6 +1480,33 @@ async fn snapshot_save(
         compute_operation_hash, ExecutionPolicy, OperationInputs, OperationKind,
     };
 
+    if let Some(label) = req.label {
+        if req.path.is_some() {
+            return Err(EngineError::InvalidInput(
+                "give either 'path' or 'label', not both".into(),
+            ));
+        }
+        let (dir, data, height, hash) = {
+            let engine = state.read().await;
+            (
+                catalog_dir(&engine)?,
+                engine.snapshot()?,
+                engine
+                    .event_committer()
+                    .map_or(0, |c| c.journal().committed_height()),
+                engine.state_hash_hex(),
+            )
+        };
+        let entry =
+            crate::persistence::SnapshotManager::add(&dir, &data, height, hash, Some(label))
+                .map_err(|e| EngineError::InvalidInput(format!("snapshot catalog: {e}")))?;
+        return Ok(Json(SnapshotSaveResponse {
+            success: true,
+            path: entry.file,
+            id: Some(entry.id),
+        }));
+    }
+
     // Validate path under a short read lock, then release.
     let (validated_path, shard_count) = {
         let engine = state.read().await;
@@ -666,26 +1580,162 @@ async fn snapshot_save(
     Ok(Json(SnapshotSaveResponse {
         success: true,
         path: filename,
+        id: None,
     }))
 }
 
-async fn snapshot_restore(
-    State(state): State<SharedEngine>,
-    Json(req): Json<SnapshotRestoreRequest>,
-) -> Result<Json<SnapshotRestoreResponse>, EngineError> {
-    let mut engine = state.write().await;
-    // Validate path against configured snapshot directory.
-    let allowed = engine.snapshot_path.as_deref().and_then(|p| p.parent());
-    let path = safe_path(&req.path, allowed)?;
-    if !path.exists() {
-        return Err(EngineError::InvalidInput(format!(
-            "snapshot not found: {}",
-            path.display()
-        )));
-    }
-    let data = tokio::fs::read(&path)
-        .await
-        .map_err(|e| EngineError::InvalidInput(e.to_string()))?;
+/// Snapshot catalog directory: `snapshots/` next to `VALORI_SNAPSHOT_PATH`.
+fn catalog_dir(engine: &Engine) -> Result<std::path::PathBuf, EngineError> {
+    engine
+        .snapshot_path
+        .as_deref()
+        .map(crate::persistence::SnapshotManager::catalog_dir)
+        .ok_or_else(|| EngineError::InvalidInput("No snapshot path configured".into()))
+}
+
+#[derive(Deserialize)]
+struct KernelStatsQuery {
+    #[serde(default)]
+    slots: bool,
+}
+
+#[derive(Serialize)]
+struct KernelStatsResponse {
+    #[serde(flatten)]
+    kernel: valori_kernel::state::stats::KernelStats,
+    committed_height: Option<u64>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    slot_maps: Option<SlotMaps>,
+}
+
+#[derive(Serialize)]
+struct SlotMaps {
+    records: SlotMapView,
+    nodes: SlotMapView,
+    edges: SlotMapView,
+}
+
+/// A [`SlotMap`](valori_kernel::state::stats::SlotMap) as hex: bit `i % 8`
+/// of byte `i / 8` is set when slot `i` is live.
+#[derive(Serialize)]
+struct SlotMapView {
+    slots: u32,
+    live: u32,
+    bitmap: String,
+}
+
+impl From<valori_kernel::state::stats::SlotMap> for SlotMapView {
+    fn from(map: valori_kernel::state::stats::SlotMap) -> Self {
+        Self {
+            slots: map.slots,
+            live: map.live(),
+            bitmap: map.to_bytes().iter().map(|b| format!("{b:02x}")).collect(),
+        }
+    }
+}
+
+/// `GET /v1/stats` — record/node/edge counts, capacity and state version
+/// for dashboards; `?slots=true` adds the live-slot bitmap of each pool.
+async fn kernel_stats(
+    State(state): State<SharedEngine>,
+    Query(q): Query<KernelStatsQuery>,
+) -> Json<KernelStatsResponse> {
+    let engine = state.read().await;
+    let kernel = &engine.state;
+    Json(KernelStatsResponse {
+        kernel: kernel.stats(),
+        committed_height: engine
+            .event_committer()
+            .map(|c| c.journal().committed_height()),
+        slot_maps: q.slots.then(|| SlotMaps {
+            records: kernel.record_slot_map().into(),
+            nodes: kernel.node_slot_map().into(),
+            edges: kernel.edge_slot_map().into(),
+        }),
+    })
+}
+
+#[derive(Serialize)]
+struct StorageStatsResponse {
+    #[serde(flatten)]
+    engine: valori_engine::StorageStats,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    snapshot_catalog: Option<SnapshotCatalogUsage>,
+}
+
+#[derive(Serialize)]
+struct SnapshotCatalogUsage {
+    snapshots: usize,
+    bytes: u64,
+}
+
+/// `GET /v1/stats/storage` — disk usage (event log, snapshots, WAL, metadata)
+/// and pool fill against capacity, to see a `CapacityExceeded` coming.
+async fn storage_stats(
+    State(state): State<SharedEngine>,
+) -> Result<Json<StorageStatsResponse>, EngineError> {
+    let engine = state.read().await;
+    let snapshot_catalog = match engine.snapshot_path.as_deref() {
+        Some(_) => {
+            let entries = crate::persistence::SnapshotManager::list(&catalog_dir(&engine)?)
+                .map_err(|e| EngineError::InvalidInput(format!("snapshot catalog: {e}")))?;
+            Some(SnapshotCatalogUsage {
+                snapshots: entries.len(),
+                bytes: entries.iter().map(|e| e.size_bytes).sum(),
+            })
+        }
+        None => None,
+    };
+    Ok(Json(StorageStatsResponse {
+        engine: engine.storage_stats(),
+        snapshot_catalog,
+    }))
+}
+
+/// `GET /v1/snapshot/list` — restore points in the snapshot catalog, oldest
+/// first.
+async fn snapshot_list(
+    State(state): State<SharedEngine>,
+) -> Result<Json<SnapshotListResponse>, EngineError> {
+    let dir = catalog_dir(&*state.read().await)?;
+    let snapshots = crate::persistence::SnapshotManager::list(&dir)
+        .map_err(|e| EngineError::InvalidInput(format!("snapshot catalog: {e}")))?;
+    Ok(Json(SnapshotListResponse { snapshots }))
+}
+
+async fn snapshot_restore(
+    State(state): State<SharedEngine>,
+    Json(req): Json<SnapshotRestoreRequest>,
+) -> Result<Json<SnapshotRestoreResponse>, EngineError> {
+    let mut engine = state.write().await;
+    let raw_path = match (req.path, req.id) {
+        (Some(path), None) => path,
+        (None, Some(id)) => {
+            let dir = catalog_dir(&engine)?;
+            let (_, data) = crate::persistence::SnapshotManager::load(&dir, id)
+                .map_err(|e| EngineError::InvalidInput(format!("snapshot catalog: {e}")))?
+                .ok_or_else(|| EngineError::InvalidInput(format!("no snapshot with id {id}")))?;
+            engine.restore(&data)?;
+            return Ok(Json(SnapshotRestoreResponse { success: true }));
+        }
+        _ => {
+            return Err(EngineError::InvalidInput(
+                "give exactly one of 'path' or 'id'".into(),
+            ))
+        }
+    };
+    // Validate path against configured snapshot directory.
+    let allowed = engine.snapshot_path.as_deref().and_then(|p| p.parent());
+    let path = safe_path(&raw_path, allowed)?;
+    if !path.exists() {
+        return Err(EngineError::InvalidInput(format!(
+            "snapshot not found: {}",
+            path.display()
+        )));
+    }
+    let data = tokio::fs::read(&path)
+        .await
+        .map_err(|e| EngineError::InvalidInput(e.to_string()))?;
     engine.restore(&data)?;
     Ok(Json(SnapshotRestoreResponse { success: true }))
 }
@@ -707,6 +1757,14 @@ impl crate::routes::meta::MetaOps for SharedEngine {
     async fn get_meta(&self, target_id: &str) -> Option<serde_json::Value> {
         self.read().await.metadata.get(target_id)
     }
+
+    async fn delete_meta(&self, target_id: String) -> Result<(), Response> {
+        self.write()
+            .await
+            .delete_meta_audited(target_id)
+            .map(|_| ())
+            .map_err(|e| e.into_response())
+    }
 }
 
 /// Standalone impl of the shared memory domain primitives.
@@ -735,31 +1793,50 @@ impl crate::routes::memory::MemoryOps for SharedEngine {
             .iter()
             .map(|b| format!("{:02x}", b))
             .collect();
-        let record_id = engine
-            .insert_record_from_f32_ns(&req.vector, ns)
-            .map_err(|e| EngineError::from(e).into_response())?;
-
-        let doc_node_id = if let Some(existing) = req.attach_to_document_node {
-            existing
-        } else {
-            engine
-                .create_node_for_record(None, NodeKind::Document as u8, ns)
-                .map_err(|e| EngineError::from(e).into_response())?
+        // One transaction, so a crash cannot leave a record without its
+        // nodes, edge or metadata.
+        use crate::engine::{TxOp, TxRef};
+        let mut ops = vec![TxOp::Insert {
+            values: req.vector.clone(),
+            metadata: None,
+            tag: 0,
+        }];
+        let doc = match req.attach_to_document_node {
+            Some(existing) => TxRef::Id(existing),
+            None => {
+                ops.push(TxOp::CreateNode {
+                    kind: NodeKind::Document as u8,
+                    record: None,
+                });
+                TxRef::Op(1)
+            }
         };
-
-        let chunk_node_id = engine
-            .create_node_for_record(Some(record_id), NodeKind::Chunk as u8, ns)
-            .map_err(|e| EngineError::from(e).into_response())?;
-        engine
-            .create_edge(doc_node_id, chunk_node_id, EdgeKind::ParentOf as u8)
-            .map_err(|e| EngineError::from(e).into_response())?;
-
+        let chunk_op = ops.len();
+        ops.push(TxOp::CreateNode {
+            kind: NodeKind::Chunk as u8,
+            record: Some(TxRef::Op(0)),
+        });
+        ops.push(TxOp::CreateEdge {
+            from: doc,
+            to: TxRef::Op(chunk_op),
+            kind: EdgeKind::ParentOf as u8,
+        });
+        let record_id = engine.next_record_id().0;
         let memory_id = format!("rec:{}", record_id);
         if let Some(meta) = &req.metadata {
-            engine
-                .set_meta_audited(memory_id.clone(), meta.clone())
-                .map_err(|e| EngineError::from(e).into_response())?;
+            ops.push(TxOp::SetMeta {
+                key: memory_id.clone(),
+                value: meta.clone(),
+            });
         }
+        let results = engine
+            .commit_transaction_ns(&ops, ns)
+            .map_err(|e| e.into_response())?;
+        let chunk_node_id = results[chunk_op].unwrap_or_default();
+        let doc_node_id = match doc {
+            TxRef::Id(id) => id,
+            TxRef::Op(i) => results[i].unwrap_or_default(),
+        };
         let state_after: String = hash_state_blake3(&engine.state)
             .iter()
             .map(|b| format!("{:02x}", b))
@@ -852,18 +1929,12 @@ impl crate::routes::memory::MemoryOps for SharedEngine {
                     created_at: engine.record_created_at(id),
                 })
                 .collect();
+            let indexed = mf.and_then(|f| engine.metadata.candidates(f));
             valori_search::decay_rerank(candidates, now, half_life, base_k)
                 .into_iter()
                 .filter(|h| match mf {
                     None => true,
-                    Some(f) => {
-                        let key = format!("rec:{}", h.id);
-                        engine
-                            .metadata
-                            .get(&key)
-                            .map(|m| valori_search::matches_metadata_filter(&m, f))
-                            .unwrap_or(false)
-                    }
+                    Some(f) => metadata_matches(&engine.metadata, indexed.as_ref(), f, h.id),
                 })
                 .take(req.k)
                 .map(|h| {
@@ -1011,6 +2082,30 @@ impl crate::routes::memory::MemoryOps for SharedEngine {
             state_after,
         })
     }
+
+    async fn dedup_check(
+        &self,
+        ns: u16,
+        req: &MemoryDedupCheckRequest,
+    ) -> Result<Vec<MemoryDedupMatch>, Response> {
+        let engine = self.read().await;
+        let hits = engine
+            .search_range_ns(&req.vector, req.max_distance, req.limit, ns)
+            .map_err(|e| e.into_response())?;
+        Ok(hits
+            .into_iter()
+            .map(|(record_id, distance)| {
+                let memory_id = format!("rec:{record_id}");
+                let metadata = engine.metadata.get(&memory_id);
+                MemoryDedupMatch {
+                    memory_id,
+                    record_id,
+                    distance,
+                    metadata,
+                }
+            })
+            .collect())
+    }
 }
 
 async fn meta_set(
@@ -1020,6 +2115,13 @@ async fn meta_set(
     crate::routes::meta::meta_set(&state, payload).await
 }
 
+async fn meta_delete(
+    State(state): State<SharedEngine>,
+    Json(payload): Json<crate::api::MetadataDeleteRequest>,
+) -> Result<Json<crate::api::MetadataDeleteResponse>, Response> {
+    crate::routes::meta::meta_delete(&state, payload).await
+}
+
 async fn meta_get(
     State(state): State<SharedEngine>,
     Query(payload): Query<MetadataGetRequest>,
@@ -1032,6 +2134,7 @@ async fn insert_record(
     axum::Extension(receipts): axum::Extension<Arc<valori_effect::ReceiptStore>>,
     axum::Extension(caps): axum::Extension<Arc<valori_effect::capability::CapabilityRegistry>>,
     axum::Extension(task_reg): axum::Extension<Arc<crate::runner::TaskRegistry>>,
+    Extension(runtime): Extension<Arc<RuntimeConfig>>,
     Json(payload): Json<InsertRecordRequest>,
 ) -> Result<Json<InsertRecordResponse>, EngineError> {
     use crate::runner::run_graph_inline;
@@ -1045,10 +2148,19 @@ async fn insert_record(
         compute_operation_hash, ExecutionPolicy, OperationInputs, OperationKind,
     };
 
+    let mut timer = QueryTimer::start("insert", runtime.slow_query_threshold());
+    timer.params = QueryParams {
+        dim: payload.values.len(),
+        collection: payload.collection.clone(),
+        ..QueryParams::default()
+    };
+
     // Resolve namespace under a short read lock (no write needed yet — insert
     // goes through the effect bus / EngineKernelCapability below).
     let (ns, old_root, state_before, shard_count) = {
+        let waited = std::time::Instant::now();
         let eng = state.read().await;
+        timer.waited(waited);
         let ns = eng.resolve_collection(payload.collection.as_deref())?;
         let or: [u8; 32] = hash_state_blake3(&eng.state);
         let sb = or.iter().map(|b| format!("{:02x}", b)).collect::<String>();
@@ -1074,8 +2186,10 @@ async fn insert_record(
         "values": payload.values,
         "text": payload.text,
         "metadata": null,
-        "tag": 0u8,
+        "tag": payload.tag.unwrap_or(0),
         "request_id": null,
+        "if_version": payload.if_version,
+        "on_duplicate": payload.on_duplicate,
     }))
     .unwrap_or_default();
 
@@ -1124,20 +2238,31 @@ async fn insert_record(
             valori_effect::error::EffectError::Capacity(_) => {
                 EngineError::Kernel(valori_kernel::error::KernelError::CapacityExceeded)
             }
+            valori_effect::error::EffectError::VersionConflict { expected, current } => {
+                EngineError::VersionConflict { expected, current }
+            }
+            valori_effect::error::EffectError::DuplicateVector { existing } => {
+                EngineError::DuplicateVector { existing }
+            }
             valori_effect::error::EffectError::Dispatch(msg)
             | valori_effect::error::EffectError::TaskFailed(msg) => EngineError::InvalidInput(msg),
             other => EngineError::Unknown(other.to_string()),
         })?;
 
-    let record_id = outputs
-        .into_iter()
-        .next()
-        .flatten()
-        .and_then(|o| o.json.get("record_id").and_then(|v| v.as_u64()))
-        .unwrap_or(0) as u32;
+    let output = outputs.into_iter().next().flatten();
+    let field = |name: &str| {
+        output
+            .as_ref()
+            .and_then(|o| o.json.get(name).and_then(|v| v.as_u64()))
+    };
+    let record_id = field("record_id").unwrap_or(0) as u32;
+    let state_version = field("state_version");
+    let duplicate_of = field("duplicate_of").map(|id| id as u32);
 
     let (new_root, state_after, sequence) = {
+        let waited = std::time::Instant::now();
         let eng = state.read().await;
+        timer.waited(waited);
         let nr: [u8; 32] = hash_state_blake3(&eng.state);
         let sa = nr.iter().map(|b| format!("{:02x}", b)).collect::<String>();
         let seq = eng
@@ -1179,17 +2304,30 @@ async fn insert_record(
     Ok(Json(InsertRecordResponse {
         id: record_id,
         receipt: receipt.into(),
+        state_version,
+        duplicate_of,
     }))
 }
 
 async fn batch_insert(
     State(state): State<SharedEngine>,
     axum::Extension(receipts): axum::Extension<Arc<valori_effect::ReceiptStore>>,
+    Extension(runtime): Extension<Arc<RuntimeConfig>>,
     Json(payload): Json<BatchInsertRequest>,
 ) -> Result<Json<BatchInsertResponse>, EngineError> {
     use valori_kernel::snapshot::blake3::hash_state_blake3;
+    let mut timer = QueryTimer::start("batch_insert", runtime.slow_query_threshold());
+    timer.params = QueryParams {
+        dim: payload.batch.first().map_or(0, Vec::len),
+        k: Some(payload.batch.len()),
+        collection: payload.collection.clone(),
+        ..QueryParams::default()
+    };
+    let waited = std::time::Instant::now();
     let mut engine = state.write().await;
+    timer.waited(waited);
     let ns = engine.resolve_collection(payload.collection.as_deref())?;
+    engine.check_version(payload.if_version)?;
     let state_before: String = hash_state_blake3(&engine.state)
         .iter()
         .map(|b| format!("{:02x}", b))
@@ -1218,24 +2356,27 @@ async fn batch_insert(
                 })
                 .collect()
         });
-    let ids = engine.insert_batch_ns(
+    let inserted = engine.insert_batch_dedup_ns(
         &payload.batch,
         meta_bytes.as_deref(),
         ns,
         parsed_request_ids.as_deref(),
+        payload.on_duplicate.unwrap_or_default(),
     )?;
     // register text for BM25 reranking — one text string per vector
     if let Some(ref texts) = payload.texts {
-        for (id, text) in ids.iter().zip(texts.iter()) {
-            if let Some(t) = text {
-                engine.reranker_insert(*id, t);
+        for (item, text) in inserted.iter().zip(texts.iter()) {
+            if let Some(t) = text.as_ref().filter(|_| item.inserted()) {
+                engine.reranker_insert(item.id, t);
             }
         }
     }
+    let ids: Vec<u32> = inserted.iter().map(|item| item.id).collect();
     let state_after: String = hash_state_blake3(&engine.state)
         .iter()
         .map(|b| format!("{:02x}", b))
         .collect();
+    let state_version = engine.state_version();
     drop(engine);
     {
         use valori_planner::operation::{OperationInputs, OperationKind};
@@ -1259,20 +2400,43 @@ async fn batch_insert(
             state_after,
         );
     }
-    Ok(Json(BatchInsertResponse { ids }))
+    Ok(Json(BatchInsertResponse {
+        ids,
+        state_version: Some(state_version),
+        duplicate_of: payload
+            .on_duplicate
+            .map(|_| inserted.iter().map(|item| item.duplicate_of).collect()),
+    }))
 }
 
 async fn search(
     State(state): State<SharedEngine>,
     axum::Extension(receipts): axum::Extension<Arc<valori_effect::ReceiptStore>>,
+    Extension(runtime): Extension<Arc<RuntimeConfig>>,
     Json(payload): Json<SearchRequest>,
 ) -> Result<Json<SearchResponse>, EngineError> {
     use valori_kernel::snapshot::blake3::hash_state_blake3;
 
+    let mut timer = QueryTimer::start("search", runtime.slow_query_threshold());
+    timer.params = QueryParams {
+        dim: payload.query.len(),
+        k: Some(payload.k),
+        index: payload.index.clone(),
+        collection: payload.collection.clone(),
+        filter: payload
+            .metadata_filter
+            .as_ref()
+            .and_then(|f| serde_json::to_string(f).ok()),
+        ..QueryParams::default()
+    };
+    let deadline = runtime.deadline(std::time::Instant::now(), payload.timeout_ms);
     if payload.as_of.is_some() || payload.as_of_log_index.is_some() {
-        return search_as_of(state, payload).await;
+        return search_as_of(state, payload, deadline).await;
     }
-    let engine = state.read().await;
+    let waited = std::time::Instant::now();
+    let engine = read_until(&state, deadline).await?;
+    timer.waited(waited);
+    timer.params.ef = Some(engine.hnsw_config.ef_search);
     let state_hash: String = hash_state_blake3(&engine.state)
         .iter()
         .map(|b| format!("{:02x}", b))
@@ -1286,10 +2450,11 @@ async fn search(
         .or(engine.decay_half_life_secs)
         .unwrap_or(0);
 
-    // When metadata_filter is set, over-fetch a wider pool so post-filtering
-    // has enough candidates to fill k results.
+    // When metadata_filter or tag_filter is set, over-fetch a wider pool so
+    // post-filtering has enough candidates to fill k results.
     let mf = payload.metadata_filter.as_ref();
-    let base_k = if mf.is_some() {
+    let tf = payload.effective_tag_filter();
+    let base_k = if mf.is_some() || tf.is_some() {
         payload.k.saturating_mul(10).max(100).min(5000)
     } else {
         payload.k
@@ -1303,12 +2468,17 @@ async fn search(
         } else {
             base_k
         };
-        let hits = if ns == 0 {
-            engine.search_l2(&payload.query, fetch_k)?
-        } else {
-            engine.search_l2_ns(&payload.query, fetch_k, ns)?
-        };
-        let filtered = apply_metadata_filter(hits.into_iter(), mf, &engine.metadata, payload.k);
+        let hits = engine.search_l2_index_until(
+            &payload.query,
+            fetch_k,
+            ns,
+            payload.index.as_deref(),
+            deadline,
+        )?;
+        let hits = hits
+            .into_iter()
+            .filter(|(id, _)| tag_matches(&engine.state, tf, *id));
+        let filtered = apply_metadata_filter(hits, mf, &engine.metadata, payload.k);
         let final_hits = if use_rerank {
             let query_text = payload.query_text.as_deref().unwrap_or("");
             let candidates: Vec<(u64, f32)> =
@@ -1366,11 +2536,13 @@ async fn search(
     // Decay path: over-fetch a bounded pool, re-rank by decayed distance,
     // then trim to k. This lets a fresh near-match overtake a stale better one.
     let pool = base_k.saturating_mul(4).max(50).min(5000);
-    let raw = if ns == 0 {
-        engine.search_l2(&payload.query, pool)?
-    } else {
-        engine.search_l2_ns(&payload.query, pool, ns)?
-    };
+    let raw = engine.search_l2_index_until(
+        &payload.query,
+        pool,
+        ns,
+        payload.index.as_deref(),
+        deadline,
+    )?;
     let now = std::time::SystemTime::now()
         .duration_since(std::time::UNIX_EPOCH)
         .map(|d| d.as_secs())
@@ -1384,18 +2556,13 @@ async fn search(
         })
         .collect();
     let decayed = valori_search::decay_rerank(candidates, now, half_life, pool);
+    let indexed = mf.and_then(|f| engine.metadata.candidates(f));
     let results: Vec<SearchHit> = decayed
         .into_iter()
-        .filter(|h| {
-            if let Some(f) = mf {
-                let key = format!("rec:{}", h.id);
-                match engine.metadata.get(&key) {
-                    Some(meta) => valori_search::matches_metadata_filter(&meta, f),
-                    None => false,
-                }
-            } else {
-                true
-            }
+        .filter(|h| tag_matches(&engine.state, tf, h.id))
+        .filter(|h| match mf {
+            Some(f) => metadata_matches(&engine.metadata, indexed.as_ref(), f, h.id),
+            None => true,
         })
         .take(payload.k)
         .map(|h| SearchHit {
@@ -1433,11 +2600,26 @@ async fn search(
     Ok(Json(SearchResponse::simple(results)))
 }
 
+/// Take the engine read lock, giving up with `DeadlineExceeded` if it is
+/// not free before `deadline`.
+async fn read_until(
+    state: &SharedEngine,
+    deadline: Option<std::time::Instant>,
+) -> Result<tokio::sync::RwLockReadGuard<'_, Engine>, EngineError> {
+    match deadline {
+        Some(d) => tokio::time::timeout_at(d.into(), state.read())
+            .await
+            .map_err(|_| EngineError::DeadlineExceeded),
+        None => Ok(state.read().await),
+    }
+}
+
 /// Point-in-time search: replay committed events up to the target index/timestamp,
 /// run the search on the replayed state, and return the results with a BLAKE3 proof.
 async fn search_as_of(
     state: SharedEngine,
     payload: SearchRequest,
+    deadline: Option<std::time::Instant>,
 ) -> Result<Json<SearchResponse>, EngineError> {
     use valori_kernel::fxp::qformat::SCALE;
     use valori_kernel::index::SearchResult;
@@ -1446,7 +2628,8 @@ async fn search_as_of(
     use valori_kernel::types::scalar::FxpScalar;
     use valori_kernel::types::vector::FxpVector;
 
-    let engine = state.read().await;
+    let engine = read_until(&state, deadline).await?;
+    let expired = || deadline.is_some_and(|d| std::time::Instant::now() >= d);
 
     let committer = engine.event_committer().ok_or_else(|| {
         EngineError::InvalidInput(
@@ -1491,7 +2674,10 @@ async fn search_as_of(
 
     // Replay events[0..=target_idx] into a fresh kernel.
     let mut replay = KernelState::new();
-    for event in &events[0..=target_idx] {
+    for (i, event) in events[0..=target_idx].iter().enumerate() {
+        if i % valori_kernel::index::CANCEL_CHECK_INTERVAL == 0 && expired() {
+            return Err(EngineError::DeadlineExceeded);
+        }
         let _ = replay.apply_event(event);
     }
 
@@ -1501,7 +2687,7 @@ async fn search_as_of(
 
     // Convert f32 query to Q16.16 FxpVector.
     for &v in &payload.query {
-        if v > 32767.99 || v < -32768.0 {
+        if !valori_protocol::fxp::in_range(v) {
             return Err(EngineError::InvalidInput(
                 "query values must be in [-32768.0, 32767.99]".into(),
             ));
@@ -1516,13 +2702,20 @@ async fn search_as_of(
 
     let k = payload.k;
     let mut results_buf = vec![SearchResult::default(); k];
+    let tf = payload.effective_tag_filter();
     let found = if ns == 0 {
-        replay.search_l2(&fxp_query, &mut results_buf, None)
+        if expired() {
+            return Err(EngineError::DeadlineExceeded);
+        }
+        replay.search_l2(&fxp_query, &mut results_buf, tf)
     } else {
-        replay.search_l2_ns(&fxp_query, &mut results_buf, ns)
+        replay
+            .search_l2_ns_cancellable(&fxp_query, &mut results_buf, ns, &expired)
+            .ok_or(EngineError::DeadlineExceeded)?
     };
     let results: Vec<SearchHit> = results_buf[..found]
         .iter()
+        .filter(|r| tag_matches(&replay, tf, r.id.0))
         .map(|r| {
             let score = r.score as f32 / (SCALE as f32 * SCALE as f32);
             // Decay is a "now"-relative re-rank; it is intentionally NOT applied to
@@ -1892,9 +3085,44 @@ async fn graphrag(
     Ok(Json(result))
 }
 
-async fn snapshot(State(state): State<SharedEngine>) -> Result<Vec<u8>, EngineError> {
-    let engine = state.read().await;
-    engine.snapshot()
+/// `GET /v1/snapshot/download` — the current state as a snapshot container,
+/// spooled next to the snapshot catalog (or in the system temp dir) and
+/// streamed from there. See [`crate::snapshot_stream`]. The state hash and
+/// committed height it was taken at ride in headers, so a bootstrapping
+/// follower can verify it before restoring.
+async fn snapshot(State(state): State<SharedEngine>) -> Result<Response, EngineError> {
+    use crate::snapshot_stream::{HEIGHT_HEADER, STATE_HASH_HEADER};
+
+    let spool_failed = |e: std::io::Error| {
+        tracing::error!("snapshot download spool failed: {e}");
+        EngineError::Internal
+    };
+    let (spool, state_hash, height) = {
+        let engine = state.read().await;
+        let dir = catalog_dir(&engine).unwrap_or_else(|_| std::env::temp_dir());
+        let bytes = engine.snapshot()?;
+        let height = engine
+            .event_committer()
+            .map(|c| c.journal().committed_height());
+        (
+            SpooledSnapshot::write(&dir, &bytes).map_err(spool_failed)?,
+            engine.state_hash_hex(),
+            height,
+        )
+    };
+    let mut resp = spool
+        .into_response("snapshot.snap")
+        .await
+        .map_err(spool_failed)?;
+    let headers = resp.headers_mut();
+    headers.insert(
+        STATE_HASH_HEADER,
+        HeaderValue::from_str(&state_hash).expect("hex is a valid header value"),
+    );
+    if let Some(height) = height {
+        headers.insert(HEIGHT_HEADER, HeaderValue::from(height));
+    }
+    Ok(resp)
 }
 
 async fn restore(
@@ -1906,6 +3134,102 @@ async fn restore(
     Ok(())
 }
 
+/// Request body limit for every route but `/v1/admin/import`. Snapshot
+/// upload (binary) legitimately needs more room; everything else uses JSON
+/// that should never exceed 32 MB.
+const MAX_REQUEST_BODY: usize = 32 * 1024 * 1024;
+
+/// Largest chunk `PUT /v1/snapshot/upload/:id` accepts. Below
+/// [`MAX_REQUEST_BODY`].
+const MAX_UPLOAD_CHUNK: usize = 16 * 1024 * 1024;
+
+fn upload_error(e: crate::persistence::UploadError) -> Response {
+    use crate::persistence::UploadError;
+    let status = match &e {
+        UploadError::NotFound => StatusCode::NOT_FOUND,
+        UploadError::Offset { .. } | UploadError::Incomplete { .. } => StatusCode::CONFLICT,
+        UploadError::ChunkHash | UploadError::SnapshotHash => StatusCode::UNPROCESSABLE_ENTITY,
+        UploadError::TooLarge => StatusCode::PAYLOAD_TOO_LARGE,
+        UploadError::Io(_) => StatusCode::INTERNAL_SERVER_ERROR,
+    };
+    let mut body = serde_json::json!({"error": e.to_string()});
+    if let UploadError::Offset { received } | UploadError::Incomplete { received, .. } = e {
+        body["received"] = received.into();
+    }
+    (status, Json(body)).into_response()
+}
+
+async fn upload_dir(state: &SharedEngine) -> Result<std::path::PathBuf, Response> {
+    catalog_dir(&*state.read().await).map_err(IntoResponse::into_response)
+}
+
+/// `POST /v1/snapshot/upload/init` — start a resumable multipart snapshot
+/// upload. Chunks are staged in the snapshot catalog directory.
+async fn snapshot_upload_init(
+    State(state): State<SharedEngine>,
+    Json(req): Json<SnapshotUploadInitRequest>,
+) -> Result<Json<crate::persistence::UploadSession>, Response> {
+    if req.hash.len() != 64 || !req.hash.bytes().all(|b| b.is_ascii_hexdigit()) {
+        return Err(
+            EngineError::InvalidInput("hash must be 64 hex characters (BLAKE3)".into())
+                .into_response(),
+        );
+    }
+    let dir = upload_dir(&state).await?;
+    crate::persistence::SnapshotManager::upload_init(&dir, req.size, &req.hash)
+        .map(Json)
+        .map_err(upload_error)
+}
+
+/// `GET /v1/snapshot/upload/:id` — how far the upload got, to resume it.
+async fn snapshot_upload_status(
+    State(state): State<SharedEngine>,
+    AxumPath(id): AxumPath<String>,
+) -> Result<Json<crate::persistence::UploadSession>, Response> {
+    let dir = upload_dir(&state).await?;
+    crate::persistence::SnapshotManager::upload_status(&dir, &id)
+        .map(Json)
+        .map_err(upload_error)
+}
+
+/// `PUT /v1/snapshot/upload/:id?offset=&hash=` — append one chunk. `409`
+/// with `received` when `offset` is not where the upload left off.
+async fn snapshot_upload_chunk(
+    State(state): State<SharedEngine>,
+    AxumPath(id): AxumPath<String>,
+    Query(params): Query<SnapshotUploadChunkParams>,
+    body: axum::body::Bytes,
+) -> Result<Json<crate::persistence::UploadSession>, Response> {
+    let dir = upload_dir(&state).await?;
+    crate::persistence::SnapshotManager::upload_chunk(&dir, &id, params.offset, &body, &params.hash)
+        .map(Json)
+        .map_err(upload_error)
+}
+
+/// `POST /v1/snapshot/upload/:id/finalize` — verify the whole snapshot and
+/// restore from it, as `POST /v1/snapshot/upload` would.
+async fn snapshot_upload_finalize(
+    State(state): State<SharedEngine>,
+    AxumPath(id): AxumPath<String>,
+) -> Result<Json<SnapshotRestoreResponse>, Response> {
+    let dir = upload_dir(&state).await?;
+    let data =
+        crate::persistence::SnapshotManager::upload_finalize(&dir, &id).map_err(upload_error)?;
+    let mut engine = state.write().await;
+    engine.restore(&data).map_err(IntoResponse::into_response)?;
+    Ok(Json(SnapshotRestoreResponse { success: true }))
+}
+
+/// `DELETE /v1/snapshot/upload/:id` — discard an upload.
+async fn snapshot_upload_abort(
+    State(state): State<SharedEngine>,
+    AxumPath(id): AxumPath<String>,
+) -> Result<StatusCode, Response> {
+    let dir = upload_dir(&state).await?;
+    crate::persistence::SnapshotManager::upload_abort(&dir, &id).map_err(upload_error)?;
+    Ok(StatusCode::NO_CONTENT)
+}
+
 async fn memory_upsert_vector(
     State(state): State<SharedEngine>,
     axum::Extension(receipts): axum::Extension<Arc<valori_effect::ReceiptStore>>,
@@ -2047,7 +3371,7 @@ async fn memory_search_vector(
     )))
 }
 
-async fn get_proof(State(state): State<SharedEngine>) -> impl IntoResponse {
+async fn get_proof(State(state): State<SharedEngine>) -> Json<StateProofResponse> {
     let engine = state.read().await;
     let proof = engine.get_proof();
     // Encode all 32 bytes as lowercase hex — same wire format as the cluster's
@@ -2057,7 +3381,18 @@ async fn get_proof(State(state): State<SharedEngine>) -> impl IntoResponse {
         .iter()
         .map(|b| format!("{b:02x}"))
         .collect();
-    Json(serde_json::json!({ "final_state_hash": hex }))
+    Json(StateProofResponse {
+        final_state_hash: hex,
+        state_root: Some(
+            engine
+                .state_root()
+                .iter()
+                .map(|b| format!("{b:02x}"))
+                .collect(),
+        ),
+        version: Some(engine.state.version()),
+        truncated: engine.damage.clone(),
+    })
 }
 
 // ── C4.2: Memory consolidation ───────────────────────────────────────────────
@@ -2078,23 +3413,37 @@ async fn memory_contradict(
     crate::routes::memory::memory_contradict(&state, &receipts, payload).await
 }
 
+async fn memory_dedup_check(
+    State(state): State<SharedEngine>,
+    Json(payload): Json<MemoryDedupCheckRequest>,
+) -> Result<Json<MemoryDedupCheckResponse>, Response> {
+    crate::routes::memory::memory_dedup_check(&state, payload).await
+}
+
 async fn get_event_proof(
     State(state): State<SharedEngine>,
 ) -> Result<Json<EventProofResponse>, EngineError> {
-    let engine = state.read().await;
+    let mut engine = state.write().await; // flush requires &mut
 
+    if let Some(committer) = engine.event_committer_mut() {
+        // Group-committed entries still buffered are not in the log hash yet.
+        committer
+            .flush_log()
+            .map_err(|e| EngineError::InvalidInput(format!("event log flush: {e}")))?;
+    }
     if let Some(committer) = engine.event_committer() {
         let proof = engine.get_proof();
         let committed_height = committer.journal().committed_height();
 
-        // Hash the actual event-log file with BLAKE3 (full 32 bytes → 64 hex chars).
-        // Previously this was incorrectly set to the final_state_hash value, and both
-        // hashes were truncated to 16 bytes then formatted without zero-padding,
-        // yielding ≤32 hex chars instead of the correct 64.
-        let event_log_path = committer.event_log().path().to_path_buf();
-        let event_log_hash_bytes =
-            crate::events::event_proof::compute_event_log_hash(&event_log_path)
-                .unwrap_or([0u8; 32]);
+        // The writer's running log hash over every entry of every segment
+        // (full 32 bytes → 64 hex chars), recomputed from disk only when the
+        // writer could not restore it on open.
+        let log = committer.event_log();
+        let event_log_hash_bytes = match log.log_hash() {
+            Some(h) => h,
+            None => crate::events::event_proof::compute_log_hash(log.path())
+                .map_err(|e| EngineError::InvalidInput(format!("event log hash: {e}")))?,
+        };
 
         let response = EventProofResponse {
             kernel_version: 1,
@@ -2110,6 +3459,7 @@ async fn get_event_proof(
             snapshot_hash: None,
             event_count: committed_height,
             committed_height,
+            truncated: engine.damage.clone(),
         };
 
         Ok(Json(response))
@@ -2120,118 +3470,1223 @@ async fn get_event_proof(
     }
 }
 
-// ── Receipt endpoints (Phase A8) ──────────────────────────────────────────────
-
-/// `GET /v1/proof/receipt` — return the most recently assembled Receipt.
-///
-/// Returns 404 if no receipt has been assembled yet (no operation has been
-/// driven through the TaskRunner since node start).
-async fn get_latest_receipt(
-    axum::Extension(store): axum::Extension<Arc<valori_effect::ReceiptStore>>,
-) -> Result<Json<serde_json::Value>, (StatusCode, Json<serde_json::Value>)> {
-    match store.latest() {
-        Some(r) => Ok(Json(
-            serde_json::to_value(&r).unwrap_or(serde_json::Value::Null),
-        )),
-        None => Err((
-            StatusCode::NOT_FOUND,
-            Json(serde_json::json!({"error": "no receipt available yet"})),
-        )),
-    }
+// ── Receipt endpoints (Phase A8) ──────────────────────────────────────────────
+
+/// `GET /v1/proof/receipt` — return the most recently assembled Receipt.
+///
+/// Returns 404 if no receipt has been assembled yet (no operation has been
+/// driven through the TaskRunner since node start).
+async fn get_latest_receipt(
+    axum::Extension(store): axum::Extension<Arc<valori_effect::ReceiptStore>>,
+) -> Result<Json<serde_json::Value>, (StatusCode, Json<serde_json::Value>)> {
+    match store.latest() {
+        Some(r) => Ok(Json(
+            serde_json::to_value(&r).unwrap_or(serde_json::Value::Null),
+        )),
+        None => Err((
+            StatusCode::NOT_FOUND,
+            Json(serde_json::json!({"error": "no receipt available yet"})),
+        )),
+    }
+}
+
+/// `GET /v1/proof/receipt/:id` — return a specific Receipt by receipt_id.
+async fn get_receipt_by_id(
+    axum::extract::Path(id): axum::extract::Path<String>,
+    axum::Extension(store): axum::Extension<Arc<valori_effect::ReceiptStore>>,
+) -> Result<Json<serde_json::Value>, (StatusCode, Json<serde_json::Value>)> {
+    match store.get(&id) {
+        Some(r) => Ok(Json(
+            serde_json::to_value(&r).unwrap_or(serde_json::Value::Null),
+        )),
+        None => Err((
+            StatusCode::NOT_FOUND,
+            Json(serde_json::json!({"error": format!("receipt '{}' not found", id)})),
+        )),
+    }
+}
+
+async fn get_wal_stream(State(state): State<SharedEngine>) -> Result<Body, EngineError> {
+    let path = {
+        let engine = state.read().await;
+        engine.wal_path.clone()
+    }
+    .ok_or(EngineError::InvalidInput("No WAL configured".into()))?;
+
+    let file = tokio::fs::File::open(&path)
+        .await
+        .map_err(|e| EngineError::InvalidInput(e.to_string()))?;
+    Ok(Body::from_stream(ReaderStream::new(file)))
+}
+
+#[derive(Deserialize)]
+struct ReplicationParams {
+    start_offset: Option<u64>,
+}
+
+async fn get_replication_events(
+    State(state): State<SharedEngine>,
+    Query(params): Query<ReplicationParams>,
+    headers: axum::http::HeaderMap,
+) -> Result<Response, EngineError> {
+    use crate::replication_codec::StreamFormat;
+    use crate::replication_compression::{encode_stream, StreamCompression};
+
+    let start_offset = params.start_offset.unwrap_or(0);
+    let mut format = StreamFormat::from_accept(
+        headers
+            .get(axum::http::header::ACCEPT)
+            .and_then(|v| v.to_str().ok()),
+    );
+    let compression = headers
+        .get(axum::http::header::ACCEPT_ENCODING)
+        .and_then(|v| v.to_str().ok())
+        .map(StreamCompression::negotiate)
+        .unwrap_or_default();
+
+    let (log_path, rx) = {
+        let mut engine = state.write().await; // flush requires &mut
+        if let Some(committer) = engine.event_committer_mut() {
+            if let Err(e) = committer.flush_log() {
+                tracing::error!("Failed to flush event log for replication: {}", e);
+            }
+            (
+                committer.event_log().path().to_path_buf(),
+                committer.subscribe(),
+            )
+        } else {
+            return Err(EngineError::InvalidInput(
+                "Event log not enabled".to_string(),
+            ));
+        }
+    };
+
+    // Older history lives in sealed segments; streaming the live segment to a
+    // follower below its first height would apply events out of order.
+    let live_first = crate::replication_segments::live_first_height(&log_path);
+    if start_offset < live_first {
+        return Ok((
+            StatusCode::GONE,
+            Json(serde_json::json!({
+                "error": "start_offset precedes the live segment — catch up from /v1/replication/segments",
+                "live_first_height": live_first,
+            })),
+        )
+            .into_response());
+    }
+
+    // Chained frames start from the event chain at `start_offset`. A log
+    // that does not reach back to genesis (seeded from a snapshot) cannot
+    // supply it; such a leader answers with plain frames.
+    let chain_anchor = match format {
+        StreamFormat::ChainedFrames => crate::geo_replication::local_event_chain(&state)
+            .await
+            .ok()
+            .and_then(|chain| chain.get(start_offset as usize).copied()),
+        _ => None,
+    };
+    if format == StreamFormat::ChainedFrames && chain_anchor.is_none() {
+        format = StreamFormat::Frames;
+    }
+
+    let rx_stream = crate::replication::spawn_replication_stream(
+        log_path,
+        rx,
+        start_offset,
+        format,
+        chain_anchor,
+    )
+    .await?;
+
+    use futures::StreamExt;
+    let entry_stream =
+        tokio_stream::wrappers::ReceiverStream::new(rx_stream).map(|res| match res {
+            Ok(wire_entry) => Ok(wire_entry),
+            Err(e) => Err(std::io::Error::new(
+                std::io::ErrorKind::Other,
+                e.to_string(),
+            )),
+        });
+    let body_stream = encode_stream(entry_stream, compression)
+        .map_err(|e| EngineError::Unknown(format!("stream encoder: {e}")))?;
+
+    let mut resp = Body::from_stream(body_stream).into_response();
+    resp.headers_mut().insert(
+        axum::http::header::CONTENT_TYPE,
+        HeaderValue::from_static(format.content_type()),
+    );
+    resp.headers_mut().insert(
+        axum::http::header::VARY,
+        HeaderValue::from_static("accept-encoding"),
+    );
+    if let Some(enc) = compression.header_value() {
+        resp.headers_mut().insert(
+            axum::http::header::CONTENT_ENCODING,
+            HeaderValue::from_static(enc),
+        );
+    }
+    Ok(resp)
+}
+
+/// The live event log path, or an error when the event log is disabled.
+async fn event_log_path(state: &SharedEngine) -> Result<std::path::PathBuf, EngineError> {
+    let engine = state.read().await;
+    engine
+        .event_committer()
+        .map(|c| c.event_log().path().to_path_buf())
+        .ok_or_else(|| EngineError::InvalidInput("Event log not enabled".to_string()))
+}
+
+/// `GET /v1/replication/segments` — sealed event-log segments still on disk
+/// and the height the live stream starts at.
+async fn get_replication_segments(
+    State(state): State<SharedEngine>,
+) -> Result<Json<crate::replication_segments::SegmentIndex>, EngineError> {
+    let log_path = event_log_path(&state).await?;
+    crate::replication_segments::index(&log_path)
+        .map(Json)
+        .map_err(|e| EngineError::InvalidInput(format!("segment index: {e}")))
+}
+
+/// `GET /v1/replication/segments/:seq` — raw bytes of sealed segment `seq`.
+async fn download_replication_segment(
+    State(state): State<SharedEngine>,
+    AxumPath(seq): AxumPath<u32>,
+) -> Result<Response, EngineError> {
+    let log_path = event_log_path(&state).await?;
+    let Some(path) = crate::replication_segments::archived_path(&log_path, seq) else {
+        return Ok((
+            StatusCode::NOT_FOUND,
+            Json(
+                serde_json::json!({"error": format!("segment {seq} is not archived on this node")}),
+            ),
+        )
+            .into_response());
+    };
+    let file = tokio::fs::File::open(&path)
+        .await
+        .map_err(|e| EngineError::InvalidInput(e.to_string()))?;
+    let mut resp = Body::from_stream(ReaderStream::new(file)).into_response();
+    resp.headers_mut().insert(
+        axum::http::header::CONTENT_TYPE,
+        HeaderValue::from_static("application/octet-stream"),
+    );
+    Ok(resp)
+}
+
+#[derive(Deserialize)]
+struct PartialProofParams {
+    collections: Option<String>,
+    tags: Option<String>,
+    /// Height to replay to; the committed height when absent.
+    height: Option<u64>,
+}
+
+/// `GET /v1/replication/partial-proof?collections=..&tags=..&height=N` —
+/// the state hash a partial replica with that filter has at `height`,
+/// replayed from this node's event log (archived segments included). See
+/// [`crate::replication_filter`].
+async fn get_partial_proof(
+    State(state): State<SharedEngine>,
+    Query(q): Query<PartialProofParams>,
+) -> Result<Json<crate::replication_filter::PartialProof>, EngineError> {
+    use crate::replication_filter::{replay_partial, PartialProof, ReplicationFilter};
+
+    let filter = ReplicationFilter::parse(q.collections.as_deref(), q.tags.as_deref())
+        .map_err(EngineError::InvalidInput)?
+        .ok_or_else(|| {
+            EngineError::InvalidInput("give `collections` and/or `tags` to filter by".into())
+        })?;
+    let (log_path, head, dim, registry) = {
+        let mut engine = state.write().await; // flush requires &mut
+        let Some(committer) = engine.event_committer_mut() else {
+            return Err(EngineError::InvalidInput(
+                "Event log not enabled".to_string(),
+            ));
+        };
+        committer
+            .flush_log()
+            .map_err(|e| EngineError::InvalidInput(format!("event log flush: {e}")))?;
+        (
+            committer.event_log().path().to_path_buf(),
+            committer.journal().committed_height(),
+            engine.dim,
+            engine.list_collections(),
+        )
+    };
+    let height = q.height.unwrap_or(head);
+    if height > head {
+        return Err(EngineError::InvalidInput(format!(
+            "height {height} is beyond the committed height {head}"
+        )));
+    }
+    let resolved = filter.resolve(&registry);
+
+    // The replay reads the whole log; keep it off the engine lock and the
+    // async workers.
+    let hash = tokio::task::spawn_blocking(move || {
+        let events = crate::events::event_replay::read_all_segments(&log_path, None)
+            .map_err(|e| format!("event log read: {e}"))?;
+        if (events.len() as u64) < height {
+            return Err(format!(
+                "event log on disk holds {} events, short of height {height} \
+                 (archived segments missing?)",
+                events.len()
+            ));
+        }
+        let partial = replay_partial(&events[..height as usize], &resolved, dim)?;
+        Ok(valori_kernel::snapshot::blake3::hash_state_blake3(&partial))
+    })
+    .await
+    .map_err(|_| EngineError::Internal)?
+    .map_err(EngineError::InvalidInput)?;
+
+    Ok(Json(PartialProof {
+        height,
+        partial_state_hash: bytes_to_hex(&hash),
+    }))
+}
+
+/// `GET /v1/replication/head` — committed height and leader epoch, polled
+/// by secondary regions.
+async fn get_replication_head(
+    State(state): State<SharedEngine>,
+) -> Json<crate::geo_replication::ReplicationHead> {
+    let engine = state.read().await;
+    Json(crate::geo_replication::ReplicationHead {
+        height: engine
+            .event_committer()
+            .map(|c| c.journal().committed_height())
+            .unwrap_or(0),
+        epoch: engine.leader_epoch.epoch,
+    })
+}
+
+#[derive(Deserialize)]
+struct ChainParams {
+    /// The committed height when absent.
+    height: Option<u64>,
+}
+
+/// `GET /v1/replication/chain?height=N` — the region-independent event
+/// chain at `height` (see [`crate::geo_replication`]).
+async fn get_event_chain(
+    State(state): State<SharedEngine>,
+    Query(q): Query<ChainParams>,
+) -> Result<Json<crate::geo_replication::ChainProof>, EngineError> {
+    use crate::geo_replication::{chain_hex, local_event_chain, ChainProof};
+
+    let chain = local_event_chain(&state).await?;
+    let head = (chain.len() - 1) as u64;
+    let height = q.height.unwrap_or(head);
+    let Some(hash) = chain.get(height as usize) else {
+        return Err(EngineError::InvalidInput(format!(
+            "height {height} is beyond the committed height {head}"
+        )));
+    };
+    Ok(Json(ChainProof {
+        height,
+        chain_hash: chain_hex(hash),
+    }))
+}
+
+/// `GET /v1/proof/at?height=N` — the event chain, state hash and section
+/// hashes of this node's log replayed to `N` (the committed height when
+/// absent) in a fresh kernel. `valori attest` compares it with a local log.
+async fn get_attest_proof(
+    State(state): State<SharedEngine>,
+    Query(q): Query<ChainParams>,
+) -> Result<Json<crate::attest::AttestProof>, EngineError> {
+    let events = crate::geo_replication::local_events(&state).await?;
+    let height = q.height.unwrap_or(events.len() as u64);
+    if height > events.len() as u64 {
+        return Err(EngineError::InvalidInput(format!(
+            "height {height} is beyond the committed height {}",
+            events.len()
+        )));
+    }
+    tokio::task::spawn_blocking(move || crate::attest::prove(&events, height))
+        .await
+        .map_err(|_| EngineError::Internal)?
+        .map(Json)
+        .map_err(EngineError::InvalidInput)
+}
+
+/// `GET /v1/replication/region` — lag and state of a secondary region.
+async fn get_region_status(
+    region: Option<Extension<Arc<crate::geo_replication::RegionTracker>>>,
+) -> Result<Json<crate::geo_replication::RegionStatus>, EngineError> {
+    let Some(Extension(region)) = region else {
+        return Err(EngineError::InvalidInput(
+            "not a secondary region (VALORI_REGION_PRIMARY is unset)".into(),
+        ));
+    };
+    Ok(Json(region.status()))
+}
+
+/// `POST /v1/admin/region/promote` — make this secondary region the
+/// primary: stop pulling, claim a new leader epoch, take writes.
+async fn promote_region(
+    State(state): State<SharedEngine>,
+    region: Option<Extension<Arc<crate::geo_replication::RegionTracker>>>,
+) -> Result<Json<crate::geo_replication::RegionStatus>, EngineError> {
+    let Some(Extension(region)) = region else {
+        return Err(EngineError::InvalidInput(
+            "not a secondary region (VALORI_REGION_PRIMARY is unset)".into(),
+        ));
+    };
+    crate::geo_replication::promote(&state, &region)
+        .await
+        .map(Json)
+}
+
+#[derive(Deserialize)]
+struct HandshakeParams {
+    epoch: Option<u64>,
+}
+
+/// `GET /v1/replication/handshake?epoch=N` — the leader epoch a follower
+/// must check before replicating. `epoch` is the newest the follower has
+/// seen: if it is newer than this node's, this node was superseded as
+/// leader, fences itself and answers 409.
+async fn replication_handshake(
+    State(state): State<SharedEngine>,
+    Query(params): Query<HandshakeParams>,
+) -> Response {
+    let mut engine = state.write().await;
+    if engine.fence_if_superseded(params.epoch.unwrap_or(0)) {
+        if let Some(reason) = &engine.fenced {
+            tracing::error!("Replication handshake refused: {}", reason);
+        }
+        return (
+            StatusCode::CONFLICT,
+            Json(serde_json::json!({
+                "error": engine.fenced,
+                "epoch": engine.leader_epoch.epoch,
+            })),
+        )
+            .into_response();
+    }
+    Json(engine.leader_epoch).into_response()
+}
+
+async fn get_replication_state() -> Json<ReplicationStateResponse> {
+    Json(ReplicationStateResponse {
+        status: crate::replication::replication_display_state().to_string(),
+    })
+}
+
+/// `GET /metrics` — Prometheus text exposition format.
+///
+/// Refreshes all KernelState gauges synchronously before rendering so that
+/// the scrape always reflects the live pool sizes regardless of write
+/// activity between scrapes.
+///
+/// This endpoint is **always unauthenticated** so that Prometheus can scrape
+/// without a bearer token. If the engine is write-locked (notably by startup
+/// recovery) the kernel gauges are left as they were rather than waiting, so
+/// scrapes during a long replay still see `valori_recovery_*`.
+async fn metrics_handler(State(state): State<SharedEngine>) -> String {
+    // Update kernel gauges from live state before rendering.
+    if let Ok(engine) = state.try_read() {
+        engine.update_prometheus_metrics();
+    }
+    crate::telemetry::get_metrics()
+}
+
+/// Kernel invariants plus index / graph-map consistency. Always 200; the
+/// verdict is `ok` in the body so monitoring can alert on violations.
+async fn admin_check(State(state): State<SharedEngine>) -> Json<crate::engine::ConsistencyReport> {
+    Json(state.read().await.check_consistency())
+}
+
+fn require_forget_policy(engine: &Engine) -> Result<(), EngineError> {
+    if engine.forget_policy.is_none() {
+        return Err(EngineError::InvalidInput(
+            "Forgetting policy not enabled (set VALORI_FORGET_EVERY_EVENTS)".to_string(),
+        ));
+    }
+    if engine.event_committer().is_none() {
+        return Err(EngineError::InvalidInput(
+            "Event log not enabled (set VALORI_EVENT_LOG_PATH)".to_string(),
+        ));
+    }
+    Ok(())
+}
+
+/// Dry run: records the forgetting policy would delete right now.
+async fn get_forget_candidates(
+    State(state): State<SharedEngine>,
+) -> Result<Json<Vec<crate::engine::ForgetCandidate>>, EngineError> {
+    let engine = state.read().await;
+    require_forget_policy(&engine)?;
+    Ok(Json(engine.forget_candidates()))
+}
+
+/// Run the forgetting policy now instead of waiting for the next interval.
+async fn run_forget_policy(
+    State(state): State<SharedEngine>,
+) -> Result<Json<serde_json::Value>, EngineError> {
+    let mut engine = state.write().await;
+    require_forget_policy(&engine)?;
+    let forgotten = engine.run_forget_policy()?;
+    let height = engine
+        .event_committer()
+        .map(|c| c.journal().committed_height());
+    engine.forget_last_height = height;
+    Ok(Json(
+        serde_json::json!({ "forgotten": forgotten, "height": height }),
+    ))
+}
+
+#[derive(serde::Deserialize)]
+struct ResizeRequest {
+    records: Option<usize>,
+    nodes: Option<usize>,
+    edges: Option<usize>,
+}
+
+/// Grow (or shrink) the record / node / edge limits through a logged
+/// `ResizePools` event. Omitted fields keep their current limit.
+async fn resize_pools(
+    State(state): State<SharedEngine>,
+    Json(req): Json<ResizeRequest>,
+) -> Result<Json<serde_json::Value>, EngineError> {
+    let mut engine = state.write().await;
+    let records = req.records.unwrap_or(engine.max_records);
+    let nodes = req.nodes.unwrap_or(engine.max_nodes);
+    let edges = req.edges.unwrap_or(engine.max_edges);
+    engine.resize_pools(records, nodes, edges)?;
+    Ok(Json(serde_json::json!({
+        "records": engine.max_records,
+        "nodes": engine.max_nodes,
+        "edges": engine.max_edges,
+    })))
+}
+
+/// Re-pack the record pool through a logged `Vacuum` event. Returns the
+/// old→new id of every moved record so clients holding ids can follow.
+async fn vacuum_records(
+    State(state): State<SharedEngine>,
+) -> Result<Json<serde_json::Value>, EngineError> {
+    let mut engine = state.write().await;
+    let report = engine.vacuum()?;
+    let height = engine
+        .event_committer()
+        .map(|c| c.journal().committed_height());
+    Ok(Json(serde_json::json!({
+        "reclaimed": report.reclaimed,
+        "moved": report.moves.len(),
+        "moves": report.moves,
+        "records": engine.record_count(),
+        "height": height,
+    })))
+}
+
+#[derive(serde::Deserialize)]
+#[serde(deny_unknown_fields)]
+struct AnnotateRequest {
+    label: String,
+    /// Committed height to label; the current one when absent.
+    height: Option<u64>,
+}
+
+async fn annotate_timeline(
+    State(state): State<SharedEngine>,
+    headers: axum::http::HeaderMap,
+    Json(req): Json<AnnotateRequest>,
+) -> Result<Json<serde_json::Value>, EngineError> {
+    // Who labelled it: the first 16 bytes of the bearer token's hash, or
+    // zeros on an open node.
+    let mut authorized_by = [0u8; 16];
+    if let Some(token) = headers
+        .get(AUTHORIZATION)
+        .and_then(|v| v.to_str().ok())
+        .and_then(|v| v.strip_prefix("Bearer "))
+    {
+        authorized_by.copy_from_slice(&blake3::hash(token.as_bytes()).as_bytes()[..16]);
+    }
+    let mut engine = state.write().await;
+    let height = engine.annotate(req.height, &req.label, authorized_by)?;
+    Ok(Json(serde_json::json!({
+        "height": height,
+        "label": req.label.trim(),
+    })))
+}
+
+#[derive(serde::Deserialize, Default)]
+#[serde(deny_unknown_fields)]
+struct RecallRequest {
+    /// Records used as queries (default 100).
+    sample: Option<usize>,
+    k: Option<usize>,
+    /// Index to measure; the primary when absent.
+    index: Option<String>,
+}
+
+/// Estimate recall@k of an index against exact search and publish it in
+/// `/metrics`. See [`crate::recall_job`].
+async fn estimate_recall(
+    State(state): State<SharedEngine>,
+    body: Option<Json<RecallRequest>>,
+) -> Result<Json<crate::engine::RecallReport>, EngineError> {
+    let req = body.map(|Json(r)| r).unwrap_or_default();
+    let report = crate::recall_job::run_once(
+        &state,
+        req.sample.unwrap_or(crate::recall_job::DEFAULT_SAMPLE),
+        req.k.unwrap_or(crate::recall_job::DEFAULT_K),
+        req.index,
+    )
+    .await?;
+    Ok(Json(report))
+}
+
+/// Dry run: what a repair of the HNSW graphs would fix. `ok` means there
+/// is nothing to fix.
+async fn check_index_graphs(
+    State(state): State<SharedEngine>,
+) -> Result<Json<serde_json::Value>, EngineError> {
+    let reports = state.write().await.repair_indexes(false)?;
+    let ok = reports.values().all(|r| r.is_clean());
+    Ok(Json(serde_json::json!({
+        "applied": false,
+        "ok": ok,
+        "indexes": reports,
+    })))
+}
+
+/// Drop orphaned links, reset a stale entry point and re-link unreachable
+/// nodes. `ok` means every live node is reachable afterwards.
+async fn repair_index_graphs(
+    State(state): State<SharedEngine>,
+) -> Result<Json<serde_json::Value>, EngineError> {
+    let reports = state.write().await.repair_indexes(true)?;
+    let ok = reports.values().all(|r| r.still_unreachable.is_empty());
+    if !ok {
+        tracing::warn!("index repair left nodes unreachable from the entry point");
+    }
+    Ok(Json(serde_json::json!({
+        "applied": true,
+        "ok": ok,
+        "indexes": reports,
+    })))
+}
+
+#[derive(Serialize)]
+struct RuntimeConfigView {
+    snapshot_interval_secs: Option<u64>,
+    rate_limit_rps: Option<u32>,
+    slow_query_ms: Option<u64>,
+    request_timeout_ms: Option<u64>,
+    ef_search: usize,
+    log_level: Option<String>,
+}
+
+fn runtime_config_view(engine: &Engine, runtime: &RuntimeConfig) -> RuntimeConfigView {
+    RuntimeConfigView {
+        snapshot_interval_secs: runtime.snapshot_interval_secs(),
+        rate_limit_rps: runtime.rate_limit_rps(),
+        slow_query_ms: runtime.slow_query_ms(),
+        request_timeout_ms: runtime.request_timeout_ms(),
+        ef_search: engine.hnsw_config.ef_search,
+        log_level: crate::telemetry::log_filter(),
+    }
+}
+
+/// `GET /v1/admin/config` — the settings that can change without a restart.
+async fn get_runtime_config(
+    State(state): State<SharedEngine>,
+    Extension(runtime): Extension<Arc<RuntimeConfig>>,
+) -> Json<RuntimeConfigView> {
+    let engine = state.read().await;
+    Json(runtime_config_view(&engine, &runtime))
+}
+
+#[derive(serde::Deserialize)]
+#[serde(deny_unknown_fields)]
+struct RuntimeConfigPatch {
+    /// 0 turns auto-snapshots off.
+    snapshot_interval_secs: Option<u64>,
+    /// 0 removes the limit.
+    rate_limit_rps: Option<u32>,
+    /// 0 turns the slow query log off.
+    slow_query_ms: Option<u64>,
+    /// 0 removes the default deadline.
+    request_timeout_ms: Option<u64>,
+    ef_search: Option<usize>,
+    /// `RUST_LOG` directives, e.g. `"valori_node=info,tower_http=warn"`.
+    log_level: Option<String>,
+}
+
+/// `PATCH /v1/admin/config` — change runtime settings. Omitted fields keep
+/// their value. The whole patch is validated before any of it is applied.
+async fn patch_runtime_config(
+    State(state): State<SharedEngine>,
+    Extension(runtime): Extension<Arc<RuntimeConfig>>,
+    Json(patch): Json<RuntimeConfigPatch>,
+) -> Result<Json<RuntimeConfigView>, EngineError> {
+    let mut engine = state.write().await;
+    if patch.ef_search == Some(0) {
+        return Err(EngineError::InvalidInput(
+            "ef_search must be at least 1".into(),
+        ));
+    }
+    if patch.snapshot_interval_secs.is_some_and(|s| s > 0) && engine.snapshot_path.is_none() {
+        return Err(EngineError::InvalidInput(
+            "snapshot_interval_secs needs VALORI_SNAPSHOT_PATH".into(),
+        ));
+    }
+    if let Some(level) = &patch.log_level {
+        if crate::telemetry::log_filter().is_none() {
+            return Err(EngineError::InvalidInput(
+                "logging is not reloadable in this process".into(),
+            ));
+        }
+        tracing_subscriber::EnvFilter::try_new(level)
+            .map_err(|e| EngineError::InvalidInput(format!("log_level: {e}")))?;
+    }
+
+    if let Some(level) = &patch.log_level {
+        crate::telemetry::set_log_filter(level).map_err(EngineError::InvalidInput)?;
+    }
+    if let Some(ef) = patch.ef_search {
+        engine.set_ef_search(ef);
+    }
+    if let Some(secs) = patch.snapshot_interval_secs {
+        runtime.set_snapshot_interval_secs(Some(secs));
+    }
+    if let Some(rps) = patch.rate_limit_rps {
+        runtime.set_rate_limit_rps(Some(rps));
+    }
+    if let Some(ms) = patch.slow_query_ms {
+        runtime.set_slow_query_ms(Some(ms));
+    }
+    if let Some(ms) = patch.request_timeout_ms {
+        runtime.set_request_timeout_ms(Some(ms));
+    }
+    tracing::info!(
+        snapshot_interval_secs = ?runtime.snapshot_interval_secs(),
+        rate_limit_rps = ?runtime.rate_limit_rps(),
+        slow_query_ms = ?runtime.slow_query_ms(),
+        request_timeout_ms = ?runtime.request_timeout_ms(),
+        ef_search = engine.hnsw_config.ef_search,
+        "Runtime configuration updated"
+    );
+    Ok(Json(runtime_config_view(&engine, &runtime)))
+}
+
+#[derive(serde::Deserialize)]
+struct RestoreQuery {
+    /// Committed height to restore to.
+    height: u64,
+}
+
+/// Point-in-time restore: load the newest cataloged snapshot at or below
+/// `height` (genesis when there is none) and replay the event log up to
+/// exactly `height`. The event log is detached afterwards; see
+/// [`Engine::restore_to_height`].
+async fn restore_to_height(
+    State(state): State<SharedEngine>,
+    Query(q): Query<RestoreQuery>,
+) -> Result<Json<serde_json::Value>, EngineError> {
+    use crate::persistence::SnapshotManager;
+
+    let mut engine = state.write().await;
+    let base = match engine.snapshot_path.as_deref() {
+        Some(path) => {
+            let dir = SnapshotManager::catalog_dir(path);
+            let best = SnapshotManager::list(&dir)
+                .map_err(|e| EngineError::InvalidInput(format!("snapshot catalog: {e}")))?
+                .into_iter()
+                .filter(|e| e.height <= q.height)
+                .max_by_key(|e| (e.height, e.id));
+            match best {
+                Some(entry) => SnapshotManager::load(&dir, entry.id)
+                    .map_err(|e| EngineError::InvalidInput(format!("snapshot catalog: {e}")))?,
+                None => None,
+            }
+        }
+        None => None,
+    };
+
+    let replayed = engine.restore_to_height(
+        base.as_ref()
+            .map(|(entry, data)| (data.as_slice(), entry.height)),
+        q.height,
+    )?;
+    Ok(Json(serde_json::json!({
+        "height": q.height,
+        "base_snapshot_id": base.as_ref().map(|(entry, _)| entry.id),
+        "base_height": base.as_ref().map_or(0, |(entry, _)| entry.height),
+        "replayed_events": replayed,
+        "state_hash": engine.state_hash_hex(),
+        "event_log_detached": true,
+    })))
+}
+
+fn rotation_json(r: &valori_storage::events::Rotation) -> serde_json::Value {
+    serde_json::json!({
+        "archived_segment": r.archive_path,
+        "height": r.height,
+        "checkpoint_hash": bytes_to_hex(&r.checkpoint_hash),
+    })
+}
+
+/// `POST /v1/admin/rotate-log` — archive the live event-log segment now,
+/// whatever its size, so rotation can be scheduled externally.
+async fn rotate_event_log(
+    State(state): State<SharedEngine>,
+) -> Result<Json<serde_json::Value>, EngineError> {
+    let mut engine = state.write().await;
+    let rotation = engine.rotate_event_log()?;
+    Ok(Json(rotation_json(&rotation)))
+}
+
+/// `POST /v1/admin/compact` — snapshot to `VALORI_SNAPSHOT_PATH`, then
+/// rotate. See [`Engine::compact`].
+async fn compact_event_log(
+    State(state): State<SharedEngine>,
+) -> Result<Json<serde_json::Value>, EngineError> {
+    let mut engine = state.write().await;
+    let (snapshot, rotation) = engine.compact()?;
+    let mut body = rotation_json(&rotation);
+    body["snapshot_path"] = serde_json::json!(snapshot);
+    Ok(Json(body))
+}
+
+#[derive(Deserialize)]
+struct ExportParams {
+    collection: Option<String>,
+    #[serde(default)]
+    graph: bool,
+    #[serde(default)]
+    metadata: bool,
+}
+
+/// `GET /v1/export?collection=&graph=&metadata=` — stream a collection as
+/// NDJSON. See [`crate::export`].
+async fn export_records(
+    State(state): State<SharedEngine>,
+    Query(params): Query<ExportParams>,
+) -> Result<Response, EngineError> {
+    let ns = state
+        .read()
+        .await
+        .resolve_collection(params.collection.as_deref())?;
+    let opts = crate::export::ExportOptions {
+        ns,
+        graph: params.graph,
+        metadata: params.metadata,
+    };
+    let (tx, rx) = tokio::sync::mpsc::channel(16);
+    tokio::spawn(crate::export::run(state, opts, tx));
+    use futures::StreamExt;
+    let lines =
+        tokio_stream::wrappers::ReceiverStream::new(rx).map(Ok::<_, std::convert::Infallible>);
+    let mut resp = Body::from_stream(lines).into_response();
+    resp.headers_mut().insert(
+        axum::http::header::CONTENT_TYPE,
+        HeaderValue::from_static("application/x-ndjson"),
+    );
+    Ok(resp)
+}
+
+#[derive(Deserialize)]
+struct ImportParams {
+    collection: Option<String>,
+    batch_size: Option<usize>,
+}
+
+/// `POST /v1/admin/import?collection=&batch_size=` — stream NDJSON rows in,
+/// progress lines out. See [`crate::import`].
+async fn bulk_import(
+    State(state): State<SharedEngine>,
+    Query(params): Query<ImportParams>,
+    headers: axum::http::HeaderMap,
+    body: Body,
+) -> Result<Response, EngineError> {
+    use crate::import::{DEFAULT_BATCH_SIZE, MAX_BATCH_SIZE};
+    let content_type = headers
+        .get(axum::http::header::CONTENT_TYPE)
+        .and_then(|v| v.to_str().ok())
+        .unwrap_or("");
+    if content_type.contains("arrow") {
+        return Ok((
+            StatusCode::UNSUPPORTED_MEDIA_TYPE,
+            Json(serde_json::json!({
+                "error": "Arrow import is not supported by this build — send application/x-ndjson",
+            })),
+        )
+            .into_response());
+    }
+    let batch_size = params.batch_size.unwrap_or(DEFAULT_BATCH_SIZE);
+    if batch_size == 0 || batch_size > MAX_BATCH_SIZE {
+        return Err(EngineError::InvalidInput(format!(
+            "batch_size must be between 1 and {MAX_BATCH_SIZE}"
+        )));
+    }
+    let ns = {
+        let engine = state.read().await;
+        engine.check_writable()?;
+        engine.resolve_collection(params.collection.as_deref())?
+    };
+
+    let (tx, rx) = tokio::sync::mpsc::channel(16);
+    tokio::spawn(crate::import::run(state, body, ns, batch_size, tx));
+    use futures::StreamExt;
+    let lines =
+        tokio_stream::wrappers::ReceiverStream::new(rx).map(Ok::<_, std::convert::Infallible>);
+    let mut resp = Body::from_stream(lines).into_response();
+    resp.headers_mut().insert(
+        axum::http::header::CONTENT_TYPE,
+        HeaderValue::from_static("application/x-ndjson"),
+    );
+    Ok(resp)
+}
+
+/// `POST /v1/admin/bulk-load/begin` — defer index insertion for the inserts
+/// that follow. See [`Engine::begin_bulk_load`].
+async fn begin_bulk_load(
+    State(state): State<SharedEngine>,
+) -> Result<Json<serde_json::Value>, EngineError> {
+    let mut engine = state.write().await;
+    engine.begin_bulk_load()?;
+    Ok(Json(serde_json::json!({
+        "bulk_loading": true,
+        "records": engine.record_count(),
+    })))
+}
+
+/// `POST /v1/admin/bulk-load/finish` — build every index once over all
+/// records and resume normal indexing.
+async fn finish_bulk_load(
+    State(state): State<SharedEngine>,
+) -> Result<Json<serde_json::Value>, EngineError> {
+    let mut engine = state.write().await;
+    let started = std::time::Instant::now();
+    let indexed = engine.finish_bulk_load()?;
+    Ok(Json(serde_json::json!({
+        "bulk_loading": false,
+        "indexed": indexed,
+        "build_ms": started.elapsed().as_millis() as u64,
+    })))
+}
+
+#[derive(serde::Deserialize)]
+struct DiffQuery {
+    /// Committed height (number of events applied) of the base state.
+    from: u64,
+    /// Committed height of the target state.
+    to: u64,
+}
+
+/// Structural diff between the states at two committed heights, rebuilt by
+/// replaying the journal into fresh kernels (as `search_as_of` does).
+async fn get_state_diff(
+    State(state): State<SharedEngine>,
+    Query(q): Query<DiffQuery>,
+) -> Result<Json<DiffResponse>, EngineError> {
+    use valori_kernel::snapshot::blake3::hash_state_blake3;
+    use valori_kernel::state::diff::{diff_states, SectionHashes};
+    use valori_kernel::state::kernel::KernelState;
+
+    let engine = state.read().await;
+    let committer = engine.event_committer().ok_or_else(|| {
+        EngineError::InvalidInput(
+            "state diff requires the event log (set VALORI_EVENT_LOG_PATH)".into(),
+        )
+    })?;
+    let events = committer.journal().committed();
+    for h in [q.from, q.to] {
+        if h > events.len() as u64 {
+            return Err(EngineError::InvalidInput(format!(
+                "height {h} is out of range (have {} events)",
+                events.len()
+            )));
+        }
+    }
+
+    let replay = |height: u64| {
+        let mut s = KernelState::new();
+        for event in &events[..height as usize] {
+            let _ = s.apply_event(event);
+        }
+        s
+    };
+    let (a, b) = (replay(q.from), replay(q.to));
+    drop(engine);
+
+    let d = diff_states(&a, &b);
+    let hex = |h: &SectionHashes| SectionHashesHex {
+        records: bytes_to_hex(&h.records),
+        nodes: bytes_to_hex(&h.nodes),
+        edges: bytes_to_hex(&h.edges),
+    };
+    Ok(Json(DiffResponse {
+        from: q.from,
+        to: q.to,
+        from_state_hash: bytes_to_hex(&hash_state_blake3(&a)),
+        to_state_hash: bytes_to_hex(&hash_state_blake3(&b)),
+        from_sections: hex(&d.from_hashes),
+        to_sections: hex(&d.to_hashes),
+        records_added: d.records_added,
+        records_removed: d.records_removed,
+        records_changed: d.records_changed,
+        nodes_added: d.nodes_added,
+        nodes_removed: d.nodes_removed,
+        nodes_changed: d.nodes_changed,
+        edges_added: d.edges_added,
+        edges_removed: d.edges_removed,
+        edges_changed: d.edges_changed,
+    }))
+}
+
+#[derive(serde::Deserialize)]
+struct DriftQuery {
+    /// Events in the recent window (default 100).
+    window: Option<u64>,
+    /// `score` above which a tag counts as drifted (default 1.0).
+    threshold: Option<f64>,
+    /// Report only this tag.
+    tag: Option<u64>,
+    /// Height buckets in the per-tag series (default 0 = no series).
+    buckets: Option<u64>,
 }
 
-/// `GET /v1/proof/receipt/:id` — return a specific Receipt by receipt_id.
-async fn get_receipt_by_id(
-    axum::extract::Path(id): axum::extract::Path<String>,
-    axum::Extension(store): axum::Extension<Arc<valori_effect::ReceiptStore>>,
-) -> Result<Json<serde_json::Value>, (StatusCode, Json<serde_json::Value>)> {
-    match store.get(&id) {
-        Some(r) => Ok(Json(
-            serde_json::to_value(&r).unwrap_or(serde_json::Value::Null),
-        )),
-        None => Err((
-            StatusCode::NOT_FOUND,
-            Json(serde_json::json!({"error": format!("receipt '{}' not found", id)})),
-        )),
+/// Per-tag centroid drift of recent inserts against the history, computed
+/// from the committed journal (see `crate::drift`).
+async fn get_drift(
+    State(state): State<SharedEngine>,
+    Query(q): Query<DriftQuery>,
+) -> Result<Json<crate::drift::DriftReport>, EngineError> {
+    let window = q.window.unwrap_or(100);
+    let threshold = q.threshold.unwrap_or(1.0);
+    let buckets = q.buckets.unwrap_or(0);
+    if window == 0 || !threshold.is_finite() || threshold < 0.0 || buckets > 1000 {
+        return Err(EngineError::InvalidInput(
+            "window must be > 0, threshold a non-negative number, buckets <= 1000".into(),
+        ));
     }
+
+    let engine = state.read().await;
+    let committer = engine.event_committer().ok_or_else(|| {
+        EngineError::InvalidInput(
+            "drift analytics require the event log (set VALORI_EVENT_LOG_PATH)".into(),
+        )
+    })?;
+    let events = committer.journal().committed();
+    Ok(Json(crate::drift::drift_report(
+        &events, window, threshold, q.tag, buckets,
+    )))
 }
 
-async fn get_wal_stream(State(state): State<SharedEngine>) -> Result<Body, EngineError> {
-    let path = {
-        let engine = state.read().await;
-        engine.wal_path.clone()
-    }
-    .ok_or(EngineError::InvalidInput("No WAL configured".into()))?;
+/// Default and maximum page size of `GET /v1/events`.
+const EVENTS_PAGE_DEFAULT: usize = 100;
+const EVENTS_PAGE_MAX: usize = 1000;
 
-    let file = tokio::fs::File::open(&path)
-        .await
-        .map_err(|e| EngineError::InvalidInput(e.to_string()))?;
-    Ok(Body::from_stream(ReaderStream::new(file)))
+#[derive(serde::Deserialize, Default)]
+struct EventsQuery {
+    /// First log index (inclusive).
+    from: Option<u64>,
+    /// Last log index (exclusive) — a committed height.
+    to: Option<u64>,
+    /// Comma-separated event types (`InsertRecord`) or classes (`graph`).
+    #[serde(rename = "type")]
+    types: Option<String>,
+    limit: Option<usize>,
+    /// Include record vectors (omitted by default: they dwarf the rest).
+    #[serde(default)]
+    vectors: bool,
 }
 
-#[derive(Deserialize)]
-struct ReplicationParams {
-    start_offset: Option<u64>,
+/// One committed event as JSON. Metadata that is JSON is returned as JSON,
+/// other bytes as base64.
+fn event_json(
+    log_index: u64,
+    timestamp: u64,
+    event: &valori_kernel::event::KernelEvent,
+    vectors: bool,
+) -> serde_json::Value {
+    use base64::{engine::general_purpose::STANDARD, Engine as _};
+    use serde_json::json;
+    use valori_kernel::event::KernelEvent;
+    let floats = |v: &valori_kernel::types::vector::FxpVector| -> serde_json::Value {
+        if !vectors {
+            return serde_json::Value::Null;
+        }
+        v.data
+            .iter()
+            .map(|&s| valori_kernel::fxp::ops::to_f32(s))
+            .collect::<Vec<_>>()
+            .into()
+    };
+    let bytes = |b: &Option<Vec<u8>>| -> serde_json::Value {
+        match b {
+            None => serde_json::Value::Null,
+            Some(b) => serde_json::from_slice(b).unwrap_or_else(|_| STANDARD.encode(b).into()),
+        }
+    };
+    let fields = match event {
+        KernelEvent::InsertRecord {
+            id,
+            vector,
+            metadata,
+            tag,
+        } => json!({
+            "id": id.0, "vector": floats(vector), "metadata": bytes(metadata), "tag": tag,
+        }),
+        KernelEvent::DeleteRecord { id } | KernelEvent::SoftDeleteRecord { id } => {
+            json!({ "id": id.0 })
+        }
+        KernelEvent::CreateNode { id, kind, record } => json!({
+            "id": id.0, "kind": *kind as u8, "record": record.map(|r| r.0),
+        }),
+        KernelEvent::CreateEdge { id, from, to, kind } => json!({
+            "id": id.0, "from": from.0, "to": to.0, "kind": *kind as u8,
+        }),
+        KernelEvent::DeleteEdge { id } => json!({ "id": id.0 }),
+        KernelEvent::DeleteNode { id } => json!({ "id": id.0 }),
+        KernelEvent::InsertRecordEncrypted {
+            id,
+            key_id,
+            ciphertext,
+            tag,
+            ..
+        } => json!({
+            "id": id.0,
+            "key_id": key_id_to_hex(key_id),
+            "ciphertext_bytes": ciphertext.len(),
+            "tag": tag,
+        }),
+        KernelEvent::ShredKey { key_id } => json!({ "key_id": key_id_to_hex(key_id) }),
+        KernelEvent::AutoInsertRecord {
+            vector,
+            metadata,
+            tag,
+        } => json!({ "vector": floats(vector), "metadata": bytes(metadata), "tag": tag }),
+        KernelEvent::AutoCreateNode { kind, record } => json!({
+            "kind": *kind as u8, "record": record.map(|r| r.0),
+        }),
+        KernelEvent::AutoCreateEdge { from, to, kind } => json!({
+            "from": from.0, "to": to.0, "kind": *kind as u8,
+        }),
+        KernelEvent::AutoInsertRecordEncrypted {
+            namespace_id,
+            key_id,
+            ciphertext,
+            tag,
+        } => json!({
+            "namespace_id": namespace_id,
+            "key_id": key_id_to_hex(key_id),
+            "ciphertext_bytes": ciphertext.len(),
+            "tag": tag,
+        }),
+        KernelEvent::UpdateRecordMetadata { id, metadata } => json!({
+            "id": id.0, "metadata": bytes(metadata),
+        }),
+        KernelEvent::SetMeta { key, value } => json!({
+            "key": key,
+            "value": serde_json::from_str::<serde_json::Value>(value)
+                .unwrap_or_else(|_| value.clone().into()),
+        }),
+        KernelEvent::AutoCreateNamespace { name } | KernelEvent::DropNamespace { name } => {
+            json!({ "name": name })
+        }
+        KernelEvent::ResizePools {
+            records,
+            nodes,
+            edges,
+        } => json!({ "records": records, "nodes": nodes, "edges": edges }),
+        KernelEvent::Vacuum { moves } => json!({
+            "moves": moves.iter().map(|(old, new)| [old.0, new.0]).collect::<Vec<_>>(),
+        }),
+        KernelEvent::DeleteMeta { key } => json!({ "key": key }),
+        KernelEvent::Omitted { record } => json!({ "record": record.map(|r| r.0) }),
+        KernelEvent::SetSparseVector { id, vector } => json!({ "id": id.0, "nnz": vector.nnz() }),
+    };
+    let mut body = json!({
+        "log_index": log_index,
+        "timestamp_unix": timestamp,
+        "timestamp_iso": unix_to_iso8601(timestamp),
+        "type": event.event_type(),
+    });
+    if let (Some(body), serde_json::Value::Object(mut fields)) = (body.as_object_mut(), fields) {
+        if !vectors {
+            fields.remove("vector");
+        }
+        body.extend(fields);
+    }
+    body
 }
 
-async fn get_replication_events(
+/// `GET /v1/events` — committed events decoded as JSON, in log order, one
+/// page at a time. `from` / `to` bound the log index range, `type` keeps
+/// the listed event types or classes, and `next` continues the listing.
+async fn get_events(
     State(state): State<SharedEngine>,
-    Query(params): Query<ReplicationParams>,
-) -> Result<Body, EngineError> {
-    let start_offset = params.start_offset.unwrap_or(0);
+    Query(q): Query<EventsQuery>,
+) -> Result<Json<EventsResponse>, EngineError> {
+    use valori_kernel::event::KernelEvent;
+    use valori_storage::events::EventClass;
 
-    let (log_path, rx) = {
-        let mut engine = state.write().await; // flush requires &mut
-        if let Some(committer) = engine.event_committer_mut() {
-            if let Err(e) = committer.flush_log() {
-                tracing::error!("Failed to flush event log for replication: {}", e);
+    let mut types = Vec::new();
+    let mut classes = Vec::new();
+    for t in q.types.iter().flat_map(|t| t.split(',')).map(str::trim) {
+        if t.is_empty() {
+            continue;
+        }
+        if t.starts_with(|c: char| c.is_ascii_uppercase()) {
+            if !KernelEvent::EVENT_TYPES.contains(&t) {
+                return Err(EngineError::InvalidInput(format!(
+                    "unknown event type '{t}'"
+                )));
             }
-            (
-                committer.event_log().path().to_path_buf(),
-                committer.subscribe(),
-            )
+            types.push(t);
         } else {
-            return Err(EngineError::InvalidInput(
-                "Event log not enabled".to_string(),
-            ));
+            classes.push(t.parse::<EventClass>().map_err(EngineError::InvalidInput)?);
         }
-    };
-
-    let rx_stream =
-        crate::replication::spawn_replication_stream(log_path, rx, start_offset).await?;
-
-    use futures::StreamExt;
-    let body_stream = tokio_stream::wrappers::ReceiverStream::new(rx_stream).map(|res| match res {
-        Ok(json_line) => Ok(json_line),
-        Err(e) => Err(std::io::Error::new(
-            std::io::ErrorKind::Other,
-            e.to_string(),
-        )),
-    });
-
-    Ok(Body::from_stream(body_stream))
-}
+    }
+    let limit = q
+        .limit
+        .unwrap_or(EVENTS_PAGE_DEFAULT)
+        .clamp(1, EVENTS_PAGE_MAX);
 
-async fn get_replication_state() -> Json<serde_json::Value> {
-    let status_str = crate::replication::replication_display_state();
-    Json(serde_json::json!({ "status": status_str }))
-}
+    let engine = state.read().await;
+    let Some(committer) = engine.event_committer() else {
+        return Err(EngineError::InvalidInput(
+            "Event log not enabled (set VALORI_EVENT_LOG_PATH)".to_string(),
+        ));
+    };
+    let journal = committer.journal();
+    let height = journal.committed().len() as u64;
+    let from = q.from.unwrap_or(0);
+    let to = q.to.unwrap_or(height).min(height);
 
-/// `GET /metrics` — Prometheus text exposition format.
-///
-/// Refreshes all KernelState gauges synchronously before rendering so that
-/// the scrape always reflects the live pool sizes regardless of write
-/// activity between scrapes.
-///
-/// This endpoint is **always unauthenticated** so that Prometheus can scrape
-/// without a bearer token.
-async fn metrics_handler(State(state): State<SharedEngine>) -> String {
-    // Update kernel gauges from live state before rendering.
-    {
-        let engine = state.read().await;
-        engine.update_prometheus_metrics();
+    let mut events = Vec::new();
+    let mut next = None;
+    let matching = journal
+        .committed_with_timestamps()
+        .enumerate()
+        .map(|(i, (event, ts))| (i as u64, event, ts))
+        .skip(from as usize)
+        .take_while(|(i, _, _)| *i < to)
+        .filter(|(_, event, _)| {
+            (types.is_empty() && classes.is_empty())
+                || types.contains(&event.event_type())
+                || classes.contains(&EventClass::of(event))
+        });
+    for (i, event, ts) in matching {
+        if events.len() == limit {
+            next = Some(i);
+            break;
+        }
+        events.push(event_json(i, ts, event, q.vectors));
     }
-    crate::telemetry::get_metrics()
+    Ok(Json(EventsResponse {
+        events,
+        next,
+        height,
+    }))
 }
 
 #[derive(serde::Deserialize, Default)]
@@ -2265,6 +4720,16 @@ async fn get_timeline(
     let journal = committer.journal();
     let mut entries: Vec<TimelineEntry> = Vec::new();
 
+    // Scores belong to the live record, i.e. to the last insert of its id.
+    let mut scored_insert: std::collections::HashMap<u32, usize> = std::collections::HashMap::new();
+    if !engine.anomaly_scores.is_empty() {
+        for (i, event) in journal.committed().iter().enumerate() {
+            if let KernelEvent::InsertRecord { id, .. } = event {
+                scored_insert.insert(id.0, i);
+            }
+        }
+    }
+
     for (log_index, (event, ts)) in journal.committed_with_timestamps().enumerate() {
         // Apply timestamp range filter.
         if let Some(from) = from_unix {
@@ -2302,6 +4767,20 @@ async fn get_timeline(
             KernelEvent::UpdateRecordMetadata { id, .. } => {
                 ("UpdateRecordMetadata", Some(id.0), None, None)
             }
+            KernelEvent::ResizePools { .. } => ("ResizePools", None, None, None),
+            KernelEvent::Vacuum { .. } => ("Vacuum", None, None, None),
+            KernelEvent::DeleteMeta { .. } => ("DeleteMeta", None, None, None),
+            KernelEvent::Omitted { record } => ("Omitted", record.map(|r| r.0), None, None),
+            KernelEvent::SetSparseVector { id, .. } => ("SetSparseVector", Some(id.0), None, None),
+        };
+
+        let anomaly = match (event, record_id) {
+            (KernelEvent::InsertRecord { .. }, Some(id))
+                if scored_insert.get(&id) == Some(&log_index) =>
+            {
+                engine.record_anomaly(id)
+            }
+            _ => None,
         };
 
         entries.push(TimelineEntry {
@@ -2313,6 +4792,8 @@ async fn get_timeline(
             record_id,
             node_id,
             edge_id,
+            anomaly_score: anomaly.map(|(score, _)| score),
+            outlier: anomaly.is_some_and(|(_, outlier)| outlier),
         });
     }
 
@@ -2366,6 +4847,11 @@ async fn get_operations(
             KernelEvent::UpdateRecordMetadata { id, .. } => {
                 ("UpdateRecordMetadata", Some(id.0), None, None)
             }
+            KernelEvent::ResizePools { .. } => ("ResizePools", None, None, None),
+            KernelEvent::Vacuum { .. } => ("Vacuum", None, None, None),
+            KernelEvent::DeleteMeta { .. } => ("DeleteMeta", None, None, None),
+            KernelEvent::Omitted { record } => ("Omitted", record.map(|r| r.0), None, None),
+            KernelEvent::SetSparseVector { id, .. } => ("SetSparseVector", Some(id.0), None, None),
         };
 
         let details = serde_json::json!({
@@ -2453,6 +4939,11 @@ async fn get_operation_by_id(
         KernelEvent::UpdateRecordMetadata { id, .. } => {
             ("UpdateRecordMetadata", Some(id.0), None, None)
         }
+        KernelEvent::ResizePools { .. } => ("ResizePools", None, None, None),
+        KernelEvent::Vacuum { .. } => ("Vacuum", None, None, None),
+        KernelEvent::DeleteMeta { .. } => ("DeleteMeta", None, None, None),
+        KernelEvent::Omitted { record } => ("Omitted", record.map(|r| r.0), None, None),
+        KernelEvent::SetSparseVector { id, .. } => ("SetSparseVector", Some(id.0), None, None),
     };
 
     let op_id = format!("op-{}", log_index);
@@ -2597,6 +5088,325 @@ async fn drop_collection_handler(
     crate::routes::collections::drop_collection(&state, &name).await
 }
 
+// ── Configured collections (`/v1/collections`) ─────────────────────────────
+//
+// Each collection is its own engine in the node's `EngineRegistry`, with its
+// own dim, capacity and index; see `crate::engine_registry`. The node engine
+// behind `SharedEngine` is not involved.
+
+use crate::engine_registry::{Collection, CollectionProof, EngineRegistry, RegistryError};
+
+fn configured_collection(
+    registry: &EngineRegistry,
+    name: &str,
+) -> Result<Collection, RegistryError> {
+    registry
+        .get(name)
+        .ok_or_else(|| RegistryError::NotFound(name.to_string()))
+}
+
+async fn describe_collection(name: String, c: &Collection) -> ConfiguredCollectionInfo {
+    ConfiguredCollectionInfo {
+        name,
+        dim: c.spec.dim,
+        max_records: c.spec.max_records,
+        index: c.spec.index.name().to_string(),
+        quantization: c.spec.quantization.name().to_string(),
+        records: c.engine.read().await.record_count(),
+    }
+}
+
+fn check_dim(c: &Collection, values: &[f32]) -> Result<(), Response> {
+    if values.len() != c.spec.dim {
+        return Err(EngineError::InvalidInput(format!(
+            "vector has {} dimensions; the collection has {}",
+            values.len(),
+            c.spec.dim
+        ))
+        .into_response());
+    }
+    Ok(())
+}
+
+/// `POST /v1/collections` — 201 with the new collection, 409 when the name
+/// is taken.
+async fn create_configured_collection(
+    State(state): State<SharedEngine>,
+    Extension(registry): Extension<Arc<EngineRegistry>>,
+    Json(payload): Json<CreateConfiguredCollectionRequest>,
+) -> Result<(axum::http::StatusCode, Json<ConfiguredCollectionInfo>), Response> {
+    let name = payload.name.trim().to_string();
+    crate::routes::collections::validate_name(&name)?;
+    let (default_records, default_index, default_quant) = {
+        let engine = state.read().await;
+        (
+            engine.max_records,
+            engine.index_kind,
+            engine.quantization_kind,
+        )
+    };
+    let index = match payload.index.as_deref() {
+        None => default_index,
+        Some(n) => crate::engine::IndexKind::from_name(n).ok_or_else(|| {
+            EngineError::InvalidInput(format!("unknown index '{n}'")).into_response()
+        })?,
+    };
+    let quantization = match payload.quantization.as_deref() {
+        None => default_quant,
+        Some(n) => crate::engine::QuantizationKind::from_name(n).ok_or_else(|| {
+            EngineError::InvalidInput(format!("unknown quantization '{n}'")).into_response()
+        })?,
+    };
+    let spec = crate::engine_registry::CollectionSpec {
+        dim: payload.dim,
+        max_records: payload.max_records.unwrap_or(default_records),
+        index,
+        quantization,
+    };
+    let collection = tokio::task::spawn_blocking({
+        let name = name.clone();
+        move || registry.create(&name, spec)
+    })
+    .await
+    .map_err(|_| EngineError::Internal.into_response())?
+    .map_err(IntoResponse::into_response)?;
+    Ok((
+        axum::http::StatusCode::CREATED,
+        Json(describe_collection(name, &collection).await),
+    ))
+}
+
+/// `GET /v1/collections`.
+async fn list_configured_collections(
+    Extension(registry): Extension<Arc<EngineRegistry>>,
+) -> Json<ListConfiguredCollectionsResponse> {
+    let mut collections = Vec::new();
+    for (name, c) in registry.list() {
+        collections.push(describe_collection(name, &c).await);
+    }
+    Json(ListConfiguredCollectionsResponse { collections })
+}
+
+/// `GET /v1/collections/:name`.
+async fn get_configured_collection(
+    Extension(registry): Extension<Arc<EngineRegistry>>,
+    AxumPath(name): AxumPath<String>,
+) -> Result<Json<ConfiguredCollectionInfo>, RegistryError> {
+    let c = configured_collection(&registry, &name)?;
+    Ok(Json(describe_collection(name, &c).await))
+}
+
+/// `DELETE /v1/collections/:name` — drops the collection and deletes its
+/// directory.
+async fn drop_configured_collection(
+    Extension(registry): Extension<Arc<EngineRegistry>>,
+    AxumPath(name): AxumPath<String>,
+) -> Result<axum::http::StatusCode, RegistryError> {
+    registry.remove(&name).await?;
+    Ok(axum::http::StatusCode::NO_CONTENT)
+}
+
+/// `POST /v1/collections/:name/records`.
+async fn collection_insert(
+    Extension(registry): Extension<Arc<EngineRegistry>>,
+    AxumPath(name): AxumPath<String>,
+    Json(payload): Json<CollectionInsertRequest>,
+) -> Result<Json<CollectionInsertResponse>, Response> {
+    let c = configured_collection(&registry, &name).map_err(IntoResponse::into_response)?;
+    check_dim(&c, &payload.values)?;
+    if !payload
+        .values
+        .iter()
+        .all(|&v| valori_protocol::fxp::in_range(v))
+    {
+        return Err(EngineError::InvalidInput(
+            "Vector values must be between -32768.0 and 32767.99".into(),
+        )
+        .into_response());
+    }
+    let vector = valori_kernel::types::vector::FxpVector {
+        data: payload
+            .values
+            .iter()
+            .map(|&f| valori_kernel::fxp::ops::from_f32(f))
+            .collect(),
+    };
+    let metadata = payload
+        .metadata
+        .map(|m| serde_json::to_vec(&m).expect("JSON value serializes"));
+    let id = c
+        .engine
+        .write()
+        .await
+        .insert_record_fxp(
+            vector,
+            metadata,
+            payload.tag.unwrap_or(0),
+            valori_kernel::types::id::DEFAULT_NS.0,
+        )
+        .map_err(IntoResponse::into_response)?;
+    Ok(Json(CollectionInsertResponse { id }))
+}
+
+/// `GET /v1/collections/:name/records/:id` — same shape as
+/// `GET /v1/records/:id`.
+async fn collection_get_record(
+    Extension(registry): Extension<Arc<EngineRegistry>>,
+    AxumPath((name, id)): AxumPath<(String, u32)>,
+) -> Result<Json<serde_json::Value>, Response> {
+    let c = configured_collection(&registry, &name).map_err(IntoResponse::into_response)?;
+    let engine = c.engine.read().await;
+    let rec = engine
+        .get_record(valori_kernel::types::id::RecordId(id))
+        .filter(|r| r.is_active())
+        .ok_or_else(|| {
+            EngineError::Kernel(valori_kernel::error::KernelError::NotFound).into_response()
+        })?;
+    let vector: Vec<f32> = engine
+        .record_vector(rec)
+        .iter()
+        .map(|s| valori_kernel::fxp::ops::to_f32(*s))
+        .collect();
+    Ok(Json(serde_json::json!({
+        "id": id,
+        "vector": vector,
+        "metadata": rec.metadata.as_ref()
+            .and_then(|b| serde_json::from_slice::<serde_json::Value>(b).ok()),
+        "tag": rec.tag,
+        "version": rec.version,
+    })))
+}
+
+/// `DELETE /v1/collections/:name/records/:id`.
+async fn collection_delete_record(
+    Extension(registry): Extension<Arc<EngineRegistry>>,
+    AxumPath((name, id)): AxumPath<(String, u32)>,
+) -> Result<axum::http::StatusCode, Response> {
+    let c = configured_collection(&registry, &name).map_err(IntoResponse::into_response)?;
+    c.engine
+        .write()
+        .await
+        .delete_record(id)
+        .map_err(IntoResponse::into_response)?;
+    Ok(axum::http::StatusCode::NO_CONTENT)
+}
+
+/// `POST /v1/collections/:name/search` — exact or index search, by the
+/// collection's index kind.
+async fn collection_search(
+    Extension(registry): Extension<Arc<EngineRegistry>>,
+    AxumPath(name): AxumPath<String>,
+    Json(payload): Json<CollectionSearchRequest>,
+) -> Result<Json<SearchResponse>, Response> {
+    let c = configured_collection(&registry, &name).map_err(IntoResponse::into_response)?;
+    check_dim(&c, &payload.query)?;
+    let hits = c
+        .engine
+        .read()
+        .await
+        .search_l2(&payload.query, payload.k)
+        .map_err(IntoResponse::into_response)?;
+    Ok(Json(SearchResponse::simple(
+        hits.into_iter()
+            .map(|(id, score)| SearchHit {
+                id,
+                score,
+                decay_factor: None,
+                age_secs: None,
+            })
+            .collect(),
+    )))
+}
+
+fn collection_proof_response(p: CollectionProof) -> CollectionProofResponse {
+    CollectionProofResponse {
+        kernel_version: p.proof.kernel_version,
+        final_state_hash: bytes_to_hex(&p.proof.final_state_hash),
+        state_root: bytes_to_hex(&p.state_root),
+        version: p.version,
+        leaf: bytes_to_hex(&p.leaf),
+        name: p.name,
+    }
+}
+
+/// `GET /v1/collections/:name/snapshot` — the collection's state as a
+/// snapshot container, with its state hash in `x-valori-state-hash`.
+async fn collection_snapshot(
+    Extension(registry): Extension<Arc<EngineRegistry>>,
+    AxumPath(name): AxumPath<String>,
+) -> Result<Response, Response> {
+    use crate::snapshot_stream::STATE_HASH_HEADER;
+
+    let c = configured_collection(&registry, &name).map_err(IntoResponse::into_response)?;
+    let (bytes, state_hash) = {
+        let engine = c.engine.read().await;
+        (
+            engine.snapshot().map_err(IntoResponse::into_response)?,
+            engine.state_hash_hex(),
+        )
+    };
+    Ok((
+        [
+            (
+                axum::http::header::CONTENT_TYPE,
+                HeaderValue::from_static("application/octet-stream"),
+            ),
+            (
+                axum::http::HeaderName::from_static(STATE_HASH_HEADER),
+                HeaderValue::from_str(&state_hash).expect("hex is a valid header value"),
+            ),
+        ],
+        bytes,
+    )
+        .into_response())
+}
+
+/// `POST /v1/collections/:name/snapshot` — replace the collection's state
+/// with the snapshot in the body. 400 when it does not decode or has
+/// another dimension. Returns the proof of the restored state.
+async fn collection_restore(
+    Extension(registry): Extension<Arc<EngineRegistry>>,
+    AxumPath(name): AxumPath<String>,
+    body: axum::body::Bytes,
+) -> Result<Json<CollectionProofResponse>, Response> {
+    tokio::task::spawn_blocking({
+        let registry = registry.clone();
+        let name = name.clone();
+        move || registry.restore(&name, &body)
+    })
+    .await
+    .map_err(|_| EngineError::Internal.into_response())?
+    .map_err(IntoResponse::into_response)?;
+    let c = configured_collection(&registry, &name).map_err(IntoResponse::into_response)?;
+    Ok(Json(collection_proof_response(
+        CollectionProof::of(&name, &c).await,
+    )))
+}
+
+/// `GET /v1/collections/:name/proof` — the collection's own state proof and
+/// its leaf under `GET /v1/proof/collections`.
+async fn collection_proof(
+    Extension(registry): Extension<Arc<EngineRegistry>>,
+    AxumPath(name): AxumPath<String>,
+) -> Result<Json<CollectionProofResponse>, RegistryError> {
+    let c = configured_collection(&registry, &name)?;
+    Ok(Json(collection_proof_response(
+        CollectionProof::of(&name, &c).await,
+    )))
+}
+
+/// `GET /v1/proof/collections` — every collection's proof and the Merkle
+/// root over them.
+async fn collections_root_proof(
+    Extension(registry): Extension<Arc<EngineRegistry>>,
+) -> Json<CollectionsRootProofResponse> {
+    let (root, proofs) = registry.root_proof().await;
+    Json(CollectionsRootProofResponse {
+        root: bytes_to_hex(&root),
+        collections: proofs.into_iter().map(collection_proof_response).collect(),
+    })
+}
+
 // ── Phase 3.1: object-store handlers ─────────────────────────────────────────
 
 #[derive(serde::Serialize)]
@@ -2995,6 +5805,8 @@ async fn crypto_status_handler(
 #[derive(Serialize)]
 struct IndexConfigResponse {
     index_type: String,
+    /// Every index a search can select with `"index"`, primary first.
+    indexes: Vec<String>,
     hnsw: Option<HnswConfigView>,
 }
 
@@ -3008,14 +5820,10 @@ struct HnswConfigView {
 
 async fn index_config_handler(State(state): State<SharedEngine>) -> impl IntoResponse {
     let engine = state.read().await;
-    let index_type = match engine.index_kind {
-        crate::config::IndexKind::BruteForce => "brute_force",
-        crate::config::IndexKind::Hnsw => "hnsw",
-        crate::config::IndexKind::Ivf => "ivf",
-        crate::config::IndexKind::Bq => "bq",
-        crate::config::IndexKind::Auto => "auto",
-    };
-    let hnsw = if engine.index_kind == crate::config::IndexKind::Hnsw {
+    let index_type = engine.index_kind.name();
+    let hnsw = if engine.index_kind == crate::config::IndexKind::Hnsw
+        || engine.named_indexes.contains_key("hnsw")
+    {
         let c = &engine.hnsw_config;
         Some(HnswConfigView {
             m: c.m,
@@ -3028,13 +5836,14 @@ async fn index_config_handler(State(state): State<SharedEngine>) -> impl IntoRes
     };
     Json(IndexConfigResponse {
         index_type: index_type.into(),
+        indexes: engine.index_names().into_iter().map(String::from).collect(),
         hnsw,
     })
 }
 
 /// `POST /v1/index/rebuild` — switch the active index type and rebuild it.
 ///
-/// Body: `{"index": "auto" | "brute" | "bq" | "hnsw" | "ivf"}`
+/// Body: `{"index": "auto" | "brute" | "bq" | "sq" | "hnsw" | "ivf"}`
 ///
 /// The node immediately discards the current index, sets `index_kind` to the
 /// requested type, and rebuilds from the live record pool.  For `"auto"` the
@@ -3052,6 +5861,7 @@ async fn index_rebuild_handler(
         "hnsw" => IndexKind::Hnsw,
         "ivf" => IndexKind::Ivf,
         "bq" => IndexKind::Bq,
+        "sq" => IndexKind::Sq,
         "auto" | "mstg" => IndexKind::Auto,
         _ => IndexKind::BruteForce,
     };
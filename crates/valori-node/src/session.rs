@@ -0,0 +1,242 @@
+// Copyright (c) 2025 Varshith Gudur. Dual-licensed under MIT OR Apache-2.0.
+//! `SessionRegistry` — server-side write sessions with savepoints.
+//!
+//! A session buffers transaction operations without committing anything:
+//! the buffer is the journal's "shadow" side held open across requests.
+//! Each append is dry-run against live state so a bad operation is refused
+//! right away, but nothing reaches the event log until `commit`, which sends
+//! the whole buffer through [`crate::engine::Engine::commit_transaction_ns`]
+//! as one event batch. `rollback` drops the buffer, or just the tail after
+//! a savepoint, and never touches the log.
+//!
+//! Sessions live in memory only. A restart or an idle timeout abandons
+//! them, which is the safe outcome: nothing of a session is durable until
+//! it commits.
+
+use std::collections::HashMap;
+use std::sync::{Arc, Mutex};
+use std::time::{Duration, Instant};
+
+use crate::engine::TxOp;
+
+/// Open sessions per node; opening one more fails until others end.
+pub const MAX_OPEN_SESSIONS: usize = 1024;
+
+/// Sessions untouched for this long are abandoned.
+pub const SESSION_IDLE_TIMEOUT: Duration = Duration::from_secs(60 * 60);
+
+#[derive(Debug, thiserror::Error, PartialEq)]
+pub enum SessionError {
+    #[error("session not found")]
+    NotFound,
+    #[error("too many open sessions ({0} max)")]
+    TooManySessions(usize),
+    #[error("unknown savepoint '{0}'")]
+    UnknownSavepoint(String),
+}
+
+/// One open session: its collection, buffered operations and savepoints.
+#[derive(Debug)]
+pub struct Session {
+    pub id: String,
+    pub collection: Option<String>,
+    ops: Vec<TxOp>,
+    /// Savepoint name and buffer length when it was taken, oldest first.
+    savepoints: Vec<(String, usize)>,
+    last_used: Instant,
+    /// Set once committed or abandoned, for requests that were waiting on
+    /// the session lock.
+    closed: bool,
+}
+
+impl Session {
+    pub fn ops(&self) -> &[TxOp] {
+        &self.ops
+    }
+
+    pub fn savepoints(&self) -> &[(String, usize)] {
+        &self.savepoints
+    }
+
+    pub fn push(&mut self, ops: Vec<TxOp>) {
+        self.ops.extend(ops);
+    }
+
+    /// Mark the current buffer length as `name`. Reusing a name moves the
+    /// savepoint. Returns the number of operations it keeps.
+    pub fn savepoint(&mut self, name: &str) -> usize {
+        self.savepoints.retain(|(n, _)| n != name);
+        self.savepoints.push((name.to_string(), self.ops.len()));
+        self.ops.len()
+    }
+
+    /// Drop the operations buffered after savepoint `name`, and the
+    /// savepoints taken after it, or everything with `None`. The savepoint
+    /// itself stays. Returns the number of operations discarded.
+    pub fn rollback(&mut self, name: Option<&str>) -> Result<usize, SessionError> {
+        let (keep_ops, keep_savepoints) = match name {
+            None => (0, 0),
+            Some(name) => {
+                let pos = self
+                    .savepoints
+                    .iter()
+                    .position(|(n, _)| n == name)
+                    .ok_or_else(|| SessionError::UnknownSavepoint(name.to_string()))?;
+                (self.savepoints[pos].1, pos + 1)
+            }
+        };
+        let discarded = self.ops.len() - keep_ops;
+        self.ops.truncate(keep_ops);
+        self.savepoints.truncate(keep_savepoints);
+        Ok(discarded)
+    }
+}
+
+/// Open sessions by id. Each session has its own async lock, so one slow
+/// commit does not hold up the others.
+pub struct SessionRegistry {
+    sessions: Mutex<HashMap<String, Arc<tokio::sync::Mutex<Session>>>>,
+    capacity: usize,
+    idle_timeout: Duration,
+}
+
+impl SessionRegistry {
+    pub fn new(capacity: usize, idle_timeout: Duration) -> Self {
+        Self {
+            sessions: Mutex::new(HashMap::new()),
+            capacity,
+            idle_timeout,
+        }
+    }
+
+    /// Open an empty session on `collection` and return its id.
+    pub fn open(&self, collection: Option<String>) -> Result<String, SessionError> {
+        let mut sessions = self.sessions.lock().unwrap();
+        let now = Instant::now();
+        // Sessions locked by a request are in use, not idle.
+        sessions.retain(|_, s| {
+            s.try_lock().map_or(true, |s| {
+                now.duration_since(s.last_used) < self.idle_timeout
+            })
+        });
+        if sessions.len() >= self.capacity {
+            return Err(SessionError::TooManySessions(self.capacity));
+        }
+        let id = format!("sess_{}", valori_core::id::ExecutionId::new_random());
+        let session = Session {
+            id: id.clone(),
+            collection,
+            ops: Vec::new(),
+            savepoints: Vec::new(),
+            last_used: now,
+            closed: false,
+        };
+        sessions.insert(id.clone(), Arc::new(tokio::sync::Mutex::new(session)));
+        Ok(id)
+    }
+
+    /// Lock session `id` for one request. Idle sessions past the timeout
+    /// are abandoned here rather than handed out.
+    pub async fn lock(
+        &self,
+        id: &str,
+    ) -> Result<tokio::sync::OwnedMutexGuard<Session>, SessionError> {
+        let session = self
+            .sessions
+            .lock()
+            .unwrap()
+            .get(id)
+            .cloned()
+            .ok_or(SessionError::NotFound)?;
+        let mut guard = session.lock_owned().await;
+        if guard.closed {
+            return Err(SessionError::NotFound);
+        }
+        if guard.last_used.elapsed() >= self.idle_timeout {
+            self.close(&mut guard);
+            return Err(SessionError::NotFound);
+        }
+        guard.last_used = Instant::now();
+        Ok(guard)
+    }
+
+    /// End a locked session: after a commit, or when it is abandoned.
+    pub fn close(&self, session: &mut Session) {
+        session.closed = true;
+        self.sessions.lock().unwrap().remove(&session.id);
+    }
+
+    pub fn len(&self) -> usize {
+        self.sessions.lock().unwrap().len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.len() == 0
+    }
+}
+
+impl Default for SessionRegistry {
+    fn default() -> Self {
+        Self::new(MAX_OPEN_SESSIONS, SESSION_IDLE_TIMEOUT)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn set(key: &str) -> TxOp {
+        TxOp::SetMeta {
+            key: key.to_string(),
+            value: serde_json::Value::Null,
+        }
+    }
+
+    #[tokio::test]
+    async fn rollback_to_savepoint_keeps_earlier_operations() {
+        let registry = SessionRegistry::default();
+        let id = registry.open(None).unwrap();
+        let mut s = registry.lock(&id).await.unwrap();
+        s.push(vec![set("a")]);
+        assert_eq!(s.savepoint("one"), 1);
+        s.push(vec![set("b"), set("c")]);
+        s.savepoint("two");
+        s.push(vec![set("d")]);
+
+        assert_eq!(s.rollback(Some("one")), Ok(3));
+        assert_eq!(s.ops(), &[set("a")]);
+        assert_eq!(s.savepoints().len(), 1, "later savepoints are dropped");
+        assert_eq!(
+            s.rollback(Some("two")),
+            Err(SessionError::UnknownSavepoint("two".into()))
+        );
+        assert_eq!(s.rollback(None), Ok(1));
+        assert!(s.ops().is_empty() && s.savepoints().is_empty());
+    }
+
+    #[tokio::test]
+    async fn closed_and_idle_sessions_are_gone() {
+        let registry = SessionRegistry::new(1, Duration::ZERO);
+        let id = registry.open(None).unwrap();
+        assert_eq!(
+            registry.lock(&id).await.unwrap_err(),
+            SessionError::NotFound
+        );
+        assert!(registry.is_empty());
+
+        let registry = SessionRegistry::new(1, SESSION_IDLE_TIMEOUT);
+        let id = registry.open(None).unwrap();
+        assert_eq!(
+            registry.open(None).unwrap_err(),
+            SessionError::TooManySessions(1)
+        );
+        let mut s = registry.lock(&id).await.unwrap();
+        registry.close(&mut s);
+        drop(s);
+        assert_eq!(
+            registry.lock(&id).await.unwrap_err(),
+            SessionError::NotFound
+        );
+        assert!(registry.open(None).is_ok());
+    }
+}
@@ -0,0 +1,83 @@
+// Copyright (c) 2025 Varshith Gudur. Dual-licensed under MIT OR Apache-2.0.
+//! Slow query log (`VALORI_SLOW_QUERY_MS`, `slow_query_ms` in
+//! `/v1/admin/config`).
+//!
+//! Search and insert handlers start a [`QueryTimer`] on entry. The timer
+//! tracks how long the handler waited for the engine lock separately from
+//! the rest. When it is dropped and the total is over the threshold, one
+//! `warn` line is logged with the query's parameters and
+//! `valori_slow_queries_total{op}` is counted. The line sits inside the
+//! request span, so it also carries the `request_id`. Dropping the timer
+//! covers error returns too. A failing query that is slow is just as
+//! interesting.
+
+use std::time::{Duration, Instant};
+
+/// Parameters worth seeing next to a slow query. Unset fields are logged
+/// as `None`.
+#[derive(Debug, Default, Clone)]
+pub struct QueryParams {
+    /// Vector dimension of the query or of each inserted vector.
+    pub dim: usize,
+    /// Results requested (search) or vectors sent (batch insert).
+    pub k: Option<usize>,
+    /// HNSW `ef_search` in effect.
+    pub ef: Option<usize>,
+    pub index: Option<String>,
+    pub collection: Option<String>,
+    /// The metadata filter, as JSON.
+    pub filter: Option<String>,
+}
+
+pub struct QueryTimer {
+    op: &'static str,
+    threshold: Option<Duration>,
+    started: Instant,
+    lock_wait: Duration,
+    pub params: QueryParams,
+}
+
+impl QueryTimer {
+    /// Start timing `op` ("search", "insert", "batch_insert"). `None`
+    /// turns the log off.
+    pub fn start(op: &'static str, threshold: Option<Duration>) -> Self {
+        Self {
+            op,
+            threshold,
+            started: Instant::now(),
+            lock_wait: Duration::ZERO,
+            params: QueryParams::default(),
+        }
+    }
+
+    /// Count the time since `since` as lock wait. Call it right after
+    /// acquiring the engine lock, with the instant taken right before.
+    pub fn waited(&mut self, since: Instant) {
+        self.lock_wait += since.elapsed();
+    }
+}
+
+impl Drop for QueryTimer {
+    fn drop(&mut self) {
+        let total = self.started.elapsed();
+        match self.threshold {
+            Some(t) if total > t => {}
+            _ => return,
+        }
+        metrics::counter!("valori_slow_queries_total", 1, "op" => self.op);
+        let p = &self.params;
+        tracing::warn!(
+            op = self.op,
+            total_ms = total.as_millis() as u64,
+            lock_wait_ms = self.lock_wait.as_millis() as u64,
+            compute_ms = total.saturating_sub(self.lock_wait).as_millis() as u64,
+            dim = p.dim,
+            k = ?p.k,
+            ef = ?p.ef,
+            index = ?p.index,
+            collection = ?p.collection,
+            filter = ?p.filter,
+            "slow query"
+        );
+    }
+}
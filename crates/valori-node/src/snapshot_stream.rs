@@ -0,0 +1,133 @@
+// Copyright (c) 2025 Varshith Gudur. Dual-licensed under MIT OR Apache-2.0.
+//! Serving `GET /v1/snapshot/download` from disk.
+//!
+//! Encoding a snapshot needs the engine (or state machine) lock and yields
+//! the whole container in memory. Returning that buffer as the response
+//! body kept a full copy of the store alive for as long as the transfer
+//! took, once per concurrent download. Instead the container is spooled to
+//! a file and dropped before the lock is released, and the file is streamed
+//! back with its length and BLAKE3 hash in the headers. The spool file is
+//! removed when the response body is dropped, finished or not.
+
+use axum::{
+    body::Body,
+    http::{header, HeaderValue},
+    response::{IntoResponse, Response},
+};
+use futures::StreamExt;
+use std::io::Write;
+use std::path::{Path, PathBuf};
+use std::sync::atomic::{AtomicU64, Ordering};
+use tokio_util::io::ReaderStream;
+
+/// Response header carrying the BLAKE3 hash (hex) of the snapshot bytes —
+/// the same hash `POST /v1/snapshot/upload/init` takes.
+pub const SNAPSHOT_HASH_HEADER: &str = "x-valori-snapshot-hash";
+
+/// Response header carrying the `final_state_hash` (hex) of the state the
+/// snapshot holds, taken under the same lock as the snapshot.
+pub const STATE_HASH_HEADER: &str = "x-valori-state-hash";
+
+/// Response header carrying the committed height the snapshot was taken at.
+pub const HEIGHT_HEADER: &str = "x-valori-height";
+
+static SPOOL_SEQ: AtomicU64 = AtomicU64::new(0);
+
+/// A snapshot written to a spool file, removed on drop.
+#[derive(Debug)]
+pub struct SpooledSnapshot {
+    path: PathBuf,
+    len: u64,
+    hash: String,
+}
+
+impl SpooledSnapshot {
+    /// Write `bytes` to a fresh file under `dir`, hashing as it goes.
+    pub fn write(dir: &Path, bytes: &[u8]) -> std::io::Result<Self> {
+        std::fs::create_dir_all(dir)?;
+        let path = dir.join(format!(
+            ".download-{}-{}.snap",
+            std::process::id(),
+            SPOOL_SEQ.fetch_add(1, Ordering::Relaxed)
+        ));
+        let spool = Self {
+            path,
+            len: bytes.len() as u64,
+            hash: blake3::hash(bytes).to_hex().to_string(),
+        };
+        let mut file = std::fs::File::create(&spool.path)?;
+        file.write_all(bytes)?;
+        Ok(spool)
+    }
+
+    pub fn len(&self) -> u64 {
+        self.len
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.len == 0
+    }
+
+    pub fn hash(&self) -> &str {
+        &self.hash
+    }
+
+    /// Stream the file as an `application/octet-stream` attachment named
+    /// `filename`, with `Content-Length` and [`SNAPSHOT_HASH_HEADER`].
+    pub async fn into_response(self, filename: &str) -> std::io::Result<Response> {
+        let file = tokio::fs::File::open(&self.path).await?;
+        let len = self.len;
+        let hash = HeaderValue::from_str(&self.hash).expect("hex is a valid header value");
+        let disposition = HeaderValue::from_str(&format!("attachment; filename=\"{filename}\""))
+            .unwrap_or(HeaderValue::from_static("attachment"));
+        // The stream owns the spool, so the file outlives the transfer.
+        let body = ReaderStream::new(file).map(move |chunk| {
+            let _spool = &self;
+            chunk
+        });
+        let mut resp = Body::from_stream(body).into_response();
+        let headers = resp.headers_mut();
+        headers.insert(
+            header::CONTENT_TYPE,
+            HeaderValue::from_static("application/octet-stream"),
+        );
+        headers.insert(header::CONTENT_LENGTH, HeaderValue::from(len));
+        headers.insert(header::CONTENT_DISPOSITION, disposition);
+        headers.insert(SNAPSHOT_HASH_HEADER, hash);
+        Ok(resp)
+    }
+}
+
+impl Drop for SpooledSnapshot {
+    fn drop(&mut self) {
+        let _ = std::fs::remove_file(&self.path);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[tokio::test]
+    async fn streams_the_bytes_and_removes_the_spool() {
+        let dir = tempfile::tempdir().unwrap();
+        let bytes: Vec<u8> = (0..200_000u32).map(|i| i as u8).collect();
+        let spool = SpooledSnapshot::write(dir.path(), &bytes).unwrap();
+        assert_eq!(spool.len(), bytes.len() as u64);
+        assert_eq!(spool.hash(), blake3::hash(&bytes).to_hex().as_str());
+        let path = spool.path.clone();
+
+        let resp = spool.into_response("s.snap").await.unwrap();
+        assert_eq!(resp.headers()[header::CONTENT_LENGTH], "200000");
+        assert_eq!(
+            resp.headers()[SNAPSHOT_HASH_HEADER],
+            blake3::hash(&bytes).to_hex().as_str()
+        );
+        assert!(path.exists(), "spool kept while the body is alive");
+        let body = axum::body::to_bytes(resp.into_body(), usize::MAX)
+            .await
+            .unwrap();
+        assert_eq!(&body[..], &bytes[..]);
+        assert!(!path.exists(), "spool removed with the body");
+    }
+}
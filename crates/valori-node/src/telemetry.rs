@@ -1,20 +1,25 @@
 // Copyright (c) 2025 Varshith Gudur. Dual-licensed under MIT OR Apache-2.0.
 use metrics_exporter_prometheus::{PrometheusBuilder, PrometheusHandle};
 use std::sync::OnceLock;
-use tracing_subscriber::{layer::SubscriberExt, util::SubscriberInitExt};
+use tracing_subscriber::{layer::SubscriberExt, reload, util::SubscriberInitExt, EnvFilter};
 
 static PROM_HANDLE: OnceLock<PrometheusHandle> = OnceLock::new();
 
+type FilterHandle = reload::Handle<EnvFilter, tracing_subscriber::Registry>;
+static LOG_FILTER: OnceLock<FilterHandle> = OnceLock::new();
+
 /// Initialize telemetry (logs + metrics)
 pub fn init_telemetry() {
-    // 1. Initialize Tracing (Logs)
+    // 1. Initialize Tracing (Logs). The filter sits behind a reload layer so
+    // `PATCH /v1/admin/config` can change the log level without a restart.
+    let (filter, handle) = reload::Layer::new(EnvFilter::new(
+        std::env::var("RUST_LOG").unwrap_or_else(|_| "valori_node=debug,tower_http=debug".into()),
+    ));
     tracing_subscriber::registry()
-        .with(tracing_subscriber::EnvFilter::new(
-            std::env::var("RUST_LOG")
-                .unwrap_or_else(|_| "valori_node=debug,tower_http=debug".into()),
-        ))
+        .with(filter)
         .with(tracing_subscriber::fmt::layer())
         .init();
+    let _ = LOG_FILTER.set(handle);
 
     // 2. Initialize Metrics (Prometheus)
     let builder = PrometheusBuilder::new();
@@ -123,6 +128,20 @@ pub fn init_telemetry() {
         "Number of times this node detected a state-hash mismatch with any peer"
     );
 
+    // ── Background consistency sentinel ───────────────────────────────────────
+    metrics::describe_gauge!(
+        "valori_consistency_ok",
+        "1 when the last background consistency check passed, 0 on drift"
+    );
+    metrics::describe_counter!(
+        "valori_consistency_drift_detections_total",
+        "Number of background consistency checks that found drift"
+    );
+    metrics::describe_counter!(
+        "valori_forget_records_total",
+        "Records deleted by the forgetting policy"
+    );
+
     // ── Liveness sentinel ─────────────────────────────────────────────────────
     // Ensure at least one gauge exists at startup before any request arrives.
     metrics::gauge!("valori_node_up", 1.0);
@@ -136,3 +155,19 @@ pub fn get_metrics() -> String {
         "# metrics not initialized".to_string()
     }
 }
+
+/// The active log filter directives (`RUST_LOG` syntax), or `None` when
+/// [`init_telemetry`] has not run in this process.
+pub fn log_filter() -> Option<String> {
+    LOG_FILTER.get()?.with_current(|f| f.to_string()).ok()
+}
+
+/// Replace the log filter at runtime, e.g. `"valori_node=info"` or `"warn"`.
+pub fn set_log_filter(directives: &str) -> Result<(), String> {
+    let filter = EnvFilter::try_new(directives).map_err(|e| e.to_string())?;
+    LOG_FILTER
+        .get()
+        .ok_or_else(|| "logging is not reloadable in this process".to_string())?
+        .reload(filter)
+        .map_err(|e| e.to_string())
+}
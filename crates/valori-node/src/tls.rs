@@ -0,0 +1,262 @@
+// Copyright (c) 2025 Varshith Gudur. Dual-licensed under MIT OR Apache-2.0.
+//! Mutual TLS for the standalone leader ↔ follower replication channel.
+//!
+//! The HTTP API is served over TLS with *optional* client certificates:
+//! ordinary SDK clients connect without one, but every connection that does
+//! present a certificate must chain to the replication CA or the handshake
+//! fails. The replication route group (`/v1/replication/*` and
+//! `GET /v1/snapshot/download`) additionally requires that the connection
+//! carried such a verified certificate — a follower that cannot prove it was
+//! issued by this deployment's CA cannot stream the event log or pull a
+//! snapshot, whatever bearer token it sends.
+//!
+//! Env: `VALORI_REPLICATION_TLS_CA` / `_CERT` / `_KEY` (PEM paths). All set →
+//! TLS on; none → plaintext; partially set → startup panic (same rule as the
+//! Raft channel's `VALORI_TLS_*`: half-configured TLS must not run plaintext).
+
+use axum::extract::Request;
+use axum::http::StatusCode;
+use axum::middleware::Next;
+use axum::response::Response;
+use std::future::Future;
+use std::sync::Arc;
+use std::time::Duration;
+use tokio::net::TcpListener;
+
+/// PEM material for the replication channel. The same CA signs the leader's
+/// server certificate and every follower's client certificate.
+#[derive(Clone)]
+pub struct ReplicationTlsConfig {
+    pub ca_pem: Vec<u8>,
+    pub cert_pem: Vec<u8>,
+    pub key_pem: Vec<u8>,
+}
+
+impl std::fmt::Debug for ReplicationTlsConfig {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        // Never print key material.
+        f.debug_struct("ReplicationTlsConfig")
+            .field("ca_pem_len", &self.ca_pem.len())
+            .field("cert_pem_len", &self.cert_pem.len())
+            .field("key_pem", &"<redacted>")
+            .finish()
+    }
+}
+
+impl ReplicationTlsConfig {
+    /// Read `VALORI_REPLICATION_TLS_{CA,CERT,KEY}`. `Ok(None)` when none is set.
+    pub fn from_env() -> Result<Option<Self>, String> {
+        let ca = std::env::var("VALORI_REPLICATION_TLS_CA").ok();
+        let cert = std::env::var("VALORI_REPLICATION_TLS_CERT").ok();
+        let key = std::env::var("VALORI_REPLICATION_TLS_KEY").ok();
+        match (ca, cert, key) {
+            (None, None, None) => Ok(None),
+            (Some(ca), Some(cert), Some(key)) => {
+                let read = |p: &str| std::fs::read(p).map_err(|e| format!("{p}: {e}"));
+                Ok(Some(Self {
+                    ca_pem: read(&ca)?,
+                    cert_pem: read(&cert)?,
+                    key_pem: read(&key)?,
+                }))
+            }
+            _ => Err("replication TLS is partially configured — set all of \
+                 VALORI_REPLICATION_TLS_CA, VALORI_REPLICATION_TLS_CERT, \
+                 VALORI_REPLICATION_TLS_KEY, or none"
+                .to_string()),
+        }
+    }
+
+    /// Server side: present our certificate, verify any client certificate
+    /// against the CA, but let certificate-less clients through the handshake.
+    pub fn server_config(&self) -> Result<Arc<rustls::ServerConfig>, String> {
+        // Pin the provider: the workspace enables both ring and aws-lc-rs, so
+        // rustls cannot pick a process default on its own.
+        let provider = Arc::new(rustls::crypto::ring::default_provider());
+        let roots = self.root_store()?;
+        let verifier = rustls::server::WebPkiClientVerifier::builder_with_provider(
+            Arc::new(roots),
+            provider.clone(),
+        )
+        .allow_unauthenticated()
+        .build()
+        .map_err(|e| format!("client verifier: {e}"))?;
+        let certs = rustls_pemfile::certs(&mut self.cert_pem.as_slice())
+            .collect::<Result<Vec<_>, _>>()
+            .map_err(|e| format!("certificate PEM: {e}"))?;
+        let key = rustls_pemfile::private_key(&mut self.key_pem.as_slice())
+            .map_err(|e| format!("private key PEM: {e}"))?
+            .ok_or_else(|| "private key PEM: no key found".to_string())?;
+        let mut cfg = rustls::ServerConfig::builder_with_provider(provider)
+            .with_safe_default_protocol_versions()
+            .map_err(|e| e.to_string())?
+            .with_client_cert_verifier(verifier)
+            .with_single_cert(certs, key)
+            .map_err(|e| format!("server certificate: {e}"))?;
+        cfg.alpn_protocols = vec![b"h2".to_vec(), b"http/1.1".to_vec()];
+        Ok(Arc::new(cfg))
+    }
+
+    /// Client side: a reqwest client that trusts only the replication CA and
+    /// presents our certificate as its identity.
+    pub fn client_builder(
+        &self,
+        builder: reqwest::ClientBuilder,
+    ) -> Result<reqwest::ClientBuilder, String> {
+        let ca = reqwest::Certificate::from_pem(&self.ca_pem).map_err(|e| e.to_string())?;
+        let mut pem = self.cert_pem.clone();
+        pem.push(b'\n');
+        pem.extend_from_slice(&self.key_pem);
+        let identity = reqwest::Identity::from_pem(&pem).map_err(|e| e.to_string())?;
+        Ok(builder
+            .use_rustls_tls()
+            .tls_built_in_root_certs(false)
+            .add_root_certificate(ca)
+            .identity(identity))
+    }
+
+    fn root_store(&self) -> Result<rustls::RootCertStore, String> {
+        let mut roots = rustls::RootCertStore::empty();
+        for cert in rustls_pemfile::certs(&mut self.ca_pem.as_slice()) {
+            let cert = cert.map_err(|e| format!("CA PEM: {e}"))?;
+            roots
+                .add(cert)
+                .map_err(|e| format!("CA certificate: {e}"))?;
+        }
+        if roots.is_empty() {
+            return Err("CA PEM: no certificates found".to_string());
+        }
+        Ok(roots)
+    }
+}
+
+/// Attached to every request served by [`serve_tls`]. Absent on plaintext.
+#[derive(Debug, Clone, Copy)]
+pub struct TlsConnection {
+    /// The client presented a certificate that chains to the replication CA.
+    pub verified_client: bool,
+}
+
+/// Route-group guard: on a TLS listener, replication routes require a
+/// CA-verified client certificate. A no-op on plaintext listeners.
+pub async fn replication_mtls_guard(req: Request, next: Next) -> Result<Response, StatusCode> {
+    if let Some(conn) = req.extensions().get::<TlsConnection>() {
+        if !conn.verified_client
+            && crate::api_keys::is_replication_route(req.method(), req.uri().path())
+        {
+            return Err(StatusCode::FORBIDDEN);
+        }
+    }
+    Ok(next.run(req).await)
+}
+
+/// How long [`serve_tls`] waits for open connections to finish their
+/// in-flight requests after shutdown before dropping them.
+pub const TLS_DRAIN_TIMEOUT: Duration = Duration::from_secs(30);
+
+/// Serve `router` over TLS until `shutdown` resolves. Each connection is
+/// tagged with a [`TlsConnection`] so [`replication_mtls_guard`] can see
+/// whether the peer authenticated.
+///
+/// On shutdown the listener stops accepting, every open connection is told
+/// to finish its in-flight requests and close, and the call returns once
+/// they have — or after [`TLS_DRAIN_TIMEOUT`], aborting the stragglers —
+/// the same contract `axum::serve(..).with_graceful_shutdown` gives the
+/// plaintext listener.
+pub async fn serve_tls<F>(
+    listener: TcpListener,
+    router: axum::Router,
+    tls: Arc<rustls::ServerConfig>,
+    shutdown: F,
+) where
+    F: Future<Output = ()> + Send,
+{
+    serve_tls_with_drain(listener, router, tls, shutdown, TLS_DRAIN_TIMEOUT).await
+}
+
+/// [`serve_tls`] with an explicit drain deadline.
+pub async fn serve_tls_with_drain<F>(
+    listener: TcpListener,
+    router: axum::Router,
+    tls: Arc<rustls::ServerConfig>,
+    shutdown: F,
+    drain_timeout: Duration,
+) where
+    F: Future<Output = ()> + Send,
+{
+    use tower::ServiceExt;
+
+    let acceptor = tokio_rustls::TlsAcceptor::from(tls);
+    let (stop_tx, stop_rx) = tokio::sync::watch::channel(false);
+    let mut connections = tokio::task::JoinSet::new();
+    tokio::pin!(shutdown);
+    loop {
+        let (tcp, peer) = tokio::select! {
+            _ = &mut shutdown => break,
+            // Reap finished connections so the set does not grow unbounded.
+            Some(_) = connections.join_next(), if !connections.is_empty() => continue,
+            accepted = listener.accept() => match accepted {
+                Ok(a) => a,
+                Err(e) => {
+                    tracing::warn!("accept failed: {e}");
+                    continue;
+                }
+            },
+        };
+        let acceptor = acceptor.clone();
+        let router = router.clone();
+        let mut stop = stop_rx.clone();
+        connections.spawn(async move {
+            let stream = match acceptor.accept(tcp).await {
+                Ok(s) => s,
+                Err(e) => {
+                    tracing::warn!("TLS handshake with {peer} failed: {e}");
+                    return;
+                }
+            };
+            let conn = TlsConnection {
+                verified_client: stream.get_ref().1.peer_certificates().is_some(),
+            };
+            let svc = hyper::service::service_fn(move |mut req: Request<hyper::body::Incoming>| {
+                req.extensions_mut().insert(conn);
+                router.clone().oneshot(req.map(axum::body::Body::new))
+            });
+            let io = hyper_util::rt::TokioIo::new(stream);
+            let builder =
+                hyper_util::server::conn::auto::Builder::new(hyper_util::rt::TokioExecutor::new());
+            let served = builder.serve_connection(io, svc);
+            tokio::pin!(served);
+            let result = tokio::select! {
+                res = served.as_mut() => res,
+                _ = stop.changed() => {
+                    served.as_mut().graceful_shutdown();
+                    served.await
+                }
+            };
+            if let Err(e) = result {
+                tracing::debug!("connection from {peer} ended: {e}");
+            }
+        });
+    }
+
+    drop(listener);
+    let _ = stop_tx.send(true);
+    if connections.is_empty() {
+        return;
+    }
+    tracing::info!(
+        "TLS listener closed — draining {} connection(s)",
+        connections.len()
+    );
+    let drained = tokio::time::timeout(drain_timeout, async {
+        while connections.join_next().await.is_some() {}
+    })
+    .await;
+    if drained.is_err() {
+        tracing::warn!(
+            "{} TLS connection(s) still open after {:?}; aborting",
+            connections.len(),
+            drain_timeout
+        );
+        connections.shutdown().await;
+    }
+}
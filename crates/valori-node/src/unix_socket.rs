@@ -0,0 +1,100 @@
+// Copyright (c) 2025 Varshith Gudur. Dual-licensed under MIT OR Apache-2.0.
+//! The HTTP API over a Unix domain socket, for sidecar deployments.
+//!
+//! An application container talking to a node on the same host can skip
+//! the TCP stack entirely and keep the API off the network: the socket is
+//! reachable only through the filesystem, so its permissions are the access
+//! control. The router is the same one served on `VALORI_BIND` — auth, rate
+//! limits and every route behave identically.
+//!
+//! Env: `VALORI_UNIX_SOCKET=/run/valori/valori.sock` (or `unix_socket` in the
+//! config file, or `--unix-socket`). Clients use e.g.
+//! `curl --unix-socket /run/valori/valori.sock http://localhost/health`.
+
+use axum::extract::Request;
+use std::future::Future;
+use std::io;
+use std::path::{Path, PathBuf};
+use tokio::net::UnixListener;
+
+/// A bound socket; the socket file is removed when this is dropped.
+pub struct UnixSocketListener {
+    listener: UnixListener,
+    path: PathBuf,
+}
+
+impl UnixSocketListener {
+    /// Bind `path`, replacing a stale socket file left by a crashed node.
+    /// A socket something is still listening on is `AddrInUse`, and a
+    /// regular file at `path` is never removed.
+    pub fn bind(path: &Path) -> io::Result<Self> {
+        use std::os::unix::fs::FileTypeExt;
+
+        if let Ok(meta) = std::fs::symlink_metadata(path) {
+            if !meta.file_type().is_socket() {
+                return Err(io::Error::new(
+                    io::ErrorKind::AlreadyExists,
+                    format!("{} exists and is not a socket", path.display()),
+                ));
+            }
+            if std::os::unix::net::UnixStream::connect(path).is_ok() {
+                return Err(io::Error::new(
+                    io::ErrorKind::AddrInUse,
+                    format!("{} is in use by another process", path.display()),
+                ));
+            }
+            std::fs::remove_file(path)?;
+        }
+        Ok(Self {
+            listener: UnixListener::bind(path)?,
+            path: path.to_path_buf(),
+        })
+    }
+
+    pub fn path(&self) -> &Path {
+        &self.path
+    }
+}
+
+impl Drop for UnixSocketListener {
+    fn drop(&mut self) {
+        let _ = std::fs::remove_file(&self.path);
+    }
+}
+
+/// Serve `router` on `listener` until `shutdown` resolves, then remove the
+/// socket file. HTTP/1.1 and h2c are both accepted.
+pub async fn serve_unix<F>(listener: UnixSocketListener, router: axum::Router, shutdown: F)
+where
+    F: Future<Output = ()> + Send,
+{
+    use tower::ServiceExt;
+
+    tokio::pin!(shutdown);
+    loop {
+        let stream = tokio::select! {
+            _ = &mut shutdown => return,
+            accepted = listener.listener.accept() => match accepted {
+                Ok((stream, _)) => stream,
+                Err(e) => {
+                    tracing::warn!("unix socket accept failed: {e}");
+                    continue;
+                }
+            },
+        };
+        let router = router.clone();
+        tokio::spawn(async move {
+            let svc = hyper::service::service_fn(move |req: Request<hyper::body::Incoming>| {
+                router.clone().oneshot(req.map(axum::body::Body::new))
+            });
+            let io = hyper_util::rt::TokioIo::new(stream);
+            if let Err(e) =
+                hyper_util::server::conn::auto::Builder::new(hyper_util::rt::TokioExecutor::new())
+                    .serve_connection(io, svc)
+                    .await
+            {
+                tracing::debug!("unix socket connection ended: {e}");
+            }
+        });
+    }
+}
@@ -6,6 +6,10 @@
 //! 2. `as_of` (ISO 8601 timestamp) — search the state as it existed at a past moment.
 //! 3. `GET /v1/timeline` — structured JSON, total count, correct event types.
 //! 4. `GET /v1/timeline?from=<>&to=<>` — timestamp range filter.
+//! 5. `GET /v1/diff?from=<>&to=<>` — structural diff between two heights.
+//! 6. `GET /v1/analytics/drift` — per-tag centroid drift over the journal.
+//! 7. `VALORI_ANOMALY_K` — insert-time outlier flags in `GET /v1/timeline`.
+//! 8. `VALORI_FORGET_EVERY_EVENTS` — unused records are deleted at height intervals.
 
 use std::sync::Arc;
 use tempfile::TempDir;
@@ -18,6 +22,12 @@ use valori_node::EngineFromNodeConfig;
 // ── helpers ─────────────────────────────────────────────────────────────────
 
 async fn spawn_node_with_event_log() -> (reqwest::Client, String, TempDir) {
+    spawn_node_with(|_| {}).await
+}
+
+async fn spawn_node_with(
+    tweak: impl FnOnce(&mut NodeConfig),
+) -> (reqwest::Client, String, TempDir) {
     let dir = TempDir::new().unwrap();
     let log_path = dir.path().join("events.log");
 
@@ -27,6 +37,7 @@ async fn spawn_node_with_event_log() -> (reqwest::Client, String, TempDir) {
     cfg.max_nodes = 100;
     cfg.max_edges = 100;
     cfg.event_log_path = Some(log_path); // Engine::new sets up the event committer from this
+    tweak(&mut cfg);
 
     let state = Arc::new(RwLock::new(Engine::new(&cfg)));
 
@@ -231,3 +242,235 @@ async fn timeline_from_filter_excludes_past_events() {
     );
     assert!(body["from_unix"].is_number(), "from_unix must be present");
 }
+
+/// GET /v1/diff reports record and graph deltas between two heights, with
+/// per-section hashes that only move for the sections that changed.
+#[tokio::test]
+async fn diff_between_heights_reports_structural_deltas() {
+    let (client, base, _dir) = spawn_node_with_event_log().await;
+    for i in 0..3 {
+        insert(&client, &base, [i as f32, 0.0, 0.0, 0.0]).await;
+    }
+    for record_id in [0, 1] {
+        let resp = client
+            .post(format!("{base}/v1/graph/node"))
+            .json(&serde_json::json!({ "record_id": record_id, "kind": 0 }))
+            .send()
+            .await
+            .unwrap();
+        assert!(resp.status().is_success());
+    }
+    // Height 5: three records, two nodes.
+    let resp = client
+        .post(format!("{base}/v1/graph/edge"))
+        .json(&serde_json::json!({ "from": 0, "to": 1, "kind": 0 }))
+        .send()
+        .await
+        .unwrap();
+    assert!(resp.status().is_success());
+    let resp = client
+        .post(format!("{base}/v1/delete"))
+        .json(&serde_json::json!({ "id": 2 }))
+        .send()
+        .await
+        .unwrap();
+    assert!(resp.status().is_success());
+    insert(&client, &base, [9.0, 0.0, 0.0, 0.0]).await;
+
+    let diff: serde_json::Value = client
+        .get(format!("{base}/v1/diff?from=5&to=8"))
+        .send()
+        .await
+        .unwrap()
+        .json()
+        .await
+        .unwrap();
+    assert_eq!(diff["records_added"], serde_json::json!([3]));
+    assert_eq!(diff["records_removed"], serde_json::json!([2]));
+    assert_eq!(diff["nodes_added"], serde_json::json!([]));
+    assert_eq!(diff["edges_added"][0]["from"], 0);
+    assert_eq!(diff["edges_added"][0]["to"], 1);
+    assert_ne!(
+        diff["from_sections"]["records"],
+        diff["to_sections"]["records"]
+    );
+    assert_eq!(diff["from_sections"]["nodes"], diff["to_sections"]["nodes"]);
+    assert_ne!(diff["from_sections"]["edges"], diff["to_sections"]["edges"]);
+    assert_ne!(diff["from_state_hash"], diff["to_state_hash"]);
+
+    let empty: serde_json::Value = client
+        .get(format!("{base}/v1/diff?from=8&to=8"))
+        .send()
+        .await
+        .unwrap()
+        .json()
+        .await
+        .unwrap();
+    assert_eq!(empty["from_sections"], empty["to_sections"]);
+    assert_eq!(empty["records_changed"], serde_json::json!([]));
+
+    let resp = client
+        .get(format!("{base}/v1/diff?from=0&to=99"))
+        .send()
+        .await
+        .unwrap();
+    assert_eq!(resp.status().as_u16(), 400);
+}
+
+#[tokio::test]
+async fn drift_flags_recent_inserts_that_leave_the_history() {
+    let (client, base, _dir) = spawn_node_with_event_log().await;
+    for i in 0..6 {
+        let x = if i % 2 == 0 { 1.0 } else { -1.0 };
+        insert(&client, &base, [x, 0.0, 0.0, 0.0]).await;
+    }
+    let get = |q: &'static str| {
+        let client = client.clone();
+        let url = format!("{base}/v1/analytics/drift?{q}");
+        async move { client.get(url).send().await.unwrap() }
+    };
+
+    let steady: serde_json::Value = get("window=2").await.json().await.unwrap();
+    assert_eq!(steady["drifted_tags"], serde_json::json!([]));
+
+    for _ in 0..2 {
+        insert(&client, &base, [0.0, 5.0, 0.0, 0.0]).await;
+    }
+    let r: serde_json::Value = get("window=2&buckets=4").await.json().await.unwrap();
+    assert_eq!(r["height"], 8);
+    assert_eq!(r["baseline_height"], 6);
+    assert_eq!(r["drifted_tags"], serde_json::json!([0]));
+    let t = &r["tags"][0];
+    assert_eq!(
+        (t["baseline_count"].as_u64(), t["recent_count"].as_u64()),
+        (Some(6), Some(2))
+    );
+    assert!(t["score"].as_f64().unwrap() > 1.0, "{t}");
+    assert_eq!(t["series"].as_array().unwrap().len(), 4);
+
+    // A high enough threshold accepts the same shift.
+    let r: serde_json::Value = get("window=2&threshold=100").await.json().await.unwrap();
+    assert_eq!(r["drifted_tags"], serde_json::json!([]));
+
+    assert_eq!(
+        get("window=0").await.status(),
+        reqwest::StatusCode::BAD_REQUEST
+    );
+}
+
+/// With anomaly scoring on, a far-away insert is flagged in the timeline and
+/// its score disappears once the record is deleted.
+#[tokio::test]
+async fn timeline_flags_outlier_inserts() {
+    let (client, base, _dir) = spawn_node_with(|cfg| {
+        cfg.anomaly_k = Some(3);
+        cfg.anomaly_threshold = Some(2.0);
+    })
+    .await;
+
+    for x in [0.0, 0.1, 0.2, 0.3] {
+        insert(&client, &base, [x, 0.0, 0.0, 0.0]).await;
+    }
+    let outlier = insert(&client, &base, [0.0, 10.0, 0.0, 0.0]).await;
+
+    let timeline = || {
+        let client = client.clone();
+        let url = format!("{base}/v1/timeline");
+        async move {
+            let body: serde_json::Value =
+                client.get(url).send().await.unwrap().json().await.unwrap();
+            body["events"].as_array().unwrap().clone()
+        }
+    };
+
+    let events = timeline().await;
+    // The first insert had no neighbours to compare against.
+    assert!(events[0].get("anomaly_score").is_none());
+    for ev in &events[1..4] {
+        assert!(ev["anomaly_score"].as_f64().unwrap() < 1.0, "{ev}");
+        assert!(ev.get("outlier").is_none());
+    }
+    assert_eq!(events[4]["record_id"], outlier);
+    assert!(events[4]["anomaly_score"].as_f64().unwrap() > 9.0);
+    assert_eq!(events[4]["outlier"], true);
+
+    let resp = client
+        .post(format!("{base}/v1/delete"))
+        .json(&serde_json::json!({ "id": outlier }))
+        .send()
+        .await
+        .unwrap();
+    assert!(resp.status().is_success());
+    let events = timeline().await;
+    assert!(events[4].get("anomaly_score").is_none());
+}
+
+/// The forgetting policy deletes old, never-searched records once the height
+/// crosses the interval, and spares a record that was searched.
+#[tokio::test]
+async fn forgetting_policy_deletes_unused_records_at_interval() {
+    let (client, base, _dir) = spawn_node_with(|cfg| {
+        let mut policy = valori_engine::ForgetPolicy::every(5);
+        policy.min_age_events = 2;
+        cfg.forget_policy = Some(policy);
+    })
+    .await;
+
+    let a = insert(&client, &base, [1.0, 0.0, 0.0, 0.0]).await;
+    let b = insert(&client, &base, [0.0, 1.0, 0.0, 0.0]).await;
+    let c = insert(&client, &base, [0.0, 0.0, 1.0, 0.0]).await;
+    let resp = client
+        .post(format!("{base}/search"))
+        .json(&serde_json::json!({ "query": [1.0, 0.0, 0.0, 0.0], "k": 1 }))
+        .send()
+        .await
+        .unwrap();
+    assert!(resp.status().is_success());
+    insert(&client, &base, [0.0, 0.0, 0.0, 1.0]).await;
+
+    // Height 4: only `b` is old enough and unused.
+    let preview: serde_json::Value = client
+        .get(format!("{base}/v1/admin/forget"))
+        .send()
+        .await
+        .unwrap()
+        .json()
+        .await
+        .unwrap();
+    assert_eq!(preview.as_array().unwrap().len(), 1, "{preview}");
+    assert_eq!(preview[0]["record_id"], b);
+    assert_eq!(preview[0]["age_events"], 2);
+
+    // Height 5 crosses the interval: `b` and `c` go, searched `a` stays.
+    insert(&client, &base, [0.5, 0.5, 0.0, 0.0]).await;
+    let body: serde_json::Value = client
+        .get(format!("{base}/v1/timeline"))
+        .send()
+        .await
+        .unwrap()
+        .json()
+        .await
+        .unwrap();
+    let deleted: Vec<(String, u64)> = body["events"].as_array().unwrap()[5..]
+        .iter()
+        .map(|e| {
+            (
+                e["event_type"].as_str().unwrap().to_string(),
+                e["record_id"].as_u64().unwrap(),
+            )
+        })
+        .collect();
+    assert_eq!(
+        deleted,
+        vec![
+            ("DeleteRecord".to_string(), b as u64),
+            ("DeleteRecord".to_string(), c as u64)
+        ]
+    );
+    let resp = client
+        .get(format!("{base}/v1/records/{a}"))
+        .send()
+        .await
+        .unwrap();
+    assert!(resp.status().is_success());
+}
@@ -0,0 +1,284 @@
+// Copyright (c) 2025 Varshith Gudur. Dual-licensed under MIT OR Apache-2.0.
+//! HTTP tests for the API audit trail:
+//!   - every protected request is recorded with its principal and route
+//!   - denied requests are recorded without a principal
+//!   - mutating requests carry record ids and the committed event height
+//!   - `GET /v1/audit` is admin-only and pages with `since`
+//!   - a file-backed trail resumes its sequence numbers after reopen
+
+use axum::body::Body;
+use axum::http::{Method, Request, StatusCode};
+use serde_json::Value;
+use std::sync::Arc;
+use tokio::sync::RwLock;
+use tower::ServiceExt;
+
+use valori_node::api_audit::{ApiAuditLog, AuditQuery};
+use valori_node::api_keys::{parse_auth_roles, KeyStore};
+use valori_node::config::NodeConfig;
+use valori_node::engine::Engine;
+use valori_node::server::build_router_with_auth;
+use valori_node::EngineFromNodeConfig;
+
+fn router(cfg: &NodeConfig, audit: Arc<ApiAuditLog>) -> axum::Router {
+    let engine = Arc::new(RwLock::new(Engine::new(cfg)));
+    build_router_with_auth(
+        engine,
+        Some("admin-tok".into()),
+        None,
+        Arc::new(KeyStore::new(None)),
+        Arc::new(valori_effect::ReceiptStore::new(16)),
+        parse_auth_roles("writer=w-tok,reader=r-tok").unwrap(),
+        audit,
+    )
+}
+
+fn tiny_cfg() -> NodeConfig {
+    let mut cfg = NodeConfig::default();
+    cfg.dim = 4;
+    cfg.max_records = 100;
+    cfg.max_nodes = 50;
+    cfg.max_edges = 50;
+    cfg
+}
+
+async fn call(
+    router: axum::Router,
+    method: Method,
+    uri: &str,
+    bearer: &str,
+    body: Option<Value>,
+) -> (StatusCode, Value) {
+    let mut req = Request::builder()
+        .method(method)
+        .uri(uri)
+        .header("authorization", format!("Bearer {bearer}"));
+    let body = match body {
+        Some(b) => {
+            req = req.header("content-type", "application/json");
+            Body::from(serde_json::to_vec(&b).unwrap())
+        }
+        None => Body::empty(),
+    };
+    let resp = router.oneshot(req.body(body).unwrap()).await.unwrap();
+    let status = resp.status();
+    let bytes = axum::body::to_bytes(resp.into_body(), 1 << 20)
+        .await
+        .unwrap();
+    let json = serde_json::from_slice(&bytes).unwrap_or(serde_json::json!(null));
+    (status, json)
+}
+
+#[tokio::test]
+async fn operations_are_recorded_with_principal_ids_and_height() {
+    let tmp = tempfile::tempdir().unwrap();
+    let mut cfg = tiny_cfg();
+    cfg.event_log_path = Some(tmp.path().join("events.log"));
+    let audit = Arc::new(ApiAuditLog::in_memory());
+    let router = router(&cfg, audit.clone());
+
+    let (status, inserted) = call(
+        router.clone(),
+        Method::POST,
+        "/v1/records",
+        "w-tok",
+        Some(serde_json::json!({"values": [1.0, 0.0, 0.0, 0.0]})),
+    )
+    .await;
+    assert_eq!(status, StatusCode::OK, "{inserted}");
+    let id = inserted["id"].as_u64().unwrap();
+
+    let (status, _) = call(
+        router.clone(),
+        Method::GET,
+        &format!("/v1/records/{id}"),
+        "r-tok",
+        None,
+    )
+    .await;
+    assert_eq!(status, StatusCode::OK);
+
+    // reader may not insert — still recorded, with no principal.
+    let (status, _) = call(
+        router.clone(),
+        Method::POST,
+        "/v1/records",
+        "r-tok",
+        Some(serde_json::json!({"values": [0.0, 1.0, 0.0, 0.0]})),
+    )
+    .await;
+    assert_eq!(status, StatusCode::FORBIDDEN);
+
+    let entries = audit.query(&AuditQuery::default()).await;
+    assert_eq!(entries.len(), 3);
+
+    assert_eq!(entries[0].principal.as_deref(), Some("role:read_write"));
+    assert_eq!(entries[0].route, "/v1/records");
+    assert_eq!(entries[0].ids, vec![id]);
+    assert!(entries[0].event_height.unwrap_or(0) >= 1);
+
+    assert_eq!(entries[1].principal.as_deref(), Some("role:read_only"));
+    assert_eq!(entries[1].ids, vec![id]);
+    assert_eq!(entries[1].event_height, None, "reads carry no height");
+
+    assert_eq!(entries[2].principal, None);
+    assert_eq!(entries[2].status, 403);
+
+    assert!(entries.windows(2).all(|w| w[0].seq < w[1].seq));
+}
+
+#[tokio::test]
+async fn audit_endpoint_is_admin_only_and_pages() {
+    let audit = Arc::new(ApiAuditLog::in_memory());
+    let router = router(&tiny_cfg(), audit);
+
+    for _ in 0..3 {
+        let (status, _) = call(
+            router.clone(),
+            Method::POST,
+            "/v1/records",
+            "w-tok",
+            Some(serde_json::json!({"values": [1.0, 0.0, 0.0, 0.0]})),
+        )
+        .await;
+        assert_eq!(status, StatusCode::OK);
+    }
+
+    let (status, _) = call(router.clone(), Method::GET, "/v1/audit", "w-tok", None).await;
+    assert_eq!(status, StatusCode::FORBIDDEN);
+
+    let (status, page) = call(
+        router.clone(),
+        Method::GET,
+        "/v1/audit?limit=2&route=/v1/records",
+        "admin-tok",
+        None,
+    )
+    .await;
+    assert_eq!(status, StatusCode::OK, "{page}");
+    assert_eq!(page["entries"].as_array().unwrap().len(), 2);
+    let next = page["next_since"].as_u64().unwrap();
+
+    let (_, rest) = call(
+        router,
+        Method::GET,
+        &format!("/v1/audit?since={next}&route=/v1/records"),
+        "admin-tok",
+        None,
+    )
+    .await;
+    assert_eq!(rest["entries"].as_array().unwrap().len(), 1);
+}
+
+#[tokio::test]
+async fn file_backed_trail_resumes_sequence() {
+    let tmp = tempfile::tempdir().unwrap();
+    let path = tmp.path().join("api-audit.jsonl");
+
+    let log = ApiAuditLog::open(path.clone()).unwrap();
+    log.record(
+        Some("legacy".into()),
+        &Method::POST,
+        "/v1/records",
+        200,
+        vec![0],
+        Some(1),
+    );
+    log.record(None, &Method::GET, "/v1/keys", 401, vec![], None);
+    drop(log);
+
+    let log = ApiAuditLog::open(path).unwrap();
+    let e = log.record(
+        Some("legacy".into()),
+        &Method::POST,
+        "/v1/delete",
+        200,
+        vec![0],
+        Some(2),
+    );
+    assert_eq!(e.seq, 3);
+
+    let all = log.query(&AuditQuery::default()).await;
+    assert_eq!(all.iter().map(|e| e.seq).collect::<Vec<_>>(), vec![1, 2, 3]);
+    let denied = log
+        .query(&AuditQuery {
+            route: Some("/v1/keys".into()),
+            ..Default::default()
+        })
+        .await;
+    assert_eq!(denied.len(), 1);
+    assert_eq!(denied[0].status, 401);
+}
+
+#[tokio::test]
+async fn since_queries_skip_to_the_indexed_offset() {
+    let tmp = tempfile::tempdir().unwrap();
+    let path = tmp.path().join("api-audit.jsonl");
+    let log = ApiAuditLog::open(path.clone()).unwrap();
+    for i in 0..700 {
+        log.record(None, &Method::POST, "/v1/records", 200, vec![i], None);
+    }
+    let page = |since: u64| AuditQuery {
+        since: Some(since),
+        limit: Some(3),
+        ..Default::default()
+    };
+    let seqs = |entries: Vec<valori_node::api_audit::ApiAuditEntry>| {
+        entries.into_iter().map(|e| e.seq).collect::<Vec<_>>()
+    };
+    for (since, want) in [
+        (0, vec![1, 2, 3]),
+        (255, vec![256, 257, 258]),
+        (256, vec![257, 258, 259]),
+        (300, vec![301, 302, 303]),
+        (698, vec![699, 700]),
+        (700, vec![]),
+    ] {
+        assert_eq!(seqs(log.query(&page(since)).await), want, "since {since}");
+    }
+    drop(log);
+
+    // The index is rebuilt on reopen and extended by new appends.
+    let log = ApiAuditLog::open(path).unwrap();
+    log.record(None, &Method::GET, "/v1/keys", 401, vec![], None);
+    assert_eq!(seqs(log.query(&page(512)).await), vec![513, 514, 515]);
+    assert_eq!(seqs(log.query(&page(699)).await), vec![700, 701]);
+}
+
+#[tokio::test]
+async fn request_body_that_fails_to_arrive_is_rejected() {
+    use tokio::io::{AsyncReadExt, AsyncWriteExt};
+
+    let audit = Arc::new(ApiAuditLog::in_memory());
+    let app = router(&tiny_cfg(), audit.clone());
+    let listener = tokio::net::TcpListener::bind("127.0.0.1:0").await.unwrap();
+    let addr = listener.local_addr().unwrap();
+    tokio::spawn(async move {
+        axum::serve(listener, app).await.unwrap();
+    });
+
+    // Declares 64 bytes, sends 10, then closes: the body read fails.
+    let mut stream = tokio::net::TcpStream::connect(addr).await.unwrap();
+    stream
+        .write_all(
+            b"POST /v1/records HTTP/1.1\r\nhost: x\r\nauthorization: Bearer w-tok\r\n\
+              content-type: application/json\r\ncontent-length: 64\r\n\r\n{\"values\"",
+        )
+        .await
+        .unwrap();
+    stream.shutdown().await.unwrap();
+    let mut response = Vec::new();
+    let _ = stream.read_to_end(&mut response).await;
+
+    let deadline = tokio::time::Instant::now() + std::time::Duration::from_secs(5);
+    let entries = loop {
+        let entries = audit.query(&AuditQuery::default()).await;
+        if !entries.is_empty() || tokio::time::Instant::now() > deadline {
+            break entries;
+        }
+        tokio::time::sleep(std::time::Duration::from_millis(20)).await;
+    };
+    assert_eq!(entries.len(), 1);
+    assert_eq!(entries[0].status, 400);
+    assert_eq!(entries[0].route, "/v1/records");
+}
@@ -47,6 +47,8 @@ async fn test_batch_ingest_success() {
                 metadata: None,
                 request_ids: None,
                 texts: None,
+                if_version: None,
+                on_duplicate: None,
             })
             .unwrap(),
         ))
@@ -88,6 +90,8 @@ async fn test_batch_ingest_wrong_dimension_is_rejected() {
                 metadata: None,
                 request_ids: None,
                 texts: None,
+                if_version: None,
+                on_duplicate: None,
             })
             .unwrap(),
         ))
@@ -1,5 +1,6 @@
 // Copyright (c) 2025 Varshith Gudur. Dual-licensed under MIT OR Apache-2.0.
-//! Phase 3.13 — HNSW parameter exposure tests.
+//! Phase 3.13 — HNSW parameter exposure tests, plus per-query selection of
+//! the extra indexes from `VALORI_EXTRA_INDEXES`.
 
 use axum::{
     body::Body,
@@ -103,3 +104,72 @@ async fn hnsw_all_params_set() {
     assert_eq!(json["hnsw"]["ef_construction"], 400);
     assert_eq!(json["hnsw"]["ef_search"], 100);
 }
+
+async fn post(
+    app: axum::Router,
+    uri: &str,
+    body: serde_json::Value,
+) -> (StatusCode, serde_json::Value) {
+    let req = Request::builder()
+        .method("POST")
+        .uri(uri)
+        .header("content-type", "application/json")
+        .body(Body::from(body.to_string()))
+        .unwrap();
+    let resp = app.oneshot(req).await.unwrap();
+    let status = resp.status();
+    let body = axum::body::to_bytes(resp.into_body(), 1 << 16)
+        .await
+        .unwrap();
+    (
+        status,
+        serde_json::from_slice(&body).unwrap_or(serde_json::Value::Null),
+    )
+}
+
+#[tokio::test]
+async fn extra_indexes_are_listed_and_selectable_per_query() {
+    let mut cfg = NodeConfig::default();
+    cfg.dim = DIM;
+    cfg.index_kind = IndexKind::BruteForce;
+    cfg.extra_indexes = vec![IndexKind::Hnsw];
+    let engine = Arc::new(RwLock::new(Engine::new(&cfg)));
+    let app = build_router(engine.clone(), None, None);
+
+    for i in 0..20 {
+        let (status, _) = post(
+            app.clone(),
+            "/records",
+            serde_json::json!({"values": [i as f32, 0.0, 0.0, 0.0]}),
+        )
+        .await;
+        assert_eq!(status, StatusCode::OK);
+    }
+
+    let (_, json) = get_index_config(engine).await;
+    assert_eq!(json["indexes"], serde_json::json!(["brute_force", "hnsw"]));
+
+    for index in ["brute_force", "hnsw"] {
+        let (status, json) = post(
+            app.clone(),
+            "/search",
+            serde_json::json!({
+                "query": [7.2, 0.0, 0.0, 0.0],
+                "k": 1,
+                "rerank": false,
+                "index": index,
+            }),
+        )
+        .await;
+        assert_eq!(status, StatusCode::OK, "{index}: {json}");
+        assert_eq!(json["results"][0]["id"], 7, "{index}");
+    }
+
+    let (status, json) = post(
+        app,
+        "/search",
+        serde_json::json!({"query": [1.0, 0.0, 0.0, 0.0], "k": 1, "index": "ivf"}),
+    )
+    .await;
+    assert_eq!(status, StatusCode::BAD_REQUEST, "{json}");
+}
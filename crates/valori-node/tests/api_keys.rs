@@ -2,14 +2,15 @@
 //! Phase 3.5 — per-tenant API key integration tests.
 //!
 //! Covers: create, list, revoke, scope enforcement (read_only vs read_write),
-//! legacy VALORI_AUTH_TOKEN fallback, unauthenticated rejection.
+//! legacy VALORI_AUTH_TOKEN fallback, unauthenticated rejection, and the
+//! static role tokens (reader / writer / admin / replicator) per route group.
 
 use std::sync::Arc;
 use tokio::sync::RwLock;
-use valori_node::api_keys::KeyStore;
+use valori_node::api_keys::{parse_auth_roles, ApiScope, KeyStore};
 use valori_node::config::NodeConfig;
 use valori_node::engine::Engine;
-use valori_node::server::build_router_with_keys;
+use valori_node::server::{build_router_with_auth, build_router_with_keys};
 use valori_node::EngineFromNodeConfig;
 
 // ── Helpers ───────────────────────────────────────────────────────────────────
@@ -40,6 +41,41 @@ async fn spawn_node(
     (client, format!("http://{}", addr))
 }
 
+async fn spawn_node_with_roles(roles: &str) -> (reqwest::Client, String) {
+    let mut cfg = NodeConfig::default();
+    cfg.max_records = 100;
+    cfg.dim = 4;
+    cfg.max_nodes = 50;
+    cfg.max_edges = 50;
+
+    let state = Arc::new(RwLock::new(Engine::new(&cfg)));
+    let app = build_router_with_auth(
+        state,
+        None,
+        None,
+        Arc::new(KeyStore::new(None)),
+        std::sync::Arc::new(valori_effect::ReceiptStore::new(64)),
+        parse_auth_roles(roles).unwrap(),
+        Arc::new(valori_node::api_audit::ApiAuditLog::in_memory()),
+    );
+    let listener = tokio::net::TcpListener::bind("127.0.0.1:0").await.unwrap();
+    let addr = listener.local_addr().unwrap();
+    tokio::spawn(async move { axum::serve(listener, app).await.unwrap() });
+
+    (reqwest::Client::new(), format!("http://{}", addr))
+}
+
+async fn get_status(client: &reqwest::Client, base: &str, path: &str, bearer: &str) -> u16 {
+    client
+        .get(format!("{base}{path}"))
+        .bearer_auth(bearer)
+        .send()
+        .await
+        .unwrap()
+        .status()
+        .as_u16()
+}
+
 async fn insert(client: &reqwest::Client, base: &str, bearer: Option<&str>) -> reqwest::Response {
     let mut req = client
         .post(format!("{base}/records"))
@@ -259,3 +295,114 @@ async fn health_always_public() {
         .status();
     assert!(status.is_success());
 }
+
+/// Role names parse onto scopes; malformed entries are rejected, not dropped.
+#[test]
+fn parse_auth_roles_accepts_roles_and_rejects_garbage() {
+    let roles = parse_auth_roles("reader=r, writer=w,admin=a,replicator=f").unwrap();
+    assert_eq!(
+        roles,
+        vec![
+            (ApiScope::ReadOnly, "r".to_string()),
+            (ApiScope::ReadWrite, "w".to_string()),
+            (ApiScope::Admin, "a".to_string()),
+            (ApiScope::Replicator, "f".to_string()),
+        ]
+    );
+    assert!(parse_auth_roles("superuser=x").is_err());
+    assert!(parse_auth_roles("reader").is_err());
+    assert!(parse_auth_roles("reader=").is_err());
+}
+
+/// Routes that hand out raw log contents across namespaces are admin-only.
+#[test]
+fn journal_and_export_reads_require_admin() {
+    use axum::http::Method;
+    use valori_node::api_keys::{required_scope, ApiScope};
+
+    for path in ["/v1/events", "/v1/export", "/v1/audit"] {
+        assert_eq!(
+            required_scope(&Method::GET, path),
+            ApiScope::Admin,
+            "{path}"
+        );
+    }
+    assert_eq!(
+        required_scope(&Method::GET, "/v1/records/1"),
+        ApiScope::ReadOnly
+    );
+}
+
+/// Each role token reaches exactly its route group.
+#[tokio::test]
+async fn role_tokens_enforced_per_route_group() {
+    let (client, base) =
+        spawn_node_with_roles("reader=tok_r,writer=tok_w,admin=tok_a,replicator=tok_f").await;
+
+    // reader: search yes, insert no.
+    assert!(search(&client, &base, Some("tok_r"))
+        .await
+        .status()
+        .is_success());
+    assert_eq!(
+        get_status(&client, &base, "/v1/admin/check", "tok_r").await,
+        403
+    );
+    assert_eq!(get_status(&client, &base, "/v1/events", "tok_r").await, 403);
+    assert_eq!(
+        insert(&client, &base, Some("tok_r"))
+            .await
+            .status()
+            .as_u16(),
+        403
+    );
+
+    // writer: insert yes, snapshot/admin no, replication no.
+    assert!(insert(&client, &base, Some("tok_w"))
+        .await
+        .status()
+        .is_success());
+    assert_eq!(
+        get_status(&client, &base, "/v1/snapshot/download", "tok_w").await,
+        403
+    );
+    assert_eq!(
+        get_status(&client, &base, "/v1/replication/state", "tok_w").await,
+        403
+    );
+
+    // replicator: replication + snapshot download yes, writes and keys no.
+    assert_eq!(
+        get_status(&client, &base, "/v1/replication/state", "tok_f").await,
+        200
+    );
+    assert_eq!(
+        get_status(&client, &base, "/v1/snapshot/download", "tok_f").await,
+        200
+    );
+    assert_eq!(
+        insert(&client, &base, Some("tok_f"))
+            .await
+            .status()
+            .as_u16(),
+        403
+    );
+    assert_eq!(get_status(&client, &base, "/v1/keys", "tok_f").await, 403);
+
+    // admin: everything, including the replication group.
+    assert_eq!(get_status(&client, &base, "/v1/keys", "tok_a").await, 200);
+    assert_eq!(
+        get_status(&client, &base, "/v1/admin/check", "tok_a").await,
+        200
+    );
+    assert_eq!(
+        get_status(&client, &base, "/v1/replication/state", "tok_a").await,
+        200
+    );
+
+    // Unknown token is still 401.
+    assert_eq!(
+        insert(&client, &base, Some("nope")).await.status().as_u16(),
+        401
+    );
+}
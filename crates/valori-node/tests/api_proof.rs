@@ -114,6 +114,57 @@ async fn proof_state_changes_after_insert() {
     );
 }
 
+#[tokio::test]
+async fn proof_state_root_is_maintained_per_commit_and_survives_recovery() {
+    use valori_kernel::state::merkle::StateMerkle;
+
+    let tmp_dir = tempfile::tempdir().unwrap();
+    let mut cfg = tiny_cfg();
+    cfg.event_log_path = Some(tmp_dir.path().join("events.log"));
+    let (state, router) = engine_router(cfg.clone());
+    let mut roots = std::collections::BTreeSet::new();
+    for values in [[1.0f32, 0.0, 0.0, 0.0], [0.0, 1.0, 0.0, 0.0]] {
+        let (status, _) = post_json(
+            router.clone(),
+            "/records",
+            serde_json::json!({ "values": values }),
+        )
+        .await;
+        assert_eq!(status, StatusCode::OK);
+        let (_, body) = get(router.clone(), "/v1/proof/state").await;
+        assert!(roots.insert(body["state_root"].as_str().unwrap().to_string()));
+    }
+    let (status, _) = post_json(router.clone(), "/v1/delete", serde_json::json!({"id": 0})).await;
+    assert_eq!(status, StatusCode::OK);
+
+    let (_, body) = get(router.clone(), "/v1/proof/state").await;
+    let root = body["state_root"].as_str().unwrap().to_string();
+    assert!(roots.insert(root.clone()));
+    {
+        let engine = state.read().await;
+        assert!(engine.state.tracks_state_root());
+        let fresh = StateMerkle::build(&engine.state).root(&engine.state);
+        let fresh: String = fresh.iter().map(|b| format!("{b:02x}")).collect();
+        assert_eq!(root, fresh, "incremental root must equal a full rebuild");
+        assert_eq!(body["version"], engine.state.version());
+    }
+    // A second poll of an unchanged state is served from the proof cache.
+    let (_, again) = get(router, "/v1/proof/state").await;
+    assert_eq!(again, body);
+
+    // Recovery replaces the kernel state; the root is tracked again and
+    // equal. `local_events` flushes the log first.
+    valori_node::geo_replication::local_events(&state)
+        .await
+        .unwrap();
+    let mut engine = Engine::new(&cfg);
+    engine.try_recover();
+    let recovered = build_router(Arc::new(RwLock::new(engine)), None, None);
+    let (_, after) = get(recovered, "/v1/proof/state").await;
+    assert_eq!(after["state_root"], body["state_root"]);
+    assert_eq!(after["final_state_hash"], body["final_state_hash"]);
+}
+
 // ── /v1/proof/event-log ──────────────────────────────────────────────────────
 
 #[tokio::test]
@@ -136,7 +187,7 @@ async fn proof_event_log_with_event_log_enabled() {
     let tmp_dir = tempfile::tempdir().unwrap();
     let log_path = tmp_dir.path().join("events.log");
     let mut cfg = tiny_cfg();
-    cfg.event_log_path = Some(log_path);
+    cfg.event_log_path = Some(log_path.clone());
     let (_, router) = engine_router(cfg);
 
     // Insert something so there's a committed event.
@@ -155,6 +206,14 @@ async fn proof_event_log_with_event_log_enabled() {
         .as_str()
         .expect("missing event_log_hash");
     assert_eq!(hash.len(), 64, "event_log_hash must be 64-char hex");
+    // The running hash is the one an auditor recomputes from the file.
+    let on_disk: String = valori_node::events::event_proof::compute_log_hash(&log_path)
+        .unwrap()
+        .iter()
+        .map(|b| format!("{b:02x}"))
+        .collect();
+    assert_eq!(hash, on_disk);
+    assert_ne!(hash, "0".repeat(64), "one committed event moves the hash");
 
     let state_hash = body["final_state_hash"]
         .as_str()
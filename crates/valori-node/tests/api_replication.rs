@@ -2,7 +2,8 @@
 //! Integration test for the replication streaming endpoint.
 //!
 //! Inserts one record, then verifies /v1/replication/events yields
-//! the historical InsertRecord event and streams new ones live.
+//! the historical InsertRecord event and streams new ones live — as legacy
+//! NDJSON by default, and as CRC-checked binary frames when asked for.
 use std::sync::Arc;
 use tempfile::tempdir;
 use tokio::sync::RwLock;
@@ -94,3 +95,71 @@ async fn test_replication_stream_endpoint() {
         "Second chunk must contain at least one base64 event"
     );
 }
+
+#[tokio::test]
+async fn test_replication_stream_binary_frames() {
+    use valori_node::events::event_log::LogEntry;
+    use valori_node::replication_codec::{StreamDecoder, StreamFormat, FRAMES_CONTENT_TYPE};
+
+    let dir = tempdir().unwrap();
+    let config = valori_node::config::NodeConfig {
+        event_log_path: Some(dir.path().join("events.log")),
+        max_records: 128,
+        dim: 4,
+        max_nodes: 128,
+        max_edges: 256,
+        ..Default::default()
+    };
+    let mut engine = Engine::new(&config);
+    engine
+        .insert_record_from_f32(&[0.1, 0.2, 0.3, 0.4])
+        .unwrap();
+    let state = Arc::new(RwLock::new(engine));
+
+    let listener = tokio::net::TcpListener::bind("127.0.0.1:0").await.unwrap();
+    let addr = listener.local_addr().unwrap();
+    let app = build_router(state.clone(), None, None);
+    tokio::spawn(async move {
+        axum::serve(listener, app).await.unwrap();
+    });
+
+    let mut res = reqwest::Client::new()
+        .get(format!("http://{addr}/v1/replication/events"))
+        .header("accept", FRAMES_CONTENT_TYPE)
+        .send()
+        .await
+        .unwrap();
+    assert!(res.status().is_success());
+    let content_type = res.headers()["content-type"].to_str().unwrap().to_string();
+    assert_eq!(
+        StreamFormat::from_content_type(Some(&content_type)),
+        StreamFormat::Frames
+    );
+
+    let mut decoder = StreamDecoder::new(StreamFormat::Frames);
+    let mut entries = Vec::new();
+    let mut inserted_live = false;
+    while entries.len() < 2 {
+        if entries.len() == 1 && !inserted_live {
+            state
+                .write()
+                .await
+                .insert_record_from_f32(&[0.5, 0.6, 0.7, 0.8])
+                .unwrap();
+            inserted_live = true;
+        }
+        let chunk = tokio::time::timeout(std::time::Duration::from_secs(10), res.chunk())
+            .await
+            .expect("stream stalled")
+            .unwrap()
+            .expect("stream ended early");
+        decoder.push(&chunk);
+        while let Some(e) = decoder.next_entry().expect("frame must pass CRC") {
+            entries.push(e);
+        }
+    }
+    assert!(
+        entries.iter().all(|e| matches!(e, LogEntry::Event(_))),
+        "historical and live inserts arrive as data events"
+    );
+}
@@ -403,3 +403,224 @@ async fn snapshot_preserves_collections_and_data() {
         .collect();
     assert!(ids.contains(&expected_id));
 }
+
+// ── Configured collections (/v1/collections) ─────────────────────────────────
+
+#[tokio::test]
+async fn configured_collections_have_their_own_dim_and_index() {
+    // One router throughout: each `build_router` would get a fresh registry.
+    let app = build_router(make_shared(), None, None);
+    let post = |uri: &'static str, body: serde_json::Value| {
+        http(app.clone(), Method::POST, uri, Some(body))
+    };
+
+    let (s, small) = post(
+        "/v1/collections",
+        serde_json::json!({"name": "small", "dim": 2, "max_records": 10}),
+    )
+    .await;
+    assert_eq!(s, StatusCode::CREATED);
+    assert_eq!(small["index"], "brute_force");
+    let (s, wide) = post(
+        "/v1/collections",
+        serde_json::json!({"name": "wide", "dim": 6, "index": "sq"}),
+    )
+    .await;
+    assert_eq!(s, StatusCode::CREATED);
+    assert_eq!(
+        (wide["dim"].as_u64(), wide["index"].as_str()),
+        (Some(6), Some("sq"))
+    );
+    assert_eq!(
+        wide["max_records"], 256,
+        "falls back to the node's capacity"
+    );
+
+    let (s, _) = post(
+        "/v1/collections",
+        serde_json::json!({"name": "small", "dim": 2}),
+    )
+    .await;
+    assert_eq!(s, StatusCode::CONFLICT);
+    for bad in [
+        serde_json::json!({"name": "a/b", "dim": 2}),
+        serde_json::json!({"name": "x", "dim": 0}),
+        serde_json::json!({"name": "x", "dim": valori_protocol::MAX_DIM + 1}),
+        serde_json::json!({"name": "x", "dim": 2, "max_records": valori_protocol::MAX_RECORDS + 1}),
+        serde_json::json!({"name": "x", "dim": 2, "index": "nope"}),
+    ] {
+        let (s, _) = post("/v1/collections", bad).await;
+        assert_eq!(s, StatusCode::BAD_REQUEST);
+    }
+
+    let (s, ins) = post(
+        "/v1/collections/small/records",
+        serde_json::json!({"values": [0.5, 0.25], "metadata": {"src": "a"}}),
+    )
+    .await;
+    assert_eq!(s, StatusCode::OK);
+    let id = ins["id"].as_u64().unwrap();
+    post(
+        "/v1/collections/small/records",
+        serde_json::json!({"values": [-3.0, 2.0]}),
+    )
+    .await;
+    let (s, _) = post(
+        "/v1/collections/small/records",
+        serde_json::json!({"values": [1.0, 2.0, 3.0]}),
+    )
+    .await;
+    assert_eq!(s, StatusCode::BAD_REQUEST, "wrong dimension");
+    let (s, _) = post(
+        "/v1/collections/wide/records",
+        serde_json::json!({"values": vec![0.1_f32; 6]}),
+    )
+    .await;
+    assert_eq!(s, StatusCode::OK);
+
+    let (s, hits) = post(
+        "/v1/collections/small/search",
+        serde_json::json!({"query": [0.5, 0.3], "k": 1}),
+    )
+    .await;
+    assert_eq!(s, StatusCode::OK);
+    assert_eq!(hits["results"][0]["id"].as_u64(), Some(id));
+
+    let uri = format!("/v1/collections/small/records/{id}");
+    let (s, rec) = http(app.clone(), Method::GET, &uri, None).await;
+    assert_eq!(s, StatusCode::OK);
+    assert_eq!(rec["metadata"]["src"], "a");
+    assert_eq!(rec["vector"], serde_json::json!([0.5, 0.25]));
+
+    // The node's own engine and namespaces are untouched.
+    let (_, list) = http(app.clone(), Method::GET, "/v1/collections", None).await;
+    let records: Vec<(&str, u64)> = list["collections"]
+        .as_array()
+        .unwrap()
+        .iter()
+        .map(|c| (c["name"].as_str().unwrap(), c["records"].as_u64().unwrap()))
+        .collect();
+    assert_eq!(records, [("small", 2), ("wide", 1)]);
+    let (_, ns) = http(app.clone(), Method::GET, "/v1/namespaces", None).await;
+    assert_eq!(ns["collections"].as_array().unwrap().len(), 1);
+
+    let (s, _) = http(app.clone(), Method::DELETE, &uri, None).await;
+    assert_eq!(s, StatusCode::NO_CONTENT);
+    let (s, _) = http(app.clone(), Method::GET, &uri, None).await;
+    assert_eq!(s, StatusCode::NOT_FOUND);
+
+    let (s, _) = http(app.clone(), Method::DELETE, "/v1/collections/small", None).await;
+    assert_eq!(s, StatusCode::NO_CONTENT);
+    let (s, _) = post(
+        "/v1/collections/small/search",
+        serde_json::json!({"query": [0.5, 0.3], "k": 1}),
+    )
+    .await;
+    assert_eq!(s, StatusCode::NOT_FOUND);
+    let (s, _) = http(app, Method::GET, "/v1/collections/small", None).await;
+    assert_eq!(s, StatusCode::NOT_FOUND);
+}
+
+async fn raw(app: axum::Router, method: Method, uri: &str, body: Vec<u8>) -> (StatusCode, Vec<u8>) {
+    let req = Request::builder()
+        .method(method)
+        .uri(uri)
+        .body(Body::from(body))
+        .unwrap();
+    let resp = app.oneshot(req).await.unwrap();
+    let status = resp.status();
+    let bytes = axum::body::to_bytes(resp.into_body(), 1 << 20)
+        .await
+        .unwrap();
+    (status, bytes.to_vec())
+}
+
+#[tokio::test]
+async fn configured_collections_snapshot_and_prove_one_by_one() {
+    let app = build_router(make_shared(), None, None);
+    let post = |uri: &'static str, body: serde_json::Value| {
+        http(app.clone(), Method::POST, uri, Some(body))
+    };
+    for (name, dim) in [("a", 2), ("b", 2), ("c", 3)] {
+        let (s, _) = post(
+            "/v1/collections",
+            serde_json::json!({"name": name, "dim": dim}),
+        )
+        .await;
+        assert_eq!(s, StatusCode::CREATED);
+    }
+    post(
+        "/v1/collections/a/records",
+        serde_json::json!({"values": [0.5, 0.25]}),
+    )
+    .await;
+
+    let (s, snapshot) = raw(
+        app.clone(),
+        Method::GET,
+        "/v1/collections/a/snapshot",
+        vec![],
+    )
+    .await;
+    assert_eq!(s, StatusCode::OK);
+    let (s, _) = raw(
+        app.clone(),
+        Method::POST,
+        "/v1/collections/c/snapshot",
+        snapshot.clone(),
+    )
+    .await;
+    assert_eq!(s, StatusCode::BAD_REQUEST, "dimension differs");
+    let (s, _) = raw(
+        app.clone(),
+        Method::POST,
+        "/v1/collections/b/snapshot",
+        b"not a snapshot".to_vec(),
+    )
+    .await;
+    assert_eq!(s, StatusCode::BAD_REQUEST);
+
+    let (s, restored) = raw(
+        app.clone(),
+        Method::POST,
+        "/v1/collections/b/snapshot",
+        snapshot,
+    )
+    .await;
+    assert_eq!(s, StatusCode::OK);
+    let restored: serde_json::Value = serde_json::from_slice(&restored).unwrap();
+    let (_, proof_a) = http(app.clone(), Method::GET, "/v1/collections/a/proof", None).await;
+    assert_eq!(restored["final_state_hash"], proof_a["final_state_hash"]);
+    assert_ne!(restored["leaf"], proof_a["leaf"]);
+    let (s, rec) = http(
+        app.clone(),
+        Method::GET,
+        "/v1/collections/b/records/0",
+        None,
+    )
+    .await;
+    assert_eq!(s, StatusCode::OK);
+    assert_eq!(rec["vector"], serde_json::json!([0.5, 0.25]));
+
+    let (s, root) = http(app.clone(), Method::GET, "/v1/proof/collections", None).await;
+    assert_eq!(s, StatusCode::OK);
+    let entries = root["collections"].as_array().unwrap();
+    assert_eq!(entries.len(), 3);
+    assert_eq!(entries[0], proof_a);
+    assert_eq!(root["root"].as_str().unwrap().len(), 64);
+
+    // Writing to one collection moves only its proof, and the root.
+    post(
+        "/v1/collections/c/records",
+        serde_json::json!({"values": [1.0, 2.0, 3.0]}),
+    )
+    .await;
+    let (_, after) = http(app.clone(), Method::GET, "/v1/proof/collections", None).await;
+    assert_eq!(after["collections"][0], entries[0]);
+    assert_eq!(after["collections"][1], entries[1]);
+    assert_ne!(after["collections"][2], entries[2]);
+    assert_ne!(after["root"], root["root"]);
+
+    let (s, _) = http(app, Method::GET, "/v1/collections/zzz/proof", None).await;
+    assert_eq!(s, StatusCode::NOT_FOUND);
+}
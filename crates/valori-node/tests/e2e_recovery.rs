@@ -202,7 +202,7 @@ fn test_fresh_start_when_nothing_exists() {
 
 // ── Test 6: metadata sidecar survives crash and event-log recovery ─────────────
 //
-// `MetadataStore` lives in memory only; there is no `SetMetadata` kernel event.
+// Keys written straight to `MetadataStore` (no `SetMeta` event) live in memory only.
 // To survive an event-log recovery, `Engine::flush_metadata()` writes an atomic
 // JSON sidecar, and `try_recover()` calls `load_metadata()` after replay.
 // This test verifies the full round-trip.
@@ -260,6 +260,51 @@ fn test_metadata_persists_through_event_log_recovery() {
     }
 }
 
+// ── Test 6b: the event log, not a stale sidecar, decides metadata ─────────────
+//
+// `set_meta_audited` / `delete_meta_audited` commit SetMeta / DeleteMeta before
+// the sidecar is flushed. A crash in between leaves the sidecar behind the log;
+// recovery must replay the log's meta events rather than trust the sidecar.
+
+#[test]
+fn test_metadata_replays_from_event_log_over_stale_sidecar() {
+    let dir = tempdir().unwrap();
+    let cfg = make_cfg(dir.path(), 4);
+    let sidecar = dir.path().join("events.metadata.json");
+
+    {
+        let mut engine = Engine::new(&cfg);
+        engine.try_recover();
+        engine
+            .insert_record_from_f32(&[1.0, 0.0, 0.0, 0.0])
+            .unwrap();
+        engine
+            .set_meta_audited("doc:gone".into(), serde_json::json!({ "v": 1 }))
+            .unwrap();
+        let stale = std::fs::read(&sidecar).expect("sidecar must be written");
+
+        engine.delete_meta_audited("doc:gone".into()).unwrap();
+        engine
+            .set_meta_audited("doc:kept".into(), serde_json::json!({ "v": 2 }))
+            .unwrap();
+        // "Crash" before the last two flushes reached disk.
+        std::fs::write(&sidecar, stale).unwrap();
+    }
+
+    let mut engine2 = Engine::new(&cfg);
+    assert!(matches!(engine2.try_recover(), RecoveryMode::EventLog(4)));
+    assert_eq!(
+        engine2.metadata.get("doc:gone"),
+        None,
+        "a logged DeleteMeta must win over the stale sidecar"
+    );
+    assert_eq!(
+        engine2.metadata.get("doc:kept"),
+        Some(serde_json::json!({ "v": 2 })),
+        "a logged SetMeta must be restored without the sidecar"
+    );
+}
+
 // ── Test 4b: collections (namespaces) survive event-log recovery ──────────────
 //
 // Regression for the UI bug: after a hard restart, projects were visible (from
@@ -491,3 +536,387 @@ fn test_search_index_rebuilt_after_recovery() {
         );
     }
 }
+
+// ── Recovery dry run ──────────────────────────────────────────────────────────
+
+#[test]
+fn test_verify_recovery_reports_hash_without_touching_files() {
+    let dir = tempdir().unwrap();
+    let cfg = make_cfg(dir.path(), 4);
+    let log_path = dir.path().join("events.log");
+    let snapshot_path = dir.path().join("snapshot.bin");
+
+    let live_hash;
+    {
+        let mut engine = Engine::new(&cfg);
+        engine.try_recover();
+        for i in 0..10 {
+            let v: Vec<f32> = (0..4).map(|j| (i * 4 + j) as f32 * 0.01).collect();
+            engine.insert_record_from_f32(&v).unwrap();
+        }
+        engine.save_snapshot(None).unwrap();
+        live_hash = engine.state_hash_hex();
+    }
+    let log_before = std::fs::read(&log_path).unwrap();
+
+    let report = valori_node::engine::verify_recovery(&cfg);
+    assert_eq!(report.mode, RecoveryMode::EventLog(10));
+    assert_eq!(report.state_hash, live_hash);
+    assert_eq!(report.records, 10);
+    assert!(report.anomalies.is_empty(), "{:?}", report.anomalies);
+    assert_eq!(std::fs::read(&log_path).unwrap(), log_before);
+
+    // A corrupt snapshot is reported even though the event log covers for it.
+    std::fs::write(&snapshot_path, b"not a snapshot at all").unwrap();
+    let report = valori_node::engine::verify_recovery(&cfg);
+    assert_eq!(report.state_hash, live_hash);
+    assert_eq!(report.anomalies.len(), 1, "{:?}", report.anomalies);
+    assert!(report.anomalies[0].starts_with("snapshot"));
+}
+
+// ── Corrupt-segment quarantine ────────────────────────────────────────────────
+
+/// Flip a byte of the `prev_hash` of the entry three fifths into the log
+/// at `log_path` and return the damaged bytes. The field is fixed-width, so
+/// the damage always reads as corruption; a flipped length or varint marker
+/// can instead run the entry past the end of the file and pass for a torn
+/// tail.
+fn corrupt_mid_entry(log_path: &std::path::Path) -> Vec<u8> {
+    use valori_node::events::event_log::torn_tail;
+    let mut bytes = std::fs::read(log_path).unwrap();
+    // Cut a copy there: the torn entry it ends in starts where it keeps.
+    let probe = log_path.with_extension("probe");
+    std::fs::write(&probe, &bytes[..bytes.len() * 3 / 5]).unwrap();
+    let entry = torn_tail(&probe).unwrap().segment_len as usize;
+    std::fs::remove_file(&probe).unwrap();
+    bytes[entry + 8] ^= 0xff;
+    std::fs::write(log_path, &bytes).unwrap();
+    bytes
+}
+
+#[test]
+fn test_quarantine_recovers_to_last_verifiable_height() {
+    let dir = tempdir().unwrap();
+    let mut cfg = make_cfg(dir.path(), 4);
+    cfg.snapshot_path = None;
+    let log_path = dir.path().join("events.log");
+
+    {
+        let mut engine = Engine::new(&cfg);
+        engine.try_recover();
+        for i in 0..10 {
+            let v: Vec<f32> = (0..4).map(|j| (i * 4 + j) as f32 * 0.01).collect();
+            engine.insert_record_from_f32(&v).unwrap();
+        }
+    }
+    let bytes = corrupt_mid_entry(&log_path);
+
+    // Default: fail closed, files untouched.
+    {
+        let mut engine = Engine::new(&cfg);
+        assert_ne!(engine.try_recover(), RecoveryMode::EventLog(10));
+        assert!(engine.damage.is_none());
+        assert_eq!(std::fs::read(&log_path).unwrap(), bytes);
+    }
+
+    cfg.quarantine_corrupt_segments = true;
+    let height;
+    {
+        let mut engine = Engine::new(&cfg);
+        let damage = engine.damage.clone().expect("damage reported");
+        height = damage.recovered_height;
+        assert!(height > 0 && height < 10, "height {height}");
+        assert_eq!(damage.quarantined.len(), 1);
+        assert!(damage.quarantined[0].exists());
+        assert_eq!(engine.try_recover(), RecoveryMode::EventLog(height));
+        assert_eq!(engine.record_count(), height as usize);
+        // The truncated log accepts appends again.
+        engine.insert_record_from_f32(&[0.5; 4]).unwrap();
+    }
+
+    // The damage marker is sticky across restarts, even with the flag off.
+    cfg.quarantine_corrupt_segments = false;
+    let mut engine = Engine::new(&cfg);
+    assert_eq!(engine.try_recover(), RecoveryMode::EventLog(height + 1));
+    assert_eq!(
+        engine.damage.as_ref().map(|d| d.recovered_height),
+        Some(height)
+    );
+}
+
+// ── Graceful shutdown ─────────────────────────────────────────────────────────
+
+#[test]
+fn test_shutdown_checkpoint_seals_log_and_snapshot() {
+    let dir = tempdir().unwrap();
+    let cfg = make_cfg(dir.path(), 4);
+    let log_path = dir.path().join("events.log");
+
+    let hash;
+    {
+        let mut engine = Engine::new(&cfg);
+        engine.try_recover();
+        for i in 0..5 {
+            let v: Vec<f32> = (0..4).map(|j| (i * 4 + j) as f32 * 0.01).collect();
+            engine.insert_record_from_f32(&v).unwrap();
+        }
+        let snapshot = cfg.snapshot_path.clone().unwrap();
+        assert_eq!(
+            engine.shutdown_checkpoint(Some(&snapshot)).unwrap(),
+            Some(5)
+        );
+        assert!(snapshot.exists());
+        assert!(matches!(
+            engine.insert_record_from_f32(&[0.5; 4]),
+            Err(valori_node::engine::EngineError::ShuttingDown)
+        ));
+        hash = engine.state_hash_hex();
+    }
+
+    // The checkpoint is the last entry and records the final height and hash.
+    let bytes = std::fs::read(&log_path).unwrap();
+    let header = valori_wire::parse_header(&bytes).unwrap();
+    let mut offset = header.header_len;
+    let mut last = None;
+    while offset < bytes.len() {
+        let (decoded, n) = valori_wire::decode_entry(header.version, &bytes[offset..]).unwrap();
+        offset += n;
+        last = Some(decoded.entry);
+    }
+    match last {
+        Some(
+            valori_wire::LogEntry::Checkpoint {
+                event_count,
+                snapshot_hash,
+                ..
+            }
+            | valori_wire::LogEntry::CheckpointV2 {
+                event_count,
+                snapshot_hash,
+                ..
+            },
+        ) => {
+            assert_eq!(event_count, 5);
+            let hex: String = snapshot_hash.iter().map(|b| format!("{b:02x}")).collect();
+            assert_eq!(hex, hash);
+        }
+        other => panic!("expected a final checkpoint, got {other:?}"),
+    }
+
+    let mut engine = Engine::new(&cfg);
+    assert_eq!(engine.try_recover(), RecoveryMode::EventLog(5));
+    assert_eq!(engine.state_hash_hex(), hash);
+    engine.insert_record_from_f32(&[0.5; 4]).unwrap();
+}
+
+// ── Snapshot self-check ──────────────────────────────────────────────────────
+
+#[test]
+fn test_snapshot_failing_self_check_refuses_writes() {
+    let dir = tempdir().unwrap();
+    let mut cfg = make_cfg(dir.path(), 4);
+    cfg.event_log_path = None;
+    let snapshot_path = dir.path().join("snapshot.bin");
+
+    {
+        let mut engine = Engine::new(&cfg);
+        engine.try_recover();
+        for i in 0..8 {
+            let v: Vec<f32> = (0..4).map(|j| (i * 4 + j) as f32 * 0.01).collect();
+            engine.insert_record_from_f32(&v).unwrap();
+        }
+        engine.save_snapshot(None).unwrap();
+    }
+
+    // An intact snapshot passes and the node stays writable.
+    let mut engine = Engine::new(&cfg);
+    assert_eq!(engine.try_recover(), RecoveryMode::Snapshot);
+    let check = engine.health().snapshot_check.unwrap();
+    assert!(check.ok && check.checksummed, "{:?}", check.failures);
+    assert_eq!(check.height, 8);
+    assert!(!engine.writes_refused());
+    drop(engine);
+
+    // A corrupt checksum is refused outright rather than loaded.
+    let mut bytes = std::fs::read(&snapshot_path).unwrap();
+    *bytes.last_mut().unwrap() ^= 0xff;
+    std::fs::write(&snapshot_path, &bytes).unwrap();
+
+    let mut engine = Engine::new(&cfg);
+    assert_ne!(engine.try_recover(), RecoveryMode::Snapshot);
+    assert_eq!(engine.record_count(), 0);
+    drop(engine);
+    // Put the intact snapshot back.
+    *bytes.last_mut().unwrap() ^= 0xff;
+    std::fs::write(&snapshot_path, &bytes).unwrap();
+
+    // An event log the snapshot does not replay from: it fails recovery, so
+    // the snapshot still loads, but then fails the check.
+    cfg.event_log_path = Some(dir.path().join("events.log"));
+    {
+        use valori_kernel::event::KernelEvent;
+        use valori_kernel::types::id::RecordId;
+        use valori_node::events::event_log::{EventLogWriter, LogEntry};
+        let mut writer = EventLogWriter::open(dir.path().join("events.log"), Some(4)).unwrap();
+        let event = KernelEvent::DeleteRecord { id: RecordId(99) };
+        writer.append(&LogEntry::Event(event)).unwrap();
+    }
+
+    let mut engine = Engine::new(&cfg);
+    assert_eq!(engine.try_recover(), RecoveryMode::Snapshot);
+    assert_eq!(engine.record_count(), 8);
+    assert!(engine.writes_refused());
+    assert_eq!(engine.health().status, "read_only");
+    assert!(matches!(
+        engine.insert_record_from_f32(&[0.5; 4]),
+        Err(valori_node::engine::EngineError::SnapshotUnverified)
+    ));
+    assert_eq!(
+        engine.search_l2(&[0.0, 0.01, 0.02, 0.03], 1).unwrap()[0].0,
+        0
+    );
+    drop(engine);
+
+    // `warn` reports the same failure but keeps accepting writes.
+    cfg.snapshot_check = valori_node::engine::SnapshotCheckPolicy::Warn;
+    let mut engine = Engine::new(&cfg);
+    engine.try_recover();
+    assert!(!engine.health().snapshot_check.unwrap().ok);
+    assert!(!engine.writes_refused());
+    assert!(!matches!(
+        engine.insert_record_from_f32(&[0.5; 4]),
+        Err(valori_node::engine::EngineError::SnapshotUnverified)
+    ));
+}
+
+#[test]
+fn test_snapshot_from_another_history_fails_replay() {
+    let dir = tempdir().unwrap();
+    let cfg = make_cfg(dir.path(), 4);
+
+    // The event log records one history...
+    let mut log_only = make_cfg(dir.path(), 4);
+    log_only.snapshot_path = None;
+    {
+        let mut engine = Engine::new(&log_only);
+        engine.try_recover();
+        for i in 0..6 {
+            engine.insert_record_from_f32(&[i as f32 * 0.1; 4]).unwrap();
+        }
+    }
+    // ...and the snapshot was taken from a different one.
+    let mut snapshot_only = make_cfg(dir.path(), 4);
+    snapshot_only.event_log_path = None;
+    {
+        let mut engine = Engine::new(&snapshot_only);
+        for i in 0..4 {
+            engine.insert_record_from_f32(&[i as f32 * 0.2; 4]).unwrap();
+        }
+        engine.save_snapshot(None).unwrap();
+    }
+
+    let report = valori_node::engine::verify_recovery(&cfg);
+    assert_eq!(report.mode, RecoveryMode::EventLog(6));
+    assert_eq!(report.anomalies.len(), 1, "{:?}", report.anomalies);
+    assert!(
+        report.anomalies[0].contains("replaying the event log to height 4"),
+        "{:?}",
+        report.anomalies
+    );
+}
+
+// ── Recovery policy ──────────────────────────────────────────────────────────
+
+/// Ten events on the log and a snapshot of them, then a flipped byte in the
+/// middle of the log.
+fn corrupt_log_with_snapshot(cfg: &NodeConfig) -> Vec<u8> {
+    {
+        let mut engine = Engine::new(cfg);
+        engine.try_recover();
+        for i in 0..10 {
+            let v: Vec<f32> = (0..4).map(|j| (i * 4 + j) as f32 * 0.01).collect();
+            engine.insert_record_from_f32(&v).unwrap();
+        }
+        engine.save_snapshot(None).unwrap();
+    }
+    corrupt_mid_entry(cfg.event_log_path.as_ref().unwrap())
+}
+
+#[test]
+fn test_recovery_policy_fail_closed_refuses_a_corrupt_log() {
+    use valori_node::engine::{EngineError, RecoveryPolicy};
+    let dir = tempdir().unwrap();
+    let mut cfg = make_cfg(dir.path(), 4);
+    let bytes = corrupt_log_with_snapshot(&cfg);
+
+    // The default falls back to the snapshot.
+    assert_eq!(Engine::new(&cfg).try_recover(), RecoveryMode::Snapshot);
+
+    cfg.recovery_policy = RecoveryPolicy::FailClosed;
+    let mut engine = Engine::new(&cfg);
+    assert!(matches!(engine.try_recover(), RecoveryMode::Refused(_)));
+    assert_eq!(engine.record_count(), 0);
+    assert_eq!(engine.health().status, "read_only");
+    assert!(matches!(
+        engine.insert_record_from_f32(&[0.5; 4]),
+        Err(EngineError::RecoveryRefused(_))
+    ));
+    assert_eq!(std::fs::read(dir.path().join("events.log")).unwrap(), bytes);
+}
+
+#[test]
+fn test_recovery_policy_event_log_only_ignores_the_snapshot() {
+    use valori_node::engine::RecoveryPolicy;
+    let dir = tempdir().unwrap();
+    let mut cfg = make_cfg(dir.path(), 4);
+    {
+        let mut engine = Engine::new(&cfg);
+        engine.try_recover();
+        engine.insert_record_from_f32(&[0.1; 4]).unwrap();
+        engine.save_snapshot(None).unwrap();
+    }
+    std::fs::remove_file(dir.path().join("events.log")).unwrap();
+
+    cfg.recovery_policy = RecoveryPolicy::RecoverFromEventLogOnly;
+    let mut engine = Engine::new(&cfg);
+    assert_eq!(engine.try_recover(), RecoveryMode::Fresh);
+    assert_eq!(engine.record_count(), 0);
+    assert!(dir.path().join("snapshot.bin").exists());
+    drop(engine);
+
+    // A log that fails is refused rather than covered for by the snapshot.
+    let dir = tempdir().unwrap();
+    let mut cfg = make_cfg(dir.path(), 4);
+    corrupt_log_with_snapshot(&cfg);
+    cfg.recovery_policy = RecoveryPolicy::RecoverFromEventLogOnly;
+    assert!(matches!(
+        Engine::new(&cfg).try_recover(),
+        RecoveryMode::Refused(_)
+    ));
+}
+
+#[test]
+fn test_recovery_policy_quarantine_starts_empty() {
+    use valori_node::engine::RecoveryPolicy;
+    let dir = tempdir().unwrap();
+    let mut cfg = make_cfg(dir.path(), 4);
+    let bytes = corrupt_log_with_snapshot(&cfg);
+    cfg.recovery_policy = RecoveryPolicy::StartEmptyAndQuarantine;
+
+    {
+        let mut engine = Engine::new(&cfg);
+        let RecoveryMode::Quarantined(quarantine) = engine.try_recover() else {
+            panic!("expected quarantine");
+        };
+        assert!(quarantine.starts_with(dir.path().join("quarantine")));
+        assert_eq!(std::fs::read(quarantine.join("events.log")).unwrap(), bytes);
+        assert!(quarantine.join("snapshot.bin").exists());
+        assert!(!dir.path().join("snapshot.bin").exists());
+        assert_eq!(engine.record_count(), 0);
+        engine.insert_record_from_f32(&[0.5; 4]).unwrap();
+    }
+
+    // The fresh log is the one recovered from next time.
+    let mut engine = Engine::new(&cfg);
+    assert_eq!(engine.try_recover(), RecoveryMode::EventLog(1));
+}
@@ -0,0 +1,186 @@
+// Copyright (c) 2025 Varshith Gudur. Dual-licensed under MIT OR Apache-2.0.
+//! Asynchronous multi-region replication: a secondary region polls the
+//! primary, reports its lag, refuses writes until promoted, and an old
+//! primary rejoining the promoted region is fenced with the height where
+//! the two histories part.
+
+use std::sync::Arc;
+use std::time::Duration;
+use tokio::sync::RwLock;
+
+use valori_node::config::NodeConfig;
+use valori_node::engine::Engine;
+use valori_node::geo_replication::{
+    run_secondary_region, RegionConfig, RegionConflict, RegionState, RegionStatus, RegionTracker,
+};
+use valori_node::network::LeaderClient;
+use valori_node::server::{build_router, SharedEngine};
+use valori_node::EngineFromNodeConfig;
+
+const DIM: usize = 4;
+
+fn vector(i: usize) -> Vec<f32> {
+    (0..DIM).map(|d| ((i + d) % 7) as f32 / 7.0).collect()
+}
+
+fn engine(dir: &std::path::Path) -> Engine {
+    Engine::new(&NodeConfig {
+        event_log_path: Some(dir.join("events.log")),
+        max_records: 64,
+        dim: DIM,
+        max_nodes: 16,
+        max_edges: 16,
+        ..Default::default()
+    })
+}
+
+fn height(engine: &Engine) -> u64 {
+    engine
+        .event_committer()
+        .map(|c| c.journal().committed_height())
+        .unwrap_or(0)
+}
+
+fn write(engine: &mut Engine, from: usize, to: usize) {
+    for i in from..to {
+        engine.insert_record_tagged_ns(&vector(i), 0, 0).unwrap();
+    }
+}
+
+fn tracker(name: &str, primary: &str) -> Arc<RegionTracker> {
+    Arc::new(RegionTracker::new(RegionConfig {
+        name: name.into(),
+        primary_url: primary.into(),
+        poll_interval_ms: 50,
+    }))
+}
+
+async fn serve(state: SharedEngine, region: Option<Arc<RegionTracker>>) -> String {
+    let mut app = build_router(state, None, None);
+    if let Some(region) = region {
+        app = app.layer(axum::Extension(region));
+    }
+    let listener = tokio::net::TcpListener::bind("127.0.0.1:0").await.unwrap();
+    let base = format!("http://{}", listener.local_addr().unwrap());
+    tokio::spawn(async move { axum::serve(listener, app).await.unwrap() });
+    base
+}
+
+async fn wait_for(tracker: &RegionTracker, what: &str, done: impl Fn(&RegionStatus) -> bool) {
+    let deadline = tokio::time::Instant::now() + Duration::from_secs(20);
+    while !done(&tracker.status()) {
+        assert!(
+            tokio::time::Instant::now() < deadline,
+            "timed out waiting for {what}: {:?}",
+            tracker.status()
+        );
+        tokio::time::sleep(Duration::from_millis(25)).await;
+    }
+}
+
+#[tokio::test]
+async fn secondary_region_follows_is_promoted_and_fences_the_old_primary() {
+    let http = reqwest::Client::new();
+
+    let primary_dir = tempfile::tempdir().unwrap();
+    let mut e = engine(primary_dir.path());
+    write(&mut e, 0, 10);
+    let primary: SharedEngine = Arc::new(RwLock::new(e));
+    let primary_url = serve(primary.clone(), None).await;
+
+    let secondary_dir = tempfile::tempdir().unwrap();
+    let mut e = engine(secondary_dir.path());
+    e.secondary_of = Some(primary_url.clone());
+    let secondary: SharedEngine = Arc::new(RwLock::new(e));
+    let eu = tracker("eu", &primary_url);
+    let secondary_url = serve(secondary.clone(), Some(eu.clone())).await;
+    tokio::spawn(run_secondary_region(
+        secondary.clone(),
+        LeaderClient::new(primary_url.clone()),
+        eu.clone(),
+    ));
+
+    wait_for(&eu, "initial sync", |s| s.applied_height == 10).await;
+    {
+        let mut p = primary.write().await;
+        write(&mut p, 10, 15);
+    }
+    wait_for(&eu, "live sync", |s| s.applied_height == 15).await;
+    let status: RegionStatus = http
+        .get(format!("{secondary_url}/v1/replication/region"))
+        .send()
+        .await
+        .unwrap()
+        .json()
+        .await
+        .unwrap();
+    assert_eq!(status.state, RegionState::Following);
+    assert_eq!(
+        (status.primary_height, status.lag_events, status.lag_ms),
+        (15, 0, 0)
+    );
+    assert_eq!(
+        primary.read().await.get_proof().final_state_hash,
+        secondary.read().await.get_proof().final_state_hash
+    );
+
+    // Read-only until promoted.
+    let insert = serde_json::json!({ "vector": vector(100) });
+    let refused = http
+        .post(format!("{secondary_url}/v1/memory/upsert_vector"))
+        .json(&insert)
+        .send()
+        .await
+        .unwrap();
+    assert_eq!(refused.status().as_u16(), 503);
+
+    // The primary keeps writing while cut off; the region is promoted
+    // without those events and takes writes of its own.
+    let promoted: RegionStatus = http
+        .post(format!("{secondary_url}/v1/admin/region/promote"))
+        .send()
+        .await
+        .unwrap()
+        .json()
+        .await
+        .unwrap();
+    assert_eq!(promoted.state, RegionState::Promoted);
+    assert_eq!(promoted.promoted_epoch, Some(1));
+    {
+        let mut p = primary.write().await;
+        write(&mut p, 15, 18);
+    }
+    let accepted = http
+        .post(format!("{secondary_url}/v1/memory/upsert_vector"))
+        .json(&insert)
+        .send()
+        .await
+        .unwrap();
+    assert!(accepted.status().is_success(), "{:?}", accepted.status());
+    let promoted_height = {
+        let mut s = secondary.write().await;
+        write(&mut s, 20, 21);
+        height(&s)
+    };
+    assert!(promoted_height > 15);
+
+    // The old primary rejoins as a secondary of the promoted region.
+    let us = tracker("us", &secondary_url);
+    tokio::spawn(run_secondary_region(
+        primary.clone(),
+        LeaderClient::new(secondary_url.clone()),
+        us.clone(),
+    ));
+    wait_for(&us, "conflict", |s| s.state == RegionState::Conflict).await;
+    assert_eq!(
+        us.status().conflict,
+        Some(RegionConflict {
+            common_height: 15,
+            local_height: 18,
+            primary_height: promoted_height,
+        })
+    );
+    let p = primary.read().await;
+    assert!(p.fenced.is_some());
+    assert_eq!(height(&p), 18, "nothing was applied over the conflict");
+}
@@ -8,6 +8,7 @@
 //!   4. `GET /health` is reachable without an auth token even when auth is enabled
 //!   5. `GET /metrics` surfaces kernel-state gauges (non-empty Prometheus text)
 //!   6. `GET /metrics` is reachable without an auth token
+//!   7. `GET /readyz` reports recovery progress without taking the engine lock
 
 use valori_node::config::{IndexKind, NodeConfig};
 use valori_node::engine::Engine;
@@ -361,6 +362,45 @@ async fn test_http_metrics_returns_200_with_body() {
     assert!(!body.is_empty(), "/metrics must return a non-empty body");
 }
 
+/// `GET /readyz` answers 503 "recovering (N%)" while recovery holds the
+/// engine write lock, and 200 "ready" once it has finished.
+#[tokio::test]
+async fn test_http_readyz_reports_recovery_progress() {
+    let shared = make_shared(&tiny_cfg(100));
+    let progress = shared.read().await.recovery_progress.clone();
+    let app = build_router(shared.clone(), Some("super-secret".to_string()), None);
+    let get_readyz = || {
+        Request::builder()
+            .uri("/readyz")
+            .body(Body::empty())
+            .unwrap()
+    };
+
+    progress.begin();
+    progress.add_bytes_total(100);
+    progress.add_bytes_read(100);
+    progress.set_events_total(10);
+    for _ in 0..4 {
+        progress.event_replayed();
+    }
+    let guard = shared.write().await;
+    let resp = app.clone().oneshot(get_readyz()).await.unwrap();
+    assert_eq!(resp.status(), StatusCode::SERVICE_UNAVAILABLE);
+    let body = axum::body::to_bytes(resp.into_body(), 4096).await.unwrap();
+    let json: serde_json::Value = serde_json::from_slice(&body).unwrap();
+    assert_eq!(json["status"], "recovering (70%)");
+    assert_eq!(json["recovery"]["events_replayed"], 4);
+    assert_eq!(json["recovery"]["bytes_total"], 100);
+
+    progress.finish();
+    drop(guard);
+    let resp = app.oneshot(get_readyz()).await.unwrap();
+    assert_eq!(resp.status(), StatusCode::OK);
+    let body = axum::body::to_bytes(resp.into_body(), 4096).await.unwrap();
+    let json: serde_json::Value = serde_json::from_slice(&body).unwrap();
+    assert_eq!(json["status"], "ready");
+}
+
 /// `POST /records` must return **507 Insufficient Storage** when the record
 /// pool is already full.
 #[tokio::test]
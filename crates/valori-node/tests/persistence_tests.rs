@@ -60,3 +60,80 @@ async fn test_index_persistence() {
         println!("Truncation check passed: {:?}", res.err());
     }
 }
+
+#[tokio::test]
+async fn autosave_retention_prunes_the_catalog() {
+    use valori_node::persistence::{SnapshotManager, SnapshotRetention};
+
+    let dir = tempdir().unwrap();
+    let mut cfg = make_cfg(dir.path());
+    cfg.snapshot_keep = Some(2);
+    cfg.snapshot_keep_daily = Some(0);
+    let snap_path = cfg.snapshot_path.clone().unwrap();
+    let retention: SnapshotRetention = cfg.snapshot_retention().unwrap();
+    let catalog = SnapshotManager::catalog_dir(&snap_path);
+
+    let mut engine = Engine::new(&cfg);
+    engine
+        .insert_record_from_f32(&[1.0, 0.0, 0.0, 0.0])
+        .unwrap();
+    let data = engine.snapshot().unwrap();
+    let manual = SnapshotManager::add(
+        &catalog,
+        &data,
+        0,
+        engine.state_hash_hex(),
+        Some("manual".into()),
+    )
+    .unwrap();
+
+    let mut pruned = 0;
+    for i in 0..4 {
+        engine
+            .insert_record_from_f32(&[0.0, i as f32, 0.0, 0.0])
+            .unwrap();
+        pruned += SnapshotManager::autosave(&engine, &snap_path, Some(&retention)).unwrap();
+    }
+    assert_eq!(pruned, 2);
+    assert!(snap_path.exists());
+
+    let entries = SnapshotManager::list(&catalog).unwrap();
+    let ids: Vec<u64> = entries.iter().map(|e| e.id).collect();
+    assert_eq!(ids, vec![manual.id, 3, 4]);
+    let files = std::fs::read_dir(&catalog)
+        .unwrap()
+        .filter(|f| f.as_ref().unwrap().path().extension().unwrap() == "val")
+        .count();
+    assert_eq!(files, 3);
+
+    // Without a retention policy autosave only overwrites the snapshot path.
+    assert_eq!(
+        SnapshotManager::autosave(&engine, &snap_path, None).unwrap(),
+        0
+    );
+    assert_eq!(SnapshotManager::list(&catalog).unwrap().len(), 3);
+}
+
+#[tokio::test]
+async fn kernel_only_container_restores_into_a_node() {
+    use valori_kernel::snapshot::blake3::hash_state_blake3;
+    use valori_kernel::snapshot::container::encode_container;
+
+    let dir = tempdir().unwrap();
+    let cfg = make_cfg(dir.path());
+    let mut source = Engine::new(&cfg);
+    for i in 0..3 {
+        source.insert_record_from_f32(&[i as f32 * 0.1; 4]).unwrap();
+    }
+
+    // What the wasm kernel writes: no metadata, index or tagged sections.
+    let bytes = encode_container(&source.state).unwrap();
+    let mut engine = Engine::new(&cfg);
+    engine.restore(&bytes).expect("restore");
+    assert_eq!(
+        hash_state_blake3(&engine.state),
+        hash_state_blake3(&source.state)
+    );
+    // The index is rebuilt from the kernel.
+    assert_eq!(engine.search_l2(&[0.2; 4], 1).unwrap()[0].0, 2);
+}
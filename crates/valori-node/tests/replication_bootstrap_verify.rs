@@ -0,0 +1,222 @@
+// Copyright (c) 2025 Varshith Gudur. Dual-licensed under MIT OR Apache-2.0.
+//! A bootstrapping follower checks the leader's snapshot against the hashes
+//! the leader advertises before restoring it, and stays empty when they
+//! disagree.
+
+use std::sync::atomic::{AtomicUsize, Ordering};
+use std::sync::Arc;
+use std::time::{Duration, Instant};
+
+use axum::http::{HeaderMap, HeaderValue};
+use axum::routing::get;
+use tokio::sync::RwLock;
+use valori_node::config::{NodeConfig, NodeMode};
+use valori_node::engine::Engine;
+use valori_node::server::{build_router, SharedEngine};
+use valori_node::snapshot_stream::{HEIGHT_HEADER, SNAPSHOT_HASH_HEADER, STATE_HASH_HEADER};
+use valori_node::EngineFromNodeConfig;
+
+fn cfg() -> NodeConfig {
+    let mut cfg = NodeConfig::default();
+    cfg.dim = 4;
+    cfg.max_records = 64;
+    cfg
+}
+
+/// What a fake leader serves for `/v1/snapshot/download` and
+/// `/v1/proof/state`.
+#[derive(Clone)]
+struct Served {
+    bytes: Vec<u8>,
+    headers: Vec<(&'static str, String)>,
+    proof_hash: String,
+    proof_version: u64,
+}
+
+/// The snapshot of a leader holding three records, honestly advertised.
+fn honest() -> Served {
+    let mut leader = Engine::new(&cfg());
+    for i in 0..3 {
+        let mut row = [0.0f32; 4];
+        row[i] = 1.0;
+        leader.insert_record_from_f32(&row).unwrap();
+    }
+    let bytes = leader.snapshot().unwrap();
+    let state_hash = leader.state_hash_hex();
+    Served {
+        headers: vec![
+            (
+                SNAPSHOT_HASH_HEADER,
+                blake3::hash(&bytes).to_hex().to_string(),
+            ),
+            (STATE_HASH_HEADER, state_hash.clone()),
+            (HEIGHT_HEADER, "3".to_string()),
+        ],
+        bytes,
+        proof_hash: state_hash,
+        proof_version: leader.state.version(),
+    }
+}
+
+/// Serve `served`; returns the URL and a download counter.
+async fn fake_leader(served: Served) -> (String, Arc<AtomicUsize>) {
+    let downloads = Arc::new(AtomicUsize::new(0));
+    let (snap, proof, count) = (served.clone(), served, downloads.clone());
+    let app = axum::Router::new()
+        .route(
+            "/v1/snapshot/download",
+            get(move || {
+                let snap = snap.clone();
+                count.fetch_add(1, Ordering::SeqCst);
+                async move {
+                    let mut headers = HeaderMap::new();
+                    for (name, value) in &snap.headers {
+                        headers.insert(*name, HeaderValue::from_str(value).unwrap());
+                    }
+                    (headers, snap.bytes)
+                }
+            }),
+        )
+        .route(
+            "/v1/proof/state",
+            get(move || {
+                let body = serde_json::json!({
+                    "final_state_hash": proof.proof_hash,
+                    "version": proof.proof_version,
+                });
+                async move { axum::Json(body) }
+            }),
+        );
+    let listener = tokio::net::TcpListener::bind("127.0.0.1:0").await.unwrap();
+    let addr = listener.local_addr().unwrap();
+    tokio::spawn(async move {
+        axum::serve(listener, app).await.unwrap();
+    });
+    (format!("http://{addr}"), downloads)
+}
+
+/// Run an empty follower of `served` until it has downloaded the snapshot
+/// and had time to restore it; returns the follower.
+async fn bootstrap(served: Served) -> (SharedEngine, tempfile::TempDir) {
+    let (url, downloads) = fake_leader(served).await;
+    let dir = tempfile::tempdir().unwrap();
+    let mut cfg = cfg();
+    cfg.event_log_path = Some(dir.path().join("events.log"));
+    cfg.mode = NodeMode::Follower {
+        leader_url: url.clone(),
+    };
+    let follower = Arc::new(RwLock::new(Engine::new(&cfg)));
+    tokio::spawn(valori_node::replication::run_follower_loop(
+        follower.clone(),
+        url,
+    ));
+    let deadline = Instant::now() + Duration::from_secs(5);
+    while downloads.load(Ordering::SeqCst) == 0 {
+        assert!(Instant::now() < deadline, "follower never bootstrapped");
+        tokio::time::sleep(Duration::from_millis(20)).await;
+    }
+    tokio::time::sleep(Duration::from_millis(300)).await;
+    (follower, dir)
+}
+
+fn set(served: &mut Served, name: &str, value: String) {
+    served.headers.retain(|(n, _)| *n != name);
+    if let Some(n) = [SNAPSHOT_HASH_HEADER, STATE_HASH_HEADER, HEIGHT_HEADER]
+        .into_iter()
+        .find(|n| *n == name)
+    {
+        served.headers.push((n, value));
+    }
+}
+
+#[tokio::test(flavor = "multi_thread", worker_threads = 4)]
+async fn honest_snapshot_is_restored_at_the_advertised_height() {
+    let served = honest();
+    let want = served.proof_hash.clone();
+    let (follower, _dir) = bootstrap(served).await;
+    let engine = follower.read().await;
+    assert_eq!(engine.record_count(), 3);
+    assert_eq!(engine.state_hash_hex(), want);
+    assert_eq!(
+        engine
+            .event_committer()
+            .unwrap()
+            .journal()
+            .committed_height(),
+        3
+    );
+}
+
+#[tokio::test(flavor = "multi_thread", worker_threads = 4)]
+async fn snapshot_that_disagrees_with_the_leader_is_refused() {
+    // Advertised state hash of some other state.
+    let mut lying = honest();
+    set(&mut lying, STATE_HASH_HEADER, "ab".repeat(32));
+    // Another state's snapshot, re-hashed so the byte check passes: it
+    // decodes, but not to the state the leader advertised.
+    let mut swapped = honest();
+    let mut other = Engine::new(&cfg());
+    other.insert_record_from_f32(&[0.5, 0.5, 0.0, 0.0]).unwrap();
+    swapped.bytes = other.snapshot().unwrap();
+    let rehashed = blake3::hash(&swapped.bytes).to_hex().to_string();
+    set(&mut swapped, SNAPSHOT_HASH_HEADER, rehashed);
+    // Bytes that do not match their own advertised hash.
+    let mut torn = honest();
+    torn.bytes.truncate(torn.bytes.len() / 2);
+    // A leader without the headers whose proof at the same version differs.
+    let mut old_leader = honest();
+    old_leader.headers.clear();
+    old_leader.proof_hash = "cd".repeat(32);
+
+    for (name, served) in [
+        ("lying", lying),
+        ("swapped", swapped),
+        ("torn", torn),
+        ("old leader", old_leader),
+    ] {
+        let (follower, _dir) = bootstrap(served).await;
+        let engine = follower.read().await;
+        assert_eq!(engine.record_count(), 0, "{name}: snapshot was restored");
+        assert_eq!(
+            engine
+                .event_committer()
+                .unwrap()
+                .journal()
+                .committed_height(),
+            0,
+            "{name}"
+        );
+    }
+}
+
+#[tokio::test]
+async fn snapshot_download_advertises_state_hash_and_height() {
+    let dir = tempfile::tempdir().unwrap();
+    let mut cfg = cfg();
+    cfg.event_log_path = Some(dir.path().join("events.log"));
+    let leader = Arc::new(RwLock::new(Engine::new(&cfg)));
+    for _ in 0..2 {
+        leader
+            .write()
+            .await
+            .insert_record_from_f32(&[1.0, 0.0, 0.0, 0.0])
+            .unwrap();
+    }
+    let want = leader.read().await.state_hash_hex();
+    let app = build_router(leader, None, None);
+    let listener = tokio::net::TcpListener::bind("127.0.0.1:0").await.unwrap();
+    let addr = listener.local_addr().unwrap();
+    tokio::spawn(async move {
+        axum::serve(listener, app).await.unwrap();
+    });
+
+    let resp = reqwest::get(format!("http://{addr}/v1/snapshot/download"))
+        .await
+        .unwrap();
+    assert_eq!(resp.headers()[STATE_HASH_HEADER], want.as_str());
+    assert_eq!(resp.headers()[HEIGHT_HEADER], "2");
+    let bytes = resp.bytes().await.unwrap();
+    let (hash, _) = Engine::snapshot_state_proof(&bytes).unwrap();
+    let hex: String = hash.iter().map(|b| format!("{b:02x}")).collect();
+    assert_eq!(hex, want);
+}
@@ -0,0 +1,124 @@
+// Copyright (c) 2025 Varshith Gudur. Dual-licensed under MIT OR Apache-2.0.
+//! Chained replication frames: the leader sends each entry with its event
+//! chain, and a follower refuses a stream that disagrees with its own
+//! history before committing anything from it.
+
+use std::path::Path;
+use std::sync::Arc;
+use std::time::{Duration, Instant};
+
+use tokio::sync::RwLock;
+use valori_node::config::{NodeConfig, NodeMode};
+use valori_node::engine::Engine;
+use valori_node::geo_replication::{event_chain, local_events};
+use valori_node::replication_codec::{StreamDecoder, StreamFormat, CHAINED_FRAMES_CONTENT_TYPE};
+use valori_node::server::{build_router, SharedEngine};
+use valori_node::EngineFromNodeConfig;
+
+fn cfg(dir: &Path) -> NodeConfig {
+    let mut cfg = NodeConfig::default();
+    cfg.dim = 4;
+    cfg.max_records = 64;
+    cfg.event_log_path = Some(dir.join("events.log"));
+    cfg
+}
+
+async fn serve(engine: SharedEngine) -> String {
+    let app = build_router(engine, None, None);
+    let listener = tokio::net::TcpListener::bind("127.0.0.1:0").await.unwrap();
+    let addr = listener.local_addr().unwrap();
+    tokio::spawn(async move {
+        axum::serve(listener, app).await.unwrap();
+    });
+    format!("http://{addr}")
+}
+
+#[tokio::test]
+async fn chained_stream_carries_the_leader_event_chain() {
+    let dir = tempfile::tempdir().unwrap();
+    let leader = Arc::new(RwLock::new(Engine::new(&cfg(dir.path()))));
+    for i in 0..3 {
+        let mut row = [0.0f32; 4];
+        row[i] = 1.0;
+        leader.write().await.insert_record_from_f32(&row).unwrap();
+    }
+    let base = serve(leader.clone()).await;
+
+    let mut res = reqwest::Client::new()
+        .get(format!("{base}/v1/replication/events?start_offset=1"))
+        .header("accept", CHAINED_FRAMES_CONTENT_TYPE)
+        .send()
+        .await
+        .unwrap();
+    let content_type = res.headers()["content-type"].to_str().unwrap().to_string();
+    assert_eq!(
+        StreamFormat::from_content_type(Some(&content_type)),
+        StreamFormat::ChainedFrames
+    );
+
+    // Two historical entries (heights 1 and 2), then one live.
+    let mut decoder = StreamDecoder::new(StreamFormat::ChainedFrames);
+    let mut prevs = Vec::new();
+    while prevs.len() < 3 {
+        if prevs.len() == 2 {
+            leader
+                .write()
+                .await
+                .insert_record_from_f32(&[0.0, 0.0, 0.0, 1.0])
+                .unwrap();
+        }
+        let chunk = tokio::time::timeout(Duration::from_secs(10), res.chunk())
+            .await
+            .expect("stream stalled")
+            .unwrap()
+            .expect("stream ended early");
+        decoder.push(&chunk);
+        while let Some((prev, _)) = decoder.next_chained().unwrap() {
+            prevs.push(prev.expect("chained frames carry prev_hash"));
+        }
+    }
+
+    let chain = event_chain(&local_events(&leader).await.unwrap());
+    assert_eq!(prevs, chain[1..4].to_vec());
+}
+
+#[tokio::test(flavor = "multi_thread", worker_threads = 4)]
+async fn forked_follower_refuses_the_stream_and_heals() {
+    let leader_dir = tempfile::tempdir().unwrap();
+    let leader = Arc::new(RwLock::new(Engine::new(&cfg(leader_dir.path()))));
+    for row in [[1.0, 0.0, 0.0, 0.0], [0.0, 1.0, 0.0, 0.0]] {
+        leader.write().await.insert_record_from_f32(&row).unwrap();
+    }
+    let leader_url = serve(leader.clone()).await;
+
+    // Same height as the leader's first event, different history.
+    let follower_dir = tempfile::tempdir().unwrap();
+    let mut follower_cfg = cfg(follower_dir.path());
+    follower_cfg.mode = NodeMode::Follower {
+        leader_url: leader_url.clone(),
+    };
+    let follower = Arc::new(RwLock::new(Engine::new(&follower_cfg)));
+    follower
+        .write()
+        .await
+        .insert_record_from_f32(&[0.0, 0.0, 1.0, 0.0])
+        .unwrap();
+
+    let f = follower.clone();
+    tokio::spawn(valori_node::replication::run_follower_loop(f, leader_url));
+
+    // Refusing the leader's second event triggers the heal at once; the
+    // periodic state-hash check would not fire for another 5 s.
+    let want = leader.read().await.get_proof().final_state_hash;
+    let deadline = Instant::now() + Duration::from_secs(4);
+    loop {
+        if follower.read().await.get_proof().final_state_hash == want {
+            break;
+        }
+        assert!(
+            Instant::now() < deadline,
+            "forked follower was not healed by the chain check"
+        );
+        tokio::time::sleep(Duration::from_millis(50)).await;
+    }
+}
@@ -0,0 +1,164 @@
+// Copyright (c) 2025 Varshith Gudur. Dual-licensed under MIT OR Apache-2.0.
+//! Compressed `/v1/replication/events` stream.
+//!
+//!   - the leader honours `Accept-Encoding: zstd | gzip` and the decoded
+//!     stream is byte-identical to the uncompressed one, at a fraction of
+//!     the wire size for D=384 payloads
+//!   - a follower's `LeaderClient` asking for zstd still sees live events as
+//!     soon as they commit (the encoder is flushed per batch)
+
+use std::io::Write;
+use std::sync::Arc;
+use std::time::Duration;
+use tokio::sync::RwLock;
+
+use valori_node::config::NodeConfig;
+use valori_node::engine::Engine;
+use valori_node::network::LeaderClient;
+use valori_node::replication_codec::{StreamDecoder, StreamFormat};
+use valori_node::replication_compression::StreamCompression;
+use valori_node::server::{build_router, SharedEngine};
+use valori_node::EngineFromNodeConfig;
+
+const DIM: usize = 384;
+const HISTORY: usize = 64;
+
+fn vector(i: usize) -> Vec<f32> {
+    (0..DIM).map(|d| ((i * 7 + d) % 97) as f32 / 97.0).collect()
+}
+
+async fn spawn_leader(dir: &std::path::Path) -> (SharedEngine, String) {
+    let cfg = NodeConfig {
+        event_log_path: Some(dir.join("events.log")),
+        max_records: 256,
+        dim: DIM,
+        max_nodes: 16,
+        max_edges: 16,
+        ..Default::default()
+    };
+    let mut engine = Engine::new(&cfg);
+    for i in 0..HISTORY {
+        engine.insert_record_from_f32(&vector(i)).unwrap();
+    }
+    let state = Arc::new(RwLock::new(engine));
+    let listener = tokio::net::TcpListener::bind("127.0.0.1:0").await.unwrap();
+    let addr = listener.local_addr().unwrap();
+    let app = build_router(state.clone(), None, None);
+    tokio::spawn(async move { axum::serve(listener, app).await.unwrap() });
+    (state, format!("http://{addr}"))
+}
+
+/// Read the raw (still encoded) stream until `decode` yields `lines` lines.
+async fn read_lines(
+    base: &str,
+    accept: &str,
+    lines: usize,
+    mut decode: impl FnMut(&[u8]) -> Vec<u8>,
+) -> (Option<String>, usize, String) {
+    let client = reqwest::Client::builder()
+        .no_gzip()
+        .no_zstd()
+        .build()
+        .unwrap();
+    let mut resp = client
+        .get(format!("{base}/v1/replication/events?start_offset=0"))
+        .header("accept-encoding", accept)
+        .send()
+        .await
+        .unwrap();
+    assert!(resp.status().is_success());
+    let encoding = resp
+        .headers()
+        .get("content-encoding")
+        .map(|v| v.to_str().unwrap().to_string());
+
+    let mut wire = 0;
+    let mut decoded = Vec::new();
+    while decoded.iter().filter(|b| **b == b'\n').count() < lines {
+        let chunk = tokio::time::timeout(Duration::from_secs(10), resp.chunk())
+            .await
+            .expect("stream stalled")
+            .unwrap()
+            .expect("stream ended early");
+        wire += chunk.len();
+        decoded.extend(decode(&chunk));
+    }
+    (encoding, wire, String::from_utf8(decoded).unwrap())
+}
+
+#[tokio::test]
+async fn leader_compresses_when_asked_and_stream_decodes_identically() {
+    let tmp = tempfile::tempdir().unwrap();
+    let (_state, base) = spawn_leader(tmp.path()).await;
+
+    let (enc, raw_len, raw) = read_lines(&base, "identity", HISTORY, |c| c.to_vec()).await;
+    assert_eq!(enc, None);
+
+    let mut zstd = zstd::stream::write::Decoder::new(Vec::new()).unwrap();
+    let (enc, zstd_len, zstd_text) = read_lines(&base, "zstd", HISTORY, |c| {
+        zstd.write_all(c).unwrap();
+        zstd.flush().unwrap();
+        std::mem::take(zstd.get_mut())
+    })
+    .await;
+    assert_eq!(enc.as_deref(), Some("zstd"));
+    assert_eq!(zstd_text, raw);
+
+    let mut gzip = flate2::write::GzDecoder::new(Vec::new());
+    let (enc, gzip_len, gzip_text) = read_lines(&base, "gzip", HISTORY, |c| {
+        gzip.write_all(c).unwrap();
+        gzip.flush().unwrap();
+        std::mem::take(gzip.get_mut())
+    })
+    .await;
+    assert_eq!(enc.as_deref(), Some("gzip"));
+    assert_eq!(gzip_text, raw);
+
+    assert!(
+        zstd_len * 2 < raw_len && gzip_len * 2 < raw_len,
+        "D={DIM}: raw {raw_len} B, zstd {zstd_len} B, gzip {gzip_len} B"
+    );
+}
+
+#[tokio::test]
+async fn follower_client_receives_live_events_over_zstd() {
+    let tmp = tempfile::tempdir().unwrap();
+    let (state, base) = spawn_leader(tmp.path()).await;
+
+    let client = LeaderClient::new(base).with_compression(StreamCompression::Zstd);
+    let mut resp = client.stream_events(0).await.unwrap();
+    let format = StreamFormat::from_content_type(
+        resp.headers()
+            .get("content-type")
+            .and_then(|v| v.to_str().ok()),
+    );
+    assert_eq!(format, StreamFormat::ChainedFrames);
+
+    let mut decoder = StreamDecoder::new(format);
+    let mut received = 0;
+    let mut want = HISTORY;
+    loop {
+        while received < want {
+            let chunk = tokio::time::timeout(Duration::from_secs(10), resp.chunk())
+                .await
+                .expect("compressed stream stalled")
+                .unwrap()
+                .expect("stream ended early");
+            decoder.push(&chunk);
+            while decoder.next_entry().expect("valid frame").is_some() {
+                received += 1;
+            }
+        }
+        if want > HISTORY {
+            break;
+        }
+        // History fully received — commit one more and expect it promptly.
+        state
+            .write()
+            .await
+            .insert_record_from_f32(&vector(HISTORY))
+            .unwrap();
+        want += 1;
+    }
+    assert_eq!(received, HISTORY + 1);
+}
@@ -0,0 +1,232 @@
+// Copyright (c) 2025 Varshith Gudur. Dual-licensed under MIT OR Apache-2.0.
+//! Mutual TLS on the standalone replication channel.
+//!
+//! A leader serving over TLS accepts certificate-less clients for the normal
+//! API, but the replication route group only answers connections whose
+//! client certificate chains to the replication CA. A certificate from any
+//! other CA is refused at the handshake.
+//!
+//! Certificates are generated in-test with rcgen: one replication CA, a
+//! leader (server) leaf, a follower (client) leaf, and a rogue CA.
+
+use rcgen::{BasicConstraints, CertificateParams, IsCa, KeyPair};
+use std::sync::Arc;
+use tokio::sync::RwLock;
+
+use valori_node::config::NodeConfig;
+use valori_node::engine::Engine;
+use valori_node::network::LeaderClient;
+use valori_node::server::build_router;
+use valori_node::tls::{serve_tls, serve_tls_with_drain, ReplicationTlsConfig};
+use valori_node::EngineFromNodeConfig;
+
+struct TestCa {
+    cert: rcgen::Certificate,
+    key: KeyPair,
+}
+
+fn make_ca(name: &str) -> TestCa {
+    let mut params = CertificateParams::new(vec![]).unwrap();
+    params.is_ca = IsCa::Ca(BasicConstraints::Unconstrained);
+    params
+        .distinguished_name
+        .push(rcgen::DnType::CommonName, name);
+    let key = KeyPair::generate().unwrap();
+    let cert = params.self_signed(&key).unwrap();
+    TestCa { cert, key }
+}
+
+/// A leaf for `localhost`, signed by `ca`.
+fn tls_config(ca: &TestCa) -> ReplicationTlsConfig {
+    let params = CertificateParams::new(vec!["localhost".to_string()]).unwrap();
+    let key = KeyPair::generate().unwrap();
+    let cert = params.signed_by(&key, &ca.cert, &ca.key).unwrap();
+    ReplicationTlsConfig {
+        ca_pem: ca.cert.pem().into_bytes(),
+        cert_pem: cert.pem().into_bytes(),
+        key_pem: key.serialize_pem().into_bytes(),
+    }
+}
+
+async fn spawn_tls_leader(tls: &ReplicationTlsConfig, dir: &std::path::Path) -> String {
+    let mut cfg = NodeConfig::default();
+    cfg.event_log_path = Some(dir.join("events.log"));
+    cfg.max_records = 100;
+    cfg.dim = 4;
+    cfg.max_nodes = 50;
+    cfg.max_edges = 50;
+    let state = Arc::new(RwLock::new(Engine::new(&cfg)));
+    let app = build_router(state, None, None);
+    let listener = tokio::net::TcpListener::bind("127.0.0.1:0").await.unwrap();
+    let port = listener.local_addr().unwrap().port();
+    let server_cfg = tls.server_config().unwrap();
+    tokio::spawn(serve_tls(
+        listener,
+        app,
+        server_cfg,
+        std::future::pending::<()>(),
+    ));
+    format!("https://localhost:{port}")
+}
+
+/// A client that trusts the CA but presents no certificate.
+fn anonymous_client(ca: &TestCa) -> reqwest::Client {
+    reqwest::Client::builder()
+        .use_rustls_tls()
+        .tls_built_in_root_certs(false)
+        .add_root_certificate(reqwest::Certificate::from_pem(ca.cert.pem().as_bytes()).unwrap())
+        .build()
+        .unwrap()
+}
+
+#[tokio::test]
+async fn follower_with_ca_signed_cert_can_replicate() {
+    let ca = make_ca("valori-replication");
+    let tmp = tempfile::tempdir().unwrap();
+    let base = spawn_tls_leader(&tls_config(&ca), tmp.path()).await;
+
+    let follower = LeaderClient::new(base)
+        .with_tls(Some(&tls_config(&ca)))
+        .unwrap();
+    assert!(follower.get_proof().await.is_ok());
+    assert!(
+        follower.download_snapshot().await.is_ok(),
+        "a CA-signed follower must be able to pull the snapshot"
+    );
+    assert!(follower.stream_events(0).await.is_ok());
+}
+
+#[tokio::test]
+async fn certificate_less_client_is_refused_on_replication_routes_only() {
+    let ca = make_ca("valori-replication");
+    let tmp = tempfile::tempdir().unwrap();
+    let base = spawn_tls_leader(&tls_config(&ca), tmp.path()).await;
+    let client = anonymous_client(&ca);
+
+    let health = client.get(format!("{base}/health")).send().await.unwrap();
+    assert!(health.status().is_success());
+    let proof = client
+        .get(format!("{base}/v1/proof/state"))
+        .send()
+        .await
+        .unwrap();
+    assert!(proof.status().is_success());
+
+    for path in ["/v1/replication/state", "/v1/snapshot/download"] {
+        let resp = client.get(format!("{base}{path}")).send().await.unwrap();
+        assert_eq!(resp.status().as_u16(), 403, "{path} must require mTLS");
+    }
+}
+
+#[tokio::test]
+async fn follower_from_a_different_ca_is_refused_at_the_handshake() {
+    let ca = make_ca("valori-replication");
+    let tmp = tempfile::tempdir().unwrap();
+    let base = spawn_tls_leader(&tls_config(&ca), tmp.path()).await;
+
+    // Trusts the right CA, but its own identity was issued by a rogue CA.
+    let rogue = make_ca("rogue");
+    let mut rogue_tls = tls_config(&rogue);
+    rogue_tls.ca_pem = ca.cert.pem().into_bytes();
+    let follower = LeaderClient::new(base).with_tls(Some(&rogue_tls)).unwrap();
+
+    assert!(follower.download_snapshot().await.is_err());
+    assert!(follower.stream_events(0).await.is_err());
+}
+
+/// A router whose only route takes `delay` to answer.
+fn slow_router(delay: std::time::Duration) -> axum::Router {
+    axum::Router::new().route(
+        "/slow",
+        axum::routing::get(move || async move {
+            tokio::time::sleep(delay).await;
+            "done"
+        }),
+    )
+}
+
+#[tokio::test]
+async fn shutdown_waits_for_in_flight_requests() {
+    let ca = make_ca("valori-replication");
+    let listener = tokio::net::TcpListener::bind("127.0.0.1:0").await.unwrap();
+    let port = listener.local_addr().unwrap().port();
+    let (stop, stopped) = tokio::sync::oneshot::channel::<()>();
+    let server = tokio::spawn(serve_tls(
+        listener,
+        slow_router(std::time::Duration::from_millis(500)),
+        tls_config(&ca).server_config().unwrap(),
+        async {
+            let _ = stopped.await;
+        },
+    ));
+
+    let client = anonymous_client(&ca);
+    let request = tokio::spawn(async move {
+        client
+            .get(format!("https://localhost:{port}/slow"))
+            .send()
+            .await
+    });
+    tokio::time::sleep(std::time::Duration::from_millis(150)).await;
+    stop.send(()).unwrap();
+    tokio::time::sleep(std::time::Duration::from_millis(100)).await;
+    assert!(
+        !server.is_finished(),
+        "serve_tls must not return while a request is in flight"
+    );
+
+    let resp = request
+        .await
+        .unwrap()
+        .expect("in-flight request must complete");
+    assert!(resp.status().is_success());
+    assert_eq!(resp.text().await.unwrap(), "done");
+    tokio::time::timeout(std::time::Duration::from_secs(5), server)
+        .await
+        .expect("serve_tls must return once drained")
+        .unwrap();
+}
+
+#[tokio::test]
+async fn shutdown_aborts_connections_after_the_drain_timeout() {
+    let ca = make_ca("valori-replication");
+    let listener = tokio::net::TcpListener::bind("127.0.0.1:0").await.unwrap();
+    let port = listener.local_addr().unwrap().port();
+    let (stop, stopped) = tokio::sync::oneshot::channel::<()>();
+    let server = tokio::spawn(serve_tls_with_drain(
+        listener,
+        slow_router(std::time::Duration::from_secs(60)),
+        tls_config(&ca).server_config().unwrap(),
+        async {
+            let _ = stopped.await;
+        },
+        std::time::Duration::from_millis(200),
+    ));
+
+    let client = anonymous_client(&ca);
+    let request = tokio::spawn(async move {
+        client
+            .get(format!("https://localhost:{port}/slow"))
+            .send()
+            .await
+    });
+    tokio::time::sleep(std::time::Duration::from_millis(150)).await;
+    stop.send(()).unwrap();
+
+    tokio::time::timeout(std::time::Duration::from_secs(5), server)
+        .await
+        .expect("serve_tls must give up on a stuck connection")
+        .unwrap();
+    assert!(
+        request.await.unwrap().is_err(),
+        "the aborted request must not succeed"
+    );
+}
+
+#[test]
+fn debug_redacts_the_private_key() {
+    let tls = tls_config(&make_ca("valori-replication"));
+    let dbg = format!("{tls:?}");
+    assert!(dbg.contains("<redacted>"));
+    assert!(!dbg.contains("PRIVATE KEY"));
+}
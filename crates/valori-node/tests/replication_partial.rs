@@ -0,0 +1,185 @@
+// Copyright (c) 2025 Varshith Gudur. Dual-licensed under MIT OR Apache-2.0.
+//! Partial replication: a follower that keeps one collection's records with
+//! a given tag bit.
+//!
+//! The follower replays the leader's whole log (sealed segments, then the
+//! live stream) through its filter, stays at the leader's height, and ends
+//! up with exactly the state the leader reports for that filter on
+//! `GET /v1/replication/partial-proof`.
+
+use std::sync::Arc;
+use std::time::Duration;
+use tokio::sync::RwLock;
+
+use valori_node::api_audit::{ApiAuditLog, AuditQuery};
+use valori_node::api_keys::KeyStore;
+use valori_node::config::NodeConfig;
+use valori_node::engine::Engine;
+use valori_node::network::LeaderClient;
+use valori_node::replication_filter::{PartialProof, ReplicationFilter};
+use valori_node::server::{build_router_with_auth, SharedEngine};
+use valori_node::EngineFromNodeConfig;
+
+const DIM: usize = 4;
+
+fn vector(i: usize) -> Vec<f32> {
+    (0..DIM).map(|d| ((i + d) % 11) as f32 / 11.0).collect()
+}
+
+fn config(dir: &std::path::Path) -> NodeConfig {
+    NodeConfig {
+        event_log_path: Some(dir.join("events.log")),
+        event_log_rotation_bytes: Some(1024),
+        max_records: 256,
+        dim: DIM,
+        max_nodes: 16,
+        max_edges: 16,
+        ..Default::default()
+    }
+}
+
+fn height(engine: &Engine) -> u64 {
+    engine
+        .event_committer()
+        .map(|c| c.journal().committed_height())
+        .unwrap_or(0)
+}
+
+/// Records `i` lands in: even ids in `edge`, odd ones in `default`; every
+/// third carries tag bit 2.
+fn write(engine: &mut Engine, edge: u16, i: usize) -> u32 {
+    let ns = if i % 2 == 0 { edge } else { 0 };
+    let tag = if i % 3 == 0 { 2 | 1 } else { 1 };
+    engine.insert_record_tagged_ns(&vector(i), tag, ns).unwrap()
+}
+
+async fn wait_for_height(follower: &SharedEngine, target: u64) {
+    let deadline = tokio::time::Instant::now() + Duration::from_secs(20);
+    while height(&*follower.read().await) < target {
+        assert!(
+            tokio::time::Instant::now() < deadline,
+            "follower stuck at {} of {target}",
+            height(&*follower.read().await)
+        );
+        tokio::time::sleep(Duration::from_millis(50)).await;
+    }
+}
+
+#[tokio::test]
+async fn filtered_follower_matches_the_leaders_partial_proof() {
+    let leader_dir = tempfile::tempdir().unwrap();
+    let mut engine = Engine::new(&config(leader_dir.path()));
+    let edge = engine.create_collection("edge").unwrap();
+    for i in 0..60 {
+        write(&mut engine, edge, i);
+    }
+    // Deletes on both sides of the filter: 0 is kept, 1 never was.
+    engine.delete_record(0).unwrap();
+    engine.delete_record(1).unwrap();
+    let leader = Arc::new(RwLock::new(engine));
+    let audit = Arc::new(ApiAuditLog::in_memory());
+    let app = build_router_with_auth(
+        leader.clone(),
+        None,
+        None,
+        Arc::new(KeyStore::new(None)),
+        Arc::new(valori_effect::ReceiptStore::new(16)),
+        Vec::new(),
+        audit.clone(),
+    );
+    let listener = tokio::net::TcpListener::bind("127.0.0.1:0").await.unwrap();
+    let base = format!("http://{}", listener.local_addr().unwrap());
+    tokio::spawn(async move { axum::serve(listener, app).await.unwrap() });
+
+    let follower_dir = tempfile::tempdir().unwrap();
+    let mut cfg = config(follower_dir.path());
+    cfg.event_log_rotation_bytes = None;
+    let follower = Arc::new(RwLock::new(Engine::new(&cfg)));
+    let filter = ReplicationFilter::parse(Some("edge"), Some("any:2"))
+        .unwrap()
+        .unwrap();
+    tokio::spawn(valori_node::replication::run_follower_loop_with_filter(
+        follower.clone(),
+        LeaderClient::new(base.clone()),
+        Some(filter.clone()),
+    ));
+
+    let caught_up = height(&*leader.read().await);
+    wait_for_height(&follower, caught_up).await;
+    // Live events after the catch-up go through the same filter.
+    {
+        let mut engine = leader.write().await;
+        for i in 60..66 {
+            write(&mut engine, edge, i);
+        }
+    }
+    let target = height(&*leader.read().await);
+    wait_for_height(&follower, target).await;
+
+    // Kept: even (edge) ids divisible by 3, i.e. multiples of 6, minus the
+    // deleted record 0.
+    let kept: Vec<u32> = (6..66).step_by(6).collect();
+    {
+        let f = follower.read().await;
+        assert_eq!(f.record_count(), kept.len());
+        for id in &kept {
+            assert!(f
+                .state
+                .get_record(valori_kernel::types::id::RecordId(*id))
+                .is_some());
+        }
+    }
+
+    let http = reqwest::Client::new();
+    let proof: PartialProof = http
+        .get(format!(
+            "{base}/v1/replication/partial-proof?{}",
+            filter.query()
+        ))
+        .send()
+        .await
+        .unwrap()
+        .json()
+        .await
+        .unwrap();
+    assert_eq!(proof.height, target);
+    let follower_hash: String = follower
+        .read()
+        .await
+        .get_proof()
+        .final_state_hash
+        .iter()
+        .map(|b| format!("{b:02x}"))
+        .collect();
+    assert_eq!(proof.partial_state_hash, follower_hash);
+    let leader_hash: String = leader
+        .read()
+        .await
+        .get_proof()
+        .final_state_hash
+        .iter()
+        .map(|b| format!("{b:02x}"))
+        .collect();
+    assert_ne!(proof.partial_state_hash, leader_hash);
+
+    let routes: Vec<String> = audit
+        .query(&AuditQuery {
+            limit: Some(1000),
+            ..Default::default()
+        })
+        .await
+        .into_iter()
+        .map(|e| e.route)
+        .collect();
+    assert!(
+        !routes.iter().any(|r| r == "/v1/snapshot/download"),
+        "a partial replica must not bootstrap from the full snapshot: {routes:?}"
+    );
+
+    let bad = http
+        .get(format!("{base}/v1/replication/partial-proof?tags=some:1"))
+        .send()
+        .await
+        .unwrap();
+    assert_eq!(bad.status().as_u16(), 400);
+}
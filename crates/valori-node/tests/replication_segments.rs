@@ -0,0 +1,239 @@
+// Copyright (c) 2025 Varshith Gudur. Dual-licensed under MIT OR Apache-2.0.
+//! Deep follower catch-up from the leader's sealed event-log segments.
+//!
+//! The leader rotates its event log every few KiB, so a follower that fell
+//! behind is below the live segment's first height. It must replay the
+//! sealed segments (`/v1/replication/segments/{n}`) rather than pull a
+//! snapshot, and end up byte-identical to the leader.
+
+use std::sync::Arc;
+use std::time::Duration;
+use tokio::sync::RwLock;
+
+use valori_node::api_audit::{ApiAuditLog, AuditQuery};
+use valori_node::api_keys::KeyStore;
+use valori_node::config::NodeConfig;
+use valori_node::engine::Engine;
+use valori_node::network::LeaderClient;
+use valori_node::replication_segments::{decode_segment, SegmentIndex};
+use valori_node::server::{build_router_with_auth, SharedEngine};
+use valori_node::EngineFromNodeConfig;
+
+const DIM: usize = 4;
+const TOTAL: usize = 200;
+const FOLLOWER_HAS: usize = 5;
+
+fn vector(i: usize) -> Vec<f32> {
+    (0..DIM).map(|d| ((i + d) % 11) as f32 / 11.0).collect()
+}
+
+fn config(dir: &std::path::Path) -> NodeConfig {
+    NodeConfig {
+        event_log_path: Some(dir.join("events.log")),
+        event_log_rotation_bytes: Some(1024),
+        max_records: 256,
+        dim: DIM,
+        max_nodes: 16,
+        max_edges: 16,
+        ..Default::default()
+    }
+}
+
+fn height(engine: &Engine) -> u64 {
+    engine
+        .event_committer()
+        .map(|c| c.journal().committed_height())
+        .unwrap_or(0)
+}
+
+async fn spawn_leader(dir: &std::path::Path) -> (SharedEngine, Arc<ApiAuditLog>, String) {
+    let mut engine = Engine::new(&config(dir));
+    for i in 0..TOTAL {
+        engine.insert_record_from_f32(&vector(i)).unwrap();
+    }
+    let state = Arc::new(RwLock::new(engine));
+    let audit = Arc::new(ApiAuditLog::in_memory());
+    let app = build_router_with_auth(
+        state.clone(),
+        None,
+        None,
+        Arc::new(KeyStore::new(None)),
+        Arc::new(valori_effect::ReceiptStore::new(16)),
+        Vec::new(),
+        audit.clone(),
+    );
+    let listener = tokio::net::TcpListener::bind("127.0.0.1:0").await.unwrap();
+    let addr = listener.local_addr().unwrap();
+    tokio::spawn(async move { axum::serve(listener, app).await.unwrap() });
+    (state, audit, format!("http://{addr}"))
+}
+
+#[tokio::test]
+async fn leader_lists_and_serves_sealed_segments() {
+    let tmp = tempfile::tempdir().unwrap();
+    let (_state, _audit, base) = spawn_leader(tmp.path()).await;
+    let http = reqwest::Client::new();
+
+    let index: SegmentIndex = http
+        .get(format!("{base}/v1/replication/segments"))
+        .send()
+        .await
+        .unwrap()
+        .json()
+        .await
+        .unwrap();
+    assert!(index.archived.len() >= 2, "{index:?}");
+    assert!(index.live_first_height > FOLLOWER_HAS as u64);
+    assert_eq!(index.archived[0].first_height, Some(0));
+
+    // Every sealed segment decodes, and together they run contiguously up to
+    // the live segment.
+    let mut next = 0;
+    let mut prev = None;
+    for seg in &index.archived {
+        let bytes = http
+            .get(format!("{base}/v1/replication/segments/{}", seg.seq))
+            .send()
+            .await
+            .unwrap()
+            .bytes()
+            .await
+            .unwrap();
+        assert_eq!(bytes.len() as u64, seg.bytes);
+        let segment = decode_segment(&bytes, prev.as_ref()).unwrap();
+        for (h, _) in segment.entries {
+            assert_eq!(h, next);
+            next += 1;
+        }
+        prev = Some(segment.end);
+    }
+    assert_eq!(next, index.live_first_height);
+
+    let missing = http
+        .get(format!("{base}/v1/replication/segments/9999"))
+        .send()
+        .await
+        .unwrap();
+    assert_eq!(missing.status().as_u16(), 404);
+
+    let stale = http
+        .get(format!("{base}/v1/replication/events?start_offset=0"))
+        .send()
+        .await
+        .unwrap();
+    assert_eq!(
+        stale.status().as_u16(),
+        410,
+        "the live stream must refuse offsets below its first height"
+    );
+}
+
+#[tokio::test]
+async fn lagging_follower_replays_segments_instead_of_a_snapshot() {
+    let leader_dir = tempfile::tempdir().unwrap();
+    let (leader, audit, base) = spawn_leader(leader_dir.path()).await;
+
+    // The follower committed the first few events before it went away.
+    let follower_dir = tempfile::tempdir().unwrap();
+    let mut cfg = config(follower_dir.path());
+    cfg.event_log_rotation_bytes = None;
+    let mut engine = Engine::new(&cfg);
+    for i in 0..FOLLOWER_HAS {
+        engine.insert_record_from_f32(&vector(i)).unwrap();
+    }
+    let follower = Arc::new(RwLock::new(engine));
+
+    tokio::spawn(valori_node::replication::run_follower_loop_with_client(
+        follower.clone(),
+        LeaderClient::new(base),
+    ));
+
+    let target = height(&*leader.read().await);
+    let deadline = tokio::time::Instant::now() + Duration::from_secs(20);
+    while height(&*follower.read().await) < target {
+        assert!(
+            tokio::time::Instant::now() < deadline,
+            "follower stuck at {} of {target}",
+            height(&*follower.read().await)
+        );
+        tokio::time::sleep(Duration::from_millis(50)).await;
+    }
+
+    assert_eq!(
+        follower.read().await.get_proof().final_state_hash,
+        leader.read().await.get_proof().final_state_hash
+    );
+    let routes: Vec<String> = audit
+        .query(&AuditQuery {
+            limit: Some(1000),
+            ..Default::default()
+        })
+        .await
+        .into_iter()
+        .map(|e| e.route)
+        .collect();
+    assert!(routes
+        .iter()
+        .any(|r| r.starts_with("/v1/replication/segments/")));
+    assert!(
+        !routes.iter().any(|r| r == "/v1/snapshot/download"),
+        "catch-up must not fall back to a snapshot: {routes:?}"
+    );
+}
+
+#[tokio::test]
+async fn segments_that_do_not_extend_the_follower_chain_are_not_applied() {
+    let leader_dir = tempfile::tempdir().unwrap();
+    let (leader, audit, base) = spawn_leader(leader_dir.path()).await;
+
+    // The follower's first events are not the leader's: its history parted
+    // before the segments it would replay.
+    let follower_dir = tempfile::tempdir().unwrap();
+    let mut cfg = config(follower_dir.path());
+    cfg.event_log_rotation_bytes = None;
+    let mut engine = Engine::new(&cfg);
+    for i in 0..FOLLOWER_HAS {
+        engine.insert_record_from_f32(&vector(i + 50)).unwrap();
+    }
+    let follower = Arc::new(RwLock::new(engine));
+
+    tokio::spawn(valori_node::replication::run_follower_loop_with_client(
+        follower.clone(),
+        LeaderClient::new(base),
+    ));
+
+    let leader_hash = leader.read().await.get_proof().final_state_hash;
+    let deadline = tokio::time::Instant::now() + Duration::from_secs(20);
+    while follower.read().await.get_proof().final_state_hash != leader_hash {
+        assert!(
+            tokio::time::Instant::now() < deadline,
+            "follower never converged"
+        );
+        tokio::time::sleep(Duration::from_millis(50)).await;
+    }
+
+    // The segments were downloaded and refused: the follower went to a
+    // snapshot without streaming on top of a forked history first.
+    let routes: Vec<String> = audit
+        .query(&AuditQuery {
+            limit: Some(1000),
+            ..Default::default()
+        })
+        .await
+        .into_iter()
+        .map(|e| e.route)
+        .collect();
+    let snapshot = routes
+        .iter()
+        .position(|r| r == "/v1/snapshot/download")
+        .expect("a forked follower must bootstrap from a snapshot");
+    assert!(routes[..snapshot]
+        .iter()
+        .any(|r| r.starts_with("/v1/replication/segments/")));
+    assert!(
+        !routes[..snapshot]
+            .iter()
+            .any(|r| r == "/v1/replication/events"),
+        "forked segments must be refused before the live stream: {routes:?}"
+    );
+}
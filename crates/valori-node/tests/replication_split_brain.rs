@@ -0,0 +1,150 @@
+// Copyright (c) 2025 Varshith Gudur. Dual-licensed under MIT OR Apache-2.0.
+//! Split-brain detection with leader epochs.
+//!
+//! A node claims a new epoch each time it starts as leader. A follower that
+//! holds events written after the height a newer leader's epoch started at
+//! has a forked log: it must fence itself rather than replicate over it. A
+//! leader told of a newer epoch in the handshake was superseded and must
+//! refuse writes.
+
+use std::sync::Arc;
+use std::time::Duration;
+use tokio::sync::RwLock;
+
+use valori_node::api_audit::ApiAuditLog;
+use valori_node::api_keys::KeyStore;
+use valori_node::config::NodeConfig;
+use valori_node::engine::Engine;
+use valori_node::errors::EngineError;
+use valori_node::network::LeaderClient;
+use valori_node::server::{build_router_with_auth, SharedEngine};
+use valori_node::EngineFromNodeConfig;
+
+const DIM: usize = 4;
+
+fn config(dir: &std::path::Path) -> NodeConfig {
+    NodeConfig {
+        event_log_path: Some(dir.join("events.log")),
+        max_records: 64,
+        dim: DIM,
+        max_nodes: 16,
+        max_edges: 16,
+        ..Default::default()
+    }
+}
+
+fn insert(engine: &mut Engine, n: usize) {
+    for i in 0..n {
+        engine
+            .insert_record_from_f32(&[i as f32 / 10.0, 0.1, 0.2, 0.3])
+            .unwrap();
+    }
+}
+
+async fn serve(engine: Engine) -> (SharedEngine, String) {
+    let state = Arc::new(RwLock::new(engine));
+    let app = build_router_with_auth(
+        state.clone(),
+        None,
+        None,
+        Arc::new(KeyStore::new(None)),
+        Arc::new(valori_effect::ReceiptStore::new(16)),
+        Vec::new(),
+        Arc::new(ApiAuditLog::in_memory()),
+    );
+    let listener = tokio::net::TcpListener::bind("127.0.0.1:0").await.unwrap();
+    let addr = listener.local_addr().unwrap();
+    tokio::spawn(async move { axum::serve(listener, app).await.unwrap() });
+    (state, format!("http://{addr}"))
+}
+
+#[test]
+fn claimed_epoch_is_chained_and_survives_restart() {
+    let tmp = tempfile::tempdir().unwrap();
+    {
+        let mut engine = Engine::new(&config(tmp.path()));
+        insert(&mut engine, 3);
+        let e = engine.claim_leader_epoch().unwrap();
+        assert_eq!((e.epoch, e.start_height), (1, 3));
+        assert_eq!(engine.claim_leader_epoch().unwrap().epoch, 2);
+    }
+    let mut engine = Engine::new(&config(tmp.path()));
+    engine.try_recover();
+    assert_eq!(engine.leader_epoch.epoch, 2);
+    assert_eq!(engine.health().leader_epoch, 2);
+    assert_eq!(engine.claim_leader_epoch().unwrap().epoch, 3);
+}
+
+#[test]
+fn superseded_leader_is_fenced_and_refuses_writes() {
+    let tmp = tempfile::tempdir().unwrap();
+    let mut engine = Engine::new(&config(tmp.path()));
+    engine.claim_leader_epoch().unwrap();
+
+    assert!(!engine.fence_if_superseded(1));
+    assert!(engine.fence_if_superseded(2));
+    assert!(engine.health().fenced.is_some());
+    assert!(matches!(
+        engine.insert_record_from_f32(&[0.1, 0.2, 0.3, 0.4]),
+        Err(EngineError::Fenced(_))
+    ));
+}
+
+#[tokio::test]
+async fn handshake_fences_a_leader_that_was_superseded() {
+    let tmp = tempfile::tempdir().unwrap();
+    let mut engine = Engine::new(&config(tmp.path()));
+    insert(&mut engine, 2);
+    engine.claim_leader_epoch().unwrap();
+    let (leader, base) = serve(engine).await;
+    let client = LeaderClient::new(base.clone());
+
+    let epoch = client.handshake(1).await.unwrap().unwrap();
+    assert_eq!((epoch.epoch, epoch.start_height), (1, 2));
+    assert!(leader.read().await.fenced.is_none());
+
+    // A follower has already seen epoch 2: this leader was replaced.
+    assert!(client.handshake(2).await.is_err());
+    assert!(leader.read().await.fenced.is_some());
+    let resp = reqwest::Client::new()
+        .post(format!("{base}/v1/memory/upsert_vector"))
+        .json(&serde_json::json!({"vector": [0.1, 0.2, 0.3, 0.4]}))
+        .send()
+        .await
+        .unwrap();
+    assert_eq!(resp.status().as_u16(), 503);
+}
+
+#[tokio::test]
+async fn follower_with_a_forked_log_fences_instead_of_replicating() {
+    // New leader: epoch 1 started at height 2.
+    let leader_dir = tempfile::tempdir().unwrap();
+    let mut engine = Engine::new(&config(leader_dir.path()));
+    insert(&mut engine, 2);
+    engine.claim_leader_epoch().unwrap();
+    let (_leader, base) = serve(engine).await;
+
+    // The old leader kept taking writes under epoch 0 past that height.
+    let follower_dir = tempfile::tempdir().unwrap();
+    let mut engine = Engine::new(&config(follower_dir.path()));
+    insert(&mut engine, 5);
+    let before = engine.get_proof().final_state_hash;
+    let follower = Arc::new(RwLock::new(engine));
+
+    let run = tokio::spawn(valori_node::replication::run_follower_loop_with_client(
+        follower.clone(),
+        LeaderClient::new(base),
+    ));
+    tokio::time::timeout(Duration::from_secs(10), run)
+        .await
+        .expect("a forked follower must stop replicating")
+        .unwrap();
+
+    let engine = follower.read().await;
+    assert!(engine.fenced.as_deref().unwrap().contains("split brain"));
+    assert_eq!(engine.get_proof().final_state_hash, before);
+    assert_eq!(
+        valori_node::replication::replication_display_state(),
+        "SplitBrain"
+    );
+}
@@ -25,17 +25,115 @@ const STANDALONE_ONLY: &[&str] = &[
     // Raw snapshot upload writes directly into the local engine — a cluster
     // node must restore via Raft snapshot install, not an HTTP body.
     "/v1/snapshot/upload",
+    "/v1/snapshot/upload/init",
+    "/v1/snapshot/upload/:id",
+    "/v1/snapshot/upload/:id/finalize",
     // WAL streaming/replication endpoints are the standalone replication
     // primitive; cluster mode replicates through Raft instead.
     "/v1/replication/wal",
     "/v1/replication/events",
     "/v1/replication/state",
+    // Leader epochs fence standalone leader/follower pairs; Raft terms do
+    // the same job in a cluster.
+    "/v1/replication/handshake",
+    // Sealed-segment catch-up serves the standalone event-log files.
+    "/v1/replication/segments",
+    "/v1/replication/segments/:seq",
+    // Replays the standalone event log through a partial-replication filter.
+    "/v1/replication/partial-proof",
+    // Asynchronous multi-region replication between standalone leaders.
+    "/v1/replication/head",
+    "/v1/replication/chain",
+    "/v1/replication/region",
+    "/v1/admin/region/promote",
     // Object-store offload is per-node standalone ops tooling today.
     "/v1/storage/snapshots",
     "/v1/storage/snapshots/upload",
     "/v1/storage/snapshots/restore",
     "/v1/storage/wal",
     "/v1/storage/wal/archive",
+    // Rebuilt from the standalone engine's event journal; cluster history
+    // lives in the Raft log and is only exposed through /v1/timeline.
+    "/v1/diff",
+    "/v1/analytics/drift",
+    "/v1/events",
+    // Checks the standalone engine's index and graph maps against its kernel.
+    "/v1/admin/check",
+    // The forgetting policy runs on the standalone insert path.
+    "/v1/admin/forget",
+    // ResizePools is committed through the standalone event log; a cluster
+    // would need it proposed through Raft.
+    "/v1/admin/resize",
+    // Vacuum, likewise, is a standalone event-log commit.
+    "/v1/admin/vacuum",
+    // Bulk import commits batches through the standalone engine.
+    "/v1/admin/import",
+    // Export pages through the standalone engine's kernel pools.
+    "/v1/export",
+    // Annotations are admin entries in the standalone event log.
+    "/v1/admin/annotations",
+    // Checks and repairs the standalone engine's in-memory HNSW graphs.
+    "/v1/admin/index/repair",
+    // Measures the standalone engine's ANN indexes against exact search.
+    "/v1/admin/recall",
+    // Replays the standalone event log on top of a cataloged snapshot.
+    "/v1/admin/restore",
+    // Configured collections are separate engines in the standalone node's
+    // registry; a cluster replicates a single engine through Raft.
+    "/v1/collections",
+    "/v1/collections/:name",
+    "/v1/collections/:name/records",
+    "/v1/collections/:name/records/:id",
+    "/v1/collections/:name/search",
+    "/v1/collections/:name/snapshot",
+    "/v1/collections/:name/proof",
+    "/v1/proof/collections",
+    // Rotate / snapshot the standalone event log on demand; cluster segments
+    // rotate inside the audit sink and Raft owns its snapshots.
+    "/v1/admin/rotate-log",
+    "/v1/admin/compact",
+    // Defers index insertion on the standalone engine; cluster shards apply
+    // committed entries through the Raft state machine.
+    "/v1/admin/bulk-load/begin",
+    "/v1/admin/bulk-load/finish",
+    // Tunes the standalone process (auto-snapshot task, rate limiter, log
+    // filter); cluster nodes take these from their environment.
+    "/v1/admin/config",
+    // Search-hit counters are kept by the standalone engine's search path.
+    "/v1/records/:id/stats",
+    // Sparse vectors are committed through the standalone event log and
+    // scanned from its engine; cluster shards do not route them yet.
+    "/v1/records/:id/sparse",
+    "/v1/sparse/search",
+    // One event batch through the standalone event log; the cluster path
+    // commits one Raft entry per event and has no batch entry yet.
+    "/v1/records/delete_batch",
+    // Validated and committed as one batch under the standalone engine lock;
+    // the cluster path commits one Raft entry per event.
+    "/v1/transactions",
+    // Sessions buffer on the node that owns them and commit through the
+    // standalone transaction path above.
+    "/v1/sessions",
+    "/v1/sessions/:id",
+    "/v1/sessions/:id/operations",
+    "/v1/sessions/:id/savepoints",
+    "/v1/sessions/:id/rollback",
+    "/v1/sessions/:id/commit",
+    "/v1/tx/begin",
+    "/v1/tx/stage",
+    "/v1/tx/commit",
+    "/v1/tx/abort",
+    // The catalog lives next to the standalone VALORI_SNAPSHOT_PATH; cluster
+    // snapshots are taken and installed by Raft.
+    "/v1/snapshot/list",
+    // Sizes the standalone engine's event log, snapshot and metadata files;
+    // cluster storage is per-shard Raft state.
+    "/v1/stats/storage",
+    // Counts one kernel; a cluster node holds one per shard.
+    "/v1/stats",
+    // Replays the standalone event log; cluster shards prove through
+    // /v1/cluster/proof.
+    "/v1/proof/at",
 ];
 
 /// Routes that exist ONLY on the cluster router, with the reason.
@@ -0,0 +1,90 @@
+// Copyright (c) 2025 Varshith Gudur. Dual-licensed under MIT OR Apache-2.0.
+//! The HTTP API served over `VALORI_UNIX_SOCKET`: same router, socket file
+//! lifecycle (stale files replaced, live ones refused, removed on shutdown).
+#![cfg(unix)]
+
+use std::sync::Arc;
+use tokio::io::{AsyncReadExt, AsyncWriteExt};
+use tokio::net::UnixStream;
+use tokio::sync::RwLock;
+
+use valori_node::config::NodeConfig;
+use valori_node::engine::Engine;
+use valori_node::server::build_router;
+use valori_node::unix_socket::{serve_unix, UnixSocketListener};
+use valori_node::EngineFromNodeConfig;
+
+fn router() -> axum::Router {
+    let mut cfg = NodeConfig::default();
+    cfg.dim = 4;
+    cfg.max_records = 100;
+    cfg.event_log_path = None;
+    cfg.snapshot_path = None;
+    cfg.wal_path = None;
+    build_router(Arc::new(RwLock::new(Engine::new(&cfg))), None, None)
+}
+
+/// One HTTP/1.1 request over the socket; returns the raw response.
+async fn request(path: &std::path::Path, method: &str, uri: &str, body: &str) -> String {
+    let mut stream = UnixStream::connect(path).await.unwrap();
+    let req = format!(
+        "{method} {uri} HTTP/1.1\r\nHost: localhost\r\nConnection: close\r\n\
+         Content-Type: application/json\r\nContent-Length: {}\r\n\r\n{body}",
+        body.len()
+    );
+    stream.write_all(req.as_bytes()).await.unwrap();
+    let mut out = String::new();
+    stream.read_to_string(&mut out).await.unwrap();
+    out
+}
+
+#[tokio::test]
+async fn api_is_served_over_the_socket() {
+    let dir = tempfile::tempdir().unwrap();
+    let sock = dir.path().join("valori.sock");
+    let listener = UnixSocketListener::bind(&sock).unwrap();
+    let (stop_tx, mut stop_rx) = tokio::sync::watch::channel(());
+    let server = tokio::spawn(serve_unix(listener, router(), async move {
+        let _ = stop_rx.changed().await;
+    }));
+
+    let health = request(&sock, "GET", "/health", "").await;
+    assert!(health.starts_with("HTTP/1.1 200"), "{health}");
+
+    let insert = request(
+        &sock,
+        "POST",
+        "/v1/records",
+        r#"{"values":[0.1,0.2,0.3,0.4]}"#,
+    )
+    .await;
+    assert!(insert.starts_with("HTTP/1.1 200"), "{insert}");
+    assert!(insert.contains(r#""id":0"#), "{insert}");
+
+    stop_tx.send(()).unwrap();
+    server.await.unwrap();
+    assert!(!sock.exists(), "socket file must be removed on shutdown");
+}
+
+#[tokio::test]
+async fn bind_replaces_stale_sockets_only() {
+    let dir = tempfile::tempdir().unwrap();
+
+    // A socket left behind by a crashed process is replaced.
+    let stale = dir.path().join("stale.sock");
+    drop(std::os::unix::net::UnixListener::bind(&stale).unwrap());
+    assert!(stale.exists());
+    let listener = UnixSocketListener::bind(&stale).unwrap();
+
+    // One that is still listening is not.
+    let err = UnixSocketListener::bind(&stale).err().unwrap();
+    assert_eq!(err.kind(), std::io::ErrorKind::AddrInUse);
+    drop(listener);
+    assert!(!stale.exists());
+
+    // Nor is anything that is not a socket.
+    let file = dir.path().join("data.bin");
+    std::fs::write(&file, b"keep me").unwrap();
+    assert!(UnixSocketListener::bind(&file).is_err());
+    assert_eq!(std::fs::read(&file).unwrap(), b"keep me");
+}
@@ -62,10 +62,13 @@ pub enum OperationKind {
     Consolidate,
     Contradict,
     HealthCheck,
-    /// Hard or soft deletion of one record.
+    /// Hard or soft deletion of one record, or of a batch
+    /// (`/v1/records/delete_batch`).
     Delete,
     /// Batch insert of multiple vectors in one HTTP call.
     BatchInsert,
+    /// Mixed operations committed as one event batch (`/v1/transactions`).
+    Transaction,
 
     // ── Planned: endpoint exists in valori-node but still calls logic directly ──
     // These will be migrated to the planner pipeline in a future phase.
@@ -120,7 +123,7 @@ pub enum OperationInputs {
         shard_id: u8,
     },
     HealthCheck,
-    /// Hard or soft deletion of one record by id.
+    /// Hard or soft deletion of one record by id, or of a batch of ids.
     Delete {
         collection: String,
         shard_id: u8,
@@ -133,6 +136,12 @@ pub enum OperationInputs {
         collection: String,
         shard_id: u8,
     },
+    /// Mixed operations committed as one event batch.
+    Transaction {
+        count: u32,
+        collection: String,
+        shard_id: u8,
+    },
 
     // ── Planned: endpoint exists in valori-node but still calls logic directly ──
     // To be migrated to the planner pipeline. Migration order:
@@ -297,6 +306,7 @@ fn kind_discriminant(kind: OperationKind) -> u8 {
         OperationKind::TreeQuery => 13,
         OperationKind::TreeHybrid => 14,
         OperationKind::Snapshot => 15,
+        OperationKind::Transaction => 16,
     }
 }
 
@@ -0,0 +1,90 @@
+// Copyright (c) 2025 Varshith Gudur. Dual-licensed under MIT OR Apache-2.0.
+//! The compatibility check readers run when they open a file.
+//!
+//! Every reader of a versioned file calls [`check_version`] on the version
+//! in its header, and [`check_dim`] on the dimension where the header has
+//! one, before decoding anything else. A binary built against another
+//! release of this crate then refuses a file it cannot read with the same
+//! message everywhere, instead of misreading it.
+
+use crate::formats::{event_log, snapshot, wal};
+use crate::limits::MAX_DIM;
+use core::fmt;
+use core::ops::RangeInclusive;
+use thiserror::Error;
+
+/// A versioned file format.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum Format {
+    /// Event-log segment (`valori-wire`).
+    EventLog,
+    /// Legacy write-ahead log (`valori-storage`).
+    Wal,
+    /// Encoded `KernelState`, the kernel section of a snapshot.
+    KernelSnapshot,
+}
+
+impl Format {
+    /// Versions this build reads.
+    pub const fn supported(self) -> RangeInclusive<u32> {
+        match self {
+            Self::EventLog => event_log::MIN_VERSION..=event_log::CURRENT_VERSION,
+            Self::Wal => wal::MIN_VERSION..=wal::WAL_VERSION,
+            Self::KernelSnapshot => {
+                snapshot::KERNEL_MIN_SCHEMA_VERSION..=snapshot::KERNEL_SCHEMA_VERSION
+            }
+        }
+    }
+
+    /// Version this build writes.
+    pub const fn current(self) -> u32 {
+        *self.supported().end()
+    }
+}
+
+impl fmt::Display for Format {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(match self {
+            Self::EventLog => "event log",
+            Self::Wal => "WAL",
+            Self::KernelSnapshot => "kernel snapshot",
+        })
+    }
+}
+
+/// Why a file cannot be opened by this build.
+#[derive(Error, Debug, Clone, PartialEq, Eq)]
+pub enum Incompatible {
+    #[error("{format} version {found} is not supported (this build reads v{min} to v{max})")]
+    Version {
+        format: Format,
+        found: u32,
+        min: u32,
+        max: u32,
+    },
+
+    #[error("dimension {0} is outside 1..={MAX_DIM}")]
+    Dim(u32),
+}
+
+pub fn check_version(format: Format, found: u32) -> Result<(), Incompatible> {
+    let supported = format.supported();
+    if supported.contains(&found) {
+        Ok(())
+    } else {
+        Err(Incompatible::Version {
+            format,
+            found,
+            min: *supported.start(),
+            max: *supported.end(),
+        })
+    }
+}
+
+pub fn check_dim(dim: u32) -> Result<(), Incompatible> {
+    if (1..=MAX_DIM as u64).contains(&(dim as u64)) {
+        Ok(())
+    } else {
+        Err(Incompatible::Dim(dim))
+    }
+}
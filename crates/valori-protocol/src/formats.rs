@@ -0,0 +1,110 @@
+// Copyright (c) 2025 Varshith Gudur. Dual-licensed under MIT OR Apache-2.0.
+//! Magics, versions and header layouts of the files Valori writes.
+//!
+//! The encoders and decoders live with their formats (`valori-kernel`,
+//! `valori-wire`, `valori-storage`); this module is what they and every
+//! other reader share. All integers are little-endian.
+
+/// Snapshot files: the container and the kernel state inside it.
+pub mod snapshot {
+    /// Magic of the snapshot container (`valori_kernel::snapshot::container`).
+    pub const CONTAINER_MAGIC: &[u8; 4] = b"VAL1";
+
+    /// Tag of the container's CRC32 trailer.
+    pub const CHECKSUM_TAG: &[u8; 4] = b"CRC3";
+
+    /// Magic of an encoded `KernelState` (the container's kernel section).
+    pub const KERNEL_MAGIC: &[u8; 4] = b"VALK";
+
+    /// Schema version the kernel encoder writes. V10: sparse vectors.
+    pub const KERNEL_SCHEMA_VERSION: u32 = 10;
+
+    /// Oldest kernel schema version the decoder still reads.
+    pub const KERNEL_MIN_SCHEMA_VERSION: u32 = 1;
+}
+
+/// Event-log segments (`valori-wire`).
+///
+/// ```text
+/// v2:     version u32 (=2) | dim u32 | reserved u64                   16 B
+/// v3/v4:  version u32 | dim u32 | format_id u8 | reserved [u8;3] |
+///         segment_seq u32 | prev_segment_chain_head [u8;32]           48 B
+/// ```
+pub mod event_log {
+    pub const VERSION_V2: u32 = 2;
+    pub const VERSION_V3: u32 = 3;
+    /// V4 adds a CRC32 suffix to every entry; the header is V3's.
+    pub const VERSION_V4: u32 = 4;
+
+    /// Version new segments are written in.
+    pub const CURRENT_VERSION: u32 = VERSION_V4;
+    /// Oldest version readers still accept.
+    pub const MIN_VERSION: u32 = VERSION_V2;
+
+    pub const HEADER_SIZE_V2: usize = 16;
+    pub const HEADER_SIZE_V3: usize = 48;
+    pub const HEADER_SIZE_V4: usize = HEADER_SIZE_V3;
+
+    /// Bytes of the per-entry CRC32 suffix in V4 segments.
+    pub const CRC32_SUFFIX_LEN: usize = 4;
+}
+
+/// The legacy write-ahead log (`valori-storage`), also streamed to the
+/// firmware.
+///
+/// ```text
+/// version u32 | encoding_version u32 | dim u32 | checksum_len u32     16 B
+/// ```
+pub mod wal {
+    /// Version the writer emits: a bincode stream of `(KernelEvent, u16)`.
+    pub const WAL_VERSION: u32 = 2;
+    /// Oldest version readers still accept (v1: `Command` stream).
+    pub const MIN_VERSION: u32 = 1;
+
+    /// The 16-byte WAL header.
+    #[derive(Debug, Clone, Copy, PartialEq, Eq)]
+    pub struct WalHeader {
+        pub version: u32,
+        pub encoding_version: u32,
+        /// 0 when the writer did not know it.
+        pub dim: u32,
+        pub checksum_len: u32,
+    }
+
+    impl WalHeader {
+        pub const SIZE: usize = 16;
+
+        /// Header of a new file in the current version.
+        pub fn new(dim: u32) -> Self {
+            Self {
+                version: WAL_VERSION,
+                encoding_version: 0,
+                dim,
+                checksum_len: 0,
+            }
+        }
+
+        /// `None` when `bytes` is shorter than [`WalHeader::SIZE`].
+        pub fn decode(bytes: &[u8]) -> Option<Self> {
+            let word = |i: usize| {
+                let b = bytes.get(i * 4..i * 4 + 4)?;
+                Some(u32::from_le_bytes([b[0], b[1], b[2], b[3]]))
+            };
+            Some(Self {
+                version: word(0)?,
+                encoding_version: word(1)?,
+                dim: word(2)?,
+                checksum_len: word(3)?,
+            })
+        }
+
+        pub fn encode(&self) -> [u8; Self::SIZE] {
+            let mut bytes = [0u8; Self::SIZE];
+            bytes[0..4].copy_from_slice(&self.version.to_le_bytes());
+            bytes[4..8].copy_from_slice(&self.encoding_version.to_le_bytes());
+            bytes[8..12].copy_from_slice(&self.dim.to_le_bytes());
+            bytes[12..16].copy_from_slice(&self.checksum_len.to_le_bytes());
+            bytes
+        }
+    }
+}
@@ -0,0 +1,28 @@
+// Copyright (c) 2025 Varshith Gudur. Dual-licensed under MIT OR Apache-2.0.
+//! Q16.16 fixed-point: the only arithmetic format in production.
+
+/// Fractional bits of a scalar.
+pub const FRAC_BITS: u32 = 16;
+
+/// `1.0` as a raw scalar (`1 << FRAC_BITS`).
+pub const SCALE: i32 = 1 << FRAC_BITS;
+
+/// [`SCALE`] for float conversions.
+pub const SCALE_F32: f32 = SCALE as f32;
+pub const SCALE_F64: f64 = SCALE as f64;
+
+/// Smallest float accepted at the API boundary.
+pub const MIN_F32: f32 = -32768.0;
+
+/// Largest float accepted at the API boundary — just under `i32::MAX / SCALE`
+/// so that truncation never wraps.
+pub const MAX_F32: f32 = 32767.99;
+
+/// Format id stamped in event-log headers and kernel snapshots. A log or
+/// snapshot in another format must never verify as Q16.16.
+pub const FORMAT_Q16_16: u8 = 1;
+
+/// True when `v` converts to a scalar without overflow.
+pub fn in_range(v: f32) -> bool {
+    (MIN_F32..=MAX_F32).contains(&v)
+}
@@ -0,0 +1,32 @@
+// Copyright (c) 2025 Varshith Gudur. Dual-licensed under MIT OR Apache-2.0.
+//! # valori-protocol
+//!
+//! The numbers every Valori binary must agree on, defined once.
+//!
+//! The node, the offline verifier, the forensic CLI, the Python FFI, the
+//! firmware and the demo log generator all read and write the same files.
+//! Before this crate each kept its own copy of the fixed-point scale, the
+//! pool limits and the header layouts, and the copies drifted — the event
+//! log rejected dimensions the kernel accepted. Change a value here, never
+//! in a consumer.
+//!
+//! ## Contents
+//! - [`fxp`] — Q16.16 scale and the accepted `f32` range
+//! - [`limits`] — dimension, pool and metadata ceilings
+//! - [`formats`] — magics, versions and header layouts of every file
+//! - [`compat`] — the check readers run when they open a file
+
+#![cfg_attr(not(feature = "std"), no_std)]
+#![forbid(unsafe_code)]
+
+pub mod compat;
+pub mod formats;
+pub mod fxp;
+pub mod limits;
+
+pub use compat::{check_dim, check_version, Format, Incompatible};
+pub use fxp::{FRAC_BITS, SCALE};
+pub use limits::{
+    MAX_DIM, MAX_EDGES, MAX_METADATA_SIZE, MAX_META_ENTRIES, MAX_META_KEY_SIZE, MAX_NODES,
+    MAX_RECORDS, MAX_SPARSE_NNZ,
+};
@@ -0,0 +1,32 @@
+// Copyright (c) 2025 Varshith Gudur. Dual-licensed under MIT OR Apache-2.0.
+//! Hard ceilings enforced when a file is read. They bound every allocation a
+//! crafted snapshot or log could ask for.
+
+/// Maximum vector dimension, at insert time, in snapshot decode and in an
+/// event-log header. 65 536 dimensions × 4 bytes = 256 KiB per vector.
+pub const MAX_DIM: usize = 65_536;
+
+/// Maximum record slots in a snapshot.
+pub const MAX_RECORDS: usize = 10_000_000;
+
+/// Maximum graph nodes in a snapshot.
+pub const MAX_NODES: usize = 50_000_000;
+
+/// Maximum graph edges in a snapshot.
+pub const MAX_EDGES: usize = 200_000_000;
+
+/// Maximum bytes of one record's metadata blob, in a snapshot, an event and
+/// at the API boundary.
+pub const MAX_METADATA_SIZE: usize = 64 * 1024;
+
+/// Maximum key-value pairs in the kernel's `meta` section.
+pub const MAX_META_ENTRIES: usize = 1_000_000;
+
+/// Maximum bytes of one key in the kernel's `meta` section, checked when a
+/// `SetMeta` event is committed. Values share [`MAX_METADATA_SIZE`]. Replay
+/// does not check it, so logs written before it still replay.
+pub const MAX_META_KEY_SIZE: usize = 1024;
+
+/// Maximum non-zero entries of one sparse vector, in an event, a snapshot
+/// and at the API boundary. SPLADE documents stay well below it.
+pub const MAX_SPARSE_NNZ: usize = 4096;
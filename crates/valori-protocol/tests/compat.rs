@@ -0,0 +1,66 @@
+// Copyright (c) 2025 Varshith Gudur. Dual-licensed under MIT OR Apache-2.0.
+//! Open-time compatibility checks and the shared header layouts.
+
+use valori_protocol::formats::{event_log, wal::WalHeader};
+use valori_protocol::{check_dim, check_version, fxp, Format, Incompatible, MAX_DIM};
+
+#[test]
+fn every_supported_version_is_accepted() {
+    for format in [Format::EventLog, Format::Wal, Format::KernelSnapshot] {
+        for v in format.supported() {
+            assert_eq!(check_version(format, v), Ok(()), "{format} v{v}");
+        }
+        assert_eq!(format.current(), *format.supported().end());
+    }
+    assert_eq!(Format::EventLog.current(), event_log::VERSION_V4);
+}
+
+#[test]
+fn a_version_from_a_newer_build_is_refused_with_the_supported_range() {
+    let err = check_version(Format::Wal, 3).unwrap_err();
+    assert_eq!(
+        err,
+        Incompatible::Version {
+            format: Format::Wal,
+            found: 3,
+            min: 1,
+            max: 2,
+        }
+    );
+    assert_eq!(
+        err.to_string(),
+        "WAL version 3 is not supported (this build reads v1 to v2)"
+    );
+    assert!(check_version(Format::EventLog, 1).is_err());
+    assert!(check_version(Format::KernelSnapshot, 0).is_err());
+}
+
+#[test]
+fn dims_outside_one_to_max_dim_are_refused() {
+    assert_eq!(check_dim(0), Err(Incompatible::Dim(0)));
+    assert_eq!(check_dim(1), Ok(()));
+    assert_eq!(check_dim(MAX_DIM as u32), Ok(()));
+    assert_eq!(
+        check_dim(MAX_DIM as u32 + 1),
+        Err(Incompatible::Dim(MAX_DIM as u32 + 1))
+    );
+}
+
+#[test]
+fn wal_header_round_trips_and_rejects_short_input() {
+    let header = WalHeader::new(384);
+    let bytes = header.encode();
+    assert_eq!(&bytes[0..4], &2u32.to_le_bytes());
+    assert_eq!(&bytes[8..12], &384u32.to_le_bytes());
+    assert_eq!(WalHeader::decode(&bytes), Some(header));
+    assert_eq!(WalHeader::decode(&bytes[..WalHeader::SIZE - 1]), None);
+}
+
+#[test]
+fn float_range_converts_without_wrapping() {
+    assert!(fxp::in_range(fxp::MIN_F32));
+    assert!(fxp::in_range(fxp::MAX_F32));
+    assert!(!fxp::in_range(32768.0));
+    assert!(!fxp::in_range(f32::NAN));
+    assert!(((fxp::MAX_F32 * fxp::SCALE_F32) as i64) <= i32::MAX as i64);
+}
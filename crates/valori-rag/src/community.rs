@@ -263,8 +263,9 @@ pub fn build_community_store(
                 if let Some(node) = state.get_node(NodeId(nid)) {
                     if let Some(rid) = node.record {
                         if let Some(rec) = state.get_record(rid) {
-                            if rec.is_searchable() && rec.vector.data.len() == dim {
-                                for (i, s) in rec.vector.data.iter().enumerate() {
+                            let vector = state.record_vector(rec);
+                            if rec.is_searchable() && vector.len() == dim {
+                                for (i, s) in vector.iter().enumerate() {
                                     sum[i] += s.0 as f64 / SCALE as f64;
                                 }
                                 count += 1;
@@ -21,8 +21,10 @@ use crate::error::{StateError, StateResult};
 use std::path::Path;
 use valori_kernel::snapshot::decode::decode_state;
 use valori_kernel::state::kernel::KernelState;
-use valori_storage::events::event_replay::{recover_from_event_log, verify_snapshot_consistency};
-use valori_storage::events::EventJournal;
+use valori_storage::events::event_replay::{
+    recover_from_event_log_with_progress, verify_snapshot_consistency,
+};
+use valori_storage::events::{EventJournal, RecoveryProgress};
 use valori_storage::wal_reader::WalReader;
 
 /// Outcome of a bootstrap attempt.
@@ -48,7 +50,19 @@ pub enum BootstrapMode {
 pub fn recover_from_events(event_log_path: &Path) -> StateResult<(KernelState, EventJournal, u64)> {
     tracing::info!("Recovering from event log: {:?}", event_log_path);
 
-    recover_from_event_log(event_log_path)
+    recover_from_event_log_with_progress(event_log_path, None)
+        .map_err(|e| StateError::InvalidInput(format!("Event log replay failed: {:?}", e)))
+}
+
+/// [`recover_from_events`], reporting bytes read and events replayed to
+/// `progress`.
+pub fn recover_from_events_with_progress(
+    event_log_path: &Path,
+    progress: &RecoveryProgress,
+) -> StateResult<(KernelState, EventJournal, u64)> {
+    tracing::info!("Recovering from event log: {:?}", event_log_path);
+
+    recover_from_event_log_with_progress(event_log_path, Some(progress))
         .map_err(|e| StateError::InvalidInput(format!("Event log replay failed: {:?}", e)))
 }
 
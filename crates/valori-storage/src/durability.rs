@@ -0,0 +1,152 @@
+// Copyright (c) 2025 Varshith Gudur. Dual-licensed under MIT OR Apache-2.0.
+//! How appended bytes are made durable.
+//!
+//! A [`SyncStrategy`] decides how a log file's writes reach stable storage:
+//! - `Full`: `fsync` after each write (file data and all metadata).
+//! - `Data`: `fdatasync` after each write (data, plus the metadata needed to
+//!   read it back, such as the file length). The default: an append-only
+//!   file needs nothing more, and it skips the timestamp update `fsync` pays
+//!   for.
+//! - `Dsync`: open the file `O_DSYNC`, so every `write(2)` returns only once
+//!   its data is durable and no separate sync call is made. Falls back to
+//!   `Data` where `O_DSYNC` is not available.
+//!
+//! Syncing a file does not make its directory entry durable. A newly created
+//! or renamed file can vanish on a crash, on ext4 and XFS among others,
+//! until its parent directory is synced too: see [`sync_dir`].
+
+use std::fs::{File, OpenOptions};
+use std::io;
+use std::path::Path;
+use std::str::FromStr;
+
+use serde::{Deserialize, Serialize};
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default, Serialize, Deserialize)]
+pub enum SyncStrategy {
+    /// `fsync` (`File::sync_all`) after each write.
+    Full,
+    /// `fdatasync` (`File::sync_data`) after each write.
+    #[default]
+    Data,
+    /// Open with `O_DSYNC`; writes are synchronous.
+    Dsync,
+}
+
+impl SyncStrategy {
+    /// Name used by `VALORI_EVENT_LOG_SYNC`.
+    pub fn name(self) -> &'static str {
+        match self {
+            SyncStrategy::Full => "fsync",
+            SyncStrategy::Data => "fdatasync",
+            SyncStrategy::Dsync => "dsync",
+        }
+    }
+
+    pub fn from_name(name: &str) -> Option<Self> {
+        match name.to_ascii_lowercase().replace('-', "_").as_str() {
+            "fsync" | "full" | "sync_all" => Some(SyncStrategy::Full),
+            "fdatasync" | "data" | "sync_data" => Some(SyncStrategy::Data),
+            "dsync" | "o_dsync" => Some(SyncStrategy::Dsync),
+            _ => None,
+        }
+    }
+
+    /// Set the open flags this strategy needs on `opts`.
+    pub fn apply(self, opts: &mut OpenOptions) -> &mut OpenOptions {
+        #[cfg(unix)]
+        if self == SyncStrategy::Dsync {
+            use std::os::unix::fs::OpenOptionsExt;
+            opts.custom_flags(libc::O_DSYNC);
+        }
+        opts
+    }
+
+    /// Make everything written to `file` so far durable. `file` must have
+    /// been opened through [`Self::apply`].
+    pub fn sync(self, file: &File) -> io::Result<()> {
+        match self {
+            SyncStrategy::Full => file.sync_all(),
+            // Each write was already durable when it returned.
+            SyncStrategy::Dsync if cfg!(unix) => Ok(()),
+            SyncStrategy::Data | SyncStrategy::Dsync => file.sync_data(),
+        }
+    }
+}
+
+impl FromStr for SyncStrategy {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        Self::from_name(s).ok_or_else(|| {
+            format!("unknown sync strategy {s:?} (expected fsync, fdatasync or dsync)")
+        })
+    }
+}
+
+impl std::fmt::Display for SyncStrategy {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.write_str(self.name())
+    }
+}
+
+/// Make the directory entries of `dir` durable: a file created, renamed or
+/// removed in it survives a crash once this returns. A no-op where
+/// directories cannot be opened for syncing (Windows).
+pub fn sync_dir(dir: &Path) -> io::Result<()> {
+    #[cfg(unix)]
+    {
+        File::open(dir)?.sync_all()
+    }
+    #[cfg(not(unix))]
+    {
+        let _ = dir;
+        Ok(())
+    }
+}
+
+/// [`sync_dir`] on the directory holding `path`.
+pub fn sync_parent(path: &Path) -> io::Result<()> {
+    match path.parent() {
+        Some(dir) if !dir.as_os_str().is_empty() => sync_dir(dir),
+        _ => sync_dir(Path::new(".")),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::io::Write;
+
+    #[test]
+    fn names_round_trip() {
+        for s in [SyncStrategy::Full, SyncStrategy::Data, SyncStrategy::Dsync] {
+            assert_eq!(s.name().parse::<SyncStrategy>(), Ok(s));
+        }
+        assert_eq!(
+            SyncStrategy::from_name("O_DSYNC"),
+            Some(SyncStrategy::Dsync)
+        );
+        assert_eq!(
+            SyncStrategy::from_name("sync-all"),
+            Some(SyncStrategy::Full)
+        );
+        assert!("sometimes".parse::<SyncStrategy>().is_err());
+    }
+
+    #[test]
+    fn every_strategy_writes_and_syncs() {
+        let dir = tempfile::tempdir().unwrap();
+        for s in [SyncStrategy::Full, SyncStrategy::Data, SyncStrategy::Dsync] {
+            let path = dir.path().join(s.name());
+            let mut file = s
+                .apply(OpenOptions::new().create(true).append(true))
+                .open(&path)
+                .unwrap();
+            file.write_all(b"entry").unwrap();
+            s.sync(&file).unwrap();
+            sync_parent(&path).unwrap();
+            assert_eq!(std::fs::read(&path).unwrap(), b"entry");
+        }
+    }
+}
@@ -93,6 +93,17 @@ pub struct EventCommitter {
     flush_every: usize,
 }
 
+/// Outcome of [`EventCommitter::rotate_now`].
+#[derive(Debug, Clone)]
+pub struct Rotation {
+    /// Where the previous live segment was archived.
+    pub archive_path: std::path::PathBuf,
+    /// Committed height recorded in the new segment's checkpoint.
+    pub height: u64,
+    /// BLAKE3 state hash recorded in that checkpoint.
+    pub checkpoint_hash: [u8; 32],
+}
+
 impl EventCommitter {
     /// Create a new event committer
     pub fn new(event_log: EventLogWriter, journal: EventJournal, live_state: KernelState) -> Self {
@@ -124,7 +135,8 @@ impl EventCommitter {
         if self.write_buf.is_empty() {
             return Ok(());
         }
-        self.event_log.append_batch(&self.write_buf)?;
+        let _fsync = tracing::debug_span!("fsync", entries = self.write_buf.len()).entered();
+        self.event_log.append_many(&self.write_buf)?;
         self.write_buf.clear();
         Ok(())
     }
@@ -181,18 +193,26 @@ impl EventCommitter {
         };
         self.write_buf.push(entry);
         if self.write_buf.len() >= self.flush_every {
-            self.event_log.append_batch(&self.write_buf)?;
-            self.write_buf.clear();
+            self.flush_pending()?;
         }
 
         // Step 4: Commit journal.
         self.journal.append_buffered(event.clone());
-        self.journal.commit_buffer();
+        self.journal.commit_buffer_ns(namespace_id);
         tracing::debug!("Event committed: {:?}", event.event_type());
         self.maybe_rotate();
         Ok(CommitResult::Committed)
     }
 
+    /// Durably append a node-level admin entry (membership change,
+    /// annotation). It is chained after every data event committed so far
+    /// and never touches state or the journal.
+    pub fn append_admin(&mut self, event: crate::events::event_log::AdminEvent) -> Result<()> {
+        self.write_buf
+            .push(crate::events::event_log::LogEntry::Admin(event));
+        self.flush_pending()
+    }
+
     /// Explicitly flush all buffered events to disk (fsync).
     pub fn flush_log(&mut self) -> Result<()> {
         self.flush_pending()?;
@@ -211,6 +231,18 @@ impl EventCommitter {
             return;
         }
 
+        match self.rotate_now() {
+            Ok(r) => tracing::info!("Event log rotated at height {} ({} bytes)", r.height, limit),
+            Err(e) => tracing::error!("Event log rotation failed: {}", e),
+        }
+    }
+
+    /// Archive the live segment now, whatever its size, and open the next
+    /// one with a `Checkpoint` entry for the current height and state hash.
+    /// Buffered entries are written first so they land in the archived
+    /// segment, before the checkpoint that counts them.
+    pub fn rotate_now(&mut self) -> Result<Rotation> {
+        self.flush_pending()?;
         let height = self.journal.committed_height();
         let state_hash = {
             use valori_kernel::snapshot::blake3::hash_state_blake3;
@@ -236,10 +268,12 @@ impl EventCommitter {
             timestamp: now,
         };
 
-        match self.event_log.rotate(archive_path, Some(checkpoint)) {
-            Ok(_) => tracing::info!("Event log rotated at height {} ({} bytes)", height, limit,),
-            Err(e) => tracing::error!("Event log rotation failed: {}", e),
-        }
+        self.event_log.rotate(&archive_path, Some(checkpoint))?;
+        Ok(Rotation {
+            archive_path,
+            height,
+            checkpoint_hash: state_hash,
+        })
     }
 
     /// Batch commit multiple events into the default namespace.
@@ -270,7 +304,9 @@ impl EventCommitter {
                 .map_err(CommitError::ShadowApply)?;
         }
 
-        // Step 2: Persist all events (batch is now known-good).
+        // Step 2: Persist all events (batch is now known-good), behind any
+        // buffered single events committed before it.
+        self.flush_pending()?;
         let default_ns = valori_kernel::types::id::DEFAULT_NS.0;
         let log_entries: Vec<_> = events
             .iter()
@@ -285,7 +321,8 @@ impl EventCommitter {
                 }
             })
             .collect();
-        self.event_log.append_batch(&log_entries)?;
+        tracing::debug_span!("fsync", entries = log_entries.len())
+            .in_scope(|| self.event_log.append_batch(&log_entries))?;
 
         // Step 3: Live apply (must succeed — shadow passed on identical state).
         for event in &events {
@@ -298,7 +335,7 @@ impl EventCommitter {
         for event in &events {
             self.journal.append_buffered(event.clone());
         }
-        self.journal.commit_buffer();
+        self.journal.commit_buffer_ns(namespace_id);
         tracing::debug!("Batch committed: {} events", events.len());
         self.maybe_rotate();
         Ok(CommitResult::Committed)
@@ -367,7 +404,7 @@ impl EventCommitter {
     ) -> Result<CommitResult> {
         self.event_log.append(&entry)?;
 
-        if let crate::events::event_log::LogEntry::Checkpoint { event_count, .. } = entry {
+        if let Some(event_count) = entry.checkpoint_height() {
             self.journal.set_height(event_count);
         }
 
@@ -479,4 +516,34 @@ mod tests {
         assert_eq!(result, CommitResult::Committed);
         assert_eq!(committer.journal().committed_height(), 2);
     }
+
+    #[test]
+    fn test_batch_lands_after_buffered_events() {
+        let dir = tempdir().unwrap();
+        let log_path = dir.path().join("events.log");
+        let event_log = EventLogWriter::open(&log_path, Some(16)).unwrap();
+        let mut committer = EventCommitter::new(event_log, EventJournal::new(), KernelState::new());
+        let insert = |i| KernelEvent::InsertRecord {
+            id: RecordId(i),
+            vector: FxpVector::new_zeros(16),
+            metadata: None,
+            tag: 0,
+        };
+
+        // The first insert sits in the group-commit buffer when the batch
+        // is written.
+        committer.commit_event(insert(0)).unwrap();
+        committer.commit_batch(vec![insert(1), insert(2)]).unwrap();
+        committer.flush_pending().unwrap();
+
+        let logged = crate::events::event_replay::read_all_segments(&log_path, None).unwrap();
+        let ids: Vec<_> = logged
+            .iter()
+            .map(|(_, e)| match e {
+                KernelEvent::InsertRecord { id, .. } => id.0,
+                other => panic!("unexpected {other:?}"),
+            })
+            .collect();
+        assert_eq!(ids, [0, 1, 2]);
+    }
 }
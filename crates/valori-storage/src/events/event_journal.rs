@@ -93,6 +93,13 @@ impl EventJournal {
 
     /// Commit all buffered events. Each event is stamped with the current wall-clock time.
     pub fn commit_buffer(&mut self) {
+        self.commit_buffer_ns(valori_kernel::types::id::DEFAULT_NS.0)
+    }
+
+    /// Commit all buffered events, published to live subscribers as entries
+    /// in `namespace_id` — the same entries the log gets, so a replication
+    /// stream keeps each collection write in its collection.
+    pub fn commit_buffer_ns(&mut self, namespace_id: u16) {
         use crate::events::event_log::LogEntry;
 
         let now = std::time::SystemTime::now()
@@ -101,7 +108,15 @@ impl EventJournal {
             .as_secs();
 
         for event in &self.buffer {
-            let _ = self.tx.send(LogEntry::Event(event.clone()));
+            let entry = if namespace_id == valori_kernel::types::id::DEFAULT_NS.0 {
+                LogEntry::Event(event.clone())
+            } else {
+                LogEntry::EventNs {
+                    namespace_id,
+                    event: event.clone(),
+                }
+            };
+            let _ = self.tx.send(entry);
             self.timestamps.push(now);
         }
 
@@ -204,6 +219,28 @@ mod tests {
         assert_eq!(journal.committed().len(), 1);
     }
 
+    #[test]
+    fn live_subscribers_see_the_namespace() {
+        use crate::events::event_log::LogEntry;
+
+        let mut journal = EventJournal::new();
+        let mut rx = journal.subscribe();
+        let event = KernelEvent::DeleteRecord { id: RecordId(3) };
+        journal.append_buffered(event.clone());
+        journal.commit_buffer_ns(4);
+        journal.append_buffered(event.clone());
+        journal.commit_buffer();
+
+        assert!(matches!(
+            rx.try_recv().unwrap(),
+            LogEntry::EventNs {
+                namespace_id: 4,
+                ..
+            }
+        ));
+        assert!(matches!(rx.try_recv().unwrap(), LogEntry::Event(_)));
+    }
+
     #[test]
     fn test_journal_buffer_rollback() {
         let mut journal = EventJournal::new();
@@ -3,13 +3,15 @@
 //!
 //! This is the CANONICAL durability layer.
 //! - Events are written to disk BEFORE memory application
-//! - Every write is fsync'd for crash safety
+//! - Every write is synced for crash safety, per the writer's
+//!   [`SyncStrategy`]; creating or rotating a segment also syncs its
+//!   directory
 //! - No truncation or rewriting allowed
 //! - Bincode serialization for determinism
 //!
 //! The on-disk format is defined ONCE in the `valori-wire` crate (shared
 //! with `valori-verify` and `valori-cli`). This module only owns the
-//! durability mechanics: open/restore, append+fsync, batch, rotation.
+//! durability mechanics: open/restore, append+sync, batch, rotation.
 //!
 //! ## Versions
 //! - New files are written as **v3**: 48-byte header carrying the
@@ -19,17 +21,20 @@
 //! - Existing **v2** files keep appending v2 entries; the first rotation
 //!   upgrades the live segment to v3 and splices the chain.
 
+use std::borrow::Cow;
 use std::fs::{File, OpenOptions};
 use std::io::{BufWriter, Read, Write};
+use std::ops::Range;
 use std::path::{Path, PathBuf};
 use std::time::{SystemTime, UNIX_EPOCH};
 use thiserror::Error;
 
+use crate::durability::{sync_parent, SyncStrategy};
 use valori_wire::{
-    chain_advance, decode_entry, encode_entry, encode_header_v3, encode_header_v4, parse_header,
-    FORMAT_Q16_16, VERSION_V3, VERSION_V4,
+    batch_hash, chain_advance, decode_entry, encode_entry, encode_header_v3, encode_header_v4,
+    incomplete_batch, log_hash_advance, parse_header, FORMAT_Q16_16, VERSION_V3, VERSION_V4,
 };
-pub use valori_wire::{DecodedEntry, EntryV2, EntryV3, LogEntry, SegmentHeader};
+pub use valori_wire::{AdminEvent, DecodedEntry, EntryV2, EntryV3, LogEntry, SegmentHeader};
 
 #[derive(Error, Debug)]
 pub enum EventLogError {
@@ -50,6 +55,9 @@ pub enum EventLogError {
 
     #[error("event log corrupted: chain link broken at byte offset {offset}")]
     ChainBroken { offset: usize },
+
+    #[error("event log corrupted: batch at byte offset {offset} does not match its commit record")]
+    BatchBroken { offset: usize },
 }
 
 pub type Result<T> = std::result::Result<T, EventLogError>;
@@ -64,6 +72,9 @@ pub(crate) enum SegmentWalkError {
     /// tampering, a substituted entry, or a corrupted-but-still-decodable
     /// byte range.
     ChainBroken { offset: usize },
+    /// Every entry of a batch is present and chained, but they do not hash
+    /// to the batch's `BatchCommit` record.
+    BatchBroken { offset: usize },
     /// Any wire-level decode failure other than a trailing truncation
     /// (CRC mismatch, invalid enum discriminant, oversized entry, ...).
     Wire {
@@ -72,6 +83,16 @@ pub(crate) enum SegmentWalkError {
     },
 }
 
+impl From<SegmentWalkError> for EventLogError {
+    fn from(e: SegmentWalkError) -> Self {
+        match e {
+            SegmentWalkError::ChainBroken { offset } => EventLogError::ChainBroken { offset },
+            SegmentWalkError::BatchBroken { offset } => EventLogError::BatchBroken { offset },
+            SegmentWalkError::Wire { source, .. } => EventLogError::Wire(source),
+        }
+    }
+}
+
 /// Decode every entry in `buf[start_offset..]`, verifying per-entry chain
 /// continuity against `initial_chain_head`. Tolerates EXACTLY a trailing
 /// truncated entry — `valori_wire::WireError::Truncated` is a structural
@@ -83,12 +104,21 @@ pub(crate) enum SegmentWalkError {
 /// chain head) and `event_replay::read_segment_full` (needs every decoded
 /// entry plus namespace routing) so the truncation-tolerance policy is
 /// defined exactly once instead of drifting between two call sites.
+///
+/// A batch that did not reach disk whole (a `BatchCommit` record followed by
+/// fewer entries than it counts, see `valori_wire::incomplete_batch`) is
+/// torn as well: it is dropped together with its record, and the chain head
+/// returned is the one before the record.
+///
+/// Each entry comes with its byte range in `buf`. The third value is the
+/// byte length of the committed entries — less than `buf.len()` exactly
+/// when a torn trailing entry or batch was skipped.
 pub(crate) fn walk_segment_body(
     version: u32,
     buf: &[u8],
     start_offset: usize,
     initial_chain_head: [u8; 32],
-) -> std::result::Result<(Vec<DecodedEntry>, [u8; 32]), SegmentWalkError> {
+) -> std::result::Result<(Vec<(DecodedEntry, Range<usize>)>, [u8; 32], usize), SegmentWalkError> {
     let mut entries = Vec::new();
     let mut chain_head = initial_chain_head;
     let mut offset = start_offset;
@@ -101,23 +131,42 @@ pub(crate) fn walk_segment_body(
                 }
                 chain_head = chain_advance(version, &chain_head, &decoded)
                     .map_err(|source| SegmentWalkError::Wire { offset, source })?;
+                entries.push((decoded, offset..offset + bytes_read));
                 offset += bytes_read;
-                entries.push(decoded);
             }
             Err(valori_wire::WireError::Truncated) => break,
             Err(source) => return Err(SegmentWalkError::Wire { offset, source }),
         }
     }
 
-    Ok((entries, chain_head))
+    let logged: Vec<&LogEntry> = entries.iter().map(|(d, _)| &d.entry).collect();
+    match incomplete_batch(&logged) {
+        Ok(None) => {}
+        Ok(Some(at)) => {
+            offset = entries[at].1.start;
+            chain_head = entries[at].0.prev_hash;
+            entries.truncate(at);
+        }
+        Err(at) => {
+            return Err(SegmentWalkError::BatchBroken {
+                offset: entries[at].1.start,
+            })
+        }
+    }
+
+    Ok((entries, chain_head, offset))
 }
 
 /// Append-Only Event Log Writer
 ///
 /// # Safety Guarantees
-/// - `append` and `append_batch` write + flush + fsync before returning;
+/// - `append` and `append_batch` write + flush + sync before returning;
 ///   committed entries survive a crash (including SIGKILL)
-/// - `append_batch` performs a single fsync for the whole batch
+/// - `append_batch` and `append_many` perform a single sync for all their
+///   entries; a batch of several entries is recovered whole or not at all:
+///   its `BatchCommit` record lets recovery drop a partly written batch
+/// - A created or rotated segment's directory entry is synced before the
+///   call returns, so the file itself survives the crash too
 /// - Recovery tolerates a trailing partial entry (a crash mid-write loses
 ///   only the in-flight, unacknowledged entry; replay stops at the first
 ///   undecodable record)
@@ -134,6 +183,35 @@ pub struct EventLogWriter {
     chain_head: [u8; 32],
     /// Bytes written since last rotation (header not counted).
     bytes_written: u64,
+    /// Running log hash over every entry's bytes (`valori_wire::log_hash_advance`);
+    /// `None` when the archives it continues from are unreadable.
+    log_hash: Option<[u8; 32]>,
+    /// How writes are made durable.
+    sync: SyncStrategy,
+}
+
+/// Event count after `entry`: kernel events add one, checkpoints restate
+/// the count they record.
+pub(crate) fn counted(event_count: u64, entry: &LogEntry) -> u64 {
+    match entry {
+        LogEntry::Event(_) => event_count + 1,
+        // S15: namespace-scoped events count identically.
+        LogEntry::EventNs { .. } => event_count + 1,
+        LogEntry::Checkpoint { event_count: c, .. }
+        | LogEntry::CheckpointV2 { event_count: c, .. } => *c,
+        // Admin events and batch records are chained but not kernel events.
+        LogEntry::Admin(_) | LogEntry::BatchCommit { .. } => event_count,
+    }
+}
+
+/// Log hash at the start of a segment, when it can be told without the
+/// archives: recorded by an opening `CheckpointV2`, or genesis.
+fn segment_log_seed(segment_seq: u32, first: Option<&LogEntry>) -> Option<[u8; 32]> {
+    match first {
+        Some(LogEntry::CheckpointV2 { log_hash, .. }) => Some(*log_hash),
+        _ if segment_seq == 0 => Some([0u8; 32]),
+        _ => None,
+    }
 }
 
 impl EventLogWriter {
@@ -155,29 +233,91 @@ impl EventLogWriter {
         self.segment_seq
     }
 
+    /// How this writer makes its writes durable.
+    pub fn sync_strategy(&self) -> SyncStrategy {
+        self.sync
+    }
+
     /// Current BLAKE3 chain head — covers every durably written entry.
     pub fn chain_head(&self) -> &[u8; 32] {
         &self.chain_head
     }
 
+    /// Running log hash over the bytes of every entry written, across
+    /// segments (see [`valori_wire::log_hash_advance`]). O(1): maintained
+    /// per append and restored on open. `None` only when the live segment
+    /// continues archives that carry no `CheckpointV2` and cannot be read.
+    pub fn log_hash(&self) -> Option<[u8; 32]> {
+        self.log_hash
+    }
+
+    /// `entry` as it is written: a checkpoint of either shape carries this
+    /// writer's log hash as a `CheckpointV2` when it is known, and is a
+    /// plain `Checkpoint` otherwise — a relayed checkpoint never carries a
+    /// hash over another log's bytes.
+    fn stamped<'a>(&self, entry: &'a LogEntry) -> Cow<'a, LogEntry> {
+        let (event_count, snapshot_hash, timestamp) = match entry {
+            LogEntry::Checkpoint {
+                event_count,
+                snapshot_hash,
+                timestamp,
+            }
+            | LogEntry::CheckpointV2 {
+                event_count,
+                snapshot_hash,
+                timestamp,
+                ..
+            } => (*event_count, *snapshot_hash, *timestamp),
+            _ => return Cow::Borrowed(entry),
+        };
+        Cow::Owned(match self.log_hash {
+            Some(log_hash) => LogEntry::CheckpointV2 {
+                event_count,
+                snapshot_hash,
+                timestamp,
+                log_hash,
+            },
+            None => LogEntry::Checkpoint {
+                event_count,
+                snapshot_hash,
+                timestamp,
+            },
+        })
+    }
+
+    fn advance_log_hash(&mut self, entry_bytes: &[u8]) {
+        if let Some(h) = &mut self.log_hash {
+            *h = log_hash_advance(h, entry_bytes);
+        }
+    }
+
     /// Open or create an event log file.
     ///
     /// If the file exists (v2 or v3), validates the header, decodes existing
     /// entries to restore `event_count` and `chain_head`, then opens in
     /// append mode. If the file doesn't exist, creates it with a fresh v3
-    /// header (requires `expected_dim`).
+    /// header (requires `expected_dim`). Syncs with the default
+    /// [`SyncStrategy`].
     pub fn open(path: impl AsRef<Path>, expected_dim: Option<u32>) -> Result<Self> {
+        Self::open_with_sync(path, expected_dim, SyncStrategy::default())
+    }
+
+    /// [`Self::open`], making writes durable with `sync`.
+    pub fn open_with_sync(
+        path: impl AsRef<Path>,
+        expected_dim: Option<u32>,
+        sync: SyncStrategy,
+    ) -> Result<Self> {
         let path = path.as_ref().to_path_buf();
         let file_exists = path.exists();
 
-        let mut file = OpenOptions::new()
-            .create(true)
-            .append(true)
-            .read(true)
+        let mut file = sync
+            .apply(OpenOptions::new().create(true).append(true).read(true))
             .open(&path)?;
 
         let mut event_count = 0u64;
         let mut chain_head = [0u8; 32];
+        let log_hash;
         let dim;
         let version;
         let mut segment_seq = 0u32;
@@ -203,33 +343,28 @@ impl EventLogWriter {
             // final head (recorded in the header); v2 starts from zeros.
             chain_head = header.prev_segment_chain_head;
 
-            let (entries, final_head) =
-                walk_segment_body(version, &buf, header.header_len, chain_head).map_err(
-                    |e| match e {
-                        SegmentWalkError::ChainBroken { offset } => {
-                            EventLogError::ChainBroken { offset }
-                        }
-                        SegmentWalkError::Wire { source, .. } => EventLogError::Wire(source),
-                    },
-                )?;
+            let (entries, final_head, _) =
+                walk_segment_body(version, &buf, header.header_len, chain_head)?;
             chain_head = final_head;
-            for decoded in &entries {
-                match &decoded.entry {
-                    LogEntry::Event(_) => event_count += 1,
-                    // S15: namespace-scoped events count identically.
-                    LogEntry::EventNs { .. } => event_count += 1,
-                    LogEntry::Checkpoint { event_count: c, .. } => event_count = *c,
-                    // Admin events are chained but not kernel events.
-                    LogEntry::Admin(_) => {}
-                }
+            for (decoded, _) in &entries {
+                event_count = counted(event_count, &decoded.entry);
             }
+            let seed = segment_log_seed(segment_seq, entries.first().map(|(d, _)| &d.entry))
+                .or_else(|| crate::events::event_proof::archived_log_hash(&path).ok());
+            log_hash = seed.map(|seed| {
+                entries.iter().fold(seed, |h, (_, span)| {
+                    log_hash_advance(&h, &buf[span.clone()])
+                })
+            });
         } else {
             let d = expected_dim.ok_or(EventLogError::InvalidHeader)?;
             dim = d;
             version = VERSION_V4;
             let header = encode_header_v4(dim, FORMAT_Q16_16, 0, &[0u8; 32]);
             file.write_all(&header)?;
-            file.sync_all()?;
+            sync.sync(&file)?;
+            sync_parent(&path)?;
+            log_hash = Some([0u8; 32]);
         }
 
         Ok(Self {
@@ -241,6 +376,8 @@ impl EventLogWriter {
             segment_seq,
             chain_head,
             bytes_written: 0,
+            log_hash,
+            sync,
         })
     }
 
@@ -284,11 +421,12 @@ impl EventLogWriter {
             None
         };
 
-        let bytes = encode_entry(self.version, &self.chain_head, now, request_id, entry)?;
+        let entry = self.stamped(entry);
+        let bytes = encode_entry(self.version, &self.chain_head, now, request_id, &entry)?;
 
         self.file.write_all(&bytes)?;
         self.file.flush()?;
-        self.file.get_ref().sync_all()?;
+        self.sync.sync(self.file.get_ref())?;
 
         self.chain_head = chain_advance(
             self.version,
@@ -297,30 +435,52 @@ impl EventLogWriter {
                 prev_hash: self.chain_head,
                 wall_time_secs: now,
                 request_id,
-                entry: entry.clone(),
+                entry: entry.clone().into_owned(),
             },
         )?;
+        self.advance_log_hash(&bytes);
         self.bytes_written += bytes.len() as u64;
-
-        if let LogEntry::Event(_) = entry {
-            self.event_count += 1;
-        }
+        self.event_count = counted(self.event_count, &entry);
 
         Ok(())
     }
 
-    /// Explicitly flush the buffer to disk (no-op if already fsynced per entry).
+    /// Explicitly flush the buffer to disk (no-op if already synced per entry).
     pub fn flush(&mut self) -> Result<()> {
         self.file.flush()?;
-        self.file.get_ref().sync_all()?;
+        self.sync.sync(self.file.get_ref())?;
         Ok(())
     }
 
-    /// Append multiple entries with a SINGLE fsync.
+    /// Append multiple entries as one atomic batch, with a SINGLE sync.
     ///
-    /// All entries share one flush+fsync. Advances the chain head for
+    /// All entries share one flush+sync. Advances the chain head for
     /// each entry in order so chain integrity is maintained.
+    ///
+    /// Two or more entries are preceded by a `BatchCommit` record, so a
+    /// crash part-way through the write loses the whole batch rather than
+    /// leaving a prefix of it committed. A checkpoint is stamped with the
+    /// log hash of the bytes before it, which the record would have to
+    /// cover in turn, so a batch holding one is written without a record;
+    /// no caller batches checkpoints.
     pub fn append_batch(&mut self, entries: &[LogEntry]) -> Result<()> {
+        let record = (entries.len() > 1 && entries.iter().all(|e| e.checkpoint_height().is_none()))
+            .then(|| LogEntry::BatchCommit {
+                first_height: self.event_count,
+                count: entries.len() as u32,
+                batch_hash: batch_hash(entries),
+            });
+        self.append_entries(record.as_ref(), entries)
+    }
+
+    /// Append multiple independent entries with a SINGLE sync — the
+    /// group-commit flush. No `BatchCommit` record: a crash part-way
+    /// through keeps every entry that reached disk whole.
+    pub fn append_many(&mut self, entries: &[LogEntry]) -> Result<()> {
+        self.append_entries(None, entries)
+    }
+
+    fn append_entries(&mut self, record: Option<&LogEntry>, entries: &[LogEntry]) -> Result<()> {
         if entries.is_empty() {
             return Ok(());
         }
@@ -328,8 +488,9 @@ impl EventLogWriter {
         let now = Self::now_secs();
 
         let mut total_bytes = 0u64;
-        for entry in entries {
-            let bytes = encode_entry(self.version, &self.chain_head, now, None, entry)?;
+        for entry in record.into_iter().chain(entries) {
+            let entry = self.stamped(entry);
+            let bytes = encode_entry(self.version, &self.chain_head, now, None, &entry)?;
             total_bytes += bytes.len() as u64;
             self.file.write_all(&bytes)?;
             self.chain_head = chain_advance(
@@ -339,20 +500,16 @@ impl EventLogWriter {
                     prev_hash: self.chain_head,
                     wall_time_secs: now,
                     request_id: None,
-                    entry: entry.clone(),
+                    entry: entry.into_owned(),
                 },
             )?;
+            self.advance_log_hash(&bytes);
         }
 
         self.file.flush()?;
-        self.file.get_ref().sync_all()?;
+        self.sync.sync(self.file.get_ref())?;
         self.bytes_written += total_bytes;
-
-        for entry in entries {
-            if let LogEntry::Event(_) = entry {
-                self.event_count += 1;
-            }
-        }
+        self.event_count = entries.iter().fold(self.event_count, counted);
 
         Ok(())
     }
@@ -372,20 +529,23 @@ impl EventLogWriter {
     ///
     /// Rotation is also the v2 → v3 upgrade point: a legacy segment is
     /// archived as-is and the new live segment is always v3.
+    ///
+    /// Once this returns, the rename and the new segment are durable: the
+    /// directories of both paths are synced.
     pub fn rotate(
         &mut self,
         archive_path: impl AsRef<Path>,
         checkpoint_entry: Option<LogEntry>,
     ) -> Result<()> {
+        let archive_path = archive_path.as_ref();
         self.file.flush()?;
-        self.file.get_ref().sync_all()?;
+        self.sync.sync(self.file.get_ref())?;
 
         std::fs::rename(&self.path, archive_path)?;
 
-        let mut new_file = OpenOptions::new()
-            .create(true)
-            .write(true)
-            .create_new(true)
+        let mut new_file = self
+            .sync
+            .apply(OpenOptions::new().write(true).create_new(true))
             .open(&self.path)?;
 
         // Splice: the new segment opens where the archived one closed.
@@ -396,10 +556,14 @@ impl EventLogWriter {
         let header = encode_header_v4(self.dim, FORMAT_Q16_16, self.segment_seq, &prev_head);
         new_file.write_all(&header)?;
 
+        // The checkpoint opening the segment records the log hash of
+        // everything archived, so reopening never needs the archives.
         if let Some(entry) = checkpoint_entry {
             let now = Self::now_secs();
+            let entry = self.stamped(&entry).into_owned();
             let bytes = encode_entry(self.version, &self.chain_head, now, None, &entry)?;
             new_file.write_all(&bytes)?;
+            self.event_count = counted(self.event_count, &entry);
             self.chain_head = chain_advance(
                 self.version,
                 &self.chain_head,
@@ -410,9 +574,14 @@ impl EventLogWriter {
                     entry,
                 },
             )?;
+            self.advance_log_hash(&bytes);
         }
 
-        new_file.sync_all()?;
+        self.sync.sync(&new_file)?;
+        sync_parent(&self.path)?;
+        if archive_path.parent() != self.path.parent() {
+            sync_parent(archive_path)?;
+        }
         self.file = BufWriter::new(new_file);
         self.reset_bytes_written();
 
@@ -420,6 +589,52 @@ impl EventLogWriter {
     }
 }
 
+/// Torn-tail state of one segment; see [`torn_tail`].
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct TailRepair {
+    /// Bytes of partial trailing entry or batch (0 = the tail is clean).
+    pub torn_bytes: u64,
+    /// Segment length without them.
+    pub segment_len: u64,
+    /// Chain head the segment closes with.
+    pub chain_head: [u8; 32],
+}
+
+/// Find the torn final entry a crash mid-append leaves in the segment at
+/// `path`, without changing it. Anything other than a torn tail (a broken
+/// chain link, a corrupt entry before the end) is an error: that needs
+/// quarantine, not truncation.
+pub fn torn_tail(path: impl AsRef<Path>) -> Result<TailRepair> {
+    let buf = std::fs::read(path.as_ref())?;
+    let header = parse_header(&buf).map_err(|_| EventLogError::InvalidHeader)?;
+    let (_, chain_head, end) = walk_segment_body(
+        header.version,
+        &buf,
+        header.header_len,
+        header.prev_segment_chain_head,
+    )?;
+    Ok(TailRepair {
+        torn_bytes: (buf.len() - end) as u64,
+        segment_len: end as u64,
+        chain_head,
+    })
+}
+
+/// [`torn_tail`], then truncate the segment to its last complete entry and
+/// fsync. A clean segment is left untouched. Must not run while a writer has
+/// the log open: appends after a torn tail land behind garbage, which is why
+/// the tail has to go before the node restarts.
+pub fn repair_torn_tail(path: impl AsRef<Path>) -> Result<TailRepair> {
+    let path = path.as_ref();
+    let repair = torn_tail(path)?;
+    if repair.torn_bytes > 0 {
+        let file = OpenOptions::new().write(true).open(path)?;
+        file.set_len(repair.segment_len)?;
+        file.sync_all()?;
+    }
+    Ok(repair)
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -485,6 +700,111 @@ mod tests {
         }
     }
 
+    #[test]
+    fn test_repair_torn_tail_truncates_to_last_complete_entry() {
+        let dir = tempdir().unwrap();
+        let path = dir.path().join("events.log");
+        let (clean_len, head) = {
+            let mut writer = EventLogWriter::open(&path, Some(16)).unwrap();
+            for i in 0..3 {
+                writer.append(&LogEntry::Event(event(i))).unwrap();
+            }
+            (
+                std::fs::metadata(&path).unwrap().len(),
+                *writer.chain_head(),
+            )
+        };
+        assert_eq!(repair_torn_tail(&path).unwrap().torn_bytes, 0);
+
+        // A crash mid-append: half of a fourth entry reaches disk.
+        let full = {
+            let mut writer = EventLogWriter::open(&path, Some(16)).unwrap();
+            writer.append(&LogEntry::Event(event(3))).unwrap();
+            std::fs::metadata(&path).unwrap().len()
+        };
+        let file = OpenOptions::new().write(true).open(&path).unwrap();
+        file.set_len(clean_len + (full - clean_len) / 2).unwrap();
+        drop(file);
+
+        let repair = repair_torn_tail(&path).unwrap();
+        assert_eq!(repair.torn_bytes, (full - clean_len) / 2);
+        assert_eq!(repair.segment_len, clean_len);
+        assert_eq!(repair.chain_head, head);
+        assert_eq!(std::fs::metadata(&path).unwrap().len(), clean_len);
+
+        // Appends after the repair land on a clean tail.
+        let mut writer = EventLogWriter::open(&path, Some(16)).unwrap();
+        writer.append(&LogEntry::Event(event(3))).unwrap();
+        drop(writer);
+        assert_eq!(EventLogWriter::open(&path, None).unwrap().event_count(), 4);
+    }
+
+    #[test]
+    fn test_torn_batch_rolls_back_whole() {
+        let dir = tempdir().unwrap();
+        let path = dir.path().join("events.log");
+        let (clean_len, head, cut_len) = {
+            let mut writer = EventLogWriter::open(&path, Some(16)).unwrap();
+            let empty_len = std::fs::metadata(&path).unwrap().len();
+            writer.append(&LogEntry::Event(event(0))).unwrap();
+            let clean_len = std::fs::metadata(&path).unwrap().len();
+            let head = *writer.chain_head();
+            let batch: Vec<LogEntry> = (1..5).map(|i| LogEntry::Event(event(i))).collect();
+            writer.append_batch(&batch).unwrap();
+            assert_eq!(writer.event_count(), 5, "the commit record is not an event");
+            let full_len = std::fs::metadata(&path).unwrap().len();
+            (clean_len, head, full_len - (clean_len - empty_len))
+        };
+
+        // A crash mid-batch: the record and three of the batch's four
+        // entries reach disk. Every entry on disk decodes; the batch is
+        // still dropped whole.
+        let file = OpenOptions::new().write(true).open(&path).unwrap();
+        file.set_len(cut_len).unwrap();
+        drop(file);
+
+        let repair = torn_tail(&path).unwrap();
+        assert_eq!(repair.torn_bytes, cut_len - clean_len);
+        assert_eq!(repair.segment_len, clean_len);
+        assert_eq!(repair.chain_head, head);
+
+        let mut writer = EventLogWriter::open(&path, Some(16)).unwrap();
+        assert_eq!(writer.event_count(), 1);
+        assert_eq!(writer.chain_head(), &head);
+        drop(writer);
+        repair_torn_tail(&path).unwrap();
+        writer = EventLogWriter::open(&path, Some(16)).unwrap();
+        writer.append(&LogEntry::Event(event(1))).unwrap();
+        drop(writer);
+        assert_eq!(EventLogWriter::open(&path, None).unwrap().event_count(), 2);
+    }
+
+    #[test]
+    fn test_batch_that_does_not_match_its_record_is_refused() {
+        let dir = tempdir().unwrap();
+        let path = dir.path().join("events.log");
+        {
+            let mut writer = EventLogWriter::open(&path, Some(16)).unwrap();
+            writer
+                .append(&LogEntry::BatchCommit {
+                    first_height: 0,
+                    count: 2,
+                    batch_hash: [0u8; 32],
+                })
+                .unwrap();
+            writer.append(&LogEntry::Event(event(0))).unwrap();
+            writer.append(&LogEntry::Event(event(1))).unwrap();
+        }
+        assert!(matches!(
+            EventLogWriter::open(&path, Some(16)),
+            Err(EventLogError::BatchBroken { .. })
+        ));
+        assert!(matches!(
+            torn_tail(&path),
+            Err(EventLogError::BatchBroken { .. })
+        ));
+    }
+
     #[test]
     fn test_event_log_dimension_validation() {
         let dir = tempdir().unwrap();
@@ -580,6 +900,84 @@ mod tests {
         assert_eq!(reopened.segment_seq(), 1);
     }
 
+    #[test]
+    fn test_log_hash_survives_rotation_and_reopen() {
+        use crate::events::event_proof::compute_log_hash;
+
+        let dir = tempdir().unwrap();
+        let path = dir.path().join("events.log");
+        let mut writer = EventLogWriter::open(&path, Some(16)).unwrap();
+        assert_eq!(writer.log_hash(), Some([0u8; 32]));
+
+        for seg in 0..2u32 {
+            for i in 0..3 {
+                writer.append(&LogEntry::Event(event(seg * 3 + i))).unwrap();
+            }
+            let checkpoint = LogEntry::Checkpoint {
+                event_count: u64::from(seg * 3 + 3),
+                snapshot_hash: *writer.chain_head(),
+                timestamp: 0,
+            };
+            writer
+                .rotate(
+                    dir.path().join(format!("events.log.{seg}")),
+                    Some(checkpoint),
+                )
+                .unwrap();
+        }
+        writer
+            .append_batch(&[LogEntry::Event(event(6)), LogEntry::Event(event(7))])
+            .unwrap();
+        let running = writer.log_hash().unwrap();
+        drop(writer);
+        assert_eq!(running, compute_log_hash(&path).unwrap());
+
+        // The live segment opens with a CheckpointV2 carrying the hash of
+        // both archives, so reopening resumes without reading them.
+        let bytes = std::fs::read(&path).unwrap();
+        let header = parse_header(&bytes).unwrap();
+        let (first, _) = decode_entry(header.version, &bytes[header.header_len..]).unwrap();
+        assert!(matches!(
+            first.entry,
+            LogEntry::CheckpointV2 { event_count: 6, .. }
+        ));
+        for seg in 0..2 {
+            std::fs::rename(
+                dir.path().join(format!("events.log.{seg}")),
+                dir.path().join(format!("moved.{seg}")),
+            )
+            .unwrap();
+        }
+        let reopened = EventLogWriter::open(&path, Some(16)).unwrap();
+        assert_eq!(reopened.log_hash(), Some(running));
+        assert_eq!(reopened.event_count(), 8);
+    }
+
+    #[test]
+    fn test_every_sync_strategy_appends_rotates_and_reopens() {
+        for sync in [SyncStrategy::Full, SyncStrategy::Data, SyncStrategy::Dsync] {
+            let dir = tempdir().unwrap();
+            let path = dir.path().join("events.log");
+            let mut writer = EventLogWriter::open_with_sync(&path, Some(16), sync).unwrap();
+            assert_eq!(writer.sync_strategy(), sync);
+            writer.append(&LogEntry::Event(event(0))).unwrap();
+            // Archived into another directory: both directories are synced.
+            std::fs::create_dir(dir.path().join("archive")).unwrap();
+            writer
+                .rotate(dir.path().join("archive").join("events.log.0"), None)
+                .unwrap();
+            writer
+                .append_batch(&[LogEntry::Event(event(1)), LogEntry::Event(event(2))])
+                .unwrap();
+            let head = *writer.chain_head();
+            drop(writer);
+
+            let reopened = EventLogWriter::open_with_sync(&path, Some(16), sync).unwrap();
+            assert_eq!(reopened.chain_head(), &head, "{sync}");
+            assert_eq!(reopened.segment_seq(), 1, "{sync}");
+        }
+    }
+
     #[test]
     fn test_chain_head_deterministic() {
         // The chain hash covers (wall_time_secs, request_id, entry) — so
@@ -24,3 +24,56 @@ pub fn compute_event_log_hash(path: impl AsRef<std::path::Path>) -> std::io::Res
 
     Ok(*hasher.finalize().as_bytes())
 }
+
+/// Fold [`valori_wire::log_hash_advance`] over every entry of `segments`
+/// (genesis first), from zeros.
+fn fold_log_hash(segments: &[std::path::PathBuf]) -> std::io::Result<[u8; 32]> {
+    use crate::events::event_log::walk_segment_body;
+    use std::io::{Error, ErrorKind};
+
+    let mut parsed = Vec::with_capacity(segments.len());
+    for path in segments {
+        let buf = std::fs::read(path)?;
+        let header = valori_wire::parse_header(&buf).map_err(|e| {
+            Error::new(ErrorKind::InvalidData, format!("{}: {e:?}", path.display()))
+        })?;
+        parsed.push((header, buf));
+    }
+    parsed.sort_by_key(|(header, _)| header.segment_seq);
+
+    let mut log_hash = [0u8; 32];
+    for (header, buf) in &parsed {
+        let (entries, _, _) = walk_segment_body(
+            header.version,
+            buf,
+            header.header_len,
+            header.prev_segment_chain_head,
+        )
+        .map_err(|e| Error::new(ErrorKind::InvalidData, format!("{e:?}")))?;
+        for (_, span) in entries {
+            log_hash = valori_wire::log_hash_advance(&log_hash, &buf[span]);
+        }
+    }
+    Ok(log_hash)
+}
+
+/// Recompute the running log hash of the log at `live_path` — archived
+/// segments and the live file — from the bytes on disk.
+///
+/// Equals `EventLogWriter::log_hash` once the writer has flushed, so an
+/// auditor holding a copy of the log can check `/v1/proof/event-log`.
+pub fn compute_log_hash(live_path: impl AsRef<std::path::Path>) -> std::io::Result<[u8; 32]> {
+    fold_log_hash(&crate::events::event_replay::segment_paths(
+        live_path.as_ref(),
+    ))
+}
+
+/// The log hash at the start of the live segment: the fold over its
+/// archived predecessors only.
+pub(crate) fn archived_log_hash(live_path: &std::path::Path) -> std::io::Result<[u8; 32]> {
+    let archives: Vec<_> = crate::events::event_replay::segment_paths(live_path)
+        .into_iter()
+        .filter(|p| p != live_path)
+        .collect();
+    fold_log_hash(&archives)
+}
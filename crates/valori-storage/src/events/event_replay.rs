@@ -5,6 +5,7 @@
 //! **Event Log ALWAYS wins. Snapshot is just a cache.**
 
 use crate::events::event_journal::EventJournal;
+use crate::events::recovery_progress::RecoveryProgress;
 use std::fs::File;
 use std::io::{BufReader, Read};
 use std::path::Path;
@@ -41,18 +42,209 @@ pub type Result<T> = std::result::Result<T, ReplayError>;
 /// namespace (S15 — pre-S15 `Event` entries carry namespace 0, so old logs
 /// replay exactly as they always did).
 pub fn replay_events(events: &[(u16, KernelEvent)]) -> Result<KernelState> {
+    replay_events_with_progress(events, None)
+}
+
+/// [`replay_events`], counting each applied event into `progress`.
+pub fn replay_events_with_progress(
+    events: &[(u16, KernelEvent)],
+    progress: Option<&RecoveryProgress>,
+) -> Result<KernelState> {
     let mut state = KernelState::new();
+    if let Some(p) = progress {
+        p.set_events_total(events.len() as u64);
+    }
 
     for (idx, (namespace_id, event)) in events.iter().enumerate() {
         state.apply_event_ns(event, *namespace_id).map_err(|e| {
             tracing::error!("Event replay failed at index {}: {:?}", idx, e);
             ReplayError::EventApplication(e)
         })?;
+        if let Some(p) = progress {
+            p.event_replayed();
+        }
     }
 
     Ok(state)
 }
 
+/// Broad kinds of event, for [`EventFilter`].
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum EventClass {
+    /// Record inserts (plain, encrypted, auto-id), deletes, metadata updates
+    /// and sparse vectors.
+    Record,
+    /// Node and edge creates and deletes.
+    Graph,
+    /// `SetMeta` and `DeleteMeta`.
+    Meta,
+    /// Namespace creates and drops.
+    Namespace,
+    /// `ShredKey`.
+    Key,
+    /// `ResizePools`, `Vacuum`, and a partial replica's `Omitted`.
+    Maintenance,
+}
+
+impl EventClass {
+    pub fn of(event: &KernelEvent) -> Self {
+        match event {
+            KernelEvent::InsertRecord { .. }
+            | KernelEvent::DeleteRecord { .. }
+            | KernelEvent::SoftDeleteRecord { .. }
+            | KernelEvent::InsertRecordEncrypted { .. }
+            | KernelEvent::AutoInsertRecord { .. }
+            | KernelEvent::AutoInsertRecordEncrypted { .. }
+            | KernelEvent::UpdateRecordMetadata { .. }
+            | KernelEvent::SetSparseVector { .. } => Self::Record,
+            KernelEvent::CreateNode { .. }
+            | KernelEvent::CreateEdge { .. }
+            | KernelEvent::DeleteEdge { .. }
+            | KernelEvent::DeleteNode { .. }
+            | KernelEvent::AutoCreateNode { .. }
+            | KernelEvent::AutoCreateEdge { .. } => Self::Graph,
+            KernelEvent::SetMeta { .. } | KernelEvent::DeleteMeta { .. } => Self::Meta,
+            KernelEvent::AutoCreateNamespace { .. } | KernelEvent::DropNamespace { .. } => {
+                Self::Namespace
+            }
+            KernelEvent::ShredKey { .. } => Self::Key,
+            KernelEvent::ResizePools { .. }
+            | KernelEvent::Vacuum { .. }
+            | KernelEvent::Omitted { .. } => Self::Maintenance,
+        }
+    }
+}
+
+impl std::str::FromStr for EventClass {
+    type Err = String;
+
+    fn from_str(s: &str) -> std::result::Result<Self, Self::Err> {
+        match s {
+            "record" => Ok(Self::Record),
+            "graph" => Ok(Self::Graph),
+            "meta" => Ok(Self::Meta),
+            "namespace" => Ok(Self::Namespace),
+            "key" => Ok(Self::Key),
+            "maintenance" => Ok(Self::Maintenance),
+            other => Err(format!(
+                "unknown event class '{other}' (expected record, graph, meta, \
+                 namespace, key or maintenance)"
+            )),
+        }
+    }
+}
+
+/// Which events a filtered replay reports. Every set criterion must hold;
+/// the default filter matches everything.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct EventFilter {
+    /// Keep only these classes (empty = all).
+    pub classes: Vec<EventClass>,
+    /// Keep only events touching a record id in this range.
+    pub records: Option<std::ops::RangeInclusive<u32>>,
+    /// Keep only events touching a node id in this range.
+    pub nodes: Option<std::ops::RangeInclusive<u32>>,
+    /// Keep only events applied in this namespace.
+    pub namespace: Option<u16>,
+}
+
+impl EventFilter {
+    pub fn is_empty(&self) -> bool {
+        *self == Self::default()
+    }
+
+    /// Whether `event` matches, judged against `state` as it is just
+    /// before the event is applied. The state resolves what the event
+    /// itself does not carry: the id an `Auto*` create will get, the
+    /// endpoints of a deleted edge, the record of a deleted node.
+    pub fn matches(&self, state: &KernelState, namespace_id: u16, event: &KernelEvent) -> bool {
+        if !self.classes.is_empty() && !self.classes.contains(&EventClass::of(event)) {
+            return false;
+        }
+        if self.namespace.is_some_and(|ns| ns != namespace_id) {
+            return false;
+        }
+        if let Some(range) = &self.records {
+            if !touched_records(state, event).any(|id| range.contains(&id)) {
+                return false;
+            }
+        }
+        if let Some(range) = &self.nodes {
+            if !touched_nodes(state, event).any(|id| range.contains(&id)) {
+                return false;
+            }
+        }
+        true
+    }
+}
+
+/// Record ids `event` creates, changes, deletes or links a node to, judged
+/// against `state` just before the event (see [`EventFilter::matches`]).
+pub fn touched_records(state: &KernelState, event: &KernelEvent) -> impl Iterator<Item = u32> {
+    let ids: Vec<u32> = match event {
+        KernelEvent::InsertRecord { id, .. }
+        | KernelEvent::DeleteRecord { id }
+        | KernelEvent::SoftDeleteRecord { id }
+        | KernelEvent::InsertRecordEncrypted { id, .. }
+        | KernelEvent::UpdateRecordMetadata { id, .. }
+        | KernelEvent::SetSparseVector { id, .. } => vec![id.0],
+        KernelEvent::AutoInsertRecord { .. } | KernelEvent::AutoInsertRecordEncrypted { .. } => {
+            vec![state.next_record_id().0]
+        }
+        KernelEvent::CreateNode { record, .. } | KernelEvent::AutoCreateNode { record, .. } => {
+            record.iter().map(|r| r.0).collect()
+        }
+        KernelEvent::DeleteNode { id } => state
+            .get_node(*id)
+            .and_then(|n| n.record)
+            .map(|r| r.0)
+            .into_iter()
+            .collect(),
+        KernelEvent::Vacuum { moves } => moves.iter().flat_map(|(o, n)| [o.0, n.0]).collect(),
+        _ => Vec::new(),
+    };
+    ids.into_iter()
+}
+
+/// Node ids `event` creates, deletes, or adds or removes an edge on, judged
+/// against `state` just before the event.
+pub fn touched_nodes(state: &KernelState, event: &KernelEvent) -> impl Iterator<Item = u32> {
+    let ids: Vec<u32> = match event {
+        KernelEvent::CreateNode { id, .. } | KernelEvent::DeleteNode { id } => vec![id.0],
+        KernelEvent::AutoCreateNode { .. } => vec![state.next_node_id().0],
+        KernelEvent::CreateEdge { from, to, .. } | KernelEvent::AutoCreateEdge { from, to, .. } => {
+            vec![from.0, to.0]
+        }
+        KernelEvent::DeleteEdge { id } => state
+            .get_edge(*id)
+            .map(|e| vec![e.from.0, e.to.0])
+            .unwrap_or_default(),
+        _ => Vec::new(),
+    };
+    ids.into_iter()
+}
+
+/// [`replay_events`], also returning the indexes of the events `filter`
+/// matches. Every event is still applied — later events can depend on
+/// ones the filter drops — but only the matches are reported.
+pub fn replay_events_filtered(
+    events: &[(u16, KernelEvent)],
+    filter: &EventFilter,
+) -> Result<(KernelState, Vec<usize>)> {
+    let mut state = KernelState::new();
+    let mut matched = Vec::new();
+    for (idx, (namespace_id, event)) in events.iter().enumerate() {
+        if filter.matches(&state, *namespace_id, event) {
+            matched.push(idx);
+        }
+        state.apply_event_ns(event, *namespace_id).map_err(|e| {
+            tracing::error!("Event replay failed at index {}: {:?}", idx, e);
+            ReplayError::EventApplication(e)
+        })?;
+    }
+    Ok((state, matched))
+}
+
 /// One segment's replay result: its sequence number, the events it carries
 /// (with each event's namespace, S15), the chain head it splices FROM
 /// (header), and the chain head it closes WITH.
@@ -81,19 +273,20 @@ fn read_segment_full(path: impl AsRef<Path>, expected_dim: Option<u32>) -> Resul
 
     use crate::events::event_log::{walk_segment_body, LogEntry, SegmentWalkError};
 
-    let (decoded_entries, chain_head) = walk_segment_body(
+    let (decoded_entries, chain_head, _) = walk_segment_body(
         header.version,
         &buffer,
         header.header_len,
         header.prev_segment_chain_head,
     )
     .map_err(|e| match e {
-        SegmentWalkError::ChainBroken { offset } => ReplayError::Corrupted { offset },
-        SegmentWalkError::Wire { offset, .. } => ReplayError::Corrupted { offset },
+        SegmentWalkError::ChainBroken { offset }
+        | SegmentWalkError::BatchBroken { offset }
+        | SegmentWalkError::Wire { offset, .. } => ReplayError::Corrupted { offset },
     })?;
 
     let mut events = Vec::new();
-    for decoded in decoded_entries {
+    for (decoded, _) in decoded_entries {
         match decoded.entry {
             LogEntry::Event(event) => {
                 events.push((valori_kernel::types::id::DEFAULT_NS.0, event));
@@ -116,20 +309,9 @@ fn read_segment_full(path: impl AsRef<Path>, expected_dim: Option<u32>) -> Resul
     })
 }
 
-/// Discover and replay every local segment for `live_path` in order.
-///
-/// Rotation seals `events.log` to `events.log.<suffix>` and opens a fresh
-/// segment whose header splices from the sealed one's final chain head. This
-/// gathers the live file plus all sibling archives, orders them by segment
-/// sequence, verifies each splice point, and returns the full event history.
-/// A single-segment log (no rotation has happened) reads exactly as before.
-pub fn read_all_segments(
-    live_path: impl AsRef<Path>,
-    expected_dim: Option<u32>,
-) -> Result<Vec<(u16, KernelEvent)>> {
-    let live_path = live_path.as_ref();
-
-    // The live file plus any `events.log.<suffix>` archives in the same dir.
+/// The live file plus any `events.log.<suffix>` archives in the same dir,
+/// unordered.
+pub fn segment_paths(live_path: &Path) -> Vec<std::path::PathBuf> {
     let mut paths = vec![live_path.to_path_buf()];
     if let (Some(dir), Some(fname)) = (
         live_path.parent(),
@@ -146,10 +328,47 @@ pub fn read_all_segments(
             }
         }
     }
+    paths
+}
+
+/// Discover and replay every local segment for `live_path` in order.
+///
+/// Rotation seals `events.log` to `events.log.<suffix>` and opens a fresh
+/// segment whose header splices from the sealed one's final chain head. This
+/// gathers the live file plus all sibling archives, orders them by segment
+/// sequence, verifies each splice point, and returns the full event history.
+/// A single-segment log (no rotation has happened) reads exactly as before.
+pub fn read_all_segments(
+    live_path: impl AsRef<Path>,
+    expected_dim: Option<u32>,
+) -> Result<Vec<(u16, KernelEvent)>> {
+    read_all_segments_with_progress(live_path, expected_dim, None)
+}
 
+/// [`read_all_segments`], counting the segment bytes read into `progress`.
+pub fn read_all_segments_with_progress(
+    live_path: impl AsRef<Path>,
+    expected_dim: Option<u32>,
+    progress: Option<&RecoveryProgress>,
+) -> Result<Vec<(u16, KernelEvent)>> {
+    let paths = segment_paths(live_path.as_ref());
+    let sizes: Vec<u64> = paths
+        .iter()
+        .map(|p| std::fs::metadata(p).map_or(0, |m| m.len()))
+        .collect();
+    if let Some(progress) = progress {
+        progress.add_bytes_total(sizes.iter().sum());
+    }
     let mut segments: Vec<SegmentReplay> = paths
         .iter()
-        .map(|p| read_segment_full(p, expected_dim))
+        .zip(&sizes)
+        .map(|(p, &size)| {
+            let segment = read_segment_full(p, expected_dim)?;
+            if let Some(progress) = progress {
+                progress.add_bytes_read(size);
+            }
+            Ok(segment)
+        })
         .collect::<Result<_>>()?;
     segments.sort_by_key(|s| s.segment_seq);
 
@@ -174,15 +393,24 @@ pub fn read_all_segments(
 /// archives + the live file) so a rotated log recovers losslessly.
 pub fn recover_from_event_log(
     log_path: impl AsRef<Path>,
+) -> Result<(KernelState, EventJournal, u64)> {
+    recover_from_event_log_with_progress(log_path, None)
+}
+
+/// [`recover_from_event_log`], reporting bytes read and events replayed to
+/// `progress`.
+pub fn recover_from_event_log_with_progress(
+    log_path: impl AsRef<Path>,
+    progress: Option<&RecoveryProgress>,
 ) -> Result<(KernelState, EventJournal, u64)> {
     tracing::info!("Starting recovery from event log: {:?}", log_path.as_ref());
 
-    let events = read_all_segments(log_path, None)?;
+    let events = read_all_segments_with_progress(log_path, None, progress)?;
     let event_count = events.len() as u64;
 
     tracing::info!("Loaded {} events across all segments", event_count);
 
-    let state = replay_events(&events)?;
+    let state = replay_events_with_progress(&events, progress)?;
     // The journal tracks height/dedup only — it doesn't need the namespace.
     let journal = EventJournal::from_committed(events.into_iter().map(|(_, e)| e).collect());
 
@@ -244,6 +472,78 @@ mod tests {
         }
     }
 
+    #[test]
+    fn filtered_replay_reports_every_change_to_one_record() {
+        use valori_kernel::types::enums::{EdgeKind, NodeKind};
+        use valori_kernel::types::id::{EdgeId, NodeId};
+        let node = |id, record| KernelEvent::CreateNode {
+            id: NodeId(id),
+            kind: NodeKind::default(),
+            record,
+        };
+        let events: Vec<(u16, KernelEvent)> = vec![
+            ev(0),
+            ev(1),
+            node(0, Some(RecordId(1))),
+            node(1, None),
+            KernelEvent::CreateEdge {
+                id: EdgeId(0),
+                from: NodeId(1),
+                to: NodeId(0),
+                kind: EdgeKind::default(),
+            },
+            KernelEvent::UpdateRecordMetadata {
+                id: RecordId(1),
+                metadata: Some(b"{}".to_vec()),
+            },
+            KernelEvent::AutoInsertRecord {
+                vector: FxpVector::new_zeros(16),
+                metadata: None,
+                tag: 0,
+            },
+            KernelEvent::DeleteEdge { id: EdgeId(0) },
+            KernelEvent::DeleteNode { id: NodeId(0) },
+            KernelEvent::DeleteRecord { id: RecordId(1) },
+        ]
+        .into_iter()
+        .map(|e| (0, e))
+        .collect();
+
+        let record = |id| EventFilter {
+            records: Some(id..=id),
+            ..Default::default()
+        };
+        let (state, matched) = replay_events_filtered(&events, &record(1)).unwrap();
+        assert_eq!(matched, vec![1, 2, 5, 8, 9]);
+        assert_eq!(
+            hash_state_blake3(&state),
+            hash_state_blake3(&replay_events(&events).unwrap()),
+            "the filter only changes what is reported"
+        );
+        // The auto-id insert is attributed to the id it got.
+        assert_eq!(
+            replay_events_filtered(&events, &record(2)).unwrap().1,
+            vec![6]
+        );
+
+        let graph_on_node_1 = EventFilter {
+            classes: vec![EventClass::Graph],
+            nodes: Some(1..=1),
+            ..Default::default()
+        };
+        let (_, matched) = replay_events_filtered(&events, &graph_on_node_1).unwrap();
+        assert_eq!(matched, vec![3, 4, 7]);
+        assert_eq!(
+            replay_events_filtered(&events, &EventFilter::default())
+                .unwrap()
+                .1
+                .len(),
+            events.len()
+        );
+        assert!("graph".parse::<EventClass>().is_ok());
+        assert!("graphs".parse::<EventClass>().is_err());
+    }
+
     #[test]
     fn test_dimension_mismatch_rejected() {
         let dir = tempdir().unwrap();
@@ -307,6 +607,29 @@ mod tests {
         }
     }
 
+    #[test]
+    fn recovery_reports_bytes_and_events_to_progress() {
+        use crate::events::event_log::LogEntry;
+        let dir = tempdir().unwrap();
+        let path = dir.path().join("events.log");
+        let mut w = EventLogWriter::open(&path, Some(16)).unwrap();
+        for i in 0..4 {
+            w.append(&LogEntry::Event(ev(i))).unwrap();
+        }
+        drop(w);
+
+        let progress = RecoveryProgress::default();
+        progress.begin();
+        let (_, _, count) = recover_from_event_log_with_progress(&path, Some(&progress)).unwrap();
+        let status = progress.status();
+        assert_eq!(count, 4);
+        assert_eq!(status.events_replayed, 4);
+        assert_eq!(status.events_total, 4);
+        assert_eq!(status.bytes_read, std::fs::metadata(&path).unwrap().len());
+        assert_eq!(status.bytes_read, status.bytes_total);
+        assert_eq!(status.percent, 100.0);
+    }
+
     #[test]
     fn namespaced_events_recover_into_their_own_collection() {
         // Phase S15 regression: before EventNs existed, a record written to a
@@ -19,8 +19,14 @@ pub mod event_journal;
 pub mod event_log;
 pub mod event_proof;
 pub mod event_replay;
+pub mod quarantine;
+pub mod recovery_progress;
 
-pub use event_commit::{CommitResult, EventCommitter};
+pub use event_commit::{CommitResult, EventCommitter, Rotation};
 pub use event_journal::EventJournal;
 pub use event_log::EventLogWriter;
-pub use event_replay::recover_from_event_log;
+pub use event_replay::{
+    recover_from_event_log, touched_nodes, touched_records, EventClass, EventFilter,
+};
+pub use quarantine::DamageReport;
+pub use recovery_progress::{RecoveryProgress, RecoveryStatus};
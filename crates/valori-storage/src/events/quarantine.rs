@@ -0,0 +1,307 @@
+// Copyright (c) 2025 Varshith Gudur. Dual-licensed under MIT OR Apache-2.0.
+//! Corrupt-segment quarantine — opt-in salvage for a damaged event log.
+//!
+//! By default a corrupt entry anywhere in the log fails recovery closed:
+//! nothing after it can be trusted, so nothing is replayed. With quarantine
+//! enabled the operator trades the damaged suffix for a running node:
+//!
+//! 1. segments are walked in sequence order until the first one that fails
+//!    (bad header, broken splice, broken chain link, bad entry);
+//! 2. that segment and every later one are moved into
+//!    `<log dir>/quarantine/<unix secs>/`, untouched, for forensics;
+//! 3. the live segment is rewritten as the verified prefix of the failing
+//!    segment (or an empty segment spliced onto the last good one), so the
+//!    chain stays continuous and appends carry on from the last verifiable
+//!    height;
+//! 4. a [`DamageReport`] is written into the quarantine directory and next to
+//!    the log (`events.damage.json`). The engine loads the latter on every
+//!    start and marks proofs as truncated until an operator deletes it.
+//!
+//! The replacement live segment is staged and synced before anything is
+//! moved, so a crash mid-quarantine leaves at worst the staged file behind.
+//! The moves themselves are durable once this returns.
+
+use crate::durability::{sync_dir, sync_parent};
+use crate::events::event_log::{counted, walk_segment_body, SegmentWalkError};
+use crate::events::event_replay::{segment_paths, ReplayError, Result};
+use serde::{Deserialize, Serialize};
+use std::io::Write;
+use std::path::{Path, PathBuf};
+use valori_wire::{encode_header_v4, parse_header, SegmentHeader, FORMAT_Q16_16};
+
+/// What was quarantined and where recovery now ends.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct DamageReport {
+    /// Unix seconds when the damage was quarantined.
+    pub detected_at: u64,
+    /// Original path of the first segment that failed verification.
+    pub segment: PathBuf,
+    /// Byte offset of the first bad entry in that segment (0 when the
+    /// segment as a whole was unusable).
+    pub offset: usize,
+    pub reason: String,
+    pub quarantine_dir: PathBuf,
+    /// New paths of every moved segment.
+    pub quarantined: Vec<PathBuf>,
+    /// Kernel events still on the log — the height recovery now reaches.
+    pub recovered_height: u64,
+    /// Chain head the truncated log closes with, lowercase hex.
+    pub chain_head: String,
+}
+
+/// Sticky damage marker for the log at `live_path`. Deliberately not
+/// `events.log.*`, which [`segment_paths`] would pick up as an archive.
+pub fn damage_report_path(live_path: &Path) -> PathBuf {
+    live_path.with_extension("damage.json")
+}
+
+/// The damage report left by an earlier quarantine, if any.
+pub fn load_damage_report(live_path: &Path) -> Option<DamageReport> {
+    let bytes = std::fs::read(damage_report_path(live_path)).ok()?;
+    serde_json::from_slice(&bytes).ok()
+}
+
+struct Segment {
+    path: PathBuf,
+    buf: Vec<u8>,
+    header: Option<SegmentHeader>,
+}
+
+/// Walk every segment of the log at `live_path`; if one fails verification,
+/// quarantine it and everything after it (see the module docs). Returns
+/// `Ok(None)` when the log verifies cleanly and nothing was touched.
+/// `dim` is only used when a fresh segment header has to be written.
+pub fn quarantine_corrupt_segments(live_path: &Path, dim: u32) -> Result<Option<DamageReport>> {
+    let mut segments = segment_paths(live_path)
+        .into_iter()
+        .map(|path| {
+            let buf = std::fs::read(&path)?;
+            let header = parse_header(&buf).ok();
+            Ok(Segment { path, buf, header })
+        })
+        .collect::<Result<Vec<_>>>()?;
+    // Unreadable headers sort last: their place in the chain is unknown.
+    segments.sort_by_key(|s| (s.header.is_none(), s.header.as_ref().map(|h| h.segment_seq)));
+
+    let mut recovered_height = 0u64;
+    let mut prev: Option<(u32, [u8; 32])> = None;
+    let mut damage = None;
+    for (i, seg) in segments.iter().enumerate() {
+        let Some(header) = &seg.header else {
+            damage = Some((i, 0, "unreadable segment header".to_string(), None));
+            break;
+        };
+        if let Some((_, close)) = prev {
+            if header.prev_segment_chain_head != close {
+                damage = Some((
+                    i,
+                    0,
+                    "does not splice onto the previous segment".to_string(),
+                    None,
+                ));
+                break;
+            }
+        }
+        let body = |end: usize| {
+            walk_segment_body(
+                header.version,
+                &seg.buf[..end],
+                header.header_len,
+                header.prev_segment_chain_head,
+            )
+        };
+        let (offset, reason) = match body(seg.buf.len()) {
+            Ok((entries, close, _)) => {
+                recovered_height = height_after(recovered_height, &entries);
+                prev = Some((header.segment_seq, close));
+                continue;
+            }
+            Err(SegmentWalkError::ChainBroken { offset }) => {
+                (offset, "chain link broken".to_string())
+            }
+            Err(SegmentWalkError::BatchBroken { offset }) => {
+                (offset, "batch does not match its commit record".to_string())
+            }
+            Err(SegmentWalkError::Wire { offset, source }) => (offset, source.to_string()),
+        };
+        // Everything before `offset` decoded and chained, so it walks clean.
+        let (entries, close, _) = body(offset).map_err(|_| ReplayError::Corrupted { offset })?;
+        recovered_height = height_after(recovered_height, &entries);
+        prev = Some((header.segment_seq, close));
+        damage = Some((i, offset, reason, Some(seg.buf[..offset].to_vec())));
+        break;
+    }
+    let Some((first_bad, offset, reason, prefix)) = damage else {
+        return Ok(None);
+    };
+
+    let (last_seq, chain_head) = prev.unwrap_or((0, [0u8; 32]));
+    let live_is_kept = segments[..first_bad].iter().any(|s| s.path == live_path);
+    let dir = live_path.parent().unwrap_or(Path::new("."));
+    let staged = dir.join(format!(
+        ".{}.salvage",
+        live_path
+            .file_name()
+            .and_then(|n| n.to_str())
+            .unwrap_or("events.log")
+    ));
+    if !live_is_kept {
+        let bytes = prefix.unwrap_or_else(|| {
+            let next_seq = if first_bad == 0 { 0 } else { last_seq + 1 };
+            encode_header_v4(dim, FORMAT_Q16_16, next_seq, &chain_head).to_vec()
+        });
+        let mut file = std::fs::File::create(&staged)?;
+        file.write_all(&bytes)?;
+        file.sync_all()?;
+    }
+
+    let detected_at = std::time::SystemTime::now()
+        .duration_since(std::time::UNIX_EPOCH)
+        .map_or(0, |d| d.as_secs());
+    let quarantine_dir = dir.join("quarantine").join(detected_at.to_string());
+    std::fs::create_dir_all(&quarantine_dir)?;
+    let mut quarantined = Vec::new();
+    for seg in &segments[first_bad..] {
+        let target = quarantine_dir.join(seg.path.file_name().unwrap_or_default());
+        std::fs::rename(&seg.path, &target)?;
+        quarantined.push(target);
+    }
+    if !live_is_kept {
+        std::fs::rename(&staged, live_path)?;
+    }
+    sync_dir(&quarantine_dir)?;
+    sync_parent(&quarantine_dir)?;
+    sync_parent(live_path)?;
+
+    let report = DamageReport {
+        detected_at,
+        segment: segments[first_bad].path.clone(),
+        offset,
+        reason,
+        quarantine_dir: quarantine_dir.clone(),
+        quarantined,
+        recovered_height,
+        chain_head: chain_head.iter().map(|b| format!("{b:02x}")).collect(),
+    };
+    let json = serde_json::to_vec_pretty(&report)
+        .map_err(|e| ReplayError::Deserialization(e.to_string()))?;
+    std::fs::write(quarantine_dir.join("damage-report.json"), &json)?;
+    std::fs::write(damage_report_path(live_path), &json)?;
+    tracing::error!(
+        "Quarantined {} corrupt event-log segment(s) from {:?} at offset {} ({}); \
+         recovering to height {}",
+        report.quarantined.len(),
+        report.segment,
+        report.offset,
+        report.reason,
+        report.recovered_height
+    );
+    Ok(Some(report))
+}
+
+/// Event count after `entries`, starting from `height`. A checkpoint
+/// restates the count, so a segment whose archives were pruned still
+/// reports its full height.
+fn height_after(
+    height: u64,
+    entries: &[(valori_wire::DecodedEntry, std::ops::Range<usize>)],
+) -> u64 {
+    entries
+        .iter()
+        .fold(height, |n, (d, _)| counted(n, &d.entry))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::events::event_log::{EventLogWriter, LogEntry};
+    use crate::events::event_replay::recover_from_event_log;
+    use tempfile::tempdir;
+    use valori_kernel::event::KernelEvent;
+    use valori_kernel::types::id::RecordId;
+    use valori_kernel::types::vector::FxpVector;
+
+    fn ev(i: u32) -> LogEntry {
+        LogEntry::Event(KernelEvent::InsertRecord {
+            id: RecordId(i),
+            vector: FxpVector::new_zeros(16),
+            metadata: None,
+            tag: 0,
+        })
+    }
+
+    #[test]
+    fn corrupt_archive_is_quarantined_and_log_keeps_appending() {
+        let dir = tempdir().unwrap();
+        let path = dir.path().join("events.log");
+        let archive = dir.path().join("events.log.0001");
+
+        let mut w = EventLogWriter::open(&path, Some(16)).unwrap();
+        for i in 0..4 {
+            w.append(&ev(i)).unwrap();
+        }
+        w.rotate(&archive, None).unwrap();
+        for i in 4..6 {
+            w.append(&ev(i)).unwrap();
+        }
+        drop(w);
+        assert!(quarantine_corrupt_segments(&path, 16).unwrap().is_none());
+
+        // Flip a byte inside the archive's last entry.
+        let mut bytes = std::fs::read(&archive).unwrap();
+        let n = bytes.len();
+        bytes[n - 3] ^= 0xff;
+        std::fs::write(&archive, bytes).unwrap();
+        assert!(recover_from_event_log(&path).is_err());
+
+        let report = quarantine_corrupt_segments(&path, 16).unwrap().unwrap();
+        assert_eq!(report.segment, archive);
+        assert_eq!(report.recovered_height, 3);
+        assert_eq!(report.quarantined.len(), 2);
+        assert!(!archive.exists());
+        assert_eq!(load_damage_report(&path), Some(report));
+
+        let (state, _, count) = recover_from_event_log(&path).unwrap();
+        assert_eq!(count, 3);
+        assert!(state.get_record(RecordId(2)).is_some());
+        let mut w = EventLogWriter::open(&path, Some(16)).unwrap();
+        w.append(&ev(3)).unwrap();
+        drop(w);
+        assert_eq!(recover_from_event_log(&path).unwrap().2, 4);
+    }
+
+    #[test]
+    fn height_counts_from_a_checkpoint_when_archives_are_pruned() {
+        let dir = tempdir().unwrap();
+        let path = dir.path().join("events.log");
+        let archive = dir.path().join("events.log.0001");
+
+        let mut w = EventLogWriter::open(&path, Some(16)).unwrap();
+        for i in 0..4 {
+            w.append(&ev(i)).unwrap();
+        }
+        let checkpoint = LogEntry::Checkpoint {
+            event_count: 4,
+            snapshot_hash: *w.chain_head(),
+            timestamp: 0,
+        };
+        w.rotate(&archive, Some(checkpoint)).unwrap();
+        for i in 4..7 {
+            w.append(&ev(i)).unwrap();
+        }
+        drop(w);
+        // Retention pruned the archive; the live segment opens with the
+        // checkpoint that restates its four events.
+        std::fs::remove_file(&archive).unwrap();
+
+        // Flip a byte inside the live segment's last entry.
+        let mut bytes = std::fs::read(&path).unwrap();
+        let n = bytes.len();
+        bytes[n - 3] ^= 0xff;
+        std::fs::write(&path, bytes).unwrap();
+
+        let report = quarantine_corrupt_segments(&path, 16).unwrap().unwrap();
+        assert_eq!(report.segment, path);
+        assert_eq!(report.recovered_height, 6);
+    }
+}
@@ -5,11 +5,13 @@
 //! - WAL (write-ahead log): `wal_writer`, `wal_reader`
 //! - Event log + journal: `events`
 //! - Object store (S3/file): `object_store`
+//! - Sync strategies and directory fsyncs: `durability`
 //!
 //! Recovery orchestration (which files to load, in what order) lives in
 //! `valori-state::bootstrap`. This crate provides the raw primitives that
 //! bootstrap uses.
 
+pub mod durability;
 pub mod error;
 pub mod events;
 pub mod object_store;
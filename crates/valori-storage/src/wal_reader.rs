@@ -7,37 +7,14 @@ use std::io::{BufRead, BufReader, Read};
 use std::path::Path;
 use thiserror::Error;
 use valori_kernel::event::KernelEvent;
+use valori_protocol::Format;
 
-/// 16-byte header at the start of every WAL file.
+/// 16-byte header at the start of every WAL file, shared with the firmware.
 /// Layout: [Version:u32 LE][EncodingVersion:u32 LE][Dim:u32 LE][ChecksumLen:u32 LE]
 ///
 /// Version 1 = legacy Command bincode stream.
 /// Version 2 = (KernelEvent, namespace_id: u16) bincode stream.
-pub struct WalHeader {
-    pub version: u32,
-    pub encoding_version: u32,
-    pub dim: u32,
-    pub checksum_len: u32,
-}
-
-impl WalHeader {
-    pub const SIZE: usize = 16;
-
-    pub fn read(buf: &[u8]) -> Result<(Self, &[u8]), ()> {
-        if buf.len() < Self::SIZE {
-            return Err(());
-        }
-        Ok((
-            Self {
-                version: u32::from_le_bytes([buf[0], buf[1], buf[2], buf[3]]),
-                encoding_version: u32::from_le_bytes([buf[4], buf[5], buf[6], buf[7]]),
-                dim: u32::from_le_bytes([buf[8], buf[9], buf[10], buf[11]]),
-                checksum_len: u32::from_le_bytes([buf[12], buf[13], buf[14], buf[15]]),
-            },
-            &buf[Self::SIZE..],
-        ))
-    }
-}
+pub use valori_protocol::formats::wal::WalHeader;
 
 #[derive(Debug, Error)]
 pub enum WalReaderError {
@@ -89,18 +66,14 @@ impl WalReader {
     }
 
     fn read_header(&mut self) -> WalResult<()> {
-        let mut head_buf = [0u8; 16];
+        let mut head_buf = [0u8; WalHeader::SIZE];
         self.reader.read_exact(&mut head_buf)?;
 
-        let (header, _) = WalHeader::read(&head_buf)
-            .map_err(|_| WalReaderError::Header("Invalid header".into()))?;
+        let header = WalHeader::decode(&head_buf)
+            .ok_or_else(|| WalReaderError::Header("Invalid header".into()))?;
 
-        if header.version != 1 && header.version != 2 {
-            return Err(WalReaderError::Header(format!(
-                "Unsupported WAL version {}",
-                header.version
-            )));
-        }
+        valori_protocol::check_version(Format::Wal, header.version)
+            .map_err(|e| WalReaderError::Header(e.to_string()))?;
 
         if let Some(expected) = self.expected_dim {
             if header.dim != 0 && header.dim != expected {
@@ -15,7 +15,7 @@ use std::path::Path;
 use thiserror::Error;
 use valori_kernel::event::KernelEvent;
 
-pub const WAL_VERSION: u32 = 2;
+pub use valori_protocol::formats::wal::WAL_VERSION;
 
 #[derive(Debug, Error)]
 pub enum WalError {
@@ -57,7 +57,7 @@ impl WalWriter {
             let mut head_buf = [0u8; WalHeader::SIZE];
             let mut read_handle = File::open(path)?;
             read_handle.read_exact(&mut head_buf)?;
-            let version = u32::from_le_bytes([head_buf[0], head_buf[1], head_buf[2], head_buf[3]]);
+            let version = WalHeader::decode(&head_buf).map_or(0, |h| h.version);
             if version != WAL_VERSION {
                 return Err(WalError::Validation(format!(
                     "Existing WAL file uses format v{version}; \
@@ -68,12 +68,7 @@ impl WalWriter {
             file_len
         } else if file_len == 0 {
             // New file — write v2 header.
-            let mut head_buf = [0u8; WalHeader::SIZE];
-            head_buf[0..4].copy_from_slice(&WAL_VERSION.to_le_bytes());
-            head_buf[4..8].copy_from_slice(&0u32.to_le_bytes()); // encoding_version
-            head_buf[8..12].copy_from_slice(&dim.to_le_bytes());
-            head_buf[12..16].copy_from_slice(&0u32.to_le_bytes()); // checksum_len
-            raw_file.write_all(&head_buf)?;
+            raw_file.write_all(&WalHeader::new(dim).encode())?;
             raw_file.flush()?;
             WalHeader::SIZE as u64
         } else {
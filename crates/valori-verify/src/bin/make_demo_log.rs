@@ -20,6 +20,7 @@ use valori_kernel::types::enums::{EdgeKind, NodeKind};
 use valori_kernel::types::id::{EdgeId, NodeId, RecordId};
 use valori_kernel::types::scalar::FxpScalar;
 use valori_kernel::types::vector::FxpVector;
+use valori_protocol::SCALE;
 
 use valori_wire::{
     chain_advance_v3, encode_entry, encode_header_v4, hex, LogEntry, FORMAT_Q16_16, VERSION_V4,
@@ -42,7 +43,7 @@ impl Lcg {
     }
 
     fn fxp(&mut self) -> FxpScalar {
-        let raw = (self.next() % 131072) as i32 - 65536;
+        let raw = (self.next() % (2 * SCALE as u32)) as i32 - SCALE;
         FxpScalar(raw)
     }
 }
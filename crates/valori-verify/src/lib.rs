@@ -13,7 +13,8 @@ use std::time::{SystemTime, UNIX_EPOCH};
 use valori_kernel::snapshot::blake3::hash_state_blake3;
 use valori_kernel::state::kernel::KernelState;
 use valori_wire::{
-    chain_advance, decode_entry, format_utc, hex, parse_header, LogEntry, SegmentHeader,
+    chain_advance, committed_len, decode_entry, format_utc, hex, parse_header, LogEntry,
+    SegmentHeader,
 };
 
 // ── LogSummary — shared anchor / regression-test API ─────────────────────────
@@ -51,13 +52,14 @@ pub fn replay_log(path: &Path) -> Result<LogSummary, String> {
     let header = parse_header(&bytes).map_err(|e| format!("cannot parse header: {e}"))?;
 
     let body = &bytes[header.header_len..];
+    let committed = committed_len(header.version, body);
     let mut chain_head = header.prev_segment_chain_head;
     let mut event_count = 0u64;
     let mut offset = 0usize;
     let mut state = KernelState::new();
 
     while offset < body.len() {
-        let (chained, n) = match decode_entry(header.version, &body[offset..]) {
+        let (chained, n) = match decode_entry(header.version, &body[offset..committed]) {
             Ok(pair) => pair,
             Err(_) => break, // trailing partial write — caller checks trailing_bytes
         };
@@ -92,7 +94,10 @@ pub fn replay_log(path: &Path) -> Result<LogSummary, String> {
                 })?;
                 event_count += 1;
             }
-            LogEntry::Checkpoint { .. } | LogEntry::Admin(_) => {}
+            LogEntry::Checkpoint { .. }
+            | LogEntry::CheckpointV2 { .. }
+            | LogEntry::Admin(_)
+            | LogEntry::BatchCommit { .. } => {}
         }
     }
 
@@ -145,7 +150,15 @@ fn entry_summary(entry: &LogEntry) -> String {
         LogEntry::Checkpoint { event_count, .. } => {
             format!("Checkpoint {{ event_count: {event_count} }}")
         }
+        LogEntry::CheckpointV2 { event_count, .. } => {
+            format!("CheckpointV2 {{ event_count: {event_count} }}")
+        }
         LogEntry::Admin(a) => a.describe(),
+        LogEntry::BatchCommit {
+            first_height,
+            count,
+            ..
+        } => format!("BatchCommit {{ first_height: {first_height}, count: {count} }}"),
     }
 }
 
@@ -156,9 +169,11 @@ fn replay(body: &[u8], header: &SegmentHeader) -> ReplayOutcome {
     let mut offset: usize = 0;
     let mut chain_head = header.prev_segment_chain_head;
     let mut last_entry_summary = String::from("<none>");
+    // A batch cut short by a crash reads as a torn tail, like a partial entry.
+    let committed = committed_len(header.version, body);
 
     while offset < body.len() {
-        let chained = match decode_entry(header.version, &body[offset..]) {
+        let chained = match decode_entry(header.version, &body[offset..committed]) {
             Ok((ce, n)) => {
                 offset += n;
                 ce
@@ -237,11 +252,10 @@ fn replay(body: &[u8], header: &SegmentHeader) -> ReplayOutcome {
                 }
                 events_applied += 1;
             }
-            LogEntry::Checkpoint { event_count, .. } => {
-                let _ = event_count;
+            LogEntry::Checkpoint { .. } | LogEntry::CheckpointV2 { .. } => {
                 checkpoints_seen += 1;
             }
-            LogEntry::Admin(_) => {}
+            LogEntry::Admin(_) | LogEntry::BatchCommit { .. } => {}
         }
 
         last_entry_summary = entry_summary(&chained.entry);
@@ -29,7 +29,8 @@ use valori_kernel::snapshot::blake3::hash_state_blake3;
 use valori_kernel::state::kernel::KernelState;
 
 use valori_wire::{
-    chain_advance, decode_entry, format_utc, hex, parse_header, LogEntry, SegmentHeader,
+    chain_advance, committed_len, decode_entry, format_utc, hex, parse_header, LogEntry,
+    SegmentHeader,
 };
 
 #[derive(Parser, Debug)]
@@ -97,7 +98,15 @@ fn entry_summary(entry: &LogEntry) -> String {
         LogEntry::Checkpoint { event_count, .. } => {
             format!("Checkpoint {{ event_count: {event_count} }}")
         }
+        LogEntry::CheckpointV2 { event_count, .. } => {
+            format!("CheckpointV2 {{ event_count: {event_count} }}")
+        }
         LogEntry::Admin(a) => a.describe(),
+        LogEntry::BatchCommit {
+            first_height,
+            count,
+            ..
+        } => format!("BatchCommit {{ first_height: {first_height}, count: {count} }}"),
     }
 }
 
@@ -110,6 +119,8 @@ fn replay(body: &[u8], header: &SegmentHeader, trace: bool) -> ReplayOutcome {
     // (recorded in the header); v2 and genesis segments start from zeros.
     let mut chain_head = header.prev_segment_chain_head;
     let mut last_entry_summary = String::from("<none>");
+    // A batch cut short by a crash reads as a torn tail, like a partial entry.
+    let committed = committed_len(header.version, body);
     let mut entries_decoded: u64 = 0;
 
     while offset < body.len() {
@@ -125,7 +136,7 @@ fn replay(body: &[u8], header: &SegmentHeader, trace: bool) -> ReplayOutcome {
             };
         }
         entries_decoded += 1;
-        let chained = match decode_entry(header.version, &body[offset..]) {
+        let chained = match decode_entry(header.version, &body[offset..committed]) {
             Ok((ce, n)) => {
                 offset += n;
                 ce
@@ -219,7 +230,8 @@ fn replay(body: &[u8], header: &SegmentHeader, trace: bool) -> ReplayOutcome {
                 }
                 events_applied += 1;
             }
-            LogEntry::Checkpoint { event_count, .. } => {
+            LogEntry::Checkpoint { event_count, .. }
+            | LogEntry::CheckpointV2 { event_count, .. } => {
                 if trace {
                     eprintln!("  checkpoint (event_count = {event_count})");
                 }
@@ -233,6 +245,15 @@ fn replay(body: &[u8], header: &SegmentHeader, trace: bool) -> ReplayOutcome {
                     eprintln!("  admin: {}", admin.describe());
                 }
             }
+            LogEntry::BatchCommit {
+                first_height,
+                count,
+                ..
+            } => {
+                if trace {
+                    eprintln!("  batch of {count} from height {first_height}");
+                }
+            }
         }
 
         last_entry_summary = entry_summary(&chained.entry);
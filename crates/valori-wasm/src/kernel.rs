@@ -0,0 +1,224 @@
+// Copyright (c) 2025 Varshith Gudur. Dual-licensed under MIT OR Apache-2.0.
+//! Plain-Rust core behind the JS bindings.
+//!
+//! Kept free of `wasm-bindgen` so it builds and tests on the host. Every
+//! write is a `KernelEvent` applied through `apply_event`, and floats cross
+//! into Q16.16 exactly as the node converts them, so the same inserts give
+//! the same state hash here and on a server.
+
+use valori_kernel::config::SCALE;
+use valori_kernel::error::{KernelError, Result};
+use valori_kernel::event::KernelEvent;
+use valori_kernel::index::SearchResult;
+use valori_kernel::snapshot::blake3::hash_state_blake3;
+use valori_kernel::snapshot::container::{self, encode_container, Container};
+use valori_kernel::snapshot::decode::decode_state;
+use valori_kernel::state::kernel::KernelState;
+use valori_kernel::types::enums::{EdgeKind, NodeKind};
+use valori_kernel::types::id::{NodeId, RecordId};
+use valori_kernel::types::scalar::FxpScalar;
+use valori_kernel::types::vector::FxpVector;
+
+pub struct Kernel {
+    state: KernelState,
+}
+
+impl Kernel {
+    /// Empty kernel locked to `dim`-element vectors; `0` locks to the
+    /// first insert.
+    pub fn new(dim: usize) -> Self {
+        Self {
+            state: KernelState::with_dim(dim),
+        }
+    }
+
+    /// Decode a snapshot from [`Kernel::snapshot`] or a node
+    /// (`GET /v1/snapshot/download`) — the same container, whose kernel
+    /// section is used and the rest ignored. A bare encoded state, as
+    /// earlier versions of [`Kernel::snapshot`] wrote, is accepted too.
+    pub fn restore(bytes: &[u8]) -> Result<Self> {
+        let state = if bytes.starts_with(container::MAGIC) {
+            Container::parse(bytes)
+                .map_err(|_| KernelError::InvalidInput)?
+                .decode_state()?
+        } else {
+            decode_state(bytes)?
+        };
+        Ok(Self { state })
+    }
+
+    pub fn dim(&self) -> Option<usize> {
+        self.state.dim
+    }
+
+    pub fn record_count(&self) -> usize {
+        self.state.record_count()
+    }
+
+    pub fn node_count(&self) -> usize {
+        self.state.node_count()
+    }
+
+    pub fn edge_count(&self) -> usize {
+        self.state.edge_count()
+    }
+
+    pub fn insert(&mut self, values: &[f32], tag: u64) -> Result<u32> {
+        let id = self.state.next_record_id();
+        self.state.apply_event(&KernelEvent::InsertRecord {
+            id,
+            vector: to_fxp(values)?,
+            metadata: None,
+            tag,
+        })?;
+        Ok(id.0)
+    }
+
+    pub fn delete(&mut self, id: u32) -> Result<()> {
+        self.state
+            .apply_event(&KernelEvent::DeleteRecord { id: RecordId(id) })
+    }
+
+    /// Top-`k` records by L2 distance as `(id, squared distance)`, nearest
+    /// first — the same score the node's `/v1/search` reports.
+    pub fn search(&self, query: &[f32], k: usize) -> Result<Vec<(u32, f32)>> {
+        if let Some(dim) = self.state.dim {
+            if query.len() != dim {
+                return Err(KernelError::DimensionMismatch {
+                    expected: dim,
+                    found: query.len(),
+                });
+            }
+        }
+        let query = to_fxp(query)?;
+        let mut results = vec![SearchResult::default(); k];
+        let found = self.state.search_l2(&query, &mut results, None);
+        Ok(results[..found]
+            .iter()
+            .map(|r| (r.id.0, r.score as f32 / (SCALE as f32 * SCALE as f32)))
+            .collect())
+    }
+
+    pub fn create_node(&mut self, kind: u8, record: Option<u32>) -> Result<u32> {
+        let kind = NodeKind::from_u8(kind).ok_or(KernelError::InvalidInput)?;
+        Ok(self.state.create_node(kind, record.map(RecordId))?.0)
+    }
+
+    pub fn create_edge(&mut self, from: u32, to: u32, kind: u8) -> Result<u32> {
+        let kind = EdgeKind::from_u8(kind).ok_or(KernelError::InvalidInput)?;
+        Ok(self.state.create_edge(NodeId(from), NodeId(to), kind)?.0)
+    }
+
+    /// Record linked to `node`, or `None` for a bare node. Errors when the
+    /// node does not exist.
+    pub fn node_record(&self, node: u32) -> Result<Option<u32>> {
+        let node = self
+            .state
+            .get_node(NodeId(node))
+            .ok_or(KernelError::NotFound)?;
+        Ok(node.record.map(|r| r.0))
+    }
+
+    /// Outgoing edges of `node` as `(edge_id, to_node, kind)`.
+    pub fn outgoing_edges(&self, node: u32) -> Vec<(u32, u32, u8)> {
+        self.state
+            .outgoing_edges(NodeId(node))
+            .map(|edges| edges.map(|e| (e.id.0, e.to.0, e.kind as u8)).collect())
+            .unwrap_or_default()
+    }
+
+    /// BLAKE3 hash of the full kernel state — equal to a node's
+    /// `final_state_hash` after the same events.
+    pub fn state_hash(&self) -> [u8; 32] {
+        hash_state_blake3(&self.state)
+    }
+
+    /// Snapshot container holding the kernel state, restorable with
+    /// [`Kernel::restore`] or uploaded to a node
+    /// (`POST /v1/snapshot/upload`), which rebuilds its index from it.
+    pub fn snapshot(&self) -> Result<Vec<u8>> {
+        encode_container(&self.state)
+    }
+}
+
+/// Q16.16 with the node's range check and truncating conversion, so a vector
+/// stores the same scalars wherever it is inserted.
+fn to_fxp(values: &[f32]) -> Result<FxpVector> {
+    let mut data = Vec::with_capacity(values.len());
+    for &v in values {
+        if !valori_protocol::fxp::in_range(v) {
+            return Err(KernelError::InvalidInput);
+        }
+        data.push(FxpScalar((v * SCALE as f32) as i32));
+    }
+    Ok(FxpVector { data })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use valori_kernel::snapshot::container::ContainerWriter;
+
+    fn vec_n(seed: f32) -> Vec<f32> {
+        (0..4).map(|i| seed + i as f32 * 0.01).collect()
+    }
+
+    #[test]
+    fn insert_search_and_graph() {
+        let mut k = Kernel::new(4);
+        let a = k.insert(&vec_n(0.1), 0).unwrap();
+        let b = k.insert(&vec_n(0.9), 0).unwrap();
+
+        let hits = k.search(&vec_n(0.1), 2).unwrap();
+        assert_eq!(hits[0].0, a);
+        assert_eq!(hits[1].0, b);
+        assert!(hits[0].1 < hits[1].1);
+
+        let doc = k.create_node(5, Some(a)).unwrap();
+        let chunk = k.create_node(6, None).unwrap();
+        let edge = k.create_edge(doc, chunk, 6).unwrap();
+        assert_eq!(k.node_record(doc).unwrap(), Some(a));
+        assert_eq!(k.outgoing_edges(doc), vec![(edge, chunk, 6)]);
+
+        k.delete(a).unwrap();
+        assert_eq!(k.record_count(), 1);
+        assert_eq!(k.search(&vec_n(0.1), 2).unwrap().len(), 1);
+    }
+
+    #[test]
+    fn bad_input_is_rejected() {
+        let mut k = Kernel::new(4);
+        assert!(k.insert(&[0.0; 3], 0).is_err());
+        assert!(k.insert(&[40_000.0, 0.0, 0.0, 0.0], 0).is_err());
+        assert!(k.search(&[0.0; 5], 1).is_err());
+        assert!(k.create_node(200, None).is_err());
+        assert!(k.node_record(0).is_err());
+    }
+
+    #[test]
+    fn snapshot_round_trips_the_state_hash() {
+        let mut k = Kernel::new(4);
+        let r = k.insert(&vec_n(0.3), 7).unwrap();
+        k.create_node(0, Some(r)).unwrap();
+
+        let bytes = k.snapshot().unwrap();
+        let restored = Kernel::restore(&bytes).unwrap();
+        assert_eq!(restored.state_hash(), k.state_hash());
+        assert_eq!(restored.record_count(), 1);
+
+        // A node snapshot: extra sections are ignored.
+        let c = Container::parse(&bytes).unwrap();
+        let mut node = ContainerWriter::new(c.kernel, b"{}", b"index");
+        node.section(b"NSRG", b"{}");
+        assert_eq!(
+            Kernel::restore(&node.finish()).unwrap().state_hash(),
+            k.state_hash()
+        );
+
+        // A bare encoded state from before the container.
+        assert_eq!(
+            Kernel::restore(c.kernel).unwrap().state_hash(),
+            k.state_hash()
+        );
+    }
+}
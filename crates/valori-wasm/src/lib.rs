@@ -0,0 +1,158 @@
+// Copyright (c) 2025 Varshith Gudur. Dual-licensed under MIT OR Apache-2.0.
+//! WebAssembly / JavaScript bindings for the Valori kernel.
+//!
+//! The same deterministic kernel the node runs, compiled to
+//! `wasm32-unknown-unknown` for browsers and Node.js. Inserts, searches,
+//! graph writes and snapshots give the same state hash as a server fed the
+//! same operations.
+//!
+//! ```js
+//! import init, { ValoriKernel } from "valori-wasm";
+//! await init();
+//! const kernel = new ValoriKernel(384);
+//! const id = kernel.insert(new Float32Array(embedding));
+//! const hits = kernel.search(new Float32Array(query), 5); // [{ id, score }]
+//! const bytes = kernel.snapshot();                        // Uint8Array
+//! ```
+
+mod kernel;
+
+use kernel::Kernel;
+use valori_kernel::error::KernelError;
+use wasm_bindgen::prelude::*;
+
+fn js_err(e: KernelError) -> JsError {
+    JsError::new(&e.to_string())
+}
+
+/// One search hit: record id and squared L2 distance.
+#[wasm_bindgen]
+#[derive(Clone, Copy)]
+pub struct SearchHit {
+    pub id: u32,
+    pub score: f32,
+}
+
+/// One outgoing graph edge.
+#[wasm_bindgen]
+#[derive(Clone, Copy)]
+pub struct Edge {
+    pub edge_id: u32,
+    pub to_node: u32,
+    pub kind: u8,
+}
+
+/// An in-memory Valori kernel.
+#[wasm_bindgen]
+pub struct ValoriKernel {
+    inner: Kernel,
+}
+
+#[wasm_bindgen]
+impl ValoriKernel {
+    /// Empty kernel for `dim`-element vectors (`0` = lock to the first insert).
+    #[wasm_bindgen(constructor)]
+    pub fn new(dim: usize) -> ValoriKernel {
+        ValoriKernel {
+            inner: Kernel::new(dim),
+        }
+    }
+
+    /// Load a snapshot from `snapshot()` or a node's
+    /// `GET /v1/snapshot/download`.
+    pub fn restore(bytes: &[u8]) -> Result<ValoriKernel, JsError> {
+        Ok(ValoriKernel {
+            inner: Kernel::restore(bytes).map_err(js_err)?,
+        })
+    }
+
+    /// Locked vector dimension, or `undefined` before the first insert.
+    #[wasm_bindgen(getter)]
+    pub fn dim(&self) -> Option<usize> {
+        self.inner.dim()
+    }
+
+    #[wasm_bindgen(getter, js_name = recordCount)]
+    pub fn record_count(&self) -> usize {
+        self.inner.record_count()
+    }
+
+    #[wasm_bindgen(getter, js_name = nodeCount)]
+    pub fn node_count(&self) -> usize {
+        self.inner.node_count()
+    }
+
+    #[wasm_bindgen(getter, js_name = edgeCount)]
+    pub fn edge_count(&self) -> usize {
+        self.inner.edge_count()
+    }
+
+    /// Insert a vector and return its record id. `tag` is the optional
+    /// filter tag (a `BigInt`).
+    pub fn insert(&mut self, values: &[f32], tag: Option<u64>) -> Result<u32, JsError> {
+        self.inner.insert(values, tag.unwrap_or(0)).map_err(js_err)
+    }
+
+    pub fn delete(&mut self, id: u32) -> Result<(), JsError> {
+        self.inner.delete(id).map_err(js_err)
+    }
+
+    /// Top-`k` nearest records, nearest first.
+    pub fn search(&self, query: &[f32], k: usize) -> Result<Vec<SearchHit>, JsError> {
+        Ok(self
+            .inner
+            .search(query, k)
+            .map_err(js_err)?
+            .into_iter()
+            .map(|(id, score)| SearchHit { id, score })
+            .collect())
+    }
+
+    /// Create a graph node of `kind` (`NodeKind` as a number), optionally
+    /// linked to a record.
+    #[wasm_bindgen(js_name = createNode)]
+    pub fn create_node(&mut self, kind: u8, record_id: Option<u32>) -> Result<u32, JsError> {
+        self.inner.create_node(kind, record_id).map_err(js_err)
+    }
+
+    /// Create an edge of `kind` (`EdgeKind` as a number).
+    #[wasm_bindgen(js_name = createEdge)]
+    pub fn create_edge(&mut self, from: u32, to: u32, kind: u8) -> Result<u32, JsError> {
+        self.inner.create_edge(from, to, kind).map_err(js_err)
+    }
+
+    /// Record linked to `node`, or `undefined` for a bare node.
+    #[wasm_bindgen(js_name = nodeRecord)]
+    pub fn node_record(&self, node: u32) -> Result<Option<u32>, JsError> {
+        self.inner.node_record(node).map_err(js_err)
+    }
+
+    #[wasm_bindgen(js_name = outgoingEdges)]
+    pub fn outgoing_edges(&self, node: u32) -> Vec<Edge> {
+        self.inner
+            .outgoing_edges(node)
+            .into_iter()
+            .map(|(edge_id, to_node, kind)| Edge {
+                edge_id,
+                to_node,
+                kind,
+            })
+            .collect()
+    }
+
+    /// BLAKE3 state hash as lowercase hex — compare with a node's
+    /// `/v1/proof/state`.
+    #[wasm_bindgen(js_name = stateHash)]
+    pub fn state_hash(&self) -> String {
+        self.inner
+            .state_hash()
+            .iter()
+            .map(|b| format!("{b:02x}"))
+            .collect()
+    }
+
+    /// Encode the kernel state; load it back with `ValoriKernel.restore`.
+    pub fn snapshot(&self) -> Result<Vec<u8>, JsError> {
+        self.inner.snapshot().map_err(js_err)
+    }
+}
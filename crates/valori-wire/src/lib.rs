@@ -38,6 +38,30 @@
 //! segment restarted its chain from zeros and archived history could be
 //! removed undetected.
 //!
+//! ## Log hash
+//!
+//! ```text
+//! log_hash[i] = BLAKE3(log_hash[i-1] || entry bytes as written)
+//! ```
+//!
+//! A running hash of the raw entry bytes of every segment in sequence
+//! order, from `[0u8; 32]` at genesis. Writers persist it in
+//! `LogEntry::CheckpointV2`, so a segment that opens with one resumes it
+//! without its archives.
+//!
+//! ## Batch commit records
+//!
+//! ```text
+//! [BatchCommit{first_height, count, batch_hash}][entry 1]...[entry count]
+//! batch_hash = BLAKE3(bincode(entry 1) || ... || bincode(entry count))
+//! ```
+//!
+//! A writer that persists several entries in one write puts a
+//! `LogEntry::BatchCommit` in front of them. A crash mid-write can leave
+//! any prefix of that write on disk; a reader that finds fewer than `count`
+//! entries after the record drops the record and whatever follows it, so a
+//! batch is recovered whole or not at all. Single appends carry no record.
+//!
 //! ## Evolution policy (enforced by fixture tests)
 //!
 //! 1. **Enum variants are append-only.** bincode encodes variants by index;
@@ -56,18 +80,15 @@
 use serde::{Deserialize, Serialize};
 use valori_kernel::event::KernelEvent;
 
-pub const VERSION_V2: u32 = 2;
-pub const VERSION_V3: u32 = 3;
-/// V4 adds a 4-byte CRC32 suffix to every entry for cheap inline corruption detection.
-/// Format: `[bincode(EntryV4)][u32 LE CRC32 of the bincode bytes]`
-/// The chain hash, header layout, and EntryV4 fields are identical to V3.
-pub const VERSION_V4: u32 = 4;
-pub const HEADER_SIZE_V2: usize = 16;
-pub const HEADER_SIZE_V3: usize = 48;
-/// V4 reuses the V3 header layout.
-pub const HEADER_SIZE_V4: usize = HEADER_SIZE_V3;
-/// Byte length of the per-entry CRC32 suffix in V4 segments.
-pub const CRC32_SUFFIX_LEN: usize = 4;
+// Versions and header sizes are defined in `valori-protocol` so the node,
+// the verifier, the CLI and the firmware cannot disagree on them.
+// V4 adds a 4-byte CRC32 suffix to every entry for cheap inline corruption
+// detection: `[bincode(EntryV4)][u32 LE CRC32 of the bincode bytes]`. The
+// chain hash, header layout, and EntryV4 fields are identical to V3.
+pub use valori_protocol::formats::event_log::{
+    CRC32_SUFFIX_LEN, HEADER_SIZE_V2, HEADER_SIZE_V3, HEADER_SIZE_V4, VERSION_V2, VERSION_V3,
+    VERSION_V4,
+};
 
 // ── Phase 1.7 hardening constants (reserved; enforced in Phase 1.7) ──────────
 
@@ -89,7 +110,7 @@ pub const MAX_ENTRIES_PER_SEGMENT: u64 = 10_000_000;
 /// produces a more specific error than the overall allocation limit.
 /// Enforced by `encode_entry` on every metadata-bearing event variant —
 /// write-side only, so pre-cap logs remain readable.
-pub const METADATA_CAP: usize = 65_536; // 64 KiB
+pub const METADATA_CAP: usize = valori_protocol::MAX_METADATA_SIZE;
 
 /// Maximum decompressed size for a zstd-compressed segment file.
 /// Protects the verifier and the node from zstd "bombs".
@@ -104,15 +125,15 @@ pub const MAX_SEGMENT_DECOMPRESSED_BYTES: usize = 512 * 1024 * 1024; // 512 MiB
 /// Arithmetic format identifiers (hash-domain relevant — a Q8.8 log must
 /// never verify as a Q16.16 log). Only Q16.16 is implemented today; the id
 /// exists so Phase 1.3's `FxpFormat` work needs no further format bump.
-pub const FORMAT_Q16_16: u8 = 1;
+pub use valori_protocol::fxp::FORMAT_Q16_16;
 
 /// usize-typed decode limit for `bincode::config::with_limit` (const generic requires usize).
 /// Equals `MAX_ENTRY_DECODE_BYTES` — kept separate to avoid a u64→usize cast at const position.
 const DECODE_LIMIT: usize = 1 << 20; // 1 MiB
 
-/// Maximum dimension for a segment header.
-/// No real embedding is zero-dimensional or wider than 32 768 scalars.
-pub const MAX_DIM: u32 = 32_768;
+/// Maximum dimension for a segment header — the kernel's own ceiling, so
+/// every dimension the kernel accepts can be logged.
+pub const MAX_DIM: u32 = valori_protocol::MAX_DIM as u32;
 
 #[derive(Debug, thiserror::Error)]
 pub enum WireError {
@@ -174,6 +195,40 @@ pub enum LogEntry {
         namespace_id: u16,
         event: KernelEvent,
     },
+    /// `Checkpoint` plus the running log hash (see [`log_hash_advance`]) of
+    /// every entry written before this one, in this segment and all earlier
+    /// ones. Added as append-only variant 4 so a rotated segment can resume
+    /// the log hash without re-reading its archives. Writers stamp their own
+    /// log hash into every checkpoint they write, whichever shape it came
+    /// in as, and write a plain `Checkpoint` when they do not know it.
+    CheckpointV2 {
+        event_count: u64,
+        snapshot_hash: [u8; 32],
+        timestamp: u64,
+        log_hash: [u8; 32],
+    },
+    /// Commit record of a multi-entry batch, written directly ahead of its
+    /// `count` entries in the same write (append-only variant 5). The batch
+    /// counts as committed only when all `count` entries follow intact and
+    /// [`batch_hash`] over them equals `batch_hash`; see
+    /// [`incomplete_batch`]. `first_height` is the writer's event count
+    /// before the batch.
+    BatchCommit {
+        first_height: u64,
+        count: u32,
+        batch_hash: [u8; 32],
+    },
+}
+
+impl LogEntry {
+    /// Height recorded by a checkpoint of either shape.
+    pub fn checkpoint_height(&self) -> Option<u64> {
+        match self {
+            LogEntry::Checkpoint { event_count, .. }
+            | LogEntry::CheckpointV2 { event_count, .. } => Some(*event_count),
+            _ => None,
+        }
+    }
 }
 
 /// Administrative actions worth auditing forever.
@@ -198,6 +253,19 @@ pub enum AdminEvent {
         node_id: u64,
         authorized_by: [u8; 16],
     },
+    /// A forensic label on the timeline ("incident start", "model v2
+    /// deployed") at committed height `height`, i.e. after that many data
+    /// events. Node-level only: it never touches kernel state.
+    Annotate {
+        height: u64,
+        label: String,
+        authorized_by: [u8; 16],
+    },
+    /// Leader epoch `epoch` began at committed height `start_height`:
+    /// written by a node when it starts as leader, and by a follower when it
+    /// learns a newer epoch from its leader. Split-brain detection compares
+    /// epochs in the replication handshake.
+    LeaderEpoch { epoch: u64, start_height: u64 },
 }
 
 impl AdminEvent {
@@ -211,6 +279,13 @@ impl AdminEvent {
             AdminEvent::NodeLeft { node_id, .. } => {
                 format!("NodeLeft {{ node {node_id} }}")
             }
+            AdminEvent::Annotate { height, label, .. } => {
+                format!("Annotate {{ height {height}: {label:?} }}")
+            }
+            AdminEvent::LeaderEpoch {
+                epoch,
+                start_height,
+            } => format!("LeaderEpoch {{ epoch {epoch} from height {start_height} }}"),
         }
     }
 }
@@ -272,9 +347,13 @@ pub fn parse_header(bytes: &[u8]) -> Result<SegmentHeader> {
     let version = u32::from_le_bytes(bytes[0..4].try_into().unwrap());
     let dim = u32::from_le_bytes(bytes[4..8].try_into().unwrap());
 
-    if !(1..=MAX_DIM).contains(&dim) {
+    // The open-time compatibility check every reader shares.
+    if valori_protocol::check_dim(dim).is_err() {
         return Err(WireError::InvalidDim(dim));
     }
+    if valori_protocol::check_version(valori_protocol::Format::EventLog, version).is_err() {
+        return Err(WireError::UnsupportedVersion(version));
+    }
 
     match version {
         VERSION_V2 => Ok(SegmentHeader {
@@ -535,6 +614,82 @@ pub fn chain_advance_v3(
     *hasher.finalize().as_bytes()
 }
 
+/// Advance the running log hash by one entry:
+/// `BLAKE3(log_hash || entry bytes as written, CRC suffix included)`.
+///
+/// Unlike the chain head, which commits to decoded entries, the log hash
+/// commits to the exact bytes on disk, continuing across rotations from
+/// `[0u8; 32]` at genesis. Segment headers are not covered; the chain
+/// splice already binds them.
+pub fn log_hash_advance(log_hash: &[u8; 32], entry_bytes: &[u8]) -> [u8; 32] {
+    let mut hasher = blake3::Hasher::new();
+    hasher.update(log_hash);
+    hasher.update(entry_bytes);
+    *hasher.finalize().as_bytes()
+}
+
+/// Hash a batch commit record carries:
+/// `BLAKE3(bincode(entry 1) || ... || bincode(entry n))` over the entries
+/// as written.
+pub fn batch_hash<'a>(entries: impl IntoIterator<Item = &'a LogEntry>) -> [u8; 32] {
+    let mut hasher = blake3::Hasher::new();
+    for entry in entries {
+        let bytes =
+            bincode::serde::encode_to_vec(entry, cfg()).expect("LogEntry is always serialisable");
+        hasher.update(&bytes);
+    }
+    *hasher.finalize().as_bytes()
+}
+
+/// Index of the `BatchCommit` record opening a batch that did not make it
+/// to disk whole, in a segment's decoded `entries`: the record is the last
+/// one and fewer than `count` entries follow it. Entries from that index
+/// on were never committed. A batch whose entries are all present but
+/// hash differently is corruption, not a torn write, and is reported as
+/// `Err(index)`.
+pub fn incomplete_batch(entries: &[&LogEntry]) -> core::result::Result<Option<usize>, usize> {
+    let Some((at, count, want)) = entries.iter().enumerate().rev().find_map(|(i, e)| match e {
+        LogEntry::BatchCommit {
+            count, batch_hash, ..
+        } => Some((i, *count as usize, *batch_hash)),
+        _ => None,
+    }) else {
+        return Ok(None);
+    };
+    let body = &entries[at + 1..];
+    if body.len() < count {
+        return Ok(Some(at));
+    }
+    if batch_hash(body[..count].iter().copied()) != want {
+        return Err(at);
+    }
+    Ok(None)
+}
+
+/// Length of the prefix of `body` (a segment's bytes after its header)
+/// that holds committed entries. It ends before a torn trailing entry, and
+/// before the record of a batch that did not reach disk whole (see
+/// [`incomplete_batch`]). Decoding stops at the first entry that fails;
+/// chain links are not checked.
+pub fn committed_len(version: u32, body: &[u8]) -> usize {
+    let mut entries = Vec::new();
+    let mut offset = 0;
+    while offset < body.len() {
+        match decode_entry(version, &body[offset..]) {
+            Ok((decoded, n)) => {
+                entries.push((offset, decoded.entry));
+                offset += n;
+            }
+            Err(_) => break,
+        }
+    }
+    let logged: Vec<&LogEntry> = entries.iter().map(|(_, e)| e).collect();
+    match incomplete_batch(&logged) {
+        Ok(Some(at)) => entries[at].0,
+        _ => offset,
+    }
+}
+
 /// Version-dispatching chain advance over a decoded entry.
 pub fn chain_advance(version: u32, head: &[u8; 32], e: &DecodedEntry) -> Result<[u8; 32]> {
     match version {
@@ -82,6 +82,12 @@ fn walk(bytes: &[u8]) -> (u64, u64, [u8; 32]) {
             // Variant added in Phase S15 — likewise absent from the
             // pre-S15 fixtures; counted as a data event when present.
             LogEntry::EventNs { .. } => events += 1,
+            // Variant added with the running log hash — likewise absent
+            // from the older fixtures.
+            LogEntry::CheckpointV2 { .. } => checkpoints += 1,
+            // Variant added with batch commit records — likewise absent
+            // from the older fixtures.
+            LogEntry::BatchCommit { .. } => {}
         }
         offset += n;
     }
@@ -220,8 +226,17 @@ fn admin_events_chain_and_roundtrip() {
         node_id: 2,
         authorized_by: [7u8; 16],
     });
+    let note = LogEntry::Admin(AdminEvent::Annotate {
+        height: 41,
+        label: "incident start".into(),
+        authorized_by: [0u8; 16],
+    });
+    let epoch = LogEntry::Admin(AdminEvent::LeaderEpoch {
+        epoch: 3,
+        start_height: 41,
+    });
 
-    for entry in [&joined, &left] {
+    for entry in [&joined, &left, &note, &epoch] {
         let enc = encode_entry(VERSION_V3, &head, 1_700_000_000, None, entry).unwrap();
         let (decoded, n) = decode_entry(VERSION_V3, &enc).unwrap();
         assert_eq!(n, enc.len());
@@ -246,4 +261,6 @@ fn admin_events_chain_and_roundtrip() {
     assert_eq!(walk_head, head);
     assert!(kinds[0].contains("NodeJoined") && kinds[0].contains("node 2"));
     assert!(kinds[1].contains("NodeLeft"));
+    assert_eq!(kinds[2], "Annotate { height 41: \"incident start\" }");
+    assert_eq!(kinds[3], "LeaderEpoch { epoch 3 from height 41 }");
 }
@@ -14,7 +14,7 @@ use valori_wire::{
 #[test]
 fn hardening_constants_are_sensible() {
     assert_eq!(MAX_ENTRY_DECODE_BYTES, 1 << 20, "1 MiB per entry");
-    assert_eq!(MAX_DIM, 32_768);
+    assert_eq!(MAX_DIM as usize, valori_kernel::config::MAX_DIM);
     assert!(MAX_ENTRIES_PER_SEGMENT >= 1_000_000);
     assert!(METADATA_CAP >= 1024);
 }
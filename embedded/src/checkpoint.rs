@@ -50,5 +50,6 @@ impl WalCheckpoint {
             let ptr = core::ptr::addr_of_mut!(CHECKPOINT_FLASH) as *mut WalCheckpoint;
             core::ptr::write_volatile(ptr, *self);
         }
+        crate::diag::flash_write();
     }
 }
@@ -0,0 +1,100 @@
+// Field diagnostics — counters a host can read over UART without a debugger.
+//
+// Request:  TYPE_DIAG        (empty payload)
+// Reply:    TYPE_DIAG_RESULT
+//   [VERSION:1 = 1]
+//   [EVENTS_APPLIED:4 LE][COMMITS:4 LE][FLASH_WRITES:4 LE][NACKS:4 LE]
+//   [HEAP_USED:4 LE][HEAP_HIGH_WATER:4 LE][HEAP_SIZE:4 LE]
+//
+// Counters are u32 and wrap. They count since boot — nothing is persisted.
+// Updates are a load + store rather than fetch_add: thumbv6m (RP2040) has no
+// atomic read-modify-write, and the firmware is a single-threaded loop.
+// HEAP_HIGH_WATER is kept by `TrackingHeap`, the global allocator, on every
+// allocation, so it sees peaks inside a packet as well as between them.
+
+use core::alloc::{GlobalAlloc, Layout};
+use core::sync::atomic::{AtomicU32, Ordering};
+
+use embedded_alloc::Heap;
+
+use crate::transport;
+
+const DIAG_VERSION: u8 = 1;
+pub const DIAG_LEN: usize = 1 + 7 * 4;
+
+static EVENTS_APPLIED: AtomicU32 = AtomicU32::new(0);
+static COMMITS: AtomicU32 = AtomicU32::new(0);
+static FLASH_WRITES: AtomicU32 = AtomicU32::new(0);
+static NACKS: AtomicU32 = AtomicU32::new(0);
+static HEAP_HIGH_WATER: AtomicU32 = AtomicU32::new(0);
+
+#[inline]
+fn bump(counter: &AtomicU32) {
+    counter.store(counter.load(Ordering::Relaxed).wrapping_add(1), Ordering::Relaxed);
+}
+
+pub fn event_applied() { bump(&EVENTS_APPLIED); }
+pub fn commit()        { bump(&COMMITS); }
+pub fn flash_write()   { bump(&FLASH_WRITES); }
+pub fn nack()          { bump(&NACKS); }
+
+/// `embedded_alloc::Heap` that records the most bytes ever in use.
+pub struct TrackingHeap {
+    heap: Heap,
+}
+
+impl TrackingHeap {
+    pub const fn empty() -> Self {
+        Self { heap: Heap::empty() }
+    }
+
+    /// See `Heap::init`: call once, before the first allocation.
+    pub unsafe fn init(&self, start_addr: usize, size: usize) {
+        self.heap.init(start_addr, size)
+    }
+
+    pub fn used(&self) -> usize {
+        self.heap.used()
+    }
+}
+
+unsafe impl GlobalAlloc for TrackingHeap {
+    unsafe fn alloc(&self, layout: Layout) -> *mut u8 {
+        let ptr = self.heap.alloc(layout);
+        if !ptr.is_null() {
+            let used = self.heap.used() as u32;
+            if used > HEAP_HIGH_WATER.load(Ordering::Relaxed) {
+                HEAP_HIGH_WATER.store(used, Ordering::Relaxed);
+            }
+        }
+        ptr
+    }
+
+    unsafe fn dealloc(&self, ptr: *mut u8, layout: Layout) {
+        self.heap.dealloc(ptr, layout)
+    }
+}
+
+/// Encode the current counters in the `TYPE_DIAG_RESULT` layout.
+pub fn encode(heap_used: usize, heap_size: usize) -> [u8; DIAG_LEN] {
+    let words = [
+        EVENTS_APPLIED.load(Ordering::Relaxed),
+        COMMITS.load(Ordering::Relaxed),
+        FLASH_WRITES.load(Ordering::Relaxed),
+        NACKS.load(Ordering::Relaxed),
+        heap_used as u32,
+        HEAP_HIGH_WATER.load(Ordering::Relaxed),
+        heap_size as u32,
+    ];
+    let mut out = [0u8; DIAG_LEN];
+    out[0] = DIAG_VERSION;
+    for (i, w) in words.iter().enumerate() {
+        out[1 + i * 4..5 + i * 4].copy_from_slice(&w.to_le_bytes());
+    }
+    out
+}
+
+/// Answer a `TYPE_DIAG` request.
+pub fn handle(heap_used: usize, heap_size: usize) {
+    transport::export_diag_result(&encode(heap_used, heap_size));
+}
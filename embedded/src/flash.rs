@@ -50,6 +50,7 @@ impl FlashStorage {
                 (*ptr)[i] = byte;
             }
         }
+        crate::diag::flash_write();
         Ok(())
     }
 
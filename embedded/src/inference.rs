@@ -214,7 +214,7 @@ fn run_inference(
 /// by 65536, the SCALE constant in valori-kernel's fxp module).  This
 /// preserves relative distances for L2 search inside KernelState.
 fn logits_to_fxp(logits: &matmul_engine::matrix::Matrix<f32, 1, VOCAB>) -> FxpVector {
-    const Q16_SCALE: f32 = 65536.0; // 2^16 — matches FxpScalar's Q16.16 encoding
+    const Q16_SCALE: f32 = valori_protocol::fxp::SCALE_F32;
 
     let mut absmax = 1e-9_f32;
     for v in 0..VOCAB {
@@ -8,9 +8,10 @@
 //
 // Modes:
 //   SelfTest  — inserts one hardcoded vector, snapshots, emits proof over UART.
-//   WalReplay — continuous UART receive loop: ingest WAL packets → shadow-apply
-//               → on EOS: commit checkpoint + emit proof → ready for next packet.
-//               Also handles interleaved TYPE_SEARCH packets at any time.
+//   WalReplay — continuous UART receive loop: ingest a stream of WAL packets
+//               → shadow-apply → on each EOS: commit checkpoint + emit proof
+//               → next segment, no reboot. A sequence gap is NACKed with the
+//               expected seq. Also handles interleaved TYPE_SEARCH packets.
 
 extern crate alloc;
 
@@ -24,11 +25,11 @@ mod wal_stream;
 mod shadow;
 mod recovery;
 mod search;
+mod diag;
 #[cfg(feature = "int")]
 mod inference;
 
 use cortex_m_rt::entry;
-use embedded_alloc::Heap;
 use panic_halt as _;
 
 use valori_kernel::state::kernel::KernelState;
@@ -46,13 +47,14 @@ pub(crate) const DIM: usize = 128;
 const RX_PACKET_BUF: usize = 4096;
 
 #[global_allocator]
-static HEAP: Heap = Heap::empty();
+static HEAP: diag::TrackingHeap = diag::TrackingHeap::empty();
 
 // 192 KB heap — required for QGPTModel<61,64,64,256,4,3> (~172 KB) + KernelState.
 // STM32F407 has 192 KB SRAM; RP2040 / nRF52840 have 256 KB and are also fine.
 // If you need more headroom for KernelState records, shrink LAYERS or DIM in
 // inference.rs (the 2-layer DIM-32 model needs only ~56 KB).
-static mut HEAP_MEM: [u32; 49152] = [0; 49152]; // 49152 × 4 = 196 608 bytes
+const HEAP_SIZE: usize = 196_608;
+static mut HEAP_MEM: [u32; HEAP_SIZE / 4] = [0; HEAP_SIZE / 4];
 
 // Static receive buffer in .bss — not on the heap — keeps heap free for kernel data.
 static mut PKT_BUF: [u8; RX_PACKET_BUF] = [0u8; RX_PACKET_BUF];
@@ -69,7 +71,7 @@ const MODE: BootMode = BootMode::WalReplay;
 fn main() -> ! {
     unsafe {
         let ptr = core::ptr::addr_of_mut!(HEAP_MEM);
-        HEAP.init(ptr as usize, 196_608); // 192 KB
+        HEAP.init(ptr as usize, HEAP_SIZE); // 192 KB
     }
 
     // Load the baked INT model from flash into the heap.
@@ -142,6 +144,8 @@ fn run_wal_replay(state: &mut KernelState) -> ! {
     };
 
     let mut stream = wal_stream::WalStream::new(last_seq);
+    let mut segment = shadow::ShadowKernel::new();
+    segment.start_segment();
     let mut rx = transport::RxBuf::new();
 
     loop {
@@ -166,21 +170,30 @@ fn run_wal_replay(state: &mut KernelState) -> ! {
             // ── WAL packet ────────────────────────────────────────────────
             transport::PacketKind::Wal => {
                 match stream.ingest_packet(pkt) {
-                    Err(_) => {
-                        transport::export_error(b"SEQ_ERR");
-                        cortex_m::asm::bkpt();
-                    }
-                    Ok((payload, is_eos)) => {
-                        let mut shadow = shadow::ShadowKernel::new(state);
-                        shadow.start_segment();
-
-                        if shadow.apply_chunk(payload).is_err() {
+                    Ok(wal_stream::Ingest::Chunk { payload, eos }) => {
+                        if segment.apply_chunk(state, payload).is_err() {
                             transport::export_error(b"SHADOW_FAIL");
                             cortex_m::asm::bkpt();
                         }
 
-                        if is_eos {
-                            commit_and_emit_proof(shadow.state, &mut stream);
+                        if eos {
+                            // An EOS in the middle of an event means the host
+                            // framed the segment wrongly; nothing can commit.
+                            if !segment.is_complete() {
+                                transport::export_error(b"EOS_PARTIAL");
+                                cortex_m::asm::bkpt();
+                            }
+                            commit_and_emit_proof(state, &mut stream);
+                            segment.start_segment();
+                        }
+                    }
+                    Ok(wal_stream::Ingest::Duplicate) => {}
+                    // A lost or malformed packet: drop it and ask the host to
+                    // resend from the first packet not yet applied.
+                    Ok(wal_stream::Ingest::Gap) | Err(_) => {
+                        if let Some(seq) = stream.nack() {
+                            diag::nack();
+                            transport::export_nack(seq);
                         }
                     }
                 }
@@ -188,7 +201,8 @@ fn run_wal_replay(state: &mut KernelState) -> ! {
 
             // ── Search packet ─────────────────────────────────────────────
             // The host can send a search request at any time — even between
-            // WAL segments — and the device answers against committed state.
+            // WAL packets of an open segment, in which case the device
+            // answers against the events applied so far.
             transport::PacketKind::Search => {
                 search::handle(state, pkt);
             }
@@ -205,6 +219,13 @@ fn run_wal_replay(state: &mut KernelState) -> ! {
                 transport::export_error(b"INT_NOT_ENABLED");
             }
 
+            // ── Diagnostics packet ────────────────────────────────────────
+            // Health check for field devices: counters since boot plus heap
+            // use, no debugger needed.
+            transport::PacketKind::Diag => {
+                diag::handle(HEAP.used(), HEAP_SIZE);
+            }
+
             transport::PacketKind::Unknown => {
                 // Discard silently — forward compatibility.
             }
@@ -228,6 +249,7 @@ fn commit_and_emit_proof(state: &mut KernelState, stream: &mut wal_stream::WalSt
     cp.last_committed_wal_index = stream.next_expected_seq;
     cp.snapshot_hash = valori_kernel::verify::snapshot_hash(snap_data);
     cp.save();
+    diag::commit();
 
     let proof = proof::generate_proof(state, snap_data);
     let mut proof_buf = [0u8; 1024];
@@ -5,18 +5,19 @@ use blake3::Hasher;
 use valori_kernel::state::kernel::KernelState;
 use crate::wal;
 
-pub struct ShadowKernel<'a> {
-    pub state: &'a mut KernelState,
+/// One WAL segment in flight. Lives across packets until the EOS packet
+/// commits it; the kernel state is passed to each call rather than held, so
+/// search packets can read it between WAL packets.
+pub struct ShadowKernel {
     pub wal_accumulator: Hasher,
     pub segment_active: bool,
     pub buffer: Vec<u8>,
     pub header_processed: bool,
 }
 
-impl<'a> ShadowKernel<'a> {
-    pub fn new(state: &'a mut KernelState) -> Self {
+impl ShadowKernel {
+    pub fn new() -> Self {
         Self {
-            state,
             wal_accumulator: Hasher::new(),
             segment_active: false,
             buffer: Vec::new(),
@@ -34,7 +35,9 @@ impl<'a> ShadowKernel<'a> {
     /// Buffer an incoming WAL chunk and apply all complete events it contains.
     /// Updates the BLAKE3 accumulator for every applied event so the proof
     /// commits to the exact byte sequence that was applied.
-    pub fn apply_chunk(&mut self, chunk: &[u8]) -> Result<(), ()> {
+    /// A segment may span any number of chunks: an event split across two
+    /// packets stays buffered until the rest of it arrives.
+    pub fn apply_chunk(&mut self, state: &mut KernelState, chunk: &[u8]) -> Result<(), ()> {
         if !self.segment_active { return Err(()); }
 
         self.buffer.extend_from_slice(chunk);
@@ -45,11 +48,16 @@ impl<'a> ShadowKernel<'a> {
                     return Ok(());
                 }
 
-                let header = match wal::WalHeader::from_bytes(&self.buffer) {
+                let header = match wal::WalHeader::decode(&self.buffer) {
                     Some(h) => h,
                     None => return Err(()),
                 };
 
+                // Refuse a WAL written by a host this firmware cannot read.
+                if valori_protocol::check_version(valori_protocol::Format::Wal, header.version).is_err() {
+                    return Err(());
+                }
+
                 // Dimension must match this firmware's compiled-in DIM.
                 if header.dim != crate::DIM as u32 {
                     return Err(());
@@ -64,8 +72,9 @@ impl<'a> ShadowKernel<'a> {
 
             if self.buffer.is_empty() { break; }
 
-            match wal::try_apply_event(self.state, &self.buffer) {
+            match wal::try_apply_event(state, &self.buffer) {
                 wal::ApplyResult::Applied(n) => {
+                    crate::diag::event_applied();
                     self.wal_accumulator.update(&self.buffer[0..n]);
                     let _ = self.buffer.drain(0..n);
                 }
@@ -77,6 +86,12 @@ impl<'a> ShadowKernel<'a> {
         Ok(())
     }
 
+    /// True when every byte received so far has been applied — the only
+    /// state in which an EOS packet may commit.
+    pub fn is_complete(&self) -> bool {
+        self.header_processed && self.buffer.is_empty()
+    }
+
     #[allow(dead_code)]
     pub fn get_accumulator_hash(&self) -> [u8; 32] {
         *self.wal_accumulator.finalize().as_bytes()
@@ -7,6 +7,9 @@ pub const TYPE_SNAPSHOT:      u8 = 0x02;
 pub const TYPE_SEARCH_RESULT: u8 = 0x05;
 pub const TYPE_INFER:         u8 = 0x06; // prompt tokens → run INT inference
 pub const TYPE_INFER_RESULT:  u8 = 0x07; // output tokens + BLAKE3 receipt + Valori proof
+pub const TYPE_NACK:          u8 = 0x08; // [EXPECTED_SEQ:8 LE] — resend WAL packets from here
+pub const TYPE_DIAG:          u8 = 0x09; // host asks for the diagnostic counters
+pub const TYPE_DIAG_RESULT:   u8 = 0x0A; // counters, layout in diag.rs
 pub const TYPE_ERR:           u8 = 0xEE;
 
 // ── TX register ──────────────────────────────────────────────────────────────
@@ -44,6 +47,8 @@ pub fn export_snapshot(data: &[u8]) {
 pub fn export_error(code: &[u8])          { send_framed(TYPE_ERR, code); }
 pub fn export_search_result(data: &[u8]) { send_framed(TYPE_SEARCH_RESULT, data); }
 pub fn export_infer_result(data: &[u8])  { send_framed(TYPE_INFER_RESULT, data); }
+pub fn export_nack(expected_seq: u64)    { send_framed(TYPE_NACK, &expected_seq.to_le_bytes()); }
+pub fn export_diag_result(data: &[u8])   { send_framed(TYPE_DIAG_RESULT, data); }
 
 // ── RX ring buffer ───────────────────────────────────────────────────────────
 
@@ -102,6 +107,7 @@ pub enum PacketKind {
     Wal,
     Search,
     Infer,   // TYPE_INFER: run INT inference + store receipt in Valori
+    Diag,    // TYPE_DIAG: report the diagnostic counters
     Unknown,
 }
 
@@ -145,6 +151,7 @@ pub fn recv_packet(rx: &mut RxBuf, out: &mut [u8]) -> Result<ReceivedPacket, Rec
         TYPE_WAL    => PacketKind::Wal,
         TYPE_SEARCH => PacketKind::Search,
         TYPE_INFER  => PacketKind::Infer,
+        TYPE_DIAG   => PacketKind::Diag,
         _           => PacketKind::Unknown,
     };
 
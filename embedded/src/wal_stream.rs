@@ -8,34 +8,64 @@ const WAL_STREAM_VERSION: u8 = 1;
 
 pub const FLAG_EOS: u8 = 0x01;
 
+/// Out-of-order packets dropped after a NACK before it is sent again, in
+/// case the NACK itself was lost. `recv_packet` blocks without a clock, so
+/// the retry budget is counted in packets rather than time.
+pub const NACK_RETRY_PACKETS: u32 = 8;
+
+/// What to do with one WAL packet.
+pub enum Ingest<'a> {
+    /// The packet the stream expected: apply `payload`. `eos` closes the
+    /// segment — commit after applying it.
+    Chunk { payload: &'a [u8], eos: bool },
+    /// Already applied — the host is retransmitting after a NACK. Drop it.
+    Duplicate,
+    /// One or more packets before this one were lost. Drop it and NACK.
+    Gap,
+}
+
+/// Sequence tracking for a continuous stream of WAL packets.
+///
+/// Packets are applied strictly in sequence. A packet ahead of the expected
+/// sequence is dropped and the host is asked to resend from
+/// `next_expected_seq`; packets up to that retransmission are dropped
+/// without another NACK until [`NACK_RETRY_PACKETS`] of them have gone by,
+/// then the NACK is repeated. Packets behind it are duplicates and are
+/// ignored.
 pub struct WalStream {
     pub next_expected_seq: u64,
+    /// Sequence already NACKed, so a burst of out-of-order packets costs one NACK.
+    nacked: Option<u64>,
+    /// Out-of-order packets dropped since `nacked` was sent.
+    dropped_since_nack: u32,
 }
 
 impl WalStream {
     pub fn new(start_seq: u64) -> Self {
         Self {
             next_expected_seq: start_seq,
+            nacked: None,
+            dropped_since_nack: 0,
         }
     }
 
-    /// Parse and validate a WAL Chunk Packet.
-    /// Returns (Payload, is_eos).
-    /// Errors if gap, replay, or version mismatch.
-    pub fn ingest_packet<'a>(&mut self, packet: &'a [u8]) -> Result<(&'a [u8], bool)> {
+    /// Parse and validate a WAL Chunk Packet, and place it in the sequence.
+    /// Errors only if the packet itself is malformed (truncated or version
+    /// mismatch) — the caller treats that as a lost packet.
+    pub fn ingest_packet<'a>(&mut self, packet: &'a [u8]) -> Result<Ingest<'a>> {
         if packet.len() < 14 { // 1+1+8+4 = 14 bytes header
             return Err(KernelError::InvalidOperation); // Truncated header
         }
 
         let mut offset = 0;
-        
+
         let version = packet[offset]; offset += 1;
         if version != WAL_STREAM_VERSION {
             return Err(KernelError::InvalidOperation); // Version mismatch
         }
 
         let flags = packet[offset]; offset += 1;
-        
+
         // Read seq (u64 LE)
         let seq_bytes: [u8; 8] = packet[offset..offset+8].try_into().unwrap();
         let seq = u64::from_le_bytes(seq_bytes);
@@ -46,22 +76,40 @@ impl WalStream {
         let len = u32::from_le_bytes(len_bytes);
         offset += 4;
 
-        if seq != self.next_expected_seq {
-            // Replay or Gap
-            return Err(KernelError::InvalidOperation); 
-        }
-
         if packet.len() < offset + (len as usize) {
             return Err(KernelError::InvalidOperation); // Truncated payload
         }
-        
+
+        if seq < self.next_expected_seq {
+            return Ok(Ingest::Duplicate);
+        }
+        if seq > self.next_expected_seq {
+            return Ok(Ingest::Gap);
+        }
+
         let payload = &packet[offset..offset + (len as usize)];
-        
-        // Advance sequence
+
+        // Advance sequence; the gap, if any, is closed.
         self.next_expected_seq += 1;
+        self.nacked = None;
 
         let is_eos = (flags & FLAG_EOS) != 0;
-        
-        Ok((payload, is_eos))
+
+        Ok(Ingest::Chunk { payload, eos: is_eos })
+    }
+
+    /// Sequence to put in a NACK, or `None` if it was already requested and
+    /// the retransmission has not arrived yet. Once [`NACK_RETRY_PACKETS`]
+    /// packets have been dropped waiting for it, the NACK is sent again.
+    pub fn nack(&mut self) -> Option<u64> {
+        if self.nacked == Some(self.next_expected_seq)
+            && self.dropped_since_nack < NACK_RETRY_PACKETS
+        {
+            self.dropped_since_nack += 1;
+            return None;
+        }
+        self.nacked = Some(self.next_expected_seq);
+        self.dropped_since_nack = 0;
+        Some(self.next_expected_seq)
     }
 }
@@ -0,0 +1,253 @@
+//! Host tests for the firmware's WAL stream sequencing (host target, std).
+//!
+//! The firmware is a binary, so its WAL modules are compiled in here
+//! directly. Covers the `WalStream` rules the UART loop relies on:
+//!   - a packet behind the expected sequence is a duplicate and is ignored
+//!   - a packet ahead of it is a gap, NACKed once until the retransmission
+//!   - a NACK that is lost is repeated after `NACK_RETRY_PACKETS` drops
+//!   - the retransmission resumes the stream and re-arms the NACK
+//!   - an EOS that closes a segment in the middle of an event cannot commit
+//!
+//! Run with:
+//!   cargo test -p valori-embedded --test wal_stream
+
+#[path = "../src/wal_stream.rs"]
+mod wal_stream;
+#[path = "../src/wal.rs"]
+mod wal;
+#[path = "../src/shadow.rs"]
+mod shadow;
+
+/// Stand-in for the firmware's diagnostic counters.
+mod diag {
+    pub fn event_applied() {}
+}
+
+// Mirror the embedded firmware's DIM constant.
+const DIM: usize = 128;
+
+use shadow::ShadowKernel;
+use valori_kernel::event::KernelEvent;
+use valori_kernel::state::kernel::KernelState;
+use valori_kernel::types::id::RecordId;
+use valori_kernel::types::scalar::FxpScalar;
+use valori_kernel::types::vector::FxpVector;
+use wal::WalHeader;
+use wal_stream::{Ingest, WalStream, FLAG_EOS, NACK_RETRY_PACKETS};
+
+/// One WAL stream packet: `[VER:1][FLAGS:1][SEQ:8][LEN:4]` then the payload.
+fn packet(seq: u64, eos: bool, payload: &[u8]) -> Vec<u8> {
+    let mut p = vec![1u8, if eos { FLAG_EOS } else { 0 }];
+    p.extend_from_slice(&seq.to_le_bytes());
+    p.extend_from_slice(&(payload.len() as u32).to_le_bytes());
+    p.extend_from_slice(payload);
+    p
+}
+
+fn chunk(ingest: Ingest<'_>) -> (Vec<u8>, bool) {
+    match ingest {
+        Ingest::Chunk { payload, eos } => (payload.to_vec(), eos),
+        Ingest::Duplicate => panic!("expected a chunk, got a duplicate"),
+        Ingest::Gap => panic!("expected a chunk, got a gap"),
+    }
+}
+
+fn insert_event(id: u32) -> Vec<u8> {
+    let mut vector = FxpVector::new_zeros(DIM);
+    vector.data[0] = FxpScalar(65536);
+    let evt = KernelEvent::InsertRecord {
+        id: RecordId(id),
+        vector,
+        metadata: None,
+        tag: 0,
+    };
+    bincode::serde::encode_to_vec(&evt, bincode::config::standard()).unwrap()
+}
+
+#[test]
+fn in_order_packets_are_applied_in_sequence() {
+    let mut stream = WalStream::new(7);
+    assert_eq!(
+        chunk(stream.ingest_packet(&packet(7, false, b"ab")).unwrap()),
+        (b"ab".to_vec(), false)
+    );
+    assert_eq!(
+        chunk(stream.ingest_packet(&packet(8, true, b"c")).unwrap()),
+        (b"c".to_vec(), true)
+    );
+    assert_eq!(stream.next_expected_seq, 9);
+}
+
+#[test]
+fn duplicate_is_ignored() {
+    let mut stream = WalStream::new(0);
+    chunk(stream.ingest_packet(&packet(0, false, b"a")).unwrap());
+    chunk(stream.ingest_packet(&packet(1, false, b"b")).unwrap());
+
+    assert!(matches!(
+        stream.ingest_packet(&packet(0, false, b"a")),
+        Ok(Ingest::Duplicate)
+    ));
+    assert!(matches!(
+        stream.ingest_packet(&packet(1, false, b"b")),
+        Ok(Ingest::Duplicate)
+    ));
+    assert_eq!(stream.next_expected_seq, 2);
+    // A duplicate is not a gap: nothing has been NACKed yet.
+    assert_eq!(stream.nack(), Some(2));
+}
+
+#[test]
+fn gap_is_nacked_once_until_the_retransmission() {
+    let mut stream = WalStream::new(0);
+    chunk(stream.ingest_packet(&packet(0, false, b"a")).unwrap());
+
+    // Packet 1 is lost: 2 is a gap and asks for 1.
+    assert!(matches!(
+        stream.ingest_packet(&packet(2, false, b"c")),
+        Ok(Ingest::Gap)
+    ));
+    assert_eq!(stream.nack(), Some(1));
+
+    // The rest of the burst is dropped without another NACK.
+    for seq in 3..6 {
+        assert!(matches!(
+            stream.ingest_packet(&packet(seq, false, b"x")),
+            Ok(Ingest::Gap)
+        ));
+        assert_eq!(stream.nack(), None, "seq {seq}");
+    }
+    // So is a malformed packet in the same burst.
+    assert!(stream.ingest_packet(&[1, 0, 0]).is_err());
+    assert_eq!(stream.nack(), None);
+    assert_eq!(stream.next_expected_seq, 1);
+}
+
+#[test]
+fn lost_nack_is_repeated_after_the_retry_budget() {
+    let mut stream = WalStream::new(0);
+    chunk(stream.ingest_packet(&packet(0, false, b"a")).unwrap());
+
+    // Packet 1 is lost, and so is the NACK asking for it.
+    assert!(matches!(
+        stream.ingest_packet(&packet(2, false, b"c")),
+        Ok(Ingest::Gap)
+    ));
+    assert_eq!(stream.nack(), Some(1));
+
+    // The host, never told, keeps sending; the device keeps dropping.
+    let mut seq = 3;
+    for _ in 0..NACK_RETRY_PACKETS {
+        assert!(matches!(
+            stream.ingest_packet(&packet(seq, false, b"x")),
+            Ok(Ingest::Gap)
+        ));
+        assert_eq!(stream.nack(), None, "seq {seq}");
+        seq += 1;
+    }
+    // Then it asks again, and waits another full budget after that.
+    assert!(matches!(
+        stream.ingest_packet(&packet(seq, false, b"x")),
+        Ok(Ingest::Gap)
+    ));
+    assert_eq!(stream.nack(), Some(1));
+    assert_eq!(stream.nack(), None);
+
+    // The retransmission resumes the stream.
+    assert_eq!(
+        chunk(stream.ingest_packet(&packet(1, false, b"b")).unwrap()).0,
+        b"b"
+    );
+    assert_eq!(stream.next_expected_seq, 2);
+}
+
+#[test]
+fn retransmission_clears_the_nack() {
+    let mut stream = WalStream::new(0);
+    assert!(matches!(
+        stream.ingest_packet(&packet(1, false, b"b")),
+        Ok(Ingest::Gap)
+    ));
+    assert_eq!(stream.nack(), Some(0));
+    assert_eq!(stream.nack(), None);
+
+    // The host resends from 0; the stream resumes.
+    assert_eq!(
+        chunk(stream.ingest_packet(&packet(0, false, b"a")).unwrap()).0,
+        b"a"
+    );
+    assert_eq!(
+        chunk(stream.ingest_packet(&packet(1, false, b"b")).unwrap()).0,
+        b"b"
+    );
+
+    // A later gap is NACKed again, at the new position.
+    assert!(matches!(
+        stream.ingest_packet(&packet(4, false, b"e")),
+        Ok(Ingest::Gap)
+    ));
+    assert_eq!(stream.nack(), Some(2));
+    assert_eq!(stream.nack(), None);
+}
+
+#[test]
+fn malformed_packets_are_errors() {
+    let mut stream = WalStream::new(0);
+    // Truncated header.
+    assert!(stream.ingest_packet(&packet(0, false, b"")[..13]).is_err());
+    // Payload shorter than its declared length.
+    let mut short = packet(0, false, b"abcd");
+    short.truncate(short.len() - 1);
+    assert!(stream.ingest_packet(&short).is_err());
+    // Unknown stream version.
+    let mut future = packet(0, false, b"a");
+    future[0] = 2;
+    assert!(stream.ingest_packet(&future).is_err());
+    assert_eq!(stream.next_expected_seq, 0);
+}
+
+#[test]
+fn eos_in_the_middle_of_an_event_is_refused() {
+    let mut segment_bytes = WalHeader::new(DIM as u32).encode().to_vec();
+    let event = insert_event(0);
+    segment_bytes.extend_from_slice(&event);
+    let split = WalHeader::SIZE + event.len() / 2;
+
+    // The host closes the segment after half the event.
+    let mut stream = WalStream::new(0);
+    let mut state = KernelState::new();
+    let mut segment = ShadowKernel::new();
+    segment.start_segment();
+    let (payload, eos) = chunk(
+        stream
+            .ingest_packet(&packet(0, true, &segment_bytes[..split]))
+            .unwrap(),
+    );
+    assert!(eos);
+    segment.apply_chunk(&mut state, &payload).unwrap();
+    assert!(!segment.is_complete(), "a partial event must not commit");
+    assert_eq!(state.record_count(), 0);
+
+    // Framed correctly, the same bytes span two packets and commit.
+    let mut stream = WalStream::new(0);
+    let mut state = KernelState::new();
+    let mut segment = ShadowKernel::new();
+    segment.start_segment();
+    for (seq, (bytes, eos)) in [
+        (&segment_bytes[..split], false),
+        (&segment_bytes[split..], true),
+    ]
+    .into_iter()
+    .enumerate()
+    {
+        let (payload, got_eos) = chunk(
+            stream
+                .ingest_packet(&packet(seq as u64, eos, bytes))
+                .unwrap(),
+        );
+        assert_eq!(got_eos, eos);
+        segment.apply_chunk(&mut state, &payload).unwrap();
+        assert_eq!(segment.is_complete(), eos);
+    }
+    assert_eq!(state.record_count(), 1);
+}
@@ -0,0 +1,139 @@
+# Copyright (c) 2025 Varshith Gudur. Licensed under MIT OR Apache-2.0.
+"""Offline tests for AsyncRemoteClient's LocalClient-parity surface.
+
+A fake transport stands in for httpx, so these run without a node.
+"""
+import asyncio
+import os
+import sys
+
+import pytest
+
+sys.path.insert(0, os.path.join(os.path.dirname(__file__), ".."))
+
+from valoricore.exceptions import ValidationError  # noqa: E402
+from valoricore.graph import AsyncNode  # noqa: E402
+from valoricore.kinds import EDGE_PARENT_OF, NODE_CHUNK, NODE_DOCUMENT  # noqa: E402
+from valoricore.remote import AsyncRemoteClient  # noqa: E402
+
+pytestmark = pytest.mark.unit
+
+BASE = "http://node:3000"
+
+
+class _Resp:
+    def __init__(self, status_code=200, body=None):
+        self.status_code = status_code
+        self._body = body if body is not None else {}
+        self.text = str(self._body)
+
+    def json(self):
+        return self._body
+
+    def raise_for_status(self):
+        if self.status_code >= 400:
+            raise RuntimeError(f"HTTP {self.status_code}")
+
+
+class _FakeTransport:
+    """Records every call and answers like a tiny in-memory node."""
+
+    def __init__(self):
+        self.base_url = BASE
+        self._leader_url = None
+        self.calls = []
+        self.records = 0
+        self.nodes = {}
+        self.edges = []
+
+    async def post_rpc(self, path, data):
+        self.calls.append(("RPC", path, data))
+        if path == "/v1/records":
+            self.records += 1
+            return {"id": self.records - 1}
+        if path == "/v1/graph/node":
+            self.nodes[len(self.nodes)] = {"kind": data["kind"], "record_id": data["record_id"]}
+            return {"node_id": len(self.nodes) - 1}
+        if path == "/v1/graph/edge":
+            self.edges.append({"edge_id": len(self.edges), "from": data["from"],
+                               "to_node": data["to"], "kind": data["kind"]})
+            return {"edge_id": len(self.edges) - 1}
+        raise AssertionError(path)
+
+    async def get(self, url, **kw):
+        path = url[len(BASE):]
+        self.calls.append(("GET", path, kw.get("params")))
+        if path.startswith("/v1/graph/node/"):
+            node = self.nodes.get(int(path.rsplit("/", 1)[1]))
+            return _Resp(404) if node is None else _Resp(body=node)
+        if path.startswith("/v1/graph/edges/"):
+            nid = int(path.rsplit("/", 1)[1])
+            return _Resp(body={"edges": [e for e in self.edges if e["from"] == nid]})
+        if path == "/readyz":
+            return _Resp(503, {"status": "recovering (40%)"})
+        return _Resp(body={"path": path})
+
+    async def post(self, url, **kw):
+        self.calls.append(("POST", url[len(BASE):], kw.get("json")))
+        return _Resp(body={"height": 7})
+
+    async def patch(self, url, **kw):
+        self.calls.append(("PATCH", url[len(BASE):], kw.get("json")))
+        if kw["json"].get("ef_search") == 0:
+            return _Resp(400, {"error": "ef_search must be at least 1"})
+        return _Resp(body=kw["json"])
+
+    async def close(self):
+        pass
+
+
+def _client():
+    c = AsyncRemoteClient.__new__(AsyncRemoteClient)
+    c._t = _FakeTransport()
+    c.base_url = BASE
+    c._auto_snapshot_interval = None
+    c._insert_count = 0
+    return c
+
+
+def test_build_document_and_traverse_with_await():
+    async def run():
+        db = _client()
+        async with db.build_document(title="essay") as builder:
+            await builder.add_chunk([0.1, 0.2])
+            await builder.add_chunk([0.3, 0.4])
+        assert isinstance(builder.document, AsyncNode)
+        assert builder.record_ids == [0, 1]
+
+        children = await builder.document.children(EDGE_PARENT_OF)
+        assert [c.kind for c in children] == [NODE_CHUNK, NODE_CHUNK]
+        assert sorted(await builder.document.record_ids()) == [0, 1]
+
+        extra = await db.node(NODE_DOCUMENT)
+        await db.edge(extra, builder.chunks[0], EDGE_PARENT_OF)
+        assert (await extra.children())[0] == builder.chunks[0]
+
+    asyncio.run(run())
+
+
+def test_sync_context_manager_is_refused():
+    with pytest.raises(TypeError):
+        with _client().build_document():
+            pass
+
+
+def test_ops_endpoints_hit_the_node_routes():
+    async def run():
+        db = _client()
+        assert await db.ready() is False
+        assert (await db.storage_stats())["path"] == "/v1/stats/storage"
+        assert (await db.get_runtime_config())["path"] == "/v1/admin/config"
+        assert await db.update_runtime_config(ef_search=64) == {"ef_search": 64}
+        with pytest.raises(ValidationError):
+            await db.update_runtime_config(ef_search=0)
+        assert (await db.rotate_log())["height"] == 7
+        await db.compact()
+        posts = [c[1] for c in db._t.calls if c[0] == "POST"]
+        assert posts == ["/v1/admin/rotate-log", "/v1/admin/compact"]
+
+    asyncio.run(run())
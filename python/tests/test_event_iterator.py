@@ -0,0 +1,48 @@
+# Copyright (c) 2025 Varshith Gudur. Licensed under MIT OR Apache-2.0.
+"""
+Tests for ValoricoreEngine.events() — decoded event-log iteration.
+"""
+
+import os
+import shutil
+import sys
+import tempfile
+
+import pytest
+
+sys.path.insert(0, os.path.join(os.path.dirname(__file__), ".."))
+
+from valoricore.local import LocalClient  # noqa: E402
+
+pytestmark = pytest.mark.ffi
+
+
+@pytest.fixture
+def engine():
+    d = tempfile.mkdtemp(prefix="valoricore_events_")
+    yield LocalClient(path=d, dim=4)
+    shutil.rmtree(d, ignore_errors=True)
+
+
+def test_events_decode_in_log_order(engine):
+    rid = engine.insert([0.5, -1.0, 0.0, 2.0], tag=3)
+    nid = engine.create_node(kind=1, record_id=rid)
+    engine.soft_delete(rid)
+
+    events = list(engine.events())
+    assert [e["type"] for e in events] == ["InsertRecord", "CreateNode", "SoftDeleteRecord"]
+    assert [e["log_index"] for e in events] == [0, 1, 2]
+
+    insert = events[0]
+    assert insert["id"] == rid and insert["tag"] == 3 and insert["namespace"] == 0
+    assert insert["vector"] == pytest.approx([0.5, -1.0, 0.0, 2.0])
+    assert events[1]["id"] == nid and events[1]["record"] == rid
+
+
+def test_start_height_skips_earlier_events(engine):
+    for i in range(4):
+        engine.insert([float(i)] * 4)
+    tail = list(engine.events(start_height=2))
+    assert [e["log_index"] for e in tail] == [2, 3]
+    assert tail[0]["vector"] == pytest.approx([2.0] * 4)
+    assert list(engine.events(start_height=10)) == []
@@ -0,0 +1,56 @@
+# Copyright (c) 2025 Varshith Gudur. Licensed under MIT OR Apache-2.0.
+"""
+Tests for LocalClient.insert_dataframe() — DataFrame rows → records in one call.
+"""
+
+import os
+import shutil
+import sys
+import tempfile
+
+import numpy as np
+import pytest
+
+pd = pytest.importorskip("pandas")
+
+sys.path.insert(0, os.path.join(os.path.dirname(__file__), ".."))
+
+from valoricore.exceptions import ValidationError  # noqa: E402
+from valoricore.local import LocalClient  # noqa: E402
+
+pytestmark = pytest.mark.ffi
+
+
+@pytest.fixture
+def engine():
+    d = tempfile.mkdtemp(prefix="valoricore_df_")
+    yield LocalClient(path=d, dim=4)
+    shutil.rmtree(d, ignore_errors=True)
+
+
+def test_rows_become_records_with_tags_and_metadata(engine):
+    df = pd.DataFrame({
+        "embedding": [np.full(4, 0.1 * (i + 1), dtype=np.float32) for i in range(3)],
+        "tag": [7, 8, 9],
+        "title": ["a", "b", "c"],
+        "page": [1, 2, 3],
+    })
+    ids = engine.insert_dataframe(df, "embedding", tag_col="tag", metadata_cols=["title", "page"])
+
+    assert len(ids) == 3
+    assert engine.record_count() == 3
+    assert engine.get_metadata(ids[1]) == {"title": "b", "page": 2}
+    assert engine.search([0.3] * 4, k=1, filter_tag=9)[0]["id"] == ids[2]
+
+
+def test_bad_row_inserts_nothing(engine):
+    df = pd.DataFrame({"embedding": [[0.1] * 4, [0.2] * 3]})
+    with pytest.raises(ValidationError, match="embedding\\[1\\]"):
+        engine.insert_dataframe(df, "embedding")
+    assert engine.record_count() == 0
+
+
+def test_missing_column_is_reported(engine):
+    df = pd.DataFrame({"embedding": [[0.1] * 4]})
+    with pytest.raises(ValidationError, match="nope"):
+        engine.insert_dataframe(df, "embedding", tag_col="nope")
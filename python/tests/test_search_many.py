@@ -0,0 +1,57 @@
+# Copyright (c) 2025 Varshith Gudur. Licensed under MIT OR Apache-2.0.
+"""
+Tests for LocalClient.search_many() — a matrix of queries in one FFI call.
+"""
+
+import os
+import shutil
+import sys
+import tempfile
+
+import numpy as np
+import pytest
+
+sys.path.insert(0, os.path.join(os.path.dirname(__file__), ".."))
+
+from valoricore.exceptions import ValidationError  # noqa: E402
+from valoricore.local import LocalClient  # noqa: E402
+
+pytestmark = pytest.mark.ffi
+
+
+@pytest.fixture
+def engine():
+    d = tempfile.mkdtemp(prefix="valoricore_many_")
+    client = LocalClient(path=d, dim=4)
+    for i in range(20):
+        client.insert([i / 20, 1 - i / 20, (i % 3) / 3, 0.5], tag=i % 2)
+    yield client
+    shutil.rmtree(d, ignore_errors=True)
+
+
+@pytest.mark.parametrize("threads", [1, 3, 0])
+def test_rows_match_single_searches(engine, threads):
+    rng = np.random.default_rng(7)
+    queries = rng.random((9, 4), dtype=np.float32)
+    ids, scores = engine.search_many(queries, k=5, threads=threads)
+
+    assert ids.shape == scores.shape == (9, 5)
+    assert ids.dtype == scores.dtype == np.int64
+    for row, query in enumerate(queries):
+        hits = engine.search(query.tolist(), k=5)
+        assert ids[row].tolist() == [h["id"] for h in hits]
+        assert scores[row].tolist() == [h["score"] for h in hits]
+
+
+def test_short_rows_are_padded(engine):
+    ids, scores = engine.search_many([[0.5] * 4, [0.1] * 4], k=15, filter_tag=1)
+    assert ids.shape == (2, 15)
+    assert (ids[:, 10:] == -1).all() and (scores[:, 10:] == -1).all()
+    assert (ids[:, :10] % 2 == 1).all()
+
+
+def test_bad_query_is_reported_before_searching(engine):
+    with pytest.raises(ValidationError, match="queries\\[1\\]"):
+        engine.search_many([[0.5] * 4, [0.5] * 3], k=1)
+    ids, _ = engine.search_many(np.empty((0, 4), dtype=np.float32), k=3)
+    assert ids.shape == (0, 3)
@@ -0,0 +1,49 @@
+# Copyright (c) 2025 Varshith Gudur. Licensed under MIT OR Apache-2.0.
+"""
+Tests for float search scores — ValoricoreEngine.search_scored and
+LocalClient.search(float_scores=True).
+"""
+
+import math
+import os
+import shutil
+import sys
+import tempfile
+
+import pytest
+
+sys.path.insert(0, os.path.join(os.path.dirname(__file__), ".."))
+
+from valoricore.local import LocalClient  # noqa: E402
+
+pytestmark = pytest.mark.ffi
+
+
+@pytest.fixture
+def engine():
+    d = tempfile.mkdtemp(prefix="valoricore_scores_")
+    yield LocalClient(path=d, dim=4)
+    shutil.rmtree(d, ignore_errors=True)
+
+
+def test_float_scores_descale_the_raw_score(engine):
+    engine.insert([1.0, 0.0, 0.0, 0.0])
+    engine.insert([0.0, 3.0, 0.0, 0.0])
+    query = [1.0, 1.0, 0.0, 0.0]
+
+    raw = engine.search(query, k=2)
+    scored = engine.search(query, k=2, float_scores=True)
+
+    assert [h["id"] for h in scored] == [h["id"] for h in raw]
+    for r, s in zip(raw, scored):
+        assert s["distance"] == pytest.approx(math.sqrt(r["score"] / 65536), rel=1e-4)
+    assert scored[0]["distance"] == pytest.approx(1.0, rel=1e-4)
+    assert scored[0]["similarity"] == pytest.approx(1 / math.sqrt(2), rel=1e-4)
+    assert scored[1]["similarity"] == pytest.approx(1 / math.sqrt(2), rel=1e-4)
+
+
+def test_zero_vector_has_no_similarity(engine):
+    engine.insert([0.0, 0.0, 0.0, 0.0])
+    (hit,) = engine.search([1.0, 0.0, 0.0, 0.0], k=1, float_scores=True)
+    assert hit["distance"] == pytest.approx(1.0, rel=1e-4)
+    assert hit["similarity"] is None
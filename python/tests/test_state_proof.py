@@ -0,0 +1,55 @@
+# Copyright (c) 2025 Varshith Gudur. Licensed under MIT OR Apache-2.0.
+"""
+Tests for ValoricoreEngine.state_hash() / proof() — determinism checks
+without an HTTP node.
+"""
+
+import os
+import shutil
+import sys
+import tempfile
+
+import pytest
+
+sys.path.insert(0, os.path.join(os.path.dirname(__file__), ".."))
+
+from valoricore.local import LocalClient  # noqa: E402
+
+pytestmark = pytest.mark.ffi
+
+
+@pytest.fixture
+def dirs():
+    made = [tempfile.mkdtemp(prefix="valoricore_proof_") for _ in range(2)]
+    yield made
+    for d in made:
+        shutil.rmtree(d, ignore_errors=True)
+
+
+def _populate(db: LocalClient) -> None:
+    for i in range(5):
+        db.insert([0.1 * i, 0.2, 0.3, 0.4], tag=i)
+    db.create_node(kind=1, record_id=0)
+
+
+def test_same_writes_give_the_same_proof(dirs):
+    a, b = (LocalClient(path=d, dim=4) for d in dirs)
+    _populate(a)
+    _populate(b)
+
+    pa, pb = a.get_proof(), b.get_proof()
+    assert pa["final_state_hash"] == a.kernel.state_hash() == a.get_state_hash()
+    assert len(pa["final_state_hash"]) == 64
+    assert pa["final_state_hash"] == pb["final_state_hash"]
+    assert pa["committed_height"] == pb["committed_height"] == 6
+
+
+def test_proof_changes_with_state(dirs):
+    db = LocalClient(path=dirs[0], dim=4)
+    _populate(db)
+    before = db.get_proof()
+    db.insert([0.9, 0.9, 0.9, 0.9])
+    after = db.get_proof()
+    assert after["final_state_hash"] != before["final_state_hash"]
+    assert after["event_log_hash"] != before["event_log_hash"]
+    assert after["committed_height"] == before["committed_height"] + 1
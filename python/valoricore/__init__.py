@@ -51,7 +51,7 @@ from .local import LocalClient
 from .remote import SyncRemoteClient, AsyncRemoteClient, ClusterClient, AsyncClusterClient
 from .memory import MemoryClient
 from .protocol import ProtocolClient, ProtocolRemoteClient
-from .graph import Node, DocumentGraph
+from .graph import Node, DocumentGraph, AsyncNode, AsyncDocumentGraph
 from .async_memory import AsyncMemoryClient
 from .factory import Valoricore, AsyncValoricore
 from .adapter import ValoricoreAdapter
@@ -66,6 +66,7 @@ from .exceptions import (
     NotLeaderError,
     KernelError,
     TamperDetected,
+    VersionConflictError,
 )
 from .verify import AnchorVerifier, TamperFinding, VerifyReport, verify_log
 from .kinds import (
@@ -121,6 +122,8 @@ __all__ = [
     # ── High-level graph objects ───────────────────────────────────
     "Node",
     "DocumentGraph",
+    "AsyncNode",
+    "AsyncDocumentGraph",
 
     # ── Protocol clients ───────────────────────────────────────────
     "ProtocolClient",
@@ -155,6 +158,7 @@ __all__ = [
     "NotFoundError",
     "NotLeaderError",
     "KernelError",
+    "VersionConflictError",
 
     # ── Verification ───────────────────────────────────────────────
     "AnchorVerifier",
@@ -83,11 +83,16 @@ class ValoriClient(ABC):
         rerank: bool = False,
         query_text: Optional[str] = None,
         metadata_filter: Optional[Dict[str, Any]] = None,
+        tag_any: Optional[int] = None,
+        tag_all: Optional[int] = None,
         **kwargs: Any,
     ) -> List[Dict[str, Any]]:
         """
         Nearest-neighbour search. Returns list of dicts with at minimum
         ``{"id": int, "score": int}``.
+
+        ``filter_tag`` keeps records with exactly that tag; ``tag_any`` /
+        ``tag_all`` read tags as bitmasks (share a bit / carry every bit).
         """
 
     @abstractmethod
@@ -46,6 +46,16 @@ class NotLeaderError(ValoricoreError):
     """
     pass
 
+class VersionConflictError(ValoricoreError):
+    """
+    Raised when a write's version precondition (``expected_version``) no
+    longer holds — another writer got there first. Nothing was written.
+    ``current`` is the version the server reported; re-read and retry.
+    """
+    def __init__(self, message: str, current=None):
+        super().__init__(message)
+        self.current = current
+
 class TamperDetected(IntegrityError):
     """
     Raised when a live node's state hash differs from an anchor, or when
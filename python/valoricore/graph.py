@@ -272,3 +272,103 @@ class DocumentGraph:
             f"DocumentGraph(doc_node={doc_id}, "
             f"chunks={len(self.chunks)}, title={self.title!r})"
         )
+
+
+# ── Async variants ────────────────────────────────────────────────────────────
+
+class AsyncNode(Node):
+    """
+    :class:`Node` for :class:`~valoricore.remote.AsyncRemoteClient` — the
+    same attributes, but every method that talks to the node is awaitable::
+
+        doc   = await db.node(NODE_DOCUMENT)
+        chunk = await db.node(NODE_CHUNK, vector=my_embedding)
+        await doc.link_to(chunk, EDGE_PARENT_OF)
+    """
+
+    async def link_to(  # type: ignore[override]
+        self,
+        other: Union["Node", List["Node"], int],
+        edge_kind: int,
+    ) -> "AsyncNode":
+        targets = other if isinstance(other, (list, tuple)) else [other]
+        for t in targets:
+            to_id = t.id if isinstance(t, Node) else int(t)
+            await self._db.create_edge(from_id=self.id, to_id=to_id, kind=edge_kind)
+        return self
+
+    async def link_from(  # type: ignore[override]
+        self, other: Union["Node", int], edge_kind: int
+    ) -> "AsyncNode":
+        from_id = other.id if isinstance(other, Node) else int(other)
+        await self._db.create_edge(from_id=from_id, to_id=self.id, kind=edge_kind)
+        return self
+
+    async def children(  # type: ignore[override]
+        self, edge_kind: Optional[int] = None
+    ) -> List["AsyncNode"]:
+        result: List[AsyncNode] = []
+        for e in await self._db.get_edges(self.id):
+            if edge_kind is not None and e["kind"] != edge_kind:
+                continue
+            data = await self._db.get_node(e["to_node"])
+            if data is not None:
+                result.append(
+                    AsyncNode(e["to_node"], data["kind"], data["record_id"], self._db)
+                )
+        return result
+
+    async def walk(self, max_depth: int = 2) -> List["AsyncNode"]:  # type: ignore[override]
+        result: List[AsyncNode] = []
+        for nid in await self._db.walk(self.id, max_depth):
+            data = await self._db.get_node(nid)
+            if data is not None:
+                result.append(AsyncNode(nid, data["kind"], data["record_id"], self._db))
+        return result
+
+    async def record_ids(self, max_depth: int = 2) -> List[int]:  # type: ignore[override]
+        return await self._db.expand(self.id, max_depth)
+
+    async def delete(self) -> None:  # type: ignore[override]
+        await self._db.delete_node(self.id)
+
+
+class AsyncDocumentGraph(DocumentGraph):
+    """
+    :class:`DocumentGraph` for :class:`~valoricore.remote.AsyncRemoteClient`,
+    used with ``async with``::
+
+        async with db.build_document(title="My Essay") as builder:
+            for embedding in embeddings:
+                await builder.add_chunk(embedding)
+    """
+
+    def __enter__(self) -> "DocumentGraph":
+        raise TypeError("use `async with db.build_document()` with an async client")
+
+    async def __aenter__(self) -> "AsyncDocumentGraph":
+        node_id = await self._db.create_node(kind=NODE_DOCUMENT)
+        self.document = AsyncNode(node_id, kind=NODE_DOCUMENT, record_id=None, _db=self._db)
+        return self
+
+    async def add_chunk(  # type: ignore[override]
+        self,
+        vector: List[float],
+        tag: int = 0,
+        metadata: Optional[bytes] = None,
+    ) -> AsyncNode:
+        if self.document is None:
+            raise RuntimeError(
+                "add_chunk() must be called inside the 'async with build_document()' block."
+            )
+        record_id = await self._db.insert(vector, tag=tag)
+        if metadata is not None:
+            await self._db.set_metadata(record_id, metadata)
+        chunk_id = await self._db.create_node(kind=NODE_CHUNK, record_id=record_id)
+        await self._db.create_edge(from_id=self.document.id, to_id=chunk_id, kind=EDGE_PARENT_OF)
+        chunk = AsyncNode(chunk_id, kind=NODE_CHUNK, record_id=record_id, _db=self._db)
+        self.chunks.append(chunk)
+        return chunk
+
+    async def __aexit__(self, *_args: object) -> None:
+        pass
@@ -1,6 +1,6 @@
 # Copyright (c) 2025 Varshith Gudur. Licensed under MIT OR Apache-2.0.
 from collections import deque
-from typing import List, Dict, Optional, Any, Tuple
+from typing import Iterator, List, Dict, Optional, Any, Tuple
 import os
 import threading
 from .types import Vector, RecordId, NodeId, Proof, StateHash
@@ -153,15 +153,56 @@ class LocalClient(ValoriClient):
         rerank: bool = False,                       # ignored — no text index
         query_text: Optional[str] = None,           # ignored
         metadata_filter: Optional[Dict[str, Any]] = None,  # ignored
+        tag_any: Optional[int] = None,
+        tag_all: Optional[int] = None,
+        float_scores: bool = False,
         **kwargs: Any,
     ) -> List[Dict[str, Any]]:
-        """Perform nearest neighbour search."""
+        """Perform nearest neighbour search.
+
+        By default each hit is ``{"id", "score"}``, where ``score`` is the
+        kernel's squared L2 distance in Q16.16 (``distance² × 65536``). With
+        ``float_scores=True`` each hit is ``{"id", "distance", "similarity"}``:
+        the Euclidean distance and the cosine similarity (``None`` for a
+        zero vector), both computed in Rust. The order of hits is the same.
+
+        ``tag_any`` / ``tag_all`` treat tags as bitmasks: a hit shares at
+        least one bit with the mask, or carries all of its bits. Pass at most
+        one of ``filter_tag``, ``tag_any`` and ``tag_all``.
+        """
         try:
-            hits = self.kernel.search(query, k, filter_tag)
+            if float_scores:
+                hits = self.kernel.search_scored(query, k, filter_tag, tag_any, tag_all)
+                return [{"id": h[0], "distance": h[1], "similarity": h[2]} for h in hits]
+            hits = self.kernel.search(query, k, filter_tag, tag_any, tag_all)
             return [{"id": h[0], "score": h[1]} for h in hits]
         except ValueError as e:
             raise ValidationError(str(e))
 
+    def search_many(
+        self,
+        queries: Any,
+        k: int,
+        filter_tag: Optional[int] = None,
+        *,
+        tag_any: Optional[int] = None,
+        tag_all: Optional[int] = None,
+        threads: int = 1,
+    ) -> Tuple[Any, Any]:
+        """Search every row of an ``(N, D)`` array of queries in one call.
+
+        Returns ``(ids, scores)``, two ``(N, k)`` int64 numpy arrays. Row ``i``
+        holds the hits of ``queries[i]`` exactly as :meth:`search` returns
+        them, padded with ``-1`` when fewer than ``k`` records match. All
+        queries run under one engine lock with the GIL released;
+        ``threads`` spreads them over that many Rust threads (``0`` = one
+        per CPU).
+        """
+        try:
+            return self.kernel.search_many(queries, k, filter_tag, tag_any, tag_all, threads)
+        except ValueError as e:
+            raise ValidationError(str(e))
+
     def create_node(self, kind: int, record_id: Optional[int] = None) -> NodeId:
         return self.kernel.create_node(kind, record_id)
 
@@ -336,6 +377,27 @@ class LocalClient(ValoriClient):
         self._check_auto_snapshot(len(vectors))
         return res
     
+    def insert_dataframe(
+        self,
+        df: Any,
+        vector_col: str,
+        tag_col: Optional[str] = None,
+        metadata_cols: Optional[List[str]] = None,
+    ) -> List[RecordId]:
+        """Insert every row of a pandas DataFrame and return the record IDs in row order.
+
+        ``vector_col`` holds one embedding per row (list or numpy array).
+        ``tag_col`` is an optional integer column. ``metadata_cols`` are stored
+        per record and read back with :meth:`get_metadata`. Rows are converted
+        and validated on the Rust side before any of them is written.
+        """
+        try:
+            res = self.kernel.insert_dataframe(df, vector_col, tag_col, metadata_cols)
+        except ValueError as e:
+            raise ValidationError(str(e))
+        self._check_auto_snapshot(len(res))
+        return res
+
     def insert_batch_with_proof(self, vectors: List[Vector], tags: Optional[List[int]] = None) -> List[Tuple[RecordId, Proof]]:
         if tags is None:
             tags = [0] * len(vectors)
@@ -370,6 +432,22 @@ class LocalClient(ValoriClient):
     def get_state_hash(self) -> StateHash:
         """Returns the hex-encoded BLAKE3 root hash of the kernel state."""
         return self.kernel.get_state_hash()
+
+    def get_proof(self) -> Dict[str, Any]:
+        """Return the state proof as a dict, computed in-process.
+
+        Same shape as the node's ``/v1/proof/event-log``: ``kernel_version``,
+        ``final_state_hash``, ``event_log_hash`` and ``committed_height``.
+        ``final_state_hash`` is equal for equal states across runs and
+        machines, so benchmark harnesses can assert determinism without an
+        HTTP node. ``event_log_hash`` covers the log file's bytes, which
+        include entry timestamps.
+        """
+        return self.kernel.proof()
+
+    def event_log_proof(self) -> Dict[str, Any]:
+        """Alias of :meth:`get_proof`, matching the remote clients' name."""
+        return self.get_proof()
     
     def record_count(self) -> int:
         return self.kernel.record_count()
@@ -400,3 +478,19 @@ class LocalClient(ValoriClient):
             return self.kernel.get_timeline()
         except Exception as e:
             raise KernelError(f"Failed to read timeline: {e}")
+
+    def events(self, start_height: int = 0) -> Iterator[Dict[str, Any]]:
+        """
+        Iterate the canonical event log as dicts, oldest first — for forensic
+        analysis and custom replay tooling.
+
+        Each dict has ``log_index``, ``type`` (e.g. ``"InsertRecord"``),
+        ``namespace`` and the event's own fields; vectors are floats and byte
+        fields hex strings. Events before ``start_height`` are skipped. Every
+        segment on disk is read, so the history is complete even after a
+        snapshot restore.
+        """
+        try:
+            return self.kernel.events(start_height)
+        except Exception as e:
+            raise KernelError(f"Failed to read event log: {e}")
@@ -11,7 +11,7 @@ from .base import ValoriClient
 from .types import Vector, RecordId, NodeId, Proof
 from .exceptions import (
     AuthenticationError, ConnectionError, ValidationError,
-    NotFoundError, NotLeaderError,
+    NotFoundError, NotLeaderError, VersionConflictError,
 )
 
 
@@ -30,6 +30,15 @@ def _raise_for_status(resp, path: str = "") -> None:
     resp.raise_for_status()
 
 
+def _raise_for_conflict(resp, record_id: int) -> None:
+    if resp.status_code == 412:
+        body = resp.json()
+        raise VersionConflictError(
+            f"Record {record_id}: {body.get('error', 'version conflict')}",
+            current=body.get("version"),
+        )
+
+
 def _base_of(final_url: str, path: str) -> Optional[str]:
     if path and final_url.endswith(path):
         return final_url[: -len(path)]
@@ -408,14 +417,25 @@ class _SyncRecordsMixin(_SyncAutoSnapshotMixin):
         record_id: int,
         metadata: Dict[str, Any],
         collection: str = "default",
-    ) -> None:
+        expected_version: Optional[int] = None,
+    ) -> Optional[int]:
+        """Replace a record's metadata and return its new version.
+
+        With ``expected_version`` the update is a compare-and-swap: it raises
+        ``VersionConflictError`` if the record has changed since that version
+        was read (``get_record(...)["version"]``). Standalone nodes only.
+        """
         url = self._t.base_url + f"/v1/records/{record_id}/metadata"
-        params = {} if collection == "default" else {"collection": collection}
+        params: Dict[str, Any] = {} if collection == "default" else {"collection": collection}
+        if expected_version is not None:
+            params["expected_version"] = expected_version
         try:
             resp = self._t.patch(url, json=metadata, params=params)
             if resp.status_code == 404:
                 raise NotFoundError(f"Record {record_id} not found")
+            _raise_for_conflict(resp, record_id)
             _raise_for_status(resp, f"/v1/records/{record_id}/metadata")
+            return resp.json().get("version")
         except requests.exceptions.RequestException as e:
             raise ConnectionError(f"Failed to update metadata for record {record_id}: {e}")
 
@@ -436,10 +456,16 @@ class _SyncSearchMixin:
         rerank: bool = True,
         query_text: Optional[str] = None,
         metadata_filter: Optional[Dict[str, Any]] = None,
+        tag_any: Optional[int] = None,
+        tag_all: Optional[int] = None,
     ) -> List[Dict[str, Any]]:
         data: Dict[str, Any] = {"query": query, "k": k}
         if filter_tag is not None:
             data["filter_tag"] = filter_tag
+        if tag_any is not None:
+            data["tag_filter"] = {"any": tag_any}
+        elif tag_all is not None:
+            data["tag_filter"] = {"all": tag_all}
         if consistency is not None:
             data["consistency"] = consistency
         if collection != "default":
@@ -535,6 +561,21 @@ class _SyncGraphMixin:
     def neighbors(self, node_id: int, collection: str = "default") -> List[int]:
         return [e["to_node"] for e in self.get_edges(node_id, collection=collection)]
 
+    def node(self, kind: int, vector: Optional[Vector] = None, tag: int = 0):
+        """Create a graph node (inserting *vector* first if given) and return a ``Node``."""
+        from . import graph as _g
+        record_id = self.insert(vector, tag=tag) if vector is not None else None
+        return _g.Node(self.create_node(kind=kind, record_id=record_id), kind, record_id, self)
+
+    def edge(self, from_node, to_node, kind: int) -> int:
+        """Create a directed edge between ``Node`` objects or raw integer IDs."""
+        return self.create_edge(from_id=int(from_node), to_id=int(to_node), kind=kind)
+
+    def build_document(self, title: Optional[str] = None):
+        """Return a ``DocumentGraph`` context manager (see ``LocalClient.build_document``)."""
+        from . import graph as _g
+        return _g.DocumentGraph(self, title=title)
+
     def walk(self, start_node: int, max_depth: int = 2, collection: str = "default") -> List[int]:
         max_depth = min(max_depth, self._MAX_WALK_DEPTH)
         visited = {start_node}
@@ -776,6 +817,19 @@ class _SyncMemoryMixin:
             data["collection"] = collection
         return self._t.post_rpc("/v1/memory/contradict", data)
 
+    def dedup_check(
+        self,
+        vector: Vector,
+        max_distance: float,
+        limit: int = 10,
+        collection: str = "default",
+    ) -> Dict[str, Any]:
+        """Records within ``max_distance`` (squared L2) of ``vector``, nearest first."""
+        data: Dict[str, Any] = {"vector": vector, "max_distance": max_distance, "limit": limit}
+        if collection != "default":
+            data["collection"] = collection
+        return self._t.post_rpc("/v1/memory/dedup_check", data)
+
 
 class _SyncTreeMixin:
     _t: _SyncTransport
@@ -1105,6 +1159,54 @@ class _SyncIndexMixin:
             raise ConnectionError(f"Failed to get version: {e}")
 
 
+class _SyncOpsMixin:
+    """Readiness, storage usage and the ``/v1/admin`` maintenance endpoints.
+
+    The admin calls need an admin-scoped token when the node has API keys.
+    """
+    _t: _SyncTransport
+
+    def _ops_get(self, path: str) -> Dict[str, Any]:
+        resp = self._t.get(self._t.base_url + path)
+        _raise_for_status(resp, path)
+        return resp.json()
+
+    def _ops_send(self, method: str, path: str, body: Optional[Dict[str, Any]] = None) -> Dict[str, Any]:
+        send = self._t.patch if method == "PATCH" else self._t.post
+        resp = send(self._t.base_url + path, json=body)
+        if resp.status_code in (400, 409, 422):
+            raise ValidationError(f"[HTTP {resp.status_code}] {resp.text}")
+        _raise_for_status(resp, path)
+        return resp.json()
+
+    def ready(self) -> bool:
+        """``True`` once the node has finished recovering (``GET /readyz``)."""
+        try:
+            return self._t.get(self._t.base_url + "/readyz").status_code == 200
+        except requests.exceptions.RequestException as e:
+            raise ConnectionError(f"Failed to reach node: {e}")
+
+    def storage_stats(self) -> Dict[str, Any]:
+        """Disk usage of the event log, snapshots and WAL, plus pool capacity."""
+        return self._ops_get("/v1/stats/storage")
+
+    def get_runtime_config(self) -> Dict[str, Any]:
+        return self._ops_get("/v1/admin/config")
+
+    def update_runtime_config(self, **settings: Any) -> Dict[str, Any]:
+        """Patch runtime settings (``snapshot_interval_secs``, ``rate_limit_rps``,
+        ``ef_search``, ``log_level``); returns the resulting configuration."""
+        return self._ops_send("PATCH", "/v1/admin/config", settings)
+
+    def rotate_log(self) -> Dict[str, Any]:
+        """Seal the active event-log segment with a checkpoint."""
+        return self._ops_send("POST", "/v1/admin/rotate-log")
+
+    def compact(self) -> Dict[str, Any]:
+        """Snapshot to the node's snapshot path, then rotate the event log."""
+        return self._ops_send("POST", "/v1/admin/compact")
+
+
 class _SyncMetaMixin:
     _t: _SyncTransport
     ui_url: str
@@ -1170,6 +1272,36 @@ class _SyncMetaMixin:
         except requests.exceptions.RequestException as e:
             raise ConnectionError(f"Failed to fetch timeline: {e}")
 
+    def events(
+        self,
+        from_index: Optional[int] = None,
+        to_index: Optional[int] = None,
+        types: Optional[List[str]] = None,
+        limit: Optional[int] = None,
+        vectors: bool = False,
+    ) -> Dict[str, Any]:
+        """One page of decoded log events (``GET /v1/events``).
+
+        Pass the response's ``next`` as ``from_index`` to get the next page.
+        """
+        params: Dict[str, Any] = {}
+        if from_index is not None:
+            params["from"] = from_index
+        if to_index is not None:
+            params["to"] = to_index
+        if types:
+            params["type"] = ",".join(types)
+        if limit is not None:
+            params["limit"] = limit
+        if vectors:
+            params["vectors"] = "true"
+        try:
+            resp = self._t.get(f"{self._t.base_url}/v1/events", params=params)
+            _raise_for_status(resp)
+            return resp.json()
+        except requests.exceptions.RequestException as e:
+            raise ConnectionError(f"Failed to fetch events: {e}")
+
     def get_timeline(self) -> List[str]:
         url = self._t.base_url + "/v1/timeline"
         try:
@@ -1342,16 +1474,24 @@ class _AsyncRecordsMixin(_AsyncAutoSnapshotMixin):
             raise ConnectionError(f"Failed to fetch record {record_id}: {e}")
 
     async def update_record_metadata(
-        self, record_id: int, metadata: Dict[str, Any], collection: str = "default"
-    ) -> None:
+        self,
+        record_id: int,
+        metadata: Dict[str, Any],
+        collection: str = "default",
+        expected_version: Optional[int] = None,
+    ) -> Optional[int]:
         url = self._t.base_url + f"/v1/records/{record_id}/metadata"
-        params = {} if collection == "default" else {"collection": collection}
+        params: Dict[str, Any] = {} if collection == "default" else {"collection": collection}
+        if expected_version is not None:
+            params["expected_version"] = expected_version
         try:
             resp = await self._t.patch(url, json=metadata, params=params)
             if resp.status_code == 404:
                 raise NotFoundError(f"Record {record_id} not found")
+            _raise_for_conflict(resp, record_id)
             _raise_for_status(resp, f"/v1/records/{record_id}/metadata")
-        except (NotFoundError, AuthenticationError):
+            return resp.json().get("version")
+        except (NotFoundError, AuthenticationError, VersionConflictError):
             raise
         except Exception as e:
             raise ConnectionError(f"Failed to update metadata for record {record_id}: {e}")
@@ -1373,10 +1513,16 @@ class _AsyncSearchMixin:
         rerank: bool = True,
         query_text: Optional[str] = None,
         metadata_filter: Optional[Dict[str, Any]] = None,
+        tag_any: Optional[int] = None,
+        tag_all: Optional[int] = None,
     ) -> List[Dict[str, Any]]:
         data: Dict[str, Any] = {"query": query, "k": k}
         if filter_tag is not None:
             data["filter_tag"] = filter_tag
+        if tag_any is not None:
+            data["tag_filter"] = {"any": tag_any}
+        elif tag_all is not None:
+            data["tag_filter"] = {"all": tag_all}
         if consistency is not None:
             data["consistency"] = consistency
         if collection != "default":
@@ -1472,6 +1618,22 @@ class _AsyncGraphMixin:
     async def neighbors(self, node_id: int, collection: str = "default") -> List[int]:
         return [e["to_node"] for e in await self.get_edges(node_id, collection=collection)]
 
+    async def node(self, kind: int, vector: Optional[Vector] = None, tag: int = 0):
+        """Create a graph node (inserting *vector* first if given) and return an ``AsyncNode``."""
+        from . import graph as _g
+        record_id = await self.insert(vector, tag=tag) if vector is not None else None
+        node_id = await self.create_node(kind=kind, record_id=record_id)
+        return _g.AsyncNode(node_id, kind, record_id, self)
+
+    async def edge(self, from_node, to_node, kind: int) -> int:
+        """Create a directed edge between ``Node`` objects or raw integer IDs."""
+        return await self.create_edge(from_id=int(from_node), to_id=int(to_node), kind=kind)
+
+    def build_document(self, title: Optional[str] = None):
+        """Return an ``AsyncDocumentGraph``; use it with ``async with``."""
+        from . import graph as _g
+        return _g.AsyncDocumentGraph(self, title=title)
+
     async def walk(self, start_node: int, max_depth: int = 2, collection: str = "default") -> List[int]:
         max_depth = min(max_depth, self._MAX_WALK_DEPTH)
         visited = {start_node}
@@ -1713,6 +1875,19 @@ class _AsyncMemoryMixin:
             data["collection"] = collection
         return await self._t.post_rpc("/v1/memory/contradict", data)
 
+    async def dedup_check(
+        self,
+        vector: Vector,
+        max_distance: float,
+        limit: int = 10,
+        collection: str = "default",
+    ) -> Dict[str, Any]:
+        """Records within ``max_distance`` (squared L2) of ``vector``, nearest first."""
+        data: Dict[str, Any] = {"vector": vector, "max_distance": max_distance, "limit": limit}
+        if collection != "default":
+            data["collection"] = collection
+        return await self._t.post_rpc("/v1/memory/dedup_check", data)
+
 
 class _AsyncTreeMixin:
     _t: _AsyncTransport
@@ -1999,6 +2174,44 @@ class _AsyncIndexMixin:
             raise ConnectionError(f"Failed to get version: {e}")
 
 
+class _AsyncOpsMixin:
+    _t: _AsyncTransport
+
+    async def _ops_get(self, path: str) -> Dict[str, Any]:
+        resp = await self._t.get(self._t.base_url + path)
+        _raise_for_status(resp, path)
+        return resp.json()
+
+    async def _ops_send(self, method: str, path: str, body: Optional[Dict[str, Any]] = None) -> Dict[str, Any]:
+        send = self._t.patch if method == "PATCH" else self._t.post
+        resp = await send(self._t.base_url + path, json=body)
+        if resp.status_code in (400, 409, 422):
+            raise ValidationError(f"[HTTP {resp.status_code}] {resp.text}")
+        _raise_for_status(resp, path)
+        return resp.json()
+
+    async def ready(self) -> bool:
+        try:
+            return (await self._t.get(self._t.base_url + "/readyz")).status_code == 200
+        except Exception as e:
+            raise ConnectionError(f"Failed to reach node: {e}")
+
+    async def storage_stats(self) -> Dict[str, Any]:
+        return await self._ops_get("/v1/stats/storage")
+
+    async def get_runtime_config(self) -> Dict[str, Any]:
+        return await self._ops_get("/v1/admin/config")
+
+    async def update_runtime_config(self, **settings: Any) -> Dict[str, Any]:
+        return await self._ops_send("PATCH", "/v1/admin/config", settings)
+
+    async def rotate_log(self) -> Dict[str, Any]:
+        return await self._ops_send("POST", "/v1/admin/rotate-log")
+
+    async def compact(self) -> Dict[str, Any]:
+        return await self._ops_send("POST", "/v1/admin/compact")
+
+
 class _AsyncMetaMixin:
     _t: _AsyncTransport
     ui_url: str
@@ -2063,6 +2276,33 @@ class _AsyncMetaMixin:
         except Exception as e:
             raise ConnectionError(f"Failed to fetch timeline: {e}")
 
+    async def events(
+        self,
+        from_index: Optional[int] = None,
+        to_index: Optional[int] = None,
+        types: Optional[List[str]] = None,
+        limit: Optional[int] = None,
+        vectors: bool = False,
+    ) -> Dict[str, Any]:
+        """One page of decoded log events (``GET /v1/events``)."""
+        params: Dict[str, Any] = {}
+        if from_index is not None:
+            params["from"] = from_index
+        if to_index is not None:
+            params["to"] = to_index
+        if types:
+            params["type"] = ",".join(types)
+        if limit is not None:
+            params["limit"] = limit
+        if vectors:
+            params["vectors"] = "true"
+        try:
+            resp = await self._t.get(f"{self._t.base_url}/v1/events", params=params)
+            _raise_for_status(resp)
+            return resp.json()
+        except Exception as e:
+            raise ConnectionError(f"Failed to fetch events: {e}")
+
     async def get_timeline(self) -> List[str]:
         url = self._t.base_url + "/v1/timeline"
         try:
@@ -2119,6 +2359,7 @@ class SyncRemoteClient(
     _SyncKeysMixin,
     _SyncClusterMixin,
     _SyncIndexMixin,
+    _SyncOpsMixin,
     _SyncMetaMixin,
     ValoriClient,
 ):
@@ -2199,11 +2440,16 @@ class AsyncRemoteClient(
     _AsyncKeysMixin,
     _AsyncClusterMixin,
     _AsyncIndexMixin,
+    _AsyncOpsMixin,
     _AsyncMetaMixin,
     ValoriClient,
 ):
     """Asynchronous REST client for a Valoricore node (httpx-backed).
 
+    Mirrors :class:`SyncRemoteClient` and the embedded ``LocalClient``
+    method-for-method, with every call awaitable — so code written against
+    the local engine moves to a remote node by adding ``await``.
+
     ``ui_url`` is the optional Next.js UI server URL (default: base_url with
     port replaced by 3001). Required only for deprecated ``list_contradictions``
     and ``resolve_contradiction``.
@@ -2239,31 +2485,6 @@ class AsyncRemoteClient(
     def _leader_url(self, v: Optional[str]) -> None:
         self._t._leader_url = v
 
-    # ValoriClient abstract methods (sync stubs — use the async variants)
-    def insert(self, *a, **kw):  # type: ignore[override]
-        raise RuntimeError("Use `await client.insert(...)` — this client is async")
-
-    def insert_batch(self, *a, **kw):  # type: ignore[override]
-        raise RuntimeError("Use `await client.insert_batch(...)` — this client is async")
-
-    def delete(self, *a, **kw):  # type: ignore[override]
-        raise RuntimeError("Use `await client.delete(...)` — this client is async")
-
-    def search(self, *a, **kw):  # type: ignore[override]
-        raise RuntimeError("Use `await client.search(...)` — this client is async")
-
-    def record_count(self) -> int:  # type: ignore[override]
-        raise RuntimeError("Use `await client.record_count()` — this client is async")
-
-    def get_metadata(self, *a, **kw):  # type: ignore[override]
-        raise RuntimeError("Use `await client.get_metadata(...)` — this client is async")
-
-    def set_metadata(self, *a, **kw):  # type: ignore[override]
-        raise RuntimeError("Use `await client.set_metadata(...)` — this client is async")
-
-    def get_state_hash(self) -> str:  # type: ignore[override]
-        raise RuntimeError("Use `await client.get_state_hash()` — this client is async")
-
     async def close(self) -> None:
         await self._t.close()
 
@@ -2383,6 +2604,11 @@ class ClusterClient:
         return self._write_client().contradict(record_a, record_b,
                                                 threshold=threshold, collection=collection)
 
+    def dedup_check(self, vector: Vector, max_distance: float, limit: int = 10,
+                    collection: str = "default") -> Dict[str, Any]:
+        return self._write_client().dedup_check(vector, max_distance, limit=limit,
+                                                collection=collection)
+
     def list_collections(self) -> List[Dict[str, Any]]:
         return self._read_client().list_collections()
 
@@ -2523,6 +2749,11 @@ class AsyncClusterClient:
         return await self._write_client().contradict(record_a, record_b,
                                                       threshold=threshold, collection=collection)
 
+    async def dedup_check(self, vector: Vector, max_distance: float, limit: int = 10,
+                          collection: str = "default") -> Dict[str, Any]:
+        return await self._write_client().dedup_check(vector, max_distance, limit=limit,
+                                                      collection=collection)
+
     async def list_collections(self) -> List[Dict[str, Any]]:
         return await self._read_client().list_collections()
 